@@ -38,7 +38,7 @@ pub extern "C" fn increment() {
     let new_value = current + 1;
 
     if storage.set("counter", &new_value).is_ok() {
-        log(&format!("Counter incremented to {}", new_value));
+        log!("Counter incremented to {}", new_value);
         event!("Incremented", old_value: current, new_value: new_value);
     }
 }
@@ -64,7 +64,7 @@ pub extern "C" fn reset() {
     };
 
     // In a real contract, check if sender is owner
-    log(&format!("Counter reset by {}", ctx.sender()));
+    log!("Counter reset by {}", ctx.sender());
 
     if storage.set("counter", &0u64).is_ok() {
         event!("Reset", by: ctx.sender());
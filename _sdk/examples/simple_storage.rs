@@ -34,7 +34,7 @@ pub extern "C" fn store() {
     let value = "example_value".to_string();
 
     if map.set(&key, &value).is_ok() {
-        log(&format!("Stored: {} = {}", key, value));
+        log!("Stored: {} = {}", key, value);
         event!("Stored", key: key, value: value, by: ctx.sender());
     }
 }
@@ -47,10 +47,10 @@ pub extern "C" fn retrieve() {
 
     match map.get(&key) {
         Ok(Some(value)) => {
-            log(&format!("Retrieved: {} = {}", key, value));
+            log!("Retrieved: {} = {}", key, value);
         }
         Ok(None) => {
-            log(&format!("Key not found: {}", key));
+            log!("Key not found: {}", key);
         }
         Err(_) => {
             log("Error retrieving value");
@@ -72,7 +72,7 @@ pub extern "C" fn delete() {
     let key = "example_key".to_string();
 
     if map.remove(&key).is_ok() {
-        log(&format!("Deleted key: {}", key));
+        log!("Deleted key: {}", key);
         event!("Deleted", key: key, by: ctx.sender());
     }
 }
@@ -0,0 +1,286 @@
+//! Role-based permissions kept in the contract's own storage
+//!
+//! Every role has an admin role whose holders may grant and revoke it; by
+//! default that is [`DEFAULT_ADMIN_ROLE`]. Members of each role live in an
+//! [`IterableMap`] keyed by account, holding the block height of the grant,
+//! so they can be counted and paged. The last holder of
+//! [`DEFAULT_ADMIN_ROLE`] cannot be removed, so a contract never ends up
+//! without anyone able to manage it.
+//!
+//! Events match the shared `access-control` registry contract:
+//! `RoleGranted` and `RoleRevoked` with `role`, `account` and `sender`, and
+//! `RoleAdminChanged` with `role`, `previous` and `admin_role`.
+
+use crate::context::context;
+use crate::error::{ContractError, ContractResult};
+use crate::pagination::{self, Page};
+use crate::security::validation;
+use crate::storage::{IterableMap, Map};
+use alloc::format;
+use alloc::string::{String, ToString};
+
+/// Role that administers every role without an explicit admin
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+/// Role allowed to mint
+pub const MINTER_ROLE: &str = "minter";
+/// Role allowed to burn on behalf of others
+pub const BURNER_ROLE: &str = "burner";
+/// Role allowed to pause and unpause
+pub const PAUSER_ROLE: &str = "pauser";
+/// Role allowed to upgrade or migrate the contract
+pub const UPGRADER_ROLE: &str = "upgrader";
+
+/// Largest page returned by [`members`]
+pub const MAX_MEMBERS_PAGE: u64 = 50;
+
+const MAX_ROLE_BYTES: usize = 32;
+const ROLE_ADMINS_KEY: &str = "access_control::admins";
+
+fn role_admins() -> Map<String, String> {
+    Map::new(ROLE_ADMINS_KEY)
+}
+
+/// Members of `role`, each with the block height they were granted it at
+fn role_members(role: &str) -> IterableMap<String, u64> {
+    IterableMap::new(&format!("access_control::members::{}", role))
+}
+
+/// Role names are 1-32 bytes of ASCII letters, digits, `_` and `-`
+pub fn validate_role(role: &str) -> ContractResult<()> {
+    let valid = !role.is_empty()
+        && role.len() <= MAX_ROLE_BYTES
+        && role
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+    if !valid {
+        return Err(ContractError::InvalidArgument(format!(
+            "Role must be 1-{} chars of [A-Za-z0-9_-]",
+            MAX_ROLE_BYTES
+        )));
+    }
+    Ok(())
+}
+
+/// Grant [`DEFAULT_ADMIN_ROLE`] to `admin`; only valid before any admin exists
+pub fn initialize(admin: &str) -> ContractResult<()> {
+    validation::validate_address(admin)?;
+    if member_count(DEFAULT_ADMIN_ROLE)? > 0 {
+        return Err(ContractError::InvalidArgument(
+            "Roles already initialized".to_string(),
+        ));
+    }
+    add_member(DEFAULT_ADMIN_ROLE, admin)?;
+
+    crate::event!("RoleGranted", role: DEFAULT_ADMIN_ROLE, account: admin, sender: admin);
+    Ok(())
+}
+
+/// Whether `account` holds `role`
+pub fn has_role(role: &str, account: &str) -> ContractResult<bool> {
+    role_members(role).contains_key(&account.to_string())
+}
+
+/// Fail with `Unauthorized` unless the caller holds `role`
+pub fn require_role(role: &str) -> ContractResult<()> {
+    let ctx = context();
+    if !has_role(role, ctx.sender())? {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Role whose holders may grant and revoke `role`
+pub fn role_admin(role: &str) -> ContractResult<String> {
+    Ok(role_admins()
+        .get(&role.to_string())?
+        .unwrap_or_else(|| DEFAULT_ADMIN_ROLE.to_string()))
+}
+
+/// Change the admin role of `role`; the caller must hold the current one
+pub fn set_role_admin(role: &str, admin_role: &str) -> ContractResult<()> {
+    validate_role(role)?;
+    validate_role(admin_role)?;
+    let previous = role_admin(role)?;
+    require_role(&previous)?;
+    if role == DEFAULT_ADMIN_ROLE && admin_role != DEFAULT_ADMIN_ROLE {
+        return Err(ContractError::InvalidArgument(
+            "The default admin role administers itself".to_string(),
+        ));
+    }
+
+    let mut admins = role_admins();
+    if admin_role == DEFAULT_ADMIN_ROLE {
+        admins.remove(&role.to_string())?;
+    } else {
+        admins.set(&role.to_string(), &admin_role.to_string())?;
+    }
+
+    crate::event!("RoleAdminChanged", role: role, previous: previous, admin_role: admin_role);
+    Ok(())
+}
+
+/// Grant `role` to `account`; returns `false` if it already held the role
+pub fn grant_role(role: &str, account: &str) -> ContractResult<bool> {
+    validate_role(role)?;
+    validation::validate_address(account)?;
+    require_role(&role_admin(role)?)?;
+    if !add_member(role, account)? {
+        return Ok(false);
+    }
+
+    crate::event!("RoleGranted", role: role, account: account, sender: context().sender());
+    Ok(true)
+}
+
+/// Revoke `role` from `account`; returns `false` if it did not hold the role
+pub fn revoke_role(role: &str, account: &str) -> ContractResult<bool> {
+    require_role(&role_admin(role)?)?;
+    if !remove_member(role, account)? {
+        return Ok(false);
+    }
+
+    crate::event!("RoleRevoked", role: role, account: account, sender: context().sender());
+    Ok(true)
+}
+
+/// Give up `role` held by the caller
+pub fn renounce_role(role: &str) -> ContractResult<()> {
+    let ctx = context();
+    let caller = ctx.sender();
+    if !remove_member(role, caller)? {
+        return Err(ContractError::InvalidArgument(
+            "Caller does not hold the role".to_string(),
+        ));
+    }
+
+    crate::event!("RoleRevoked", role: role, account: caller, sender: caller);
+    Ok(())
+}
+
+/// Number of accounts holding `role`
+pub fn member_count(role: &str) -> ContractResult<u64> {
+    role_members(role).len()
+}
+
+/// Member at `index` (0-based); order changes when members are removed
+pub fn member_at(role: &str, index: u64) -> ContractResult<Option<String>> {
+    role_members(role).key_at(index)
+}
+
+/// Block height at which `account` was granted `role`
+pub fn granted_at(role: &str, account: &str) -> ContractResult<Option<u64>> {
+    role_members(role).get(&account.to_string())
+}
+
+/// Members of `role` from `cursor`, at most `limit` (1-50) of them
+pub fn members(role: &str, cursor: u64, limit: u64) -> ContractResult<Page<String>> {
+    let members = role_members(role);
+    pagination::paginate(members.len()?, cursor, limit, MAX_MEMBERS_PAGE, |index| {
+        members
+            .key_at(index)?
+            .ok_or(ContractError::StorageReadFailed)
+    })
+}
+
+fn add_member(role: &str, account: &str) -> ContractResult<bool> {
+    let mut members = role_members(role);
+    let account = account.to_string();
+    if members.contains_key(&account)? {
+        return Ok(false);
+    }
+    members.insert(&account, &context().block_height())
+}
+
+fn remove_member(role: &str, account: &str) -> ContractResult<bool> {
+    let mut members = role_members(role);
+    let account = account.to_string();
+    if !members.contains_key(&account)? {
+        return Ok(false);
+    }
+    if role == DEFAULT_ADMIN_ROLE && members.len()? == 1 {
+        return Err(ContractError::InvalidArgument(
+            "Cannot remove the last admin".to_string(),
+        ));
+    }
+    Ok(members.remove(&account)?.is_some())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    const ADMIN: &str = "chert1admin00000000000000000000";
+    const MINTER: &str = "chert1minter0000000000000000000";
+    const OPERATOR: &str = "chert1operator00000000000000000";
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender(ADMIN);
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(7);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    #[test]
+    fn role_admins_control_grants() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+
+        initialize(ADMIN).unwrap();
+        assert!(initialize(MINTER).is_err());
+        assert!(grant_role(MINTER_ROLE, MINTER).unwrap());
+        assert!(!grant_role(MINTER_ROLE, MINTER).unwrap());
+        assert_eq!(granted_at(MINTER_ROLE, MINTER).unwrap(), Some(7));
+
+        // Hand minter administration to a dedicated operator role.
+        grant_role("operator", OPERATOR).unwrap();
+        set_role_admin(MINTER_ROLE, "operator").unwrap();
+        assert!(matches!(
+            revoke_role(MINTER_ROLE, MINTER),
+            Err(ContractError::Unauthorized)
+        ));
+
+        mock::set_sender(OPERATOR);
+        assert!(revoke_role(MINTER_ROLE, MINTER).unwrap());
+        assert!(!has_role(MINTER_ROLE, MINTER).unwrap());
+        assert!(matches!(
+            grant_role(PAUSER_ROLE, OPERATOR),
+            Err(ContractError::Unauthorized)
+        ));
+
+        mock::set_sender(ADMIN);
+        assert!(renounce_role(DEFAULT_ADMIN_ROLE).is_err());
+        require_role(DEFAULT_ADMIN_ROLE).unwrap();
+    }
+
+    #[test]
+    fn members_are_paged_and_reindexed_on_removal() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        initialize(ADMIN).unwrap();
+
+        let accounts = [
+            "chert1aaaa000000000000000000000",
+            "chert1bbbb000000000000000000000",
+            "chert1cccc000000000000000000000",
+        ];
+        for account in accounts {
+            grant_role(MINTER_ROLE, account).unwrap();
+        }
+        let page = members(MINTER_ROLE, 0, 2).unwrap();
+        assert_eq!(page.items, accounts[..2]);
+        assert_eq!((page.next_cursor, page.total), (Some(2), 3));
+
+        // The last member moves into the freed slot.
+        revoke_role(MINTER_ROLE, accounts[0]).unwrap();
+        assert_eq!(member_count(MINTER_ROLE).unwrap(), 2);
+        let page = members(MINTER_ROLE, 0, 50).unwrap();
+        assert_eq!(page.items, [accounts[2], accounts[1]]);
+        assert!(members(MINTER_ROLE, 0, 51).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Audit log of privileged actions
+//!
+//! Every owner, admin or operator action emits one `AdminAction` event, so
+//! auditors and monitoring watch a single topic across contracts instead of
+//! each contract's own events. Those events are still emitted alongside.
+//! [`pausable`](crate::pausable), [`ownable`](crate::ownable),
+//! [`decommission`](crate::decommission) and
+//! [`circuit_breaker`](crate::circuit_breaker) record their own actions;
+//! contracts call [`record`] for everything else.
+//!
+//! The event is published in the `events` crate's versioned layout and
+//! decodes as `events::AdminActionV1`:
+//!
+//! ```text
+//! topic   = "AdminAction"
+//! indexed = [actor, action, target]
+//! data    = postcard((1u16, { actor, action, target, old_value, new_value, sequence }))
+//! ```
+//!
+//! `target` names what the action applied to, and `old_value`/`new_value`
+//! are its state before and after:
+//!
+//! | Action | Target | Values |
+//! |--------|--------|--------|
+//! | [`MINT`], [`BURN`] | Holder, or the token id | Balance, or owner |
+//! | [`PAUSE`], [`UNPAUSE`] | `paused` | `false`/`true` |
+//! | [`SET_PARAMETER`] | Parameter name | Its value |
+//! | [`SET_PARAMETER`] | `outflow_limit:<asset>` | Empty or the limit |
+//! | [`RESET_CIRCUIT_BREAKER`] | `circuit_breaker` | Tripping asset, then empty |
+//! | [`GRANT_ROLE`], [`REVOKE_ROLE`] | Account | Empty or the role |
+//! | [`TRANSFER_OWNERSHIP`] | `pending_owner`, or `owner` when it takes effect at once | Nominee, or owner |
+//! | [`ACCEPT_OWNERSHIP`], [`RENOUNCE_OWNERSHIP`] | `owner` | Owner |
+//! | [`SCHEDULE_DECOMMISSION`], [`CANCEL_DECOMMISSION`] | `decommission` | Empty or the eta |
+//! | [`DECOMMISSION`] | `decommission` | Empty, then the time it took effect |
+//!
+//! Contract-specific actions use their entrypoint name as the id.
+//!
+//! ```rust,ignore
+//! use silica_contract_sdk::admin_log;
+//!
+//! admin_log::record(admin_log::SET_PARAMETER, "fee_bps", old_fee, args.fee_bps)?;
+//! ```
+
+use crate::context::context;
+use crate::error::ContractResult;
+use crate::events;
+use crate::security::safe_math;
+use crate::storage::storage;
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+use serde::Serialize;
+
+/// Storage key of the next admin action sequence number (`u64`)
+pub const SEQUENCE_KEY: &str = "events::admin_sequence";
+/// Event topic
+pub const TOPIC: &str = "AdminAction";
+/// Schema version the event data starts with
+pub const VERSION: u16 = 1;
+
+pub const MINT: &str = "mint";
+pub const BURN: &str = "burn";
+pub const PAUSE: &str = "pause";
+pub const UNPAUSE: &str = "unpause";
+pub const SET_PARAMETER: &str = "set_parameter";
+pub const GRANT_ROLE: &str = "grant_role";
+pub const REVOKE_ROLE: &str = "revoke_role";
+pub const TRANSFER_OWNERSHIP: &str = "transfer_ownership";
+pub const ACCEPT_OWNERSHIP: &str = "accept_ownership";
+pub const RENOUNCE_OWNERSHIP: &str = "renounce_ownership";
+pub const SCHEDULE_DECOMMISSION: &str = "schedule_decommission";
+pub const CANCEL_DECOMMISSION: &str = "cancel_decommission";
+pub const DECOMMISSION: &str = "decommission";
+pub const RESET_CIRCUIT_BREAKER: &str = "reset_circuit_breaker";
+
+/// Target of [`PAUSE`] and [`UNPAUSE`]
+pub const PAUSED_TARGET: &str = "paused";
+/// Target of [`TRANSFER_OWNERSHIP`]
+pub const PENDING_OWNER_TARGET: &str = "pending_owner";
+/// Target of [`ACCEPT_OWNERSHIP`] and [`RENOUNCE_OWNERSHIP`]
+pub const OWNER_TARGET: &str = "owner";
+/// Target of the decommission actions
+pub const DECOMMISSION_TARGET: &str = "decommission";
+/// Target of [`RESET_CIRCUIT_BREAKER`]
+pub const CIRCUIT_BREAKER_TARGET: &str = "circuit_breaker";
+
+/// Field layout of `events::AdminActionV1`
+#[derive(Serialize)]
+struct AdminAction<'a> {
+    actor: &'a str,
+    action: &'a str,
+    target: &'a str,
+    old_value: String,
+    new_value: String,
+    sequence: u64,
+}
+
+/// Sequence number the next admin action gets
+pub fn next_sequence() -> ContractResult<u64> {
+    Ok(storage().get::<u64>(SEQUENCE_KEY)?.unwrap_or(0))
+}
+
+/// Emit an `AdminAction` by the caller and return its sequence number
+pub fn record(
+    action: &str,
+    target: &str,
+    old_value: impl Display,
+    new_value: impl Display,
+) -> ContractResult<u64> {
+    let sequence = next_sequence()?;
+    storage().set(SEQUENCE_KEY, &safe_math::add(sequence, 1)?)?;
+    let ctx = context();
+    let actor = ctx.sender();
+    let indexed = [
+        events::indexed_topic(actor),
+        events::indexed_topic(action),
+        events::indexed_topic(target),
+    ];
+    let event = AdminAction {
+        actor,
+        action,
+        target,
+        old_value: old_value.to_string(),
+        new_value: new_value.to_string(),
+        sequence,
+    };
+    events::emit_indexed(TOPIC, &indexed, &(VERSION, event));
+    Ok(sequence)
+}
@@ -0,0 +1,265 @@
+//! Length-limited strings and byte buffers
+//!
+//! [`BoundedString<N>`] and [`BoundedBytes<N>`] hold at most `N` bytes.
+//! The limit is checked when the value is built or deserialized, so an
+//! argument struct that uses them rejects oversized call data while it is
+//! decoded, before anything reaches storage. On the wire they are a plain
+//! `String` and `Vec<u8>`.
+//!
+//! [`Name`], [`Symbol`], [`Uri`] and [`Memo`] are the limits contracts in
+//! this repository share for the usual text fields.
+//!
+//! ```rust,ignore
+//! use silica_contract_sdk::bounded::{Name, Symbol};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! pub struct InitArgs {
+//!     pub name: Name,
+//!     pub symbol: Symbol,
+//! }
+//! ```
+//!
+//! Bounds are upper limits only; check for empty values where they matter.
+
+use crate::error::{ContractError, ContractResult};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+/// Display name of a token, collection, vault or similar
+pub type Name = BoundedString<64>;
+/// Ticker symbol
+pub type Symbol = BoundedString<16>;
+/// Metadata URI or URI prefix
+pub type Uri = BoundedString<256>;
+/// Free-form note attached to a transfer or action
+pub type Memo = BoundedString<128>;
+
+fn too_long(len: usize, max: usize) -> ContractError {
+    ContractError::InvalidArgument(format!("{} bytes exceeds the {} byte limit", len, max))
+}
+
+/// A string of at most `N` bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoundedString<const N: usize>(String);
+
+impl<const N: usize> BoundedString<N> {
+    /// Upper limit in bytes
+    pub const MAX_LEN: usize = N;
+
+    pub fn new(value: impl Into<String>) -> ContractResult<Self> {
+        let value = value.into();
+        if value.len() > N {
+            return Err(too_long(value.len(), N));
+        }
+        Ok(BoundedString(value))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<const N: usize> FromStr for BoundedString<N> {
+    type Err = ContractError;
+
+    fn from_str(value: &str) -> ContractResult<Self> {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> TryFrom<String> for BoundedString<N> {
+    type Error = ContractError;
+
+    fn try_from(value: String) -> ContractResult<Self> {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for BoundedString<N> {
+    type Error = ContractError;
+
+    fn try_from(value: &str) -> ContractResult<Self> {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> From<BoundedString<N>> for String {
+    fn from(value: BoundedString<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> Deref for BoundedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsRef<str> for BoundedString<N> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const N: usize> PartialEq<str> for BoundedString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for BoundedString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<const N: usize> fmt::Display for BoundedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<const N: usize> Serialize for BoundedString<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for BoundedString<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::new(value).map_err(de::Error::custom)
+    }
+}
+
+/// A byte buffer of at most `N` bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoundedBytes<const N: usize>(Vec<u8>);
+
+impl<const N: usize> BoundedBytes<N> {
+    /// Upper limit in bytes
+    pub const MAX_LEN: usize = N;
+
+    pub fn new(value: impl Into<Vec<u8>>) -> ContractResult<Self> {
+        let value = value.into();
+        if value.len() > N {
+            return Err(too_long(value.len(), N));
+        }
+        Ok(BoundedBytes(value))
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl<const N: usize> TryFrom<Vec<u8>> for BoundedBytes<N> {
+    type Error = ContractError;
+
+    fn try_from(value: Vec<u8>) -> ContractResult<Self> {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for BoundedBytes<N> {
+    type Error = ContractError;
+
+    fn try_from(value: &[u8]) -> ContractResult<Self> {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> From<BoundedBytes<N>> for Vec<u8> {
+    fn from(value: BoundedBytes<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> Deref for BoundedBytes<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for BoundedBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Serialize for BoundedBytes<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for BoundedBytes<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Vec::<u8>::deserialize(deserializer)?;
+        Self::new(value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn strings_are_bounded_on_construction_and_decode() {
+        let symbol = Symbol::new("CHT").unwrap();
+        assert_eq!(symbol, "CHT");
+        assert_eq!(Symbol::MAX_LEN, 16);
+        assert!(Symbol::new("X".repeat(16)).is_ok());
+        assert!(matches!(
+            Symbol::new("X".repeat(17)),
+            Err(ContractError::InvalidArgument(_))
+        ));
+        // The limit counts bytes, not characters.
+        assert!(BoundedString::<3>::new("\u{e9}\u{e9}").is_err());
+
+        // Same encoding as the String it replaces.
+        let encoded = postcard::to_allocvec(&symbol).unwrap();
+        assert_eq!(encoded, postcard::to_allocvec("CHT").unwrap());
+        assert_eq!(postcard::from_bytes::<Symbol>(&encoded).unwrap(), symbol);
+        let long = postcard::to_allocvec(&"X".repeat(17)).unwrap();
+        assert!(postcard::from_bytes::<Symbol>(&long).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn bytes_are_bounded_on_construction_and_decode() {
+        let bytes = BoundedBytes::<4>::new([1u8, 2, 3, 4]).unwrap();
+        assert_eq!(&*bytes, &[1, 2, 3, 4]);
+        assert!(BoundedBytes::<4>::new(alloc::vec![0u8; 5]).is_err());
+        assert!(BoundedBytes::<0>::new(Vec::new()).is_ok());
+
+        let encoded = postcard::to_allocvec(&bytes).unwrap();
+        assert_eq!(
+            encoded,
+            postcard::to_allocvec(&alloc::vec![1u8, 2, 3, 4]).unwrap()
+        );
+        assert_eq!(
+            postcard::from_bytes::<BoundedBytes<4>>(&encoded).unwrap(),
+            bytes
+        );
+        let long = postcard::to_allocvec(&alloc::vec![0u8; 5]).unwrap();
+        assert!(postcard::from_bytes::<BoundedBytes<4>>(&long).is_err());
+    }
+}
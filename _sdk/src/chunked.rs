@@ -0,0 +1,291 @@
+//! Call data staged over several calls
+//!
+//! Entrypoints accept at most [`MAX_CALL_DATA_BYTES`] of call data. Batch
+//! entrypoints whose arguments can outgrow that take them in chunks instead:
+//!
+//! 1. `begin_payload { sequence, total_len }` opens a buffer keyed by the
+//!    caller and `sequence`. Beginning a sequence that is already open
+//!    starts it over.
+//! 2. `append_payload { sequence, chunk }` adds the next bytes, in order.
+//! 3. `commit_payload { sequence, method }` runs `method` with the staged
+//!    bytes as its arguments once all `total_len` have arrived, and removes
+//!    the buffer.
+//!
+//! Each chunk is stored as its own entry, so appending costs one write
+//! whatever has been staged so far. [`ChunkLimits`] are passed in rather than
+//! stored, so a contract can keep them in its configuration.
+//!
+//! ```rust,ignore
+//! const CHUNK_LIMITS: ChunkLimits = ChunkLimits { max_payload_bytes: 65_536, max_open_payloads: 4 };
+//!
+//! fn execute_commit_payload() -> ContractResult<()> {
+//!     let args: CommitPayloadArgs = read_args()?;
+//!     match args.method.as_str() {
+//!         "submit_votes" => submit_votes(chunked::commit(args.sequence)?),
+//!         _ => Err(chunked::unknown_method(&args.method)),
+//!     }
+//! }
+//! ```
+//!
+//! [`MAX_CALL_DATA_BYTES`]: crate::entrypoint::MAX_CALL_DATA_BYTES
+
+use crate::context::context;
+use crate::error::{ContractError, ContractResult};
+use crate::storage::Map;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Storage prefix of open payloads
+pub const PAYLOADS_PREFIX: &str = "chunked::payloads";
+/// Storage prefix of staged chunks
+pub const CHUNKS_PREFIX: &str = "chunked::chunks";
+/// Storage prefix of each caller's open payload count
+pub const OPEN_COUNT_PREFIX: &str = "chunked::open";
+
+/// Bounds on what one caller can stage
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkLimits {
+    /// Largest payload a sequence can hold
+    pub max_payload_bytes: u32,
+    /// Sequences a caller can have open at once
+    pub max_open_payloads: u32,
+}
+
+/// 64 KiB in at most four open payloads per caller
+pub const DEFAULT_LIMITS: ChunkLimits = ChunkLimits {
+    max_payload_bytes: 65_536,
+    max_open_payloads: 4,
+};
+
+/// `begin_payload`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BeginPayloadArgs {
+    pub sequence: u64,
+    pub total_len: u32,
+}
+
+/// `append_payload`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AppendPayloadArgs {
+    pub sequence: u64,
+    pub chunk: Vec<u8>,
+}
+
+/// `commit_payload`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CommitPayloadArgs {
+    pub sequence: u64,
+    /// Batch entrypoint to run with the payload
+    pub method: String,
+}
+
+/// Progress of one open payload
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StagedPayload {
+    pub total_len: u32,
+    pub received: u32,
+    pub chunks: u32,
+}
+
+fn payloads() -> Map<(String, u64), StagedPayload> {
+    Map::new(PAYLOADS_PREFIX)
+}
+
+fn chunks() -> Map<(String, u64, u32), Vec<u8>> {
+    Map::new(CHUNKS_PREFIX)
+}
+
+fn open_counts() -> Map<String, u32> {
+    Map::new(OPEN_COUNT_PREFIX)
+}
+
+fn not_open() -> ContractError {
+    ContractError::InvalidArgument("Payload is not open".to_string())
+}
+
+/// Error for a `commit_payload` naming a method that takes no payloads
+pub fn unknown_method(method: &str) -> ContractError {
+    ContractError::InvalidArgument(format!("{} does not take staged payloads", method))
+}
+
+/// The caller's open payload `sequence`, if any
+pub fn staged(caller: &str, sequence: u64) -> ContractResult<Option<StagedPayload>> {
+    payloads().get(&(caller.to_string(), sequence))
+}
+
+/// Remove the chunks of `payload` and the payload itself
+fn discard(caller: &str, sequence: u64, payload: &StagedPayload) -> ContractResult<()> {
+    let mut chunks = chunks();
+    for index in 0..payload.chunks {
+        chunks.remove(&(caller.to_string(), sequence, index))?;
+    }
+    payloads().remove(&(caller.to_string(), sequence))
+}
+
+/// Open payload `sequence` of `total_len` bytes for the caller
+pub fn begin(limits: &ChunkLimits, sequence: u64, total_len: u32) -> ContractResult<()> {
+    if total_len == 0 || total_len > limits.max_payload_bytes {
+        return Err(ContractError::InvalidArgument(format!(
+            "Payloads hold 1-{} bytes",
+            limits.max_payload_bytes
+        )));
+    }
+    let caller = context().sender().to_string();
+    match staged(&caller, sequence)? {
+        Some(previous) => discard(&caller, sequence, &previous)?,
+        None => {
+            let mut counts = open_counts();
+            let open = counts.get(&caller)?.unwrap_or(0);
+            if open >= limits.max_open_payloads {
+                return Err(ContractError::InvalidArgument(format!(
+                    "At most {} payloads can be open",
+                    limits.max_open_payloads
+                )));
+            }
+            counts.set(&caller, &(open + 1))?;
+        }
+    }
+    payloads().set(
+        &(caller, sequence),
+        &StagedPayload {
+            total_len,
+            received: 0,
+            chunks: 0,
+        },
+    )
+}
+
+/// Add `chunk` to the caller's payload `sequence`
+pub fn append(sequence: u64, chunk: &[u8]) -> ContractResult<()> {
+    let caller = context().sender().to_string();
+    let mut payload = staged(&caller, sequence)?.ok_or_else(not_open)?;
+    let len = u32::try_from(chunk.len()).map_err(|_| ContractError::Overflow)?;
+    if len == 0 || payload.received.saturating_add(len) > payload.total_len {
+        return Err(ContractError::InvalidArgument(format!(
+            "Chunk must hold 1-{} bytes",
+            payload.total_len - payload.received
+        )));
+    }
+    chunks().set(&(caller.clone(), sequence, payload.chunks), &chunk.to_vec())?;
+    payload.received += len;
+    payload.chunks += 1;
+    payloads().set(&(caller, sequence), &payload)
+}
+
+/// Decode the caller's complete payload `sequence` as `T` and close it
+///
+/// A payload that is incomplete or does not decode stays open.
+pub fn commit<T: DeserializeOwned>(sequence: u64) -> ContractResult<T> {
+    let caller = context().sender().to_string();
+    let payload = staged(&caller, sequence)?.ok_or_else(not_open)?;
+    if payload.received != payload.total_len {
+        return Err(ContractError::InvalidArgument(format!(
+            "Payload is missing {} bytes",
+            payload.total_len - payload.received
+        )));
+    }
+    let chunks = chunks();
+    let mut bytes = Vec::with_capacity(payload.total_len as usize);
+    for index in 0..payload.chunks {
+        let chunk = chunks
+            .get(&(caller.clone(), sequence, index))?
+            .ok_or(ContractError::StorageReadFailed)?;
+        bytes.extend_from_slice(&chunk);
+    }
+    let value = postcard::from_bytes(&bytes).map_err(|_| ContractError::DeserializationFailed)?;
+
+    discard(&caller, sequence, &payload)?;
+    let mut counts = open_counts();
+    let open = counts.get(&caller)?.unwrap_or(0);
+    if open > 1 {
+        counts.set(&caller, &(open - 1))?;
+    } else {
+        counts.remove(&caller)?;
+    }
+    Ok(value)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+    use crate::testing::StorageSnapshot;
+
+    const LIMITS: ChunkLimits = ChunkLimits {
+        max_payload_bytes: 64,
+        max_open_payloads: 2,
+    };
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    #[test]
+    fn payloads_assemble_in_order_and_leave_nothing_behind() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        let before = StorageSnapshot::take();
+        let values: Vec<u64> = (0..10).map(|i| i * 1_000_000).collect();
+        let bytes = postcard::to_allocvec(&values).unwrap();
+
+        begin(&LIMITS, 7, bytes.len() as u32).unwrap();
+        let (head, tail) = bytes.split_at(5);
+        append(7, head).unwrap();
+        assert!(commit::<Vec<u64>>(7).is_err());
+        assert!(append(7, &[0; 65]).is_err());
+        append(7, tail).unwrap();
+        assert!(append(7, &[0]).is_err());
+        // Payloads are per caller.
+        mock::set_sender("chert1other0000000000000000000");
+        assert!(commit::<Vec<u64>>(7).is_err());
+        mock::set_sender("chert1sender000000000000000000");
+        assert_eq!(commit::<Vec<u64>>(7).unwrap(), values);
+
+        assert!(commit::<Vec<u64>>(7).is_err());
+        before.diff(&StorageSnapshot::take()).assert_unchanged();
+    }
+
+    #[test]
+    fn limits_bound_payload_size_and_open_payloads() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        assert!(begin(&LIMITS, 1, 0).is_err());
+        assert!(begin(&LIMITS, 1, 65).is_err());
+        begin(&LIMITS, 1, 64).unwrap();
+        begin(&LIMITS, 2, 1).unwrap();
+        assert!(begin(&LIMITS, 3, 1).is_err());
+
+        // Beginning an open sequence again starts it over.
+        append(1, &[1, 2]).unwrap();
+        begin(&LIMITS, 1, 1).unwrap();
+        assert_eq!(
+            staged("chert1sender000000000000000000", 1).unwrap(),
+            Some(StagedPayload {
+                total_len: 1,
+                received: 0,
+                chunks: 0,
+            })
+        );
+
+        // A payload that does not decode stays open.
+        append(2, &[0xff]).unwrap();
+        assert!(matches!(
+            commit::<Vec<u64>>(2),
+            Err(ContractError::DeserializationFailed)
+        ));
+        append(1, &[0]).unwrap();
+        assert_eq!(commit::<Vec<u64>>(1).unwrap(), Vec::<u64>::new());
+        begin(&LIMITS, 3, 1).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+//! Outflow circuit breaker
+//!
+//! Contracts holding pooled funds report each amount leaving them with
+//! [`record_outflow`]. An asset with a limit may release at most
+//! `max_outflow` per rolling window (see [`RollingWindow`]); the outflow
+//! that would exceed it is refused and trips the breaker instead. While
+//! tripped, every outflow fails until an admin calls [`reset`], and the
+//! contract may refuse other value movement with [`when_not_tripped`].
+//!
+//! The trip is stored even though the call that caused it fails, so
+//! contracts must record the outflow after their own checks and before
+//! writing anything else. Assets without a limit are only stopped by a trip.
+//!
+//! As with [`pausable`](crate::pausable), the module does not decide who may
+//! configure or reset the breaker; the embedding contract checks its own
+//! owner or role first. State lives under `circuit_breaker::*` keys and
+//! changes emit `OutflowLimitSet`, `OutflowLimitRemoved`,
+//! `CircuitBreakerTripped` and `CircuitBreakerReset`. Limit changes and
+//! resets are also recorded in the [`admin_log`](crate::admin_log).
+
+use crate::admin_log;
+use crate::context::context;
+use crate::error::{ContractError, ContractResult};
+use crate::rate_limit::RollingWindow;
+use crate::storage::{Map, storage};
+use crate::time::{self, Duration};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Storage key of the current [`Trip`]
+pub const TRIP_KEY: &str = "circuit_breaker::trip";
+const LIMITS_PREFIX: &str = "circuit_breaker::limits";
+const OUTFLOWS_PREFIX: &str = "circuit_breaker::outflows";
+
+/// Largest amount of an asset that may leave per window
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutflowLimit {
+    pub max_outflow: u64,
+    pub window: Duration,
+}
+
+impl fmt::Display for OutflowLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} per {}s", self.max_outflow, self.window.as_secs())
+    }
+}
+
+/// Why and when the breaker tripped
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Trip {
+    pub asset: String,
+    pub amount: u64,
+    pub at: u64,
+}
+
+fn limits() -> Map<String, OutflowLimit> {
+    Map::new(LIMITS_PREFIX)
+}
+
+fn limit_target(asset: &str) -> String {
+    format!("outflow_limit:{asset}")
+}
+
+fn outflows(limit: &OutflowLimit) -> ContractResult<RollingWindow> {
+    RollingWindow::new(OUTFLOWS_PREFIX, limit.max_outflow, limit.window)
+}
+
+/// Outflow limit of `asset`, if one is set
+pub fn limit(asset: &str) -> ContractResult<Option<OutflowLimit>> {
+    limits().get(&asset.to_string())
+}
+
+/// Limit outflows of `asset`; the caller must already be authorized
+pub fn set_limit(asset: &str, max_outflow: u64, window: Duration) -> ContractResult<()> {
+    if max_outflow == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Outflow limit must be positive".to_string(),
+        ));
+    }
+    let limit = OutflowLimit {
+        max_outflow,
+        window,
+    };
+    outflows(&limit)?;
+    let previous = self::limit(asset)?;
+    limits().set(&asset.to_string(), &limit)?;
+    crate::event!("OutflowLimitSet",
+        account: context().sender(),
+        asset: asset,
+        max_outflow: max_outflow,
+        window: window.as_secs()
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        &limit_target(asset),
+        previous.map(|l| l.to_string()).unwrap_or_default(),
+        limit,
+    )?;
+    Ok(())
+}
+
+/// Stop limiting outflows of `asset`; the caller must already be authorized
+pub fn remove_limit(asset: &str) -> ContractResult<()> {
+    let previous = limit(asset)?
+        .ok_or_else(|| ContractError::InvalidArgument("No outflow limit set".to_string()))?;
+    limits().remove(&asset.to_string())?;
+    crate::event!("OutflowLimitRemoved", account: context().sender(), asset: asset);
+    admin_log::record(admin_log::SET_PARAMETER, &limit_target(asset), previous, "")?;
+    Ok(())
+}
+
+/// The current trip, if the breaker is tripped
+pub fn tripped() -> ContractResult<Option<Trip>> {
+    storage().get::<Trip>(TRIP_KEY)
+}
+
+/// Fail while the breaker is tripped
+pub fn when_not_tripped() -> ContractResult<()> {
+    if tripped()?.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Circuit breaker tripped".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Count `amount` of `asset` leaving the contract. Fails while tripped, and
+/// trips the breaker if the amount would exceed the asset's limit.
+pub fn record_outflow(asset: &str, amount: u64) -> ContractResult<()> {
+    when_not_tripped()?;
+    let Some(limit) = limit(asset)? else {
+        return Ok(());
+    };
+    let mut outflows = outflows(&limit)?;
+    if amount > outflows.remaining(asset)? {
+        let trip = Trip {
+            asset: asset.to_string(),
+            amount,
+            at: time::now()?,
+        };
+        storage().set(TRIP_KEY, &trip)?;
+        crate::event!("CircuitBreakerTripped",
+            asset: trip.asset,
+            amount: amount,
+            max_outflow: limit.max_outflow
+        );
+        return Err(ContractError::InvalidArgument(
+            "Outflow limit exceeded; circuit breaker tripped".to_string(),
+        ));
+    }
+    outflows.check_and_consume(asset, amount)
+}
+
+/// Clear a trip and the tripping asset's window; the caller must already be
+/// authorized
+pub fn reset() -> ContractResult<()> {
+    let trip = tripped()?
+        .ok_or_else(|| ContractError::InvalidArgument("Circuit breaker not tripped".to_string()))?;
+    if let Some(limit) = limit(&trip.asset)? {
+        outflows(&limit)?.clear(&trip.asset)?;
+    }
+    storage().remove(TRIP_KEY)?;
+    crate::event!("CircuitBreakerReset", account: context().sender(), asset: trip.asset);
+    admin_log::record(
+        admin_log::RESET_CIRCUIT_BREAKER,
+        admin_log::CIRCUIT_BREAKER_TARGET,
+        &trip.asset,
+        "",
+    )?;
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn excess_outflow_trips_until_reset() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1guardian0000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+
+        // No limit: only a trip stops outflows.
+        record_outflow("native", u64::MAX).unwrap();
+        assert!(set_limit("native", 0, Duration::from_days(1)).is_err());
+        assert!(set_limit("native", 1_000, Duration::ZERO).is_err());
+        set_limit("native", 1_000, Duration::from_days(1)).unwrap();
+        set_limit("token", 10, Duration::from_days(1)).unwrap();
+
+        record_outflow("native", 600).unwrap();
+        record_outflow("native", 400).unwrap();
+        assert_eq!(tripped().unwrap(), None);
+        assert!(record_outflow("native", 1).is_err());
+        assert_eq!(
+            tripped().unwrap(),
+            Some(Trip {
+                asset: "native".to_string(),
+                amount: 1,
+                at: NOW
+            })
+        );
+        assert!(when_not_tripped().is_err());
+        // Every asset is stopped, and the trip outlives the window.
+        assert!(record_outflow("token", 1).is_err());
+        mock::set_block_timestamp(NOW + 86_400);
+        assert!(record_outflow("native", 1).is_err());
+
+        mock::set_block_timestamp(NOW + 60);
+        reset().unwrap();
+        assert!(reset().is_err());
+        when_not_tripped().unwrap();
+        // The reset also restores the tripping asset's full window.
+        record_outflow("native", 1_000).unwrap();
+        record_outflow("token", 10).unwrap();
+
+        remove_limit("native").unwrap();
+        assert!(remove_limit("native").is_err());
+        record_outflow("native", 5_000).unwrap();
+
+        let topics: alloc::vec::Vec<_> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(
+            topics,
+            [
+                "OutflowLimitSet",
+                "AdminAction",
+                "OutflowLimitSet",
+                "AdminAction",
+                "CircuitBreakerTripped",
+                "CircuitBreakerReset",
+                "AdminAction",
+                "OutflowLimitRemoved",
+                "AdminAction"
+            ]
+        );
+        assert_eq!(admin_log::next_sequence().unwrap(), 4);
+    }
+}
@@ -70,6 +70,41 @@ impl Context {
         ffi::transfer_tokens(recipient, amount)
     }
 
+    /// Invoke `method` on another contract with postcard-encoded `args`,
+    /// returning the callee's raw return bytes.
+    pub fn call_contract(
+        &self,
+        address: &str,
+        method: &str,
+        args: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        validation::validate_address(address)?;
+        validation::validate_non_empty(method, "method")?;
+        ffi::call_contract(address, method, args)
+    }
+
+    /// Whether `address` holds deployed contract code rather than a key-controlled account.
+    pub fn is_contract(&self, address: &str) -> bool {
+        ffi::is_contract(address)
+    }
+
+    /// Deploy a new instance of the code identified by `code_hash`, running its
+    /// `initialize` entrypoint with `init_args`. The address is derived from
+    /// this contract, the code hash, and `salt`, so reusing a salt fails.
+    pub fn instantiate_contract(
+        &self,
+        code_hash: &[u8; 32],
+        salt: &[u8],
+        init_args: &[u8],
+    ) -> ContractResult<String> {
+        if salt.is_empty() {
+            return Err(ContractError::InvalidArgument(String::from(
+                "salt cannot be empty",
+            )));
+        }
+        ffi::instantiate_contract(code_hash, salt, init_args)
+    }
+
     /// Ensure the attached value is at least the requested amount.
     pub fn require_min_value(&self, required: u64) -> ContractResult<()> {
         if self.value < required {
@@ -140,18 +175,22 @@ mod tests {
     use super::*;
     use crate::ffi::mock;
 
-    fn prepare_mock_env() {
+    fn prepare_mock_env() -> std::sync::MutexGuard<'static, ()> {
+        let guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         mock::reset();
         mock::set_sender("chert1sender000000000000000000");
         mock::set_contract_address("chert1contract0000000000000000");
         mock::set_block_height(42);
         mock::set_block_timestamp(1_700_000_000);
         mock::set_value(1_000);
+        guard
     }
 
     #[test]
     fn context_initializes_from_mock_runtime() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
 
         let ctx = try_context().expect("context should be available");
 
@@ -164,7 +203,7 @@ mod tests {
 
     #[test]
     fn context_rejects_invalid_addresses() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         mock::set_sender("");
 
         let err = try_context().expect_err("empty sender must be rejected");
@@ -173,7 +212,7 @@ mod tests {
 
     #[test]
     fn return_data_roundtrip() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let ctx = try_context().expect("context should be available");
 
         ctx.return_data(&64u32).expect("return data must succeed");
@@ -185,7 +224,7 @@ mod tests {
 
     #[test]
     fn call_data_roundtrip() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let payload = b"call-data";
         mock::set_call_data(payload);
 
@@ -196,7 +235,7 @@ mod tests {
 
     #[test]
     fn require_min_value_enforces_bound() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let ctx = try_context().expect("context should be available");
 
         assert!(ctx.require_min_value(500).is_ok());
@@ -217,7 +256,7 @@ mod tests {
 
     #[test]
     fn transfer_tokens_validates_inputs() {
-        prepare_mock_env();
+        let _guard = prepare_mock_env();
         let ctx = try_context().expect("context should be available");
 
         assert!(
@@ -235,4 +274,59 @@ mod tests {
             .expect_err("zero amount should fail");
         assert!(matches!(amount_err, ContractError::InvalidArgument(_)));
     }
+
+    #[test]
+    fn call_contract_routes_to_mock_handler() {
+        let _guard = prepare_mock_env();
+        mock::register_contract("chert1callee00000000000000000", "echo", |args| {
+            Ok(args.iter().rev().copied().collect())
+        });
+        let ctx = try_context().expect("context should be available");
+
+        let result = ctx
+            .call_contract("chert1callee00000000000000000", "echo", &[1, 2, 3])
+            .expect("registered handler");
+        assert_eq!(result, vec![3, 2, 1]);
+
+        let err = ctx
+            .call_contract("chert1callee00000000000000000", "missing", &[])
+            .expect_err("unregistered method must fail");
+        assert!(matches!(err, ContractError::ContractCallFailed(_)));
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].method, "echo");
+        assert_eq!(calls[0].args, vec![1, 2, 3]);
+
+        assert!(ctx.is_contract("chert1callee00000000000000000"));
+        assert!(!ctx.is_contract(ctx.sender()));
+    }
+
+    #[test]
+    fn instantiate_contract_derives_unique_addresses() {
+        let _guard = prepare_mock_env();
+        let ctx = try_context().expect("context should be available");
+        let code_hash = [7_u8; 32];
+
+        let first = ctx
+            .instantiate_contract(&code_hash, b"salt-1", &[1])
+            .expect("first deployment");
+        let second = ctx
+            .instantiate_contract(&code_hash, b"salt-2", &[2])
+            .expect("second deployment");
+        assert_ne!(first, second);
+        assert!(validation::validate_address(&first).is_ok());
+        assert!(ctx.is_contract(&first));
+
+        assert!(
+            ctx.instantiate_contract(&code_hash, b"salt-1", &[])
+                .is_err()
+        );
+        assert!(ctx.instantiate_contract(&code_hash, b"", &[]).is_err());
+
+        let records = mock::take_instantiations();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].init_args, vec![2]);
+        assert_eq!(records[1].address, second);
+    }
 }
@@ -65,6 +65,18 @@ pub fn verify_signature(
     ffi::call_verify_signature(pubkey, message, signature)
 }
 
+/// Recover the compressed secp256k1 public key that signed `digest`
+///
+/// `signature` is `r || s || v` with `v` in `0..=1`. Returns `None` when no
+/// key can be recovered.
+#[inline(always)]
+pub fn secp256k1_recover(
+    digest: &[u8; 32],
+    signature: &[u8; 65],
+) -> ContractResult<Option<[u8; 33]>> {
+    ffi::call_secp256k1_recover(digest, signature)
+}
+
 /// Batch verify multiple signatures (for efficiency in multi-sig operations)
 pub fn batch_verify_signatures(
     pubkeys: &[&[u8; 32]],
@@ -109,7 +121,7 @@ pub fn generate_keypairs(count: usize) -> ContractResult<Vec<([u8; 32], [u8; 32]
     let mut pairs = Vec::with_capacity(count);
     for index in 0..count {
         let mut hasher = Hasher::new();
-    hasher.update(b"silica-contract-sdk-keygen");
+        hasher.update(b"silica-contract-sdk-keygen");
         hasher.update(&(index as u64).to_le_bytes());
         let digest = hasher.finalize();
 
@@ -0,0 +1,209 @@
+//! End of life for deprecated deployments
+//!
+//! Every contract retires the same way, so users, indexers and explorers
+//! know what a sunset means wherever they meet it:
+//!
+//! 1. The owner gives notice with [`schedule`]: at least [`MIN_NOTICE`],
+//!    announced by `DecommissionScheduled` with the `eta`. [`cancel`]
+//!    withdraws the notice.
+//! 2. Once the notice has run out, the owner calls [`decommission`]. The
+//!    flag under [`DECOMMISSIONED_AT_KEY`] is set for good and
+//!    `Decommissioned` is emitted; it is the contract's last admin event.
+//! 3. Entrypoints that open or change positions start with
+//!    [`when_active`] and fail from then on. Withdrawals and claims stay
+//!    open, and whatever held escrowed funds back (lock-ups, maturities,
+//!    penalties) is lifted, so every owner can take out what is theirs.
+//!
+//! Like [`pausable`](crate::pausable), the module does not decide who may
+//! act; the embedding contract checks its owner before calling
+//! [`schedule`], [`cancel`] or [`decommission`]. Each of them is also
+//! recorded in the [`admin_log`](crate::admin_log).
+
+use crate::admin_log;
+use crate::context::context;
+use crate::error::{ContractError, ContractResult};
+use crate::security::safe_math;
+use crate::storage::storage;
+use crate::time::{self, Duration};
+use alloc::format;
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Serialize};
+
+/// Storage key of the time a scheduled decommission can take effect
+pub const ETA_KEY: &str = "decommission::eta";
+/// Storage key of the time the contract was decommissioned
+pub const DECOMMISSIONED_AT_KEY: &str = "decommission::at";
+/// Shortest notice users get before a decommission
+pub const MIN_NOTICE: Duration = Duration::from_days(2);
+/// Longest notice, so a forgotten schedule does not linger
+pub const MAX_NOTICE: Duration = Duration::from_days(90);
+/// Error message of [`when_active`]
+pub const DECOMMISSIONED_MESSAGE: &str = "Contract is decommissioned";
+
+/// Where a contract is in its end of life
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DecommissionStatus {
+    /// Earliest time a scheduled decommission can take effect
+    pub eta: Option<u64>,
+    /// When the contract was decommissioned
+    pub decommissioned_at: Option<u64>,
+}
+
+/// The contract's end-of-life status
+pub fn status() -> ContractResult<DecommissionStatus> {
+    let store = storage();
+    Ok(DecommissionStatus {
+        eta: store.get::<u64>(ETA_KEY)?,
+        decommissioned_at: store.get::<u64>(DECOMMISSIONED_AT_KEY)?,
+    })
+}
+
+/// When the contract was decommissioned, if it was
+pub fn decommissioned_at() -> ContractResult<Option<u64>> {
+    storage().get::<u64>(DECOMMISSIONED_AT_KEY)
+}
+
+/// Fail once the contract is decommissioned
+pub fn when_active() -> ContractResult<()> {
+    if decommissioned_at()?.is_some() {
+        return Err(ContractError::InvalidArgument(
+            DECOMMISSIONED_MESSAGE.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Give `notice` before decommissioning; the caller must already be
+/// authorized. Returns the `eta`.
+pub fn schedule(notice: Duration) -> ContractResult<u64> {
+    when_active()?;
+    if notice < MIN_NOTICE || notice > MAX_NOTICE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Notice must be {}-{} seconds",
+            MIN_NOTICE.as_secs(),
+            MAX_NOTICE.as_secs()
+        )));
+    }
+    let mut store = storage();
+    if store.has(ETA_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Decommission already scheduled".to_string(),
+        ));
+    }
+    let eta = safe_math::add(time::now()?, notice.as_secs())?;
+    store.set(ETA_KEY, &eta)?;
+    crate::event!("DecommissionScheduled", account: context().sender(), eta: eta);
+    admin_log::record(
+        admin_log::SCHEDULE_DECOMMISSION,
+        admin_log::DECOMMISSION_TARGET,
+        "",
+        eta,
+    )?;
+    Ok(eta)
+}
+
+/// Withdraw a scheduled decommission; the caller must already be authorized
+pub fn cancel() -> ContractResult<()> {
+    when_active()?;
+    let mut store = storage();
+    let eta = store.get::<u64>(ETA_KEY)?.ok_or_else(not_scheduled)?;
+    store.remove(ETA_KEY)?;
+    crate::event!("DecommissionCancelled", account: context().sender());
+    admin_log::record(
+        admin_log::CANCEL_DECOMMISSION,
+        admin_log::DECOMMISSION_TARGET,
+        eta,
+        "",
+    )?;
+    Ok(())
+}
+
+/// Decommission the contract once its notice has run out; the caller must
+/// already be authorized. Returns the time it took effect.
+pub fn decommission() -> ContractResult<u64> {
+    when_active()?;
+    let mut store = storage();
+    let eta = store.get::<u64>(ETA_KEY)?.ok_or_else(not_scheduled)?;
+    let now = time::now()?;
+    if now < eta {
+        return Err(ContractError::InvalidArgument(
+            "Decommission notice has not run out".to_string(),
+        ));
+    }
+    store.remove(ETA_KEY)?;
+    store.set(DECOMMISSIONED_AT_KEY, &now)?;
+    crate::event!("Decommissioned", account: context().sender(), at: now);
+    admin_log::record(
+        admin_log::DECOMMISSION,
+        admin_log::DECOMMISSION_TARGET,
+        "",
+        now,
+    )?;
+    Ok(now)
+}
+
+fn not_scheduled() -> ContractError {
+    ContractError::InvalidArgument("No decommission scheduled".to_string())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    #[test]
+    fn decommission_waits_out_its_notice_and_is_final() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1owner000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+
+        assert_eq!(status().unwrap(), DecommissionStatus::default());
+        assert!(decommission().is_err());
+        assert!(cancel().is_err());
+        assert!(schedule(Duration::from_days(1)).is_err());
+        assert!(schedule(Duration::from_days(91)).is_err());
+
+        let eta = schedule(MIN_NOTICE).unwrap();
+        assert_eq!(eta, 1_700_000_000 + MIN_NOTICE.as_secs());
+        assert!(schedule(MIN_NOTICE).is_err());
+        cancel().unwrap();
+        schedule(MIN_NOTICE).unwrap();
+
+        mock::set_block_timestamp(eta - 1);
+        assert!(decommission().is_err());
+        when_active().unwrap();
+        mock::set_block_timestamp(eta);
+        assert_eq!(decommission().unwrap(), eta);
+        assert_eq!(
+            status().unwrap(),
+            DecommissionStatus {
+                eta: None,
+                decommissioned_at: Some(eta),
+            }
+        );
+
+        // Nothing can be scheduled, cancelled or repeated afterwards.
+        assert!(when_active().is_err());
+        assert!(schedule(MIN_NOTICE).is_err());
+        assert!(decommission().is_err());
+        let topics: alloc::vec::Vec<_> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(
+            topics,
+            [
+                "DecommissionScheduled",
+                "AdminAction",
+                "DecommissionCancelled",
+                "AdminAction",
+                "DecommissionScheduled",
+                "AdminAction",
+                "Decommissioned",
+                "AdminAction"
+            ]
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Call-data decoding and return-data encoding for entrypoints
+//!
+//! Every entrypoint reads one postcard-encoded argument struct and answers
+//! with one postcard-encoded value. The `#[entrypoint]` attribute from
+//! `contract-macros` generates calls to these helpers; hand-written
+//! wrappers can use them directly.
+
+use crate::context::context;
+use crate::error::{ContractError, ContractResult};
+use crate::pagination::MAX_RETURN_BYTES;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Largest call data an entrypoint accepts
+pub const MAX_CALL_DATA_BYTES: usize = 4096;
+
+/// Decode the call data as `T`
+///
+/// Fails with `CallDataUnavailable` when the call carried no data.
+pub fn read_args<T: DeserializeOwned>() -> ContractResult<T> {
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Write `value` as the call's return data
+pub fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn arguments_and_results_round_trip() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+
+        assert!(matches!(
+            read_args::<(String, u64)>(),
+            Err(ContractError::CallDataUnavailable)
+        ));
+        mock::set_call_data(&postcard::to_allocvec(&("bob", 5u64)).unwrap());
+        assert_eq!(
+            read_args::<(String, u64)>().unwrap(),
+            ("bob".to_string(), 5)
+        );
+        assert!(matches!(
+            read_args::<(String, u64, u64)>(),
+            Err(ContractError::DeserializationFailed)
+        ));
+
+        try_respond(&Some(7u64)).unwrap();
+        let returned: Option<u64> = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(returned, Some(7));
+    }
+}
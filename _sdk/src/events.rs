@@ -1,13 +1,73 @@
 //! Event emission for smart contracts (memory pool optimized)
+//!
+//! Besides its topic and data, an event may carry up to
+//! [`MAX_INDEXED_TOPICS`] indexed topics: 32-byte values the node stores
+//! alongside it so indexers can filter events by account or token id
+//! without decoding their data. Each is [`indexed_topic`] of a field,
+//! `blake3(postcard(value))`, so a filter on an address hashes the address
+//! the same way.
+//!
+//! Every event also carries its sequence number: the count of events the
+//! contract emitted before it, kept under [`SEQUENCE_KEY`]. Numbers have no
+//! gaps, so a consumer that sees one skip, or go backwards after a reorg,
+//! knows to backfill.
 
-use crate::ffi;
+use crate::error::{ContractError, ContractResult};
+use crate::{crypto, ffi};
 use serde::Serialize;
 
+/// Most indexed topics one event may carry
+pub const MAX_INDEXED_TOPICS: usize = 3;
+
+/// Storage key of the next event sequence number
+pub const SEQUENCE_KEY: &str = "events::sequence";
+
+/// Sequence number the contract's next event will carry
+pub fn sequence() -> ContractResult<u64> {
+    read_sequence(&ffi::get_contract_addr())
+}
+
+// The counter is read and written by address alone: the full context would
+// cost five lookups per access, and fails when the sender is not set.
+fn read_sequence(contract: &str) -> ContractResult<u64> {
+    match ffi::read_storage(contract, SEQUENCE_KEY) {
+        Ok(data) if data.is_empty() => Ok(0),
+        Ok(data) => postcard::from_bytes(&data).map_err(|_| ContractError::DeserializationFailed),
+        Err(ContractError::StorageReadFailed) => Ok(0),
+        Err(err) => Err(err),
+    }
+}
+
+fn next_sequence() -> ContractResult<u64> {
+    let contract = ffi::get_contract_addr();
+    let sequence = read_sequence(&contract)?;
+    let next = sequence.checked_add(1).ok_or(ContractError::Overflow)?;
+    let encoded = postcard::to_allocvec(&next).map_err(|_| ContractError::SerializationFailed)?;
+    ffi::write_storage(&contract, SEQUENCE_KEY, &encoded)?;
+    Ok(sequence)
+}
+
 /// Emit an event that can be indexed by off-chain services (optimized - direct serialization)
 pub fn emit<T: Serialize>(topic: &str, data: &T) {
+    emit_indexed(topic, &[], data);
+}
+
+/// Indexed topic of `value`: `blake3(postcard(value))`
+pub fn indexed_topic<T: Serialize + ?Sized>(value: &T) -> [u8; 32] {
+    // Fields worth indexing (strings, ids) always encode.
+    crypto::hash_blake3(&postcard::to_allocvec(value).unwrap_or_default())
+}
+
+/// Emit an event with indexed topics the node can filter on; topics past
+/// [`MAX_INDEXED_TOPICS`] are dropped
+pub fn emit_indexed<T: Serialize>(topic: &str, indexed: &[[u8; 32]], data: &T) {
+    let indexed = &indexed[..indexed.len().min(MAX_INDEXED_TOPICS)];
     // Use postcard for efficient binary serialization
-    if let Ok(data_bytes) = postcard::to_allocvec(data) {
-        ffi::emit_event_internal(topic, &data_bytes);
+    let Ok(data_bytes) = postcard::to_allocvec(data) else {
+        return;
+    };
+    if let Ok(sequence) = next_sequence() {
+        ffi::emit_event_internal(topic, indexed, sequence, &data_bytes);
     }
 }
 
@@ -16,6 +76,34 @@ pub fn log(message: &str) {
     ffi::log_message(message);
 }
 
+/// Format and [`log`] a message, like `log(&format!(..))`
+///
+/// With the `minimal` feature the message is neither formatted nor logged,
+/// which keeps the formatting code out of the wasm binary. The arguments
+/// are still type-checked but not evaluated.
+#[cfg(not(feature = "minimal"))]
+#[macro_export]
+macro_rules! log {
+    ($($arg:tt)*) => {
+        $crate::events::log(&$crate::prelude::format!($($arg)*))
+    };
+}
+
+/// Format and [`log`] a message, like `log(&format!(..))`
+///
+/// With the `minimal` feature the message is neither formatted nor logged,
+/// which keeps the formatting code out of the wasm binary. The arguments
+/// are still type-checked but not evaluated.
+#[cfg(feature = "minimal")]
+#[macro_export]
+macro_rules! log {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = ::core::format_args!($($arg)*);
+        }
+    };
+}
+
 /// Helper macro for creating structured events (memory pool optimized)
 #[macro_export]
 macro_rules! event {
@@ -40,3 +128,24 @@ macro_rules! event {
         }
     };
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::ffi::mock;
+
+    #[test]
+    fn log_macro_formats_unless_minimal() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+
+        crate::log!("counter at {}", 1);
+        let logs = mock::take_logs();
+        if cfg!(feature = "minimal") {
+            assert!(logs.is_empty());
+        } else {
+            assert_eq!(logs, ["counter at 1"]);
+        }
+    }
+}
@@ -38,7 +38,16 @@ mod host {
 
         pub fn log(msg_ptr: i32, msg_len: i32);
 
-        pub fn emit_event(topic_ptr: i32, topic_len: i32, data_ptr: i32, data_len: i32);
+        /// `indexed_ptr` points at `indexed_count` consecutive 32-byte topics
+        pub fn emit_indexed_event(
+            topic_ptr: i32,
+            topic_len: i32,
+            indexed_ptr: i32,
+            indexed_count: i32,
+            sequence: u64,
+            data_ptr: i32,
+            data_len: i32,
+        );
 
         pub fn transfer(to_ptr: i32, to_len: i32, amount: u64) -> i32;
 
@@ -52,6 +61,29 @@ mod host {
         pub fn read_call_data(buffer_ptr: i32, buffer_len: i32) -> i32;
         pub fn write_return_data(buffer_ptr: i32, buffer_len: i32) -> i32;
 
+        pub fn call_contract(
+            address_ptr: i32,
+            address_len: i32,
+            method_ptr: i32,
+            method_len: i32,
+            args_ptr: i32,
+            args_len: i32,
+            result_ptr: i32,
+            result_len_ptr: i32,
+        ) -> i32;
+
+        pub fn is_contract(address_ptr: i32, address_len: i32) -> i32;
+
+        pub fn instantiate_contract(
+            code_hash_ptr: i32,
+            salt_ptr: i32,
+            salt_len: i32,
+            args_ptr: i32,
+            args_len: i32,
+            address_ptr: i32,
+        ) -> i32;
+
+        pub fn secp256k1_recover(hash_ptr: i32, signature_ptr: i32, pubkey_ptr: i32) -> i32;
     }
 
     pub fn read_storage(account: &str, key: &str) -> ContractResult<Vec<u8>> {
@@ -103,11 +135,14 @@ mod host {
         }
     }
 
-    pub fn emit_event_internal(topic: &str, data: &[u8]) {
+    pub fn emit_event_internal(topic: &str, indexed: &[[u8; 32]], sequence: u64, data: &[u8]) {
         unsafe {
-            emit_event(
+            emit_indexed_event(
                 topic.as_ptr() as i32,
                 topic.len() as i32,
+                indexed.as_ptr() as i32,
+                indexed.len() as i32,
+                sequence,
                 data.as_ptr() as i32,
                 data.len() as i32,
             );
@@ -176,6 +211,84 @@ mod host {
         }
     }
 
+    pub fn call_contract_internal(
+        address: &str,
+        method: &str,
+        args: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        const MAX_RESULT_SIZE: usize = 65_536;
+        let mut result = vec![0_u8; MAX_RESULT_SIZE];
+        let mut result_len: i32 = 0;
+
+        let status = unsafe {
+            call_contract(
+                address.as_ptr() as i32,
+                address.len() as i32,
+                method.as_ptr() as i32,
+                method.len() as i32,
+                args.as_ptr() as i32,
+                args.len() as i32,
+                result.as_mut_ptr() as i32,
+                &mut result_len as *mut i32 as i32,
+            )
+        };
+
+        if status < 0 || result_len < 0 || result_len as usize > MAX_RESULT_SIZE {
+            return Err(ContractError::ContractCallFailed(String::from(method)));
+        }
+        result.truncate(result_len as usize);
+        Ok(result)
+    }
+
+    pub fn is_contract_internal(address: &str) -> bool {
+        unsafe { is_contract(address.as_ptr() as i32, address.len() as i32) == 1 }
+    }
+
+    pub fn instantiate_contract_internal(
+        code_hash: &[u8; 32],
+        salt: &[u8],
+        init_args: &[u8],
+    ) -> ContractResult<String> {
+        let mut address = vec![0_u8; 128];
+        let len = unsafe {
+            instantiate_contract(
+                code_hash.as_ptr() as i32,
+                salt.as_ptr() as i32,
+                salt.len() as i32,
+                init_args.as_ptr() as i32,
+                init_args.len() as i32,
+                address.as_mut_ptr() as i32,
+            )
+        };
+
+        if len <= 0 || len as usize > address.len() {
+            return Err(ContractError::ContractCallFailed(String::from(
+                "instantiate_contract",
+            )));
+        }
+        address.truncate(len as usize);
+        Ok(String::from_utf8_lossy(&address).into_owned())
+    }
+
+    pub fn secp256k1_recover_internal(
+        digest: &[u8; 32],
+        signature: &[u8; 65],
+    ) -> ContractResult<Option<[u8; 33]>> {
+        let mut pubkey = [0_u8; 33];
+        let result = unsafe {
+            secp256k1_recover(
+                digest.as_ptr() as i32,
+                signature.as_ptr() as i32,
+                pubkey.as_mut_ptr() as i32,
+            )
+        };
+        match result {
+            1 => Ok(Some(pubkey)),
+            0 => Ok(None),
+            _ => Err(ContractError::InvalidSignature),
+        }
+    }
+
     fn hash_blake3_bytes(data: &[u8]) -> [u8; 32] {
         let digest = blake3::hash(data);
         *digest.as_bytes()
@@ -188,8 +301,8 @@ mod host {
     ) -> ContractResult<bool> {
         use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 
-        let verifying_key = VerifyingKey::from_bytes(pubkey)
-            .map_err(|_| ContractError::InvalidSignature)?;
+        let verifying_key =
+            VerifyingKey::from_bytes(pubkey).map_err(|_| ContractError::InvalidSignature)?;
         let signature = Signature::from_bytes(signature);
 
         match verifying_key.verify(message, &signature) {
@@ -198,37 +311,19 @@ mod host {
         }
     }
 
-    pub fn hash_blake3_internal(data_ptr: i32, data_len: i32, output_ptr: i32) {
-        unsafe {
-            let data = core::slice::from_raw_parts(data_ptr as *const u8, data_len as usize);
-            let digest = hash_blake3_bytes(data);
-            let output_slice = core::slice::from_raw_parts_mut(output_ptr as *mut u8, 32);
-            output_slice.copy_from_slice(&digest);
-        }
+    pub fn hash_blake3_internal(data: &[u8]) -> [u8; 32] {
+        hash_blake3_bytes(data)
     }
 
     pub fn verify_signature_internal(
-        pubkey_ptr: i32,
-        message_ptr: i32,
-        message_len: i32,
-        signature_ptr: i32,
+        pubkey: &[u8; 32],
+        message: &[u8],
+        signature: &[u8; 64],
     ) -> i32 {
-        unsafe {
-            let pubkey_slice = core::slice::from_raw_parts(pubkey_ptr as *const u8, 32);
-            let message =
-                core::slice::from_raw_parts(message_ptr as *const u8, message_len as usize);
-            let signature_slice = core::slice::from_raw_parts(signature_ptr as *const u8, 64);
-
-            let mut pubkey = [0_u8; 32];
-            pubkey.copy_from_slice(pubkey_slice);
-            let mut signature = [0_u8; 64];
-            signature.copy_from_slice(signature_slice);
-
-            match verify_signature_slice(&pubkey, message, &signature) {
-                Ok(true) => 1,
-                Ok(false) => 0,
-                Err(_) => -1,
-            }
+        match verify_signature_slice(pubkey, message, signature) {
+            Ok(true) => 1,
+            Ok(false) => 0,
+            Err(_) => -1,
         }
     }
 
@@ -267,14 +362,55 @@ mod host {
 mod host {
     use super::{ContractError, ContractResult, String, Vec};
     use alloc::string::ToString;
+    use alloc::sync::Arc;
     use spin::Mutex;
 
     #[derive(Clone, Debug)]
     pub struct EventRecord {
         pub topic: String,
+        /// Indexed topics, empty for events emitted without any
+        pub indexed: Vec<[u8; 32]>,
+        /// Position among every event the contract has emitted
+        pub sequence: u64,
         pub data: Vec<u8>,
     }
 
+    /// Cross-contract call observed by the mock runtime.
+    #[derive(Clone, Debug)]
+    pub struct ContractCallRecord {
+        pub address: String,
+        pub method: String,
+        pub args: Vec<u8>,
+    }
+
+    /// Contract instantiation observed by the mock runtime.
+    #[derive(Clone, Debug)]
+    pub struct InstantiationRecord {
+        pub code_hash: [u8; 32],
+        pub salt: Vec<u8>,
+        pub init_args: Vec<u8>,
+        pub address: String,
+    }
+
+    /// Host calls made since the counters were last taken.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct HostCalls {
+        pub storage_reads: u64,
+        pub storage_writes: u64,
+        pub bytes_read: u64,
+        pub bytes_written: u64,
+        pub events: u64,
+        pub event_bytes: u64,
+        pub logs: u64,
+        /// Sender, contract address, block, value and call data lookups
+        pub context_reads: u64,
+        pub return_bytes: u64,
+        pub contract_calls: u64,
+    }
+
+    /// Handler standing in for another contract's entrypoint in tests.
+    pub type ContractHandler = Arc<dyn Fn(&[u8]) -> ContractResult<Vec<u8>> + Send + Sync>;
+
     #[derive(Default)]
     pub struct MockRuntime {
         storage: alloc::collections::BTreeMap<(String, String), Vec<u8>>,
@@ -287,6 +423,12 @@ mod host {
         return_data: Vec<u8>,
         events: Vec<EventRecord>,
         logs: Vec<String>,
+        contract_handlers: alloc::collections::BTreeMap<(String, String), ContractHandler>,
+        contract_calls: Vec<ContractCallRecord>,
+        contract_accounts: alloc::collections::BTreeSet<String>,
+        instantiations: Vec<InstantiationRecord>,
+        secp256k1_keys: alloc::collections::BTreeMap<([u8; 32], [u8; 65]), [u8; 33]>,
+        host_calls: HostCalls,
     }
 
     impl MockRuntime {
@@ -294,24 +436,35 @@ mod host {
             self.storage.clear();
             self.events.clear();
             self.logs.clear();
+            self.contract_handlers.clear();
+            self.contract_calls.clear();
+            self.contract_accounts.clear();
+            self.instantiations.clear();
+            self.secp256k1_keys.clear();
             self.call_data.clear();
             self.return_data.clear();
             self.block_height = 0;
             self.block_timestamp = 0;
             self.value = 0;
+            self.host_calls = HostCalls::default();
         }
 
         fn storage_key(account: &str, key: &str) -> (String, String) {
             (account.to_string(), key.to_string())
         }
 
-        fn read_storage(&self, account: &str, key: &str) -> ContractResult<Vec<u8>> {
+        fn read_storage(&mut self, account: &str, key: &str) -> ContractResult<Vec<u8>> {
             let lookup = Self::storage_key(account, key);
-            Ok(self.storage.get(&lookup).cloned().unwrap_or_else(Vec::new))
+            let value = self.storage.get(&lookup).cloned().unwrap_or_else(Vec::new);
+            self.host_calls.storage_reads += 1;
+            self.host_calls.bytes_read += value.len() as u64;
+            Ok(value)
         }
 
         fn write_storage(&mut self, account: &str, key: &str, value: &[u8]) -> ContractResult<()> {
             let lookup = Self::storage_key(account, key);
+            self.host_calls.storage_writes += 1;
+            self.host_calls.bytes_written += value.len() as u64;
             if value.is_empty() {
                 self.storage.remove(&lookup);
             } else {
@@ -321,12 +474,23 @@ mod host {
         }
 
         fn log(&mut self, message: &str) {
+            self.host_calls.logs += 1;
             self.logs.push(message.to_string());
         }
 
-        fn emit_event_internal(&mut self, topic: &str, data: &[u8]) {
+        fn emit_event_internal(
+            &mut self,
+            topic: &str,
+            indexed: &[[u8; 32]],
+            sequence: u64,
+            data: &[u8],
+        ) {
+            self.host_calls.events += 1;
+            self.host_calls.event_bytes += (topic.len() + indexed.len() * 32 + data.len()) as u64;
             self.events.push(EventRecord {
                 topic: topic.to_string(),
+                indexed: indexed.to_vec(),
+                sequence,
                 data: data.to_vec(),
             });
         }
@@ -360,8 +524,8 @@ mod host {
         with_runtime(|rt| rt.log(message));
     }
 
-    pub fn emit_event_internal(topic: &str, data: &[u8]) {
-        with_runtime(|rt| rt.emit_event_internal(topic, data));
+    pub fn emit_event_internal(topic: &str, indexed: &[[u8; 32]], sequence: u64, data: &[u8]) {
+        with_runtime(|rt| rt.emit_event_internal(topic, indexed, sequence, data));
     }
 
     pub fn transfer_tokens(_to: &str, _amount: u64) -> ContractResult<()> {
@@ -370,36 +534,137 @@ mod host {
     }
 
     pub fn block_height() -> u64 {
-        with_runtime(|rt| rt.block_height)
+        with_runtime(|rt| {
+            rt.host_calls.context_reads += 1;
+            rt.block_height
+        })
     }
 
     pub fn block_timestamp() -> u64 {
-        with_runtime(|rt| rt.block_timestamp)
+        with_runtime(|rt| {
+            rt.host_calls.context_reads += 1;
+            rt.block_timestamp
+        })
     }
 
     pub fn sender() -> String {
-        with_runtime(|rt| rt.sender.clone())
+        with_runtime(|rt| {
+            rt.host_calls.context_reads += 1;
+            rt.sender.clone()
+        })
     }
 
     pub fn contract_address() -> String {
-        with_runtime(|rt| rt.contract_address.clone())
+        with_runtime(|rt| {
+            rt.host_calls.context_reads += 1;
+            rt.contract_address.clone()
+        })
     }
 
     pub fn value() -> u64 {
-        with_runtime(|rt| rt.value)
+        with_runtime(|rt| {
+            rt.host_calls.context_reads += 1;
+            rt.value
+        })
     }
 
     pub fn read_call_data_internal() -> ContractResult<Vec<u8>> {
-        with_runtime(|rt| Ok(rt.call_data.clone()))
+        with_runtime(|rt| {
+            rt.host_calls.context_reads += 1;
+            Ok(rt.call_data.clone())
+        })
     }
 
     pub fn write_return_data_internal(data: &[u8]) -> ContractResult<()> {
         with_runtime(|rt| {
+            rt.host_calls.return_bytes += data.len() as u64;
             rt.return_data = data.to_vec();
             Ok(())
         })
     }
 
+    pub fn call_contract_internal(
+        address: &str,
+        method: &str,
+        args: &[u8],
+    ) -> ContractResult<Vec<u8>> {
+        // The handler runs outside the runtime lock so it can use the mock host itself.
+        let handler = with_runtime(|rt| {
+            rt.host_calls.contract_calls += 1;
+            rt.contract_calls.push(ContractCallRecord {
+                address: address.to_string(),
+                method: method.to_string(),
+                args: args.to_vec(),
+            });
+            rt.contract_handlers
+                .get(&(address.to_string(), method.to_string()))
+                .cloned()
+        });
+
+        match handler {
+            Some(handler) => handler(args),
+            None => Err(ContractError::ContractCallFailed(alloc::format!(
+                "no mock handler for {}::{}",
+                address,
+                method
+            ))),
+        }
+    }
+
+    pub fn is_contract_internal(address: &str) -> bool {
+        with_runtime(|rt| rt.contract_accounts.contains(address))
+    }
+
+    /// Derive a deterministic address from (deployer, code hash, salt); the
+    /// new contract's `initialize` is not executed by the mock.
+    pub fn instantiate_contract_internal(
+        code_hash: &[u8; 32],
+        salt: &[u8],
+        init_args: &[u8],
+    ) -> ContractResult<String> {
+        with_runtime(|rt| {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(rt.contract_address.as_bytes());
+            hasher.update(code_hash);
+            hasher.update(salt);
+            let digest = hasher.finalize();
+            let address = alloc::format!("0x{}", hex::encode(&digest.as_bytes()[..20]));
+
+            if !rt.contract_accounts.insert(address.clone()) {
+                return Err(ContractError::ContractCallFailed(alloc::format!(
+                    "address {} already deployed",
+                    address
+                )));
+            }
+            rt.instantiations.push(InstantiationRecord {
+                code_hash: *code_hash,
+                salt: salt.to_vec(),
+                init_args: init_args.to_vec(),
+                address: address.clone(),
+            });
+            Ok(address)
+        })
+    }
+
+    pub fn secp256k1_recover_internal(
+        digest: &[u8; 32],
+        signature: &[u8; 65],
+    ) -> ContractResult<Option<[u8; 33]>> {
+        Ok(with_runtime(|rt| {
+            rt.secp256k1_keys.get(&(*digest, *signature)).copied()
+        }))
+    }
+
+    pub fn register_secp256k1_signature(
+        digest: &[u8; 32],
+        signature: &[u8; 65],
+        pubkey: &[u8; 33],
+    ) {
+        with_runtime(|rt| {
+            rt.secp256k1_keys.insert((*digest, *signature), *pubkey);
+        });
+    }
+
     fn hash_blake3_bytes(data: &[u8]) -> [u8; 32] {
         let digest = blake3::hash(data);
         *digest.as_bytes()
@@ -412,8 +677,8 @@ mod host {
     ) -> ContractResult<bool> {
         use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 
-        let verifying_key = VerifyingKey::from_bytes(pubkey)
-            .map_err(|_| ContractError::InvalidSignature)?;
+        let verifying_key =
+            VerifyingKey::from_bytes(pubkey).map_err(|_| ContractError::InvalidSignature)?;
         let signature = Signature::from_bytes(signature);
 
         match verifying_key.verify(message, &signature) {
@@ -422,37 +687,19 @@ mod host {
         }
     }
 
-    pub fn hash_blake3_internal(data_ptr: i32, data_len: i32, output_ptr: i32) {
-        unsafe {
-            let data = core::slice::from_raw_parts(data_ptr as *const u8, data_len as usize);
-            let digest = hash_blake3_bytes(data);
-            let output_slice = core::slice::from_raw_parts_mut(output_ptr as *mut u8, 32);
-            output_slice.copy_from_slice(&digest);
-        }
+    pub fn hash_blake3_internal(data: &[u8]) -> [u8; 32] {
+        hash_blake3_bytes(data)
     }
 
     pub fn verify_signature_internal(
-        pubkey_ptr: i32,
-        message_ptr: i32,
-        message_len: i32,
-        signature_ptr: i32,
+        pubkey: &[u8; 32],
+        message: &[u8],
+        signature: &[u8; 64],
     ) -> i32 {
-        unsafe {
-            let pubkey_slice = core::slice::from_raw_parts(pubkey_ptr as *const u8, 32);
-            let message =
-                core::slice::from_raw_parts(message_ptr as *const u8, message_len as usize);
-            let signature_slice = core::slice::from_raw_parts(signature_ptr as *const u8, 64);
-
-            let mut pubkey = [0_u8; 32];
-            pubkey.copy_from_slice(pubkey_slice);
-            let mut signature = [0_u8; 64];
-            signature.copy_from_slice(signature_slice);
-
-            match verify_signature_slice(&pubkey, message, &signature) {
-                Ok(true) => 1,
-                Ok(false) => 0,
-                Err(_) => -1,
-            }
+        match verify_signature_slice(pubkey, message, signature) {
+            Ok(true) => 1,
+            Ok(false) => 0,
+            Err(_) => -1,
         }
     }
 
@@ -530,6 +777,10 @@ mod host {
         })
     }
 
+    pub fn logs() -> Vec<String> {
+        with_runtime(|rt| rt.logs.clone())
+    }
+
     pub fn take_return_data() -> Vec<u8> {
         with_runtime(|rt| {
             let mut drained = Vec::new();
@@ -545,6 +796,45 @@ mod host {
         })
     }
 
+    /// Every stored entry, keyed by `(account, key)`
+    pub fn storage_snapshot() -> alloc::collections::BTreeMap<(String, String), Vec<u8>> {
+        with_runtime(|rt| rt.storage.clone())
+    }
+
+    pub fn take_host_calls() -> HostCalls {
+        with_runtime(|rt| core::mem::take(&mut rt.host_calls))
+    }
+
+    pub fn register_contract(address: &str, method: &str, handler: ContractHandler) {
+        with_runtime(|rt| {
+            rt.contract_accounts.insert(address.to_string());
+            rt.contract_handlers
+                .insert((address.to_string(), method.to_string()), handler);
+        });
+    }
+
+    pub fn mark_contract(address: &str) {
+        with_runtime(|rt| {
+            rt.contract_accounts.insert(address.to_string());
+        });
+    }
+
+    pub fn take_instantiations() -> Vec<InstantiationRecord> {
+        with_runtime(|rt| {
+            let mut drained = Vec::new();
+            core::mem::swap(&mut drained, &mut rt.instantiations);
+            drained
+        })
+    }
+
+    pub fn take_contract_calls() -> Vec<ContractCallRecord> {
+        with_runtime(|rt| {
+            let mut drained = Vec::new();
+            core::mem::swap(&mut drained, &mut rt.contract_calls);
+            drained
+        })
+    }
+
     pub use EventRecord as MockEventRecord;
 }
 
@@ -560,8 +850,8 @@ pub(crate) fn log_message(message: &str) {
     host::log_message(message);
 }
 
-pub(crate) fn emit_event_internal(topic: &str, data: &[u8]) {
-    host::emit_event_internal(topic, data);
+pub(crate) fn emit_event_internal(topic: &str, indexed: &[[u8; 32]], sequence: u64, data: &[u8]) {
+    host::emit_event_internal(topic, indexed, sequence, data);
 }
 
 pub fn transfer_tokens(to: &str, amount: u64) -> ContractResult<()> {
@@ -596,15 +886,25 @@ pub(crate) fn write_return_data(data: &[u8]) -> ContractResult<()> {
     host::write_return_data_internal(data)
 }
 
+pub(crate) fn call_contract(address: &str, method: &str, args: &[u8]) -> ContractResult<Vec<u8>> {
+    host::call_contract_internal(address, method, args)
+}
+
+pub(crate) fn is_contract(address: &str) -> bool {
+    host::is_contract_internal(address)
+}
+
+pub(crate) fn instantiate_contract(
+    code_hash: &[u8; 32],
+    salt: &[u8],
+    init_args: &[u8],
+) -> ContractResult<String> {
+    host::instantiate_contract_internal(code_hash, salt, init_args)
+}
+
 /// Hash data with BLAKE3 (public wrapper for crypto module)
 pub fn call_hash_blake3(data: &[u8]) -> [u8; 32] {
-    let mut output = [0u8; 32];
-    host::hash_blake3_internal(
-        data.as_ptr() as i32,
-        data.len() as i32,
-        output.as_mut_ptr() as i32,
-    );
-    output
+    host::hash_blake3_internal(data)
 }
 
 /// Verify signature (public wrapper for crypto module)
@@ -613,12 +913,7 @@ pub fn call_verify_signature(
     message: &[u8],
     signature: &[u8; 64],
 ) -> ContractResult<bool> {
-    let result = host::verify_signature_internal(
-        pubkey.as_ptr() as i32,
-        message.as_ptr() as i32,
-        message.len() as i32,
-        signature.as_ptr() as i32,
-    );
+    let result = host::verify_signature_internal(pubkey, message, signature);
 
     match result {
         1 => Ok(true),
@@ -627,6 +922,14 @@ pub fn call_verify_signature(
     }
 }
 
+/// Recover a compressed secp256k1 public key (public wrapper for crypto module)
+pub fn call_secp256k1_recover(
+    digest: &[u8; 32],
+    signature: &[u8; 65],
+) -> ContractResult<Option<[u8; 33]>> {
+    host::secp256k1_recover_internal(digest, signature)
+}
+
 /// Host-accelerated batch hashing helper
 pub fn batch_hash_blake3(inputs: &[&[u8]]) -> ContractResult<Vec<[u8; 32]>> {
     host::batch_hash_blake3(inputs)
@@ -648,6 +951,7 @@ pub mod mock {
     use alloc::vec::Vec;
 
     pub use host::MockEventRecord as EventRecord;
+    pub use host::{ContractCallRecord, ContractHandler, HostCalls, InstantiationRecord};
 
     pub fn reset() {
         host::reset();
@@ -685,6 +989,11 @@ pub mod mock {
         host::take_logs()
     }
 
+    /// Logs written since the last `take_logs`, leaving them in place
+    pub fn logs() -> Vec<String> {
+        host::logs()
+    }
+
     pub fn take_return_data() -> Vec<u8> {
         host::take_return_data()
     }
@@ -692,4 +1001,48 @@ pub mod mock {
     pub fn inspect_storage(account: &str, key: &str) -> Vec<u8> {
         host::inspect_storage(account, key)
     }
+
+    /// Every stored entry, keyed by `(account, key)`
+    pub fn storage_snapshot() -> alloc::collections::BTreeMap<(String, String), Vec<u8>> {
+        host::storage_snapshot()
+    }
+
+    /// Host calls made since the last `take_host_calls` or `reset`; the
+    /// counters start again from zero.
+    pub fn take_host_calls() -> HostCalls {
+        host::take_host_calls()
+    }
+
+    /// Route calls to `address::method` to `handler` for the rest of the test.
+    pub fn register_contract<F>(address: &str, method: &str, handler: F)
+    where
+        F: Fn(&[u8]) -> crate::error::ContractResult<Vec<u8>> + Send + Sync + 'static,
+    {
+        host::register_contract(address, method, alloc::sync::Arc::new(handler));
+    }
+
+    pub fn take_contract_calls() -> Vec<ContractCallRecord> {
+        host::take_contract_calls()
+    }
+
+    /// Treat `address` as deployed contract code. Addresses passed to
+    /// [`register_contract`] are marked automatically.
+    pub fn mark_contract(address: &str) {
+        host::mark_contract(address);
+    }
+
+    pub fn take_instantiations() -> Vec<InstantiationRecord> {
+        host::take_instantiations()
+    }
+
+    /// Make secp256k1 recovery of `signature` over `digest` return `pubkey`.
+    /// The mock runtime has no curve arithmetic; unregistered signatures
+    /// recover nothing.
+    pub fn register_secp256k1_signature(
+        digest: &[u8; 32],
+        signature: &[u8; 65],
+        pubkey: &[u8; 33],
+    ) {
+        host::register_secp256k1_signature(digest, signature, pubkey);
+    }
 }
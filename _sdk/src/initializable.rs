@@ -0,0 +1,71 @@
+//! One-time initialization with named scopes
+//!
+//! [`initialize`] marks a scope done and fails if it already was, so
+//! whatever it guards runs once per contract. Contracts mark
+//! [`CONTRACT_SCOPE`] from their `initialize` entrypoint; shared modules use
+//! scopes of their own, as [`ownable`](crate::ownable) does, so one module
+//! being set up never blocks another.
+//!
+//! The flag is never cleared. A contract whose other state can be emptied
+//! again (a renounced owner, say) cannot be initialized a second time
+//! through that gap. Failed calls keep their writes, so mark the scope after
+//! the call's own checks and before writing anything else.
+//!
+//! Flags live in the contract's storage under `initializable::<scope>`.
+
+use crate::error::{ContractError, ContractResult};
+use crate::storage::storage;
+use alloc::format;
+use alloc::string::String;
+
+/// Scope of a contract's own `initialize` entrypoint
+pub const CONTRACT_SCOPE: &str = "contract";
+
+fn scope_key(scope: &str) -> String {
+    format!("initializable::{}", scope)
+}
+
+/// Whether `scope` has been initialized
+pub fn is_initialized(scope: &str) -> ContractResult<bool> {
+    Ok(storage().has(&scope_key(scope)))
+}
+
+/// Mark `scope` initialized; fails if it already is
+pub fn initialize(scope: &str) -> ContractResult<()> {
+    if is_initialized(scope)? {
+        return Err(ContractError::InvalidArgument(format!(
+            "{} already initialized",
+            scope
+        )));
+    }
+    storage().set(&scope_key(scope), &true)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    #[test]
+    fn scopes_initialize_once_each() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1deployer0000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+
+        assert!(!is_initialized(CONTRACT_SCOPE).unwrap());
+        initialize(CONTRACT_SCOPE).unwrap();
+        assert!(is_initialized(CONTRACT_SCOPE).unwrap());
+        assert!(matches!(
+            initialize(CONTRACT_SCOPE),
+            Err(ContractError::InvalidArgument(msg)) if msg == "contract already initialized"
+        ));
+        // Other scopes are independent.
+        assert!(!is_initialized("ownable").unwrap());
+        initialize("ownable").unwrap();
+    }
+}
@@ -19,13 +19,46 @@
 
 extern crate alloc;
 
+pub mod access_control;
+pub mod admin_log;
+pub mod bounded;
+pub mod chunked;
+pub mod circuit_breaker;
 pub mod context;
 pub mod crypto;
+pub mod decommission;
+pub mod entrypoint;
 pub mod error;
 pub mod events;
 pub mod ffi;
+pub mod initializable;
+pub mod nonces;
+pub mod ownable;
+pub mod pagination;
+pub mod pausable;
+pub mod rate_limit;
+pub mod reentrancy;
 pub mod security;
 pub mod storage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
+pub mod time;
+pub mod write_cache;
+
+/// With the `minimal` feature, a panic traps at once: its message is never
+/// formatted, so the formatting code stays out of the binary.
+#[cfg(all(feature = "minimal", target_arch = "wasm32", not(test)))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    core::arch::wasm32::unreachable()
+}
+
+/// Serializes tests that share the global mock runtime.
+#[cfg(all(test, not(target_arch = "wasm32")))]
+pub(crate) fn test_lock() -> &'static std::sync::Mutex<()> {
+    static LOCK: std::sync::OnceLock<std::sync::Mutex<()>> = std::sync::OnceLock::new();
+    LOCK.get_or_init(|| std::sync::Mutex::new(()))
+}
 
 /// Common imports for contract development
 pub mod prelude {
@@ -33,10 +66,12 @@ pub mod prelude {
     pub use crate::crypto;
     pub use crate::error::{ContractError, ContractResult};
     pub use crate::events::{emit, log};
+    pub use crate::log;
+    pub use crate::pagination::Page;
     pub use crate::security::safe_math;
     pub use crate::security::validation;
     pub use crate::security::{AccessControl, ReentrancyGuard};
-    pub use crate::storage::{Map, Storage, storage};
+    pub use crate::storage::{IterableMap, Map, Storage, storage};
 
     pub use alloc::format;
     pub use alloc::string::{String, ToString};
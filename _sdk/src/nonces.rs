@@ -0,0 +1,193 @@
+//! Replay protection for signed messages
+//!
+//! Two nonce schemes, each kept under its own storage prefix:
+//!
+//! * [`Nonces`] gives every account one ordered sequence. Messages must be
+//!   submitted in order, and the current value is all a signer needs to
+//!   know. Forwarded meta-transactions and permits use it.
+//! * [`NonceBitmap`] accepts each nonce once, in any order. Nonces are bits
+//!   in 128-bit words, so a run of nearby nonces shares one storage entry.
+//!   Suited to messages that are submitted independently, such as bridge
+//!   releases or batches of signed vouchers.
+//!
+//! Both let the account void outstanding messages in advance:
+//! [`Nonces::skip_to`] moves the sequence forward and
+//! [`NonceBitmap::invalidate`] sets bits without consuming them.
+
+use crate::error::{ContractError, ContractResult};
+use crate::security::safe_math;
+use crate::storage::Map;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+/// Sequential per-account nonces
+pub struct Nonces {
+    map: Map<String, u64>,
+}
+
+impl Nonces {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            map: Map::new(prefix),
+        }
+    }
+
+    /// Nonce the next message from `account` must carry.
+    pub fn current(&self, account: &str) -> ContractResult<u64> {
+        Ok(self.map.get(&account.to_string())?.unwrap_or(0))
+    }
+
+    /// Fail unless `nonce` is the current one for `account`.
+    pub fn check(&self, account: &str, nonce: u64) -> ContractResult<()> {
+        let expected = self.current(account)?;
+        if nonce != expected {
+            return Err(ContractError::InvalidArgument(format!(
+                "Invalid nonce: expected {}",
+                expected
+            )));
+        }
+        Ok(())
+    }
+
+    /// Accept `nonce` for `account` if it is the current one, then advance.
+    pub fn consume(&mut self, account: &str, nonce: u64) -> ContractResult<()> {
+        self.check(account, nonce)?;
+        self.map
+            .set(&account.to_string(), &safe_math::add(nonce, 1)?)
+    }
+
+    /// Void every nonce below `next`; it must be past the current one.
+    pub fn skip_to(&mut self, account: &str, next: u64) -> ContractResult<()> {
+        if next <= self.current(account)? {
+            return Err(ContractError::InvalidArgument(
+                "Nonces can only move forward".to_string(),
+            ));
+        }
+        self.map.set(&account.to_string(), &next)
+    }
+}
+
+/// Bits per bitmap word
+pub const WORD_BITS: u64 = 128;
+
+/// Unordered per-account nonces, each usable once
+pub struct NonceBitmap {
+    map: Map<(String, u64), u128>,
+}
+
+impl NonceBitmap {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            map: Map::new(prefix),
+        }
+    }
+
+    /// Word index and bit of `nonce`
+    pub const fn position(nonce: u64) -> (u64, u128) {
+        (nonce / WORD_BITS, 1u128 << (nonce % WORD_BITS))
+    }
+
+    /// Used and invalidated nonces of `account` in word `word`
+    pub fn word(&self, account: &str, word: u64) -> ContractResult<u128> {
+        Ok(self.map.get(&(account.to_string(), word))?.unwrap_or(0))
+    }
+
+    /// Whether `nonce` has been used or invalidated.
+    pub fn is_used(&self, account: &str, nonce: u64) -> ContractResult<bool> {
+        let (word, bit) = Self::position(nonce);
+        Ok(self.word(account, word)? & bit != 0)
+    }
+
+    /// Mark `nonce` used; fails if it already was.
+    pub fn consume(&mut self, account: &str, nonce: u64) -> ContractResult<()> {
+        let (word, bit) = Self::position(nonce);
+        let bits = self.word(account, word)?;
+        if bits & bit != 0 {
+            return Err(ContractError::InvalidArgument(
+                "Nonce already used".to_string(),
+            ));
+        }
+        self.map.set(&(account.to_string(), word), &(bits | bit))
+    }
+
+    /// Mark every nonce in `mask` of word `word` used, whether or not it
+    /// already was.
+    pub fn invalidate(&mut self, account: &str, word: u64, mask: u128) -> ContractResult<()> {
+        let bits = self.word(account, word)?;
+        self.map.set(&(account.to_string(), word), &(bits | mask))
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    const ALICE: &str = "chert1alice000000000000000000";
+    const BOB: &str = "chert1bob00000000000000000000";
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender(ALICE);
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    #[test]
+    fn sequential_nonces_advance_one_at_a_time() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        let mut nonces = Nonces::new("nonces");
+        assert_eq!(nonces.current(ALICE).unwrap(), 0);
+        nonces.consume(ALICE, 0).unwrap();
+        assert!(matches!(
+            nonces.consume(ALICE, 0),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Invalid nonce: expected 1"
+        ));
+        assert!(nonces.consume(ALICE, 2).is_err());
+        nonces.check(ALICE, 1).unwrap();
+        nonces.consume(ALICE, 1).unwrap();
+        assert_eq!(nonces.current(ALICE).unwrap(), 2);
+        assert_eq!(nonces.current(BOB).unwrap(), 0);
+        assert_eq!(Nonces::new("other").current(ALICE).unwrap(), 0);
+
+        assert!(nonces.skip_to(ALICE, 2).is_err());
+        nonces.skip_to(ALICE, 10).unwrap();
+        assert!(nonces.consume(ALICE, 2).is_err());
+        nonces.consume(ALICE, 10).unwrap();
+    }
+
+    #[test]
+    fn bitmap_nonces_are_single_use_in_any_order() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        let mut nonces = NonceBitmap::new("bitmap");
+        for nonce in [5, 0, 127, 128, u64::MAX] {
+            assert!(!nonces.is_used(ALICE, nonce).unwrap());
+            nonces.consume(ALICE, nonce).unwrap();
+            assert!(nonces.is_used(ALICE, nonce).unwrap());
+            assert!(matches!(
+                nonces.consume(ALICE, nonce),
+                Err(ContractError::InvalidArgument(msg)) if msg == "Nonce already used"
+            ));
+        }
+        assert!(!nonces.is_used(ALICE, 6).unwrap());
+        assert!(!nonces.is_used(BOB, 5).unwrap());
+        // 0, 5 and 127 share the first word; 128 starts the second.
+        assert_eq!(nonces.word(ALICE, 0).unwrap(), 1 | 1 << 5 | 1 << 127);
+        assert_eq!(nonces.word(ALICE, 1).unwrap(), 1);
+        assert_eq!(NonceBitmap::position(u64::MAX), (u64::MAX / 128, 1 << 127));
+
+        nonces.invalidate(BOB, 0, 0b1100).unwrap();
+        assert!(nonces.consume(BOB, 2).is_err());
+        assert!(nonces.consume(BOB, 3).is_err());
+        nonces.consume(BOB, 4).unwrap();
+        nonces.invalidate(BOB, 0, 0b0100).unwrap();
+        assert_eq!(nonces.word(BOB, 0).unwrap(), 0b11100);
+    }
+}
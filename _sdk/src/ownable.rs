@@ -0,0 +1,281 @@
+//! Single-owner administration
+//!
+//! Every contract that has an owner keeps it under the same storage keys and
+//! emits the same events, so indexers and clients treat them alike.
+//! Ownership moves in two steps: the owner nominates a successor with
+//! [`transfer_ownership`], and the transfer only completes once that account
+//! calls [`accept_ownership`]. A mistyped address therefore never locks the
+//! contract. [`renounce_ownership`] leaves the contract without an owner for
+//! good: [`initialize`] is guarded by the `ownable` scope of
+//! [`initializable`](crate::initializable), which outlives the owner.
+//!
+//! Events: `OwnershipTransferStarted` and `OwnershipTransferred`, both with
+//! `previous_owner` and `new_owner`. Renouncing reports `0x0` as the new
+//! owner. Transfers, acceptances and renouncements are also recorded in the
+//! [`admin_log`](crate::admin_log).
+
+use crate::admin_log;
+use crate::context::context;
+use crate::error::ContractError;
+use crate::initializable;
+use crate::storage::storage;
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// Storage key of the current owner
+pub const OWNER_KEY: &str = "ownable::owner";
+/// Storage key of the nominated owner during a two-step transfer
+pub const PENDING_OWNER_KEY: &str = "ownable::pending_owner";
+/// [`initializable`] scope marked by [`initialize`]
+pub const INITIALIZABLE_SCOPE: &str = "ownable";
+
+const ZERO_ADDRESS: &str = "0x0";
+
+/// Why an ownership operation was refused
+#[derive(Clone, Debug)]
+pub enum OwnableError {
+    /// The contract already has an owner
+    AlreadyInitialized,
+    /// The caller is not the owner
+    NotOwner { caller: String },
+    /// The caller is not the nominated owner, or nobody is nominated
+    NotPendingOwner { caller: String },
+    /// The new owner is empty
+    InvalidOwner,
+    /// Storage could not be read or written
+    Storage(ContractError),
+}
+
+impl fmt::Display for OwnableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OwnableError::AlreadyInitialized => write!(f, "Owner already set"),
+            OwnableError::NotOwner { caller } => write!(f, "{} is not the owner", caller),
+            OwnableError::NotPendingOwner { caller } => {
+                write!(f, "{} is not the pending owner", caller)
+            }
+            OwnableError::InvalidOwner => write!(f, "Owner cannot be empty"),
+            OwnableError::Storage(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<ContractError> for OwnableError {
+    fn from(err: ContractError) -> Self {
+        OwnableError::Storage(err)
+    }
+}
+
+impl From<OwnableError> for ContractError {
+    fn from(err: OwnableError) -> Self {
+        match err {
+            OwnableError::NotOwner { .. } | OwnableError::NotPendingOwner { .. } => {
+                ContractError::Unauthorized
+            }
+            OwnableError::Storage(err) => err,
+            other => ContractError::InvalidArgument(other.to_string()),
+        }
+    }
+}
+
+pub type OwnableResult<T> = Result<T, OwnableError>;
+
+/// Set the first owner; fails if the contract has, or ever had, one
+pub fn initialize(owner: &str) -> OwnableResult<()> {
+    if owner.is_empty() {
+        return Err(OwnableError::InvalidOwner);
+    }
+    // Owners set before the scope existed count as initialized too.
+    if storage().has(OWNER_KEY) || initializable::is_initialized(INITIALIZABLE_SCOPE)? {
+        return Err(OwnableError::AlreadyInitialized);
+    }
+
+    initializable::initialize(INITIALIZABLE_SCOPE)?;
+    storage().set(OWNER_KEY, &owner.to_string())?;
+    crate::event!("OwnershipTransferred", previous_owner: ZERO_ADDRESS, new_owner: owner);
+    Ok(())
+}
+
+/// Current owner; `None` before initialization or after renouncing
+pub fn owner() -> OwnableResult<Option<String>> {
+    Ok(storage().get::<String>(OWNER_KEY)?)
+}
+
+/// Account nominated by [`transfer_ownership`], if any
+pub fn pending_owner() -> OwnableResult<Option<String>> {
+    Ok(storage().get::<String>(PENDING_OWNER_KEY)?)
+}
+
+/// Fail unless the caller is the owner
+pub fn only_owner() -> OwnableResult<()> {
+    let ctx = context();
+    let caller = ctx.sender();
+    match owner()? {
+        Some(owner) if owner == caller => Ok(()),
+        _ => Err(OwnableError::NotOwner {
+            caller: caller.to_string(),
+        }),
+    }
+}
+
+/// Nominate `new_owner`; the owner stays in charge until they accept
+///
+/// A later nomination replaces the earlier one.
+pub fn transfer_ownership(new_owner: &str) -> OwnableResult<()> {
+    only_owner()?;
+    if new_owner.is_empty() {
+        return Err(OwnableError::InvalidOwner);
+    }
+
+    let previous = pending_owner()?.unwrap_or_default();
+    storage().set(PENDING_OWNER_KEY, &new_owner.to_string())?;
+    crate::event!(
+        "OwnershipTransferStarted",
+        previous_owner: context().sender(),
+        new_owner: new_owner
+    );
+    admin_log::record(
+        admin_log::TRANSFER_OWNERSHIP,
+        admin_log::PENDING_OWNER_TARGET,
+        previous,
+        new_owner,
+    )?;
+    Ok(())
+}
+
+/// Complete a transfer; only the nominated owner may call this
+pub fn accept_ownership() -> OwnableResult<()> {
+    let ctx = context();
+    let caller = ctx.sender();
+    if pending_owner()?.as_deref() != Some(caller) {
+        return Err(OwnableError::NotPendingOwner {
+            caller: caller.to_string(),
+        });
+    }
+
+    let previous = owner()?.unwrap_or_else(|| ZERO_ADDRESS.to_string());
+    let mut store = storage();
+    store.set(OWNER_KEY, &caller.to_string())?;
+    store.remove(PENDING_OWNER_KEY)?;
+    crate::event!("OwnershipTransferred", previous_owner: &previous, new_owner: caller);
+    admin_log::record(
+        admin_log::ACCEPT_OWNERSHIP,
+        admin_log::OWNER_TARGET,
+        previous,
+        caller,
+    )?;
+    Ok(())
+}
+
+/// Give up ownership for good, cancelling any pending transfer
+pub fn renounce_ownership() -> OwnableResult<()> {
+    only_owner()?;
+
+    let mut store = storage();
+    store.remove(OWNER_KEY)?;
+    store.remove(PENDING_OWNER_KEY)?;
+    crate::event!(
+        "OwnershipTransferred",
+        previous_owner: context().sender(),
+        new_owner: ZERO_ADDRESS
+    );
+    admin_log::record(
+        admin_log::RENOUNCE_OWNERSHIP,
+        admin_log::OWNER_TARGET,
+        context().sender(),
+        "",
+    )?;
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    const OWNER: &str = "chert1owner00000000000000000000";
+    const NEXT: &str = "chert1next000000000000000000000";
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender(OWNER);
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    #[test]
+    fn ownership_moves_in_two_steps() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+
+        initialize(OWNER).unwrap();
+        assert!(matches!(
+            initialize(NEXT),
+            Err(OwnableError::AlreadyInitialized)
+        ));
+        assert_eq!(owner().unwrap().as_deref(), Some(OWNER));
+
+        mock::set_sender(NEXT);
+        assert!(matches!(
+            transfer_ownership(NEXT),
+            Err(OwnableError::NotOwner { .. })
+        ));
+        assert!(matches!(
+            accept_ownership(),
+            Err(OwnableError::NotPendingOwner { .. })
+        ));
+
+        mock::set_sender(OWNER);
+        transfer_ownership(NEXT).unwrap();
+        // The owner keeps control until the transfer is accepted.
+        only_owner().unwrap();
+        assert_eq!(pending_owner().unwrap().as_deref(), Some(NEXT));
+
+        mock::set_sender(NEXT);
+        accept_ownership().unwrap();
+        only_owner().unwrap();
+        assert_eq!(pending_owner().unwrap(), None);
+
+        let topics: alloc::vec::Vec<_> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(
+            topics,
+            [
+                "OwnershipTransferred",
+                "OwnershipTransferStarted",
+                "AdminAction",
+                "OwnershipTransferred",
+                "AdminAction"
+            ]
+        );
+    }
+
+    #[test]
+    fn renouncing_clears_owner_and_nominee() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+
+        initialize(OWNER).unwrap();
+        transfer_ownership(NEXT).unwrap();
+        renounce_ownership().unwrap();
+        assert_eq!(owner().unwrap(), None);
+        assert_eq!(pending_owner().unwrap(), None);
+        assert!(matches!(
+            ContractError::from(only_owner().unwrap_err()),
+            ContractError::Unauthorized
+        ));
+
+        mock::set_sender(NEXT);
+        assert!(accept_ownership().is_err());
+        // Nobody can claim the contract back through initialize.
+        assert!(matches!(
+            initialize(NEXT),
+            Err(OwnableError::AlreadyInitialized)
+        ));
+        assert_eq!(owner().unwrap(), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Bounded query responses
+//!
+//! Queries that list storage must fit one return buffer. [`Page`] carries
+//! the items, the total and a cursor to resume from. [`PageBuilder`] stops
+//! adding items once the encoded page would pass [`MAX_RETURN_BYTES`], so a
+//! page is cut at the same item every time and the caller always gets a
+//! cursor to continue from.
+
+use crate::error::{ContractError, ContractResult};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Largest encoded page a query returns
+pub const MAX_RETURN_BYTES: usize = 4096;
+
+/// Bytes held back for the page's own fields (item count, cursor, total)
+const PAGE_OVERHEAD_BYTES: usize = 32;
+
+/// One page of query results
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Page<T, C = u64> {
+    pub items: Vec<T>,
+    /// Where the next page starts; `None` once everything has been returned
+    pub next_cursor: Option<C>,
+    /// Number of items across all pages
+    pub total: u64,
+}
+
+/// Collects items for a [`Page`] within the return size bound
+pub struct PageBuilder<T> {
+    items: Vec<T>,
+    used: usize,
+    total: u64,
+}
+
+impl<T: Serialize> PageBuilder<T> {
+    pub fn new(total: u64) -> Self {
+        Self {
+            items: Vec::new(),
+            used: 0,
+            total,
+        }
+    }
+
+    /// Add `item` if it fits. Returns false, leaving the page unchanged, when
+    /// it would push the page past [`MAX_RETURN_BYTES`]; fails if even the
+    /// first item is too large, since no cursor could ever get past it.
+    pub fn push(&mut self, item: T) -> ContractResult<bool> {
+        let size = postcard::to_allocvec(&item)
+            .map_err(|_| ContractError::SerializationFailed)?
+            .len();
+        if self.used + size > MAX_RETURN_BYTES - PAGE_OVERHEAD_BYTES {
+            if self.items.is_empty() {
+                return Err(ContractError::InvalidArgument(
+                    "Item exceeds return size bound".to_string(),
+                ));
+            }
+            return Ok(false);
+        }
+        self.used += size;
+        self.items.push(item);
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn finish<C>(self, next_cursor: Option<C>) -> Page<T, C> {
+        Page {
+            items: self.items,
+            next_cursor,
+            total: self.total,
+        }
+    }
+}
+
+/// Page through `total` indexed items, starting at `cursor`
+///
+/// `fetch` loads the item at an index. Up to `limit` items are returned,
+/// fewer if the size bound cuts the page short; `next_cursor` is then the
+/// first index left out.
+pub fn paginate<T, F>(
+    total: u64,
+    cursor: u64,
+    limit: u64,
+    max_limit: u64,
+    mut fetch: F,
+) -> ContractResult<Page<T>>
+where
+    T: Serialize,
+    F: FnMut(u64) -> ContractResult<T>,
+{
+    if limit == 0 || limit > max_limit {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            max_limit
+        )));
+    }
+    let end = total.min(cursor.saturating_add(limit));
+    let mut page = PageBuilder::new(total);
+    for index in cursor..end {
+        if !page.push(fetch(index)?)? {
+            return Ok(page.finish(Some(index)));
+        }
+    }
+    Ok(page.finish((end < total).then_some(end)))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn pages_follow_the_cursor_to_the_end() {
+        let first = paginate(5, 0, 2, 10, |i| Ok(i * 10)).unwrap();
+        assert_eq!(first.items, vec![0, 10]);
+        assert_eq!((first.next_cursor, first.total), (Some(2), 5));
+        let last = paginate(5, 4, 2, 10, |i| Ok(i * 10)).unwrap();
+        assert_eq!((last.items, last.next_cursor), (vec![40], None));
+        let past = paginate(5, 9, 2, 10, |i| Ok(i * 10)).unwrap();
+        assert_eq!((past.items, past.next_cursor), (vec![], None));
+
+        assert!(paginate(5, 0, 0, 10, Ok::<u64, ContractError>).is_err());
+        assert!(matches!(
+            paginate(5, 0, 11, 10, Ok::<u64, ContractError>),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Page size must be 1-10"
+        ));
+    }
+
+    #[test]
+    fn oversized_pages_are_cut_with_a_cursor() {
+        // Each item encodes to 1001 bytes (length prefix + 999 bytes).
+        let item = |_| Ok(vec![7u8; 999]);
+        let page = paginate(10, 0, 10, 10, item).unwrap();
+        assert_eq!(page.items.len(), 4);
+        assert_eq!(page.next_cursor, Some(4));
+        let encoded = postcard::to_allocvec(&page).unwrap();
+        assert!(encoded.len() <= MAX_RETURN_BYTES);
+
+        // The same request always cuts at the same item.
+        assert_eq!(paginate(10, 0, 10, 10, item).unwrap(), page);
+        let rest = paginate(10, 8, 10, 10, item).unwrap();
+        assert_eq!((rest.items.len(), rest.next_cursor), (2, None));
+
+        let huge = paginate(1, 0, 1, 1, |_| Ok(vec![0u8; MAX_RETURN_BYTES]));
+        assert!(huge.is_err());
+    }
+}
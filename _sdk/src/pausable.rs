@@ -0,0 +1,96 @@
+//! Emergency stop
+//!
+//! Contracts keep their pause flag under [`PAUSED_KEY`] and emit `Paused` or
+//! `Unpaused` with the acting `account`, plus the matching
+//! [`admin_log`](crate::admin_log) entry, so monitoring treats every contract
+//! alike. The module does not decide who may pause; the embedding contract
+//! checks its own owner or role before calling [`pause`] or [`unpause`].
+//!
+//! Contracts in this repository pause entry into positions and value
+//! movement (transfers, mints, deposits, swaps, loans) but leave exits such
+//! as withdrawals open, so users are never locked in by a pause.
+
+use crate::admin_log;
+use crate::context::context;
+use crate::error::{ContractError, ContractResult};
+use crate::storage::storage;
+use alloc::string::{String, ToString};
+
+/// Storage key of the pause flag
+pub const PAUSED_KEY: &str = "pausable::paused";
+
+/// Whether the contract is paused
+pub fn paused() -> ContractResult<bool> {
+    Ok(storage().get::<bool>(PAUSED_KEY)?.unwrap_or(false))
+}
+
+/// Fail while the contract is paused
+pub fn when_not_paused() -> ContractResult<()> {
+    if paused()? {
+        return Err(ContractError::InvalidArgument(
+            "Contract is paused".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fail unless the contract is paused
+pub fn when_paused() -> ContractResult<()> {
+    if !paused()? {
+        return Err(ContractError::InvalidArgument(
+            "Contract is not paused".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Pause the contract; the caller must already be authorized
+pub fn pause() -> ContractResult<()> {
+    when_not_paused()?;
+    storage().set(PAUSED_KEY, &true)?;
+    crate::event!("Paused", account: context().sender());
+    admin_log::record(admin_log::PAUSE, admin_log::PAUSED_TARGET, false, true)?;
+    Ok(())
+}
+
+/// Lift a pause; the caller must already be authorized
+pub fn unpause() -> ContractResult<()> {
+    when_paused()?;
+    storage().remove(PAUSED_KEY)?;
+    crate::event!("Unpaused", account: context().sender());
+    admin_log::record(admin_log::UNPAUSE, admin_log::PAUSED_TARGET, true, false)?;
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    #[test]
+    fn pause_flag_round_trips_with_events() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1guardian0000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+
+        assert!(!paused().unwrap());
+        when_not_paused().unwrap();
+        assert!(unpause().is_err());
+
+        pause().unwrap();
+        assert!(paused().unwrap());
+        assert!(when_not_paused().is_err());
+        assert!(pause().is_err());
+
+        unpause().unwrap();
+        assert!(!paused().unwrap());
+        let topics: alloc::vec::Vec<_> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(topics, ["Paused", "AdminAction", "Unpaused", "AdminAction"]);
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+    }
+}
@@ -0,0 +1,297 @@
+//! Storage-backed rate limits
+//!
+//! Two limiters, each keeping per-key state under its own storage prefix.
+//! Keys are usually accounts, but any string works.
+//!
+//! * [`RollingWindow`] allows `limit` units per `window`. A key's window
+//!   opens with its first use and lasts `window` seconds from then; the
+//!   next use after it closes opens a fresh one. A limit of 1 is a
+//!   cooldown between uses.
+//! * [`TokenBucket`] holds up to `capacity` units and refills continuously,
+//!   from empty to full over `refill_period`. Bursts up to the capacity are
+//!   allowed, after which use is paced by the refill rate.
+//!
+//! Limits and periods are passed in rather than stored, so a contract can
+//! keep them in its own configuration and change them without migrating
+//! per-key state. A new limit or capacity applies to windows and buckets
+//! already running.
+//!
+//! ```rust,ignore
+//! use silica_contract_sdk::rate_limit::RollingWindow;
+//! use silica_contract_sdk::time::Duration;
+//!
+//! let mut limit = RollingWindow::new("daily_transfers", config.daily_cap, Duration::from_days(1))?;
+//! limit.check_and_consume(&sender, amount)?;
+//! ```
+
+use crate::error::{ContractError, ContractResult};
+use crate::security::safe_math;
+use crate::storage::Map;
+use crate::time::{self, Duration};
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Serialize};
+
+fn limit_exceeded() -> ContractError {
+    ContractError::InvalidArgument("Rate limit exceeded".to_string())
+}
+
+fn require_period(period: Duration) -> ContractResult<()> {
+    if period == Duration::ZERO {
+        return Err(ContractError::InvalidArgument(
+            "Rate limit period must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Use of a key within one window
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WindowUsage {
+    pub started_at: u64,
+    pub used: u64,
+}
+
+/// At most `limit` units per key in any window of `window` seconds
+pub struct RollingWindow {
+    map: Map<String, WindowUsage>,
+    limit: u64,
+    window: Duration,
+}
+
+impl RollingWindow {
+    pub fn new(prefix: &str, limit: u64, window: Duration) -> ContractResult<Self> {
+        require_period(window)?;
+        Ok(Self {
+            map: Map::new(prefix),
+            limit,
+            window,
+        })
+    }
+
+    /// Latest window of `key`, whether or not it has closed
+    pub fn usage(&self, key: &str) -> ContractResult<Option<WindowUsage>> {
+        self.map.get(&key.to_string())
+    }
+
+    /// First second after the window that starts at `started_at`
+    pub fn closes_at(&self, started_at: u64) -> u64 {
+        started_at.saturating_add(self.window.as_secs())
+    }
+
+    /// Window of `key` that is still open at `now`
+    fn open_window(&self, key: &str, now: u64) -> ContractResult<Option<WindowUsage>> {
+        Ok(self
+            .usage(key)?
+            .filter(|usage| now < self.closes_at(usage.started_at)))
+    }
+
+    /// Units `key` has used in its current window
+    pub fn used(&self, key: &str) -> ContractResult<u64> {
+        Ok(self
+            .open_window(key, time::now()?)?
+            .map_or(0, |usage| usage.used))
+    }
+
+    /// Units `key` may still use in its current window
+    pub fn remaining(&self, key: &str) -> ContractResult<u64> {
+        Ok(self.limit.saturating_sub(self.used(key)?))
+    }
+
+    /// When the current window of `key` closes, if one is open
+    pub fn resets_at(&self, key: &str) -> ContractResult<Option<u64>> {
+        Ok(self
+            .open_window(key, time::now()?)?
+            .map(|usage| self.closes_at(usage.started_at)))
+    }
+
+    /// Forget the window of `key`, restoring its full limit
+    pub fn clear(&mut self, key: &str) -> ContractResult<()> {
+        self.map.remove(&key.to_string())
+    }
+
+    /// Count `amount` against `key`, opening a window if none is open;
+    /// fails without recording anything if the limit would be exceeded.
+    pub fn check_and_consume(&mut self, key: &str, amount: u64) -> ContractResult<()> {
+        let now = time::now()?;
+        let window = self.open_window(key, now)?.unwrap_or(WindowUsage {
+            started_at: now,
+            used: 0,
+        });
+        let used = safe_math::add(window.used, amount)?;
+        if used > self.limit {
+            return Err(limit_exceeded());
+        }
+        self.map.set(
+            &key.to_string(),
+            &WindowUsage {
+                started_at: window.started_at,
+                used,
+            },
+        )
+    }
+}
+
+/// Bucket contents at `updated_at`, in units times the refill period so
+/// partial refills carry over between uses
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct BucketLevel {
+    scaled: u128,
+    updated_at: u64,
+}
+
+/// Up to `capacity` units per key, refilled from empty to full over
+/// `refill_period`
+pub struct TokenBucket {
+    map: Map<String, BucketLevel>,
+    capacity: u64,
+    refill_period: Duration,
+}
+
+impl TokenBucket {
+    pub fn new(prefix: &str, capacity: u64, refill_period: Duration) -> ContractResult<Self> {
+        require_period(refill_period)?;
+        Ok(Self {
+            map: Map::new(prefix),
+            capacity,
+            refill_period,
+        })
+    }
+
+    fn full(&self) -> u128 {
+        self.capacity as u128 * self.refill_period.as_secs() as u128
+    }
+
+    /// Scaled level of `key` at `now`; buckets start full
+    fn level(&self, key: &str, now: u64) -> ContractResult<u128> {
+        Ok(match self.map.get(&key.to_string())? {
+            Some(level) => {
+                let elapsed = now.saturating_sub(level.updated_at) as u128;
+                level
+                    .scaled
+                    .saturating_add(elapsed * self.capacity as u128)
+                    .min(self.full())
+            }
+            None => self.full(),
+        })
+    }
+
+    /// Units `key` may use now
+    pub fn remaining(&self, key: &str) -> ContractResult<u64> {
+        let level = self.level(key, time::now()?)?;
+        safe_math::cast(level / self.refill_period.as_secs() as u128)
+    }
+
+    /// Take `amount` from the bucket of `key`; fails without recording
+    /// anything if it holds less.
+    pub fn check_and_consume(&mut self, key: &str, amount: u64) -> ContractResult<()> {
+        let now = time::now()?;
+        let cost = amount as u128 * self.refill_period.as_secs() as u128;
+        let scaled = self
+            .level(key, now)?
+            .checked_sub(cost)
+            .ok_or_else(limit_exceeded)?;
+        self.map.set(
+            &key.to_string(),
+            &BucketLevel {
+                scaled,
+                updated_at: now,
+            },
+        )
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    const ALICE: &str = "chert1alice000000000000000000";
+    const BOB: &str = "chert1bob00000000000000000000";
+    const NOW: u64 = 1_700_000_000;
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender(ALICE);
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+    }
+
+    #[test]
+    fn rolling_window_opens_on_first_use() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        assert!(RollingWindow::new("limit", 100, Duration::ZERO).is_err());
+        let mut limit = RollingWindow::new("limit", 100, Duration::from_hours(1)).unwrap();
+        assert_eq!(limit.remaining(ALICE).unwrap(), 100);
+        assert_eq!(limit.resets_at(ALICE).unwrap(), None);
+
+        limit.check_and_consume(ALICE, 60).unwrap();
+        mock::set_block_timestamp(NOW + 1_800);
+        assert!(matches!(
+            limit.check_and_consume(ALICE, 41),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Rate limit exceeded"
+        ));
+        limit.check_and_consume(ALICE, 40).unwrap();
+        assert_eq!(limit.used(ALICE).unwrap(), 100);
+        assert_eq!(limit.remaining(ALICE).unwrap(), 0);
+        assert_eq!(limit.remaining(BOB).unwrap(), 100);
+        assert_eq!(limit.resets_at(ALICE).unwrap(), Some(NOW + 3_600));
+
+        // The window closes an hour after it opened, not after the last use.
+        mock::set_block_timestamp(NOW + 3_599);
+        assert!(limit.check_and_consume(ALICE, 1).is_err());
+        mock::set_block_timestamp(NOW + 3_600);
+        assert_eq!(limit.remaining(ALICE).unwrap(), 100);
+        assert_eq!(
+            limit.usage(ALICE).unwrap(),
+            Some(WindowUsage {
+                started_at: NOW,
+                used: 100
+            })
+        );
+        limit.check_and_consume(ALICE, 100).unwrap();
+        assert_eq!(limit.resets_at(ALICE).unwrap(), Some(NOW + 7_200));
+        limit.clear(ALICE).unwrap();
+        assert_eq!(limit.remaining(ALICE).unwrap(), 100);
+        limit.check_and_consume(ALICE, 100).unwrap();
+
+        // A lower limit applies to the window already open.
+        let mut lowered = RollingWindow::new("limit", 50, Duration::from_hours(1)).unwrap();
+        assert_eq!(lowered.remaining(ALICE).unwrap(), 0);
+        assert!(lowered.check_and_consume(ALICE, 1).is_err());
+    }
+
+    #[test]
+    fn token_bucket_refills_continuously() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        assert!(TokenBucket::new("bucket", 10, Duration::ZERO).is_err());
+        // 10 units, refilled at one unit per 6 minutes.
+        let mut bucket = TokenBucket::new("bucket", 10, Duration::from_hours(1)).unwrap();
+        assert_eq!(bucket.remaining(ALICE).unwrap(), 10);
+        bucket.check_and_consume(ALICE, 10).unwrap();
+        assert!(bucket.check_and_consume(ALICE, 1).is_err());
+        assert_eq!(bucket.remaining(BOB).unwrap(), 10);
+        assert!(bucket.check_and_consume(BOB, 11).is_err());
+
+        // After 9 minutes one unit is used and half a unit carries over.
+        mock::set_block_timestamp(NOW + 540);
+        bucket.check_and_consume(ALICE, 1).unwrap();
+        assert_eq!(bucket.remaining(ALICE).unwrap(), 0);
+        mock::set_block_timestamp(NOW + 719);
+        assert!(bucket.check_and_consume(ALICE, 1).is_err());
+        mock::set_block_timestamp(NOW + 720);
+        bucket.check_and_consume(ALICE, 1).unwrap();
+
+        // Refills stop at capacity.
+        mock::set_block_timestamp(NOW + 86_400);
+        assert_eq!(bucket.remaining(ALICE).unwrap(), 10);
+        bucket.check_and_consume(ALICE, 4).unwrap();
+        assert_eq!(bucket.remaining(ALICE).unwrap(), 6);
+    }
+}
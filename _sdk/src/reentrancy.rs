@@ -0,0 +1,133 @@
+//! Reentrancy protection with named scopes
+//!
+//! A state-changing entrypoint takes a [`ReentrancyLock`] on a scope for as
+//! long as it runs; a nested call that tries to lock the same scope fails
+//! with `ReentrancyDetected`. Most contracts lock [`GLOBAL_SCOPE`] so every
+//! mutating entrypoint excludes every other. Contracts whose entrypoints are
+//! independent can give each one its own scope, for example one per pool.
+//!
+//! Locks live in the contract's storage under `reentrancy::<scope>`, so they
+//! hold across nested calls into the same contract and never leak between
+//! contracts. The lock is released when the guard is dropped.
+//!
+//! Read-only entrypoints never take a lock. They choose a [`ReadPolicy`]
+//! instead: most values are consistent at every external call and can be
+//! served to a nested caller, while a value that is only correct once the
+//! locked entrypoint finishes (a share price mid-deposit, say) should be
+//! refused.
+
+use crate::error::{ContractError, ContractResult};
+use crate::storage::storage;
+use alloc::format;
+use alloc::string::String;
+
+/// Scope shared by all mutating entrypoints of a contract
+pub const GLOBAL_SCOPE: &str = "global";
+
+fn scope_key(scope: &str) -> String {
+    format!("reentrancy::{}", scope)
+}
+
+/// How a read-only entrypoint answers while its scope is locked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadPolicy {
+    /// Serve the nested read
+    Allow,
+    /// Fail with `ReentrancyDetected`
+    Deny,
+}
+
+/// Held by a running entrypoint; unlocks its scope on drop
+#[must_use = "the scope is unlocked as soon as the lock is dropped"]
+pub struct ReentrancyLock {
+    key: String,
+}
+
+impl Drop for ReentrancyLock {
+    fn drop(&mut self) {
+        // A failed release would leave the scope locked for good, but the
+        // host reverts the whole call when storage fails, lock included.
+        let _ = storage().remove(&self.key);
+    }
+}
+
+/// Unlock `scope` without holding its guard
+pub(crate) fn release(scope: &str) {
+    let _ = storage().remove(&scope_key(scope));
+}
+
+/// Whether an entrypoint holding `scope` is running
+pub fn is_entered(scope: &str) -> ContractResult<bool> {
+    Ok(storage().has(&scope_key(scope)))
+}
+
+/// Lock `scope` until the returned guard is dropped
+pub fn enter(scope: &str) -> ContractResult<ReentrancyLock> {
+    let key = scope_key(scope);
+    let mut store = storage();
+    if store.has(&key) {
+        return Err(ContractError::ReentrancyDetected);
+    }
+    store.set(&key, &true)?;
+    Ok(ReentrancyLock { key })
+}
+
+/// Check a read-only entrypoint against its scope's lock
+pub fn read_only(scope: &str, policy: ReadPolicy) -> ContractResult<()> {
+    if policy == ReadPolicy::Deny && is_entered(scope)? {
+        return Err(ContractError::ReentrancyDetected);
+    }
+    Ok(())
+}
+
+/// Run `f` with `scope` locked
+pub fn scoped<F, R>(scope: &str, f: F) -> ContractResult<R>
+where
+    F: FnOnce() -> ContractResult<R>,
+{
+    let _lock = enter(scope)?;
+    f()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    fn setup(contract: &str) {
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address(contract);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    #[test]
+    fn scopes_lock_independently_and_release_on_drop() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        setup("chert1contract0000000000000000");
+
+        let lock = enter(GLOBAL_SCOPE).unwrap();
+        assert!(matches!(
+            enter(GLOBAL_SCOPE),
+            Err(ContractError::ReentrancyDetected)
+        ));
+        // Other scopes and other contracts are unaffected.
+        drop(enter("pool:1").unwrap());
+        setup("chert1other0000000000000000000");
+        drop(enter(GLOBAL_SCOPE).unwrap());
+        setup("chert1contract0000000000000000");
+
+        read_only(GLOBAL_SCOPE, ReadPolicy::Allow).unwrap();
+        assert!(read_only(GLOBAL_SCOPE, ReadPolicy::Deny).is_err());
+
+        drop(lock);
+        assert!(!is_entered(GLOBAL_SCOPE).unwrap());
+        read_only(GLOBAL_SCOPE, ReadPolicy::Deny).unwrap();
+        let nested = scoped(GLOBAL_SCOPE, || scoped(GLOBAL_SCOPE, || Ok(())));
+        assert!(matches!(nested, Err(ContractError::ReentrancyDetected)));
+        assert!(!is_entered(GLOBAL_SCOPE).unwrap());
+    }
+}
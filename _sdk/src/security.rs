@@ -4,9 +4,9 @@
 //! validation, and constant-time comparison helpers.
 
 use crate::error::{ContractError, ContractResult};
+use crate::reentrancy;
 use crate::storage::{Map, storage};
 use alloc::string::{String, ToString};
-use core::sync::atomic::{AtomicBool, Ordering};
 
 #[inline(always)]
 fn invalid_argument(message: &'static str) -> ContractError {
@@ -24,29 +24,28 @@ fn role_storage_key(role: &str, address: &str) -> String {
     alloc::format!("{}:{}", role, address)
 }
 
-static ENTERED_FLAG: AtomicBool = AtomicBool::new(false);
-
 /// Reentrancy guard API.
+///
+/// Locks [`reentrancy::GLOBAL_SCOPE`]; use [`reentrancy`] directly for
+/// per-entrypoint scopes and read-only nested calls.
 pub struct ReentrancyGuard;
 
 /// RAII guard returned by [`ReentrancyGuard::enter`].
-pub struct ReentrancyGuardGuard;
+pub struct ReentrancyGuardGuard {
+    _lock: reentrancy::ReentrancyLock,
+}
 
 impl ReentrancyGuard {
     /// Attempt to enter the protected section, returning a guard on success.
     pub fn enter() -> ContractResult<ReentrancyGuardGuard> {
-        if ENTERED_FLAG
-            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
-            .is_err()
-        {
-            return Err(ContractError::ReentrancyDetected);
-        }
-        Ok(ReentrancyGuardGuard)
+        Ok(ReentrancyGuardGuard {
+            _lock: reentrancy::enter(reentrancy::GLOBAL_SCOPE)?,
+        })
     }
 
     /// Manually release the guard. Prefer relying on the RAII drop implementation.
     pub fn exit() {
-        ENTERED_FLAG.store(false, Ordering::Release);
+        reentrancy::release(reentrancy::GLOBAL_SCOPE);
     }
 
     /// Execute a closure with reentrancy protection.
@@ -61,12 +60,6 @@ impl ReentrancyGuard {
     }
 }
 
-impl Drop for ReentrancyGuardGuard {
-    fn drop(&mut self) {
-        ReentrancyGuard::exit();
-    }
-}
-
 /// Role-based access control manager.
 pub struct AccessControl;
 
@@ -213,6 +206,63 @@ pub mod safe_math {
     pub const fn saturating_sub(a: u64, b: u64) -> u64 {
         a.saturating_sub(b)
     }
+
+    /// Basis points in 100%
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+
+    /// How to round a division that does not come out even
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Rounding {
+        /// Toward zero
+        Down,
+        /// Away from zero
+        Up,
+        /// To the nearest value, ties away from zero
+        HalfUp,
+    }
+
+    /// `a * b / denominator` with a 128-bit intermediate, so only a result
+    /// above `u64::MAX` overflows.
+    pub fn mul_div(a: u64, b: u64, denominator: u64, rounding: Rounding) -> ContractResult<u64> {
+        if denominator == 0 {
+            return Err(super::invalid_argument("Division by zero"));
+        }
+        let product = a as u128 * b as u128;
+        let denominator = denominator as u128;
+        let quotient = product / denominator;
+        let remainder = product % denominator;
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => remainder != 0,
+            Rounding::HalfUp => remainder >= denominator - remainder,
+        };
+        cast(quotient + round_up as u128)
+    }
+
+    /// `bps` basis points of `amount`. Round fees charged to a user up and
+    /// amounts paid out down.
+    pub fn apply_bps(amount: u64, bps: u64, rounding: Rounding) -> ContractResult<u64> {
+        if bps > BPS_DENOMINATOR {
+            return Err(super::invalid_argument("Basis points above 100%"));
+        }
+        mul_div(amount, bps, BPS_DENOMINATOR, rounding)
+    }
+
+    /// Integer conversion that fails instead of truncating: `Underflow`
+    /// for negative values that do not fit, `Overflow` for the rest.
+    pub fn cast<T, U>(value: T) -> ContractResult<U>
+    where
+        T: Copy + Default + PartialOrd,
+        U: TryFrom<T>,
+    {
+        U::try_from(value).map_err(|_| {
+            if value < T::default() {
+                ContractError::Underflow
+            } else {
+                ContractError::Overflow
+            }
+        })
+    }
 }
 
 /// Input validation helpers.
@@ -341,6 +391,17 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn test_reentrancy_guard() {
+        #[cfg(not(target_arch = "wasm32"))]
+        let _lock = {
+            let lock = crate::test_lock()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            mock::reset();
+            mock::set_contract_address("chert1contract0000000000000000");
+            mock::set_block_height(1);
+            mock::set_block_timestamp(1_700_000_000);
+            lock
+        };
         let guard = ReentrancyGuard::enter().expect("first entry");
         assert!(ReentrancyGuard::enter().is_err());
         drop(guard);
@@ -349,10 +410,114 @@ mod tests {
         drop(guard);
     }
 
+    #[wasm_bindgen_test]
+    fn test_mul_div_rounding() {
+        use safe_math::{Rounding, mul_div};
+
+        const MAX: u64 = u64::MAX;
+        let roundings = [Rounding::Down, Rounding::Up, Rounding::HalfUp];
+        for (a, b, d, expected) in [
+            (0, 0, 1, [0, 0, 0]),
+            (7, 3, 2, [10, 11, 11]),
+            (5, 1, 4, [1, 2, 1]),
+            (6, 1, 4, [1, 2, 2]),
+            (1, 1, 3, [0, 1, 0]),
+            (2, 1, 3, [0, 1, 1]),
+            (MAX, MAX, MAX, [MAX, MAX, MAX]),
+            (MAX, 2, 4, [MAX / 2, MAX / 2 + 1, MAX / 2 + 1]),
+            (MAX, MAX - 1, MAX, [MAX - 1, MAX - 1, MAX - 1]),
+        ] {
+            for (rounding, expected) in roundings.into_iter().zip(expected) {
+                let result = mul_div(a, b, d, rounding).unwrap();
+                assert_eq!(result, expected, "{a} * {b} / {d} {rounding:?}");
+            }
+        }
+
+        // The intermediate is wide, but the result must still fit.
+        assert!(matches!(
+            mul_div(u64::MAX, 2, 1, Rounding::Down),
+            Err(ContractError::Overflow)
+        ));
+        // 31 * 1_190_112_520_884_487_201 == 2 * u64::MAX + 1: rounding up
+        // is what overflows.
+        let (a, b) = (31, 1_190_112_520_884_487_201);
+        assert_eq!(mul_div(a, b, 2, Rounding::Down).unwrap(), u64::MAX);
+        assert!(matches!(
+            mul_div(a, b, 2, Rounding::Up),
+            Err(ContractError::Overflow)
+        ));
+        assert!(matches!(
+            mul_div(1, 1, 0, Rounding::Down),
+            Err(ContractError::InvalidArgument(_))
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_apply_bps() {
+        use safe_math::{BPS_DENOMINATOR, Rounding, apply_bps};
+
+        assert_eq!(apply_bps(10_000, 250, Rounding::Down).unwrap(), 250);
+        assert_eq!(apply_bps(999, 250, Rounding::Down).unwrap(), 24);
+        assert_eq!(apply_bps(999, 250, Rounding::Up).unwrap(), 25);
+        assert_eq!(apply_bps(999, 250, Rounding::HalfUp).unwrap(), 25);
+        assert_eq!(apply_bps(1, 1, Rounding::Down).unwrap(), 0);
+        assert_eq!(apply_bps(1, 1, Rounding::Up).unwrap(), 1);
+        assert_eq!(apply_bps(1, 5_000, Rounding::HalfUp).unwrap(), 1);
+        assert_eq!(apply_bps(1, 4_999, Rounding::HalfUp).unwrap(), 0);
+        assert_eq!(apply_bps(u64::MAX, 0, Rounding::Up).unwrap(), 0);
+        assert_eq!(
+            apply_bps(u64::MAX, BPS_DENOMINATOR, Rounding::Down).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            apply_bps(u64::MAX, 9_999, Rounding::Up).unwrap(),
+            u64::MAX - u64::MAX / BPS_DENOMINATOR
+        );
+        assert!(apply_bps(1, BPS_DENOMINATOR + 1, Rounding::Down).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_checked_casts() {
+        use safe_math::cast;
+
+        assert_eq!(cast::<u64, u32>(u32::MAX as u64).unwrap(), u32::MAX);
+        assert!(matches!(
+            cast::<u64, u32>(u32::MAX as u64 + 1),
+            Err(ContractError::Overflow)
+        ));
+        assert_eq!(cast::<u128, u64>(u64::MAX as u128).unwrap(), u64::MAX);
+        assert!(matches!(
+            cast::<u128, u64>(u128::MAX),
+            Err(ContractError::Overflow)
+        ));
+        assert_eq!(cast::<i64, u64>(0).unwrap(), 0);
+        assert!(matches!(
+            cast::<i64, u64>(-1),
+            Err(ContractError::Underflow)
+        ));
+        assert!(matches!(
+            cast::<i128, i64>(i64::MIN as i128 - 1),
+            Err(ContractError::Underflow)
+        ));
+        assert!(matches!(
+            cast::<u64, i64>(i64::MAX as u64 + 1),
+            Err(ContractError::Overflow)
+        ));
+        assert_eq!(cast::<u64, usize>(42).unwrap(), 42usize);
+        assert_eq!(cast::<u8, u64>(u8::MAX).unwrap(), 255);
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     #[test]
     fn test_access_control() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
 
         let owner = "owner_address";
         AccessControl::initialize(owner).expect("init owner");
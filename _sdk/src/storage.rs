@@ -1,9 +1,11 @@
 //! Storage abstractions for smart contracts (memory pool optimized)
 
-use crate::context::try_context;
 use crate::error::{ContractError, ContractResult};
 use crate::ffi;
+use crate::security::validation;
+use crate::write_cache;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 use core::str;
 use itoa::Buffer;
@@ -12,52 +14,76 @@ use serde::{Deserialize, Serialize};
 /// Low-level storage access
 pub struct Storage;
 
+/// Account whose storage the running contract owns. Only the address is
+/// fetched: a full `try_context` costs five host calls per storage access.
+/// Inside a [`write_cache`] scope it is fetched once.
+pub(crate) fn contract_address() -> ContractResult<String> {
+    if let Some(address) = write_cache::contract() {
+        return Ok(address);
+    }
+    let address = ffi::get_contract_addr();
+    validation::validate_address(&address)?;
+    write_cache::remember_contract(&address);
+    Ok(address)
+}
+
+/// Raw bytes under `key`, `None` when absent; served from the
+/// [`write_cache`] inside a cache scope
+fn read_raw(key: &str) -> ContractResult<Option<Vec<u8>>> {
+    if let Some(cached) = write_cache::lookup(key) {
+        return Ok(cached);
+    }
+    let data = match ffi::read_storage(&contract_address()?, key) {
+        Ok(data) if data.is_empty() => None,
+        Ok(data) => Some(data),
+        Err(ContractError::StorageReadFailed) => None,
+        Err(e) => return Err(e),
+    };
+    write_cache::remember(key, data.as_deref());
+    Ok(data)
+}
+
 impl Storage {
-    /// Get a value from storage (optimized - single context call)
+    /// Get a value from storage
     pub fn get<T>(&self, key: &str) -> ContractResult<Option<T>>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let ctx = try_context()?;
-        match ffi::read_storage(ctx.contract_address(), key) {
-            Ok(data) if data.is_empty() => Ok(None),
-            Ok(data) => {
+        match read_raw(key)? {
+            Some(data) => {
                 let value = postcard::from_bytes(&data)
                     .map_err(|_| ContractError::DeserializationFailed)?;
                 Ok(Some(value))
             }
-            Err(ContractError::StorageReadFailed) => Ok(None),
-            Err(e) => Err(e),
+            None => Ok(None),
         }
     }
 
-    /// Set a value in storage (optimized - single context call)
+    /// Set a value in storage
     pub fn set<T>(&mut self, key: &str, value: &T) -> ContractResult<()>
     where
         T: Serialize,
     {
-        let ctx = try_context()?;
         let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
-
-        ffi::write_storage(ctx.contract_address(), key, &data)
+        if write_cache::is_active() {
+            write_cache::write(key, Some(data));
+            return Ok(());
+        }
+        ffi::write_storage(&contract_address()?, key, &data)
     }
 
-    /// Remove a value from storage (optimized - single context call)
+    /// Remove a value from storage
     pub fn remove(&mut self, key: &str) -> ContractResult<()> {
-        let ctx = try_context()?;
-        ffi::write_storage(ctx.contract_address(), key, &[])
+        if write_cache::is_active() {
+            write_cache::write(key, None);
+            return Ok(());
+        }
+        ffi::write_storage(&contract_address()?, key, &[])
     }
 
-    /// Check if a key exists (optimized - single context call)
+    /// Check if a key exists
     pub fn has(&self, key: &str) -> bool {
-        let ctx = match try_context() {
-            Ok(ctx) => ctx,
-            Err(_) => return false,
-        };
-        match ffi::read_storage(ctx.contract_address(), key) {
-            Ok(data) => !data.is_empty(),
-            Err(_) => false,
-        }
+        matches!(read_raw(key), Ok(Some(_)))
     }
 }
 
@@ -86,8 +112,9 @@ where
         }
     }
 
-    /// Generate storage key for a map entry (memory pool optimized)
-    fn storage_key(&self, key: &K) -> ContractResult<String> {
+    /// Storage key of a map entry: the prefix, `:` and the hex BLAKE3 hash
+    /// of the postcard-encoded key
+    pub fn storage_key(&self, key: &K) -> ContractResult<String> {
         let key_bytes =
             postcard::to_allocvec(key).map_err(|_| ContractError::SerializationFailed)?;
         let key_hash = blake3::hash(&key_bytes);
@@ -231,6 +258,121 @@ where
     }
 }
 
+/// Map with a key index, so its entries can be counted and paged
+///
+/// Values live in a [`Map`], alongside each key's position in a [`Vector`]
+/// of keys. Removal moves the
+/// last key into the removed slot, so every operation touches a fixed number
+/// of storage slots, but key order changes when entries are removed.
+#[derive(Clone)]
+pub struct IterableMap<K, V> {
+    values: Map<K, V>,
+    positions: Map<K, u64>,
+    keys: Vector<K>,
+}
+
+impl<K, V> IterableMap<K, V>
+where
+    K: Serialize + for<'de> Deserialize<'de>,
+    V: Serialize + for<'de> Deserialize<'de>,
+{
+    /// Create a new iterable map with a unique prefix
+    pub fn new(prefix: &str) -> Self {
+        let mut values = String::with_capacity(prefix.len() + 8);
+        values.push_str(prefix);
+        values.push_str("::values");
+        let mut positions = String::with_capacity(prefix.len() + 11);
+        positions.push_str(prefix);
+        positions.push_str("::positions");
+        let mut keys = String::with_capacity(prefix.len() + 6);
+        keys.push_str(prefix);
+        keys.push_str("::keys");
+        Self {
+            values: Map::new(&values),
+            positions: Map::new(&positions),
+            keys: Vector::new(&keys),
+        }
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> ContractResult<u64> {
+        self.keys.len()
+    }
+
+    /// Check if the map is empty
+    pub fn is_empty(&self) -> ContractResult<bool> {
+        self.keys.is_empty()
+    }
+
+    /// Get the value for a key
+    pub fn get(&self, key: &K) -> ContractResult<Option<V>> {
+        self.values.get(key)
+    }
+
+    /// Check if a key exists
+    pub fn contains_key(&self, key: &K) -> ContractResult<bool> {
+        self.positions.contains_key(key)
+    }
+
+    /// Insert or overwrite a value; returns true if the key is new
+    pub fn insert(&mut self, key: &K, value: &V) -> ContractResult<bool> {
+        let is_new = !self.positions.contains_key(key)?;
+        if is_new {
+            let index = self.keys.len()?;
+            self.keys.push(key)?;
+            self.positions.set(key, &index)?;
+        }
+        self.values.set(key, value)?;
+        Ok(is_new)
+    }
+
+    /// Remove a key, returning its value if it was present
+    pub fn remove(&mut self, key: &K) -> ContractResult<Option<V>> {
+        let index = match self.positions.get(key)? {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let value = self.values.get(key)?;
+
+        // Swap the last key into the freed slot, then drop the tail.
+        let last = self.keys.pop()?.ok_or(ContractError::StorageReadFailed)?;
+        if self.keys.len()? > index {
+            self.keys.set(index, &last)?;
+            self.positions.set(&last, &index)?;
+        }
+        self.positions.remove(key)?;
+        self.values.remove(key)?;
+        Ok(value)
+    }
+
+    /// Key at an index position
+    pub fn key_at(&self, index: u64) -> ContractResult<Option<K>> {
+        self.keys.get(index)
+    }
+
+    /// Up to `limit` keys starting at index `start`
+    pub fn keys_page(&self, start: u64, limit: u64) -> ContractResult<Vec<K>> {
+        let end = self.len()?.min(start.saturating_add(limit));
+        let mut keys = Vec::new();
+        for index in start..end {
+            if let Some(key) = self.keys.get(index)? {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Up to `limit` entries starting at index `start`
+    pub fn entries_page(&self, start: u64, limit: u64) -> ContractResult<Vec<(K, V)>> {
+        let mut entries = Vec::new();
+        for key in self.keys_page(start, limit)? {
+            let value = self.get(&key)?.ok_or(ContractError::StorageReadFailed)?;
+            entries.push((key, value));
+        }
+        Ok(entries)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,4 +388,61 @@ mod tests {
         assert!(storage_key.starts_with("balances:"));
         assert!(storage_key.len() > "balances:".len());
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn iterable_map_tracks_keys_through_removal() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        crate::ffi::mock::reset();
+        crate::ffi::mock::set_sender("chert1sender000000000000000000");
+        crate::ffi::mock::set_contract_address("chert1contract0000000000000000");
+        crate::ffi::mock::set_block_height(1);
+        crate::ffi::mock::set_block_timestamp(1_700_000_000);
+
+        let mut holders: IterableMap<String, u64> = IterableMap::new("holders");
+        assert!(holders.is_empty().unwrap());
+        for (name, balance) in [("a", 1), ("b", 2), ("c", 3), ("d", 4)] {
+            assert!(holders.insert(&name.to_string(), &balance).unwrap());
+        }
+        assert!(!holders.insert(&"b".to_string(), &20).unwrap());
+        assert_eq!(holders.len().unwrap(), 4);
+        assert_eq!(holders.get(&"b".to_string()).unwrap(), Some(20));
+
+        // Removing "a" moves "d" into its slot.
+        assert_eq!(holders.remove(&"a".to_string()).unwrap(), Some(1));
+        assert_eq!(holders.remove(&"a".to_string()).unwrap(), None);
+        assert_eq!(holders.keys_page(0, 10).unwrap(), ["d", "b", "c"]);
+        assert_eq!(holders.remove(&"c".to_string()).unwrap(), Some(3));
+        assert_eq!(holders.entries_page(1, 5).unwrap(), [("b".to_string(), 20)]);
+        assert_eq!(holders.keys_page(2, 5).unwrap(), Vec::<String>::new());
+        assert_eq!(holders.key_at(0).unwrap(), Some("d".to_string()));
+        assert!(!holders.contains_key(&"c".to_string()).unwrap());
+
+        // A removed key can come back at the end.
+        assert!(holders.insert(&"a".to_string(), &5).unwrap());
+        assert_eq!(holders.keys_page(0, 10).unwrap(), ["d", "b", "a"]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn storage_calls_look_up_only_the_contract_address() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        crate::ffi::mock::reset();
+        crate::ffi::mock::set_sender("chert1sender000000000000000000");
+        crate::ffi::mock::set_contract_address("chert1contract0000000000000000");
+        crate::ffi::mock::set_block_height(1);
+        crate::ffi::mock::set_block_timestamp(1_700_000_000);
+
+        let mut store = storage();
+        crate::ffi::mock::take_host_calls();
+        store.set("counter", &1u64).unwrap();
+        assert_eq!(store.get::<u64>("counter").unwrap(), Some(1));
+        assert!(store.has("counter"));
+        store.remove("counter").unwrap();
+        assert_eq!(crate::ffi::mock::take_host_calls().context_reads, 4);
+    }
 }
@@ -0,0 +1,451 @@
+//! Randomized property checks for native tests
+//!
+//! [`check`] runs a property once per seed, each time with a fresh [`Rng`]
+//! that drives the operations the property performs against the mock
+//! runtime. A failing case is reported with its seed so it can be replayed
+//! on its own:
+//!
+//! ```text
+//! PROPERTY_SEED=17 cargo test -p crc20-token balances_sum_to_total_supply
+//! ```
+//!
+//! `PROPERTY_CASES` raises or lowers the number of seeds tried. Cases are
+//! not shrunk; properties keep their operation sequences short instead.
+//!
+//! [`fuzz_call`] feeds one input to one of a contract's entrypoint bodies.
+//! Native tests drive it with [`random_call_data`]; the cargo-fuzz targets
+//! under `fuzz/` drive it with libFuzzer.
+//!
+//! [`StorageSnapshot`] captures the mock runtime's storage so a test can
+//! assert which keys an operation changed:
+//!
+//! ```rust,ignore
+//! let before = StorageSnapshot::take();
+//! burn();
+//! before.diff(&StorageSnapshot::take()).assert_only(&[
+//!     &tokens().storage_key(&1)?,
+//!     "balances:*",
+//! ]);
+//! ```
+
+extern crate std;
+
+use crate::entrypoint::MAX_CALL_DATA_BYTES;
+use crate::error::{ContractError, ContractResult};
+use crate::ffi::mock;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Seeds tried by [`check`] when `PROPERTY_CASES` is unset
+pub const DEFAULT_CASES: u64 = 64;
+
+/// Deterministic generator (SplitMix64); the same seed yields the same values
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`; `bound` must be positive
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "Rng::below needs a positive bound");
+        self.next_u64() % bound
+    }
+
+    /// Uniform value in `low..=high`
+    pub fn between(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low <= high, "Rng::between needs low <= high");
+        match (high - low).checked_add(1) {
+            Some(span) => low + self.below(span),
+            None => self.next_u64(),
+        }
+    }
+
+    /// True with probability `percent` / 100
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    /// A uniformly chosen element of a non-empty slice
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// Run `property` for every seed, panicking with the seed of the first failure
+pub fn check(name: &str, mut property: impl FnMut(&mut Rng)) {
+    let seeds = match env_u64("PROPERTY_SEED") {
+        Some(seed) => seed..seed + 1,
+        None => 0..env_u64("PROPERTY_CASES").unwrap_or(DEFAULT_CASES),
+    };
+    for seed in seeds {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| property(&mut Rng::new(seed))));
+        if let Err(cause) = outcome {
+            panic!(
+                "property `{}` failed for seed {} (replay with PROPERTY_SEED={}): {}",
+                name,
+                seed,
+                seed,
+                panic_message(cause.as_ref())
+            );
+        }
+    }
+}
+
+/// An entrypoint body run natively, without its `extern "C"` wrapper
+///
+/// Wrappers cannot unwind, so a panic inside one aborts the test process;
+/// bodies can be caught and inspected.
+pub type Handler = fn() -> ContractResult<()>;
+
+/// `(name, handler)` pairs for `execute_*` entrypoint bodies
+///
+/// ```rust,ignore
+/// pub const HANDLERS: &[(&str, Handler)] = handlers![execute_transfer, execute_balance_of];
+/// ```
+#[macro_export]
+macro_rules! handlers {
+    ($($execute:ident),* $(,)?) => {
+        &[$((stringify!($execute), || $execute().map(|_| ()))),*]
+    };
+}
+
+/// Call one handler with fuzzed call data and check it fails cleanly
+///
+/// The first byte of `input` selects the handler and the rest, cut to
+/// [`MAX_CALL_DATA_BYTES`], is the call data. Panics whose message contains
+/// one of `allowed_panics` are the contract's own assert bounds and pass;
+/// any other panic fails. A call rejected while decoding its arguments must
+/// leave storage untouched and emit nothing.
+pub fn fuzz_call(handlers: &[(&str, Handler)], allowed_panics: &[&str], input: &[u8]) {
+    let Some((&selector, data)) = input.split_first() else {
+        return;
+    };
+    let (name, handler) = handlers[selector as usize % handlers.len()];
+    let data = &data[..data.len().min(MAX_CALL_DATA_BYTES)];
+
+    let before = mock::storage_snapshot();
+    mock::take_events();
+    mock::set_call_data(data);
+    let hook = panic::take_hook();
+    panic::set_hook(std::boxed::Box::new(|_| {}));
+    let outcome = panic::catch_unwind(handler);
+    panic::set_hook(hook);
+
+    match outcome {
+        Err(cause) => {
+            let message = panic_message(cause.as_ref());
+            assert!(
+                allowed_panics
+                    .iter()
+                    .any(|allowed| message.contains(allowed)),
+                "{} panicked on call data {:02x?}: {}",
+                name,
+                data,
+                message
+            );
+        }
+        Ok(Err(ContractError::DeserializationFailed | ContractError::CallDataUnavailable)) => {
+            assert!(
+                mock::storage_snapshot() == before,
+                "{} wrote storage before rejecting call data {:02x?}",
+                name,
+                data
+            );
+            assert!(
+                mock::take_events().is_empty(),
+                "{} emitted events before rejecting call data {:02x?}",
+                name,
+                data
+            );
+        }
+        Ok(_) => {}
+    }
+    mock::take_return_data();
+}
+
+/// Fuzz input for [`fuzz_call`]: a handler selector, then random bytes or a
+/// mutated copy of one of `valid` (well-formed call data)
+pub fn random_call_data(rng: &mut Rng, valid: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = if valid.is_empty() || rng.chance(40) {
+        let len = rng.below(48) as usize;
+        (0..len).map(|_| rng.next_u64() as u8).collect()
+    } else {
+        rng.pick(valid).clone()
+    };
+    match rng.below(4) {
+        0 if !data.is_empty() => {
+            let cut = rng.below(data.len() as u64) as usize;
+            data.truncate(cut);
+        }
+        1 if !data.is_empty() => {
+            let at = rng.below(data.len() as u64) as usize;
+            data[at] ^= 1 << rng.below(8);
+        }
+        2 => data.push(rng.next_u64() as u8),
+        _ => {}
+    }
+    data.insert(0, rng.next_u64() as u8);
+    data
+}
+
+/// Every storage entry of the mock runtime at one point in a test
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageSnapshot {
+    entries: BTreeMap<(String, String), Vec<u8>>,
+}
+
+impl StorageSnapshot {
+    /// Capture the current storage
+    pub fn take() -> Self {
+        StorageSnapshot {
+            entries: mock::storage_snapshot(),
+        }
+    }
+
+    /// Entries that differ between this snapshot and `later`
+    pub fn diff(&self, later: &StorageSnapshot) -> StorageDiff {
+        let mut changes = BTreeMap::new();
+        for (slot, before) in &self.entries {
+            match later.entries.get(slot) {
+                Some(after) if after == before => {}
+                Some(after) => {
+                    changes.insert(
+                        slot.clone(),
+                        Change::Modified {
+                            before: before.clone(),
+                            after: after.clone(),
+                        },
+                    );
+                }
+                None => {
+                    changes.insert(slot.clone(), Change::Removed(before.clone()));
+                }
+            }
+        }
+        for (slot, after) in &later.entries {
+            if !self.entries.contains_key(slot) {
+                changes.insert(slot.clone(), Change::Added(after.clone()));
+            }
+        }
+        StorageDiff { changes }
+    }
+}
+
+/// How one storage entry changed between two snapshots
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added(Vec<u8>),
+    Removed(Vec<u8>),
+    Modified { before: Vec<u8>, after: Vec<u8> },
+}
+
+/// Changed storage entries, keyed by `(account, key)`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageDiff {
+    pub changes: BTreeMap<(String, String), Change>,
+}
+
+impl StorageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changed keys, in any account
+    pub fn keys(&self) -> Vec<&str> {
+        self.changes.keys().map(|(_, key)| key.as_str()).collect()
+    }
+
+    /// Changes to keys that match none of `expected`, one line each
+    ///
+    /// An entry matches a key equal to it; an entry ending in `*` matches
+    /// every key starting with what comes before the `*`, so `"balances:*"`
+    /// allows any entry of the `balances` map. Expected keys that did not
+    /// change are fine.
+    pub fn unexpected(&self, expected: &[&str]) -> Vec<String> {
+        self.changes
+            .iter()
+            .filter(|((_, key), _)| !expected.iter().any(|pattern| key_matches(pattern, key)))
+            .map(|((account, key), change)| format!("{}/{}: {:?}", account, key, change))
+            .collect()
+    }
+
+    /// Panic unless every changed key matches one of `expected`
+    pub fn assert_only(&self, expected: &[&str]) {
+        let unexpected = self.unexpected(expected);
+        assert!(
+            unexpected.is_empty(),
+            "unexpected storage changes:\n{}",
+            unexpected.join("\n")
+        );
+    }
+
+    /// Panic unless nothing changed
+    pub fn assert_unchanged(&self) {
+        self.assert_only(&[]);
+    }
+}
+
+/// A call back into the contract under test, made from inside a mocked
+/// cross-contract call
+///
+/// Mock token crates run one while handling a transfer, the way a hostile
+/// token or a recipient hook would.
+#[derive(Clone, Debug)]
+pub struct Reentry {
+    pub sender: String,
+    pub entrypoint: extern "C" fn(),
+    pub call_data: Vec<u8>,
+}
+
+impl Reentry {
+    /// Call `entrypoint` as `sender` with postcard-encoded `args`
+    pub fn new<A: serde::Serialize + ?Sized>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        args: &A,
+    ) -> Self {
+        Reentry {
+            sender: String::from(sender),
+            entrypoint,
+            call_data: postcard::to_allocvec(args).expect("encode reentry arguments"),
+        }
+    }
+
+    /// Make the call and restore the outer call's sender and call data
+    ///
+    /// Returns the `"<entrypoint> failed: ..."` line the call logged, or
+    /// `None` if it succeeded. Failures of reentries nested inside this one
+    /// are theirs, not this call's. Its return data is discarded.
+    pub fn run(&self) -> Option<String> {
+        let ctx = crate::context::context();
+        let (sender, call_data) = (
+            String::from(ctx.sender()),
+            ctx.call_data().unwrap_or_default(),
+        );
+        let logged = mock::logs().len();
+        let claimed = REPORTED_FAILURES.get();
+        mock::set_sender(&self.sender);
+        mock::set_call_data(&self.call_data);
+        (self.entrypoint)();
+        mock::take_return_data();
+        mock::set_sender(&sender);
+        mock::set_call_data(&call_data);
+
+        let failures: Vec<String> = mock::logs()
+            .into_iter()
+            .skip(logged)
+            .filter(|line| line.contains(" failed: "))
+            .collect();
+        let nested = REPORTED_FAILURES.get() - claimed;
+        if failures.len() <= nested {
+            return None;
+        }
+        REPORTED_FAILURES.set(REPORTED_FAILURES.get() + 1);
+        failures.last().cloned()
+    }
+}
+
+std::thread_local! {
+    /// Failure lines already returned by a [`Reentry::run`]
+    static REPORTED_FAILURES: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+fn key_matches(pattern: &str, key: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == pattern,
+    }
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok()?.parse().ok()
+}
+
+fn panic_message(cause: &(dyn core::any::Any + Send)) -> String {
+    if let Some(message) = cause.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = cause.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        format!("{:?}", cause)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_replay_and_failures_name_them() {
+        let draw = |seed| {
+            let mut rng = Rng::new(seed);
+            (
+                rng.below(10),
+                rng.between(5, 7),
+                *rng.pick(&["a", "b", "c"]),
+            )
+        };
+        assert_eq!(draw(3), draw(3));
+        let mut rng = Rng::new(1);
+        assert!((0..1000).all(|_| (5..=7).contains(&rng.between(5, 7))));
+
+        let failure = panic::catch_unwind(|| {
+            check("never_seven", |rng| {
+                assert_ne!(rng.below(8), 7, "drew seven")
+            })
+        })
+        .unwrap_err();
+        let message = panic_message(failure.as_ref());
+        assert!(message.starts_with("property `never_seven` failed for seed "));
+        assert!(message.contains("drew seven"));
+    }
+
+    #[test]
+    fn diffs_name_the_changed_keys() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+        let mut store = crate::storage::storage();
+        store.set("kept", &1u8).unwrap();
+        store.set("edited", &1u8).unwrap();
+        store.set("dropped", &1u8).unwrap();
+        let before = StorageSnapshot::take();
+
+        store.set("edited", &2u8).unwrap();
+        store.remove("dropped").unwrap();
+        store.set("balances:ab12", &3u8).unwrap();
+        let diff = before.diff(&StorageSnapshot::take());
+
+        assert_eq!(diff.keys(), ["balances:ab12", "dropped", "edited"]);
+        diff.assert_only(&["edited", "dropped", "balances:*", "kept"]);
+        let failure =
+            panic::catch_unwind(|| diff.assert_only(&["edited", "balances:*"])).unwrap_err();
+        let message = panic_message(failure.as_ref());
+        assert!(message.contains("/dropped: Removed"));
+        assert!(!message.contains("edited"));
+        StorageSnapshot::take()
+            .diff(&StorageSnapshot::take())
+            .assert_unchanged();
+    }
+}
@@ -0,0 +1,302 @@
+//! Block time, deadlines and epochs
+//!
+//! Contracts compare the block timestamp with stored times in many places;
+//! this module fixes the boundary conventions once:
+//!
+//! * A [`Deadline`] is the last second something is allowed: it is open
+//!   while `now <= deadline` and has passed once `now > deadline`.
+//! * [`not_before`] passes from `start` on; [`not_after`] passes up to and
+//!   including `end`. Together they bound an inclusive window.
+//! * Epoch `n` of [`Epochs`] covers `[start + n * length, start + (n + 1) * length)`.
+//!
+//! Times are Unix seconds from the block header. Arithmetic fails with
+//! `Overflow` rather than wrapping.
+//!
+//! ```rust,ignore
+//! use silica_contract_sdk::time::{self, Deadline, Duration};
+//!
+//! let deadline = Deadline::after(Duration::from_days(7))?; // stored with the offer
+//! deadline.ensure_open()?;                                   // when accepting it
+//! time::not_before(config.start_time)?;                      // sale has opened
+//! ```
+
+use crate::context::try_context;
+use crate::error::{ContractError, ContractResult};
+use alloc::string::ToString;
+use serde::{Deserialize, Serialize};
+
+/// Current block timestamp
+pub fn now() -> ContractResult<u64> {
+    Ok(try_context()?.block_timestamp())
+}
+
+/// Current block height
+pub fn block_height() -> ContractResult<u64> {
+    Ok(try_context()?.block_height())
+}
+
+/// Fail before `start`
+pub fn not_before(start: u64) -> ContractResult<()> {
+    if now()? < start {
+        return Err(ContractError::InvalidArgument("Too early".to_string()));
+    }
+    Ok(())
+}
+
+/// Fail after `end`
+pub fn not_after(end: u64) -> ContractResult<()> {
+    if now()? > end {
+        return Err(ContractError::InvalidArgument("Too late".to_string()));
+    }
+    Ok(())
+}
+
+/// A span of seconds. The `from_*` constructors are meant for constants
+/// and panic on overflow.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Duration(u64);
+
+impl Duration {
+    pub const ZERO: Duration = Duration(0);
+
+    pub const fn from_secs(secs: u64) -> Self {
+        Duration(secs)
+    }
+
+    pub const fn from_minutes(minutes: u64) -> Self {
+        Duration(minutes * 60)
+    }
+
+    pub const fn from_hours(hours: u64) -> Self {
+        Duration(hours * 3_600)
+    }
+
+    pub const fn from_days(days: u64) -> Self {
+        Duration(days * 86_400)
+    }
+
+    pub const fn as_secs(self) -> u64 {
+        self.0
+    }
+
+    pub fn checked_add(self, other: Duration) -> ContractResult<Duration> {
+        self.0
+            .checked_add(other.0)
+            .map(Duration)
+            .ok_or(ContractError::Overflow)
+    }
+
+    pub fn checked_mul(self, factor: u64) -> ContractResult<Duration> {
+        self.0
+            .checked_mul(factor)
+            .map(Duration)
+            .ok_or(ContractError::Overflow)
+    }
+
+    /// Time from `earlier` to `later`, zero if `later` is not later
+    pub const fn between(earlier: u64, later: u64) -> Self {
+        Duration(later.saturating_sub(earlier))
+    }
+}
+
+/// The last timestamp at which something is still allowed
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Deadline(u64);
+
+impl Deadline {
+    pub const fn at(timestamp: u64) -> Self {
+        Deadline(timestamp)
+    }
+
+    /// `duration` from now
+    pub fn after(duration: Duration) -> ContractResult<Self> {
+        Deadline::at(now()?).extended(duration)
+    }
+
+    pub const fn timestamp(self) -> u64 {
+        self.0
+    }
+
+    /// This deadline moved `by` later
+    pub fn extended(self, by: Duration) -> ContractResult<Self> {
+        self.0
+            .checked_add(by.0)
+            .map(Deadline)
+            .ok_or(ContractError::Overflow)
+    }
+
+    /// Whether `timestamp` is past the deadline
+    pub const fn passed_at(self, timestamp: u64) -> bool {
+        timestamp > self.0
+    }
+
+    /// Whether the current block is past the deadline
+    pub fn has_passed(self) -> ContractResult<bool> {
+        Ok(self.passed_at(now()?))
+    }
+
+    /// Fail once the deadline has passed
+    pub fn ensure_open(self) -> ContractResult<()> {
+        if self.has_passed()? {
+            return Err(ContractError::InvalidArgument(
+                "Deadline has passed".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fail until the deadline has passed
+    pub fn ensure_passed(self) -> ContractResult<()> {
+        if !self.has_passed()? {
+            return Err(ContractError::InvalidArgument(
+                "Deadline has not passed".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Time left until the deadline, zero once it has passed
+    pub fn remaining(self) -> ContractResult<Duration> {
+        Ok(Duration::between(now()?, self.0))
+    }
+}
+
+/// Fixed-length periods counted from `start`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Epochs {
+    pub start: u64,
+    pub length: Duration,
+}
+
+impl Epochs {
+    pub fn new(start: u64, length: Duration) -> ContractResult<Self> {
+        if length == Duration::ZERO {
+            return Err(ContractError::InvalidArgument(
+                "Epoch length must be positive".to_string(),
+            ));
+        }
+        Ok(Epochs { start, length })
+    }
+
+    /// Epoch containing `timestamp`, or `None` before `start`
+    pub fn index_at(&self, timestamp: u64) -> Option<u64> {
+        let elapsed = timestamp.checked_sub(self.start)?;
+        Some(elapsed / self.length.0)
+    }
+
+    /// Epoch of the current block; fails before `start`
+    pub fn current(&self) -> ContractResult<u64> {
+        self.index_at(now()?).ok_or_else(|| {
+            ContractError::InvalidArgument("First epoch has not started".to_string())
+        })
+    }
+
+    /// First second of `epoch`
+    pub fn start_of(&self, epoch: u64) -> ContractResult<u64> {
+        self.length.checked_mul(epoch).and_then(|offset| {
+            self.start
+                .checked_add(offset.0)
+                .ok_or(ContractError::Overflow)
+        })
+    }
+
+    /// First second after `epoch`
+    pub fn end_of(&self, epoch: u64) -> ContractResult<u64> {
+        self.start_of(epoch.checked_add(1).ok_or(ContractError::Overflow)?)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::ffi::mock;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn set_time(timestamp: u64) {
+        mock::set_block_timestamp(timestamp);
+    }
+
+    #[test]
+    fn deadlines_and_windows_are_inclusive() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(7);
+        set_time(NOW);
+        assert_eq!(now().unwrap(), NOW);
+        assert_eq!(block_height().unwrap(), 7);
+
+        let deadline = Deadline::after(Duration::from_hours(1)).unwrap();
+        assert_eq!(deadline, Deadline::at(NOW + 3_600));
+        assert_eq!(deadline.remaining().unwrap(), Duration::from_minutes(60));
+        deadline.ensure_open().unwrap();
+        assert!(deadline.ensure_passed().is_err());
+
+        set_time(NOW + 3_600);
+        deadline.ensure_open().unwrap();
+        assert_eq!(deadline.remaining().unwrap(), Duration::ZERO);
+        set_time(NOW + 3_601);
+        assert!(deadline.has_passed().unwrap());
+        assert!(deadline.ensure_open().is_err());
+        deadline.ensure_passed().unwrap();
+        assert_eq!(deadline.remaining().unwrap(), Duration::ZERO);
+
+        assert!(
+            Deadline::at(u64::MAX)
+                .extended(Duration::from_secs(1))
+                .is_err()
+        );
+        assert!(
+            Duration::from_secs(u64::MAX)
+                .checked_add(Duration::from_secs(1))
+                .is_err()
+        );
+
+        set_time(NOW - 1);
+        assert!(not_before(NOW).is_err());
+        not_after(NOW).unwrap();
+        set_time(NOW);
+        not_before(NOW).unwrap();
+        not_after(NOW).unwrap();
+        set_time(NOW + 1);
+        assert!(not_after(NOW).is_err());
+    }
+
+    #[test]
+    fn epochs_are_half_open() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+
+        assert!(Epochs::new(NOW, Duration::ZERO).is_err());
+        let epochs = Epochs::new(NOW, Duration::from_days(1)).unwrap();
+        assert_eq!(epochs.index_at(NOW - 1), None);
+        assert_eq!(epochs.index_at(NOW), Some(0));
+        assert_eq!(epochs.index_at(NOW + 86_399), Some(0));
+        assert_eq!(epochs.index_at(NOW + 86_400), Some(1));
+        assert_eq!(epochs.start_of(2).unwrap(), NOW + 2 * 86_400);
+        assert_eq!(epochs.end_of(2).unwrap(), NOW + 3 * 86_400);
+        assert_eq!(epochs.index_at(epochs.end_of(2).unwrap() - 1), Some(2));
+        assert!(epochs.start_of(u64::MAX).is_err());
+        assert!(epochs.end_of(u64::MAX).is_err());
+
+        set_time(NOW - 1);
+        assert!(epochs.current().is_err());
+        set_time(NOW + 5 * 86_400 + 10);
+        assert_eq!(epochs.current().unwrap(), 5);
+    }
+}
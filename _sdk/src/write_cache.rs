@@ -0,0 +1,256 @@
+//! Transaction-scoped write-back storage cache
+//!
+//! Inside [`scope`], [`Storage`](crate::storage::Storage) and everything
+//! built on it (`Map`, `Vector`, ...) go through an in-memory cache:
+//!
+//! * The first read of a key goes to the host; later reads, including
+//!   existence checks, are answered from memory.
+//! * Writes and removals only update memory. When the outermost scope ends,
+//!   each changed key is written once, whatever the number of writes to it.
+//! * The contract address is looked up once per scope, however many keys
+//!   miss the cache.
+//!
+//! Storage ends the scope as it would without the cache, whether the body
+//! succeeds or fails. Nested scopes join the outer one. A panic inside a
+//! scope drops the pending writes.
+//!
+//! Pending writes are invisible to other contracts. Call [`flush`] before a
+//! cross-contract call whose callee may read this contract's storage.
+//!
+//! ```rust,ignore
+//! #[entrypoint]
+//! fn transfer(args: TransferArgs) -> ContractResult<()> {
+//!     write_cache::scope(|| {
+//!         let balance = read_balance(&sender)?;   // host read
+//!         write_balance(&sender, balance - 1)?;   // cached
+//!         read_balance(&sender)                   // answered from memory
+//!     })?;                                        // one host write
+//!     Ok(())
+//! }
+//! ```
+
+use crate::error::ContractResult;
+use crate::ffi;
+use crate::storage::contract_address;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use spin::Mutex;
+
+/// Value of a key as the contract currently sees it
+struct Entry {
+    /// `None` when the key is absent or removed
+    value: Option<Vec<u8>>,
+    /// Changed since it was read or last flushed
+    dirty: bool,
+}
+
+#[derive(Default)]
+struct WriteCache {
+    entries: BTreeMap<String, Entry>,
+    /// Address of the running contract, once a storage call has looked it up
+    contract: Option<String>,
+    /// Open scopes; the cache exists while this is above zero
+    depth: u32,
+}
+
+static CACHE: Mutex<Option<WriteCache>> = Mutex::new(None);
+
+/// Closes one scope, dropping the cache with the outermost one
+struct ScopeGuard;
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let mut cache = CACHE.lock();
+        if let Some(active) = cache.as_mut() {
+            active.depth -= 1;
+            if active.depth == 0 {
+                *cache = None;
+            }
+        }
+    }
+}
+
+/// Run `body` with storage cached, then write every changed key once
+///
+/// A flush failure is returned in place of the body's result.
+pub fn scope<T>(body: impl FnOnce() -> ContractResult<T>) -> ContractResult<T> {
+    CACHE.lock().get_or_insert_with(WriteCache::default).depth += 1;
+    let _guard = ScopeGuard;
+    let result = body();
+    let outermost = CACHE.lock().as_ref().is_some_and(|cache| cache.depth == 1);
+    if outermost {
+        flush()?;
+    }
+    result
+}
+
+/// Whether storage calls currently go through the cache
+pub fn is_active() -> bool {
+    CACHE.lock().is_some()
+}
+
+/// Write the changed keys now and keep caching
+pub fn flush() -> ContractResult<()> {
+    let pending: Vec<(String, Option<Vec<u8>>)> = {
+        let mut cache = CACHE.lock();
+        let Some(active) = cache.as_mut() else {
+            return Ok(());
+        };
+        active
+            .entries
+            .iter_mut()
+            .filter(|(_, entry)| entry.dirty)
+            .map(|(key, entry)| {
+                entry.dirty = false;
+                (key.clone(), entry.value.clone())
+            })
+            .collect()
+    };
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let contract = contract_address()?;
+    for (key, value) in pending {
+        ffi::write_storage(&contract, &key, value.as_deref().unwrap_or(&[]))?;
+    }
+    Ok(())
+}
+
+/// Cached value of `key`: `None` when inactive or not cached yet, `Some(None)`
+/// when the key is known to be absent
+pub(crate) fn lookup(key: &str) -> Option<Option<Vec<u8>>> {
+    let cache = CACHE.lock();
+    let entry = cache.as_ref()?.entries.get(key)?;
+    Some(entry.value.clone())
+}
+
+/// Contract address looked up earlier in the scope
+pub(crate) fn contract() -> Option<String> {
+    CACHE.lock().as_ref()?.contract.clone()
+}
+
+/// Remember the contract address for the rest of the scope
+pub(crate) fn remember_contract(address: &str) {
+    if let Some(active) = CACHE.lock().as_mut() {
+        active.contract = Some(address.to_string());
+    }
+}
+
+/// Remember what the host returned for `key`
+pub(crate) fn remember(key: &str, value: Option<&[u8]>) {
+    if let Some(active) = CACHE.lock().as_mut() {
+        active.entries.insert(
+            key.to_string(),
+            Entry {
+                value: value.map(<[u8]>::to_vec),
+                dirty: false,
+            },
+        );
+    }
+}
+
+/// Record a write (`Some`) or removal (`None`) of `key`
+pub(crate) fn write(key: &str, value: Option<Vec<u8>>) {
+    if let Some(active) = CACHE.lock().as_mut() {
+        active
+            .entries
+            .insert(key.to_string(), Entry { value, dirty: true });
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::error::ContractError;
+    use crate::ffi::mock;
+    use crate::storage::{Map, storage};
+    use crate::testing::StorageSnapshot;
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    /// Interleaved reads and writes, failing at the end when `fail` is set
+    fn work(fail: bool) -> ContractResult<u64> {
+        let mut balances: Map<String, u64> = Map::new("balances");
+        let (alice, bob) = ("alice".to_string(), "bob".to_string());
+        for _ in 0..3 {
+            let a = balances.get(&alice)?.unwrap_or(100);
+            balances.set(&alice, &(a - 10))?;
+            let b = balances.get(&bob)?.unwrap_or(0);
+            balances.set(&bob, &(b + 10))?;
+        }
+        storage().set("scratch", &1u8)?;
+        storage().remove("scratch")?;
+        storage().remove("stale")?;
+        if fail {
+            return Err(ContractError::Unauthorized);
+        }
+        Ok(balances.get(&alice)?.unwrap_or(0))
+    }
+
+    #[test]
+    fn cached_runs_end_in_the_same_state() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for fail in [false, true] {
+            setup();
+            storage().set("stale", &7u8).unwrap();
+            let direct = work(fail);
+            let expected = StorageSnapshot::take();
+
+            setup();
+            storage().set("stale", &7u8).unwrap();
+            mock::take_host_calls();
+            let cached = scope(|| work(fail));
+            let calls = mock::take_host_calls();
+            assert_eq!(StorageSnapshot::take(), expected);
+            assert_eq!(cached.ok(), direct.ok());
+            assert!(!is_active());
+            // One read per key, one write per changed key and a single
+            // contract address lookup.
+            assert_eq!(calls.storage_reads, 2);
+            assert_eq!(calls.storage_writes, 4);
+            assert_eq!(calls.context_reads, 1);
+        }
+    }
+
+    #[test]
+    fn nested_scopes_flush_once_and_panics_drop_pending_writes() {
+        let _guard = crate::test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+        scope(|| {
+            storage().set("outer", &1u8)?;
+            scope(|| storage().set("inner", &2u8))?;
+            assert!(
+                !mock::storage_snapshot()
+                    .keys()
+                    .any(|(_, key)| key == "inner")
+            );
+            assert_eq!(storage().get::<u8>("inner")?, Some(2));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(storage().get::<u8>("inner").unwrap(), Some(2));
+        assert!(storage().has("outer"));
+
+        let before = StorageSnapshot::take();
+        let panicked = std::panic::catch_unwind(|| {
+            let _ = scope(|| -> ContractResult<()> {
+                storage().set("outer", &9u8)?;
+                panic!("body panicked");
+            });
+        });
+        assert!(panicked.is_err());
+        assert!(!is_active());
+        before.diff(&StorageSnapshot::take()).assert_unchanged();
+    }
+}
@@ -0,0 +1,272 @@
+//! Contract interface schema export
+//!
+//! Scans every workspace member with `extern "C"` entrypoints and writes one
+//! JSON schema per contract: entrypoint names, argument structs in encoding
+//! order, return types, emitted events and the shared error codes. Explorers,
+//! wallets and client generators read these files instead of the binaries.
+//!
+//! ```text
+//! cargo run -p abi-export -- [--root DIR] [--out DIR] [PACKAGE ...]
+//! ```
+//!
+//! `--root` defaults to the workspace this tool lives in and `--out` to
+//! `target/abi` under it. Naming packages limits the export to them.
+
+mod scan;
+mod schema;
+
+use scan::CrateIndex;
+use schema::ContractSchema;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// A workspace member with its parsed sources
+struct Member {
+    name: String,
+    version: String,
+    description: String,
+    index: CrateIndex,
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(written) => {
+            println!("wrote {} contract schemas", written);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("abi-export: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<usize, String> {
+    let mut root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let mut out = None;
+    let mut only = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => root = PathBuf::from(args.next().ok_or("--root needs a directory")?),
+            "--out" => out = Some(PathBuf::from(args.next().ok_or("--out needs a directory")?)),
+            _ => only.push(arg),
+        }
+    }
+    let out = out.unwrap_or_else(|| root.join("target").join("abi"));
+
+    let schemas = export(&root)?;
+    fs::create_dir_all(&out).map_err(|err| format!("{}: {}", out.display(), err))?;
+    let mut written = 0;
+    for schema in schemas {
+        if !only.is_empty() && !only.contains(&schema.contract) {
+            continue;
+        }
+        let path = out.join(format!("{}.json", schema.contract));
+        let json = serde_json::to_string_pretty(&schema).map_err(|err| err.to_string())?;
+        fs::write(&path, json + "\n").map_err(|err| format!("{}: {}", path.display(), err))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Build the schema of every contract in the workspace at `root`
+fn export(root: &Path) -> Result<Vec<ContractSchema>, String> {
+    let manifest = read(&root.join("Cargo.toml"))?;
+    let errors = match parse(&root.join("crc-errors/src/lib.rs")) {
+        Some(file) => scan::error_codes(&file),
+        None => Vec::new(),
+    };
+
+    let mut shared = CrateIndex::default();
+    let mut contracts = Vec::new();
+    for dir in workspace_members(&manifest) {
+        let dir = root.join(dir);
+        // Members commented out or missing from a checkout are skipped.
+        let Ok(member) = load_member(&dir) else {
+            continue;
+        };
+        if member.index.has_entrypoints() {
+            contracts.push(member);
+        } else {
+            shared.absorb(member.index);
+        }
+    }
+
+    Ok(contracts
+        .into_iter()
+        .map(|member| {
+            let (entrypoints, events) = member.index.entrypoints(&shared);
+            ContractSchema {
+                contract: member.name,
+                version: member.version,
+                description: member.description,
+                entrypoints,
+                events,
+                errors: errors.clone(),
+            }
+        })
+        .collect())
+}
+
+fn load_member(dir: &Path) -> Result<Member, String> {
+    let manifest = read(&dir.join("Cargo.toml"))?;
+    let mut index = CrateIndex::default();
+    let mut sources = Vec::new();
+    collect_sources(&dir.join("src"), &mut sources);
+    sources.sort();
+    for path in sources {
+        // `lib.rs` and `main.rs` are the crate root; other files are modules.
+        let module = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some("lib" | "main") | None => "",
+            Some(stem) => stem,
+        };
+        match parse(&path) {
+            Some(file) => index.add_file(&file, module),
+            None => eprintln!("abi-export: skipping {}", path.display()),
+        }
+    }
+    Ok(Member {
+        name: package_field(&manifest, "name").ok_or("package has no name")?,
+        version: package_field(&manifest, "version").unwrap_or_default(),
+        description: package_field(&manifest, "description").unwrap_or_default(),
+        index,
+    })
+}
+
+fn collect_sources(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_sources(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+fn parse(path: &Path) -> Option<syn::File> {
+    syn::parse_file(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Uncommented entries of the root manifest's `members` list
+fn workspace_members(manifest: &str) -> Vec<String> {
+    manifest
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("members"))
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with(']'))
+        .map(str::trim)
+        .filter(|line| line.starts_with('"'))
+        .map(|line| line.trim_end_matches(',').trim_matches('"').to_string())
+        .collect()
+}
+
+/// A string field of the `[package]` table
+fn package_field(manifest: &str, key: &str) -> Option<String> {
+    manifest
+        .lines()
+        .skip_while(|line| line.trim() != "[package]")
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .find_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            if name.trim() != key {
+                return None;
+            }
+            let value = value.trim();
+            (value.starts_with('"')).then(|| value.trim_matches('"').to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+    }
+
+    #[test]
+    fn crc20_schema_lists_args_returns_and_events() {
+        let schemas = export(&workspace()).unwrap();
+        let crc20 = schemas
+            .iter()
+            .find(|schema| schema.contract == "crc20-token")
+            .unwrap();
+
+        let transfer = crc20
+            .entrypoints
+            .iter()
+            .find(|entry| entry.name == "transfer")
+            .unwrap();
+        let args = transfer.args.as_ref().unwrap();
+        assert_eq!(args.name, "TransferArgs");
+        let fields: Vec<_> = args.fields.iter().map(|f| (&*f.name, &*f.ty)).collect();
+        assert_eq!(fields, [("to", "Address"), ("amount", "u64")]);
+        assert_eq!(transfer.returns, None);
+        // Transfers to the zero address burn, which is audited
+        assert_eq!(transfer.events, ["Transfer", "AdminAction"]);
+
+        let balance_of = crc20
+            .entrypoints
+            .iter()
+            .find(|entry| entry.name == "balance_of")
+            .unwrap();
+        assert_eq!(balance_of.returns.as_deref(), Some("u64"));
+        let checkpoints = crc20
+            .entrypoints
+            .iter()
+            .find(|entry| entry.name == "checkpoints")
+            .unwrap();
+        assert_eq!(checkpoints.returns.as_deref(), Some("Page<Checkpoint>"));
+        assert!(checkpoints.doc.starts_with("List an account's balance"));
+
+        let pause = crc20
+            .entrypoints
+            .iter()
+            .find(|entry| entry.name == "pause")
+            .unwrap();
+        assert_eq!(pause.events, ["Paused", "AdminAction"]);
+        let admin_action = crc20
+            .events
+            .iter()
+            .find(|event| event.topic == "AdminAction")
+            .unwrap();
+        assert_eq!(admin_action.schema.as_deref(), Some("AdminActionV1"));
+        assert_eq!(admin_action.indexed, ["actor", "action", "target"]);
+
+        let transfer_event = crc20
+            .events
+            .iter()
+            .find(|event| event.topic == "Transfer")
+            .unwrap();
+        assert_eq!(transfer_event.schema.as_deref(), Some("TransferV1"));
+        assert_eq!(transfer_event.version, Some(1));
+        assert_eq!(transfer_event.indexed, ["from", "to"]);
+        assert!(
+            crc20
+                .errors
+                .iter()
+                .any(|e| e.code == 302 && e.name == "Paused")
+        );
+    }
+
+    #[test]
+    fn manifest_helpers_skip_comments() {
+        let manifest =
+            "[workspace]\nmembers = [\n    \"_sdk\",\n    # \"old\",\n    \"crc20\",\n]\n";
+        assert_eq!(workspace_members(manifest), ["_sdk", "crc20"]);
+        let package = "[package]\nname = \"demo\"\nedition.workspace = true\n\n[dependencies]\nname = \"x\"\n";
+        assert_eq!(package_field(package, "name").as_deref(), Some("demo"));
+        assert_eq!(package_field(package, "version"), None);
+    }
+}
@@ -0,0 +1,723 @@
+//! Reads contract interfaces out of parsed sources
+//!
+//! Contracts follow one of two layouts. Hand-written ones pair an
+//! `#[unsafe(no_mangle)] extern "C"` wrapper with an `execute_*` function,
+//! which decodes its call data with `read_args` and answers with
+//! `try_respond`. Functions under `#[entrypoint]` state both in their
+//! signature instead. Either way events come from `event!` or
+//! `events::emit`, or from SDK helpers such as `pausable::pause` and the
+//! audit log's `record`; the scanner follows calls from each entrypoint
+//! through the crate's own functions and records what it finds along the
+//! way.
+
+use crate::schema::{Entrypoint, ErrorCode, EventSchema, Field, StructSchema};
+use quote::ToTokens;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{Expr, Item, ItemFn, LitInt, LitStr, ReturnType, Token, Type};
+
+/// SDK and shared-crate modules whose functions are never local
+const EXTERNAL_MODULES: &[&str] = &[
+    "access_control",
+    "admin",
+    "admin_log",
+    "circuit_breaker",
+    "crc_errors",
+    "decommission",
+    "events",
+    "migrations",
+    "ownable",
+    "pagination",
+    "pausable",
+    "reentrancy",
+    "safe_math",
+    "validation",
+];
+
+/// Events emitted inside shared SDK modules, by the function that emits them
+const SDK_EVENTS: &[(&str, &str, &[&str])] = &[
+    ("ownable::initialize", "OwnershipTransferred", OWNERSHIP),
+    (
+        "ownable::transfer_ownership",
+        "OwnershipTransferStarted",
+        OWNERSHIP,
+    ),
+    (
+        "ownable::accept_ownership",
+        "OwnershipTransferred",
+        OWNERSHIP,
+    ),
+    (
+        "ownable::renounce_ownership",
+        "OwnershipTransferred",
+        OWNERSHIP,
+    ),
+    ("pausable::pause", "Paused", &["account"]),
+    ("pausable::unpause", "Unpaused", &["account"]),
+    ("access_control::initialize", "RoleGranted", ROLE_CHANGE),
+    ("access_control::grant_role", "RoleGranted", ROLE_CHANGE),
+    ("access_control::revoke_role", "RoleRevoked", ROLE_CHANGE),
+    ("access_control::renounce_role", "RoleRevoked", ROLE_CHANGE),
+    (
+        "access_control::set_role_admin",
+        "RoleAdminChanged",
+        &["role", "previous", "admin_role"],
+    ),
+    (
+        "decommission::schedule",
+        "DecommissionScheduled",
+        &["account", "eta"],
+    ),
+    (
+        "decommission::cancel",
+        "DecommissionCancelled",
+        &["account"],
+    ),
+    (
+        "decommission::decommission",
+        "Decommissioned",
+        &["account", "at"],
+    ),
+    (
+        "circuit_breaker::set_limit",
+        "OutflowLimitSet",
+        &["account", "asset", "max_outflow", "window"],
+    ),
+    (
+        "circuit_breaker::remove_limit",
+        "OutflowLimitRemoved",
+        &["account", "asset"],
+    ),
+    (
+        "circuit_breaker::record_outflow",
+        "CircuitBreakerTripped",
+        &["asset", "amount", "max_outflow"],
+    ),
+    (
+        "circuit_breaker::reset",
+        "CircuitBreakerReset",
+        &["account", "asset"],
+    ),
+];
+
+/// Functions that record an `AdminActionV1` in the audit log
+const ADMIN_ACTION_RECORDERS: &[&str] = &[
+    "admin::record",
+    "admin_log::record",
+    "ownable::transfer_ownership",
+    "ownable::accept_ownership",
+    "ownable::renounce_ownership",
+    "pausable::pause",
+    "pausable::unpause",
+    "decommission::schedule",
+    "decommission::cancel",
+    "decommission::decommission",
+    "circuit_breaker::set_limit",
+    "circuit_breaker::remove_limit",
+    "circuit_breaker::reset",
+];
+const OWNERSHIP: &[&str] = &["previous_owner", "new_owner"];
+const ROLE_CHANGE: &[&str] = &["role", "account", "sender"];
+
+/// Typed event schema declared with `schema!` in the `events` crate
+#[derive(Clone, Debug)]
+pub struct TypedEvent {
+    pub topic: String,
+    pub version: u16,
+    pub fields: Vec<Field>,
+    pub indexed: Vec<String>,
+}
+
+/// Everything the scanner learned from one crate's sources
+#[derive(Default)]
+pub struct CrateIndex {
+    /// Functions keyed by `module::name`; the crate root is module `""`
+    fns: HashMap<String, ItemFn>,
+    entrypoints: Vec<String>,
+    pub structs: HashMap<String, StructSchema>,
+    pub typed_events: HashMap<String, TypedEvent>,
+    /// `migrate_entrypoint!` was invoked
+    exports_migrate: bool,
+}
+
+impl CrateIndex {
+    /// Index a source file belonging to `module` (`""` for the crate root)
+    pub fn add_file(&mut self, file: &syn::File, module: &str) {
+        self.add_items(&file.items, module);
+    }
+
+    fn add_items(&mut self, items: &[Item], module: &str) {
+        for item in items {
+            match item {
+                Item::Fn(item) => {
+                    let key = format!("{}::{}", module, item.sig.ident);
+                    if is_entrypoint(item) {
+                        self.entrypoints.push(key.clone());
+                    }
+                    self.fns.entry(key).or_insert_with(|| item.clone());
+                }
+                Item::Struct(item) => {
+                    if let syn::Fields::Named(named) = &item.fields {
+                        let name = item.ident.to_string();
+                        let fields = named
+                            .named
+                            .iter()
+                            .map(|field| Field {
+                                name: field
+                                    .ident
+                                    .as_ref()
+                                    .map(|i| i.to_string())
+                                    .unwrap_or_default(),
+                                ty: type_name(&field.ty),
+                            })
+                            .collect();
+                        self.structs
+                            .entry(name.clone())
+                            .or_insert(StructSchema { name, fields });
+                    }
+                }
+                Item::Macro(item) => {
+                    let name = last_segment(&item.mac.path);
+                    if name == "schema" {
+                        if let Ok(schema) = item.mac.parse_body::<SchemaMacro>() {
+                            self.typed_events.insert(schema.name.clone(), schema.event);
+                        }
+                    } else if name == "migrate_entrypoint" {
+                        self.exports_migrate = true;
+                    }
+                }
+                Item::Mod(item) if !is_test_only(&item.attrs) => {
+                    if let Some((_, items)) = &item.content {
+                        self.add_items(items, &item.ident.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn has_entrypoints(&self) -> bool {
+        !self.entrypoints.is_empty() || self.exports_migrate
+    }
+
+    /// Describe every entrypoint, using `shared` for types from library crates
+    pub fn entrypoints(&self, shared: &CrateIndex) -> (Vec<Entrypoint>, Vec<EventSchema>) {
+        let mut events: BTreeMap<String, EventSchema> = BTreeMap::new();
+        let mut entrypoints = Vec::new();
+        for key in &self.entrypoints {
+            let item = &self.fns[key];
+            let facts = self.reachable_facts(key);
+
+            let (arg_type, returns) = if has_entrypoint_attr(item) {
+                // `#[entrypoint]` decodes its one argument and answers with
+                // the `ContractResult` value.
+                let arg_type = item.sig.inputs.iter().find_map(|input| match input {
+                    syn::FnArg::Typed(arg) => Some(type_name(&arg.ty)),
+                    syn::FnArg::Receiver(_) => None,
+                });
+                let returns = match &item.sig.output {
+                    ReturnType::Type(_, ty) => result_type(ty),
+                    ReturnType::Default => None,
+                };
+                (arg_type, returns)
+            } else {
+                let returns = match &item.sig.output {
+                    ReturnType::Type(_, ty) => Some(type_name(ty)),
+                    ReturnType::Default if facts.responds => {
+                        Some(facts.result_type.unwrap_or_else(|| "bytes".to_string()))
+                    }
+                    ReturnType::Default => None,
+                };
+                (facts.arg_type, returns)
+            };
+            let args = arg_type
+                .as_deref()
+                .map(|ty| self.resolve_struct(ty, shared));
+
+            let mut topics = Vec::new();
+            for used in facts.events {
+                let schema = self.event_schema(used, shared);
+                if !topics.contains(&schema.topic) {
+                    topics.push(schema.topic.clone());
+                }
+                events.entry(schema.topic.clone()).or_insert(schema);
+            }
+
+            entrypoints.push(Entrypoint {
+                name: item.sig.ident.to_string(),
+                doc: summary(&item.attrs),
+                args,
+                returns,
+                events: topics,
+            });
+        }
+
+        if self.exports_migrate {
+            entrypoints.push(Entrypoint {
+                name: "migrate".to_string(),
+                doc: "Apply pending storage migrations, or only report them".to_string(),
+                args: Some(self.resolve_struct("MigrateArgs", shared)),
+                returns: Some("MigrationPlan".to_string()),
+                events: vec!["Migrated".to_string()],
+            });
+            events.entry("Migrated".to_string()).or_insert(EventSchema {
+                topic: "Migrated".to_string(),
+                schema: None,
+                version: None,
+                fields: string_fields(&["from", "to", "name"]),
+                indexed: Vec::new(),
+            });
+        }
+
+        (entrypoints, events.into_values().collect())
+    }
+
+    /// Walk `root` and every local function it reaches
+    fn reachable_facts(&self, root: &str) -> BodyFacts {
+        let mut merged = BodyFacts::default();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([(root.to_string(), true)]);
+        while let Some((key, direct)) = queue.pop_front() {
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            let item = &self.fns[&key];
+            let module = key.rsplit_once("::").map_or("", |(module, _)| module);
+            let mut facts = BodyFacts::default();
+            facts.visit_block(&item.block);
+
+            if merged.arg_type.is_none() {
+                merged.arg_type = facts.arg_type;
+            }
+            merged.responds |= facts.responds;
+            merged.events.extend(facts.events);
+            for call in &facts.calls {
+                if let Some((_, topic, fields)) =
+                    SDK_EVENTS.iter().find(|(path, _, _)| call.ends_with(path))
+                {
+                    merged.events.push(EventUse::Adhoc {
+                        topic: topic.to_string(),
+                        fields: fields.iter().map(|f| f.to_string()).collect(),
+                    });
+                }
+                if ADMIN_ACTION_RECORDERS
+                    .iter()
+                    .any(|path| call.ends_with(path))
+                {
+                    merged
+                        .events
+                        .push(EventUse::Typed("AdminActionV1".to_string()));
+                }
+                let Some(callee) = self.local_fn(call, module) else {
+                    continue;
+                };
+                if direct && merged.result_type.is_none() {
+                    merged.result_type = handed_back(&self.fns[&callee]);
+                }
+                queue.push_back((callee, false));
+            }
+        }
+        merged
+    }
+
+    /// Key of the crate function a call path refers to, seen from `module`
+    fn local_fn(&self, call: &str, module: &str) -> Option<String> {
+        let mut segments: Vec<&str> = call
+            .split("::")
+            .filter(|segment| !matches!(*segment, "crate" | "self" | "super"))
+            .collect();
+        let name = segments.pop()?;
+        if segments
+            .iter()
+            .any(|segment| EXTERNAL_MODULES.contains(segment))
+        {
+            return None;
+        }
+        let candidates = match segments.last() {
+            Some(parent) => vec![format!("{}::{}", parent, name)],
+            None => vec![format!("{}::{}", module, name), format!("::{}", name)],
+        };
+        candidates
+            .into_iter()
+            .find(|key| self.fns.contains_key(key))
+    }
+
+    fn resolve_struct(&self, ty: &str, shared: &CrateIndex) -> StructSchema {
+        let name = ty.rsplit("::").next().unwrap_or(ty);
+        self.structs
+            .get(name)
+            .or_else(|| shared.structs.get(name))
+            .cloned()
+            .unwrap_or_else(|| StructSchema {
+                name: name.to_string(),
+                fields: Vec::new(),
+            })
+    }
+
+    fn event_schema(&self, used: EventUse, shared: &CrateIndex) -> EventSchema {
+        match used {
+            EventUse::Adhoc { topic, fields } => EventSchema {
+                topic,
+                schema: None,
+                version: None,
+                fields: string_fields(&fields),
+                indexed: Vec::new(),
+            },
+            EventUse::Typed(name) => {
+                let typed = self
+                    .typed_events
+                    .get(&name)
+                    .or_else(|| shared.typed_events.get(&name));
+                EventSchema {
+                    topic: typed.map_or_else(|| name.clone(), |t| t.topic.clone()),
+                    version: typed.map(|t| t.version),
+                    fields: typed.map(|t| t.fields.clone()).unwrap_or_default(),
+                    indexed: typed.map(|t| t.indexed.clone()).unwrap_or_default(),
+                    schema: Some(name),
+                }
+            }
+        }
+    }
+
+    /// Merge library-crate definitions into a shared lookup index
+    pub fn absorb(&mut self, other: CrateIndex) {
+        for (name, schema) in other.structs {
+            self.structs.entry(name).or_insert(schema);
+        }
+        self.typed_events.extend(other.typed_events);
+    }
+}
+
+/// Read the `ErrorCode` table from the `crc-errors` sources
+pub fn error_codes(file: &syn::File) -> Vec<ErrorCode> {
+    let Some(codes) = file.items.iter().find_map(|item| match item {
+        Item::Enum(item) if item.ident == "ErrorCode" => Some(item),
+        _ => None,
+    }) else {
+        return Vec::new();
+    };
+    codes
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let (_, Expr::Lit(lit)) = variant.discriminant.as_ref()? else {
+                return None;
+            };
+            let syn::Lit::Int(code) = &lit.lit else {
+                return None;
+            };
+            Some(ErrorCode {
+                code: code.base10_parse().ok()?,
+                name: variant.ident.to_string(),
+            })
+        })
+        .collect()
+}
+
+enum EventUse {
+    Adhoc { topic: String, fields: Vec<String> },
+    Typed(String),
+}
+
+#[derive(Default)]
+struct BodyFacts {
+    calls: Vec<String>,
+    arg_type: Option<String>,
+    result_type: Option<String>,
+    responds: bool,
+    events: Vec<EventUse>,
+}
+
+impl<'ast> Visit<'ast> for BodyFacts {
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let (syn::Pat::Type(pat), Some(init)) = (&local.pat, &local.init)
+            && self.arg_type.is_none()
+            && calls_read_args(&init.expr)
+        {
+            self.arg_type = Some(type_name(&pat.ty));
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let Expr::Path(func) = &*call.func {
+            let path = path_name(&func.path);
+            let name = last_segment(&func.path);
+            match name.as_str() {
+                "read_args" => {
+                    if let Some(ty) = turbofish(&func.path) {
+                        self.arg_type.get_or_insert(ty);
+                    }
+                }
+                "try_respond" | "respond" | "return_bytes" => self.responds = true,
+                "emit" if path.starts_with("events::") => {
+                    if let Some(Expr::Reference(arg)) = call.args.first()
+                        && let Expr::Struct(event) = &*arg.expr
+                    {
+                        self.events.push(EventUse::Typed(last_segment(&event.path)));
+                    }
+                }
+                _ => {}
+            }
+            self.calls.push(path);
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        if call.method == "return_bytes" {
+            self.responds = true;
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if last_segment(&mac.path) == "event" {
+            if let Ok(event) = mac.parse_body::<EventMacro>() {
+                self.events.push(EventUse::Adhoc {
+                    topic: event.topic,
+                    fields: event.fields,
+                });
+            }
+        } else if let Ok(args) =
+            mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        {
+            // Calls inside `assert!`, `format!` and the like still count.
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+/// `event!("Topic", field: value, ...)`
+struct EventMacro {
+    topic: String,
+    fields: Vec<String>,
+}
+
+impl Parse for EventMacro {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let topic: LitStr = input.parse()?;
+        let mut fields = Vec::new();
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            let field: syn::Ident = input.parse()?;
+            input.parse::<Token![:]>()?;
+            input.parse::<Expr>()?;
+            fields.push(field.to_string());
+        }
+        Ok(EventMacro {
+            topic: topic.value(),
+            fields,
+        })
+    }
+}
+
+/// `schema! { Name = "Topic", version N, indexed [field, ...] { field: Type, ... } }`
+struct SchemaMacro {
+    name: String,
+    event: TypedEvent,
+}
+
+impl Parse for SchemaMacro {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.call(syn::Attribute::parse_outer)?;
+        let name: syn::Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let topic: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let keyword: syn::Ident = input.parse()?;
+        if keyword != "version" {
+            return Err(syn::Error::new(keyword.span(), "expected `version`"));
+        }
+        let version: LitInt = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let keyword: syn::Ident = input.parse()?;
+        if keyword != "indexed" {
+            return Err(syn::Error::new(keyword.span(), "expected `indexed`"));
+        }
+        let list;
+        syn::bracketed!(list in input);
+        let indexed = Punctuated::<syn::Ident, Token![,]>::parse_terminated(&list)?;
+        let body;
+        syn::braced!(body in input);
+        let fields = Punctuated::<syn::Field, Token![,]>::parse_terminated_with(
+            &body,
+            syn::Field::parse_named,
+        )?;
+        Ok(SchemaMacro {
+            name: name.to_string(),
+            event: TypedEvent {
+                topic: topic.value(),
+                version: version.base10_parse()?,
+                fields: fields
+                    .iter()
+                    .map(|field| Field {
+                        name: field
+                            .ident
+                            .as_ref()
+                            .map(|i| i.to_string())
+                            .unwrap_or_default(),
+                        ty: type_name(&field.ty),
+                    })
+                    .collect(),
+                indexed: indexed.iter().map(|field| field.to_string()).collect(),
+            },
+        })
+    }
+}
+
+fn is_entrypoint(item: &ItemFn) -> bool {
+    let exported = item.sig.abi.is_some()
+        && item
+            .attrs
+            .iter()
+            .any(|attr| attr.to_token_stream().to_string().contains("no_mangle"));
+    exported || has_entrypoint_attr(item)
+}
+
+/// Written as an ordinary function under `#[entrypoint]`
+fn has_entrypoint_attr(item: &ItemFn) -> bool {
+    item.attrs
+        .iter()
+        .any(|attr| last_segment(attr.path()) == "entrypoint")
+}
+
+fn is_test_only(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("cfg") && attr.to_token_stream().to_string().contains("test")
+    })
+}
+
+fn calls_read_args(expr: &Expr) -> bool {
+    match expr {
+        Expr::Try(inner) => calls_read_args(&inner.expr),
+        Expr::Call(call) => {
+            matches!(&*call.func, Expr::Path(func) if last_segment(&func.path) == "read_args")
+        }
+        _ => false,
+    }
+}
+
+fn turbofish(path: &syn::Path) -> Option<String> {
+    let syn::PathArguments::AngleBracketed(args) = &path.segments.last()?.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(type_name(ty)),
+        _ => None,
+    })
+}
+
+/// `T` of a `ContractResult<T>` or `Result<T, _>` return type, unless `()`
+fn result_type(ty: &Type) -> Option<String> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "ContractResult" && segment.ident != "Result" {
+        return None;
+    }
+    let inner = turbofish(&path.path)?;
+    (inner != "()").then_some(inner)
+}
+
+/// The value a wrapper's direct callee answers with
+///
+/// Either a responder taking the result (`respond_amount(name, result)`)
+/// or an `execute_*` function returning it. Generic helpers say nothing.
+fn handed_back(callee: &ItemFn) -> Option<String> {
+    let generic = |ty: &str| {
+        callee
+            .sig
+            .generics
+            .type_params()
+            .any(|param| param.ident == ty)
+    };
+    let from_param = callee.sig.inputs.iter().find_map(|input| match input {
+        syn::FnArg::Typed(param) => result_type(&param.ty),
+        syn::FnArg::Receiver(_) => None,
+    });
+    let from_output = match &callee.sig.output {
+        ReturnType::Type(_, ty) if callee.sig.ident.to_string().starts_with("execute_") => {
+            result_type(ty)
+        }
+        _ => None,
+    };
+    from_param.or(from_output).filter(|ty| !generic(ty))
+}
+
+fn path_name(path: &syn::Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn last_segment(path: &syn::Path) -> String {
+    path.segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default()
+}
+
+fn string_fields(names: &[impl AsRef<str>]) -> Vec<Field> {
+    names
+        .iter()
+        .map(|name| Field {
+            name: name.as_ref().to_string(),
+            ty: "String".to_string(),
+        })
+        .collect()
+}
+
+/// Render a type the way it is written in source
+pub fn type_name(ty: &Type) -> String {
+    const PUNCT: &str = "<>:&()[];,";
+    let raw = ty.to_token_stream().to_string();
+    let chars: Vec<char> = raw.chars().collect();
+    let mut out = String::with_capacity(raw.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied().unwrap_or(' ');
+            if prev != ',' && prev != ';' && (PUNCT.contains(prev) || PUNCT.contains(next)) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// First paragraph of an item's doc comment
+fn summary(attrs: &[syn::Attribute]) -> String {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        let syn::Meta::NameValue(meta) = &attr.meta else {
+            continue;
+        };
+        if !meta.path.is_ident("doc") {
+            continue;
+        }
+        let Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(text),
+            ..
+        }) = &meta.value
+        else {
+            continue;
+        };
+        let line = text.value().trim().to_string();
+        if line.is_empty() {
+            if lines.is_empty() {
+                continue;
+            }
+            break;
+        }
+        lines.push(line);
+    }
+    lines.join(" ")
+}
@@ -0,0 +1,67 @@
+//! JSON layout of an exported contract schema
+
+use serde::Serialize;
+
+/// Interface of one contract crate
+#[derive(Serialize, Debug)]
+pub struct ContractSchema {
+    /// Cargo package name
+    pub contract: String,
+    pub version: String,
+    pub description: String,
+    pub entrypoints: Vec<Entrypoint>,
+    /// Every event any entrypoint can emit, by topic
+    pub events: Vec<EventSchema>,
+    /// Stable codes from `crc-errors`
+    pub errors: Vec<ErrorCode>,
+}
+
+/// One exported `extern "C"` function
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Entrypoint {
+    pub name: String,
+    /// First paragraph of the doc comment
+    pub doc: String,
+    /// Postcard-encoded call data; `None` if the entrypoint reads none
+    pub args: Option<StructSchema>,
+    /// Postcard-encoded return data: a Rust type, `bytes` when the type is
+    /// not visible in the signature, or `None` if nothing is returned
+    pub returns: Option<String>,
+    /// Topics of the events this entrypoint can emit
+    pub events: Vec<String>,
+}
+
+/// A struct and its fields in declaration (and encoding) order
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StructSchema {
+    #[serde(rename = "type")]
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// An event topic and its payload
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EventSchema {
+    pub topic: String,
+    /// Typed schema from the `events` crate, if the event uses one
+    pub schema: Option<String>,
+    /// Schema version prefixed to typed event data
+    pub version: Option<u16>,
+    /// For `event!` payloads every field is a `String`
+    pub fields: Vec<Field>,
+    /// Fields also published as indexed topics
+    pub indexed: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ErrorCode {
+    pub code: u16,
+    pub name: String,
+}
@@ -0,0 +1,29 @@
+//! Queries against a shared roles registry
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct RoleAccountArgs {
+    pub role: String,
+    pub account: String,
+}
+
+/// Ask the registry at `registry` whether `account` holds `role`.
+pub fn has_role(registry: &str, role: &str, account: &str) -> ContractResult<bool> {
+    let args = postcard::to_allocvec(&RoleAccountArgs {
+        role: role.to_string(),
+        account: account.to_string(),
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    let response = context().call_contract(registry, "has_role", &args)?;
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Fail with `Unauthorized` unless the registry says `account` holds `role`.
+pub fn require_role(registry: &str, role: &str, account: &str) -> ContractResult<()> {
+    if !has_role(registry, role, account)? {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
@@ -0,0 +1,398 @@
+//! Shared roles registry entrypoints
+
+use crate::client::RoleAccountArgs;
+use crate::roles;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::prelude::*;
+
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+#[derive(Serialize, Deserialize)]
+struct RoleArgs {
+    role: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetRoleAdminArgs {
+    role: String,
+    admin_role: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MembersArgs {
+    role: String,
+    offset: u64,
+    limit: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn execute_grant_role() -> ContractResult<bool> {
+    let args: RoleAccountArgs = read_args()?;
+    roles::grant_role(context().sender(), &args.role, &args.account)
+}
+
+fn execute_revoke_role() -> ContractResult<bool> {
+    let args: RoleAccountArgs = read_args()?;
+    roles::revoke_role(context().sender(), &args.role, &args.account)
+}
+
+fn execute_renounce_role() -> ContractResult<()> {
+    let args: RoleArgs = read_args()?;
+    roles::renounce_role(context().sender(), &args.role)
+}
+
+fn execute_set_role_admin() -> ContractResult<()> {
+    let args: SetRoleAdminArgs = read_args()?;
+    roles::set_role_admin(context().sender(), &args.role, &args.admin_role)
+}
+
+/// Initialize the registry; the caller receives the `admin` role
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = roles::initialize(context().sender()) {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Grant a role (holders of the role's admin role only)
+///
+/// # Arguments
+/// * `role` - Role name
+/// * `account` - Account receiving the role
+///
+/// # Returns
+/// `false` if the account already held the role
+#[unsafe(no_mangle)]
+pub extern "C" fn grant_role() {
+    match execute_grant_role() {
+        Ok(granted) => {
+            let _ = try_respond(&granted);
+        }
+        Err(err) => log!("grant_role failed: {}", err),
+    }
+}
+
+/// Revoke a role (holders of the role's admin role only)
+///
+/// # Arguments
+/// * `role` - Role name
+/// * `account` - Account losing the role
+///
+/// # Returns
+/// `false` if the account did not hold the role
+#[unsafe(no_mangle)]
+pub extern "C" fn revoke_role() {
+    match execute_revoke_role() {
+        Ok(revoked) => {
+            let _ = try_respond(&revoked);
+        }
+        Err(err) => log!("revoke_role failed: {}", err),
+    }
+}
+
+/// Give up a role held by the caller
+///
+/// # Arguments
+/// * `role` - Role name
+#[unsafe(no_mangle)]
+pub extern "C" fn renounce_role() {
+    if let Err(err) = execute_renounce_role() {
+        log!("renounce_role failed: {}", err);
+    }
+}
+
+/// Change which role administers a role (holders of its current admin role only)
+///
+/// # Arguments
+/// * `role` - Role name
+/// * `admin_role` - New admin role
+#[unsafe(no_mangle)]
+pub extern "C" fn set_role_admin() {
+    if let Err(err) = execute_set_role_admin() {
+        log!("set_role_admin failed: {}", err);
+    }
+}
+
+/// Check whether an account holds a role
+///
+/// # Arguments
+/// * `role` - Role name
+/// * `account` - Account address
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn has_role() {
+    let result = read_args::<RoleAccountArgs>()
+        .and_then(|args| roles::has_role(&args.role, &args.account))
+        .and_then(|held| try_respond(&held));
+    if let Err(err) = result {
+        log!("has_role failed: {}", err);
+    }
+}
+
+/// Get the admin role of a role
+///
+/// # Arguments
+/// * `role` - Role name
+///
+/// # Returns
+/// `String`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_role_admin() {
+    let result = read_args::<RoleArgs>()
+        .and_then(|args| roles::role_admin(&args.role))
+        .and_then(|admin| try_respond(&admin));
+    if let Err(err) = result {
+        log!("get_role_admin failed: {}", err);
+    }
+}
+
+/// List the members of a role
+///
+/// # Arguments
+/// * `role` - Role name
+/// * `offset` - First index to return
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `MemberPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn role_members() {
+    let result = read_args::<MembersArgs>()
+        .and_then(|args| roles::members(&args.role, args.offset, args.limit))
+        .and_then(|page| try_respond(&page));
+    if let Err(err) = result {
+        log!("role_members failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client;
+    use crate::roles::{DEFAULT_ADMIN_ROLE, MemberPage};
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ADMIN: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000d04";
+    const REGISTRY: &str = "0x0000000000000000000000000000000000000f00";
+    const MINTER: &str = "minter";
+    const MINTER_ADMIN: &str = "minter_admin";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(REGISTRY);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::set_sender(ADDR_ADMIN);
+        initialize();
+    }
+
+    fn grant(sender: &str, role: &str, account: &str) {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(&RoleAccountArgs {
+            role: role.to_string(),
+            account: account.to_string(),
+        }));
+        grant_role();
+    }
+
+    fn revoke(sender: &str, role: &str, account: &str) {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(&RoleAccountArgs {
+            role: role.to_string(),
+            account: account.to_string(),
+        }));
+        revoke_role();
+    }
+
+    fn page(role: &str, offset: u64, limit: u64) -> MemberPage {
+        mock::set_call_data(&encode(&MembersArgs {
+            role: role.to_string(),
+            offset,
+            limit,
+        }));
+        role_members();
+        postcard::from_bytes(&mock::take_return_data()).expect("member page")
+    }
+
+    #[test]
+    fn admin_grants_and_revokes_with_enumeration() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        for account in [ADDR_ALICE, ADDR_BOB, ADDR_CAROL] {
+            grant(ADDR_ADMIN, MINTER, account);
+        }
+        assert!(roles::has_role(MINTER, ADDR_BOB).unwrap());
+        assert_eq!(
+            page(MINTER, 0, 50).items,
+            [ADDR_ALICE, ADDR_BOB, ADDR_CAROL]
+        );
+
+        revoke(ADDR_ADMIN, MINTER, ADDR_ALICE);
+        assert!(!roles::has_role(MINTER, ADDR_ALICE).unwrap());
+        // The last member moves into the freed slot.
+        assert_eq!(page(MINTER, 0, 50).items, [ADDR_CAROL, ADDR_BOB]);
+
+        let first = page(MINTER, 0, 1);
+        assert_eq!((first.total, first.next_offset), (2, Some(1)));
+        assert_eq!(page(MINTER, 1, 1).next_offset, None);
+    }
+
+    #[test]
+    fn role_admins_delegate_management() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        mock::set_call_data(&encode(&SetRoleAdminArgs {
+            role: MINTER.to_string(),
+            admin_role: MINTER_ADMIN.to_string(),
+        }));
+        set_role_admin();
+        assert_eq!(roles::role_admin(MINTER).unwrap(), MINTER_ADMIN);
+
+        // The default admin no longer manages minters directly.
+        grant(ADDR_ADMIN, MINTER, ADDR_BOB);
+        assert!(!roles::has_role(MINTER, ADDR_BOB).unwrap());
+
+        grant(ADDR_ADMIN, MINTER_ADMIN, ADDR_ALICE);
+        grant(ADDR_ALICE, MINTER, ADDR_BOB);
+        assert!(roles::has_role(MINTER, ADDR_BOB).unwrap());
+    }
+
+    #[test]
+    fn non_admins_cannot_manage_roles() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        grant(ADDR_ALICE, MINTER, ADDR_ALICE);
+        assert!(!roles::has_role(MINTER, ADDR_ALICE).unwrap());
+
+        grant(ADDR_ADMIN, MINTER, ADDR_BOB);
+        revoke(ADDR_ALICE, MINTER, ADDR_BOB);
+        assert!(roles::has_role(MINTER, ADDR_BOB).unwrap());
+
+        grant(ADDR_ADMIN, "not a role", ADDR_BOB);
+        assert_eq!(roles::member_count("not a role").unwrap(), 0);
+
+        mock::set_sender(ADDR_ALICE);
+        initialize();
+        assert!(!roles::has_role(DEFAULT_ADMIN_ROLE, ADDR_ALICE).unwrap());
+    }
+
+    #[test]
+    fn renounce_keeps_last_admin() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        grant(ADDR_ADMIN, MINTER, ADDR_BOB);
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&RoleArgs {
+            role: MINTER.to_string(),
+        }));
+        renounce_role();
+        assert!(!roles::has_role(MINTER, ADDR_BOB).unwrap());
+
+        mock::set_sender(ADDR_ADMIN);
+        mock::set_call_data(&encode(&RoleArgs {
+            role: DEFAULT_ADMIN_ROLE.to_string(),
+        }));
+        renounce_role();
+        assert!(roles::has_role(DEFAULT_ADMIN_ROLE, ADDR_ADMIN).unwrap());
+
+        grant(ADDR_ADMIN, DEFAULT_ADMIN_ROLE, ADDR_ALICE);
+        revoke(ADDR_ALICE, DEFAULT_ADMIN_ROLE, ADDR_ADMIN);
+        assert!(!roles::has_role(DEFAULT_ADMIN_ROLE, ADDR_ADMIN).unwrap());
+        assert_eq!(roles::member_count(DEFAULT_ADMIN_ROLE).unwrap(), 1);
+    }
+
+    #[test]
+    fn client_queries_shared_registry() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        grant(ADDR_ADMIN, MINTER, ADDR_BOB);
+
+        mock::register_contract(REGISTRY, "has_role", |args| {
+            mock::set_call_data(args);
+            has_role();
+            Ok(mock::take_return_data())
+        });
+        assert!(client::has_role(REGISTRY, MINTER, ADDR_BOB).unwrap());
+        assert!(client::require_role(REGISTRY, MINTER, ADDR_BOB).is_ok());
+        assert!(matches!(
+            client::require_role(REGISTRY, MINTER, ADDR_ALICE),
+            Err(ContractError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "access-control-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,35 @@
+//! Access Control
+//!
+//! Role-based permissions with per-role admins and member enumeration. The
+//! [`roles`] module keeps roles in the embedding contract's own storage; the
+//! `entrypoints` feature exports the same logic as a shared registry, which
+//! other contracts query through [`client`] instead of reinventing owner
+//! checks.
+//!
+//! ## Embedding
+//! ```toml
+//! access-control-contract = { path = "../access-control", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use access_control_contract::{client, roles};
+//!
+//! // Roles stored locally
+//! roles::require_role(MINTER_ROLE, ctx.sender())?;
+//! // Roles held in a shared registry
+//! client::require_role(&config.acl, MINTER_ROLE, ctx.sender())?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod client;
+pub mod roles;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
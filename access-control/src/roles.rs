@@ -0,0 +1,231 @@
+//! Embeddable role storage
+//!
+//! Every role has an admin role whose holders may grant and revoke it; by
+//! default that is [`DEFAULT_ADMIN_ROLE`]. Members are kept in an indexed
+//! set so they can be enumerated page by page. The last holder of
+//! [`DEFAULT_ADMIN_ROLE`] cannot be removed, so a contract never ends up
+//! without anyone able to manage it.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+/// Role that administers every role without an explicit admin
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+/// Largest page returned by [`members`]
+pub const MAX_PAGE_SIZE: u64 = 50;
+
+const MAX_ROLE_BYTES: usize = 32;
+const ROLE_ADMINS_PREFIX: &str = "acl_role_admin";
+const MEMBER_INDEX_PREFIX: &str = "acl_member_index";
+const MEMBER_AT_PREFIX: &str = "acl_member_at";
+const MEMBER_COUNT_PREFIX: &str = "acl_member_count";
+
+/// One page of role members
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MemberPage {
+    pub items: Vec<String>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+fn role_admins() -> Map<String, String> {
+    Map::new(ROLE_ADMINS_PREFIX)
+}
+
+/// 1-based position of an account in a role's member list
+fn member_index() -> Map<(String, String), u64> {
+    Map::new(MEMBER_INDEX_PREFIX)
+}
+
+fn member_at_map() -> Map<(String, u64), String> {
+    Map::new(MEMBER_AT_PREFIX)
+}
+
+fn member_counts() -> Map<String, u64> {
+    Map::new(MEMBER_COUNT_PREFIX)
+}
+
+/// Role names are 1-32 bytes of ASCII letters, digits, `_` and `-`.
+pub fn validate_role(role: &str) -> ContractResult<()> {
+    let valid = !role.is_empty()
+        && role.len() <= MAX_ROLE_BYTES
+        && role
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+    if !valid {
+        return Err(ContractError::InvalidArgument(format!(
+            "Role must be 1-{} chars of [A-Za-z0-9_-]",
+            MAX_ROLE_BYTES
+        )));
+    }
+    Ok(())
+}
+
+/// Grant [`DEFAULT_ADMIN_ROLE`] to `admin`; only valid before any admin exists.
+pub fn initialize(admin: &str) -> ContractResult<()> {
+    validation::validate_address(admin)?;
+    if member_count(DEFAULT_ADMIN_ROLE)? > 0 {
+        return Err(ContractError::InvalidArgument(
+            "Roles already initialized".to_string(),
+        ));
+    }
+    add_member(DEFAULT_ADMIN_ROLE, admin)?;
+
+    event!("RoleGranted", role: DEFAULT_ADMIN_ROLE, account: admin, sender: admin);
+    Ok(())
+}
+
+/// Whether `account` holds `role`.
+pub fn has_role(role: &str, account: &str) -> ContractResult<bool> {
+    member_index().contains_key(&(role.to_string(), account.to_string()))
+}
+
+/// Fail with `Unauthorized` unless `account` holds `role`.
+pub fn require_role(role: &str, account: &str) -> ContractResult<()> {
+    if !has_role(role, account)? {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Role whose holders may grant and revoke `role`.
+pub fn role_admin(role: &str) -> ContractResult<String> {
+    Ok(role_admins()
+        .get(&role.to_string())?
+        .unwrap_or_else(|| DEFAULT_ADMIN_ROLE.to_string()))
+}
+
+/// Change the admin role of `role`; `caller` must hold the current admin role.
+pub fn set_role_admin(caller: &str, role: &str, admin_role: &str) -> ContractResult<()> {
+    validate_role(role)?;
+    validate_role(admin_role)?;
+    let previous = role_admin(role)?;
+    require_role(&previous, caller)?;
+    if role == DEFAULT_ADMIN_ROLE && admin_role != DEFAULT_ADMIN_ROLE {
+        return Err(ContractError::InvalidArgument(
+            "The default admin role administers itself".to_string(),
+        ));
+    }
+
+    let mut admins = role_admins();
+    if admin_role == DEFAULT_ADMIN_ROLE {
+        admins.remove(&role.to_string())?;
+    } else {
+        admins.set(&role.to_string(), &admin_role.to_string())?;
+    }
+
+    event!("RoleAdminChanged", role: role, previous: previous, admin_role: admin_role);
+    Ok(())
+}
+
+/// Grant `role` to `account`. Returns `false` if it already held the role.
+pub fn grant_role(caller: &str, role: &str, account: &str) -> ContractResult<bool> {
+    validate_role(role)?;
+    validation::validate_address(account)?;
+    require_role(&role_admin(role)?, caller)?;
+    if has_role(role, account)? {
+        return Ok(false);
+    }
+    add_member(role, account)?;
+
+    event!("RoleGranted", role: role, account: account, sender: caller);
+    Ok(true)
+}
+
+/// Revoke `role` from `account`. Returns `false` if it did not hold the role.
+pub fn revoke_role(caller: &str, role: &str, account: &str) -> ContractResult<bool> {
+    require_role(&role_admin(role)?, caller)?;
+    if !remove_member(role, account)? {
+        return Ok(false);
+    }
+
+    event!("RoleRevoked", role: role, account: account, sender: caller);
+    Ok(true)
+}
+
+/// Give up `role` held by `caller`.
+pub fn renounce_role(caller: &str, role: &str) -> ContractResult<()> {
+    if !remove_member(role, caller)? {
+        return Err(ContractError::InvalidArgument(
+            "Caller does not hold the role".to_string(),
+        ));
+    }
+
+    event!("RoleRevoked", role: role, account: caller, sender: caller);
+    Ok(())
+}
+
+/// Number of accounts holding `role`.
+pub fn member_count(role: &str) -> ContractResult<u64> {
+    Ok(member_counts().get(&role.to_string())?.unwrap_or(0))
+}
+
+/// Member at `index` (0-based); order changes when members are removed.
+pub fn member_at(role: &str, index: u64) -> ContractResult<Option<String>> {
+    member_at_map().get(&(role.to_string(), index))
+}
+
+/// Members of `role` from `offset`, at most `limit` (1-50) of them.
+pub fn members(role: &str, offset: u64, limit: u64) -> ContractResult<MemberPage> {
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+
+    let total = member_count(role)?;
+    let end = offset.saturating_add(limit).min(total);
+    let mut items = Vec::new();
+    for index in offset..end {
+        if let Some(account) = member_at(role, index)? {
+            items.push(account);
+        }
+    }
+    Ok(MemberPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+fn add_member(role: &str, account: &str) -> ContractResult<()> {
+    let count = member_count(role)?;
+    member_at_map().set(&(role.to_string(), count), &account.to_string())?;
+    member_index().set(
+        &(role.to_string(), account.to_string()),
+        &safe_math::add(count, 1)?,
+    )?;
+    member_counts().set(&role.to_string(), &safe_math::add(count, 1)?)
+}
+
+/// Swap-remove `account` from the role's member list.
+fn remove_member(role: &str, account: &str) -> ContractResult<bool> {
+    let key = (role.to_string(), account.to_string());
+    let mut index_map = member_index();
+    let Some(position) = index_map.get(&key)? else {
+        return Ok(false);
+    };
+    let count = member_count(role)?;
+    if role == DEFAULT_ADMIN_ROLE && count == 1 {
+        return Err(ContractError::InvalidArgument(
+            "Cannot remove the last admin".to_string(),
+        ));
+    }
+
+    let last = safe_math::sub(count, 1)?;
+    let removed = safe_math::sub(position, 1)?;
+    let mut at_map = member_at_map();
+    if removed != last {
+        let moved = at_map
+            .get(&(role.to_string(), last))?
+            .ok_or(ContractError::StorageReadFailed)?;
+        at_map.set(&(role.to_string(), removed), &moved)?;
+        index_map.set(&(role.to_string(), moved), &position)?;
+    }
+    at_map.remove(&(role.to_string(), last))?;
+    index_map.remove(&key)?;
+    member_counts().set(&role.to_string(), &last)?;
+    Ok(true)
+}
@@ -0,0 +1,484 @@
+//! Account and contract addresses
+//!
+//! [`Address`] is a validated address string: `0x` followed by 40 hex
+//! digits, [`ZERO_ADDRESS`], or a native chain address such as
+//! `chert1...`. Argument structs use it in place of `String`, so a
+//! malformed address fails when the call data is decoded, with the same
+//! error in every contract, instead of reaching storage.
+//!
+//! Hex addresses are kept in lowercase, so `0xAB..` and `0xab..` name the
+//! same account and the same storage entries. Mixed-case input must carry a
+//! valid checksum: each hex letter is uppercase where the matching nibble
+//! of the BLAKE3 hash of the lowercase digits is 8 or more. All-lowercase
+//! and all-uppercase input has no checksum to check.
+//!
+//! Native addresses are what `validation::validate_address` has always
+//! accepted, limited to ASCII letters and digits: between
+//! [`NATIVE_MIN_LEN`] and [`NATIVE_MAX_LEN`] characters, not starting with
+//! `0x` and not 40 bare hex digits. They are kept exactly as spelled, like
+//! every address was before this crate.
+//!
+//! On the wire an `Address` is a plain string, so replacing a `String`
+//! field with one does not change the encoding. Storage keys use
+//! [`AddressKey`] instead: the 20 bytes a hex address spells, or the
+//! 32-byte BLAKE3 hash of a native address.
+//!
+//! ## Embedding
+//! ```toml
+//! address = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use address::Address;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! pub struct TransferArgs {
+//!     pub to: Address,
+//!     pub amount: u64,
+//! }
+//!
+//! let to: Address = "0x52908400098527886e0f7030069857d2e4169ee7".parse()?;
+//! println!("{}", to.to_checksum());
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use silica_contract_sdk::prelude::*;
+
+/// Counterparty of mints and burns, as written in event payloads
+pub const ZERO_ADDRESS: &str = "0x0";
+
+/// Hex digits after the `0x` prefix
+pub const ADDRESS_DIGITS: usize = 40;
+
+/// Bytes the hex digits of an address spell
+pub const ADDRESS_BYTES: usize = ADDRESS_DIGITS / 2;
+
+/// Bytes of the hash a native address is keyed by
+pub const NATIVE_KEY_BYTES: usize = 32;
+
+/// Shortest native address `validation::validate_address` accepts
+pub const NATIVE_MIN_LEN: usize = 10;
+
+/// Longest native address `validation::validate_address` accepts
+pub const NATIVE_MAX_LEN: usize = 100;
+
+/// A validated address; hex addresses are lowercase
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(String);
+
+impl Address {
+    /// Validate `address`, normalizing a hex address to lowercase
+    pub fn parse(address: &str) -> ContractResult<Self> {
+        if address == ZERO_ADDRESS {
+            return Ok(Self::zero());
+        }
+        let Some(digits) = address.strip_prefix("0x") else {
+            return Self::parse_native(address);
+        };
+        if digits.len() != ADDRESS_DIGITS {
+            return Err(invalid("Address must have 40 hex digits"));
+        }
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid("Address has a non-hex digit"));
+        }
+        let lower = digits.to_ascii_lowercase();
+        let mixed_case = digits.bytes().any(|b| b.is_ascii_lowercase())
+            && digits.bytes().any(|b| b.is_ascii_uppercase());
+        if mixed_case && checksummed(&lower) != digits {
+            return Err(invalid("Address checksum mismatch"));
+        }
+        Ok(Address(format!("0x{}", lower)))
+    }
+
+    fn parse_native(address: &str) -> ContractResult<Self> {
+        if !(NATIVE_MIN_LEN..=NATIVE_MAX_LEN).contains(&address.len()) {
+            return Err(invalid("Invalid address length"));
+        }
+        if !address.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(invalid("Address must be 0x-prefixed hex or alphanumeric"));
+        }
+        // Without the prefix a hex address would name a different account.
+        if address.len() == ADDRESS_DIGITS && address.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid("Address must start with 0x"));
+        }
+        Ok(Address(address.to_string()))
+    }
+
+    /// [`ZERO_ADDRESS`]
+    pub fn zero() -> Self {
+        Address(ZERO_ADDRESS.to_string())
+    }
+
+    /// Whether this is [`ZERO_ADDRESS`] or its 40-digit spelling
+    pub fn is_zero(&self) -> bool {
+        self.is_hex() && self.0[2..].bytes().all(|b| b == b'0')
+    }
+
+    /// Whether this is a `0x` address rather than a native one
+    pub fn is_hex(&self) -> bool {
+        self.0.starts_with("0x")
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Mixed-case spelling carrying the checksum; native addresses and
+    /// [`ZERO_ADDRESS`] come back unchanged
+    pub fn to_checksum(&self) -> String {
+        if self.0 == ZERO_ADDRESS || !self.is_hex() {
+            return self.0.clone();
+        }
+        format!("0x{}", checksummed(&self.0[2..]))
+    }
+
+    /// Spellings storage keyed by the caller's raw string may hold this
+    /// account under: lowercase, checksummed and uppercase for a hex
+    /// address, the address itself for a native one
+    ///
+    /// Other mixed-case spellings of a hex address were never valid input
+    /// to a well-formed client and are not tried.
+    pub fn spellings(&self) -> Vec<String> {
+        if !self.is_hex() || self.0 == ZERO_ADDRESS {
+            return vec![self.0.clone()];
+        }
+        let mut spellings = vec![self.0.clone()];
+        for spelling in [
+            self.to_checksum(),
+            format!("0x{}", self.0[2..].to_ascii_uppercase()),
+        ] {
+            if !spellings.contains(&spelling) {
+                spellings.push(spelling);
+            }
+        }
+        spellings
+    }
+
+    /// Storage key of this address; [`ZERO_ADDRESS`] is all zeros
+    pub fn to_key(&self) -> AddressKey {
+        if !self.is_hex() {
+            return AddressKey::Native(self.clone());
+        }
+        let mut bytes = [0u8; ADDRESS_BYTES];
+        if self.0 != ZERO_ADDRESS {
+            let digits = &self.0.as_bytes()[2..];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = (nibble(digits[2 * i]) << 4) | nibble(digits[2 * i + 1]);
+            }
+        }
+        AddressKey::Hex(bytes)
+    }
+}
+
+/// Value of a lowercase hex digit, which [`Address::parse`] guarantees
+fn nibble(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        _ => digit - b'a' + 10,
+    }
+}
+
+/// An address in fixed-size form, for storage keys
+///
+/// Map keys are hashed from their encoding, which for an `AddressKey` is
+/// exactly [`ADDRESS_BYTES`] bytes for a hex address and
+/// [`NATIVE_KEY_BYTES`] for a native one. The lengths differ, so the two
+/// kinds never share a key. Convert at the boundary: arguments and events
+/// keep the [`Address`], storage uses the key.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AddressKey {
+    /// The bytes a hex address spells
+    Hex([u8; ADDRESS_BYTES]),
+    /// A native address, encoded as the BLAKE3 hash of its spelling
+    Native(Address),
+}
+
+impl AddressKey {
+    /// Key of an address; fails like [`Address::parse`]
+    pub fn parse(address: &str) -> ContractResult<Self> {
+        Ok(Address::parse(address)?.to_key())
+    }
+
+    pub fn from_bytes(bytes: [u8; ADDRESS_BYTES]) -> Self {
+        AddressKey::Hex(bytes)
+    }
+
+    /// Address the key stands for; hex keys come back lowercase with all
+    /// 40 digits, even the zero key
+    pub fn to_address(&self) -> Address {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let bytes = match self {
+            AddressKey::Hex(bytes) => bytes,
+            AddressKey::Native(address) => return address.clone(),
+        };
+        let mut hex = String::with_capacity(2 + ADDRESS_DIGITS);
+        hex.push_str("0x");
+        for byte in bytes {
+            hex.push(DIGITS[usize::from(byte >> 4)] as char);
+            hex.push(DIGITS[usize::from(byte & 0x0f)] as char);
+        }
+        Address(hex)
+    }
+}
+
+impl Serialize for AddressKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            AddressKey::Hex(bytes) => bytes.serialize(serializer),
+            AddressKey::Native(address) => {
+                crypto::hash_blake3(address.as_bytes()).serialize(serializer)
+            }
+        }
+    }
+}
+
+impl From<&Address> for AddressKey {
+    fn from(address: &Address) -> Self {
+        address.to_key()
+    }
+}
+
+impl From<AddressKey> for Address {
+    fn from(key: AddressKey) -> Self {
+        key.to_address()
+    }
+}
+
+impl fmt::Display for AddressKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_address())
+    }
+}
+
+/// `lower` with each letter uppercased where its hash nibble is 8 or more
+fn checksummed(lower: &str) -> String {
+    let hash = crypto::hash_blake3(lower.as_bytes());
+    lower
+        .char_indices()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn invalid(message: &str) -> ContractError {
+    ContractError::InvalidArgument(message.to_string())
+}
+
+impl FromStr for Address {
+    type Err = ContractError;
+
+    fn from_str(address: &str) -> ContractResult<Self> {
+        Self::parse(address)
+    }
+}
+
+impl TryFrom<&str> for Address {
+    type Error = ContractError;
+
+    fn try_from(address: &str) -> ContractResult<Self> {
+        Self::parse(address)
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = ContractError;
+
+    fn try_from(address: String) -> ContractResult<Self> {
+        Self::parse(&address)
+    }
+}
+
+impl From<Address> for String {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+impl Deref for Address {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Address {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Address {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Address {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Address {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Address> for String {
+    fn eq(&self, other: &Address) -> bool {
+        *self == other.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        Address::parse(&address).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    const LOWER: &str = "0x52908400098527886e0f7030069857d2e4169ee7";
+    const NATIVE: &str = "chert1owner00000000000000000000";
+
+    #[test]
+    fn parsing_normalizes_and_checks_the_checksum() {
+        let address = Address::parse(LOWER).unwrap();
+        assert_eq!(address, LOWER);
+        let upper = format!("0x{}", LOWER[2..].to_ascii_uppercase());
+        assert_eq!(Address::parse(&upper).unwrap(), address);
+
+        let checksum = address.to_checksum();
+        assert_ne!(checksum, LOWER);
+        assert_eq!(checksum.to_ascii_lowercase(), LOWER);
+        assert_eq!(Address::parse(&checksum).unwrap(), address);
+        // Flip the case of one letter of the checksummed spelling.
+        let mut tampered = checksum.clone().into_bytes();
+        let letter = tampered.iter().rposition(u8::is_ascii_alphabetic).unwrap();
+        tampered[letter] ^= 0x20;
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(Address::parse(&tampered).is_err());
+
+        for bad in [
+            "",
+            "0x",
+            "52908400098527886e0f7030069857d2e4169ee7",
+            "0x1234",
+            "0x52908400098527886e0f7030069857d2e4169eeg",
+            "crc20_contract",
+            "chert1",
+        ] {
+            assert!(Address::parse(bad).is_err(), "{bad} parsed");
+        }
+    }
+
+    #[test]
+    fn spellings_cover_the_case_variants() {
+        let address = Address::parse(LOWER).unwrap();
+        assert_eq!(
+            address.spellings(),
+            [
+                LOWER.to_string(),
+                address.to_checksum(),
+                format!("0x{}", LOWER[2..].to_ascii_uppercase()),
+            ]
+        );
+        let zero = Address::parse(&format!("0x{}", "0".repeat(40))).unwrap();
+        assert_eq!(zero.spellings().len(), 1);
+        assert_eq!(Address::parse(NATIVE).unwrap().spellings(), [NATIVE]);
+    }
+
+    #[test]
+    fn native_addresses_keep_their_spelling() {
+        let address = Address::parse(NATIVE).unwrap();
+        assert_eq!(address, NATIVE);
+        assert!(!address.is_hex() && !address.is_zero());
+        assert_eq!(address.to_checksum(), NATIVE);
+        assert!(Address::parse(&"a".repeat(NATIVE_MAX_LEN + 1)).is_err());
+        assert!(Address::parse("chert1 owner000000").is_err());
+    }
+
+    #[test]
+    fn zero_and_serde() {
+        assert!(Address::zero().is_zero());
+        assert!(
+            Address::parse(&format!("0x{}", "0".repeat(40)))
+                .unwrap()
+                .is_zero()
+        );
+        assert!(!Address::parse(LOWER).unwrap().is_zero());
+        assert_eq!(Address::zero().to_checksum(), ZERO_ADDRESS);
+
+        // Same encoding as the string it replaces.
+        let address = Address::parse(LOWER).unwrap();
+        let encoded = postcard::to_allocvec(&address).unwrap();
+        assert_eq!(encoded, postcard::to_allocvec(LOWER).unwrap());
+        assert_eq!(postcard::from_bytes::<Address>(&encoded).unwrap(), address);
+        let bad = postcard::to_allocvec("0xnot-an-address").unwrap();
+        assert!(postcard::from_bytes::<Address>(&bad).is_err());
+    }
+
+    #[test]
+    fn keys_are_the_address_bytes() {
+        let address = Address::parse(LOWER).unwrap();
+        let key = address.to_key();
+        let AddressKey::Hex(bytes) = key.clone() else {
+            panic!("hex address got a native key");
+        };
+        assert_eq!(bytes[0], 0x52);
+        assert_eq!(bytes[ADDRESS_BYTES - 1], 0xe7);
+        assert_eq!(key.to_address(), address);
+        assert_eq!(AddressKey::parse(&address.to_checksum()).unwrap(), key);
+        assert_eq!(key.to_string(), LOWER);
+        assert!(AddressKey::parse("0x1234").is_err());
+
+        assert_eq!(Address::zero().to_key(), AddressKey::from_bytes([0; 20]));
+        assert!(Address::zero().to_key().to_address().is_zero());
+
+        let encoded = postcard::to_allocvec(&key).unwrap();
+        assert_eq!(encoded.len(), ADDRESS_BYTES);
+        assert_eq!(encoded, bytes);
+
+        let native = AddressKey::parse(NATIVE).unwrap();
+        assert_eq!(native.to_address(), NATIVE);
+        assert_eq!(native.to_string(), NATIVE);
+        let encoded = postcard::to_allocvec(&native).unwrap();
+        assert_eq!(encoded.len(), NATIVE_KEY_BYTES);
+        assert_eq!(encoded, crypto::hash_blake3(NATIVE.as_bytes()));
+    }
+}
@@ -0,0 +1,84 @@
+//! Permit types and the pull helper for protocols using the manager
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+const PERMIT_DOMAIN: &str = "silica-permit-v1";
+
+/// Sub-allowances of this size are never decremented
+pub const UNLIMITED: u64 = u64::MAX;
+
+/// A spender's sub-allowance over one of an owner's tokens
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Allowance {
+    pub amount: u64,
+    /// Timestamp after which the allowance is void
+    pub expiration: u64,
+    /// Nonce the next permit for this pair must carry
+    pub nonce: u64,
+}
+
+/// A sub-allowance signed off-chain by `owner`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Permit {
+    pub owner: String,
+    pub token: String,
+    pub spender: String,
+    pub amount: u64,
+    pub expiration: u64,
+    pub nonce: u64,
+    /// Last timestamp at which the permit can be submitted
+    pub sig_deadline: u64,
+}
+
+/// Arguments of the manager's `permit` entrypoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignedPermit {
+    pub permit: Permit,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TransferFromArgs {
+    pub owner: String,
+    pub to: String,
+    pub token: String,
+    pub amount: u64,
+}
+
+/// Bytes `permit.owner` signs for `permit` on the manager at `manager`.
+pub fn permit_message(manager: &str, permit: &Permit) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(&(
+        PERMIT_DOMAIN,
+        manager,
+        &permit.owner,
+        &permit.token,
+        &permit.spender,
+        permit.amount,
+        permit.expiration,
+        permit.nonce,
+        permit.sig_deadline,
+    ))
+    .map_err(|_| ContractError::SerializationFailed)
+}
+
+/// Move `amount` of `token` from `owner` to `to`, spending the calling
+/// contract's sub-allowance on `manager`.
+pub fn transfer_from(
+    manager: &str,
+    owner: &str,
+    to: &str,
+    token: &str,
+    amount: u64,
+) -> ContractResult<()> {
+    let args = postcard::to_allocvec(&TransferFromArgs {
+        owner: owner.to_string(),
+        to: to.to_string(),
+        token: token.to_string(),
+        amount,
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    context()
+        .call_contract(manager, "transfer_from", &args)
+        .map(|_| ())
+}
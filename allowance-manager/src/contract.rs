@@ -0,0 +1,677 @@
+//! Allowance manager entrypoints
+
+use crate::client::{Allowance, SignedPermit, TransferFromArgs, UNLIMITED, permit_message};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const KEYS_PREFIX: &str = "signer_keys";
+const ALLOWANCES_PREFIX: &str = "allowances";
+const SIGNATURE_LEN: usize = 64;
+const MAX_LOCKDOWN: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// A token and spender whose sub-allowance should be revoked
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenSpender {
+    pub token: String,
+    pub spender: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegisterKeyArgs {
+    public_key: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    token: String,
+    spender: String,
+    amount: u64,
+    expiration: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LockdownArgs {
+    pairs: Vec<TokenSpender>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InvalidateNoncesArgs {
+    token: String,
+    spender: String,
+    new_nonce: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllowanceArgs {
+    owner: String,
+    token: String,
+    spender: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn signer_keys() -> Map<String, [u8; 32]> {
+    Map::new(KEYS_PREFIX)
+}
+
+/// Sub-allowances by (owner, token, spender)
+fn allowances() -> Map<(String, String, String), Allowance> {
+    Map::new(ALLOWANCES_PREFIX)
+}
+
+fn load_allowance(owner: &str, token: &str, spender: &str) -> ContractResult<Allowance> {
+    Ok(allowances()
+        .get(&(owner.to_string(), token.to_string(), spender.to_string()))?
+        .unwrap_or_default())
+}
+
+fn save_allowance(
+    owner: &str,
+    token: &str,
+    spender: &str,
+    allowance: &Allowance,
+) -> ContractResult<()> {
+    allowances().set(
+        &(owner.to_string(), token.to_string(), spender.to_string()),
+        allowance,
+    )
+}
+
+fn validate_grant(token: &str, spender: &str, expiration: u64) -> ContractResult<()> {
+    validation::validate_address(token)?;
+    validation::validate_address(spender)?;
+    if expiration <= context().block_timestamp() {
+        return Err(ContractError::InvalidArgument(
+            "Expiration must be in the future".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn execute_register_key() -> ContractResult<()> {
+    let account = context().sender().to_string();
+    let args: RegisterKeyArgs = read_args()?;
+    if args.public_key == [0u8; 32] {
+        return Err(ContractError::InvalidArgument(
+            "Public key cannot be zero".to_string(),
+        ));
+    }
+
+    signer_keys().set(&account, &args.public_key)?;
+
+    event!("SignerKeyRegistered", account: account);
+    Ok(())
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: ApproveArgs = read_args()?;
+    validate_grant(&args.token, &args.spender, args.expiration)?;
+    let mut allowance = load_allowance(&owner, &args.token, &args.spender)?;
+    allowance.amount = args.amount;
+    allowance.expiration = args.expiration;
+    save_allowance(&owner, &args.token, &args.spender, &allowance)?;
+
+    event!("Approval",
+        owner: owner,
+        token: args.token,
+        spender: args.spender,
+        amount: args.amount,
+        expiration: args.expiration
+    );
+    Ok(())
+}
+
+fn execute_permit() -> ContractResult<()> {
+    let ctx = context();
+    let args: SignedPermit = read_args()?;
+    let permit = args.permit;
+    validate_grant(&permit.token, &permit.spender, permit.expiration)?;
+    if ctx.block_timestamp() > permit.sig_deadline {
+        return Err(ContractError::InvalidArgument("Permit expired".to_string()));
+    }
+    let mut allowance = load_allowance(&permit.owner, &permit.token, &permit.spender)?;
+    if permit.nonce != allowance.nonce {
+        return Err(ContractError::InvalidArgument(format!(
+            "Invalid nonce: expected {}",
+            allowance.nonce
+        )));
+    }
+
+    let public_key = signer_keys()
+        .get(&permit.owner)?
+        .ok_or(ContractError::Unauthorized)?;
+    let signature: [u8; SIGNATURE_LEN] = args
+        .signature
+        .as_slice()
+        .try_into()
+        .map_err(|_| ContractError::InvalidSignature)?;
+    let message = permit_message(ctx.contract_address(), &permit)?;
+    if !crypto::verify_signature(&public_key, &message, &signature)? {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    allowance.amount = permit.amount;
+    allowance.expiration = permit.expiration;
+    allowance.nonce = safe_math::add(allowance.nonce, 1)?;
+    save_allowance(&permit.owner, &permit.token, &permit.spender, &allowance)?;
+
+    event!("Permit",
+        owner: permit.owner,
+        token: permit.token,
+        spender: permit.spender,
+        amount: permit.amount,
+        expiration: permit.expiration,
+        nonce: permit.nonce
+    );
+    Ok(())
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let spender = ctx.sender().to_string();
+    let args: TransferFromArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    validation::validate_address(&args.to)?;
+    let mut allowance = load_allowance(&args.owner, &args.token, &spender)?;
+    if ctx.block_timestamp() > allowance.expiration {
+        return Err(ContractError::InvalidArgument(
+            "Allowance expired".to_string(),
+        ));
+    }
+    if allowance.amount < args.amount {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: allowance.amount,
+        });
+    }
+    if allowance.amount != UNLIMITED {
+        allowance.amount -= args.amount;
+        save_allowance(&args.owner, &args.token, &spender, &allowance)?;
+    }
+
+    let call = postcard::to_allocvec(&TokenTransferFromArgs {
+        from: args.owner.clone(),
+        to: args.to.clone(),
+        amount: args.amount,
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    ctx.call_contract(&args.token, "transfer_from", &call)?;
+
+    event!("AllowanceSpent",
+        owner: args.owner,
+        token: args.token,
+        spender: spender,
+        to: args.to,
+        amount: args.amount,
+        remaining: allowance.amount
+    );
+    Ok(())
+}
+
+fn execute_lockdown() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: LockdownArgs = read_args()?;
+    if args.pairs.is_empty() || args.pairs.len() > MAX_LOCKDOWN {
+        return Err(ContractError::InvalidArgument(format!(
+            "Lockdown takes 1-{} pairs",
+            MAX_LOCKDOWN
+        )));
+    }
+    for pair in args.pairs {
+        let mut allowance = load_allowance(&owner, &pair.token, &pair.spender)?;
+        if allowance.amount == 0 {
+            continue;
+        }
+        allowance.amount = 0;
+        save_allowance(&owner, &pair.token, &pair.spender, &allowance)?;
+
+        event!("Lockdown",
+            owner: owner.clone(),
+            token: pair.token,
+            spender: pair.spender
+        );
+    }
+    Ok(())
+}
+
+fn execute_invalidate_nonces() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: InvalidateNoncesArgs = read_args()?;
+    let mut allowance = load_allowance(&owner, &args.token, &args.spender)?;
+    if args.new_nonce <= allowance.nonce {
+        return Err(ContractError::InvalidArgument(
+            "Nonces can only move forward".to_string(),
+        ));
+    }
+    let old_nonce = allowance.nonce;
+    allowance.nonce = args.new_nonce;
+    save_allowance(&owner, &args.token, &args.spender, &allowance)?;
+
+    event!("NonceInvalidated",
+        owner: owner,
+        token: args.token,
+        spender: args.spender,
+        old_nonce: old_nonce,
+        new_nonce: args.new_nonce
+    );
+    Ok(())
+}
+
+/// Register the Ed25519 key that signs permits for the caller
+///
+/// # Arguments
+/// * `public_key` - Ed25519 public key
+#[unsafe(no_mangle)]
+pub extern "C" fn register_key() {
+    if let Err(err) = execute_register_key() {
+        log!("register_key failed: {}", err);
+    }
+}
+
+/// Set a spender's sub-allowance over one of the caller's tokens
+///
+/// # Arguments
+/// * `token` - CRC-20 address (approve the manager on it first)
+/// * `spender` - Protocol allowed to pull the tokens
+/// * `amount` - Allowance; `u64::MAX` is never decremented
+/// * `expiration` - Timestamp after which the allowance is void
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("approve failed: {}", err);
+    }
+}
+
+/// Set a sub-allowance from a permit the owner signed (anyone can submit)
+///
+/// # Arguments
+/// * `permit` - `Permit` whose nonce matches the pair's current nonce
+/// * `signature` - Owner's Ed25519 signature over `permit_message`
+#[unsafe(no_mangle)]
+pub extern "C" fn permit() {
+    if let Err(err) = execute_permit() {
+        log!("permit failed: {}", err);
+    }
+}
+
+/// Pull tokens from an owner, spending the caller's sub-allowance
+///
+/// # Arguments
+/// * `owner` - Token holder
+/// * `to` - Recipient
+/// * `token` - CRC-20 address
+/// * `amount` - Amount to move
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("transfer_from failed: {}", err);
+    }
+}
+
+/// Revoke the caller's sub-allowances for up to 64 token/spender pairs
+///
+/// Nonces are left alone; use `invalidate_nonces` to void signed permits
+/// that have not been submitted yet.
+///
+/// # Arguments
+/// * `pairs` - `TokenSpender` entries
+#[unsafe(no_mangle)]
+pub extern "C" fn lockdown() {
+    if let Err(err) = execute_lockdown() {
+        log!("lockdown failed: {}", err);
+    }
+}
+
+/// Void every unsubmitted permit for a token/spender pair below `new_nonce`
+///
+/// # Arguments
+/// * `token` - CRC-20 address
+/// * `spender` - Spender address
+/// * `new_nonce` - Next valid nonce; must be higher than the current one
+#[unsafe(no_mangle)]
+pub extern "C" fn invalidate_nonces() {
+    if let Err(err) = execute_invalidate_nonces() {
+        log!("invalidate_nonces failed: {}", err);
+    }
+}
+
+/// Get a sub-allowance
+///
+/// # Arguments
+/// * `owner` - Token holder
+/// * `token` - CRC-20 address
+/// * `spender` - Spender address
+///
+/// # Returns
+/// `Allowance`
+#[unsafe(no_mangle)]
+pub extern "C" fn allowance() {
+    let result = read_args::<AllowanceArgs>()
+        .and_then(|args| load_allowance(&args.owner, &args.token, &args.spender))
+        .and_then(|allowance| try_respond(&allowance));
+    if let Err(err) = result {
+        log!("allowance failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client::Permit;
+    use crc_standards::version::ContractVersion;
+    use ed25519_dalek::{Signer, SigningKey};
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ATTACKER: &str = "0x0000000000000000000000000000000000000e05";
+    const MANAGER: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const OTHER_TOKEN: &str = "0x0000000000000000000000000000000000000f02";
+    const DEX: &str = "0x0000000000000000000000000000000000000d01";
+    const LENDER: &str = "0x0000000000000000000000000000000000000d02";
+    const START: u64 = 1_736_000_000;
+    const HOUR: u64 = 3_600;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn keys() -> Vec<SigningKey> {
+        crypto::generate_keypairs(2)
+            .expect("keypairs")
+            .iter()
+            .map(|(_, secret)| SigningKey::from_bytes(secret))
+            .collect()
+    }
+
+    fn setup() -> SigningKey {
+        mock::reset();
+        mock::set_contract_address(MANAGER);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        for token in [TOKEN, OTHER_TOKEN] {
+            mock::register_contract(token, "transfer_from", |_| Ok(Vec::new()));
+        }
+        let key = keys().remove(0);
+        call(
+            ADDR_OWNER,
+            register_key,
+            encode(&RegisterKeyArgs {
+                public_key: key.verifying_key().to_bytes(),
+            }),
+        );
+        key
+    }
+
+    fn grant(token: &str, spender: &str, amount: u64, expiration: u64) {
+        call(
+            ADDR_OWNER,
+            approve,
+            encode(&ApproveArgs {
+                token: token.to_string(),
+                spender: spender.to_string(),
+                amount,
+                expiration,
+            }),
+        );
+    }
+
+    fn pull(spender: &str, amount: u64) {
+        call(
+            spender,
+            transfer_from,
+            encode(&TransferFromArgs {
+                owner: ADDR_OWNER.to_string(),
+                to: spender.to_string(),
+                token: TOKEN.to_string(),
+                amount,
+            }),
+        );
+    }
+
+    fn permit_for(spender: &str, amount: u64, nonce: u64) -> Permit {
+        Permit {
+            owner: ADDR_OWNER.to_string(),
+            token: TOKEN.to_string(),
+            spender: spender.to_string(),
+            amount,
+            expiration: START + HOUR,
+            nonce,
+            sig_deadline: START + 60,
+        }
+    }
+
+    fn submit(permit: Permit, key: &SigningKey) {
+        let message = permit_message(MANAGER, &permit).unwrap();
+        let signature = key.sign(&message).to_bytes().to_vec();
+        call(
+            DEX,
+            super::permit,
+            encode(&SignedPermit { permit, signature }),
+        );
+    }
+
+    fn current(token: &str, spender: &str) -> Allowance {
+        load_allowance(ADDR_OWNER, token, spender).unwrap()
+    }
+
+    /// (from, to, amount) of every token pull since the last call
+    fn pulls() -> Vec<(String, String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer_from")
+            .map(|c| {
+                let args: TokenTransferFromArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.from, args.to, args.amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spenders_pull_within_their_allowance() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        grant(TOKEN, DEX, 100, START + HOUR);
+
+        pull(DEX, 60);
+        pull(DEX, 41);
+        pull(LENDER, 1);
+        pull(DEX, 40);
+        assert_eq!(
+            pulls(),
+            [
+                (ADDR_OWNER.to_string(), DEX.to_string(), 60),
+                (ADDR_OWNER.to_string(), DEX.to_string(), 40),
+            ]
+        );
+        assert_eq!(current(TOKEN, DEX).amount, 0);
+
+        grant(TOKEN, DEX, UNLIMITED, START + HOUR);
+        pull(DEX, 1_000);
+        assert_eq!(current(TOKEN, DEX).amount, UNLIMITED);
+        mock::set_block_timestamp(START + HOUR + 1);
+        pull(DEX, 1);
+        assert_eq!(pulls().len(), 1);
+    }
+
+    #[test]
+    fn signed_permits_grant_allowances_once() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let key = setup();
+
+        submit(permit_for(DEX, 500, 0), &key);
+        assert_eq!(
+            current(TOKEN, DEX),
+            Allowance {
+                amount: 500,
+                expiration: START + HOUR,
+                nonce: 1
+            }
+        );
+        // Replaying the same permit fails on the nonce.
+        submit(permit_for(DEX, 500, 0), &key);
+        assert_eq!(current(TOKEN, DEX).nonce, 1);
+
+        // A permit signed by another key, or past its deadline, is rejected.
+        submit(permit_for(DEX, 900, 1), &keys()[1]);
+        mock::set_block_timestamp(START + 61);
+        submit(permit_for(DEX, 900, 1), &key);
+        assert_eq!(current(TOKEN, DEX).amount, 500);
+    }
+
+    #[test]
+    fn tampered_permits_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let key = setup();
+        let signed = permit_for(DEX, 10, 0);
+        let message = permit_message(MANAGER, &signed).unwrap();
+        let signature = key.sign(&message).to_bytes().to_vec();
+
+        let mut redirected = signed.clone();
+        redirected.spender = ADDR_ATTACKER.to_string();
+        let mut inflated = signed;
+        inflated.amount = UNLIMITED;
+        for permit in [redirected, inflated] {
+            call(
+                ADDR_ATTACKER,
+                super::permit,
+                encode(&SignedPermit {
+                    permit,
+                    signature: signature.clone(),
+                }),
+            );
+        }
+        assert_eq!(current(TOKEN, ADDR_ATTACKER), Allowance::default());
+        assert_eq!(current(TOKEN, DEX), Allowance::default());
+    }
+
+    #[test]
+    fn lockdown_revokes_many_allowances_at_once() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        grant(TOKEN, DEX, 100, START + HOUR);
+        grant(TOKEN, LENDER, 100, START + HOUR);
+        grant(OTHER_TOKEN, DEX, 100, START + HOUR);
+
+        let pair = |token: &str, spender: &str| TokenSpender {
+            token: token.to_string(),
+            spender: spender.to_string(),
+        };
+        call(
+            ADDR_OWNER,
+            lockdown,
+            encode(&LockdownArgs {
+                pairs: vec![pair(TOKEN, DEX), pair(OTHER_TOKEN, DEX)],
+            }),
+        );
+        assert_eq!(current(TOKEN, DEX).amount, 0);
+        assert_eq!(current(OTHER_TOKEN, DEX).amount, 0);
+        assert_eq!(current(TOKEN, LENDER).amount, 100);
+        pull(DEX, 1);
+        assert!(pulls().is_empty());
+    }
+
+    #[test]
+    fn invalidated_nonces_void_outstanding_permits() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let key = setup();
+        let invalidate = |new_nonce: u64| {
+            call(
+                ADDR_OWNER,
+                invalidate_nonces,
+                encode(&InvalidateNoncesArgs {
+                    token: TOKEN.to_string(),
+                    spender: DEX.to_string(),
+                    new_nonce,
+                }),
+            );
+        };
+
+        invalidate(5);
+        submit(permit_for(DEX, 100, 0), &key);
+        assert_eq!(current(TOKEN, DEX).amount, 0);
+        invalidate(3);
+        assert_eq!(current(TOKEN, DEX).nonce, 5);
+        submit(permit_for(DEX, 100, 5), &key);
+        assert_eq!(current(TOKEN, DEX).amount, 100);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "allowance-manager-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Allowance Manager
+//!
+//! One approval per token instead of one per protocol. Users approve the
+//! manager once on each CRC-20, then grant individual protocols expiring
+//! sub-allowances, either directly or with an Ed25519-signed permit that
+//! the protocol submits itself. Protocols pull tokens through the manager
+//! with [`client::transfer_from`], and a user can revoke any number of
+//! sub-allowances in one call.
+//!
+//! ## Embedding
+//! ```toml
+//! allowance-manager-contract = { path = "../allowance-manager", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use allowance_manager_contract::client;
+//!
+//! client::transfer_from(&config.allowance_manager, &user, ctx.contract_address(), &token, amount)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod client;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
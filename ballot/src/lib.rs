@@ -0,0 +1,716 @@
+//! Commit-Reveal Ballots
+//!
+//! Token-weighted votes that stay secret until everyone has voted. During
+//! the commit phase a voter submits only a hash of their choice and a
+//! random salt, so nobody can copy or react to votes already cast. During
+//! the reveal phase voters publish the choice and salt, and the contract
+//! checks them against the commitment. Only revealed votes are counted.
+//!
+//! Voting weight is the voter's balance of the ballot's CRC-20 at the block
+//! before the ballot was created (`balance_of_at`), so tokens bought or
+//! moved after creation carry no extra weight.
+//!
+//! ## Features
+//! - Any number of concurrent ballots, each with its own token and phases
+//! - Commitments bound to ballot and voter, so they cannot be replayed
+//! - Commitments may be replaced until the commit phase ends
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub use commit_reveal::Phase;
+use commit_reveal::{Commitment, Phases, Salt};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const NEXT_BALLOT_KEY: &str = "next_ballot_id";
+const BALLOTS_PREFIX: &str = "ballots";
+const COMMITMENTS_PREFIX: &str = "commitments";
+const COMMITMENT_PURPOSE: &str = "ballot";
+const MIN_PHASE_DURATION: u64 = 60 * 60;
+const MAX_PHASE_DURATION: u64 = 30 * 24 * 60 * 60;
+const MIN_OPTIONS: usize = 2;
+const MAX_OPTIONS: usize = 16;
+const MAX_TITLE_LEN: usize = 128;
+const MAX_OPTION_LEN: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// A ballot and its running tally
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Ballot {
+    pub creator: String,
+    pub title: String,
+    /// CRC-20 whose balances weight the votes
+    pub token: String,
+    pub options: Vec<String>,
+    /// Block whose closing balances are the voting weights
+    pub snapshot_block: u64,
+    /// Commitments are accepted until `commit_end`, reveals from then until
+    /// `reveal_end`
+    pub phases: Phases,
+    /// Revealed weight per option
+    pub tallies: Vec<u64>,
+    pub commit_count: u64,
+    pub reveal_count: u64,
+}
+
+/// A voter's sealed vote
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteCommitment {
+    pub commitment: [u8; 32],
+    pub weight: u64,
+    /// Set once the vote is revealed
+    pub choice: Option<u32>,
+}
+
+/// Current standing of a ballot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BallotResults {
+    pub phase: Phase,
+    pub tallies: Vec<u64>,
+    pub revealed_weight: u64,
+    /// Option with the most weight once the ballot is closed; `None` on a
+    /// tie or when no vote was revealed
+    pub winner: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateBallotArgs {
+    title: String,
+    token: String,
+    options: Vec<String>,
+    commit_duration: u64,
+    reveal_duration: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommitVoteArgs {
+    ballot_id: u64,
+    commitment: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct RevealVoteArgs {
+    ballot_id: u64,
+    choice: u32,
+    salt: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct BallotIdArgs {
+    ballot_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetCommitmentArgs {
+    ballot_id: u64,
+    voter: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BalanceOfAtArgs {
+    account: String,
+    block: u64,
+}
+
+/// Commitment a voter submits for `choice` on `ballot_id`: the
+/// [`commit_reveal`] commitment to `choice` for the purpose
+/// `("ballot", ballot_id)`.
+pub fn commitment_hash(
+    ballot_id: u64,
+    voter: &str,
+    choice: u32,
+    salt: &Salt,
+) -> ContractResult<Commitment> {
+    commit_reveal::commitment(&(COMMITMENT_PURPOSE, ballot_id), voter, &choice, salt)
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn ballots() -> Map<u64, Ballot> {
+    Map::new(BALLOTS_PREFIX)
+}
+
+fn commitments() -> Map<(u64, String), VoteCommitment> {
+    Map::new(COMMITMENTS_PREFIX)
+}
+
+fn load_ballot(ballot_id: u64) -> ContractResult<Ballot> {
+    ballots()
+        .get(&ballot_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown ballot".to_string()))
+}
+
+fn validate_duration(duration: u64, name: &str) -> ContractResult<()> {
+    if !(MIN_PHASE_DURATION..=MAX_PHASE_DURATION).contains(&duration) {
+        return Err(ContractError::InvalidArgument(format!(
+            "{} must be between 1 hour and 30 days",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn snapshot_weight(ballot: &Ballot, voter: &str) -> ContractResult<u64> {
+    let call = encode_call(&BalanceOfAtArgs {
+        account: voter.to_string(),
+        block: ballot.snapshot_block,
+    })?;
+    let response = context().call_contract(&ballot.token, "balance_of_at", &call)?;
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn results(ballot: &Ballot, now: u64) -> BallotResults {
+    let phase = ballot.phases.at(now);
+    let revealed_weight = ballot.tallies.iter().sum();
+    let mut winner = None;
+    if phase == Phase::Closed && revealed_weight > 0 {
+        let top = ballot.tallies.iter().copied().max().unwrap_or(0);
+        let mut leaders = ballot
+            .tallies
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| **w == top);
+        if let (Some((index, _)), None) = (leaders.next(), leaders.next()) {
+            winner = Some(index as u32);
+        }
+    }
+    BallotResults {
+        phase,
+        tallies: ballot.tallies.clone(),
+        revealed_weight,
+        winner,
+    }
+}
+
+fn execute_create_ballot() -> ContractResult<u64> {
+    let ctx = context();
+    let args: CreateBallotArgs = read_args()?;
+    validation::validate_non_empty(&args.title, "title")?;
+    if args.title.len() > MAX_TITLE_LEN {
+        return Err(ContractError::InvalidArgument(format!(
+            "Title cannot exceed {} bytes",
+            MAX_TITLE_LEN
+        )));
+    }
+    if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&args.options.len()) {
+        return Err(ContractError::InvalidArgument(format!(
+            "A ballot needs {}-{} options",
+            MIN_OPTIONS, MAX_OPTIONS
+        )));
+    }
+    for option in &args.options {
+        validation::validate_non_empty(option, "option")?;
+        if option.len() > MAX_OPTION_LEN {
+            return Err(ContractError::InvalidArgument(format!(
+                "Options cannot exceed {} bytes",
+                MAX_OPTION_LEN
+            )));
+        }
+    }
+    validation::validate_address(&args.token)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    validate_duration(args.commit_duration, "Commit phase")?;
+    validate_duration(args.reveal_duration, "Reveal phase")?;
+    let snapshot_block = ctx.block_height().checked_sub(1).ok_or_else(|| {
+        ContractError::InvalidArgument("No finished block to snapshot".to_string())
+    })?;
+
+    let phases = Phases::starting_now(
+        Duration::from_secs(args.commit_duration),
+        Duration::from_secs(args.reveal_duration),
+    )?;
+    let ballot_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_BALLOT_KEY)?.unwrap_or(0);
+        store.set(NEXT_BALLOT_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    ballots().set(
+        &ballot_id,
+        &Ballot {
+            creator: ctx.sender().to_string(),
+            title: args.title,
+            token: args.token.clone(),
+            tallies: vec![0; args.options.len()],
+            options: args.options,
+            snapshot_block,
+            phases,
+            commit_count: 0,
+            reveal_count: 0,
+        },
+    )?;
+
+    event!("BallotCreated",
+        ballot_id: ballot_id,
+        creator: ctx.sender(),
+        token: args.token,
+        snapshot_block: snapshot_block,
+        commit_end: phases.commit_end,
+        reveal_end: phases.reveal_end
+    );
+    Ok(ballot_id)
+}
+
+fn execute_commit_vote() -> ContractResult<()> {
+    let voter = context().sender().to_string();
+    let args: CommitVoteArgs = read_args()?;
+    let mut ballot = load_ballot(args.ballot_id)?;
+    ballot.phases.require(Phase::Commit)?;
+
+    let key = (args.ballot_id, voter.clone());
+    let mut store = commitments();
+    let weight = match store.get(&key)? {
+        // Replacing a commitment keeps the weight already read.
+        Some(existing) => existing.weight,
+        None => {
+            let weight = snapshot_weight(&ballot, &voter)?;
+            if weight == 0 {
+                return Err(ContractError::InvalidArgument(
+                    "No voting weight at the snapshot block".to_string(),
+                ));
+            }
+            ballot.commit_count = safe_math::add(ballot.commit_count, 1)?;
+            ballots().set(&args.ballot_id, &ballot)?;
+            weight
+        }
+    };
+    store.set(
+        &key,
+        &VoteCommitment {
+            commitment: args.commitment,
+            weight,
+            choice: None,
+        },
+    )?;
+
+    event!("VoteCommitted", ballot_id: args.ballot_id, voter: voter, weight: weight);
+    Ok(())
+}
+
+fn execute_reveal_vote() -> ContractResult<()> {
+    let voter = context().sender().to_string();
+    let args: RevealVoteArgs = read_args()?;
+    let mut ballot = load_ballot(args.ballot_id)?;
+    ballot.phases.require(Phase::Reveal)?;
+
+    let key = (args.ballot_id, voter.clone());
+    let mut store = commitments();
+    let mut vote = store
+        .get(&key)?
+        .ok_or_else(|| ContractError::InvalidArgument("No commitment to reveal".to_string()))?;
+    if vote.choice.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Vote already revealed".to_string(),
+        ));
+    }
+    commit_reveal::verify(
+        &vote.commitment,
+        &(COMMITMENT_PURPOSE, args.ballot_id),
+        &voter,
+        &args.choice,
+        &args.salt,
+    )?;
+    // A commitment to an option that does not exist can never be counted.
+    let tally = ballot
+        .tallies
+        .get_mut(args.choice as usize)
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown option".to_string()))?;
+    *tally = safe_math::add(*tally, vote.weight)?;
+    ballot.reveal_count = safe_math::add(ballot.reveal_count, 1)?;
+    ballots().set(&args.ballot_id, &ballot)?;
+    vote.choice = Some(args.choice);
+    store.set(&key, &vote)?;
+
+    event!("VoteRevealed",
+        ballot_id: args.ballot_id,
+        voter: voter,
+        choice: args.choice,
+        weight: vote.weight
+    );
+    Ok(())
+}
+
+/// Create a ballot; voting weights are snapshotted at the previous block
+///
+/// # Arguments
+/// * `title` - Question being voted on
+/// * `token` - CRC-20 whose balances weight the votes
+/// * `options` - 2-16 option labels
+/// * `commit_duration` - Length of the commit phase in seconds (1 hour to 30 days)
+/// * `reveal_duration` - Length of the reveal phase in seconds (1 hour to 30 days)
+///
+/// # Returns
+/// Ballot id
+#[unsafe(no_mangle)]
+pub extern "C" fn create_ballot() {
+    let result = execute_create_ballot().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("create_ballot failed: {}", err);
+    }
+}
+
+/// Submit or replace a sealed vote during the commit phase
+///
+/// # Arguments
+/// * `ballot_id` - Ballot id
+/// * `commitment` - `commitment_hash(ballot_id, voter, choice, salt)`
+#[unsafe(no_mangle)]
+pub extern "C" fn commit_vote() {
+    if let Err(err) = execute_commit_vote() {
+        log!("commit_vote failed: {}", err);
+    }
+}
+
+/// Reveal a committed vote during the reveal phase; it is counted at once
+///
+/// # Arguments
+/// * `ballot_id` - Ballot id
+/// * `choice` - Index of the chosen option
+/// * `salt` - Salt used in the commitment
+#[unsafe(no_mangle)]
+pub extern "C" fn reveal_vote() {
+    if let Err(err) = execute_reveal_vote() {
+        log!("reveal_vote failed: {}", err);
+    }
+}
+
+/// Get a ballot
+///
+/// # Arguments
+/// * `ballot_id` - Ballot id
+///
+/// # Returns
+/// `Ballot`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_ballot() {
+    let result = read_args::<BallotIdArgs>()
+        .and_then(|args| load_ballot(args.ballot_id))
+        .and_then(|ballot| try_respond(&ballot));
+    if let Err(err) = result {
+        log!("get_ballot failed: {}", err);
+    }
+}
+
+/// Get the phase, tallies and (once closed) the winner of a ballot
+///
+/// # Arguments
+/// * `ballot_id` - Ballot id
+///
+/// # Returns
+/// `BallotResults`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_results() {
+    let result = read_args::<BallotIdArgs>()
+        .and_then(|args| load_ballot(args.ballot_id))
+        .and_then(|ballot| try_respond(&results(&ballot, context().block_timestamp())));
+    if let Err(err) = result {
+        log!("get_results failed: {}", err);
+    }
+}
+
+/// Get a voter's commitment
+///
+/// # Arguments
+/// * `ballot_id` - Ballot id
+/// * `voter` - Voter address
+///
+/// # Returns
+/// `Option<VoteCommitment>`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_commitment() {
+    let result = read_args::<GetCommitmentArgs>()
+        .and_then(|args| commitments().get(&(args.ballot_id, args.voter)))
+        .and_then(|vote| try_respond(&vote));
+    if let Err(err) = result {
+        log!("get_commitment failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_CREATOR: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_DAVE: &str = "0x0000000000000000000000000000000000000e05";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const START: u64 = 1_736_000_000;
+    const HOUR: u64 = 60 * 60;
+    const SALT: [u8; 32] = [7; 32];
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    /// Snapshot balances: Alice 300, Bob 200, Carol 100, Dave 0. Creates
+    /// ballot 0 with two options and one-day phases at block 10.
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(10);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "balance_of_at", |args| {
+            let args: BalanceOfAtArgs = postcard::from_bytes(args).unwrap();
+            assert_eq!(args.block, 9);
+            let balance: u64 = match args.account.as_str() {
+                ADDR_ALICE => 300,
+                ADDR_BOB => 200,
+                ADDR_CAROL => 100,
+                _ => 0,
+            };
+            Ok(postcard::to_allocvec(&balance).unwrap())
+        });
+        let id = call(
+            ADDR_CREATOR,
+            create_ballot,
+            encode(&CreateBallotArgs {
+                title: "Adopt proposal 12?".to_string(),
+                token: TOKEN.to_string(),
+                options: vec!["No".to_string(), "Yes".to_string()],
+                commit_duration: 24 * HOUR,
+                reveal_duration: 24 * HOUR,
+            }),
+        );
+        assert_eq!(postcard::from_bytes::<u64>(&id).unwrap(), 0);
+    }
+
+    fn commit(voter: &str, choice: u32) {
+        call(
+            voter,
+            commit_vote,
+            encode(&CommitVoteArgs {
+                ballot_id: 0,
+                commitment: commitment_hash(0, voter, choice, &SALT).unwrap(),
+            }),
+        );
+    }
+
+    fn reveal(voter: &str, choice: u32, salt: [u8; 32]) {
+        call(
+            voter,
+            reveal_vote,
+            encode(&RevealVoteArgs {
+                ballot_id: 0,
+                choice,
+                salt,
+            }),
+        );
+    }
+
+    fn results_now() -> BallotResults {
+        let data = call(
+            ADDR_CREATOR,
+            get_results,
+            encode(&BallotIdArgs { ballot_id: 0 }),
+        );
+        postcard::from_bytes(&data).unwrap()
+    }
+
+    #[test]
+    fn only_revealed_votes_are_tallied() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        commit(ADDR_ALICE, 1);
+        commit(ADDR_BOB, 0);
+        commit(ADDR_CAROL, 0);
+        let ballot = load_ballot(0).unwrap();
+        assert_eq!((ballot.snapshot_block, ballot.commit_count), (9, 3));
+
+        mock::set_block_timestamp(START + 24 * HOUR);
+        reveal(ADDR_ALICE, 1, SALT);
+        reveal(ADDR_BOB, 0, SALT);
+        let results = results_now();
+        assert_eq!(results.phase, Phase::Reveal);
+        assert_eq!(results.tallies, [200, 300]);
+        assert_eq!(results.winner, None);
+
+        // Carol never reveals; her weight is not counted.
+        mock::set_block_timestamp(START + 48 * HOUR);
+        let results = results_now();
+        assert_eq!(results.phase, Phase::Closed);
+        assert_eq!(results.revealed_weight, 500);
+        assert_eq!(results.winner, Some(1));
+        assert_eq!(load_ballot(0).unwrap().reveal_count, 2);
+    }
+
+    #[test]
+    fn reveals_must_match_the_commitment() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        commit(ADDR_ALICE, 1);
+
+        // Too early to reveal.
+        reveal(ADDR_ALICE, 1, SALT);
+        assert_eq!(results_now().revealed_weight, 0);
+
+        mock::set_block_timestamp(START + 24 * HOUR);
+        reveal(ADDR_ALICE, 0, SALT);
+        reveal(ADDR_ALICE, 1, [8; 32]);
+        // Bob cannot reveal Alice's vote as his own.
+        reveal(ADDR_BOB, 1, SALT);
+        assert_eq!(results_now().revealed_weight, 0);
+
+        reveal(ADDR_ALICE, 1, SALT);
+        reveal(ADDR_ALICE, 1, SALT);
+        assert_eq!(results_now().tallies, [0, 300]);
+    }
+
+    #[test]
+    fn commitments_close_with_the_commit_phase() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        // Dave held nothing at the snapshot.
+        commit(ADDR_DAVE, 1);
+        assert!(
+            commitments()
+                .get(&(0, ADDR_DAVE.to_string()))
+                .unwrap()
+                .is_none()
+        );
+
+        // Alice changes her mind before the commit phase ends.
+        commit(ADDR_ALICE, 1);
+        commit(ADDR_ALICE, 0);
+        assert_eq!(load_ballot(0).unwrap().commit_count, 1);
+
+        mock::set_block_timestamp(START + 24 * HOUR);
+        commit(ADDR_BOB, 1);
+        assert_eq!(load_ballot(0).unwrap().commit_count, 1);
+
+        reveal(ADDR_ALICE, 1, SALT);
+        reveal(ADDR_ALICE, 0, SALT);
+        assert_eq!(results_now().tallies, [300, 0]);
+    }
+
+    #[test]
+    fn commitments_are_bound_to_the_ballot() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_ne!(
+            commitment_hash(0, ADDR_ALICE, 1, &SALT).unwrap(),
+            commitment_hash(1, ADDR_ALICE, 1, &SALT).unwrap()
+        );
+        assert_ne!(
+            commitment_hash(0, ADDR_ALICE, 1, &SALT).unwrap(),
+            commitment_hash(0, ADDR_BOB, 1, &SALT).unwrap()
+        );
+
+        // A commitment to an option that does not exist is never counted.
+        commit(ADDR_ALICE, 5);
+        mock::set_block_timestamp(START + 24 * HOUR);
+        reveal(ADDR_ALICE, 5, SALT);
+        assert_eq!(results_now().revealed_weight, 0);
+    }
+
+    #[test]
+    fn ballot_configuration_is_validated() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let create = |options: Vec<&str>, commit_duration: u64| {
+            call(
+                ADDR_CREATOR,
+                create_ballot,
+                encode(&CreateBallotArgs {
+                    title: "Pick one".to_string(),
+                    token: TOKEN.to_string(),
+                    options: options.into_iter().map(str::to_string).collect(),
+                    commit_duration,
+                    reveal_duration: HOUR,
+                }),
+            )
+        };
+        assert!(create(vec!["Only"], HOUR).is_empty());
+        assert!(create(vec!["A", "B"], 60).is_empty());
+        assert!(create(vec!["A", ""], HOUR).is_empty());
+        let id: u64 = postcard::from_bytes(&create(vec!["A", "B", "C"], HOUR)).unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(load_ballot(1).unwrap().tallies, [0, 0, 0]);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "ballot-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Instance- and implementation-side helpers
+//!
+//! Instances call [`forward`] from their entrypoints. Shared implementations
+//! call [`require_instance`] to learn which instance they are serving and
+//! [`forwarded_sender`] to recover the account that called it.
+
+use proxy_implementation as implementation;
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct InstanceArgs {
+    pub instance: String,
+}
+
+fn query<T: for<'de> Deserialize<'de>>(
+    beacon: &str,
+    method: &str,
+    instance: &str,
+) -> ContractResult<T> {
+    let args = postcard::to_allocvec(&InstanceArgs {
+        instance: instance.to_string(),
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    let response = context().call_contract(beacon, method, &args)?;
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Implementation the beacon currently assigns to the calling instance.
+pub fn implementation_for_self(beacon: &str) -> ContractResult<String> {
+    query(beacon, "implementation_for", context().contract_address())
+}
+
+/// Forward `method` to this instance's implementation with the caller appended.
+pub fn forward(beacon: &str, method: &str, data: &[u8]) -> ContractResult<Vec<u8>> {
+    let target = implementation_for_self(beacon)?;
+    let ctx = context();
+    let data = implementation::append_sender(data, ctx.sender())?;
+    ctx.call_contract(&target, method, &data)
+}
+
+/// Check that the caller is an instance registered with `beacon` and return it.
+pub fn require_instance(beacon: &str) -> ContractResult<String> {
+    let instance = context().sender().to_string();
+    if !query::<bool>(beacon, "is_instance", &instance)? {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(instance)
+}
+
+/// Account that called the instance; only meaningful after [`require_instance`].
+pub fn forwarded_sender() -> ContractResult<String> {
+    let data = context().call_data()?;
+    let (_, sender) =
+        implementation::split_sender(&data).ok_or(ContractError::DeserializationFailed)?;
+    validation::validate_address(sender)?;
+    Ok(sender.to_string())
+}
@@ -0,0 +1,668 @@
+//! Beacon entrypoints
+
+use crate::client::InstanceArgs;
+use proxy_implementation as implementation;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "beacon_config";
+const INSTANCES_PREFIX: &str = "instances";
+const REGISTRARS_PREFIX: &str = "registrars";
+const REGISTRAR_ROLE: &str = "registrar";
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Beacon configuration and current implementation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BeaconConfig {
+    pub owner: String,
+    pub implementation: String,
+    pub version: u32,
+    pub schema_version: u32,
+}
+
+/// Implementation an instance pinned when it froze
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FrozenPin {
+    pub implementation: String,
+    pub version: u32,
+    pub frozen_at: u64,
+}
+
+/// A proxy instance following this beacon
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InstanceRecord {
+    pub registered_at: u64,
+    pub frozen: Option<FrozenPin>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ImplementationArgs {
+    implementation: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetRegistrarArgs {
+    registrar: String,
+    enabled: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferOwnershipArgs {
+    new_owner: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<BeaconConfig> {
+    storage()
+        .get::<BeaconConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Beacon not initialized".to_string()))
+}
+
+fn save_config(config: &BeaconConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<BeaconConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn instances() -> Map<String, InstanceRecord> {
+    Map::new(INSTANCES_PREFIX)
+}
+
+fn registrars() -> Map<String, bool> {
+    Map::new(REGISTRARS_PREFIX)
+}
+
+/// Load the calling instance's record; only registered instances may freeze.
+fn load_caller_instance() -> ContractResult<(String, InstanceRecord)> {
+    let instance = context().sender().to_string();
+    let record = instances()
+        .get(&instance)?
+        .ok_or(ContractError::Unauthorized)?;
+    Ok((instance, record))
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Beacon already initialized".to_string(),
+        ));
+    }
+    let args: ImplementationArgs = read_args()?;
+    validation::validate_address(&args.implementation)?;
+    let schema_version = implementation::query_schema_version(&args.implementation)?;
+
+    let owner = context().sender().to_string();
+    save_config(&BeaconConfig {
+        owner: owner.clone(),
+        implementation: args.implementation.clone(),
+        version: 1,
+        schema_version,
+    })?;
+
+    event!("BeaconInitialized",
+        owner: owner,
+        implementation: args.implementation,
+        schema_version: schema_version
+    );
+    Ok(())
+}
+
+fn execute_upgrade() -> ContractResult<u32> {
+    let mut config = load_owned_config()?;
+    let args: ImplementationArgs = read_args()?;
+    validation::validate_address(&args.implementation)?;
+    if args.implementation == config.implementation {
+        return Err(ContractError::InvalidArgument(
+            "Implementation is already current".to_string(),
+        ));
+    }
+
+    let schema_version = implementation::query_schema_version(&args.implementation)?;
+    if schema_version < config.schema_version {
+        return Err(ContractError::InvalidArgument(format!(
+            "Schema downgrade from {} to {}",
+            config.schema_version, schema_version
+        )));
+    }
+
+    let previous = core::mem::replace(&mut config.implementation, args.implementation.clone());
+    config.version = config
+        .version
+        .checked_add(1)
+        .ok_or(ContractError::Overflow)?;
+    config.schema_version = schema_version;
+    save_config(&config)?;
+
+    event!("BeaconUpgraded",
+        previous: previous,
+        implementation: args.implementation,
+        version: config.version,
+        schema_version: schema_version
+    );
+    admin_log::record("upgrade", "implementation", previous, &args.implementation)?;
+    Ok(config.version)
+}
+
+fn execute_set_registrar() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: SetRegistrarArgs = read_args()?;
+    validation::validate_address(&args.registrar)?;
+
+    let mut map = registrars();
+    let was_registrar = map.get(&args.registrar)?.unwrap_or(false);
+    if args.enabled {
+        map.set(&args.registrar, &true)?;
+    } else {
+        map.remove(&args.registrar)?;
+    }
+
+    event!("RegistrarUpdated", registrar: args.registrar, enabled: args.enabled);
+    let role = |enabled: bool| if enabled { REGISTRAR_ROLE } else { "" };
+    let action = if args.enabled {
+        admin_log::GRANT_ROLE
+    } else {
+        admin_log::REVOKE_ROLE
+    };
+    admin_log::record(
+        action,
+        &args.registrar,
+        role(was_registrar),
+        role(args.enabled),
+    )?;
+    Ok(())
+}
+
+fn execute_register_instance() -> ContractResult<()> {
+    let config = load_config()?;
+    let ctx = context();
+    let sender = ctx.sender();
+    if sender != config.owner && !registrars().get(&sender.to_string())?.unwrap_or(false) {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let args: InstanceArgs = read_args()?;
+    validation::validate_address(&args.instance)?;
+    let mut map = instances();
+    if map.contains_key(&args.instance)? {
+        return Err(ContractError::InvalidArgument(
+            "Instance already registered".to_string(),
+        ));
+    }
+    map.set(
+        &args.instance,
+        &InstanceRecord {
+            registered_at: ctx.block_timestamp(),
+            frozen: None,
+        },
+    )?;
+
+    event!("InstanceRegistered", instance: args.instance, registrar: sender);
+    Ok(())
+}
+
+fn execute_freeze() -> ContractResult<()> {
+    let config = load_config()?;
+    let (instance, mut record) = load_caller_instance()?;
+    if record.frozen.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Instance already frozen".to_string(),
+        ));
+    }
+
+    record.frozen = Some(FrozenPin {
+        implementation: config.implementation.clone(),
+        version: config.version,
+        frozen_at: context().block_timestamp(),
+    });
+    instances().set(&instance, &record)?;
+
+    event!("InstanceFrozen",
+        instance: instance,
+        implementation: config.implementation,
+        version: config.version
+    );
+    Ok(())
+}
+
+fn execute_unfreeze() -> ContractResult<()> {
+    let config = load_config()?;
+    let (instance, mut record) = load_caller_instance()?;
+    if record.frozen.take().is_none() {
+        return Err(ContractError::InvalidArgument(
+            "Instance is not frozen".to_string(),
+        ));
+    }
+    instances().set(&instance, &record)?;
+
+    event!("InstanceUnfrozen",
+        instance: instance,
+        implementation: config.implementation,
+        version: config.version
+    );
+    Ok(())
+}
+
+fn execute_transfer_ownership() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: TransferOwnershipArgs = read_args()?;
+    validation::validate_address(&args.new_owner)?;
+
+    let previous = core::mem::replace(&mut config.owner, args.new_owner.clone());
+    save_config(&config)?;
+
+    event!("OwnershipTransferred", previous: previous, owner: args.new_owner);
+    admin_log::record(
+        admin_log::TRANSFER_OWNERSHIP,
+        admin_log::OWNER_TARGET,
+        previous,
+        &args.new_owner,
+    )?;
+    Ok(())
+}
+
+fn execute_implementation_for() -> ContractResult<()> {
+    let config = load_config()?;
+    let args: InstanceArgs = read_args()?;
+    let record = instances()
+        .get(&args.instance)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown instance".to_string()))?;
+    let target = match record.frozen {
+        Some(pin) => pin.implementation,
+        None => config.implementation,
+    };
+    try_respond(&target)
+}
+
+fn execute_is_instance() -> ContractResult<()> {
+    let args: InstanceArgs = read_args()?;
+    try_respond(&instances().contains_key(&args.instance)?)
+}
+
+fn execute_get_instance() -> ContractResult<()> {
+    let args: InstanceArgs = read_args()?;
+    try_respond(&instances().get(&args.instance)?)
+}
+
+/// Initialize the beacon; the caller becomes owner
+///
+/// # Arguments
+/// * `implementation` - First implementation; must answer `schema_version`
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Point every unfrozen instance at a new implementation (owner only)
+///
+/// # Arguments
+/// * `implementation` - New implementation; its schema may not be older
+///
+/// # Returns
+/// The new beacon version
+#[unsafe(no_mangle)]
+pub extern "C" fn upgrade() {
+    match execute_upgrade() {
+        Ok(version) => {
+            let _ = try_respond(&version);
+        }
+        Err(err) => log!("upgrade failed: {}", err),
+    }
+}
+
+/// Allow or revoke an account (e.g. a factory) to register instances (owner only)
+///
+/// # Arguments
+/// * `registrar` - Account address
+/// * `enabled` - Whether it may register instances
+#[unsafe(no_mangle)]
+pub extern "C" fn set_registrar() {
+    if let Err(err) = execute_set_registrar() {
+        log!("set_registrar failed: {}", err);
+    }
+}
+
+/// Add a proxy instance to the family (owner or registrar)
+///
+/// # Arguments
+/// * `instance` - Instance contract address
+#[unsafe(no_mangle)]
+pub extern "C" fn register_instance() {
+    if let Err(err) = execute_register_instance() {
+        log!("register_instance failed: {}", err);
+    }
+}
+
+/// Pin the calling instance to the current implementation
+#[unsafe(no_mangle)]
+pub extern "C" fn freeze() {
+    if let Err(err) = execute_freeze() {
+        log!("freeze failed: {}", err);
+    }
+}
+
+/// Make the calling instance follow the beacon again
+#[unsafe(no_mangle)]
+pub extern "C" fn unfreeze() {
+    if let Err(err) = execute_unfreeze() {
+        log!("unfreeze failed: {}", err);
+    }
+}
+
+/// Hand the beacon to a new owner (owner only)
+///
+/// # Arguments
+/// * `new_owner` - New owner address
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_ownership() {
+    if let Err(err) = execute_transfer_ownership() {
+        log!("transfer_ownership failed: {}", err);
+    }
+}
+
+/// Get the implementation an instance should call
+///
+/// # Arguments
+/// * `instance` - Registered instance address
+///
+/// # Returns
+/// The pinned implementation if frozen, otherwise the current one
+#[unsafe(no_mangle)]
+pub extern "C" fn implementation_for() {
+    if let Err(err) = execute_implementation_for() {
+        log!("implementation_for failed: {}", err);
+    }
+}
+
+/// Check whether an address is a registered instance
+///
+/// # Arguments
+/// * `instance` - Address to check
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn is_instance() {
+    if let Err(err) = execute_is_instance() {
+        log!("is_instance failed: {}", err);
+    }
+}
+
+/// Get an instance record
+///
+/// # Arguments
+/// * `instance` - Instance address
+///
+/// # Returns
+/// `Option<InstanceRecord>`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_instance() {
+    if let Err(err) = execute_get_instance() {
+        log!("get_instance failed: {}", err);
+    }
+}
+
+/// Get the beacon configuration
+///
+/// # Returns
+/// `BeaconConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_beacon() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_beacon failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_USER: &str = "0x0000000000000000000000000000000000000b02";
+    const FACTORY: &str = "0x0000000000000000000000000000000000000d01";
+    const IMPL_V1: &str = "0x0000000000000000000000000000000000000c01";
+    const IMPL_V2: &str = "0x0000000000000000000000000000000000000c02";
+    const INSTANCE_A: &str = "0x0000000000000000000000000000000000000e01";
+    const INSTANCE_B: &str = "0x0000000000000000000000000000000000000e02";
+    const BEACON: &str = "0x0000000000000000000000000000000000000f00";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn register_schema(address: &str, version: u32) {
+        mock::register_contract(address, "schema_version", move |_| Ok(encode(&version)));
+    }
+
+    fn call_as(sender: &str, entrypoint: extern "C" fn(), args: &[u8]) {
+        mock::set_sender(sender);
+        mock::set_call_data(args);
+        entrypoint();
+    }
+
+    fn register(sender: &str, instance: &str) {
+        let args = encode(&InstanceArgs {
+            instance: instance.to_string(),
+        });
+        call_as(sender, register_instance, &args);
+    }
+
+    fn target_of(instance: &str) -> Option<String> {
+        let args = encode(&InstanceArgs {
+            instance: instance.to_string(),
+        });
+        call_as(ADDR_USER, implementation_for, &args);
+        let data = mock::take_return_data();
+        (!data.is_empty()).then(|| postcard::from_bytes(&data).expect("implementation"))
+    }
+
+    fn upgrade_to(sender: &str, implementation: &str) {
+        let args = encode(&ImplementationArgs {
+            implementation: implementation.to_string(),
+        });
+        call_as(sender, upgrade, &args);
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(BEACON);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        register_schema(IMPL_V1, 1);
+        register_schema(IMPL_V2, 1);
+        let args = encode(&ImplementationArgs {
+            implementation: IMPL_V1.to_string(),
+        });
+        call_as(ADDR_OWNER, initialize, &args);
+        register(ADDR_OWNER, INSTANCE_A);
+        register(ADDR_OWNER, INSTANCE_B);
+    }
+
+    #[test]
+    fn upgrade_moves_every_instance() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(target_of(INSTANCE_A).as_deref(), Some(IMPL_V1));
+
+        upgrade_to(ADDR_OWNER, IMPL_V2);
+        let version: u32 = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(target_of(INSTANCE_A).as_deref(), Some(IMPL_V2));
+        assert_eq!(target_of(INSTANCE_B).as_deref(), Some(IMPL_V2));
+        assert!(target_of(ADDR_USER).is_none());
+    }
+
+    #[test]
+    fn frozen_instances_keep_their_pin() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        call_as(INSTANCE_A, freeze, &[]);
+        upgrade_to(ADDR_OWNER, IMPL_V2);
+        assert_eq!(target_of(INSTANCE_A).as_deref(), Some(IMPL_V1));
+        assert_eq!(target_of(INSTANCE_B).as_deref(), Some(IMPL_V2));
+
+        call_as(INSTANCE_A, unfreeze, &[]);
+        assert_eq!(target_of(INSTANCE_A).as_deref(), Some(IMPL_V2));
+
+        // Only registered instances can freeze, and only themselves.
+        call_as(ADDR_USER, freeze, &[]);
+        assert!(!mock::take_logs().is_empty());
+        assert!(instances().get(&ADDR_USER.to_string()).unwrap().is_none());
+    }
+
+    #[test]
+    fn upgrades_and_registration_are_gated() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        upgrade_to(ADDR_USER, IMPL_V2);
+        assert_eq!(load_config().unwrap().implementation, IMPL_V1);
+
+        register_schema(IMPL_V2, 0);
+        upgrade_to(ADDR_OWNER, IMPL_V2);
+        assert_eq!(
+            load_config().unwrap().version,
+            1,
+            "schema downgrade refused"
+        );
+
+        const INSTANCE_C: &str = "0x0000000000000000000000000000000000000e03";
+        register(FACTORY, INSTANCE_C);
+        assert!(target_of(INSTANCE_C).is_none());
+
+        let args = encode(&SetRegistrarArgs {
+            registrar: FACTORY.to_string(),
+            enabled: true,
+        });
+        call_as(ADDR_OWNER, set_registrar, &args);
+        register(FACTORY, INSTANCE_C);
+        assert_eq!(target_of(INSTANCE_C).as_deref(), Some(IMPL_V1));
+        // Only the registrar grant reached the audit log.
+        assert_eq!(admin_log::next_sequence().unwrap(), 1);
+    }
+
+    #[test]
+    fn client_forwards_through_beacon() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        upgrade_to(ADDR_OWNER, IMPL_V2);
+
+        // Beacon entrypoints run against the beacon's own storage.
+        fn as_beacon(entrypoint: extern "C" fn(), args: &[u8]) -> ContractResult<Vec<u8>> {
+            mock::set_contract_address(BEACON);
+            mock::set_call_data(args);
+            entrypoint();
+            mock::set_contract_address(INSTANCE_A);
+            Ok(mock::take_return_data())
+        }
+        mock::register_contract(BEACON, "implementation_for", |args| {
+            as_beacon(implementation_for, args)
+        });
+        mock::register_contract(BEACON, "is_instance", |args| as_beacon(is_instance, args));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let captured = seen.clone();
+        mock::register_contract(IMPL_V2, "mint", move |args| {
+            *captured.lock().unwrap() = args.to_vec();
+            Ok(encode(&1u64))
+        });
+
+        mock::set_contract_address(INSTANCE_A);
+        mock::set_sender(ADDR_USER);
+        let response = client::forward(BEACON, "mint", &encode(&5u64)).unwrap();
+        assert_eq!(response, encode(&1u64));
+
+        // Seen from the implementation: the instance is the caller.
+        let forwarded = seen.lock().unwrap().clone();
+        mock::set_contract_address(IMPL_V2);
+        mock::set_sender(INSTANCE_A);
+        mock::set_call_data(&forwarded);
+        assert_eq!(client::require_instance(BEACON).unwrap(), INSTANCE_A);
+        // The mock shares one call-data slot with the nested beacon query.
+        mock::set_call_data(&forwarded);
+        assert_eq!(client::forwarded_sender().unwrap(), ADDR_USER);
+
+        mock::set_contract_address(IMPL_V2);
+        mock::set_sender(ADDR_USER);
+        assert!(matches!(
+            client::require_instance(BEACON),
+            Err(ContractError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "beacon-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Upgrade Beacon
+//!
+//! Holds the current implementation for a family of proxy instances, such as
+//! every collection deployed by a factory. Instances ask the beacon where to
+//! forward each call, so one `upgrade` on the beacon moves the whole family.
+//! An instance can `freeze` itself to pin the implementation it runs today
+//! and opt out of later upgrades.
+//!
+//! The [`client`] module is what instances and shared implementations embed.
+//! Implementations follow the conventions in `proxy_implementation`.
+//! Because every instance calls the same implementation, they also partition
+//! their storage by the calling instance.
+//!
+//! ## Embedding
+//! ```toml
+//! beacon-contract = { path = "../beacon", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use beacon_contract::client;
+//!
+//! let response = client::forward(&config.beacon, "mint", &args)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod client;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
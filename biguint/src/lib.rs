@@ -0,0 +1,118 @@
+//! 256-bit unsigned integers
+//!
+//! [`U256`] covers the values that do not fit in `u64`: 18-decimal token
+//! supplies, AMM reserve products and price ratios. Arithmetic comes in the
+//! same flavours as the primitive integers (`checked_*`, `overflowing_*`,
+//! `saturating_*`, and operators that panic on overflow), plus
+//! [`U256::mul_div`] with a 512-bit intermediate and [`U256::isqrt`].
+//!
+//! [`safe_math`] mirrors the SDK's `safe_math` module, returning
+//! `ContractError::Overflow`/`Underflow` instead of `None`.
+//!
+//! Values serialize as four little-endian `u64` limbs, so postcard stores
+//! small numbers compactly.
+//!
+//! ## Embedding
+//! ```toml
+//! biguint = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use biguint::{U256, safe_math};
+//!
+//! let k = safe_math::mul(U256::from(reserve_a), U256::from(reserve_b))?;
+//! let out = U256::from(amount_in).mul_div(reserve_out.into(), reserve_in.into());
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+mod u256;
+
+pub use u256::{OutOfRange, ParseU256Error, U256};
+
+/// `U256` arithmetic that fails with a `ContractError`.
+pub mod safe_math {
+    use super::U256;
+    use silica_contract_sdk::error::{ContractError, ContractResult};
+    pub use silica_contract_sdk::security::safe_math::{BPS_DENOMINATOR, Rounding};
+
+    pub fn add(a: U256, b: U256) -> ContractResult<U256> {
+        a.checked_add(b).ok_or(ContractError::Overflow)
+    }
+
+    pub fn sub(a: U256, b: U256) -> ContractResult<U256> {
+        a.checked_sub(b).ok_or(ContractError::Underflow)
+    }
+
+    pub fn mul(a: U256, b: U256) -> ContractResult<U256> {
+        a.checked_mul(b).ok_or(ContractError::Overflow)
+    }
+
+    pub fn div(a: U256, b: U256) -> ContractResult<U256> {
+        a.checked_div(b)
+            .ok_or_else(|| ContractError::InvalidArgument("Division by zero".into()))
+    }
+
+    pub fn pow(base: U256, exp: u32) -> ContractResult<U256> {
+        base.checked_pow(exp).ok_or(ContractError::Overflow)
+    }
+
+    /// `a * b / denominator` with a 512-bit intermediate and the given
+    /// rounding.
+    pub fn mul_div(
+        a: U256,
+        b: U256,
+        denominator: U256,
+        rounding: Rounding,
+    ) -> ContractResult<U256> {
+        if denominator.is_zero() {
+            return Err(ContractError::InvalidArgument("Division by zero".into()));
+        }
+        let quotient = a.mul_div(b, denominator).ok_or(ContractError::Overflow)?;
+        // a * b - quotient * denominator is the remainder, which is below
+        // `denominator`, so computing it modulo 2^256 is exact.
+        let remainder = a
+            .overflowing_mul(b)
+            .0
+            .overflowing_sub(quotient.overflowing_mul(denominator).0)
+            .0;
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => !remainder.is_zero(),
+            Rounding::HalfUp => remainder >= denominator - remainder,
+        };
+        if round_up {
+            add(quotient, U256::ONE)
+        } else {
+            Ok(quotient)
+        }
+    }
+
+    /// `bps` basis points of `amount`. Round fees charged to a user up and
+    /// amounts paid out down.
+    pub fn apply_bps(amount: U256, bps: u64, rounding: Rounding) -> ContractResult<U256> {
+        if bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidArgument(
+                "Basis points above 100%".into(),
+            ));
+        }
+        mul_div(
+            amount,
+            U256::from(bps),
+            U256::from(BPS_DENOMINATOR),
+            rounding,
+        )
+    }
+
+    /// Narrow to `u64`, failing with `Overflow` if it does not fit.
+    pub fn to_u64(value: U256) -> ContractResult<u64> {
+        u64::try_from(value).map_err(|_| ContractError::Overflow)
+    }
+
+    /// Narrow to `u128`, failing with `Overflow` if it does not fit.
+    pub fn to_u128(value: U256) -> ContractResult<u128> {
+        u128::try_from(value).map_err(|_| ContractError::Overflow)
+    }
+}
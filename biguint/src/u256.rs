@@ -0,0 +1,649 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{
+    Add, AddAssign, BitAnd, BitOr, BitXor, Div, DivAssign, Mul, MulAssign, Not, Rem, Shl, Shr, Sub,
+    SubAssign,
+};
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// 256-bit unsigned integer, stored as little-endian `u64` limbs
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct U256([u64; 4]);
+
+/// A `U256` did not fit the requested primitive type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("U256 value out of range")
+    }
+}
+
+/// A decimal string was not a valid `U256`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseU256Error {
+    Empty,
+    InvalidDigit,
+    Overflow,
+}
+
+impl fmt::Display for ParseU256Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty U256 string"),
+            Self::InvalidDigit => f.write_str("invalid digit in U256 string"),
+            Self::Overflow => f.write_str("U256 string out of range"),
+        }
+    }
+}
+
+impl U256 {
+    pub const ZERO: Self = Self([0; 4]);
+    pub const ONE: Self = Self([1, 0, 0, 0]);
+    pub const MAX: Self = Self([u64::MAX; 4]);
+    pub const BITS: u32 = 256;
+
+    /// Build from little-endian limbs.
+    pub const fn from_limbs(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    pub const fn limbs(&self) -> [u64; 4] {
+        self.0
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.0[0] == 0 && self.0[1] == 0 && self.0[2] == 0 && self.0[3] == 0
+    }
+
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 32 - 8 * (i + 1);
+            let mut chunk = [0u8; 8];
+            chunk.copy_from_slice(&bytes[start..start + 8]);
+            *limb = u64::from_be_bytes(chunk);
+        }
+        Self(limbs)
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, limb) in self.0.iter().enumerate() {
+            let start = 32 - 8 * (i + 1);
+            bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Parse a decimal string; no sign, separators or whitespace.
+    pub fn from_dec_str(s: &str) -> Result<Self, ParseU256Error> {
+        if s.is_empty() {
+            return Err(ParseU256Error::Empty);
+        }
+        let ten = Self::from(10u64);
+        let mut value = Self::ZERO;
+        for byte in s.bytes() {
+            if !byte.is_ascii_digit() {
+                return Err(ParseU256Error::InvalidDigit);
+            }
+            value = value
+                .checked_mul(ten)
+                .and_then(|v| v.checked_add(Self::from(byte - b'0')))
+                .ok_or(ParseU256Error::Overflow)?;
+        }
+        Ok(value)
+    }
+
+    pub fn leading_zeros(&self) -> u32 {
+        for i in (0..4).rev() {
+            if self.0[i] != 0 {
+                return (3 - i as u32) * 64 + self.0[i].leading_zeros();
+            }
+        }
+        256
+    }
+
+    /// Number of bits needed to represent the value.
+    pub fn bits(&self) -> u32 {
+        256 - self.leading_zeros()
+    }
+
+    pub fn bit(&self, index: u32) -> bool {
+        index < 256 && (self.0[(index / 64) as usize] >> (index % 64)) & 1 == 1
+    }
+
+    fn fits_u128(&self) -> bool {
+        self.0[2] == 0 && self.0[3] == 0
+    }
+
+    fn low_u128(&self) -> u128 {
+        (self.0[1] as u128) << 64 | self.0[0] as u128
+    }
+
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let mut out = [0u64; 4];
+        let mut carry = false;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        (Self(out), carry)
+    }
+
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let mut out = [0u64; 4];
+        let mut borrow = false;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (diff, b1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+        (Self(out), borrow)
+    }
+
+    /// Full 512-bit product as little-endian limbs.
+    fn widening_mul(self, rhs: Self) -> [u64; 8] {
+        let mut out = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let cur = out[i + j] as u128 + self.0[i] as u128 * rhs.0[j] as u128 + carry;
+                out[i + j] = cur as u64;
+                carry = cur >> 64;
+            }
+            out[i + 4] = carry as u64;
+        }
+        out
+    }
+
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let wide = self.widening_mul(rhs);
+        let low = Self([wide[0], wide[1], wide[2], wide[3]]);
+        (low, wide[4..].iter().any(|&limb| limb != 0))
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (value, false) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (value, false) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_mul(rhs) {
+            (value, false) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        (!rhs.is_zero()).then(|| self.div_rem(rhs).0)
+    }
+
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        (!rhs.is_zero()).then(|| self.div_rem(rhs).1)
+    }
+
+    pub fn checked_pow(self, mut exp: u32) -> Option<Self> {
+        let mut base = self;
+        let mut result = Self::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Some(result)
+    }
+
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Self::MAX)
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).unwrap_or(Self::ZERO)
+    }
+
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).unwrap_or(Self::MAX)
+    }
+
+    /// Quotient and remainder.
+    ///
+    /// # Panics
+    /// If `divisor` is zero.
+    pub fn div_rem(self, divisor: Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+        if self < divisor {
+            return (Self::ZERO, self);
+        }
+        if self.fits_u128() {
+            let (a, b) = (self.low_u128(), divisor.low_u128());
+            return (Self::from(a / b), Self::from(a % b));
+        }
+
+        let shift = divisor.leading_zeros() - self.leading_zeros();
+        let mut divisor = divisor << shift;
+        let mut remainder = self;
+        let mut quotient = Self::ZERO;
+        for bit in (0..=shift).rev() {
+            if remainder >= divisor {
+                remainder = remainder.overflowing_sub(divisor).0;
+                quotient.0[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+            divisor = divisor >> 1;
+        }
+        (quotient, remainder)
+    }
+
+    /// `self * rhs / denominator` rounded down, without overflowing in the
+    /// product. `None` if `denominator` is zero or the result exceeds 256 bits.
+    pub fn mul_div(self, rhs: Self, denominator: Self) -> Option<Self> {
+        if denominator.is_zero() {
+            return None;
+        }
+        let product = self.widening_mul(rhs);
+        if product[4..].iter().all(|&limb| limb == 0) {
+            let low = Self([product[0], product[1], product[2], product[3]]);
+            return Some(low.div_rem(denominator).0);
+        }
+
+        // Schoolbook binary division of the 512-bit product.
+        let mut quotient = [0u64; 8];
+        let mut remainder = Self::ZERO;
+        for bit in (0..512u32).rev() {
+            let carried = remainder.bit(255);
+            remainder = remainder << 1;
+            remainder.0[0] |= (product[(bit / 64) as usize] >> (bit % 64)) & 1;
+            if carried || remainder >= denominator {
+                remainder = remainder.overflowing_sub(denominator).0;
+                quotient[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+        }
+        if quotient[4..].iter().any(|&limb| limb != 0) {
+            return None;
+        }
+        Some(Self([quotient[0], quotient[1], quotient[2], quotient[3]]))
+    }
+
+    /// Largest `r` with `r * r <= self`.
+    pub fn isqrt(self) -> Self {
+        if self.is_zero() {
+            return Self::ZERO;
+        }
+        // Newton's method from an initial guess above the root.
+        let mut x = Self::ONE << self.bits().div_ceil(2);
+        loop {
+            let y = (x + self / x) >> 1;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for i in (0..4).rev() {
+            match self.0[i].cmp(&other.0[i]) {
+                Ordering::Equal => continue,
+                unequal => return unequal,
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! impl_from_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for U256 {
+                fn from(value: $ty) -> Self {
+                    Self([value as u64, 0, 0, 0])
+                }
+            }
+        )*
+    };
+}
+
+impl_from_primitive!(u8, u16, u32, u64);
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        Self([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+impl TryFrom<U256> for u64 {
+    type Error = OutOfRange;
+
+    fn try_from(value: U256) -> Result<Self, Self::Error> {
+        if value.0[1..].iter().any(|&limb| limb != 0) {
+            return Err(OutOfRange);
+        }
+        Ok(value.0[0])
+    }
+}
+
+impl TryFrom<U256> for u128 {
+    type Error = OutOfRange;
+
+    fn try_from(value: U256) -> Result<Self, Self::Error> {
+        if !value.fits_u128() {
+            return Err(OutOfRange);
+        }
+        Ok(value.low_u128())
+    }
+}
+
+impl FromStr for U256 {
+    type Err = ParseU256Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_dec_str(s)
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.fits_u128() {
+            return fmt::Display::fmt(&self.low_u128(), f);
+        }
+        // Peel off 19 decimal digits at a time, least significant first.
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut chunks = alloc::vec::Vec::new();
+        let mut rest = *self;
+        while !rest.is_zero() {
+            let (q, r) = rest.div_rem(U256::from(CHUNK));
+            chunks.push(r.0[0]);
+            rest = q;
+        }
+        let mut digits = alloc::string::String::new();
+        for (i, chunk) in chunks.iter().rev().enumerate() {
+            if i == 0 {
+                digits.push_str(&alloc::format!("{}", chunk));
+            } else {
+                digits.push_str(&alloc::format!("{:019}", chunk));
+            }
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl fmt::Debug for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+macro_rules! impl_checked_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $checked:ident, $msg:literal) => {
+        impl $trait for U256 {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                self.$checked(rhs).expect($msg)
+            }
+        }
+
+        impl $assign_trait for U256 {
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $trait::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_checked_op!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    checked_add,
+    "attempt to add with overflow"
+);
+impl_checked_op!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    checked_sub,
+    "attempt to subtract with overflow"
+);
+impl_checked_op!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    checked_mul,
+    "attempt to multiply with overflow"
+);
+impl_checked_op!(
+    Div,
+    div,
+    DivAssign,
+    div_assign,
+    checked_div,
+    "attempt to divide by zero"
+);
+
+impl Rem for U256 {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        self.checked_rem(rhs)
+            .expect("attempt to calculate the remainder with a divisor of zero")
+    }
+}
+
+impl Shl<u32> for U256 {
+    type Output = Self;
+
+    fn shl(self, shift: u32) -> Self {
+        if shift >= 256 {
+            return Self::ZERO;
+        }
+        let (limbs, bits) = ((shift / 64) as usize, shift % 64);
+        let mut out = [0u64; 4];
+        for (i, limb) in out.iter_mut().enumerate().skip(limbs) {
+            *limb = self.0[i - limbs] << bits;
+            if bits > 0 && i > limbs {
+                *limb |= self.0[i - limbs - 1] >> (64 - bits);
+            }
+        }
+        Self(out)
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = Self;
+
+    fn shr(self, shift: u32) -> Self {
+        if shift >= 256 {
+            return Self::ZERO;
+        }
+        let (limbs, bits) = ((shift / 64) as usize, shift % 64);
+        let mut out = [0u64; 4];
+        for (i, limb) in out.iter_mut().take(4 - limbs).enumerate() {
+            *limb = self.0[i + limbs] >> bits;
+            if bits > 0 && i + limbs + 1 < 4 {
+                *limb |= self.0[i + limbs + 1] << (64 - bits);
+            }
+        }
+        Self(out)
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait for U256 {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                let mut out = self.0;
+                for (limb, other) in out.iter_mut().zip(rhs.0) {
+                    *limb $op other;
+                }
+                Self(out)
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, &=);
+impl_bit_op!(BitOr, bitor, |=);
+impl_bit_op!(BitXor, bitxor, ^=);
+
+impl Not for U256 {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(self.0.map(|limb| !limb))
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn u(s: &str) -> U256 {
+        s.parse().unwrap()
+    }
+
+    const SAMPLES: [u128; 8] = [
+        0,
+        1,
+        9,
+        u64::MAX as u128,
+        u64::MAX as u128 + 1,
+        0x1234_5678_9abc_def0_0fed_cba9_8765_4321,
+        u128::MAX / 3,
+        u128::MAX,
+    ];
+
+    #[test]
+    fn matches_u128_arithmetic() {
+        for &a in &SAMPLES {
+            for &b in &SAMPLES {
+                let (x, y) = (U256::from(a), U256::from(b));
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                if let Some(sum) = a.checked_add(b) {
+                    assert_eq!(x + y, U256::from(sum));
+                }
+                assert_eq!(
+                    x.checked_sub(y).map(u128::try_from),
+                    a.checked_sub(b).map(Ok)
+                );
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(x * y, U256::from(product));
+                }
+                if let (Some(q), Some(r)) = (a.checked_div(b), a.checked_rem(b)) {
+                    assert_eq!(x.div_rem(y), (U256::from(q), U256::from(r)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wide_values_round_trip_and_divide() {
+        let max = U256::MAX;
+        assert_eq!(
+            max.to_string(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert_eq!(u(&max.to_string()), max);
+        assert_eq!(U256::from_be_bytes(max.to_be_bytes()), max);
+
+        // 10^18 * 10^18 * 10^18 = 10^54, well past u128.
+        let wad = U256::from(1_000_000_000_000_000_000u64);
+        let big = wad * wad * wad;
+        assert_eq!(big.to_string(), alloc::format!("1{}", "0".repeat(54)));
+        assert_eq!(big / wad, wad * wad);
+        assert_eq!((big + U256::from(7u64)) % wad, U256::from(7u64));
+        assert_eq!((big << 3) >> 3, big);
+        assert_eq!(U256::ONE << 255 >> 255, U256::ONE);
+        assert_eq!(U256::ONE << 256, U256::ZERO);
+
+        assert_eq!(u64::try_from(U256::from(u64::MAX)), Ok(u64::MAX));
+        assert_eq!(
+            u64::try_from(U256::from(u64::MAX as u128 + 1)),
+            Err(OutOfRange)
+        );
+    }
+
+    #[test]
+    fn overflow_is_detected() {
+        assert_eq!(U256::MAX.overflowing_add(U256::ONE), (U256::ZERO, true));
+        assert_eq!(U256::ZERO.overflowing_sub(U256::ONE), (U256::MAX, true));
+        assert_eq!(U256::MAX.checked_mul(U256::from(2u64)), None);
+        assert_eq!(U256::from(2u64).checked_pow(255), Some(U256::ONE << 255));
+        assert_eq!(U256::from(2u64).checked_pow(256), None);
+        assert_eq!(U256::MAX.saturating_add(U256::ONE), U256::MAX);
+        assert_eq!(U256::ONE.checked_div(U256::ZERO), None);
+        assert_eq!(U256::from_dec_str(""), Err(ParseU256Error::Empty));
+        assert_eq!(U256::from_dec_str("12a"), Err(ParseU256Error::InvalidDigit));
+        assert_eq!(
+            U256::from_dec_str(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+            ),
+            Err(ParseU256Error::Overflow)
+        );
+    }
+
+    #[test]
+    fn mul_div_keeps_the_512_bit_product() {
+        let max = U256::MAX;
+        assert_eq!(max.mul_div(max, max), Some(max));
+        assert_eq!(
+            max.mul_div(U256::from(3u64), U256::from(4u64)),
+            Some(max / U256::from(4u64) * U256::from(3u64) + U256::from(2u64))
+        );
+        assert_eq!(max.mul_div(U256::from(2u64), U256::ONE), None);
+        assert_eq!(max.mul_div(U256::ONE, U256::ZERO), None);
+        assert_eq!(
+            U256::from(10u64).mul_div(U256::from(10u64), U256::from(3u64)),
+            Some(U256::from(33u64))
+        );
+    }
+
+    #[test]
+    fn isqrt_and_postcard_encoding() {
+        for n in [0u128, 1, 2, 3, 4, 15, 16, 17, u64::MAX as u128, u128::MAX] {
+            let root = U256::from(n).isqrt();
+            assert!(root * root <= U256::from(n));
+            let next = root + U256::ONE;
+            assert!(next * next > U256::from(n));
+        }
+        assert_eq!(U256::MAX.isqrt(), U256::from(u128::MAX));
+
+        let small = postcard::to_allocvec(&U256::from(5u64)).unwrap();
+        assert_eq!(small, [5, 0, 0, 0]);
+        let big = U256::MAX - U256::from(12_345u64);
+        let bytes = postcard::to_allocvec(&big).unwrap();
+        assert_eq!(postcard::from_bytes::<U256>(&bytes).unwrap(), big);
+    }
+}
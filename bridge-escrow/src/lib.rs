@@ -0,0 +1,1203 @@
+//! Bridge Escrow
+//!
+//! Lock-and-mint escrow for moving CRC-20 tokens and native coin off Chert.
+//! Deposits are locked here and announced through structured `Deposit` events
+//! that relayers pick up; funds are released back on Chert once a quorum of
+//! relayers signs the corresponding withdrawal message.
+//!
+//! ## Features
+//! - Native coin and allowlisted CRC-20 deposits with destination chain/address
+//! - Sequential deposit nonces and queryable deposit records
+//! - Ed25519 relayer set with configurable signature threshold
+//! - Per-(source chain, nonce) replay protection on releases
+//! - Locked-balance accounting per asset
+//! - Per-asset release limits that trip a circuit breaker until the owner
+//!   resets it
+//! - Decommissioning: after the owner's notice runs out, deposits stop while
+//!   releases of locked funds continue
+//! - Owner pause for deposits; releases stay open
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::circuit_breaker::{self, Trip};
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::nonces::NonceBitmap;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "config";
+const RELAYERS_PREFIX: &str = "relayers";
+const TOKENS_PREFIX: &str = "tokens";
+const LOCKED_PREFIX: &str = "locked";
+const DEPOSITS_PREFIX: &str = "deposits";
+const PROCESSED_PREFIX: &str = "processed";
+const WITHDRAWAL_DOMAIN: &str = "chert-bridge-withdrawal-v1";
+/// Asset identifier used for native coin deposits and releases
+pub const NATIVE_ASSET: &str = "native";
+const MAX_RELAYERS: u32 = 32;
+const RELAYER_ROLE: &str = "relayer";
+const MAX_CHAIN_ID_LEN: usize = 32;
+const MAX_DESTINATION_LEN: usize = 128;
+const SIGNATURE_LEN: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Bridge configuration stored at initialization
+#[derive(Serialize, Deserialize)]
+pub struct BridgeConfig {
+    pub owner: String,
+    pub threshold: u32,
+    pub relayer_count: u32,
+    pub next_deposit_nonce: u64,
+}
+
+/// Locked deposit awaiting mint on the destination chain
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DepositRecord {
+    pub nonce: u64,
+    pub token: String,
+    pub sender: String,
+    pub amount: u64,
+    pub destination_chain: String,
+    pub destination_address: String,
+    pub block_height: u64,
+}
+
+/// Release instruction signed by the relayer quorum
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WithdrawalMessage {
+    pub source_chain: String,
+    pub nonce: u64,
+    pub token: String,
+    pub recipient: String,
+    pub amount: u64,
+}
+
+/// Relayer attestation over a withdrawal message
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RelayerSignature {
+    pub public_key: [u8; 32],
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    threshold: u32,
+    relayers: Vec<[u8; 32]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RelayerArgs {
+    public_key: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetThresholdArgs {
+    threshold: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenArgs {
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepositArgs {
+    token: String,
+    amount: u64,
+    destination_chain: String,
+    destination_address: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReleaseArgs {
+    withdrawal: WithdrawalMessage,
+    signatures: Vec<RelayerSignature>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetDepositArgs {
+    nonce: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IsProcessedArgs {
+    source_chain: String,
+    nonce: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutflowLimitArgs {
+    token: String,
+    max_outflow: u64,
+    window: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+/// Build the canonical message relayers sign to release a withdrawal.
+///
+/// The layout is the postcard encoding of `(domain, bridge, withdrawal)` so a
+/// signature is only valid for this escrow deployment.
+pub fn withdrawal_message(bridge: &str, withdrawal: &WithdrawalMessage) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(&(WITHDRAWAL_DOMAIN, bridge, withdrawal))
+        .map_err(|_| ContractError::SerializationFailed)
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<BridgeConfig> {
+    storage()
+        .get::<BridgeConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Bridge not initialized".to_string()))
+}
+
+fn save_config(config: &BridgeConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<BridgeConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn relayers() -> Map<[u8; 32], bool> {
+    Map::new(RELAYERS_PREFIX)
+}
+
+fn supported_tokens() -> Map<String, bool> {
+    Map::new(TOKENS_PREFIX)
+}
+
+fn locked_balances() -> Map<String, u64> {
+    Map::new(LOCKED_PREFIX)
+}
+
+fn deposits() -> Map<u64, DepositRecord> {
+    Map::new(DEPOSITS_PREFIX)
+}
+
+/// Processed withdrawal nonces, one bitmap per source chain
+fn processed() -> NonceBitmap {
+    NonceBitmap::new(PROCESSED_PREFIX)
+}
+
+fn is_supported(token: &str) -> ContractResult<bool> {
+    if token == NATIVE_ASSET {
+        return Ok(true);
+    }
+    Ok(supported_tokens().get(&token.to_string())?.unwrap_or(false))
+}
+
+fn read_locked(token: &str) -> ContractResult<u64> {
+    Ok(locked_balances().get(&token.to_string())?.unwrap_or(0))
+}
+
+fn write_locked(token: &str, amount: u64) -> ContractResult<()> {
+    locked_balances().set(&token.to_string(), &amount)
+}
+
+fn validate_bounded(value: &str, field_name: &str, max_len: usize) -> ContractResult<()> {
+    validation::validate_non_empty(value, field_name)?;
+    if value.len() > max_len {
+        return Err(ContractError::InvalidArgument(format!(
+            "{} too long",
+            field_name
+        )));
+    }
+    Ok(())
+}
+
+fn encode_call<T: Serialize>(args: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(args).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn add_relayer_internal(config: &mut BridgeConfig, public_key: [u8; 32]) -> ContractResult<()> {
+    let mut relayers = relayers();
+    if relayers.get(&public_key)?.unwrap_or(false) {
+        return Err(ContractError::InvalidArgument(
+            "Relayer already registered".to_string(),
+        ));
+    }
+    if config.relayer_count >= MAX_RELAYERS {
+        return Err(ContractError::InvalidArgument(
+            "Relayer set is full".to_string(),
+        ));
+    }
+    relayers.set(&public_key, &true)?;
+    config.relayer_count += 1;
+    Ok(())
+}
+
+/// Count distinct registered relayers with a valid signature over `message`.
+fn count_valid_signatures(message: &[u8], signatures: &[RelayerSignature]) -> ContractResult<u32> {
+    let relayers = relayers();
+    let mut seen: Vec<[u8; 32]> = Vec::with_capacity(signatures.len());
+    for entry in signatures {
+        if seen.contains(&entry.public_key) {
+            continue;
+        }
+        if !relayers.get(&entry.public_key)?.unwrap_or(false) {
+            continue;
+        }
+        let signature: [u8; SIGNATURE_LEN] = match entry.signature.as_slice().try_into() {
+            Ok(signature) => signature,
+            Err(_) => continue,
+        };
+        if crypto::verify_signature(&entry.public_key, message, &signature).unwrap_or(false) {
+            seen.push(entry.public_key);
+        }
+    }
+    Ok(seen.len() as u32)
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let args: InitializeArgs = read_args()?;
+
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Bridge already initialized".to_string(),
+        ));
+    }
+
+    let ctx = context();
+    let owner = ctx.sender();
+    validation::validate_address(owner)?;
+
+    let mut config = BridgeConfig {
+        owner: owner.to_string(),
+        threshold: args.threshold,
+        relayer_count: 0,
+        next_deposit_nonce: 1,
+    };
+    for public_key in args.relayers {
+        add_relayer_internal(&mut config, public_key)?;
+    }
+    validation::validate_range(args.threshold as u64, 1, config.relayer_count as u64)?;
+    save_config(&config)?;
+
+    event!("BridgeInitialized",
+        owner: owner,
+        threshold: config.threshold,
+        relayers: config.relayer_count
+    );
+    Ok(())
+}
+
+fn execute_add_relayer() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: RelayerArgs = read_args()?;
+
+    add_relayer_internal(&mut config, args.public_key)?;
+    save_config(&config)?;
+
+    let public_key = hex::encode(args.public_key);
+    event!("RelayerAdded", public_key: public_key);
+    admin_log::record(admin_log::GRANT_ROLE, &public_key, "", RELAYER_ROLE)?;
+    Ok(())
+}
+
+fn execute_remove_relayer() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: RelayerArgs = read_args()?;
+
+    let mut relayers = relayers();
+    if !relayers.get(&args.public_key)?.unwrap_or(false) {
+        return Err(ContractError::InvalidArgument(
+            "Relayer not registered".to_string(),
+        ));
+    }
+    if config.relayer_count <= config.threshold {
+        return Err(ContractError::InvalidArgument(
+            "Removal would make threshold unreachable".to_string(),
+        ));
+    }
+
+    relayers.remove(&args.public_key)?;
+    config.relayer_count -= 1;
+    save_config(&config)?;
+
+    let public_key = hex::encode(args.public_key);
+    event!("RelayerRemoved", public_key: public_key);
+    admin_log::record(admin_log::REVOKE_ROLE, &public_key, RELAYER_ROLE, "")?;
+    Ok(())
+}
+
+fn execute_set_threshold() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: SetThresholdArgs = read_args()?;
+    validation::validate_range(args.threshold as u64, 1, config.relayer_count as u64)?;
+
+    let previous = config.threshold;
+    config.threshold = args.threshold;
+    save_config(&config)?;
+
+    event!("ThresholdUpdated", previous: previous, current: args.threshold);
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "threshold",
+        previous,
+        args.threshold,
+    )?;
+    Ok(())
+}
+
+fn execute_add_token() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::when_active()?;
+    let args: TokenArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+
+    let mut tokens = supported_tokens();
+    let was_supported = tokens.get(&args.token)?.unwrap_or(false);
+    tokens.set(&args.token, &true)?;
+    event!("TokenSupported", token: args.token);
+    admin_log::record("add_token", &args.token, was_supported, true)?;
+    Ok(())
+}
+
+fn execute_deposit() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    circuit_breaker::when_not_tripped()?;
+    let mut config = load_config()?;
+    decommission::when_active()?;
+    pausable::when_not_paused()?;
+    let ctx = context();
+    let sender = ctx.sender().to_string();
+    let args: DepositArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    validate_bounded(
+        &args.destination_chain,
+        "destination_chain",
+        MAX_CHAIN_ID_LEN,
+    )?;
+    validate_bounded(
+        &args.destination_address,
+        "destination_address",
+        MAX_DESTINATION_LEN,
+    )?;
+
+    if !is_supported(&args.token)? {
+        return Err(ContractError::InvalidArgument(
+            "Token not supported".to_string(),
+        ));
+    }
+
+    if args.token == NATIVE_ASSET {
+        if ctx.value() != args.amount {
+            return Err(ContractError::InvalidArgument(
+                "Attached value must equal deposit amount".to_string(),
+            ));
+        }
+    } else {
+        if ctx.value() != 0 {
+            return Err(ContractError::InvalidArgument(
+                "Token deposits must not attach native value".to_string(),
+            ));
+        }
+        let call = encode_call(&TokenTransferFromArgs {
+            from: sender.clone(),
+            to: ctx.contract_address().to_string(),
+            amount: args.amount,
+        })?;
+        ctx.call_contract(&args.token, "transfer_from", &call)?;
+    }
+
+    let locked = safe_math::add(read_locked(&args.token)?, args.amount)?;
+    write_locked(&args.token, locked)?;
+
+    let nonce = config.next_deposit_nonce;
+    config.next_deposit_nonce = safe_math::add(nonce, 1)?;
+    save_config(&config)?;
+
+    let record = DepositRecord {
+        nonce,
+        token: args.token,
+        sender,
+        amount: args.amount,
+        destination_chain: args.destination_chain,
+        destination_address: args.destination_address,
+        block_height: ctx.block_height(),
+    };
+    deposits().set(&nonce, &record)?;
+
+    event!("Deposit",
+        nonce: record.nonce,
+        token: record.token,
+        sender: record.sender,
+        amount: record.amount,
+        destination_chain: record.destination_chain,
+        destination_address: record.destination_address
+    );
+    Ok(nonce)
+}
+
+fn execute_release() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let ctx = context();
+    let args: ReleaseArgs = read_args()?;
+    let withdrawal = args.withdrawal;
+    validation::validate_address(&withdrawal.recipient)?;
+    validation::validate_positive_amount(withdrawal.amount)?;
+    validate_bounded(&withdrawal.source_chain, "source_chain", MAX_CHAIN_ID_LEN)?;
+
+    let mut processed = processed();
+    if processed.is_used(&withdrawal.source_chain, withdrawal.nonce)? {
+        return Err(ContractError::InvalidArgument(
+            "Withdrawal already processed".to_string(),
+        ));
+    }
+
+    let message = withdrawal_message(ctx.contract_address(), &withdrawal)?;
+    let approvals = count_valid_signatures(&message, &args.signatures)?;
+    if approvals < config.threshold {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    let locked = read_locked(&withdrawal.token)?;
+    if locked < withdrawal.amount {
+        return Err(ContractError::InsufficientBalance {
+            required: withdrawal.amount,
+            available: locked,
+        });
+    }
+    circuit_breaker::record_outflow(&withdrawal.token, withdrawal.amount)?;
+
+    // Effects before interactions: the nonce is burned and the escrow debited
+    // before any value leaves the contract.
+    processed.consume(&withdrawal.source_chain, withdrawal.nonce)?;
+    write_locked(&withdrawal.token, locked - withdrawal.amount)?;
+
+    if withdrawal.token == NATIVE_ASSET {
+        ctx.transfer_tokens(&withdrawal.recipient, withdrawal.amount)?;
+    } else {
+        let call = encode_call(&TokenTransferArgs {
+            to: withdrawal.recipient.clone(),
+            amount: withdrawal.amount,
+        })?;
+        ctx.call_contract(&withdrawal.token, "transfer", &call)?;
+    }
+
+    event!("Withdrawal",
+        source_chain: withdrawal.source_chain,
+        nonce: withdrawal.nonce,
+        token: withdrawal.token,
+        recipient: withdrawal.recipient,
+        amount: withdrawal.amount,
+        approvals: approvals
+    );
+    Ok(())
+}
+
+fn execute_set_outflow_limit() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::when_active()?;
+    let args: OutflowLimitArgs = read_args()?;
+    if args.max_outflow == 0 {
+        return circuit_breaker::remove_limit(&args.token);
+    }
+    circuit_breaker::set_limit(
+        &args.token,
+        args.max_outflow,
+        Duration::from_secs(args.window),
+    )
+}
+
+fn execute_reset_circuit_breaker() -> ContractResult<()> {
+    load_owned_config()?;
+    circuit_breaker::reset()
+}
+
+fn execute_pause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::unpause()
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_owned_config()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_circuit_breaker_status() -> ContractResult<Option<Trip>> {
+    load_config()?;
+    let trip = circuit_breaker::tripped()?;
+    try_respond(&trip)?;
+    Ok(trip)
+}
+
+fn execute_get_deposit() -> ContractResult<DepositRecord> {
+    load_config()?;
+    let args: GetDepositArgs = read_args()?;
+    let record = deposits()
+        .get(&args.nonce)?
+        .ok_or_else(|| ContractError::InvalidArgument("Deposit not found".to_string()))?;
+    try_respond(&record)?;
+    Ok(record)
+}
+
+fn execute_is_processed() -> ContractResult<bool> {
+    load_config()?;
+    let args: IsProcessedArgs = read_args()?;
+    let done = processed().is_used(&args.source_chain, args.nonce)?;
+    try_respond(&done)?;
+    Ok(done)
+}
+
+fn execute_locked_balance() -> ContractResult<u64> {
+    load_config()?;
+    let args: TokenArgs = read_args()?;
+    let locked = read_locked(&args.token)?;
+    try_respond(&locked)?;
+    Ok(locked)
+}
+
+/// Initialize the escrow
+///
+/// # Arguments
+/// * `threshold` - Relayer signatures required to release a withdrawal
+/// * `relayers` - Initial relayer Ed25519 public keys
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Register a relayer key (owner only)
+///
+/// # Arguments
+/// * `public_key` - Relayer Ed25519 public key
+#[unsafe(no_mangle)]
+pub extern "C" fn add_relayer() {
+    if let Err(err) = execute_add_relayer() {
+        log!("add_relayer failed: {}", err);
+    }
+}
+
+/// Deregister a relayer key (owner only)
+///
+/// # Arguments
+/// * `public_key` - Relayer Ed25519 public key
+#[unsafe(no_mangle)]
+pub extern "C" fn remove_relayer() {
+    if let Err(err) = execute_remove_relayer() {
+        log!("remove_relayer failed: {}", err);
+    }
+}
+
+/// Update the release signature threshold (owner only)
+///
+/// # Arguments
+/// * `threshold` - New threshold, bounded by the relayer count
+#[unsafe(no_mangle)]
+pub extern "C" fn set_threshold() {
+    if let Err(err) = execute_set_threshold() {
+        log!("set_threshold failed: {}", err);
+    }
+}
+
+/// Allow deposits of a CRC-20 token (owner only)
+///
+/// # Arguments
+/// * `token` - CRC-20 contract address
+#[unsafe(no_mangle)]
+pub extern "C" fn add_token() {
+    if let Err(err) = execute_add_token() {
+        log!("add_token failed: {}", err);
+    }
+}
+
+/// Lock native coin or CRC-20 tokens for minting on another chain
+///
+/// CRC-20 deposits require a prior `approve` of this contract for `amount`.
+///
+/// # Arguments
+/// * `token` - CRC-20 address or [`NATIVE_ASSET`] (amount must equal attached value)
+/// * `amount` - Amount to lock
+/// * `destination_chain` - Destination chain identifier
+/// * `destination_address` - Recipient on the destination chain
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    if let Err(err) = execute_deposit() {
+        log!("Deposit failed: {}", err);
+    }
+}
+
+/// Release locked funds for a withdrawal attested by the relayer quorum
+///
+/// # Arguments
+/// * `withdrawal` - [`WithdrawalMessage`] being released
+/// * `signatures` - Relayer signatures over [`withdrawal_message`]
+#[unsafe(no_mangle)]
+pub extern "C" fn release() {
+    if let Err(err) = execute_release() {
+        log!("Release failed: {}", err);
+    }
+}
+
+/// Limit how much of an asset releases may pay out per window (owner only)
+///
+/// A release that would exceed the limit trips the circuit breaker, which
+/// halts deposits and releases of every asset until `reset_circuit_breaker`.
+///
+/// # Arguments
+/// * `token` - CRC-20 address or [`NATIVE_ASSET`]
+/// * `max_outflow` - Amount allowed per window; 0 removes the limit
+/// * `window` - Window length in seconds
+#[unsafe(no_mangle)]
+pub extern "C" fn set_outflow_limit() {
+    if let Err(err) = execute_set_outflow_limit() {
+        log!("set_outflow_limit failed: {}", err);
+    }
+}
+
+/// Clear a tripped circuit breaker (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn reset_circuit_breaker() {
+    if let Err(err) = execute_reset_circuit_breaker() {
+        log!("reset_circuit_breaker failed: {}", err);
+    }
+}
+
+/// Pause the escrow (owner only)
+///
+/// Deposits fail until `unpause`; relayers keep releasing locked funds.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the escrow is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Give notice that the escrow will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the escrow once the notice has run out (owner only)
+///
+/// Deposits, new tokens and changes to the relayer set, threshold and
+/// outflow limits stop for good. Relayers keep releasing locked funds, and
+/// the owner can still reset a tripped circuit breaker so they can.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the escrow is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Query the circuit breaker
+///
+/// # Returns
+/// `Option<Trip>` - The release that tripped it, if tripped
+#[unsafe(no_mangle)]
+pub extern "C" fn circuit_breaker_status() {
+    if let Err(err) = execute_circuit_breaker_status() {
+        log!("circuit_breaker_status failed: {}", err);
+    }
+}
+
+/// Query a deposit record by nonce
+#[unsafe(no_mangle)]
+pub extern "C" fn get_deposit() {
+    if let Err(err) = execute_get_deposit() {
+        log!("get_deposit failed: {}", err);
+    }
+}
+
+/// Check whether a withdrawal nonce from a source chain was already released
+#[unsafe(no_mangle)]
+pub extern "C" fn is_processed() {
+    if let Err(err) = execute_is_processed() {
+        log!("is_processed failed: {}", err);
+    }
+}
+
+/// Query the amount currently locked for an asset
+#[unsafe(no_mangle)]
+pub extern "C" fn locked_balance() {
+    if let Err(err) = execute_locked_balance() {
+        log!("locked_balance failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use ed25519_dalek::{Signer, SigningKey};
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_USER: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_RECIPIENT: &str = "0x0000000000000000000000000000000000000c03";
+    const TOKEN: &str = "0x00000000000000000000000000000000000000f1";
+    const BRIDGE_ADDR: &str = "bridge_contract";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn signers() -> Vec<SigningKey> {
+        crypto::generate_keypairs(3)
+            .expect("keypairs")
+            .iter()
+            .map(|(_, secret)| SigningKey::from_bytes(secret))
+            .collect()
+    }
+
+    fn init_default() {
+        mock::reset();
+        mock::set_sender(ADDR_OWNER);
+        mock::set_contract_address(BRIDGE_ADDR);
+        mock::set_block_height(5);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+
+        mock::set_call_data(&encode(&InitializeArgs {
+            threshold: 2,
+            relayers: signers()
+                .iter()
+                .map(|key| key.verifying_key().to_bytes())
+                .collect(),
+        }));
+        initialize();
+        mock::set_call_data(&encode(&TokenArgs {
+            token: TOKEN.to_string(),
+        }));
+        add_token();
+        mock::take_events();
+    }
+
+    fn deposit_as_user(token: &str, amount: u64, value: u64) {
+        mock::set_sender(ADDR_USER);
+        mock::set_value(value);
+        mock::set_call_data(&encode(&DepositArgs {
+            token: token.to_string(),
+            amount,
+            destination_chain: "ethereum".to_string(),
+            destination_address: "0xabc0000000000000000000000000000000000def".to_string(),
+        }));
+        deposit();
+        mock::set_value(0);
+    }
+
+    fn withdrawal(token: &str, nonce: u64, amount: u64) -> WithdrawalMessage {
+        WithdrawalMessage {
+            source_chain: "ethereum".to_string(),
+            nonce,
+            token: token.to_string(),
+            recipient: ADDR_RECIPIENT.to_string(),
+            amount,
+        }
+    }
+
+    fn sign_with(indices: &[usize], message: &WithdrawalMessage) -> Vec<RelayerSignature> {
+        let keys = signers();
+        let payload = withdrawal_message(BRIDGE_ADDR, message).unwrap();
+        indices
+            .iter()
+            .map(|&index| RelayerSignature {
+                public_key: keys[index].verifying_key().to_bytes(),
+                signature: keys[index].sign(&payload).to_bytes().to_vec(),
+            })
+            .collect()
+    }
+
+    fn release_with(message: WithdrawalMessage, signatures: Vec<RelayerSignature>) {
+        mock::set_call_data(&encode(&ReleaseArgs {
+            withdrawal: message,
+            signatures,
+        }));
+        release();
+    }
+
+    #[test]
+    fn token_deposit_pulls_funds_and_records_nonce() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+
+        deposit_as_user(TOKEN, 500, 0);
+        deposit_as_user(TOKEN, 250, 0);
+
+        assert_eq!(read_locked(TOKEN).unwrap(), 750);
+        let record = deposits().get(&2).unwrap().expect("second deposit");
+        assert_eq!(record.amount, 250);
+        assert_eq!(record.sender, ADDR_USER);
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 2);
+        let pulled: TokenTransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(pulled.from, ADDR_USER);
+        assert_eq!(pulled.to, BRIDGE_ADDR);
+        assert_eq!(pulled.amount, 500);
+
+        let events = mock::take_events();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.topic == "Deposit")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn native_deposit_requires_matching_value() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+
+        deposit_as_user(NATIVE_ASSET, 100, 99);
+        assert_eq!(read_locked(NATIVE_ASSET).unwrap(), 0);
+
+        deposit_as_user(NATIVE_ASSET, 100, 100);
+        assert_eq!(read_locked(NATIVE_ASSET).unwrap(), 100);
+
+        deposit_as_user("0x00000000000000000000000000000000000000f9", 10, 0);
+        assert!(
+            deposits().get(&2).unwrap().is_none(),
+            "unknown token rejected"
+        );
+    }
+
+    #[test]
+    fn quorum_release_pays_out_once() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        deposit_as_user(TOKEN, 1_000, 0);
+        mock::take_contract_calls();
+
+        let message = withdrawal(TOKEN, 7, 400);
+        release_with(message.clone(), sign_with(&[0, 2], &message));
+
+        assert_eq!(read_locked(TOKEN).unwrap(), 600);
+        assert!(processed().is_used("ethereum", 7).unwrap());
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].method, "transfer");
+
+        release_with(message.clone(), sign_with(&[0, 1, 2], &message));
+        assert_eq!(read_locked(TOKEN).unwrap(), 600, "replay must be rejected");
+        assert!(mock::take_contract_calls().is_empty());
+    }
+
+    #[test]
+    fn release_rejects_insufficient_or_duplicate_signatures() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        deposit_as_user(TOKEN, 1_000, 0);
+
+        let message = withdrawal(TOKEN, 1, 100);
+        release_with(message.clone(), sign_with(&[1, 1, 1], &message));
+        assert_eq!(read_locked(TOKEN).unwrap(), 1_000);
+
+        // Signatures over a different amount do not count toward the quorum.
+        let tampered = withdrawal(TOKEN, 1, 900);
+        release_with(tampered, sign_with(&[0, 1], &message));
+        assert_eq!(read_locked(TOKEN).unwrap(), 1_000);
+
+        // Quorum cannot release more than is locked.
+        let excessive = withdrawal(TOKEN, 2, 5_000);
+        release_with(excessive.clone(), sign_with(&[0, 1], &excessive));
+        assert_eq!(read_locked(TOKEN).unwrap(), 1_000);
+        assert!(!processed().is_used("ethereum", 2).unwrap());
+    }
+
+    #[test]
+    fn relayer_management_preserves_threshold() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        let keys = signers();
+
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&RelayerArgs {
+            public_key: keys[0].verifying_key().to_bytes(),
+        }));
+        remove_relayer();
+        assert_eq!(load_config().unwrap().relayer_count, 2);
+
+        mock::set_call_data(&encode(&RelayerArgs {
+            public_key: keys[1].verifying_key().to_bytes(),
+        }));
+        remove_relayer();
+        assert_eq!(load_config().unwrap().relayer_count, 2);
+
+        mock::set_call_data(&encode(&SetThresholdArgs { threshold: 3 }));
+        set_threshold();
+        assert_eq!(load_config().unwrap().threshold, 2);
+
+        // Only the successful removal reaches the audit log, after the
+        // token added during setup.
+        let admin_actions = mock::take_events()
+            .into_iter()
+            .filter(|event| event.topic == admin_log::TOPIC)
+            .count();
+        assert_eq!(admin_actions, 1);
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+    }
+
+    #[test]
+    fn excess_releases_trip_the_circuit_breaker() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        deposit_as_user(TOKEN, 1_000, 0);
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&OutflowLimitArgs {
+            token: TOKEN.to_string(),
+            max_outflow: 500,
+            window: 86_400,
+        }));
+        set_outflow_limit();
+
+        let first = withdrawal(TOKEN, 1, 400);
+        release_with(first.clone(), sign_with(&[0, 1], &first));
+        let second = withdrawal(TOKEN, 2, 200);
+        release_with(second.clone(), sign_with(&[0, 1], &second));
+        assert_eq!(read_locked(TOKEN).unwrap(), 600);
+        assert!(!processed().is_used("ethereum", 2).unwrap());
+        let trip = circuit_breaker::tripped().unwrap().expect("tripped");
+        assert_eq!((trip.asset.as_str(), trip.amount), (TOKEN, 200));
+
+        // Everything halts until the owner resets the breaker.
+        let small = withdrawal(TOKEN, 3, 1);
+        release_with(small.clone(), sign_with(&[0, 1], &small));
+        deposit_as_user(TOKEN, 100, 0);
+        assert_eq!(read_locked(TOKEN).unwrap(), 600);
+        reset_circuit_breaker();
+        assert!(circuit_breaker::tripped().unwrap().is_some());
+
+        mock::set_sender(ADDR_OWNER);
+        reset_circuit_breaker();
+        circuit_breaker_status();
+        let status: Option<Trip> = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(status, None);
+        release_with(second.clone(), sign_with(&[0, 1], &second));
+        assert_eq!(read_locked(TOKEN).unwrap(), 400);
+    }
+
+    #[test]
+    fn pausing_stops_deposits_but_not_releases() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        deposit_as_user(TOKEN, 1_000, 0);
+
+        pause();
+        assert!(!pausable::paused().unwrap());
+        mock::set_sender(ADDR_OWNER);
+        pause();
+        mock::set_call_data(&[]);
+        paused();
+        assert!(postcard::from_bytes::<bool>(&mock::take_return_data()).unwrap());
+
+        deposit_as_user(TOKEN, 500, 0);
+        assert_eq!(read_locked(TOKEN).unwrap(), 1_000);
+        let message = withdrawal(TOKEN, 1, 1_000);
+        release_with(message.clone(), sign_with(&[0, 1], &message));
+        assert_eq!(read_locked(TOKEN).unwrap(), 0);
+
+        mock::set_sender(ADDR_OWNER);
+        unpause();
+        deposit_as_user(TOKEN, 500, 0);
+        assert_eq!(read_locked(TOKEN).unwrap(), 500);
+    }
+
+    #[test]
+    fn decommissioning_stops_deposits_but_not_releases() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        deposit_as_user(TOKEN, 1_000, 0);
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        mock::set_call_data(&notice);
+        schedule_decommission();
+        assert_eq!(decommission::status().unwrap().eta, None);
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&notice);
+        schedule_decommission();
+        let eta: u64 = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        mock::set_block_timestamp(eta);
+        decommission();
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        deposit_as_user(TOKEN, 500, 0);
+        assert_eq!(read_locked(TOKEN).unwrap(), 1_000);
+        assert!(deposits().get(&2).unwrap().is_none());
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&SetThresholdArgs { threshold: 3 }));
+        set_threshold();
+        mock::set_call_data(&encode(&RelayerArgs {
+            public_key: [7; 32],
+        }));
+        add_relayer();
+        let config = load_config().unwrap();
+        assert_eq!((config.threshold, config.relayer_count), (2, 3));
+
+        let message = withdrawal(TOKEN, 1, 1_000);
+        release_with(message.clone(), sign_with(&[0, 1], &message));
+        assert_eq!(read_locked(TOKEN).unwrap(), 0);
+        assert!(
+            mock::take_events()
+                .iter()
+                .any(|e| e.topic == "Decommissioned")
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "bridge-escrow-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
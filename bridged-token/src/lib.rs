@@ -0,0 +1,832 @@
+//! Bridged CRC-20 Token
+//!
+//! A CRC-20 variant for assets whose canonical supply lives on another chain.
+//! Supply only enters through registered bridges, each capped by a daily mint
+//! limit, and only leaves through `bridge_burn`, which emits the cross-chain
+//! payload relayers use to release funds on the destination chain.
+//!
+//! ## Features
+//! - Standard transfer / approve / transfer_from surface
+//! - Owner-managed bridge role with per-bridge daily mint limits
+//! - `bridge_burn` exits carrying a sequenced `BridgeBurnPayload`
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::rate_limit::RollingWindow;
+use silica_contract_sdk::time::Duration;
+
+const METADATA_KEY: &str = "metadata";
+const BALANCES_PREFIX: &str = "balances";
+const ALLOWANCES_PREFIX: &str = "allowances";
+const BRIDGES_PREFIX: &str = "bridges";
+const BRIDGE_MINTED_PREFIX: &str = "bridge_minted";
+const ZERO_ADDRESS: &str = "0x0";
+const MINT_WINDOW: Duration = Duration::from_days(1);
+const MAX_DESTINATION_BYTES: usize = 128;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Token metadata stored once at initialization
+#[derive(Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: u64,
+    pub owner: String,
+    pub next_burn_nonce: u64,
+}
+
+/// Mint allowance of a registered bridge
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BridgeLimit {
+    pub daily_limit: u64,
+}
+
+/// Bridge limit as seen at the current block
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BridgeView {
+    pub bridge: String,
+    pub daily_limit: u64,
+    pub minted_in_window: u64,
+    pub remaining_in_window: u64,
+    pub window_resets_at: Option<u64>,
+}
+
+/// Cross-chain payload emitted under the `BridgeBurn` topic
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BridgeBurnPayload {
+    pub token: String,
+    pub nonce: u64,
+    pub from: String,
+    pub amount: u64,
+    pub destination: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    name: String,
+    symbol: String,
+    decimals: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BalanceOfArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetBridgeArgs {
+    bridge: String,
+    daily_limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BridgeArgs {
+    bridge: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BridgeMintArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BridgeBurnArgs {
+    amount: u64,
+    destination: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_metadata() -> ContractResult<TokenMetadata> {
+    storage()
+        .get::<TokenMetadata>(METADATA_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Token not initialized".to_string()))
+}
+
+fn save_metadata(metadata: &TokenMetadata) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(METADATA_KEY, metadata)
+}
+
+fn bridges() -> Map<String, BridgeLimit> {
+    Map::new(BRIDGES_PREFIX)
+}
+
+/// Amount minted by each bridge in its current 24-hour window
+fn minted(limit: &BridgeLimit) -> ContractResult<RollingWindow> {
+    RollingWindow::new(BRIDGE_MINTED_PREFIX, limit.daily_limit, MINT_WINDOW)
+}
+
+fn read_balance(address: &str) -> ContractResult<u64> {
+    let balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
+    Ok(balances.get(&address.to_string())?.unwrap_or(0))
+}
+
+fn write_balance(address: &str, amount: u64) -> ContractResult<()> {
+    let mut balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
+    balances.set(&address.to_string(), &amount)
+}
+
+fn read_allowance(owner: &str, spender: &str) -> ContractResult<u64> {
+    let allowances: Map<(String, String), u64> = Map::new(ALLOWANCES_PREFIX);
+    Ok(allowances
+        .get(&(owner.to_string(), spender.to_string()))?
+        .unwrap_or(0))
+}
+
+fn write_allowance(owner: &str, spender: &str, amount: u64) -> ContractResult<()> {
+    let mut allowances: Map<(String, String), u64> = Map::new(ALLOWANCES_PREFIX);
+    allowances.set(&(owner.to_string(), spender.to_string()), &amount)
+}
+
+fn require_owner(metadata: &TokenMetadata) -> ContractResult<()> {
+    if context().sender() != metadata.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn transfer_impl(from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    validation::validate_address(from)?;
+    validation::validate_address(to)?;
+    validation::validate_positive_amount(amount)?;
+
+    let from_balance = read_balance(from)?;
+    if from_balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: from_balance,
+        });
+    }
+
+    let new_from_balance = safe_math::sub(from_balance, amount)?;
+    write_balance(from, new_from_balance)?;
+    let new_to_balance = safe_math::add(read_balance(to)?, amount)?;
+    write_balance(to, new_to_balance)?;
+    Ok(())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let args: InitializeArgs = read_args()?;
+    validation::validate_non_empty(&args.name, "name")?;
+    validation::validate_non_empty(&args.symbol, "symbol")?;
+
+    if storage().has(METADATA_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Token already initialized".to_string(),
+        ));
+    }
+
+    let deployer = context().sender().to_string();
+    validation::validate_address(&deployer)?;
+
+    save_metadata(&TokenMetadata {
+        name: args.name.clone(),
+        symbol: args.symbol.clone(),
+        decimals: args.decimals,
+        total_supply: 0,
+        owner: deployer.clone(),
+        next_burn_nonce: 0,
+    })?;
+
+    event!("BridgedTokenInitialized",
+        name: args.name,
+        symbol: args.symbol,
+        owner: deployer
+    );
+    Ok(())
+}
+
+fn execute_transfer() -> ContractResult<()> {
+    load_metadata()?;
+    let sender = context().sender().to_string();
+    let args: TransferArgs = read_args()?;
+
+    transfer_impl(&sender, &args.to, args.amount)?;
+    event!("Transfer", from: sender, to: args.to, amount: args.amount);
+    Ok(())
+}
+
+fn execute_approve() -> ContractResult<()> {
+    load_metadata()?;
+    let owner = context().sender().to_string();
+    let args: ApproveArgs = read_args()?;
+    validation::validate_address(&args.spender)?;
+
+    write_allowance(&owner, &args.spender, args.amount)?;
+    event!("Approval", owner: owner, spender: args.spender, amount: args.amount);
+    Ok(())
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    load_metadata()?;
+    let spender = context().sender().to_string();
+    let args: TransferFromArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    let allowance = read_allowance(&args.from, &spender)?;
+    if allowance < args.amount {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: allowance,
+        });
+    }
+
+    transfer_impl(&args.from, &args.to, args.amount)?;
+    write_allowance(
+        &args.from,
+        &spender,
+        safe_math::sub(allowance, args.amount)?,
+    )?;
+
+    event!("Transfer", from: args.from, to: args.to, amount: args.amount);
+    Ok(())
+}
+
+fn execute_set_bridge() -> ContractResult<()> {
+    let metadata = load_metadata()?;
+    require_owner(&metadata)?;
+    let args: SetBridgeArgs = read_args()?;
+    validation::validate_address(&args.bridge)?;
+
+    let mut registry = bridges();
+    let previous = registry.get(&args.bridge)?;
+    registry.set(
+        &args.bridge,
+        &BridgeLimit {
+            daily_limit: args.daily_limit,
+        },
+    )?;
+
+    event!("BridgeConfigured", bridge: args.bridge, daily_limit: args.daily_limit);
+    admin_log::record(
+        "set_bridge",
+        &args.bridge,
+        previous
+            .map(|limit| limit.daily_limit.to_string())
+            .unwrap_or_default(),
+        args.daily_limit,
+    )?;
+    Ok(())
+}
+
+fn execute_remove_bridge() -> ContractResult<()> {
+    let metadata = load_metadata()?;
+    require_owner(&metadata)?;
+    let args: BridgeArgs = read_args()?;
+
+    let mut registry = bridges();
+    let previous = registry
+        .get(&args.bridge)?
+        .ok_or_else(|| ContractError::InvalidArgument("Bridge not registered".to_string()))?;
+    registry.remove(&args.bridge)?;
+
+    event!("BridgeRemoved", bridge: args.bridge);
+    admin_log::record("remove_bridge", &args.bridge, previous.daily_limit, "")?;
+    Ok(())
+}
+
+fn execute_bridge_mint() -> ContractResult<()> {
+    let mut metadata = load_metadata()?;
+    let bridge = context().sender().to_string();
+    let args: BridgeMintArgs = read_args()?;
+    validation::validate_address(&args.to)?;
+    validation::validate_positive_amount(args.amount)?;
+
+    let limit = bridges().get(&bridge)?.ok_or(ContractError::Unauthorized)?;
+    minted(&limit)?.check_and_consume(&bridge, args.amount)?;
+
+    metadata.total_supply = safe_math::add(metadata.total_supply, args.amount)?;
+    save_metadata(&metadata)?;
+    let balance = read_balance(&args.to)?;
+    let new_balance = safe_math::add(balance, args.amount)?;
+    write_balance(&args.to, new_balance)?;
+
+    event!("Transfer", from: ZERO_ADDRESS, to: args.to, amount: args.amount);
+    event!("BridgeMint", bridge: bridge, to: args.to, amount: args.amount);
+    admin_log::record(admin_log::MINT, &args.to, balance, new_balance)?;
+    Ok(())
+}
+
+fn execute_bridge_burn() -> ContractResult<u64> {
+    let mut metadata = load_metadata()?;
+    let ctx = context();
+    let from = ctx.sender().to_string();
+    let args: BridgeBurnArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    validation::validate_non_empty(&args.destination, "destination")?;
+    if args.destination.len() > MAX_DESTINATION_BYTES {
+        return Err(ContractError::InvalidArgument(
+            "Destination too long".to_string(),
+        ));
+    }
+
+    let balance = read_balance(&from)?;
+    if balance < args.amount {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: balance,
+        });
+    }
+    let new_balance = safe_math::sub(balance, args.amount)?;
+    write_balance(&from, new_balance)?;
+
+    let nonce = metadata.next_burn_nonce;
+    metadata.next_burn_nonce = safe_math::add(nonce, 1)?;
+    metadata.total_supply = safe_math::sub(metadata.total_supply, args.amount)?;
+    save_metadata(&metadata)?;
+
+    event!("Transfer", from: from, to: ZERO_ADDRESS, amount: args.amount);
+    admin_log::record(admin_log::BURN, &from, balance, new_balance)?;
+    emit(
+        "BridgeBurn",
+        &BridgeBurnPayload {
+            token: ctx.contract_address().to_string(),
+            nonce,
+            from,
+            amount: args.amount,
+            destination: args.destination,
+        },
+    );
+    try_respond(&nonce)?;
+    Ok(nonce)
+}
+
+fn execute_bridge_info() -> ContractResult<()> {
+    load_metadata()?;
+    let args: BridgeArgs = read_args()?;
+    let limit = bridges()
+        .get(&args.bridge)?
+        .ok_or_else(|| ContractError::InvalidArgument("Bridge not registered".to_string()))?;
+    let minted = minted(&limit)?;
+
+    try_respond(&BridgeView {
+        minted_in_window: minted.used(&args.bridge)?,
+        remaining_in_window: minted.remaining(&args.bridge)?,
+        window_resets_at: minted.resets_at(&args.bridge)?,
+        bridge: args.bridge,
+        daily_limit: limit.daily_limit,
+    })
+}
+
+fn execute_balance_of() -> ContractResult<u64> {
+    load_metadata()?;
+    let args: BalanceOfArgs = read_args()?;
+    let balance = read_balance(&args.account)?;
+    try_respond(&balance)?;
+    Ok(balance)
+}
+
+fn execute_total_supply() -> ContractResult<u64> {
+    let metadata = load_metadata()?;
+    try_respond(&metadata.total_supply)?;
+    Ok(metadata.total_supply)
+}
+
+fn execute_decimals() -> ContractResult<u8> {
+    let metadata = load_metadata()?;
+    try_respond(&metadata.decimals)?;
+    Ok(metadata.decimals)
+}
+
+fn execute_name() -> ContractResult<()> {
+    try_respond(&load_metadata()?.name)
+}
+
+fn execute_symbol() -> ContractResult<()> {
+    try_respond(&load_metadata()?.symbol)
+}
+
+/// Initialize the bridged token with zero supply
+///
+/// # Arguments
+/// * `name` - Token name (e.g., "Bridged Ether")
+/// * `symbol` - Token symbol (e.g., "bETH")
+/// * `decimals` - Number of decimal places
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Transfer tokens from sender to recipient
+///
+/// # Arguments
+/// * `to` - Recipient address
+/// * `amount` - Amount to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer() {
+    if let Err(err) = execute_transfer() {
+        log!("Transfer failed: {}", err);
+    }
+}
+
+/// Approve a spender to transfer tokens on behalf of the sender
+///
+/// # Arguments
+/// * `spender` - Address allowed to spend
+/// * `amount` - Maximum amount they can spend
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("Approve failed: {}", err);
+    }
+}
+
+/// Transfer tokens on behalf of another account (requires prior approval)
+///
+/// # Arguments
+/// * `from` - Account to transfer from
+/// * `to` - Recipient address
+/// * `amount` - Amount to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("TransferFrom failed: {}", err);
+    }
+}
+
+/// Register a bridge or update its daily mint limit (owner only)
+///
+/// # Arguments
+/// * `bridge` - Bridge contract address granted the mint role
+/// * `daily_limit` - Maximum amount the bridge may mint per 24-hour window
+#[unsafe(no_mangle)]
+pub extern "C" fn set_bridge() {
+    if let Err(err) = execute_set_bridge() {
+        log!("set_bridge failed: {}", err);
+    }
+}
+
+/// Revoke a bridge's mint role (owner only)
+///
+/// # Arguments
+/// * `bridge` - Bridge contract address
+#[unsafe(no_mangle)]
+pub extern "C" fn remove_bridge() {
+    if let Err(err) = execute_remove_bridge() {
+        log!("remove_bridge failed: {}", err);
+    }
+}
+
+/// Mint tokens for an inbound transfer (registered bridges only)
+///
+/// # Arguments
+/// * `to` - Recipient address
+/// * `amount` - Amount to mint; counts against the caller's daily limit
+#[unsafe(no_mangle)]
+pub extern "C" fn bridge_mint() {
+    if let Err(err) = execute_bridge_mint() {
+        log!("bridge_mint failed: {}", err);
+    }
+}
+
+/// Burn the caller's tokens to exit to another chain
+///
+/// # Arguments
+/// * `amount` - Amount to burn
+/// * `destination` - Recipient on the destination chain (e.g., "ethereum:0x...")
+///
+/// # Returns
+/// Burn nonce carried in the `BridgeBurn` payload
+#[unsafe(no_mangle)]
+pub extern "C" fn bridge_burn() {
+    if let Err(err) = execute_bridge_burn() {
+        log!("bridge_burn failed: {}", err);
+    }
+}
+
+/// Query a bridge's daily limit and usage
+///
+/// # Arguments
+/// * `bridge` - Bridge contract address
+///
+/// # Returns
+/// `BridgeView`
+#[unsafe(no_mangle)]
+pub extern "C" fn bridge_info() {
+    if let Err(err) = execute_bridge_info() {
+        log!("bridge_info failed: {}", err);
+    }
+}
+
+/// Query balance of an account
+///
+/// # Arguments
+/// * `account` - Address to query
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() -> u64 {
+    match execute_balance_of() {
+        Ok(value) => value,
+        Err(err) => {
+            log!("balance_of failed: {}", err);
+            0
+        }
+    }
+}
+
+/// Get total token supply
+#[unsafe(no_mangle)]
+pub extern "C" fn total_supply() -> u64 {
+    match execute_total_supply() {
+        Ok(value) => value,
+        Err(err) => {
+            log!("total_supply failed: {}", err);
+            0
+        }
+    }
+}
+
+/// Get token decimals
+#[unsafe(no_mangle)]
+pub extern "C" fn decimals() -> u8 {
+    match execute_decimals() {
+        Ok(value) => value,
+        Err(err) => {
+            log!("decimals failed: {}", err);
+            0
+        }
+    }
+}
+
+/// Get token name
+#[unsafe(no_mangle)]
+pub extern "C" fn name() {
+    if let Err(err) = execute_name() {
+        log!("name failed: {}", err);
+    }
+}
+
+/// Get token symbol
+#[unsafe(no_mangle)]
+pub extern "C" fn symbol() {
+    if let Err(err) = execute_symbol() {
+        log!("symbol failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_BRIDGE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000d04";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000e05";
+    const START: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender(ADDR_OWNER);
+        mock::set_contract_address(TOKEN);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::set_call_data(&encode(&InitializeArgs {
+            name: "Bridged Ether".to_string(),
+            symbol: "bETH".to_string(),
+            decimals: 18,
+        }));
+        initialize();
+        mock::set_call_data(&encode(&SetBridgeArgs {
+            bridge: ADDR_BRIDGE.to_string(),
+            daily_limit: 1_000,
+        }));
+        set_bridge();
+        mock::take_events();
+    }
+
+    fn mint_as(sender: &str, to: &str, amount: u64) {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(&BridgeMintArgs {
+            to: to.to_string(),
+            amount,
+        }));
+        bridge_mint();
+    }
+
+    #[test]
+    fn bridge_mint_credits_recipient_within_limit() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 600);
+        assert_eq!(read_balance(ADDR_ALICE).unwrap(), 600);
+        assert_eq!(load_metadata().unwrap().total_supply, 600);
+
+        mock::set_call_data(&encode(&BridgeArgs {
+            bridge: ADDR_BRIDGE.to_string(),
+        }));
+        bridge_info();
+        let view: BridgeView = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(view.minted_in_window, 600);
+        assert_eq!(view.remaining_in_window, 400);
+        assert_eq!(view.window_resets_at, Some(START + 86_400));
+    }
+
+    #[test]
+    fn only_registered_bridges_can_mint() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        mint_as(ADDR_OWNER, ADDR_ALICE, 10);
+        mint_as(ADDR_ALICE, ADDR_ALICE, 10);
+        assert_eq!(read_balance(ADDR_ALICE).unwrap(), 0);
+
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&BridgeArgs {
+            bridge: ADDR_BRIDGE.to_string(),
+        }));
+        remove_bridge();
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 10);
+        assert_eq!(load_metadata().unwrap().total_supply, 0);
+        // Setup registered the bridge; only its removal followed.
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+    }
+
+    #[test]
+    fn daily_limit_resets_a_day_after_first_mint() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 800);
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 300);
+        assert_eq!(read_balance(ADDR_ALICE).unwrap(), 800);
+
+        mock::set_block_timestamp(START + 86_399);
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 300);
+        assert_eq!(read_balance(ADDR_ALICE).unwrap(), 800);
+
+        mock::set_block_timestamp(START + 86_400);
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 300);
+        assert_eq!(read_balance(ADDR_ALICE).unwrap(), 1_100);
+    }
+
+    #[test]
+    fn bridge_burn_emits_sequenced_payload() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 500);
+        mock::take_events();
+
+        mock::set_sender(ADDR_ALICE);
+        for amount in [100, 50] {
+            mock::set_call_data(&encode(&BridgeBurnArgs {
+                amount,
+                destination: "ethereum:0xabc".to_string(),
+            }));
+            bridge_burn();
+        }
+
+        let payloads: Vec<BridgeBurnPayload> = mock::take_events()
+            .into_iter()
+            .filter(|event| event.topic == "BridgeBurn")
+            .map(|event| postcard::from_bytes(&event.data).unwrap())
+            .collect();
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0].nonce, 0);
+        assert_eq!(payloads[1].nonce, 1);
+        assert_eq!(payloads[0].token, TOKEN);
+        assert_eq!(payloads[0].from, ADDR_ALICE);
+        assert_eq!(payloads[1].amount, 50);
+        assert_eq!(read_balance(ADDR_ALICE).unwrap(), 350);
+        assert_eq!(load_metadata().unwrap().total_supply, 350);
+    }
+
+    #[test]
+    fn bridge_burn_rejects_overdraft() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 100);
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&encode(&BridgeBurnArgs {
+            amount: 101,
+            destination: "ethereum:0xabc".to_string(),
+        }));
+        bridge_burn();
+        assert_eq!(read_balance(ADDR_ALICE).unwrap(), 100);
+        assert_eq!(load_metadata().unwrap().next_burn_nonce, 0);
+    }
+
+    #[test]
+    fn transfers_and_allowances_follow_crc20() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mint_as(ADDR_BRIDGE, ADDR_ALICE, 300);
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&encode(&ApproveArgs {
+            spender: ADDR_BOB.to_string(),
+            amount: 120,
+        }));
+        approve();
+
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&TransferFromArgs {
+            from: ADDR_ALICE.to_string(),
+            to: ADDR_BOB.to_string(),
+            amount: 100,
+        }));
+        transfer_from();
+
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 100);
+        assert_eq!(read_allowance(ADDR_ALICE, ADDR_BOB).unwrap(), 20);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "bridged-token-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
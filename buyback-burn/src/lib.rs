@@ -0,0 +1,941 @@
+//! Buyback-and-Burn Executor
+//!
+//! Collects protocol revenue in one CRC-20 and periodically swaps it for the
+//! protocol token through the AMM router, then burns what it bought. Anyone
+//! can trigger a buyback once per interval and earns a small keeper reward
+//! out of the revenue being sold. Each swap is checked against the router's
+//! TWAP so a manipulated spot price cannot drain the revenue.
+//!
+//! ## Features
+//! - Public `execute_buyback` rate-limited to one run per interval
+//! - Spot/TWAP deviation check and TWAP-based minimum output
+//! - Per-run sell cap to limit price impact
+//! - Keeper reward in basis points of the revenue sold
+//! - Owner pause for deposits and buybacks
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+
+const CONFIG_KEY: &str = "buyback_config";
+const STATE_KEY: &str = "buyback_state";
+/// CRC-20 has no burn entrypoint; bought tokens go to this keyless address.
+pub const BURN_ADDRESS: &str = "0x000000000000000000000000000000000000dead";
+const MIN_INTERVAL: u64 = 3_600;
+const MAX_INTERVAL: u64 = 30 * 86_400;
+const MIN_TWAP_WINDOW: u64 = 60;
+const MAX_SLIPPAGE_BPS: u32 = 1_000;
+const MAX_TWAP_DEVIATION_BPS: u32 = 2_000;
+const MAX_KEEPER_REWARD_BPS: u32 = 100;
+const BPS_DENOMINATOR: u128 = safe_math::BPS_DENOMINATOR as u128;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Tunable buyback parameters
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackParams {
+    /// Minimum seconds between buybacks
+    pub interval: u64,
+    /// Most revenue sold per buyback
+    pub max_sell_per_run: u64,
+    /// Accepted output shortfall below the TWAP quote
+    pub max_slippage_bps: u32,
+    /// Accepted spot/TWAP divergence
+    pub max_twap_deviation_bps: u32,
+    /// Seconds of price history the TWAP covers
+    pub twap_window: u64,
+    /// Keeper's share of the revenue sold
+    pub keeper_reward_bps: u32,
+}
+
+/// Contract configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackConfig {
+    pub owner: String,
+    pub router: String,
+    pub revenue_token: String,
+    pub protocol_token: String,
+    pub params: BuybackParams,
+}
+
+/// Revenue accounting and lifetime totals
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuybackState {
+    /// Revenue waiting to be sold
+    pub pending_revenue: u64,
+    pub last_execution: u64,
+    pub total_sold: u64,
+    pub total_burned: u64,
+    pub total_keeper_rewards: u64,
+}
+
+/// Outcome of one buyback
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackReceipt {
+    pub sold: u64,
+    pub burned: u64,
+    pub keeper_reward: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    router: String,
+    revenue_token: String,
+    protocol_token: String,
+    params: BuybackParams,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RouterArgs {
+    router: String,
+}
+
+/// Arguments of the router's `get_amount_out`
+#[derive(Serialize, Deserialize)]
+struct QuoteArgs {
+    token_in: String,
+    token_out: String,
+    amount_in: u64,
+}
+
+/// Arguments of the router's `consult`
+#[derive(Serialize, Deserialize)]
+struct ConsultArgs {
+    token_in: String,
+    token_out: String,
+    amount_in: u64,
+    window: u64,
+}
+
+/// Arguments of the router's `swap_exact_in`
+#[derive(Serialize, Deserialize)]
+struct SwapArgs {
+    token_in: String,
+    token_out: String,
+    amount_in: u64,
+    min_amount_out: u64,
+    to: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<BuybackConfig> {
+    storage()
+        .get::<BuybackConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_config(config: &BuybackConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<BuybackConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn load_state() -> ContractResult<BuybackState> {
+    Ok(storage()
+        .get::<BuybackState>(STATE_KEY)?
+        .unwrap_or_default())
+}
+
+fn save_state(state: &BuybackState) -> ContractResult<()> {
+    storage().set(STATE_KEY, state)
+}
+
+fn validate_params(params: &BuybackParams) -> ContractResult<()> {
+    if params.interval < MIN_INTERVAL || params.interval > MAX_INTERVAL {
+        return Err(ContractError::InvalidArgument(
+            "Interval must be between 1 hour and 30 days".to_string(),
+        ));
+    }
+    validation::validate_positive_amount(params.max_sell_per_run)?;
+    if params.max_slippage_bps > MAX_SLIPPAGE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Slippage cannot exceed {} bps",
+            MAX_SLIPPAGE_BPS
+        )));
+    }
+    if params.max_twap_deviation_bps > MAX_TWAP_DEVIATION_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "TWAP deviation cannot exceed {} bps",
+            MAX_TWAP_DEVIATION_BPS
+        )));
+    }
+    if params.twap_window < MIN_TWAP_WINDOW {
+        return Err(ContractError::InvalidArgument(format!(
+            "TWAP window must be at least {} seconds",
+            MIN_TWAP_WINDOW
+        )));
+    }
+    if params.keeper_reward_bps > MAX_KEEPER_REWARD_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Keeper reward cannot exceed {} bps",
+            MAX_KEEPER_REWARD_BPS
+        )));
+    }
+    Ok(())
+}
+
+/// Absolute deviation of `price` from `reference` in basis points, saturating at `u64::MAX`.
+fn deviation_bps(reference: u64, price: u64) -> u64 {
+    if reference == 0 {
+        return if price == 0 { 0 } else { u64::MAX };
+    }
+    let delta = reference.abs_diff(price) as u128;
+    let bps = delta * BPS_DENOMINATOR / reference as u128;
+    u64::try_from(bps).unwrap_or(u64::MAX)
+}
+
+fn call_router<T: Serialize>(router: &str, method: &str, args: &T) -> ContractResult<u64> {
+    let response = context().call_contract(router, method, &encode_call(args)?)?;
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    for address in [&args.router, &args.revenue_token, &args.protocol_token] {
+        validation::validate_address(address)?;
+        if !ctx.is_contract(address) {
+            return Err(ContractError::InvalidArgument(
+                "Router and tokens must be contracts".to_string(),
+            ));
+        }
+    }
+    if args.revenue_token == args.protocol_token {
+        return Err(ContractError::InvalidArgument(
+            "Revenue token must differ from the protocol token".to_string(),
+        ));
+    }
+    validate_params(&args.params)?;
+
+    save_config(&BuybackConfig {
+        owner: ctx.sender().to_string(),
+        router: args.router.clone(),
+        revenue_token: args.revenue_token.clone(),
+        protocol_token: args.protocol_token.clone(),
+        params: args.params,
+    })?;
+
+    event!("BuybackInitialized",
+        owner: ctx.sender(),
+        router: args.router,
+        revenue_token: args.revenue_token,
+        protocol_token: args.protocol_token
+    );
+    Ok(())
+}
+
+fn execute_deposit_revenue() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    let ctx = context();
+    let config = load_config()?;
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    let mut state = load_state()?;
+    state.pending_revenue = safe_math::add(state.pending_revenue, args.amount)?;
+    save_state(&state)?;
+
+    let call = encode_call(&TokenTransferFromArgs {
+        from: ctx.sender().to_string(),
+        to: ctx.contract_address().to_string(),
+        amount: args.amount,
+    })?;
+    ctx.call_contract(&config.revenue_token, "transfer_from", &call)?;
+
+    event!("RevenueDeposited",
+        from: ctx.sender(),
+        amount: args.amount,
+        pending: state.pending_revenue
+    );
+    Ok(())
+}
+
+fn run_buyback() -> ContractResult<BuybackReceipt> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    let ctx = context();
+    let config = load_config()?;
+    let params = &config.params;
+    let mut state = load_state()?;
+    let now = ctx.block_timestamp();
+    if state.last_execution != 0 && now < safe_math::add(state.last_execution, params.interval)? {
+        return Err(ContractError::InvalidArgument(
+            "Buyback interval has not elapsed".to_string(),
+        ));
+    }
+    let amount = state.pending_revenue.min(params.max_sell_per_run);
+    if amount == 0 {
+        return Err(ContractError::InvalidArgument(
+            "No revenue to sell".to_string(),
+        ));
+    }
+    let keeper_reward =
+        safe_math::apply_bps(amount, params.keeper_reward_bps.into(), Rounding::Down)?;
+    let sold = amount - keeper_reward;
+
+    let spot = call_router(
+        &config.router,
+        "get_amount_out",
+        &QuoteArgs {
+            token_in: config.revenue_token.clone(),
+            token_out: config.protocol_token.clone(),
+            amount_in: sold,
+        },
+    )?;
+    let twap = call_router(
+        &config.router,
+        "consult",
+        &ConsultArgs {
+            token_in: config.revenue_token.clone(),
+            token_out: config.protocol_token.clone(),
+            amount_in: sold,
+            window: params.twap_window,
+        },
+    )?;
+    if twap == 0 || deviation_bps(twap, spot) > params.max_twap_deviation_bps as u64 {
+        return Err(ContractError::Custom(
+            "Spot price deviates from TWAP".to_string(),
+        ));
+    }
+    let min_amount_out =
+        twap - safe_math::apply_bps(twap, params.max_slippage_bps.into(), Rounding::Down)?;
+
+    state.pending_revenue -= amount;
+    state.last_execution = now;
+    save_state(&state)?;
+
+    let approve = encode_call(&TokenApproveArgs {
+        spender: config.router.clone(),
+        amount: sold,
+    })?;
+    ctx.call_contract(&config.revenue_token, "approve", &approve)?;
+    let bought = call_router(
+        &config.router,
+        "swap_exact_in",
+        &SwapArgs {
+            token_in: config.revenue_token.clone(),
+            token_out: config.protocol_token.clone(),
+            amount_in: sold,
+            min_amount_out,
+            to: ctx.contract_address().to_string(),
+        },
+    )?;
+    if bought < min_amount_out {
+        return Err(ContractError::Custom(
+            "Router returned less than the minimum output".to_string(),
+        ));
+    }
+    if bought > 0 {
+        send_tokens(&config.protocol_token, BURN_ADDRESS, bought)?;
+    }
+    if keeper_reward > 0 {
+        send_tokens(&config.revenue_token, ctx.sender(), keeper_reward)?;
+    }
+
+    state.total_sold = safe_math::add(state.total_sold, sold)?;
+    state.total_burned = safe_math::add(state.total_burned, bought)?;
+    state.total_keeper_rewards = safe_math::add(state.total_keeper_rewards, keeper_reward)?;
+    save_state(&state)?;
+
+    event!("BuybackExecuted",
+        keeper: ctx.sender(),
+        sold: sold,
+        burned: bought,
+        keeper_reward: keeper_reward,
+        spot_quote: spot,
+        twap_quote: twap
+    );
+    Ok(BuybackReceipt {
+        sold,
+        burned: bought,
+        keeper_reward,
+    })
+}
+
+fn execute_set_params() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let params: BuybackParams = read_args()?;
+    validate_params(&params)?;
+    let previous = core::mem::replace(&mut config.params, params);
+    save_config(&config)?;
+
+    event!("ParamsUpdated",
+        interval: config.params.interval,
+        max_sell_per_run: config.params.max_sell_per_run,
+        max_slippage_bps: config.params.max_slippage_bps,
+        max_twap_deviation_bps: config.params.max_twap_deviation_bps,
+        twap_window: config.params.twap_window,
+        keeper_reward_bps: config.params.keeper_reward_bps
+    );
+    let current = &config.params;
+    let changes: [(&str, u64, u64); 6] = [
+        ("interval", previous.interval, current.interval),
+        (
+            "max_sell_per_run",
+            previous.max_sell_per_run,
+            current.max_sell_per_run,
+        ),
+        (
+            "max_slippage_bps",
+            previous.max_slippage_bps.into(),
+            current.max_slippage_bps.into(),
+        ),
+        (
+            "max_twap_deviation_bps",
+            previous.max_twap_deviation_bps.into(),
+            current.max_twap_deviation_bps.into(),
+        ),
+        ("twap_window", previous.twap_window, current.twap_window),
+        (
+            "keeper_reward_bps",
+            previous.keeper_reward_bps.into(),
+            current.keeper_reward_bps.into(),
+        ),
+    ];
+    for (parameter, old, new) in changes {
+        admin_log::record(admin_log::SET_PARAMETER, parameter, old, new)?;
+    }
+    Ok(())
+}
+
+fn execute_set_router() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: RouterArgs = read_args()?;
+    validation::validate_address(&args.router)?;
+    if !context().is_contract(&args.router) {
+        return Err(ContractError::InvalidArgument(
+            "Router must be a contract".to_string(),
+        ));
+    }
+    let previous = core::mem::replace(&mut config.router, args.router.clone());
+    save_config(&config)?;
+
+    event!("RouterUpdated", router: args.router);
+    admin_log::record(admin_log::SET_PARAMETER, "router", previous, &args.router)?;
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::unpause()
+}
+
+fn execute_next_execution() -> ContractResult<u64> {
+    let config = load_config()?;
+    let state = load_state()?;
+    if state.last_execution == 0 {
+        return Ok(0);
+    }
+    safe_math::add(state.last_execution, config.params.interval)
+}
+
+/// Initialize the contract; the caller becomes its owner
+///
+/// # Arguments
+/// * `router` - AMM router exposing `get_amount_out`, `consult` and `swap_exact_in`
+/// * `revenue_token` - CRC-20 the protocol earns
+/// * `protocol_token` - CRC-20 bought and burned
+/// * `params` - `BuybackParams`
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Deposit revenue to be sold in later buybacks
+///
+/// # Arguments
+/// * `amount` - Revenue token amount, pulled from the caller
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit_revenue() {
+    if let Err(err) = execute_deposit_revenue() {
+        log!("deposit_revenue failed: {}", err);
+    }
+}
+
+/// Sell pending revenue for the protocol token and burn it (anyone, once per interval)
+///
+/// The caller receives the keeper reward in the revenue token.
+///
+/// # Returns
+/// `BuybackReceipt`
+#[unsafe(no_mangle)]
+pub extern "C" fn execute_buyback() {
+    if let Err(err) = run_buyback().and_then(|receipt| try_respond(&receipt)) {
+        log!("execute_buyback failed: {}", err);
+    }
+}
+
+/// Replace the buyback parameters (owner only)
+///
+/// # Arguments
+/// * `params` - `BuybackParams`
+#[unsafe(no_mangle)]
+pub extern "C" fn set_params() {
+    if let Err(err) = execute_set_params() {
+        log!("set_params failed: {}", err);
+    }
+}
+
+/// Point the executor at a new AMM router (owner only)
+///
+/// # Arguments
+/// * `router` - Router contract address
+#[unsafe(no_mangle)]
+pub extern "C" fn set_router() {
+    if let Err(err) = execute_set_router() {
+        log!("set_router failed: {}", err);
+    }
+}
+
+/// Pause the executor (owner only)
+///
+/// Revenue deposits and buybacks fail until `unpause`; pending revenue stays
+/// in the contract.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the executor is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Get the contract configuration
+///
+/// # Returns
+/// `BuybackConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get pending revenue and lifetime totals
+///
+/// # Returns
+/// `BuybackState`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_state() {
+    if let Err(err) = load_state().and_then(|state| try_respond(&state)) {
+        log!("get_state failed: {}", err);
+    }
+}
+
+/// Get the earliest timestamp at which `execute_buyback` can run again
+///
+/// # Returns
+/// Timestamp (u64); 0 if no buyback has run yet
+#[unsafe(no_mangle)]
+pub extern "C" fn next_execution() {
+    if let Err(err) = execute_next_execution().and_then(|at| try_respond(&at)) {
+        log!("next_execution failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_FEES: &str = "0x0000000000000000000000000000000000000a02";
+    const ADDR_KEEPER: &str = "0x0000000000000000000000000000000000000b02";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const ROUTER: &str = "0x0000000000000000000000000000000000000f01";
+    const USDC: &str = "0x0000000000000000000000000000000000000f02";
+    const SILICA: &str = "0x0000000000000000000000000000000000000f03";
+    const START: u64 = 1_736_000_000;
+    const DAY: u64 = 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn params() -> BuybackParams {
+        BuybackParams {
+            interval: DAY,
+            max_sell_per_run: 10_000,
+            max_slippage_bps: 100,
+            max_twap_deviation_bps: 500,
+            twap_window: 1_800,
+            keeper_reward_bps: 50,
+        }
+    }
+
+    /// Router quoting `spot` and `twap` protocol tokens per revenue token
+    fn mock_router(spot: u64, twap: u64, fill: u64) {
+        mock::register_contract(ROUTER, "get_amount_out", move |args| {
+            let args: QuoteArgs = postcard::from_bytes(args).unwrap();
+            Ok(postcard::to_allocvec(&(args.amount_in * spot)).unwrap())
+        });
+        mock::register_contract(ROUTER, "consult", move |args| {
+            let args: ConsultArgs = postcard::from_bytes(args).unwrap();
+            Ok(postcard::to_allocvec(&(args.amount_in * twap)).unwrap())
+        });
+        mock::register_contract(ROUTER, "swap_exact_in", move |args| {
+            let args: SwapArgs = postcard::from_bytes(args).unwrap();
+            Ok(postcard::to_allocvec(&(args.amount_in * fill)).unwrap())
+        });
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        for token in [USDC, SILICA] {
+            for method in ["transfer", "transfer_from", "approve"] {
+                mock::register_contract(token, method, |_| Ok(Vec::new()));
+            }
+        }
+        mock_router(2, 2, 2);
+        call(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                router: ROUTER.to_string(),
+                revenue_token: USDC.to_string(),
+                protocol_token: SILICA.to_string(),
+                params: params(),
+            }),
+        );
+    }
+
+    fn deposit(amount: u64) {
+        call(ADDR_FEES, deposit_revenue, encode(&AmountArgs { amount }));
+    }
+
+    fn buyback() -> Option<BuybackReceipt> {
+        mock::set_sender(ADDR_KEEPER);
+        execute_buyback();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// (token, method, args) of every call since the last take
+    fn calls() -> Vec<(String, String, Vec<u8>)> {
+        mock::take_contract_calls()
+            .into_iter()
+            .map(|c| (c.address, c.method, c.args))
+            .collect()
+    }
+
+    fn transfers(calls: &[(String, String, Vec<u8>)]) -> Vec<(String, String, u64)> {
+        calls
+            .iter()
+            .filter(|(_, method, _)| method == "transfer")
+            .map(|(token, _, args)| {
+                let args: TokenTransferArgs = postcard::from_bytes(args).unwrap();
+                (token.clone(), args.to, args.amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn buyback_swaps_burns_and_pays_the_keeper() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit(4_000);
+        calls();
+
+        assert_eq!(
+            buyback(),
+            Some(BuybackReceipt {
+                sold: 3_980,
+                burned: 7_960,
+                keeper_reward: 20
+            })
+        );
+        let calls = calls();
+        let swap = calls
+            .iter()
+            .find(|(_, method, _)| method == "swap_exact_in")
+            .unwrap();
+        let swap: SwapArgs = postcard::from_bytes(&swap.2).unwrap();
+        // 1% slippage below the TWAP quote of 7_960.
+        assert_eq!((swap.amount_in, swap.min_amount_out), (3_980, 7_881));
+        assert_eq!(
+            transfers(&calls),
+            [
+                (SILICA.to_string(), BURN_ADDRESS.to_string(), 7_960),
+                (USDC.to_string(), ADDR_KEEPER.to_string(), 20)
+            ]
+        );
+        assert_eq!(
+            load_state().unwrap(),
+            BuybackState {
+                pending_revenue: 0,
+                last_execution: START,
+                total_sold: 3_980,
+                total_burned: 7_960,
+                total_keeper_rewards: 20
+            }
+        );
+    }
+
+    #[test]
+    fn buybacks_run_once_per_interval() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(buyback(), None);
+        deposit(25_000);
+
+        assert_eq!(buyback().unwrap().sold + 50, 10_000);
+        assert_eq!(buyback(), None);
+        mock::set_block_timestamp(START + DAY - 1);
+        assert_eq!(buyback(), None);
+        mock::set_block_timestamp(START + DAY);
+        assert!(buyback().is_some());
+        mock::set_block_timestamp(START + 2 * DAY);
+        assert_eq!(buyback().unwrap().sold + 25, 5_000);
+        assert_eq!(load_state().unwrap().pending_revenue, 0);
+
+        mock::set_sender(ADDR_KEEPER);
+        next_execution();
+        let next: u64 = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(next, START + 3 * DAY);
+    }
+
+    #[test]
+    fn manipulated_spot_price_is_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit(1_000);
+        // Spot 50% below the TWAP: someone pushed the pool price.
+        mock_router(1, 2, 1);
+        calls();
+        assert_eq!(buyback(), None);
+        assert!(
+            calls()
+                .iter()
+                .all(|(_, method, _)| method != "swap_exact_in")
+        );
+        assert_eq!(load_state().unwrap().pending_revenue, 1_000);
+
+        // Within 5% of the TWAP the buyback goes through.
+        mock_router(21, 20, 21);
+        assert!(buyback().is_some());
+    }
+
+    #[test]
+    fn short_fills_fail_the_buyback() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit(1_000);
+        // The router ignores `min_amount_out` and fills at half the quote.
+        mock::register_contract(ROUTER, "swap_exact_in", |args| {
+            let args: SwapArgs = postcard::from_bytes(args).unwrap();
+            Ok(postcard::to_allocvec(&args.amount_in).unwrap())
+        });
+        calls();
+        assert_eq!(buyback(), None);
+        assert!(transfers(&calls()).is_empty());
+    }
+
+    #[test]
+    fn pausing_stops_deposits_and_buybacks() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit(1_000);
+        call(ADDR_KEEPER, pause, Vec::new());
+        assert!(!pausable::paused().unwrap());
+        call(ADDR_OWNER, pause, Vec::new());
+        let paused_now = call(ADDR_KEEPER, paused, Vec::new());
+        assert!(postcard::from_bytes::<bool>(&paused_now).unwrap());
+
+        deposit(500);
+        assert_eq!(buyback(), None);
+        assert_eq!(load_state().unwrap().pending_revenue, 1_000);
+
+        call(ADDR_OWNER, unpause, Vec::new());
+        assert!(buyback().is_some());
+    }
+
+    #[test]
+    fn only_the_owner_tunes_parameters() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let tuned = BuybackParams {
+            keeper_reward_bps: 100,
+            ..params()
+        };
+        call(ADDR_KEEPER, set_params, encode(&tuned));
+        assert_eq!(load_config().unwrap().params, params());
+        for invalid in [
+            BuybackParams {
+                interval: 60,
+                ..params()
+            },
+            BuybackParams {
+                max_slippage_bps: 5_000,
+                ..params()
+            },
+            BuybackParams {
+                keeper_reward_bps: 101,
+                ..params()
+            },
+            BuybackParams {
+                twap_window: 0,
+                ..params()
+            },
+        ] {
+            call(ADDR_OWNER, set_params, encode(&invalid));
+        }
+        assert_eq!(load_config().unwrap().params, params());
+        call(ADDR_OWNER, set_params, encode(&tuned));
+        assert_eq!(load_config().unwrap().params, tuned);
+        // The accepted call logs each of the six parameters.
+        assert_eq!(admin_log::next_sequence().unwrap(), 6);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "buyback-burn-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,688 @@
+//! CRC-721 Collection Factory
+//!
+//! Deploys CRC-721 collections from an owner-approved code hash with a
+//! standard configuration (royalties and mint phases), hands ownership of each
+//! collection to its creator, and keeps a paginated registry so marketplaces
+//! can enumerate everything deployed through the factory.
+//!
+//! ## Features
+//! - One-call collection deployment with validated configuration
+//! - Royalty and mint-phase presets passed to the collection's `initialize`
+//! - Global and per-creator registries with offset/limit pagination
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::bounded::{Name, Symbol, Uri};
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "factory_config";
+const COLLECTIONS_PREFIX: &str = "collections";
+const CREATOR_INDEX_PREFIX: &str = "creator_index";
+const CREATOR_COUNTS_PREFIX: &str = "creator_counts";
+const ADDRESS_INDEX_PREFIX: &str = "address_index";
+const SALT_DOMAIN: &str = "chert-collection-v1";
+const MAX_ROYALTY_BPS: u16 = 1_000;
+const MAX_PHASES: usize = 8;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 16_384;
+
+/// Factory owner and the collection code it deploys
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FactoryConfig {
+    pub owner: String,
+    pub collection_code_hash: [u8; 32],
+    pub collection_count: u64,
+}
+
+/// Secondary-sale royalty paid to `receiver`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Royalty {
+    pub receiver: String,
+    pub bps: u16,
+}
+
+/// Time-boxed minting window
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MintPhase {
+    pub name: Name,
+    pub starts_at: u64,
+    pub price: u64,
+    pub per_wallet_limit: u32,
+    /// Merkle root of allowed minters; `None` for a public phase
+    pub allowlist_root: Option<[u8; 32]>,
+}
+
+/// Payload passed to a new collection's `initialize` entrypoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CollectionInit {
+    pub name: Name,
+    pub symbol: Symbol,
+    pub base_uri: Uri,
+    pub owner: String,
+    pub max_supply: u64,
+    pub royalty: Royalty,
+    pub phases: Vec<MintPhase>,
+}
+
+/// Registry entry for a deployed collection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CollectionRecord {
+    pub id: u64,
+    pub address: String,
+    pub creator: String,
+    pub name: String,
+    pub symbol: String,
+    pub created_at: u64,
+}
+
+/// One page of registry results
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CollectionPage {
+    pub items: Vec<CollectionRecord>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    collection_code_hash: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetCollectionCodeArgs {
+    collection_code_hash: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateCollectionArgs {
+    name: Name,
+    symbol: Symbol,
+    base_uri: Uri,
+    max_supply: u64,
+    royalty: Royalty,
+    phases: Vec<MintPhase>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PageArgs {
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreatorPageArgs {
+    creator: String,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AddressArgs {
+    address: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<FactoryConfig> {
+    storage()
+        .get::<FactoryConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Factory not initialized".to_string()))
+}
+
+fn save_config(config: &FactoryConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn collections() -> Map<u64, CollectionRecord> {
+    Map::new(COLLECTIONS_PREFIX)
+}
+
+fn creator_index() -> Map<(String, u64), u64> {
+    Map::new(CREATOR_INDEX_PREFIX)
+}
+
+fn creator_counts() -> Map<String, u64> {
+    Map::new(CREATOR_COUNTS_PREFIX)
+}
+
+fn address_index() -> Map<String, u64> {
+    Map::new(ADDRESS_INDEX_PREFIX)
+}
+
+/// Check the standard configuration every factory collection must satisfy.
+pub fn validate_collection_config(
+    royalty: &Royalty,
+    phases: &[MintPhase],
+    max_supply: u64,
+) -> ContractResult<()> {
+    validation::validate_positive_amount(max_supply)?;
+    validation::validate_address(&royalty.receiver)?;
+    if royalty.bps > MAX_ROYALTY_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Royalty exceeds {} bps",
+            MAX_ROYALTY_BPS
+        )));
+    }
+
+    if phases.is_empty() || phases.len() > MAX_PHASES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Collections need 1-{} mint phases",
+            MAX_PHASES
+        )));
+    }
+    for (index, phase) in phases.iter().enumerate() {
+        validation::validate_non_empty(&phase.name, "phase name")?;
+        validation::validate_positive_amount(phase.per_wallet_limit as u64)?;
+        if index > 0 && phase.starts_at <= phases[index - 1].starts_at {
+            return Err(ContractError::InvalidArgument(
+                "Mint phases must start in strictly increasing order".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Collect `[offset, offset + limit)` from an indexed sequence of `total` items.
+fn paginate<F>(total: u64, offset: u64, limit: u64, mut fetch: F) -> ContractResult<CollectionPage>
+where
+    F: FnMut(u64) -> ContractResult<Option<CollectionRecord>>,
+{
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+
+    let end = offset.saturating_add(limit).min(total);
+    let mut items = Vec::new();
+    for index in offset..end {
+        if let Some(record) = fetch(index)? {
+            items.push(record);
+        }
+    }
+    Ok(CollectionPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let args: InitializeArgs = read_args()?;
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Factory already initialized".to_string(),
+        ));
+    }
+
+    let owner = context().sender().to_string();
+    validation::validate_address(&owner)?;
+    save_config(&FactoryConfig {
+        owner: owner.clone(),
+        collection_code_hash: args.collection_code_hash,
+        collection_count: 0,
+    })?;
+
+    event!("FactoryInitialized",
+        owner: owner,
+        collection_code_hash: hex::encode(args.collection_code_hash)
+    );
+    Ok(())
+}
+
+fn execute_set_collection_code() -> ContractResult<()> {
+    let mut config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: SetCollectionCodeArgs = read_args()?;
+
+    let previous = core::mem::replace(&mut config.collection_code_hash, args.collection_code_hash);
+    save_config(&config)?;
+
+    let current = hex::encode(args.collection_code_hash);
+    event!("CollectionCodeUpdated", collection_code_hash: current);
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "collection_code_hash",
+        hex::encode(previous),
+        current,
+    )?;
+    Ok(())
+}
+
+fn execute_create_collection() -> ContractResult<String> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_config()?;
+    let ctx = context();
+    let creator = ctx.sender().to_string();
+    let args: CreateCollectionArgs = read_args()?;
+    validation::validate_address(&creator)?;
+    validation::validate_non_empty(&args.name, "name")?;
+    validation::validate_non_empty(&args.symbol, "symbol")?;
+    validation::validate_non_empty(&args.base_uri, "base_uri")?;
+    validate_collection_config(&args.royalty, &args.phases, args.max_supply)?;
+
+    let id = config.collection_count;
+    let init = CollectionInit {
+        name: args.name,
+        symbol: args.symbol,
+        base_uri: args.base_uri,
+        owner: creator.clone(),
+        max_supply: args.max_supply,
+        royalty: args.royalty,
+        phases: args.phases,
+    };
+    let init_args = postcard::to_allocvec(&init).map_err(|_| ContractError::SerializationFailed)?;
+    let salt = postcard::to_allocvec(&(SALT_DOMAIN, id))
+        .map_err(|_| ContractError::SerializationFailed)?;
+    let address = ctx.instantiate_contract(&config.collection_code_hash, &salt, &init_args)?;
+
+    config.collection_count = safe_math::add(id, 1)?;
+    save_config(&config)?;
+
+    let record = CollectionRecord {
+        id,
+        address: address.clone(),
+        creator: creator.clone(),
+        name: init.name.into(),
+        symbol: init.symbol.into(),
+        created_at: ctx.block_timestamp(),
+    };
+    collections().set(&id, &record)?;
+    address_index().set(&address, &id)?;
+
+    let mut counts = creator_counts();
+    let creator_total = counts.get(&creator)?.unwrap_or(0);
+    creator_index().set(&(creator.clone(), creator_total), &id)?;
+    counts.set(&creator, &safe_math::add(creator_total, 1)?)?;
+
+    event!("CollectionCreated",
+        id: id,
+        address: address,
+        creator: creator,
+        name: record.name,
+        symbol: record.symbol
+    );
+    try_respond(&record.address)?;
+    Ok(record.address)
+}
+
+fn execute_collections() -> ContractResult<()> {
+    let config = load_config()?;
+    let args: PageArgs = read_args()?;
+    let registry = collections();
+    let page = paginate(config.collection_count, args.offset, args.limit, |id| {
+        registry.get(&id)
+    })?;
+    try_respond(&page)
+}
+
+fn execute_collections_by_creator() -> ContractResult<()> {
+    load_config()?;
+    let args: CreatorPageArgs = read_args()?;
+    let total = creator_counts().get(&args.creator)?.unwrap_or(0);
+    let registry = collections();
+    let index = creator_index();
+    let page = paginate(total, args.offset, args.limit, |position| {
+        match index.get(&(args.creator.clone(), position))? {
+            Some(id) => registry.get(&id),
+            None => Ok(None),
+        }
+    })?;
+    try_respond(&page)
+}
+
+fn execute_get_collection() -> ContractResult<()> {
+    let args: AddressArgs = read_args()?;
+    let record = match address_index().get(&args.address)? {
+        Some(id) => collections().get(&id)?,
+        None => None,
+    };
+    try_respond(&record)
+}
+
+/// Initialize the factory; the caller becomes its owner
+///
+/// # Arguments
+/// * `collection_code_hash` - Code hash of the CRC-721 collection template
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Point new deployments at a different collection template (owner only)
+///
+/// # Arguments
+/// * `collection_code_hash` - Code hash of the new template
+#[unsafe(no_mangle)]
+pub extern "C" fn set_collection_code() {
+    if let Err(err) = execute_set_collection_code() {
+        log!("set_collection_code failed: {}", err);
+    }
+}
+
+/// Deploy a collection owned by the caller
+///
+/// # Arguments
+/// * `name` - Collection name, at most 64 bytes
+/// * `symbol` - Collection symbol, at most 16 bytes
+/// * `base_uri` - Base URI for token metadata, at most 256 bytes
+/// * `max_supply` - Maximum number of tokens
+/// * `royalty` - `{ receiver, bps }`, at most 1_000 bps
+/// * `phases` - 1-8 mint phases in increasing `starts_at` order
+///
+/// # Returns
+/// Address of the new collection
+#[unsafe(no_mangle)]
+pub extern "C" fn create_collection() {
+    if let Err(err) = execute_create_collection() {
+        log!("create_collection failed: {}", err);
+    }
+}
+
+/// Page through every collection deployed by the factory
+///
+/// # Arguments
+/// * `offset` - Index of the first collection
+/// * `limit` - Page size, at most 50
+///
+/// # Returns
+/// `CollectionPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn collections_page() {
+    if let Err(err) = execute_collections() {
+        log!("collections_page failed: {}", err);
+    }
+}
+
+/// Page through the collections deployed by one creator
+///
+/// # Arguments
+/// * `creator` - Creator address
+/// * `offset` - Index of the first collection
+/// * `limit` - Page size, at most 50
+///
+/// # Returns
+/// `CollectionPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn collections_by_creator() {
+    if let Err(err) = execute_collections_by_creator() {
+        log!("collections_by_creator failed: {}", err);
+    }
+}
+
+/// Look up a collection by address
+///
+/// # Arguments
+/// * `address` - Collection address
+///
+/// # Returns
+/// `Option<CollectionRecord>`; `None` if the address was not deployed here
+#[unsafe(no_mangle)]
+pub extern "C" fn get_collection() {
+    if let Err(err) = execute_get_collection() {
+        log!("get_collection failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const CODE_HASH: [u8; 32] = [0x72; 32];
+    const START: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address("0x0000000000000000000000000000000000000f00");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&InitializeArgs {
+            collection_code_hash: CODE_HASH,
+        }));
+        initialize();
+    }
+
+    fn phase(name: &str, starts_at: u64) -> MintPhase {
+        MintPhase {
+            name: Name::new(name).unwrap(),
+            starts_at,
+            price: 10,
+            per_wallet_limit: 2,
+            allowlist_root: None,
+        }
+    }
+
+    fn collection_args(
+        name: &str,
+        royalty_bps: u16,
+        phases: Vec<MintPhase>,
+    ) -> CreateCollectionArgs {
+        CreateCollectionArgs {
+            name: Name::new(name).unwrap(),
+            symbol: Symbol::new("CPX").unwrap(),
+            base_uri: Uri::new("ipfs://collection/").unwrap(),
+            max_supply: 1_000,
+            royalty: Royalty {
+                receiver: ADDR_ALICE.to_string(),
+                bps: royalty_bps,
+            },
+            phases,
+        }
+    }
+
+    fn create_as(creator: &str, name: &str) -> Option<String> {
+        mock::set_sender(creator);
+        mock::set_call_data(&encode(&collection_args(
+            name,
+            500,
+            vec![phase("allowlist", START), phase("public", START + 3_600)],
+        )));
+        create_collection();
+        let data = mock::take_return_data();
+        (!data.is_empty()).then(|| postcard::from_bytes(&data).expect("collection address"))
+    }
+
+    fn page(offset: u64, limit: u64) -> CollectionPage {
+        mock::set_call_data(&encode(&PageArgs { offset, limit }));
+        collections_page();
+        postcard::from_bytes(&mock::take_return_data()).expect("page")
+    }
+
+    #[test]
+    fn create_collection_deploys_template_owned_by_creator() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        let address = create_as(ADDR_ALICE, "Chert Punks").expect("deployed");
+        let deployments = mock::take_instantiations();
+        assert_eq!(deployments.len(), 1);
+        assert_eq!(deployments[0].code_hash, CODE_HASH);
+        assert_eq!(deployments[0].address, address);
+
+        let init: CollectionInit = postcard::from_bytes(&deployments[0].init_args).unwrap();
+        assert_eq!(init.owner, ADDR_ALICE);
+        assert_eq!(init.royalty.bps, 500);
+        assert_eq!(init.phases.len(), 2);
+
+        mock::set_call_data(&encode(&AddressArgs {
+            address: address.clone(),
+        }));
+        get_collection();
+        let record: Option<CollectionRecord> =
+            postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(record.map(|r| r.creator), Some(ADDR_ALICE.to_string()));
+    }
+
+    #[test]
+    fn invalid_configurations_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::set_sender(ADDR_ALICE);
+
+        for args in [
+            collection_args("Too Greedy", 1_001, vec![phase("public", START)]),
+            collection_args("No Phases", 100, Vec::new()),
+            collection_args(
+                "Backwards",
+                100,
+                vec![phase("public", START + 10), phase("late", START)],
+            ),
+        ] {
+            mock::set_call_data(&encode(&args));
+            create_collection();
+        }
+
+        assert!(mock::take_instantiations().is_empty());
+        assert_eq!(load_config().unwrap().collection_count, 0);
+    }
+
+    #[test]
+    fn registry_pages_through_all_collections() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        for index in 0..5 {
+            create_as(ADDR_ALICE, &format!("Collection {}", index)).expect("deployed");
+        }
+
+        let first = page(0, 2);
+        assert_eq!(first.total, 5);
+        assert_eq!(first.items.len(), 2);
+        assert_eq!(first.next_offset, Some(2));
+
+        let last = page(4, 2);
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.items[0].name, "Collection 4");
+        assert_eq!(last.next_offset, None);
+
+        assert!(page(10, 2).items.is_empty());
+        mock::set_call_data(&encode(&PageArgs {
+            offset: 0,
+            limit: MAX_PAGE_SIZE + 1,
+        }));
+        collections_page();
+        assert!(mock::take_return_data().is_empty());
+    }
+
+    #[test]
+    fn creator_index_lists_only_their_collections() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        create_as(ADDR_ALICE, "Alice One").unwrap();
+        create_as(ADDR_BOB, "Bob One").unwrap();
+        create_as(ADDR_ALICE, "Alice Two").unwrap();
+
+        mock::set_call_data(&encode(&CreatorPageArgs {
+            creator: ADDR_ALICE.to_string(),
+            offset: 0,
+            limit: 10,
+        }));
+        collections_by_creator();
+        let alice: CollectionPage = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        let names: Vec<_> = alice.items.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Alice One", "Alice Two"]);
+        assert_eq!(alice.total, 2);
+    }
+
+    #[test]
+    fn only_owner_updates_collection_code() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let call = encode(&SetCollectionCodeArgs {
+            collection_code_hash: [0x99; 32],
+        });
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&call);
+        set_collection_code();
+        assert_eq!(load_config().unwrap().collection_code_hash, CODE_HASH);
+
+        mock::set_sender(ADDR_OWNER);
+        set_collection_code();
+        assert_eq!(admin_log::next_sequence().unwrap(), 1);
+        create_as(ADDR_BOB, "New Template").unwrap();
+        assert_eq!(mock::take_instantiations()[0].code_hash, [0x99; 32]);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "collection-factory-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
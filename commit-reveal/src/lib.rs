@@ -0,0 +1,239 @@
+//! Commit-reveal
+//!
+//! Sealed moves, votes and seeds all follow the same pattern: a participant
+//! first publishes a hash of a secret value, and only later the value
+//! itself, which the contract checks against the hash. This crate fixes the
+//! hash so every contract binds commitments the same way:
+//!
+//! ```text
+//! commitment = blake3(postcard(("silica-commit-v1", purpose, sender, value, salt)))
+//! ```
+//!
+//! * `purpose` names what is being committed to, and usually includes the
+//!   id of the game or ballot, so a commitment means nothing elsewhere.
+//! * `sender` is the committing account. A copied commitment cannot be
+//!   revealed by anyone else.
+//! * `salt` is 32 fresh random bytes. Without it, a value from a small set
+//!   (a move, a ballot option) could be found by hashing every candidate.
+//!   A value that is itself 32 random bytes, such as a seed, can be
+//!   committed as the salt with `()` as the value.
+//!
+//! [`Phases`] tracks a fixed commit window followed by a reveal window, and
+//! [`randomness`] turns revealed seeds into unbiased draws.
+//!
+//! ## Embedding
+//! ```toml
+//! commit-reveal = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! let phases = Phases::starting_now(Duration::from_days(2), Duration::from_days(1))?;
+//! // commit_vote
+//! phases.require(Phase::Commit)?;
+//! // reveal_vote
+//! phases.require(Phase::Reveal)?;
+//! commit_reveal::verify(&vote.commitment, &("ballot", ballot_id), &voter, &choice, &salt)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+pub mod randomness;
+
+use alloc::format;
+use alloc::string::ToString;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::{self, Duration};
+
+/// A 32-byte commitment
+pub type Commitment = [u8; 32];
+
+/// Random bytes hashed into a commitment to hide its value
+pub type Salt = [u8; 32];
+
+/// Tag hashed into every commitment
+pub const COMMITMENT_TAG: &str = "silica-commit-v1";
+
+/// Commitment `sender` publishes to `value` for `purpose`.
+pub fn commitment<P, V>(
+    purpose: &P,
+    sender: &str,
+    value: &V,
+    salt: &Salt,
+) -> ContractResult<Commitment>
+where
+    P: Serialize + ?Sized,
+    V: Serialize + ?Sized,
+{
+    let preimage = postcard::to_allocvec(&(COMMITMENT_TAG, purpose, sender, value, salt))
+        .map_err(|_| ContractError::SerializationFailed)?;
+    Ok(crypto::hash_blake3(&preimage))
+}
+
+/// Fail unless `value` and `salt` open `commitment` for `sender` and
+/// `purpose`.
+pub fn verify<P, V>(
+    commitment: &Commitment,
+    purpose: &P,
+    sender: &str,
+    value: &V,
+    salt: &Salt,
+) -> ContractResult<()>
+where
+    P: Serialize + ?Sized,
+    V: Serialize + ?Sized,
+{
+    if self::commitment(purpose, sender, value, salt)? != *commitment {
+        return Err(ContractError::InvalidArgument(
+            "Reveal does not match the commitment".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Where a commit-reveal process is at a given time
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Commit,
+    Reveal,
+    Closed,
+}
+
+/// A commit window followed by a reveal window. Commitments are accepted
+/// before `commit_end`, reveals from `commit_end` until before `reveal_end`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Phases {
+    pub commit_end: u64,
+    pub reveal_end: u64,
+}
+
+impl Phases {
+    pub fn new(commit_end: u64, reveal_end: u64) -> ContractResult<Self> {
+        if reveal_end <= commit_end {
+            return Err(ContractError::InvalidArgument(
+                "Reveal phase must end after the commit phase".to_string(),
+            ));
+        }
+        Ok(Self {
+            commit_end,
+            reveal_end,
+        })
+    }
+
+    /// Commit phase opening now, each phase lasting the given time
+    pub fn starting_now(commit: Duration, reveal: Duration) -> ContractResult<Self> {
+        let commit_end = time::now()?
+            .checked_add(commit.as_secs())
+            .ok_or(ContractError::Overflow)?;
+        let reveal_end = commit_end
+            .checked_add(reveal.as_secs())
+            .ok_or(ContractError::Overflow)?;
+        Self::new(commit_end, reveal_end)
+    }
+
+    /// Phase at `timestamp`
+    pub fn at(&self, timestamp: u64) -> Phase {
+        if timestamp < self.commit_end {
+            Phase::Commit
+        } else if timestamp < self.reveal_end {
+            Phase::Reveal
+        } else {
+            Phase::Closed
+        }
+    }
+
+    /// Phase of the current block
+    pub fn current(&self) -> ContractResult<Phase> {
+        Ok(self.at(time::now()?))
+    }
+
+    /// Fail unless the current block is in `expected`
+    pub fn require(&self, expected: Phase) -> ContractResult<()> {
+        let current = self.current()?;
+        if current != expected {
+            return Err(ContractError::InvalidArgument(format!(
+                "In {:?} phase, expected {:?}",
+                current, expected
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    const BOB: &str = "0x0000000000000000000000000000000000000b0b";
+    pub(crate) const NOW: u64 = 1_736_000_000;
+    const SALT: Salt = [7; 32];
+
+    pub(crate) fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    pub(crate) fn setup() {
+        mock::reset();
+        mock::set_contract_address("0x0000000000000000000000000000000000000f00");
+        mock::set_sender(ALICE);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+    }
+
+    #[test]
+    fn commitments_bind_purpose_sender_value_and_salt() {
+        let sealed = commitment(&("ballot", 1u64), ALICE, &2u32, &SALT).unwrap();
+        verify(&sealed, &("ballot", 1u64), ALICE, &2u32, &SALT).unwrap();
+
+        let mismatches = [
+            commitment(&("ballot", 2u64), ALICE, &2u32, &SALT),
+            commitment(&("poll", 1u64), ALICE, &2u32, &SALT),
+            commitment(&("ballot", 1u64), BOB, &2u32, &SALT),
+            commitment(&("ballot", 1u64), ALICE, &3u32, &SALT),
+            commitment(&("ballot", 1u64), ALICE, &2u32, &[8; 32]),
+        ];
+        for other in mismatches {
+            assert_ne!(other.unwrap(), sealed);
+        }
+        assert!(matches!(
+            verify(&sealed, &("ballot", 1u64), BOB, &2u32, &SALT),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Reveal does not match the commitment"
+        ));
+
+        // The documented preimage, so clients can compute it off-chain.
+        let preimage =
+            postcard::to_allocvec(&(COMMITMENT_TAG, ("ballot", 1u64), ALICE, 2u32, SALT)).unwrap();
+        assert_eq!(sealed, crypto::hash_blake3(&preimage));
+    }
+
+    #[test]
+    fn phases_are_half_open() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert!(Phases::new(NOW, NOW).is_err());
+        let phases =
+            Phases::starting_now(Duration::from_hours(1), Duration::from_hours(2)).unwrap();
+        assert_eq!(phases, Phases::new(NOW + 3_600, NOW + 10_800).unwrap());
+        assert_eq!(phases.at(NOW + 3_599), Phase::Commit);
+        assert_eq!(phases.at(NOW + 3_600), Phase::Reveal);
+        assert_eq!(phases.at(NOW + 10_799), Phase::Reveal);
+        assert_eq!(phases.at(NOW + 10_800), Phase::Closed);
+
+        phases.require(Phase::Commit).unwrap();
+        assert!(matches!(
+            phases.require(Phase::Reveal),
+            Err(ContractError::InvalidArgument(msg)) if msg == "In Commit phase, expected Reveal"
+        ));
+        mock::set_block_timestamp(NOW + 3_600);
+        assert_eq!(phases.current().unwrap(), Phase::Reveal);
+        phases.require(Phase::Reveal).unwrap();
+        assert!(
+            Phases::starting_now(Duration::from_secs(u64::MAX), Duration::from_hours(1)).is_err()
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Random draws from mixed sources
+//!
+//! Nothing a contract sees is secret, so it cannot make randomness on its
+//! own. [`Mixer`] hashes together whichever sources a contract has:
+//!
+//! * a seed revealed through commit-reveal, which nobody but its committer
+//!   knows until the reveal;
+//! * inputs from other participants, such as an opener's client seed, so
+//!   the committer alone cannot pick the outcome;
+//! * block height and timestamp, which the block producer knows, and can
+//!   nudge, in advance;
+//! * a beacon value published by an oracle the contract trusts.
+//!
+//! The outcome is as unpredictable as the best source that stays secret
+//! until every other source is fixed. Block data never meets that bar on
+//! its own. Mix it only when it is fixed before the draw, such as the block
+//! of an opening, not the block of whoever settles it.
+//!
+//! ```text
+//! state  = blake3(postcard(("silica-random-v1", domain)) || sources)
+//! word n = first 16 bytes of blake3(state || n as u64 LE), as a u128 LE
+//! draw   = start + word % (end - start)
+//! ```
+//!
+//! Sources are appended in call order: a seed as `0x01 || seed`, a block as
+//! `0x02 || height LE || timestamp LE`, a beacon as `0x03 || beacon`, and an
+//! input as `0x04 || length as u32 LE || postcard(input)`.
+//!
+//! ## Bias
+//! Reducing a 128-bit word modulo `n` makes `2^128 mod n` of the results one
+//! word more likely than the rest. Every result's probability is therefore
+//! within `2^-128` of `1/n`, a relative error below `2^-64` for any range of
+//! `u64`s.
+//!
+//! ```rust,ignore
+//! let mut random = Mixer::new("raffle").seed(&seed).input(&ticket_count)?.finish();
+//! let winner = random.draw(0..ticket_count)?;
+//! ```
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::ops::Range;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time;
+
+/// Tag hashed into every randomness state
+pub const RANDOMNESS_TAG: &str = "silica-random-v1";
+
+const SEED: u8 = 0x01;
+const BLOCK: u8 = 0x02;
+const BEACON: u8 = 0x03;
+const INPUT: u8 = 0x04;
+
+/// Collects the sources of one random state
+pub struct Mixer {
+    preimage: Vec<u8>,
+}
+
+impl Mixer {
+    /// Start mixing for `domain`, which names the contract and use so two
+    /// draws from the same sources never coincide.
+    pub fn new(domain: &str) -> Self {
+        // A pair of strings always encodes.
+        let preimage = postcard::to_allocvec(&(RANDOMNESS_TAG, domain)).unwrap_or_default();
+        Self { preimage }
+    }
+
+    fn push(mut self, tag: u8, bytes: &[u8]) -> Self {
+        self.preimage.push(tag);
+        self.preimage.extend_from_slice(bytes);
+        self
+    }
+
+    /// Mix in a revealed commit-reveal seed
+    pub fn seed(self, seed: &[u8; 32]) -> Self {
+        self.push(SEED, seed)
+    }
+
+    /// Mix in a block; see the module docs for when that helps
+    pub fn block(self, height: u64, timestamp: u64) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&height.to_le_bytes());
+        bytes[8..].copy_from_slice(&timestamp.to_le_bytes());
+        self.push(BLOCK, &bytes)
+    }
+
+    /// Mix in the executing block
+    pub fn current_block(self) -> ContractResult<Self> {
+        Ok(self.block(time::block_height()?, time::now()?))
+    }
+
+    /// Mix in an oracle beacon value, if the contract has one
+    pub fn beacon(self, beacon: Option<&[u8; 32]>) -> Self {
+        match beacon {
+            Some(beacon) => self.push(BEACON, beacon),
+            None => self,
+        }
+    }
+
+    /// Mix in any other public input, such as a client seed or an id
+    pub fn input<T: Serialize + ?Sized>(self, value: &T) -> ContractResult<Self> {
+        let encoded =
+            postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+        let len = u32::try_from(encoded.len()).map_err(|_| ContractError::Overflow)?;
+        let mut mixed = self.push(INPUT, &len.to_le_bytes());
+        mixed.preimage.extend_from_slice(&encoded);
+        Ok(mixed)
+    }
+
+    pub fn finish(self) -> Randomness {
+        Randomness {
+            state: crypto::hash_blake3(&self.preimage),
+            words: 0,
+        }
+    }
+}
+
+/// A random state and the draws taken from it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Randomness {
+    state: [u8; 32],
+    words: u64,
+}
+
+impl Randomness {
+    /// The mixed state, for publishing so draws can be checked off-chain
+    pub fn state(&self) -> [u8; 32] {
+        self.state
+    }
+
+    fn next_word(&mut self) -> ContractResult<u128> {
+        let mut preimage = [0u8; 40];
+        preimage[..32].copy_from_slice(&self.state);
+        preimage[32..].copy_from_slice(&self.words.to_le_bytes());
+        self.words = self.words.checked_add(1).ok_or(ContractError::Overflow)?;
+        let digest = crypto::hash_blake3(&preimage);
+        let mut word = [0u8; 16];
+        word.copy_from_slice(&digest[..16]);
+        Ok(u128::from_le_bytes(word))
+    }
+
+    /// A value in `range`; each draw uses a fresh word of the state
+    pub fn draw(&mut self, range: Range<u64>) -> ContractResult<u64> {
+        if range.is_empty() {
+            return Err(ContractError::InvalidArgument(
+                "Cannot draw from an empty range".to_string(),
+            ));
+        }
+        let span = (range.end - range.start) as u128;
+        let offset = (self.next_word()? % span) as u64;
+        Ok(range.start + offset)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::tests::{NOW, setup, test_lock};
+
+    #[test]
+    fn draws_depend_on_every_source() {
+        let mixed = |domain: &str, seed: u8, beacon: Option<&[u8; 32]>, input: u64| {
+            Mixer::new(domain)
+                .seed(&[seed; 32])
+                .block(10, NOW)
+                .beacon(beacon)
+                .input(&input)
+                .unwrap()
+                .finish()
+                .state()
+        };
+        let base = mixed("raffle", 1, None, 7);
+        assert_eq!(mixed("raffle", 1, None, 7), base);
+        for other in [
+            mixed("lottery", 1, None, 7),
+            mixed("raffle", 2, None, 7),
+            mixed("raffle", 1, Some(&[0; 32]), 7),
+            mixed("raffle", 1, None, 8),
+            Mixer::new("raffle")
+                .seed(&[1; 32])
+                .block(11, NOW)
+                .input(&7u64)
+                .unwrap()
+                .finish()
+                .state(),
+        ] {
+            assert_ne!(other, base);
+        }
+    }
+
+    #[test]
+    fn draws_stay_in_range() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let mut random = Mixer::new("raffle")
+            .seed(&[1; 32])
+            .current_block()
+            .unwrap()
+            .finish();
+        assert_eq!(
+            random.state(),
+            Mixer::new("raffle")
+                .seed(&[1; 32])
+                .block(1, NOW)
+                .finish()
+                .state()
+        );
+        // Draws replay from the same state, and each takes a new word.
+        let first = random.clone().draw(0..u64::MAX).unwrap();
+        assert_eq!(random.draw(0..u64::MAX).unwrap(), first);
+        assert_ne!(random.draw(0..u64::MAX).unwrap(), first);
+
+        let mut seen = [false; 6];
+        for _ in 0..200 {
+            let value = random.draw(10..16).unwrap();
+            assert!((10..16).contains(&value));
+            seen[(value - 10) as usize] = true;
+        }
+        assert_eq!(seen, [true; 6]);
+        assert_eq!(random.draw(5..6).unwrap(), 5);
+        assert!(matches!(
+            random.draw(3..3),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Cannot draw from an empty range"
+        ));
+    }
+}
@@ -0,0 +1,888 @@
+//! Content Registry
+//!
+//! A neutral on-chain anchor for content provenance. Creators anchor the
+//! hash of a work, which records who anchored it and when, optionally with
+//! licensing terms. The first anchor of a hash wins. Registrations can be
+//! transferred to a new owner, who then controls the licence. Moderators
+//! appointed by the owner can flag a registration as disputed, which freezes
+//! it until the flag is cleared.
+//!
+//! ## Features
+//! - First-come anchoring of 32-byte content hashes with timestamps
+//! - Optional licensing terms, editable by the registration owner
+//! - Transferable registrations; the original creator is always kept
+//! - Moderator dispute flags that freeze transfers and licence changes
+//! - Per-creator registration index with offset/limit pagination
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "content_config";
+const REGISTRATION_COUNT_KEY: &str = "registration_count";
+const REGISTRATIONS_PREFIX: &str = "registrations";
+const HASH_INDEX_PREFIX: &str = "hash_index";
+const CREATOR_INDEX_PREFIX: &str = "creator_index";
+const CREATOR_COUNTS_PREFIX: &str = "creator_counts";
+const MODERATORS_PREFIX: &str = "moderators";
+const MODERATOR_ROLE: &str = "moderator";
+const MAX_LICENSE_BYTES: usize = 256;
+const MAX_REASON_BYTES: usize = 256;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 32_768;
+
+/// Registry configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ContentConfig {
+    /// Appoints moderators
+    pub owner: String,
+    pub moderator_count: u64,
+}
+
+/// A moderator's dispute flag
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Dispute {
+    pub moderator: String,
+    pub reason: String,
+    pub flagged_at: u64,
+}
+
+/// An anchored content hash
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Registration {
+    pub id: u64,
+    pub content_hash: [u8; 32],
+    /// Account that anchored the hash; never changes
+    pub creator: String,
+    /// Current owner; controls the licence and transfers
+    pub owner: String,
+    pub anchored_at: u64,
+    pub block_height: u64,
+    /// Licence identifier or terms URI
+    pub license: Option<String>,
+    /// Set while a moderator has the registration flagged
+    pub dispute: Option<Dispute>,
+}
+
+/// One page of registrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegistrationPage {
+    pub items: Vec<Registration>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetModeratorArgs {
+    account: String,
+    enabled: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AnchorArgs {
+    content_hash: [u8; 32],
+    license: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetLicenseArgs {
+    content_hash: [u8; 32],
+    license: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferArgs {
+    content_hash: [u8; 32],
+    to: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FlagArgs {
+    content_hash: [u8; 32],
+    reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HashArgs {
+    content_hash: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreatorPageArgs {
+    creator: String,
+    offset: u64,
+    limit: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<ContentConfig> {
+    storage()
+        .get::<ContentConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn registrations() -> Map<u64, Registration> {
+    Map::new(REGISTRATIONS_PREFIX)
+}
+
+fn hash_index() -> Map<[u8; 32], u64> {
+    Map::new(HASH_INDEX_PREFIX)
+}
+
+fn creator_index() -> Map<(String, u64), u64> {
+    Map::new(CREATOR_INDEX_PREFIX)
+}
+
+fn creator_counts() -> Map<String, u64> {
+    Map::new(CREATOR_COUNTS_PREFIX)
+}
+
+fn moderators() -> Map<String, bool> {
+    Map::new(MODERATORS_PREFIX)
+}
+
+fn find_registration(content_hash: &[u8; 32]) -> ContractResult<Option<Registration>> {
+    match hash_index().get(content_hash)? {
+        Some(id) => registrations().get(&id),
+        None => Ok(None),
+    }
+}
+
+fn load_registration(content_hash: &[u8; 32]) -> ContractResult<Registration> {
+    find_registration(content_hash)?
+        .ok_or_else(|| ContractError::InvalidArgument("Content not registered".to_string()))
+}
+
+/// Load a registration the caller owns and that is not under dispute.
+fn load_owned_registration(content_hash: &[u8; 32]) -> ContractResult<Registration> {
+    let registration = load_registration(content_hash)?;
+    if context().sender() != registration.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    if registration.dispute.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Registration is under dispute".to_string(),
+        ));
+    }
+    Ok(registration)
+}
+
+fn require_moderator() -> ContractResult<String> {
+    load_config()?;
+    let caller = context().sender().to_string();
+    if !moderators().get(&caller)?.unwrap_or(false) {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(caller)
+}
+
+fn validate_license(license: &Option<String>) -> ContractResult<()> {
+    if let Some(license) = license {
+        validation::validate_non_empty(license, "license")?;
+        if license.len() > MAX_LICENSE_BYTES {
+            return Err(ContractError::InvalidArgument(format!(
+                "License exceeds {} bytes",
+                MAX_LICENSE_BYTES
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Collect `[offset, offset + limit)` from an indexed sequence of `total` items.
+fn paginate<F>(
+    total: u64,
+    offset: u64,
+    limit: u64,
+    mut fetch: F,
+) -> ContractResult<RegistrationPage>
+where
+    F: FnMut(u64) -> ContractResult<Option<Registration>>,
+{
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+
+    let end = offset.saturating_add(limit).min(total);
+    let mut items = Vec::new();
+    for index in offset..end {
+        if let Some(registration) = fetch(index)? {
+            items.push(registration);
+        }
+    }
+    Ok(RegistrationPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let config = ContentConfig {
+        owner: context().sender().to_string(),
+        moderator_count: 0,
+    };
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("ContentRegistryInitialized", owner: config.owner);
+    Ok(())
+}
+
+fn execute_set_moderator() -> ContractResult<()> {
+    let mut config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: SetModeratorArgs = read_args()?;
+    validation::validate_address(&args.account)?;
+    let current = moderators().get(&args.account)?.unwrap_or(false);
+    if current == args.enabled {
+        return Ok(());
+    }
+    if args.enabled {
+        moderators().set(&args.account, &true)?;
+        config.moderator_count = safe_math::add(config.moderator_count, 1)?;
+    } else {
+        moderators().remove(&args.account)?;
+        config.moderator_count = safe_math::sub(config.moderator_count, 1)?;
+    }
+    let mut store = storage();
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("ModeratorUpdated", account: args.account, enabled: args.enabled);
+    if args.enabled {
+        admin_log::record(admin_log::GRANT_ROLE, &args.account, "", MODERATOR_ROLE)?;
+    } else {
+        admin_log::record(admin_log::REVOKE_ROLE, &args.account, MODERATOR_ROLE, "")?;
+    }
+    Ok(())
+}
+
+fn execute_anchor() -> ContractResult<u64> {
+    load_config()?;
+    let ctx = context();
+    let creator = ctx.sender().to_string();
+    let args: AnchorArgs = read_args()?;
+    if args.content_hash == [0u8; 32] {
+        return Err(ContractError::InvalidArgument(
+            "Content hash cannot be zero".to_string(),
+        ));
+    }
+    validate_license(&args.license)?;
+    if hash_index().contains_key(&args.content_hash)? {
+        return Err(ContractError::InvalidArgument(
+            "Content already registered".to_string(),
+        ));
+    }
+
+    let mut store = storage();
+    let id = store.get::<u64>(REGISTRATION_COUNT_KEY)?.unwrap_or(0);
+    let registration = Registration {
+        id,
+        content_hash: args.content_hash,
+        creator: creator.clone(),
+        owner: creator.clone(),
+        anchored_at: ctx.block_timestamp(),
+        block_height: ctx.block_height(),
+        license: args.license,
+        dispute: None,
+    };
+    registrations().set(&id, &registration)?;
+    hash_index().set(&args.content_hash, &id)?;
+    let position = creator_counts().get(&creator)?.unwrap_or(0);
+    creator_index().set(&(creator.clone(), position), &id)?;
+    creator_counts().set(&creator, &safe_math::add(position, 1)?)?;
+    store.set(REGISTRATION_COUNT_KEY, &safe_math::add(id, 1)?)?;
+
+    event!("ContentAnchored",
+        id: id,
+        content_hash: hex::encode(args.content_hash),
+        creator: creator,
+        anchored_at: registration.anchored_at
+    );
+    Ok(id)
+}
+
+fn execute_set_license() -> ContractResult<()> {
+    let args: SetLicenseArgs = read_args()?;
+    let mut registration = load_owned_registration(&args.content_hash)?;
+    validate_license(&args.license)?;
+    registration.license = args.license;
+    registrations().set(&registration.id, &registration)?;
+
+    event!("LicenseUpdated",
+        id: registration.id,
+        license: registration.license.unwrap_or_default()
+    );
+    Ok(())
+}
+
+fn execute_transfer_registration() -> ContractResult<()> {
+    let args: TransferArgs = read_args()?;
+    let mut registration = load_owned_registration(&args.content_hash)?;
+    validation::validate_address(&args.to)?;
+    if args.to == registration.owner {
+        return Err(ContractError::InvalidArgument(
+            "Already the owner".to_string(),
+        ));
+    }
+    let from = core::mem::replace(&mut registration.owner, args.to.clone());
+    registrations().set(&registration.id, &registration)?;
+
+    event!("RegistrationTransferred",
+        id: registration.id,
+        from: from,
+        to: args.to
+    );
+    Ok(())
+}
+
+fn execute_flag_dispute() -> ContractResult<()> {
+    let moderator = require_moderator()?;
+    let args: FlagArgs = read_args()?;
+    validation::validate_non_empty(&args.reason, "reason")?;
+    if args.reason.len() > MAX_REASON_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Reason exceeds {} bytes",
+            MAX_REASON_BYTES
+        )));
+    }
+    let mut registration = load_registration(&args.content_hash)?;
+    if registration.dispute.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Registration already disputed".to_string(),
+        ));
+    }
+    registration.dispute = Some(Dispute {
+        moderator: moderator.clone(),
+        reason: args.reason.clone(),
+        flagged_at: context().block_timestamp(),
+    });
+    registrations().set(&registration.id, &registration)?;
+
+    event!("DisputeFlagged",
+        id: registration.id,
+        moderator: moderator,
+        reason: args.reason
+    );
+    admin_log::record(
+        "flag_dispute",
+        &registration.id.to_string(),
+        "",
+        &args.reason,
+    )?;
+    Ok(())
+}
+
+fn execute_clear_dispute() -> ContractResult<()> {
+    let moderator = require_moderator()?;
+    let args: HashArgs = read_args()?;
+    let mut registration = load_registration(&args.content_hash)?;
+    let dispute = registration.dispute.take().ok_or_else(|| {
+        ContractError::InvalidArgument("Registration is not disputed".to_string())
+    })?;
+    registrations().set(&registration.id, &registration)?;
+
+    event!("DisputeCleared", id: registration.id, moderator: moderator);
+    admin_log::record(
+        "clear_dispute",
+        &registration.id.to_string(),
+        dispute.reason,
+        "",
+    )?;
+    Ok(())
+}
+
+fn execute_registrations_by_creator() -> ContractResult<RegistrationPage> {
+    let args: CreatorPageArgs = read_args()?;
+    let total = creator_counts().get(&args.creator)?.unwrap_or(0);
+    let registry = registrations();
+    let index = creator_index();
+    paginate(total, args.offset, args.limit, |position| {
+        match index.get(&(args.creator.clone(), position))? {
+            Some(id) => registry.get(&id),
+            None => Ok(None),
+        }
+    })
+}
+
+/// Initialize the registry; the caller becomes the owner
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Appoint or remove a moderator (owner only)
+///
+/// # Arguments
+/// * `account` - Moderator address
+/// * `enabled` - Whether the account may flag disputes
+#[unsafe(no_mangle)]
+pub extern "C" fn set_moderator() {
+    if let Err(err) = execute_set_moderator() {
+        log!("set_moderator failed: {}", err);
+    }
+}
+
+/// Anchor a content hash; the caller becomes its creator and owner
+///
+/// # Arguments
+/// * `content_hash` - 32-byte hash of the content
+/// * `license` - Optional licence identifier or terms URI (max 256 bytes)
+///
+/// # Returns
+/// Registration ID (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn anchor() {
+    if let Err(err) = execute_anchor().and_then(|id| try_respond(&id)) {
+        log!("anchor failed: {}", err);
+    }
+}
+
+/// Replace or remove the licensing terms (registration owner only)
+///
+/// # Arguments
+/// * `content_hash` - Registered hash
+/// * `license` - New terms, or `None` to remove them
+#[unsafe(no_mangle)]
+pub extern "C" fn set_license() {
+    if let Err(err) = execute_set_license() {
+        log!("set_license failed: {}", err);
+    }
+}
+
+/// Transfer a registration (registration owner only, not while disputed)
+///
+/// # Arguments
+/// * `content_hash` - Registered hash
+/// * `to` - New owner
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_registration() {
+    if let Err(err) = execute_transfer_registration() {
+        log!("transfer_registration failed: {}", err);
+    }
+}
+
+/// Flag a registration as disputed (moderators only)
+///
+/// # Arguments
+/// * `content_hash` - Registered hash
+/// * `reason` - Why it is disputed (max 256 bytes)
+#[unsafe(no_mangle)]
+pub extern "C" fn flag_dispute() {
+    if let Err(err) = execute_flag_dispute() {
+        log!("flag_dispute failed: {}", err);
+    }
+}
+
+/// Clear a dispute flag (moderators only)
+///
+/// # Arguments
+/// * `content_hash` - Registered hash
+#[unsafe(no_mangle)]
+pub extern "C" fn clear_dispute() {
+    if let Err(err) = execute_clear_dispute() {
+        log!("clear_dispute failed: {}", err);
+    }
+}
+
+/// Look up a content hash
+///
+/// # Arguments
+/// * `content_hash` - Hash to look up
+///
+/// # Returns
+/// `Option<Registration>`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_registration() {
+    let result = read_args::<HashArgs>()
+        .and_then(|args| find_registration(&args.content_hash))
+        .and_then(|registration| try_respond(&registration));
+    if let Err(err) = result {
+        log!("get_registration failed: {}", err);
+    }
+}
+
+/// List the registrations an account anchored
+///
+/// # Arguments
+/// * `creator` - Creator address
+/// * `offset` - Index of the first item
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `RegistrationPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn registrations_by_creator() {
+    if let Err(err) = execute_registrations_by_creator().and_then(|page| try_respond(&page)) {
+        log!("registrations_by_creator failed: {}", err);
+    }
+}
+
+/// Get the number of anchored hashes
+///
+/// # Returns
+/// Count (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn registration_count() {
+    let result = storage()
+        .get::<u64>(REGISTRATION_COUNT_KEY)
+        .and_then(|count| try_respond(&count.unwrap_or(0)));
+    if let Err(err) = result {
+        log!("registration_count failed: {}", err);
+    }
+}
+
+/// Check whether an account is a moderator
+///
+/// # Arguments
+/// * `account` - Address to check
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn is_moderator() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| moderators().get(&args.account))
+        .and_then(|enabled| try_respond(&enabled.unwrap_or(false)));
+    if let Err(err) = result {
+        log!("is_moderator failed: {}", err);
+    }
+}
+
+/// Get the registry configuration
+///
+/// # Returns
+/// `ContentConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_MODERATOR: &str = "0x0000000000000000000000000000000000000d04";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const NOW: u64 = 1_736_000_000;
+    const HASH_A: [u8; 32] = [0xaa; 32];
+    const HASH_B: [u8; 32] = [0xbb; 32];
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        call::<()>(ADDR_OWNER, initialize, Vec::new());
+        call::<()>(
+            ADDR_OWNER,
+            set_moderator,
+            encode(&SetModeratorArgs {
+                account: ADDR_MODERATOR.to_string(),
+                enabled: true,
+            }),
+        );
+    }
+
+    fn anchor_hash(sender: &str, content_hash: [u8; 32], license: Option<&str>) -> Option<u64> {
+        call(
+            sender,
+            anchor,
+            encode(&AnchorArgs {
+                content_hash,
+                license: license.map(str::to_string),
+            }),
+        )
+    }
+
+    fn registration(content_hash: [u8; 32]) -> Registration {
+        call::<Option<Registration>>(
+            ADDR_BOB,
+            get_registration,
+            encode(&HashArgs { content_hash }),
+        )
+        .flatten()
+        .unwrap()
+    }
+
+    fn transfer(sender: &str, content_hash: [u8; 32], to: &str) {
+        call::<()>(
+            sender,
+            transfer_registration,
+            encode(&TransferArgs {
+                content_hash,
+                to: to.to_string(),
+            }),
+        );
+    }
+
+    fn flag(sender: &str, content_hash: [u8; 32]) {
+        call::<()>(
+            sender,
+            flag_dispute,
+            encode(&FlagArgs {
+                content_hash,
+                reason: "Prior publication".to_string(),
+            }),
+        );
+    }
+
+    #[test]
+    fn first_anchor_of_a_hash_wins() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::set_block_height(42);
+        assert_eq!(anchor_hash(ADDR_ALICE, HASH_A, Some("CC-BY-4.0")), Some(0));
+        assert_eq!(anchor_hash(ADDR_BOB, HASH_A, None), None);
+        assert_eq!(anchor_hash(ADDR_BOB, [0; 32], None), None);
+
+        let anchored = registration(HASH_A);
+        assert_eq!(
+            (anchored.creator.as_str(), anchored.owner.as_str()),
+            (ADDR_ALICE, ADDR_ALICE)
+        );
+        assert_eq!((anchored.anchored_at, anchored.block_height), (NOW, 42));
+        assert_eq!(anchored.license.as_deref(), Some("CC-BY-4.0"));
+        assert_eq!(
+            call::<Option<Registration>>(
+                ADDR_BOB,
+                get_registration,
+                encode(&HashArgs {
+                    content_hash: HASH_B
+                })
+            ),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn owners_manage_licence_terms() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        anchor_hash(ADDR_ALICE, HASH_A, None);
+        let set = |sender: &str, license: Option<String>| {
+            call::<()>(
+                sender,
+                set_license,
+                encode(&SetLicenseArgs {
+                    content_hash: HASH_A,
+                    license,
+                }),
+            )
+        };
+        set(ADDR_BOB, Some("MIT".to_string()));
+        assert_eq!(registration(HASH_A).license, None);
+        set(ADDR_ALICE, Some("x".repeat(MAX_LICENSE_BYTES + 1)));
+        assert_eq!(registration(HASH_A).license, None);
+        set(ADDR_ALICE, Some("ipfs://terms".to_string()));
+        assert_eq!(
+            registration(HASH_A).license.as_deref(),
+            Some("ipfs://terms")
+        );
+        set(ADDR_ALICE, None);
+        assert_eq!(registration(HASH_A).license, None);
+    }
+
+    #[test]
+    fn transfers_change_owner_but_keep_creator() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        anchor_hash(ADDR_ALICE, HASH_A, None);
+        transfer(ADDR_BOB, HASH_A, ADDR_BOB);
+        assert_eq!(registration(HASH_A).owner, ADDR_ALICE);
+        transfer(ADDR_ALICE, HASH_A, ADDR_BOB);
+        let moved = registration(HASH_A);
+        assert_eq!(
+            (moved.creator.as_str(), moved.owner.as_str()),
+            (ADDR_ALICE, ADDR_BOB)
+        );
+        transfer(ADDR_ALICE, HASH_A, ADDR_ALICE);
+        assert_eq!(registration(HASH_A).owner, ADDR_BOB);
+    }
+
+    #[test]
+    fn disputes_freeze_registrations_until_cleared() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        anchor_hash(ADDR_ALICE, HASH_A, None);
+        flag(ADDR_BOB, HASH_A);
+        assert_eq!(registration(HASH_A).dispute, None);
+
+        mock::set_block_timestamp(NOW + 60);
+        flag(ADDR_MODERATOR, HASH_A);
+        let dispute = registration(HASH_A).dispute.unwrap();
+        assert_eq!(
+            (dispute.moderator.as_str(), dispute.flagged_at),
+            (ADDR_MODERATOR, NOW + 60)
+        );
+        transfer(ADDR_ALICE, HASH_A, ADDR_BOB);
+        assert_eq!(registration(HASH_A).owner, ADDR_ALICE);
+
+        call::<()>(
+            ADDR_MODERATOR,
+            clear_dispute,
+            encode(&HashArgs {
+                content_hash: HASH_A,
+            }),
+        );
+        assert_eq!(registration(HASH_A).dispute, None);
+        transfer(ADDR_ALICE, HASH_A, ADDR_BOB);
+        assert_eq!(registration(HASH_A).owner, ADDR_BOB);
+
+        call::<()>(
+            ADDR_OWNER,
+            set_moderator,
+            encode(&SetModeratorArgs {
+                account: ADDR_MODERATOR.to_string(),
+                enabled: false,
+            }),
+        );
+        flag(ADDR_MODERATOR, HASH_A);
+        assert_eq!(registration(HASH_A).dispute, None);
+        assert_eq!(load_config().unwrap().moderator_count, 0);
+        // Grant, flag, clear and revoke; Bob's attempt is not logged.
+        assert_eq!(admin_log::next_sequence().unwrap(), 4);
+    }
+
+    #[test]
+    fn registrations_page_by_creator() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        for byte in 1..=3u8 {
+            anchor_hash(ADDR_ALICE, [byte; 32], None);
+        }
+        anchor_hash(ADDR_BOB, HASH_B, None);
+        let page = |offset, limit| {
+            call::<RegistrationPage>(
+                ADDR_BOB,
+                registrations_by_creator,
+                encode(&CreatorPageArgs {
+                    creator: ADDR_ALICE.to_string(),
+                    offset,
+                    limit,
+                }),
+            )
+        };
+        let first = page(0, 2).unwrap();
+        assert_eq!(
+            first.items.iter().map(|r| r.id).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!((first.total, first.next_offset), (3, Some(2)));
+        let last = page(2, 2).unwrap();
+        assert_eq!((last.items.len(), last.next_offset), (1, None));
+        assert_eq!(page(0, 0), None);
+        assert_eq!(
+            call::<u64>(ADDR_BOB, registration_count, Vec::new()),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "content-registry-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,294 @@
+//! Entrypoint code generation
+//!
+//! Every contract entrypoint used to be two hand-written functions: an
+//! `execute_*` body that decoded its call data, did the work and wrote the
+//! result back, and an `extern "C"` wrapper that reported failures. The
+//! [`macro@entrypoint`] attribute writes both from one ordinary function:
+//!
+//! ```rust,ignore
+//! use contract_macros::entrypoint;
+//!
+//! /// Query balance of an account
+//! #[entrypoint]
+//! fn balance_of(args: BalanceOfArgs) -> ContractResult<u64> {
+//!     read_balance(&args.account)
+//! }
+//! ```
+//!
+//! expands to
+//!
+//! ```rust,ignore
+//! fn execute_balance_of() -> ContractResult<u64> {
+//!     fn balance_of(args: BalanceOfArgs) -> ContractResult<u64> { /* body */ }
+//!     let args = silica_contract_sdk::entrypoint::read_args()?;
+//!     let value = balance_of(args)?;
+//!     silica_contract_sdk::entrypoint::try_respond(&value)?;
+//!     Ok(value)
+//! }
+//!
+//! /// Query balance of an account
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn balance_of() -> u64 {
+//!     match execute_balance_of() {
+//!         Ok(value) => value,
+//!         Err(err) => {
+//!             crc_errors::report("balance_of", &err);
+//!             0
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! * The function takes no argument, or one postcard-decoded argument struct.
+//! * It returns `ContractResult<T>`. Unless `T` is `()`, the value is written
+//!   as return data; integers are also returned directly, `0` on failure.
+//! * Failures go through `crc_errors::report`, which logs them and writes a
+//!   decodable error payload.
+//! * On wasm32 each entrypoint adds a line such as
+//!   `balance_of(BalanceOfArgs) -> u64` to the `silica_abi` custom section,
+//!   so tools can list a binary's interface without its sources.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{ToTokens, format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{FnArg, GenericArgument, ItemFn, LitByteStr, PathArguments, ReturnType, Type};
+
+/// Custom section collecting the interface lines of a wasm binary
+const ABI_SECTION: &str = "silica_abi";
+
+/// Scalars handed back as the wasm return value as well as return data
+const NATIVE_RETURNS: &[&str] = &["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+
+/// Turn a function into a contract entrypoint of the same name
+///
+/// See the [crate documentation](crate) for the generated code.
+#[proc_macro_attribute]
+pub fn entrypoint(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(attr.into(), item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new(
+            attr.span(),
+            "#[entrypoint] takes no options",
+        ));
+    }
+    let item: ItemFn = syn::parse2(item)?;
+    let sig = &item.sig;
+    if sig.asyncness.is_some() || !sig.generics.params.is_empty() || sig.abi.is_some() {
+        return Err(syn::Error::new(
+            sig.span(),
+            "entrypoints are plain, non-generic functions",
+        ));
+    }
+    let arg_type = match sig.inputs.len() {
+        0 => None,
+        1 => match &sig.inputs[0] {
+            FnArg::Typed(arg) => Some(&*arg.ty),
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "entrypoints cannot take `self`",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                sig.inputs.span(),
+                "entrypoints take at most one argument struct",
+            ));
+        }
+    };
+    let value_type = result_value(&sig.output)?;
+
+    let name = &sig.ident;
+    let execute = format_ident!("execute_{}", name);
+    let vis = &item.vis;
+    let (docs, attrs): (Vec<_>, Vec<_>) = item
+        .attrs
+        .iter()
+        .partition(|attr| attr.path().is_ident("doc"));
+    let inner = ItemFn {
+        attrs: attrs.into_iter().cloned().collect(),
+        vis: syn::Visibility::Inherited,
+        sig: sig.clone(),
+        block: item.block.clone(),
+    };
+
+    let (decode, call) = match arg_type {
+        Some(_) => (
+            Some(quote! { let args = ::silica_contract_sdk::entrypoint::read_args()?; }),
+            quote! { #name(args)? },
+        ),
+        None => (None, quote! { #name()? }),
+    };
+    let finish = if is_unit(value_type) {
+        quote! {
+            #call;
+            Ok(())
+        }
+    } else {
+        quote! {
+            let value = #call;
+            ::silica_contract_sdk::entrypoint::try_respond(&value)?;
+            Ok(value)
+        }
+    };
+
+    let label = name.to_string();
+    let abi = abi_static(&label, arg_type, value_type);
+    let native = NATIVE_RETURNS.contains(&type_name(value_type).as_str());
+    let wrapper = if native {
+        quote! {
+            pub extern "C" fn #name() -> #value_type {
+                #abi
+                match #execute() {
+                    Ok(value) => value,
+                    Err(err) => {
+                        ::crc_errors::report(#label, &err);
+                        0
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {
+            pub extern "C" fn #name() {
+                #abi
+                if let Err(err) = #execute() {
+                    ::crc_errors::report(#label, &err);
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #vis fn #execute() -> ::silica_contract_sdk::error::ContractResult<#value_type> {
+            #inner
+            #decode
+            #finish
+        }
+
+        #(#docs)*
+        #[unsafe(no_mangle)]
+        #wrapper
+    })
+}
+
+/// `#[used]` static placing the entrypoint's interface line in the custom
+/// section; only wasm32 builds keep custom sections
+fn abi_static(name: &str, arg_type: Option<&Type>, value_type: &Type) -> TokenStream2 {
+    let line = abi_line(name, arg_type, value_type);
+    let len = line.len();
+    let bytes = LitByteStr::new(line.as_bytes(), Span::call_site());
+    quote! {
+        #[cfg(target_arch = "wasm32")]
+        #[used]
+        #[unsafe(link_section = #ABI_SECTION)]
+        static ABI: [u8; #len] = *#bytes;
+    }
+}
+
+/// `name(Args) -> Returns`, one per line
+fn abi_line(name: &str, arg_type: Option<&Type>, value_type: &Type) -> String {
+    let args = arg_type.map(type_name).unwrap_or_default();
+    if is_unit(value_type) {
+        format!("{}({})\n", name, args)
+    } else {
+        format!("{}({}) -> {}\n", name, args, type_name(value_type))
+    }
+}
+
+/// `T` of a `ContractResult<T>` or `Result<T, _>` return type
+fn result_value(output: &ReturnType) -> syn::Result<&Type> {
+    let expected = "entrypoints return `ContractResult<T>`";
+    let ReturnType::Type(_, ty) = output else {
+        return Err(syn::Error::new(output.span(), expected));
+    };
+    let Type::Path(path) = &**ty else {
+        return Err(syn::Error::new(ty.span(), expected));
+    };
+    let segment = path.path.segments.last();
+    let value = segment
+        .filter(|segment| segment.ident == "ContractResult" || segment.ident == "Result")
+        .and_then(|segment| match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args.args.first(),
+            _ => None,
+        });
+    match value {
+        Some(GenericArgument::Type(value)) => Ok(value),
+        _ => Err(syn::Error::new(ty.span(), expected)),
+    }
+}
+
+fn is_unit(ty: &Type) -> bool {
+    matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
+/// Render a type the way it is written in source
+fn type_name(ty: &Type) -> String {
+    const PUNCT: &str = "<>:&()[];,";
+    let raw = ty.to_token_stream().to_string();
+    let chars: Vec<char> = raw.chars().collect();
+    let mut out = String::with_capacity(raw.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied().unwrap_or(' ');
+            if prev != ',' && prev != ';' && (PUNCT.contains(prev) || PUNCT.contains(next)) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_str(item: &str) -> syn::Result<String> {
+        expand(TokenStream2::new(), item.parse().unwrap()).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn generates_wrapper_and_interface_line() {
+        let expanded = expand_str(
+            "/// Query a balance\nfn balance_of(args: BalanceOfArgs) -> ContractResult<u64> { read_balance(&args.account) }",
+        )
+        .unwrap();
+        assert!(expanded.contains("fn execute_balance_of ()"));
+        assert!(expanded.contains("pub extern \"C\" fn balance_of () -> u64"));
+        assert!(expanded.contains("report (\"balance_of\" , & err)"));
+        assert!(expanded.contains("b\"balance_of(BalanceOfArgs) -> u64\\n\""));
+
+        let expanded =
+            expand_str("fn owner() -> ContractResult<Option<String>> { ownable::owner() }")
+                .unwrap();
+        assert!(expanded.contains("pub extern \"C\" fn owner () {"));
+        assert!(expanded.contains("b\"owner() -> Option<String>\\n\""));
+    }
+
+    #[test]
+    fn rejects_unsupported_signatures() {
+        let message = |item: &str| expand_str(item).unwrap_err().to_string();
+        assert_eq!(
+            message("fn transfer(to: String, amount: u64) -> ContractResult<()> { Ok(()) }"),
+            "entrypoints take at most one argument struct"
+        );
+        assert_eq!(
+            message("fn pause() { }"),
+            "entrypoints return `ContractResult<T>`"
+        );
+        assert_eq!(
+            message("fn get<T>() -> ContractResult<T> { todo!() }"),
+            "entrypoints are plain, non-generic functions"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{invoke, query};
+use crc_standards::crc1155::{
+    BalanceOfArgs, BalanceOfBatchArgs, Crc1155, IsApprovedForAllArgs, SafeBatchTransferFromArgs,
+    SafeTransferFromArgs, SetApprovalForAllArgs, UriArgs, methods,
+};
+use silica_contract_sdk::prelude::*;
+
+/// Client for a deployed CRC-1155 multi-token
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crc1155Client {
+    address: String,
+}
+
+impl Crc1155Client {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Crc1155 for Crc1155Client {
+    fn uri(&self, id: u64) -> ContractResult<String> {
+        query(&self.address, methods::URI, &UriArgs { id })
+    }
+
+    fn balance_of(&self, account: &str, id: u64) -> ContractResult<u64> {
+        let args = BalanceOfArgs {
+            account: account.to_string(),
+            id,
+        };
+        query(&self.address, methods::BALANCE_OF, &args)
+    }
+
+    fn balance_of_batch(&self, accounts: &[String], ids: &[u64]) -> ContractResult<Vec<u64>> {
+        let args = BalanceOfBatchArgs {
+            accounts: accounts.to_vec(),
+            ids: ids.to_vec(),
+        };
+        query(&self.address, methods::BALANCE_OF_BATCH, &args)
+    }
+
+    fn is_approved_for_all(&self, account: &str, operator: &str) -> ContractResult<bool> {
+        let args = IsApprovedForAllArgs {
+            account: account.to_string(),
+            operator: operator.to_string(),
+        };
+        query(&self.address, methods::IS_APPROVED_FOR_ALL, &args)
+    }
+
+    fn set_approval_for_all(&self, operator: &str, approved: bool) -> ContractResult<()> {
+        let args = SetApprovalForAllArgs {
+            operator: operator.to_string(),
+            approved,
+        };
+        invoke(&self.address, methods::SET_APPROVAL_FOR_ALL, &args)
+    }
+
+    fn safe_transfer_from(
+        &self,
+        from: &str,
+        to: &str,
+        id: u64,
+        amount: u64,
+        data: &[u8],
+    ) -> ContractResult<()> {
+        let args = SafeTransferFromArgs {
+            from: from.to_string(),
+            to: to.to_string(),
+            id,
+            amount,
+            data: data.to_vec(),
+        };
+        invoke(&self.address, methods::SAFE_TRANSFER_FROM, &args)
+    }
+
+    fn safe_batch_transfer_from(
+        &self,
+        from: &str,
+        to: &str,
+        ids: &[u64],
+        amounts: &[u64],
+        data: &[u8],
+    ) -> ContractResult<()> {
+        let args = SafeBatchTransferFromArgs {
+            from: from.to_string(),
+            to: to.to_string(),
+            ids: ids.to_vec(),
+            amounts: amounts.to_vec(),
+            data: data.to_vec(),
+        };
+        invoke(&self.address, methods::SAFE_BATCH_TRANSFER_FROM, &args)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{invoke, query, query_bare};
+use crc_standards::Address;
+use crc_standards::crc20::{
+    AccountOverview, AccountOverviewArgs, AggregateViewsArgs, AllowanceArgs, ApproveArgs,
+    BalanceOfArgs, BurnArgs, Crc20, TransferArgs, TransferFromArgs, ViewRequest, ViewResult,
+    methods,
+};
+use silica_contract_sdk::prelude::*;
+
+/// Client for a deployed CRC-20 token
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crc20Client {
+    address: String,
+}
+
+impl Crc20Client {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Several queries in one call; needs the `aggregate_views` extension
+    pub fn aggregate_views(&self, requests: Vec<ViewRequest>) -> ContractResult<Vec<ViewResult>> {
+        let args = AggregateViewsArgs { requests };
+        query(&self.address, methods::AGGREGATE_VIEWS, &args)
+    }
+
+    /// Balance and allowances of `account`; needs the `account_overview`
+    /// extension
+    pub fn account_overview(
+        &self,
+        account: &str,
+        spenders: &[&str],
+    ) -> ContractResult<AccountOverview> {
+        let args = AccountOverviewArgs {
+            account: Address::parse(account)?,
+            spenders: spenders
+                .iter()
+                .map(|spender| Address::parse(spender))
+                .collect::<ContractResult<_>>()?,
+        };
+        query(&self.address, methods::ACCOUNT_OVERVIEW, &args)
+    }
+
+    /// Destroy `amount` of the caller's tokens; needs the `burn` extension
+    pub fn burn(&self, amount: u64) -> ContractResult<()> {
+        invoke(&self.address, methods::BURN, &BurnArgs { amount })
+    }
+}
+
+impl Crc20 for Crc20Client {
+    fn name(&self) -> ContractResult<String> {
+        query_bare(&self.address, methods::NAME)
+    }
+
+    fn symbol(&self) -> ContractResult<String> {
+        query_bare(&self.address, methods::SYMBOL)
+    }
+
+    fn decimals(&self) -> ContractResult<u8> {
+        query_bare(&self.address, methods::DECIMALS)
+    }
+
+    fn total_supply(&self) -> ContractResult<u64> {
+        query_bare(&self.address, methods::TOTAL_SUPPLY)
+    }
+
+    fn balance_of(&self, account: &str) -> ContractResult<u64> {
+        let args = BalanceOfArgs {
+            account: Address::parse(account)?,
+        };
+        query(&self.address, methods::BALANCE_OF, &args)
+    }
+
+    fn allowance(&self, owner: &str, spender: &str) -> ContractResult<u64> {
+        let args = AllowanceArgs {
+            owner: Address::parse(owner)?,
+            spender: Address::parse(spender)?,
+        };
+        query(&self.address, methods::ALLOWANCE, &args)
+    }
+
+    fn transfer(&self, to: &str, amount: u64) -> ContractResult<()> {
+        let args = TransferArgs {
+            to: Address::parse(to)?,
+            amount,
+        };
+        invoke(&self.address, methods::TRANSFER, &args)
+    }
+
+    fn approve(&self, spender: &str, amount: u64) -> ContractResult<()> {
+        let args = ApproveArgs {
+            spender: Address::parse(spender)?,
+            amount,
+        };
+        invoke(&self.address, methods::APPROVE, &args)
+    }
+
+    fn transfer_from(&self, from: &str, to: &str, amount: u64) -> ContractResult<()> {
+        let args = TransferFromArgs {
+            from: Address::parse(from)?,
+            to: Address::parse(to)?,
+            amount,
+        };
+        invoke(&self.address, methods::TRANSFER_FROM, &args)
+    }
+}
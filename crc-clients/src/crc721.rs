@@ -0,0 +1,147 @@
+use crate::{invoke, query, query_bare};
+use crc_standards::Address;
+use crc_standards::crc721::{
+    AggregateViewsArgs, ApproveArgs, BalanceOfArgs, Crc721, IsApprovedForAllArgs,
+    SafeTransferFromArgs, SetApprovalForAllArgs, TokenIdArgs, TransferFromArgs,
+    TransferFromWithMemoArgs, TransferRecord, ViewRequest, ViewResult, methods,
+};
+use silica_contract_sdk::bounded::Memo;
+use silica_contract_sdk::prelude::*;
+
+/// Client for a deployed CRC-721 collection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crc721Client {
+    address: String,
+}
+
+impl Crc721Client {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Several queries in one call; needs the `aggregate_views` extension
+    pub fn aggregate_views(&self, requests: Vec<ViewRequest>) -> ContractResult<Vec<ViewResult>> {
+        let args = AggregateViewsArgs { requests };
+        query(&self.address, methods::AGGREGATE_VIEWS, &args)
+    }
+
+    /// Recent transfers of `token_id`, oldest first; needs the
+    /// `transfer_history` extension
+    pub fn transfer_history(&self, token_id: u64) -> ContractResult<Vec<TransferRecord>> {
+        query(
+            &self.address,
+            methods::TRANSFER_HISTORY,
+            &TokenIdArgs { token_id },
+        )
+    }
+
+    /// `transfer_from_with_memo`: a note of at most 128 bytes for the
+    /// Transfer event
+    pub fn transfer_from_with_memo(
+        &self,
+        from: &str,
+        to: &str,
+        token_id: u64,
+        memo: &str,
+    ) -> ContractResult<()> {
+        let args = TransferFromWithMemoArgs {
+            from: Address::parse(from)?,
+            to: Address::parse(to)?,
+            token_id,
+            memo: Memo::new(memo)?,
+        };
+        invoke(&self.address, methods::TRANSFER_FROM_WITH_MEMO, &args)
+    }
+}
+
+impl Crc721 for Crc721Client {
+    fn name(&self) -> ContractResult<String> {
+        query_bare(&self.address, methods::NAME)
+    }
+
+    fn symbol(&self) -> ContractResult<String> {
+        query_bare(&self.address, methods::SYMBOL)
+    }
+
+    fn total_supply(&self) -> ContractResult<u64> {
+        query_bare(&self.address, methods::TOTAL_SUPPLY)
+    }
+
+    fn balance_of(&self, owner: &str) -> ContractResult<u64> {
+        let args = BalanceOfArgs {
+            owner: Address::parse(owner)?,
+        };
+        query(&self.address, methods::BALANCE_OF, &args)
+    }
+
+    fn owner_of(&self, token_id: u64) -> ContractResult<String> {
+        query(&self.address, methods::OWNER_OF, &TokenIdArgs { token_id })
+    }
+
+    fn token_uri(&self, token_id: u64) -> ContractResult<String> {
+        query(&self.address, methods::TOKEN_URI, &TokenIdArgs { token_id })
+    }
+
+    fn get_approved(&self, token_id: u64) -> ContractResult<Option<String>> {
+        query(
+            &self.address,
+            methods::GET_APPROVED,
+            &TokenIdArgs { token_id },
+        )
+    }
+
+    fn is_approved_for_all(&self, owner: &str, operator: &str) -> ContractResult<bool> {
+        let args = IsApprovedForAllArgs {
+            owner: Address::parse(owner)?,
+            operator: Address::parse(operator)?,
+        };
+        query(&self.address, methods::IS_APPROVED_FOR_ALL, &args)
+    }
+
+    fn approve(&self, to: &str, token_id: u64) -> ContractResult<()> {
+        let args = ApproveArgs {
+            to: Address::parse(to)?,
+            token_id,
+        };
+        invoke(&self.address, methods::APPROVE, &args)
+    }
+
+    fn set_approval_for_all(&self, operator: &str, approved: bool) -> ContractResult<()> {
+        let args = SetApprovalForAllArgs {
+            operator: Address::parse(operator)?,
+            approved,
+        };
+        invoke(&self.address, methods::SET_APPROVAL_FOR_ALL, &args)
+    }
+
+    fn transfer_from(&self, from: &str, to: &str, token_id: u64) -> ContractResult<()> {
+        let args = TransferFromArgs {
+            from: Address::parse(from)?,
+            to: Address::parse(to)?,
+            token_id,
+        };
+        invoke(&self.address, methods::TRANSFER_FROM, &args)
+    }
+
+    fn safe_transfer_from(
+        &self,
+        from: &str,
+        to: &str,
+        token_id: u64,
+        data: &[u8],
+    ) -> ContractResult<()> {
+        let args = SafeTransferFromArgs {
+            from: Address::parse(from)?,
+            to: Address::parse(to)?,
+            token_id,
+            data: data.to_vec(),
+        };
+        invoke(&self.address, methods::SAFE_TRANSFER_FROM, &args)
+    }
+}
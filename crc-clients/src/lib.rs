@@ -0,0 +1,232 @@
+//! Typed cross-contract clients
+//!
+//! Clients for the standards in `crc_standards`. Each one encodes the call
+//! arguments, makes the cross-contract call and decodes the return data.
+//! Failures come back as `ContractError`s that name the contract and
+//! method, so contracts stop hand-rolling the same
+//! encode/call/decode sequence for every token they touch. Address
+//! arguments are parsed as `Address`es first; a malformed one fails with
+//! `InvalidArgument` without making the call.
+//!
+//! Each client implements the matching `crc_standards` trait, re-exported
+//! here, so bring the trait into scope to call its methods.
+//!
+//! ## Embedding
+//! ```toml
+//! crc-clients = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use crc_clients::{Crc20, Crc20Client};
+//!
+//! let token = Crc20Client::new(&config.token);
+//! token.transfer_from(ctx.sender(), ctx.contract_address(), amount)?;
+//! let held = token.balance_of(ctx.contract_address())?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+mod crc1155;
+mod crc20;
+mod crc721;
+mod ownable;
+mod pausable;
+pub mod safe_transfer;
+mod version;
+
+pub use crate::crc20::Crc20Client;
+pub use crate::crc721::Crc721Client;
+pub use crate::crc1155::Crc1155Client;
+pub use crate::ownable::OwnableClient;
+pub use crate::pausable::PausableClient;
+pub use crate::version::VersionedClient;
+pub use crc_standards::crc20::Crc20;
+pub use crc_standards::crc721::Crc721;
+pub use crc_standards::crc1155::Crc1155;
+pub use crc_standards::ownable::Ownable;
+pub use crc_standards::pausable::Pausable;
+pub use crc_standards::version::Versioned;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::prelude::*;
+
+/// Call `method` on `address` with postcard-encoded `args`, ignoring any return data.
+pub fn invoke<A: Serialize>(address: &str, method: &str, args: &A) -> ContractResult<()> {
+    let call = postcard::to_allocvec(args).map_err(|_| ContractError::SerializationFailed)?;
+    call_raw(address, method, &call).map(|_| ())
+}
+
+/// Call `method` on `address` with postcard-encoded `args` and decode the result.
+pub fn query<A, R>(address: &str, method: &str, args: &A) -> ContractResult<R>
+where
+    A: Serialize,
+    R: DeserializeOwned,
+{
+    let call = postcard::to_allocvec(args).map_err(|_| ContractError::SerializationFailed)?;
+    decode(address, method, &call_raw(address, method, &call)?)
+}
+
+/// Call an argument-less `method` on `address` and decode the result.
+pub fn query_bare<R: DeserializeOwned>(address: &str, method: &str) -> ContractResult<R> {
+    decode(address, method, &call_raw(address, method, &[])?)
+}
+
+/// Call an argument-less `method` on `address`, ignoring any return data.
+pub fn invoke_bare(address: &str, method: &str) -> ContractResult<()> {
+    call_raw(address, method, &[]).map(|_| ())
+}
+
+fn call_raw(address: &str, method: &str, call: &[u8]) -> ContractResult<Vec<u8>> {
+    context()
+        .call_contract(address, method, call)
+        .map_err(|err| match err {
+            // Keep the callee's own error when it already says what went wrong.
+            ContractError::ContractCallFailed(reason) => {
+                ContractError::ContractCallFailed(format!("{}::{}: {}", address, method, reason))
+            }
+            other => {
+                ContractError::ContractCallFailed(format!("{}::{}: {}", address, method, other))
+            }
+        })
+}
+
+/// Entrypoints that fail only log, so an empty response is a failed query.
+fn decode<R: DeserializeOwned>(address: &str, method: &str, data: &[u8]) -> ContractResult<R> {
+    if data.is_empty() {
+        return Err(ContractError::ContractCallFailed(format!(
+            "{}::{} returned no data",
+            address, method
+        )));
+    }
+    postcard::from_bytes(data).map_err(|_| ContractError::DeserializationFailed)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::crc20::TransferFromArgs;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    pub(crate) const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    pub(crate) const ADDR_BOB: &str = "0x0000000000000000000000000000000000000b02";
+    pub(crate) const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    pub(crate) const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+
+    pub(crate) fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    pub(crate) fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_sender(ADDR_ALICE);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+    }
+
+    #[test]
+    fn calls_encode_standard_arguments() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        Crc20Client::new(TOKEN)
+            .transfer_from(ADDR_ALICE, ADDR_BOB, 250)
+            .unwrap();
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(
+            (calls[0].address.as_str(), calls[0].method.as_str()),
+            (TOKEN, "transfer_from")
+        );
+        let args: TransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(
+            args,
+            TransferFromArgs {
+                from: ADDR_ALICE.parse().unwrap(),
+                to: ADDR_BOB.parse().unwrap(),
+                amount: 250,
+            }
+        );
+
+        // Malformed addresses never reach the token.
+        assert!(matches!(
+            Crc20Client::new(TOKEN).transfer("bob", 1),
+            Err(ContractError::InvalidArgument(_))
+        ));
+        assert!(mock::take_contract_calls().is_empty());
+    }
+
+    #[test]
+    fn queries_decode_return_data() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::register_contract(TOKEN, "balance_of", |_| {
+            Ok(postcard::to_allocvec(&1_000u64).unwrap())
+        });
+        mock::register_contract(TOKEN, "symbol", |_| {
+            Ok(postcard::to_allocvec("SIL").unwrap())
+        });
+        let token = Crc20Client::new(TOKEN);
+        assert_eq!(token.balance_of(ADDR_ALICE).unwrap(), 1_000);
+        assert_eq!(token.symbol().unwrap(), "SIL");
+        assert_eq!(mock::take_contract_calls()[1].args, Vec::<u8>::new());
+    }
+
+    #[test]
+    fn failures_name_the_contract_and_method() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::register_contract(TOKEN, "transfer", |_| Err(ContractError::Unauthorized));
+        mock::register_contract(TOKEN, "total_supply", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "name", |_| Ok(vec![0xff]));
+        let token = Crc20Client::new(TOKEN);
+
+        match token.transfer(ADDR_BOB, 1) {
+            Err(ContractError::ContractCallFailed(reason)) => {
+                assert!(reason.starts_with(&format!("{}::transfer", TOKEN)));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            token.total_supply(),
+            Err(ContractError::ContractCallFailed(reason)) if reason.ends_with("returned no data")
+        ));
+        assert!(matches!(
+            token.name(),
+            Err(ContractError::DeserializationFailed)
+        ));
+    }
+
+    #[test]
+    fn nft_and_admin_clients_use_standard_methods() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::register_contract(TOKEN, "owner_of", |_| {
+            Ok(postcard::to_allocvec(ADDR_ALICE).unwrap())
+        });
+        mock::register_contract(TOKEN, "paused", |_| {
+            Ok(postcard::to_allocvec(&true).unwrap())
+        });
+        mock::register_contract(TOKEN, "owner", |_| {
+            Ok(postcard::to_allocvec(&None::<String>).unwrap())
+        });
+        mock::register_contract(TOKEN, "balance_of_batch", |_| {
+            Ok(postcard::to_allocvec(&vec![3u64, 0]).unwrap())
+        });
+
+        assert_eq!(Crc721Client::new(TOKEN).owner_of(7).unwrap(), ADDR_ALICE);
+        assert!(PausableClient::new(TOKEN).paused().unwrap());
+        assert_eq!(OwnableClient::new(TOKEN).owner().unwrap(), None);
+        let accounts = vec![ADDR_ALICE.to_string(), ADDR_BOB.to_string()];
+        assert_eq!(
+            Crc1155Client::new(TOKEN)
+                .balance_of_batch(&accounts, &[1, 1])
+                .unwrap(),
+            vec![3, 0]
+        );
+    }
+}
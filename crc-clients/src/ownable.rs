@@ -0,0 +1,46 @@
+use crate::{invoke, invoke_bare, query_bare};
+use crc_standards::ownable::{Ownable, TransferOwnershipArgs, methods};
+use silica_contract_sdk::prelude::*;
+
+/// Client for a contract implementing Ownable
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnableClient {
+    address: String,
+}
+
+impl OwnableClient {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Ownable for OwnableClient {
+    fn owner(&self) -> ContractResult<Option<String>> {
+        query_bare(&self.address, methods::OWNER)
+    }
+
+    fn pending_owner(&self) -> ContractResult<Option<String>> {
+        query_bare(&self.address, methods::PENDING_OWNER)
+    }
+
+    fn transfer_ownership(&self, new_owner: &str) -> ContractResult<()> {
+        let args = TransferOwnershipArgs {
+            new_owner: new_owner.to_string(),
+        };
+        invoke(&self.address, methods::TRANSFER_OWNERSHIP, &args)
+    }
+
+    fn accept_ownership(&self) -> ContractResult<()> {
+        invoke_bare(&self.address, methods::ACCEPT_OWNERSHIP)
+    }
+
+    fn renounce_ownership(&self) -> ContractResult<()> {
+        invoke_bare(&self.address, methods::RENOUNCE_OWNERSHIP)
+    }
+}
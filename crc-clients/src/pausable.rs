@@ -0,0 +1,35 @@
+use crate::{invoke_bare, query_bare};
+use crc_standards::pausable::{Pausable, methods};
+use silica_contract_sdk::prelude::*;
+
+/// Client for a contract implementing Pausable
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PausableClient {
+    address: String,
+}
+
+impl PausableClient {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Pausable for PausableClient {
+    fn paused(&self) -> ContractResult<bool> {
+        query_bare(&self.address, methods::PAUSED)
+    }
+
+    fn pause(&self) -> ContractResult<()> {
+        invoke_bare(&self.address, methods::PAUSE)
+    }
+
+    fn unpause(&self) -> ContractResult<()> {
+        invoke_bare(&self.address, methods::UNPAUSE)
+    }
+}
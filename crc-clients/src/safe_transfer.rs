@@ -0,0 +1,286 @@
+//! Checked CRC-20 transfers
+//!
+//! Wrappers around `transfer` and `transfer_from` that refuse to treat a
+//! doubtful call as a success. The token must be a contract. A token that
+//! answers with return data must answer `true`. Pulls into this contract
+//! can be measured by balance delta, so fee-on-transfer tokens are credited
+//! with what actually arrived.
+//!
+//! ```rust,ignore
+//! use crc_clients::safe_transfer;
+//!
+//! let received = safe_transfer::transfer_in(&config.token, ctx.sender(), amount)?;
+//! safe_transfer::safe_transfer(&config.token, &recipient, payout)?;
+//! ```
+
+use crate::{Crc20, Crc20Client};
+use core::fmt;
+use crc_standards::Address;
+use crc_standards::crc20::{TransferArgs, TransferFromArgs, methods};
+use silica_contract_sdk::prelude::*;
+
+/// Why a checked transfer failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SafeTransferError {
+    /// The token address has no contract behind it
+    NotAContract { token: String },
+    /// The token call failed
+    CallFailed {
+        token: String,
+        method: String,
+        reason: String,
+    },
+    /// The token answered with something other than `true`
+    Rejected { token: String, method: String },
+    /// A balance query failed while measuring the transfer
+    BalanceUnavailable { token: String, reason: String },
+    /// Less arrived than was sent, e.g. a fee-on-transfer token
+    Shortfall { expected: u64, received: u64 },
+}
+
+impl fmt::Display for SafeTransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAContract { token } => write!(f, "{} is not a contract", token),
+            Self::CallFailed {
+                token,
+                method,
+                reason,
+            } => write!(f, "{}::{} failed: {}", token, method, reason),
+            Self::Rejected { token, method } => write!(f, "{}::{} returned false", token, method),
+            Self::BalanceUnavailable { token, reason } => {
+                write!(f, "{}::balance_of failed: {}", token, reason)
+            }
+            Self::Shortfall { expected, received } => {
+                write!(f, "Expected {} tokens, received {}", expected, received)
+            }
+        }
+    }
+}
+
+impl From<SafeTransferError> for ContractError {
+    fn from(err: SafeTransferError) -> Self {
+        match err {
+            SafeTransferError::Rejected { .. } => ContractError::TransferFailed,
+            SafeTransferError::Shortfall { expected, received } => {
+                ContractError::InsufficientBalance {
+                    required: expected,
+                    available: received,
+                }
+            }
+            other => ContractError::ContractCallFailed(other.to_string()),
+        }
+    }
+}
+
+/// Result of a checked transfer
+pub type SafeTransferResult<T> = Result<T, SafeTransferError>;
+
+/// Call a transfer method and check the token's answer.
+fn checked_call<A: Serialize>(token: &str, method: &str, args: &A) -> SafeTransferResult<()> {
+    let ctx = context();
+    if !ctx.is_contract(token) {
+        return Err(SafeTransferError::NotAContract {
+            token: token.to_string(),
+        });
+    }
+    let call = postcard::to_allocvec(args).map_err(|err| SafeTransferError::CallFailed {
+        token: token.to_string(),
+        method: method.to_string(),
+        reason: err.to_string(),
+    })?;
+    let data =
+        ctx.call_contract(token, method, &call)
+            .map_err(|err| SafeTransferError::CallFailed {
+                token: token.to_string(),
+                method: method.to_string(),
+                reason: err.to_string(),
+            })?;
+    // Tokens that report success must report `true`; silence means success.
+    if !data.is_empty() && postcard::from_bytes::<bool>(&data) != Ok(true) {
+        return Err(SafeTransferError::Rejected {
+            token: token.to_string(),
+            method: method.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// An address argument of a transfer; a malformed one fails the call.
+fn parse_address(token: &str, method: &str, address: &str) -> SafeTransferResult<Address> {
+    Address::parse(address).map_err(|err| SafeTransferError::CallFailed {
+        token: token.to_string(),
+        method: method.to_string(),
+        reason: err.to_string(),
+    })
+}
+
+fn balance(token: &str, account: &str) -> SafeTransferResult<u64> {
+    Crc20Client::new(token).balance_of(account).map_err(|err| {
+        SafeTransferError::BalanceUnavailable {
+            token: token.to_string(),
+            reason: err.to_string(),
+        }
+    })
+}
+
+/// Send `amount` of `token` from this contract to `to`; zero is a no-op.
+pub fn safe_transfer(token: &str, to: &str, amount: u64) -> SafeTransferResult<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let args = TransferArgs {
+        to: parse_address(token, methods::TRANSFER, to)?,
+        amount,
+    };
+    checked_call(token, methods::TRANSFER, &args)
+}
+
+/// Move `amount` of `token` from `from` to `to` under this contract's allowance.
+pub fn safe_transfer_from(
+    token: &str,
+    from: &str,
+    to: &str,
+    amount: u64,
+) -> SafeTransferResult<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let args = TransferFromArgs {
+        from: parse_address(token, methods::TRANSFER_FROM, from)?,
+        to: parse_address(token, methods::TRANSFER_FROM, to)?,
+        amount,
+    };
+    checked_call(token, methods::TRANSFER_FROM, &args)
+}
+
+/// Pull `amount` from `from` into this contract and return what actually arrived.
+///
+/// Fee-on-transfer tokens deliver less than `amount`; credit the result,
+/// not the request.
+pub fn transfer_in(token: &str, from: &str, amount: u64) -> SafeTransferResult<u64> {
+    if amount == 0 {
+        return Ok(0);
+    }
+    let this = context().contract_address().to_string();
+    let before = balance(token, &this)?;
+    safe_transfer_from(token, from, &this, amount)?;
+    let after = balance(token, &this)?;
+    Ok(after.saturating_sub(before))
+}
+
+/// Pull exactly `amount` into this contract; rejects fee-on-transfer tokens.
+pub fn transfer_in_exact(token: &str, from: &str, amount: u64) -> SafeTransferResult<()> {
+    let received = transfer_in(token, from, amount)?;
+    if received < amount {
+        return Err(SafeTransferError::Shortfall {
+            expected: amount,
+            received,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::tests::{ADDR_ALICE, ADDR_BOB, CONTRACT, TOKEN, setup, test_lock};
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Token holding `balance` for this contract that keeps `fee_bps` of every pull
+    fn register_token(fee_bps: u64) -> Arc<AtomicU64> {
+        let held = Arc::new(AtomicU64::new(1_000));
+        let credited = held.clone();
+        mock::register_contract(TOKEN, "transfer_from", move |args| {
+            let args: TransferFromArgs = postcard::from_bytes(args).unwrap();
+            let fee = args.amount * fee_bps / 10_000;
+            credited.fetch_add(args.amount - fee, Ordering::SeqCst);
+            Ok(Vec::new())
+        });
+        let reported = held.clone();
+        mock::register_contract(TOKEN, "balance_of", move |_| {
+            Ok(postcard::to_allocvec(&reported.load(Ordering::SeqCst)).unwrap())
+        });
+        held
+    }
+
+    #[test]
+    fn return_data_must_confirm_success() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::register_contract(TOKEN, "transfer", |args| {
+            let args: TransferArgs = postcard::from_bytes(args).unwrap();
+            match args.amount {
+                1 => Ok(Vec::new()),
+                2 => Ok(postcard::to_allocvec(&true).unwrap()),
+                3 => Ok(postcard::to_allocvec(&false).unwrap()),
+                _ => Ok(vec![7, 7]),
+            }
+        });
+        assert_eq!(safe_transfer(TOKEN, ADDR_BOB, 1), Ok(()));
+        assert_eq!(safe_transfer(TOKEN, ADDR_BOB, 2), Ok(()));
+        assert!(matches!(
+            safe_transfer(TOKEN, ADDR_BOB, 3),
+            Err(SafeTransferError::Rejected { .. })
+        ));
+        assert!(matches!(
+            safe_transfer(TOKEN, ADDR_BOB, 4),
+            Err(SafeTransferError::Rejected { .. })
+        ));
+        assert!(matches!(
+            ContractError::from(safe_transfer(TOKEN, ADDR_BOB, 3).unwrap_err()),
+            ContractError::TransferFailed
+        ));
+    }
+
+    #[test]
+    fn calls_to_missing_or_failing_tokens_fail() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(
+            safe_transfer(ADDR_ALICE, ADDR_BOB, 5),
+            Err(SafeTransferError::NotAContract {
+                token: ADDR_ALICE.to_string()
+            })
+        );
+        mock::register_contract(TOKEN, "transfer_from", |_| {
+            Err(ContractError::InsufficientBalance {
+                required: 5,
+                available: 0,
+            })
+        });
+        assert!(matches!(
+            safe_transfer_from(TOKEN, ADDR_ALICE, ADDR_BOB, 5),
+            Err(SafeTransferError::CallFailed { method, .. }) if method == "transfer_from"
+        ));
+        assert_eq!(safe_transfer_from(TOKEN, ADDR_ALICE, ADDR_BOB, 0), Ok(()));
+    }
+
+    #[test]
+    fn pulls_are_measured_by_balance_delta() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let held = register_token(100);
+        assert_eq!(transfer_in(TOKEN, ADDR_ALICE, 500), Ok(495));
+        assert_eq!(held.load(Ordering::SeqCst), 1_495);
+        assert_eq!(
+            transfer_in_exact(TOKEN, ADDR_ALICE, 500),
+            Err(SafeTransferError::Shortfall {
+                expected: 500,
+                received: 495
+            })
+        );
+
+        let calls = mock::take_contract_calls();
+        let pulled: TransferFromArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!(
+            (pulled.from.as_str(), pulled.to.as_str()),
+            (ADDR_ALICE, CONTRACT)
+        );
+
+        register_token(0);
+        assert_eq!(transfer_in_exact(TOKEN, ADDR_ALICE, 500), Ok(()));
+    }
+}
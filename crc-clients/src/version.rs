@@ -0,0 +1,27 @@
+use crate::query_bare;
+use crc_standards::version::{ContractVersion, Versioned, methods};
+use silica_contract_sdk::prelude::*;
+
+/// Client for any contract's `contract_version`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionedClient {
+    address: String,
+}
+
+impl VersionedClient {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Versioned for VersionedClient {
+    fn contract_version(&self) -> ContractResult<ContractVersion> {
+        query_bare(&self.address, methods::CONTRACT_VERSION)
+    }
+}
@@ -0,0 +1,328 @@
+//! Stable error codes shared by every contract
+//!
+//! Contracts keep returning [`ContractError`]; this crate maps each failure
+//! to an [`ErrorCode`] whose number never changes, so wallets and clients
+//! can branch on the code instead of parsing log text. Codes are grouped by
+//! hundreds:
+//!
+//! * `1xx` - runtime and encoding failures
+//! * `2xx` - the caller may not do this
+//! * `3xx` - the contract is in the wrong state
+//! * `4xx` - balances and arithmetic
+//! * `5xx` - bad input
+//!
+//! Numbers are only ever added. Each variant's doc comment is its
+//! description in the `error-registry` contract, which is generated from
+//! this file. A failing entrypoint calls [`report`], which logs the usual
+//! `"<method> failed: ..."` line and writes an [`ErrorPayload`] as return
+//! data.
+//!
+//! ## Embedding
+//! ```toml
+//! crc-errors = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn transfer() {
+//!     if let Err(err) = execute_transfer() {
+//!         crc_errors::report("Transfer", &err);
+//!     }
+//! }
+//!
+//! // Client side
+//! let payload = ErrorPayload::decode(&return_data)?;
+//! if payload.error_code() == ErrorCode::Paused { /* ... */ }
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use core::fmt;
+use silica_contract_sdk::prelude::*;
+
+/// Message used by contracts for "Contract is paused"
+pub const PAUSED_MESSAGE: &str = "Contract is paused";
+/// Message used by contracts for "Contract is not paused"
+pub const NOT_PAUSED_MESSAGE: &str = "Contract is not paused";
+/// Message of `decommission::when_active`, kept in one place with the SDK
+pub use silica_contract_sdk::decommission::DECOMMISSIONED_MESSAGE;
+/// Message used by tokens refusing a transfer to the zero address
+pub const ZERO_RECIPIENT_MESSAGE: &str = "Cannot transfer to the zero address";
+/// Message used by tokens refusing a transfer to their own contract address
+pub const CONTRACT_RECIPIENT_MESSAGE: &str = "Cannot transfer to the token contract";
+
+/// Stable numeric code of a contract failure
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum ErrorCode {
+    /// Not covered by any other code
+    Unknown = 0,
+
+    /// Contract storage could not be read
+    StorageReadFailed = 100,
+    /// Contract storage could not be written
+    StorageWriteFailed = 101,
+    /// A value could not be encoded
+    SerializationFailed = 102,
+    /// Call data or a stored value could not be decoded
+    DeserializationFailed = 103,
+    /// The call carried no arguments
+    CallDataUnavailable = 104,
+    /// The result could not be returned to the caller
+    ReturnDataWriteFailed = 105,
+    /// A call to another contract failed
+    ContractCallFailed = 106,
+
+    /// The caller is not allowed to do this
+    Unauthorized = 200,
+    /// A signature did not verify
+    InvalidSignature = 201,
+    /// The contract was re-entered during a guarded call
+    ReentrancyDetected = 202,
+
+    /// The contract has not been initialized
+    NotInitialized = 300,
+    /// The contract or item was already initialized
+    AlreadyInitialized = 301,
+    /// The contract is paused
+    Paused = 302,
+    /// The contract is not paused
+    NotPaused = 303,
+    /// The contract is retired; only withdrawals and claims still work
+    Decommissioned = 304,
+
+    /// The account holds less than the amount required
+    InsufficientBalance = 400,
+    /// A token or coin transfer failed
+    TransferFailed = 401,
+    /// An amount exceeded its maximum
+    Overflow = 402,
+    /// An amount went below zero
+    Underflow = 403,
+
+    /// An argument was rejected; the message says why
+    InvalidArgument = 500,
+    /// Tokens sent to the zero address would vanish without a burn
+    ZeroAddressRecipient = 501,
+    /// Tokens sent to the token contract itself would be stranded
+    ContractRecipient = 502,
+}
+
+impl ErrorCode {
+    const ALL: [ErrorCode; 23] = [
+        ErrorCode::Unknown,
+        ErrorCode::StorageReadFailed,
+        ErrorCode::StorageWriteFailed,
+        ErrorCode::SerializationFailed,
+        ErrorCode::DeserializationFailed,
+        ErrorCode::CallDataUnavailable,
+        ErrorCode::ReturnDataWriteFailed,
+        ErrorCode::ContractCallFailed,
+        ErrorCode::Unauthorized,
+        ErrorCode::InvalidSignature,
+        ErrorCode::ReentrancyDetected,
+        ErrorCode::NotInitialized,
+        ErrorCode::AlreadyInitialized,
+        ErrorCode::Paused,
+        ErrorCode::NotPaused,
+        ErrorCode::Decommissioned,
+        ErrorCode::InsufficientBalance,
+        ErrorCode::TransferFailed,
+        ErrorCode::Overflow,
+        ErrorCode::Underflow,
+        ErrorCode::InvalidArgument,
+        ErrorCode::ZeroAddressRecipient,
+        ErrorCode::ContractRecipient,
+    ];
+
+    /// The wire number of this code
+    pub const fn as_u16(self) -> u16 {
+        self as u16
+    }
+
+    /// Code for a wire number; numbers from newer contracts become `Unknown`
+    pub fn from_u16(code: u16) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|known| known.as_u16() == code)
+            .unwrap_or(ErrorCode::Unknown)
+    }
+
+    /// Classify a contract error
+    ///
+    /// `InvalidArgument` messages ending in "not initialized" or "already
+    /// initialized", the SDK's pause and decommission messages and the
+    /// recipient messages above get their own codes.
+    pub fn of(err: &ContractError) -> Self {
+        match err {
+            ContractError::StorageReadFailed => ErrorCode::StorageReadFailed,
+            ContractError::StorageWriteFailed => ErrorCode::StorageWriteFailed,
+            ContractError::SerializationFailed => ErrorCode::SerializationFailed,
+            ContractError::DeserializationFailed => ErrorCode::DeserializationFailed,
+            ContractError::CallDataUnavailable => ErrorCode::CallDataUnavailable,
+            ContractError::ReturnDataWriteFailed => ErrorCode::ReturnDataWriteFailed,
+            ContractError::ContractCallFailed(_) => ErrorCode::ContractCallFailed,
+            ContractError::Unauthorized => ErrorCode::Unauthorized,
+            ContractError::InvalidSignature => ErrorCode::InvalidSignature,
+            ContractError::ReentrancyDetected => ErrorCode::ReentrancyDetected,
+            ContractError::InsufficientBalance { .. } => ErrorCode::InsufficientBalance,
+            ContractError::TransferFailed => ErrorCode::TransferFailed,
+            ContractError::Overflow => ErrorCode::Overflow,
+            ContractError::Underflow => ErrorCode::Underflow,
+            ContractError::InvalidArgument(message) => classify_message(message),
+            ContractError::Custom(_) => ErrorCode::Unknown,
+        }
+    }
+}
+
+fn classify_message(message: &str) -> ErrorCode {
+    if message == PAUSED_MESSAGE {
+        ErrorCode::Paused
+    } else if message == NOT_PAUSED_MESSAGE {
+        ErrorCode::NotPaused
+    } else if message == DECOMMISSIONED_MESSAGE {
+        ErrorCode::Decommissioned
+    } else if message == ZERO_RECIPIENT_MESSAGE {
+        ErrorCode::ZeroAddressRecipient
+    } else if message == CONTRACT_RECIPIENT_MESSAGE {
+        ErrorCode::ContractRecipient
+    } else if message.ends_with("not initialized") {
+        ErrorCode::NotInitialized
+    } else if message.ends_with("already initialized") || message == "Owner already set" {
+        ErrorCode::AlreadyInitialized
+    } else {
+        ErrorCode::InvalidArgument
+    }
+}
+
+impl From<&ContractError> for ErrorCode {
+    fn from(err: &ContractError) -> Self {
+        ErrorCode::of(err)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{:03}", self.as_u16())
+    }
+}
+
+/// Failure written as return data by [`report`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorPayload {
+    /// [`ErrorCode`] number
+    pub code: u16,
+    /// Human-readable text of the original error
+    pub message: String,
+    /// `(required, available)` for `InsufficientBalance`
+    pub amounts: Option<(u64, u64)>,
+}
+
+impl ErrorPayload {
+    /// The code, or `Unknown` for numbers this build does not know
+    pub fn error_code(&self) -> ErrorCode {
+        ErrorCode::from_u16(self.code)
+    }
+
+    /// Postcard-encode the payload
+    pub fn encode(&self) -> ContractResult<Vec<u8>> {
+        postcard::to_allocvec(self).map_err(|_| ContractError::SerializationFailed)
+    }
+
+    /// Decode a payload written by [`report`]
+    pub fn decode(bytes: &[u8]) -> ContractResult<Self> {
+        postcard::from_bytes(bytes).map_err(|_| ContractError::DeserializationFailed)
+    }
+}
+
+impl From<&ContractError> for ErrorPayload {
+    fn from(err: &ContractError) -> Self {
+        let amounts = match err {
+            ContractError::InsufficientBalance {
+                required,
+                available,
+            } => Some((*required, *available)),
+            _ => None,
+        };
+        ErrorPayload {
+            code: ErrorCode::of(err).as_u16(),
+            message: err.to_string(),
+            amounts,
+        }
+    }
+}
+
+/// Log a failed entrypoint and write its [`ErrorPayload`] as return data
+pub fn report(method: &str, err: &ContractError) {
+    log!("{} failed: {}", method, err);
+    if let (Ok(ctx), Ok(data)) = (try_context(), ErrorPayload::from(err).encode()) {
+        let _ = ctx.return_bytes(&data);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+
+    #[test]
+    fn codes_are_stable_and_round_trip() {
+        assert_eq!(ErrorCode::Unauthorized.as_u16(), 200);
+        assert_eq!(ErrorCode::Paused.as_u16(), 302);
+        assert_eq!(ErrorCode::InsufficientBalance.as_u16(), 400);
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_u16(code.as_u16()), code);
+        }
+        assert_eq!(ErrorCode::from_u16(999), ErrorCode::Unknown);
+        assert_eq!(ErrorCode::NotInitialized.to_string(), "E300");
+
+        let message = |text: &str| ContractError::InvalidArgument(text.to_string());
+        assert_eq!(ErrorCode::of(&message(PAUSED_MESSAGE)), ErrorCode::Paused);
+        assert_eq!(
+            ErrorCode::of(&message(DECOMMISSIONED_MESSAGE)),
+            ErrorCode::Decommissioned
+        );
+        assert_eq!(
+            ErrorCode::of(&message(ZERO_RECIPIENT_MESSAGE)),
+            ErrorCode::ZeroAddressRecipient
+        );
+        assert_eq!(
+            ErrorCode::of(&message("Token not initialized")),
+            ErrorCode::NotInitialized
+        );
+        assert_eq!(
+            ErrorCode::of(&message("Pool already initialized")),
+            ErrorCode::AlreadyInitialized
+        );
+        assert_eq!(
+            ErrorCode::of(&message("Amount must be positive")),
+            ErrorCode::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn report_writes_a_decodable_payload() {
+        mock::reset();
+        mock::set_sender("chert1sender000000000000000000");
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+        report(
+            "Transfer",
+            &ContractError::InsufficientBalance {
+                required: 10,
+                available: 3,
+            },
+        );
+
+        let payload = ErrorPayload::decode(&mock::take_return_data()).unwrap();
+        assert_eq!(payload.error_code(), ErrorCode::InsufficientBalance);
+        assert_eq!(payload.amounts, Some((10, 3)));
+        assert_eq!(
+            payload.message,
+            "Insufficient balance: required 10, available 3"
+        );
+    }
+}
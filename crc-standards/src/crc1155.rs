@@ -0,0 +1,105 @@
+//! CRC-1155 multi-tokens
+
+use silica_contract_sdk::prelude::*;
+
+/// Entrypoint names
+pub mod methods {
+    pub const URI: &str = "uri";
+    pub const BALANCE_OF: &str = "balance_of";
+    pub const BALANCE_OF_BATCH: &str = "balance_of_batch";
+    pub const IS_APPROVED_FOR_ALL: &str = "is_approved_for_all";
+    pub const SET_APPROVAL_FOR_ALL: &str = "set_approval_for_all";
+    pub const SAFE_TRANSFER_FROM: &str = "safe_transfer_from";
+    pub const SAFE_BATCH_TRANSFER_FROM: &str = "safe_batch_transfer_from";
+    /// Called on contract recipients of either safe transfer
+    pub const ON_CRC1155_RECEIVED: &str = "on_crc1155_received";
+}
+
+/// `balance_of`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BalanceOfArgs {
+    pub account: String,
+    pub id: u64,
+}
+
+/// `balance_of_batch`; `accounts[i]` is paired with `ids[i]`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BalanceOfBatchArgs {
+    pub accounts: Vec<String>,
+    pub ids: Vec<u64>,
+}
+
+/// `safe_transfer_from`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SafeTransferFromArgs {
+    pub from: String,
+    pub to: String,
+    pub id: u64,
+    pub amount: u64,
+    pub data: Vec<u8>,
+}
+
+/// `safe_batch_transfer_from`; `ids[i]` is paired with `amounts[i]`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SafeBatchTransferFromArgs {
+    pub from: String,
+    pub to: String,
+    pub ids: Vec<u64>,
+    pub amounts: Vec<u64>,
+    pub data: Vec<u8>,
+}
+
+/// `set_approval_for_all`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SetApprovalForAllArgs {
+    pub operator: String,
+    pub approved: bool,
+}
+
+/// `is_approved_for_all`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IsApprovedForAllArgs {
+    pub account: String,
+    pub operator: String,
+}
+
+/// `uri`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UriArgs {
+    pub id: u64,
+}
+
+/// `on_crc1155_received`; the recipient returns `true` to accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OnReceivedArgs {
+    pub operator: String,
+    pub from: String,
+    pub ids: Vec<u64>,
+    pub amounts: Vec<u64>,
+    pub data: Vec<u8>,
+}
+
+/// The CRC-1155 interface
+pub trait Crc1155 {
+    fn uri(&self, id: u64) -> ContractResult<String>;
+    fn balance_of(&self, account: &str, id: u64) -> ContractResult<u64>;
+    fn balance_of_batch(&self, accounts: &[String], ids: &[u64]) -> ContractResult<Vec<u64>>;
+    fn is_approved_for_all(&self, account: &str, operator: &str) -> ContractResult<bool>;
+    fn set_approval_for_all(&self, operator: &str, approved: bool) -> ContractResult<()>;
+    fn safe_transfer_from(
+        &self,
+        from: &str,
+        to: &str,
+        id: u64,
+        amount: u64,
+        data: &[u8],
+    ) -> ContractResult<()>;
+    fn safe_batch_transfer_from(
+        &self,
+        from: &str,
+        to: &str,
+        ids: &[u64],
+        amounts: &[u64],
+        data: &[u8],
+    ) -> ContractResult<()>;
+}
@@ -0,0 +1,171 @@
+//! CRC-20 fungible tokens
+
+use address::Address;
+use silica_contract_sdk::prelude::*;
+
+/// Entrypoint names
+pub mod methods {
+    pub const NAME: &str = "name";
+    pub const SYMBOL: &str = "symbol";
+    pub const DECIMALS: &str = "decimals";
+    pub const TOTAL_SUPPLY: &str = "total_supply";
+    pub const BALANCE_OF: &str = "balance_of";
+    pub const ALLOWANCE: &str = "allowance";
+    pub const TRANSFER: &str = "transfer";
+    pub const APPROVE: &str = "approve";
+    pub const TRANSFER_FROM: &str = "transfer_from";
+    /// Optional extension: historical balances
+    pub const BALANCE_OF_AT: &str = "balance_of_at";
+    /// Optional extension: owner or minter issuance
+    pub const MINT: &str = "mint";
+    /// Optional extension: holders destroy their own tokens
+    pub const BURN: &str = "burn";
+    /// Optional extension: let transfers to the zero address burn (only owner)
+    pub const SET_ZERO_ADDRESS_BURNS: &str = "set_zero_address_burns";
+    /// Optional extension: several queries in one call
+    pub const AGGREGATE_VIEWS: &str = "aggregate_views";
+    /// Optional extension: an account's balances and allowances in one call
+    pub const ACCOUNT_OVERVIEW: &str = "account_overview";
+}
+
+/// `transfer`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferArgs {
+    pub to: Address,
+    pub amount: u64,
+}
+
+/// `approve`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ApproveArgs {
+    pub spender: Address,
+    pub amount: u64,
+}
+
+/// `transfer_from`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferFromArgs {
+    pub from: Address,
+    pub to: Address,
+    pub amount: u64,
+}
+
+/// `balance_of`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BalanceOfArgs {
+    pub account: Address,
+}
+
+/// `allowance`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AllowanceArgs {
+    pub owner: Address,
+    pub spender: Address,
+}
+
+/// `balance_of_at`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BalanceOfAtArgs {
+    pub account: Address,
+    pub block: u64,
+}
+
+/// `mint`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MintArgs {
+    pub to: Address,
+    pub amount: u64,
+}
+
+/// `add_minter`, `remove_minter` and `is_minter`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MinterArgs {
+    pub account: Address,
+}
+
+/// `burn`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BurnArgs {
+    pub amount: u64,
+}
+
+/// `set_zero_address_burns`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SetZeroAddressBurnsArgs {
+    pub enabled: bool,
+}
+
+/// `aggregate_views`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregateViewsArgs {
+    /// At most [`MAX_VIEW_REQUESTS`](crate::MAX_VIEW_REQUESTS)
+    pub requests: Vec<ViewRequest>,
+}
+
+/// One query of `aggregate_views`, named after the entrypoint it mirrors
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ViewRequest {
+    Name,
+    Symbol,
+    Decimals,
+    TotalSupply,
+    BalanceOf { account: Address },
+    Allowance { owner: Address, spender: Address },
+    BalanceOfAt { account: Address, block: u64 },
+}
+
+/// Answer to a [`ViewRequest`], at the same position in the response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ViewResult {
+    /// `Name` and `Symbol`
+    Text(String),
+    /// `Decimals`
+    Decimals(u8),
+    /// `TotalSupply`, `BalanceOf`, `Allowance` and `BalanceOfAt`
+    Amount(u64),
+    /// The query failed with this `crc-errors` code
+    Failed(u16),
+}
+
+/// `account_overview`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountOverviewArgs {
+    pub account: Address,
+    /// At most [`MAX_VIEW_REQUESTS`](crate::MAX_VIEW_REQUESTS)
+    pub spenders: Vec<Address>,
+}
+
+/// Allowance an account granted to one spender
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SpenderAllowance {
+    pub spender: String,
+    pub amount: u64,
+}
+
+/// Answer to `account_overview`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccountOverview {
+    pub account: String,
+    pub balance: u64,
+    /// Part of `balance` the account cannot transfer yet; `0` for tokens
+    /// without locks
+    pub locked_balance: u64,
+    /// One per requested spender, in request order
+    pub allowances: Vec<SpenderAllowance>,
+}
+
+/// The CRC-20 interface
+///
+/// Mutating methods act on behalf of the caller of the implementing
+/// contract, as with the entrypoints themselves.
+pub trait Crc20 {
+    fn name(&self) -> ContractResult<String>;
+    fn symbol(&self) -> ContractResult<String>;
+    fn decimals(&self) -> ContractResult<u8>;
+    fn total_supply(&self) -> ContractResult<u64>;
+    fn balance_of(&self, account: &str) -> ContractResult<u64>;
+    fn allowance(&self, owner: &str, spender: &str) -> ContractResult<u64>;
+    fn transfer(&self, to: &str, amount: u64) -> ContractResult<()>;
+    fn approve(&self, spender: &str, amount: u64) -> ContractResult<()>;
+    fn transfer_from(&self, from: &str, to: &str, amount: u64) -> ContractResult<()>;
+}
@@ -0,0 +1,189 @@
+//! CRC-721 non-fungible tokens
+
+use address::Address;
+use silica_contract_sdk::bounded::Memo;
+use silica_contract_sdk::prelude::*;
+
+/// Entrypoint names
+pub mod methods {
+    pub const NAME: &str = "name";
+    pub const SYMBOL: &str = "symbol";
+    pub const TOTAL_SUPPLY: &str = "total_supply";
+    pub const BALANCE_OF: &str = "balance_of";
+    pub const OWNER_OF: &str = "owner_of";
+    pub const TOKEN_URI: &str = "token_uri";
+    pub const GET_APPROVED: &str = "get_approved";
+    pub const IS_APPROVED_FOR_ALL: &str = "is_approved_for_all";
+    pub const APPROVE: &str = "approve";
+    pub const SET_APPROVAL_FOR_ALL: &str = "set_approval_for_all";
+    pub const TRANSFER_FROM: &str = "transfer_from";
+    pub const SAFE_TRANSFER_FROM: &str = "safe_transfer_from";
+    /// `transfer_from` with a note for the Transfer event
+    pub const TRANSFER_FROM_WITH_MEMO: &str = "transfer_from_with_memo";
+    /// `safe_transfer_from` with a note for the Transfer event
+    pub const SAFE_TRANSFER_FROM_WITH_MEMO: &str = "safe_transfer_from_with_memo";
+    /// Called on contract recipients of `safe_transfer_from`
+    pub const ON_CRC721_RECEIVED: &str = "on_crc721_received";
+    /// Optional extension: several queries in one call
+    pub const AGGREGATE_VIEWS: &str = "aggregate_views";
+    /// Optional extension: recent transfers of one token
+    pub const TRANSFER_HISTORY: &str = "transfer_history";
+    /// Optional extension: start or stop recording transfer history
+    pub const SET_PROVENANCE_LOG: &str = "set_provenance_log";
+}
+
+/// `transfer_from`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferFromArgs {
+    pub from: Address,
+    pub to: Address,
+    pub token_id: u64,
+}
+
+/// `safe_transfer_from`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SafeTransferFromArgs {
+    pub from: Address,
+    pub to: Address,
+    pub token_id: u64,
+    pub data: Vec<u8>,
+}
+
+/// `transfer_from_with_memo`
+///
+/// A separate entrypoint rather than a field on [`TransferFromArgs`], so
+/// existing call data keeps decoding and a forwarded caller suffix is never
+/// read as a memo.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferFromWithMemoArgs {
+    pub from: Address,
+    pub to: Address,
+    pub token_id: u64,
+    /// Published in the Transfer event
+    pub memo: Memo,
+}
+
+/// `safe_transfer_from_with_memo`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SafeTransferFromWithMemoArgs {
+    pub from: Address,
+    pub to: Address,
+    pub token_id: u64,
+    pub data: Vec<u8>,
+    /// Published in the Transfer event
+    pub memo: Memo,
+}
+
+/// `approve`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ApproveArgs {
+    pub to: Address,
+    pub token_id: u64,
+}
+
+/// `set_approval_for_all`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SetApprovalForAllArgs {
+    pub operator: Address,
+    pub approved: bool,
+}
+
+/// `is_approved_for_all`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IsApprovedForAllArgs {
+    pub owner: Address,
+    pub operator: Address,
+}
+
+/// `balance_of`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BalanceOfArgs {
+    pub owner: Address,
+}
+
+/// `owner_of`, `token_uri` and `get_approved`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenIdArgs {
+    pub token_id: u64,
+}
+
+/// `on_crc721_received`; the recipient returns `true` to accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OnReceivedArgs {
+    pub operator: String,
+    pub from: String,
+    pub token_id: u64,
+    pub data: Vec<u8>,
+}
+
+/// `aggregate_views`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregateViewsArgs {
+    /// At most [`MAX_VIEW_REQUESTS`](crate::MAX_VIEW_REQUESTS)
+    pub requests: Vec<ViewRequest>,
+}
+
+/// One query of `aggregate_views`, named after the entrypoint it mirrors
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ViewRequest {
+    Name,
+    Symbol,
+    TotalSupply,
+    BalanceOf { owner: Address },
+    OwnerOf { token_id: u64 },
+    TokenUri { token_id: u64 },
+    GetApproved { token_id: u64 },
+    IsApprovedForAll { owner: Address, operator: Address },
+}
+
+/// Answer to a [`ViewRequest`], at the same position in the response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ViewResult {
+    /// `Name`, `Symbol`, `OwnerOf` and `TokenUri`
+    Text(String),
+    /// `TotalSupply` and `BalanceOf`
+    Count(u64),
+    /// `GetApproved`
+    Approved(Option<String>),
+    /// `IsApprovedForAll`
+    Flag(bool),
+    /// The query failed with this `crc-errors` code
+    Failed(u16),
+}
+
+/// `set_provenance_log`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SetProvenanceLogArgs {
+    pub enabled: bool,
+}
+
+/// One transfer returned by `transfer_history`; mints come from and burns go
+/// to the zero address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferRecord {
+    pub from: String,
+    pub to: String,
+    pub block_height: u64,
+}
+
+/// The CRC-721 interface
+pub trait Crc721 {
+    fn name(&self) -> ContractResult<String>;
+    fn symbol(&self) -> ContractResult<String>;
+    fn total_supply(&self) -> ContractResult<u64>;
+    fn balance_of(&self, owner: &str) -> ContractResult<u64>;
+    fn owner_of(&self, token_id: u64) -> ContractResult<String>;
+    fn token_uri(&self, token_id: u64) -> ContractResult<String>;
+    fn get_approved(&self, token_id: u64) -> ContractResult<Option<String>>;
+    fn is_approved_for_all(&self, owner: &str, operator: &str) -> ContractResult<bool>;
+    fn approve(&self, to: &str, token_id: u64) -> ContractResult<()>;
+    fn set_approval_for_all(&self, operator: &str, approved: bool) -> ContractResult<()>;
+    fn transfer_from(&self, from: &str, to: &str, token_id: u64) -> ContractResult<()>;
+    fn safe_transfer_from(
+        &self,
+        from: &str,
+        to: &str,
+        token_id: u64,
+        data: &[u8],
+    ) -> ContractResult<()>;
+}
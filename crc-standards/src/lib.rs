@@ -0,0 +1,54 @@
+//! Canonical CRC interface definitions
+//!
+//! One set of argument structs, method names and traits for the token and
+//! contract standards used across this workspace. Contracts decode their
+//! call data with these structs, and clients encode calls with the same
+//! ones, so the wire format of a standard is defined in exactly one place.
+//!
+//! * [`crc20`] - fungible tokens
+//! * [`crc721`] - non-fungible tokens
+//! * [`crc1155`] - multi-tokens
+//! * [`ownable`] - single-owner administration
+//! * [`pausable`] - emergency stop
+//! * [`version`] - deployed code identification, exported by every contract
+//!
+//! Every module has a `methods` submodule with the entrypoint names and a
+//! trait describing the interface. Call arguments are postcard-encoded in
+//! field order. Queries return their postcard-encoded result.
+//!
+//! The CRC-20 and CRC-721 modules also define `aggregate_views`, an optional
+//! entrypoint that answers up to [`MAX_VIEW_REQUESTS`] queries in one call.
+//! A failed query answers `Failed` with its `crc-errors` code instead of
+//! failing the whole call.
+//!
+//! Address arguments of the CRC-20 and CRC-721 calls are [`Address`]es, so
+//! a contract decoding them rejects malformed addresses with
+//! `DeserializationFailed` before its entrypoint runs.
+//!
+//! ## Embedding
+//! ```toml
+//! crc-standards = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use crc_standards::crc20::{self, TransferArgs};
+//!
+//! let call = postcard::to_allocvec(&TransferArgs { to, amount })?;
+//! ctx.call_contract(&token, crc20::methods::TRANSFER, &call)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+pub mod crc1155;
+pub mod crc20;
+pub mod crc721;
+pub mod ownable;
+pub mod pausable;
+pub mod version;
+
+pub use address::Address;
+
+/// Most queries one `aggregate_views` call answers
+pub const MAX_VIEW_REQUESTS: usize = 32;
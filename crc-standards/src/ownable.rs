@@ -0,0 +1,32 @@
+//! Single-owner administration
+
+use silica_contract_sdk::prelude::*;
+
+/// Entrypoint names
+pub mod methods {
+    pub const OWNER: &str = "owner";
+    pub const PENDING_OWNER: &str = "pending_owner";
+    pub const TRANSFER_OWNERSHIP: &str = "transfer_ownership";
+    pub const ACCEPT_OWNERSHIP: &str = "accept_ownership";
+    pub const RENOUNCE_OWNERSHIP: &str = "renounce_ownership";
+}
+
+/// `transfer_ownership`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransferOwnershipArgs {
+    pub new_owner: String,
+}
+
+/// The Ownable interface
+///
+/// Ownership moves in two steps: `transfer_ownership` nominates the new
+/// owner, who takes over by calling `accept_ownership`.
+pub trait Ownable {
+    /// Current owner; `None` once ownership has been renounced
+    fn owner(&self) -> ContractResult<Option<String>>;
+    /// Nominated owner awaiting `accept_ownership`
+    fn pending_owner(&self) -> ContractResult<Option<String>>;
+    fn transfer_ownership(&self, new_owner: &str) -> ContractResult<()>;
+    fn accept_ownership(&self) -> ContractResult<()>;
+    fn renounce_ownership(&self) -> ContractResult<()>;
+}
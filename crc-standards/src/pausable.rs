@@ -0,0 +1,17 @@
+//! Emergency stop
+
+use silica_contract_sdk::prelude::*;
+
+/// Entrypoint names
+pub mod methods {
+    pub const PAUSED: &str = "paused";
+    pub const PAUSE: &str = "pause";
+    pub const UNPAUSE: &str = "unpause";
+}
+
+/// The Pausable interface; `pause` and `unpause` take no arguments
+pub trait Pausable {
+    fn paused(&self) -> ContractResult<bool>;
+    fn pause(&self) -> ContractResult<()>;
+    fn unpause(&self) -> ContractResult<()>;
+}
@@ -0,0 +1,78 @@
+//! Deployed code identification
+//!
+//! Every contract exports `contract_version`, which answers the crate and
+//! version it was built from, the commit of that build and the storage
+//! schema version it holds. Operators compare the answer with a release to
+//! check exactly which code runs on which storage layout.
+//!
+//! The commit is read from the `SILICA_GIT_HASH` variable when the contract
+//! is compiled; builds without it answer [`UNKNOWN_GIT_HASH`]:
+//!
+//! ```text
+//! SILICA_GIT_HASH=$(git rev-parse HEAD) cargo build --release --target wasm32-unknown-unknown
+//! ```
+//!
+//! ```rust,ignore
+//! #[entrypoint]
+//! fn contract_version() -> ContractResult<ContractVersion> {
+//!     Ok(crc_standards::contract_version!(proxy_implementation::schema_version()?))
+//! }
+//! ```
+
+use silica_contract_sdk::prelude::*;
+
+/// Entrypoint names
+pub mod methods {
+    pub const CONTRACT_VERSION: &str = "contract_version";
+}
+
+/// Commit of contracts built without `SILICA_GIT_HASH`
+pub const UNKNOWN_GIT_HASH: &str = "unknown";
+
+/// `contract_version`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ContractVersion {
+    /// Cargo package the contract was built from
+    pub crate_name: String,
+    pub crate_version: String,
+    /// Commit of the build, or [`UNKNOWN_GIT_HASH`]
+    pub git_hash: String,
+    /// Storage schema version recorded in the contract's storage; `0` if
+    /// none was recorded
+    pub schema_version: u32,
+}
+
+impl ContractVersion {
+    pub fn new(
+        crate_name: &str,
+        crate_version: &str,
+        git_hash: Option<&str>,
+        schema_version: u32,
+    ) -> Self {
+        Self {
+            crate_name: crate_name.to_string(),
+            crate_version: crate_version.to_string(),
+            git_hash: git_hash.unwrap_or(UNKNOWN_GIT_HASH).to_string(),
+            schema_version,
+        }
+    }
+}
+
+/// [`ContractVersion`] of the crate invoking the macro, built from its Cargo
+/// metadata and `SILICA_GIT_HASH`, with the given schema version
+#[macro_export]
+macro_rules! contract_version {
+    ($schema_version:expr) => {
+        $crate::version::ContractVersion::new(
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            option_env!("SILICA_GIT_HASH"),
+            $schema_version,
+        )
+    };
+}
+
+/// The interface every contract implements
+pub trait Versioned {
+    fn contract_version(&self) -> ContractResult<ContractVersion>;
+}
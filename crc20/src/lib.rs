@@ -8,6 +8,16 @@
 //! - Approve spending allowances
 //! - Delegated transfers via allowances
 //! - Query balances and total supply
+//! - Historical balances (`balance_of_at`) for snapshot voting
+//! - Batched queries (`aggregate_views`) for wallets and indexers
+//! - Balance and allowances of one account in one call (`account_overview`)
+//! - Build and storage schema identification (`contract_version`)
+//! - Two-step ownership transfer via the shared `ownable` module
+//! - Minting by the owner and by minters the owner adds, such as bridges
+//!   and vesting contracts
+//! - Owner-controlled pause of transfers and minting
+//! - Burning, and transfers to the zero address or the token contract refused
+//!   unless the owner routes zero-address transfers to burns
 //! - Event emission for indexing
 
 #![cfg_attr(target_arch = "wasm32", no_std)]
@@ -15,16 +25,49 @@
 
 extern crate alloc;
 
+use address::{Address, AddressKey, ZERO_ADDRESS};
+use contract_macros::entrypoint;
+use crc_errors::{CONTRACT_RECIPIENT_MESSAGE, ErrorCode, ZERO_RECIPIENT_MESSAGE};
+use crc_standards::MAX_VIEW_REQUESTS;
+use crc_standards::crc20::{
+    AccountOverview, AccountOverviewArgs, AggregateViewsArgs, ApproveArgs, BalanceOfArgs,
+    BalanceOfAtArgs, BurnArgs, MintArgs, MinterArgs, SetZeroAddressBurnsArgs, SpenderAllowance,
+    TransferArgs, TransferFromArgs, ViewRequest, ViewResult,
+};
+use crc_standards::ownable::TransferOwnershipArgs;
+use crc_standards::version::ContractVersion;
+use events::{ApprovalV1, MinterAddedV1, MinterRemovedV1, TransferV1, admin};
+use silica_contract_sdk::access_control::MINTER_ROLE;
+use silica_contract_sdk::bounded::{Name, Symbol};
 use silica_contract_sdk::event;
+use silica_contract_sdk::initializable;
+use silica_contract_sdk::ownable;
+use silica_contract_sdk::pagination;
+use silica_contract_sdk::pausable;
 use silica_contract_sdk::prelude::*;
-use serde::de::DeserializeOwned;
-
+use silica_contract_sdk::reentrancy;
+use silica_contract_sdk::write_cache;
+use storage_keys::{CompatMap, CompatValue, Namespace};
+
+/// Storage schema version of fresh deployments
+const SCHEMA_VERSION: u32 = 1;
+/// Namespace of this contract's keys. The domains below are also the bare
+/// keys deployments before namespacing wrote, which stay readable.
+const KEYS: Namespace = Namespace::new("crc20", 1);
+/// Layout 2 of the account-keyed domains keys them by address bytes instead
+/// of the hex string; layout 1 and bare-key entries stay readable.
+const ACCOUNT_KEYS: Namespace = KEYS.at_version(2);
 const METADATA_KEY: &str = "metadata";
 const BALANCES_PREFIX: &str = "balances";
 const ALLOWANCES_PREFIX: &str = "allowances";
-const ZERO_ADDRESS: &str = "0x0";
-const MAX_CALL_DATA_BYTES: usize = 4096;
-const MAX_RETURN_BYTES: usize = 4096;
+const CHECKPOINTS_PREFIX: &str = "balance_checkpoints";
+const CHECKPOINT_COUNT_PREFIX: &str = "balance_checkpoint_count";
+const ZERO_ADDRESS_BURNS_KEY: &str = "zero_address_burns";
+const MINTERS_PREFIX: &str = "minters";
+const MAX_CHECKPOINT_PAGE: u64 = 100;
+/// Re-read balances and allowances after writing them. On in debug builds,
+/// which tests use, and in release builds with the `verify-writes` feature.
+const VERIFY_WRITES: bool = cfg!(any(debug_assertions, feature = "verify-writes"));
 
 /// Token metadata stored once at initialization
 #[derive(Serialize, Deserialize)]
@@ -33,81 +76,84 @@ pub struct TokenMetadata {
     pub symbol: String,
     pub decimals: u8,
     pub total_supply: u64,
-    pub owner: String,
+}
+
+/// An account's balance at the end of `block`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub block: u64,
+    pub balance: u64,
 }
 
 #[derive(Serialize, Deserialize)]
 struct InitializeArgs {
-    name: String,
-    symbol: String,
+    name: Name,
+    symbol: Symbol,
     decimals: u8,
     initial_supply: u64,
 }
 
 #[derive(Serialize, Deserialize)]
-struct TransferArgs {
-    to: String,
-    amount: u64,
+struct CheckpointsArgs {
+    account: Address,
+    cursor: u64,
+    limit: u64,
 }
 
-#[derive(Serialize, Deserialize)]
-struct ApproveArgs {
-    spender: String,
-    amount: u64,
+fn metadata() -> CompatValue<TokenMetadata> {
+    KEYS.compat_value(METADATA_KEY)
 }
 
-#[derive(Serialize, Deserialize)]
-struct TransferFromArgs {
-    from: String,
-    to: String,
-    amount: u64,
+/// Spellings earlier layouts may have keyed an account by; they used the
+/// caller's string as given
+fn legacy_account(account: &AddressKey) -> Vec<String> {
+    account.to_address().spellings()
 }
 
-#[derive(Serialize, Deserialize)]
-struct BalanceOfArgs {
-    account: String,
+/// Every combination of the legacy spellings of two accounts
+fn legacy_pair(first: &AddressKey, second: &AddressKey) -> Vec<(String, String)> {
+    let seconds = legacy_account(second);
+    legacy_account(first)
+        .into_iter()
+        .flat_map(|first| {
+            seconds
+                .iter()
+                .map(move |second| (first.clone(), second.clone()))
+        })
+        .collect()
 }
 
-#[derive(Serialize, Deserialize)]
-struct MintArgs {
-    to: String,
-    amount: u64,
+fn balances() -> CompatMap<AddressKey, u64> {
+    ACCOUNT_KEYS.respelled_map(BALANCES_PREFIX, legacy_account)
 }
 
-fn read_args<T>() -> ContractResult<T>
-where
-    T: DeserializeOwned,
-{
-    let payload = context().call_data()?;
-    assert!(
-        payload.len() <= MAX_CALL_DATA_BYTES,
-        "Call data exceeds static bound"
-    );
-    if payload.is_empty() {
-        return Err(ContractError::CallDataUnavailable);
-    }
+fn allowances() -> CompatMap<(AddressKey, AddressKey), u64> {
+    ACCOUNT_KEYS.respelled_map(ALLOWANCES_PREFIX, |(owner, spender)| {
+        legacy_pair(owner, spender)
+    })
+}
 
-    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+fn checkpoint_counts() -> CompatMap<AddressKey, u64> {
+    ACCOUNT_KEYS.respelled_map(CHECKPOINT_COUNT_PREFIX, legacy_account)
 }
 
-fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
-    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
-    assert!(
-        data.len() <= MAX_RETURN_BYTES,
-        "Return payload exceeds static bound"
-    );
-    context().return_bytes(&data)
+fn checkpoint_entries() -> CompatMap<(AddressKey, u64), Checkpoint> {
+    ACCOUNT_KEYS.respelled_map(CHECKPOINTS_PREFIX, |(account, index)| {
+        legacy_account(account)
+            .into_iter()
+            .map(|spelling| (spelling, *index))
+            .collect()
+    })
 }
 
 fn load_metadata() -> ContractResult<TokenMetadata> {
-    storage()
-        .get::<TokenMetadata>(METADATA_KEY)?
+    metadata()
+        .get()?
         .ok_or_else(|| ContractError::InvalidArgument("Token not initialized".to_string()))
 }
 
 fn save_metadata(metadata: &TokenMetadata) -> ContractResult<()> {
-    let mut store = storage();
-    store.set(METADATA_KEY, metadata)
+    self::metadata().set(metadata)
 }
 
 fn read_balance(address: &str) -> ContractResult<u64> {
@@ -115,8 +161,7 @@ fn read_balance(address: &str) -> ContractResult<u64> {
         !address.is_empty(),
         "Balance lookup requires non-empty address"
     );
-    let balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
-    Ok(balances.get(&address.to_string())?.unwrap_or(0))
+    Ok(balances().get(&AddressKey::parse(address)?)?.unwrap_or(0))
 }
 
 fn write_balance(address: &str, amount: u64) -> ContractResult<()> {
@@ -124,35 +169,143 @@ fn write_balance(address: &str, amount: u64) -> ContractResult<()> {
         !address.is_empty(),
         "Balance write requires non-empty address"
     );
-    let mut balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
-    balances.set(&address.to_string(), &amount)?;
-    let stored = balances.get(&address.to_string())?.unwrap_or(0);
-    assert_eq!(stored, amount, "Balance write verification failed");
-    Ok(())
+    let key = AddressKey::parse(address)?;
+    let mut balances = balances();
+    balances.set(&key, &amount)?;
+    if VERIFY_WRITES {
+        let stored = balances.get(&key)?.unwrap_or(0);
+        assert_eq!(stored, amount, "Balance write verification failed");
+    }
+    write_checkpoint(address, amount)
+}
+
+/// Record `amount` as the balance of `address` at the current block. Several
+/// writes in one block share a single checkpoint.
+fn write_checkpoint(address: &str, amount: u64) -> ContractResult<()> {
+    let block = context().block_height();
+    let account = AddressKey::parse(address)?;
+    let mut counts = checkpoint_counts();
+    let mut checkpoints = checkpoint_entries();
+    let count = counts.get(&account)?.unwrap_or(0);
+    if count > 0 {
+        let last_key = (account.clone(), count - 1);
+        if let Some(last) = checkpoints.get(&last_key)?
+            && last.block == block
+        {
+            return checkpoints.set(
+                &last_key,
+                &Checkpoint {
+                    block,
+                    balance: amount,
+                },
+            );
+        }
+    }
+    checkpoints.set(
+        &(account.clone(), count),
+        &Checkpoint {
+            block,
+            balance: amount,
+        },
+    )?;
+    counts.set(&account, &safe_math::add(count, 1)?)
+}
+
+/// Balance of `address` at the end of `block`, found by binary search over
+/// its checkpoints.
+fn read_balance_at(address: &str, block: u64) -> ContractResult<u64> {
+    let account = AddressKey::parse(address)?;
+    let counts = checkpoint_counts();
+    let checkpoints = checkpoint_entries();
+    let checkpoint = |index: u64| -> ContractResult<Checkpoint> {
+        checkpoints
+            .get(&(account.clone(), index))?
+            .ok_or(ContractError::StorageReadFailed)
+    };
+
+    // Find the first checkpoint written after `block`; the one before it
+    // holds the balance.
+    let (mut low, mut high) = (0, counts.get(&account)?.unwrap_or(0));
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if checkpoint(mid)?.block > block {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    if low == 0 {
+        return Ok(0);
+    }
+    Ok(checkpoint(low - 1)?.balance)
+}
+
+/// Balance at the end of `block`, which must be finished: the current one
+/// can still change.
+fn final_balance_at(address: &str, block: u64) -> ContractResult<u64> {
+    if block >= context().block_height() {
+        return Err(ContractError::InvalidArgument(
+            "Block must be in the past".to_string(),
+        ));
+    }
+    read_balance_at(address, block)
 }
 
 fn read_allowance(owner: &str, spender: &str) -> ContractResult<u64> {
     assert!(!owner.is_empty(), "Allowance owner cannot be empty");
     assert!(!spender.is_empty(), "Allowance spender cannot be empty");
-    let allowances: Map<(String, String), u64> = Map::new(ALLOWANCES_PREFIX);
-    Ok(allowances
-        .get(&(owner.to_string(), spender.to_string()))?
+    Ok(allowances()
+        .get(&(AddressKey::parse(owner)?, AddressKey::parse(spender)?))?
         .unwrap_or(0))
 }
 
 fn write_allowance(owner: &str, spender: &str, amount: u64) -> ContractResult<()> {
     assert!(!owner.is_empty(), "Allowance owner cannot be empty");
     assert!(!spender.is_empty(), "Allowance spender cannot be empty");
-    let mut allowances: Map<(String, String), u64> = Map::new(ALLOWANCES_PREFIX);
-    let key = (owner.to_string(), spender.to_string());
+    let mut allowances = allowances();
+    let key = (AddressKey::parse(owner)?, AddressKey::parse(spender)?);
     allowances.set(&key, &amount)?;
-    let stored = allowances.get(&key)?.unwrap_or(0);
-    assert_eq!(stored, amount, "Allowance write verification failed");
+    if VERIFY_WRITES {
+        let stored = allowances.get(&key)?.unwrap_or(0);
+        assert_eq!(stored, amount, "Allowance write verification failed");
+    }
     Ok(())
 }
 
+/// Whether the owner lets transfers to the zero address burn
+fn zero_address_burns() -> ContractResult<bool> {
+    Ok(storage()
+        .get::<bool>(&KEYS.prefix(ZERO_ADDRESS_BURNS_KEY))?
+        .unwrap_or(false))
+}
+
+/// Accounts besides the owner allowed to mint
+fn minters() -> Map<String, bool> {
+    KEYS.map(MINTERS_PREFIX)
+}
+
+fn is_minter_account(account: &str) -> ContractResult<bool> {
+    Ok(minters().get(&account.to_string())?.unwrap_or(false))
+}
+
+/// Fail unless the caller is the owner or a minter
+///
+/// Minters are keyed by the normalized address, so the caller is normalized
+/// before either comparison.
+fn only_minter() -> ContractResult<()> {
+    let sender = Address::parse(context().sender())?;
+    let owner = ownable::owner()?
+        .as_deref()
+        .map(Address::parse)
+        .transpose()?;
+    if owner.as_ref() == Some(&sender) || is_minter_account(&sender)? {
+        return Ok(());
+    }
+    Err(ContractError::Unauthorized)
+}
+
 fn ensure_initialized() -> ContractResult<()> {
-    if !storage().has(METADATA_KEY) {
+    if !metadata().exists() {
         return Err(ContractError::InvalidArgument(
             "Token contract not initialized".to_string(),
         ));
@@ -160,7 +313,25 @@ fn ensure_initialized() -> ContractResult<()> {
     Ok(())
 }
 
-fn transfer_impl(from: &str, to: &str, amount: u64) -> ContractResult<()> {
+/// Move `amount` from `from` to `to`
+///
+/// The zero address is refused unless zero-address burns are on, in which
+/// case the transfer burns. The token contract itself is always refused.
+fn transfer_impl(ctx: &Context, from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    if Address::parse(to)?.is_zero() {
+        if !zero_address_burns()? {
+            return Err(ContractError::InvalidArgument(
+                ZERO_RECIPIENT_MESSAGE.to_string(),
+            ));
+        }
+        return burn_impl(from, amount);
+    }
+    if to.eq_ignore_ascii_case(ctx.contract_address()) {
+        return Err(ContractError::InvalidArgument(
+            CONTRACT_RECIPIENT_MESSAGE.to_string(),
+        ));
+    }
+
     // Input validation
     validation::validate_address(from)?;
     validation::validate_address(to)?;
@@ -174,23 +345,50 @@ fn transfer_impl(from: &str, to: &str, amount: u64) -> ContractResult<()> {
         });
     }
 
-    // Safe arithmetic operations
-    let new_from_balance = safe_math::sub(from_balance, amount)?;
+    // Safe arithmetic operations. The recipient is read after the debit so
+    // a transfer to oneself leaves the balance unchanged.
+    write_balance(from, safe_math::sub(from_balance, amount)?)?;
     let to_balance = read_balance(to)?;
-    let new_to_balance = safe_math::add(to_balance, amount)?;
+    write_balance(to, safe_math::add(to_balance, amount)?)?;
 
-    write_balance(from, new_from_balance)?;
-    write_balance(to, new_to_balance)?;
+    Ok(())
+}
 
+/// Destroy `amount` of `from`'s tokens and reduce the total supply
+fn burn_impl(from: &str, amount: u64) -> ContractResult<()> {
+    validation::validate_address(from)?;
+    validation::validate_positive_amount(amount)?;
+
+    let balance = read_balance(from)?;
+    if balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: balance,
+        });
+    }
+    let new_balance = safe_math::sub(balance, amount)?;
+    write_balance(from, new_balance)?;
+
+    let mut metadata = load_metadata()?;
+    metadata.total_supply = safe_math::sub(metadata.total_supply, amount)?;
+    save_metadata(&metadata)?;
+    admin::record(admin::BURN, from, balance, new_balance)?;
     Ok(())
 }
 
-fn execute_initialize() -> ContractResult<()> {
-    let args: InitializeArgs = read_args()?;
+/// Initialize the token contract
+///
+/// # Arguments (should be parsed from transaction data)
+/// * `name` - Token name (e.g., "Chert Token"), at most 64 bytes
+/// * `symbol` - Token symbol (e.g., "CHT"), at most 16 bytes
+/// * `decimals` - Number of decimal places (e.g., 18)
+/// * `initial_supply` - Initial token supply (will be minted to deployer)
+#[entrypoint]
+fn initialize(args: InitializeArgs) -> ContractResult<()> {
     validation::validate_non_empty(&args.name, "name")?;
     validation::validate_non_empty(&args.symbol, "symbol")?;
 
-    if storage().has(METADATA_KEY) {
+    if metadata().exists() {
         return Err(ContractError::InvalidArgument(
             "Token already initialized".to_string(),
         ));
@@ -199,254 +397,590 @@ fn execute_initialize() -> ContractResult<()> {
     let ctx = context();
     let deployer = ctx.sender();
     validation::validate_address(deployer)?;
+    initializable::initialize(initializable::CONTRACT_SCOPE)?;
 
     let metadata = TokenMetadata {
-        name: args.name.clone(),
-        symbol: args.symbol.clone(),
+        name: args.name.into(),
+        symbol: args.symbol.into(),
         decimals: args.decimals,
         total_supply: args.initial_supply,
-        owner: deployer.to_string(),
     };
 
     save_metadata(&metadata)?;
+    proxy_implementation::init_schema_version(SCHEMA_VERSION)?;
+    ownable::initialize(deployer)?;
     write_balance(deployer, args.initial_supply)?;
 
-    event!("Transfer", from: ZERO_ADDRESS, to: deployer, amount: args.initial_supply);
+    events::emit(&TransferV1 {
+        from: ZERO_ADDRESS.to_string(),
+        to: deployer.to_string(),
+        amount: args.initial_supply,
+    });
     Ok(())
 }
 
-fn execute_transfer() -> ContractResult<()> {
-    ensure_initialized()?;
-    let ctx = context();
-    let sender = ctx.sender().to_string();
-    let args: TransferArgs = read_args()?;
-    validation::validate_positive_amount(args.amount)?;
-
-    transfer_impl(&sender, &args.to, args.amount)?;
-    event!("Transfer", from: sender, to: args.to, amount: args.amount);
-    Ok(())
+/// Transfer tokens from sender to recipient
+///
+/// # Arguments (from transaction data)
+/// * `to` - Recipient address
+/// * `amount` - Amount to transfer
+#[entrypoint]
+fn transfer(args: TransferArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    write_cache::scope(|| {
+        ensure_initialized()?;
+        pausable::when_not_paused()?;
+        let ctx = context();
+        let sender = ctx.sender().to_string();
+        validation::validate_positive_amount(args.amount)?;
+
+        transfer_impl(&ctx, &sender, &args.to, args.amount)?;
+        events::emit(&TransferV1 {
+            from: sender,
+            to: args.to.into(),
+            amount: args.amount,
+        });
+        Ok(())
+    })
 }
 
-fn execute_approve() -> ContractResult<()> {
-    ensure_initialized()?;
-    let ctx = context();
-    let owner = ctx.sender().to_string();
-    let args: ApproveArgs = read_args()?;
-
-    write_allowance(&owner, &args.spender, args.amount)?;
-    event!("Approval", owner: owner, spender: args.spender, amount: args.amount);
-    Ok(())
+/// Approve a spender to transfer tokens on behalf of the sender
+///
+/// # Arguments
+/// * `spender` - Address allowed to spend
+/// * `amount` - Maximum amount they can spend
+#[entrypoint]
+fn approve(args: ApproveArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    write_cache::scope(|| {
+        ensure_initialized()?;
+        let ctx = context();
+        let owner = ctx.sender().to_string();
+
+        write_allowance(&owner, &args.spender, args.amount)?;
+        events::emit(&ApprovalV1 {
+            owner,
+            spender: args.spender.into(),
+            amount: args.amount,
+        });
+        Ok(())
+    })
 }
 
-fn execute_transfer_from() -> ContractResult<()> {
-    ensure_initialized()?;
-    let ctx = context();
-    let spender = ctx.sender().to_string();
-    let args: TransferFromArgs = read_args()?;
-    validation::validate_positive_amount(args.amount)?;
+/// Transfer tokens on behalf of another account (requires prior approval)
+///
+/// # Arguments
+/// * `from` - Account to transfer from
+/// * `to` - Recipient address
+/// * `amount` - Amount to transfer
+#[entrypoint]
+fn transfer_from(args: TransferFromArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    write_cache::scope(|| {
+        ensure_initialized()?;
+        pausable::when_not_paused()?;
+        let ctx = context();
+        let spender = ctx.sender().to_string();
+        validation::validate_positive_amount(args.amount)?;
+
+        let allowance = read_allowance(&args.from, &spender)?;
+        if allowance < args.amount {
+            return Err(ContractError::InsufficientBalance {
+                required: args.amount,
+                available: allowance,
+            });
+        }
 
-    let allowance = read_allowance(&args.from, &spender)?;
-    if allowance < args.amount {
-        return Err(ContractError::InsufficientBalance {
-            required: args.amount,
-            available: allowance,
-        });
-    }
+        transfer_impl(&ctx, &args.from, &args.to, args.amount)?;
+        let new_allowance = safe_math::sub(allowance, args.amount)?;
+        write_allowance(&args.from, &spender, new_allowance)?;
 
-    transfer_impl(&args.from, &args.to, args.amount)?;
-    let new_allowance = safe_math::sub(allowance, args.amount)?;
-    write_allowance(&args.from, &spender, new_allowance)?;
+        events::emit(&TransferV1 {
+            from: args.from.into(),
+            to: args.to.into(),
+            amount: args.amount,
+        });
+        Ok(())
+    })
+}
 
-    event!("Transfer", from: args.from, to: args.to, amount: args.amount);
-    Ok(())
+/// Query balance of an account
+///
+/// # Arguments
+/// * `account` - Address to query
+///
+/// # Returns
+/// Balance of the account
+#[entrypoint]
+fn balance_of(args: BalanceOfArgs) -> ContractResult<u64> {
+    ensure_initialized()?;
+    read_balance(&args.account)
 }
 
-fn execute_balance_of() -> ContractResult<u64> {
+/// Query the balance of an account at the end of a past block
+///
+/// # Arguments
+/// * `account` - Address to query
+/// * `block` - Block height, lower than the current one
+///
+/// # Returns
+/// Balance of the account once `block` was final
+#[entrypoint]
+fn balance_of_at(args: BalanceOfAtArgs) -> ContractResult<u64> {
     ensure_initialized()?;
-    let args: BalanceOfArgs = read_args()?;
-    let balance = read_balance(&args.account)?;
-    try_respond(&balance)?;
-    Ok(balance)
+    final_balance_at(&args.account, args.block)
 }
 
-fn execute_total_supply() -> ContractResult<u64> {
+/// List an account's balance checkpoints, oldest first
+///
+/// # Arguments
+/// * `account` - Address to query
+/// * `cursor` - Index of the first checkpoint to return
+/// * `limit` - Page size, 1-100
+///
+/// # Returns
+/// `Page<Checkpoint>` with the checkpoints, the total count and the cursor
+/// of the next page
+#[entrypoint]
+fn checkpoints(args: CheckpointsArgs) -> ContractResult<Page<Checkpoint>> {
     ensure_initialized()?;
-    let metadata = load_metadata()?;
-    try_respond(&metadata.total_supply)?;
-    Ok(metadata.total_supply)
+    let counts = checkpoint_counts();
+    let checkpoints = checkpoint_entries();
+    let account = args.account.to_key();
+    let total = counts.get(&account)?.unwrap_or(0);
+    pagination::paginate(
+        total,
+        args.cursor,
+        args.limit,
+        MAX_CHECKPOINT_PAGE,
+        |index| {
+            checkpoints
+                .get(&(account.clone(), index))?
+                .ok_or(ContractError::StorageReadFailed)
+        },
+    )
 }
 
-fn execute_decimals() -> ContractResult<u8> {
+/// Get total token supply
+///
+/// # Returns
+/// Total supply of tokens
+#[entrypoint]
+fn total_supply() -> ContractResult<u64> {
     ensure_initialized()?;
-    let metadata = load_metadata()?;
-    try_respond(&metadata.decimals)?;
-    Ok(metadata.decimals)
+    Ok(load_metadata()?.total_supply)
 }
 
-fn execute_name() -> ContractResult<()> {
+/// Get token decimals
+#[entrypoint]
+fn decimals() -> ContractResult<u8> {
     ensure_initialized()?;
-    let metadata = load_metadata()?;
-    try_respond(&metadata.name)
+    Ok(load_metadata()?.decimals)
 }
 
-fn execute_symbol() -> ContractResult<()> {
+/// Get token name
+#[entrypoint]
+fn name() -> ContractResult<String> {
     ensure_initialized()?;
-    let metadata = load_metadata()?;
-    try_respond(&metadata.symbol)
+    Ok(load_metadata()?.name)
 }
 
-fn execute_mint() -> ContractResult<()> {
+/// Get token symbol
+#[entrypoint]
+fn symbol() -> ContractResult<String> {
     ensure_initialized()?;
-    let ctx = context();
-    let caller = ctx.sender().to_string();
-    let args: MintArgs = read_args()?;
-    validation::validate_positive_amount(args.amount)?;
+    Ok(load_metadata()?.symbol)
+}
 
-    let mut metadata = load_metadata()?;
-    if caller != metadata.owner {
-        return Err(ContractError::Unauthorized);
+/// Answer several queries in one call
+///
+/// # Arguments
+/// * `requests` - Up to 32 `ViewRequest`s
+///
+/// # Returns
+/// One `ViewResult` per request, in order. A failed query answers
+/// `Failed` with its error code; the call itself fails only if the
+/// answers do not fit in one response.
+#[entrypoint]
+fn aggregate_views(args: AggregateViewsArgs) -> ContractResult<Vec<ViewResult>> {
+    ensure_initialized()?;
+    if args.requests.len() > MAX_VIEW_REQUESTS {
+        return Err(ContractError::InvalidArgument(format!(
+            "At most {} views per call",
+            MAX_VIEW_REQUESTS
+        )));
     }
-
-    let new_total = safe_math::add(metadata.total_supply, args.amount)?;
-    metadata.total_supply = new_total;
-    save_metadata(&metadata)?;
-
-    let current_balance = read_balance(&args.to)?;
-    let new_balance = safe_math::add(current_balance, args.amount)?;
-    write_balance(&args.to, new_balance)?;
-
-    event!("Transfer", from: ZERO_ADDRESS, to: args.to, amount: args.amount);
-    Ok(())
+    let results: Vec<ViewResult> = args.requests.iter().map(view).collect();
+    let encoded =
+        postcard::to_allocvec(&results).map_err(|_| ContractError::SerializationFailed)?;
+    if encoded.len() > pagination::MAX_RETURN_BYTES {
+        return Err(ContractError::InvalidArgument(
+            "Views do not fit in one response".to_string(),
+        ));
+    }
+    Ok(results)
 }
 
-/// Initialize the token contract
+/// Balance and allowances of one account, for wallets
 ///
-/// # Arguments (should be parsed from transaction data)
-/// * `name` - Token name (e.g., "Chert Token")
-/// * `symbol` - Token symbol (e.g., "CHT")
-/// * `decimals` - Number of decimal places (e.g., 18)
-/// * `initial_supply` - Initial token supply (will be minted to deployer)
-#[unsafe(no_mangle)]
-pub extern "C" fn initialize() {
-    if let Err(err) = execute_initialize() {
-        log(&format!("Initialize failed: {}", err));
+/// # Arguments
+/// * `account` - Address to describe
+/// * `spenders` - Up to 32 spenders to report allowances for
+///
+/// # Returns
+/// `AccountOverview` with the balance, the locked part of it (always 0:
+/// this token locks nothing) and one allowance per spender, in order
+#[entrypoint]
+fn account_overview(args: AccountOverviewArgs) -> ContractResult<AccountOverview> {
+    ensure_initialized()?;
+    if args.spenders.len() > MAX_VIEW_REQUESTS {
+        return Err(ContractError::InvalidArgument(format!(
+            "At most {} spenders per call",
+            MAX_VIEW_REQUESTS
+        )));
     }
+    let account = String::from(args.account);
+    let allowances = args
+        .spenders
+        .into_iter()
+        .map(|spender| {
+            Ok(SpenderAllowance {
+                amount: read_allowance(&account, &spender)?,
+                spender: spender.into(),
+            })
+        })
+        .collect::<ContractResult<Vec<_>>>()?;
+    Ok(AccountOverview {
+        balance: read_balance(&account)?,
+        locked_balance: 0,
+        account,
+        allowances,
+    })
 }
 
-/// Transfer tokens from sender to recipient
+/// Crate version, build commit and storage schema version of this deployment
+#[entrypoint]
+fn contract_version() -> ContractResult<ContractVersion> {
+    Ok(crc_standards::contract_version!(
+        proxy_implementation::schema_version()?
+    ))
+}
+
+fn view(request: &ViewRequest) -> ViewResult {
+    let result = match request {
+        ViewRequest::Name => load_metadata().map(|metadata| ViewResult::Text(metadata.name)),
+        ViewRequest::Symbol => load_metadata().map(|metadata| ViewResult::Text(metadata.symbol)),
+        ViewRequest::Decimals => {
+            load_metadata().map(|metadata| ViewResult::Decimals(metadata.decimals))
+        }
+        ViewRequest::TotalSupply => {
+            load_metadata().map(|metadata| ViewResult::Amount(metadata.total_supply))
+        }
+        ViewRequest::BalanceOf { account } => read_balance(account).map(ViewResult::Amount),
+        ViewRequest::Allowance { owner, spender } => {
+            read_allowance(owner, spender).map(ViewResult::Amount)
+        }
+        ViewRequest::BalanceOfAt { account, block } => {
+            final_balance_at(account, *block).map(ViewResult::Amount)
+        }
+    };
+    result.unwrap_or_else(|err| ViewResult::Failed(ErrorCode::of(&err).as_u16()))
+}
+
+/// Mint new tokens (owner or minter)
 ///
-/// # Arguments (from transaction data)
+/// # Arguments
 /// * `to` - Recipient address
-/// * `amount` - Amount to transfer
-#[unsafe(no_mangle)]
-pub extern "C" fn transfer() {
-    if let Err(err) = execute_transfer() {
-        log(&format!("Transfer failed: {}", err));
-    }
+/// * `amount` - Amount to mint
+#[entrypoint]
+fn mint(args: MintArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    write_cache::scope(|| {
+        ensure_initialized()?;
+        only_minter()?;
+        pausable::when_not_paused()?;
+        validation::validate_positive_amount(args.amount)?;
+
+        let mut metadata = load_metadata()?;
+
+        let new_total = safe_math::add(metadata.total_supply, args.amount)?;
+        metadata.total_supply = new_total;
+        save_metadata(&metadata)?;
+
+        let current_balance = read_balance(&args.to)?;
+        let new_balance = safe_math::add(current_balance, args.amount)?;
+        write_balance(&args.to, new_balance)?;
+
+        admin::record(admin::MINT, &args.to, current_balance, new_balance)?;
+        events::emit(&TransferV1 {
+            from: ZERO_ADDRESS.to_string(),
+            to: args.to.into(),
+            amount: args.amount,
+        });
+        Ok(())
+    })
 }
 
-/// Approve a spender to transfer tokens on behalf of the sender
+/// Let an account mint (only owner)
+///
+/// Adding an account that already mints changes nothing.
 ///
 /// # Arguments
-/// * `spender` - Address allowed to spend
-/// * `amount` - Maximum amount they can spend
-#[unsafe(no_mangle)]
-pub extern "C" fn approve() {
-    if let Err(err) = execute_approve() {
-        log(&format!("Approve failed: {}", err));
+/// * `account` - Bridge, vesting contract or other minter
+#[entrypoint]
+fn add_minter(args: MinterArgs) -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    if is_minter_account(&args.account)? {
+        return Ok(());
     }
+    minters().set(&args.account.to_string(), &true)?;
+    admin::record(admin::GRANT_ROLE, &args.account, "", MINTER_ROLE)?;
+    events::emit(&MinterAddedV1 {
+        account: args.account.into(),
+    });
+    Ok(())
 }
 
-/// Transfer tokens on behalf of another account (requires prior approval)
+/// Take minting rights away from an account (only owner)
 ///
 /// # Arguments
-/// * `from` - Account to transfer from
-/// * `to` - Recipient address
-/// * `amount` - Amount to transfer
-#[unsafe(no_mangle)]
-pub extern "C" fn transfer_from() {
-    if let Err(err) = execute_transfer_from() {
-        log(&format!("TransferFrom failed: {}", err));
+/// * `account` - Minter to remove
+#[entrypoint]
+fn remove_minter(args: MinterArgs) -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    if !is_minter_account(&args.account)? {
+        return Ok(());
     }
+    minters().remove(&args.account.to_string())?;
+    admin::record(admin::REVOKE_ROLE, &args.account, MINTER_ROLE, "")?;
+    events::emit(&MinterRemovedV1 {
+        account: args.account.into(),
+    });
+    Ok(())
 }
 
-/// Query balance of an account
+/// Check whether an account was added as a minter
+///
+/// The owner can mint without being listed.
+#[entrypoint]
+fn is_minter(args: MinterArgs) -> ContractResult<bool> {
+    is_minter_account(&args.account)
+}
+
+/// Burn tokens from the sender's balance
 ///
 /// # Arguments
-/// * `account` - Address to query
+/// * `amount` - Amount to destroy
+#[entrypoint]
+fn burn(args: BurnArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    write_cache::scope(|| {
+        ensure_initialized()?;
+        pausable::when_not_paused()?;
+        let ctx = context();
+        let sender = ctx.sender().to_string();
+
+        burn_impl(&sender, args.amount)?;
+        events::emit(&TransferV1 {
+            from: sender,
+            to: ZERO_ADDRESS.to_string(),
+            amount: args.amount,
+        });
+        Ok(())
+    })
+}
+
+/// Let transfers to the zero address burn instead of failing (only owner)
 ///
-/// # Returns
-/// Balance of the account
-#[unsafe(no_mangle)]
-pub extern "C" fn balance_of() -> u64 {
-    match execute_balance_of() {
-        Ok(value) => value,
-        Err(err) => {
-            log(&format!("balance_of failed: {}", err));
-            0
-        }
-    }
+/// Off by default, so a mistyped recipient cannot destroy tokens. Turn it
+/// on for integrations that burn by sending to the zero address.
+///
+/// # Arguments
+/// * `enabled` - True to burn on zero-address transfers
+#[entrypoint]
+fn set_zero_address_burns(args: SetZeroAddressBurnsArgs) -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    let previous = zero_address_burns()?;
+    storage().set(&KEYS.prefix(ZERO_ADDRESS_BURNS_KEY), &args.enabled)?;
+    admin::record(
+        admin::SET_PARAMETER,
+        ZERO_ADDRESS_BURNS_KEY,
+        previous,
+        args.enabled,
+    )?;
+    event!("ZeroAddressBurnsSet", enabled: args.enabled);
+    Ok(())
 }
 
-/// Get total token supply
+/// Check whether the contract is paused
+#[entrypoint]
+fn paused() -> ContractResult<bool> {
+    pausable::paused()
+}
+
+/// Pause the contract (only owner)
 ///
-/// # Returns
-/// Total supply of tokens
-#[unsafe(no_mangle)]
-pub extern "C" fn total_supply() -> u64 {
-    match execute_total_supply() {
-        Ok(value) => value,
-        Err(err) => {
-            log(&format!("total_supply failed: {}", err));
-            0
-        }
-    }
+/// Transfers and minting fail until `unpause`; approvals and queries still work.
+#[entrypoint]
+fn pause() -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    pausable::pause()
 }
 
-/// Get token decimals
-#[unsafe(no_mangle)]
-pub extern "C" fn decimals() -> u8 {
-    match execute_decimals() {
-        Ok(value) => value,
-        Err(err) => {
-            log(&format!("decimals failed: {}", err));
-            0
-        }
-    }
+/// Lift a pause (only owner)
+#[entrypoint]
+fn unpause() -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    pausable::unpause()
 }
 
-/// Get token name
-#[unsafe(no_mangle)]
-pub extern "C" fn name() {
-    if let Err(err) = execute_name() {
-        log(&format!("name failed: {}", err));
-    }
+/// Get the contract owner
+///
+/// # Returns
+/// `None` once ownership has been renounced
+#[entrypoint]
+fn owner() -> ContractResult<Option<String>> {
+    Ok(ownable::owner()?)
 }
 
-/// Get token symbol
-#[unsafe(no_mangle)]
-pub extern "C" fn symbol() {
-    if let Err(err) = execute_symbol() {
-        log(&format!("symbol failed: {}", err));
-    }
+/// Get the owner nominated by `transfer_ownership`
+#[entrypoint]
+fn pending_owner() -> ContractResult<Option<String>> {
+    Ok(ownable::pending_owner()?)
 }
 
-/// Mint new tokens (only owner)
+/// Nominate a new owner (only owner)
+///
+/// The current owner keeps control until the nominee accepts.
 ///
 /// # Arguments
-/// * `to` - Recipient address
-/// * `amount` - Amount to mint
-#[unsafe(no_mangle)]
-pub extern "C" fn mint() {
-    if let Err(err) = execute_mint() {
-        log(&format!("Mint failed: {}", err));
+/// * `new_owner` - Account to hand ownership to
+#[entrypoint]
+fn transfer_ownership(args: TransferOwnershipArgs) -> ContractResult<()> {
+    ensure_initialized()?;
+    Ok(ownable::transfer_ownership(&args.new_owner)?)
+}
+
+/// Accept a pending ownership transfer (only the nominated owner)
+#[entrypoint]
+fn accept_ownership() -> ContractResult<()> {
+    ensure_initialized()?;
+    Ok(ownable::accept_ownership()?)
+}
+
+/// Give up ownership; the minter set is frozen for good
+#[entrypoint]
+fn renounce_ownership() -> ContractResult<()> {
+    ensure_initialized()?;
+    Ok(ownable::renounce_ownership()?)
+}
+
+/// Entrypoint bodies and assert bounds for the `fuzz/` targets
+#[cfg(not(target_arch = "wasm32"))]
+#[doc(hidden)]
+pub mod fuzzing {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+    use silica_contract_sdk::handlers;
+    use silica_contract_sdk::testing::Handler;
+
+    pub const HANDLERS: &[(&str, Handler)] = handlers![
+        execute_initialize,
+        execute_transfer,
+        execute_approve,
+        execute_transfer_from,
+        execute_balance_of,
+        execute_balance_of_at,
+        execute_checkpoints,
+        execute_account_overview,
+        execute_total_supply,
+        execute_decimals,
+        execute_name,
+        execute_symbol,
+        execute_mint,
+        execute_add_minter,
+        execute_remove_minter,
+        execute_is_minter,
+        execute_burn,
+        execute_set_zero_address_burns,
+        execute_paused,
+        execute_pause,
+        execute_unpause,
+        execute_owner,
+        execute_pending_owner,
+        execute_transfer_ownership,
+        execute_accept_ownership,
+        execute_renounce_ownership,
+    ];
+
+    /// Panics the contract raises on purpose
+    pub const ALLOWED_PANICS: &[&str] = &[
+        "Call data exceeds static bound",
+        "Return payload exceeds static bound",
+        "Balance lookup requires non-empty address",
+        "Balance write requires non-empty address",
+        "Allowance owner cannot be empty",
+        "Allowance spender cannot be empty",
+    ];
+
+    pub const DEPLOYER: &str = "0x0000000000000000000000000000000000000d01";
+
+    /// Fresh mock runtime with the token initialized by [`DEPLOYER`]
+    pub fn setup() {
+        mock::reset();
+        mock::set_sender(DEPLOYER);
+        mock::set_contract_address("crc20_contract");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        let args = InitializeArgs {
+            name: Name::new("Chert Token").unwrap(),
+            symbol: Symbol::new("CHT").unwrap(),
+            decimals: 18,
+            initial_supply: 1_000,
+        };
+        mock::set_call_data(&postcard::to_allocvec(&args).unwrap());
+        execute_initialize().unwrap();
+        mock::take_events();
+    }
+
+    /// Well-formed call data for mutation
+    pub fn seeds() -> Vec<Vec<u8>> {
+        let to: Address = DEPLOYER.parse().unwrap();
+        vec![
+            postcard::to_allocvec(&TransferArgs {
+                to: to.clone(),
+                amount: 5,
+            })
+            .unwrap(),
+            postcard::to_allocvec(&TransferFromArgs {
+                from: to.clone(),
+                to: to.clone(),
+                amount: 5,
+            })
+            .unwrap(),
+            postcard::to_allocvec(&BalanceOfAtArgs {
+                account: to.clone(),
+                block: 1,
+            })
+            .unwrap(),
+            postcard::to_allocvec(&MintArgs { to, amount: 5 }).unwrap(),
+        ]
     }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
+    use crc_errors::ErrorCode;
+    use events::AdminActionV1;
+    use scenario::Scenario;
     use silica_contract_sdk::ffi::mock;
+    use silica_contract_sdk::testing::{self, Rng};
     use std::sync::{Mutex, OnceLock};
 
     const ADDR_DEPLOYER: &str = "0x0000000000000000000000000000000000000d01";
@@ -454,6 +988,7 @@ mod tests {
     const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000c03";
     const ADDR_DAVE: &str = "0x0000000000000000000000000000000000000d04";
     const ADDR_EVE: &str = "0x0000000000000000000000000000000000000e05";
+    const ADDR_NATIVE: &str = "chert1holder0000000000000000000";
 
     fn test_lock() -> &'static Mutex<()> {
         static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
@@ -475,8 +1010,8 @@ mod tests {
     fn init_default() {
         setup_runtime(ADDR_DEPLOYER);
         let args = InitializeArgs {
-            name: "Chert Token".to_string(),
-            symbol: "CHT".to_string(),
+            name: Name::new("Chert Token").unwrap(),
+            symbol: Symbol::new("CHT").unwrap(),
             decimals: 18,
             initial_supply: 1_000,
         };
@@ -490,8 +1025,8 @@ mod tests {
         let _guard = test_lock().lock().expect("test mutex poisoned");
         init_default();
 
-        let metadata = storage()
-            .get::<TokenMetadata>(METADATA_KEY)
+        let metadata = metadata()
+            .get()
             .expect("metadata read")
             .expect("metadata exists");
         assert_eq!(metadata.name, "Chert Token");
@@ -502,6 +1037,210 @@ mod tests {
         assert_eq!(balance, 1_000);
     }
 
+    #[test]
+    fn storage_from_before_namespacing_stays_readable() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup_runtime(ADDR_DEPLOYER);
+        // The layout of deployments that used bare keys.
+        storage()
+            .set(
+                METADATA_KEY,
+                &TokenMetadata {
+                    name: "Chert Token".to_string(),
+                    symbol: "CHT".to_string(),
+                    decimals: 18,
+                    total_supply: 1_000,
+                },
+            )
+            .unwrap();
+        let mut legacy_balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
+        let mut legacy_allowances: Map<(String, String), u64> = Map::new(ALLOWANCES_PREFIX);
+        let deployer = ADDR_DEPLOYER.to_string();
+        legacy_balances.set(&deployer, &1_000).unwrap();
+        legacy_allowances
+            .set(&(deployer.clone(), ADDR_BOB.to_string()), &40)
+            .unwrap();
+
+        assert_eq!(load_metadata().unwrap().symbol, "CHT");
+        assert_eq!(read_allowance(ADDR_DEPLOYER, ADDR_BOB).unwrap(), 40);
+        mock::set_call_data(&encode(&InitializeArgs {
+            name: Name::new("Other").unwrap(),
+            symbol: Symbol::new("OTH").unwrap(),
+            decimals: 0,
+            initial_supply: 1,
+        }));
+        assert!(matches!(
+            execute_initialize(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Token already initialized"
+        ));
+
+        // Writes move entries to their namespaced keys.
+        mock::set_call_data(&encode(&TransferArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            amount: 100,
+        }));
+        execute_transfer().unwrap();
+        assert_eq!(legacy_balances.get(&deployer).unwrap(), None);
+        assert_eq!(read_balance(ADDR_DEPLOYER).unwrap(), 900);
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 100);
+        assert!(
+            balances()
+                .storage_key(&AddressKey::parse(ADDR_DEPLOYER).unwrap())
+                .unwrap()
+                .starts_with("crc20/balances/v2:")
+        );
+        // Untouched entries keep their legacy key.
+        assert!(
+            legacy_allowances
+                .contains_key(&(deployer, ADDR_BOB.to_string()))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn legacy_entries_under_other_spellings_stay_readable() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        // Earlier layouts keyed accounts by whatever string the caller
+        // passed, checksummed or uppercase included.
+        let checksummed = Address::parse(ADDR_CAROL).unwrap().to_checksum();
+        let upper = format!("0x{}", ADDR_DAVE[2..].to_ascii_uppercase());
+        let mut legacy_balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
+        let mut legacy_allowances: Map<(String, String), u64> = Map::new(ALLOWANCES_PREFIX);
+        legacy_balances.set(&upper, &70).unwrap();
+        legacy_allowances
+            .set(&(checksummed.clone(), upper.clone()), &30)
+            .unwrap();
+
+        assert_eq!(read_balance(ADDR_DAVE).unwrap(), 70);
+        assert_eq!(read_allowance(ADDR_CAROL, ADDR_DAVE).unwrap(), 30);
+        write_allowance(ADDR_CAROL, ADDR_DAVE, 5).unwrap();
+        assert!(
+            !legacy_allowances
+                .contains_key(&(checksummed, upper))
+                .unwrap()
+        );
+        assert_eq!(read_allowance(ADDR_CAROL, ADDR_DAVE).unwrap(), 5);
+    }
+
+    #[test]
+    fn native_address_holders_keep_their_balances() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        // A holder credited under its native address before the address
+        // crate, keyed by the raw string.
+        let mut legacy_balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
+        legacy_balances.set(&ADDR_NATIVE.to_string(), &250).unwrap();
+        assert_eq!(read_balance(ADDR_NATIVE).unwrap(), 250);
+
+        mock::set_sender(ADDR_NATIVE);
+        mock::set_call_data(&encode(&TransferArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            amount: 50,
+        }));
+        execute_transfer().unwrap();
+        assert_eq!(read_balance(ADDR_NATIVE).unwrap(), 200);
+        assert_eq!(legacy_balances.get(&ADDR_NATIVE.to_string()).unwrap(), None);
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 50);
+
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&TransferArgs {
+            to: ADDR_NATIVE.parse().unwrap(),
+            amount: 10,
+        }));
+        execute_transfer().unwrap();
+        assert_eq!(read_balance(ADDR_NATIVE).unwrap(), 210);
+    }
+
+    #[test]
+    fn cached_entrypoints_end_in_the_same_state_as_direct_writes() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let run = |cached: bool| {
+            init_default();
+            write_allowance(ADDR_DEPLOYER, ADDR_BOB, 300).unwrap();
+            mock::set_block_height(2);
+            if cached {
+                mock::set_call_data(&encode(&TransferArgs {
+                    to: ADDR_CAROL.parse().unwrap(),
+                    amount: 100,
+                }));
+                execute_transfer().unwrap();
+                mock::set_sender(ADDR_BOB);
+                mock::set_call_data(&encode(&TransferFromArgs {
+                    from: ADDR_DEPLOYER.parse().unwrap(),
+                    to: ADDR_DAVE.parse().unwrap(),
+                    amount: 50,
+                }));
+                execute_transfer_from().unwrap();
+            } else {
+                let lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE).unwrap();
+                transfer_impl(&context(), ADDR_DEPLOYER, ADDR_CAROL, 100).unwrap();
+                drop(lock);
+                let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE).unwrap();
+                transfer_impl(&context(), ADDR_DEPLOYER, ADDR_DAVE, 50).unwrap();
+                write_allowance(ADDR_DEPLOYER, ADDR_BOB, 250).unwrap();
+                // The events' sequence number is stored too.
+                for (to, amount) in [(ADDR_CAROL, 100), (ADDR_DAVE, 50)] {
+                    events::emit(&TransferV1 {
+                        from: ADDR_DEPLOYER.to_string(),
+                        to: to.to_string(),
+                        amount,
+                    });
+                }
+            }
+            testing::StorageSnapshot::take()
+        };
+        let direct = run(false);
+        assert_eq!(run(true), direct);
+        assert!(!write_cache::is_active());
+    }
+
+    #[test]
+    fn string_keyed_layout_stays_readable() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        // Layout 1 keyed accounts by their hex string.
+        let mut v1_balances: Map<String, u64> = KEYS.map(BALANCES_PREFIX);
+        let mut v1_counts: Map<String, u64> = KEYS.map(CHECKPOINT_COUNT_PREFIX);
+        let mut v1_checkpoints: Map<(String, u64), Checkpoint> = KEYS.map(CHECKPOINTS_PREFIX);
+        let mut v1_allowances: Map<(String, String), u64> = KEYS.map(ALLOWANCES_PREFIX);
+        let carol = ADDR_CAROL.to_string();
+        v1_balances.set(&carol, &70).unwrap();
+        v1_counts.set(&carol, &1).unwrap();
+        v1_checkpoints
+            .set(
+                &(carol.clone(), 0),
+                &Checkpoint {
+                    block: 1,
+                    balance: 70,
+                },
+            )
+            .unwrap();
+        v1_allowances
+            .set(&(carol.clone(), ADDR_DAVE.to_string()), &25)
+            .unwrap();
+        mock::set_block_height(5);
+
+        assert_eq!(read_balance(ADDR_CAROL).unwrap(), 70);
+        assert_eq!(read_balance_at(ADDR_CAROL, 1).unwrap(), 70);
+        assert_eq!(read_allowance(ADDR_CAROL, ADDR_DAVE).unwrap(), 25);
+
+        mock::set_sender(ADDR_DAVE);
+        mock::set_call_data(&encode(&TransferFromArgs {
+            from: ADDR_CAROL.parse().unwrap(),
+            to: ADDR_DAVE.parse().unwrap(),
+            amount: 20,
+        }));
+        execute_transfer_from().unwrap();
+        assert!(!v1_balances.contains_key(&carol).unwrap());
+        assert!(!v1_counts.contains_key(&carol).unwrap());
+        assert_eq!(read_balance(ADDR_CAROL).unwrap(), 50);
+        assert_eq!(read_allowance(ADDR_CAROL, ADDR_DAVE).unwrap(), 5);
+        // History written before the move is still found.
+        assert_eq!(read_balance_at(ADDR_CAROL, 1).unwrap(), 70);
+        assert_eq!(read_balance_at(ADDR_CAROL, 4).unwrap(), 70);
+    }
+
     #[test]
     fn transfer_moves_balance_and_emits_event() {
         let _guard = test_lock().lock().expect("test mutex poisoned");
@@ -509,7 +1248,7 @@ mod tests {
 
         mock::set_sender(ADDR_DEPLOYER);
         let args = TransferArgs {
-            to: ADDR_BOB.to_string(),
+            to: ADDR_BOB.parse().unwrap(),
             amount: 200,
         };
         mock::set_call_data(&encode(&args));
@@ -520,8 +1259,87 @@ mod tests {
         assert_eq!(deployer_balance, 800);
         assert_eq!(bob_balance, 200);
 
-        let events = mock::take_events();
-        assert!(!events.is_empty(), "transfer should emit event");
+        let emitted = mock::take_events();
+        let transfer = events::decode::<TransferV1>(&emitted[0].data).unwrap();
+        assert_eq!(
+            transfer,
+            Some(TransferV1 {
+                from: ADDR_DEPLOYER.to_string(),
+                to: ADDR_BOB.to_string(),
+                amount: 200,
+            })
+        );
+        assert_eq!(
+            emitted[0].indexed,
+            [
+                silica_contract_sdk::events::indexed_topic(ADDR_DEPLOYER),
+                silica_contract_sdk::events::indexed_topic(ADDR_BOB),
+            ]
+        );
+    }
+
+    #[test]
+    fn pause_blocks_transfers_until_lifted() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        let transfer_args = encode(&TransferArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            amount: 10,
+        });
+
+        mock::set_sender(ADDR_BOB);
+        pause();
+        mock::set_sender(ADDR_DEPLOYER);
+        pause();
+        mock::set_call_data(&transfer_args);
+        transfer();
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 0);
+        let failure = crc_errors::ErrorPayload::decode(&mock::take_return_data()).unwrap();
+        assert_eq!(failure.error_code(), crc_errors::ErrorCode::Paused);
+        mock::set_call_data(&encode(&ApproveArgs {
+            spender: ADDR_CAROL.parse().unwrap(),
+            amount: 5,
+        }));
+        approve();
+        assert_eq!(read_allowance(ADDR_DEPLOYER, ADDR_CAROL).unwrap(), 5);
+
+        unpause();
+        mock::set_call_data(&transfer_args);
+        transfer();
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 10);
+        let topics: Vec<String> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(
+            topics,
+            [
+                "Paused",
+                "AdminAction",
+                "Approval",
+                "Unpaused",
+                "AdminAction",
+                "Transfer"
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_transfers_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mock::set_call_data(&encode(&TransferArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            amount: 10,
+        }));
+
+        let outer = reentrancy::enter(reentrancy::GLOBAL_SCOPE).unwrap();
+        assert!(matches!(
+            execute_transfer(),
+            Err(ContractError::ReentrancyDetected)
+        ));
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 0);
+        drop(outer);
+
+        execute_transfer().unwrap();
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 10);
     }
 
     #[test]
@@ -531,7 +1349,7 @@ mod tests {
 
         mock::set_sender(ADDR_DEPLOYER);
         let approve_args = ApproveArgs {
-            spender: ADDR_CAROL.to_string(),
+            spender: ADDR_CAROL.parse().unwrap(),
             amount: 300,
         };
         mock::set_call_data(&encode(&approve_args));
@@ -539,8 +1357,8 @@ mod tests {
 
         mock::set_sender(ADDR_CAROL);
         let transfer_from_args = TransferFromArgs {
-            from: ADDR_DEPLOYER.to_string(),
-            to: ADDR_DAVE.to_string(),
+            from: ADDR_DEPLOYER.parse().unwrap(),
+            to: ADDR_DAVE.parse().unwrap(),
             amount: 150,
         };
         mock::set_call_data(&encode(&transfer_from_args));
@@ -559,7 +1377,7 @@ mod tests {
 
         mock::set_sender(ADDR_DEPLOYER);
         let args = MintArgs {
-            to: ADDR_EVE.to_string(),
+            to: ADDR_EVE.parse().unwrap(),
             amount: 250,
         };
         mock::set_call_data(&encode(&args));
@@ -571,13 +1389,266 @@ mod tests {
         assert_eq!(eve_balance, 250);
     }
 
+    #[test]
+    fn minters_mint_until_the_owner_removes_them() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        let bridge = encode(&MinterArgs {
+            account: ADDR_BOB.parse().unwrap(),
+        });
+        let mint_to_eve = |sender: &str| {
+            mock::set_sender(sender);
+            mock::set_call_data(&encode(&MintArgs {
+                to: ADDR_EVE.parse().unwrap(),
+                amount: 10,
+            }));
+            mint();
+            read_balance(ADDR_EVE).unwrap()
+        };
+
+        assert_eq!(mint_to_eve(ADDR_BOB), 0);
+        // Only the owner manages minters.
+        mock::set_call_data(&bridge);
+        add_minter();
+        assert!(!is_minter_account(ADDR_BOB).unwrap());
+
+        mock::set_sender(ADDR_DEPLOYER);
+        mock::set_call_data(&bridge);
+        add_minter();
+        add_minter();
+        is_minter();
+        assert!(postcard::from_bytes::<bool>(&mock::take_return_data()).unwrap());
+        assert_eq!(mint_to_eve(ADDR_BOB), 10);
+        // The owner mints without being listed.
+        assert_eq!(mint_to_eve(ADDR_DEPLOYER), 20);
+
+        mock::set_call_data(&bridge);
+        remove_minter();
+        assert_eq!(mint_to_eve(ADDR_BOB), 20);
+        assert_eq!(load_metadata().unwrap().total_supply, 1_020);
+
+        let events = mock::take_events();
+        let added: Vec<_> = events
+            .iter()
+            .filter(|e| e.topic == "MinterAdded")
+            .map(|e| events::decode::<MinterAddedV1>(&e.data).unwrap())
+            .collect();
+        let removed: Vec<_> = events
+            .iter()
+            .filter(|e| e.topic == "MinterRemoved")
+            .map(|e| events::decode::<MinterRemovedV1>(&e.data).unwrap())
+            .collect();
+        assert_eq!(
+            added,
+            [Some(MinterAddedV1 {
+                account: ADDR_BOB.to_string(),
+            })]
+        );
+        assert_eq!(
+            removed,
+            [Some(MinterRemovedV1 {
+                account: ADDR_BOB.to_string(),
+            })]
+        );
+    }
+
+    #[test]
+    fn minters_are_recognized_whatever_the_case_of_the_sender() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        const MINTER: &str = "0x00000000000000000000000000000000000abc06";
+        mock::set_call_data(&encode(&MinterArgs {
+            account: MINTER.parse().unwrap(),
+        }));
+        add_minter();
+
+        // Checksummed and all-uppercase spellings of the minter and owner
+        for sender in [
+            Address::parse(MINTER).unwrap().to_checksum(),
+            format!("0x{}", MINTER[2..].to_ascii_uppercase()),
+            format!("0x{}", ADDR_DEPLOYER[2..].to_ascii_uppercase()),
+        ] {
+            mock::set_sender(&sender);
+            mock::set_call_data(&encode(&MintArgs {
+                to: ADDR_EVE.parse().unwrap(),
+                amount: 10,
+            }));
+            mint();
+        }
+        assert_eq!(read_balance(ADDR_EVE).unwrap(), 30);
+    }
+
+    #[test]
+    fn zero_address_and_contract_recipients_are_refused_unless_burning() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        const TOKEN: &str = "0x0000000000000000000000000000000000000c20";
+        let transfer_to = |to: &str, amount: u64| {
+            mock::set_call_data(&encode(&TransferArgs {
+                to: to.parse().unwrap(),
+                amount,
+            }));
+            transfer();
+            let data = mock::take_return_data();
+            (!data.is_empty()).then(|| {
+                crc_errors::ErrorPayload::decode(&data)
+                    .unwrap()
+                    .error_code()
+            })
+        };
+        // A hex contract address, so the token itself can be named as recipient.
+        setup_runtime(ADDR_DEPLOYER);
+        mock::set_contract_address(TOKEN);
+        mock::set_call_data(&encode(&InitializeArgs {
+            name: Name::new("Chert Token").unwrap(),
+            symbol: Symbol::new("CHT").unwrap(),
+            decimals: 18,
+            initial_supply: 1_000,
+        }));
+        initialize();
+        mock::take_events();
+
+        let long_zero = "0x0000000000000000000000000000000000000000";
+        for to in [ZERO_ADDRESS, long_zero] {
+            assert_eq!(transfer_to(to, 10), Some(ErrorCode::ZeroAddressRecipient));
+        }
+        assert_eq!(transfer_to(TOKEN, 10), Some(ErrorCode::ContractRecipient));
+        assert_eq!(read_balance(ADDR_DEPLOYER).unwrap(), 1_000);
+
+        // The burn entrypoint, or zero-address transfers once the owner opts in.
+        mock::set_call_data(&encode(&BurnArgs { amount: 100 }));
+        burn();
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&SetZeroAddressBurnsArgs { enabled: true }));
+        assert!(matches!(
+            execute_set_zero_address_burns(),
+            Err(ContractError::Unauthorized)
+        ));
+        mock::set_sender(ADDR_DEPLOYER);
+        set_zero_address_burns();
+        assert_eq!(transfer_to(ZERO_ADDRESS, 50), None);
+        assert_eq!(transfer_to(TOKEN, 10), Some(ErrorCode::ContractRecipient));
+        assert_eq!(read_balance(ADDR_DEPLOYER).unwrap(), 850);
+        assert_eq!(load_metadata().unwrap().total_supply, 850);
+        // Both kinds of burn are audited with the holder's new balance.
+        let actions: Vec<(String, String)> = mock::take_events()
+            .iter()
+            .filter(|e| e.topic == "AdminAction")
+            .filter_map(|e| events::decode::<AdminActionV1>(&e.data).unwrap())
+            .map(|a| (a.action, a.new_value))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (admin::BURN.to_string(), "900".to_string()),
+                (admin::SET_PARAMETER.to_string(), "true".to_string()),
+                (admin::BURN.to_string(), "850".to_string()),
+            ]
+        );
+        mock::set_call_data(&encode(&BurnArgs { amount: 851 }));
+        assert!(matches!(
+            execute_burn(),
+            Err(ContractError::InsufficientBalance {
+                required: 851,
+                available: 850
+            })
+        ));
+    }
+
+    #[test]
+    fn ownership_transfer_gates_minting() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        let mint_args = encode(&MintArgs {
+            to: ADDR_EVE.parse().unwrap(),
+            amount: 5,
+        });
+
+        mock::set_call_data(&encode(&TransferOwnershipArgs {
+            new_owner: ADDR_BOB.to_string(),
+        }));
+        transfer_ownership();
+        // Nominating does not hand over control yet.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&mint_args);
+        mint();
+        assert_eq!(read_balance(ADDR_EVE).unwrap(), 0);
+
+        mock::set_call_data(&[]);
+        accept_ownership();
+        assert_eq!(ownable::owner().unwrap().as_deref(), Some(ADDR_BOB));
+        mock::set_call_data(&mint_args);
+        mint();
+        assert_eq!(read_balance(ADDR_EVE).unwrap(), 5);
+
+        mock::set_sender(ADDR_DEPLOYER);
+        mint();
+        assert_eq!(read_balance(ADDR_EVE).unwrap(), 5);
+
+        mock::set_sender(ADDR_BOB);
+        renounce_ownership();
+        owner();
+        let owner: Option<String> = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(owner, None);
+        let emitted = mock::take_events();
+        let topics: Vec<&str> = emitted.iter().map(|e| e.topic.as_str()).collect();
+        assert_eq!(
+            topics,
+            [
+                "OwnershipTransferStarted",
+                "AdminAction",
+                "OwnershipTransferred",
+                "AdminAction",
+                "AdminAction",
+                "Transfer",
+                "OwnershipTransferred",
+                "AdminAction"
+            ]
+        );
+        // Each privileged call is in the audit log, numbered in order.
+        let actions: Vec<(String, String, String, u64)> = emitted
+            .iter()
+            .filter(|e| e.topic == "AdminAction")
+            .filter_map(|e| events::decode::<AdminActionV1>(&e.data).unwrap())
+            .map(|a| (a.actor, a.action, a.new_value, a.sequence))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (
+                    ADDR_DEPLOYER.to_string(),
+                    admin::TRANSFER_OWNERSHIP.to_string(),
+                    ADDR_BOB.to_string(),
+                    0
+                ),
+                (
+                    ADDR_BOB.to_string(),
+                    admin::ACCEPT_OWNERSHIP.to_string(),
+                    ADDR_BOB.to_string(),
+                    1
+                ),
+                (
+                    ADDR_BOB.to_string(),
+                    admin::MINT.to_string(),
+                    "5".to_string(),
+                    2
+                ),
+                (
+                    ADDR_BOB.to_string(),
+                    admin::RENOUNCE_OWNERSHIP.to_string(),
+                    String::new(),
+                    3
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn metadata_queries_return_values() {
         let _guard = test_lock().lock().expect("test mutex poisoned");
         init_default();
 
         mock::set_call_data(&encode(&BalanceOfArgs {
-            account: ADDR_DEPLOYER.to_string(),
+            account: ADDR_DEPLOYER.parse().unwrap(),
         }));
         let balance = balance_of();
         let balance_bytes = mock::take_return_data();
@@ -605,4 +1676,434 @@ mod tests {
         let symbol_value: String = postcard::from_bytes(&symbol_bytes).expect("decode symbol");
         assert_eq!(symbol_value, "CHT");
     }
+
+    #[test]
+    fn contract_version_reports_the_build_and_schema() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "crc20-token",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                SCHEMA_VERSION
+            )
+        );
+        assert_eq!(version.schema_version, 1);
+    }
+
+    #[test]
+    fn aggregate_views_answer_each_request_in_order() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mock::set_call_data(&encode(&ApproveArgs {
+            spender: ADDR_BOB.parse().unwrap(),
+            amount: 40,
+        }));
+        approve();
+        mock::set_block_height(3);
+
+        let deployer: Address = ADDR_DEPLOYER.parse().unwrap();
+        mock::set_call_data(&encode(&AggregateViewsArgs {
+            requests: vec![
+                ViewRequest::Symbol,
+                ViewRequest::Decimals,
+                ViewRequest::TotalSupply,
+                ViewRequest::BalanceOf {
+                    account: deployer.clone(),
+                },
+                ViewRequest::Allowance {
+                    owner: deployer.clone(),
+                    spender: ADDR_BOB.parse().unwrap(),
+                },
+                ViewRequest::BalanceOfAt {
+                    account: deployer,
+                    block: 3,
+                },
+                ViewRequest::Name,
+            ],
+        }));
+        aggregate_views();
+        let results: Vec<ViewResult> = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(
+            results,
+            [
+                ViewResult::Text("CHT".to_string()),
+                ViewResult::Decimals(18),
+                ViewResult::Amount(1_000),
+                ViewResult::Amount(1_000),
+                ViewResult::Amount(40),
+                // The current block is not final; only this query fails.
+                ViewResult::Failed(ErrorCode::InvalidArgument.as_u16()),
+                ViewResult::Text("Chert Token".to_string()),
+            ]
+        );
+
+        mock::set_call_data(&encode(&AggregateViewsArgs {
+            requests: vec![ViewRequest::Name; MAX_VIEW_REQUESTS + 1],
+        }));
+        aggregate_views();
+        assert!(mock::take_logs()[0].contains("At most 32 views per call"));
+    }
+
+    #[test]
+    fn account_overview_answers_balance_and_allowances() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mock::set_call_data(&encode(&ApproveArgs {
+            spender: ADDR_BOB.parse().unwrap(),
+            amount: 40,
+        }));
+        approve();
+
+        let overview = |spenders: Vec<Address>| {
+            mock::set_call_data(&encode(&AccountOverviewArgs {
+                account: ADDR_DEPLOYER.parse().unwrap(),
+                spenders,
+            }));
+            execute_account_overview()
+        };
+        let bob: Address = ADDR_BOB.parse().unwrap();
+        let carol: Address = ADDR_CAROL.parse().unwrap();
+        assert_eq!(
+            overview(vec![carol.clone(), bob.clone()]).unwrap(),
+            AccountOverview {
+                account: ADDR_DEPLOYER.to_string(),
+                balance: 1_000,
+                locked_balance: 0,
+                allowances: vec![
+                    SpenderAllowance {
+                        spender: ADDR_CAROL.to_string(),
+                        amount: 0,
+                    },
+                    SpenderAllowance {
+                        spender: ADDR_BOB.to_string(),
+                        amount: 40,
+                    },
+                ],
+            }
+        );
+        assert!(overview(Vec::new()).unwrap().allowances.is_empty());
+        assert!(matches!(
+            overview(vec![bob; MAX_VIEW_REQUESTS + 1]),
+            Err(ContractError::InvalidArgument(msg)) if msg == "At most 32 spenders per call"
+        ));
+
+        // The wrapper answers the same struct.
+        mock::set_call_data(&encode(&AccountOverviewArgs {
+            account: ADDR_BOB.parse().unwrap(),
+            spenders: vec![carol],
+        }));
+        account_overview();
+        let answer: AccountOverview = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!((answer.balance, answer.allowances[0].amount), (0, 0));
+    }
+
+    #[test]
+    fn balance_of_at_returns_historical_balances() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+
+        let send = |amount: u64| {
+            mock::set_call_data(&encode(&TransferArgs {
+                to: ADDR_BOB.parse().unwrap(),
+                amount,
+            }));
+            transfer();
+        };
+        mock::set_block_height(5);
+        send(100);
+        send(50);
+        mock::set_block_height(9);
+        send(25);
+        mock::set_block_height(12);
+
+        let balance_at = |account: &str, block: u64| {
+            mock::set_call_data(&encode(&BalanceOfAtArgs {
+                account: account.parse().unwrap(),
+                block,
+            }));
+            balance_of_at()
+        };
+        assert_eq!(balance_at(ADDR_BOB, 4), 0);
+        assert_eq!(balance_at(ADDR_BOB, 5), 150);
+        assert_eq!(balance_at(ADDR_BOB, 8), 150);
+        assert_eq!(balance_at(ADDR_BOB, 11), 175);
+        assert_eq!(balance_at(ADDR_DEPLOYER, 1), 1_000);
+        assert_eq!(balance_at(ADDR_DEPLOYER, 6), 850);
+
+        // The current block is not final yet.
+        assert_eq!(balance_at(ADDR_BOB, 12), 0);
+        assert!(mock::take_logs()[0].contains("Block must be in the past"));
+    }
+
+    #[test]
+    fn checkpoints_are_paged_oldest_first() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        for block in 2..=6 {
+            mock::set_block_height(block);
+            mock::set_call_data(&encode(&TransferArgs {
+                to: ADDR_BOB.parse().unwrap(),
+                amount: 10,
+            }));
+            transfer();
+        }
+
+        let page = |cursor: u64, limit: u64| {
+            mock::set_call_data(&encode(&CheckpointsArgs {
+                account: ADDR_BOB.parse().unwrap(),
+                cursor,
+                limit,
+            }));
+            checkpoints();
+            let data = mock::take_return_data();
+            postcard::from_bytes::<Page<Checkpoint>>(&data).ok()
+        };
+        let first = page(0, 3).expect("first page");
+        assert_eq!(first.total, 5);
+        assert_eq!(first.next_cursor, Some(3));
+        assert_eq!(
+            first.items[0],
+            Checkpoint {
+                block: 2,
+                balance: 10
+            }
+        );
+        let rest = page(3, 3).expect("second page");
+        assert_eq!(rest.items.len(), 2);
+        assert_eq!(rest.items[1].balance, 50);
+        assert_eq!(rest.next_cursor, None);
+
+        assert!(page(0, 101).is_none());
+        assert!(mock::take_logs()[0].contains("Page size must be 1-100"));
+    }
+
+    const ACCOUNTS: [&str; 4] = [ADDR_DEPLOYER, ADDR_BOB, ADDR_CAROL, ADDR_DAVE];
+
+    /// Balances, allowances and supply as seen through storage
+    #[derive(Debug, PartialEq)]
+    struct Ledger {
+        balances: Vec<u64>,
+        allowances: Vec<(&'static str, &'static str, u64)>,
+        total_supply: u64,
+    }
+
+    fn ledger() -> Ledger {
+        let mut allowances = Vec::new();
+        for owner in ACCOUNTS {
+            for spender in ACCOUNTS {
+                allowances.push((owner, spender, read_allowance(owner, spender).unwrap()));
+            }
+        }
+        Ledger {
+            balances: ACCOUNTS
+                .map(|account| read_balance(account).unwrap())
+                .to_vec(),
+            allowances,
+            total_supply: load_metadata().unwrap().total_supply,
+        }
+    }
+
+    /// Call `entrypoint` as `sender`; false if it reported an error
+    fn call_as<T: Serialize>(sender: &str, entrypoint: extern "C" fn(), args: &T) -> bool {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(args));
+        entrypoint();
+        mock::take_return_data().is_empty()
+    }
+
+    /// One state-changing call with random caller, accounts and amount
+    fn random_operation(rng: &mut Rng) -> bool {
+        let sender = *rng.pick(&ACCOUNTS);
+        let other: Address = rng.pick(&ACCOUNTS).parse().unwrap();
+        let third: Address = rng.pick(&ACCOUNTS).parse().unwrap();
+        let amount = if rng.chance(5) {
+            u64::MAX
+        } else {
+            rng.between(0, 400)
+        };
+        match rng.below(7) {
+            0 | 1 => call_as(sender, transfer, &TransferArgs { to: other, amount }),
+            2 => call_as(
+                sender,
+                approve,
+                &ApproveArgs {
+                    spender: other,
+                    amount,
+                },
+            ),
+            3 | 4 => call_as(
+                sender,
+                transfer_from,
+                &TransferFromArgs {
+                    from: other,
+                    to: third,
+                    amount,
+                },
+            ),
+            5 => call_as(sender, mint, &MintArgs { to: other, amount }),
+            _ if rng.chance(50) => call_as(sender, pause, &()),
+            _ => call_as(sender, unpause, &()),
+        }
+    }
+
+    #[test]
+    fn balances_sum_to_total_supply() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        testing::check("balances_sum_to_total_supply", |rng| {
+            init_default();
+            for _ in 0..40 {
+                let before = ledger();
+                let succeeded = random_operation(rng);
+                let events = mock::take_events();
+                let after = ledger();
+
+                assert_eq!(after.balances.iter().sum::<u64>(), after.total_supply);
+                if !succeeded {
+                    assert_eq!(before, after, "a failed call changed state");
+                    assert!(events.is_empty(), "a failed call emitted events");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn allowances_only_grow_through_approval() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        testing::check("allowances_only_grow_through_approval", |rng| {
+            init_default();
+            for _ in 0..40 {
+                let before = ledger();
+                random_operation(rng);
+                let approvals: Vec<ApprovalV1> = mock::take_events()
+                    .iter()
+                    .filter(|event| event.topic == "Approval")
+                    .filter_map(|event| events::decode::<ApprovalV1>(&event.data).ok()?)
+                    .collect();
+                let after = ledger();
+
+                for (old, new) in before.allowances.iter().zip(&after.allowances) {
+                    let (owner, spender, allowance) = *new;
+                    if allowance > old.2 {
+                        let announced = ApprovalV1 {
+                            owner: owner.to_string(),
+                            spender: spender.to_string(),
+                            amount: allowance,
+                        };
+                        assert!(
+                            approvals.contains(&announced),
+                            "{} -> {} grew to {} without an Approval event",
+                            owner,
+                            spender,
+                            allowance
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn malformed_call_data_is_rejected_cleanly() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let seeds = fuzzing::seeds();
+        testing::check("malformed_call_data_is_rejected_cleanly", |rng| {
+            fuzzing::setup();
+            for _ in 0..16 {
+                let input = testing::random_call_data(rng, &seeds);
+                testing::fuzz_call(fuzzing::HANDLERS, fuzzing::ALLOWED_PANICS, &input);
+            }
+        });
+    }
+
+    /// Deployer approves `spender` for `amount`
+    fn approved(spender: &'static str, amount: u64) -> impl FnOnce(Scenario) -> Scenario {
+        move |scenario| {
+            scenario
+                .as_sender(ADDR_DEPLOYER)
+                .call(
+                    approve,
+                    &ApproveArgs {
+                        spender: spender.parse().unwrap(),
+                        amount,
+                    },
+                )
+                .expect_event(ApprovalV1 {
+                    owner: ADDR_DEPLOYER.to_string(),
+                    spender: spender.to_string(),
+                    amount,
+                })
+        }
+    }
+
+    #[test]
+    fn approve_spend_and_overspend() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        Scenario::new()
+            .at_contract("crc20_contract")
+            .as_sender(ADDR_DEPLOYER)
+            // Symbols are limited to 16 bytes when the call data is decoded.
+            .call(
+                initialize,
+                &("Chert Token", "CHERT-TOKEN-SYMBOL", 18u8, 1_000u64),
+            )
+            .expect_error(ErrorCode::DeserializationFailed)
+            .expect_storage_changes(&[])
+            .call(
+                initialize,
+                &InitializeArgs {
+                    name: Name::new("Chert Token").unwrap(),
+                    symbol: Symbol::new("CHT").unwrap(),
+                    decimals: 18,
+                    initial_supply: 1_000,
+                },
+            )
+            .then(approved(ADDR_CAROL, 50))
+            .as_sender(ADDR_CAROL)
+            .call(
+                transfer_from,
+                &TransferFromArgs {
+                    from: ADDR_DEPLOYER.parse().unwrap(),
+                    to: ADDR_DAVE.parse().unwrap(),
+                    amount: 30,
+                },
+            )
+            .expect_topics(&["Transfer"])
+            .expect_event(TransferV1 {
+                from: ADDR_DEPLOYER.to_string(),
+                to: ADDR_DAVE.to_string(),
+                amount: 30,
+            })
+            .call(
+                transfer_from,
+                &TransferFromArgs {
+                    from: ADDR_DEPLOYER.parse().unwrap(),
+                    to: ADDR_DAVE.parse().unwrap(),
+                    amount: 30,
+                },
+            )
+            .expect_error(ErrorCode::InsufficientBalance)
+            .check(|| {
+                assert_eq!(read_allowance(ADDR_DEPLOYER, ADDR_CAROL).unwrap(), 20);
+                assert_eq!(read_balance(ADDR_DEPLOYER).unwrap(), 970);
+            })
+            .call(
+                balance_of,
+                &BalanceOfArgs {
+                    account: ADDR_DAVE.parse().unwrap(),
+                },
+            )
+            .expect_return(30u64)
+            // Addresses are checked when the call data is decoded, and
+            // other spellings of an account reach the same balance.
+            .call(transfer, &("0xdave", 5u64))
+            .expect_error(ErrorCode::DeserializationFailed)
+            .expect_storage_changes(&[])
+            .call(balance_of, &format!("0x{}", ADDR_DAVE[2..].to_uppercase()))
+            .expect_return(30u64);
+    }
 }
@@ -9,21 +9,63 @@
 //! - Operator Approval - Approve operators to manage all tokens
 //! - Metadata URI - Link to off-chain metadata (images, attributes)
 //! - Enumeration - Query tokens by owner and total supply
+//! - Batched Queries - `aggregate_views` answers several queries in one call
+//! - Contract Version - `contract_version` identifies the build and storage schema
 //! - Minting - Create new NFTs (controlled access)
 //! - Burning - Destroy NFTs permanently
+//! - Ownership - Two-step ownership transfer via the shared `ownable` module
+//! - Pause - Owner can halt minting, transfers and burns
+//! - Provenance Log - Optional record of each token's recent transfers
 //! - Events - Transfer, Approval, and ApprovalForAll events
 
 #![cfg_attr(target_arch = "wasm32", no_std)]
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
-#[cfg(target_arch = "wasm32")]
 extern crate alloc;
 
-use alloc::vec;
-
+use address::{Address, AddressKey, ZERO_ADDRESS};
+use contract_macros::entrypoint;
+use crc_errors::ErrorCode;
+use crc_standards::MAX_VIEW_REQUESTS;
+use crc_standards::crc721::{
+    AggregateViewsArgs, ApproveArgs, BalanceOfArgs, IsApprovedForAllArgs, SafeTransferFromArgs,
+    SafeTransferFromWithMemoArgs, SetApprovalForAllArgs, SetProvenanceLogArgs, TokenIdArgs,
+    TransferFromArgs, TransferFromWithMemoArgs, TransferRecord, ViewRequest, ViewResult,
+};
+use crc_standards::ownable::TransferOwnershipArgs;
+use crc_standards::version::ContractVersion;
+use events::{ApprovalForAllV1, NftApprovalV1, NftTransferV1, NftTransferV2, admin};
+use silica_contract_sdk::bounded::{Memo, Name, Symbol, Uri};
 use silica_contract_sdk::event;
+use silica_contract_sdk::initializable;
+use silica_contract_sdk::ownable;
+use silica_contract_sdk::pagination;
+use silica_contract_sdk::pausable;
 use silica_contract_sdk::prelude::*;
-use serde::{Deserialize, Serialize};
+use silica_contract_sdk::reentrancy;
+use storage_keys::{CompatMap, CompatValue, Namespace};
+
+/// Storage schema version of fresh deployments
+const SCHEMA_VERSION: u32 = 1;
+/// Namespace of this contract's keys. The domains below are also the bare
+/// keys deployments before namespacing wrote, which stay readable.
+const KEYS: Namespace = Namespace::new("crc721", 1);
+/// Layout 2 of the account-keyed domains keys them by address bytes instead
+/// of the hex string; layout 1 and bare-key entries stay readable.
+const ACCOUNT_KEYS: Namespace = KEYS.at_version(2);
+const METADATA_KEY: &str = "collection_metadata";
+const TOKENS_PREFIX: &str = "tokens";
+const BALANCES_PREFIX: &str = "balances";
+const TOKEN_APPROVALS_PREFIX: &str = "token_approvals";
+const OPERATOR_APPROVALS_PREFIX: &str = "operator_approvals";
+const ALL_TOKENS_PREFIX: &str = "all_tokens";
+const ALL_TOKENS_KEY: &str = "global";
+const OWNER_TOKENS_PREFIX: &str = "owner_tokens";
+const PROVENANCE_LOG_KEY: &str = "provenance_log";
+const TRANSFER_HISTORY_PREFIX: &str = "transfer_history";
+/// Transfers kept per token while the provenance log is on; older ones are
+/// overwritten
+const TRANSFER_HISTORY_LENGTH: usize = 16;
 
 /// NFT collection metadata
 #[derive(Serialize, Deserialize, Clone)]
@@ -32,7 +74,6 @@ pub struct CollectionMetadata {
     pub symbol: String,
     pub base_uri: String,
     pub total_supply: u64,
-    pub owner: String,
     pub initialized: bool,
 }
 
@@ -45,367 +86,385 @@ pub struct TokenInfo {
     pub burned: bool,
 }
 
-/// Initialize the NFT collection
-///
-/// # Arguments
-/// * `name` - Collection name (e.g., "Chert Punks")
-/// * `symbol` - Collection symbol (e.g., "CPUNK")
-/// * `base_uri` - Base URI for token metadata
-#[unsafe(no_mangle)]
-pub extern "C" fn initialize(name: String, symbol: String, base_uri: String) {
-    let ctx = context();
-    let deployer = ctx.sender();
+/// Last [`TRANSFER_HISTORY_LENGTH`] transfers of a token
+#[derive(Serialize, Deserialize, Default)]
+struct TransferHistory {
+    /// Slot the next record goes to once `records` is full
+    next: u32,
+    records: Vec<TransferRecord>,
+}
 
-    // Validate parameters
-    if name.is_empty() {
-        log("Collection name is required");
-        return;
-    }
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    name: Name,
+    symbol: Symbol,
+    base_uri: Uri,
+}
 
-    if symbol.is_empty() {
-        log("Collection symbol is required");
-        return;
-    }
+#[derive(Serialize, Deserialize)]
+struct MintArgs {
+    to: Address,
+    token_id: u64,
+    metadata_uri: Uri,
+}
 
-    if base_uri.is_empty() {
-        log("Base URI is required");
-        return;
-    }
+#[derive(Serialize, Deserialize)]
+struct TokenByIndexArgs {
+    index: u64,
+}
 
-    // Initialize collection metadata
-    let metadata = CollectionMetadata {
-        name: name.clone(),
-        symbol: symbol.clone(),
-        base_uri: base_uri.clone(),
-        total_supply: 0,
-        owner: deployer.to_string(),
-        initialized: true,
-    };
+#[derive(Serialize, Deserialize)]
+struct TokenOfOwnerByIndexArgs {
+    owner: Address,
+    index: u64,
+}
 
-    let storage_ref = storage();
-    if storage_ref.set("collection_metadata", &metadata).is_err() {
-        log("Failed to store collection metadata");
-        return;
-    }
+fn metadata() -> CompatValue<CollectionMetadata> {
+    KEYS.compat_value(METADATA_KEY)
+}
 
-    log(&format!(
-        "NFT Collection '{}' ({}) initialized with base URI: {}",
-        name, symbol, base_uri
-    ));
-    event!("CollectionInitialized",
-        name: name,
-        symbol: symbol,
-        base_uri: base_uri,
-        owner: deployer
-    );
+fn load_metadata() -> ContractResult<CollectionMetadata> {
+    metadata()
+        .get()?
+        .ok_or_else(|| ContractError::InvalidArgument("Collection not initialized".to_string()))
 }
 
-/// Check if caller is the contract owner
-fn is_owner() -> bool {
-    let ctx = context();
-    let storage_ref = storage();
-    let metadata: CollectionMetadata = match storage_ref.get("collection_metadata") {
-        Ok(Some(m)) => m,
-        _ => return false,
-    };
-    ctx.sender() == metadata.owner
+fn save_metadata(metadata: &CollectionMetadata) -> ContractResult<()> {
+    self::metadata().set(metadata)
 }
 
-/// Check if an address is approved for a specific token
-fn is_approved_for_token(token_id: u64, address: &str) -> bool {
-    let storage_ref = storage();
+fn tokens() -> CompatMap<u64, TokenInfo> {
+    KEYS.compat_map(TOKENS_PREFIX)
+}
 
-    // Check operator approvals first (takes precedence)
-    let metadata: CollectionMetadata = match storage_ref.get("collection_metadata") {
-        Ok(Some(m)) => m,
-        _ => return false,
-    };
+/// Spellings earlier layouts may have keyed an account by; they used the
+/// caller's string as given
+fn legacy_account(account: &AddressKey) -> Vec<String> {
+    account.to_address().spellings()
+}
 
-    let operator_approvals: Map<(String, String), bool> = Map::new("operator_approvals");
-    let metadata_ref = &metadata;
-    let operator_key = (metadata_ref.owner.clone(), address.to_string());
+/// Every combination of the legacy spellings of two accounts
+fn legacy_pair(first: &AddressKey, second: &AddressKey) -> Vec<(String, String)> {
+    let seconds = legacy_account(second);
+    legacy_account(first)
+        .into_iter()
+        .flat_map(|first| {
+            seconds
+                .iter()
+                .map(move |second| (first.clone(), second.clone()))
+        })
+        .collect()
+}
 
-    if operator_approvals.get(&operator_key).ok().flatten() == Some(true) {
-        return true;
-    }
+fn balances() -> CompatMap<AddressKey, u64> {
+    ACCOUNT_KEYS.respelled_map(BALANCES_PREFIX, legacy_account)
+}
 
-    // Check specific token approval
-    let token_approvals: Map<u64, String> = Map::new("token_approvals");
-    match token_approvals.get(&token_id) {
-        Ok(Some(approved_addr)) => approved_addr == address.to_string(),
-        _ => false,
-    }
+fn token_approvals() -> CompatMap<u64, String> {
+    KEYS.compat_map(TOKEN_APPROVALS_PREFIX)
 }
 
-/// Mint a new NFT to the specified address
-///
-/// # Arguments
-/// * `to` - Recipient address
-/// * `token_id` - Unique token identifier
-/// * `metadata_uri` - URI suffix for token metadata
-#[unsafe(no_mangle)]
-pub extern "C" fn mint(to: String, token_id: u64, metadata_uri: String) {
-    // Reentrancy protection
-    let _guard = match ReentrancyGuard::enter() {
-        Ok(guard) => guard,
-        Err(_) => {
-            log("Reentrancy detected in mint");
-            return;
-        }
-    };
+fn operator_approvals() -> CompatMap<(AddressKey, AddressKey), bool> {
+    ACCOUNT_KEYS.respelled_map(OPERATOR_APPROVALS_PREFIX, |(owner, operator)| {
+        legacy_pair(owner, operator)
+    })
+}
 
-    let ctx = context();
-    let minter = ctx.sender();
+/// The global token list, a single entry under [`ALL_TOKENS_KEY`]
+fn all_tokens() -> CompatMap<&'static str, Vec<u64>> {
+    KEYS.compat_map(ALL_TOKENS_PREFIX)
+}
 
-    // Check if caller has minting permission
-    if !is_owner() {
-        log("Only owner can mint tokens");
-        return;
-    }
+fn owner_tokens() -> CompatMap<AddressKey, Vec<u64>> {
+    ACCOUNT_KEYS.respelled_map(OWNER_TOKENS_PREFIX, legacy_account)
+}
 
-    // Validate parameters
-    if to.is_empty() {
-        log("Recipient address is required");
-        return;
-    }
+fn transfer_histories() -> Map<u64, TransferHistory> {
+    KEYS.map(TRANSFER_HISTORY_PREFIX)
+}
 
-    if metadata_uri.is_empty() {
-        log("Metadata URI is required");
-        return;
-    }
+fn provenance_log_enabled() -> ContractResult<bool> {
+    Ok(storage()
+        .get::<bool>(&KEYS.prefix(PROVENANCE_LOG_KEY))?
+        .unwrap_or(false))
+}
 
-    // Check if token ID already exists
-    let storage_ref = storage();
-    let tokens: Map<u64, TokenInfo> = Map::new("tokens");
-    if tokens.get(&token_id).ok().flatten().is_some() {
-        log("Token ID already exists");
-        return;
+/// Add a transfer to the token's history when the provenance log is on
+fn record_transfer(token_id: u64, from: &str, to: &str) -> ContractResult<()> {
+    if !provenance_log_enabled()? {
+        return Ok(());
     }
-
-    // Create token info
-    let token_info = TokenInfo {
-        token_id,
-        owner: to.clone(),
-        metadata_uri: metadata_uri.clone(),
-        burned: false,
+    let mut histories = transfer_histories();
+    let mut history = histories.get(&token_id)?.unwrap_or_default();
+    let record = TransferRecord {
+        from: from.to_string(),
+        to: to.to_string(),
+        block_height: context().block_height(),
     };
+    if history.records.len() < TRANSFER_HISTORY_LENGTH {
+        history.records.push(record);
+    } else {
+        history.records[history.next as usize] = record;
+        history.next = (history.next + 1) % TRANSFER_HISTORY_LENGTH as u32;
+    }
+    histories.set(&token_id, &history)
+}
 
-    // Store token information
-    if tokens.set(&token_id, &token_info).is_err() {
-        log("Failed to store token information");
-        return;
-    }
-
-    // Update owner's token count
-    let mut balances: Map<String, u64> = Map::new("balances");
-    let current_balance = balances.get(&to).ok().flatten().unwrap_or(0);
-    let new_balance = safe_math::add(current_balance, 1).unwrap_or(0);
-
-    if balances.set(&to, &new_balance).is_err() {
-        log("Failed to update owner balance");
-        return;
-    }
-
-    // Track all tokens for enumeration
-    let mut all_tokens: Vec<u64> = Vec::new();
-    let all_tokens_vec: Map<String, Vec<u64>> = Map::new("all_tokens");
-    match all_tokens_vec.get(&"global".to_string()) {
-        Ok(Some(mut tokens_vec)) => {
-            tokens_vec.push(token_id);
-            if all_tokens_vec
-                .set(&"global".to_string(), &tokens_vec)
-                .is_err()
-            {
-                log("Failed to update all tokens list");
-                return;
-            }
-            all_tokens = tokens_vec;
-        }
-        _ => {
-            all_tokens.push(token_id);
-            if all_tokens_vec
-                .set(&"global".to_string(), &all_tokens)
-                .is_err()
-            {
-                log("Failed to initialize all tokens list");
-                return;
-            }
-        }
+/// Live (minted and not burned) token
+fn load_token(token_id: u64) -> ContractResult<TokenInfo> {
+    match tokens().get(&token_id)? {
+        Some(token) if !token.burned => Ok(token),
+        _ => Err(ContractError::InvalidArgument(
+            "Token does not exist".to_string(),
+        )),
     }
+}
 
-    // Track tokens for owner enumeration
-    let mut owner_tokens: Map<String, Vec<u64>> = Map::new("owner_tokens");
-    let mut owner_tokens_vec = match owner_tokens.get(&to) {
-        Ok(Some(tokens_vec)) => tokens_vec,
-        _ => Vec::new(),
-    };
-    owner_tokens_vec.push(token_id);
+fn read_balance(owner: &str) -> ContractResult<u64> {
+    Ok(balances().get(&AddressKey::parse(owner)?)?.unwrap_or(0))
+}
 
-    if owner_tokens.set(&to, &owner_tokens_vec).is_err() {
-        log("Failed to track owner tokens");
-        return;
-    }
+fn write_balance(owner: &str, amount: u64) -> ContractResult<()> {
+    balances().set(&AddressKey::parse(owner)?, &amount)
+}
 
-    // Update total supply
-    let mut metadata: CollectionMetadata = match storage_ref.get("collection_metadata") {
-        Ok(Some(mut m)) => {
-            m.total_supply = safe_math::add(m.total_supply, 1).unwrap_or(m.total_supply);
-            if storage_ref.set("collection_metadata", &m).is_err() {
-                log("Failed to update total supply");
-                return;
-            }
-            m
-        }
-        _ => {
-            log("Failed to load collection metadata");
-            return;
-        }
-    };
+fn read_approval(token_id: u64) -> ContractResult<Option<String>> {
+    Ok(token_approvals()
+        .get(&token_id)?
+        .filter(|approved| !approved.is_empty()))
+}
 
-    log(&format!(
-        "Token {} minted to {} with metadata URI: {}",
-        token_id, to, metadata_uri
-    ));
-    event!("Transfer",
-        from: "0x0".to_string(),
-        to: to,
-        token_id: token_id
-    );
+fn clear_approval(token_id: u64) -> ContractResult<()> {
+    token_approvals().remove(&token_id)
 }
 
-/// Transfer an NFT from one address to another
-///
-/// # Arguments
-/// * `from` - Current owner address
-/// * `to` - Recipient address
-/// * `token_id` - Token to transfer
-#[unsafe(no_mangle)]
-pub extern "C" fn transfer_from(from: String, to: String, token_id: u64) {
-    // Reentrancy protection
-    let _guard = match ReentrancyGuard::enter() {
-        Ok(guard) => guard,
-        Err(_) => {
-            log("Reentrancy detected in transfer_from");
-            return;
-        }
-    };
+/// Approval of a live token
+fn read_token_approval(token_id: u64) -> ContractResult<Option<String>> {
+    load_token(token_id)?;
+    read_approval(token_id)
+}
 
-    let ctx = context();
-    let caller = ctx.sender();
+/// Base URI followed by the token's own URI
+fn read_token_uri(token_id: u64) -> ContractResult<String> {
+    let token = load_token(token_id)?;
+    let metadata = load_metadata()?;
+    Ok(format!("{}{}", metadata.base_uri, token.metadata_uri))
+}
 
-    // Validate parameters
-    if from.is_empty() || to.is_empty() {
-        log("From and to addresses are required");
-        return;
-    }
+fn read_operator_approval(owner: &str, operator: &str) -> ContractResult<bool> {
+    Ok(operator_approvals()
+        .get(&(AddressKey::parse(owner)?, AddressKey::parse(operator)?))?
+        .unwrap_or(false))
+}
 
-    // Check if token exists and get current owner
-    let storage_ref = storage();
-    let tokens: Map<u64, TokenInfo> = Map::new("tokens");
-    let mut token_info = match tokens.get(&token_id) {
-        Ok(Some(t)) => t,
-        Ok(None) => {
-            log("Token does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read token information");
-            return;
-        }
-    };
+fn read_token_list<K>(lists: &CompatMap<K, Vec<u64>>, key: &K) -> ContractResult<Vec<u64>>
+where
+    K: Serialize + 'static,
+{
+    Ok(lists.get(key)?.unwrap_or_default())
+}
 
-    // Verify ownership
-    if token_info.owner != from {
-        log("From address is not the token owner");
-        return;
-    }
+fn add_to_list<K>(mut lists: CompatMap<K, Vec<u64>>, key: &K, token_id: u64) -> ContractResult<()>
+where
+    K: Serialize + 'static,
+{
+    let mut list = read_token_list(&lists, key)?;
+    list.push(token_id);
+    lists.set(key, &list)
+}
 
-    // Check if caller is authorized to transfer
-    let caller_addr = caller.to_string();
-    if token_info.owner != caller_addr && !is_approved_for_token(token_id, &caller_addr) {
-        log("Caller is not authorized to transfer this token");
-        return;
+fn remove_from_list<K>(
+    mut lists: CompatMap<K, Vec<u64>>,
+    key: &K,
+    token_id: u64,
+) -> ContractResult<()>
+where
+    K: Serialize + 'static,
+{
+    let mut list = read_token_list(&lists, key)?;
+    if let Some(pos) = list.iter().position(|&id| id == token_id) {
+        list.remove(pos);
     }
+    lists.set(key, &list)
+}
 
-    // Transfer ownership
-    token_info.owner = to.clone();
+/// Owner, approved address or operator of the owner
+fn is_approved_or_owner(token: &TokenInfo, spender: &str) -> ContractResult<bool> {
+    if token.owner == spender || read_operator_approval(&token.owner, spender)? {
+        return Ok(true);
+    }
+    Ok(read_approval(token.token_id)?.as_deref() == Some(spender))
+}
 
-    if tokens.set(&token_id, &token_info).is_err() {
-        log("Failed to update token ownership");
-        return;
+fn ensure_initialized() -> ContractResult<()> {
+    if !metadata().exists() {
+        return Err(ContractError::InvalidArgument(
+            "Collection not initialized".to_string(),
+        ));
     }
+    Ok(())
+}
 
-    // Update balances
-    let mut balances: Map<String, u64> = Map::new("balances");
+fn transfer_impl(from: &str, to: &str, token_id: u64, memo: Option<Memo>) -> ContractResult<()> {
+    validation::validate_address(from)?;
+    validation::validate_address(to)?;
 
-    // Decrease sender balance
-    let from_balance = balances.get(&from).ok().flatten().unwrap_or(0);
-    if from_balance == 0 {
-        log("Sender has no tokens to transfer");
-        return;
+    let mut token = load_token(token_id)?;
+    if token.owner != from {
+        return Err(ContractError::InvalidArgument(
+            "From address is not the token owner".to_string(),
+        ));
     }
-
-    let new_from_balance = safe_math::sub(from_balance, 1).unwrap_or(0);
-    if balances.set(&from, &new_from_balance).is_err() {
-        log("Failed to update sender balance");
-        return;
+    let ctx = context();
+    if !is_approved_or_owner(&token, ctx.sender())? {
+        return Err(ContractError::Unauthorized);
     }
 
-    // Increase recipient balance
-    let to_balance = balances.get(&to).ok().flatten().unwrap_or(0);
-    let new_to_balance = safe_math::add(to_balance, 1).unwrap_or(0);
-    if balances.set(&to, &new_to_balance).is_err() {
-        log("Failed to update recipient balance");
-        return;
+    token.owner = to.to_string();
+    tokens().set(&token_id, &token)?;
+    clear_approval(token_id)?;
+
+    write_balance(from, safe_math::sub(read_balance(from)?, 1)?)?;
+    write_balance(to, safe_math::add(read_balance(to)?, 1)?)?;
+    remove_from_list(owner_tokens(), &AddressKey::parse(from)?, token_id)?;
+    add_to_list(owner_tokens(), &AddressKey::parse(to)?, token_id)?;
+    record_transfer(token_id, from, to)?;
+
+    match memo {
+        Some(memo) => events::emit(&NftTransferV2 {
+            from: from.to_string(),
+            to: to.to_string(),
+            token_id,
+            memo: memo.into_inner(),
+        }),
+        None => events::emit(&NftTransferV1 {
+            from: from.to_string(),
+            to: to.to_string(),
+            token_id,
+        }),
     }
+    Ok(())
+}
 
-    // Clear token approval
-    let mut token_approvals: Map<u64, String> = Map::new("token_approvals");
-    // Clear token approval (just set to empty string)
-    if token_approvals.set(&token_id, &"".to_string()).is_err() {
-        // Approval already cleared
-        log("Failed to clear token approval");
-        return;
+/// Initialize the NFT collection
+///
+/// # Arguments
+/// * `name` - Collection name (e.g., "Chert Punks"), at most 64 bytes
+/// * `symbol` - Collection symbol (e.g., "CPUNK"), at most 16 bytes
+/// * `base_uri` - Base URI for token metadata, at most 256 bytes
+#[entrypoint]
+fn initialize(args: InitializeArgs) -> ContractResult<()> {
+    validation::validate_non_empty(&args.name, "name")?;
+    validation::validate_non_empty(&args.symbol, "symbol")?;
+    validation::validate_non_empty(&args.base_uri, "base_uri")?;
+
+    if metadata().exists() {
+        return Err(ContractError::InvalidArgument(
+            "Collection already initialized".to_string(),
+        ));
     }
 
-    // Update owner token lists
-    let mut owner_tokens: Map<String, Vec<u64>> = Map::new("owner_tokens");
+    let ctx = context();
+    let deployer = ctx.sender();
+    validation::validate_address(deployer)?;
+    initializable::initialize(initializable::CONTRACT_SCOPE)?;
 
-    // Remove from sender's token list
-    let mut sender_tokens = match owner_tokens.get(&from) {
-        Ok(Some(tokens_vec)) => tokens_vec,
-        _ => Vec::new(),
+    let metadata = CollectionMetadata {
+        name: args.name.to_string(),
+        symbol: args.symbol.to_string(),
+        base_uri: args.base_uri.to_string(),
+        total_supply: 0,
+        initialized: true,
     };
-    if let Some(pos) = sender_tokens.iter().position(|&x| x == token_id) {
-        sender_tokens.remove(pos);
-    }
 
-    if owner_tokens.set(&from, &sender_tokens).is_err() {
-        log("Failed to update sender token list");
-        return;
+    save_metadata(&metadata)?;
+    proxy_implementation::init_schema_version(SCHEMA_VERSION)?;
+    ownable::initialize(deployer)?;
+
+    event!("CollectionInitialized",
+        name: args.name,
+        symbol: args.symbol,
+        base_uri: args.base_uri,
+        owner: deployer
+    );
+    Ok(())
+}
+
+/// Mint a new NFT to the specified address (only owner)
+///
+/// # Arguments
+/// * `to` - Recipient address
+/// * `token_id` - Unique token identifier
+/// * `metadata_uri` - URI suffix for token metadata, at most 256 bytes
+#[entrypoint]
+fn mint(args: MintArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
+    pausable::when_not_paused()?;
+    ownable::only_owner()?;
+    validation::validate_address(&args.to)?;
+    validation::validate_non_empty(&args.metadata_uri, "metadata_uri")?;
+
+    let mut tokens = tokens();
+    if tokens.get(&args.token_id)?.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Token ID already exists".to_string(),
+        ));
     }
 
-    // Add to recipient's token list
-    let mut recipient_tokens = match owner_tokens.get(&to) {
-        Ok(Some(tokens_vec)) => tokens_vec,
-        _ => Vec::new(),
+    let token = TokenInfo {
+        token_id: args.token_id,
+        owner: args.to.to_string(),
+        metadata_uri: args.metadata_uri.into(),
+        burned: false,
     };
-    recipient_tokens.push(token_id);
+    tokens.set(&args.token_id, &token)?;
+
+    write_balance(&args.to, safe_math::add(read_balance(&args.to)?, 1)?)?;
+    add_to_list(all_tokens(), &ALL_TOKENS_KEY, args.token_id)?;
+    add_to_list(owner_tokens(), &args.to.to_key(), args.token_id)?;
+
+    let mut metadata = load_metadata()?;
+    metadata.total_supply = safe_math::add(metadata.total_supply, 1)?;
+    save_metadata(&metadata)?;
+    record_transfer(args.token_id, ZERO_ADDRESS, &args.to)?;
+
+    admin::record(admin::MINT, &args.token_id.to_string(), "", &args.to)?;
+    events::emit(&NftTransferV1 {
+        from: ZERO_ADDRESS.to_string(),
+        to: args.to.into(),
+        token_id: args.token_id,
+    });
+    Ok(())
+}
 
-    if owner_tokens.set(&to, &recipient_tokens).is_err() {
-        log("Failed to update recipient token list");
-        return;
-    }
+/// Transfer an NFT from one address to another
+///
+/// # Arguments
+/// * `from` - Current owner address
+/// * `to` - Recipient address
+/// * `token_id` - Token to transfer
+#[entrypoint]
+fn transfer_from(args: TransferFromArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
+    pausable::when_not_paused()?;
+    transfer_impl(&args.from, &args.to, args.token_id, None)
+}
 
-    log(&format!(
-        "Token {} transferred from {} to {}",
-        token_id, from, to
-    ));
-    event!("Transfer",
-        from: from,
-        to: to,
-        token_id: token_id
-    );
+/// Transfer an NFT and publish a note in the Transfer event
+///
+/// # Arguments
+/// * `from` - Current owner address
+/// * `to` - Recipient address
+/// * `token_id` - Token to transfer
+/// * `memo` - Note of at most 128 bytes
+#[entrypoint]
+fn transfer_from_with_memo(args: TransferFromWithMemoArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
+    pausable::when_not_paused()?;
+    transfer_impl(&args.from, &args.to, args.token_id, Some(args.memo))
 }
 
 /// Safely transfer an NFT with recipient validation
@@ -415,18 +474,30 @@ pub extern "C" fn transfer_from(from: String, to: String, token_id: u64) {
 /// * `to` - Recipient address
 /// * `token_id` - Token to transfer
 /// * `data` - Additional data for recipient contract
-#[unsafe(no_mangle)]
-pub extern "C" fn safe_transfer_from(from: String, to: String, token_id: u64, data: Vec<u8>) {
-    // For now, safe transfer behaves like regular transfer
-    // In a full implementation, this would check if recipient is a contract
-    // and call onCRC721Received callback
-
-    transfer_from(from, to, token_id);
+#[entrypoint]
+fn safe_transfer_from(args: SafeTransferFromArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
+    pausable::when_not_paused()?;
+    // Recipient contracts are not called back yet, so this behaves like
+    // `transfer_from`; `data` is accepted for interface compatibility.
+    transfer_impl(&args.from, &args.to, args.token_id, None)
+}
 
-    log(&format!(
-        "Safe transfer completed for token {} with data: {:?}",
-        token_id, data
-    ));
+/// Safely transfer an NFT and publish a note in the Transfer event
+///
+/// # Arguments
+/// * `from` - Current owner address
+/// * `to` - Recipient address
+/// * `token_id` - Token to transfer
+/// * `data` - Additional data for recipient contract
+/// * `memo` - Note of at most 128 bytes
+#[entrypoint]
+fn safe_transfer_from_with_memo(args: SafeTransferFromWithMemoArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
+    pausable::when_not_paused()?;
+    transfer_impl(&args.from, &args.to, args.token_id, Some(args.memo))
 }
 
 /// Approve an address to transfer a specific token
@@ -434,51 +505,35 @@ pub extern "C" fn safe_transfer_from(from: String, to: String, token_id: u64, da
 /// # Arguments
 /// * `to` - Address to approve (or "0x0" to clear approval)
 /// * `token_id` - Token to grant approval for
-#[unsafe(no_mangle)]
-pub extern "C" fn approve(to: String, token_id: u64) {
+#[entrypoint]
+fn approve(args: ApproveArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
     let ctx = context();
-    let owner = ctx.sender();
-
-    // Check if token exists and owner matches
-    let storage_ref = storage();
-    let tokens: Map<u64, TokenInfo> = Map::new("tokens");
-    let token_info = match tokens.get(&token_id) {
-        Ok(Some(t)) => t,
-        Ok(None) => {
-            log("Token does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read token information");
-            return;
-        }
-    };
+    let caller = ctx.sender();
 
-    // Verify ownership
-    if token_info.owner != owner {
-        log("Caller is not the token owner");
-        return;
+    let token = load_token(args.token_id)?;
+    if token.owner != caller && !read_operator_approval(&token.owner, caller)? {
+        return Err(ContractError::Unauthorized);
     }
-
-    // Cannot approve the owner
-    if to == owner {
-        log("Cannot approve the token owner");
-        return;
+    if args.to.as_str() == token.owner {
+        return Err(ContractError::InvalidArgument(
+            "Cannot approve the token owner".to_string(),
+        ));
     }
 
-    // Store approval
-    let mut token_approvals: Map<u64, String> = Map::new("token_approvals");
-    if token_approvals.set(&token_id, &to).is_err() {
-        log("Failed to store token approval");
-        return;
+    if args.to.is_zero() {
+        clear_approval(args.token_id)?;
+    } else {
+        token_approvals().set(&args.token_id, &args.to.to_string())?;
     }
 
-    log(&format!("Token {} approved for address: {}", token_id, to));
-    event!("Approval",
-        owner: owner,
-        approved: to,
-        token_id: token_id
-    );
+    events::emit(&NftApprovalV1 {
+        owner: token.owner,
+        approved: args.to.into(),
+        token_id: args.token_id,
+    });
+    Ok(())
 }
 
 /// Approve or revoke an operator to manage all tokens
@@ -486,300 +541,1662 @@ pub extern "C" fn approve(to: String, token_id: u64) {
 /// # Arguments
 /// * `operator` - Address to set operator status for
 /// * `approved` - True to approve, false to revoke
-#[unsafe(no_mangle)]
-pub extern "C" fn set_approval_for_all(operator: String, approved: bool) {
+#[entrypoint]
+fn set_approval_for_all(args: SetApprovalForAllArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
     let ctx = context();
     let owner = ctx.sender();
-
-    // Validate parameters
-    if operator.is_empty() {
-        log("Operator address is required");
-        return;
-    }
-
-    // Cannot approve yourself
-    if operator == owner {
-        log("Cannot set yourself as operator");
-        return;
+    validation::validate_address(&args.operator)?;
+    if args.operator == owner {
+        return Err(ContractError::InvalidArgument(
+            "Cannot set yourself as operator".to_string(),
+        ));
     }
 
-    let storage_ref = storage();
-    let metadata: CollectionMetadata = match storage_ref.get("collection_metadata") {
-        Ok(Some(m)) => m,
-        _ => {
-            log("Failed to load collection metadata");
-            return;
-        }
-    };
-
-    // Store operator approval
-    let mut operator_approvals: Map<(String, String), bool> = Map::new("operator_approvals");
-    let approval_key = (owner.clone(), operator.clone());
-
-    if operator_approvals.set(&approval_key, &approved).is_err() {
-        log("Failed to store operator approval");
-        return;
-    }
-
-    log(&format!(
-        "Operator {} {} for {}",
-        operator,
-        if approved { "approved" } else { "revoked" },
-        owner
-    ));
-    event!("ApprovalForAll",
-        owner: owner,
-        operator: operator,
-        approved: approved
-    );
+    operator_approvals().set(
+        &(AddressKey::parse(owner)?, args.operator.to_key()),
+        &args.approved,
+    )?;
+
+    events::emit(&ApprovalForAllV1 {
+        owner: owner.to_string(),
+        operator: args.operator.into(),
+        approved: args.approved,
+    });
+    Ok(())
 }
 
 /// Burn (destroy) an NFT permanently
 ///
 /// # Arguments
 /// * `token_id` - Token to burn
-#[unsafe(no_mangle)]
-pub extern "C" fn burn(token_id: u64) {
-    // Reentrancy protection
-    let _guard = match ReentrancyGuard::enter() {
-        Ok(guard) => guard,
-        Err(_) => {
-            log("Reentrancy detected in burn");
-            return;
-        }
-    };
-
+#[entrypoint]
+fn burn(args: TokenIdArgs) -> ContractResult<()> {
+    let _lock = reentrancy::enter(reentrancy::GLOBAL_SCOPE)?;
+    ensure_initialized()?;
+    pausable::when_not_paused()?;
     let ctx = context();
-    let burner = ctx.sender();
-
-    // Check if token exists and get current owner
-    let storage_ref = storage();
-    let tokens: Map<u64, TokenInfo> = Map::new("tokens");
-    let mut token_info = match tokens.get(&token_id) {
-        Ok(Some(t)) => t,
-        Ok(None) => {
-            log("Token does not exist");
-            return;
-        }
-        Err(_) => {
-            log("Failed to read token information");
-            return;
-        }
-    };
 
-    // Verify ownership
-    let burner_addr = burner.to_string();
-    if token_info.owner != burner_addr && !is_approved_for_token(token_id, &burner_addr) {
-        log("Caller is not authorized to burn this token");
-        return;
+    let mut token = load_token(args.token_id)?;
+    if !is_approved_or_owner(&token, ctx.sender())? {
+        return Err(ContractError::Unauthorized);
     }
 
-    // Mark token as burned
-    token_info.burned = true;
-    token_info.owner = "0x0".to_string(); // Zero address for burned tokens
+    let owner = core::mem::replace(&mut token.owner, ZERO_ADDRESS.to_string());
+    token.burned = true;
+    tokens().set(&args.token_id, &token)?;
+    clear_approval(args.token_id)?;
+
+    write_balance(&owner, safe_math::sub(read_balance(&owner)?, 1)?)?;
+    remove_from_list(owner_tokens(), &AddressKey::parse(&owner)?, args.token_id)?;
+    remove_from_list(all_tokens(), &ALL_TOKENS_KEY, args.token_id)?;
+
+    let mut metadata = load_metadata()?;
+    metadata.total_supply = safe_math::sub(metadata.total_supply, 1)?;
+    save_metadata(&metadata)?;
+    record_transfer(args.token_id, &owner, ZERO_ADDRESS)?;
+
+    admin::record(admin::BURN, &args.token_id.to_string(), &owner, "")?;
+    events::emit(&NftTransferV1 {
+        from: owner,
+        to: ZERO_ADDRESS.to_string(),
+        token_id: args.token_id,
+    });
+    Ok(())
+}
 
-    if tokens.set(&token_id, &token_info).is_err() {
-        log("Failed to update token information");
-        return;
+/// Get the owner of a specific token
+///
+/// # Returns
+/// Owner address; fails for unknown or burned tokens
+#[entrypoint]
+fn owner_of(args: TokenIdArgs) -> ContractResult<String> {
+    Ok(load_token(args.token_id)?.owner)
+}
+
+/// Get the number of tokens owned by an address
+#[entrypoint]
+fn balance_of(args: BalanceOfArgs) -> ContractResult<u64> {
+    read_balance(&args.owner)
+}
+
+/// Get the approved address for a token
+#[entrypoint]
+fn get_approved(args: TokenIdArgs) -> ContractResult<Option<String>> {
+    read_token_approval(args.token_id)
+}
+
+/// Check if an operator is approved for all tokens of an owner
+#[entrypoint]
+fn is_approved_for_all(args: IsApprovedForAllArgs) -> ContractResult<bool> {
+    read_operator_approval(&args.owner, &args.operator)
+}
+
+/// Get the metadata URI for a token (base URI + token URI)
+#[entrypoint]
+fn token_uri(args: TokenIdArgs) -> ContractResult<String> {
+    ensure_initialized()?;
+    read_token_uri(args.token_id)
+}
+
+/// Get the total number of tokens in existence
+#[entrypoint]
+fn total_supply() -> ContractResult<u64> {
+    ensure_initialized()?;
+    Ok(load_metadata()?.total_supply)
+}
+
+/// Get token ID at a given index in the global token list
+///
+/// # Returns
+/// `None` past the end of the list
+#[entrypoint]
+fn token_by_index(args: TokenByIndexArgs) -> ContractResult<Option<u64>> {
+    let list = read_token_list(&all_tokens(), &ALL_TOKENS_KEY)?;
+    Ok(list.get(args.index as usize).copied())
+}
+
+/// Get token ID at a given index in an owner's token list
+///
+/// # Returns
+/// `None` past the end of the list
+#[entrypoint]
+fn token_of_owner_by_index(args: TokenOfOwnerByIndexArgs) -> ContractResult<Option<u64>> {
+    let list = read_token_list(&owner_tokens(), &args.owner.to_key())?;
+    Ok(list.get(args.index as usize).copied())
+}
+
+/// Get collection name
+#[entrypoint]
+fn name() -> ContractResult<String> {
+    ensure_initialized()?;
+    Ok(load_metadata()?.name)
+}
+
+/// Get collection symbol
+#[entrypoint]
+fn symbol() -> ContractResult<String> {
+    ensure_initialized()?;
+    Ok(load_metadata()?.symbol)
+}
+
+/// Answer several queries in one call
+///
+/// # Arguments
+/// * `requests` - Up to 32 `ViewRequest`s
+///
+/// # Returns
+/// One `ViewResult` per request, in order. A failed query, such as
+/// `OwnerOf` for a burned token, answers `Failed` with its error code; the
+/// call itself fails only if the answers do not fit in one response.
+#[entrypoint]
+fn aggregate_views(args: AggregateViewsArgs) -> ContractResult<Vec<ViewResult>> {
+    ensure_initialized()?;
+    if args.requests.len() > MAX_VIEW_REQUESTS {
+        return Err(ContractError::InvalidArgument(format!(
+            "At most {} views per call",
+            MAX_VIEW_REQUESTS
+        )));
+    }
+    let results: Vec<ViewResult> = args.requests.iter().map(view).collect();
+    let encoded =
+        postcard::to_allocvec(&results).map_err(|_| ContractError::SerializationFailed)?;
+    if encoded.len() > pagination::MAX_RETURN_BYTES {
+        return Err(ContractError::InvalidArgument(
+            "Views do not fit in one response".to_string(),
+        ));
     }
+    Ok(results)
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+#[entrypoint]
+fn contract_version() -> ContractResult<ContractVersion> {
+    Ok(crc_standards::contract_version!(
+        proxy_implementation::schema_version()?
+    ))
+}
 
-    // Decrease owner's balance
-    let mut balances: Map<String, u64> = Map::new("balances");
-    let owner_balance = match balances.get(&token_info.owner) {
-        Ok(Some(b)) => b,
-        _ => {
-            log("Owner balance not found");
-            return;
+fn view(request: &ViewRequest) -> ViewResult {
+    let result = match request {
+        ViewRequest::Name => load_metadata().map(|metadata| ViewResult::Text(metadata.name)),
+        ViewRequest::Symbol => load_metadata().map(|metadata| ViewResult::Text(metadata.symbol)),
+        ViewRequest::TotalSupply => {
+            load_metadata().map(|metadata| ViewResult::Count(metadata.total_supply))
+        }
+        ViewRequest::BalanceOf { owner } => read_balance(owner).map(ViewResult::Count),
+        ViewRequest::OwnerOf { token_id } => {
+            load_token(*token_id).map(|token| ViewResult::Text(token.owner))
+        }
+        ViewRequest::TokenUri { token_id } => read_token_uri(*token_id).map(ViewResult::Text),
+        ViewRequest::GetApproved { token_id } => {
+            read_token_approval(*token_id).map(ViewResult::Approved)
+        }
+        ViewRequest::IsApprovedForAll { owner, operator } => {
+            read_operator_approval(owner, operator).map(ViewResult::Flag)
         }
     };
+    result.unwrap_or_else(|err| ViewResult::Failed(ErrorCode::of(&err).as_u16()))
+}
 
-    if owner_balance > 0 {
-        let new_owner_balance = safe_math::sub(owner_balance, 1).unwrap_or(0);
-        if balances.set(&token_info.owner, &new_owner_balance).is_err() {
-            log("Failed to update owner balance");
-            return;
-        }
+/// Get the recent transfers of a token, oldest first
+///
+/// Holds at most the last 16 transfers, mint and burn included, made while
+/// the provenance log was on. Burned tokens keep their history.
+///
+/// # Arguments
+/// * `token_id` - Token to look up
+#[entrypoint]
+fn transfer_history(args: TokenIdArgs) -> ContractResult<Vec<TransferRecord>> {
+    ensure_initialized()?;
+    if !tokens().contains_key(&args.token_id)? {
+        return Err(ContractError::InvalidArgument(
+            "Token does not exist".to_string(),
+        ));
     }
+    let Some(mut history) = transfer_histories().get(&args.token_id)? else {
+        return Ok(Vec::new());
+    };
+    history.records.rotate_left(history.next as usize);
+    Ok(history.records)
+}
 
-    // Clear all approvals
-    let mut token_approvals: Map<u64, String> = Map::new("token_approvals");
-    // Clear token approval
-    let _ = token_approvals.set(&token_id, &"".to_string());
+/// Start or stop recording transfer history (only owner)
+///
+/// Stopping keeps the records made so far.
+///
+/// # Arguments
+/// * `enabled` - True to record transfers from now on
+#[entrypoint]
+fn set_provenance_log(args: SetProvenanceLogArgs) -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    let previous = provenance_log_enabled()?;
+    storage().set(&KEYS.prefix(PROVENANCE_LOG_KEY), &args.enabled)?;
+    admin::record(
+        admin::SET_PARAMETER,
+        PROVENANCE_LOG_KEY,
+        previous,
+        args.enabled,
+    )?;
+    event!("ProvenanceLogSet", enabled: args.enabled);
+    Ok(())
+}
 
-    let mut operator_approvals: Map<(String, String), bool> = Map::new("operator_approvals");
-    let mut keys_to_remove = Vec::new();
+/// Check whether the contract is paused
+#[entrypoint]
+fn paused() -> ContractResult<bool> {
+    pausable::paused()
+}
 
-    // Find and remove all operator approvals for this owner
-    let all_operator_keys = vec![
-        (token_info.owner.clone(), "operator1".to_string()),
-        (token_info.owner.clone(), "operator2".to_string()),
-        // In real implementation, you'd iterate through all stored keys
+/// Pause the contract (only owner)
+///
+/// Transfers, minting and burning fail until `unpause`; approvals and queries still work.
+#[entrypoint]
+fn pause() -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    pausable::pause()
+}
+
+/// Lift a pause (only owner)
+#[entrypoint]
+fn unpause() -> ContractResult<()> {
+    ensure_initialized()?;
+    ownable::only_owner()?;
+    pausable::unpause()
+}
+
+/// Get the contract owner
+///
+/// # Returns
+/// `None` once ownership has been renounced
+#[entrypoint]
+fn owner() -> ContractResult<Option<String>> {
+    Ok(ownable::owner()?)
+}
+
+/// Get the owner nominated by `transfer_ownership`
+#[entrypoint]
+fn pending_owner() -> ContractResult<Option<String>> {
+    Ok(ownable::pending_owner()?)
+}
+
+/// Nominate a new owner (only owner)
+///
+/// The current owner keeps control until the nominee accepts.
+///
+/// # Arguments
+/// * `new_owner` - Account to hand ownership to
+#[entrypoint]
+fn transfer_ownership(args: TransferOwnershipArgs) -> ContractResult<()> {
+    ensure_initialized()?;
+    Ok(ownable::transfer_ownership(&args.new_owner)?)
+}
+
+/// Accept a pending ownership transfer (only the nominated owner)
+#[entrypoint]
+fn accept_ownership() -> ContractResult<()> {
+    ensure_initialized()?;
+    Ok(ownable::accept_ownership()?)
+}
+
+/// Give up ownership; minting is disabled for good
+#[entrypoint]
+fn renounce_ownership() -> ContractResult<()> {
+    ensure_initialized()?;
+    Ok(ownable::renounce_ownership()?)
+}
+
+/// Entrypoint bodies and assert bounds for the `fuzz/` targets
+#[cfg(not(target_arch = "wasm32"))]
+#[doc(hidden)]
+pub mod fuzzing {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+    use silica_contract_sdk::handlers;
+    use silica_contract_sdk::testing::Handler;
+
+    pub const HANDLERS: &[(&str, Handler)] = handlers![
+        execute_initialize,
+        execute_mint,
+        execute_transfer_from,
+        execute_safe_transfer_from,
+        execute_transfer_from_with_memo,
+        execute_safe_transfer_from_with_memo,
+        execute_approve,
+        execute_set_approval_for_all,
+        execute_burn,
+        execute_owner_of,
+        execute_balance_of,
+        execute_get_approved,
+        execute_is_approved_for_all,
+        execute_token_uri,
+        execute_total_supply,
+        execute_token_by_index,
+        execute_token_of_owner_by_index,
+        execute_name,
+        execute_symbol,
+        execute_paused,
+        execute_pause,
+        execute_unpause,
+        execute_owner,
+        execute_pending_owner,
+        execute_transfer_ownership,
+        execute_accept_ownership,
+        execute_renounce_ownership,
+        execute_transfer_history,
+        execute_set_provenance_log,
     ];
 
-    for key in all_operator_keys {
-        if operator_approvals.get(&key).ok().flatten() == Some(true) {
-            keys_to_remove.push(key);
-        }
-    }
+    /// Panics the contract raises on purpose
+    pub const ALLOWED_PANICS: &[&str] = &[
+        "Call data exceeds static bound",
+        "Return payload exceeds static bound",
+    ];
 
-    for key in keys_to_remove {
-        // Clear operator approval
-        let _ = operator_approvals.set(&key, &false);
+    pub const DEPLOYER: &str = "0x0000000000000000000000000000000000000d01";
+
+    /// Fresh mock runtime with the collection initialized by [`DEPLOYER`],
+    /// who owns token 1
+    pub fn setup() {
+        mock::reset();
+        mock::set_sender(DEPLOYER);
+        mock::set_contract_address("crc721_contract");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::set_call_data(
+            &postcard::to_allocvec(&InitializeArgs {
+                name: Name::new("Chert Punks").unwrap(),
+                symbol: Symbol::new("CPUNK").unwrap(),
+                base_uri: Uri::new("https://api.chertpunks.io/metadata/").unwrap(),
+            })
+            .unwrap(),
+        );
+        execute_initialize().unwrap();
+        mock::set_call_data(
+            &postcard::to_allocvec(&MintArgs {
+                to: DEPLOYER.parse().unwrap(),
+                token_id: 1,
+                metadata_uri: Uri::new("1.json").unwrap(),
+            })
+            .unwrap(),
+        );
+        execute_mint().unwrap();
+        mock::take_events();
     }
 
-    log(&format!("Token {} burned permanently", token_id));
-    event!("Transfer",
-        from: burner_addr,
-        to: "0x0".to_string(),
-        token_id: token_id
-    );
+    /// Well-formed call data for mutation
+    pub fn seeds() -> Vec<Vec<u8>> {
+        let owner: Address = DEPLOYER.parse().unwrap();
+        vec![
+            postcard::to_allocvec(&TokenIdArgs { token_id: 1 }).unwrap(),
+            postcard::to_allocvec(&TransferFromArgs {
+                from: owner.clone(),
+                to: owner.clone(),
+                token_id: 1,
+            })
+            .unwrap(),
+            postcard::to_allocvec(&SetApprovalForAllArgs {
+                operator: owner.clone(),
+                approved: true,
+            })
+            .unwrap(),
+            postcard::to_allocvec(&MintArgs {
+                to: owner,
+                token_id: 2,
+                metadata_uri: Uri::new("2.json").unwrap(),
+            })
+            .unwrap(),
+        ]
+    }
 }
 
-/// Get the owner of a specific token
-#[unsafe(no_mangle)]
-pub extern "C" fn owner_of(token_id: u64) -> String {
-    let tokens: Map<u64, TokenInfo> = Map::new("tokens");
-    match tokens.get(&token_id) {
-        Ok(Some(token_info)) => {
-            if token_info.burned {
-                "0x0".to_string()
-            } else {
-                token_info.owner
-            }
-        }
-        _ => "0x0".to_string(),
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_errors::ErrorCode;
+    use events::AdminActionV1;
+    use scenario::Scenario;
+    use silica_contract_sdk::ffi::mock;
+    use silica_contract_sdk::testing::{self, Rng};
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_DEPLOYER: &str = "0x0000000000000000000000000000000000000d01";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_NATIVE: &str = "chert1holder0000000000000000000";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
     }
-}
 
-/// Get the number of tokens owned by an address
-#[unsafe(no_mangle)]
-pub extern "C" fn balance_of(owner: String) -> u64 {
-    if owner.is_empty() {
-        return 0;
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
     }
 
-    let balances: Map<String, u64> = Map::new("balances");
-    balances.get(&owner).ok().flatten().unwrap_or(0)
-}
+    fn init_default() {
+        mock::reset();
+        mock::set_sender(ADDR_DEPLOYER);
+        mock::set_contract_address("crc721_contract");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::set_call_data(&encode(&InitializeArgs {
+            name: Name::new("Chert Punks").unwrap(),
+            symbol: Symbol::new("CPUNK").unwrap(),
+            base_uri: Uri::new("https://api.chertpunks.io/metadata/").unwrap(),
+        }));
+        initialize();
+        mock::take_events();
+    }
 
-/// Get the approved address for a token
-#[unsafe(no_mangle)]
-pub extern "C" fn get_approved(token_id: u64) -> String {
-    let token_approvals: Map<u64, String> = Map::new("token_approvals");
-    match token_approvals.get(&token_id) {
-        Ok(Some(approved)) => approved,
-        _ => "0x0".to_string(),
+    fn mint_to(to: &str, token_id: u64) {
+        mock::set_call_data(&encode(&MintArgs {
+            to: to.parse().unwrap(),
+            token_id,
+            metadata_uri: Uri::new(format!("{}.json", token_id)).unwrap(),
+        }));
+        mint();
     }
-}
 
-/// Check if an operator is approved for all tokens of an owner
-#[unsafe(no_mangle)]
-pub extern "C" fn is_approved_for_all(owner: String, operator: String) -> bool {
-    if owner.is_empty() || operator.is_empty() {
-        return false;
-    }
-
-    let operator_approvals: Map<(String, String), bool> = Map::new("operator_approvals");
-    let approval_key = (owner, operator);
-    operator_approvals
-        .get(&approval_key)
-        .ok()
-        .flatten()
-        .unwrap_or(false)
-}
-
-/// Get the metadata URI for a token
-#[unsafe(no_mangle)]
-pub extern "C" fn token_uri(token_id: u64) -> String {
-    let tokens: Map<u64, TokenInfo> = Map::new("tokens");
-    let storage_ref = storage();
-
-    match tokens.get(&token_id) {
-        Ok(Some(token_info)) => {
-            let metadata: CollectionMetadata = match storage_ref.get("collection_metadata") {
-                Ok(Some(m)) => m,
-                _ => return "".to_string(),
-            };
+    #[test]
+    fn second_initialize_fails_and_changes_nothing() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        mock::take_events();
+        let before = testing::StorageSnapshot::take();
+
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&InitializeArgs {
+            name: Name::new("Stolen Punks").unwrap(),
+            symbol: Symbol::new("STOLEN").unwrap(),
+            base_uri: Uri::new("https://stolen.example/").unwrap(),
+        }));
+        assert!(matches!(
+            execute_initialize(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Collection already initialized"
+        ));
+        before
+            .diff(&testing::StorageSnapshot::take())
+            .assert_unchanged();
+        assert!(mock::take_events().is_empty());
+        assert_eq!(ownable::owner().unwrap().as_deref(), Some(ADDR_DEPLOYER));
+        assert_eq!(load_metadata().unwrap().name, "Chert Punks");
+        assert!(initializable::is_initialized(initializable::CONTRACT_SCOPE).unwrap());
+    }
 
-            if token_info.burned {
-                "".to_string()
-            } else {
-                // Combine base URI with token-specific metadata URI
-                format!("{}{}", metadata.base_uri, token_info.metadata_uri)
-            }
+    #[test]
+    fn minting_follows_the_shared_owner() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        assert_eq!(ownable::owner().unwrap().as_deref(), Some(ADDR_DEPLOYER));
+
+        mint_to(ADDR_BOB, 1);
+        assert_eq!(load_token(1).unwrap().owner, ADDR_BOB);
+
+        mock::set_call_data(&encode(&TransferOwnershipArgs {
+            new_owner: ADDR_CAROL.to_string(),
+        }));
+        transfer_ownership();
+        mock::set_sender(ADDR_CAROL);
+        accept_ownership();
+
+        mint_to(ADDR_CAROL, 2);
+        mock::set_sender(ADDR_DEPLOYER);
+        mint_to(ADDR_DEPLOYER, 3);
+        assert!(load_token(2).is_ok());
+        assert!(load_token(3).is_err());
+        assert_eq!(load_metadata().unwrap().total_supply, 2);
+
+        let emitted = mock::take_events();
+        let topics: Vec<&str> = emitted.iter().map(|e| &*e.topic).collect();
+        assert_eq!(
+            topics,
+            [
+                "AdminAction",
+                "Transfer",
+                "OwnershipTransferStarted",
+                "AdminAction",
+                "OwnershipTransferred",
+                "AdminAction",
+                "AdminAction",
+                "Transfer"
+            ]
+        );
+        // Mints are audited by token and indexed by sender, recipient and token.
+        assert_eq!(
+            events::decode::<AdminActionV1>(&emitted[0].data).unwrap(),
+            Some(AdminActionV1 {
+                actor: ADDR_DEPLOYER.to_string(),
+                action: admin::MINT.to_string(),
+                target: "1".to_string(),
+                old_value: String::new(),
+                new_value: ADDR_BOB.to_string(),
+                sequence: 0,
+            })
+        );
+        assert_eq!(
+            emitted[1].indexed,
+            [
+                silica_contract_sdk::events::indexed_topic(ZERO_ADDRESS),
+                silica_contract_sdk::events::indexed_topic(ADDR_BOB),
+                silica_contract_sdk::events::indexed_topic(&1u64),
+            ]
+        );
+        assert!(emitted[2].indexed.is_empty());
+    }
+
+    #[test]
+    fn burn_debits_the_previous_owner() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 7);
+        mock::take_events();
+
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&TokenIdArgs { token_id: 7 }));
+        burn();
+
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 0);
+        assert_eq!(read_balance(ZERO_ADDRESS).unwrap(), 0);
+        assert!(load_token(7).is_err());
+        assert_eq!(load_metadata().unwrap().total_supply, 0);
+        // Burns are audited like mints, by token.
+        let emitted = mock::take_events();
+        assert_eq!(
+            events::decode::<AdminActionV1>(&emitted[0].data).unwrap(),
+            Some(AdminActionV1 {
+                actor: ADDR_BOB.to_string(),
+                action: admin::BURN.to_string(),
+                target: "7".to_string(),
+                old_value: ADDR_BOB.to_string(),
+                new_value: String::new(),
+                sequence: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build_and_schema() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "crc721-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                SCHEMA_VERSION
+            )
+        );
+        assert_eq!(version.schema_version, 1);
+    }
+
+    #[test]
+    fn aggregate_views_answer_each_request_in_order() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&ApproveArgs {
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 1,
+        }));
+        approve();
+
+        let bob: Address = ADDR_BOB.parse().unwrap();
+        mock::set_call_data(&encode(&AggregateViewsArgs {
+            requests: vec![
+                ViewRequest::Symbol,
+                ViewRequest::TotalSupply,
+                ViewRequest::BalanceOf { owner: bob.clone() },
+                ViewRequest::OwnerOf { token_id: 1 },
+                ViewRequest::TokenUri { token_id: 1 },
+                ViewRequest::GetApproved { token_id: 1 },
+                ViewRequest::IsApprovedForAll {
+                    owner: bob,
+                    operator: ADDR_CAROL.parse().unwrap(),
+                },
+                // Unknown tokens fail only their own query.
+                ViewRequest::OwnerOf { token_id: 2 },
+            ],
+        }));
+        aggregate_views();
+        let results: Vec<ViewResult> = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(
+            results,
+            [
+                ViewResult::Text("CPUNK".to_string()),
+                ViewResult::Count(1),
+                ViewResult::Count(1),
+                ViewResult::Text(ADDR_BOB.to_string()),
+                ViewResult::Text("https://api.chertpunks.io/metadata/1.json".to_string()),
+                ViewResult::Approved(Some(ADDR_CAROL.to_string())),
+                ViewResult::Flag(false),
+                ViewResult::Failed(ErrorCode::InvalidArgument.as_u16()),
+            ]
+        );
+
+        // Answers that would not fit in one response fail the call.
+        mock::set_sender(ADDR_DEPLOYER);
+        mock::set_call_data(&encode(&MintArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            token_id: 2,
+            metadata_uri: Uri::new("x".repeat(Uri::MAX_LEN)).unwrap(),
+        }));
+        mint();
+        mock::set_call_data(&encode(&AggregateViewsArgs {
+            requests: vec![ViewRequest::TokenUri { token_id: 2 }; MAX_VIEW_REQUESTS],
+        }));
+        aggregate_views();
+        assert!(mock::take_logs()[0].contains("Views do not fit in one response"));
+    }
+
+    /// Call a query entrypoint and decode its answer
+    fn query<A: Serialize, R: serde::de::DeserializeOwned>(
+        entrypoint: extern "C" fn(),
+        args: &A,
+    ) -> R {
+        mock::set_call_data(&encode(args));
+        entrypoint();
+        postcard::from_bytes(&mock::take_return_data()).expect("decode query answer")
+    }
+
+    fn transfer_args(from: &str, to: &str, token_id: u64) -> TransferFromArgs {
+        TransferFromArgs {
+            from: from.parse().unwrap(),
+            to: to.parse().unwrap(),
+            token_id,
         }
-        _ => "".to_string(),
     }
-}
 
-/// Get the total number of tokens in existence
-#[unsafe(no_mangle)]
-pub extern "C" fn total_supply() -> u64 {
-    let storage_ref = storage();
-    let metadata: CollectionMetadata =
-        match storage_ref.get::<CollectionMetadata>("collection_metadata") {
-            Ok(Some(m)) => m.total_supply,
-            _ => 0,
+    #[test]
+    fn calls_before_initialize_fail() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        mock::set_sender(ADDR_DEPLOYER);
+        mock::set_contract_address("crc721_contract");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+
+        let not_initialized = |result: ContractResult<()>| matches!(result, Err(ContractError::InvalidArgument(msg)) if msg == "Collection not initialized");
+        mock::set_call_data(&encode(&MintArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            token_id: 1,
+            metadata_uri: Uri::new("1.json").unwrap(),
+        }));
+        assert!(not_initialized(execute_mint()));
+        mock::set_call_data(&encode(&transfer_args(ADDR_DEPLOYER, ADDR_BOB, 1)));
+        assert!(not_initialized(execute_transfer_from()));
+        assert!(matches!(
+            execute_total_supply(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Collection not initialized"
+        ));
+        assert!(mock::take_events().is_empty());
+    }
+
+    #[test]
+    fn only_the_owner_mints_and_ids_are_never_reused() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        mock::take_events();
+        let before = testing::StorageSnapshot::take();
+
+        // Only the collection owner mints.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&MintArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            token_id: 2,
+            metadata_uri: Uri::new("2.json").unwrap(),
+        }));
+        assert!(matches!(execute_mint(), Err(ContractError::Unauthorized)));
+
+        // A live id cannot be minted again.
+        mock::set_sender(ADDR_DEPLOYER);
+        mock::set_call_data(&encode(&MintArgs {
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 1,
+            metadata_uri: Uri::new("other.json").unwrap(),
+        }));
+        assert!(matches!(
+            execute_mint(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Token ID already exists"
+        ));
+
+        // Every token needs its own metadata URI.
+        mock::set_call_data(&encode(&MintArgs {
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 2,
+            metadata_uri: Uri::new("").unwrap(),
+        }));
+        assert!(execute_mint().is_err());
+        before
+            .diff(&testing::StorageSnapshot::take())
+            .assert_unchanged();
+        assert!(mock::take_events().is_empty());
+
+        // Nor can a burned one.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&TokenIdArgs { token_id: 1 }));
+        burn();
+        mock::set_sender(ADDR_DEPLOYER);
+        mock::set_call_data(&encode(&MintArgs {
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 1,
+            metadata_uri: Uri::new("1.json").unwrap(),
+        }));
+        assert!(matches!(
+            execute_mint(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Token ID already exists"
+        ));
+        assert_eq!(total_supply(), 0);
+    }
+
+    #[test]
+    fn transfers_need_the_owner_an_approval_or_an_operator() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        mint_to(ADDR_BOB, 2);
+        mock::take_events();
+        let before = testing::StorageSnapshot::take();
+
+        // Strangers cannot move the token, and `from` must be its owner.
+        mock::set_sender(ADDR_CAROL);
+        mock::set_call_data(&encode(&transfer_args(ADDR_BOB, ADDR_CAROL, 1)));
+        assert!(matches!(
+            execute_transfer_from(),
+            Err(ContractError::Unauthorized)
+        ));
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&transfer_args(ADDR_CAROL, ADDR_BOB, 1)));
+        assert!(matches!(
+            execute_transfer_from(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "From address is not the token owner"
+        ));
+        mock::set_call_data(&encode(&transfer_args(ADDR_BOB, ADDR_CAROL, 9)));
+        assert!(execute_transfer_from().is_err());
+        before
+            .diff(&testing::StorageSnapshot::take())
+            .assert_unchanged();
+
+        // An approved address may transfer once; the approval goes with it.
+        mock::set_call_data(&encode(&ApproveArgs {
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 1,
+        }));
+        approve();
+        mock::set_sender(ADDR_CAROL);
+        mock::set_call_data(&encode(&transfer_args(ADDR_BOB, ADDR_DEPLOYER, 1)));
+        execute_transfer_from().unwrap();
+        assert_eq!(
+            query::<_, Option<String>>(get_approved, &TokenIdArgs { token_id: 1 }),
+            None
+        );
+        mock::set_call_data(&encode(&transfer_args(ADDR_DEPLOYER, ADDR_CAROL, 1)));
+        assert!(matches!(
+            execute_transfer_from(),
+            Err(ContractError::Unauthorized)
+        ));
+
+        // An operator may move any of the owner's tokens, safely or not.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&SetApprovalForAllArgs {
+            operator: ADDR_CAROL.parse().unwrap(),
+            approved: true,
+        }));
+        set_approval_for_all();
+        mock::set_sender(ADDR_CAROL);
+        mock::set_call_data(&encode(&SafeTransferFromArgs {
+            from: ADDR_BOB.parse().unwrap(),
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 2,
+            data: vec![1, 2, 3],
+        }));
+        execute_safe_transfer_from().unwrap();
+
+        let topics: Vec<String> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(
+            topics,
+            ["Approval", "Transfer", "ApprovalForAll", "Transfer"]
+        );
+        let balance = |owner: &str| {
+            mock::set_call_data(&encode(&BalanceOfArgs {
+                owner: owner.parse().unwrap(),
+            }));
+            balance_of()
         };
-    metadata
-}
+        assert_eq!(balance(ADDR_BOB), 0);
+        assert_eq!(balance(ADDR_CAROL), 1);
+        assert_eq!(balance(ADDR_DEPLOYER), 1);
+        assert_eq!(
+            query::<_, String>(owner_of, &TokenIdArgs { token_id: 1 }),
+            ADDR_DEPLOYER
+        );
+        assert_eq!(
+            query::<_, String>(owner_of, &TokenIdArgs { token_id: 2 }),
+            ADDR_CAROL
+        );
+    }
 
-/// Get token ID at a given index in the global token list
-#[unsafe(no_mangle)]
-pub extern "C" fn token_by_index(index: u64) -> u64 {
-    let all_tokens_vec: Map<String, Vec<u64>> = Map::new("all_tokens");
-    match all_tokens_vec.get(&"global".to_string()) {
-        Ok(Some(tokens_vec)) => {
-            if (index as usize) < tokens_vec.len() {
-                tokens_vec[index as usize]
+    #[test]
+    fn approvals_come_from_the_owner_or_an_operator() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        mock::take_events();
+        let approve_as = |sender: &str, to: Address| {
+            mock::set_sender(sender);
+            mock::set_call_data(&encode(&ApproveArgs { to, token_id: 1 }));
+            execute_approve()
+        };
+        let approved = || query::<_, Option<String>>(get_approved, &TokenIdArgs { token_id: 1 });
+
+        assert!(matches!(
+            approve_as(ADDR_CAROL, ADDR_CAROL.parse().unwrap()),
+            Err(ContractError::Unauthorized)
+        ));
+        assert!(matches!(
+            approve_as(ADDR_BOB, ADDR_BOB.parse().unwrap()),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Cannot approve the token owner"
+        ));
+        assert_eq!(approved(), None);
+
+        approve_as(ADDR_BOB, ADDR_CAROL.parse().unwrap()).unwrap();
+        assert_eq!(approved().as_deref(), Some(ADDR_CAROL));
+        // Approving the zero address clears the approval.
+        approve_as(ADDR_BOB, Address::zero()).unwrap();
+        assert_eq!(approved(), None);
+
+        // Operators are set and revoked by the owner, never for themselves.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&SetApprovalForAllArgs {
+            operator: ADDR_BOB.parse().unwrap(),
+            approved: true,
+        }));
+        assert!(matches!(
+            execute_set_approval_for_all(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Cannot set yourself as operator"
+        ));
+        let operator = |approved: bool| {
+            mock::set_sender(ADDR_BOB);
+            mock::set_call_data(&encode(&SetApprovalForAllArgs {
+                operator: ADDR_CAROL.parse().unwrap(),
+                approved,
+            }));
+            execute_set_approval_for_all().unwrap();
+            query::<_, bool>(
+                is_approved_for_all,
+                &IsApprovedForAllArgs {
+                    owner: ADDR_BOB.parse().unwrap(),
+                    operator: ADDR_CAROL.parse().unwrap(),
+                },
+            )
+        };
+        assert!(operator(true));
+        // An operator may approve on the owner's behalf.
+        approve_as(ADDR_CAROL, ADDR_DEPLOYER.parse().unwrap()).unwrap();
+        assert_eq!(approved().as_deref(), Some(ADDR_DEPLOYER));
+        assert!(!operator(false));
+        assert!(matches!(
+            approve_as(ADDR_CAROL, ADDR_CAROL.parse().unwrap()),
+            Err(ContractError::Unauthorized)
+        ));
+
+        let approvals: Vec<NftApprovalV1> = mock::take_events()
+            .iter()
+            .filter(|event| event.topic == "Approval")
+            .filter_map(|event| events::decode(&event.data).unwrap())
+            .collect();
+        assert_eq!(
+            approvals
+                .iter()
+                .map(|a| a.approved.as_str())
+                .collect::<Vec<_>>(),
+            [ADDR_CAROL, ZERO_ADDRESS, ADDR_DEPLOYER]
+        );
+        assert!(approvals.iter().all(|a| a.owner == ADDR_BOB));
+    }
+
+    #[test]
+    fn transfer_history_keeps_the_latest_transfers_while_enabled() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        let history = |token_id: u64| -> Vec<TransferRecord> {
+            query(transfer_history, &TokenIdArgs { token_id })
+        };
+        let set_log = |sender: &str, enabled: bool| {
+            mock::set_sender(sender);
+            mock::set_call_data(&encode(&SetProvenanceLogArgs { enabled }));
+            execute_set_provenance_log()
+        };
+        let transfer = |from: &str, to: &str| {
+            mock::set_sender(from);
+            mock::set_call_data(&encode(&transfer_args(from, to, 1)));
+            execute_transfer_from().unwrap();
+        };
+
+        // Off by default.
+        mint_to(ADDR_BOB, 1);
+        assert!(history(1).is_empty());
+        assert!(matches!(
+            set_log(ADDR_BOB, true),
+            Err(ContractError::Unauthorized)
+        ));
+        set_log(ADDR_DEPLOYER, true).unwrap();
+
+        mock::set_block_height(5);
+        transfer(ADDR_BOB, ADDR_CAROL);
+        assert_eq!(
+            history(1),
+            [TransferRecord {
+                from: ADDR_BOB.to_string(),
+                to: ADDR_CAROL.to_string(),
+                block_height: 5,
+            }]
+        );
+
+        // Past the capacity the oldest records give way.
+        let extra = TRANSFER_HISTORY_LENGTH as u64 + 2;
+        for step in 0..extra {
+            mock::set_block_height(10 + step);
+            let (from, to) = if step % 2 == 0 {
+                (ADDR_CAROL, ADDR_BOB)
             } else {
-                0 // Invalid index
-            }
+                (ADDR_BOB, ADDR_CAROL)
+            };
+            transfer(from, to);
         }
-        _ => 0,
+        let records = history(1);
+        assert_eq!(records.len(), TRANSFER_HISTORY_LENGTH);
+        assert_eq!(
+            records.iter().map(|r| r.block_height).collect::<Vec<_>>(),
+            (10 + extra - TRANSFER_HISTORY_LENGTH as u64..10 + extra).collect::<Vec<_>>()
+        );
+
+        // Burns are recorded and burned tokens keep their history; turning
+        // the log off keeps what was recorded.
+        let owner = records.last().unwrap().to.clone();
+        mock::set_sender(&owner);
+        mock::set_call_data(&encode(&TokenIdArgs { token_id: 1 }));
+        execute_burn().unwrap();
+        let last = history(1).pop().unwrap();
+        assert_eq!(
+            (last.from.as_str(), last.to.as_str()),
+            (owner.as_str(), ZERO_ADDRESS)
+        );
+        set_log(ADDR_DEPLOYER, false).unwrap();
+        mock::set_sender(ADDR_DEPLOYER);
+        mint_to(ADDR_BOB, 2);
+        assert_eq!(history(1).len(), TRANSFER_HISTORY_LENGTH);
+        assert!(history(2).is_empty());
+        mock::set_call_data(&encode(&TokenIdArgs { token_id: 3 }));
+        assert!(execute_transfer_history().is_err());
     }
-}
 
-/// Get token ID at a given index in an owner's token list
-#[unsafe(no_mangle)]
-pub extern "C" fn token_of_owner_by_index(owner: String, index: u64) -> u64 {
-    if owner.is_empty() {
-        return 0;
+    #[test]
+    fn transfer_memos_are_bounded_and_published() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        mock::take_events();
+        mock::set_sender(ADDR_BOB);
+
+        // Over 128 bytes the call does not decode.
+        mock::set_call_data(&encode(&(ADDR_BOB, ADDR_CAROL, 1u64, "x".repeat(129))));
+        assert!(matches!(
+            execute_transfer_from_with_memo(),
+            Err(ContractError::DeserializationFailed)
+        ));
+        assert_eq!(load_token(1).unwrap().owner, ADDR_BOB);
+
+        mock::set_call_data(&encode(&TransferFromWithMemoArgs {
+            from: ADDR_BOB.parse().unwrap(),
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 1,
+            memo: Memo::new("invoice 42").unwrap(),
+        }));
+        execute_transfer_from_with_memo().unwrap();
+        let events = mock::take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events::decode::<NftTransferV1>(&events[0].data).unwrap(),
+            None
+        );
+        assert_eq!(
+            events::decode::<NftTransferV2>(&events[0].data).unwrap(),
+            Some(NftTransferV2 {
+                from: ADDR_BOB.to_string(),
+                to: ADDR_CAROL.to_string(),
+                token_id: 1,
+                memo: "invoice 42".to_string(),
+            })
+        );
+
+        mock::set_sender(ADDR_CAROL);
+        mock::set_call_data(&encode(&SafeTransferFromWithMemoArgs {
+            from: ADDR_CAROL.parse().unwrap(),
+            to: ADDR_BOB.parse().unwrap(),
+            token_id: 1,
+            data: Vec::new(),
+            memo: Memo::new("returned").unwrap(),
+        }));
+        execute_safe_transfer_from_with_memo().unwrap();
+        let events = mock::take_events();
+        assert_eq!(
+            events::decode::<NftTransferV2>(&events[0].data)
+                .unwrap()
+                .unwrap()
+                .memo,
+            "returned"
+        );
     }
 
-    let owner_tokens: Map<String, Vec<u64>> = Map::new("owner_tokens");
-    match owner_tokens.get(&owner) {
-        Ok(Some(tokens_vec)) => {
-            if (index as usize) < tokens_vec.len() {
-                tokens_vec[index as usize]
-            } else {
-                0 // Invalid index
-            }
+    #[test]
+    fn legacy_transfer_payloads_still_decode_without_a_memo() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        mock::take_events();
+
+        // The three-field payload callers have always sent.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&(ADDR_BOB, ADDR_CAROL, 1u64)));
+        execute_transfer_from().unwrap();
+        assert_eq!(load_token(1).unwrap().owner, ADDR_CAROL);
+        let events = mock::take_events();
+        assert_eq!(events::version_of(&events[0].data).unwrap(), 1);
+
+        // Through a forwarder the caller suffix follows the arguments and
+        // must not be read as part of them.
+        let forwarder = "0x0000000000000000000000000000000000000f0f";
+        mock::set_sender(ADDR_CAROL);
+        mock::set_call_data(&encode(&SetApprovalForAllArgs {
+            operator: forwarder.parse().unwrap(),
+            approved: true,
+        }));
+        execute_set_approval_for_all().unwrap();
+        mock::take_events();
+        mock::set_sender(forwarder);
+        mock::set_call_data(
+            &proxy_implementation::append_sender(
+                &encode(&transfer_args(ADDR_CAROL, ADDR_BOB, 1)),
+                ADDR_CAROL,
+            )
+            .unwrap(),
+        );
+        execute_transfer_from().unwrap();
+        assert_eq!(load_token(1).unwrap().owner, ADDR_BOB);
+        let events = mock::take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events::decode::<NftTransferV1>(&events[0].data).unwrap(),
+            Some(NftTransferV1 {
+                from: ADDR_CAROL.to_string(),
+                to: ADDR_BOB.to_string(),
+                token_id: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn burning_needs_the_owner_an_approval_or_an_operator() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        for token_id in 1..=3 {
+            mint_to(ADDR_BOB, token_id);
+        }
+        let burn_as = |sender: &str, token_id: u64| {
+            mock::set_sender(sender);
+            mock::set_call_data(&encode(&TokenIdArgs { token_id }));
+            execute_burn()
+        };
+
+        assert!(matches!(
+            burn_as(ADDR_CAROL, 1),
+            Err(ContractError::Unauthorized)
+        ));
+        burn_as(ADDR_BOB, 1).unwrap();
+        mock::set_call_data(&encode(&ApproveArgs {
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 2,
+        }));
+        approve();
+        burn_as(ADDR_CAROL, 2).unwrap();
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&SetApprovalForAllArgs {
+            operator: ADDR_DEPLOYER.parse().unwrap(),
+            approved: true,
+        }));
+        set_approval_for_all();
+        burn_as(ADDR_DEPLOYER, 3).unwrap();
+
+        // Burned tokens are gone from every query and cannot burn twice.
+        assert!(burn_as(ADDR_BOB, 3).is_err());
+        for token_id in 1..=3 {
+            let args = TokenIdArgs { token_id };
+            mock::set_call_data(&encode(&args));
+            assert!(execute_owner_of().is_err());
+            mock::set_call_data(&encode(&args));
+            assert!(execute_token_uri().is_err());
+            mock::set_call_data(&encode(&args));
+            assert!(execute_get_approved().is_err());
         }
-        _ => 0,
+        assert_eq!(total_supply(), 0);
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 0);
     }
-}
 
-/// Get collection metadata
-#[unsafe(no_mangle)]
-pub extern "C" fn get_collection_info() -> String {
-    let storage_ref = storage();
-    let metadata: CollectionMetadata = match storage_ref.get("collection_metadata") {
-        Ok(Some(m)) => {
-            log(&format!(
-                "Collection: {} ({}) - Total Supply: {}",
-                m.name, m.symbol, m.total_supply
-            ));
-            m
+    #[test]
+    fn enumeration_follows_mints_transfers_and_burns() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        for (owner, token_id) in [
+            (ADDR_BOB, 10),
+            (ADDR_CAROL, 20),
+            (ADDR_BOB, 30),
+            (ADDR_BOB, 40),
+        ] {
+            mint_to(owner, token_id);
         }
-        _ => {
-            log("Failed to load collection metadata");
-            return "".to_string();
+        let all = || -> Vec<u64> {
+            (0..)
+                .map_while(|index| query(token_by_index, &TokenByIndexArgs { index }))
+                .collect()
+        };
+        let owned = |owner: &str| -> Vec<u64> {
+            (0..)
+                .map_while(|index| {
+                    query(
+                        token_of_owner_by_index,
+                        &TokenOfOwnerByIndexArgs {
+                            owner: owner.parse().unwrap(),
+                            index,
+                        },
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(all(), [10, 20, 30, 40]);
+        assert_eq!(owned(ADDR_BOB), [10, 30, 40]);
+        assert_eq!(owned(ADDR_CAROL), [20]);
+        assert_eq!(owned(ADDR_DEPLOYER), []);
+
+        // Transfers move a token to the end of its new owner's list; burns
+        // drop it and keep the order of the rest.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&transfer_args(ADDR_BOB, ADDR_CAROL, 10)));
+        transfer_from();
+        mock::set_call_data(&encode(&TokenIdArgs { token_id: 30 }));
+        burn();
+        assert_eq!(all(), [10, 20, 40]);
+        assert_eq!(owned(ADDR_BOB), [40]);
+        assert_eq!(owned(ADDR_CAROL), [20, 10]);
+        assert_eq!(total_supply(), 3);
+    }
+
+    #[test]
+    fn token_uris_join_the_base_and_token_uri() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 7);
+        mock::set_call_data(&encode(&MintArgs {
+            to: ADDR_BOB.parse().unwrap(),
+            token_id: 8,
+            metadata_uri: Uri::new("special/8?v=2").unwrap(),
+        }));
+        mint();
+
+        let uri = |token_id| query::<_, String>(token_uri, &TokenIdArgs { token_id });
+        assert_eq!(uri(7), "https://api.chertpunks.io/metadata/7.json");
+        assert_eq!(uri(8), "https://api.chertpunks.io/metadata/special/8?v=2");
+        mock::set_call_data(&encode(&TokenIdArgs { token_id: 9 }));
+        assert!(matches!(
+            execute_token_uri(),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Token does not exist"
+        ));
+
+        // The URI follows the token, not its owner.
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&transfer_args(ADDR_BOB, ADDR_CAROL, 7)));
+        transfer_from();
+        assert_eq!(uri(7), "https://api.chertpunks.io/metadata/7.json");
+        assert_eq!(query::<_, String>(name, &()), "Chert Punks");
+        assert_eq!(query::<_, String>(symbol, &()), "CPUNK");
+    }
+
+    const ACCOUNTS: [&str; 3] = [ADDR_DEPLOYER, ADDR_BOB, ADDR_CAROL];
+    const TOKEN_IDS: u64 = 6;
+
+    /// Call `entrypoint` as `sender`; false if it reported an error
+    fn call_as<T: Serialize>(sender: &str, entrypoint: extern "C" fn(), args: &T) -> bool {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(args));
+        entrypoint();
+        mock::take_return_data().is_empty()
+    }
+
+    /// One state-changing call with random caller, accounts and token
+    fn random_operation(rng: &mut Rng) -> bool {
+        let sender = *rng.pick(&ACCOUNTS);
+        let from: Address = rng.pick(&ACCOUNTS).parse().unwrap();
+        let to: Address = rng.pick(&ACCOUNTS).parse().unwrap();
+        let token_id = rng.between(1, TOKEN_IDS);
+        match rng.below(9) {
+            0 | 1 => call_as(
+                ADDR_DEPLOYER,
+                mint,
+                &MintArgs {
+                    to,
+                    token_id,
+                    metadata_uri: Uri::new(format!("{}.json", token_id)).unwrap(),
+                },
+            ),
+            2 | 3 => call_as(
+                sender,
+                transfer_from,
+                &TransferFromArgs { from, to, token_id },
+            ),
+            4 => call_as(
+                sender,
+                safe_transfer_from,
+                &SafeTransferFromArgs {
+                    from,
+                    to,
+                    token_id,
+                    data: Vec::new(),
+                },
+            ),
+            5 => call_as(sender, approve, &ApproveArgs { to, token_id }),
+            6 => call_as(
+                sender,
+                set_approval_for_all,
+                &SetApprovalForAllArgs {
+                    operator: to,
+                    approved: rng.chance(50),
+                },
+            ),
+            7 => call_as(sender, burn, &TokenIdArgs { token_id }),
+            _ if rng.chance(50) => call_as(ADDR_DEPLOYER, pause, &()),
+            _ => call_as(ADDR_DEPLOYER, unpause, &()),
         }
-    };
+    }
+
+    fn burned_ids() -> Vec<u64> {
+        (1..=TOKEN_IDS)
+            .filter(|id| tokens().get(id).unwrap().is_some_and(|token| token.burned))
+            .collect()
+    }
+
+    /// Token ids named by the `Transfer` and `Approval` events of one call
+    fn touched_ids() -> Vec<u64> {
+        mock::take_events()
+            .iter()
+            .filter_map(|event| match event.topic.as_str() {
+                "Transfer" => events::decode::<NftTransferV1>(&event.data)
+                    .unwrap()
+                    .map(|transfer| transfer.token_id),
+                "Approval" => events::decode::<NftApprovalV1>(&event.data)
+                    .unwrap()
+                    .map(|approval| approval.token_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn burned_tokens_never_transfer() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        testing::check("burned_tokens_never_transfer", |rng| {
+            init_default();
+            for _ in 0..40 {
+                let burned = burned_ids();
+                random_operation(rng);
+                let touched = touched_ids();
+
+                assert!(
+                    touched.iter().all(|id| !burned.contains(id)),
+                    "a burned token moved or was approved: {:?}",
+                    touched
+                );
+                let now_burned = burned_ids();
+                assert!(burned.iter().all(|id| now_burned.contains(id)));
+                for id in now_burned {
+                    assert!(load_token(id).is_err());
+                    assert!(read_approval(id).unwrap().is_none());
+                }
+            }
+        });
+    }
+    #[test]
+    fn balances_match_ownership() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        testing::check("balances_match_ownership", |rng| {
+            init_default();
+            for _ in 0..40 {
+                random_operation(rng);
+                mock::take_events();
+
+                let live: Vec<TokenInfo> = (1..=TOKEN_IDS)
+                    .filter_map(|id| load_token(id).ok())
+                    .collect();
+                let mut total = 0;
+                for account in ACCOUNTS {
+                    let owned: Vec<u64> = live
+                        .iter()
+                        .filter(|token| token.owner == account)
+                        .map(|token| token.token_id)
+                        .collect();
+                    let mut listed =
+                        read_token_list(&owner_tokens(), &AddressKey::parse(account).unwrap())
+                            .unwrap();
+                    listed.sort_unstable();
+                    assert_eq!(read_balance(account).unwrap(), owned.len() as u64);
+                    assert_eq!(listed, owned);
+                    total += owned.len() as u64;
+                }
+                assert_eq!(load_metadata().unwrap().total_supply, total);
+                assert_eq!(
+                    read_token_list(&all_tokens(), &ALL_TOKENS_KEY)
+                        .unwrap()
+                        .len() as u64,
+                    total
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn malformed_call_data_is_rejected_cleanly() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let seeds = fuzzing::seeds();
+        testing::check("malformed_call_data_is_rejected_cleanly", |rng| {
+            fuzzing::setup();
+            for _ in 0..16 {
+                let input = testing::random_call_data(rng, &seeds);
+                testing::fuzz_call(fuzzing::HANDLERS, fuzzing::ALLOWED_PANICS, &input);
+            }
+        });
+    }
+
+    #[test]
+    fn approve_transfer_and_burn() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let token = TokenIdArgs { token_id: 1 };
+        Scenario::new()
+            .at_contract("crc721_contract")
+            .as_sender(ADDR_DEPLOYER)
+            .call(
+                initialize,
+                &InitializeArgs {
+                    name: Name::new("Chert Punks").unwrap(),
+                    symbol: Symbol::new("CPUNK").unwrap(),
+                    base_uri: Uri::new("https://api.chertpunks.io/metadata/").unwrap(),
+                },
+            )
+            .call(
+                mint,
+                &MintArgs {
+                    to: ADDR_DEPLOYER.parse().unwrap(),
+                    token_id: 1,
+                    metadata_uri: Uri::new("1.json").unwrap(),
+                },
+            )
+            // Metadata URIs are limited to 256 bytes.
+            .call(mint, &(ADDR_DEPLOYER, 2u64, "x".repeat(257)))
+            .expect_error(ErrorCode::DeserializationFailed)
+            .expect_storage_changes(&[])
+            .call(
+                approve,
+                &ApproveArgs {
+                    to: ADDR_CAROL.parse().unwrap(),
+                    token_id: 1,
+                },
+            )
+            .expect_event(NftApprovalV1 {
+                owner: ADDR_DEPLOYER.to_string(),
+                approved: ADDR_CAROL.to_string(),
+                token_id: 1,
+            })
+            .call(approve, &("carol", 1u64))
+            .expect_error(ErrorCode::DeserializationFailed)
+            .as_sender(ADDR_CAROL)
+            .call(
+                transfer_from,
+                &TransferFromArgs {
+                    from: ADDR_DEPLOYER.parse().unwrap(),
+                    to: ADDR_BOB.parse().unwrap(),
+                    token_id: 1,
+                },
+            )
+            .expect_event(NftTransferV1 {
+                from: ADDR_DEPLOYER.to_string(),
+                to: ADDR_BOB.to_string(),
+                token_id: 1,
+            })
+            // The transfer cleared Carol's approval.
+            .call(burn, &token)
+            .expect_error(ErrorCode::Unauthorized)
+            .as_sender(ADDR_BOB)
+            .call(burn, &token)
+            .expect_topics(&["AdminAction", "Transfer"])
+            .call(owner_of, &token)
+            .expect_error(ErrorCode::InvalidArgument)
+            .call(total_supply, &())
+            .expect_return(0u64);
+    }
+
+    #[test]
+    fn burn_touches_only_the_token_and_its_owner() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_DEPLOYER, 1);
+        mint_to(ADDR_DEPLOYER, 2);
+        mint_to(ADDR_BOB, 3);
+        let owner = AddressKey::parse(ADDR_DEPLOYER).unwrap();
+        let expected = [
+            tokens().storage_key(&1).unwrap(),
+            token_approvals().storage_key(&1).unwrap(),
+            balances().storage_key(&owner).unwrap(),
+            owner_tokens().storage_key(&owner).unwrap(),
+            all_tokens().storage_key(&ALL_TOKENS_KEY).unwrap(),
+            metadata().key().to_string(),
+        ];
+        let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+
+        Scenario::resume()
+            .as_sender(ADDR_DEPLOYER)
+            .call(
+                approve,
+                &ApproveArgs {
+                    to: ADDR_CAROL.parse().unwrap(),
+                    token_id: 1,
+                },
+            )
+            .call(burn, &TokenIdArgs { token_id: 1 })
+            .expect_storage_changes(&expected);
+    }
+
+    #[test]
+    fn storage_from_before_namespacing_stays_readable() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        mock::set_sender(ADDR_BOB);
+        mock::set_contract_address("crc721_contract");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        // The layout of deployments that used bare keys.
+        storage()
+            .set(
+                METADATA_KEY,
+                &CollectionMetadata {
+                    name: "Chert Punks".to_string(),
+                    symbol: "CPUNK".to_string(),
+                    base_uri: "https://api.chertpunks.io/metadata/".to_string(),
+                    total_supply: 1,
+                    initialized: true,
+                },
+            )
+            .unwrap();
+        let bob = ADDR_BOB.to_string();
+        let mut legacy_tokens: Map<u64, TokenInfo> = Map::new(TOKENS_PREFIX);
+        let mut legacy_balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
+        let mut legacy_lists: Map<String, Vec<u64>> = Map::new(OWNER_TOKENS_PREFIX);
+        legacy_tokens
+            .set(
+                &1,
+                &TokenInfo {
+                    token_id: 1,
+                    owner: bob.clone(),
+                    metadata_uri: "1.json".to_string(),
+                    burned: false,
+                },
+            )
+            .unwrap();
+        legacy_balances.set(&bob, &1).unwrap();
+        legacy_lists.set(&bob, &vec![1]).unwrap();
+        Map::<String, Vec<u64>>::new(ALL_TOKENS_PREFIX)
+            .set(&ALL_TOKENS_KEY.to_string(), &vec![1])
+            .unwrap();
+
+        mock::set_call_data(&encode(&TokenIdArgs { token_id: 1 }));
+        token_uri();
+        let uri: String = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(uri, "https://api.chertpunks.io/metadata/1.json");
+
+        // Writes move entries to their namespaced keys.
+        mock::set_call_data(&encode(&TransferFromArgs {
+            from: ADDR_BOB.parse().unwrap(),
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 1,
+        }));
+        execute_transfer_from().unwrap();
+        assert_eq!(load_token(1).unwrap().owner, ADDR_CAROL);
+        assert!(!legacy_tokens.contains_key(&1).unwrap());
+        assert!(!legacy_balances.contains_key(&bob).unwrap());
+        assert!(!legacy_lists.contains_key(&bob).unwrap());
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 0);
+        assert_eq!(
+            read_token_list(&owner_tokens(), &AddressKey::parse(ADDR_CAROL).unwrap()).unwrap(),
+            [1]
+        );
+        // Untouched entries keep their legacy key.
+        assert!(storage().has(METADATA_KEY));
+        assert_eq!(
+            read_token_list(&all_tokens(), &ALL_TOKENS_KEY).unwrap(),
+            [1]
+        );
+        assert_eq!(metadata().key(), "crc721/collection_metadata/v1");
+    }
 
-    format!(
-        "{}|{}|{}|{}",
-        metadata.name, metadata.symbol, metadata.base_uri, metadata.total_supply
-    )
+    #[test]
+    fn legacy_entries_under_other_spellings_stay_readable() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        // Earlier layouts keyed accounts by whatever string the caller
+        // passed, checksummed or uppercase included.
+        let owner = format!("0x{}", ADDR_BOB[2..].to_ascii_uppercase());
+        let operator = Address::parse(ADDR_CAROL).unwrap().to_checksum();
+        let mut legacy_approvals: Map<(String, String), bool> = Map::new(OPERATOR_APPROVALS_PREFIX);
+        legacy_approvals
+            .set(&(owner.clone(), operator.clone()), &true)
+            .unwrap();
+        Map::<String, u64>::new(BALANCES_PREFIX)
+            .set(&owner, &3)
+            .unwrap();
+
+        assert_eq!(read_balance(ADDR_BOB).unwrap(), 3);
+        assert!(
+            operator_approvals()
+                .get(&(
+                    AddressKey::parse(ADDR_BOB).unwrap(),
+                    AddressKey::parse(ADDR_CAROL).unwrap()
+                ))
+                .unwrap()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn native_address_holders_keep_their_tokens() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_NATIVE, 1);
+        mint_to(ADDR_NATIVE, 2);
+        assert_eq!(read_balance(ADDR_NATIVE).unwrap(), 2);
+        // The balance entry of a holder from before the address crate,
+        // keyed by the raw string.
+        let mut legacy_balances: Map<String, u64> = Map::new(BALANCES_PREFIX);
+        balances()
+            .remove(&AddressKey::parse(ADDR_NATIVE).unwrap())
+            .unwrap();
+        legacy_balances.set(&ADDR_NATIVE.to_string(), &2).unwrap();
+        assert_eq!(read_balance(ADDR_NATIVE).unwrap(), 2);
+
+        mock::set_sender(ADDR_NATIVE);
+        mock::set_call_data(&encode(&TransferFromArgs {
+            from: ADDR_NATIVE.parse().unwrap(),
+            to: ADDR_BOB.parse().unwrap(),
+            token_id: 1,
+        }));
+        execute_transfer_from().unwrap();
+        assert_eq!(load_token(1).unwrap().owner, ADDR_BOB);
+        assert_eq!(read_balance(ADDR_NATIVE).unwrap(), 1);
+        assert!(
+            !legacy_balances
+                .contains_key(&ADDR_NATIVE.to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            read_token_list(&owner_tokens(), &AddressKey::parse(ADDR_NATIVE).unwrap()).unwrap(),
+            [2]
+        );
+    }
+
+    #[test]
+    fn string_keyed_layout_stays_readable() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default();
+        mint_to(ADDR_BOB, 1);
+        // Layout 1 keyed accounts by their hex string.
+        let carol = ADDR_CAROL.to_string();
+        let mut v1_balances: Map<String, u64> = KEYS.map(BALANCES_PREFIX);
+        let mut v1_lists: Map<String, Vec<u64>> = KEYS.map(OWNER_TOKENS_PREFIX);
+        let mut v1_operators: Map<(String, String), bool> = KEYS.map(OPERATOR_APPROVALS_PREFIX);
+        v1_balances.set(&carol, &2).unwrap();
+        v1_lists.set(&carol, &vec![7, 8]).unwrap();
+        v1_operators
+            .set(&(ADDR_BOB.to_string(), carol.clone()), &true)
+            .unwrap();
+
+        assert_eq!(read_balance(ADDR_CAROL).unwrap(), 2);
+        assert!(read_operator_approval(ADDR_BOB, ADDR_CAROL).unwrap());
+
+        // Carol moves Bob's token to herself as his operator.
+        mock::set_sender(ADDR_CAROL);
+        mock::set_call_data(&encode(&TransferFromArgs {
+            from: ADDR_BOB.parse().unwrap(),
+            to: ADDR_CAROL.parse().unwrap(),
+            token_id: 1,
+        }));
+        execute_transfer_from().unwrap();
+        assert!(!v1_balances.contains_key(&carol).unwrap());
+        assert!(!v1_lists.contains_key(&carol).unwrap());
+        assert_eq!(read_balance(ADDR_CAROL).unwrap(), 3);
+        assert_eq!(
+            read_token_list(&owner_tokens(), &AddressKey::parse(ADDR_CAROL).unwrap()).unwrap(),
+            [7, 8, 1]
+        );
+        assert!(
+            balances()
+                .storage_key(&AddressKey::parse(ADDR_CAROL).unwrap())
+                .unwrap()
+                .starts_with("crc721/balances/v2:")
+        );
+    }
 }
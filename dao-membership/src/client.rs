@@ -0,0 +1,38 @@
+//! Queries against a membership registry
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+/// A member's token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Membership {
+    pub token_id: u64,
+    /// 1 is the lowest tier
+    pub tier: u8,
+    pub joined_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GateArgs {
+    pub account: String,
+    pub min_tier: u8,
+}
+
+/// Ask the registry at `registry` whether `account` is a member of at least `min_tier`.
+pub fn gated_call(registry: &str, account: &str, min_tier: u8) -> ContractResult<bool> {
+    let args = postcard::to_allocvec(&GateArgs {
+        account: account.to_string(),
+        min_tier,
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    let response = context().call_contract(registry, "gated_call", &args)?;
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Fail with `Unauthorized` unless `account` is a member of at least `min_tier`.
+pub fn require_tier(registry: &str, account: &str, min_tier: u8) -> ContractResult<()> {
+    if !gated_call(registry, account, min_tier)? {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
@@ -0,0 +1,670 @@
+//! Membership registry entrypoints
+
+use crate::client::{GateArgs, Membership};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "membership_config";
+const NEXT_TOKEN_KEY: &str = "next_token_id";
+const SUPPLY_KEY: &str = "total_supply";
+const MEMBERS_PREFIX: &str = "members";
+const OWNERS_PREFIX: &str = "token_owners";
+const MAX_TIERS: u8 = 16;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Registry configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MembershipConfig {
+    pub owner: String,
+    pub name: String,
+    pub symbol: String,
+    /// Highest tier; tiers run from 1 to this
+    pub max_tier: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    name: String,
+    symbol: String,
+    max_tier: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MemberTierArgs {
+    account: String,
+    tier: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenArgs {
+    token_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OwnershipArgs {
+    new_owner: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<MembershipConfig> {
+    storage()
+        .get::<MembershipConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn load_owned_config() -> ContractResult<MembershipConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn members() -> Map<String, Membership> {
+    Map::new(MEMBERS_PREFIX)
+}
+
+fn token_owners() -> Map<u64, String> {
+    Map::new(OWNERS_PREFIX)
+}
+
+fn load_supply() -> ContractResult<u64> {
+    Ok(storage().get::<u64>(SUPPLY_KEY)?.unwrap_or(0))
+}
+
+fn validate_tier(config: &MembershipConfig, tier: u8) -> ContractResult<()> {
+    if tier == 0 || tier > config.max_tier {
+        return Err(ContractError::InvalidArgument(format!(
+            "Tier must be 1-{}",
+            config.max_tier
+        )));
+    }
+    Ok(())
+}
+
+fn load_member(account: &str) -> ContractResult<Membership> {
+    members()
+        .get(&account.to_string())?
+        .ok_or_else(|| ContractError::InvalidArgument("Account is not a member".to_string()))
+}
+
+fn tier_of_account(account: &str) -> ContractResult<u8> {
+    Ok(members()
+        .get(&account.to_string())?
+        .map_or(0, |member| member.tier))
+}
+
+fn remove_member(account: &str) -> ContractResult<Membership> {
+    let member = load_member(account)?;
+    members().remove(&account.to_string())?;
+    token_owners().remove(&member.token_id)?;
+    let mut store = storage();
+    store.set(SUPPLY_KEY, &safe_math::sub(load_supply()?, 1)?)?;
+
+    event!("Transfer", from: account, to: "", token_id: member.token_id);
+    Ok(member)
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let args: InitializeArgs = read_args()?;
+    if args.name.is_empty() || args.symbol.is_empty() {
+        return Err(ContractError::InvalidArgument(
+            "Name and symbol are required".to_string(),
+        ));
+    }
+    if args.max_tier == 0 || args.max_tier > MAX_TIERS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Tier count must be 1-{}",
+            MAX_TIERS
+        )));
+    }
+    let config = MembershipConfig {
+        owner: context().sender().to_string(),
+        name: args.name,
+        symbol: args.symbol,
+        max_tier: args.max_tier,
+    };
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("MembershipInitialized",
+        owner: config.owner,
+        name: config.name,
+        max_tier: config.max_tier
+    );
+    Ok(())
+}
+
+fn execute_mint() -> ContractResult<u64> {
+    let config = load_owned_config()?;
+    let args: MemberTierArgs = read_args()?;
+    validation::validate_address(&args.account)?;
+    validate_tier(&config, args.tier)?;
+    if members().contains_key(&args.account)? {
+        return Err(ContractError::InvalidArgument(
+            "Account is already a member".to_string(),
+        ));
+    }
+
+    let mut store = storage();
+    let token_id = store.get::<u64>(NEXT_TOKEN_KEY)?.unwrap_or(1);
+    store.set(NEXT_TOKEN_KEY, &safe_math::add(token_id, 1)?)?;
+    store.set(SUPPLY_KEY, &safe_math::add(load_supply()?, 1)?)?;
+    members().set(
+        &args.account,
+        &Membership {
+            token_id,
+            tier: args.tier,
+            joined_at: context().block_timestamp(),
+        },
+    )?;
+    token_owners().set(&token_id, &args.account)?;
+
+    event!("Transfer", from: "", to: args.account.clone(), token_id: token_id);
+    event!("MemberAdded", account: args.account, token_id: token_id, tier: args.tier);
+    admin_log::record(admin_log::MINT, &token_id.to_string(), "", &args.account)?;
+    Ok(token_id)
+}
+
+fn execute_set_tier() -> ContractResult<()> {
+    let config = load_owned_config()?;
+    let args: MemberTierArgs = read_args()?;
+    validate_tier(&config, args.tier)?;
+    let mut member = load_member(&args.account)?;
+    let previous = member.tier;
+    member.tier = args.tier;
+    members().set(&args.account, &member)?;
+
+    event!("TierChanged", account: args.account, previous: previous, tier: args.tier);
+    admin_log::record("set_tier", &args.account, previous, args.tier)?;
+    Ok(())
+}
+
+fn execute_revoke() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: AccountArgs = read_args()?;
+    let member = remove_member(&args.account)?;
+
+    event!("MemberRemoved",
+        account: args.account,
+        token_id: member.token_id,
+        by: context().sender()
+    );
+    admin_log::record("revoke", &args.account, member.tier, "")?;
+    Ok(())
+}
+
+fn execute_leave() -> ContractResult<()> {
+    let account = context().sender().to_string();
+    let member = remove_member(&account)?;
+
+    event!("MemberRemoved", account: account.clone(), token_id: member.token_id, by: account);
+    Ok(())
+}
+
+fn execute_transfer_ownership() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: OwnershipArgs = read_args()?;
+    validation::validate_address(&args.new_owner)?;
+    let previous = core::mem::replace(&mut config.owner, args.new_owner);
+    let mut store = storage();
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("OwnershipTransferred", previous: previous, new_owner: config.owner);
+    admin_log::record(
+        admin_log::TRANSFER_OWNERSHIP,
+        admin_log::OWNER_TARGET,
+        previous,
+        &config.owner,
+    )?;
+    Ok(())
+}
+
+fn execute_gated_call() -> ContractResult<bool> {
+    let args: GateArgs = read_args()?;
+    let tier = tier_of_account(&args.account)?;
+    Ok(tier > 0 && tier >= args.min_tier)
+}
+
+/// Initialize the registry; the caller becomes the owner
+///
+/// # Arguments
+/// * `name` - Collection name
+/// * `symbol` - Collection symbol
+/// * `max_tier` - Number of tiers (1-16)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Mint a membership token (owner only)
+///
+/// # Arguments
+/// * `account` - New member; must not already be one
+/// * `tier` - Starting tier
+///
+/// # Returns
+/// Token ID (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn mint() {
+    if let Err(err) = execute_mint().and_then(|token_id| try_respond(&token_id)) {
+        log!("Mint failed: {}", err);
+    }
+}
+
+/// Move a member to another tier (owner only)
+///
+/// # Arguments
+/// * `account` - Member address
+/// * `tier` - New tier
+#[unsafe(no_mangle)]
+pub extern "C" fn set_tier() {
+    if let Err(err) = execute_set_tier() {
+        log!("set_tier failed: {}", err);
+    }
+}
+
+/// Burn a member's token (owner only)
+///
+/// # Arguments
+/// * `account` - Member address
+#[unsafe(no_mangle)]
+pub extern "C" fn revoke() {
+    if let Err(err) = execute_revoke() {
+        log!("revoke failed: {}", err);
+    }
+}
+
+/// Burn the caller's own membership token
+#[unsafe(no_mangle)]
+pub extern "C" fn leave() {
+    if let Err(err) = execute_leave() {
+        log!("leave failed: {}", err);
+    }
+}
+
+/// Hand the registry to a new owner (owner only)
+///
+/// # Arguments
+/// * `new_owner` - New owner address
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_ownership() {
+    if let Err(err) = execute_transfer_ownership() {
+        log!("transfer_ownership failed: {}", err);
+    }
+}
+
+/// Membership tokens cannot be transferred; always fails
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    log("transfer_from failed: Membership tokens are non-transferable");
+}
+
+/// Check whether an account is a member of at least a tier
+///
+/// Meant for cross-contract calls through [`crate::client`]. Non-members
+/// are always rejected, even for `min_tier` 0.
+///
+/// # Arguments
+/// * `account` - Account address
+/// * `min_tier` - Lowest accepted tier
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn gated_call() {
+    if let Err(err) = execute_gated_call().and_then(|allowed| try_respond(&allowed)) {
+        log!("gated_call failed: {}", err);
+    }
+}
+
+/// Get an account's membership
+///
+/// # Arguments
+/// * `account` - Account address
+///
+/// # Returns
+/// `Option<Membership>`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_membership() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| members().get(&args.account))
+        .and_then(|member| try_respond(&member));
+    if let Err(err) = result {
+        log!("get_membership failed: {}", err);
+    }
+}
+
+/// Get an account's tier
+///
+/// # Arguments
+/// * `account` - Account address
+///
+/// # Returns
+/// Tier, 0 for non-members (u8)
+#[unsafe(no_mangle)]
+pub extern "C" fn tier_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| tier_of_account(&args.account))
+        .and_then(|tier| try_respond(&tier));
+    if let Err(err) = result {
+        log!("tier_of failed: {}", err);
+    }
+}
+
+/// Get the member holding a token
+///
+/// # Arguments
+/// * `token_id` - Token ID
+///
+/// # Returns
+/// Member address (String)
+#[unsafe(no_mangle)]
+pub extern "C" fn owner_of() {
+    let result = read_args::<TokenArgs>()
+        .and_then(|args| {
+            token_owners()
+                .get(&args.token_id)?
+                .ok_or_else(|| ContractError::InvalidArgument("Token does not exist".to_string()))
+        })
+        .and_then(|owner| try_respond(&owner));
+    if let Err(err) = result {
+        log!("owner_of failed: {}", err);
+    }
+}
+
+/// Get the number of membership tokens an account holds
+///
+/// # Arguments
+/// * `account` - Account address
+///
+/// # Returns
+/// 0 or 1 (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| members().contains_key(&args.account))
+        .and_then(|member| try_respond(&(member as u64)));
+    if let Err(err) = result {
+        log!("balance_of failed: {}", err);
+    }
+}
+
+/// Get the number of current members
+///
+/// # Returns
+/// Supply (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn total_supply() {
+    if let Err(err) = load_supply().and_then(|supply| try_respond(&supply)) {
+        log!("total_supply failed: {}", err);
+    }
+}
+
+/// Get the registry configuration
+///
+/// # Returns
+/// `MembershipConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000d04";
+    const REGISTRY: &str = "0x0000000000000000000000000000000000000f00";
+    const NOW: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// Three tiers, with Alice at tier 1 and Bob at tier 3
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(REGISTRY);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                name: "Silica DAO".to_string(),
+                symbol: "SDAO".to_string(),
+                max_tier: 3,
+            }),
+        );
+        mint_member(ADDR_OWNER, ADDR_ALICE, 1);
+        mint_member(ADDR_OWNER, ADDR_BOB, 3);
+    }
+
+    fn mint_member(sender: &str, account: &str, tier: u8) -> Option<u64> {
+        call(
+            sender,
+            mint,
+            encode(&MemberTierArgs {
+                account: account.to_string(),
+                tier,
+            }),
+        )
+    }
+
+    fn account(account: &str) -> Vec<u8> {
+        encode(&AccountArgs {
+            account: account.to_string(),
+        })
+    }
+
+    #[test]
+    fn owner_mints_one_token_per_member() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(
+            call::<Option<Membership>>(ADDR_CAROL, get_membership, account(ADDR_BOB)),
+            Some(Some(Membership {
+                token_id: 2,
+                tier: 3,
+                joined_at: NOW,
+            }))
+        );
+        assert_eq!(
+            call::<String>(ADDR_CAROL, owner_of, encode(&TokenArgs { token_id: 1 })),
+            Some(ADDR_ALICE.to_string())
+        );
+        assert_eq!(call::<u64>(ADDR_CAROL, total_supply, Vec::new()), Some(2));
+
+        assert_eq!(mint_member(ADDR_OWNER, ADDR_ALICE, 2), None);
+        assert_eq!(mint_member(ADDR_OWNER, ADDR_CAROL, 4), None);
+        assert_eq!(mint_member(ADDR_OWNER, ADDR_CAROL, 0), None);
+        assert_eq!(mint_member(ADDR_ALICE, ADDR_CAROL, 1), None);
+        assert_eq!(mint_member(ADDR_OWNER, ADDR_CAROL, 2), Some(3));
+    }
+
+    #[test]
+    fn tokens_cannot_be_transferred() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::take_events();
+        call::<()>(ADDR_ALICE, transfer_from, Vec::new());
+        assert!(mock::take_events().is_empty());
+        assert_eq!(
+            call::<String>(ADDR_CAROL, owner_of, encode(&TokenArgs { token_id: 1 })),
+            Some(ADDR_ALICE.to_string())
+        );
+        assert_eq!(
+            call::<u64>(ADDR_CAROL, balance_of, account(ADDR_ALICE)),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn tiers_change_and_members_are_removed() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        call::<()>(
+            ADDR_OWNER,
+            set_tier,
+            encode(&MemberTierArgs {
+                account: ADDR_ALICE.to_string(),
+                tier: 2,
+            }),
+        );
+        assert_eq!(
+            call::<u8>(ADDR_CAROL, tier_of, account(ADDR_ALICE)),
+            Some(2)
+        );
+
+        call::<()>(ADDR_ALICE, revoke, account(ADDR_BOB));
+        assert_eq!(call::<u8>(ADDR_CAROL, tier_of, account(ADDR_BOB)), Some(3));
+        call::<()>(ADDR_OWNER, revoke, account(ADDR_BOB));
+        assert_eq!(call::<u8>(ADDR_CAROL, tier_of, account(ADDR_BOB)), Some(0));
+        assert_eq!(
+            call::<String>(ADDR_CAROL, owner_of, encode(&TokenArgs { token_id: 2 })),
+            None
+        );
+
+        call::<()>(ADDR_ALICE, leave, Vec::new());
+        assert_eq!(call::<u64>(ADDR_CAROL, total_supply, Vec::new()), Some(0));
+        // Rejoining issues a fresh token.
+        assert_eq!(mint_member(ADDR_OWNER, ADDR_ALICE, 1), Some(3));
+        // Setup's two mints, then the tier change, revocation and re-mint;
+        // leaving is the member's own call and is not logged.
+        assert_eq!(admin_log::next_sequence().unwrap(), 5);
+    }
+
+    #[test]
+    fn ownership_moves_to_the_new_owner() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        call::<()>(
+            ADDR_OWNER,
+            transfer_ownership,
+            encode(&OwnershipArgs {
+                new_owner: ADDR_CAROL.to_string(),
+            }),
+        );
+        assert_eq!(load_config().unwrap().owner, ADDR_CAROL);
+        assert_eq!(mint_member(ADDR_OWNER, ADDR_CAROL, 1), None);
+        assert_eq!(mint_member(ADDR_CAROL, ADDR_CAROL, 1), Some(3));
+    }
+
+    #[test]
+    fn client_gates_on_minimum_tier() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::register_contract(REGISTRY, "gated_call", |args| {
+            mock::set_call_data(args);
+            gated_call();
+            Ok(mock::take_return_data())
+        });
+
+        assert!(client::gated_call(REGISTRY, ADDR_BOB, 3).unwrap());
+        assert!(client::gated_call(REGISTRY, ADDR_ALICE, 1).unwrap());
+        assert!(!client::gated_call(REGISTRY, ADDR_ALICE, 2).unwrap());
+        assert!(!client::gated_call(REGISTRY, ADDR_CAROL, 0).unwrap());
+        assert!(client::require_tier(REGISTRY, ADDR_BOB, 2).is_ok());
+        assert!(matches!(
+            client::require_tier(REGISTRY, ADDR_ALICE, 2),
+            Err(ContractError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "dao-membership-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! DAO Membership
+//!
+//! Non-transferable membership NFTs with tiers. The owner mints one token per
+//! member, moves members between tiers and revokes them; members can leave on
+//! their own. Other contracts restrict entrypoints to members of a minimum
+//! tier by querying the registry through [`client`].
+//!
+//! ## Gating
+//! ```toml
+//! dao-membership-contract = { path = "../dao-membership", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use dao_membership_contract::client;
+//!
+//! // Only tier 2 members and above may propose
+//! client::require_tier(&config.membership, ctx.sender(), 2)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod client;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
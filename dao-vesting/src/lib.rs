@@ -0,0 +1,985 @@
+//! DAO-Revocable Vesting
+//!
+//! Governance (typically a timelock) funds CRC-20 grants that vest linearly
+//! after a cliff. Only governance can revoke a grant: revocation freezes the
+//! vested amount, which stays claimable by the beneficiary, and returns the
+//! unvested remainder to the treasury. Intended for contributor compensation.
+//!
+//! ## Features
+//! - Linear vesting with an optional cliff
+//! - Revocation restricted to the governance address
+//! - Vested tokens survive revocation; unvested tokens go to the treasury
+//! - Per-beneficiary grant listing
+//! - Decommissioning: after governance's notice runs out, new grants stop
+//!   while existing grants keep vesting and paying out
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "dao_vesting_config";
+const NEXT_GRANT_KEY: &str = "next_grant_id";
+const GRANTS_PREFIX: &str = "grants";
+const BENEFICIARY_GRANTS_PREFIX: &str = "beneficiary_grants";
+const BENEFICIARY_GRANT_COUNT_PREFIX: &str = "beneficiary_grant_count";
+const SECONDS_PER_DAY: u64 = 86_400;
+const MIN_DURATION: u64 = SECONDS_PER_DAY;
+const MAX_DURATION: u64 = 10 * 365 * SECONDS_PER_DAY;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Contract configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestingConfig {
+    pub token: String,
+    /// Only address allowed to create and revoke grants
+    pub governance: String,
+    /// Receives unvested tokens of revoked grants
+    pub treasury: String,
+}
+
+/// Snapshot taken when governance revokes a grant
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Revocation {
+    pub revoked_at: u64,
+    /// Amount vested at revocation; the most the beneficiary can ever claim
+    pub vested: u64,
+    /// Amount returned to the treasury
+    pub returned: u64,
+}
+
+/// A vesting grant
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Grant {
+    pub beneficiary: String,
+    pub total: u64,
+    pub start: u64,
+    /// Seconds after `start` before anything vests
+    pub cliff: u64,
+    /// Seconds after `start` until the grant is fully vested
+    pub duration: u64,
+    pub claimed: u64,
+    pub revocation: Option<Revocation>,
+}
+
+/// One page of a beneficiary's grant ids
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GrantPage {
+    pub items: Vec<u64>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    token: String,
+    governance: String,
+    treasury: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateGrantArgs {
+    beneficiary: String,
+    amount: u64,
+    start: u64,
+    cliff: u64,
+    duration: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GrantIdArgs {
+    grant_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AddressArgs {
+    address: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GrantsOfArgs {
+    beneficiary: String,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<VestingConfig> {
+    storage()
+        .get::<VestingConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_config(config: &VestingConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+fn load_governed_config() -> ContractResult<VestingConfig> {
+    let config = load_config()?;
+    if context().sender() != config.governance {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn grants() -> Map<u64, Grant> {
+    Map::new(GRANTS_PREFIX)
+}
+
+fn beneficiary_grants() -> Map<(String, u64), u64> {
+    Map::new(BENEFICIARY_GRANTS_PREFIX)
+}
+
+fn beneficiary_grant_counts() -> Map<String, u64> {
+    Map::new(BENEFICIARY_GRANT_COUNT_PREFIX)
+}
+
+fn load_grant(grant_id: u64) -> ContractResult<Grant> {
+    grants()
+        .get(&grant_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown grant".to_string()))
+}
+
+fn pull_tokens(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+/// Amount of `grant` vested at `now`, frozen at revocation.
+pub fn vested_amount(grant: &Grant, now: u64) -> u64 {
+    if let Some(revocation) = &grant.revocation {
+        return revocation.vested;
+    }
+    let elapsed = now.saturating_sub(grant.start);
+    if elapsed < grant.cliff {
+        return 0;
+    }
+    if elapsed >= grant.duration {
+        return grant.total;
+    }
+    // Bounded by `total` since `elapsed < duration`.
+    ((grant.total as u128) * (elapsed as u128) / (grant.duration as u128)) as u64
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    validation::validate_address(&args.governance)?;
+    validation::validate_address(&args.treasury)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+
+    save_config(&VestingConfig {
+        token: args.token.clone(),
+        governance: args.governance.clone(),
+        treasury: args.treasury.clone(),
+    })?;
+
+    event!("VestingInitialized",
+        token: args.token,
+        governance: args.governance,
+        treasury: args.treasury
+    );
+    Ok(())
+}
+
+fn execute_create_grant() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    decommission::when_active()?;
+    let config = load_governed_config()?;
+    let args: CreateGrantArgs = read_args()?;
+    validation::validate_address(&args.beneficiary)?;
+    validation::validate_positive_amount(args.amount)?;
+    if args.duration < MIN_DURATION || args.duration > MAX_DURATION {
+        return Err(ContractError::InvalidArgument(
+            "Vesting must last between 1 day and 10 years".to_string(),
+        ));
+    }
+    if args.cliff > args.duration {
+        return Err(ContractError::InvalidArgument(
+            "Cliff cannot exceed the vesting duration".to_string(),
+        ));
+    }
+    safe_math::add(args.start, args.duration)?;
+
+    let mut store = storage();
+    let grant_id = store.get::<u64>(NEXT_GRANT_KEY)?.unwrap_or(0);
+    store.set(NEXT_GRANT_KEY, &safe_math::add(grant_id, 1)?)?;
+    grants().set(
+        &grant_id,
+        &Grant {
+            beneficiary: args.beneficiary.clone(),
+            total: args.amount,
+            start: args.start,
+            cliff: args.cliff,
+            duration: args.duration,
+            claimed: 0,
+            revocation: None,
+        },
+    )?;
+    let mut counts = beneficiary_grant_counts();
+    let count = counts.get(&args.beneficiary)?.unwrap_or(0);
+    beneficiary_grants().set(&(args.beneficiary.clone(), count), &grant_id)?;
+    counts.set(&args.beneficiary, &safe_math::add(count, 1)?)?;
+
+    pull_tokens(&config.token, &config.governance, args.amount)?;
+
+    event!("GrantCreated",
+        grant_id: grant_id,
+        beneficiary: args.beneficiary,
+        amount: args.amount,
+        start: args.start,
+        cliff: args.cliff,
+        duration: args.duration
+    );
+    admin_log::record(
+        "create_grant",
+        &grant_id.to_string(),
+        "",
+        format!("{} to {}", args.amount, args.beneficiary),
+    )?;
+    Ok(grant_id)
+}
+
+fn execute_claim() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let config = load_config()?;
+    let args: GrantIdArgs = read_args()?;
+    let mut grant = load_grant(args.grant_id)?;
+    if ctx.sender() != grant.beneficiary {
+        return Err(ContractError::Unauthorized);
+    }
+    let amount = vested_amount(&grant, ctx.block_timestamp()) - grant.claimed;
+    if amount == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Nothing to claim".to_string(),
+        ));
+    }
+    grant.claimed = safe_math::add(grant.claimed, amount)?;
+    grants().set(&args.grant_id, &grant)?;
+
+    send_tokens(&config.token, &grant.beneficiary, amount)?;
+
+    event!("TokensClaimed",
+        grant_id: args.grant_id,
+        beneficiary: grant.beneficiary,
+        amount: amount
+    );
+    Ok(amount)
+}
+
+fn execute_revoke() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let config = load_governed_config()?;
+    let args: GrantIdArgs = read_args()?;
+    let mut grant = load_grant(args.grant_id)?;
+    if grant.revocation.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Grant already revoked".to_string(),
+        ));
+    }
+    let now = ctx.block_timestamp();
+    let vested = vested_amount(&grant, now);
+    let returned = grant.total - vested;
+    if returned == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Grant is fully vested".to_string(),
+        ));
+    }
+    grant.revocation = Some(Revocation {
+        revoked_at: now,
+        vested,
+        returned,
+    });
+    grants().set(&args.grant_id, &grant)?;
+
+    send_tokens(&config.token, &config.treasury, returned)?;
+
+    event!("GrantRevoked",
+        grant_id: args.grant_id,
+        beneficiary: grant.beneficiary,
+        vested: vested,
+        returned: returned,
+        treasury: config.treasury
+    );
+    // The grant shrinks to what had vested.
+    admin_log::record("revoke", &args.grant_id.to_string(), grant.total, vested)?;
+    Ok(returned)
+}
+
+fn execute_set_governance() -> ContractResult<()> {
+    decommission::when_active()?;
+    let mut config = load_governed_config()?;
+    let args: AddressArgs = read_args()?;
+    validation::validate_address(&args.address)?;
+    let previous = core::mem::replace(&mut config.governance, args.address.clone());
+    save_config(&config)?;
+
+    event!("GovernanceTransferred",
+        previous: previous,
+        governance: args.address
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "governance",
+        previous,
+        &args.address,
+    )?;
+    Ok(())
+}
+
+fn execute_set_treasury() -> ContractResult<()> {
+    decommission::when_active()?;
+    let mut config = load_governed_config()?;
+    let args: AddressArgs = read_args()?;
+    validation::validate_address(&args.address)?;
+    let previous = core::mem::replace(&mut config.treasury, args.address.clone());
+    save_config(&config)?;
+
+    event!("TreasuryUpdated", treasury: args.address);
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "treasury",
+        previous,
+        &args.address,
+    )?;
+    Ok(())
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_governed_config()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_governed_config()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_governed_config()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_claimable() -> ContractResult<u64> {
+    let args: GrantIdArgs = read_args()?;
+    let grant = load_grant(args.grant_id)?;
+    Ok(vested_amount(&grant, context().block_timestamp()) - grant.claimed)
+}
+
+fn execute_grants_of() -> ContractResult<GrantPage> {
+    let args: GrantsOfArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let total = beneficiary_grant_counts()
+        .get(&args.beneficiary)?
+        .unwrap_or(0);
+    let end = args.offset.saturating_add(args.limit).min(total);
+    let index = beneficiary_grants();
+    let mut items = Vec::new();
+    for position in args.offset..end {
+        if let Some(id) = index.get(&(args.beneficiary.clone(), position))? {
+            items.push(id);
+        }
+    }
+    Ok(GrantPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+/// Initialize the contract
+///
+/// # Arguments
+/// * `token` - CRC-20 vested by every grant
+/// * `governance` - Address allowed to create and revoke grants
+/// * `treasury` - Receives unvested tokens of revoked grants
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Create a grant funded from the governance address (governance only)
+///
+/// # Arguments
+/// * `beneficiary` - Recipient of the vested tokens
+/// * `amount` - Total amount; governance must have approved it
+/// * `start` - Vesting start timestamp
+/// * `cliff` - Seconds after `start` before anything vests
+/// * `duration` - Seconds after `start` until fully vested (1 day to 10 years)
+///
+/// # Returns
+/// Grant id (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn create_grant() {
+    if let Err(err) = execute_create_grant().and_then(|id| try_respond(&id)) {
+        log!("create_grant failed: {}", err);
+    }
+}
+
+/// Claim everything vested so far (beneficiary only)
+///
+/// Works on revoked grants until the amount vested at revocation is claimed.
+///
+/// # Arguments
+/// * `grant_id` - Grant to claim from
+///
+/// # Returns
+/// Amount claimed (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn claim() {
+    if let Err(err) = execute_claim().and_then(|amount| try_respond(&amount)) {
+        log!("claim failed: {}", err);
+    }
+}
+
+/// Revoke a grant (governance only)
+///
+/// The amount vested so far stays claimable by the beneficiary; the
+/// unvested remainder is sent to the treasury.
+///
+/// # Arguments
+/// * `grant_id` - Grant to revoke
+///
+/// # Returns
+/// Amount returned to the treasury (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn revoke() {
+    if let Err(err) = execute_revoke().and_then(|returned| try_respond(&returned)) {
+        log!("revoke failed: {}", err);
+    }
+}
+
+/// Hand governance to a new address (governance only)
+///
+/// # Arguments
+/// * `address` - New governance address
+#[unsafe(no_mangle)]
+pub extern "C" fn set_governance() {
+    if let Err(err) = execute_set_governance() {
+        log!("set_governance failed: {}", err);
+    }
+}
+
+/// Change the treasury (governance only)
+///
+/// # Arguments
+/// * `address` - New treasury address
+#[unsafe(no_mangle)]
+pub extern "C" fn set_treasury() {
+    if let Err(err) = execute_set_treasury() {
+        log!("set_treasury failed: {}", err);
+    }
+}
+
+/// Give notice that the vesting contract will be decommissioned (governance only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (governance only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the vesting contract once the notice has run out (governance only)
+///
+/// New grants and changes to governance or the treasury stop for good.
+/// Beneficiaries keep claiming, and governance can still revoke.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the vesting contract is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Get the contract configuration
+///
+/// # Returns
+/// `VestingConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get a grant
+///
+/// # Arguments
+/// * `grant_id` - Grant id
+///
+/// # Returns
+/// `Grant`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_grant() {
+    let result = read_args::<GrantIdArgs>()
+        .and_then(|args| load_grant(args.grant_id))
+        .and_then(|grant| try_respond(&grant));
+    if let Err(err) = result {
+        log!("get_grant failed: {}", err);
+    }
+}
+
+/// Get the amount a grant's beneficiary can claim now
+///
+/// # Arguments
+/// * `grant_id` - Grant id
+///
+/// # Returns
+/// Claimable amount (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn claimable() {
+    if let Err(err) = execute_claimable().and_then(|amount| try_respond(&amount)) {
+        log!("claimable failed: {}", err);
+    }
+}
+
+/// List a beneficiary's grant ids
+///
+/// # Arguments
+/// * `beneficiary` - Beneficiary address
+/// * `offset` - Index of the first grant
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `GrantPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn grants_of() {
+    if let Err(err) = execute_grants_of().and_then(|page| try_respond(&page)) {
+        log!("grants_of failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_GOVERNANCE: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_TREASURY: &str = "0x0000000000000000000000000000000000000a02";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const START: u64 = 1_736_000_000;
+    const YEAR: u64 = 365 * SECONDS_PER_DAY;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Option<u64> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+        call(
+            ADDR_BOB,
+            initialize,
+            encode(&InitializeArgs {
+                token: TOKEN.to_string(),
+                governance: ADDR_GOVERNANCE.to_string(),
+                treasury: ADDR_TREASURY.to_string(),
+            }),
+        );
+    }
+
+    /// 4-year grant with a 1-year cliff
+    fn grant_as(sender: &str, beneficiary: &str, amount: u64) -> Option<u64> {
+        call(
+            sender,
+            create_grant,
+            encode(&CreateGrantArgs {
+                beneficiary: beneficiary.to_string(),
+                amount,
+                start: START,
+                cliff: YEAR,
+                duration: 4 * YEAR,
+            }),
+        )
+    }
+
+    fn id(grant_id: u64) -> Vec<u8> {
+        encode(&GrantIdArgs { grant_id })
+    }
+
+    /// (to, amount) of every token transfer out since the last call
+    fn payouts() -> Vec<(String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer")
+            .map(|c| {
+                let args: TokenTransferArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.to, args.amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grants_vest_linearly_after_the_cliff() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let grant_id = grant_as(ADDR_GOVERNANCE, ADDR_ALICE, 4_000).unwrap();
+        let pulled = mock::take_contract_calls();
+        let funding: TokenTransferFromArgs = postcard::from_bytes(&pulled[0].args).unwrap();
+        assert_eq!(
+            (funding.from.as_str(), funding.amount),
+            (ADDR_GOVERNANCE, 4_000)
+        );
+
+        mock::set_block_timestamp(START + YEAR - 1);
+        assert_eq!(call(ADDR_ALICE, claimable, id(grant_id)), Some(0));
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), None);
+
+        mock::set_block_timestamp(START + 2 * YEAR);
+        assert_eq!(call(ADDR_BOB, claim, id(grant_id)), None);
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), Some(2_000));
+        mock::set_block_timestamp(START + 10 * YEAR);
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), Some(2_000));
+        assert_eq!(
+            payouts(),
+            [
+                (ADDR_ALICE.to_string(), 2_000),
+                (ADDR_ALICE.to_string(), 2_000)
+            ]
+        );
+        assert_eq!(load_grant(grant_id).unwrap().claimed, 4_000);
+    }
+
+    #[test]
+    fn only_governance_creates_and_revokes() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(grant_as(ADDR_ALICE, ADDR_ALICE, 4_000), None);
+        assert_eq!(grant_as(ADDR_TREASURY, ADDR_ALICE, 4_000), None);
+        let grant_id = grant_as(ADDR_GOVERNANCE, ADDR_ALICE, 4_000).unwrap();
+        mock::take_contract_calls();
+
+        mock::set_block_timestamp(START + 2 * YEAR);
+        for sender in [ADDR_ALICE, ADDR_TREASURY, ADDR_BOB] {
+            assert_eq!(call(sender, revoke, id(grant_id)), None);
+        }
+        assert!(payouts().is_empty());
+        assert_eq!(load_grant(grant_id).unwrap().revocation, None);
+        // Only governance's grant reached the audit log.
+        assert_eq!(admin_log::next_sequence().unwrap(), 1);
+    }
+
+    #[test]
+    fn revocation_keeps_vested_and_returns_the_rest() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let grant_id = grant_as(ADDR_GOVERNANCE, ADDR_ALICE, 4_000).unwrap();
+        mock::set_block_timestamp(START + 2 * YEAR);
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), Some(2_000));
+
+        mock::set_block_timestamp(START + 3 * YEAR);
+        assert_eq!(call(ADDR_GOVERNANCE, revoke, id(grant_id)), Some(1_000));
+        assert_eq!(
+            load_grant(grant_id).unwrap().revocation,
+            Some(Revocation {
+                revoked_at: START + 3 * YEAR,
+                vested: 3_000,
+                returned: 1_000
+            })
+        );
+        assert_eq!(call(ADDR_GOVERNANCE, revoke, id(grant_id)), None);
+
+        // Nothing vests after revocation; the vested balance is still claimable.
+        mock::set_block_timestamp(START + 10 * YEAR);
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), Some(1_000));
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), None);
+        assert_eq!(
+            payouts(),
+            [
+                (ADDR_ALICE.to_string(), 2_000),
+                (ADDR_TREASURY.to_string(), 1_000),
+                (ADDR_ALICE.to_string(), 1_000)
+            ]
+        );
+        assert!(
+            mock::take_events()
+                .iter()
+                .any(|event| event.topic == "GrantRevoked")
+        );
+    }
+
+    #[test]
+    fn fully_vested_grants_cannot_be_revoked() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let grant_id = grant_as(ADDR_GOVERNANCE, ADDR_ALICE, 4_000).unwrap();
+        mock::take_contract_calls();
+        mock::set_block_timestamp(START + 4 * YEAR);
+        assert_eq!(call(ADDR_GOVERNANCE, revoke, id(grant_id)), None);
+        assert!(payouts().is_empty());
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), Some(4_000));
+
+        // A backdated grant is vested on creation; one revoked before its
+        // cliff returns everything.
+        let early = grant_as(ADDR_GOVERNANCE, ADDR_BOB, 500).unwrap();
+        mock::set_block_timestamp(START + 4 * YEAR + 1);
+        let late = call(
+            ADDR_GOVERNANCE,
+            create_grant,
+            encode(&CreateGrantArgs {
+                beneficiary: ADDR_BOB.to_string(),
+                amount: 700,
+                start: START + 4 * YEAR,
+                cliff: YEAR,
+                duration: 2 * YEAR,
+            }),
+        )
+        .unwrap();
+        assert_eq!(call(ADDR_GOVERNANCE, revoke, id(early)), None);
+        assert_eq!(call(ADDR_GOVERNANCE, revoke, id(late)), Some(700));
+        assert_eq!(call(ADDR_BOB, claimable, id(late)), Some(0));
+    }
+
+    #[test]
+    fn governance_can_be_handed_over() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let address = |address: &str| {
+            encode(&AddressArgs {
+                address: address.to_string(),
+            })
+        };
+        call(ADDR_ALICE, set_governance, address(ADDR_ALICE));
+        call(ADDR_GOVERNANCE, set_treasury, address(ADDR_BOB));
+        call(ADDR_GOVERNANCE, set_governance, address(ADDR_ALICE));
+        assert_eq!(
+            load_config().unwrap(),
+            VestingConfig {
+                token: TOKEN.to_string(),
+                governance: ADDR_ALICE.to_string(),
+                treasury: ADDR_BOB.to_string(),
+            }
+        );
+        assert_eq!(grant_as(ADDR_GOVERNANCE, ADDR_BOB, 100), None);
+        let first = grant_as(ADDR_ALICE, ADDR_BOB, 100).unwrap();
+        let second = grant_as(ADDR_ALICE, ADDR_BOB, 200).unwrap();
+
+        mock::set_sender(ADDR_BOB);
+        mock::set_call_data(&encode(&GrantsOfArgs {
+            beneficiary: ADDR_BOB.to_string(),
+            offset: 0,
+            limit: 1,
+        }));
+        grants_of();
+        let page: GrantPage = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(
+            page,
+            GrantPage {
+                items: vec![first],
+                total: 2,
+                next_offset: Some(1)
+            }
+        );
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn decommissioning_stops_new_grants_but_not_claims() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let grant_id = grant_as(ADDR_GOVERNANCE, ADDR_ALICE, 4_000).unwrap();
+        mock::take_contract_calls();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call(ADDR_GOVERNANCE, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        call(ADDR_GOVERNANCE, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(grant_as(ADDR_GOVERNANCE, ADDR_BOB, 100), None);
+        call(
+            ADDR_GOVERNANCE,
+            set_treasury,
+            encode(&AddressArgs {
+                address: ADDR_BOB.to_string(),
+            }),
+        );
+        assert_eq!(load_config().unwrap().treasury, ADDR_TREASURY);
+
+        mock::set_block_timestamp(START + 2 * YEAR);
+        assert_eq!(call(ADDR_ALICE, claim, id(grant_id)), Some(2_000));
+        assert_eq!(call(ADDR_GOVERNANCE, revoke, id(grant_id)), Some(2_000));
+        assert_eq!(
+            payouts(),
+            [
+                (ADDR_ALICE.to_string(), 2_000),
+                (ADDR_TREASURY.to_string(), 2_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "dao-vesting-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Delegation checks
+//!
+//! Thin wrappers around the registry's `check_delegate_*` entrypoints. The
+//! checks are hierarchical: an all-assets delegation passes every check and
+//! a contract delegation passes every token check for that contract.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+/// What a delegation covers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum DelegationScope {
+    /// Everything the vault holds
+    All,
+    /// Everything the vault holds in `contract`
+    Contract { contract: String },
+    /// Token `token_id` of `contract`
+    Token { contract: String, token_id: u64 },
+}
+
+/// A vault's grant of rights to a delegate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Delegation {
+    pub vault: String,
+    pub delegate: String,
+    pub scope: DelegationScope,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CheckAllArgs {
+    pub delegate: String,
+    pub vault: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CheckContractArgs {
+    pub delegate: String,
+    pub vault: String,
+    pub contract: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CheckTokenArgs {
+    pub delegate: String,
+    pub vault: String,
+    pub contract: String,
+    pub token_id: u64,
+}
+
+fn query<T: Serialize>(registry: &str, method: &str, args: &T) -> ContractResult<bool> {
+    let data = postcard::to_allocvec(args).map_err(|_| ContractError::SerializationFailed)?;
+    let response = context().call_contract(registry, method, &data)?;
+    if response.is_empty() {
+        return Err(ContractError::ContractCallFailed(format!(
+            "Delegation registry returned nothing for {}",
+            method
+        )));
+    }
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Whether `delegate` may act for `vault` on all of its assets.
+pub fn check_delegate_for_all(registry: &str, delegate: &str, vault: &str) -> ContractResult<bool> {
+    query(
+        registry,
+        "check_delegate_for_all",
+        &CheckAllArgs {
+            delegate: delegate.to_string(),
+            vault: vault.to_string(),
+        },
+    )
+}
+
+/// Whether `delegate` may act for `vault` on its assets in `contract`.
+pub fn check_delegate_for_contract(
+    registry: &str,
+    delegate: &str,
+    vault: &str,
+    contract: &str,
+) -> ContractResult<bool> {
+    query(
+        registry,
+        "check_delegate_for_contract",
+        &CheckContractArgs {
+            delegate: delegate.to_string(),
+            vault: vault.to_string(),
+            contract: contract.to_string(),
+        },
+    )
+}
+
+/// Whether `delegate` may act for `vault` on token `token_id` of `contract`.
+pub fn check_delegate_for_token(
+    registry: &str,
+    delegate: &str,
+    vault: &str,
+    contract: &str,
+    token_id: u64,
+) -> ContractResult<bool> {
+    query(
+        registry,
+        "check_delegate_for_token",
+        &CheckTokenArgs {
+            delegate: delegate.to_string(),
+            vault: vault.to_string(),
+            contract: contract.to_string(),
+            token_id,
+        },
+    )
+}
+
+/// Whether `caller` is `holder` or one of its delegates for `contract`, or
+/// for token `token_id` of it when given. Holders never need a registry call.
+pub fn acts_for(
+    registry: &str,
+    caller: &str,
+    holder: &str,
+    contract: &str,
+    token_id: Option<u64>,
+) -> ContractResult<bool> {
+    if caller == holder {
+        return Ok(true);
+    }
+    match token_id {
+        Some(token_id) => check_delegate_for_token(registry, caller, holder, contract, token_id),
+        None => check_delegate_for_contract(registry, caller, holder, contract),
+    }
+}
@@ -0,0 +1,645 @@
+//! Delegation registry entrypoints
+
+use crate::client::{CheckAllArgs, CheckContractArgs, CheckTokenArgs, Delegation, DelegationScope};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const NEXT_DELEGATION_KEY: &str = "next_delegation_id";
+const DELEGATIONS_PREFIX: &str = "delegations";
+const ACTIVE_PREFIX: &str = "active_delegations";
+const VAULT_INDEX: IdIndex = IdIndex {
+    items: "vault_delegations",
+    count: "vault_delegation_count",
+    position: "vault_delegation_position",
+};
+const DELEGATE_INDEX: IdIndex = IdIndex {
+    items: "delegate_delegations",
+    count: "delegate_delegation_count",
+    position: "delegate_delegation_position",
+};
+const MAX_DELEGATIONS_PER_VAULT: u64 = 64;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// One page of delegations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DelegationPage {
+    pub items: Vec<Delegation>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DelegateArgs {
+    delegate: String,
+    scope: DelegationScope,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PageArgs {
+    account: String,
+    offset: u64,
+    limit: u64,
+}
+
+/// Unordered list of delegation ids per account, with O(1) removal by
+/// moving the last id into the freed slot.
+struct IdIndex {
+    items: &'static str,
+    count: &'static str,
+    position: &'static str,
+}
+
+impl IdIndex {
+    fn items(&self) -> Map<(String, u64), u64> {
+        Map::new(self.items)
+    }
+
+    fn counts(&self) -> Map<String, u64> {
+        Map::new(self.count)
+    }
+
+    fn positions(&self) -> Map<u64, u64> {
+        Map::new(self.position)
+    }
+
+    fn len(&self, account: &str) -> ContractResult<u64> {
+        Ok(self.counts().get(&account.to_string())?.unwrap_or(0))
+    }
+
+    fn get(&self, account: &str, index: u64) -> ContractResult<u64> {
+        self.items()
+            .get(&(account.to_string(), index))?
+            .ok_or(ContractError::StorageReadFailed)
+    }
+
+    fn push(&self, account: &str, id: u64) -> ContractResult<()> {
+        let len = self.len(account)?;
+        self.items().set(&(account.to_string(), len), &id)?;
+        self.positions().set(&id, &len)?;
+        self.counts()
+            .set(&account.to_string(), &safe_math::add(len, 1)?)
+    }
+
+    fn remove(&self, account: &str, id: u64) -> ContractResult<()> {
+        let mut positions = self.positions();
+        let position = positions
+            .get(&id)?
+            .ok_or(ContractError::StorageReadFailed)?;
+        let last = self.len(account)? - 1;
+        let mut items = self.items();
+        if position != last {
+            let moved = self.get(account, last)?;
+            items.set(&(account.to_string(), position), &moved)?;
+            positions.set(&moved, &position)?;
+        }
+        items.remove(&(account.to_string(), last))?;
+        positions.remove(&id)?;
+        self.counts().set(&account.to_string(), &last)
+    }
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn delegations() -> Map<u64, Delegation> {
+    Map::new(DELEGATIONS_PREFIX)
+}
+
+/// Delegation id by (vault, delegate, scope)
+fn active() -> Map<(String, String, DelegationScope), u64> {
+    Map::new(ACTIVE_PREFIX)
+}
+
+fn load_delegation(id: u64) -> ContractResult<Delegation> {
+    delegations()
+        .get(&id)?
+        .ok_or(ContractError::StorageReadFailed)
+}
+
+fn is_delegated(delegate: &str, vault: &str, scope: DelegationScope) -> ContractResult<bool> {
+    active().contains_key(&(vault.to_string(), delegate.to_string(), scope))
+}
+
+fn check_all(delegate: &str, vault: &str) -> ContractResult<bool> {
+    is_delegated(delegate, vault, DelegationScope::All)
+}
+
+fn check_contract(delegate: &str, vault: &str, contract: &str) -> ContractResult<bool> {
+    Ok(check_all(delegate, vault)?
+        || is_delegated(
+            delegate,
+            vault,
+            DelegationScope::Contract {
+                contract: contract.to_string(),
+            },
+        )?)
+}
+
+fn check_token(delegate: &str, vault: &str, contract: &str, token_id: u64) -> ContractResult<bool> {
+    Ok(check_contract(delegate, vault, contract)?
+        || is_delegated(
+            delegate,
+            vault,
+            DelegationScope::Token {
+                contract: contract.to_string(),
+                token_id,
+            },
+        )?)
+}
+
+fn remove_delegation(id: u64, delegation: &Delegation) -> ContractResult<()> {
+    active().remove(&(
+        delegation.vault.clone(),
+        delegation.delegate.clone(),
+        delegation.scope.clone(),
+    ))?;
+    delegations().remove(&id)?;
+    VAULT_INDEX.remove(&delegation.vault, id)?;
+    DELEGATE_INDEX.remove(&delegation.delegate, id)
+}
+
+fn execute_delegate() -> ContractResult<u64> {
+    let ctx = context();
+    let vault = ctx.sender().to_string();
+    let args: DelegateArgs = read_args()?;
+    validation::validate_address(&args.delegate)?;
+    if args.delegate == vault {
+        return Err(ContractError::InvalidArgument(
+            "Cannot delegate to yourself".to_string(),
+        ));
+    }
+    match &args.scope {
+        DelegationScope::All => {}
+        DelegationScope::Contract { contract } | DelegationScope::Token { contract, .. } => {
+            validation::validate_address(contract)?
+        }
+    }
+    let key = (vault.clone(), args.delegate.clone(), args.scope.clone());
+    if active().contains_key(&key)? {
+        return Err(ContractError::InvalidArgument(
+            "Delegation already exists".to_string(),
+        ));
+    }
+    if VAULT_INDEX.len(&vault)? >= MAX_DELEGATIONS_PER_VAULT {
+        return Err(ContractError::InvalidArgument(format!(
+            "A vault can have at most {} delegations",
+            MAX_DELEGATIONS_PER_VAULT
+        )));
+    }
+
+    let id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_DELEGATION_KEY)?.unwrap_or(0);
+        store.set(NEXT_DELEGATION_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    delegations().set(
+        &id,
+        &Delegation {
+            vault: vault.clone(),
+            delegate: args.delegate.clone(),
+            scope: args.scope.clone(),
+            created_at: ctx.block_timestamp(),
+        },
+    )?;
+    active().set(&key, &id)?;
+    VAULT_INDEX.push(&vault, id)?;
+    DELEGATE_INDEX.push(&args.delegate, id)?;
+
+    event!("DelegationCreated",
+        id: id,
+        vault: vault,
+        delegate: args.delegate,
+        scope: format!("{:?}", args.scope)
+    );
+    Ok(id)
+}
+
+fn execute_revoke() -> ContractResult<()> {
+    let vault = context().sender().to_string();
+    let args: DelegateArgs = read_args()?;
+    let id = active()
+        .get(&(vault.clone(), args.delegate.clone(), args.scope))?
+        .ok_or_else(|| ContractError::InvalidArgument("No such delegation".to_string()))?;
+    let delegation = load_delegation(id)?;
+    remove_delegation(id, &delegation)?;
+
+    event!("DelegationRevoked", id: id, vault: vault, delegate: args.delegate);
+    Ok(())
+}
+
+fn execute_revoke_all() -> ContractResult<u64> {
+    let vault = context().sender().to_string();
+    let count = VAULT_INDEX.len(&vault)?;
+    // Always take the last entry so removal never moves another id.
+    for index in (0..count).rev() {
+        let id = VAULT_INDEX.get(&vault, index)?;
+        let delegation = load_delegation(id)?;
+        remove_delegation(id, &delegation)?;
+    }
+
+    event!("AllDelegationsRevoked", vault: vault, count: count);
+    Ok(count)
+}
+
+fn execute_page(index: &IdIndex) -> ContractResult<DelegationPage> {
+    let args: PageArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let total = index.len(&args.account)?;
+    let end = args.offset.saturating_add(args.limit).min(total);
+    let mut items = Vec::new();
+    for position in args.offset..end {
+        items.push(load_delegation(index.get(&args.account, position)?)?);
+    }
+    Ok(DelegationPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+/// Delegate rights over the caller's assets to another wallet
+///
+/// # Arguments
+/// * `delegate` - Wallet that may act for the caller
+/// * `scope` - `All`, `Contract { contract }` or `Token { contract, token_id }`
+///
+/// # Returns
+/// Delegation id
+#[unsafe(no_mangle)]
+pub extern "C" fn delegate() {
+    let result = execute_delegate().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("delegate failed: {}", err);
+    }
+}
+
+/// Revoke one of the caller's delegations
+///
+/// # Arguments
+/// * `delegate` - Delegated wallet
+/// * `scope` - Scope of the delegation to revoke
+#[unsafe(no_mangle)]
+pub extern "C" fn revoke() {
+    if let Err(err) = execute_revoke() {
+        log!("revoke failed: {}", err);
+    }
+}
+
+/// Revoke every delegation the caller has made
+///
+/// # Returns
+/// Number of delegations revoked
+#[unsafe(no_mangle)]
+pub extern "C" fn revoke_all() {
+    let result = execute_revoke_all().and_then(|count| try_respond(&count));
+    if let Err(err) = result {
+        log!("revoke_all failed: {}", err);
+    }
+}
+
+/// Whether `delegate` may act for `vault` on all of its assets
+///
+/// # Arguments
+/// * `delegate` - Wallet to check
+/// * `vault` - Wallet that holds the assets
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn check_delegate_for_all() {
+    let result = read_args::<CheckAllArgs>()
+        .and_then(|args| check_all(&args.delegate, &args.vault))
+        .and_then(|allowed| try_respond(&allowed));
+    if let Err(err) = result {
+        log!("check_delegate_for_all failed: {}", err);
+    }
+}
+
+/// Whether `delegate` may act for `vault` on its assets in `contract`
+///
+/// # Arguments
+/// * `delegate` - Wallet to check
+/// * `vault` - Wallet that holds the assets
+/// * `contract` - Token contract
+///
+/// # Returns
+/// `bool`; true for all-assets and contract delegations
+#[unsafe(no_mangle)]
+pub extern "C" fn check_delegate_for_contract() {
+    let result = read_args::<CheckContractArgs>()
+        .and_then(|args| check_contract(&args.delegate, &args.vault, &args.contract))
+        .and_then(|allowed| try_respond(&allowed));
+    if let Err(err) = result {
+        log!("check_delegate_for_contract failed: {}", err);
+    }
+}
+
+/// Whether `delegate` may act for `vault` on one token of `contract`
+///
+/// # Arguments
+/// * `delegate` - Wallet to check
+/// * `vault` - Wallet that holds the token
+/// * `contract` - Token contract
+/// * `token_id` - Token id
+///
+/// # Returns
+/// `bool`; true for all-assets, contract and token delegations
+#[unsafe(no_mangle)]
+pub extern "C" fn check_delegate_for_token() {
+    let result = read_args::<CheckTokenArgs>()
+        .and_then(|args| check_token(&args.delegate, &args.vault, &args.contract, args.token_id))
+        .and_then(|allowed| try_respond(&allowed));
+    if let Err(err) = result {
+        log!("check_delegate_for_token failed: {}", err);
+    }
+}
+
+/// List the delegations a vault has made
+///
+/// # Arguments
+/// * `account` - Vault
+/// * `offset` - First index to return
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `DelegationPage`; order changes when delegations are revoked
+#[unsafe(no_mangle)]
+pub extern "C" fn delegations_by_vault() {
+    if let Err(err) = execute_page(&VAULT_INDEX).and_then(|page| try_respond(&page)) {
+        log!("delegations_by_vault failed: {}", err);
+    }
+}
+
+/// List the delegations a wallet has received
+///
+/// # Arguments
+/// * `account` - Delegate
+/// * `offset` - First index to return
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `DelegationPage`; order changes when delegations are revoked
+#[unsafe(no_mangle)]
+pub extern "C" fn delegations_by_delegate() {
+    if let Err(err) = execute_page(&DELEGATE_INDEX).and_then(|page| try_respond(&page)) {
+        log!("delegations_by_delegate failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_COLD: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_HOT: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_OTHER: &str = "0x0000000000000000000000000000000000000c03";
+    const NFT: &str = "0x0000000000000000000000000000000000000d04";
+    const GAME: &str = "0x0000000000000000000000000000000000000e05";
+    const REGISTRY: &str = "0x0000000000000000000000000000000000000f00";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(REGISTRY);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn grant(vault: &str, delegate_to: &str, scope: DelegationScope) -> Vec<u8> {
+        call(
+            vault,
+            delegate,
+            encode(&DelegateArgs {
+                delegate: delegate_to.to_string(),
+                scope,
+            }),
+        )
+    }
+
+    fn token(contract: &str, token_id: u64) -> DelegationScope {
+        DelegationScope::Token {
+            contract: contract.to_string(),
+            token_id,
+        }
+    }
+
+    fn page(entrypoint: extern "C" fn(), account: &str) -> DelegationPage {
+        let data = call(
+            ADDR_OTHER,
+            entrypoint,
+            encode(&PageArgs {
+                account: account.to_string(),
+                offset: 0,
+                limit: 50,
+            }),
+        );
+        postcard::from_bytes(&data).unwrap()
+    }
+
+    #[test]
+    fn checks_follow_the_scope_hierarchy() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        grant(ADDR_COLD, ADDR_HOT, token(NFT, 7));
+        assert!(check_token(ADDR_HOT, ADDR_COLD, NFT, 7).unwrap());
+        assert!(!check_token(ADDR_HOT, ADDR_COLD, NFT, 8).unwrap());
+        assert!(!check_contract(ADDR_HOT, ADDR_COLD, NFT).unwrap());
+
+        grant(
+            ADDR_COLD,
+            ADDR_HOT,
+            DelegationScope::Contract {
+                contract: NFT.to_string(),
+            },
+        );
+        assert!(check_token(ADDR_HOT, ADDR_COLD, NFT, 8).unwrap());
+        assert!(!check_contract(ADDR_HOT, ADDR_COLD, GAME).unwrap());
+        assert!(!check_all(ADDR_HOT, ADDR_COLD).unwrap());
+
+        grant(ADDR_COLD, ADDR_HOT, DelegationScope::All);
+        assert!(check_token(ADDR_HOT, ADDR_COLD, GAME, 1).unwrap());
+        // Delegation is one-way.
+        assert!(!check_all(ADDR_COLD, ADDR_HOT).unwrap());
+    }
+
+    #[test]
+    fn duplicate_and_self_delegations_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        assert!(!grant(ADDR_COLD, ADDR_HOT, DelegationScope::All).is_empty());
+        assert!(grant(ADDR_COLD, ADDR_HOT, DelegationScope::All).is_empty());
+        assert!(grant(ADDR_COLD, ADDR_COLD, DelegationScope::All).is_empty());
+        assert_eq!(page(delegations_by_vault, ADDR_COLD).total, 1);
+    }
+
+    #[test]
+    fn revocation_updates_both_listings() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        grant(ADDR_COLD, ADDR_HOT, token(NFT, 1));
+        grant(ADDR_COLD, ADDR_HOT, token(NFT, 2));
+        grant(ADDR_COLD, ADDR_OTHER, token(NFT, 3));
+
+        // Only the vault can revoke its delegation.
+        let revoke_first = encode(&DelegateArgs {
+            delegate: ADDR_HOT.to_string(),
+            scope: token(NFT, 1),
+        });
+        call(ADDR_HOT, revoke, revoke_first.clone());
+        assert!(check_token(ADDR_HOT, ADDR_COLD, NFT, 1).unwrap());
+        call(ADDR_COLD, revoke, revoke_first);
+        assert!(!check_token(ADDR_HOT, ADDR_COLD, NFT, 1).unwrap());
+
+        let scopes = |page: DelegationPage| -> Vec<DelegationScope> {
+            page.items.into_iter().map(|d| d.scope).collect()
+        };
+        assert_eq!(
+            scopes(page(delegations_by_vault, ADDR_COLD)),
+            [token(NFT, 3), token(NFT, 2)]
+        );
+        assert_eq!(
+            scopes(page(delegations_by_delegate, ADDR_HOT)),
+            [token(NFT, 2)]
+        );
+    }
+
+    #[test]
+    fn revoke_all_clears_the_vault() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        grant(ADDR_COLD, ADDR_HOT, DelegationScope::All);
+        grant(ADDR_COLD, ADDR_OTHER, token(NFT, 3));
+        grant(ADDR_OTHER, ADDR_HOT, DelegationScope::All);
+
+        let count: u64 = postcard::from_bytes(&call(ADDR_COLD, revoke_all, Vec::new())).unwrap();
+        assert_eq!(count, 2);
+        assert!(!check_all(ADDR_HOT, ADDR_COLD).unwrap());
+        assert_eq!(page(delegations_by_vault, ADDR_COLD).total, 0);
+        assert_eq!(page(delegations_by_delegate, ADDR_OTHER).total, 0);
+        // Delegations made by other vaults are untouched.
+        assert_eq!(page(delegations_by_delegate, ADDR_HOT).total, 1);
+
+        // The vault can delegate again afterwards.
+        assert!(!grant(ADDR_COLD, ADDR_HOT, DelegationScope::All).is_empty());
+    }
+
+    #[test]
+    fn client_checks_through_cross_contract_call() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        grant(
+            ADDR_COLD,
+            ADDR_HOT,
+            DelegationScope::Contract {
+                contract: NFT.to_string(),
+            },
+        );
+
+        for method in [
+            "check_delegate_for_all",
+            "check_delegate_for_contract",
+            "check_delegate_for_token",
+        ] {
+            let entrypoint: extern "C" fn() = match method {
+                "check_delegate_for_all" => check_delegate_for_all,
+                "check_delegate_for_contract" => check_delegate_for_contract,
+                _ => check_delegate_for_token,
+            };
+            mock::register_contract(REGISTRY, method, move |args| {
+                mock::set_call_data(args);
+                entrypoint();
+                Ok(mock::take_return_data())
+            });
+        }
+
+        assert!(!client::check_delegate_for_all(REGISTRY, ADDR_HOT, ADDR_COLD).unwrap());
+        assert!(client::acts_for(REGISTRY, ADDR_HOT, ADDR_COLD, NFT, Some(4)).unwrap());
+        assert!(client::acts_for(REGISTRY, ADDR_HOT, ADDR_COLD, NFT, None).unwrap());
+        assert!(!client::acts_for(REGISTRY, ADDR_HOT, ADDR_COLD, GAME, None).unwrap());
+        assert!(!client::acts_for(REGISTRY, ADDR_OTHER, ADDR_COLD, NFT, Some(4)).unwrap());
+        assert!(client::acts_for(REGISTRY, ADDR_COLD, ADDR_COLD, GAME, None).unwrap());
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "delegation-registry-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
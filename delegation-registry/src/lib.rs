@@ -0,0 +1,35 @@
+//! Delegation Registry
+//!
+//! Lets a cold wallet (the vault) name hot wallets that may act for it
+//! without moving any assets. A delegation covers everything the vault
+//! holds, everything it holds in one contract, or a single token of one
+//! contract. Delegations can be listed by vault or by delegate and revoked
+//! at any time. The [`client`] module is the query API token-gated contracts
+//! use to decide whether a caller may act for a holder.
+//!
+//! ## Embedding
+//! ```toml
+//! delegation-registry-contract = { path = "../delegation-registry", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use delegation_registry_contract::client;
+//!
+//! let holder = ticket.owner;
+//! if !client::acts_for(&config.delegations, caller, &holder, ctx.contract_address(), Some(id))? {
+//!     return Err(ContractError::Unauthorized);
+//! }
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod client;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
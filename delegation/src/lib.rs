@@ -0,0 +1,1396 @@
+//! Validator Delegation
+//!
+//! Users delegate the staking asset to validators that the owner has
+//! registered. The asset is either the native coin or a CRC-20. Each epoch
+//! the reward oracle pushes every validator's rewards. The validator keeps
+//! its commission, and the rest is shared among its delegators in
+//! proportion to their stake. Rewards accrue through a per-validator
+//! accumulator, so distribution costs the same however many delegators a
+//! validator has. Undelegated stake is released after the unbonding
+//! period. Redelegating moves stake between validators at once, but each
+//! delegator can only do it once per cooldown.
+//!
+//! ## Features
+//! - Owner-registered validators with self-set commission
+//! - Per-epoch reward distribution by an authorized oracle
+//! - Pull-based rewards for delegators and commission for validators
+//! - Unbonding queue and rate-limited redelegation
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "delegation_config";
+const NEXT_UNBOND_ID_KEY: &str = "next_unbond_id";
+const VALIDATOR_COUNT_KEY: &str = "validator_count";
+const VALIDATORS_PREFIX: &str = "validators";
+const VALIDATOR_AT_PREFIX: &str = "validator_at";
+const DELEGATIONS_PREFIX: &str = "delegations";
+const UNBONDS_PREFIX: &str = "unbonds";
+const REDELEGATED_AT_PREFIX: &str = "redelegated_at";
+/// Pass as `asset` to delegate the native coin
+pub const NATIVE_ASSET: &str = "native";
+const BPS_DENOMINATOR: u64 = 10_000;
+/// Scale of the reward-per-stake accumulator
+const REWARD_PRECISION: u128 = 1_000_000_000_000;
+const MAX_REWARDS_PER_EPOCH: usize = 50;
+const MAX_PERIOD: u64 = 60 * 86_400;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Delegation configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DelegationConfig {
+    /// Registers validators and sets the oracle and periods
+    pub owner: String,
+    /// Pushes epoch rewards
+    pub oracle: String,
+    /// `NATIVE_ASSET` or a CRC-20 address
+    pub asset: String,
+    /// Seconds between undelegating and release
+    pub unbonding_period: u64,
+    /// Seconds a delegator waits between redelegations
+    pub redelegation_cooldown: u64,
+    /// Last epoch rewards were distributed for
+    pub last_epoch: u64,
+}
+
+/// A registered validator
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Validator {
+    pub commission_bps: u64,
+    /// Inactive validators take no new stake
+    pub active: bool,
+    pub total_delegated: u64,
+    /// Rewards per unit of stake, scaled by 1e12
+    pub reward_per_stake: u128,
+    /// Commission not yet claimed
+    pub commission_owed: u64,
+}
+
+/// One delegator's stake with one validator
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Delegation {
+    pub amount: u64,
+    /// Rewards settled but not yet claimed
+    pub pending_rewards: u64,
+    /// `amount * reward_per_stake` at the last settlement
+    pub reward_debt: u128,
+}
+
+/// Stake on its way out
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Unbond {
+    pub delegator: String,
+    pub validator: String,
+    pub amount: u64,
+    pub ready_at: u64,
+    pub claimed: bool,
+}
+
+/// Rewards earned by one validator in an epoch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorReward {
+    pub validator: String,
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    asset: String,
+    oracle: String,
+    unbonding_period: u64,
+    redelegation_cooldown: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ParamsArgs {
+    oracle: String,
+    unbonding_period: u64,
+    redelegation_cooldown: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegisterArgs {
+    validator: String,
+    commission_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetActiveArgs {
+    validator: String,
+    active: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommissionArgs {
+    commission_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StakeArgs {
+    validator: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RedelegateArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DistributeArgs {
+    epoch: u64,
+    rewards: Vec<ValidatorReward>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ValidatorArgs {
+    validator: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DelegationArgs {
+    delegator: String,
+    validator: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UnbondIdArgs {
+    unbond_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexArgs {
+    index: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<DelegationConfig> {
+    storage()
+        .get::<DelegationConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_config(config: &DelegationConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<DelegationConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn validators() -> Map<String, Validator> {
+    Map::new(VALIDATORS_PREFIX)
+}
+
+fn validator_at() -> Map<u64, String> {
+    Map::new(VALIDATOR_AT_PREFIX)
+}
+
+fn delegations() -> Map<(String, String), Delegation> {
+    Map::new(DELEGATIONS_PREFIX)
+}
+
+fn unbonds() -> Map<u64, Unbond> {
+    Map::new(UNBONDS_PREFIX)
+}
+
+fn redelegated_at() -> Map<String, u64> {
+    Map::new(REDELEGATED_AT_PREFIX)
+}
+
+fn load_validator(validator: &str) -> ContractResult<Validator> {
+    validators()
+        .get(&validator.to_string())?
+        .ok_or_else(|| ContractError::InvalidArgument(format!("Unknown validator {}", validator)))
+}
+
+fn load_delegation(delegator: &str, validator: &str) -> ContractResult<Delegation> {
+    Ok(delegations()
+        .get(&(delegator.to_string(), validator.to_string()))?
+        .unwrap_or_default())
+}
+
+fn save_delegation(
+    delegator: &str,
+    validator: &str,
+    delegation: &Delegation,
+) -> ContractResult<()> {
+    let key = (delegator.to_string(), validator.to_string());
+    if delegation.amount == 0 && delegation.pending_rewards == 0 {
+        return delegations().remove(&key);
+    }
+    delegations().set(&key, delegation)
+}
+
+fn validate_periods(unbonding_period: u64, redelegation_cooldown: u64) -> ContractResult<()> {
+    validation::validate_range(unbonding_period, 0, MAX_PERIOD)?;
+    validation::validate_range(redelegation_cooldown, 0, MAX_PERIOD)
+}
+
+/// Move rewards earned since the last settlement into `pending_rewards`.
+///
+/// Must run before `amount` changes.
+fn settle(validator: &Validator, delegation: &mut Delegation) -> ContractResult<()> {
+    let accrued = delegation.amount as u128 * validator.reward_per_stake / REWARD_PRECISION;
+    let earned = accrued.saturating_sub(delegation.reward_debt);
+    let earned = u64::try_from(earned).map_err(|_| ContractError::Overflow)?;
+    delegation.pending_rewards = safe_math::add(delegation.pending_rewards, earned)?;
+    delegation.reward_debt = accrued;
+    Ok(())
+}
+
+/// Reset the reward debt after `amount` changed.
+fn checkpoint(validator: &Validator, delegation: &mut Delegation) {
+    delegation.reward_debt =
+        delegation.amount as u128 * validator.reward_per_stake / REWARD_PRECISION;
+}
+
+/// Rewards a delegation could claim right now.
+pub fn claimable_rewards(validator: &Validator, delegation: &Delegation) -> u64 {
+    let accrued = delegation.amount as u128 * validator.reward_per_stake / REWARD_PRECISION;
+    let earned = u64::try_from(accrued.saturating_sub(delegation.reward_debt)).unwrap_or(u64::MAX);
+    delegation.pending_rewards.saturating_add(earned)
+}
+
+/// Native payments must attach exactly `amount`; token payments attach nothing.
+fn check_attached_value(config: &DelegationConfig, amount: u64) -> ContractResult<()> {
+    let expected = if config.asset == NATIVE_ASSET {
+        amount
+    } else {
+        0
+    };
+    if context().value() != expected {
+        return Err(ContractError::InvalidArgument(format!(
+            "Attached value must be {}",
+            expected
+        )));
+    }
+    Ok(())
+}
+
+/// Pull `amount` of a CRC-20 asset from the caller; native value is already here.
+fn receive_asset(config: &DelegationConfig, amount: u64) -> ContractResult<()> {
+    if config.asset == NATIVE_ASSET {
+        return Ok(());
+    }
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: ctx.sender().to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(&config.asset, "transfer_from", &call)
+        .map(|_| ())
+}
+
+fn send_asset(config: &DelegationConfig, to: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    if config.asset == NATIVE_ASSET {
+        return ctx.transfer_tokens(to, amount);
+    }
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    ctx.call_contract(&config.asset, "transfer", &call)
+        .map(|_| ())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    if args.asset != NATIVE_ASSET {
+        validation::validate_address(&args.asset)?;
+        if !ctx.is_contract(&args.asset) {
+            return Err(ContractError::InvalidArgument(
+                "Asset must be native or a contract".to_string(),
+            ));
+        }
+    }
+    validation::validate_address(&args.oracle)?;
+    validate_periods(args.unbonding_period, args.redelegation_cooldown)?;
+    let config = DelegationConfig {
+        owner: ctx.sender().to_string(),
+        oracle: args.oracle,
+        asset: args.asset,
+        unbonding_period: args.unbonding_period,
+        redelegation_cooldown: args.redelegation_cooldown,
+        last_epoch: 0,
+    };
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("DelegationInitialized",
+        owner: config.owner,
+        oracle: config.oracle,
+        asset: config.asset
+    );
+    Ok(())
+}
+
+fn execute_set_params() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: ParamsArgs = read_args()?;
+    validation::validate_address(&args.oracle)?;
+    validate_periods(args.unbonding_period, args.redelegation_cooldown)?;
+    let previous_oracle = core::mem::replace(&mut config.oracle, args.oracle);
+    let previous_unbonding =
+        core::mem::replace(&mut config.unbonding_period, args.unbonding_period);
+    let previous_cooldown = core::mem::replace(
+        &mut config.redelegation_cooldown,
+        args.redelegation_cooldown,
+    );
+    save_config(&config)?;
+
+    event!("ParamsUpdated",
+        oracle: config.oracle,
+        unbonding_period: config.unbonding_period,
+        redelegation_cooldown: config.redelegation_cooldown
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "oracle",
+        previous_oracle,
+        &config.oracle,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "unbonding_period",
+        previous_unbonding,
+        config.unbonding_period,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "redelegation_cooldown",
+        previous_cooldown,
+        config.redelegation_cooldown,
+    )?;
+    Ok(())
+}
+
+fn execute_register_validator() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: RegisterArgs = read_args()?;
+    validation::validate_address(&args.validator)?;
+    validation::validate_range(args.commission_bps, 0, BPS_DENOMINATOR)?;
+    if validators().contains_key(&args.validator)? {
+        return Err(ContractError::InvalidArgument(
+            "Validator already registered".to_string(),
+        ));
+    }
+    validators().set(
+        &args.validator,
+        &Validator {
+            commission_bps: args.commission_bps,
+            active: true,
+            total_delegated: 0,
+            reward_per_stake: 0,
+            commission_owed: 0,
+        },
+    )?;
+    let mut store = storage();
+    let count = store.get::<u64>(VALIDATOR_COUNT_KEY)?.unwrap_or(0);
+    validator_at().set(&count, &args.validator)?;
+    store.set(VALIDATOR_COUNT_KEY, &safe_math::add(count, 1)?)?;
+
+    event!("ValidatorRegistered",
+        validator: args.validator,
+        commission_bps: args.commission_bps
+    );
+    admin_log::record(
+        "register_validator",
+        &args.validator,
+        "",
+        args.commission_bps,
+    )?;
+    Ok(())
+}
+
+fn execute_set_validator_active() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: SetActiveArgs = read_args()?;
+    let mut validator = load_validator(&args.validator)?;
+    let previous = core::mem::replace(&mut validator.active, args.active);
+    validators().set(&args.validator, &validator)?;
+
+    event!("ValidatorStatusChanged", validator: args.validator, active: args.active);
+    admin_log::record(
+        "set_validator_active",
+        &args.validator,
+        previous,
+        args.active,
+    )?;
+    Ok(())
+}
+
+fn execute_set_commission() -> ContractResult<()> {
+    let address = context().sender().to_string();
+    let args: CommissionArgs = read_args()?;
+    validation::validate_range(args.commission_bps, 0, BPS_DENOMINATOR)?;
+    let mut validator = load_validator(&address)?;
+    let previous = validator.commission_bps;
+    validator.commission_bps = args.commission_bps;
+    validators().set(&address, &validator)?;
+
+    event!("CommissionChanged",
+        validator: address,
+        previous: previous,
+        commission_bps: args.commission_bps
+    );
+    Ok(())
+}
+
+fn execute_delegate() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let delegator = context().sender().to_string();
+    let config = load_config()?;
+    let args: StakeArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    check_attached_value(&config, args.amount)?;
+    let mut validator = load_validator(&args.validator)?;
+    if !validator.active {
+        return Err(ContractError::InvalidArgument(
+            "Validator is not accepting delegations".to_string(),
+        ));
+    }
+    let mut delegation = load_delegation(&delegator, &args.validator)?;
+    settle(&validator, &mut delegation)?;
+    delegation.amount = safe_math::add(delegation.amount, args.amount)?;
+    checkpoint(&validator, &mut delegation);
+    validator.total_delegated = safe_math::add(validator.total_delegated, args.amount)?;
+    validators().set(&args.validator, &validator)?;
+    save_delegation(&delegator, &args.validator, &delegation)?;
+
+    receive_asset(&config, args.amount)?;
+
+    event!("Delegated",
+        delegator: delegator,
+        validator: args.validator,
+        amount: args.amount
+    );
+    Ok(())
+}
+
+/// Take `amount` off a delegation, settling its rewards first.
+fn remove_stake(delegator: &str, validator_address: &str, amount: u64) -> ContractResult<()> {
+    validation::validate_positive_amount(amount)?;
+    let mut validator = load_validator(validator_address)?;
+    let mut delegation = load_delegation(delegator, validator_address)?;
+    if delegation.amount < amount {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: delegation.amount,
+        });
+    }
+    settle(&validator, &mut delegation)?;
+    delegation.amount -= amount;
+    checkpoint(&validator, &mut delegation);
+    validator.total_delegated -= amount;
+    validators().set(&validator_address.to_string(), &validator)?;
+    save_delegation(delegator, validator_address, &delegation)
+}
+
+fn execute_undelegate() -> ContractResult<u64> {
+    let ctx = context();
+    let delegator = ctx.sender().to_string();
+    let config = load_config()?;
+    let args: StakeArgs = read_args()?;
+    remove_stake(&delegator, &args.validator, args.amount)?;
+
+    let mut store = storage();
+    let unbond_id = store.get::<u64>(NEXT_UNBOND_ID_KEY)?.unwrap_or(0);
+    store.set(NEXT_UNBOND_ID_KEY, &safe_math::add(unbond_id, 1)?)?;
+    let ready_at = safe_math::add(ctx.block_timestamp(), config.unbonding_period)?;
+    unbonds().set(
+        &unbond_id,
+        &Unbond {
+            delegator: delegator.clone(),
+            validator: args.validator.clone(),
+            amount: args.amount,
+            ready_at,
+            claimed: false,
+        },
+    )?;
+
+    event!("Undelegated",
+        unbond_id: unbond_id,
+        delegator: delegator,
+        validator: args.validator,
+        amount: args.amount,
+        ready_at: ready_at
+    );
+    Ok(unbond_id)
+}
+
+fn execute_claim_unbonded() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let config = load_config()?;
+    let args: UnbondIdArgs = read_args()?;
+    let mut unbond = unbonds()
+        .get(&args.unbond_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown unbond".to_string()))?;
+    if ctx.sender() != unbond.delegator {
+        return Err(ContractError::Unauthorized);
+    }
+    if unbond.claimed {
+        return Err(ContractError::InvalidArgument(
+            "Unbond already claimed".to_string(),
+        ));
+    }
+    if ctx.block_timestamp() < unbond.ready_at {
+        return Err(ContractError::InvalidArgument(format!(
+            "Unbonding until {}",
+            unbond.ready_at
+        )));
+    }
+    unbond.claimed = true;
+    unbonds().set(&args.unbond_id, &unbond)?;
+
+    send_asset(&config, &unbond.delegator, unbond.amount)?;
+
+    event!("UnbondClaimed",
+        unbond_id: args.unbond_id,
+        delegator: unbond.delegator,
+        amount: unbond.amount
+    );
+    Ok(unbond.amount)
+}
+
+fn execute_redelegate() -> ContractResult<()> {
+    let ctx = context();
+    let delegator = ctx.sender().to_string();
+    let config = load_config()?;
+    let args: RedelegateArgs = read_args()?;
+    if args.from == args.to {
+        return Err(ContractError::InvalidArgument(
+            "Cannot redelegate to the same validator".to_string(),
+        ));
+    }
+    let now = ctx.block_timestamp();
+    if let Some(last) = redelegated_at().get(&delegator)? {
+        let available_at = safe_math::add(last, config.redelegation_cooldown)?;
+        if now < available_at {
+            return Err(ContractError::InvalidArgument(format!(
+                "Redelegation cooling down until {}",
+                available_at
+            )));
+        }
+    }
+    let mut target = load_validator(&args.to)?;
+    if !target.active {
+        return Err(ContractError::InvalidArgument(
+            "Validator is not accepting delegations".to_string(),
+        ));
+    }
+    remove_stake(&delegator, &args.from, args.amount)?;
+    let mut delegation = load_delegation(&delegator, &args.to)?;
+    settle(&target, &mut delegation)?;
+    delegation.amount = safe_math::add(delegation.amount, args.amount)?;
+    checkpoint(&target, &mut delegation);
+    target.total_delegated = safe_math::add(target.total_delegated, args.amount)?;
+    validators().set(&args.to, &target)?;
+    save_delegation(&delegator, &args.to, &delegation)?;
+    redelegated_at().set(&delegator, &now)?;
+
+    event!("Redelegated",
+        delegator: delegator,
+        from: args.from,
+        to: args.to,
+        amount: args.amount
+    );
+    Ok(())
+}
+
+fn execute_distribute_rewards() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_config()?;
+    if context().sender() != config.oracle {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: DistributeArgs = read_args()?;
+    if args.epoch <= config.last_epoch {
+        return Err(ContractError::InvalidArgument(format!(
+            "Epoch must be after {}",
+            config.last_epoch
+        )));
+    }
+    if args.rewards.is_empty() || args.rewards.len() > MAX_REWARDS_PER_EPOCH {
+        return Err(ContractError::InvalidArgument(format!(
+            "Rewards must cover 1-{} validators",
+            MAX_REWARDS_PER_EPOCH
+        )));
+    }
+    let mut total = 0u64;
+    let mut updated = Vec::new();
+    for reward in &args.rewards {
+        validation::validate_positive_amount(reward.amount)?;
+        if updated
+            .iter()
+            .any(|(address, _)| address == &reward.validator)
+        {
+            return Err(ContractError::InvalidArgument(format!(
+                "Validator {} listed twice",
+                reward.validator
+            )));
+        }
+        total = safe_math::add(total, reward.amount)?;
+        let mut validator = load_validator(&reward.validator)?;
+        let commission = if validator.total_delegated == 0 {
+            // Nobody to share with; the validator keeps it all.
+            reward.amount
+        } else {
+            (reward.amount as u128 * validator.commission_bps as u128 / BPS_DENOMINATOR as u128)
+                as u64
+        };
+        let shared = reward.amount - commission;
+        validator.commission_owed = safe_math::add(validator.commission_owed, commission)?;
+        if shared > 0 {
+            validator.reward_per_stake +=
+                shared as u128 * REWARD_PRECISION / validator.total_delegated as u128;
+        }
+        updated.push((reward.validator.clone(), validator));
+    }
+    check_attached_value(&config, total)?;
+    for (address, validator) in &updated {
+        validators().set(address, validator)?;
+    }
+    config.last_epoch = args.epoch;
+    save_config(&config)?;
+
+    receive_asset(&config, total)?;
+
+    event!("RewardsDistributed",
+        epoch: args.epoch,
+        validators: updated.len() as u64,
+        total: total
+    );
+    admin_log::record("distribute_rewards", &args.epoch.to_string(), "", total)?;
+    Ok(total)
+}
+
+fn execute_claim_rewards() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let delegator = context().sender().to_string();
+    let config = load_config()?;
+    let args: ValidatorArgs = read_args()?;
+    let validator = load_validator(&args.validator)?;
+    let mut delegation = load_delegation(&delegator, &args.validator)?;
+    settle(&validator, &mut delegation)?;
+    let amount = delegation.pending_rewards;
+    validation::validate_positive_amount(amount)?;
+    delegation.pending_rewards = 0;
+    save_delegation(&delegator, &args.validator, &delegation)?;
+
+    send_asset(&config, &delegator, amount)?;
+
+    event!("RewardsClaimed",
+        delegator: delegator,
+        validator: args.validator,
+        amount: amount
+    );
+    Ok(amount)
+}
+
+fn execute_claim_commission() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let address = context().sender().to_string();
+    let config = load_config()?;
+    let mut validator = load_validator(&address)?;
+    let amount = validator.commission_owed;
+    validation::validate_positive_amount(amount)?;
+    validator.commission_owed = 0;
+    validators().set(&address, &validator)?;
+
+    send_asset(&config, &address, amount)?;
+
+    event!("CommissionClaimed", validator: address, amount: amount);
+    Ok(amount)
+}
+
+/// Initialize the manager; the caller becomes the owner
+///
+/// # Arguments
+/// * `asset` - `"native"` or the CRC-20 being delegated
+/// * `oracle` - Account allowed to push epoch rewards
+/// * `unbonding_period` - Seconds before undelegated stake is released (max 60 days)
+/// * `redelegation_cooldown` - Seconds between a delegator's redelegations (max 60 days)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Change the oracle and periods (owner only)
+///
+/// # Arguments
+/// * `oracle` - Account allowed to push epoch rewards
+/// * `unbonding_period` - Applies to new undelegations
+/// * `redelegation_cooldown` - Applies from the next redelegation
+#[unsafe(no_mangle)]
+pub extern "C" fn set_params() {
+    if let Err(err) = execute_set_params() {
+        log!("set_params failed: {}", err);
+    }
+}
+
+/// Register a validator (owner only)
+///
+/// # Arguments
+/// * `validator` - Validator address, which also receives commission
+/// * `commission_bps` - Share of rewards the validator keeps
+#[unsafe(no_mangle)]
+pub extern "C" fn register_validator() {
+    if let Err(err) = execute_register_validator() {
+        log!("register_validator failed: {}", err);
+    }
+}
+
+/// Open or close a validator to new stake (owner only)
+///
+/// Existing delegations keep earning and can always leave.
+///
+/// # Arguments
+/// * `validator` - Validator address
+/// * `active` - Whether it accepts delegations
+#[unsafe(no_mangle)]
+pub extern "C" fn set_validator_active() {
+    if let Err(err) = execute_set_validator_active() {
+        log!("set_validator_active failed: {}", err);
+    }
+}
+
+/// Change the caller's commission, from the next distribution (validators only)
+///
+/// # Arguments
+/// * `commission_bps` - New commission
+#[unsafe(no_mangle)]
+pub extern "C" fn set_commission() {
+    if let Err(err) = execute_set_commission() {
+        log!("set_commission failed: {}", err);
+    }
+}
+
+/// Delegate stake to a validator
+///
+/// # Arguments
+/// * `validator` - Active validator
+/// * `amount` - Stake, attached as value or approved
+#[unsafe(no_mangle)]
+pub extern "C" fn delegate() {
+    if let Err(err) = execute_delegate() {
+        log!("delegate failed: {}", err);
+    }
+}
+
+/// Start unbonding stake from a validator
+///
+/// # Arguments
+/// * `validator` - Validator address
+/// * `amount` - Stake to withdraw
+///
+/// # Returns
+/// Unbond ID (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn undelegate() {
+    if let Err(err) = execute_undelegate().and_then(|id| try_respond(&id)) {
+        log!("undelegate failed: {}", err);
+    }
+}
+
+/// Collect unbonded stake (delegator only)
+///
+/// # Arguments
+/// * `unbond_id` - Unbond ID
+///
+/// # Returns
+/// Amount released (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_unbonded() {
+    if let Err(err) = execute_claim_unbonded().and_then(|amount| try_respond(&amount)) {
+        log!("claim_unbonded failed: {}", err);
+    }
+}
+
+/// Move stake to another validator without unbonding
+///
+/// # Arguments
+/// * `from` - Current validator
+/// * `to` - Active validator receiving the stake
+/// * `amount` - Stake to move
+#[unsafe(no_mangle)]
+pub extern "C" fn redelegate() {
+    if let Err(err) = execute_redelegate() {
+        log!("redelegate failed: {}", err);
+    }
+}
+
+/// Distribute an epoch's rewards (oracle only)
+///
+/// The total is attached as value or approved.
+///
+/// # Arguments
+/// * `epoch` - Epoch number, greater than the last one
+/// * `rewards` - Reward per validator (1-50 entries)
+///
+/// # Returns
+/// Total distributed (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn distribute_rewards() {
+    if let Err(err) = execute_distribute_rewards().and_then(|total| try_respond(&total)) {
+        log!("distribute_rewards failed: {}", err);
+    }
+}
+
+/// Claim rewards earned with a validator
+///
+/// # Arguments
+/// * `validator` - Validator address
+///
+/// # Returns
+/// Amount paid (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_rewards() {
+    if let Err(err) = execute_claim_rewards().and_then(|amount| try_respond(&amount)) {
+        log!("claim_rewards failed: {}", err);
+    }
+}
+
+/// Claim the caller's commission (validators only)
+///
+/// # Returns
+/// Amount paid (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_commission() {
+    if let Err(err) = execute_claim_commission().and_then(|amount| try_respond(&amount)) {
+        log!("claim_commission failed: {}", err);
+    }
+}
+
+/// Get a validator
+///
+/// # Arguments
+/// * `validator` - Validator address
+///
+/// # Returns
+/// `Validator`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_validator() {
+    let result = read_args::<ValidatorArgs>()
+        .and_then(|args| load_validator(&args.validator))
+        .and_then(|validator| try_respond(&validator));
+    if let Err(err) = result {
+        log!("get_validator failed: {}", err);
+    }
+}
+
+/// Get a registered validator by position
+///
+/// # Arguments
+/// * `index` - 0-based registration order
+///
+/// # Returns
+/// Validator address (String)
+#[unsafe(no_mangle)]
+pub extern "C" fn validator_by_index() {
+    let result = read_args::<IndexArgs>()
+        .and_then(|args| {
+            validator_at()
+                .get(&args.index)?
+                .ok_or_else(|| ContractError::InvalidArgument("Index out of range".to_string()))
+        })
+        .and_then(|address| try_respond(&address));
+    if let Err(err) = result {
+        log!("validator_by_index failed: {}", err);
+    }
+}
+
+/// Get the number of registered validators
+///
+/// # Returns
+/// Count (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn validator_count() {
+    let result = storage()
+        .get::<u64>(VALIDATOR_COUNT_KEY)
+        .and_then(|count| try_respond(&count.unwrap_or(0)));
+    if let Err(err) = result {
+        log!("validator_count failed: {}", err);
+    }
+}
+
+/// Get a delegation, with rewards settled up to now
+///
+/// # Arguments
+/// * `delegator` - Delegator address
+/// * `validator` - Validator address
+///
+/// # Returns
+/// `Delegation`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_delegation() {
+    let result = read_args::<DelegationArgs>()
+        .and_then(|args| {
+            let validator = load_validator(&args.validator)?;
+            let mut delegation = load_delegation(&args.delegator, &args.validator)?;
+            delegation.pending_rewards = claimable_rewards(&validator, &delegation);
+            checkpoint(&validator, &mut delegation);
+            Ok(delegation)
+        })
+        .and_then(|delegation| try_respond(&delegation));
+    if let Err(err) = result {
+        log!("get_delegation failed: {}", err);
+    }
+}
+
+/// Get an unbond
+///
+/// # Arguments
+/// * `unbond_id` - Unbond ID
+///
+/// # Returns
+/// `Unbond`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_unbond() {
+    let result = read_args::<UnbondIdArgs>()
+        .and_then(|args| {
+            unbonds()
+                .get(&args.unbond_id)?
+                .ok_or_else(|| ContractError::InvalidArgument("Unknown unbond".to_string()))
+        })
+        .and_then(|unbond| try_respond(&unbond));
+    if let Err(err) = result {
+        log!("get_unbond failed: {}", err);
+    }
+}
+
+/// Get the manager configuration
+///
+/// # Returns
+/// `DelegationConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ORACLE: &str = "0x0000000000000000000000000000000000000a02";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const VAL_A: &str = "0x0000000000000000000000000000000000000d04";
+    const VAL_B: &str = "0x0000000000000000000000000000000000000e05";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const NOW: u64 = 1_736_000_000;
+    const DAY: u64 = 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// Native staking; validator A takes 10% and B takes nothing
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                asset: NATIVE_ASSET.to_string(),
+                oracle: ADDR_ORACLE.to_string(),
+                unbonding_period: 14 * DAY,
+                redelegation_cooldown: 7 * DAY,
+            }),
+        );
+        for (validator, commission_bps) in [(VAL_A, 1_000), (VAL_B, 0)] {
+            call::<()>(
+                ADDR_OWNER,
+                register_validator,
+                encode(&RegisterArgs {
+                    validator: validator.to_string(),
+                    commission_bps,
+                }),
+            );
+        }
+    }
+
+    fn with_value<R: DeserializeOwned>(
+        value: u64,
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_value(value);
+        let result = call(sender, entrypoint, data);
+        mock::set_value(0);
+        result
+    }
+
+    fn stake(delegator: &str, validator: &str, amount: u64) {
+        with_value::<()>(
+            amount,
+            delegator,
+            delegate,
+            encode(&StakeArgs {
+                validator: validator.to_string(),
+                amount,
+            }),
+        );
+    }
+
+    fn distribute(epoch: u64, rewards: &[(&str, u64)]) -> Option<u64> {
+        let rewards: Vec<ValidatorReward> = rewards
+            .iter()
+            .map(|(validator, amount)| ValidatorReward {
+                validator: validator.to_string(),
+                amount: *amount,
+            })
+            .collect();
+        let total = rewards.iter().map(|r| r.amount).sum();
+        with_value(
+            total,
+            ADDR_ORACLE,
+            distribute_rewards,
+            encode(&DistributeArgs { epoch, rewards }),
+        )
+    }
+
+    fn claimable(delegator: &str, validator: &str) -> u64 {
+        claimable_rewards(
+            &load_validator(validator).unwrap(),
+            &load_delegation(delegator, validator).unwrap(),
+        )
+    }
+
+    fn validator_args(validator: &str) -> Vec<u8> {
+        encode(&ValidatorArgs {
+            validator: validator.to_string(),
+        })
+    }
+
+    #[test]
+    fn delegations_track_validator_totals() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        stake(ADDR_ALICE, VAL_A, 300);
+        stake(ADDR_BOB, VAL_A, 100);
+        stake(ADDR_BOB, VAL_B, 50);
+        with_value::<()>(
+            10,
+            ADDR_BOB,
+            delegate,
+            encode(&StakeArgs {
+                validator: VAL_B.to_string(),
+                amount: 20,
+            }),
+        );
+        assert_eq!(load_validator(VAL_A).unwrap().total_delegated, 400);
+        assert_eq!(load_validator(VAL_B).unwrap().total_delegated, 50);
+
+        call::<()>(
+            ADDR_OWNER,
+            set_validator_active,
+            encode(&SetActiveArgs {
+                validator: VAL_B.to_string(),
+                active: false,
+            }),
+        );
+        stake(ADDR_ALICE, VAL_B, 10);
+        assert_eq!(load_validator(VAL_B).unwrap().total_delegated, 50);
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, validator_count, Vec::new()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn rewards_beyond_u64_fail_instead_of_wrapping() {
+        let validator = Validator {
+            commission_bps: 0,
+            active: true,
+            total_delegated: 2,
+            reward_per_stake: (u64::MAX as u128 + 2) * REWARD_PRECISION,
+            commission_owed: 0,
+        };
+        let mut delegation = Delegation {
+            amount: 1,
+            ..Delegation::default()
+        };
+        assert_eq!(claimable_rewards(&validator, &delegation), u64::MAX);
+        assert!(matches!(
+            settle(&validator, &mut delegation),
+            Err(ContractError::Overflow)
+        ));
+        assert_eq!(
+            delegation,
+            Delegation {
+                amount: 1,
+                ..Delegation::default()
+            }
+        );
+    }
+
+    #[test]
+    fn epoch_rewards_split_commission_and_stake() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        stake(ADDR_ALICE, VAL_A, 300);
+        stake(ADDR_BOB, VAL_A, 100);
+        assert_eq!(distribute(1, &[(VAL_A, 1_000)]), Some(1_000));
+        assert_eq!(load_validator(VAL_A).unwrap().commission_owed, 100);
+        assert_eq!(claimable(ADDR_ALICE, VAL_A), 675);
+        assert_eq!(claimable(ADDR_BOB, VAL_A), 225);
+
+        // Bob joins VAL_B alone after its first epoch.
+        assert_eq!(distribute(2, &[(VAL_B, 40)]), Some(40));
+        assert_eq!(load_validator(VAL_B).unwrap().commission_owed, 40);
+        stake(ADDR_BOB, VAL_B, 10);
+        distribute(3, &[(VAL_B, 40)]);
+        assert_eq!(claimable(ADDR_BOB, VAL_B), 40);
+
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, claim_rewards, validator_args(VAL_A)),
+            Some(675)
+        );
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, claim_rewards, validator_args(VAL_A)),
+            None
+        );
+        assert_eq!(call::<u64>(VAL_A, claim_commission, Vec::new()), Some(100));
+    }
+
+    #[test]
+    fn only_the_oracle_distributes_new_epochs() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        stake(ADDR_ALICE, VAL_A, 100);
+        let rewards = encode(&DistributeArgs {
+            epoch: 1,
+            rewards: vec![ValidatorReward {
+                validator: VAL_A.to_string(),
+                amount: 100,
+            }],
+        });
+        assert_eq!(
+            with_value::<u64>(100, ADDR_OWNER, distribute_rewards, rewards),
+            None
+        );
+        assert_eq!(distribute(1, &[(VAL_A, 100)]), Some(100));
+        assert_eq!(distribute(1, &[(VAL_A, 100)]), None);
+        assert_eq!(distribute(2, &[(VAL_A, 100), (VAL_A, 100)]), None);
+        assert_eq!(distribute(2, &[(ADDR_BOB, 100)]), None);
+        assert_eq!(
+            with_value::<u64>(
+                99,
+                ADDR_ORACLE,
+                distribute_rewards,
+                encode(&DistributeArgs {
+                    epoch: 2,
+                    rewards: vec![ValidatorReward {
+                        validator: VAL_A.to_string(),
+                        amount: 100,
+                    }],
+                }),
+            ),
+            None
+        );
+        assert_eq!(load_config().unwrap().last_epoch, 1);
+        assert_eq!(claimable(ADDR_ALICE, VAL_A), 90);
+        // Two registrations in setup, then the one accepted epoch.
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+    }
+
+    #[test]
+    fn undelegated_stake_unbonds_before_release() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        stake(ADDR_ALICE, VAL_A, 100);
+        distribute(1, &[(VAL_A, 100)]);
+        let undelegate_args = |amount| {
+            encode(&StakeArgs {
+                validator: VAL_A.to_string(),
+                amount,
+            })
+        };
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, undelegate, undelegate_args(101)),
+            None
+        );
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, undelegate, undelegate_args(100)),
+            Some(0)
+        );
+        assert_eq!(load_validator(VAL_A).unwrap().total_delegated, 0);
+        // Rewards earned before leaving are kept.
+        assert_eq!(claimable(ADDR_ALICE, VAL_A), 90);
+
+        let unbond = encode(&UnbondIdArgs { unbond_id: 0 });
+        mock::set_block_timestamp(NOW + 14 * DAY - 1);
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, claim_unbonded, unbond.clone()),
+            None
+        );
+        mock::set_block_timestamp(NOW + 14 * DAY);
+        assert_eq!(call::<u64>(ADDR_BOB, claim_unbonded, unbond.clone()), None);
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, claim_unbonded, unbond.clone()),
+            Some(100)
+        );
+        assert_eq!(call::<u64>(ADDR_ALICE, claim_unbonded, unbond), None);
+    }
+
+    #[test]
+    fn redelegation_moves_stake_with_a_cooldown() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        stake(ADDR_ALICE, VAL_A, 100);
+        distribute(1, &[(VAL_A, 100)]);
+        let move_stake = |from: &str, to: &str, amount| {
+            call::<()>(
+                ADDR_ALICE,
+                redelegate,
+                encode(&RedelegateArgs {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    amount,
+                }),
+            )
+        };
+        move_stake(VAL_A, VAL_B, 60);
+        assert_eq!(load_validator(VAL_A).unwrap().total_delegated, 40);
+        assert_eq!(load_validator(VAL_B).unwrap().total_delegated, 60);
+        assert_eq!(claimable(ADDR_ALICE, VAL_A), 90);
+        assert_eq!(claimable(ADDR_ALICE, VAL_B), 0);
+
+        mock::set_block_timestamp(NOW + 7 * DAY - 1);
+        move_stake(VAL_B, VAL_A, 60);
+        assert_eq!(load_validator(VAL_B).unwrap().total_delegated, 60);
+        mock::set_block_timestamp(NOW + 7 * DAY);
+        move_stake(VAL_B, VAL_A, 60);
+        assert_eq!(load_validator(VAL_A).unwrap().total_delegated, 100);
+        assert_eq!(
+            load_delegation(ADDR_ALICE, VAL_B).unwrap(),
+            Delegation::default()
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "delegation-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
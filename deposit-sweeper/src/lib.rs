@@ -0,0 +1,1121 @@
+//! Exchange Deposit Sweeper
+//!
+//! An exchange gives each customer a deposit sub-account. Its address is
+//! derived from the customer id and this contract's address, so it can be
+//! shown to the customer before anything arrives. Deposits in the native
+//! coin or any CRC-20 are credited to the sub-account and held here until an
+//! operator sweeps them to the exchange's cold wallet in batches. Every sweep
+//! emits one `CustomerCredited` event per sub-account and asset, which the
+//! back office reconciles against its ledger.
+//!
+//! Sub-accounts are entries in this contract, not chain accounts: deposits
+//! go through `deposit` and `deposit_token`, naming the sub-account.
+//!
+//! ## Features
+//! - Deterministic per-customer sub-account addresses
+//! - Native coin and CRC-20 deposits, credited with what actually arrived
+//! - Batched sweeps with one transfer per asset per batch
+//! - Per-customer credit events for reconciliation
+//! - Admin and operator roles, with every admin and operator action in the
+//!   `AdminAction` audit log
+//! - Decommissioning: after an admin's notice runs out, deposits and new
+//!   sub-accounts stop while operators sweep what is left
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use address::{ADDRESS_BYTES, Address, AddressKey};
+use crc_clients::safe_transfer;
+use events::admin;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::access_control::{self, DEFAULT_ADMIN_ROLE};
+use silica_contract_sdk::bounded::BoundedString;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "sweeper_config";
+const SUB_ACCOUNTS_PREFIX: &str = "sub_accounts";
+const PENDING_PREFIX: &str = "pending";
+/// Prefixes the derivation input, so sub-account addresses cannot be
+/// derived for another purpose by accident
+const SUB_ACCOUNT_DOMAIN: &[u8] = b"deposit-sweeper/sub-account";
+/// Role allowed to open sub-accounts and sweep
+pub const OPERATOR_ROLE: &str = "operator";
+/// Asset name of native coin deposits
+pub const NATIVE_ASSET: &str = "native";
+/// Most pending balances one sweep moves
+pub const MAX_SWEEP_BATCH: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 8192;
+
+/// Exchange-side customer reference
+pub type CustomerId = BoundedString<64>;
+
+/// Sweeper configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SweeperConfig {
+    /// Receives every sweep
+    pub cold_wallet: String,
+    /// Sweeps made so far; the latest sweep's batch number
+    pub batches: u64,
+}
+
+/// A customer's deposit sub-account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SubAccount {
+    pub customer_id: String,
+    pub opened_at: u64,
+}
+
+/// Deposits to one sub-account in one asset since the last sweep
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingDeposit {
+    pub sub_account: String,
+    /// [`NATIVE_ASSET`] or a CRC-20 address
+    pub asset: String,
+    pub amount: u64,
+}
+
+/// Outcome of one sweep
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SweepReport {
+    pub batch: u64,
+    /// Pending balances moved, one `CustomerCredited` event each
+    pub credited: u64,
+    /// Pending balances left for later sweeps
+    pub remaining: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    cold_wallet: Address,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ColdWalletArgs {
+    cold_wallet: Address,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RoleArgs {
+    role: String,
+    account: Address,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CustomerArgs {
+    customer_id: CustomerId,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SubAccountArgs {
+    sub_account: Address,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepositTokenArgs {
+    token: Address,
+    sub_account: Address,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SweepArgs {
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PageArgs {
+    offset: u64,
+    limit: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<SweeperConfig> {
+    storage()
+        .get::<SweeperConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn sub_accounts() -> Map<String, SubAccount> {
+    Map::new(SUB_ACCOUNTS_PREFIX)
+}
+
+/// Balances waiting to be swept, keyed by (sub-account, asset)
+fn pending() -> IterableMap<(String, String), u64> {
+    IterableMap::new(PENDING_PREFIX)
+}
+
+fn load_sub_account(sub_account: &str) -> ContractResult<SubAccount> {
+    sub_accounts()
+        .get(&sub_account.to_string())?
+        .ok_or_else(|| {
+            ContractError::InvalidArgument(format!("Unknown sub-account {}", sub_account))
+        })
+}
+
+fn validate_cold_wallet(cold_wallet: &Address) -> ContractResult<()> {
+    if cold_wallet.is_zero() {
+        return Err(ContractError::InvalidArgument(
+            "Cold wallet cannot be the zero address".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Sub-account address of `customer_id` at `contract`
+///
+/// The first 20 bytes of `blake3(domain || contract || 0x00 || customer_id)`.
+/// The same customer gets a different sub-account at every sweeper.
+pub fn derive_sub_account(contract: &str, customer_id: &str) -> Address {
+    let mut seed =
+        Vec::with_capacity(SUB_ACCOUNT_DOMAIN.len() + contract.len() + 1 + customer_id.len());
+    seed.extend_from_slice(SUB_ACCOUNT_DOMAIN);
+    seed.extend_from_slice(contract.as_bytes());
+    seed.push(0);
+    seed.extend_from_slice(customer_id.as_bytes());
+    let hash = crypto::hash_blake3(&seed);
+    let mut bytes = [0u8; ADDRESS_BYTES];
+    bytes.copy_from_slice(&hash[..ADDRESS_BYTES]);
+    AddressKey::from_bytes(bytes).to_address()
+}
+
+/// Add `amount` of `asset` to the sub-account's pending balance.
+fn credit(sub_account: &str, account: &SubAccount, asset: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let key = (sub_account.to_string(), asset.to_string());
+    let mut pending = pending();
+    let balance = pending.get(&key)?.unwrap_or(0);
+    pending.insert(&key, &safe_math::add(balance, amount)?)?;
+
+    event!("DepositReceived",
+        sub_account: sub_account,
+        customer_id: account.customer_id,
+        asset: asset,
+        amount: amount,
+        depositor: ctx.sender()
+    );
+    Ok(())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let args: InitializeArgs = read_args()?;
+    validate_cold_wallet(&args.cold_wallet)?;
+    let ctx = context();
+    access_control::initialize(ctx.sender())?;
+    let config = SweeperConfig {
+        cold_wallet: args.cold_wallet.into(),
+        batches: 0,
+    };
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("SweeperInitialized",
+        admin: ctx.sender(),
+        cold_wallet: config.cold_wallet
+    );
+    Ok(())
+}
+
+fn execute_set_cold_wallet() -> ContractResult<()> {
+    let mut config = load_config()?;
+    access_control::require_role(DEFAULT_ADMIN_ROLE)?;
+    let args: ColdWalletArgs = read_args()?;
+    validate_cold_wallet(&args.cold_wallet)?;
+    let previous = core::mem::replace(&mut config.cold_wallet, args.cold_wallet.into());
+    storage().set(CONFIG_KEY, &config)?;
+    admin::record(
+        admin::SET_PARAMETER,
+        "cold_wallet",
+        &previous,
+        &config.cold_wallet,
+    )?;
+
+    event!("ColdWalletUpdated",
+        previous: previous,
+        cold_wallet: config.cold_wallet
+    );
+    Ok(())
+}
+
+fn execute_open_sub_account() -> ContractResult<String> {
+    load_config()?;
+    access_control::require_role(OPERATOR_ROLE)?;
+    decommission::when_active()?;
+    let args: CustomerArgs = read_args()?;
+    if args.customer_id.is_empty() {
+        return Err(ContractError::InvalidArgument(
+            "Customer id cannot be empty".to_string(),
+        ));
+    }
+    let ctx = context();
+    let sub_account = derive_sub_account(ctx.contract_address(), &args.customer_id).to_string();
+    let mut accounts = sub_accounts();
+    if accounts.contains_key(&sub_account)? {
+        return Err(ContractError::InvalidArgument(format!(
+            "Sub-account {} already open",
+            sub_account
+        )));
+    }
+    let account = SubAccount {
+        customer_id: args.customer_id.into_inner(),
+        opened_at: ctx.block_height(),
+    };
+    accounts.set(&sub_account, &account)?;
+    admin::record("open_sub_account", &sub_account, "", &account.customer_id)?;
+
+    event!("SubAccountOpened",
+        customer_id: account.customer_id,
+        sub_account: sub_account
+    );
+    Ok(sub_account)
+}
+
+fn execute_deposit() -> ContractResult<()> {
+    decommission::when_active()?;
+    let ctx = context();
+    let args: SubAccountArgs = read_args()?;
+    validation::validate_positive_amount(ctx.value())?;
+    let account = load_sub_account(&args.sub_account)?;
+    credit(&args.sub_account, &account, NATIVE_ASSET, ctx.value())
+}
+
+fn execute_deposit_token() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let args: DepositTokenArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    if ctx.value() != 0 {
+        return Err(ContractError::InvalidArgument(
+            "Token deposits take no attached value".to_string(),
+        ));
+    }
+    let account = load_sub_account(&args.sub_account)?;
+    let received = safe_transfer::transfer_in(&args.token, ctx.sender(), args.amount)?;
+    validation::validate_positive_amount(received)?;
+    credit(&args.sub_account, &account, &args.token, received)?;
+    Ok(received)
+}
+
+fn execute_sweep() -> ContractResult<SweepReport> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_config()?;
+    access_control::require_role(OPERATOR_ROLE)?;
+    let args: SweepArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_SWEEP_BATCH {
+        return Err(ContractError::InvalidArgument(format!(
+            "Sweeps move 1-{} balances",
+            MAX_SWEEP_BATCH
+        )));
+    }
+    let mut pending = pending();
+    let entries = pending.entries_page(0, args.limit)?;
+    if entries.is_empty() {
+        return Err(ContractError::InvalidArgument(
+            "Nothing to sweep".to_string(),
+        ));
+    }
+    config.batches = safe_math::add(config.batches, 1)?;
+    storage().set(CONFIG_KEY, &config)?;
+    admin::record("sweep", "batches", config.batches - 1, config.batches)?;
+
+    // Settle the bookkeeping first, then move one total per asset.
+    let mut totals: Vec<(String, u64)> = Vec::new();
+    for ((sub_account, asset), amount) in &entries {
+        pending.remove(&(sub_account.clone(), asset.clone()))?;
+        let account = load_sub_account(sub_account)?;
+        match totals.iter_mut().find(|(name, _)| name == asset) {
+            Some((_, total)) => *total = safe_math::add(*total, *amount)?,
+            None => totals.push((asset.clone(), *amount)),
+        }
+
+        event!("CustomerCredited",
+            batch: config.batches,
+            customer_id: account.customer_id,
+            sub_account: sub_account,
+            asset: asset,
+            amount: amount
+        );
+    }
+    for (asset, total) in &totals {
+        if asset == NATIVE_ASSET {
+            context().transfer_tokens(&config.cold_wallet, *total)?;
+        } else {
+            safe_transfer::safe_transfer(asset, &config.cold_wallet, *total)?;
+        }
+
+        event!("Swept",
+            batch: config.batches,
+            asset: asset,
+            amount: total,
+            cold_wallet: config.cold_wallet
+        );
+    }
+
+    Ok(SweepReport {
+        batch: config.batches,
+        credited: entries.len() as u64,
+        remaining: pending.len()?,
+    })
+}
+
+fn execute_grant_role() -> ContractResult<()> {
+    let args: RoleArgs = read_args()?;
+    if access_control::grant_role(&args.role, &args.account)? {
+        admin::record(admin::GRANT_ROLE, &args.account, "", &args.role)?;
+    }
+    Ok(())
+}
+
+fn execute_revoke_role() -> ContractResult<()> {
+    let args: RoleArgs = read_args()?;
+    if access_control::revoke_role(&args.role, &args.account)? {
+        admin::record(admin::REVOKE_ROLE, &args.account, &args.role, "")?;
+    }
+    Ok(())
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_config()?;
+    access_control::require_role(DEFAULT_ADMIN_ROLE)?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_config()?;
+    access_control::require_role(DEFAULT_ADMIN_ROLE)?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_config()?;
+    access_control::require_role(DEFAULT_ADMIN_ROLE)?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_pending_deposits() -> ContractResult<Vec<PendingDeposit>> {
+    let args: PageArgs = read_args()?;
+    let limit = args.limit.min(MAX_PAGE_SIZE);
+    Ok(pending()
+        .entries_page(args.offset, limit)?
+        .into_iter()
+        .map(|((sub_account, asset), amount)| PendingDeposit {
+            sub_account,
+            asset,
+            amount,
+        })
+        .collect())
+}
+
+/// Initialize the sweeper; the caller becomes its admin
+///
+/// # Arguments
+/// * `cold_wallet` - Receives every sweep
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Change the cold wallet (admin only)
+///
+/// # Arguments
+/// * `cold_wallet` - Receives later sweeps
+#[unsafe(no_mangle)]
+pub extern "C" fn set_cold_wallet() {
+    if let Err(err) = execute_set_cold_wallet() {
+        log!("set_cold_wallet failed: {}", err);
+    }
+}
+
+/// Grant a role (holders of the role's admin role only)
+///
+/// # Arguments
+/// * `role` - `"operator"`, or `"admin"` to add an admin
+/// * `account` - Account receiving the role
+#[unsafe(no_mangle)]
+pub extern "C" fn grant_role() {
+    if let Err(err) = execute_grant_role() {
+        log!("grant_role failed: {}", err);
+    }
+}
+
+/// Revoke a role (holders of the role's admin role only)
+///
+/// The last admin cannot be revoked.
+///
+/// # Arguments
+/// * `role` - Role to revoke
+/// * `account` - Account losing the role
+#[unsafe(no_mangle)]
+pub extern "C" fn revoke_role() {
+    if let Err(err) = execute_revoke_role() {
+        log!("revoke_role failed: {}", err);
+    }
+}
+
+/// Open a customer's deposit sub-account (operators only)
+///
+/// # Arguments
+/// * `customer_id` - Exchange-side customer reference, 1-64 bytes
+///
+/// # Returns
+/// The sub-account address
+#[unsafe(no_mangle)]
+pub extern "C" fn open_sub_account() {
+    if let Err(err) = execute_open_sub_account().and_then(|address| try_respond(&address)) {
+        log!("open_sub_account failed: {}", err);
+    }
+}
+
+/// Deposit the attached native coin to a sub-account
+///
+/// # Arguments
+/// * `sub_account` - Open sub-account to credit
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    if let Err(err) = execute_deposit() {
+        log!("deposit failed: {}", err);
+    }
+}
+
+/// Deposit a CRC-20 to a sub-account
+///
+/// The caller must approve `amount`. The sub-account is credited with what
+/// arrives, which is less than `amount` for fee-on-transfer tokens.
+///
+/// # Arguments
+/// * `token` - CRC-20 contract
+/// * `sub_account` - Open sub-account to credit
+/// * `amount` - Amount to pull from the caller
+///
+/// # Returns
+/// The amount credited
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit_token() {
+    if let Err(err) = execute_deposit_token().and_then(|received| try_respond(&received)) {
+        log!("deposit_token failed: {}", err);
+    }
+}
+
+/// Sweep pending balances to the cold wallet (operators only)
+///
+/// Emits `CustomerCredited` for every balance moved and `Swept` for every
+/// asset transferred.
+///
+/// # Arguments
+/// * `limit` - Pending balances to move, 1-50
+///
+/// # Returns
+/// `SweepReport`
+#[unsafe(no_mangle)]
+pub extern "C" fn sweep() {
+    if let Err(err) = execute_sweep().and_then(|report| try_respond(&report)) {
+        log!("sweep failed: {}", err);
+    }
+}
+
+/// Give notice that the sweeper will be decommissioned (admin only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (admin only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the sweeper once the notice has run out (admin only)
+///
+/// Deposits and new sub-accounts stop for good; operators can still sweep
+/// pending balances to the cold wallet.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the sweeper is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Sub-account address of a customer, whether or not it is open
+///
+/// # Arguments
+/// * `customer_id` - Exchange-side customer reference
+///
+/// # Returns
+/// The sub-account address
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit_address() {
+    let result = read_args::<CustomerArgs>()
+        .map(|args| derive_sub_account(context().contract_address(), &args.customer_id))
+        .and_then(|address| try_respond(&address.to_string()));
+    if let Err(err) = result {
+        log!("deposit_address failed: {}", err);
+    }
+}
+
+/// Get an open sub-account
+///
+/// # Arguments
+/// * `sub_account` - Sub-account address
+///
+/// # Returns
+/// `SubAccount`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_sub_account() {
+    let result = read_args::<SubAccountArgs>()
+        .and_then(|args| load_sub_account(&args.sub_account))
+        .and_then(|account| try_respond(&account));
+    if let Err(err) = result {
+        log!("get_sub_account failed: {}", err);
+    }
+}
+
+/// Page through balances waiting to be swept
+///
+/// Sweeps take balances from the front, so order changes as they run.
+///
+/// # Arguments
+/// * `offset` - Index to start at
+/// * `limit` - Page size, at most 50
+///
+/// # Returns
+/// `Vec<PendingDeposit>`
+#[unsafe(no_mangle)]
+pub extern "C" fn pending_deposits() {
+    if let Err(err) = execute_pending_deposits().and_then(|page| try_respond(&page)) {
+        log!("pending_deposits failed: {}", err);
+    }
+}
+
+/// Number of balances waiting to be swept
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn pending_count() {
+    if let Err(err) = pending().len().and_then(|count| try_respond(&count)) {
+        log!("pending_count failed: {}", err);
+    }
+}
+
+/// Whether an account holds a role
+///
+/// # Arguments
+/// * `role` - Role name
+/// * `account` - Account to check
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn has_role() {
+    let result = read_args::<RoleArgs>()
+        .and_then(|args| access_control::has_role(&args.role, &args.account))
+        .and_then(|held| try_respond(&held));
+    if let Err(err) = result {
+        log!("has_role failed: {}", err);
+    }
+}
+
+/// Get the sweeper configuration
+///
+/// # Returns
+/// `SweeperConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use mock_crc20::MockCrc20;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ADMIN: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_OPERATOR: &str = "0x0000000000000000000000000000000000000a02";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b01";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_COLD: &str = "0x0000000000000000000000000000000000000c01";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const USDC: &str = "0x0000000000000000000000000000000000000f01";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn run<R>(
+        sender: &str,
+        execute: fn() -> ContractResult<R>,
+        data: Vec<u8>,
+    ) -> ContractResult<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        execute()
+    }
+
+    fn customer(id: &str) -> Vec<u8> {
+        encode(&CustomerArgs {
+            customer_id: CustomerId::new(id).unwrap(),
+        })
+    }
+
+    fn open(id: &str) -> String {
+        run(ADDR_OPERATOR, execute_open_sub_account, customer(id)).unwrap()
+    }
+
+    fn deposit_native(sender: &str, sub_account: &str, value: u64) -> ContractResult<()> {
+        mock::set_value(value);
+        let result = run(
+            sender,
+            execute_deposit,
+            encode(&SubAccountArgs {
+                sub_account: sub_account.parse().unwrap(),
+            }),
+        );
+        mock::set_value(0);
+        result
+    }
+
+    fn deposit_usdc(sender: &str, sub_account: &str, amount: u64) -> ContractResult<u64> {
+        run(
+            sender,
+            execute_deposit_token,
+            encode(&DepositTokenArgs {
+                token: USDC.parse().unwrap(),
+                sub_account: sub_account.parse().unwrap(),
+                amount,
+            }),
+        )
+    }
+
+    fn operator_role() -> Vec<u8> {
+        encode(&RoleArgs {
+            role: OPERATOR_ROLE.to_string(),
+            account: ADDR_OPERATOR.parse().unwrap(),
+        })
+    }
+
+    fn sweep_up_to(limit: u64) -> ContractResult<SweepReport> {
+        run(ADDR_OPERATOR, execute_sweep, encode(&SweepArgs { limit }))
+    }
+
+    /// Initialized by ADDR_ADMIN with ADDR_OPERATOR as operator and a USDC
+    /// balance for Alice and Bob
+    fn setup() -> MockCrc20 {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        let usdc = MockCrc20::deploy(USDC);
+        for holder in [ADDR_ALICE, ADDR_BOB] {
+            usdc.mint(holder, 1_000_000);
+            usdc.approve(holder, CONTRACT, u64::MAX);
+        }
+        run(
+            ADDR_ADMIN,
+            execute_initialize,
+            encode(&InitializeArgs {
+                cold_wallet: ADDR_COLD.parse().unwrap(),
+            }),
+        )
+        .unwrap();
+        run(ADDR_ADMIN, execute_grant_role, operator_role()).unwrap();
+        mock::take_events();
+        usdc
+    }
+
+    #[test]
+    fn sub_accounts_are_derived_per_customer_and_sweeper() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let alice = derive_sub_account(CONTRACT, "alice");
+        assert_ne!(alice, derive_sub_account(CONTRACT, "bob"));
+        assert_ne!(alice, derive_sub_account(USDC, "alice"));
+
+        // Only operators open sub-accounts, once each.
+        assert!(matches!(
+            run(ADDR_ALICE, execute_open_sub_account, customer("alice")),
+            Err(ContractError::Unauthorized)
+        ));
+        assert_eq!(open("alice"), alice.as_str());
+        assert!(run(ADDR_OPERATOR, execute_open_sub_account, customer("alice")).is_err());
+        assert!(run(ADDR_OPERATOR, execute_open_sub_account, customer("")).is_err());
+        assert_eq!(
+            load_sub_account(&alice).unwrap(),
+            SubAccount {
+                customer_id: "alice".to_string(),
+                opened_at: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn deposits_accumulate_per_sub_account_and_asset() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let usdc = setup();
+        let alice = open("alice");
+
+        deposit_native(ADDR_ALICE, &alice, 300).unwrap();
+        deposit_native(ADDR_BOB, &alice, 200).unwrap();
+        assert_eq!(deposit_usdc(ADDR_ALICE, &alice, 1_000).unwrap(), 1_000);
+
+        // Fee-on-transfer tokens are credited with what arrived.
+        usdc.set_fee_bps(100);
+        assert_eq!(deposit_usdc(ADDR_ALICE, &alice, 1_000).unwrap(), 990);
+        assert_eq!(usdc.balance_of(CONTRACT), 1_990);
+
+        // Unknown sub-accounts, empty deposits and value on token deposits fail.
+        let unknown = derive_sub_account(CONTRACT, "bob").to_string();
+        assert!(deposit_native(ADDR_BOB, &unknown, 100).is_err());
+        assert!(deposit_native(ADDR_BOB, &alice, 0).is_err());
+        mock::set_value(5);
+        assert!(deposit_usdc(ADDR_BOB, &alice, 100).is_err());
+        mock::set_value(0);
+
+        let page = run(
+            ADDR_BOB,
+            execute_pending_deposits,
+            encode(&PageArgs {
+                offset: 0,
+                limit: 10,
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            page,
+            [
+                PendingDeposit {
+                    sub_account: alice.clone(),
+                    asset: NATIVE_ASSET.to_string(),
+                    amount: 500,
+                },
+                PendingDeposit {
+                    sub_account: alice,
+                    asset: USDC.to_string(),
+                    amount: 1_990,
+                },
+            ]
+        );
+        let topics: Vec<String> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(topics.len(), 6);
+        assert!(
+            topics
+                .iter()
+                .skip(2)
+                .all(|topic| topic == "DepositReceived")
+        );
+    }
+
+    #[test]
+    fn sweeps_move_batches_to_the_cold_wallet_and_credit_customers() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let usdc = setup();
+        let alice = open("alice");
+        let bob = open("bob");
+        deposit_usdc(ADDR_ALICE, &alice, 700).unwrap();
+        deposit_usdc(ADDR_BOB, &bob, 300).unwrap();
+        deposit_native(ADDR_BOB, &bob, 50).unwrap();
+        mock::take_events();
+
+        assert!(matches!(
+            run(ADDR_ALICE, execute_sweep, encode(&SweepArgs { limit: 2 })),
+            Err(ContractError::Unauthorized)
+        ));
+        assert!(sweep_up_to(0).is_err());
+        assert!(sweep_up_to(MAX_SWEEP_BATCH + 1).is_err());
+
+        assert_eq!(
+            sweep_up_to(2).unwrap(),
+            SweepReport {
+                batch: 1,
+                credited: 2,
+                remaining: 1,
+            }
+        );
+        // One transfer per asset, one credit per customer balance.
+        assert_eq!(usdc.balance_of(ADDR_COLD), 1_000);
+        let events = mock::take_events();
+        let credits: Vec<(String, String, String, String, String)> = events
+            .iter()
+            .filter(|event| event.topic == "CustomerCredited")
+            .map(|event| postcard::from_bytes(&event.data).unwrap())
+            .collect();
+        assert_eq!(
+            credits,
+            [
+                (
+                    "1".to_string(),
+                    "alice".to_string(),
+                    alice.clone(),
+                    USDC.to_string(),
+                    "700".to_string(),
+                ),
+                (
+                    "1".to_string(),
+                    "bob".to_string(),
+                    bob.clone(),
+                    USDC.to_string(),
+                    "300".to_string(),
+                ),
+            ]
+        );
+        assert_eq!(
+            events.iter().filter(|event| event.topic == "Swept").count(),
+            1
+        );
+
+        assert_eq!(
+            sweep_up_to(MAX_SWEEP_BATCH).unwrap(),
+            SweepReport {
+                batch: 2,
+                credited: 1,
+                remaining: 0,
+            }
+        );
+        assert!(sweep_up_to(1).is_err());
+        assert_eq!(load_config().unwrap().batches, 2);
+    }
+
+    #[test]
+    fn decommissioning_stops_deposits_but_not_sweeps() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let usdc = setup();
+        let alice = open("alice");
+        deposit_usdc(ADDR_ALICE, &alice, 100).unwrap();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        // Operators cannot retire the sweeper.
+        assert!(matches!(
+            run(ADDR_OPERATOR, execute_schedule_decommission, notice.clone()),
+            Err(ContractError::Unauthorized)
+        ));
+        let eta = run(ADDR_ADMIN, execute_schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        run(ADDR_ADMIN, execute_decommission, Vec::new()).unwrap();
+
+        assert!(deposit_usdc(ADDR_ALICE, &alice, 100).is_err());
+        assert!(deposit_native(ADDR_ALICE, &alice, 100).is_err());
+        assert!(run(ADDR_OPERATOR, execute_open_sub_account, customer("bob")).is_err());
+        assert_eq!(sweep_up_to(1).unwrap().credited, 1);
+        assert_eq!(usdc.balance_of(ADDR_COLD), 100);
+
+        let actions: Vec<String> = mock::take_events()
+            .iter()
+            .filter(|event| event.topic == "AdminAction")
+            .map(|event| {
+                events::decode::<events::AdminActionV1>(&event.data)
+                    .unwrap()
+                    .unwrap()
+                    .action
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                "open_sub_account",
+                "schedule_decommission",
+                "decommission",
+                "sweep"
+            ]
+        );
+    }
+
+    #[test]
+    fn the_admin_manages_operators_and_the_cold_wallet() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let usdc = setup();
+        let set_cold_wallet = |sender: &str, cold_wallet: &str| {
+            run(
+                sender,
+                execute_set_cold_wallet,
+                encode(&ColdWalletArgs {
+                    cold_wallet: cold_wallet.parse().unwrap(),
+                }),
+            )
+        };
+        assert!(matches!(
+            set_cold_wallet(ADDR_OPERATOR, ADDR_OPERATOR),
+            Err(ContractError::Unauthorized)
+        ));
+        assert!(set_cold_wallet(ADDR_ADMIN, "0x0").is_err());
+        set_cold_wallet(ADDR_ADMIN, ADDR_BOB).unwrap();
+
+        let alice = open("alice");
+        deposit_usdc(ADDR_ALICE, &alice, 100).unwrap();
+        sweep_up_to(1).unwrap();
+        assert_eq!(usdc.balance_of(ADDR_BOB), 1_000_100);
+
+        // A revoked operator can no longer sweep.
+        run(ADDR_ADMIN, execute_revoke_role, operator_role()).unwrap();
+        deposit_usdc(ADDR_ALICE, &alice, 100).unwrap();
+        assert!(matches!(sweep_up_to(1), Err(ContractError::Unauthorized)));
+
+        // Every admin and operator action is in the audit log, in order.
+        let actions: Vec<(String, String, String, String, String)> = mock::take_events()
+            .iter()
+            .filter(|event| event.topic == "AdminAction")
+            .map(|event| events::decode::<events::AdminActionV1>(&event.data).unwrap())
+            .map(|action| {
+                let action = action.unwrap();
+                (
+                    action.actor,
+                    action.action,
+                    action.target,
+                    action.old_value,
+                    action.new_value,
+                )
+            })
+            .collect();
+        let entry = |actor: &str, action: &str, target: &str, old: &str, new: &str| {
+            (
+                actor.to_string(),
+                action.to_string(),
+                target.to_string(),
+                old.to_string(),
+                new.to_string(),
+            )
+        };
+        assert_eq!(
+            actions,
+            [
+                entry(
+                    ADDR_ADMIN,
+                    admin::SET_PARAMETER,
+                    "cold_wallet",
+                    ADDR_COLD,
+                    ADDR_BOB
+                ),
+                entry(ADDR_OPERATOR, "open_sub_account", &alice, "", "alice"),
+                entry(ADDR_OPERATOR, "sweep", "batches", "0", "1"),
+                entry(
+                    ADDR_ADMIN,
+                    admin::REVOKE_ROLE,
+                    ADDR_OPERATOR,
+                    OPERATOR_ROLE,
+                    ""
+                ),
+            ]
+        );
+        // The operator grant in `setup` came first.
+        assert_eq!(admin::next_sequence().unwrap(), 5);
+        assert!(matches!(
+            run(
+                ADDR_ADMIN,
+                execute_initialize,
+                encode(&InitializeArgs {
+                    cold_wallet: ADDR_COLD.parse().unwrap(),
+                }),
+            ),
+            Err(ContractError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "deposit-sweeper-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
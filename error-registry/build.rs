@@ -0,0 +1,80 @@
+//! Generates the code table from the `ErrorCode` enum in `crc-errors`.
+//!
+//! Every variant needs an explicit number and a doc comment, which becomes
+//! its description.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use syn::{Expr, Item, Lit, Meta};
+
+const SOURCE: &str = "../crc-errors/src/lib.rs";
+
+fn main() {
+    println!("cargo:rerun-if-changed={SOURCE}");
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let source = fs::read_to_string(manifest_dir.join(SOURCE)).expect("read crc-errors sources");
+    let file = syn::parse_file(&source).expect("parse crc-errors sources");
+    let codes = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Enum(item) if item.ident == "ErrorCode" => Some(item),
+            _ => None,
+        })
+        .expect("crc-errors defines ErrorCode");
+
+    let mut entries: Vec<(u16, String, String)> = codes
+        .variants
+        .iter()
+        .map(|variant| {
+            let name = variant.ident.to_string();
+            let code = match variant.discriminant.as_ref() {
+                Some((_, Expr::Lit(lit))) => match &lit.lit {
+                    Lit::Int(code) => code.base10_parse::<u16>().ok(),
+                    _ => None,
+                },
+                _ => None,
+            }
+            .unwrap_or_else(|| panic!("ErrorCode::{name} needs an explicit number"));
+            let description = variant
+                .attrs
+                .iter()
+                .filter_map(|attr| match &attr.meta {
+                    Meta::NameValue(doc) if doc.path.is_ident("doc") => match &doc.value {
+                        Expr::Lit(lit) => match &lit.lit {
+                            Lit::Str(line) => Some(line.value().trim().to_string()),
+                            _ => None,
+                        },
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            assert!(
+                !description.is_empty(),
+                "ErrorCode::{name} needs a doc comment to describe it"
+            );
+            (code, name, description)
+        })
+        .collect();
+    entries.sort_by_key(|(code, _, _)| *code);
+    assert!(
+        entries.windows(2).all(|pair| pair[0].0 != pair[1].0),
+        "ErrorCode numbers must be unique"
+    );
+
+    let mut table = String::from(
+        "/// `(code, name, description)` of every `ErrorCode`, sorted by code\n\
+         pub const ERROR_CODES: &[(u16, &str, &str)] = &[\n",
+    );
+    for (code, name, description) in &entries {
+        writeln!(table, "    ({code}, {name:?}, {description:?}),").unwrap();
+    }
+    table.push_str("];\n");
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("error_codes.rs");
+    fs::write(out, table).expect("write the generated code table");
+}
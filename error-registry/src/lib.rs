@@ -0,0 +1,280 @@
+//! Error Code Registry
+//!
+//! Contracts in this workspace report failures as `crc-errors` codes in an
+//! `ErrorPayload`. This contract answers what each code means, so explorers
+//! and wallets can render a failure from the chain instead of shipping their
+//! own copy of the table.
+//!
+//! The table is generated at build time from the `ErrorCode` enum in
+//! `crc-errors`: each variant's number, name and doc comment. Redeploying
+//! after a new code is added publishes it. The contract holds no state.
+//!
+//! ## Features
+//! - Look up one code, or page through all of them
+//! - Same names and numbers as `crc_errors::ErrorCode`
+//! - No storage, no owner
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::prelude::*;
+
+include!(concat!(env!("OUT_DIR"), "/error_codes.rs"));
+
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 64;
+const MAX_RETURN_BYTES: usize = 8192;
+
+/// What one error code means
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorDescription {
+    pub code: u16,
+    /// `ErrorCode` variant name, e.g. `InsufficientBalance`
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DescribeArgs {
+    code: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PageArgs {
+    offset: u64,
+    limit: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn to_description(&(code, name, description): &(u16, &str, &str)) -> ErrorDescription {
+    ErrorDescription {
+        code,
+        name: name.to_string(),
+        description: description.to_string(),
+    }
+}
+
+/// The registered code `code`, if any
+pub fn lookup(code: u16) -> Option<ErrorDescription> {
+    ERROR_CODES
+        .binary_search_by_key(&code, |&(known, _, _)| known)
+        .ok()
+        .map(|index| to_description(&ERROR_CODES[index]))
+}
+
+fn execute_describe_error() -> ContractResult<Option<ErrorDescription>> {
+    let args: DescribeArgs = read_args()?;
+    Ok(lookup(args.code))
+}
+
+fn execute_list_errors() -> ContractResult<Vec<ErrorDescription>> {
+    let args: PageArgs = read_args()?;
+    let offset = usize::try_from(args.offset).unwrap_or(usize::MAX);
+    let limit = args.limit.min(MAX_PAGE_SIZE) as usize;
+    Ok(ERROR_CODES
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .map(to_description)
+        .collect())
+}
+
+/// Describe one error code
+///
+/// # Arguments
+/// * `code` - `ErrorPayload::code` of a failed call
+///
+/// # Returns
+/// `Option<ErrorDescription>`; `None` for codes this deployment predates
+#[unsafe(no_mangle)]
+pub extern "C" fn describe_error() {
+    if let Err(err) = execute_describe_error().and_then(|found| try_respond(&found)) {
+        log!("describe_error failed: {}", err);
+    }
+}
+
+/// Page through all codes in ascending order
+///
+/// # Arguments
+/// * `offset` - Codes to skip
+/// * `limit` - At most 50 per page
+///
+/// # Returns
+/// `Vec<ErrorDescription>`
+#[unsafe(no_mangle)]
+pub extern "C" fn list_errors() {
+    if let Err(err) = execute_list_errors().and_then(|page| try_respond(&page)) {
+        log!("list_errors failed: {}", err);
+    }
+}
+
+/// Number of registered codes
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn error_count() {
+    if let Err(err) = try_respond(&(ERROR_CODES.len() as u64)) {
+        log!("error_count failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_errors::ErrorCode;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender("0x0000000000000000000000000000000000000a01");
+        mock::set_contract_address("0x0000000000000000000000000000000000000f00");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    fn call<A: Serialize, R>(execute: fn() -> ContractResult<R>, args: &A) -> R {
+        mock::set_call_data(&postcard::to_allocvec(args).unwrap());
+        execute().unwrap()
+    }
+
+    #[test]
+    fn table_matches_the_shared_error_codes() {
+        // Every number crc-errors knows is registered under its own name,
+        // and nothing else is.
+        let known: Vec<u16> = (0..=u16::MAX)
+            .filter(|&code| code == 0 || ErrorCode::from_u16(code) != ErrorCode::Unknown)
+            .collect();
+        let registered: Vec<u16> = ERROR_CODES.iter().map(|&(code, _, _)| code).collect();
+        assert_eq!(registered, known);
+        for &(code, name, description) in ERROR_CODES {
+            assert_eq!(format!("{:?}", ErrorCode::from_u16(code)), name);
+            assert!(!description.is_empty());
+        }
+
+        let paused = lookup(ErrorCode::Paused.as_u16()).unwrap();
+        assert_eq!(paused.name, "Paused");
+        assert_eq!(paused.description, "The contract is paused");
+    }
+
+    #[test]
+    fn codes_are_described_and_paged() {
+        let _guard = test_lock()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        setup();
+
+        let found = call(execute_describe_error, &DescribeArgs { code: 400 });
+        assert_eq!(
+            found,
+            Some(ErrorDescription {
+                code: 400,
+                name: "InsufficientBalance".to_string(),
+                description: "The account holds less than the amount required".to_string(),
+            })
+        );
+        assert_eq!(
+            call(execute_describe_error, &DescribeArgs { code: 999 }),
+            None
+        );
+
+        let mut listed = Vec::new();
+        loop {
+            let page = call(
+                execute_list_errors,
+                &PageArgs {
+                    offset: listed.len() as u64,
+                    limit: 5,
+                },
+            );
+            if page.is_empty() {
+                break;
+            }
+            assert!(page.len() <= 5);
+            listed.extend(page);
+        }
+        assert_eq!(listed.len(), ERROR_CODES.len());
+        assert!(listed.windows(2).all(|pair| pair[0].code < pair[1].code));
+        assert!(
+            call(
+                execute_list_errors,
+                &PageArgs {
+                    offset: u64::MAX,
+                    limit: 5,
+                },
+            )
+            .is_empty()
+        );
+
+        mock::set_call_data(&[]);
+        assert!(matches!(
+            execute_describe_error(),
+            Err(ContractError::CallDataUnavailable)
+        ));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "error-registry-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
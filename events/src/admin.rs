@@ -0,0 +1,28 @@
+//! Audit log of privileged actions
+//!
+//! Every owner, admin or operator action emits one
+//! [`AdminActionV1`](crate::AdminActionV1), so
+//! auditors and monitoring watch a single topic across contracts instead of
+//! each contract's own events. Those events are still emitted alongside.
+//!
+//! The recorder lives in the SDK's
+//! [`admin_log`](silica_contract_sdk::admin_log), whose `pausable`, `ownable`
+//! and `decommission` modules record their own actions; this module
+//! re-exports it next to the schema. See there for the action ids and what
+//! `target`, `old_value` and `new_value` hold for each.
+//!
+//! Contract-specific actions use their entrypoint name as the id.
+//!
+//! ```rust,ignore
+//! use events::admin;
+//!
+//! admin::record(admin::SET_PARAMETER, "fee_bps", old_fee, args.fee_bps)?;
+//! ```
+
+pub use silica_contract_sdk::admin_log::{
+    ACCEPT_OWNERSHIP, BURN, CANCEL_DECOMMISSION, CIRCUIT_BREAKER_TARGET, DECOMMISSION,
+    DECOMMISSION_TARGET, GRANT_ROLE, MINT, OWNER_TARGET, PAUSE, PAUSED_TARGET,
+    PENDING_OWNER_TARGET, RENOUNCE_OWNERSHIP, RESET_CIRCUIT_BREAKER, REVOKE_ROLE,
+    SCHEDULE_DECOMMISSION, SEQUENCE_KEY, SET_PARAMETER, TRANSFER_OWNERSHIP, UNPAUSE, next_sequence,
+    record,
+};
@@ -0,0 +1,343 @@
+//! Typed, versioned event schemas
+//!
+//! The SDK's `event!` macro turns every field into a string and leaves the
+//! field list to each call site, so two contracts emitting `Transfer` can
+//! disagree on names and types. Events here are plain structs with typed
+//! fields, and each one is published with its schema version:
+//!
+//! ```text
+//! topic   = Event::TOPIC
+//! indexed = [blake3(postcard(field)) for each indexed field]
+//! data    = postcard((Event::VERSION, event))
+//! ```
+//!
+//! Account and token id fields are also published as indexed topics, so the
+//! node can filter a contract's events by sender, recipient or token before
+//! an indexer decodes any data. Indexers read the version first and decode
+//! the rest with the matching struct. Changing a schema means adding a new struct (`TransferV2`) under
+//! the same topic with the next version; shipped structs never change, so
+//! old and new contracts can be indexed side by side.
+//!
+//! Amounts and token ids are `u64`; addresses are strings, with
+//! [`ZERO_ADDRESS`] standing in for mints and burns.
+//!
+//! ## Embedding
+//! ```toml
+//! events = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use events::TransferV1;
+//!
+//! events::emit(&TransferV1 { from, to, amount });
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+pub mod admin;
+
+use alloc::vec::Vec;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::prelude::*;
+
+/// Counterparty of mints and burns
+pub const ZERO_ADDRESS: &str = address::ZERO_ADDRESS;
+
+/// A versioned event schema
+pub trait Event: Serialize {
+    /// Topic indexers subscribe to
+    const TOPIC: &'static str;
+    /// Schema version; every struct under a topic has its own
+    const VERSION: u16;
+
+    /// Indexed topics of this event, in field order; see
+    /// [`indexed_topic`](silica_contract_sdk::events::indexed_topic)
+    fn indexed(&self) -> Vec<[u8; 32]> {
+        Vec::new()
+    }
+}
+
+/// Publish `event` under its topic with its indexed fields, prefixed with
+/// its schema version
+pub fn emit<E: Event>(event: &E) {
+    silica_contract_sdk::events::emit_indexed(E::TOPIC, &event.indexed(), &(E::VERSION, event));
+}
+
+/// Schema version of emitted event data
+pub fn version_of(data: &[u8]) -> ContractResult<u16> {
+    let (version, _): (u16, &[u8]) =
+        postcard::take_from_bytes(data).map_err(|_| ContractError::DeserializationFailed)?;
+    Ok(version)
+}
+
+/// Decode event data as `E`; `None` if it was emitted with another version
+pub fn decode<E: Event + DeserializeOwned>(data: &[u8]) -> ContractResult<Option<E>> {
+    if version_of(data)? != E::VERSION {
+        return Ok(None);
+    }
+    let (_, event): (u16, E) =
+        postcard::from_bytes(data).map_err(|_| ContractError::DeserializationFailed)?;
+    Ok(Some(event))
+}
+
+macro_rules! schema {
+    ($(#[$meta:meta])* $name:ident = $topic:literal, version $version:literal, indexed [$($indexed:ident),*] { $($(#[$field_meta:meta])* $field:ident: $ty:ty),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+        pub struct $name {
+            $($(#[$field_meta])* pub $field: $ty),*
+        }
+
+        impl Event for $name {
+            const TOPIC: &'static str = $topic;
+            const VERSION: u16 = $version;
+
+            fn indexed(&self) -> Vec<[u8; 32]> {
+                alloc::vec![$(silica_contract_sdk::events::indexed_topic(&self.$indexed)),*]
+            }
+        }
+    };
+}
+
+schema! {
+    /// Fungible tokens moved; mints come from and burns go to [`ZERO_ADDRESS`]
+    TransferV1 = "Transfer", version 1, indexed [from, to] {
+        from: String,
+        to: String,
+        amount: u64,
+    }
+}
+
+schema! {
+    /// `spender` may now move up to `amount` of `owner`'s tokens
+    ApprovalV1 = "Approval", version 1, indexed [owner, spender] {
+        owner: String,
+        spender: String,
+        amount: u64,
+    }
+}
+
+schema! {
+    /// A non-fungible token changed owner
+    NftTransferV1 = "Transfer", version 1, indexed [from, to, token_id] {
+        from: String,
+        to: String,
+        token_id: u64,
+    }
+}
+
+schema! {
+    /// A non-fungible token changed owner with a note from the sender
+    NftTransferV2 = "Transfer", version 2, indexed [from, to, token_id] {
+        from: String,
+        to: String,
+        token_id: u64,
+        memo: String,
+    }
+}
+
+schema! {
+    /// `approved` may move `token_id`; [`ZERO_ADDRESS`] clears the approval
+    NftApprovalV1 = "Approval", version 1, indexed [owner, approved, token_id] {
+        owner: String,
+        approved: String,
+        token_id: u64,
+    }
+}
+
+schema! {
+    /// `operator` may or may no longer move all of `owner`'s tokens
+    ApprovalForAllV1 = "ApprovalForAll", version 1, indexed [owner, operator] {
+        owner: String,
+        operator: String,
+        approved: bool,
+    }
+}
+
+schema! {
+    /// `buyer` bought `token_id` from `seller`
+    SaleV1 = "Sale", version 1, indexed [seller, buyer, token_id] {
+        seller: String,
+        buyer: String,
+        token_id: u64,
+        /// Paid in the chain's native token
+        price: u64,
+    }
+}
+
+schema! {
+    /// `account` may now mint tokens
+    MinterAddedV1 = "MinterAdded", version 1, indexed [account] {
+        account: String,
+    }
+}
+
+schema! {
+    /// `account` may no longer mint tokens
+    MinterRemovedV1 = "MinterRemoved", version 1, indexed [account] {
+        account: String,
+    }
+}
+
+schema! {
+    /// A privileged action; see [`admin`]
+    AdminActionV1 = "AdminAction", version 1, indexed [actor, action, target] {
+        /// Account that performed the action
+        actor: String,
+        /// One of the ids in [`admin`]
+        action: String,
+        /// Account, token or parameter acted on
+        target: String,
+        /// State of `target` before the action, empty if it had none
+        old_value: String,
+        /// State of `target` after the action, empty if it has none
+        new_value: String,
+        /// Counts the contract's admin actions from 0, so gaps show
+        sequence: u64,
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TransferV2 {
+        from: String,
+        to: String,
+        amount: u64,
+        memo: String,
+    }
+
+    impl Event for TransferV2 {
+        const TOPIC: &'static str = "Transfer";
+        const VERSION: u16 = 2;
+    }
+
+    #[test]
+    fn events_carry_their_schema_version() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        let transfer = TransferV1 {
+            from: ZERO_ADDRESS.to_string(),
+            to: "chert1bob0000000000000000000000".to_string(),
+            amount: 500,
+        };
+        emit(&transfer);
+        emit(&TransferV2 {
+            from: transfer.to.clone(),
+            to: ZERO_ADDRESS.to_string(),
+            amount: 5,
+            memo: "fee".to_string(),
+        });
+
+        let events = mock::take_events();
+        assert!(events.iter().all(|event| event.topic == "Transfer"));
+        assert_eq!(version_of(&events[0].data).unwrap(), 1);
+        assert_eq!(
+            decode::<TransferV1>(&events[0].data).unwrap(),
+            Some(transfer)
+        );
+        // A v1 indexer skips v2 data instead of misreading it.
+        assert_eq!(decode::<TransferV1>(&events[1].data).unwrap(), None);
+        assert_eq!(
+            decode::<TransferV2>(&events[1].data).unwrap().unwrap().memo,
+            "fee"
+        );
+        // Schemas without indexed fields emit none.
+        assert!(events[1].indexed.is_empty());
+    }
+
+    #[test]
+    fn events_are_numbered_per_contract() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        mock::set_contract_address("chert1token00000000000000000000");
+        let approval = ApprovalV1 {
+            owner: "chert1alice000000000000000000".to_string(),
+            spender: "chert1bob0000000000000000000000".to_string(),
+            amount: 5,
+        };
+        emit(&approval);
+        silica_contract_sdk::event!("Paused", account: approval.owner);
+        emit(&approval);
+        mock::set_contract_address("chert1other00000000000000000000");
+        emit(&approval);
+
+        let sequences: Vec<u64> = mock::take_events().iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, [0, 1, 2, 0]);
+        assert_eq!(silica_contract_sdk::events::sequence().unwrap(), 1);
+    }
+
+    #[test]
+    fn indexed_fields_become_topics() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        let bob = "chert1bob0000000000000000000000";
+        emit(&NftTransferV1 {
+            from: ZERO_ADDRESS.to_string(),
+            to: bob.to_string(),
+            token_id: 7,
+        });
+
+        let events = mock::take_events();
+        assert_eq!(
+            events[0].indexed,
+            [
+                silica_contract_sdk::events::indexed_topic(ZERO_ADDRESS),
+                silica_contract_sdk::events::indexed_topic(bob),
+                silica_contract_sdk::events::indexed_topic(&7u64),
+            ]
+        );
+        // The documented topic, so filters can be built off-chain.
+        assert_eq!(
+            events[0].indexed[2],
+            crypto::hash_blake3(&postcard::to_allocvec(&7u64).unwrap())
+        );
+    }
+
+    #[test]
+    fn admin_actions_are_numbered_per_contract() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        mock::set_contract_address("chert1token00000000000000000000");
+        mock::set_sender("chert1admin00000000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+        assert_eq!(
+            admin::record(admin::PAUSE, admin::PAUSED_TARGET, false, true).unwrap(),
+            0
+        );
+        assert_eq!(
+            admin::record(admin::SET_PARAMETER, "fee_bps", 30, 25).unwrap(),
+            1
+        );
+        assert_eq!(admin::next_sequence().unwrap(), 2);
+
+        // The SDK records it in this crate's layout.
+        let events = mock::take_events();
+        let expected = AdminActionV1 {
+            actor: "chert1admin00000000000000000000".to_string(),
+            action: "set_parameter".to_string(),
+            target: "fee_bps".to_string(),
+            old_value: "30".to_string(),
+            new_value: "25".to_string(),
+            sequence: 1,
+        };
+        assert_eq!(events[1].topic, AdminActionV1::TOPIC);
+        assert_eq!(events[1].indexed, expected.indexed());
+        assert_eq!(
+            decode::<AdminActionV1>(&events[1].data).unwrap(),
+            Some(expected)
+        );
+    }
+}
@@ -0,0 +1,558 @@
+//! Testnet Faucet
+//!
+//! Dispenses a fixed drip of native coin or a CRC-20 token to each address at
+//! most once per cooldown period. Contract callers are rejected so a single
+//! deployer cannot farm the faucet through throwaway contracts.
+//!
+//! ## Features
+//! - Fixed drip amount per claim
+//! - Per-address cooldown, a one-claim rolling window per address
+//! - Owner refills and parameter updates
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::rate_limit::RollingWindow;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "faucet_config";
+const CLAIMS_PREFIX: &str = "claims";
+const NATIVE_ASSET: &str = "native";
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Faucet parameters and remaining float
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FaucetConfig {
+    pub owner: String,
+    pub token: String,
+    pub drip_amount: u64,
+    pub cooldown: u64,
+    pub available: u64,
+}
+
+/// Claim status for a single account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ClaimStatus {
+    pub last_claim: Option<u64>,
+    pub next_claim_at: u64,
+    pub can_claim: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    token: String,
+    drip_amount: u64,
+    cooldown: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RefillArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetParamsArgs {
+    drip_amount: u64,
+    cooldown: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ClaimStatusArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<FaucetConfig> {
+    storage()
+        .get::<FaucetConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Faucet not initialized".to_string()))
+}
+
+fn save_config(config: &FaucetConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<FaucetConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+/// One claim per address per cooldown, counted from the claim
+fn claims(config: &FaucetConfig) -> ContractResult<RollingWindow> {
+    RollingWindow::new(CLAIMS_PREFIX, 1, Duration::from_secs(config.cooldown))
+}
+
+fn validate_params(drip_amount: u64, cooldown: u64) -> ContractResult<()> {
+    validation::validate_positive_amount(drip_amount)?;
+    validation::validate_positive_amount(cooldown)
+}
+
+fn claim_status(config: &FaucetConfig, account: &str) -> ContractResult<ClaimStatus> {
+    let claims = claims(config)?;
+    let last_claim = claims.usage(account)?.map(|window| window.started_at);
+    Ok(ClaimStatus {
+        last_claim,
+        next_claim_at: last_claim.map_or(0, |at| claims.closes_at(at)),
+        can_claim: claims.remaining(account)? > 0,
+    })
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let args: InitializeArgs = read_args()?;
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Faucet already initialized".to_string(),
+        ));
+    }
+    if args.token != NATIVE_ASSET {
+        validation::validate_address(&args.token)?;
+    }
+    validate_params(args.drip_amount, args.cooldown)?;
+
+    let owner = context().sender().to_string();
+    validation::validate_address(&owner)?;
+    save_config(&FaucetConfig {
+        owner: owner.clone(),
+        token: args.token.clone(),
+        drip_amount: args.drip_amount,
+        cooldown: args.cooldown,
+        available: 0,
+    })?;
+
+    event!("FaucetInitialized",
+        owner: owner,
+        token: args.token,
+        drip_amount: args.drip_amount,
+        cooldown: args.cooldown
+    );
+    Ok(())
+}
+
+fn execute_claim() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_config()?;
+    let ctx = context();
+    let claimant = ctx.sender().to_string();
+    validation::validate_address(&claimant)?;
+
+    if ctx.is_contract(&claimant) {
+        return Err(ContractError::InvalidArgument(
+            "Contracts cannot claim from the faucet".to_string(),
+        ));
+    }
+
+    let now = ctx.block_timestamp();
+    let status = claim_status(&config, &claimant)?;
+    if !status.can_claim {
+        return Err(ContractError::InvalidArgument(format!(
+            "Cooldown active until {}",
+            status.next_claim_at
+        )));
+    }
+    if config.available < config.drip_amount {
+        return Err(ContractError::InsufficientBalance {
+            required: config.drip_amount,
+            available: config.available,
+        });
+    }
+
+    config.available -= config.drip_amount;
+    save_config(&config)?;
+    claims(&config)?.check_and_consume(&claimant, 1)?;
+
+    if config.token == NATIVE_ASSET {
+        ctx.transfer_tokens(&claimant, config.drip_amount)?;
+    } else {
+        let call = encode_call(&TokenTransferArgs {
+            to: claimant.clone(),
+            amount: config.drip_amount,
+        })?;
+        ctx.call_contract(&config.token, "transfer", &call)?;
+    }
+
+    event!("Claimed", account: claimant, amount: config.drip_amount, at: now);
+    Ok(config.drip_amount)
+}
+
+fn execute_refill() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_owned_config()?;
+    let ctx = context();
+    let args: RefillArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    if config.token == NATIVE_ASSET {
+        if ctx.value() != args.amount {
+            return Err(ContractError::InvalidArgument(
+                "Attached value must equal refill amount".to_string(),
+            ));
+        }
+    } else {
+        if ctx.value() != 0 {
+            return Err(ContractError::InvalidArgument(
+                "Token refills must not attach native value".to_string(),
+            ));
+        }
+        let call = encode_call(&TokenTransferFromArgs {
+            from: config.owner.clone(),
+            to: ctx.contract_address().to_string(),
+            amount: args.amount,
+        })?;
+        ctx.call_contract(&config.token, "transfer_from", &call)?;
+    }
+
+    let previous = config.available;
+    config.available = safe_math::add(previous, args.amount)?;
+    save_config(&config)?;
+
+    event!("Refilled", amount: args.amount, available: config.available);
+    admin_log::record("refill", "available", previous, config.available)?;
+    Ok(())
+}
+
+fn execute_set_params() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: SetParamsArgs = read_args()?;
+    validate_params(args.drip_amount, args.cooldown)?;
+
+    let previous = (config.drip_amount, config.cooldown);
+    config.drip_amount = args.drip_amount;
+    config.cooldown = args.cooldown;
+    save_config(&config)?;
+
+    event!("ParamsUpdated", drip_amount: args.drip_amount, cooldown: args.cooldown);
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "drip_amount",
+        previous.0,
+        args.drip_amount,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "cooldown",
+        previous.1,
+        args.cooldown,
+    )?;
+    Ok(())
+}
+
+fn execute_get_config() -> ContractResult<()> {
+    try_respond(&load_config()?)
+}
+
+fn execute_claim_status() -> ContractResult<()> {
+    let config = load_config()?;
+    let args: ClaimStatusArgs = read_args()?;
+    let status = claim_status(&config, &args.account)?;
+    try_respond(&status)
+}
+
+/// Initialize the faucet
+///
+/// # Arguments
+/// * `token` - CRC-20 contract address, or `"native"` for Chert Coin
+/// * `drip_amount` - Amount dispensed per claim
+/// * `cooldown` - Seconds an address must wait between claims
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Claim one drip for the caller
+///
+/// Rejected for contract callers, during the caller's cooldown, or when the
+/// faucet holds less than one drip.
+#[unsafe(no_mangle)]
+pub extern "C" fn claim() {
+    if let Err(err) = execute_claim() {
+        log!("claim failed: {}", err);
+    }
+}
+
+/// Add funds to the faucet (owner only)
+///
+/// # Arguments
+/// * `amount` - Native value attached to the call, or CRC-20 amount pulled
+///   from the owner via `transfer_from`
+#[unsafe(no_mangle)]
+pub extern "C" fn refill() {
+    if let Err(err) = execute_refill() {
+        log!("refill failed: {}", err);
+    }
+}
+
+/// Update the drip amount and cooldown (owner only)
+///
+/// # Arguments
+/// * `drip_amount` - New amount dispensed per claim
+/// * `cooldown` - New cooldown in seconds
+#[unsafe(no_mangle)]
+pub extern "C" fn set_params() {
+    if let Err(err) = execute_set_params() {
+        log!("set_params failed: {}", err);
+    }
+}
+
+/// Get the faucet configuration
+///
+/// # Returns
+/// `FaucetConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = execute_get_config() {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get an account's last claim and when it may claim next
+///
+/// # Arguments
+/// * `account` - Address to query
+///
+/// # Returns
+/// `ClaimStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_status_of() {
+    if let Err(err) = execute_claim_status() {
+        log!("claim_status_of failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_CONTRACT: &str = "0x0000000000000000000000000000000000000c03";
+    const FAUCET: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000e05";
+    const START: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup(token: &str) {
+        mock::reset();
+        mock::set_sender(ADDR_OWNER);
+        mock::set_contract_address(FAUCET);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::set_call_data(&encode(&InitializeArgs {
+            token: token.to_string(),
+            drip_amount: 100,
+            cooldown: 3_600,
+        }));
+        initialize();
+    }
+
+    fn refill_native(amount: u64) {
+        mock::set_sender(ADDR_OWNER);
+        mock::set_value(amount);
+        mock::set_call_data(&encode(&RefillArgs { amount }));
+        refill();
+        mock::set_value(0);
+    }
+
+    fn claim_as(sender: &str) {
+        mock::set_sender(sender);
+        claim();
+    }
+
+    fn last_claim(account: &str) -> Option<u64> {
+        let claims = claims(&load_config().unwrap()).unwrap();
+        claims
+            .usage(account)
+            .unwrap()
+            .map(|window| window.started_at)
+    }
+
+    #[test]
+    fn claim_respects_cooldown() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        refill_native(1_000);
+
+        claim_as(ADDR_ALICE);
+        claim_as(ADDR_ALICE);
+        assert_eq!(load_config().unwrap().available, 900);
+
+        mock::set_block_timestamp(START + 3_600);
+        claim_as(ADDR_ALICE);
+        assert_eq!(load_config().unwrap().available, 800);
+        assert_eq!(last_claim(ADDR_ALICE), Some(START + 3_600));
+    }
+
+    #[test]
+    fn contract_callers_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        refill_native(1_000);
+        mock::mark_contract(ADDR_CONTRACT);
+
+        claim_as(ADDR_CONTRACT);
+        assert_eq!(load_config().unwrap().available, 1_000);
+        assert_eq!(last_claim(ADDR_CONTRACT), None);
+    }
+
+    #[test]
+    fn empty_faucet_does_not_start_cooldown() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        refill_native(50);
+
+        claim_as(ADDR_ALICE);
+        assert_eq!(last_claim(ADDR_ALICE), None);
+
+        refill_native(50);
+        claim_as(ADDR_ALICE);
+        assert_eq!(load_config().unwrap().available, 0);
+    }
+
+    #[test]
+    fn owner_updates_params_and_status_reflects_cooldown() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        refill_native(1_000);
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&encode(&SetParamsArgs {
+            drip_amount: 500,
+            cooldown: 60,
+        }));
+        set_params();
+        assert_eq!(load_config().unwrap().drip_amount, 100);
+
+        mock::set_sender(ADDR_OWNER);
+        set_params();
+        claim_as(ADDR_ALICE);
+        assert_eq!(load_config().unwrap().available, 500);
+        // The refill, then one entry per parameter; claims are not logged.
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+
+        mock::set_call_data(&encode(&ClaimStatusArgs {
+            account: ADDR_ALICE.to_string(),
+        }));
+        claim_status_of();
+        let status: ClaimStatus = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(status.next_claim_at, START + 60);
+        assert!(!status.can_claim);
+    }
+
+    #[test]
+    fn token_faucet_pulls_refills_and_transfers_drips() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(TOKEN);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&RefillArgs { amount: 300 }));
+        refill();
+        claim_as(ADDR_ALICE);
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 2);
+        let pulled: TokenTransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(pulled.from, ADDR_OWNER);
+        assert_eq!(pulled.to, FAUCET);
+        let dripped: TokenTransferArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!(dripped.to, ADDR_ALICE);
+        assert_eq!(dripped.amount, 100);
+        assert_eq!(load_config().unwrap().available, 200);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "faucet-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,866 @@
+//! Marketplace Fee Router
+//!
+//! Marketplaces forward sale proceeds here instead of doing their own payout
+//! math. One `route_sale` call pays the creator royalty reported by the
+//! royalty registry, the protocol treasury's fee, an optional referrer, and
+//! the seller, who receives the remainder. Every marketplace has its own
+//! split: the owner sets the protocol fee, and the marketplace sets its
+//! referral share and the most royalty it will honour.
+//!
+//! ## Features
+//! - Native coin or CRC-20 proceeds
+//! - Royalties looked up per token from the royalty registry
+//! - Per-marketplace protocol, referral and royalty-cap splits
+//! - Side-effect-free previews for listing UIs
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use crc_clients::{Crc20, Crc20Client};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+
+const CONFIG_KEY: &str = "router_config";
+const MARKETPLACES_PREFIX: &str = "marketplaces";
+/// Pass as `currency` to route native coin proceeds
+pub const NATIVE_ASSET: &str = "native";
+const BPS_DENOMINATOR: u64 = 10_000;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Router configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RouterConfig {
+    pub owner: String,
+    /// Receives protocol fees
+    pub treasury: String,
+    /// Answers `royalty_info(collection, token_id)`
+    pub royalty_registry: String,
+}
+
+/// A marketplace's payout split, in basis points of the sale price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Marketplace {
+    /// Treasury fee, set by the router owner
+    pub protocol_bps: u64,
+    /// Referrer share, set by the marketplace
+    pub referral_bps: u64,
+    /// Highest royalty paid, set by the marketplace
+    pub royalty_cap_bps: u64,
+    /// Inactive marketplaces cannot route sales
+    pub active: bool,
+    pub sales: u64,
+}
+
+/// Royalty reported by the registry for a token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RoyaltyInfo {
+    pub receiver: String,
+    pub bps: u16,
+}
+
+/// How a sale price is divided
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SaleSplit {
+    pub seller: u64,
+    pub royalty: u64,
+    pub protocol: u64,
+    pub referral: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    treasury: String,
+    royalty_registry: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetConfigArgs {
+    treasury: String,
+    royalty_registry: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetMarketplaceArgs {
+    marketplace: String,
+    protocol_bps: u64,
+    active: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetSplitsArgs {
+    referral_bps: u64,
+    royalty_cap_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RouteSaleArgs {
+    currency: String,
+    collection: String,
+    token_id: u64,
+    seller: String,
+    price: u64,
+    referrer: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PreviewArgs {
+    marketplace: String,
+    collection: String,
+    token_id: u64,
+    price: u64,
+    with_referrer: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MarketplaceArgs {
+    marketplace: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RoyaltyQueryArgs {
+    collection: String,
+    token_id: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<RouterConfig> {
+    storage()
+        .get::<RouterConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn load_owned_config() -> ContractResult<RouterConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn marketplaces() -> Map<String, Marketplace> {
+    Map::new(MARKETPLACES_PREFIX)
+}
+
+fn load_marketplace(marketplace: &str) -> ContractResult<Marketplace> {
+    marketplaces()
+        .get(&marketplace.to_string())?
+        .ok_or_else(|| {
+            ContractError::InvalidArgument(format!("Unknown marketplace {}", marketplace))
+        })
+}
+
+/// The three configurable shares may not exceed the whole price.
+fn validate_splits(marketplace: &Marketplace) -> ContractResult<()> {
+    let total = marketplace
+        .protocol_bps
+        .saturating_add(marketplace.referral_bps)
+        .saturating_add(marketplace.royalty_cap_bps);
+    if total > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidArgument(format!(
+            "Splits total {} bps, above {}",
+            total, BPS_DENOMINATOR
+        )));
+    }
+    Ok(())
+}
+
+/// Divide `price`; the seller receives whatever the other shares leave.
+///
+/// Each share rounds down, so rounding dust goes to the seller.
+pub fn split_sale(
+    price: u64,
+    royalty_bps: u64,
+    protocol_bps: u64,
+    referral_bps: u64,
+) -> ContractResult<SaleSplit> {
+    let royalty = safe_math::apply_bps(price, royalty_bps, Rounding::Down)?;
+    let protocol = safe_math::apply_bps(price, protocol_bps, Rounding::Down)?;
+    let referral = safe_math::apply_bps(price, referral_bps, Rounding::Down)?;
+    let fees = safe_math::add(safe_math::add(royalty, protocol)?, referral)?;
+    Ok(SaleSplit {
+        seller: safe_math::sub(price, fees)?,
+        royalty,
+        protocol,
+        referral,
+    })
+}
+
+/// Ask the registry for a token's royalty; `None` if it has none.
+fn lookup_royalty(
+    config: &RouterConfig,
+    collection: &str,
+    token_id: u64,
+) -> ContractResult<Option<RoyaltyInfo>> {
+    let args = RoyaltyQueryArgs {
+        collection: collection.to_string(),
+        token_id,
+    };
+    crc_clients::query(&config.royalty_registry, "royalty_info", &args)
+}
+
+/// Work out a sale's split and the royalty receiver.
+fn compute_split(
+    config: &RouterConfig,
+    marketplace: &Marketplace,
+    collection: &str,
+    token_id: u64,
+    price: u64,
+    with_referrer: bool,
+) -> ContractResult<(SaleSplit, Option<String>)> {
+    let royalty = lookup_royalty(config, collection, token_id)?;
+    let royalty_bps = royalty
+        .as_ref()
+        .map_or(0, |info| (info.bps as u64).min(marketplace.royalty_cap_bps));
+    let referral_bps = if with_referrer {
+        marketplace.referral_bps
+    } else {
+        0
+    };
+    let split = split_sale(price, royalty_bps, marketplace.protocol_bps, referral_bps)?;
+    Ok((split, royalty.map(|info| info.receiver)))
+}
+
+/// Pay one share; native value is already here, CRC-20s come from the marketplace.
+fn pay(currency: &str, from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    if currency == NATIVE_ASSET {
+        return context().transfer_tokens(to, amount);
+    }
+    Crc20Client::new(currency).transfer_from(from, to, amount)
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.treasury)?;
+    validation::validate_address(&args.royalty_registry)?;
+    let config = RouterConfig {
+        owner: context().sender().to_string(),
+        treasury: args.treasury,
+        royalty_registry: args.royalty_registry,
+    };
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("RouterInitialized",
+        owner: config.owner,
+        treasury: config.treasury,
+        royalty_registry: config.royalty_registry
+    );
+    Ok(())
+}
+
+fn execute_set_config() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: SetConfigArgs = read_args()?;
+    validation::validate_address(&args.treasury)?;
+    validation::validate_address(&args.royalty_registry)?;
+    let previous_treasury = core::mem::replace(&mut config.treasury, args.treasury);
+    let previous_registry = core::mem::replace(&mut config.royalty_registry, args.royalty_registry);
+    let mut store = storage();
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("RouterConfigUpdated",
+        treasury: config.treasury,
+        royalty_registry: config.royalty_registry
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "treasury",
+        previous_treasury,
+        &config.treasury,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "royalty_registry",
+        previous_registry,
+        &config.royalty_registry,
+    )?;
+    Ok(())
+}
+
+fn execute_set_marketplace() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: SetMarketplaceArgs = read_args()?;
+    validation::validate_address(&args.marketplace)?;
+    let previous = marketplaces().get(&args.marketplace)?;
+    let mut marketplace = previous.clone().unwrap_or(Marketplace {
+        protocol_bps: 0,
+        referral_bps: 0,
+        royalty_cap_bps: 0,
+        active: true,
+        sales: 0,
+    });
+    marketplace.protocol_bps = args.protocol_bps;
+    marketplace.active = args.active;
+    validate_splits(&marketplace)?;
+    marketplaces().set(&args.marketplace, &marketplace)?;
+
+    event!("MarketplaceUpdated",
+        marketplace: args.marketplace,
+        protocol_bps: args.protocol_bps,
+        active: args.active
+    );
+    let terms = |m: &Marketplace| format!("protocol_bps={} active={}", m.protocol_bps, m.active);
+    admin_log::record(
+        "set_marketplace",
+        &args.marketplace,
+        previous.as_ref().map(terms).unwrap_or_default(),
+        terms(&marketplace),
+    )?;
+    Ok(())
+}
+
+fn execute_set_splits() -> ContractResult<()> {
+    let address = context().sender().to_string();
+    let args: SetSplitsArgs = read_args()?;
+    let mut marketplace = load_marketplace(&address)?;
+    marketplace.referral_bps = args.referral_bps;
+    marketplace.royalty_cap_bps = args.royalty_cap_bps;
+    validate_splits(&marketplace)?;
+    marketplaces().set(&address, &marketplace)?;
+
+    event!("SplitsUpdated",
+        marketplace: address,
+        referral_bps: args.referral_bps,
+        royalty_cap_bps: args.royalty_cap_bps
+    );
+    Ok(())
+}
+
+fn execute_route_sale() -> ContractResult<SaleSplit> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let caller = ctx.sender().to_string();
+    let config = load_config()?;
+    let args: RouteSaleArgs = read_args()?;
+    let mut marketplace = load_marketplace(&caller)?;
+    if !marketplace.active {
+        return Err(ContractError::Unauthorized);
+    }
+    validation::validate_address(&args.seller)?;
+    validation::validate_positive_amount(args.price)?;
+    if args.currency != NATIVE_ASSET {
+        validation::validate_address(&args.currency)?;
+    }
+    if let Some(referrer) = &args.referrer {
+        validation::validate_address(referrer)?;
+        if referrer == &args.seller {
+            return Err(ContractError::InvalidArgument(
+                "Seller cannot refer their own sale".to_string(),
+            ));
+        }
+    }
+    let expected_value = if args.currency == NATIVE_ASSET {
+        args.price
+    } else {
+        0
+    };
+    if ctx.value() != expected_value {
+        return Err(ContractError::InvalidArgument(format!(
+            "Attached value must be {}",
+            expected_value
+        )));
+    }
+
+    let (split, royalty_receiver) = compute_split(
+        &config,
+        &marketplace,
+        &args.collection,
+        args.token_id,
+        args.price,
+        args.referrer.is_some(),
+    )?;
+    marketplace.sales = safe_math::add(marketplace.sales, 1)?;
+    marketplaces().set(&caller, &marketplace)?;
+
+    if let Some(receiver) = &royalty_receiver {
+        pay(&args.currency, &caller, receiver, split.royalty)?;
+    }
+    pay(&args.currency, &caller, &config.treasury, split.protocol)?;
+    if let Some(referrer) = &args.referrer {
+        pay(&args.currency, &caller, referrer, split.referral)?;
+    }
+    pay(&args.currency, &caller, &args.seller, split.seller)?;
+
+    event!("SaleRouted",
+        marketplace: caller,
+        collection: args.collection,
+        token_id: args.token_id,
+        currency: args.currency,
+        price: args.price,
+        seller: split.seller,
+        royalty: split.royalty,
+        protocol: split.protocol,
+        referral: split.referral
+    );
+    Ok(split)
+}
+
+fn execute_preview_split() -> ContractResult<SaleSplit> {
+    let config = load_config()?;
+    let args: PreviewArgs = read_args()?;
+    let marketplace = load_marketplace(&args.marketplace)?;
+    let (split, _) = compute_split(
+        &config,
+        &marketplace,
+        &args.collection,
+        args.token_id,
+        args.price,
+        args.with_referrer,
+    )?;
+    Ok(split)
+}
+
+/// Initialize the router; the caller becomes the owner
+///
+/// # Arguments
+/// * `treasury` - Receives protocol fees
+/// * `royalty_registry` - Contract answering `royalty_info(collection, token_id)`
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Change the treasury and royalty registry (owner only)
+///
+/// # Arguments
+/// * `treasury` - Receives protocol fees
+/// * `royalty_registry` - Royalty registry contract
+#[unsafe(no_mangle)]
+pub extern "C" fn set_config() {
+    if let Err(err) = execute_set_config() {
+        log!("set_config failed: {}", err);
+    }
+}
+
+/// Register or update a marketplace (owner only)
+///
+/// # Arguments
+/// * `marketplace` - Marketplace contract that will call `route_sale`
+/// * `protocol_bps` - Treasury fee on its sales
+/// * `active` - Whether it may route sales
+#[unsafe(no_mangle)]
+pub extern "C" fn set_marketplace() {
+    if let Err(err) = execute_set_marketplace() {
+        log!("set_marketplace failed: {}", err);
+    }
+}
+
+/// Set the caller's own referral share and royalty cap (marketplaces only)
+///
+/// Protocol, referral and royalty cap together may not exceed 10_000 bps.
+///
+/// # Arguments
+/// * `referral_bps` - Share paid to referrers
+/// * `royalty_cap_bps` - Highest royalty honoured
+#[unsafe(no_mangle)]
+pub extern "C" fn set_splits() {
+    if let Err(err) = execute_set_splits() {
+        log!("set_splits failed: {}", err);
+    }
+}
+
+/// Pay out a sale (active marketplaces only)
+///
+/// Native proceeds are attached as value. CRC-20 proceeds are paid straight
+/// from the marketplace, which must approve the price.
+///
+/// # Arguments
+/// * `currency` - `"native"` or a CRC-20 address
+/// * `collection` - NFT collection sold from
+/// * `token_id` - Token sold
+/// * `seller` - Receives the remainder
+/// * `price` - Sale price
+/// * `referrer` - Optional referrer; without one the seller keeps the referral share
+///
+/// # Returns
+/// `SaleSplit`
+#[unsafe(no_mangle)]
+pub extern "C" fn route_sale() {
+    if let Err(err) = execute_route_sale().and_then(|split| try_respond(&split)) {
+        log!("route_sale failed: {}", err);
+    }
+}
+
+/// Preview how a sale would be split
+///
+/// # Arguments
+/// * `marketplace` - Marketplace address
+/// * `collection` - NFT collection
+/// * `token_id` - Token
+/// * `price` - Sale price
+/// * `with_referrer` - Whether a referrer would be paid
+///
+/// # Returns
+/// `SaleSplit`
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_split() {
+    if let Err(err) = execute_preview_split().and_then(|split| try_respond(&split)) {
+        log!("preview_split failed: {}", err);
+    }
+}
+
+/// Get a marketplace's split
+///
+/// # Arguments
+/// * `marketplace` - Marketplace address
+///
+/// # Returns
+/// `Marketplace`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_marketplace() {
+    let result = read_args::<MarketplaceArgs>()
+        .and_then(|args| load_marketplace(&args.marketplace))
+        .and_then(|marketplace| try_respond(&marketplace));
+    if let Err(err) = result {
+        log!("get_marketplace failed: {}", err);
+    }
+}
+
+/// Get the router configuration
+///
+/// # Returns
+/// `RouterConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::crc20::TransferFromArgs;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_SELLER: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_CREATOR: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_REFERRER: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_TREASURY: &str = "0x0000000000000000000000000000000000000e05";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const REGISTRY: &str = "0x0000000000000000000000000000000000000f01";
+    const MARKET: &str = "0x0000000000000000000000000000000000000f02";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f03";
+    const COLLECTION: &str = "0x0000000000000000000000000000000000000f04";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// Registry reports `royalty_bps` for token 1 and nothing for others
+    fn register_royalty(royalty_bps: u16) {
+        mock::register_contract(REGISTRY, "royalty_info", move |args| {
+            let query: RoyaltyQueryArgs = postcard::from_bytes(args).unwrap();
+            let info = (query.token_id == 1).then(|| RoyaltyInfo {
+                receiver: ADDR_CREATOR.to_string(),
+                bps: royalty_bps,
+            });
+            Ok(postcard::to_allocvec(&info).unwrap())
+        });
+    }
+
+    /// MARKET charges 2.5% protocol, 1% referral and honours up to 10% royalty
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        register_royalty(500);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                treasury: ADDR_TREASURY.to_string(),
+                royalty_registry: REGISTRY.to_string(),
+            }),
+        );
+        call::<()>(
+            ADDR_OWNER,
+            set_marketplace,
+            encode(&SetMarketplaceArgs {
+                marketplace: MARKET.to_string(),
+                protocol_bps: 250,
+                active: true,
+            }),
+        );
+        call::<()>(
+            MARKET,
+            set_splits,
+            encode(&SetSplitsArgs {
+                referral_bps: 100,
+                royalty_cap_bps: 1_000,
+            }),
+        );
+        mock::take_contract_calls();
+    }
+
+    fn sale(currency: &str, token_id: u64, price: u64, referrer: Option<&str>) -> Vec<u8> {
+        encode(&RouteSaleArgs {
+            currency: currency.to_string(),
+            collection: COLLECTION.to_string(),
+            token_id,
+            seller: ADDR_SELLER.to_string(),
+            price,
+            referrer: referrer.map(str::to_string),
+        })
+    }
+
+    fn route(value: u64, data: Vec<u8>) -> Option<SaleSplit> {
+        mock::set_value(value);
+        let split = call(MARKET, route_sale, data);
+        mock::set_value(0);
+        split
+    }
+
+    #[test]
+    fn sale_pays_every_party() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let split = route(10_000, sale(NATIVE_ASSET, 1, 10_000, Some(ADDR_REFERRER)));
+        assert_eq!(
+            split,
+            Some(SaleSplit {
+                seller: 9_150,
+                royalty: 500,
+                protocol: 250,
+                referral: 100,
+            })
+        );
+        assert_eq!(load_marketplace(MARKET).unwrap().sales, 1);
+        assert!(
+            mock::take_events()
+                .iter()
+                .any(|event| event.topic == "SaleRouted")
+        );
+    }
+
+    #[test]
+    fn royalties_are_capped_and_optional() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        register_royalty(2_500);
+        let split = route(10_000, sale(NATIVE_ASSET, 1, 10_000, None)).unwrap();
+        assert_eq!(
+            (split.royalty, split.referral, split.seller),
+            (1_000, 0, 8_750)
+        );
+
+        let split = route(10_000, sale(NATIVE_ASSET, 2, 10_000, None)).unwrap();
+        assert_eq!((split.royalty, split.seller), (0, 9_750));
+
+        // Rounding dust stays with the seller.
+        assert_eq!(
+            split_sale(999, 500, 250, 100).unwrap(),
+            SaleSplit {
+                seller: 917,
+                royalty: 49,
+                protocol: 24,
+                referral: 9,
+            }
+        );
+    }
+
+    #[test]
+    fn only_active_marketplaces_route_sales() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::set_value(1_000);
+        assert_eq!(
+            call::<SaleSplit>(ADDR_SELLER, route_sale, sale(NATIVE_ASSET, 1, 1_000, None)),
+            None
+        );
+        mock::set_value(0);
+        assert_eq!(route(999, sale(NATIVE_ASSET, 1, 1_000, None)), None);
+        assert_eq!(
+            route(1_000, sale(NATIVE_ASSET, 1, 1_000, Some(ADDR_SELLER))),
+            None
+        );
+
+        call::<()>(
+            ADDR_OWNER,
+            set_marketplace,
+            encode(&SetMarketplaceArgs {
+                marketplace: MARKET.to_string(),
+                protocol_bps: 250,
+                active: false,
+            }),
+        );
+        assert_eq!(route(1_000, sale(NATIVE_ASSET, 1, 1_000, None)), None);
+        assert_eq!(load_marketplace(MARKET).unwrap().sales, 0);
+        // Registered in setup, then deactivated.
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+    }
+
+    #[test]
+    fn splits_cannot_exceed_the_price() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        call::<()>(
+            MARKET,
+            set_splits,
+            encode(&SetSplitsArgs {
+                referral_bps: 500,
+                royalty_cap_bps: 9_300,
+            }),
+        );
+        call::<()>(
+            ADDR_OWNER,
+            set_marketplace,
+            encode(&SetMarketplaceArgs {
+                marketplace: MARKET.to_string(),
+                protocol_bps: 9_000,
+                active: true,
+            }),
+        );
+        call::<()>(
+            ADDR_SELLER,
+            set_splits,
+            encode(&SetSplitsArgs {
+                referral_bps: 0,
+                royalty_cap_bps: 0,
+            }),
+        );
+        let market = load_marketplace(MARKET).unwrap();
+        assert_eq!(
+            (
+                market.protocol_bps,
+                market.referral_bps,
+                market.royalty_cap_bps
+            ),
+            (250, 100, 1_000)
+        );
+
+        let preview = call::<SaleSplit>(
+            ADDR_SELLER,
+            preview_split,
+            encode(&PreviewArgs {
+                marketplace: MARKET.to_string(),
+                collection: COLLECTION.to_string(),
+                token_id: 1,
+                price: 10_000,
+                with_referrer: true,
+            }),
+        );
+        assert_eq!(preview.map(|split| split.seller), Some(9_150));
+    }
+
+    #[test]
+    fn token_proceeds_come_from_the_marketplace() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(route(10_000, sale(TOKEN, 1, 10_000, None)), None);
+        mock::take_contract_calls();
+        route(0, sale(TOKEN, 1, 10_000, Some(ADDR_REFERRER))).unwrap();
+
+        let payouts: Vec<(String, u64)> = mock::take_contract_calls()
+            .into_iter()
+            .filter(|call| call.address == TOKEN)
+            .map(|call| {
+                let args: TransferFromArgs = postcard::from_bytes(&call.args).unwrap();
+                assert_eq!(args.from, MARKET);
+                (args.to.into(), args.amount)
+            })
+            .collect();
+        assert_eq!(
+            payouts,
+            vec![
+                (ADDR_CREATOR.to_string(), 500),
+                (ADDR_TREASURY.to_string(), 250),
+                (ADDR_REFERRER.to_string(), 100),
+                (ADDR_SELLER.to_string(), 9_150),
+            ]
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "fee-router-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! WAD/RAY fixed-point math
+//!
+//! Decimal fixed-point on top of [`biguint::U256`]: a WAD carries 18
+//! decimals (`1.0 == 10^18`) and a RAY carries 27 (`1.0 == 10^27`). WADs
+//! suit prices and token amounts, RAYs suit per-second interest rates where
+//! the extra precision survives compounding.
+//!
+//! Every operation goes through [`mul_div`], so intermediate products never
+//! overflow and the rounding direction is explicit. The plain helpers round
+//! down; the `_up` variants round up. Round against the user: down when
+//! paying out, up when charging.
+//!
+//! ## Embedding
+//! ```toml
+//! fixed-point = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use fixed_point::{RAY, ray_mul, compound_ray};
+//!
+//! let growth = compound_ray(config.rate_per_second, elapsed)?;
+//! market.borrow_index = ray_mul(market.borrow_index, growth)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+pub use biguint::U256;
+use biguint::safe_math;
+pub use biguint::safe_math::{Rounding, mul_div};
+use silica_contract_sdk::error::{ContractError, ContractResult};
+
+/// 1.0 with 18 decimals
+pub const WAD: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+/// 1.0 with 27 decimals
+pub const RAY: U256 = U256::from_limbs([11_515_845_246_265_065_472, 54_210_108, 0, 0]);
+/// RAY / WAD
+pub const WAD_RAY_RATIO: U256 = U256::from_limbs([1_000_000_000, 0, 0, 0]);
+/// Basis points in 100%
+pub const BPS: u64 = safe_math::BPS_DENOMINATOR;
+
+/// `a * b` for WADs, rounding down.
+pub fn wad_mul(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, b, WAD, Rounding::Down)
+}
+
+/// `a * b` for WADs, rounding up.
+pub fn wad_mul_up(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, b, WAD, Rounding::Up)
+}
+
+/// `a / b` for WADs, rounding down.
+pub fn wad_div(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, WAD, b, Rounding::Down)
+}
+
+/// `a / b` for WADs, rounding up.
+pub fn wad_div_up(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, WAD, b, Rounding::Up)
+}
+
+/// `a * b` for RAYs, rounding down.
+pub fn ray_mul(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, b, RAY, Rounding::Down)
+}
+
+/// `a * b` for RAYs, rounding up.
+pub fn ray_mul_up(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, b, RAY, Rounding::Up)
+}
+
+/// `a / b` for RAYs, rounding down.
+pub fn ray_div(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, RAY, b, Rounding::Down)
+}
+
+/// `a / b` for RAYs, rounding up.
+pub fn ray_div_up(a: U256, b: U256) -> ContractResult<U256> {
+    mul_div(a, RAY, b, Rounding::Up)
+}
+
+pub fn wad_to_ray(wad: U256) -> ContractResult<U256> {
+    wad.checked_mul(WAD_RAY_RATIO)
+        .ok_or(ContractError::Overflow)
+}
+
+pub fn ray_to_wad(ray: U256, rounding: Rounding) -> ContractResult<U256> {
+    mul_div(ray, U256::ONE, WAD_RAY_RATIO, rounding)
+}
+
+/// `base ^ exp` for a RAY base, by squaring with half-up rounding.
+pub fn ray_pow(base: U256, mut exp: u64) -> ContractResult<U256> {
+    let mut base = base;
+    let mut result = RAY;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_div(result, base, RAY, Rounding::HalfUp)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = mul_div(base, base, RAY, Rounding::HalfUp)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Growth factor `(1 + rate) ^ periods` as a RAY, for a per-period RAY rate.
+pub fn compound_ray(rate: U256, periods: u64) -> ContractResult<U256> {
+    let base = RAY.checked_add(rate).ok_or(ContractError::Overflow)?;
+    ray_pow(base, periods)
+}
+
+/// Growth factor `1 + rate * periods` as a RAY, for a per-period RAY rate.
+pub fn linear_ray(rate: U256, periods: u64) -> ContractResult<U256> {
+    rate.checked_mul(U256::from(periods))
+        .and_then(|interest| interest.checked_add(RAY))
+        .ok_or(ContractError::Overflow)
+}
+
+/// `bps` basis points of `amount`; fails above 100%.
+pub fn bps_of(amount: U256, bps: u64, rounding: Rounding) -> ContractResult<U256> {
+    safe_math::apply_bps(amount, bps, rounding)
+}
+
+/// A basis-point value as a WAD fraction (`10_000` bps is `WAD`).
+pub fn bps_to_wad(bps: u64) -> U256 {
+    U256::from(bps) * (WAD / U256::from(BPS))
+}
+
+/// `part / whole` as a WAD fraction, rounding down.
+pub fn ratio_wad(part: U256, whole: U256) -> ContractResult<U256> {
+    mul_div(part, WAD, whole, Rounding::Down)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn wad(units: u64) -> U256 {
+        U256::from(units) * WAD
+    }
+
+    #[test]
+    fn constants_have_the_right_scale() {
+        assert_eq!(WAD.to_string(), "1000000000000000000");
+        assert_eq!(RAY.to_string(), "1000000000000000000000000000");
+        assert_eq!(WAD * WAD_RAY_RATIO, RAY);
+        assert_eq!(bps_to_wad(BPS), WAD);
+        assert_eq!(bps_to_wad(250), WAD / U256::from(40u64));
+    }
+
+    #[test]
+    fn rounding_modes_differ_only_on_remainders() {
+        let (ten, three) = (U256::from(10u64), U256::from(3u64));
+        assert_eq!(
+            mul_div(ten, U256::ONE, three, Rounding::Down).unwrap(),
+            three
+        );
+        assert_eq!(
+            mul_div(ten, U256::ONE, three, Rounding::Up).unwrap(),
+            U256::from(4u64)
+        );
+        assert_eq!(
+            mul_div(ten, U256::ONE, three, Rounding::HalfUp).unwrap(),
+            three
+        );
+        assert_eq!(
+            mul_div(
+                U256::from(5u64),
+                U256::ONE,
+                U256::from(2u64),
+                Rounding::HalfUp
+            )
+            .unwrap(),
+            three
+        );
+        for rounding in [Rounding::Down, Rounding::Up, Rounding::HalfUp] {
+            assert_eq!(
+                mul_div(ten, three, U256::from(6u64), rounding).unwrap(),
+                U256::from(5u64)
+            );
+        }
+
+        // Remainders are found even when the product needs 512 bits.
+        let max = U256::MAX;
+        assert_eq!(mul_div(max, max, max, Rounding::Up).unwrap(), max);
+        assert_eq!(
+            mul_div(max, U256::from(2u64), U256::from(4u64), Rounding::Up).unwrap(),
+            (max >> 1) + U256::ONE
+        );
+        assert!(matches!(
+            mul_div(max, U256::from(2u64), U256::ONE, Rounding::Down),
+            Err(ContractError::Overflow)
+        ));
+        assert!(matches!(
+            mul_div(ten, ten, U256::ZERO, Rounding::Down),
+            Err(ContractError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn wad_and_ray_arithmetic() {
+        assert_eq!(wad_mul(wad(3), wad(4)).unwrap(), wad(12));
+        assert_eq!(
+            wad_div(wad(1), wad(3)).unwrap().to_string(),
+            "333333333333333333"
+        );
+        assert_eq!(
+            wad_div_up(wad(1), wad(3)).unwrap().to_string(),
+            "333333333333333334"
+        );
+        assert_eq!(wad_mul_up(U256::ONE, U256::ONE).unwrap(), U256::ONE);
+        assert_eq!(wad_mul(U256::ONE, U256::ONE).unwrap(), U256::ZERO);
+        assert_eq!(ray_mul(RAY, RAY).unwrap(), RAY);
+        assert_eq!(
+            ray_div(RAY, RAY * U256::from(4u64)).unwrap(),
+            RAY / U256::from(4u64)
+        );
+        assert_eq!(
+            ray_div_up(U256::ONE, RAY * U256::from(2u64)).unwrap(),
+            U256::ONE
+        );
+        assert_eq!(wad_to_ray(wad(2)).unwrap(), RAY * U256::from(2u64));
+        assert_eq!(
+            ray_to_wad(RAY + U256::from(500_000_000u64), Rounding::HalfUp).unwrap(),
+            WAD + U256::ONE
+        );
+    }
+
+    #[test]
+    fn compounding_tracks_the_closed_form() {
+        // 5% a year compounded yearly for ten years: 1.05^10 = 1.628894626777441...
+        let five_percent = RAY / U256::from(20u64);
+        let growth = compound_ray(five_percent, 10).unwrap();
+        assert_eq!(
+            ray_to_wad(growth, Rounding::Down).unwrap().to_string(),
+            "1628894626777441406"
+        );
+        assert_eq!(compound_ray(five_percent, 0).unwrap(), RAY);
+        assert_eq!(
+            linear_ray(five_percent, 10).unwrap(),
+            RAY + RAY / U256::from(2u64)
+        );
+
+        // Per-second compounding approaches, but stays below, e^(rate * t).
+        let per_second = RAY / U256::from(31_536_000u64 * 10); // 10% a year
+        let year = compound_ray(per_second, 31_536_000).unwrap();
+        let wad_year = ray_to_wad(year, Rounding::Down).unwrap();
+        assert!(wad_year > U256::from(1_105_170_917_000_000_000u64));
+        assert!(wad_year < U256::from(1_105_170_918_075_647_000u64)); // e^0.1
+    }
+
+    #[test]
+    fn basis_points_and_ratios() {
+        let amount = U256::from(999u64);
+        assert_eq!(
+            bps_of(amount, 250, Rounding::Down).unwrap(),
+            U256::from(24u64)
+        );
+        assert_eq!(
+            bps_of(amount, 250, Rounding::Up).unwrap(),
+            U256::from(25u64)
+        );
+        assert_eq!(bps_of(amount, BPS, Rounding::Down).unwrap(), amount);
+        assert_eq!(
+            ratio_wad(U256::from(1u64), U256::from(4u64)).unwrap(),
+            WAD / U256::from(4u64)
+        );
+        assert!(ratio_wad(U256::ONE, U256::ZERO).is_err());
+    }
+}
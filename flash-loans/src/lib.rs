@@ -0,0 +1,1131 @@
+//! Flash Loan Provider
+//!
+//! Pools CRC-20 liquidity per token and lends any part of it for the span of
+//! a single call. The loan is sent to a borrower contract, which receives an
+//! `on_flash_loan` callback and must approve the pool for the principal plus
+//! fee before returning. The pool then pulls the repayment; if the callback
+//! fails, returns anything but the success marker, or the repayment cannot be
+//! pulled, the whole call fails and the transaction reverts.
+//!
+//! Liquidity providers hold shares of a token's pool. Fees stay in the pool,
+//! so each share redeems for more tokens as loans are taken.
+//!
+//! ## Features
+//! - One pool per CRC-20, created by its first deposit
+//! - Callback-based loans with atomic repayment
+//! - Fees accrue to liquidity providers through the share price
+//! - Owner-set fee, capped at 1%
+//! - Per-token withdrawal limits backed by a circuit breaker
+//! - Decommissioning: after the owner's notice runs out, deposits and loans
+//!   stop for good while providers withdraw
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::circuit_breaker::{self, Trip};
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "flash_config";
+const RESERVES_PREFIX: &str = "reserves";
+const SHARES_PREFIX: &str = "shares";
+/// Value the borrower's `on_flash_loan` must return
+pub const CALLBACK_SUCCESS: &str = "FlashBorrower.on_flash_loan";
+const BPS_DENOMINATOR: u64 = 10_000;
+const MAX_FEE_BPS: u64 = 100;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Provider configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FlashLoanConfig {
+    pub owner: String,
+    pub fee_bps: u64,
+}
+
+/// Pooled liquidity of one token
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reserve {
+    /// Tokens owed to providers, including earned fees
+    pub liquidity: u64,
+    pub total_shares: u64,
+    pub fees_earned: u64,
+    pub loans: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepositArgs {
+    token: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WithdrawArgs {
+    token: String,
+    shares: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FlashLoanArgs {
+    receiver: String,
+    token: String,
+    amount: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetFeeArgs {
+    fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutflowLimitArgs {
+    token: String,
+    max_outflow: u64,
+    window: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenArgs {
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FlashFeeArgs {
+    token: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SharesOfArgs {
+    token: String,
+    provider: String,
+}
+
+/// Arguments of the borrower's `on_flash_loan` callback
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OnFlashLoanArgs {
+    /// Account that requested the loan
+    pub initiator: String,
+    pub token: String,
+    pub amount: u64,
+    pub fee: u64,
+    /// Opaque payload forwarded from the `flash_loan` caller
+    pub data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<FlashLoanConfig> {
+    storage()
+        .get::<FlashLoanConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn reserves() -> Map<String, Reserve> {
+    Map::new(RESERVES_PREFIX)
+}
+
+fn shares() -> Map<(String, String), u64> {
+    Map::new(SHARES_PREFIX)
+}
+
+fn load_reserve(token: &str) -> ContractResult<Reserve> {
+    Ok(reserves().get(&token.to_string())?.unwrap_or_default())
+}
+
+fn pull_tokens(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn validate_fee(fee_bps: u64) -> ContractResult<()> {
+    if fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Fee cannot exceed {} bps",
+            MAX_FEE_BPS
+        )));
+    }
+    Ok(())
+}
+
+/// Fee on a loan of `amount`, rounded up so small loans are not free.
+pub fn flash_fee(fee_bps: u64, amount: u64) -> u64 {
+    let scaled = amount as u128 * fee_bps as u128;
+    scaled.div_ceil(BPS_DENOMINATOR as u128) as u64
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validate_fee(args.fee_bps)?;
+    storage().set(
+        CONFIG_KEY,
+        &FlashLoanConfig {
+            owner: ctx.sender().to_string(),
+            fee_bps: args.fee_bps,
+        },
+    )?;
+
+    event!("FlashLoansInitialized",
+        owner: ctx.sender(),
+        fee_bps: args.fee_bps
+    );
+    Ok(())
+}
+
+fn execute_deposit() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    circuit_breaker::when_not_tripped()?;
+    let ctx = context();
+    let provider = ctx.sender().to_string();
+    load_config()?;
+    decommission::when_active()?;
+    let args: DepositArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    validation::validate_positive_amount(args.amount)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    let mut reserve = load_reserve(&args.token)?;
+    let minted = if reserve.total_shares == 0 {
+        args.amount
+    } else {
+        (args.amount as u128 * reserve.total_shares as u128 / reserve.liquidity as u128) as u64
+    };
+    if minted == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Deposit too small for one share".to_string(),
+        ));
+    }
+    reserve.liquidity = safe_math::add(reserve.liquidity, args.amount)?;
+    reserve.total_shares = safe_math::add(reserve.total_shares, minted)?;
+    reserves().set(&args.token, &reserve)?;
+    let key = (args.token.clone(), provider.clone());
+    let held = shares().get(&key)?.unwrap_or(0);
+    shares().set(&key, &safe_math::add(held, minted)?)?;
+
+    pull_tokens(&args.token, &provider, args.amount)?;
+
+    event!("Deposited",
+        provider: provider,
+        token: args.token,
+        amount: args.amount,
+        shares: minted
+    );
+    Ok(minted)
+}
+
+fn execute_withdraw() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let provider = context().sender().to_string();
+    let args: WithdrawArgs = read_args()?;
+    validation::validate_positive_amount(args.shares)?;
+    let key = (args.token.clone(), provider.clone());
+    let held = shares().get(&key)?.unwrap_or(0);
+    if held < args.shares {
+        return Err(ContractError::InsufficientBalance {
+            required: args.shares,
+            available: held,
+        });
+    }
+    let mut reserve = load_reserve(&args.token)?;
+    let amount =
+        (args.shares as u128 * reserve.liquidity as u128 / reserve.total_shares as u128) as u64;
+    circuit_breaker::record_outflow(&args.token, amount)?;
+    reserve.liquidity -= amount;
+    reserve.total_shares -= args.shares;
+    reserves().set(&args.token, &reserve)?;
+    shares().set(&key, &(held - args.shares))?;
+
+    if amount > 0 {
+        send_tokens(&args.token, &provider, amount)?;
+    }
+
+    event!("Withdrawn",
+        provider: provider,
+        token: args.token,
+        amount: amount,
+        shares: args.shares
+    );
+    Ok(amount)
+}
+
+fn execute_flash_loan() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    circuit_breaker::when_not_tripped()?;
+    let ctx = context();
+    let initiator = ctx.sender().to_string();
+    let config = load_config()?;
+    decommission::when_active()?;
+    let args: FlashLoanArgs = read_args()?;
+    validation::validate_address(&args.receiver)?;
+    validation::validate_positive_amount(args.amount)?;
+    if !ctx.is_contract(&args.receiver) {
+        return Err(ContractError::InvalidArgument(
+            "Receiver must be a contract".to_string(),
+        ));
+    }
+    let mut reserve = load_reserve(&args.token)?;
+    if args.amount > reserve.liquidity {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: reserve.liquidity,
+        });
+    }
+    let fee = flash_fee(config.fee_bps, args.amount);
+    let repayment = safe_math::add(args.amount, fee)?;
+
+    send_tokens(&args.token, &args.receiver, args.amount)?;
+    let callback = encode_call(&OnFlashLoanArgs {
+        initiator: initiator.clone(),
+        token: args.token.clone(),
+        amount: args.amount,
+        fee,
+        data: args.data,
+    })?;
+    let response = ctx.call_contract(&args.receiver, "on_flash_loan", &callback)?;
+    let marker: String =
+        postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)?;
+    if marker != CALLBACK_SUCCESS {
+        return Err(ContractError::ContractCallFailed(
+            "Borrower callback did not succeed".to_string(),
+        ));
+    }
+    pull_tokens(&args.token, &args.receiver, repayment)?;
+
+    // Only book the fee once the repayment is in.
+    reserve.liquidity = safe_math::add(reserve.liquidity, fee)?;
+    reserve.fees_earned = safe_math::add(reserve.fees_earned, fee)?;
+    reserve.loans = safe_math::add(reserve.loans, 1)?;
+    reserves().set(&args.token, &reserve)?;
+
+    event!("FlashLoan",
+        initiator: initiator,
+        receiver: args.receiver,
+        token: args.token,
+        amount: args.amount,
+        fee: fee
+    );
+    Ok(fee)
+}
+
+fn execute_set_fee() -> ContractResult<()> {
+    let mut config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: SetFeeArgs = read_args()?;
+    validate_fee(args.fee_bps)?;
+    let previous = config.fee_bps;
+    config.fee_bps = args.fee_bps;
+    storage().set(CONFIG_KEY, &config)?;
+
+    event!("FeeUpdated", fee_bps: args.fee_bps);
+    admin_log::record(admin_log::SET_PARAMETER, "fee_bps", previous, args.fee_bps)?;
+    Ok(())
+}
+
+fn execute_flash_fee() -> ContractResult<u64> {
+    let args: FlashFeeArgs = read_args()?;
+    let config = load_config()?;
+    let reserve = load_reserve(&args.token)?;
+    if args.amount > reserve.liquidity {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: reserve.liquidity,
+        });
+    }
+    Ok(flash_fee(config.fee_bps, args.amount))
+}
+
+fn execute_pause() -> ContractResult<()> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    pausable::unpause()
+}
+
+fn execute_set_outflow_limit() -> ContractResult<()> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: OutflowLimitArgs = read_args()?;
+    if args.max_outflow == 0 {
+        return circuit_breaker::remove_limit(&args.token);
+    }
+    circuit_breaker::set_limit(
+        &args.token,
+        args.max_outflow,
+        Duration::from_secs(args.window),
+    )
+}
+
+fn execute_reset_circuit_breaker() -> ContractResult<()> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    circuit_breaker::reset()
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_circuit_breaker_status() -> ContractResult<Option<Trip>> {
+    let trip = circuit_breaker::tripped()?;
+    try_respond(&trip)?;
+    Ok(trip)
+}
+
+/// Configure the provider; the caller becomes its owner
+///
+/// # Arguments
+/// * `fee_bps` - Loan fee (max 100)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Add liquidity to a token's pool
+///
+/// # Arguments
+/// * `token` - CRC-20 deposited; the caller must have approved the pool
+/// * `amount` - Tokens deposited
+///
+/// # Returns
+/// Shares minted (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    if let Err(err) = execute_deposit().and_then(|minted| try_respond(&minted)) {
+        log!("deposit failed: {}", err);
+    }
+}
+
+/// Redeem shares for their part of the pool, fees included
+///
+/// # Arguments
+/// * `token` - Pool token
+/// * `shares` - Shares redeemed
+///
+/// # Returns
+/// Tokens received (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw() {
+    if let Err(err) = execute_withdraw().and_then(|amount| try_respond(&amount)) {
+        log!("withdraw failed: {}", err);
+    }
+}
+
+/// Lend tokens to `receiver` for the duration of its `on_flash_loan` callback
+///
+/// The receiver gets `amount`, then `on_flash_loan(initiator, token, amount,
+/// fee, data)`. It must return `CALLBACK_SUCCESS` and have approved the pool
+/// for `amount + fee`, which is pulled back before this call returns.
+///
+/// # Arguments
+/// * `receiver` - Borrower contract
+/// * `token` - Token borrowed
+/// * `amount` - Amount borrowed, up to the pool's liquidity
+/// * `data` - Forwarded to the callback
+///
+/// # Returns
+/// Fee paid (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn flash_loan() {
+    if let Err(err) = execute_flash_loan().and_then(|fee| try_respond(&fee)) {
+        log!("flash_loan failed: {}", err);
+    }
+}
+
+/// Update the loan fee (owner only)
+///
+/// # Arguments
+/// * `fee_bps` - Loan fee (max 100)
+#[unsafe(no_mangle)]
+pub extern "C" fn set_fee() {
+    if let Err(err) = execute_set_fee() {
+        log!("set_fee failed: {}", err);
+    }
+}
+
+/// Get the provider configuration
+///
+/// # Returns
+/// `FlashLoanConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get a token's pool
+///
+/// # Arguments
+/// * `token` - Pool token
+///
+/// # Returns
+/// `Reserve`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_reserve() {
+    let result = read_args::<TokenArgs>()
+        .and_then(|args| load_reserve(&args.token))
+        .and_then(|reserve| try_respond(&reserve));
+    if let Err(err) = result {
+        log!("get_reserve failed: {}", err);
+    }
+}
+
+/// Get the shares a provider holds in a token's pool
+///
+/// # Arguments
+/// * `token` - Pool token
+/// * `provider` - Liquidity provider
+///
+/// # Returns
+/// Shares (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn shares_of() {
+    let result = read_args::<SharesOfArgs>()
+        .and_then(|args| Ok(shares().get(&(args.token, args.provider))?.unwrap_or(0)))
+        .and_then(|held| try_respond(&held));
+    if let Err(err) = result {
+        log!("shares_of failed: {}", err);
+    }
+}
+
+/// Get the largest loan currently available for a token
+///
+/// # Arguments
+/// * `token` - Token to borrow
+///
+/// # Returns
+/// Amount (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn max_flash_loan() {
+    let result = read_args::<TokenArgs>()
+        .and_then(|args| load_reserve(&args.token))
+        .and_then(|reserve| try_respond(&reserve.liquidity));
+    if let Err(err) = result {
+        log!("max_flash_loan failed: {}", err);
+    }
+}
+
+/// Quote the fee on a loan
+///
+/// # Arguments
+/// * `token` - Token to borrow
+/// * `amount` - Amount to borrow, up to the pool's liquidity
+///
+/// # Returns
+/// Fee (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn quote_flash_fee() {
+    if let Err(err) = execute_flash_fee().and_then(|fee| try_respond(&fee)) {
+        log!("quote_flash_fee failed: {}", err);
+    }
+}
+
+/// Pause the contract (owner only)
+///
+/// Deposits and loans fail until `unpause`; withdrawals stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the contract is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Limit how much of a token providers may withdraw per window (owner only)
+///
+/// A withdrawal that would exceed the limit trips the circuit breaker, which
+/// halts deposits, withdrawals and loans until `reset_circuit_breaker`.
+///
+/// # Arguments
+/// * `token` - Pooled CRC-20
+/// * `max_outflow` - Tokens allowed per window; 0 removes the limit
+/// * `window` - Window length in seconds
+#[unsafe(no_mangle)]
+pub extern "C" fn set_outflow_limit() {
+    if let Err(err) = execute_set_outflow_limit() {
+        log!("set_outflow_limit failed: {}", err);
+    }
+}
+
+/// Clear a tripped circuit breaker (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn reset_circuit_breaker() {
+    if let Err(err) = execute_reset_circuit_breaker() {
+        log!("reset_circuit_breaker failed: {}", err);
+    }
+}
+
+/// Give notice that the provider will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the provider once the notice has run out (owner only)
+///
+/// Deposits and loans stop for good; providers can still withdraw.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the provider is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Query the circuit breaker
+///
+/// # Returns
+/// `Option<Trip>` - The withdrawal that tripped it, if tripped
+#[unsafe(no_mangle)]
+pub extern "C" fn circuit_breaker_status() {
+    if let Err(err) = execute_circuit_breaker_status() {
+        log!("circuit_breaker_status failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const BORROWER: &str = "0x0000000000000000000000000000000000000f02";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// 9 bps fee; the borrower answers `on_flash_loan` with `marker`
+    fn setup(marker: &'static str) {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        for method in ["transfer", "transfer_from"] {
+            mock::register_contract(TOKEN, method, |_| Ok(Vec::new()));
+        }
+        mock::register_contract(BORROWER, "on_flash_loan", move |_| {
+            Ok(postcard::to_allocvec(&marker.to_string()).unwrap())
+        });
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs { fee_bps: 9 }),
+        );
+    }
+
+    fn deposit_as(sender: &str, amount: u64) -> Option<u64> {
+        call(
+            sender,
+            deposit,
+            encode(&DepositArgs {
+                token: TOKEN.to_string(),
+                amount,
+            }),
+        )
+    }
+
+    fn borrow(amount: u64) -> Option<u64> {
+        call(
+            ADDR_BOB,
+            flash_loan,
+            encode(&FlashLoanArgs {
+                receiver: BORROWER.to_string(),
+                token: TOKEN.to_string(),
+                amount,
+                data: vec![7, 7],
+            }),
+        )
+    }
+
+    #[test]
+    fn loans_are_sent_called_back_and_repaid_with_fee() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        deposit_as(ADDR_ALICE, 1_000_000).unwrap();
+        mock::take_contract_calls();
+
+        assert_eq!(borrow(1_000_000), Some(900));
+        let calls = mock::take_contract_calls();
+        let methods: Vec<_> = calls
+            .iter()
+            .map(|c| (c.address.as_str(), c.method.as_str()))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                (TOKEN, "transfer"),
+                (BORROWER, "on_flash_loan"),
+                (TOKEN, "transfer_from")
+            ]
+        );
+        let lent: TokenTransferArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!((lent.to.as_str(), lent.amount), (BORROWER, 1_000_000));
+        let callback: OnFlashLoanArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!(
+            callback,
+            OnFlashLoanArgs {
+                initiator: ADDR_BOB.to_string(),
+                token: TOKEN.to_string(),
+                amount: 1_000_000,
+                fee: 900,
+                data: vec![7, 7],
+            }
+        );
+        let repaid: TokenTransferFromArgs = postcard::from_bytes(&calls[2].args).unwrap();
+        assert_eq!(
+            (repaid.from.as_str(), repaid.to.as_str(), repaid.amount),
+            (BORROWER, CONTRACT, 1_000_900)
+        );
+        let reserve = load_reserve(TOKEN).unwrap();
+        assert_eq!(
+            (reserve.liquidity, reserve.fees_earned, reserve.loans),
+            (1_000_900, 900, 1)
+        );
+    }
+
+    #[test]
+    fn pausing_stops_deposits_and_loans_but_not_withdrawals() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        deposit_as(ADDR_ALICE, 1_000_000).unwrap();
+
+        call::<()>(ADDR_ALICE, pause, Vec::new());
+        assert!(!pausable::paused().unwrap());
+        call::<()>(ADDR_OWNER, pause, Vec::new());
+        assert_eq!(call::<bool>(ADDR_BOB, paused, Vec::new()), Some(true));
+
+        assert_eq!(deposit_as(ADDR_ALICE, 1_000), None);
+        assert_eq!(borrow(1_000), None);
+        let withdrawn = call::<u64>(
+            ADDR_ALICE,
+            withdraw,
+            encode(&WithdrawArgs {
+                token: TOKEN.to_string(),
+                shares: 500_000,
+            }),
+        );
+        assert_eq!(withdrawn, Some(500_000));
+
+        call::<()>(ADDR_OWNER, unpause, Vec::new());
+        assert_eq!(borrow(1_000), Some(1));
+    }
+
+    #[test]
+    fn decommissioning_stops_deposits_and_loans_but_not_withdrawals() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        deposit_as(ADDR_ALICE, 1_000_000).unwrap();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call::<u64>(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        // Loans continue during the notice.
+        assert_eq!(borrow(1_000), Some(1));
+        mock::set_block_timestamp(eta);
+        call::<()>(ADDR_OWNER, decommission, Vec::new());
+        let status =
+            call::<decommission::DecommissionStatus>(ADDR_BOB, decommission_status, Vec::new())
+                .unwrap();
+        assert_eq!(status.decommissioned_at, Some(eta));
+
+        assert_eq!(deposit_as(ADDR_BOB, 1_000), None);
+        assert_eq!(borrow(1_000), None);
+        let withdrawn = call::<u64>(
+            ADDR_ALICE,
+            withdraw,
+            encode(&WithdrawArgs {
+                token: TOKEN.to_string(),
+                shares: 1_000_000,
+            }),
+        );
+        assert_eq!(withdrawn, Some(1_000_001));
+    }
+
+    #[test]
+    fn withdrawal_limit_trips_circuit_breaker() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        deposit_as(ADDR_ALICE, 1_000_000).unwrap();
+        call::<()>(
+            ADDR_OWNER,
+            set_outflow_limit,
+            encode(&OutflowLimitArgs {
+                token: TOKEN.to_string(),
+                max_outflow: 500_000,
+                window: 3_600,
+            }),
+        );
+        let withdraw_as_alice = |shares| {
+            call::<u64>(
+                ADDR_ALICE,
+                withdraw,
+                encode(&WithdrawArgs {
+                    token: TOKEN.to_string(),
+                    shares,
+                }),
+            )
+        };
+
+        assert_eq!(withdraw_as_alice(400_000), Some(400_000));
+        assert_eq!(withdraw_as_alice(200_000), None);
+        assert_eq!(load_reserve(TOKEN).unwrap().liquidity, 600_000);
+        let trip = call::<Option<Trip>>(ADDR_BOB, circuit_breaker_status, Vec::new());
+        assert_eq!(trip.flatten().map(|trip| trip.amount), Some(200_000));
+        assert_eq!(borrow(1_000), None);
+        assert_eq!(deposit_as(ADDR_ALICE, 1_000), None);
+
+        call::<()>(ADDR_ALICE, reset_circuit_breaker, Vec::new());
+        assert_eq!(withdraw_as_alice(1), None);
+        call::<()>(ADDR_OWNER, reset_circuit_breaker, Vec::new());
+        assert_eq!(withdraw_as_alice(200_000), Some(200_000));
+    }
+
+    #[test]
+    fn failed_callbacks_and_repayments_abort_the_loan() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup("nope");
+        deposit_as(ADDR_ALICE, 1_000_000).unwrap();
+        mock::take_contract_calls();
+        assert_eq!(borrow(1_000), None);
+        assert!(
+            mock::take_contract_calls()
+                .iter()
+                .all(|c| c.method != "transfer_from")
+        );
+        assert_eq!(load_reserve(TOKEN).unwrap().loans, 0);
+
+        // The borrower never approved the repayment.
+        setup(CALLBACK_SUCCESS);
+        deposit_as(ADDR_ALICE, 1_000_000).unwrap();
+        mock::register_contract(TOKEN, "transfer_from", |_| {
+            Err(ContractError::TransferFailed)
+        });
+        assert_eq!(borrow(1_000), None);
+        let reserve = load_reserve(TOKEN).unwrap();
+        assert_eq!((reserve.liquidity, reserve.loans), (1_000_000, 0));
+    }
+
+    #[test]
+    fn borrowers_cannot_reenter_the_pool() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        deposit_as(ADDR_ALICE, 1_000_000).unwrap();
+        let inner = Arc::new(Mutex::new(None));
+        let seen = inner.clone();
+        mock::register_contract(BORROWER, "on_flash_loan", move |_| {
+            *seen.lock().unwrap() = Some(matches!(
+                execute_withdraw(),
+                Err(ContractError::ReentrancyDetected)
+            ));
+            Ok(postcard::to_allocvec(&CALLBACK_SUCCESS.to_string()).unwrap())
+        });
+        assert_eq!(borrow(500_000), Some(450));
+        assert_eq!(*inner.lock().unwrap(), Some(true));
+
+        // Loans are limited to pooled liquidity and contract receivers.
+        assert_eq!(borrow(1_000_451), None);
+        let to_wallet = call::<u64>(
+            ADDR_BOB,
+            flash_loan,
+            encode(&FlashLoanArgs {
+                receiver: ADDR_BOB.to_string(),
+                token: TOKEN.to_string(),
+                amount: 1,
+                data: Vec::new(),
+            }),
+        );
+        assert_eq!(to_wallet, None);
+    }
+
+    #[test]
+    fn fees_grow_provider_shares() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        assert_eq!(deposit_as(ADDR_ALICE, 1_000_000), Some(1_000_000));
+        for _ in 0..10 {
+            borrow(1_000_000).unwrap();
+        }
+        // 9_000 in fees: Bob's later deposit buys fewer shares.
+        let bob_shares = deposit_as(ADDR_BOB, 1_009_000).unwrap();
+        assert_eq!(bob_shares, 1_000_000);
+
+        let withdraw_all = |sender: &str, shares: u64| {
+            call::<u64>(
+                sender,
+                withdraw,
+                encode(&WithdrawArgs {
+                    token: TOKEN.to_string(),
+                    shares,
+                }),
+            )
+        };
+        assert_eq!(withdraw_all(ADDR_BOB, bob_shares + 1), None);
+        assert_eq!(withdraw_all(ADDR_ALICE, 1_000_000), Some(1_009_000));
+        assert_eq!(withdraw_all(ADDR_BOB, bob_shares), Some(1_009_000));
+        assert_eq!(load_reserve(TOKEN).unwrap().liquidity, 0);
+    }
+
+    #[test]
+    fn owner_sets_a_capped_fee() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        // Fees round up: one unit lent still pays one unit.
+        assert_eq!(flash_fee(9, 1), 1);
+        assert_eq!(flash_fee(0, 1_000_000), 0);
+
+        call::<()>(ADDR_ALICE, set_fee, encode(&SetFeeArgs { fee_bps: 0 }));
+        call::<()>(ADDR_OWNER, set_fee, encode(&SetFeeArgs { fee_bps: 101 }));
+        assert_eq!(load_config().unwrap().fee_bps, 9);
+        call::<()>(ADDR_OWNER, set_fee, encode(&SetFeeArgs { fee_bps: 30 }));
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs { fee_bps: 0 }),
+        );
+        assert_eq!(load_config().unwrap().fee_bps, 30);
+        // Only the accepted change is in the audit log.
+        assert_eq!(admin_log::next_sequence().unwrap(), 1);
+
+        deposit_as(ADDR_ALICE, 10_000).unwrap();
+        let quote = |amount: u64| {
+            call::<u64>(
+                ADDR_BOB,
+                quote_flash_fee,
+                encode(&FlashFeeArgs {
+                    token: TOKEN.to_string(),
+                    amount,
+                }),
+            )
+        };
+        assert_eq!(quote(10_000), Some(30));
+        assert_eq!(quote(10_001), None);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(CALLBACK_SUCCESS);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "flash-loans-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Request types and sender recovery for contracts using the forwarder
+//!
+//! Targets that trust a forwarder recover the real sender with
+//! [`msg_sender`]; relayers and paymasters build [`SignedRequest`]s.
+//!
+//! The forwarder contract re-exports this crate as `forwarder_contract::client`.
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use proxy_implementation as implementation;
+use silica_contract_sdk::prelude::*;
+
+const FORWARD_DOMAIN: &str = "silica-forwarder-v1";
+
+/// A call signed by `from` for a relayer to submit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ForwardRequest {
+    pub from: String,
+    pub target: String,
+    pub method: String,
+    pub data: Vec<u8>,
+    pub nonce: u64,
+    pub deadline: u64,
+}
+
+/// Arguments of the forwarder's `execute` entrypoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignedRequest {
+    pub request: ForwardRequest,
+    pub signature: Vec<u8>,
+}
+
+/// Bytes a signer signs for `request` on the forwarder at `forwarder`.
+pub fn forward_message(forwarder: &str, request: &ForwardRequest) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(&(
+        FORWARD_DOMAIN,
+        forwarder,
+        &request.from,
+        &request.target,
+        &request.method,
+        &request.data,
+        request.nonce,
+        request.deadline,
+    ))
+    .map_err(|_| ContractError::SerializationFailed)
+}
+
+/// Whether the current call came through `trusted_forwarder`.
+pub fn is_trusted_forwarder(trusted_forwarder: &str) -> bool {
+    context().sender() == trusted_forwarder
+}
+
+/// The account behind the current call.
+///
+/// Calls from `trusted_forwarder` carry the signer as a suffix; any other
+/// caller is returned as-is, so direct calls keep working.
+pub fn msg_sender(trusted_forwarder: &str) -> ContractResult<String> {
+    let ctx = context();
+    if ctx.sender() != trusted_forwarder {
+        return Ok(ctx.sender().to_string());
+    }
+    let data = ctx.call_data()?;
+    let (_, signer) =
+        implementation::split_sender(&data).ok_or(ContractError::DeserializationFailed)?;
+    validation::validate_address(signer)?;
+    Ok(signer.to_string())
+}
@@ -0,0 +1,398 @@
+//! Forwarder entrypoints
+
+use crate::client::{SignedRequest, forward_message};
+use proxy_implementation as implementation;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::nonces::Nonces;
+use silica_contract_sdk::prelude::*;
+
+const KEYS_PREFIX: &str = "signer_keys";
+const NONCES_PREFIX: &str = "signer_nonces";
+const SIGNATURE_LEN: usize = 64;
+const MAX_METHOD_BYTES: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 16_384;
+const MAX_RETURN_BYTES: usize = 4096;
+
+#[derive(Serialize, Deserialize)]
+struct RegisterKeyArgs {
+    public_key: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn signer_keys() -> Map<String, [u8; 32]> {
+    Map::new(KEYS_PREFIX)
+}
+
+fn nonces() -> Nonces {
+    Nonces::new(NONCES_PREFIX)
+}
+
+fn execute_register_key() -> ContractResult<()> {
+    let account = context().sender().to_string();
+    let args: RegisterKeyArgs = read_args()?;
+    if args.public_key == [0u8; 32] {
+        return Err(ContractError::InvalidArgument(
+            "Public key cannot be zero".to_string(),
+        ));
+    }
+
+    signer_keys().set(&account, &args.public_key)?;
+
+    event!("SignerKeyRegistered", account: account);
+    Ok(())
+}
+
+fn execute_forward() -> ContractResult<Vec<u8>> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: SignedRequest = read_args()?;
+    let request = args.request;
+    validation::validate_address(&request.target)?;
+    if request.method.is_empty() || request.method.len() > MAX_METHOD_BYTES {
+        return Err(ContractError::InvalidArgument(
+            "Invalid method name".to_string(),
+        ));
+    }
+    if request.target == ctx.contract_address() {
+        return Err(ContractError::InvalidArgument(
+            "Cannot forward to the forwarder".to_string(),
+        ));
+    }
+    if ctx.block_timestamp() > request.deadline {
+        return Err(ContractError::InvalidArgument(
+            "Request expired".to_string(),
+        ));
+    }
+
+    let mut nonces = nonces();
+    nonces.check(&request.from, request.nonce)?;
+
+    let public_key = signer_keys()
+        .get(&request.from)?
+        .ok_or(ContractError::Unauthorized)?;
+    let signature: [u8; SIGNATURE_LEN] = args
+        .signature
+        .as_slice()
+        .try_into()
+        .map_err(|_| ContractError::InvalidSignature)?;
+    let message = forward_message(ctx.contract_address(), &request)?;
+    if !crypto::verify_signature(&public_key, &message, &signature)? {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    // Consume the nonce before the call so the target cannot replay it.
+    nonces.consume(&request.from, request.nonce)?;
+
+    let data = implementation::append_sender(&request.data, &request.from)?;
+    let response = ctx.call_contract(&request.target, &request.method, &data)?;
+
+    event!("Forwarded",
+        from: request.from,
+        target: request.target,
+        method: request.method,
+        nonce: request.nonce,
+        relayer: ctx.sender()
+    );
+    Ok(response)
+}
+
+/// Register the Ed25519 key that signs requests for the caller
+///
+/// Calling again rotates the key; requests signed by the old key stop
+/// verifying.
+///
+/// # Arguments
+/// * `public_key` - Ed25519 public key
+#[unsafe(no_mangle)]
+pub extern "C" fn register_key() {
+    if let Err(err) = execute_register_key() {
+        log!("register_key failed: {}", err);
+    }
+}
+
+/// Verify a signed request and forward it to its target
+///
+/// # Arguments
+/// * `request` - `ForwardRequest { from, target, method, data, nonce, deadline }`
+/// * `signature` - Signature by `from`'s key over `forward_message`
+///
+/// # Returns
+/// The target's return data, unchanged
+#[unsafe(no_mangle)]
+pub extern "C" fn execute() {
+    match execute_forward() {
+        Ok(response) => {
+            if let Err(err) = context().return_bytes(&response) {
+                log!("execute failed: {}", err);
+            }
+        }
+        Err(err) => log!("execute failed: {}", err),
+    }
+}
+
+/// Get the next nonce for an account
+///
+/// # Arguments
+/// * `account` - Signer address
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn nonce_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| nonces().current(&args.account))
+        .and_then(|nonce| try_respond(&nonce));
+    if let Err(err) = result {
+        log!("nonce_of failed: {}", err);
+    }
+}
+
+/// Get the registered key for an account
+///
+/// # Arguments
+/// * `account` - Signer address
+///
+/// # Returns
+/// `Option<[u8; 32]>`
+#[unsafe(no_mangle)]
+pub extern "C" fn key_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| signer_keys().get(&args.account))
+        .and_then(|key| try_respond(&key));
+    if let Err(err) = result {
+        log!("key_of failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client::{self, ForwardRequest};
+    use crc_standards::version::ContractVersion;
+    use ed25519_dalek::{Signer, SigningKey};
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    const ADDR_USER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_RELAYER: &str = "0x0000000000000000000000000000000000000b02";
+    const TARGET: &str = "0x0000000000000000000000000000000000000c03";
+    const FORWARDER: &str = "0x0000000000000000000000000000000000000f00";
+    const NOW: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn signers() -> Vec<SigningKey> {
+        crypto::generate_keypairs(2)
+            .expect("keypairs")
+            .iter()
+            .map(|(_, secret)| SigningKey::from_bytes(secret))
+            .collect()
+    }
+
+    fn request(nonce: u64, deadline: u64) -> ForwardRequest {
+        ForwardRequest {
+            from: ADDR_USER.to_string(),
+            target: TARGET.to_string(),
+            method: "set_value".to_string(),
+            data: encode(&7u64),
+            nonce,
+            deadline,
+        }
+    }
+
+    /// Captures what the target receives and answers with `42u64`.
+    fn setup() -> Arc<Mutex<Vec<Vec<u8>>>> {
+        mock::reset();
+        mock::set_contract_address(FORWARDER);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        mock::set_sender(ADDR_USER);
+        mock::set_call_data(&encode(&RegisterKeyArgs {
+            public_key: signers()[0].verifying_key().to_bytes(),
+        }));
+        register_key();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let captured = seen.clone();
+        mock::register_contract(TARGET, "set_value", move |args| {
+            captured.lock().unwrap().push(args.to_vec());
+            Ok(encode(&42u64))
+        });
+        seen
+    }
+
+    fn relay(request: &ForwardRequest, key: &SigningKey) -> Vec<u8> {
+        let message = forward_message(FORWARDER, request).unwrap();
+        mock::set_sender(ADDR_RELAYER);
+        mock::set_call_data(&encode(&SignedRequest {
+            request: request.clone(),
+            signature: key.sign(&message).to_bytes().to_vec(),
+        }));
+        execute();
+        mock::take_return_data()
+    }
+
+    fn next_nonce() -> u64 {
+        nonces().current(ADDR_USER).unwrap()
+    }
+
+    #[test]
+    fn forwards_with_signer_appended() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let seen = setup();
+
+        let response = relay(&request(0, NOW + 60), &signers()[0]);
+        assert_eq!(response, encode(&42u64));
+        assert_eq!(next_nonce(), 1);
+
+        let forwarded = seen.lock().unwrap()[0].clone();
+        let (payload, signer) = implementation::split_sender(&forwarded).unwrap();
+        assert_eq!(signer, ADDR_USER);
+        assert_eq!(payload, encode(&7u64).as_slice());
+
+        let events = mock::take_events();
+        assert!(events.iter().any(|e| e.topic == "Forwarded"));
+    }
+
+    #[test]
+    fn replayed_and_expired_requests_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let seen = setup();
+        let key = &signers()[0];
+
+        relay(&request(0, NOW + 60), key);
+        relay(&request(0, NOW + 60), key);
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        relay(&request(1, NOW - 1), key);
+        assert_eq!(seen.lock().unwrap().len(), 1);
+        assert_eq!(next_nonce(), 1, "failed requests keep the nonce");
+    }
+
+    #[test]
+    fn signature_must_match_registered_key() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let seen = setup();
+
+        relay(&request(0, NOW + 60), &signers()[1]);
+        assert!(seen.lock().unwrap().is_empty());
+
+        // A signature over one target cannot be moved to another.
+        let signed = request(0, NOW + 60);
+        let message = forward_message(FORWARDER, &signed).unwrap();
+        let tampered = ForwardRequest {
+            data: encode(&8u64),
+            ..signed
+        };
+        mock::set_call_data(&encode(&SignedRequest {
+            request: tampered,
+            signature: signers()[0].sign(&message).to_bytes().to_vec(),
+        }));
+        execute();
+        assert!(seen.lock().unwrap().is_empty());
+        assert_eq!(next_nonce(), 0);
+    }
+
+    #[test]
+    fn key_rotation_invalidates_old_key() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let seen = setup();
+
+        mock::set_sender(ADDR_USER);
+        mock::set_call_data(&encode(&RegisterKeyArgs {
+            public_key: signers()[1].verifying_key().to_bytes(),
+        }));
+        register_key();
+
+        relay(&request(0, NOW + 60), &signers()[0]);
+        assert!(seen.lock().unwrap().is_empty());
+        relay(&request(0, NOW + 60), &signers()[1]);
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn client_recovers_sender_only_from_trusted_forwarder() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        mock::set_contract_address(TARGET);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+
+        let forwarded = implementation::append_sender(&encode(&7u64), ADDR_USER).unwrap();
+        mock::set_call_data(&forwarded);
+        mock::set_sender(FORWARDER);
+        assert!(client::is_trusted_forwarder(FORWARDER));
+        assert_eq!(client::msg_sender(FORWARDER).unwrap(), ADDR_USER);
+
+        mock::set_sender(ADDR_RELAYER);
+        assert_eq!(client::msg_sender(FORWARDER).unwrap(), ADDR_RELAYER);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "forwarder-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
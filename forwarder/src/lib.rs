@@ -0,0 +1,32 @@
+//! Meta-Transaction Forwarder
+//!
+//! Relayers submit calls that users signed off-chain. The forwarder checks
+//! the Ed25519 signature over `(target, method, call data, nonce, deadline)`
+//! against the key the user registered, consumes the nonce, and calls the
+//! target with the signer's address appended. Targets that trust the
+//! forwarder recover the real sender with [`client::msg_sender`].
+//!
+//! The suffix format is the one the proxy uses, described in
+//! `proxy_implementation`.
+//!
+//! ## Embedding
+//! ```toml
+//! forwarder-client = { path = "../forwarder-client" }
+//! ```
+//!
+//! ```rust,ignore
+//! let sender = forwarder_client::msg_sender(&config.trusted_forwarder)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub use forwarder_client as client;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
@@ -0,0 +1,1302 @@
+//! Fractional NFT Vault
+//!
+//! Escrows one CRC-721 and issues CRC-20 fractions against it; the vault
+//! contract is itself the fraction token. Fraction holders vote on a reserve
+//! price, and the reserve is the average of the votes weighted by balance.
+//! Once enough of the supply has voted, anyone can start a buyout auction
+//! with a bid at or above the reserve. The highest bidder gets the NFT when
+//! the auction ends. The winning bid stays in the vault, and holders burn
+//! their fractions for a pro-rata share of it. A holder of the entire
+//! supply can instead redeem the NFT directly.
+//!
+//! ## Features
+//! - NFT escrow with a fixed supply of CRC-20 fractions
+//! - Balance-weighted reserve price with a voting quorum
+//! - English buyout auction with minimum raises and anti-sniping extensions
+//! - Pro-rata claims on the auction proceeds
+//! - Full-supply redemption of the NFT
+//! - The curator can pause auctions, bids and fraction transfers; cashing
+//!   out and redemption stay open
+//! - Decommissioning: after the curator's notice runs out, the reserve
+//!   freezes and the vault can only be bought out or redeemed
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const VAULT_KEY: &str = "vault_config";
+const AUCTION_KEY: &str = "vault_auction";
+const SUPPLY_KEY: &str = "total_supply";
+const TALLY_KEY: &str = "vote_tally";
+const BALANCES_PREFIX: &str = "balances";
+const ALLOWANCES_PREFIX: &str = "allowances";
+const PRICES_PREFIX: &str = "user_prices";
+const BPS_DENOMINATOR: u64 = 10_000;
+/// Share of the supply that must have voted before an auction can start
+const MIN_VOTE_BPS: u64 = 2_500;
+/// Each bid must beat the last one by this much
+const MIN_BID_INCREMENT_BPS: u64 = 500;
+/// Bids this close to the end push the end back to this far out
+const EXTENSION_WINDOW: u64 = 900;
+const MIN_AUCTION_DURATION: u64 = 86_400;
+const MAX_AUCTION_DURATION: u64 = 14 * 86_400;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Vault configuration, fixed at creation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultConfig {
+    pub curator: String,
+    pub collection: String,
+    pub token_id: u64,
+    /// CRC-20 bids are paid in
+    pub currency: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub auction_duration: u64,
+}
+
+/// Stage of the buyout
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuctionStatus {
+    /// Holders are voting; no auction yet
+    Inactive,
+    /// Bids are open until `ends_at`
+    Live,
+    /// The NFT went to the winner; holders can cash out
+    Ended,
+    /// The NFT was redeemed by a holder of the whole supply
+    Redeemed,
+}
+
+/// Buyout auction state
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Auction {
+    pub status: AuctionStatus,
+    pub ends_at: u64,
+    pub winner: Option<String>,
+    pub bid: u64,
+    /// Winning bid not yet cashed out by holders
+    pub proceeds: u64,
+}
+
+/// Aggregate of the holders' reserve price votes
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VoteTally {
+    /// Fractions held by accounts with a vote
+    pub voting_supply: u64,
+    /// Sum of balance times voted price
+    pub weighted_price: u128,
+}
+
+/// Reserve price and quorum at the current supply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteView {
+    pub reserve_price: u64,
+    pub voting_supply: u64,
+    pub total_supply: u64,
+    pub quorum_reached: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    collection: String,
+    token_id: u64,
+    currency: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    supply: u64,
+    reserve_price: u64,
+    auction_duration: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PriceArgs {
+    price: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BidArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllowanceArgs {
+    owner: String,
+    spender: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NftTransferFromArgs {
+    from: String,
+    to: String,
+    token_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<VaultConfig> {
+    storage()
+        .get::<VaultConfig>(VAULT_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn load_auction() -> ContractResult<Auction> {
+    storage()
+        .get::<Auction>(AUCTION_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_auction(auction: &Auction) -> ContractResult<()> {
+    storage().set(AUCTION_KEY, auction)
+}
+
+fn load_supply() -> ContractResult<u64> {
+    Ok(storage().get::<u64>(SUPPLY_KEY)?.unwrap_or(0))
+}
+
+fn load_tally() -> ContractResult<VoteTally> {
+    Ok(storage().get::<VoteTally>(TALLY_KEY)?.unwrap_or_default())
+}
+
+fn balances() -> Map<String, u64> {
+    Map::new(BALANCES_PREFIX)
+}
+
+fn allowances() -> Map<(String, String), u64> {
+    Map::new(ALLOWANCES_PREFIX)
+}
+
+fn prices() -> Map<String, u64> {
+    Map::new(PRICES_PREFIX)
+}
+
+fn balance_of_account(account: &str) -> ContractResult<u64> {
+    Ok(balances().get(&account.to_string())?.unwrap_or(0))
+}
+
+fn expect_status(auction: &Auction, status: AuctionStatus) -> ContractResult<()> {
+    if auction.status != status {
+        return Err(ContractError::InvalidArgument(format!(
+            "Auction is {:?}, expected {:?}",
+            auction.status, status
+        )));
+    }
+    Ok(())
+}
+
+fn pull_tokens(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn move_nft(config: &VaultConfig, from: &str, to: &str) -> ContractResult<()> {
+    let call = encode_call(&NftTransferFromArgs {
+        from: from.to_string(),
+        to: to.to_string(),
+        token_id: config.token_id,
+    })?;
+    context()
+        .call_contract(&config.collection, "transfer_from", &call)
+        .map(|_| ())
+}
+
+/// Reserve price from the tally: the balance-weighted average vote.
+pub fn reserve_price(tally: &VoteTally) -> u64 {
+    if tally.voting_supply == 0 {
+        return 0;
+    }
+    // An average of u64 prices fits in u64.
+    (tally.weighted_price / tally.voting_supply as u128) as u64
+}
+
+fn vote_view(tally: &VoteTally, total_supply: u64) -> VoteView {
+    VoteView {
+        reserve_price: reserve_price(tally),
+        voting_supply: tally.voting_supply,
+        total_supply,
+        quorum_reached: tally.voting_supply as u128 * BPS_DENOMINATOR as u128
+            >= total_supply as u128 * MIN_VOTE_BPS as u128,
+    }
+}
+
+/// Set a balance and keep the account's vote weighted by it.
+fn set_balance(account: &str, old: u64, new: u64) -> ContractResult<()> {
+    balances().set(&account.to_string(), &new)?;
+    let price = prices().get(&account.to_string())?.unwrap_or(0);
+    if price == 0 || old == new {
+        return Ok(());
+    }
+    let mut tally = load_tally()?;
+    tally.voting_supply = tally.voting_supply - old + new;
+    tally.weighted_price =
+        tally.weighted_price - old as u128 * price as u128 + new as u128 * price as u128;
+    storage().set(TALLY_KEY, &tally)
+}
+
+fn move_fractions(from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    validation::validate_address(to)?;
+    let from_balance = balance_of_account(from)?;
+    if from_balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: from_balance,
+        });
+    }
+    if from != to {
+        set_balance(from, from_balance, from_balance - amount)?;
+        let to_balance = balance_of_account(to)?;
+        set_balance(to, to_balance, safe_math::add(to_balance, amount)?)?;
+    }
+    event!("Transfer", from: from, to: to, amount: amount);
+    Ok(())
+}
+
+fn burn_all(account: &str) -> ContractResult<u64> {
+    let balance = balance_of_account(account)?;
+    if balance == 0 {
+        return Err(ContractError::InsufficientBalance {
+            required: 1,
+            available: 0,
+        });
+    }
+    set_balance(account, balance, 0)?;
+    storage().set(SUPPLY_KEY, &(load_supply()? - balance))?;
+    event!("Transfer", from: account, to: "", amount: balance);
+    Ok(balance)
+}
+
+/// Lowest bid that can replace `bid`.
+pub fn min_next_bid(bid: u64) -> u64 {
+    let raise = (bid as u128 * MIN_BID_INCREMENT_BPS as u128).div_ceil(BPS_DENOMINATOR as u128);
+    bid.saturating_add(raise.max(1) as u64)
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    if storage().has(VAULT_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let curator = ctx.sender().to_string();
+    let args: InitializeArgs = read_args()?;
+    for address in [&args.collection, &args.currency] {
+        validation::validate_address(address)?;
+        if !ctx.is_contract(address) {
+            return Err(ContractError::InvalidArgument(
+                "Collection and currency must be contracts".to_string(),
+            ));
+        }
+    }
+    if args.name.is_empty() || args.symbol.is_empty() {
+        return Err(ContractError::InvalidArgument(
+            "Name and symbol are required".to_string(),
+        ));
+    }
+    validation::validate_positive_amount(args.supply)?;
+    validation::validate_positive_amount(args.reserve_price)?;
+    validation::validate_range(
+        args.auction_duration,
+        MIN_AUCTION_DURATION,
+        MAX_AUCTION_DURATION,
+    )?;
+
+    let config = VaultConfig {
+        curator: curator.clone(),
+        collection: args.collection.clone(),
+        token_id: args.token_id,
+        currency: args.currency,
+        name: args.name,
+        symbol: args.symbol,
+        decimals: args.decimals,
+        auction_duration: args.auction_duration,
+    };
+    storage().set(VAULT_KEY, &config)?;
+    save_auction(&Auction {
+        status: AuctionStatus::Inactive,
+        ends_at: 0,
+        winner: None,
+        bid: 0,
+        proceeds: 0,
+    })?;
+    storage().set(SUPPLY_KEY, &args.supply)?;
+    // The curator's initial price is the first vote.
+    prices().set(&curator, &args.reserve_price)?;
+    storage().set(
+        TALLY_KEY,
+        &VoteTally {
+            voting_supply: args.supply,
+            weighted_price: args.supply as u128 * args.reserve_price as u128,
+        },
+    )?;
+    balances().set(&curator, &args.supply)?;
+
+    move_nft(&config, &curator, ctx.contract_address())?;
+
+    event!("VaultCreated",
+        curator: curator.clone(),
+        collection: args.collection,
+        token_id: args.token_id,
+        supply: args.supply,
+        reserve_price: args.reserve_price
+    );
+    event!("Transfer", from: "", to: curator, amount: args.supply);
+    Ok(())
+}
+
+fn execute_update_user_price() -> ContractResult<()> {
+    decommission::when_active()?;
+    let voter = context().sender().to_string();
+    let args: PriceArgs = read_args()?;
+    expect_status(&load_auction()?, AuctionStatus::Inactive)?;
+    let old_price = prices().get(&voter)?.unwrap_or(0);
+    let balance = balance_of_account(&voter)? as u128;
+    let mut tally = load_tally()?;
+    if old_price > 0 {
+        tally.voting_supply -= balance as u64;
+        tally.weighted_price -= balance * old_price as u128;
+    }
+    if args.price > 0 {
+        tally.voting_supply += balance as u64;
+        tally.weighted_price += balance * args.price as u128;
+    }
+    storage().set(TALLY_KEY, &tally)?;
+    prices().set(&voter, &args.price)?;
+
+    event!("PriceUpdated",
+        voter: voter,
+        price: args.price,
+        reserve_price: reserve_price(&tally)
+    );
+    Ok(())
+}
+
+fn execute_start_auction() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    let ctx = context();
+    let bidder = ctx.sender().to_string();
+    let config = load_config()?;
+    let args: BidArgs = read_args()?;
+    let mut auction = load_auction()?;
+    expect_status(&auction, AuctionStatus::Inactive)?;
+    let votes = vote_view(&load_tally()?, load_supply()?);
+    if !votes.quorum_reached {
+        return Err(ContractError::InvalidArgument(
+            "Not enough fractions have voted on a reserve price".to_string(),
+        ));
+    }
+    if args.amount < votes.reserve_price {
+        return Err(ContractError::InvalidArgument(format!(
+            "Bid {} below reserve price {}",
+            args.amount, votes.reserve_price
+        )));
+    }
+    auction.status = AuctionStatus::Live;
+    auction.ends_at = safe_math::add(ctx.block_timestamp(), config.auction_duration)?;
+    auction.winner = Some(bidder.clone());
+    auction.bid = args.amount;
+    save_auction(&auction)?;
+
+    pull_tokens(&config.currency, &bidder, args.amount)?;
+
+    event!("AuctionStarted",
+        bidder: bidder,
+        amount: args.amount,
+        ends_at: auction.ends_at
+    );
+    Ok(())
+}
+
+fn execute_bid() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    let ctx = context();
+    let bidder = ctx.sender().to_string();
+    let config = load_config()?;
+    let args: BidArgs = read_args()?;
+    let mut auction = load_auction()?;
+    expect_status(&auction, AuctionStatus::Live)?;
+    let now = ctx.block_timestamp();
+    if now >= auction.ends_at {
+        return Err(ContractError::InvalidArgument(
+            "Auction has ended".to_string(),
+        ));
+    }
+    let minimum = min_next_bid(auction.bid);
+    if args.amount < minimum {
+        return Err(ContractError::InvalidArgument(format!(
+            "Bid must be at least {}",
+            minimum
+        )));
+    }
+    let outbid = auction.winner.replace(bidder.clone()).unwrap_or_default();
+    let refund = auction.bid;
+    auction.bid = args.amount;
+    if auction.ends_at - now < EXTENSION_WINDOW {
+        auction.ends_at = now + EXTENSION_WINDOW;
+    }
+    save_auction(&auction)?;
+
+    pull_tokens(&config.currency, &bidder, args.amount)?;
+    send_tokens(&config.currency, &outbid, refund)?;
+
+    event!("Bid",
+        bidder: bidder,
+        amount: args.amount,
+        ends_at: auction.ends_at
+    );
+    Ok(())
+}
+
+fn execute_end_auction() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let config = load_config()?;
+    let mut auction = load_auction()?;
+    expect_status(&auction, AuctionStatus::Live)?;
+    if ctx.block_timestamp() < auction.ends_at {
+        return Err(ContractError::InvalidArgument(
+            "Auction is still running".to_string(),
+        ));
+    }
+    let winner = auction.winner.clone().unwrap_or_default();
+    auction.status = AuctionStatus::Ended;
+    auction.proceeds = auction.bid;
+    save_auction(&auction)?;
+
+    move_nft(&config, ctx.contract_address(), &winner)?;
+
+    event!("AuctionEnded", winner: winner, amount: auction.bid);
+    Ok(())
+}
+
+fn execute_cash() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let holder = context().sender().to_string();
+    let config = load_config()?;
+    let mut auction = load_auction()?;
+    expect_status(&auction, AuctionStatus::Ended)?;
+    let supply = load_supply()?;
+    let burned = burn_all(&holder)?;
+    let share = (auction.proceeds as u128 * burned as u128 / supply as u128) as u64;
+    auction.proceeds -= share;
+    save_auction(&auction)?;
+
+    if share > 0 {
+        send_tokens(&config.currency, &holder, share)?;
+    }
+
+    event!("Cashed", holder: holder, burned: burned, amount: share);
+    Ok(share)
+}
+
+fn execute_redeem() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let holder = ctx.sender().to_string();
+    let config = load_config()?;
+    let mut auction = load_auction()?;
+    expect_status(&auction, AuctionStatus::Inactive)?;
+    if balance_of_account(&holder)? != load_supply()? {
+        return Err(ContractError::InvalidArgument(
+            "Redeeming requires the entire supply".to_string(),
+        ));
+    }
+    burn_all(&holder)?;
+    auction.status = AuctionStatus::Redeemed;
+    save_auction(&auction)?;
+
+    move_nft(&config, ctx.contract_address(), &holder)?;
+
+    event!("Redeemed", holder: holder);
+    Ok(())
+}
+
+fn execute_transfer() -> ContractResult<()> {
+    pausable::when_not_paused()?;
+    let args: TransferArgs = read_args()?;
+    move_fractions(context().sender(), &args.to, args.amount)
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: ApproveArgs = read_args()?;
+    validation::validate_address(&args.spender)?;
+    allowances().set(&(owner.clone(), args.spender.clone()), &args.amount)?;
+    event!("Approval", owner: owner, spender: args.spender, amount: args.amount);
+    Ok(())
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    pausable::when_not_paused()?;
+    let spender = context().sender().to_string();
+    let args: TransferFromArgs = read_args()?;
+    let key = (args.from.clone(), spender);
+    let allowance = allowances().get(&key)?.unwrap_or(0);
+    if allowance < args.amount {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: allowance,
+        });
+    }
+    move_fractions(&args.from, &args.to, args.amount)?;
+    allowances().set(&key, &(allowance - args.amount))
+}
+
+fn only_curator() -> ContractResult<()> {
+    if context().sender() != load_config()?.curator {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    only_curator()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    only_curator()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    only_curator()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    only_curator()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    only_curator()?;
+    pausable::unpause()
+}
+
+/// Create the vault: escrow the NFT and mint the whole supply to the caller
+///
+/// The caller must have approved this contract on the collection. Their
+/// `reserve_price` counts as the first vote.
+///
+/// # Arguments
+/// * `collection` / `token_id` - NFT to fractionalize
+/// * `currency` - CRC-20 for buyout bids
+/// * `name` / `symbol` / `decimals` - Fraction token metadata
+/// * `supply` - Fractions minted
+/// * `reserve_price` - Curator's reserve price vote
+/// * `auction_duration` - Buyout auction length (1-14 days)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Vote on the reserve price, weighted by the caller's balance
+///
+/// Votes follow the balance as fractions move. Only possible before an
+/// auction starts.
+///
+/// # Arguments
+/// * `price` - Reserve price in the bid currency; 0 withdraws the vote
+#[unsafe(no_mangle)]
+pub extern "C" fn update_user_price() {
+    if let Err(err) = execute_update_user_price() {
+        log!("update_user_price failed: {}", err);
+    }
+}
+
+/// Start the buyout auction with an opening bid
+///
+/// Requires 25% of the supply to have voted and a bid at or above the
+/// reserve price.
+///
+/// # Arguments
+/// * `amount` - Opening bid, pulled from the caller
+#[unsafe(no_mangle)]
+pub extern "C" fn start_auction() {
+    if let Err(err) = execute_start_auction() {
+        log!("start_auction failed: {}", err);
+    }
+}
+
+/// Outbid the current leader by at least 5%
+///
+/// Bids in the last 15 minutes extend the auction to 15 minutes from now.
+/// The previous leader is refunded.
+///
+/// # Arguments
+/// * `amount` - Bid, pulled from the caller
+#[unsafe(no_mangle)]
+pub extern "C" fn bid() {
+    if let Err(err) = execute_bid() {
+        log!("bid failed: {}", err);
+    }
+}
+
+/// Close a finished auction and send the NFT to the winner
+#[unsafe(no_mangle)]
+pub extern "C" fn end_auction() {
+    if let Err(err) = execute_end_auction() {
+        log!("end_auction failed: {}", err);
+    }
+}
+
+/// Burn all of the caller's fractions for their share of the proceeds
+///
+/// # Returns
+/// Amount received (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn cash() {
+    if let Err(err) = execute_cash().and_then(|amount| try_respond(&amount)) {
+        log!("cash failed: {}", err);
+    }
+}
+
+/// Burn the entire supply and take the NFT, before any auction
+#[unsafe(no_mangle)]
+pub extern "C" fn redeem() {
+    if let Err(err) = execute_redeem() {
+        log!("redeem failed: {}", err);
+    }
+}
+
+/// Get the vault configuration
+///
+/// # Returns
+/// `VaultConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_vault() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_vault failed: {}", err);
+    }
+}
+
+/// Get the buyout auction state
+///
+/// # Returns
+/// `Auction`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_auction() {
+    if let Err(err) = load_auction().and_then(|auction| try_respond(&auction)) {
+        log!("get_auction failed: {}", err);
+    }
+}
+
+/// Get the reserve price and voting quorum
+///
+/// # Returns
+/// `VoteView`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_votes() {
+    let result = load_tally()
+        .and_then(|tally| Ok(vote_view(&tally, load_supply()?)))
+        .and_then(|view| try_respond(&view));
+    if let Err(err) = result {
+        log!("get_votes failed: {}", err);
+    }
+}
+
+/// Get an account's reserve price vote
+///
+/// # Arguments
+/// * `account` - Holder address
+///
+/// # Returns
+/// Price, 0 if not voting (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn user_price() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| Ok(prices().get(&args.account)?.unwrap_or(0)))
+        .and_then(|price| try_respond(&price));
+    if let Err(err) = result {
+        log!("user_price failed: {}", err);
+    }
+}
+
+/// Transfer fractions
+///
+/// # Arguments
+/// * `to` - Recipient
+/// * `amount` - Fractions to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer() {
+    if let Err(err) = execute_transfer() {
+        log!("Transfer failed: {}", err);
+    }
+}
+
+/// Approve a spender for fractions
+///
+/// # Arguments
+/// * `spender` - Address allowed to spend
+/// * `amount` - Allowance, replacing any previous one
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("Approve failed: {}", err);
+    }
+}
+
+/// Transfer fractions using an allowance
+///
+/// # Arguments
+/// * `from` - Fraction owner
+/// * `to` - Recipient
+/// * `amount` - Fractions to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("TransferFrom failed: {}", err);
+    }
+}
+
+/// Get the fractions held by an account
+///
+/// # Arguments
+/// * `account` - Holder address
+///
+/// # Returns
+/// Balance (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| balance_of_account(&args.account))
+        .and_then(|balance| try_respond(&balance));
+    if let Err(err) = result {
+        log!("balance_of failed: {}", err);
+    }
+}
+
+/// Get the remaining fraction allowance of a spender
+///
+/// # Arguments
+/// * `owner` - Fraction owner
+/// * `spender` - Approved spender
+///
+/// # Returns
+/// Allowance (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn allowance() {
+    let result = read_args::<AllowanceArgs>()
+        .and_then(|args| Ok(allowances().get(&(args.owner, args.spender))?.unwrap_or(0)))
+        .and_then(|amount| try_respond(&amount));
+    if let Err(err) = result {
+        log!("allowance failed: {}", err);
+    }
+}
+
+/// Get the fractions in circulation, which shrinks as holders cash out
+///
+/// # Returns
+/// Supply (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn total_supply() {
+    if let Err(err) = load_supply().and_then(|supply| try_respond(&supply)) {
+        log!("total_supply failed: {}", err);
+    }
+}
+
+/// Give notice that the vault will be decommissioned (curator only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (curator only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the vault once the notice has run out (curator only)
+///
+/// Reserve price votes freeze for good. A buyout auction, fraction
+/// transfers, cashing out and redemption stay open, since a sale or
+/// redemption is the only way holders get value out of the NFT.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the vault is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Pause the vault (curator only)
+///
+/// Starting an auction, bidding and fraction transfers fail until
+/// `unpause`; ending an auction, cashing out and redemption stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (curator only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the vault is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_CURATOR: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000d04";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const PUNKS: &str = "0x0000000000000000000000000000000000000f01";
+    const USDC: &str = "0x0000000000000000000000000000000000000f02";
+    const NOW: u64 = 1_736_000_000;
+    const DAY: u64 = 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// Punk 42 split into 1000 fractions with a 100k reserve
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        mock::register_contract(PUNKS, "transfer_from", |_| Ok(Vec::new()));
+        for method in ["transfer", "transfer_from"] {
+            mock::register_contract(USDC, method, |_| Ok(Vec::new()));
+        }
+        call::<()>(
+            ADDR_CURATOR,
+            initialize,
+            encode(&InitializeArgs {
+                collection: PUNKS.to_string(),
+                token_id: 42,
+                currency: USDC.to_string(),
+                name: "Fractional Punk 42".to_string(),
+                symbol: "FP42".to_string(),
+                decimals: 0,
+                supply: 1_000,
+                reserve_price: 100_000,
+                auction_duration: 3 * DAY,
+            }),
+        );
+    }
+
+    fn send(from: &str, to: &str, amount: u64) {
+        call::<()>(
+            from,
+            transfer,
+            encode(&TransferArgs {
+                to: to.to_string(),
+                amount,
+            }),
+        );
+    }
+
+    fn vote(sender: &str, price: u64) {
+        call::<()>(sender, update_user_price, encode(&PriceArgs { price }));
+    }
+
+    fn votes() -> VoteView {
+        vote_view(&load_tally().unwrap(), load_supply().unwrap())
+    }
+
+    fn offer(sender: &str, entrypoint: extern "C" fn(), amount: u64) {
+        call::<()>(sender, entrypoint, encode(&BidArgs { amount }));
+    }
+
+    fn nft_moves() -> Vec<(String, String)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.address == PUNKS)
+            .map(|c| {
+                let args: NftTransferFromArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.from, args.to)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vault_escrows_the_nft_and_mints_fractions() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(
+            nft_moves(),
+            [(ADDR_CURATOR.to_string(), CONTRACT.to_string())]
+        );
+        assert_eq!(balance_of_account(ADDR_CURATOR).unwrap(), 1_000);
+        assert_eq!(
+            votes(),
+            VoteView {
+                reserve_price: 100_000,
+                voting_supply: 1_000,
+                total_supply: 1_000,
+                quorum_reached: true,
+            }
+        );
+
+        send(ADDR_CURATOR, ADDR_ALICE, 600);
+        send(ADDR_ALICE, ADDR_BOB, 601);
+        assert_eq!(balance_of_account(ADDR_ALICE).unwrap(), 600);
+        call::<()>(
+            ADDR_ALICE,
+            approve,
+            encode(&ApproveArgs {
+                spender: ADDR_BOB.to_string(),
+                amount: 100,
+            }),
+        );
+        call::<()>(
+            ADDR_BOB,
+            transfer_from,
+            encode(&TransferFromArgs {
+                from: ADDR_ALICE.to_string(),
+                to: ADDR_BOB.to_string(),
+                amount: 100,
+            }),
+        );
+        assert_eq!(balance_of_account(ADDR_BOB).unwrap(), 100);
+        // Only the curator's 400 still carry a vote.
+        assert_eq!(votes().voting_supply, 400);
+        call::<()>(ADDR_CURATOR, initialize, Vec::new());
+        assert_eq!(load_supply().unwrap(), 1_000);
+    }
+
+    #[test]
+    fn reserve_is_the_balance_weighted_vote_with_a_quorum() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        send(ADDR_CURATOR, ADDR_ALICE, 800);
+        // The curator's 200 are below the 25% quorum.
+        assert!(!votes().quorum_reached);
+        offer(ADDR_BOB, start_auction, 1_000_000);
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Inactive);
+
+        vote(ADDR_ALICE, 200_000);
+        // (200 * 100k + 800 * 200k) / 1000
+        assert_eq!(votes().reserve_price, 180_000);
+        assert!(votes().quorum_reached);
+        send(ADDR_ALICE, ADDR_CAROL, 400);
+        // (200 * 100k + 400 * 200k) / 600
+        assert_eq!(votes().reserve_price, 166_666);
+        vote(ADDR_ALICE, 0);
+        assert_eq!(votes().reserve_price, 100_000);
+        vote(ADDR_ALICE, 200_000);
+
+        offer(ADDR_BOB, start_auction, 166_665);
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Inactive);
+        offer(ADDR_BOB, start_auction, 166_666);
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Live);
+        vote(ADDR_CAROL, 1);
+        assert_eq!(votes().reserve_price, 166_666);
+    }
+
+    #[test]
+    fn bids_must_raise_and_late_bids_extend_the_auction() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        offer(ADDR_ALICE, start_auction, 100_000);
+        let ends_at = load_auction().unwrap().ends_at;
+        assert_eq!(ends_at, NOW + 3 * DAY);
+        assert_eq!(min_next_bid(100_000), 105_000);
+        mock::take_contract_calls();
+
+        offer(ADDR_BOB, bid, 104_999);
+        assert!(mock::take_contract_calls().is_empty());
+        offer(ADDR_BOB, bid, 105_000);
+        let calls = mock::take_contract_calls();
+        let refund: TransferArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!((refund.to.as_str(), refund.amount), (ADDR_ALICE, 100_000));
+        assert_eq!(load_auction().unwrap().ends_at, ends_at);
+
+        mock::set_block_timestamp(ends_at - 60);
+        offer(ADDR_CAROL, bid, 200_000);
+        let auction = load_auction().unwrap();
+        assert_eq!(auction.ends_at, ends_at - 60 + EXTENSION_WINDOW);
+        assert_eq!(auction.winner.as_deref(), Some(ADDR_CAROL));
+        mock::set_block_timestamp(auction.ends_at);
+        offer(ADDR_BOB, bid, 300_000);
+        assert_eq!(load_auction().unwrap().bid, 200_000);
+    }
+
+    #[test]
+    fn holders_cash_out_the_winning_bid_pro_rata() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        send(ADDR_CURATOR, ADDR_ALICE, 300);
+        offer(ADDR_BOB, start_auction, 100_001);
+        assert_eq!(call::<u64>(ADDR_ALICE, cash, Vec::new()), None);
+        mock::set_block_timestamp(NOW + 3 * DAY - 1);
+        call::<()>(ADDR_ALICE, end_auction, Vec::new());
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Live);
+        mock::take_contract_calls();
+
+        mock::set_block_timestamp(NOW + 3 * DAY);
+        call::<()>(ADDR_ALICE, end_auction, Vec::new());
+        assert_eq!(nft_moves(), [(CONTRACT.to_string(), ADDR_BOB.to_string())]);
+
+        assert_eq!(call::<u64>(ADDR_ALICE, cash, Vec::new()), Some(30_000));
+        assert_eq!(call::<u64>(ADDR_ALICE, cash, Vec::new()), None);
+        assert_eq!(call::<u64>(ADDR_CURATOR, cash, Vec::new()), Some(70_001));
+        assert_eq!(load_supply().unwrap(), 0);
+        assert_eq!(load_auction().unwrap().proceeds, 0);
+        vote(ADDR_CURATOR, 1);
+        assert_eq!(
+            prices().get(&ADDR_CURATOR.to_string()).unwrap(),
+            Some(100_000)
+        );
+    }
+
+    #[test]
+    fn pausing_stops_auctions_and_transfers_but_not_cashing_out() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        send(ADDR_CURATOR, ADDR_ALICE, 300);
+        call::<()>(ADDR_ALICE, pause, Vec::new());
+        assert!(!pausable::paused().unwrap());
+        call::<()>(ADDR_CURATOR, pause, Vec::new());
+        assert_eq!(call::<bool>(ADDR_BOB, paused, Vec::new()), Some(true));
+
+        offer(ADDR_BOB, start_auction, 100_000);
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Inactive);
+        send(ADDR_ALICE, ADDR_BOB, 100);
+        assert_eq!(balance_of_account(ADDR_ALICE).unwrap(), 300);
+
+        call::<()>(ADDR_CURATOR, unpause, Vec::new());
+        offer(ADDR_BOB, start_auction, 100_000);
+        call::<()>(ADDR_CURATOR, pause, Vec::new());
+        offer(ADDR_CAROL, bid, 200_000);
+        assert_eq!(load_auction().unwrap().bid, 100_000);
+
+        // A finished auction still settles and pays out while paused.
+        mock::set_block_timestamp(NOW + 3 * DAY);
+        call::<()>(ADDR_ALICE, end_auction, Vec::new());
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Ended);
+        assert_eq!(call::<u64>(ADDR_ALICE, cash, Vec::new()), Some(30_000));
+    }
+
+    #[test]
+    fn decommissioning_freezes_votes_but_not_the_buyout() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        send(ADDR_CURATOR, ADDR_ALICE, 300);
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call::<u64>(ADDR_CURATOR, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        call::<()>(ADDR_CURATOR, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        vote(ADDR_ALICE, 1);
+        assert_eq!(votes().reserve_price, 100_000);
+
+        // The buyout still pays every holder out.
+        offer(ADDR_BOB, start_auction, 100_000);
+        mock::set_block_timestamp(eta + 3 * DAY);
+        call::<()>(ADDR_ALICE, end_auction, Vec::new());
+        assert_eq!(call::<u64>(ADDR_ALICE, cash, Vec::new()), Some(30_000));
+        assert_eq!(call::<u64>(ADDR_CURATOR, cash, Vec::new()), Some(70_000));
+    }
+
+    #[test]
+    fn the_whole_supply_redeems_the_nft() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        send(ADDR_CURATOR, ADDR_ALICE, 1);
+        mock::take_contract_calls();
+        call::<()>(ADDR_CURATOR, redeem, Vec::new());
+        assert!(nft_moves().is_empty());
+
+        send(ADDR_ALICE, ADDR_CURATOR, 1);
+        call::<()>(ADDR_CURATOR, redeem, Vec::new());
+        assert_eq!(
+            nft_moves(),
+            [(CONTRACT.to_string(), ADDR_CURATOR.to_string())]
+        );
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Redeemed);
+        assert_eq!(load_supply().unwrap(), 0);
+        offer(ADDR_BOB, start_auction, 1_000_000);
+        assert_eq!(load_auction().unwrap().status, AuctionStatus::Redeemed);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "fractional-vault-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
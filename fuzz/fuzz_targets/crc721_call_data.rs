@@ -0,0 +1,14 @@
+//! Arbitrary call data against every crc721 entrypoint
+//!
+//! The first input byte picks the entrypoint; the rest is its call data.
+
+#![no_main]
+
+use crc721_contract::fuzzing;
+use libfuzzer_sys::fuzz_target;
+use silica_contract_sdk::testing;
+
+fuzz_target!(|input: &[u8]| {
+    fuzzing::setup();
+    testing::fuzz_call(fuzzing::HANDLERS, fuzzing::ALLOWED_PANICS, input);
+});
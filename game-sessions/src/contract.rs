@@ -0,0 +1,933 @@
+//! Session entrypoints
+
+use crate::rules::{self, MoveOutcome, MoveRequest};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const NEXT_SESSION_KEY: &str = "next_session_id";
+const SESSIONS_PREFIX: &str = "sessions";
+const LOBBY_PREFIX: &str = "lobby";
+const MIN_PLAYERS: u8 = 2;
+const MAX_PLAYERS: u8 = 8;
+const MIN_MOVE_TIMEOUT: u64 = 60;
+const MAX_MOVE_TIMEOUT: u64 = 7 * 24 * 60 * 60;
+const MAX_STATE_BYTES: usize = 2048;
+const MAX_MOVE_BYTES: usize = 512;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Everything that defines a game; `quick_match` pairs players whose
+/// parameters are identical
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SessionParams {
+    /// Contract implementing [`crate::rules`]
+    pub rules: String,
+    /// CRC-20 for wagers; `None` for free games
+    pub token: Option<String>,
+    /// Stake per player; must be zero for free games
+    pub wager: u64,
+    pub max_players: u8,
+    /// Seconds each player has to move
+    pub move_timeout: u64,
+    pub initial_state: Vec<u8>,
+}
+
+/// Where a session is in its lifecycle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// Seats are still open
+    Waiting,
+    /// All seats taken; players move in turn
+    Active,
+    /// The pot went to `winner`, or was split on a draw if `None`
+    Finished { winner: Option<String> },
+    /// The creator left before the game started; wagers were refunded
+    Cancelled,
+}
+
+/// A game session
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub params: SessionParams,
+    pub creator: String,
+    /// Seated players, in turn order
+    pub players: Vec<String>,
+    /// Whether each seat is still in the game
+    pub active: Vec<bool>,
+    pub state: Vec<u8>,
+    /// Seat whose turn it is
+    pub turn: u8,
+    pub turn_deadline: u64,
+    pub move_count: u64,
+    pub status: SessionStatus,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionIdArgs {
+    session_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SubmitMoveArgs {
+    session_id: u64,
+    move_data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn sessions() -> Map<u64, Session> {
+    Map::new(SESSIONS_PREFIX)
+}
+
+/// Waiting `quick_match` session by parameter hash
+fn lobby() -> Map<[u8; 32], u64> {
+    Map::new(LOBBY_PREFIX)
+}
+
+fn lobby_key(params: &SessionParams) -> ContractResult<[u8; 32]> {
+    Ok(crypto::hash_blake3(&encode_call(params)?))
+}
+
+fn load_session(session_id: u64) -> ContractResult<Session> {
+    sessions()
+        .get(&session_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown session".to_string()))
+}
+
+fn validate_params(params: &SessionParams) -> ContractResult<()> {
+    let ctx = context();
+    validation::validate_address(&params.rules)?;
+    if !ctx.is_contract(&params.rules) {
+        return Err(ContractError::InvalidArgument(
+            "Rules must be a contract".to_string(),
+        ));
+    }
+    match &params.token {
+        Some(token) => {
+            validation::validate_address(token)?;
+            if !ctx.is_contract(token) {
+                return Err(ContractError::InvalidArgument(
+                    "Token must be a contract".to_string(),
+                ));
+            }
+            validation::validate_positive_amount(params.wager)?;
+            // The whole pot must fit in a u64.
+            params
+                .wager
+                .checked_mul(params.max_players as u64)
+                .ok_or(ContractError::Overflow)?;
+        }
+        None if params.wager != 0 => {
+            return Err(ContractError::InvalidArgument(
+                "Free games cannot have a wager".to_string(),
+            ));
+        }
+        None => {}
+    }
+    if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&params.max_players) {
+        return Err(ContractError::InvalidArgument(format!(
+            "Sessions seat {}-{} players",
+            MIN_PLAYERS, MAX_PLAYERS
+        )));
+    }
+    if !(MIN_MOVE_TIMEOUT..=MAX_MOVE_TIMEOUT).contains(&params.move_timeout) {
+        return Err(ContractError::InvalidArgument(
+            "Move timeout must be between 1 minute and 7 days".to_string(),
+        ));
+    }
+    if params.initial_state.len() > MAX_STATE_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "State cannot exceed {} bytes",
+            MAX_STATE_BYTES
+        )));
+    }
+    Ok(())
+}
+
+fn pull_wager(params: &SessionParams, from: &str) -> ContractResult<()> {
+    let Some(token) = &params.token else {
+        return Ok(());
+    };
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount: params.wager,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn pay(params: &SessionParams, to: &str, amount: u64) -> ContractResult<()> {
+    let Some(token) = &params.token else {
+        return Ok(());
+    };
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn require_active(session: &Session) -> ContractResult<()> {
+    if session.status != SessionStatus::Active {
+        return Err(ContractError::InvalidArgument(
+            "Session is not in play".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn create(params: SessionParams) -> ContractResult<u64> {
+    validate_params(&params)?;
+    let creator = context().sender().to_string();
+    let session_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_SESSION_KEY)?.unwrap_or(0);
+        store.set(NEXT_SESSION_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    let session = Session {
+        state: params.initial_state.clone(),
+        params,
+        creator: creator.clone(),
+        players: Vec::new(),
+        active: Vec::new(),
+        turn: 0,
+        turn_deadline: 0,
+        move_count: 0,
+        status: SessionStatus::Waiting,
+    };
+
+    event!("SessionCreated",
+        session_id: session_id,
+        creator: creator,
+        rules: session.params.rules.clone(),
+        wager: session.params.wager,
+        max_players: session.params.max_players
+    );
+    seat(session_id, session, &creator)?;
+    Ok(session_id)
+}
+
+/// Take the wager and seat `player`; starts the game once every seat is taken.
+fn seat(session_id: u64, mut session: Session, player: &str) -> ContractResult<()> {
+    if session.status != SessionStatus::Waiting {
+        return Err(ContractError::InvalidArgument(
+            "Session is not open".to_string(),
+        ));
+    }
+    if session.players.iter().any(|seated| seated == player) {
+        return Err(ContractError::InvalidArgument("Already seated".to_string()));
+    }
+    pull_wager(&session.params, player)?;
+    session.players.push(player.to_string());
+    session.active.push(true);
+
+    let seat = session.players.len() - 1;
+    event!("PlayerJoined", session_id: session_id, player: player, seat: seat);
+
+    if session.players.len() == session.params.max_players as usize {
+        session.status = SessionStatus::Active;
+        session.turn_deadline =
+            safe_math::add(context().block_timestamp(), session.params.move_timeout)?;
+        let key = lobby_key(&session.params)?;
+        let mut waiting = lobby();
+        if waiting.get(&key)? == Some(session_id) {
+            waiting.remove(&key)?;
+        }
+        event!("SessionStarted",
+            session_id: session_id,
+            turn_deadline: session.turn_deadline
+        );
+    }
+    sessions().set(&session_id, &session)
+}
+
+/// Pass the turn to the next seat still in the game.
+fn advance_turn(session: &mut Session) -> ContractResult<()> {
+    let seats = session.players.len();
+    let mut next = session.turn as usize;
+    for _ in 0..seats {
+        next = (next + 1) % seats;
+        if session.active[next] {
+            break;
+        }
+    }
+    session.turn = next as u8;
+    session.turn_deadline =
+        safe_math::add(context().block_timestamp(), session.params.move_timeout)?;
+    Ok(())
+}
+
+/// Record the result, then pay the pot to `winner` or split it among the
+/// players still in the game.
+fn settle(session_id: u64, session: &mut Session, winner: Option<u8>) -> ContractResult<()> {
+    let pot = session.params.wager * session.players.len() as u64;
+    let winner = winner.map(|seat| session.players[seat as usize].clone());
+    session.status = SessionStatus::Finished {
+        winner: winner.clone(),
+    };
+    sessions().set(&session_id, session)?;
+
+    if let Some(winner) = &winner {
+        pay(&session.params, winner, pot)?;
+    } else if pot > 0 {
+        let remaining: Vec<&String> = session
+            .players
+            .iter()
+            .zip(&session.active)
+            .filter_map(|(player, active)| active.then_some(player))
+            .collect();
+        let share = pot / remaining.len() as u64;
+        // Rounding dust goes to the earliest remaining seat.
+        let dust = pot - share * remaining.len() as u64;
+        for (index, player) in remaining.into_iter().enumerate() {
+            let amount = if index == 0 { share + dust } else { share };
+            pay(&session.params, player, amount)?;
+        }
+    }
+
+    event!("SessionFinished",
+        session_id: session_id,
+        winner: winner.unwrap_or_default(),
+        pot: pot
+    );
+    Ok(())
+}
+
+fn execute_create_session() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    create(read_args()?)
+}
+
+fn execute_quick_match() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let params: SessionParams = read_args()?;
+    let key = lobby_key(&params)?;
+    if let Some(session_id) = lobby().get(&key)? {
+        seat(session_id, load_session(session_id)?, context().sender())?;
+        return Ok(session_id);
+    }
+    let session_id = create(params)?;
+    lobby().set(&key, &session_id)?;
+    Ok(session_id)
+}
+
+fn execute_join_session() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let args: SessionIdArgs = read_args()?;
+    seat(
+        args.session_id,
+        load_session(args.session_id)?,
+        context().sender(),
+    )
+}
+
+fn execute_leave_session() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let caller = context().sender().to_string();
+    let args: SessionIdArgs = read_args()?;
+    let mut session = load_session(args.session_id)?;
+    if session.status != SessionStatus::Waiting {
+        return Err(ContractError::InvalidArgument(
+            "Players can only leave before the game starts".to_string(),
+        ));
+    }
+    let seat = session
+        .players
+        .iter()
+        .position(|player| *player == caller)
+        .ok_or(ContractError::Unauthorized)?;
+
+    // The creator leaving cancels the session for everyone.
+    let refunds = if caller == session.creator {
+        session.status = SessionStatus::Cancelled;
+        let key = lobby_key(&session.params)?;
+        let mut waiting = lobby();
+        if waiting.get(&key)? == Some(args.session_id) {
+            waiting.remove(&key)?;
+        }
+        core::mem::take(&mut session.players)
+    } else {
+        session.active.remove(seat);
+        vec![session.players.remove(seat)]
+    };
+    sessions().set(&args.session_id, &session)?;
+    for player in &refunds {
+        pay(&session.params, player, session.params.wager)?;
+    }
+
+    if session.status == SessionStatus::Cancelled {
+        event!("SessionCancelled", session_id: args.session_id);
+    } else {
+        event!("PlayerLeft", session_id: args.session_id, player: caller);
+    }
+    Ok(())
+}
+
+fn execute_submit_move() -> ContractResult<SessionStatus> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let caller = ctx.sender().to_string();
+    let args: SubmitMoveArgs = read_args()?;
+    if args.move_data.len() > MAX_MOVE_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Moves cannot exceed {} bytes",
+            MAX_MOVE_BYTES
+        )));
+    }
+    let mut session = load_session(args.session_id)?;
+    require_active(&session)?;
+    if session.players[session.turn as usize] != caller {
+        return Err(ContractError::InvalidArgument("Not your turn".to_string()));
+    }
+    if ctx.block_timestamp() >= session.turn_deadline {
+        return Err(ContractError::InvalidArgument(
+            "Move deadline has passed".to_string(),
+        ));
+    }
+
+    let verdict = rules::validate_move(
+        &session.params.rules,
+        &MoveRequest {
+            session_id: args.session_id,
+            state: session.state.clone(),
+            players: session.players.clone(),
+            active: session.active.clone(),
+            player: session.turn,
+            move_data: args.move_data,
+        },
+    )?;
+    if verdict.state.len() > MAX_STATE_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "State cannot exceed {} bytes",
+            MAX_STATE_BYTES
+        )));
+    }
+    session.state = verdict.state;
+    session.move_count = safe_math::add(session.move_count, 1)?;
+
+    event!("MoveMade",
+        session_id: args.session_id,
+        player: caller,
+        move_number: session.move_count
+    );
+
+    match verdict.outcome {
+        MoveOutcome::Continue => {
+            advance_turn(&mut session)?;
+            sessions().set(&args.session_id, &session)?;
+        }
+        MoveOutcome::Win { seat } => settle(args.session_id, &mut session, Some(seat))?,
+        MoveOutcome::Draw => settle(args.session_id, &mut session, None)?,
+    }
+    Ok(session.status)
+}
+
+fn execute_claim_timeout() -> ContractResult<SessionStatus> {
+    let _guard = ReentrancyGuard::enter()?;
+    let args: SessionIdArgs = read_args()?;
+    let mut session = load_session(args.session_id)?;
+    require_active(&session)?;
+    if context().block_timestamp() < session.turn_deadline {
+        return Err(ContractError::InvalidArgument(
+            "Move deadline has not passed".to_string(),
+        ));
+    }
+
+    let timed_out = session.turn;
+    session.active[timed_out as usize] = false;
+    event!("PlayerTimedOut",
+        session_id: args.session_id,
+        player: session.players[timed_out as usize].clone()
+    );
+
+    let mut remaining = session
+        .active
+        .iter()
+        .enumerate()
+        .filter(|(_, active)| **active);
+    match (remaining.next(), remaining.next()) {
+        (Some((last, _)), None) => settle(args.session_id, &mut session, Some(last as u8))?,
+        _ => {
+            advance_turn(&mut session)?;
+            sessions().set(&args.session_id, &session)?;
+        }
+    }
+    Ok(session.status)
+}
+
+/// Create a session and take its first seat (approve the wager first)
+///
+/// # Arguments
+/// * `rules` - Rules contract
+/// * `token` - CRC-20 for wagers, or `None` for a free game
+/// * `wager` - Stake per player
+/// * `max_players` - Seats (2-8); the game starts when all are taken
+/// * `move_timeout` - Seconds per move (1 minute to 7 days)
+/// * `initial_state` - Starting state passed to the rules (max 2048 bytes)
+///
+/// # Returns
+/// Session id
+#[unsafe(no_mangle)]
+pub extern "C" fn create_session() {
+    let result = execute_create_session().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("create_session failed: {}", err);
+    }
+}
+
+/// Join the waiting session with exactly these parameters, or open one
+/// (approve the wager first)
+///
+/// # Arguments
+/// Same as `create_session`
+///
+/// # Returns
+/// Session id
+#[unsafe(no_mangle)]
+pub extern "C" fn quick_match() {
+    let result = execute_quick_match().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("quick_match failed: {}", err);
+    }
+}
+
+/// Take a seat in a waiting session (approve the wager first)
+///
+/// # Arguments
+/// * `session_id` - Session id
+#[unsafe(no_mangle)]
+pub extern "C" fn join_session() {
+    if let Err(err) = execute_join_session() {
+        log!("join_session failed: {}", err);
+    }
+}
+
+/// Leave a session before it starts and recover the wager
+///
+/// If the creator leaves, the session is cancelled and every player is
+/// refunded.
+///
+/// # Arguments
+/// * `session_id` - Session id
+#[unsafe(no_mangle)]
+pub extern "C" fn leave_session() {
+    if let Err(err) = execute_leave_session() {
+        log!("leave_session failed: {}", err);
+    }
+}
+
+/// Make a move on your turn; the rules contract validates it
+///
+/// # Arguments
+/// * `session_id` - Session id
+/// * `move_data` - Move encoded for the rules contract (max 512 bytes)
+///
+/// # Returns
+/// `SessionStatus` after the move
+#[unsafe(no_mangle)]
+pub extern "C" fn submit_move() {
+    let result = execute_submit_move().and_then(|status| try_respond(&status));
+    if let Err(err) = result {
+        log!("submit_move failed: {}", err);
+    }
+}
+
+/// Eliminate the current player once their move deadline has passed
+///
+/// If one player is left, they win the pot.
+///
+/// # Arguments
+/// * `session_id` - Session id
+///
+/// # Returns
+/// `SessionStatus` after the timeout
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_timeout() {
+    let result = execute_claim_timeout().and_then(|status| try_respond(&status));
+    if let Err(err) = result {
+        log!("claim_timeout failed: {}", err);
+    }
+}
+
+/// Get a session
+///
+/// # Arguments
+/// * `session_id` - Session id
+///
+/// # Returns
+/// `Session`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_session() {
+    let result = read_args::<SessionIdArgs>()
+        .and_then(|args| load_session(args.session_id))
+        .and_then(|session| try_respond(&session));
+    if let Err(err) = result {
+        log!("get_session failed: {}", err);
+    }
+}
+
+/// Find the waiting `quick_match` session for a set of parameters
+///
+/// # Arguments
+/// Same as `create_session`
+///
+/// # Returns
+/// `Option<u64>` session id
+#[unsafe(no_mangle)]
+pub extern "C" fn find_match() {
+    let result = read_args::<SessionParams>()
+        .and_then(|params| lobby().get(&lobby_key(&params)?))
+        .and_then(|session_id| try_respond(&session_id));
+    if let Err(err) = result {
+        log!("find_match failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::rules::MoveVerdict;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const RULES: &str = "0x0000000000000000000000000000000000000f02";
+    const START: u64 = 1_736_000_000;
+    const TIMEOUT: u64 = 300;
+    /// Move that makes the race rules declare a draw
+    const DRAW: u8 = 9;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    /// Race to 5: the state is a counter, each move adds 1 or 2, and
+    /// whoever reaches 5 wins.
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+        mock::register_contract(RULES, "validate_move", |args| {
+            let request: MoveRequest = postcard::from_bytes(args).unwrap();
+            let step = request.move_data[0];
+            let total = request.state[0] + step;
+            let outcome = match step {
+                DRAW => MoveOutcome::Draw,
+                1 | 2 if total >= 5 => MoveOutcome::Win {
+                    seat: request.player,
+                },
+                1 | 2 => MoveOutcome::Continue,
+                _ => {
+                    return Err(ContractError::InvalidArgument("Illegal move".to_string()));
+                }
+            };
+            Ok(postcard::to_allocvec(&MoveVerdict {
+                state: vec![total],
+                outcome,
+            })
+            .unwrap())
+        });
+    }
+
+    fn params(wager: u64, max_players: u8) -> SessionParams {
+        SessionParams {
+            rules: RULES.to_string(),
+            token: Some(TOKEN.to_string()),
+            wager,
+            max_players,
+            move_timeout: TIMEOUT,
+            initial_state: vec![0],
+        }
+    }
+
+    fn matchmake(player: &str, params: SessionParams) -> u64 {
+        postcard::from_bytes(&call(player, quick_match, encode(&params))).unwrap()
+    }
+
+    fn play(player: &str, session_id: u64, step: u8) -> Vec<u8> {
+        call(
+            player,
+            submit_move,
+            encode(&SubmitMoveArgs {
+                session_id,
+                move_data: vec![step],
+            }),
+        )
+    }
+
+    /// (recipient, amount) of every payout since the last call
+    fn payouts() -> Vec<(String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer")
+            .map(|c| {
+                let args: TokenTransferArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.to, args.amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quick_match_pairs_identical_parameters() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        let first = matchmake(ADDR_ALICE, params(100, 2));
+        let other = matchmake(ADDR_CAROL, params(50, 2));
+        assert_ne!(first, other);
+        let joined = matchmake(ADDR_BOB, params(100, 2));
+        assert_eq!(joined, first);
+
+        let session = load_session(first).unwrap();
+        assert_eq!(session.status, SessionStatus::Active);
+        assert_eq!(session.players, [ADDR_ALICE, ADDR_BOB]);
+        assert_eq!(session.turn_deadline, START + TIMEOUT);
+        // The full session left the lobby; the next player opens a new one.
+        assert_ne!(matchmake(ADDR_CAROL, params(100, 2)), first);
+    }
+
+    #[test]
+    fn turns_are_enforced_and_the_winner_takes_the_pot() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = matchmake(ADDR_ALICE, params(100, 2));
+        matchmake(ADDR_BOB, params(100, 2));
+        mock::take_contract_calls();
+
+        assert!(play(ADDR_BOB, id, 1).is_empty());
+        // The rules reject a step of 3.
+        assert!(play(ADDR_ALICE, id, 3).is_empty());
+        play(ADDR_ALICE, id, 2);
+        play(ADDR_BOB, id, 1);
+        let status: SessionStatus = postcard::from_bytes(&play(ADDR_ALICE, id, 2)).unwrap();
+        assert_eq!(
+            status,
+            SessionStatus::Finished {
+                winner: Some(ADDR_ALICE.to_string())
+            }
+        );
+        assert_eq!(load_session(id).unwrap().move_count, 3);
+        assert_eq!(payouts(), [(ADDR_ALICE.to_string(), 200)]);
+        assert!(play(ADDR_BOB, id, 1).is_empty());
+    }
+
+    #[test]
+    fn missed_deadlines_eliminate_players() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = matchmake(ADDR_ALICE, params(100, 3));
+        matchmake(ADDR_BOB, params(100, 3));
+        matchmake(ADDR_CAROL, params(100, 3));
+        mock::take_contract_calls();
+
+        call(
+            ADDR_BOB,
+            claim_timeout,
+            encode(&SessionIdArgs { session_id: id }),
+        );
+        assert_eq!(load_session(id).unwrap().active, [true, true, true]);
+
+        // Alice misses her move; Bob is up next with a fresh deadline.
+        mock::set_block_timestamp(START + TIMEOUT);
+        call(
+            ADDR_BOB,
+            claim_timeout,
+            encode(&SessionIdArgs { session_id: id }),
+        );
+        let session = load_session(id).unwrap();
+        assert_eq!(session.active, [false, true, true]);
+        assert_eq!(
+            (session.turn, session.turn_deadline),
+            (1, START + 2 * TIMEOUT)
+        );
+        assert!(play(ADDR_ALICE, id, 1).is_empty());
+
+        // Bob moves, then Carol times out and Bob is the last one left.
+        play(ADDR_BOB, id, 1);
+        mock::set_block_timestamp(START + 3 * TIMEOUT);
+        call(
+            ADDR_ALICE,
+            claim_timeout,
+            encode(&SessionIdArgs { session_id: id }),
+        );
+        assert_eq!(
+            load_session(id).unwrap().status,
+            SessionStatus::Finished {
+                winner: Some(ADDR_BOB.to_string())
+            }
+        );
+        assert_eq!(payouts(), [(ADDR_BOB.to_string(), 300)]);
+    }
+
+    #[test]
+    fn draws_split_the_pot_among_remaining_players() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = matchmake(ADDR_ALICE, params(101, 3));
+        matchmake(ADDR_BOB, params(101, 3));
+        matchmake(ADDR_CAROL, params(101, 3));
+        mock::set_block_timestamp(START + TIMEOUT);
+        call(
+            ADDR_CAROL,
+            claim_timeout,
+            encode(&SessionIdArgs { session_id: id }),
+        );
+        mock::take_contract_calls();
+
+        play(ADDR_BOB, id, DRAW);
+        assert_eq!(
+            load_session(id).unwrap().status,
+            SessionStatus::Finished { winner: None }
+        );
+        assert_eq!(
+            payouts(),
+            [(ADDR_BOB.to_string(), 152), (ADDR_CAROL.to_string(), 151)]
+        );
+    }
+
+    #[test]
+    fn leaving_before_the_start_refunds_wagers() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = matchmake(ADDR_ALICE, params(100, 3));
+        call(
+            ADDR_BOB,
+            join_session,
+            encode(&SessionIdArgs { session_id: id }),
+        );
+        call(
+            ADDR_CAROL,
+            join_session,
+            encode(&SessionIdArgs { session_id: 99 }),
+        );
+        mock::take_contract_calls();
+
+        call(
+            ADDR_BOB,
+            leave_session,
+            encode(&SessionIdArgs { session_id: id }),
+        );
+        assert_eq!(load_session(id).unwrap().players, [ADDR_ALICE]);
+        call(
+            ADDR_ALICE,
+            leave_session,
+            encode(&SessionIdArgs { session_id: id }),
+        );
+        assert_eq!(load_session(id).unwrap().status, SessionStatus::Cancelled);
+        assert_eq!(
+            payouts(),
+            [(ADDR_BOB.to_string(), 100), (ADDR_ALICE.to_string(), 100)]
+        );
+        let lookup = call(ADDR_CAROL, find_match, encode(&params(100, 3)));
+        assert_eq!(postcard::from_bytes::<Option<u64>>(&lookup).unwrap(), None);
+
+        // Free games move no tokens.
+        let mut free = params(0, 2);
+        free.token = None;
+        let id = matchmake(ADDR_ALICE, free.clone());
+        matchmake(ADDR_BOB, free);
+        play(ADDR_ALICE, id, DRAW);
+        assert!(
+            mock::take_contract_calls()
+                .iter()
+                .all(|c| c.method == "validate_move")
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::set_sender(ADDR_ALICE);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "game-sessions-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
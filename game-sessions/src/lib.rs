@@ -0,0 +1,37 @@
+//! Turn-Based Game Sessions
+//!
+//! Session plumbing for turn-based games: matchmaking, wager escrow, turn
+//! order with move deadlines and an opaque per-session state blob. The
+//! game itself lives in a separate rules contract that validates each move
+//! and returns the new state and whether the game is over, so a studio only
+//! writes the rules.
+//!
+//! ## Features
+//! - Sessions for 2-8 players, joined by id or matched by identical parameters
+//! - CRC-20 wagers escrowed until the game ends (or free games)
+//! - Round-robin turns; a player who misses the move deadline is eliminated
+//! - Winner takes the pot; draws split it among the remaining players
+//!
+//! ## Writing rules
+//! ```toml
+//! game-sessions-contract = { path = "../game-sessions", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use game_sessions_contract::rules::{MoveOutcome, MoveRequest, MoveVerdict};
+//! ```
+//!
+//! See [`rules`] for the entrypoint a rules contract must export.
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod rules;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
@@ -0,0 +1,66 @@
+//! Rules interface
+//!
+//! A rules contract decides what a move means. The session contract calls
+//! it once per move, after checking that the mover is the current player
+//! and the move deadline has not passed:
+//!
+//! | Method          | Arguments     | Returns       | Effect                                  |
+//! |-----------------|---------------|---------------|-----------------------------------------|
+//! | `validate_move` | `MoveRequest` | `MoveVerdict` | Fail to reject the move, else new state |
+//!
+//! Rules should be pure functions of the request: the session contract
+//! stores the state, so rules need no storage of their own. A failed call
+//! rejects the move and leaves the session unchanged.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+/// Arguments of the rules `validate_move` entrypoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MoveRequest {
+    pub session_id: u64,
+    /// State before the move
+    pub state: Vec<u8>,
+    /// Every seated player, in turn order
+    pub players: Vec<String>,
+    /// Players still in the game, by seat
+    pub active: Vec<bool>,
+    /// Seat of the player moving
+    pub player: u8,
+    pub move_data: Vec<u8>,
+}
+
+/// What a valid move did to the game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum MoveOutcome {
+    /// The game goes on with the next player
+    Continue,
+    /// The player in `seat` won
+    Win { seat: u8 },
+    /// The game ended without a winner
+    Draw,
+}
+
+/// Response of the rules `validate_move` entrypoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MoveVerdict {
+    /// State after the move
+    pub state: Vec<u8>,
+    pub outcome: MoveOutcome,
+}
+
+/// Ask `rules` to apply `request`; fails if the move is invalid.
+pub fn validate_move(rules: &str, request: &MoveRequest) -> ContractResult<MoveVerdict> {
+    let args = postcard::to_allocvec(request).map_err(|_| ContractError::SerializationFailed)?;
+    let response = context().call_contract(rules, "validate_move", &args)?;
+    let verdict: MoveVerdict =
+        postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)?;
+    if let MoveOutcome::Win { seat } = verdict.outcome
+        && !request.active.get(seat as usize).copied().unwrap_or(false)
+    {
+        return Err(ContractError::InvalidArgument(
+            "Rules declared a winner who is not in the game".to_string(),
+        ));
+    }
+    Ok(verdict)
+}
@@ -0,0 +1,1045 @@
+//! Grants and Bounties
+//!
+//! Funders post bounties split into milestones and escrow the full reward up
+//! front. An applicant claims a bounty and submits the milestones in order.
+//! Each submission is approved or rejected by the funder or a committee
+//! member, and each approval pays that milestone out. A rejected
+//! submission can be reworked or disputed, and disputes are settled by the
+//! committee alone. Timeouts cover both sides: a submission nobody reviews
+//! within the review period can be paid out by the claimant, and a claimant
+//! who goes quiet for longer than the work period can be released so the
+//! bounty reopens.
+//!
+//! ## Features
+//! - Up to 10 milestones per bounty, escrowed when posted
+//! - Review by the funder or the committee, paid per milestone
+//! - Disputes resolved by the committee
+//! - Review and work timeouts so neither side can stall the other
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "grants_config";
+const NEXT_BOUNTY_ID_KEY: &str = "next_bounty_id";
+const BOUNTIES_PREFIX: &str = "bounties";
+const COMMITTEE_PREFIX: &str = "committee";
+const COMMITTEE_ROLE: &str = "committee";
+const MAX_MILESTONES: usize = 10;
+const MAX_TEXT_BYTES: usize = 256;
+const MIN_PERIOD: u64 = 86_400;
+const MAX_PERIOD: u64 = 90 * 86_400;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Contract configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GrantsConfig {
+    /// Manages the committee
+    pub owner: String,
+    pub committee_size: u64,
+}
+
+/// Lifecycle of a bounty
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BountyStatus {
+    /// Waiting for an applicant
+    Open,
+    /// An applicant is working through the milestones
+    Claimed,
+    /// Every milestone was paid
+    Completed,
+    /// Withdrawn by the funder; the unpaid reward was refunded
+    Cancelled,
+}
+
+/// Review state of the current milestone
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MilestoneState {
+    /// Being worked on
+    Pending,
+    /// Waiting for review
+    Submitted,
+    /// Sent back by a reviewer; rework or dispute
+    Rejected,
+    /// Waiting for the committee
+    Disputed,
+}
+
+/// A bounty and the progress of its claimant
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Bounty {
+    pub funder: String,
+    /// CRC-20 the reward is paid in
+    pub token: String,
+    pub title: String,
+    /// Payout of each milestone, in order
+    pub milestones: Vec<u64>,
+    /// Seconds the claimant has to submit or rework a milestone
+    pub work_period: u64,
+    /// Seconds reviewers have to answer a submission
+    pub review_period: u64,
+    pub status: BountyStatus,
+    pub claimant: Option<String>,
+    /// Index of the milestone being worked on; earlier ones are paid
+    pub current: u32,
+    pub milestone_state: MilestoneState,
+    /// When `milestone_state` last changed
+    pub updated_at: u64,
+    /// Latest submission or rejection reason
+    pub note: String,
+    pub paid: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PostBountyArgs {
+    token: String,
+    title: String,
+    milestones: Vec<u64>,
+    work_period: u64,
+    review_period: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BountyIdArgs {
+    bounty_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoteArgs {
+    bounty_id: u64,
+    note: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ResolveArgs {
+    bounty_id: u64,
+    approve: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommitteeArgs {
+    account: String,
+    member: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<GrantsConfig> {
+    storage()
+        .get::<GrantsConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn load_owned_config() -> ContractResult<GrantsConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn bounties() -> Map<u64, Bounty> {
+    Map::new(BOUNTIES_PREFIX)
+}
+
+fn committee() -> Map<String, bool> {
+    Map::new(COMMITTEE_PREFIX)
+}
+
+fn load_bounty(bounty_id: u64) -> ContractResult<Bounty> {
+    bounties()
+        .get(&bounty_id)?
+        .ok_or_else(|| ContractError::InvalidArgument(format!("Bounty {} not found", bounty_id)))
+}
+
+fn is_committee_member(account: &str) -> ContractResult<bool> {
+    committee().contains_key(&account.to_string())
+}
+
+fn require_committee(account: &str) -> ContractResult<()> {
+    if !is_committee_member(account)? {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn require_reviewer(bounty: &Bounty, account: &str) -> ContractResult<()> {
+    if account == bounty.funder {
+        return Ok(());
+    }
+    require_committee(account)
+}
+
+fn require_claimant(bounty: &Bounty, account: &str) -> ContractResult<()> {
+    if bounty.claimant.as_deref() != Some(account) {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn expect_status(bounty: &Bounty, status: BountyStatus) -> ContractResult<()> {
+    if bounty.status != status {
+        return Err(ContractError::InvalidArgument(format!(
+            "Bounty is {:?}, expected {:?}",
+            bounty.status, status
+        )));
+    }
+    Ok(())
+}
+
+fn expect_milestone(bounty: &Bounty, states: &[MilestoneState]) -> ContractResult<()> {
+    expect_status(bounty, BountyStatus::Claimed)?;
+    if !states.contains(&bounty.milestone_state) {
+        return Err(ContractError::InvalidArgument(format!(
+            "Milestone is {:?}",
+            bounty.milestone_state
+        )));
+    }
+    Ok(())
+}
+
+fn validate_note(note: &str) -> ContractResult<()> {
+    if note.is_empty() || note.len() > MAX_TEXT_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Text must be 1-{} bytes",
+            MAX_TEXT_BYTES
+        )));
+    }
+    Ok(())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn set_milestone_state(bounty: &mut Bounty, state: MilestoneState, note: String) {
+    bounty.milestone_state = state;
+    bounty.updated_at = context().block_timestamp();
+    bounty.note = note;
+}
+
+/// Pay the current milestone to the claimant and move on to the next.
+fn pay_milestone(bounty_id: u64, mut bounty: Bounty) -> ContractResult<()> {
+    let claimant = bounty.claimant.clone().unwrap_or_default();
+    let milestone = bounty.current;
+    let amount = bounty.milestones[milestone as usize];
+    bounty.paid = safe_math::add(bounty.paid, amount)?;
+    bounty.current += 1;
+    if bounty.current as usize == bounty.milestones.len() {
+        bounty.status = BountyStatus::Completed;
+    }
+    set_milestone_state(&mut bounty, MilestoneState::Pending, String::new());
+    bounties().set(&bounty_id, &bounty)?;
+
+    send_tokens(&bounty.token, &claimant, amount)?;
+
+    event!("MilestonePaid",
+        bounty_id: bounty_id,
+        milestone: milestone,
+        claimant: claimant,
+        amount: amount
+    );
+    if bounty.status == BountyStatus::Completed {
+        event!("BountyCompleted", bounty_id: bounty_id, paid: bounty.paid);
+    }
+    Ok(())
+}
+
+/// Drop the claimant and reopen the bounty at its current milestone.
+fn reopen(bounty_id: u64, mut bounty: Bounty) -> ContractResult<()> {
+    let claimant = bounty.claimant.take().unwrap_or_default();
+    bounty.status = BountyStatus::Open;
+    set_milestone_state(&mut bounty, MilestoneState::Pending, String::new());
+    bounties().set(&bounty_id, &bounty)?;
+
+    event!("ClaimReleased", bounty_id: bounty_id, claimant: claimant);
+    Ok(())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let owner = context().sender().to_string();
+    store.set(
+        CONFIG_KEY,
+        &GrantsConfig {
+            owner: owner.clone(),
+            committee_size: 0,
+        },
+    )?;
+
+    event!("GrantsInitialized", owner: owner);
+    Ok(())
+}
+
+fn execute_set_committee() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: CommitteeArgs = read_args()?;
+    validation::validate_address(&args.account)?;
+    let was_member = is_committee_member(&args.account)?;
+    if args.member == was_member {
+        return Ok(());
+    }
+    if args.member {
+        committee().set(&args.account, &true)?;
+        config.committee_size = safe_math::add(config.committee_size, 1)?;
+    } else {
+        committee().remove(&args.account)?;
+        config.committee_size = safe_math::sub(config.committee_size, 1)?;
+    }
+    let mut store = storage();
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("CommitteeUpdated", account: args.account, member: args.member);
+    if args.member {
+        admin_log::record(admin_log::GRANT_ROLE, &args.account, "", COMMITTEE_ROLE)?;
+    } else {
+        admin_log::record(admin_log::REVOKE_ROLE, &args.account, COMMITTEE_ROLE, "")?;
+    }
+    Ok(())
+}
+
+fn execute_post_bounty() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let funder = ctx.sender().to_string();
+    load_config()?;
+    let args: PostBountyArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    validate_note(&args.title)?;
+    if args.milestones.is_empty() || args.milestones.len() > MAX_MILESTONES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Bounties need 1-{} milestones",
+            MAX_MILESTONES
+        )));
+    }
+    let mut total = 0u64;
+    for amount in &args.milestones {
+        validation::validate_positive_amount(*amount)?;
+        total = safe_math::add(total, *amount)?;
+    }
+    validation::validate_range(args.work_period, MIN_PERIOD, MAX_PERIOD)?;
+    validation::validate_range(args.review_period, MIN_PERIOD, MAX_PERIOD)?;
+
+    let mut store = storage();
+    let bounty_id = store.get::<u64>(NEXT_BOUNTY_ID_KEY)?.unwrap_or(0);
+    store.set(NEXT_BOUNTY_ID_KEY, &safe_math::add(bounty_id, 1)?)?;
+    bounties().set(
+        &bounty_id,
+        &Bounty {
+            funder: funder.clone(),
+            token: args.token.clone(),
+            title: args.title.clone(),
+            milestones: args.milestones.clone(),
+            work_period: args.work_period,
+            review_period: args.review_period,
+            status: BountyStatus::Open,
+            claimant: None,
+            current: 0,
+            milestone_state: MilestoneState::Pending,
+            updated_at: ctx.block_timestamp(),
+            note: String::new(),
+            paid: 0,
+        },
+    )?;
+
+    let call = encode_call(&TokenTransferFromArgs {
+        from: funder.clone(),
+        to: ctx.contract_address().to_string(),
+        amount: total,
+    })?;
+    ctx.call_contract(&args.token, "transfer_from", &call)?;
+
+    event!("BountyPosted",
+        bounty_id: bounty_id,
+        funder: funder,
+        token: args.token,
+        title: args.title,
+        milestones: args.milestones.len() as u64,
+        total: total
+    );
+    Ok(bounty_id)
+}
+
+fn execute_cancel() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: BountyIdArgs = read_args()?;
+    let mut bounty = load_bounty(args.bounty_id)?;
+    if ctx.sender() != bounty.funder {
+        return Err(ContractError::Unauthorized);
+    }
+    expect_status(&bounty, BountyStatus::Open)?;
+    let refund = bounty.milestones[bounty.current as usize..].iter().sum();
+    bounty.status = BountyStatus::Cancelled;
+    bounties().set(&args.bounty_id, &bounty)?;
+
+    send_tokens(&bounty.token, &bounty.funder, refund)?;
+
+    event!("BountyCancelled", bounty_id: args.bounty_id, refund: refund);
+    Ok(refund)
+}
+
+fn execute_claim() -> ContractResult<()> {
+    let ctx = context();
+    let applicant = ctx.sender().to_string();
+    let args: BountyIdArgs = read_args()?;
+    let mut bounty = load_bounty(args.bounty_id)?;
+    expect_status(&bounty, BountyStatus::Open)?;
+    if applicant == bounty.funder {
+        return Err(ContractError::InvalidArgument(
+            "Funder cannot claim their own bounty".to_string(),
+        ));
+    }
+    bounty.status = BountyStatus::Claimed;
+    bounty.claimant = Some(applicant.clone());
+    set_milestone_state(&mut bounty, MilestoneState::Pending, String::new());
+    bounties().set(&args.bounty_id, &bounty)?;
+
+    event!("BountyClaimed", bounty_id: args.bounty_id, claimant: applicant);
+    Ok(())
+}
+
+fn execute_submit() -> ContractResult<()> {
+    let ctx = context();
+    let args: NoteArgs = read_args()?;
+    validate_note(&args.note)?;
+    let mut bounty = load_bounty(args.bounty_id)?;
+    require_claimant(&bounty, ctx.sender())?;
+    expect_milestone(
+        &bounty,
+        &[MilestoneState::Pending, MilestoneState::Rejected],
+    )?;
+    set_milestone_state(&mut bounty, MilestoneState::Submitted, args.note.clone());
+    bounties().set(&args.bounty_id, &bounty)?;
+
+    event!("MilestoneSubmitted",
+        bounty_id: args.bounty_id,
+        milestone: bounty.current,
+        note: args.note
+    );
+    Ok(())
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let args: BountyIdArgs = read_args()?;
+    let bounty = load_bounty(args.bounty_id)?;
+    require_reviewer(&bounty, context().sender())?;
+    expect_milestone(&bounty, &[MilestoneState::Submitted])?;
+    pay_milestone(args.bounty_id, bounty)
+}
+
+fn execute_reject() -> ContractResult<()> {
+    let args: NoteArgs = read_args()?;
+    validate_note(&args.note)?;
+    let mut bounty = load_bounty(args.bounty_id)?;
+    require_reviewer(&bounty, context().sender())?;
+    expect_milestone(&bounty, &[MilestoneState::Submitted])?;
+    set_milestone_state(&mut bounty, MilestoneState::Rejected, args.note.clone());
+    bounties().set(&args.bounty_id, &bounty)?;
+
+    event!("MilestoneRejected",
+        bounty_id: args.bounty_id,
+        milestone: bounty.current,
+        reason: args.note
+    );
+    Ok(())
+}
+
+fn execute_dispute() -> ContractResult<()> {
+    let args: NoteArgs = read_args()?;
+    validate_note(&args.note)?;
+    let mut bounty = load_bounty(args.bounty_id)?;
+    require_claimant(&bounty, context().sender())?;
+    expect_milestone(&bounty, &[MilestoneState::Rejected])?;
+    set_milestone_state(&mut bounty, MilestoneState::Disputed, args.note.clone());
+    bounties().set(&args.bounty_id, &bounty)?;
+
+    event!("MilestoneDisputed",
+        bounty_id: args.bounty_id,
+        milestone: bounty.current,
+        note: args.note
+    );
+    Ok(())
+}
+
+fn execute_resolve_dispute() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let args: ResolveArgs = read_args()?;
+    require_committee(context().sender())?;
+    let bounty = load_bounty(args.bounty_id)?;
+    expect_milestone(&bounty, &[MilestoneState::Disputed])?;
+
+    event!("DisputeResolved",
+        bounty_id: args.bounty_id,
+        milestone: bounty.current,
+        approved: args.approve
+    );
+    admin_log::record(
+        "resolve_dispute",
+        &args.bounty_id.to_string(),
+        "",
+        args.approve,
+    )?;
+    if args.approve {
+        pay_milestone(args.bounty_id, bounty)
+    } else {
+        reopen(args.bounty_id, bounty)
+    }
+}
+
+fn execute_claim_timeout() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: BountyIdArgs = read_args()?;
+    let bounty = load_bounty(args.bounty_id)?;
+    require_claimant(&bounty, ctx.sender())?;
+    expect_milestone(&bounty, &[MilestoneState::Submitted])?;
+    if ctx.block_timestamp() <= safe_math::add(bounty.updated_at, bounty.review_period)? {
+        return Err(ContractError::InvalidArgument(
+            "Review period has not expired".to_string(),
+        ));
+    }
+    pay_milestone(args.bounty_id, bounty)
+}
+
+fn execute_release_claim() -> ContractResult<()> {
+    let ctx = context();
+    let args: BountyIdArgs = read_args()?;
+    let bounty = load_bounty(args.bounty_id)?;
+    require_reviewer(&bounty, ctx.sender())?;
+    expect_milestone(
+        &bounty,
+        &[MilestoneState::Pending, MilestoneState::Rejected],
+    )?;
+    if ctx.block_timestamp() <= safe_math::add(bounty.updated_at, bounty.work_period)? {
+        return Err(ContractError::InvalidArgument(
+            "Work period has not expired".to_string(),
+        ));
+    }
+    reopen(args.bounty_id, bounty)
+}
+
+/// Initialize the contract; the caller becomes the owner
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Add or remove a committee member (owner only)
+///
+/// Committee members review any bounty and settle disputes.
+///
+/// # Arguments
+/// * `account` - Member address
+/// * `member` - `true` to add, `false` to remove
+#[unsafe(no_mangle)]
+pub extern "C" fn set_committee() {
+    if let Err(err) = execute_set_committee() {
+        log!("set_committee failed: {}", err);
+    }
+}
+
+/// Post a bounty and escrow its full reward
+///
+/// The caller must have approved the total of all milestones.
+///
+/// # Arguments
+/// * `token` - CRC-20 the reward is paid in
+/// * `title` - Description (1-256 bytes)
+/// * `milestones` - Payout per milestone (1-10 entries)
+/// * `work_period` - Time the claimant has per milestone (1-90 days)
+/// * `review_period` - Time reviewers have per submission (1-90 days)
+///
+/// # Returns
+/// Bounty ID (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn post_bounty() {
+    if let Err(err) = execute_post_bounty().and_then(|id| try_respond(&id)) {
+        log!("post_bounty failed: {}", err);
+    }
+}
+
+/// Cancel an unclaimed bounty and refund its unpaid milestones (funder only)
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+///
+/// # Returns
+/// Amount refunded (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel() {
+    if let Err(err) = execute_cancel().and_then(|refund| try_respond(&refund)) {
+        log!("cancel failed: {}", err);
+    }
+}
+
+/// Claim an open bounty
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+#[unsafe(no_mangle)]
+pub extern "C" fn claim() {
+    if let Err(err) = execute_claim() {
+        log!("claim failed: {}", err);
+    }
+}
+
+/// Submit the current milestone for review (claimant only)
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+/// * `note` - Link or description of the work (1-256 bytes)
+#[unsafe(no_mangle)]
+pub extern "C" fn submit() {
+    if let Err(err) = execute_submit() {
+        log!("submit failed: {}", err);
+    }
+}
+
+/// Approve a submission and pay the milestone (funder or committee)
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("approve failed: {}", err);
+    }
+}
+
+/// Send a submission back for rework (funder or committee)
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+/// * `note` - Reason (1-256 bytes)
+#[unsafe(no_mangle)]
+pub extern "C" fn reject() {
+    if let Err(err) = execute_reject() {
+        log!("reject failed: {}", err);
+    }
+}
+
+/// Dispute a rejection (claimant only)
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+/// * `note` - Grounds for the dispute (1-256 bytes)
+#[unsafe(no_mangle)]
+pub extern "C" fn dispute() {
+    if let Err(err) = execute_dispute() {
+        log!("dispute failed: {}", err);
+    }
+}
+
+/// Settle a dispute (committee only)
+///
+/// Approving pays the milestone; otherwise the claimant is released and the
+/// bounty reopens at the same milestone.
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+/// * `approve` - Whether the submission stands
+#[unsafe(no_mangle)]
+pub extern "C" fn resolve_dispute() {
+    if let Err(err) = execute_resolve_dispute() {
+        log!("resolve_dispute failed: {}", err);
+    }
+}
+
+/// Pay a submission nobody reviewed within the review period (claimant only)
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_timeout() {
+    if let Err(err) = execute_claim_timeout() {
+        log!("claim_timeout failed: {}", err);
+    }
+}
+
+/// Release a claimant who missed the work period (funder or committee)
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+#[unsafe(no_mangle)]
+pub extern "C" fn release_claim() {
+    if let Err(err) = execute_release_claim() {
+        log!("release_claim failed: {}", err);
+    }
+}
+
+/// Get a bounty
+///
+/// # Arguments
+/// * `bounty_id` - Bounty ID
+///
+/// # Returns
+/// `Bounty`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_bounty() {
+    let result = read_args::<BountyIdArgs>()
+        .and_then(|args| load_bounty(args.bounty_id))
+        .and_then(|bounty| try_respond(&bounty));
+    if let Err(err) = result {
+        log!("get_bounty failed: {}", err);
+    }
+}
+
+/// Check whether an account sits on the committee
+///
+/// # Arguments
+/// * `account` - Account address
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn is_committee() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| is_committee_member(&args.account))
+        .and_then(|member| try_respond(&member));
+    if let Err(err) = result {
+        log!("is_committee failed: {}", err);
+    }
+}
+
+/// Get the contract configuration
+///
+/// # Returns
+/// `GrantsConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_FUNDER: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_DEV: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_REVIEWER: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_OTHER: &str = "0x0000000000000000000000000000000000000e05";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const USDC: &str = "0x0000000000000000000000000000000000000f01";
+    const NOW: u64 = 1_736_000_000;
+    const DAY: u64 = 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// Bounty 0: milestones of 300 and 700 USDC, claimed by the dev
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        for method in ["transfer", "transfer_from"] {
+            mock::register_contract(USDC, method, |_| Ok(Vec::new()));
+        }
+        call::<()>(ADDR_OWNER, initialize, Vec::new());
+        call::<()>(
+            ADDR_OWNER,
+            set_committee,
+            encode(&CommitteeArgs {
+                account: ADDR_REVIEWER.to_string(),
+                member: true,
+            }),
+        );
+        let posted = call::<u64>(
+            ADDR_FUNDER,
+            post_bounty,
+            encode(&PostBountyArgs {
+                token: USDC.to_string(),
+                title: "Indexer plugin".to_string(),
+                milestones: vec![300, 700],
+                work_period: 14 * DAY,
+                review_period: 7 * DAY,
+            }),
+        );
+        assert_eq!(posted, Some(0));
+        call::<()>(ADDR_DEV, claim, id());
+    }
+
+    fn id() -> Vec<u8> {
+        encode(&BountyIdArgs { bounty_id: 0 })
+    }
+
+    fn note(text: &str) -> Vec<u8> {
+        encode(&NoteArgs {
+            bounty_id: 0,
+            note: text.to_string(),
+        })
+    }
+
+    fn payouts() -> Vec<(String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer")
+            .map(|c| {
+                let args: TokenTransferArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.to, args.amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn milestones_pay_out_in_order_after_approval() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let escrow: TokenTransferFromArgs =
+            postcard::from_bytes(&mock::take_contract_calls()[0].args).unwrap();
+        assert_eq!((escrow.to.as_str(), escrow.amount), (CONTRACT, 1_000));
+
+        call::<()>(ADDR_FUNDER, approve, id());
+        assert!(payouts().is_empty());
+        call::<()>(ADDR_DEV, submit, note("https://example.com/pr/1"));
+        call::<()>(ADDR_FUNDER, approve, id());
+        assert_eq!(payouts(), [(ADDR_DEV.to_string(), 300)]);
+        let bounty = load_bounty(0).unwrap();
+        assert_eq!((bounty.current, bounty.paid), (1, 300));
+
+        call::<()>(ADDR_DEV, submit, note("https://example.com/pr/2"));
+        call::<()>(ADDR_FUNDER, approve, id());
+        assert_eq!(payouts(), [(ADDR_DEV.to_string(), 700)]);
+        assert_eq!(load_bounty(0).unwrap().status, BountyStatus::Completed);
+    }
+
+    #[test]
+    fn only_the_funder_or_committee_review() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        call::<()>(ADDR_OTHER, submit, note("not my bounty"));
+        assert_eq!(
+            load_bounty(0).unwrap().milestone_state,
+            MilestoneState::Pending
+        );
+        call::<()>(ADDR_DEV, submit, note("done"));
+        for outsider in [ADDR_OTHER, ADDR_DEV] {
+            call::<()>(outsider, approve, id());
+        }
+        mock::take_contract_calls();
+        assert!(payouts().is_empty());
+
+        call::<()>(ADDR_REVIEWER, approve, id());
+        assert_eq!(payouts(), [(ADDR_DEV.to_string(), 300)]);
+
+        call::<()>(
+            ADDR_OWNER,
+            set_committee,
+            encode(&CommitteeArgs {
+                account: ADDR_REVIEWER.to_string(),
+                member: false,
+            }),
+        );
+        call::<()>(ADDR_DEV, submit, note("done again"));
+        call::<()>(ADDR_REVIEWER, approve, id());
+        assert!(payouts().is_empty());
+        assert_eq!(load_config().unwrap().committee_size, 0);
+    }
+
+    #[test]
+    fn disputes_are_settled_by_the_committee() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::take_contract_calls();
+        call::<()>(ADDR_DEV, submit, note("done"));
+        call::<()>(ADDR_FUNDER, reject, note("tests missing"));
+        assert_eq!(load_bounty(0).unwrap().note, "tests missing");
+        call::<()>(ADDR_DEV, dispute, note("tests are in the PR"));
+        assert_eq!(
+            load_bounty(0).unwrap().milestone_state,
+            MilestoneState::Disputed
+        );
+
+        // The funder is a party to the dispute.
+        let resolve = |approve| {
+            encode(&ResolveArgs {
+                bounty_id: 0,
+                approve,
+            })
+        };
+        call::<()>(ADDR_FUNDER, resolve_dispute, resolve(true));
+        assert!(payouts().is_empty());
+        call::<()>(ADDR_REVIEWER, resolve_dispute, resolve(true));
+        assert_eq!(payouts(), [(ADDR_DEV.to_string(), 300)]);
+
+        call::<()>(ADDR_DEV, submit, note("part two"));
+        call::<()>(ADDR_REVIEWER, reject, note("incomplete"));
+        call::<()>(ADDR_DEV, dispute, note("it is complete"));
+        call::<()>(ADDR_REVIEWER, resolve_dispute, resolve(false));
+        let bounty = load_bounty(0).unwrap();
+        assert_eq!((bounty.status, bounty.claimant), (BountyStatus::Open, None));
+        assert_eq!(bounty.current, 1);
+        // The committee seat from setup and both rulings; the funder's
+        // attempt is not logged.
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+    }
+
+    #[test]
+    fn unreviewed_submissions_pay_out_after_the_review_period() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::take_contract_calls();
+        call::<()>(ADDR_DEV, submit, note("done"));
+        mock::set_block_timestamp(NOW + 7 * DAY);
+        call::<()>(ADDR_DEV, claim_timeout, id());
+        assert!(payouts().is_empty());
+
+        mock::set_block_timestamp(NOW + 7 * DAY + 1);
+        call::<()>(ADDR_OTHER, claim_timeout, id());
+        assert!(payouts().is_empty());
+        call::<()>(ADDR_DEV, claim_timeout, id());
+        assert_eq!(payouts(), [(ADDR_DEV.to_string(), 300)]);
+    }
+
+    #[test]
+    fn idle_claims_are_released_and_open_bounties_cancelled() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::take_contract_calls();
+        call::<u64>(ADDR_FUNDER, cancel, id());
+        assert_eq!(load_bounty(0).unwrap().status, BountyStatus::Claimed);
+
+        mock::set_block_timestamp(NOW + 14 * DAY);
+        call::<()>(ADDR_FUNDER, release_claim, id());
+        assert_eq!(load_bounty(0).unwrap().status, BountyStatus::Claimed);
+        mock::set_block_timestamp(NOW + 14 * DAY + 1);
+        call::<()>(ADDR_OTHER, release_claim, id());
+        assert_eq!(load_bounty(0).unwrap().status, BountyStatus::Claimed);
+        call::<()>(ADDR_REVIEWER, release_claim, id());
+        assert_eq!(load_bounty(0).unwrap().status, BountyStatus::Open);
+
+        call::<()>(ADDR_FUNDER, claim, id());
+        assert_eq!(load_bounty(0).unwrap().status, BountyStatus::Open);
+        assert_eq!(call::<u64>(ADDR_FUNDER, cancel, id()), Some(1_000));
+        assert_eq!(payouts(), [(ADDR_FUNDER.to_string(), 1_000)]);
+        call::<()>(ADDR_DEV, claim, id());
+        assert_eq!(load_bounty(0).unwrap().status, BountyStatus::Cancelled);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "grants-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
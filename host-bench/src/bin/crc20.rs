@@ -0,0 +1,175 @@
+//! Host-call counts for the crc20 entrypoints
+
+use crc_standards::crc20::{
+    ApproveArgs, BalanceOfArgs, BalanceOfAtArgs, MintArgs, TransferArgs, TransferFromArgs,
+};
+use host_bench::{RECIPIENT, SENDER, SPENDER, Scenario, call_with, fresh_runtime};
+use silica_contract_sdk::ffi::mock;
+use std::process::ExitCode;
+
+/// Transfers made by `with_history`, one per block
+const HISTORY_BLOCKS: u64 = 64;
+
+const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "initialize",
+        setup: || {
+            fresh_runtime("crc20_contract");
+            call_with(&("Chert Token", "CHT", 18u8, 1_000u64));
+        },
+        call: || {
+            crc20_token::initialize();
+        },
+    },
+    Scenario {
+        name: "transfer",
+        setup: deployed,
+        call: || {
+            call_with(&TransferArgs {
+                to: RECIPIENT.parse().unwrap(),
+                amount: 10,
+            });
+            crc20_token::transfer();
+        },
+    },
+    Scenario {
+        name: "transfer/64 checkpoints",
+        setup: with_history,
+        call: || {
+            call_with(&TransferArgs {
+                to: RECIPIENT.parse().unwrap(),
+                amount: 10,
+            });
+            crc20_token::transfer();
+        },
+    },
+    Scenario {
+        name: "approve",
+        setup: deployed,
+        call: || {
+            call_with(&ApproveArgs {
+                spender: SPENDER.parse().unwrap(),
+                amount: 50,
+            });
+            crc20_token::approve();
+        },
+    },
+    Scenario {
+        name: "transfer_from",
+        setup: || {
+            deployed();
+            call_with(&ApproveArgs {
+                spender: SPENDER.parse().unwrap(),
+                amount: 50,
+            });
+            crc20_token::approve();
+            mock::set_sender(SPENDER);
+        },
+        call: || {
+            call_with(&TransferFromArgs {
+                from: SENDER.parse().unwrap(),
+                to: RECIPIENT.parse().unwrap(),
+                amount: 10,
+            });
+            crc20_token::transfer_from();
+        },
+    },
+    Scenario {
+        name: "mint",
+        setup: deployed,
+        call: || {
+            call_with(&MintArgs {
+                to: RECIPIENT.parse().unwrap(),
+                amount: 10,
+            });
+            crc20_token::mint();
+        },
+    },
+    Scenario {
+        name: "balance_of",
+        setup: deployed,
+        call: || {
+            call_with(&BalanceOfArgs {
+                account: SENDER.parse().unwrap(),
+            });
+            crc20_token::balance_of();
+        },
+    },
+    Scenario {
+        name: "balance_of_at/64 checkpoints",
+        setup: with_history,
+        call: || {
+            call_with(&BalanceOfAtArgs {
+                account: SENDER.parse().unwrap(),
+                block: HISTORY_BLOCKS / 3,
+            });
+            crc20_token::balance_of_at();
+        },
+    },
+    Scenario {
+        name: "total_supply",
+        setup: deployed,
+        call: || {
+            crc20_token::total_supply();
+        },
+    },
+];
+
+/// Token with 1000 units held by [`SENDER`]
+fn deployed() {
+    fresh_runtime("crc20_contract");
+    // `InitializeArgs { name, symbol, decimals, initial_supply }`
+    call_with(&("Chert Token", "CHT", 18u8, 1_000u64));
+    crc20_token::initialize();
+}
+
+/// [`deployed`], then one transfer per block so [`SENDER`] has a long
+/// checkpoint history
+fn with_history() {
+    deployed();
+    for block in 1..=HISTORY_BLOCKS {
+        mock::set_block_height(block);
+        call_with(&TransferArgs {
+            to: RECIPIENT.parse().unwrap(),
+            amount: 1,
+        });
+        crc20_token::transfer();
+    }
+    mock::set_block_height(HISTORY_BLOCKS + 1);
+}
+
+fn main() -> ExitCode {
+    host_bench::run(SCENARIOS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_scenario_succeeds() {
+        for scenario in SCENARIOS {
+            let row = host_bench::measure(scenario).unwrap();
+            assert!(row.storage_reads > 0, "{} read nothing", scenario.name);
+        }
+    }
+
+    /// Context lookups per call. Storage calls used to fetch the whole
+    /// execution context (142 lookups for a transfer); they now fetch only
+    /// the contract address, once per write-cache scope, so reads that fall
+    /// back through legacy key spellings cost no extra lookups.
+    #[test]
+    fn context_lookups_stay_within_budget() {
+        for (name, budget) in [("transfer", 26), ("approve", 16), ("transfer_from", 26)] {
+            let scenario = SCENARIOS.iter().find(|s| s.name == name).unwrap();
+            let row = host_bench::measure(scenario).unwrap();
+            assert!(
+                row.context_reads <= budget,
+                "{} made {} context lookups, budget {}",
+                name,
+                row.context_reads,
+                budget
+            );
+        }
+    }
+}
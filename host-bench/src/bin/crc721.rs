@@ -0,0 +1,144 @@
+//! Host-call counts for the crc721 entrypoints
+//!
+//! Enumeration keeps whole token-id lists in single storage values, so most
+//! scenarios run against both a one-token and a [`LARGE`]-token collection.
+
+use crc_standards::crc721::{ApproveArgs, BalanceOfArgs, TokenIdArgs, TransferFromArgs};
+use host_bench::{RECIPIENT, SENDER, SPENDER, Scenario, call_with, fresh_runtime};
+use std::process::ExitCode;
+
+/// Tokens held by [`SENDER`] in the large collection
+const LARGE: u64 = 200;
+
+const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "mint/1 token",
+        setup: small,
+        call: || mint(LARGE + 1),
+    },
+    Scenario {
+        name: "mint/200 tokens",
+        setup: large,
+        call: || mint(LARGE + 1),
+    },
+    Scenario {
+        name: "transfer_from/1 token",
+        setup: small,
+        call: transfer_first,
+    },
+    Scenario {
+        name: "transfer_from/200 tokens",
+        setup: large,
+        call: transfer_first,
+    },
+    Scenario {
+        name: "approve",
+        setup: small,
+        call: || {
+            call_with(&ApproveArgs {
+                to: SPENDER.parse().unwrap(),
+                token_id: 1,
+            });
+            crc721_contract::approve();
+        },
+    },
+    Scenario {
+        name: "burn/1 token",
+        setup: small,
+        call: burn_first,
+    },
+    Scenario {
+        name: "burn/200 tokens",
+        setup: large,
+        call: burn_first,
+    },
+    Scenario {
+        name: "owner_of",
+        setup: small,
+        call: || {
+            call_with(&TokenIdArgs { token_id: 1 });
+            crc721_contract::owner_of();
+        },
+    },
+    Scenario {
+        name: "balance_of/200 tokens",
+        setup: large,
+        call: || {
+            call_with(&BalanceOfArgs {
+                owner: SENDER.parse().unwrap(),
+            });
+            crc721_contract::balance_of();
+        },
+    },
+    Scenario {
+        name: "token_of_owner_by_index/200 tokens",
+        setup: large,
+        call: || {
+            // `TokenOfOwnerByIndexArgs { owner, index }`
+            call_with(&(SENDER, LARGE - 1));
+            crc721_contract::token_of_owner_by_index();
+        },
+    },
+];
+
+fn deployed() {
+    fresh_runtime("crc721_contract");
+    // `InitializeArgs { name, symbol, base_uri }`
+    call_with(&(
+        "Chert Punks",
+        "CPUNK",
+        "https://api.chertpunks.io/metadata/",
+    ));
+    crc721_contract::initialize();
+}
+
+fn mint(token_id: u64) {
+    // `MintArgs { to, token_id, metadata_uri }`
+    call_with(&(SENDER, token_id, format!("{}.json", token_id)));
+    crc721_contract::mint();
+}
+
+/// Collection with token 1 held by [`SENDER`]
+fn small() {
+    deployed();
+    mint(1);
+}
+
+/// Collection with tokens `1..=LARGE` held by [`SENDER`]
+fn large() {
+    deployed();
+    for token_id in 1..=LARGE {
+        mint(token_id);
+    }
+}
+
+fn transfer_first() {
+    call_with(&TransferFromArgs {
+        from: SENDER.parse().unwrap(),
+        to: RECIPIENT.parse().unwrap(),
+        token_id: 1,
+    });
+    crc721_contract::transfer_from();
+}
+
+fn burn_first() {
+    call_with(&TokenIdArgs { token_id: 1 });
+    crc721_contract::burn();
+}
+
+fn main() -> ExitCode {
+    host_bench::run(SCENARIOS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_scenario_succeeds() {
+        for scenario in SCENARIOS {
+            let row = host_bench::measure(scenario).unwrap();
+            assert!(row.storage_reads > 0, "{} read nothing", scenario.name);
+        }
+    }
+}
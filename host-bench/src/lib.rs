@@ -0,0 +1,244 @@
+//! Host-call benchmarks under the mock runtime
+//!
+//! Each scenario prepares contract state, then calls one entrypoint while
+//! the mock runtime counts storage reads and writes, bytes moved, events
+//! and context lookups. The report lists the counts per scenario and, given
+//! a baseline saved from another revision, the change in each:
+//!
+//! ```text
+//! cargo run -p host-bench --bin crc20 -- --save before.json
+//! # edit the contract
+//! cargo run -p host-bench --bin crc20 -- --baseline before.json
+//! ```
+//!
+//! Contracts export their entrypoints under the same unmangled names, so
+//! each contract gets its own binary rather than sharing one.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::ffi::mock::{self, HostCalls};
+use std::fmt::Write;
+use std::fs;
+use std::process::ExitCode;
+
+/// Relative weight of each counted operation in the `weight` column
+///
+/// These rank scenarios against each other; they are not the runtime's gas
+/// schedule.
+pub const STORAGE_READ_WEIGHT: u64 = 100;
+pub const STORAGE_WRITE_WEIGHT: u64 = 500;
+pub const BYTE_READ_WEIGHT: u64 = 1;
+pub const BYTE_WRITE_WEIGHT: u64 = 5;
+pub const EVENT_WEIGHT: u64 = 50;
+pub const CONTEXT_READ_WEIGHT: u64 = 10;
+
+pub const SENDER: &str = "0x0000000000000000000000000000000000000d01";
+pub const RECIPIENT: &str = "0x0000000000000000000000000000000000000b02";
+pub const SPENDER: &str = "0x0000000000000000000000000000000000000c03";
+
+/// One measured entrypoint call
+pub struct Scenario {
+    pub name: &'static str,
+    /// Runs on a freshly reset runtime; its host calls are not counted
+    pub setup: fn(),
+    /// The entrypoint call being measured
+    pub call: fn(),
+}
+
+/// Counts for one scenario, as saved in a report file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub scenario: String,
+    pub storage_reads: u64,
+    pub storage_writes: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    pub events: u64,
+    pub context_reads: u64,
+    pub weight: u64,
+}
+
+impl Row {
+    fn new(scenario: &str, calls: &HostCalls) -> Self {
+        Row {
+            scenario: scenario.to_string(),
+            storage_reads: calls.storage_reads,
+            storage_writes: calls.storage_writes,
+            bytes_read: calls.bytes_read,
+            bytes_written: calls.bytes_written,
+            events: calls.events,
+            context_reads: calls.context_reads,
+            weight: calls.storage_reads * STORAGE_READ_WEIGHT
+                + calls.storage_writes * STORAGE_WRITE_WEIGHT
+                + calls.bytes_read * BYTE_READ_WEIGHT
+                + calls.bytes_written * BYTE_WRITE_WEIGHT
+                + calls.events * EVENT_WEIGHT
+                + calls.context_reads * CONTEXT_READ_WEIGHT,
+        }
+    }
+
+    fn columns(&self) -> [u64; 7] {
+        [
+            self.storage_reads,
+            self.storage_writes,
+            self.bytes_read,
+            self.bytes_written,
+            self.events,
+            self.context_reads,
+            self.weight,
+        ]
+    }
+}
+
+const HEADINGS: [&str; 7] = [
+    "reads",
+    "writes",
+    "bytes in",
+    "bytes out",
+    "events",
+    "context",
+    "weight",
+];
+
+/// Reset the runtime to a deployed contract called by [`SENDER`]
+pub fn fresh_runtime(contract: &str) {
+    mock::reset();
+    mock::set_sender(SENDER);
+    mock::set_contract_address(contract);
+    mock::set_block_height(1);
+    mock::set_block_timestamp(1_736_000_000);
+}
+
+/// Call data for the next entrypoint call
+pub fn call_with<T: Serialize>(args: &T) {
+    mock::set_call_data(&postcard::to_allocvec(args).expect("encode call arguments"));
+}
+
+/// Set up and run a scenario; a failed entrypoint call is an error, since
+/// its counts would describe the failure path
+pub fn measure(scenario: &Scenario) -> Result<Row, String> {
+    (scenario.setup)();
+    let failures = |logs: Vec<String>| logs.into_iter().find(|line| line.contains(" failed: "));
+    if let Some(failure) = failures(mock::take_logs()) {
+        return Err(format!("{} setup: {}", scenario.name, failure));
+    }
+    mock::take_host_calls();
+    (scenario.call)();
+    let calls = mock::take_host_calls();
+    if let Some(failure) = failures(mock::take_logs()) {
+        return Err(format!("{}: {}", scenario.name, failure));
+    }
+    Ok(Row::new(scenario.name, &calls))
+}
+
+/// Table of `rows`, with the change against `baseline` where it has the
+/// same scenario
+pub fn render(rows: &[Row], baseline: Option<&[Row]>) -> String {
+    let width = rows
+        .iter()
+        .map(|row| row.scenario.len())
+        .max()
+        .unwrap_or(0)
+        .max("scenario".len());
+    let mut out = format!("{:<width$}", "scenario");
+    for heading in HEADINGS {
+        let _ = write!(out, " {:>10}", heading);
+    }
+    out.push('\n');
+    for row in rows {
+        let before = baseline.and_then(|rows| rows.iter().find(|b| b.scenario == row.scenario));
+        let _ = write!(out, "{:<width$}", row.scenario);
+        for (i, value) in row.columns().into_iter().enumerate() {
+            let cell = match before.map(|b| b.columns()[i]) {
+                Some(old) if old != value => {
+                    format!("{} ({:+})", value, value as i64 - old as i64)
+                }
+                _ => value.to_string(),
+            };
+            let _ = write!(out, " {:>10}", cell);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Command-line driver shared by the per-contract binaries
+///
+/// Arguments: `--save FILE`, `--baseline FILE`, and optional substrings
+/// selecting scenarios by name.
+pub fn run(scenarios: &[Scenario]) -> ExitCode {
+    match try_run(scenarios, std::env::args().skip(1).collect()) {
+        Ok(report) => {
+            print!("{}", report);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("host-bench: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn try_run(scenarios: &[Scenario], args: Vec<String>) -> Result<String, String> {
+    let mut save = None;
+    let mut baseline = None;
+    let mut only = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--save" => save = Some(args.next().ok_or("--save needs a file")?),
+            "--baseline" => baseline = Some(args.next().ok_or("--baseline needs a file")?),
+            _ => only.push(arg),
+        }
+    }
+
+    let baseline: Option<Vec<Row>> = match baseline {
+        Some(path) => {
+            let json = fs::read_to_string(&path).map_err(|err| format!("{}: {}", path, err))?;
+            Some(serde_json::from_str(&json).map_err(|err| format!("{}: {}", path, err))?)
+        }
+        None => None,
+    };
+    let rows = scenarios
+        .iter()
+        .filter(|s| only.is_empty() || only.iter().any(|name| s.name.contains(name.as_str())))
+        .map(measure)
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(path) = save {
+        let json = serde_json::to_string_pretty(&rows).map_err(|err| err.to_string())?;
+        fs::write(&path, json + "\n").map_err(|err| format!("{}: {}", path, err))?;
+    }
+    Ok(render(&rows, baseline.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use silica_contract_sdk::prelude::*;
+
+    fn write_twice() {
+        let mut store = storage();
+        store.set("counter", &1u64).unwrap();
+        store.set("counter", &2u64).unwrap();
+        assert_eq!(store.get::<u64>("counter").unwrap(), Some(2));
+    }
+
+    #[test]
+    fn counts_calls_and_renders_changes() {
+        let scenario = Scenario {
+            name: "write_twice",
+            setup: || fresh_runtime("bench_contract"),
+            call: write_twice,
+        };
+        let row = measure(&scenario).unwrap();
+        assert_eq!((row.storage_reads, row.storage_writes), (1, 2));
+        assert_eq!((row.bytes_read, row.bytes_written), (1, 2));
+        assert!(row.context_reads > 0);
+
+        let mut cheaper = row.clone();
+        cheaper.storage_writes = 1;
+        let table = render(&[row], Some(&[cheaper]));
+        assert!(table.starts_with("scenario"));
+        assert!(table.contains("write_twice"));
+        assert!(table.contains("2 (+1)"));
+    }
+}
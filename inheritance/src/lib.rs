@@ -0,0 +1,1012 @@
+//! Inheritance
+//!
+//! A dead-man's switch for CRC-20 holdings. An owner deposits tokens into an
+//! estate, names beneficiaries with fixed shares and proves they are alive
+//! by sending heartbeats. If no heartbeat arrives within the interval, any
+//! beneficiary can trigger the switch, which starts a challenge period. A
+//! heartbeat during the challenge period cancels the claim; once it ends
+//! the estate is released and each beneficiary withdraws their share of
+//! every token.
+//!
+//! ## Features
+//! - Up to 10 beneficiaries with basis-point shares and 10 tokens per estate
+//! - Any owner action (heartbeat, deposit, withdrawal) counts as proof of life
+//! - Challenge period before release, cancellable by the owner
+//! - Rounding dust goes to the first beneficiary, so nothing is stranded
+//! - Decommissioning: after the contract owner's notice runs out, new
+//!   estates and deposits stop while existing estates run their course
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::ownable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const NEXT_ESTATE_KEY: &str = "next_estate_id";
+const ESTATES_PREFIX: &str = "estates";
+const BALANCES_PREFIX: &str = "estate_balances";
+const CLAIMED_PREFIX: &str = "estate_claimed";
+const SECONDS_PER_DAY: u64 = 86_400;
+const MIN_HEARTBEAT_INTERVAL: u64 = SECONDS_PER_DAY;
+const MAX_HEARTBEAT_INTERVAL: u64 = 5 * 365 * SECONDS_PER_DAY;
+const MIN_CHALLENGE_PERIOD: u64 = SECONDS_PER_DAY;
+const MAX_CHALLENGE_PERIOD: u64 = 365 * SECONDS_PER_DAY;
+const MAX_BENEFICIARIES: usize = 10;
+const MAX_TOKENS: usize = 10;
+const TOTAL_SHARE_BPS: u32 = 10_000;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// A beneficiary and their share of every token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Beneficiary {
+    pub account: String,
+    pub share_bps: u32,
+}
+
+/// Where an estate is in its lifecycle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum EstateStatus {
+    /// The owner is in control
+    Active,
+    /// A beneficiary reported a missed heartbeat; the owner can still cancel
+    Triggered { triggered_at: u64 },
+    /// The challenge period passed; beneficiaries can claim
+    Released,
+}
+
+/// An owner's estate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Estate {
+    pub owner: String,
+    pub beneficiaries: Vec<Beneficiary>,
+    /// Longest allowed gap between heartbeats
+    pub heartbeat_interval: u64,
+    /// Time the owner has to cancel a trigger
+    pub challenge_period: u64,
+    pub last_heartbeat: u64,
+    /// Every token ever deposited, in deposit order
+    pub tokens: Vec<String>,
+    pub status: EstateStatus,
+}
+
+/// One token held by an estate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub token: String,
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateEstateArgs {
+    beneficiaries: Vec<Beneficiary>,
+    heartbeat_interval: u64,
+    challenge_period: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetBeneficiariesArgs {
+    estate_id: u64,
+    beneficiaries: Vec<Beneficiary>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenAmountArgs {
+    estate_id: u64,
+    token: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EstateIdArgs {
+    estate_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ClaimedArgs {
+    estate_id: u64,
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn estates() -> Map<u64, Estate> {
+    Map::new(ESTATES_PREFIX)
+}
+
+fn balances() -> Map<(u64, String), u64> {
+    Map::new(BALANCES_PREFIX)
+}
+
+/// Beneficiaries who have taken their share of a released estate
+fn claimed() -> Map<(u64, String), bool> {
+    Map::new(CLAIMED_PREFIX)
+}
+
+fn load_estate(estate_id: u64) -> ContractResult<Estate> {
+    estates()
+        .get(&estate_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown estate".to_string()))
+}
+
+fn balance_of(estate_id: u64, token: &str) -> ContractResult<u64> {
+    Ok(balances()
+        .get(&(estate_id, token.to_string()))?
+        .unwrap_or(0))
+}
+
+fn estate_balances(estate_id: u64, estate: &Estate) -> ContractResult<Vec<TokenBalance>> {
+    estate
+        .tokens
+        .iter()
+        .map(|token| {
+            Ok(TokenBalance {
+                token: token.clone(),
+                amount: balance_of(estate_id, token)?,
+            })
+        })
+        .collect()
+}
+
+fn validate_beneficiaries(owner: &str, beneficiaries: &[Beneficiary]) -> ContractResult<()> {
+    if beneficiaries.is_empty() || beneficiaries.len() > MAX_BENEFICIARIES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Estates name 1-{} beneficiaries",
+            MAX_BENEFICIARIES
+        )));
+    }
+    let mut total = 0u32;
+    for (index, beneficiary) in beneficiaries.iter().enumerate() {
+        validation::validate_address(&beneficiary.account)?;
+        if beneficiary.account == owner {
+            return Err(ContractError::InvalidArgument(
+                "The owner cannot be a beneficiary".to_string(),
+            ));
+        }
+        if beneficiaries[..index]
+            .iter()
+            .any(|other| other.account == beneficiary.account)
+        {
+            return Err(ContractError::InvalidArgument(
+                "Duplicate beneficiary".to_string(),
+            ));
+        }
+        if beneficiary.share_bps == 0 {
+            return Err(ContractError::InvalidArgument(
+                "Shares must be positive".to_string(),
+            ));
+        }
+        total = total.saturating_add(beneficiary.share_bps);
+    }
+    if total != TOTAL_SHARE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Shares must add up to {} bps",
+            TOTAL_SHARE_BPS
+        )));
+    }
+    Ok(())
+}
+
+/// Load an estate the caller owns and record the call as a heartbeat,
+/// cancelling any pending trigger.
+fn load_owned_estate(estate_id: u64) -> ContractResult<Estate> {
+    let ctx = context();
+    let mut estate = load_estate(estate_id)?;
+    if estate.owner != ctx.sender() {
+        return Err(ContractError::Unauthorized);
+    }
+    // Once the challenge period is over the owner can no longer cancel.
+    settle_status(&mut estate, ctx.block_timestamp())?;
+    if estate.status == EstateStatus::Released {
+        return Err(ContractError::InvalidArgument(
+            "Estate has been released".to_string(),
+        ));
+    }
+    if matches!(estate.status, EstateStatus::Triggered { .. }) {
+        estate.status = EstateStatus::Active;
+        event!("ClaimCancelled", estate_id: estate_id);
+    }
+    estate.last_heartbeat = ctx.block_timestamp();
+    Ok(estate)
+}
+
+/// Release the estate if its challenge period has passed.
+fn settle_status(estate: &mut Estate, now: u64) -> ContractResult<bool> {
+    if let EstateStatus::Triggered { triggered_at } = estate.status
+        && now >= safe_math::add(triggered_at, estate.challenge_period)?
+    {
+        estate.status = EstateStatus::Released;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    ownable::only_owner()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_create_estate() -> ContractResult<u64> {
+    decommission::when_active()?;
+    let ctx = context();
+    let owner = ctx.sender().to_string();
+    let args: CreateEstateArgs = read_args()?;
+    validate_beneficiaries(&owner, &args.beneficiaries)?;
+    if !(MIN_HEARTBEAT_INTERVAL..=MAX_HEARTBEAT_INTERVAL).contains(&args.heartbeat_interval) {
+        return Err(ContractError::InvalidArgument(
+            "Heartbeat interval must be between 1 day and 5 years".to_string(),
+        ));
+    }
+    if !(MIN_CHALLENGE_PERIOD..=MAX_CHALLENGE_PERIOD).contains(&args.challenge_period) {
+        return Err(ContractError::InvalidArgument(
+            "Challenge period must be between 1 day and 1 year".to_string(),
+        ));
+    }
+
+    let estate_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_ESTATE_KEY)?.unwrap_or(0);
+        store.set(NEXT_ESTATE_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    let beneficiary_count = args.beneficiaries.len();
+    estates().set(
+        &estate_id,
+        &Estate {
+            owner: owner.clone(),
+            beneficiaries: args.beneficiaries,
+            heartbeat_interval: args.heartbeat_interval,
+            challenge_period: args.challenge_period,
+            last_heartbeat: ctx.block_timestamp(),
+            tokens: Vec::new(),
+            status: EstateStatus::Active,
+        },
+    )?;
+
+    event!("EstateCreated",
+        estate_id: estate_id,
+        owner: owner,
+        beneficiaries: beneficiary_count,
+        heartbeat_interval: args.heartbeat_interval,
+        challenge_period: args.challenge_period
+    );
+    Ok(estate_id)
+}
+
+fn execute_heartbeat() -> ContractResult<()> {
+    let args: EstateIdArgs = read_args()?;
+    let estate = load_owned_estate(args.estate_id)?;
+    estates().set(&args.estate_id, &estate)?;
+
+    event!("Heartbeat", estate_id: args.estate_id, at: estate.last_heartbeat);
+    Ok(())
+}
+
+fn execute_set_beneficiaries() -> ContractResult<()> {
+    let args: SetBeneficiariesArgs = read_args()?;
+    let mut estate = load_owned_estate(args.estate_id)?;
+    validate_beneficiaries(&estate.owner, &args.beneficiaries)?;
+    estate.beneficiaries = args.beneficiaries;
+    estates().set(&args.estate_id, &estate)?;
+
+    event!("BeneficiariesUpdated",
+        estate_id: args.estate_id,
+        beneficiaries: estate.beneficiaries.len()
+    );
+    Ok(())
+}
+
+fn execute_deposit() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let args: TokenAmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    let mut estate = load_owned_estate(args.estate_id)?;
+    if !estate.tokens.contains(&args.token) {
+        validation::validate_address(&args.token)?;
+        if !ctx.is_contract(&args.token) {
+            return Err(ContractError::InvalidArgument(
+                "Token must be a contract".to_string(),
+            ));
+        }
+        if estate.tokens.len() == MAX_TOKENS {
+            return Err(ContractError::InvalidArgument(format!(
+                "Estates hold at most {} tokens",
+                MAX_TOKENS
+            )));
+        }
+        estate.tokens.push(args.token.clone());
+    }
+
+    let call = encode_call(&TokenTransferFromArgs {
+        from: estate.owner.clone(),
+        to: ctx.contract_address().to_string(),
+        amount: args.amount,
+    })?;
+    ctx.call_contract(&args.token, "transfer_from", &call)?;
+    let balance = safe_math::add(balance_of(args.estate_id, &args.token)?, args.amount)?;
+    balances().set(&(args.estate_id, args.token.clone()), &balance)?;
+    estates().set(&args.estate_id, &estate)?;
+
+    event!("Deposited",
+        estate_id: args.estate_id,
+        token: args.token,
+        amount: args.amount
+    );
+    Ok(())
+}
+
+fn execute_withdraw() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let args: TokenAmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    let estate = load_owned_estate(args.estate_id)?;
+    let balance = balance_of(args.estate_id, &args.token)?;
+    if args.amount > balance {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: balance,
+        });
+    }
+    balances().set(
+        &(args.estate_id, args.token.clone()),
+        &(balance - args.amount),
+    )?;
+    estates().set(&args.estate_id, &estate)?;
+    send_tokens(&args.token, &estate.owner, args.amount)?;
+
+    event!("Withdrawn",
+        estate_id: args.estate_id,
+        token: args.token,
+        amount: args.amount
+    );
+    Ok(())
+}
+
+fn execute_trigger() -> ContractResult<()> {
+    let ctx = context();
+    let caller = ctx.sender().to_string();
+    let now = ctx.block_timestamp();
+    let args: EstateIdArgs = read_args()?;
+    let mut estate = load_estate(args.estate_id)?;
+    if !estate.beneficiaries.iter().any(|b| b.account == caller) {
+        return Err(ContractError::Unauthorized);
+    }
+    if estate.status != EstateStatus::Active {
+        return Err(ContractError::InvalidArgument(
+            "Estate is not active".to_string(),
+        ));
+    }
+    if now < safe_math::add(estate.last_heartbeat, estate.heartbeat_interval)? {
+        return Err(ContractError::InvalidArgument(
+            "The owner's heartbeat has not lapsed".to_string(),
+        ));
+    }
+    estate.status = EstateStatus::Triggered { triggered_at: now };
+    estates().set(&args.estate_id, &estate)?;
+
+    event!("ClaimTriggered",
+        estate_id: args.estate_id,
+        beneficiary: caller,
+        releases_at: now + estate.challenge_period
+    );
+    Ok(())
+}
+
+fn execute_claim() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let caller = ctx.sender().to_string();
+    let args: EstateIdArgs = read_args()?;
+    let mut estate = load_estate(args.estate_id)?;
+    let position = estate
+        .beneficiaries
+        .iter()
+        .position(|b| b.account == caller)
+        .ok_or(ContractError::Unauthorized)?;
+    if settle_status(&mut estate, ctx.block_timestamp())? {
+        estates().set(&args.estate_id, &estate)?;
+        event!("EstateReleased", estate_id: args.estate_id);
+    }
+    if estate.status != EstateStatus::Released {
+        return Err(ContractError::InvalidArgument(
+            "Estate has not been released".to_string(),
+        ));
+    }
+    let key = (args.estate_id, caller.clone());
+    if claimed().contains_key(&key)? {
+        return Err(ContractError::InvalidArgument(
+            "Share already claimed".to_string(),
+        ));
+    }
+    claimed().set(&key, &true)?;
+
+    // Balances are frozen once released, so every claim sees the same totals.
+    let share_bps = estate.beneficiaries[position].share_bps as u128;
+    for balance in estate_balances(args.estate_id, &estate)? {
+        let mut amount = (balance.amount as u128 * share_bps / TOTAL_SHARE_BPS as u128) as u64;
+        if position == 0 {
+            let distributed: u64 = estate
+                .beneficiaries
+                .iter()
+                .map(|b| {
+                    (balance.amount as u128 * b.share_bps as u128 / TOTAL_SHARE_BPS as u128) as u64
+                })
+                .sum();
+            amount += balance.amount - distributed;
+        }
+        if amount > 0 {
+            send_tokens(&balance.token, &caller, amount)?;
+        }
+        event!("ShareClaimed",
+            estate_id: args.estate_id,
+            beneficiary: caller.clone(),
+            token: balance.token,
+            amount: amount
+        );
+    }
+    Ok(())
+}
+
+/// Give notice that the contract will be decommissioned (contract owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (contract owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the contract once the notice has run out (contract owner only)
+///
+/// New estates and deposits stop for good. Existing estates keep running:
+/// owners can still send heartbeats, change beneficiaries and withdraw, and
+/// lapsed estates still release to their beneficiaries.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the contract is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Set the contract owner, who may decommission the contract; call right
+/// after deployment
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = ownable::initialize(context().sender()) {
+        log!("initialize failed: {}", err);
+    }
+}
+
+/// Create an estate owned by the caller
+///
+/// # Arguments
+/// * `beneficiaries` - 1-10 `Beneficiary` entries whose shares add up to 10000 bps
+/// * `heartbeat_interval` - Longest gap between heartbeats (1 day to 5 years)
+/// * `challenge_period` - Time to cancel a trigger (1 day to 1 year)
+///
+/// # Returns
+/// Estate id
+#[unsafe(no_mangle)]
+pub extern "C" fn create_estate() {
+    let result = execute_create_estate().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("create_estate failed: {}", err);
+    }
+}
+
+/// Prove the owner is alive; cancels a pending trigger
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+#[unsafe(no_mangle)]
+pub extern "C" fn heartbeat() {
+    if let Err(err) = execute_heartbeat() {
+        log!("heartbeat failed: {}", err);
+    }
+}
+
+/// Replace the beneficiaries (only owner; counts as a heartbeat)
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+/// * `beneficiaries` - 1-10 `Beneficiary` entries whose shares add up to 10000 bps
+#[unsafe(no_mangle)]
+pub extern "C" fn set_beneficiaries() {
+    if let Err(err) = execute_set_beneficiaries() {
+        log!("set_beneficiaries failed: {}", err);
+    }
+}
+
+/// Add tokens to an estate (only owner; counts as a heartbeat)
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+/// * `token` - CRC-20 address (at most 10 per estate)
+/// * `amount` - Amount to deposit (approve it first)
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    if let Err(err) = execute_deposit() {
+        log!("deposit failed: {}", err);
+    }
+}
+
+/// Take tokens back out of an estate (only owner; counts as a heartbeat)
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+/// * `token` - CRC-20 address
+/// * `amount` - Amount to withdraw
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw() {
+    if let Err(err) = execute_withdraw() {
+        log!("withdraw failed: {}", err);
+    }
+}
+
+/// Report a missed heartbeat and start the challenge period (beneficiaries only)
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+#[unsafe(no_mangle)]
+pub extern "C" fn trigger() {
+    if let Err(err) = execute_trigger() {
+        log!("trigger failed: {}", err);
+    }
+}
+
+/// Take the caller's share of every token once the challenge period has passed
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+#[unsafe(no_mangle)]
+pub extern "C" fn claim() {
+    if let Err(err) = execute_claim() {
+        log!("claim failed: {}", err);
+    }
+}
+
+/// Get an estate
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+///
+/// # Returns
+/// `Estate`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_estate() {
+    let result = read_args::<EstateIdArgs>()
+        .and_then(|args| load_estate(args.estate_id))
+        .and_then(|estate| try_respond(&estate));
+    if let Err(err) = result {
+        log!("get_estate failed: {}", err);
+    }
+}
+
+/// Get an estate's token balances
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+///
+/// # Returns
+/// `Vec<TokenBalance>`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_balances() {
+    let result = read_args::<EstateIdArgs>()
+        .and_then(|args| estate_balances(args.estate_id, &load_estate(args.estate_id)?))
+        .and_then(|balances| try_respond(&balances));
+    if let Err(err) = result {
+        log!("get_balances failed: {}", err);
+    }
+}
+
+/// Whether a beneficiary has claimed their share
+///
+/// # Arguments
+/// * `estate_id` - Estate id
+/// * `account` - Beneficiary address
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn has_claimed() {
+    let result = read_args::<ClaimedArgs>()
+        .and_then(|args| claimed().contains_key(&(args.estate_id, args.account)))
+        .and_then(|claimed| try_respond(&claimed));
+    if let Err(err) = result {
+        log!("has_claimed failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_ADMIN: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_EVE: &str = "0x0000000000000000000000000000000000000e05";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const OTHER_TOKEN: &str = "0x0000000000000000000000000000000000000f02";
+    const START: u64 = 1_736_000_000;
+    const INTERVAL: u64 = 30 * SECONDS_PER_DAY;
+    const CHALLENGE: u64 = 7 * SECONDS_PER_DAY;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn shares(alice_bps: u32, bob_bps: u32) -> Vec<Beneficiary> {
+        vec![
+            Beneficiary {
+                account: ADDR_ALICE.to_string(),
+                share_bps: alice_bps,
+            },
+            Beneficiary {
+                account: ADDR_BOB.to_string(),
+                share_bps: bob_bps,
+            },
+        ]
+    }
+
+    fn create(beneficiaries: Vec<Beneficiary>) -> Vec<u8> {
+        call(
+            ADDR_OWNER,
+            create_estate,
+            encode(&CreateEstateArgs {
+                beneficiaries,
+                heartbeat_interval: INTERVAL,
+                challenge_period: CHALLENGE,
+            }),
+        )
+    }
+
+    fn setup() -> u64 {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        for token in [TOKEN, OTHER_TOKEN] {
+            mock::register_contract(token, "transfer_from", |_| Ok(Vec::new()));
+            mock::register_contract(token, "transfer", |_| Ok(Vec::new()));
+        }
+        postcard::from_bytes(&create(shares(3_333, 6_667))).unwrap()
+    }
+
+    fn move_tokens(entrypoint: extern "C" fn(), estate_id: u64, token: &str, amount: u64) {
+        call(
+            ADDR_OWNER,
+            entrypoint,
+            encode(&TokenAmountArgs {
+                estate_id,
+                token: token.to_string(),
+                amount,
+            }),
+        );
+    }
+
+    fn estate_call(sender: &str, entrypoint: extern "C" fn(), estate_id: u64) {
+        call(sender, entrypoint, encode(&EstateIdArgs { estate_id }));
+    }
+
+    /// (token, recipient, amount) of every payout since the last call
+    fn payouts() -> Vec<(String, String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer")
+            .map(|c| {
+                let args: TokenTransferArgs = postcard::from_bytes(&c.args).unwrap();
+                (c.address.clone(), args.to, args.amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn beneficiaries_must_split_the_whole_estate() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert!(create(shares(5_000, 4_000)).is_empty());
+        assert!(create(shares(10_000, 0)).is_empty());
+        let mut duplicate = shares(5_000, 5_000);
+        duplicate[1].account = ADDR_ALICE.to_string();
+        assert!(create(duplicate).is_empty());
+        let mut owner = shares(5_000, 5_000);
+        owner[1].account = ADDR_OWNER.to_string();
+        assert!(create(owner).is_empty());
+        assert!(!create(shares(1, 9_999)).is_empty());
+    }
+
+    #[test]
+    fn triggers_wait_for_a_missed_heartbeat() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let id = setup();
+        mock::set_block_timestamp(START + INTERVAL - 1);
+        estate_call(ADDR_ALICE, trigger, id);
+        assert_eq!(load_estate(id).unwrap().status, EstateStatus::Active);
+
+        estate_call(ADDR_OWNER, heartbeat, id);
+        mock::set_block_timestamp(START + INTERVAL + 1);
+        estate_call(ADDR_ALICE, trigger, id);
+        assert_eq!(load_estate(id).unwrap().status, EstateStatus::Active);
+
+        mock::set_block_timestamp(START + 2 * INTERVAL);
+        estate_call(ADDR_EVE, trigger, id);
+        assert_eq!(load_estate(id).unwrap().status, EstateStatus::Active);
+        estate_call(ADDR_BOB, trigger, id);
+        assert_eq!(
+            load_estate(id).unwrap().status,
+            EstateStatus::Triggered {
+                triggered_at: START + 2 * INTERVAL
+            }
+        );
+    }
+
+    #[test]
+    fn owner_activity_cancels_a_trigger() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let id = setup();
+        move_tokens(deposit, id, TOKEN, 1_000);
+        mock::set_block_timestamp(START + INTERVAL);
+        estate_call(ADDR_ALICE, trigger, id);
+
+        // Any owner action during the challenge period proves life.
+        mock::set_block_timestamp(START + INTERVAL + CHALLENGE - 1);
+        move_tokens(withdraw, id, TOKEN, 100);
+        let estate = load_estate(id).unwrap();
+        assert_eq!(estate.status, EstateStatus::Active);
+        assert_eq!(estate.last_heartbeat, START + INTERVAL + CHALLENGE - 1);
+        assert_eq!(balance_of(id, TOKEN).unwrap(), 900);
+
+        mock::set_block_timestamp(START + INTERVAL + CHALLENGE);
+        estate_call(ADDR_ALICE, claim, id);
+        assert!(
+            !claimed()
+                .contains_key(&(id, ADDR_ALICE.to_string()))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn released_estates_pay_each_share_once() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let id = setup();
+        move_tokens(deposit, id, TOKEN, 1_000);
+        move_tokens(deposit, id, OTHER_TOKEN, 10);
+        mock::set_block_timestamp(START + INTERVAL);
+        estate_call(ADDR_ALICE, trigger, id);
+        mock::set_block_timestamp(START + INTERVAL + CHALLENGE - 1);
+        estate_call(ADDR_ALICE, claim, id);
+        mock::set_block_timestamp(START + INTERVAL + CHALLENGE);
+        mock::take_contract_calls();
+
+        estate_call(ADDR_BOB, claim, id);
+        estate_call(ADDR_BOB, claim, id);
+        estate_call(ADDR_EVE, claim, id);
+        estate_call(ADDR_ALICE, claim, id);
+        assert_eq!(
+            payouts(),
+            [
+                (TOKEN.to_string(), ADDR_BOB.to_string(), 666),
+                (OTHER_TOKEN.to_string(), ADDR_BOB.to_string(), 6),
+                (TOKEN.to_string(), ADDR_ALICE.to_string(), 334),
+                (OTHER_TOKEN.to_string(), ADDR_ALICE.to_string(), 4),
+            ]
+        );
+        assert_eq!(load_estate(id).unwrap().status, EstateStatus::Released);
+    }
+
+    #[test]
+    fn released_estates_are_closed_to_the_owner() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let id = setup();
+        move_tokens(deposit, id, TOKEN, 500);
+        mock::set_block_timestamp(START + INTERVAL);
+        estate_call(ADDR_BOB, trigger, id);
+        mock::set_block_timestamp(START + INTERVAL + CHALLENGE);
+        mock::take_contract_calls();
+
+        // Too late to cancel, even before anyone has claimed.
+        estate_call(ADDR_OWNER, heartbeat, id);
+        move_tokens(withdraw, id, TOKEN, 10);
+        move_tokens(deposit, id, TOKEN, 10);
+        call(
+            ADDR_OWNER,
+            set_beneficiaries,
+            encode(&SetBeneficiariesArgs {
+                estate_id: id,
+                beneficiaries: shares(5_000, 5_000),
+            }),
+        );
+        assert!(mock::take_contract_calls().is_empty());
+        assert_eq!(load_estate(id).unwrap().beneficiaries, shares(3_333, 6_667));
+        assert_eq!(balance_of(id, TOKEN).unwrap(), 500);
+
+        estate_call(ADDR_BOB, claim, id);
+        assert_eq!(load_estate(id).unwrap().status, EstateStatus::Released);
+    }
+
+    #[test]
+    fn decommissioning_stops_new_estates_but_not_withdrawals_or_claims() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let id = setup();
+        call(ADDR_ADMIN, initialize, Vec::new());
+        move_tokens(deposit, id, TOKEN, 500);
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        call(ADDR_OWNER, schedule_decommission, notice.clone());
+        assert_eq!(decommission::status().unwrap().eta, None);
+        let eta: u64 =
+            postcard::from_bytes(&call(ADDR_ADMIN, schedule_decommission, notice)).unwrap();
+        mock::set_block_timestamp(eta);
+        call(ADDR_ADMIN, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert!(create(shares(5_000, 5_000)).is_empty());
+        move_tokens(deposit, id, TOKEN, 10);
+        assert_eq!(balance_of(id, TOKEN).unwrap(), 500);
+
+        estate_call(ADDR_OWNER, heartbeat, id);
+        move_tokens(withdraw, id, TOKEN, 100);
+        assert_eq!(balance_of(id, TOKEN).unwrap(), 400);
+
+        let lapsed = load_estate(id).unwrap().last_heartbeat + INTERVAL;
+        mock::set_block_timestamp(lapsed);
+        estate_call(ADDR_BOB, trigger, id);
+        mock::set_block_timestamp(lapsed + CHALLENGE);
+        mock::take_contract_calls();
+        estate_call(ADDR_ALICE, claim, id);
+        assert_eq!(
+            payouts(),
+            vec![(TOKEN.to_string(), ADDR_ALICE.to_string(), 134)]
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "inheritance-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
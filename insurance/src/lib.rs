@@ -0,0 +1,1304 @@
+//! Insurance Coverage Pool
+//!
+//! Underwriters stake a CRC-20 into a shared pool and receive pool shares.
+//! Users buy time-limited coverage by paying a premium into the pool. Claims
+//! are filed against a policy while it is active or within the claim window
+//! after it ends, and are paid once enough assessors approve them.
+//!
+//! Premiums and claim payouts both change the pool's assets, never the
+//! share count, so every underwriter earns premiums and absorbs losses in
+//! proportion to their stake.
+//!
+//! ## Features
+//! - Share-based underwriting with pro-rata premiums and losses
+//! - Capacity checks: active coverage never exceeds pool assets
+//! - Locked capital: underwriters cannot withdraw below active coverage
+//! - Claims approved by a single assessor or an assessor vote
+//! - Owner pause for new stakes and coverage; exits and claims stay open
+//! - Decommissioning: after the owner's notice runs out, stakes and coverage
+//!   stop for good while policies run off and underwriters withdraw
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "insurance_config";
+const POOL_KEY: &str = "insurance_pool";
+const NEXT_POLICY_KEY: &str = "next_policy_id";
+const NEXT_CLAIM_KEY: &str = "next_claim_id";
+const SHARES_PREFIX: &str = "underwriter_shares";
+const ASSESSORS_PREFIX: &str = "assessors";
+const ASSESSOR_ROLE: &str = "assessor";
+const POLICIES_PREFIX: &str = "policies";
+const CLAIMS_PREFIX: &str = "claims";
+const VOTES_PREFIX: &str = "claim_votes";
+const SECONDS_PER_DAY: u64 = 86_400;
+const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
+const MIN_COVER_DURATION: u64 = SECONDS_PER_DAY;
+const MAX_COVER_DURATION: u64 = SECONDS_PER_YEAR;
+const MAX_CLAIM_WINDOW: u64 = 90 * SECONDS_PER_DAY;
+const MAX_PREMIUM_RATE_BPS: u32 = 10_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+const MAX_EVIDENCE_BYTES: usize = 256;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Pool configuration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InsuranceConfig {
+    pub owner: String,
+    pub token: String,
+    /// Annual premium as basis points of the cover amount
+    pub premium_rate_bps: u32,
+    /// Seconds after a policy ends during which claims may still be filed
+    pub claim_window: u64,
+    /// Assessor votes needed to approve (or reject) a claim
+    pub approvals_required: u32,
+    pub assessor_count: u32,
+}
+
+/// Pool totals
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolState {
+    pub total_assets: u64,
+    pub total_shares: u64,
+    /// Cover still payable on unreleased policies
+    pub active_cover: u64,
+    pub premiums_earned: u64,
+    pub claims_paid: u64,
+}
+
+/// A purchased coverage policy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Policy {
+    pub holder: String,
+    pub cover_amount: u64,
+    pub premium: u64,
+    pub start: u64,
+    pub end: u64,
+    pub claimed: u64,
+    pub pending_claim: Option<u64>,
+    pub released: bool,
+}
+
+/// Claim lifecycle
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClaimStatus {
+    Pending,
+    Paid,
+    Rejected,
+}
+
+/// A claim against a policy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Claim {
+    pub policy_id: u64,
+    pub amount: u64,
+    pub evidence: String,
+    pub filed_at: u64,
+    pub approvals: u32,
+    pub rejections: u32,
+    pub status: ClaimStatus,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    token: String,
+    premium_rate_bps: u32,
+    claim_window: u64,
+    approvals_required: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetAssessorArgs {
+    account: String,
+    enabled: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SharesArgs {
+    shares: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CoverageArgs {
+    cover_amount: u64,
+    duration: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileClaimArgs {
+    policy_id: u64,
+    amount: u64,
+    evidence: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AssessClaimArgs {
+    claim_id: u64,
+    approve: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdArgs {
+    id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<InsuranceConfig> {
+    storage()
+        .get::<InsuranceConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Pool not initialized".to_string()))
+}
+
+fn save_config(config: &InsuranceConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+fn load_pool() -> ContractResult<PoolState> {
+    Ok(storage().get::<PoolState>(POOL_KEY)?.unwrap_or_default())
+}
+
+fn save_pool(pool: &PoolState) -> ContractResult<()> {
+    storage().set(POOL_KEY, pool)
+}
+
+fn shares() -> Map<String, u64> {
+    Map::new(SHARES_PREFIX)
+}
+
+fn assessors() -> Map<String, bool> {
+    Map::new(ASSESSORS_PREFIX)
+}
+
+fn policies() -> Map<u64, Policy> {
+    Map::new(POLICIES_PREFIX)
+}
+
+fn claims() -> Map<u64, Claim> {
+    Map::new(CLAIMS_PREFIX)
+}
+
+fn votes() -> Map<(u64, String), bool> {
+    Map::new(VOTES_PREFIX)
+}
+
+fn share_balance(account: &str) -> ContractResult<u64> {
+    Ok(shares().get(&account.to_string())?.unwrap_or(0))
+}
+
+fn load_policy(policy_id: u64) -> ContractResult<Policy> {
+    policies()
+        .get(&policy_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown policy".to_string()))
+}
+
+fn load_claim(claim_id: u64) -> ContractResult<Claim> {
+    claims()
+        .get(&claim_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown claim".to_string()))
+}
+
+fn next_id(key: &str) -> ContractResult<u64> {
+    let mut store = storage();
+    let id = store.get::<u64>(key)?.unwrap_or(0);
+    store.set(key, &safe_math::add(id, 1)?)?;
+    Ok(id)
+}
+
+/// Premium for `cover_amount` over `duration` seconds, rounded up.
+fn premium_for(config: &InsuranceConfig, cover_amount: u64, duration: u64) -> ContractResult<u64> {
+    let numerator = (cover_amount as u128) * (config.premium_rate_bps as u128) * (duration as u128);
+    let denominator = BPS_DENOMINATOR * (SECONDS_PER_YEAR as u128);
+    u64::try_from(numerator.div_ceil(denominator)).map_err(|_| ContractError::Overflow)
+}
+
+fn pull_tokens(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Pool already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    if args.premium_rate_bps == 0 || args.premium_rate_bps > MAX_PREMIUM_RATE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Premium rate must be 1-{} bps",
+            MAX_PREMIUM_RATE_BPS
+        )));
+    }
+    if args.claim_window == 0 || args.claim_window > MAX_CLAIM_WINDOW {
+        return Err(ContractError::InvalidArgument(
+            "Claim window must be between 1 second and 90 days".to_string(),
+        ));
+    }
+    if args.approvals_required == 0 {
+        return Err(ContractError::InvalidArgument(
+            "At least one approval is required".to_string(),
+        ));
+    }
+
+    save_config(&InsuranceConfig {
+        owner: ctx.sender().to_string(),
+        token: args.token.clone(),
+        premium_rate_bps: args.premium_rate_bps,
+        claim_window: args.claim_window,
+        approvals_required: args.approvals_required,
+        assessor_count: 0,
+    })?;
+
+    event!("PoolInitialized", owner: ctx.sender(), token: args.token);
+    Ok(())
+}
+
+fn execute_set_assessor() -> ContractResult<()> {
+    let mut config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    decommission::when_active()?;
+    let args: SetAssessorArgs = read_args()?;
+    validation::validate_address(&args.account)?;
+
+    let mut map = assessors();
+    let current = map.get(&args.account)?.unwrap_or(false);
+    if current == args.enabled {
+        return Ok(());
+    }
+    if args.enabled {
+        map.set(&args.account, &true)?;
+        config.assessor_count = config
+            .assessor_count
+            .checked_add(1)
+            .ok_or(ContractError::Overflow)?;
+    } else {
+        map.remove(&args.account)?;
+        config.assessor_count -= 1;
+    }
+    save_config(&config)?;
+
+    event!("AssessorUpdated", account: args.account, enabled: args.enabled);
+    if args.enabled {
+        admin_log::record(admin_log::GRANT_ROLE, &args.account, "", ASSESSOR_ROLE)?;
+    } else {
+        admin_log::record(admin_log::REVOKE_ROLE, &args.account, ASSESSOR_ROLE, "")?;
+    }
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    if context().sender() != load_config()?.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    if context().sender() != load_config()?.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    pausable::unpause()
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    if context().sender() != load_config()?.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    if context().sender() != load_config()?.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    if context().sender() != load_config()?.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_stake() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    let config = load_config()?;
+    let underwriter = context().sender().to_string();
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    let mut pool = load_pool()?;
+    // One virtual share and asset keep the first stake from setting an
+    // exploitable share price.
+    let minted = safe_math::mul_div(
+        args.amount,
+        safe_math::add(pool.total_shares, 1)?,
+        safe_math::add(pool.total_assets, 1)?,
+        Rounding::Down,
+    )?;
+    if minted == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Stake too small to mint shares".to_string(),
+        ));
+    }
+    pull_tokens(&config.token, &underwriter, args.amount)?;
+
+    pool.total_assets = safe_math::add(pool.total_assets, args.amount)?;
+    pool.total_shares = safe_math::add(pool.total_shares, minted)?;
+    save_pool(&pool)?;
+    let balance = safe_math::add(share_balance(&underwriter)?, minted)?;
+    shares().set(&underwriter, &balance)?;
+
+    event!("Staked", underwriter: underwriter, amount: args.amount, shares: minted);
+    Ok(minted)
+}
+
+fn execute_unstake() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let underwriter = context().sender().to_string();
+    let args: SharesArgs = read_args()?;
+    validation::validate_positive_amount(args.shares)?;
+
+    let held = share_balance(&underwriter)?;
+    let remaining = held
+        .checked_sub(args.shares)
+        .ok_or(ContractError::InsufficientBalance {
+            required: args.shares,
+            available: held,
+        })?;
+    let mut pool = load_pool()?;
+    let amount = safe_math::mul_div(
+        args.shares,
+        safe_math::add(pool.total_assets, 1)?,
+        safe_math::add(pool.total_shares, 1)?,
+        Rounding::Down,
+    )?;
+    let free = pool.total_assets.saturating_sub(pool.active_cover);
+    if amount > free {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: free,
+        });
+    }
+
+    shares().set(&underwriter, &remaining)?;
+    pool.total_shares -= args.shares;
+    pool.total_assets -= amount;
+    save_pool(&pool)?;
+    send_tokens(&config.token, &underwriter, amount)?;
+
+    event!("Unstaked", underwriter: underwriter, amount: amount, shares: args.shares);
+    Ok(amount)
+}
+
+fn execute_buy_coverage() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    let config = load_config()?;
+    let ctx = context();
+    let holder = ctx.sender().to_string();
+    let args: CoverageArgs = read_args()?;
+    validation::validate_positive_amount(args.cover_amount)?;
+    if !(MIN_COVER_DURATION..=MAX_COVER_DURATION).contains(&args.duration) {
+        return Err(ContractError::InvalidArgument(
+            "Coverage must last between 1 and 365 days".to_string(),
+        ));
+    }
+
+    let mut pool = load_pool()?;
+    let active_cover = safe_math::add(pool.active_cover, args.cover_amount)?;
+    if active_cover > pool.total_assets {
+        return Err(ContractError::InsufficientBalance {
+            required: args.cover_amount,
+            available: pool.total_assets.saturating_sub(pool.active_cover),
+        });
+    }
+    let premium = premium_for(&config, args.cover_amount, args.duration)?;
+    pull_tokens(&config.token, &holder, premium)?;
+
+    pool.active_cover = active_cover;
+    pool.total_assets = safe_math::add(pool.total_assets, premium)?;
+    pool.premiums_earned = safe_math::add(pool.premiums_earned, premium)?;
+    save_pool(&pool)?;
+
+    let start = ctx.block_timestamp();
+    let policy_id = next_id(NEXT_POLICY_KEY)?;
+    policies().set(
+        &policy_id,
+        &Policy {
+            holder: holder.clone(),
+            cover_amount: args.cover_amount,
+            premium,
+            start,
+            end: safe_math::add(start, args.duration)?,
+            claimed: 0,
+            pending_claim: None,
+            released: false,
+        },
+    )?;
+
+    event!("CoveragePurchased",
+        policy_id: policy_id,
+        holder: holder,
+        cover_amount: args.cover_amount,
+        premium: premium,
+        end: start + args.duration
+    );
+    Ok(policy_id)
+}
+
+fn execute_file_claim() -> ContractResult<u64> {
+    let config = load_config()?;
+    let ctx = context();
+    let args: FileClaimArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    if args.evidence.is_empty() || args.evidence.len() > MAX_EVIDENCE_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Evidence must be 1-{} bytes",
+            MAX_EVIDENCE_BYTES
+        )));
+    }
+
+    let mut policy = load_policy(args.policy_id)?;
+    if ctx.sender() != policy.holder {
+        return Err(ContractError::Unauthorized);
+    }
+    let now = ctx.block_timestamp();
+    if now > safe_math::add(policy.end, config.claim_window)? || policy.released {
+        return Err(ContractError::InvalidArgument(
+            "Claim window has closed".to_string(),
+        ));
+    }
+    if policy.pending_claim.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Policy already has a pending claim".to_string(),
+        ));
+    }
+    let remaining = policy.cover_amount - policy.claimed;
+    if args.amount > remaining {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: remaining,
+        });
+    }
+
+    let claim_id = next_id(NEXT_CLAIM_KEY)?;
+    claims().set(
+        &claim_id,
+        &Claim {
+            policy_id: args.policy_id,
+            amount: args.amount,
+            evidence: args.evidence,
+            filed_at: now,
+            approvals: 0,
+            rejections: 0,
+            status: ClaimStatus::Pending,
+        },
+    )?;
+    policy.pending_claim = Some(claim_id);
+    policies().set(&args.policy_id, &policy)?;
+
+    event!("ClaimFiled",
+        claim_id: claim_id,
+        policy_id: args.policy_id,
+        holder: policy.holder,
+        amount: args.amount
+    );
+    Ok(claim_id)
+}
+
+fn execute_assess_claim() -> ContractResult<ClaimStatus> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let assessor = context().sender().to_string();
+    if !assessors().get(&assessor)?.unwrap_or(false) {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: AssessClaimArgs = read_args()?;
+    let mut claim = load_claim(args.claim_id)?;
+    if claim.status != ClaimStatus::Pending {
+        return Err(ContractError::InvalidArgument(
+            "Claim already settled".to_string(),
+        ));
+    }
+    let vote_key = (args.claim_id, assessor.clone());
+    let mut vote_map = votes();
+    if vote_map.contains_key(&vote_key)? {
+        return Err(ContractError::InvalidArgument(
+            "Assessor already voted".to_string(),
+        ));
+    }
+    vote_map.set(&vote_key, &args.approve)?;
+
+    if args.approve {
+        claim.approvals += 1;
+    } else {
+        claim.rejections += 1;
+    }
+    event!("ClaimAssessed", claim_id: args.claim_id, assessor: assessor, approve: args.approve);
+    admin_log::record("assess_claim", &args.claim_id.to_string(), "", args.approve)?;
+
+    let mut policy = load_policy(claim.policy_id)?;
+    if claim.approvals >= config.approvals_required {
+        claim.status = ClaimStatus::Paid;
+        policy.claimed = safe_math::add(policy.claimed, claim.amount)?;
+        policy.pending_claim = None;
+        let mut pool = load_pool()?;
+        pool.total_assets = safe_math::sub(pool.total_assets, claim.amount)?;
+        pool.active_cover = safe_math::sub(pool.active_cover, claim.amount)?;
+        pool.claims_paid = safe_math::add(pool.claims_paid, claim.amount)?;
+        save_pool(&pool)?;
+    } else if claim.rejections >= config.approvals_required {
+        claim.status = ClaimStatus::Rejected;
+        policy.pending_claim = None;
+    }
+    claims().set(&args.claim_id, &claim)?;
+    policies().set(&claim.policy_id, &policy)?;
+
+    match claim.status {
+        ClaimStatus::Paid => {
+            send_tokens(&config.token, &policy.holder, claim.amount)?;
+            event!("ClaimPaid", claim_id: args.claim_id, holder: policy.holder, amount: claim.amount);
+        }
+        ClaimStatus::Rejected => {
+            event!("ClaimRejected", claim_id: args.claim_id);
+        }
+        ClaimStatus::Pending => {}
+    }
+    Ok(claim.status)
+}
+
+fn execute_release_policy() -> ContractResult<()> {
+    let config = load_config()?;
+    let args: IdArgs = read_args()?;
+    let mut policy = load_policy(args.id)?;
+    if policy.released {
+        return Err(ContractError::InvalidArgument(
+            "Policy already released".to_string(),
+        ));
+    }
+    if context().block_timestamp() <= safe_math::add(policy.end, config.claim_window)?
+        || policy.pending_claim.is_some()
+    {
+        return Err(ContractError::InvalidArgument(
+            "Policy can still be claimed against".to_string(),
+        ));
+    }
+
+    let unused = policy.cover_amount - policy.claimed;
+    let mut pool = load_pool()?;
+    pool.active_cover = safe_math::sub(pool.active_cover, unused)?;
+    save_pool(&pool)?;
+    policy.released = true;
+    policies().set(&args.id, &policy)?;
+
+    event!("PolicyReleased", policy_id: args.id, released_cover: unused);
+    Ok(())
+}
+
+fn execute_underwriter_value(account: &str) -> ContractResult<u64> {
+    let pool = load_pool()?;
+    safe_math::mul_div(
+        share_balance(account)?,
+        safe_math::add(pool.total_assets, 1)?,
+        safe_math::add(pool.total_shares, 1)?,
+        Rounding::Down,
+    )
+}
+
+fn respond_id(name: &str, result: ContractResult<u64>) {
+    if let Err(err) = result.and_then(|id| try_respond(&id)) {
+        log!("{} failed: {}", name, err);
+    }
+}
+
+/// Initialize the pool; the caller becomes its owner
+///
+/// # Arguments
+/// * `token` - CRC-20 staked, paid as premium and paid out on claims
+/// * `premium_rate_bps` - Annual premium as basis points of cover (1-10000)
+/// * `claim_window` - Seconds after a policy ends during which claims may be filed
+/// * `approvals_required` - Assessor votes needed to approve or reject a claim
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Add or remove a claim assessor (owner only)
+///
+/// # Arguments
+/// * `account` - Assessor address
+/// * `enabled` - Whether the account may assess claims
+#[unsafe(no_mangle)]
+pub extern "C" fn set_assessor() {
+    if let Err(err) = execute_set_assessor() {
+        log!("set_assessor failed: {}", err);
+    }
+}
+
+/// Pause the pool (owner only)
+///
+/// Staking and buying coverage fail until `unpause`; unstaking, claims and
+/// policy releases stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the pool is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Stake tokens as an underwriter (approve the pool first)
+///
+/// # Arguments
+/// * `amount` - Tokens to stake
+///
+/// # Returns
+/// Pool shares minted
+#[unsafe(no_mangle)]
+pub extern "C" fn stake() {
+    respond_id("stake", execute_stake());
+}
+
+/// Burn pool shares for their current value; capital backing active
+/// coverage cannot be withdrawn
+///
+/// # Arguments
+/// * `shares` - Shares to burn
+///
+/// # Returns
+/// Tokens sent to the underwriter
+#[unsafe(no_mangle)]
+pub extern "C" fn unstake() {
+    respond_id("unstake", execute_unstake());
+}
+
+/// Buy coverage starting now (approve the premium first)
+///
+/// # Arguments
+/// * `cover_amount` - Most the policy pays out in total
+/// * `duration` - Coverage length in seconds (1-365 days)
+///
+/// # Returns
+/// Policy id
+#[unsafe(no_mangle)]
+pub extern "C" fn buy_coverage() {
+    respond_id("buy_coverage", execute_buy_coverage());
+}
+
+/// File a claim against one of the caller's policies
+///
+/// # Arguments
+/// * `policy_id` - Policy to claim against
+/// * `amount` - Amount claimed, at most the policy's unclaimed cover
+/// * `evidence` - Reference to supporting evidence (max 256 bytes)
+///
+/// # Returns
+/// Claim id
+#[unsafe(no_mangle)]
+pub extern "C" fn file_claim() {
+    respond_id("file_claim", execute_file_claim());
+}
+
+/// Vote on a pending claim (assessors only); the claim is paid or rejected
+/// as soon as either side reaches the required number of votes
+///
+/// # Arguments
+/// * `claim_id` - Claim to assess
+/// * `approve` - Approve or reject
+///
+/// # Returns
+/// `ClaimStatus` after the vote
+#[unsafe(no_mangle)]
+pub extern "C" fn assess_claim() {
+    let result = execute_assess_claim().and_then(|status| try_respond(&status));
+    if let Err(err) = result {
+        log!("assess_claim failed: {}", err);
+    }
+}
+
+/// Free the unused cover of a policy whose claim window has closed
+///
+/// # Arguments
+/// * `id` - Policy id
+#[unsafe(no_mangle)]
+pub extern "C" fn release_policy() {
+    if let Err(err) = execute_release_policy() {
+        log!("release_policy failed: {}", err);
+    }
+}
+
+/// Give notice that the pool will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the pool once the notice has run out (owner only)
+///
+/// Stakes, new coverage and assessor changes stop for good. Underwriters
+/// can still unstake whatever is not backing active cover, and existing
+/// policies can still be claimed against and released.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the pool is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Premium for a prospective policy
+///
+/// # Arguments
+/// * `cover_amount` - Cover amount
+/// * `duration` - Coverage length in seconds
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn quote_premium() {
+    let result = read_args::<CoverageArgs>()
+        .and_then(|args| premium_for(&load_config()?, args.cover_amount, args.duration));
+    respond_id("quote_premium", result);
+}
+
+/// Current token value of an underwriter's shares
+///
+/// # Arguments
+/// * `account` - Underwriter address
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn underwriter_value() {
+    let result =
+        read_args::<AccountArgs>().and_then(|args| execute_underwriter_value(&args.account));
+    respond_id("underwriter_value", result);
+}
+
+/// Get a policy
+///
+/// # Arguments
+/// * `id` - Policy id
+///
+/// # Returns
+/// `Policy`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_policy() {
+    let result = read_args::<IdArgs>()
+        .and_then(|args| load_policy(args.id))
+        .and_then(|policy| try_respond(&policy));
+    if let Err(err) = result {
+        log!("get_policy failed: {}", err);
+    }
+}
+
+/// Get a claim
+///
+/// # Arguments
+/// * `id` - Claim id
+///
+/// # Returns
+/// `Claim`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_claim() {
+    let result = read_args::<IdArgs>()
+        .and_then(|args| load_claim(args.id))
+        .and_then(|claim| try_respond(&claim));
+    if let Err(err) = result {
+        log!("get_claim failed: {}", err);
+    }
+}
+
+/// Get the pool totals
+///
+/// # Returns
+/// `PoolState`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_pool() {
+    if let Err(err) = load_pool().and_then(|pool| try_respond(&pool)) {
+        log!("get_pool failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_USER: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_ASSESSOR_1: &str = "0x0000000000000000000000000000000000000e05";
+    const ADDR_ASSESSOR_2: &str = "0x0000000000000000000000000000000000000e06";
+    const POOL: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const START: u64 = 1_736_000_000;
+    const WINDOW: u64 = 7 * SECONDS_PER_DAY;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn call_id(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Option<u64> {
+        let response = call(sender, entrypoint, data);
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// Pool with two assessors (2 votes needed), Alice staking 6000 and Bob 4000
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(POOL);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+        call(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                token: TOKEN.to_string(),
+                premium_rate_bps: 1_000,
+                claim_window: WINDOW,
+                approvals_required: 2,
+            }),
+        );
+        for assessor in [ADDR_ASSESSOR_1, ADDR_ASSESSOR_2] {
+            call(
+                ADDR_OWNER,
+                set_assessor,
+                encode(&SetAssessorArgs {
+                    account: assessor.to_string(),
+                    enabled: true,
+                }),
+            );
+        }
+        call(ADDR_ALICE, stake, encode(&AmountArgs { amount: 6_000 }));
+        call(ADDR_BOB, stake, encode(&AmountArgs { amount: 4_000 }));
+    }
+
+    fn buy(cover_amount: u64, duration: u64) -> Option<u64> {
+        call_id(
+            ADDR_USER,
+            buy_coverage,
+            encode(&CoverageArgs {
+                cover_amount,
+                duration,
+            }),
+        )
+    }
+
+    fn claim(policy_id: u64, amount: u64) -> Option<u64> {
+        call_id(
+            ADDR_USER,
+            file_claim,
+            encode(&FileClaimArgs {
+                policy_id,
+                amount,
+                evidence: "ipfs://incident-report".to_string(),
+            }),
+        )
+    }
+
+    fn assess(assessor: &str, claim_id: u64, approve: bool) -> Vec<u8> {
+        call(
+            assessor,
+            assess_claim,
+            encode(&AssessClaimArgs { claim_id, approve }),
+        )
+    }
+
+    fn value_of(account: &str) -> u64 {
+        execute_underwriter_value(account).unwrap()
+    }
+
+    #[test]
+    fn premiums_accrue_to_underwriters_pro_rata() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        // 10% a year on 5000 for 73 days is 100.
+        let policy_id = buy(5_000, 73 * SECONDS_PER_DAY).unwrap();
+        let policy = load_policy(policy_id).unwrap();
+        assert_eq!(policy.premium, 100);
+        assert_eq!(policy.end, START + 73 * SECONDS_PER_DAY);
+
+        let pool = load_pool().unwrap();
+        assert_eq!((pool.total_assets, pool.active_cover), (10_100, 5_000));
+        assert_eq!(value_of(ADDR_ALICE), 6_059);
+        assert_eq!(value_of(ADDR_BOB), 4_039);
+    }
+
+    #[test]
+    fn coverage_and_withdrawals_respect_locked_capital() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        assert_eq!(buy(10_001, 30 * SECONDS_PER_DAY), None);
+        buy(8_000, 30 * SECONDS_PER_DAY).unwrap();
+
+        // Only assets above the 8000 of active cover can leave the pool.
+        let bob_shares = share_balance(ADDR_BOB).unwrap();
+        assert!(
+            call_id(
+                ADDR_BOB,
+                unstake,
+                encode(&SharesArgs { shares: bob_shares })
+            )
+            .is_none()
+        );
+        let withdrawn = call_id(ADDR_BOB, unstake, encode(&SharesArgs { shares: 2_000 }));
+        assert_eq!(withdrawn, Some(2_013));
+        assert_eq!(load_pool().unwrap().total_assets, 10_066 - 2_013);
+    }
+
+    #[test]
+    fn pausing_stops_stakes_and_coverage_but_not_exits_or_claims() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let policy_id = buy(5_000, 73 * SECONDS_PER_DAY).unwrap();
+        call(ADDR_ALICE, pause, Vec::new());
+        assert!(!pausable::paused().unwrap());
+        call(ADDR_OWNER, pause, Vec::new());
+        assert!(postcard::from_bytes::<bool>(&call(ADDR_USER, paused, Vec::new())).unwrap());
+
+        assert_eq!(
+            call_id(ADDR_ALICE, stake, encode(&AmountArgs { amount: 1_000 })),
+            None
+        );
+        assert_eq!(buy(1_000, 30 * SECONDS_PER_DAY), None);
+
+        // Underwriters can leave and policy holders can still claim.
+        assert!(call_id(ADDR_BOB, unstake, encode(&SharesArgs { shares: 1_000 })).is_some());
+        let claim_id = claim(policy_id, 2_000).unwrap();
+        assess(ADDR_ASSESSOR_1, claim_id, true);
+        assess(ADDR_ASSESSOR_2, claim_id, true);
+        assert_eq!(load_claim(claim_id).unwrap().status, ClaimStatus::Paid);
+
+        call(ADDR_OWNER, unpause, Vec::new());
+        assert!(buy(1_000, 30 * SECONDS_PER_DAY).is_some());
+    }
+
+    #[test]
+    fn decommissioning_stops_new_business_but_not_withdrawals_or_claims() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let policy_id = buy(5_000, 73 * SECONDS_PER_DAY).unwrap();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call_id(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call_id(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        call(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(
+            call_id(ADDR_ALICE, stake, encode(&AmountArgs { amount: 1_000 })),
+            None
+        );
+        assert_eq!(buy(1_000, 30 * SECONDS_PER_DAY), None);
+        call(
+            ADDR_OWNER,
+            set_assessor,
+            encode(&SetAssessorArgs {
+                account: ADDR_ASSESSOR_1.to_string(),
+                enabled: false,
+            }),
+        );
+        assert!(
+            assessors()
+                .get(&ADDR_ASSESSOR_1.to_string())
+                .unwrap()
+                .unwrap()
+        );
+
+        // The running policy is still honoured and free capital can leave.
+        let claim_id = claim(policy_id, 2_000).unwrap();
+        assess(ADDR_ASSESSOR_1, claim_id, true);
+        assess(ADDR_ASSESSOR_2, claim_id, true);
+        assert_eq!(load_claim(claim_id).unwrap().status, ClaimStatus::Paid);
+        assert!(call_id(ADDR_BOB, unstake, encode(&SharesArgs { shares: 1_000 })).is_some());
+    }
+
+    #[test]
+    fn approved_claims_are_paid_and_losses_shared() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let policy_id = buy(5_000, 73 * SECONDS_PER_DAY).unwrap();
+        let claim_id = claim(policy_id, 2_000).unwrap();
+        assert_eq!(claim(policy_id, 1_000), None);
+
+        assert!(assess(ADDR_USER, claim_id, true).is_empty());
+        assess(ADDR_ASSESSOR_1, claim_id, true);
+        assert!(assess(ADDR_ASSESSOR_1, claim_id, true).is_empty());
+        assert_eq!(load_claim(claim_id).unwrap().status, ClaimStatus::Pending);
+        mock::take_contract_calls();
+
+        let status: ClaimStatus =
+            postcard::from_bytes(&assess(ADDR_ASSESSOR_2, claim_id, true)).unwrap();
+        assert_eq!(status, ClaimStatus::Paid);
+        // Setup's two assessors, then one entry per accepted vote.
+        assert_eq!(admin_log::next_sequence().unwrap(), 4);
+        let calls = mock::take_contract_calls();
+        assert_eq!(
+            calls[0].args,
+            encode(&TokenTransferArgs {
+                to: ADDR_USER.to_string(),
+                amount: 2_000,
+            })
+        );
+
+        let pool = load_pool().unwrap();
+        assert_eq!((pool.total_assets, pool.active_cover), (8_100, 3_000));
+        // The 2000 loss is split 60/40.
+        assert_eq!(value_of(ADDR_ALICE), 4_860);
+        assert_eq!(value_of(ADDR_BOB), 3_240);
+        assert_eq!(load_policy(policy_id).unwrap().claimed, 2_000);
+    }
+
+    #[test]
+    fn claims_are_limited_to_holder_window_and_cover() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let policy_id = buy(1_000, 30 * SECONDS_PER_DAY).unwrap();
+
+        assert_eq!(claim(policy_id, 1_001), None);
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&encode(&FileClaimArgs {
+            policy_id,
+            amount: 10,
+            evidence: "ipfs://x".to_string(),
+        }));
+        file_claim();
+        assert!(mock::take_return_data().is_empty());
+
+        // Rejected claims free the policy for another claim.
+        let first = claim(policy_id, 500).unwrap();
+        assess(ADDR_ASSESSOR_1, first, false);
+        assess(ADDR_ASSESSOR_2, first, false);
+        assert_eq!(load_claim(first).unwrap().status, ClaimStatus::Rejected);
+        assert_eq!(load_pool().unwrap().claims_paid, 0);
+
+        mock::set_block_timestamp(START + 30 * SECONDS_PER_DAY + WINDOW);
+        let last = claim(policy_id, 500).unwrap();
+        assess(ADDR_ASSESSOR_1, last, false);
+        assess(ADDR_ASSESSOR_2, last, false);
+        mock::set_block_timestamp(START + 30 * SECONDS_PER_DAY + WINDOW + 1);
+        assert_eq!(claim(policy_id, 100), None);
+    }
+
+    #[test]
+    fn expired_policies_release_cover() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let policy_id = buy(4_000, 30 * SECONDS_PER_DAY).unwrap();
+        let release = || call(ADDR_BOB, release_policy, encode(&IdArgs { id: policy_id }));
+
+        mock::set_block_timestamp(START + 30 * SECONDS_PER_DAY + WINDOW);
+        release();
+        assert_eq!(load_pool().unwrap().active_cover, 4_000);
+
+        mock::set_block_timestamp(START + 30 * SECONDS_PER_DAY + WINDOW + 1);
+        release();
+        assert_eq!(load_pool().unwrap().active_cover, 0);
+        assert!(load_policy(policy_id).unwrap().released);
+        assert_eq!(claim(policy_id, 1), None);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "insurance-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,1137 @@
+//! Liquidity Bootstrapping Pool
+//!
+//! Sells a new token through a two-token weighted pool whose weights shift
+//! linearly from the sale token to the raise token over the sale window. The
+//! price starts high and falls unless buyers step in, which discourages
+//! front-running bots and lets the market find a price. Participation is by
+//! swapping against the pool, with an optional per-address purchase cap.
+//! After the sale the owner migrates the proceeds into a standard AMM pair at
+//! the final pool price.
+//!
+//! ## Features
+//! - Linear weight schedule between configurable start and end weights
+//! - Weighted constant-product swaps in both directions with a swap fee
+//! - Net purchase cap per address
+//! - Post-sale migration to an AMM pair at the closing price
+//! - Decommissioning: after the owner's notice runs out, swaps stop and the
+//!   pool waits for migration
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod weighted_math;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+use silica_contract_sdk::time::Duration;
+use weighted_math::BPS_DENOMINATOR;
+
+const CONFIG_KEY: &str = "lbp_config";
+const POOL_KEY: &str = "lbp_pool";
+const PURCHASED_PREFIX: &str = "purchased";
+const MIN_SALE_DURATION: u64 = 3_600;
+const MAX_SALE_DURATION: u64 = 30 * 86_400;
+const MIN_WEIGHT_BPS: u64 = 100;
+const MAX_WEIGHT_BPS: u64 = 9_900;
+const MAX_SWAP_FEE_BPS: u64 = 1_000;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Sale configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LbpConfig {
+    pub owner: String,
+    pub sale_token: String,
+    pub raise_token: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Sale token weight at `start_time`; the raise token gets the rest
+    pub start_sale_weight_bps: u64,
+    /// Sale token weight from `end_time` on
+    pub end_sale_weight_bps: u64,
+    pub swap_fee_bps: u64,
+    /// Most sale tokens one address may hold from the pool, net of sells
+    pub max_purchase: Option<u64>,
+    /// AMM router receiving the liquidity after the sale
+    pub router: String,
+}
+
+/// Pool balances and lifecycle flags
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pool {
+    pub sale_balance: u64,
+    pub raise_balance: u64,
+    pub funded: bool,
+    pub migrated: bool,
+}
+
+/// Pool state at the current block
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolView {
+    pub sale_balance: u64,
+    pub raise_balance: u64,
+    pub sale_weight_bps: u64,
+    pub raise_weight_bps: u64,
+    /// Raise tokens per sale token, scaled by 1e18
+    pub sale_price: u128,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    sale_token: String,
+    raise_token: String,
+    start_time: u64,
+    end_time: u64,
+    start_sale_weight_bps: u64,
+    end_sale_weight_bps: u64,
+    swap_fee_bps: u64,
+    max_purchase: Option<u64>,
+    router: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FundArgs {
+    sale_amount: u64,
+    raise_amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SwapArgs {
+    token_in: String,
+    amount_in: u64,
+    min_amount_out: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QuoteArgs {
+    token_in: String,
+    amount_in: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MigrateArgs {
+    recipient: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+/// Arguments of the router's `add_liquidity`
+#[derive(Serialize, Deserialize)]
+struct AddLiquidityArgs {
+    token_a: String,
+    token_b: String,
+    amount_a: u64,
+    amount_b: u64,
+    to: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+/// A priced swap, before any state changes
+struct SwapQuote {
+    buying: bool,
+    fee: u64,
+    amount_out: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<LbpConfig> {
+    storage()
+        .get::<LbpConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn load_owned_config() -> ContractResult<LbpConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn load_pool() -> ContractResult<Pool> {
+    Ok(storage().get::<Pool>(POOL_KEY)?.unwrap_or_default())
+}
+
+fn save_pool(pool: &Pool) -> ContractResult<()> {
+    storage().set(POOL_KEY, pool)
+}
+
+fn purchased() -> Map<String, u64> {
+    Map::new(PURCHASED_PREFIX)
+}
+
+fn pull_tokens(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+/// Sale token weight at `now`, interpolated over the sale window.
+pub fn sale_weight_at(config: &LbpConfig, now: u64) -> u64 {
+    let now = now.clamp(config.start_time, config.end_time);
+    let elapsed = (now - config.start_time) as u128;
+    let duration = (config.end_time - config.start_time) as u128;
+    let start = config.start_sale_weight_bps as u128;
+    let end = config.end_sale_weight_bps as u128;
+    // Both branches stay between the start and end weights.
+    if end >= start {
+        (start + (end - start) * elapsed / duration) as u64
+    } else {
+        (start - (start - end) * elapsed / duration) as u64
+    }
+}
+
+fn pool_view(config: &LbpConfig, pool: &Pool, now: u64) -> ContractResult<PoolView> {
+    let sale_weight = sale_weight_at(config, now);
+    let raise_weight = BPS_DENOMINATOR - sale_weight;
+    let sale_price = if pool.sale_balance == 0 {
+        0
+    } else {
+        weighted_math::spot_price(
+            pool.raise_balance,
+            raise_weight,
+            pool.sale_balance,
+            sale_weight,
+        )?
+    };
+    Ok(PoolView {
+        sale_balance: pool.sale_balance,
+        raise_balance: pool.raise_balance,
+        sale_weight_bps: sale_weight,
+        raise_weight_bps: raise_weight,
+        sale_price,
+    })
+}
+
+fn quote_swap(
+    config: &LbpConfig,
+    pool: &Pool,
+    token_in: &str,
+    amount_in: u64,
+    now: u64,
+) -> ContractResult<SwapQuote> {
+    validation::validate_positive_amount(amount_in)?;
+    let buying = if token_in == config.raise_token {
+        true
+    } else if token_in == config.sale_token {
+        false
+    } else {
+        return Err(ContractError::InvalidArgument(
+            "Token is not part of the pool".to_string(),
+        ));
+    };
+    let sale_weight = sale_weight_at(config, now);
+    let raise_weight = BPS_DENOMINATOR - sale_weight;
+    let fee = safe_math::apply_bps(amount_in, config.swap_fee_bps, Rounding::Down)?;
+    let amount_out = if buying {
+        weighted_math::out_given_in(
+            pool.raise_balance,
+            raise_weight,
+            pool.sale_balance,
+            sale_weight,
+            amount_in - fee,
+        )?
+    } else {
+        weighted_math::out_given_in(
+            pool.sale_balance,
+            sale_weight,
+            pool.raise_balance,
+            raise_weight,
+            amount_in - fee,
+        )?
+    };
+    Ok(SwapQuote {
+        buying,
+        fee,
+        amount_out,
+    })
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    for address in [&args.sale_token, &args.raise_token, &args.router] {
+        validation::validate_address(address)?;
+        if !ctx.is_contract(address) {
+            return Err(ContractError::InvalidArgument(
+                "Tokens and router must be contracts".to_string(),
+            ));
+        }
+    }
+    if args.sale_token == args.raise_token {
+        return Err(ContractError::InvalidArgument(
+            "Sale and raise tokens must differ".to_string(),
+        ));
+    }
+    if args.start_time < ctx.block_timestamp() {
+        return Err(ContractError::InvalidArgument(
+            "Sale cannot start in the past".to_string(),
+        ));
+    }
+    let duration = args.end_time.saturating_sub(args.start_time);
+    if !(MIN_SALE_DURATION..=MAX_SALE_DURATION).contains(&duration) {
+        return Err(ContractError::InvalidArgument(
+            "Sale must last between 1 hour and 30 days".to_string(),
+        ));
+    }
+    for weight in [args.start_sale_weight_bps, args.end_sale_weight_bps] {
+        validation::validate_range(weight, MIN_WEIGHT_BPS, MAX_WEIGHT_BPS)?;
+    }
+    if args.swap_fee_bps > MAX_SWAP_FEE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Swap fee cannot exceed {} bps",
+            MAX_SWAP_FEE_BPS
+        )));
+    }
+    if args.max_purchase == Some(0) {
+        return Err(ContractError::InvalidArgument(
+            "Purchase cap must be positive".to_string(),
+        ));
+    }
+
+    storage().set(
+        CONFIG_KEY,
+        &LbpConfig {
+            owner: ctx.sender().to_string(),
+            sale_token: args.sale_token.clone(),
+            raise_token: args.raise_token.clone(),
+            start_time: args.start_time,
+            end_time: args.end_time,
+            start_sale_weight_bps: args.start_sale_weight_bps,
+            end_sale_weight_bps: args.end_sale_weight_bps,
+            swap_fee_bps: args.swap_fee_bps,
+            max_purchase: args.max_purchase,
+            router: args.router,
+        },
+    )?;
+
+    event!("LbpInitialized",
+        owner: ctx.sender(),
+        sale_token: args.sale_token,
+        raise_token: args.raise_token,
+        start_time: args.start_time,
+        end_time: args.end_time
+    );
+    Ok(())
+}
+
+fn execute_fund() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: FundArgs = read_args()?;
+    validation::validate_positive_amount(args.sale_amount)?;
+    validation::validate_positive_amount(args.raise_amount)?;
+    let mut pool = load_pool()?;
+    if pool.funded {
+        return Err(ContractError::InvalidArgument(
+            "Pool already funded".to_string(),
+        ));
+    }
+    if ctx.block_timestamp() >= config.start_time {
+        return Err(ContractError::InvalidArgument(
+            "Pool must be funded before the sale starts".to_string(),
+        ));
+    }
+    pool.sale_balance = args.sale_amount;
+    pool.raise_balance = args.raise_amount;
+    pool.funded = true;
+    save_pool(&pool)?;
+
+    pull_tokens(&config.sale_token, &config.owner, args.sale_amount)?;
+    pull_tokens(&config.raise_token, &config.owner, args.raise_amount)?;
+
+    event!("PoolFunded",
+        sale_amount: args.sale_amount,
+        raise_amount: args.raise_amount
+    );
+    admin_log::record(
+        "fund",
+        "pool",
+        "",
+        format!("{}/{}", args.sale_amount, args.raise_amount),
+    )?;
+    Ok(())
+}
+
+fn execute_swap() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let trader = ctx.sender().to_string();
+    let config = load_config()?;
+    let args: SwapArgs = read_args()?;
+    let mut pool = load_pool()?;
+    let now = ctx.block_timestamp();
+    if !pool.funded || pool.migrated || now < config.start_time || now >= config.end_time {
+        return Err(ContractError::InvalidArgument(
+            "Sale is not open".to_string(),
+        ));
+    }
+    let quote = quote_swap(&config, &pool, &args.token_in, args.amount_in, now)?;
+    if quote.amount_out == 0 || quote.amount_out < args.min_amount_out {
+        return Err(ContractError::InvalidArgument(format!(
+            "Output {} below minimum {}",
+            quote.amount_out, args.min_amount_out
+        )));
+    }
+
+    let bought = purchased().get(&trader)?.unwrap_or(0);
+    let (bought, token_out) = if quote.buying {
+        let bought = safe_math::add(bought, quote.amount_out)?;
+        if config.max_purchase.is_some_and(|cap| bought > cap) {
+            return Err(ContractError::InvalidArgument(
+                "Purchase cap exceeded".to_string(),
+            ));
+        }
+        pool.raise_balance = safe_math::add(pool.raise_balance, args.amount_in)?;
+        pool.sale_balance -= quote.amount_out;
+        (bought, &config.sale_token)
+    } else {
+        pool.sale_balance = safe_math::add(pool.sale_balance, args.amount_in)?;
+        pool.raise_balance -= quote.amount_out;
+        (bought.saturating_sub(args.amount_in), &config.raise_token)
+    };
+    save_pool(&pool)?;
+    purchased().set(&trader, &bought)?;
+
+    pull_tokens(&args.token_in, &trader, args.amount_in)?;
+    send_tokens(token_out, &trader, quote.amount_out)?;
+
+    event!("Swap",
+        trader: trader,
+        token_in: args.token_in,
+        amount_in: args.amount_in,
+        amount_out: quote.amount_out,
+        fee: quote.fee,
+        sale_weight_bps: sale_weight_at(&config, now)
+    );
+    Ok(quote.amount_out)
+}
+
+fn execute_migrate() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let config = load_owned_config()?;
+    let args: MigrateArgs = read_args()?;
+    validation::validate_address(&args.recipient)?;
+    let mut pool = load_pool()?;
+    if !pool.funded || pool.migrated {
+        return Err(ContractError::InvalidArgument(
+            "Nothing to migrate".to_string(),
+        ));
+    }
+    if ctx.block_timestamp() < config.end_time {
+        return Err(ContractError::InvalidArgument(
+            "Sale has not ended".to_string(),
+        ));
+    }
+
+    // An AMM pair prices at the balance ratio, so keep the closing weighted
+    // price by matching the side that would be over-supplied.
+    let sale_weight = config.end_sale_weight_bps as u128;
+    let raise_weight = (BPS_DENOMINATOR - config.end_sale_weight_bps) as u128;
+    let sale_amount = u64::try_from(
+        ((pool.sale_balance as u128) * raise_weight / sale_weight).min(pool.sale_balance as u128),
+    )
+    .map_err(|_| ContractError::Overflow)?;
+    let raise_amount = u64::try_from(
+        ((pool.raise_balance as u128) * sale_weight / raise_weight).min(pool.raise_balance as u128),
+    )
+    .map_err(|_| ContractError::Overflow)?;
+    let sale_leftover = pool.sale_balance - sale_amount;
+    let raise_leftover = pool.raise_balance - raise_amount;
+    pool.sale_balance = 0;
+    pool.raise_balance = 0;
+    pool.migrated = true;
+    save_pool(&pool)?;
+
+    for (token, amount) in [
+        (&config.sale_token, sale_amount),
+        (&config.raise_token, raise_amount),
+    ] {
+        let approve = encode_call(&TokenApproveArgs {
+            spender: config.router.clone(),
+            amount,
+        })?;
+        ctx.call_contract(token, "approve", &approve)?;
+    }
+    let call = encode_call(&AddLiquidityArgs {
+        token_a: config.sale_token.clone(),
+        token_b: config.raise_token.clone(),
+        amount_a: sale_amount,
+        amount_b: raise_amount,
+        to: args.recipient.clone(),
+    })?;
+    let response = ctx.call_contract(&config.router, "add_liquidity", &call)?;
+    let liquidity: u64 =
+        postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)?;
+    if sale_leftover > 0 {
+        send_tokens(&config.sale_token, &config.owner, sale_leftover)?;
+    }
+    if raise_leftover > 0 {
+        send_tokens(&config.raise_token, &config.owner, raise_leftover)?;
+    }
+
+    event!("LiquidityMigrated",
+        recipient: args.recipient,
+        sale_amount: sale_amount,
+        raise_amount: raise_amount,
+        liquidity: liquidity,
+        sale_leftover: sale_leftover,
+        raise_leftover: raise_leftover
+    );
+    admin_log::record("migrate", &args.recipient, "", liquidity)?;
+    Ok(liquidity)
+}
+
+fn execute_get_pool() -> ContractResult<PoolView> {
+    pool_view(&load_config()?, &load_pool()?, context().block_timestamp())
+}
+
+fn execute_quote() -> ContractResult<u64> {
+    let args: QuoteArgs = read_args()?;
+    let quote = quote_swap(
+        &load_config()?,
+        &load_pool()?,
+        &args.token_in,
+        args.amount_in,
+        context().block_timestamp(),
+    )?;
+    Ok(quote.amount_out)
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_owned_config()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::unpause()
+}
+
+/// Configure the sale; the caller becomes its owner
+///
+/// # Arguments
+/// * `sale_token` - CRC-20 being sold
+/// * `raise_token` - CRC-20 buyers pay with
+/// * `start_time` / `end_time` - Sale window (1 hour to 30 days)
+/// * `start_sale_weight_bps` / `end_sale_weight_bps` - Sale token weight at each end (100-9900)
+/// * `swap_fee_bps` - Fee kept by the pool (max 1000)
+/// * `max_purchase` - Optional net purchase cap per address
+/// * `router` - AMM router used for migration
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Seed the pool before the sale starts (owner only)
+///
+/// # Arguments
+/// * `sale_amount` - Sale tokens offered
+/// * `raise_amount` - Raise tokens seeding the opening price
+#[unsafe(no_mangle)]
+pub extern "C" fn fund() {
+    if let Err(err) = execute_fund() {
+        log!("fund failed: {}", err);
+    }
+}
+
+/// Swap against the pool during the sale
+///
+/// # Arguments
+/// * `token_in` - Raise token to buy, sale token to sell
+/// * `amount_in` - Amount paid, at most 30% of that token's pool balance
+/// * `min_amount_out` - Slippage bound
+///
+/// # Returns
+/// Amount received (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn swap() {
+    if let Err(err) = execute_swap().and_then(|out| try_respond(&out)) {
+        log!("swap failed: {}", err);
+    }
+}
+
+/// Move the pool into an AMM pair at the closing price (owner only, after the sale)
+///
+/// Tokens that do not fit the closing price are returned to the owner.
+///
+/// # Arguments
+/// * `recipient` - Receives the LP tokens
+///
+/// # Returns
+/// Liquidity minted by the router (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn migrate() {
+    if let Err(err) = execute_migrate().and_then(|liquidity| try_respond(&liquidity)) {
+        log!("migrate failed: {}", err);
+    }
+}
+
+/// Get the sale configuration
+///
+/// # Returns
+/// `LbpConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get balances, current weights and the sale token price
+///
+/// # Returns
+/// `PoolView`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_pool() {
+    if let Err(err) = execute_get_pool().and_then(|view| try_respond(&view)) {
+        log!("get_pool failed: {}", err);
+    }
+}
+
+/// Quote a swap at the current weights
+///
+/// # Arguments
+/// * `token_in` - Token paid
+/// * `amount_in` - Amount paid
+///
+/// # Returns
+/// Amount out (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn quote() {
+    if let Err(err) = execute_quote().and_then(|out| try_respond(&out)) {
+        log!("quote failed: {}", err);
+    }
+}
+
+/// Get the sale tokens an address holds from the pool, net of sells
+///
+/// # Arguments
+/// * `account` - Trader address
+///
+/// # Returns
+/// Amount (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn purchased_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| Ok(purchased().get(&args.account)?.unwrap_or(0)))
+        .and_then(|amount| try_respond(&amount));
+    if let Err(err) = result {
+        log!("purchased_of failed: {}", err);
+    }
+}
+
+/// Give notice that the sale will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the sale once the notice has run out (owner only)
+///
+/// Funding and swaps stop for good. Buyers already hold what they bought,
+/// and the owner can still migrate the proceeds once the sale window ends.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the sale is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Pause the contract (owner only)
+///
+/// Swaps fail until `unpause`.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the contract is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_LOCKER: &str = "0x0000000000000000000000000000000000000d04";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const SALE: &str = "0x0000000000000000000000000000000000000f01";
+    const USDC: &str = "0x0000000000000000000000000000000000000f02";
+    const ROUTER: &str = "0x0000000000000000000000000000000000000f03";
+    const NOW: u64 = 1_736_000_000;
+    const START: u64 = NOW + 3_600;
+    const END: u64 = START + 3 * 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// 1M sale tokens against 10k USDC, weights 90/10 shifting to 50/50
+    fn setup(max_purchase: Option<u64>) {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        for token in [SALE, USDC] {
+            for method in ["transfer", "transfer_from", "approve"] {
+                mock::register_contract(token, method, |_| Ok(Vec::new()));
+            }
+        }
+        mock::register_contract(ROUTER, "add_liquidity", |_| {
+            Ok(postcard::to_allocvec(&777u64).unwrap())
+        });
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                sale_token: SALE.to_string(),
+                raise_token: USDC.to_string(),
+                start_time: START,
+                end_time: END,
+                start_sale_weight_bps: 9_000,
+                end_sale_weight_bps: 5_000,
+                swap_fee_bps: 100,
+                max_purchase,
+                router: ROUTER.to_string(),
+            }),
+        );
+        call::<()>(
+            ADDR_OWNER,
+            fund,
+            encode(&FundArgs {
+                sale_amount: 1_000_000,
+                raise_amount: 10_000,
+            }),
+        );
+    }
+
+    fn view() -> PoolView {
+        execute_get_pool().unwrap()
+    }
+
+    fn buy(sender: &str, amount_in: u64) -> Option<u64> {
+        call(
+            sender,
+            swap,
+            encode(&SwapArgs {
+                token_in: USDC.to_string(),
+                amount_in,
+                min_amount_out: 0,
+            }),
+        )
+    }
+
+    fn sell(sender: &str, amount_in: u64) -> Option<u64> {
+        call(
+            sender,
+            swap,
+            encode(&SwapArgs {
+                token_in: SALE.to_string(),
+                amount_in,
+                min_amount_out: 0,
+            }),
+        )
+    }
+
+    /// Price as a float for readability: USDC per sale token
+    fn price() -> f64 {
+        view().sale_price as f64 / weighted_math::ONE as f64
+    }
+
+    #[test]
+    fn price_decays_as_weights_shift() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(None);
+        mock::set_block_timestamp(START);
+        assert_eq!(view().sale_weight_bps, 9_000);
+        // (10_000 / 0.1) / (1_000_000 / 0.9) = 0.09
+        assert!((price() - 0.09).abs() < 1e-12);
+
+        mock::set_block_timestamp(START + (END - START) / 2);
+        assert_eq!(
+            (view().sale_weight_bps, view().raise_weight_bps),
+            (7_000, 3_000)
+        );
+        let middle = price();
+        mock::set_block_timestamp(END + 86_400);
+        assert_eq!(view().sale_weight_bps, 5_000);
+        assert!(price() < middle && middle < 0.09);
+        assert!((price() - 0.01).abs() < 1e-12);
+    }
+
+    #[test]
+    fn buyers_receive_the_quoted_amount() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(None);
+        assert_eq!(buy(ADDR_ALICE, 100), None);
+        mock::set_block_timestamp(START);
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&encode(&QuoteArgs {
+            token_in: USDC.to_string(),
+            amount_in: 1_000,
+        }));
+        quote();
+        let quoted: u64 = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        mock::take_contract_calls();
+
+        let bought = buy(ADDR_ALICE, 1_000).unwrap();
+        assert_eq!(bought, quoted);
+        // 990 USDC after fees buys ~10.3k tokens at a 0.09 opening price.
+        assert!((10_000..10_990).contains(&bought), "{}", bought);
+        let pool = load_pool().unwrap();
+        assert_eq!(pool.raise_balance, 11_000);
+        assert_eq!(pool.sale_balance, 1_000_000 - bought);
+        let calls = mock::take_contract_calls();
+        let sent: TokenTransferArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!(
+            (calls[1].address.as_str(), sent.to.as_str(), sent.amount),
+            (SALE, ADDR_ALICE, bought)
+        );
+
+        // A round trip loses the fee twice and never profits.
+        let back = sell(ADDR_ALICE, bought).unwrap();
+        assert!(back < 1_000 && back > 960, "{}", back);
+        let slipped = call::<u64>(
+            ADDR_BOB,
+            swap,
+            encode(&SwapArgs {
+                token_in: USDC.to_string(),
+                amount_in: 1_000,
+                min_amount_out: 1_000_000,
+            }),
+        );
+        assert_eq!(slipped, None);
+    }
+
+    #[test]
+    fn purchase_cap_counts_net_buys() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(Some(15_000));
+        mock::set_block_timestamp(START);
+        let first = buy(ADDR_ALICE, 1_000).unwrap();
+        assert_eq!(buy(ADDR_ALICE, 1_000), None);
+        assert!(buy(ADDR_BOB, 1_000).is_some());
+
+        sell(ADDR_ALICE, first / 2).unwrap();
+        assert_eq!(
+            purchased().get(&ADDR_ALICE.to_string()).unwrap(),
+            Some(first - first / 2)
+        );
+        assert!(buy(ADDR_ALICE, 500).is_some());
+    }
+
+    #[test]
+    fn only_the_owner_funds_before_the_start() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(None);
+        let refund = encode(&FundArgs {
+            sale_amount: 1,
+            raise_amount: 1,
+        });
+        call::<()>(ADDR_OWNER, fund, refund.clone());
+        assert_eq!(load_pool().unwrap().sale_balance, 1_000_000);
+        // Only setup's funding is in the audit log.
+        assert_eq!(admin_log::next_sequence().unwrap(), 1);
+
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_timestamp(NOW);
+        for token in [SALE, USDC, ROUTER] {
+            mock::register_contract(token, "transfer_from", |_| Ok(Vec::new()));
+        }
+        assert!(execute_get_pool().is_err());
+        let args = |start_time: u64, end_time: u64, start_sale_weight_bps: u64| {
+            encode(&InitializeArgs {
+                sale_token: SALE.to_string(),
+                raise_token: USDC.to_string(),
+                start_time,
+                end_time,
+                start_sale_weight_bps,
+                end_sale_weight_bps: 5_000,
+                swap_fee_bps: 100,
+                max_purchase: None,
+                router: ROUTER.to_string(),
+            })
+        };
+        call::<()>(ADDR_OWNER, initialize, args(NOW - 1, END, 9_000));
+        call::<()>(ADDR_OWNER, initialize, args(START, START + 60, 9_000));
+        call::<()>(ADDR_OWNER, initialize, args(START, END, 9_950));
+        assert!(load_config().is_err());
+        call::<()>(ADDR_OWNER, initialize, args(START, END, 9_000));
+
+        call::<()>(ADDR_ALICE, fund, refund.clone());
+        mock::set_block_timestamp(START);
+        call::<()>(ADDR_OWNER, fund, refund);
+        assert!(!load_pool().unwrap().funded);
+        assert_eq!(buy(ADDR_ALICE, 1), None);
+    }
+
+    #[test]
+    fn decommissioning_stops_swaps_but_not_migration() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(None);
+        mock::set_block_timestamp(START);
+        buy(ADDR_ALICE, 3_000).unwrap();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call::<u64>(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        call::<()>(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(buy(ADDR_BOB, 100), None);
+        assert_eq!(sell(ADDR_ALICE, 100), None);
+
+        mock::set_block_timestamp(END);
+        let recipient = encode(&MigrateArgs {
+            recipient: ADDR_LOCKER.to_string(),
+        });
+        assert_eq!(call::<u64>(ADDR_OWNER, migrate, recipient), Some(777));
+    }
+
+    #[test]
+    fn migration_keeps_the_closing_price() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(None);
+        mock::set_block_timestamp(START);
+        buy(ADDR_ALICE, 3_000).unwrap();
+        let recipient = encode(&MigrateArgs {
+            recipient: ADDR_LOCKER.to_string(),
+        });
+        mock::set_block_timestamp(END - 1);
+        assert_eq!(call::<u64>(ADDR_OWNER, migrate, recipient.clone()), None);
+
+        mock::set_block_timestamp(END);
+        assert_eq!(buy(ADDR_BOB, 100), None);
+        assert_eq!(call::<u64>(ADDR_ALICE, migrate, recipient.clone()), None);
+        let pool = load_pool().unwrap();
+        mock::take_contract_calls();
+        assert_eq!(
+            call::<u64>(ADDR_OWNER, migrate, recipient.clone()),
+            Some(777)
+        );
+
+        let calls = mock::take_contract_calls();
+        let added = calls.iter().find(|c| c.method == "add_liquidity").unwrap();
+        let added: AddLiquidityArgs = postcard::from_bytes(&added.args).unwrap();
+        // 50/50 end weights: the pair gets everything at the pool's ratio.
+        assert_eq!(
+            (added.amount_a, added.amount_b, added.to.as_str()),
+            (pool.sale_balance, pool.raise_balance, ADDR_LOCKER)
+        );
+        assert!(calls.iter().all(|c| c.method != "transfer"));
+        assert_eq!(call::<u64>(ADDR_OWNER, migrate, recipient), None);
+        assert_eq!(load_pool().unwrap().sale_balance, 0);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(None);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "lbp-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
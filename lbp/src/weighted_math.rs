@@ -0,0 +1,174 @@
+//! Weighted pool math
+//!
+//! Fixed-point (1e18) implementation of the weighted constant-product
+//! invariant `B_in^w_in * B_out^w_out = k`. Fractional powers are computed
+//! as `exp(w * ln(x))` with series expansions, since `no_std` targets have no
+//! floating-point `powf`.
+
+use silica_contract_sdk::prelude::*;
+
+/// Fixed-point scale
+pub const ONE: i128 = 1_000_000_000_000_000_000;
+/// Denominator for basis-point weights and fees
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// A swap may add at most this share of the input balance (30%)
+pub const MAX_IN_RATIO_BPS: u64 = 3_000;
+
+const LN_2: i128 = 693_147_180_559_945_309;
+/// Rounding margin added to every power so the pool never pays out too much
+const POW_ERROR: i128 = 10_000;
+
+fn overflow() -> ContractError {
+    ContractError::Overflow
+}
+
+/// Natural log of `x` for `0 < x <= ONE`, in fixed point.
+pub fn ln(x: i128) -> ContractResult<i128> {
+    if x <= 0 || x > ONE {
+        return Err(ContractError::InvalidArgument(
+            "ln argument out of range".to_string(),
+        ));
+    }
+    // Scale into [0.5, 1] so the atanh series converges quickly.
+    let mut mantissa = x;
+    let mut halvings = 0i128;
+    while mantissa < ONE / 2 {
+        mantissa *= 2;
+        halvings += 1;
+    }
+    // ln(m) = 2 * atanh(z) with z = (m - 1) / (m + 1), |z| <= 1/3.
+    let z = (mantissa - ONE) * ONE / (mantissa + ONE);
+    let z_squared = z * z / ONE;
+    let mut term = z;
+    let mut sum = 0i128;
+    let mut n = 1i128;
+    while term != 0 {
+        sum += term / n;
+        term = term * z_squared / ONE;
+        n += 2;
+    }
+    Ok(2 * sum - halvings * LN_2)
+}
+
+/// `e^y` for `y <= 0`, in fixed point.
+pub fn exp(y: i128) -> ContractResult<i128> {
+    if y > 0 {
+        return Err(ContractError::InvalidArgument(
+            "exp argument must not be positive".to_string(),
+        ));
+    }
+    // e^y = 2^-k * e^r with r in (-ln 2, 0].
+    let k = -y / LN_2;
+    if k >= 127 {
+        return Ok(0);
+    }
+    let r = y + k * LN_2;
+    let mut term = ONE;
+    let mut sum = ONE;
+    let mut n = 1i128;
+    while term != 0 {
+        term = term * r / ONE / n;
+        sum += term;
+        n += 1;
+    }
+    Ok(sum >> k)
+}
+
+/// `base^exponent` for `0 < base <= ONE` and a non-negative fixed-point exponent.
+pub fn pow(base: i128, exponent: i128) -> ContractResult<i128> {
+    if exponent < 0 {
+        return Err(ContractError::InvalidArgument(
+            "Exponent must not be negative".to_string(),
+        ));
+    }
+    let log = ln(base)?;
+    let product = log.checked_mul(exponent).ok_or_else(overflow)? / ONE;
+    exp(product)
+}
+
+/// Tokens out for `amount_in` tokens in, given balances and weights in bps.
+///
+/// Rounds against the trader. `amount_in` must already have fees removed.
+pub fn out_given_in(
+    balance_in: u64,
+    weight_in: u64,
+    balance_out: u64,
+    weight_out: u64,
+    amount_in: u64,
+) -> ContractResult<u64> {
+    if balance_in == 0 || balance_out == 0 || weight_in == 0 || weight_out == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Pool balances and weights must be positive".to_string(),
+        ));
+    }
+    if (amount_in as u128) * (BPS_DENOMINATOR as u128)
+        > (balance_in as u128) * (MAX_IN_RATIO_BPS as u128)
+    {
+        return Err(ContractError::InvalidArgument(
+            "Swap exceeds 30% of the pool balance".to_string(),
+        ));
+    }
+    let ratio = (balance_in as i128) * ONE / (balance_in as i128 + amount_in as i128);
+    let exponent = (weight_in as i128) * ONE / (weight_out as i128);
+    let power = (pow(ratio, exponent)? + POW_ERROR).min(ONE);
+    let out = (balance_out as i128) * (ONE - power) / ONE;
+    u64::try_from(out).map_err(|_| overflow())
+}
+
+/// Spot price of the out token in units of the in token, scaled by `ONE`.
+pub fn spot_price(
+    balance_in: u64,
+    weight_in: u64,
+    balance_out: u64,
+    weight_out: u64,
+) -> ContractResult<u128> {
+    if balance_out == 0 || weight_in == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Pool balances and weights must be positive".to_string(),
+        ));
+    }
+    let numerator = (balance_in as u128) * (weight_out as u128) * (ONE as u128);
+    Ok(numerator / ((balance_out as u128) * (weight_in as u128)))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn to_f64(x: i128) -> f64 {
+        x as f64 / ONE as f64
+    }
+
+    #[test]
+    fn pow_matches_floating_point() {
+        for base in [0.5, 0.769, 0.9, 0.99, 0.999_999, 1.0] {
+            for exponent in [0.0101, 0.25, 1.0, 1.5, 9.0, 99.0] {
+                let fixed =
+                    pow((base * ONE as f64) as i128, (exponent * ONE as f64) as i128).unwrap();
+                let expected = f64::powf(base, exponent);
+                assert!(
+                    (to_f64(fixed) - expected).abs() < 1e-12,
+                    "{}^{}: {} vs {}",
+                    base,
+                    exponent,
+                    to_f64(fixed),
+                    expected
+                );
+            }
+        }
+        assert_eq!(exp(0).unwrap(), ONE);
+        assert_eq!(ln(ONE).unwrap(), 0);
+        assert!(ln(0).is_err());
+    }
+
+    #[test]
+    fn equal_weights_reduce_to_constant_product() {
+        // x * y = k: 1_000_000 * 1_000_000 / (1_000_000 + 100_000) = 909_090.9
+        let out = out_given_in(1_000_000, 5_000, 1_000_000, 5_000, 100_000).unwrap();
+        assert_eq!(out, 90_909);
+        // At equal balances a 90% weight token costs 9x the 10% one.
+        let price = spot_price(1_000_000, 1_000, 1_000_000, 9_000).unwrap();
+        assert_eq!(price, 9 * ONE as u128);
+        assert!(out_given_in(1_000, 5_000, 1_000, 5_000, 301).is_err());
+    }
+}
@@ -0,0 +1,360 @@
+//! Storage-backed doubly linked list
+//!
+//! [`LinkedList`] keeps a sequence of unique keys in contract storage. Each
+//! key stores links to its neighbours, so inserting next to a known key,
+//! removing any key and popping either end each touch a fixed number of
+//! storage slots, however long the list grows. That fits order books (price
+//! levels kept in order by inserting next to a hint), holder registries and
+//! FIFO queues such as unbonding requests.
+//!
+//! Lists only hold keys. Keep the data for each key in a `Map` next to it.
+//! Pages are cursor-based: pass the last key of one page to get the next,
+//! which stays correct while other entries are inserted or removed.
+//!
+//! ## Embedding
+//! ```toml
+//! linked-list = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use linked_list::LinkedList;
+//!
+//! let mut queue: LinkedList<u64> = LinkedList::new("unbond_queue");
+//! queue.push_back(&request_id)?;
+//! while let Some(id) = queue.front()? { /* ... */ queue.pop_front()?; }
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use silica_contract_sdk::prelude::*;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct Node<K> {
+    prev: Option<K>,
+    next: Option<K>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct Ends<K> {
+    head: Option<K>,
+    tail: Option<K>,
+    len: u64,
+}
+
+/// One page of keys and the cursor for the next page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListPage<K> {
+    pub items: Vec<K>,
+    /// Last key of this page; `None` once the end of the list is reached
+    pub next_cursor: Option<K>,
+}
+
+/// Ordered set of unique keys with O(1) insertion and removal
+pub struct LinkedList<K> {
+    ends_key: String,
+    nodes: Map<K, Node<K>>,
+}
+
+impl<K> LinkedList<K>
+where
+    K: Serialize + for<'de> Deserialize<'de> + Clone + PartialEq,
+{
+    /// Create a list stored under a unique prefix
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            ends_key: format!("{}::ends", prefix),
+            nodes: Map::new(&format!("{}::nodes", prefix)),
+        }
+    }
+
+    fn ends(&self) -> ContractResult<Ends<K>> {
+        Ok(storage().get(&self.ends_key)?.unwrap_or(Ends {
+            head: None,
+            tail: None,
+            len: 0,
+        }))
+    }
+
+    fn node(&self, key: &K) -> ContractResult<Node<K>> {
+        self.nodes.get(key)?.ok_or(ContractError::StorageReadFailed)
+    }
+
+    fn set_prev(&mut self, key: &K, prev: Option<K>) -> ContractResult<()> {
+        let mut node = self.node(key)?;
+        node.prev = prev;
+        self.nodes.set(key, &node)
+    }
+
+    fn set_next(&mut self, key: &K, next: Option<K>) -> ContractResult<()> {
+        let mut node = self.node(key)?;
+        node.next = next;
+        self.nodes.set(key, &node)
+    }
+
+    fn ensure_absent(&self, key: &K) -> ContractResult<()> {
+        if self.contains(key)? {
+            return Err(ContractError::InvalidArgument("Key already in list".into()));
+        }
+        Ok(())
+    }
+
+    fn ensure_present(&self, key: &K) -> ContractResult<()> {
+        if !self.contains(key)? {
+            return Err(ContractError::InvalidArgument("Key not in list".into()));
+        }
+        Ok(())
+    }
+
+    /// Link `key` between `prev` and `next`, which must be adjacent.
+    fn link(&mut self, key: &K, prev: Option<K>, next: Option<K>) -> ContractResult<()> {
+        let mut ends = self.ends()?;
+        match &prev {
+            Some(prev) => self.set_next(prev, Some(key.clone()))?,
+            None => ends.head = Some(key.clone()),
+        }
+        match &next {
+            Some(next) => self.set_prev(next, Some(key.clone()))?,
+            None => ends.tail = Some(key.clone()),
+        }
+        ends.len = safe_math::add(ends.len, 1)?;
+        self.nodes.set(key, &Node { prev, next })?;
+        storage().set(&self.ends_key, &ends)
+    }
+
+    pub fn len(&self) -> ContractResult<u64> {
+        Ok(self.ends()?.len)
+    }
+
+    pub fn is_empty(&self) -> ContractResult<bool> {
+        Ok(self.ends()?.len == 0)
+    }
+
+    pub fn contains(&self, key: &K) -> ContractResult<bool> {
+        self.nodes.contains_key(key)
+    }
+
+    pub fn front(&self) -> ContractResult<Option<K>> {
+        Ok(self.ends()?.head)
+    }
+
+    pub fn back(&self) -> ContractResult<Option<K>> {
+        Ok(self.ends()?.tail)
+    }
+
+    /// Key after `key`; fails if `key` is not in the list
+    pub fn next(&self, key: &K) -> ContractResult<Option<K>> {
+        self.ensure_present(key)?;
+        Ok(self.node(key)?.next)
+    }
+
+    /// Key before `key`; fails if `key` is not in the list
+    pub fn prev(&self, key: &K) -> ContractResult<Option<K>> {
+        self.ensure_present(key)?;
+        Ok(self.node(key)?.prev)
+    }
+
+    pub fn push_front(&mut self, key: &K) -> ContractResult<()> {
+        self.ensure_absent(key)?;
+        let head = self.ends()?.head;
+        self.link(key, None, head)
+    }
+
+    pub fn push_back(&mut self, key: &K) -> ContractResult<()> {
+        self.ensure_absent(key)?;
+        let tail = self.ends()?.tail;
+        self.link(key, tail, None)
+    }
+
+    /// Insert `key` directly after `anchor`
+    pub fn insert_after(&mut self, anchor: &K, key: &K) -> ContractResult<()> {
+        self.ensure_absent(key)?;
+        let next = self.next(anchor)?;
+        self.link(key, Some(anchor.clone()), next)
+    }
+
+    /// Insert `key` directly before `anchor`
+    pub fn insert_before(&mut self, anchor: &K, key: &K) -> ContractResult<()> {
+        self.ensure_absent(key)?;
+        let prev = self.prev(anchor)?;
+        self.link(key, prev, Some(anchor.clone()))
+    }
+
+    /// Unlink `key`; returns false if it was not in the list
+    pub fn remove(&mut self, key: &K) -> ContractResult<bool> {
+        let node = match self.nodes.get(key)? {
+            Some(node) => node,
+            None => return Ok(false),
+        };
+        let mut ends = self.ends()?;
+        match &node.prev {
+            Some(prev) => self.set_next(prev, node.next.clone())?,
+            None => ends.head = node.next.clone(),
+        }
+        match &node.next {
+            Some(next) => self.set_prev(next, node.prev.clone())?,
+            None => ends.tail = node.prev.clone(),
+        }
+        ends.len = safe_math::sub(ends.len, 1)?;
+        self.nodes.remove(key)?;
+        storage().set(&self.ends_key, &ends)?;
+        Ok(true)
+    }
+
+    pub fn pop_front(&mut self) -> ContractResult<Option<K>> {
+        let head = self.ends()?.head;
+        if let Some(key) = &head {
+            self.remove(key)?;
+        }
+        Ok(head)
+    }
+
+    pub fn pop_back(&mut self) -> ContractResult<Option<K>> {
+        let tail = self.ends()?.tail;
+        if let Some(key) = &tail {
+            self.remove(key)?;
+        }
+        Ok(tail)
+    }
+
+    /// Up to `limit` keys after `cursor`, or from the front when `cursor` is `None`
+    pub fn page(&self, cursor: Option<&K>, limit: u64) -> ContractResult<ListPage<K>> {
+        let mut current = match cursor {
+            Some(cursor) => self.next(cursor)?,
+            None => self.front()?,
+        };
+        let mut items = Vec::new();
+        while (items.len() as u64) < limit {
+            let Some(key) = current.take() else {
+                break;
+            };
+            current = self.node(&key)?.next;
+            items.push(key);
+        }
+        let next_cursor = if current.is_some() {
+            items.last().cloned()
+        } else {
+            None
+        };
+        Ok(ListPage { items, next_cursor })
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address("0x0000000000000000000000000000000000000f00");
+        mock::set_sender("0x0000000000000000000000000000000000000a01");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+    }
+
+    fn all(list: &LinkedList<u64>) -> Vec<u64> {
+        list.page(None, u64::MAX).unwrap().items
+    }
+
+    #[test]
+    fn inserts_keep_order_and_links() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let mut list = LinkedList::new("levels");
+        assert!(list.is_empty().unwrap());
+        list.push_back(&20).unwrap();
+        list.push_front(&10).unwrap();
+        list.push_back(&40).unwrap();
+        list.insert_after(&20, &30).unwrap();
+        list.insert_before(&10, &5).unwrap();
+
+        assert_eq!(all(&list), [5, 10, 20, 30, 40]);
+        assert_eq!(list.len().unwrap(), 5);
+        assert_eq!(
+            (list.front().unwrap(), list.back().unwrap()),
+            (Some(5), Some(40))
+        );
+        assert_eq!(list.next(&30).unwrap(), Some(40));
+        assert_eq!(list.prev(&5).unwrap(), None);
+
+        assert!(list.push_back(&20).is_err());
+        assert!(list.insert_after(&99, &50).is_err());
+        assert!(list.next(&99).is_err());
+    }
+
+    #[test]
+    fn removal_relinks_neighbours() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let mut list = LinkedList::new("holders");
+        for key in 1..=5 {
+            list.push_back(&key).unwrap();
+        }
+        assert!(list.remove(&3).unwrap());
+        assert!(!list.remove(&3).unwrap());
+        assert_eq!(list.next(&2).unwrap(), Some(4));
+        assert_eq!(list.prev(&4).unwrap(), Some(2));
+        assert!(list.remove(&1).unwrap());
+        assert!(list.remove(&5).unwrap());
+        assert_eq!(all(&list), [2, 4]);
+        assert_eq!(
+            (list.front().unwrap(), list.back().unwrap()),
+            (Some(2), Some(4))
+        );
+
+        // A removed key can be inserted again.
+        list.push_front(&3).unwrap();
+        assert_eq!(all(&list), [3, 2, 4]);
+    }
+
+    #[test]
+    fn queues_pop_from_either_end() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let mut queue = LinkedList::new("queue");
+        for key in [7, 8, 9] {
+            queue.push_back(&key).unwrap();
+        }
+        assert_eq!(queue.pop_front().unwrap(), Some(7));
+        assert_eq!(queue.pop_back().unwrap(), Some(9));
+        assert_eq!(queue.pop_front().unwrap(), Some(8));
+        assert_eq!(queue.pop_front().unwrap(), None);
+        assert!(queue.is_empty().unwrap());
+        assert_eq!(
+            (queue.front().unwrap(), queue.back().unwrap()),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn pages_resume_from_the_cursor() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let mut list = LinkedList::new("pages");
+        for key in 1..=5 {
+            list.push_back(&key).unwrap();
+        }
+        let first = list.page(None, 2).unwrap();
+        assert_eq!(first.items, [1, 2]);
+        assert_eq!(first.next_cursor, Some(2));
+
+        // Entries removed behind or ahead of the cursor do not break paging.
+        list.remove(&1).unwrap();
+        list.remove(&4).unwrap();
+        let second = list.page(first.next_cursor.as_ref(), 2).unwrap();
+        assert_eq!(second.items, [3, 5]);
+        assert_eq!(second.next_cursor, None);
+
+        assert_eq!(list.page(None, 0).unwrap().items, Vec::<u64>::new());
+    }
+}
@@ -0,0 +1,1242 @@
+//! Liquid Staking
+//!
+//! Stakers deposit the staking asset and receive stToken, a CRC-20 issued
+//! by this contract. The asset is either the native coin or a CRC-20. Each
+//! stToken is a share of the pool, so when the operator adds staking
+//! rewards every share becomes worth more and balances stay the same.
+//! Unstaking burns the shares at the current rate and queues the asset for
+//! release after the unbonding period.
+//!
+//! ## Features
+//! - Native coin or CRC-20 staking asset
+//! - Yield-bearing stToken with a rising exchange rate
+//! - Unbonding queue with per-account request lists
+//! - Full CRC-20 interface on the stToken
+//! - Decommissioning: after the owner's notice runs out, stakes stop and
+//!   unstake requests can be claimed without waiting out the unbonding period
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "staking_config";
+const POOL_KEY: &str = "staking_pool";
+const NEXT_REQUEST_ID_KEY: &str = "next_request_id";
+const BALANCES_PREFIX: &str = "balances";
+const ALLOWANCES_PREFIX: &str = "allowances";
+const REQUESTS_PREFIX: &str = "unbond_requests";
+const ACCOUNT_REQUESTS_PREFIX: &str = "account_requests";
+const ACCOUNT_REQUEST_COUNT_PREFIX: &str = "account_request_count";
+/// Pass as `asset` to stake the native coin
+pub const NATIVE_ASSET: &str = "native";
+const MAX_UNBONDING_PERIOD: u64 = 60 * 86_400;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Staking configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakingConfig {
+    /// Adds rewards and sets the unbonding period
+    pub owner: String,
+    /// `NATIVE_ASSET` or a CRC-20 address
+    pub asset: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Seconds between an unstake request and its release
+    pub unbonding_period: u64,
+}
+
+/// Pool totals; their ratio is the exchange rate
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pool {
+    /// Staked asset backing the stToken, rewards included
+    pub total_pooled: u64,
+    /// stToken supply
+    pub total_shares: u64,
+    /// Asset queued for unbonding and not yet claimed
+    pub unbonding: u64,
+}
+
+/// A queued unstake
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnbondRequest {
+    pub owner: String,
+    pub amount: u64,
+    pub ready_at: u64,
+    pub claimed: bool,
+}
+
+/// One page of unbond request ids for an account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RequestPage {
+    pub items: Vec<u64>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    asset: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    unbonding_period: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SharesArgs {
+    shares: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PeriodArgs {
+    unbonding_period: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestIdArgs {
+    request_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestsOfArgs {
+    account: String,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllowanceArgs {
+    owner: String,
+    spender: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<StakingConfig> {
+    storage()
+        .get::<StakingConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn load_owned_config() -> ContractResult<StakingConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn load_pool() -> ContractResult<Pool> {
+    Ok(storage().get::<Pool>(POOL_KEY)?.unwrap_or_default())
+}
+
+fn save_pool(pool: &Pool) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(POOL_KEY, pool)
+}
+
+fn balances() -> Map<String, u64> {
+    Map::new(BALANCES_PREFIX)
+}
+
+fn allowances() -> Map<(String, String), u64> {
+    Map::new(ALLOWANCES_PREFIX)
+}
+
+fn requests() -> Map<u64, UnbondRequest> {
+    Map::new(REQUESTS_PREFIX)
+}
+
+fn account_requests() -> Map<(String, u64), u64> {
+    Map::new(ACCOUNT_REQUESTS_PREFIX)
+}
+
+fn account_request_counts() -> Map<String, u64> {
+    Map::new(ACCOUNT_REQUEST_COUNT_PREFIX)
+}
+
+fn balance_of_account(account: &str) -> ContractResult<u64> {
+    Ok(balances().get(&account.to_string())?.unwrap_or(0))
+}
+
+fn load_request(request_id: u64) -> ContractResult<UnbondRequest> {
+    requests()
+        .get(&request_id)?
+        .ok_or_else(|| ContractError::InvalidArgument(format!("Request {} not found", request_id)))
+}
+
+/// Shares minted for staking `amount`; 1:1 while the pool is empty.
+pub fn shares_for_amount(pool: &Pool, amount: u64) -> ContractResult<u64> {
+    if pool.total_shares == 0 || pool.total_pooled == 0 {
+        return Ok(amount);
+    }
+    safe_math::mul_div(amount, pool.total_shares, pool.total_pooled, Rounding::Down)
+}
+
+/// Asset released for burning `shares`.
+pub fn amount_for_shares(pool: &Pool, shares: u64) -> ContractResult<u64> {
+    if pool.total_shares == 0 {
+        return Ok(0);
+    }
+    safe_math::mul_div(shares, pool.total_pooled, pool.total_shares, Rounding::Down)
+}
+
+/// Native payments must attach exactly `amount`; token payments attach nothing.
+fn check_attached_value(config: &StakingConfig, amount: u64) -> ContractResult<()> {
+    let expected = if config.asset == NATIVE_ASSET {
+        amount
+    } else {
+        0
+    };
+    if context().value() != expected {
+        return Err(ContractError::InvalidArgument(format!(
+            "Attached value must be {}",
+            expected
+        )));
+    }
+    Ok(())
+}
+
+/// Pull `amount` of a CRC-20 asset from the caller; native value is already here.
+fn receive_asset(config: &StakingConfig, amount: u64) -> ContractResult<()> {
+    if config.asset == NATIVE_ASSET {
+        return Ok(());
+    }
+    let ctx = context();
+    let call = encode_call(&TransferFromArgs {
+        from: ctx.sender().to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(&config.asset, "transfer_from", &call)
+        .map(|_| ())
+}
+
+fn send_asset(config: &StakingConfig, to: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    if config.asset == NATIVE_ASSET {
+        return ctx.transfer_tokens(to, amount);
+    }
+    let call = encode_call(&TransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    ctx.call_contract(&config.asset, "transfer", &call)
+        .map(|_| ())
+}
+
+fn move_shares(from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    validation::validate_address(to)?;
+    let from_balance = balance_of_account(from)?;
+    if from_balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: from_balance,
+        });
+    }
+    if from != to {
+        balances().set(&from.to_string(), &(from_balance - amount))?;
+        let to_balance = balance_of_account(to)?;
+        balances().set(&to.to_string(), &safe_math::add(to_balance, amount)?)?;
+    }
+    event!("Transfer", from: from, to: to, amount: amount);
+    Ok(())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    if args.asset != NATIVE_ASSET {
+        validation::validate_address(&args.asset)?;
+        if !ctx.is_contract(&args.asset) {
+            return Err(ContractError::InvalidArgument(
+                "Asset must be native or a contract".to_string(),
+            ));
+        }
+    }
+    if args.name.is_empty() || args.symbol.is_empty() {
+        return Err(ContractError::InvalidArgument(
+            "Name and symbol are required".to_string(),
+        ));
+    }
+    validation::validate_range(args.unbonding_period, 0, MAX_UNBONDING_PERIOD)?;
+    let config = StakingConfig {
+        owner: ctx.sender().to_string(),
+        asset: args.asset,
+        name: args.name,
+        symbol: args.symbol,
+        decimals: args.decimals,
+        unbonding_period: args.unbonding_period,
+    };
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("StakingInitialized",
+        owner: config.owner,
+        asset: config.asset,
+        symbol: config.symbol,
+        unbonding_period: config.unbonding_period
+    );
+    Ok(())
+}
+
+fn execute_stake() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    let staker = context().sender().to_string();
+    let config = load_config()?;
+    decommission::when_active()?;
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    check_attached_value(&config, args.amount)?;
+    let mut pool = load_pool()?;
+    let shares = shares_for_amount(&pool, args.amount)?;
+    if shares == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Stake too small to mint a share".to_string(),
+        ));
+    }
+    pool.total_pooled = safe_math::add(pool.total_pooled, args.amount)?;
+    pool.total_shares = safe_math::add(pool.total_shares, shares)?;
+    save_pool(&pool)?;
+    let balance = balance_of_account(&staker)?;
+    balances().set(&staker, &safe_math::add(balance, shares)?)?;
+
+    receive_asset(&config, args.amount)?;
+
+    event!("Transfer", from: "", to: staker.clone(), amount: shares);
+    event!("Staked", staker: staker, amount: args.amount, shares: shares);
+    Ok(shares)
+}
+
+fn execute_add_rewards() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    check_attached_value(&config, args.amount)?;
+    let mut pool = load_pool()?;
+    // Rewards with nobody staked would go to the first staker.
+    if pool.total_shares == 0 {
+        return Err(ContractError::InvalidArgument(
+            "No stake to reward".to_string(),
+        ));
+    }
+    let previous = pool.total_pooled;
+    pool.total_pooled = safe_math::add(previous, args.amount)?;
+    save_pool(&pool)?;
+
+    receive_asset(&config, args.amount)?;
+
+    event!("RewardsAdded",
+        amount: args.amount,
+        total_pooled: pool.total_pooled,
+        total_shares: pool.total_shares
+    );
+    admin_log::record("add_rewards", "total_pooled", previous, pool.total_pooled)?;
+    Ok(())
+}
+
+fn execute_request_unstake() -> ContractResult<u64> {
+    let ctx = context();
+    let staker = ctx.sender().to_string();
+    let config = load_config()?;
+    let args: SharesArgs = read_args()?;
+    validation::validate_positive_amount(args.shares)?;
+    let balance = balance_of_account(&staker)?;
+    if balance < args.shares {
+        return Err(ContractError::InsufficientBalance {
+            required: args.shares,
+            available: balance,
+        });
+    }
+    let mut pool = load_pool()?;
+    let amount = amount_for_shares(&pool, args.shares)?;
+    validation::validate_positive_amount(amount)?;
+    pool.total_pooled -= amount;
+    pool.total_shares -= args.shares;
+    pool.unbonding = safe_math::add(pool.unbonding, amount)?;
+    save_pool(&pool)?;
+    balances().set(&staker, &(balance - args.shares))?;
+
+    let mut store = storage();
+    let request_id = store.get::<u64>(NEXT_REQUEST_ID_KEY)?.unwrap_or(0);
+    store.set(NEXT_REQUEST_ID_KEY, &safe_math::add(request_id, 1)?)?;
+    let ready_at = safe_math::add(ctx.block_timestamp(), config.unbonding_period)?;
+    requests().set(
+        &request_id,
+        &UnbondRequest {
+            owner: staker.clone(),
+            amount,
+            ready_at,
+            claimed: false,
+        },
+    )?;
+    let mut counts = account_request_counts();
+    let count = counts.get(&staker)?.unwrap_or(0);
+    account_requests().set(&(staker.clone(), count), &request_id)?;
+    counts.set(&staker, &safe_math::add(count, 1)?)?;
+
+    event!("Transfer", from: staker.clone(), to: "", amount: args.shares);
+    event!("UnstakeRequested",
+        request_id: request_id,
+        staker: staker,
+        shares: args.shares,
+        amount: amount,
+        ready_at: ready_at
+    );
+    Ok(request_id)
+}
+
+fn execute_claim_unstake() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let config = load_config()?;
+    let args: RequestIdArgs = read_args()?;
+    let mut request = load_request(args.request_id)?;
+    if ctx.sender() != request.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    if request.claimed {
+        return Err(ContractError::InvalidArgument(
+            "Request already claimed".to_string(),
+        ));
+    }
+    // A decommissioned pool releases every request at once.
+    if ctx.block_timestamp() < request.ready_at && decommission::decommissioned_at()?.is_none() {
+        return Err(ContractError::InvalidArgument(format!(
+            "Unbonding until {}",
+            request.ready_at
+        )));
+    }
+    request.claimed = true;
+    requests().set(&args.request_id, &request)?;
+    let mut pool = load_pool()?;
+    pool.unbonding -= request.amount;
+    save_pool(&pool)?;
+
+    send_asset(&config, &request.owner, request.amount)?;
+
+    event!("UnstakeClaimed",
+        request_id: args.request_id,
+        staker: request.owner,
+        amount: request.amount
+    );
+    Ok(request.amount)
+}
+
+fn execute_set_unbonding_period() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: PeriodArgs = read_args()?;
+    validation::validate_range(args.unbonding_period, 0, MAX_UNBONDING_PERIOD)?;
+    let previous = core::mem::replace(&mut config.unbonding_period, args.unbonding_period);
+    let mut store = storage();
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("UnbondingPeriodUpdated", unbonding_period: args.unbonding_period);
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "unbonding_period",
+        previous,
+        args.unbonding_period,
+    )?;
+    Ok(())
+}
+
+fn execute_requests_of() -> ContractResult<RequestPage> {
+    let args: RequestsOfArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let total = account_request_counts().get(&args.account)?.unwrap_or(0);
+    let end = args.offset.saturating_add(args.limit).min(total);
+    let index = account_requests();
+    let mut items = Vec::new();
+    for position in args.offset..end {
+        if let Some(id) = index.get(&(args.account.clone(), position))? {
+            items.push(id);
+        }
+    }
+    Ok(RequestPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+fn execute_transfer() -> ContractResult<()> {
+    let args: TransferArgs = read_args()?;
+    move_shares(context().sender(), &args.to, args.amount)
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: ApproveArgs = read_args()?;
+    validation::validate_address(&args.spender)?;
+    allowances().set(&(owner.clone(), args.spender.clone()), &args.amount)?;
+    event!("Approval", owner: owner, spender: args.spender, amount: args.amount);
+    Ok(())
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    let spender = context().sender().to_string();
+    let args: TransferFromArgs = read_args()?;
+    let key = (args.from.clone(), spender);
+    let allowance = allowances().get(&key)?.unwrap_or(0);
+    if allowance < args.amount {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: allowance,
+        });
+    }
+    move_shares(&args.from, &args.to, args.amount)?;
+    allowances().set(&key, &(allowance - args.amount))
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_owned_config()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::unpause()
+}
+
+/// Initialize the pool; the caller becomes the owner
+///
+/// # Arguments
+/// * `asset` - `"native"` or the CRC-20 being staked
+/// * `name` / `symbol` / `decimals` - stToken metadata
+/// * `unbonding_period` - Seconds before unstaked assets are released (max 60 days)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Stake the asset and receive stToken at the current rate
+///
+/// Native stakes attach `amount` as value; CRC-20 stakes approve it.
+///
+/// # Arguments
+/// * `amount` - Asset to stake
+///
+/// # Returns
+/// stToken minted (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn stake() {
+    if let Err(err) = execute_stake().and_then(|shares| try_respond(&shares)) {
+        log!("stake failed: {}", err);
+    }
+}
+
+/// Add staking rewards to the pool, raising the exchange rate (owner only)
+///
+/// # Arguments
+/// * `amount` - Reward, attached or approved like a stake
+#[unsafe(no_mangle)]
+pub extern "C" fn add_rewards() {
+    if let Err(err) = execute_add_rewards() {
+        log!("add_rewards failed: {}", err);
+    }
+}
+
+/// Burn stToken and queue the asset for release
+///
+/// The amount is fixed at the current rate; queued assets earn no rewards.
+///
+/// # Arguments
+/// * `shares` - stToken to burn
+///
+/// # Returns
+/// Request ID (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn request_unstake() {
+    if let Err(err) = execute_request_unstake().and_then(|id| try_respond(&id)) {
+        log!("request_unstake failed: {}", err);
+    }
+}
+
+/// Collect an unbonded request (request owner only)
+///
+/// Once the pool is decommissioned, requests need not wait for `ready_at`.
+///
+/// # Arguments
+/// * `request_id` - Request ID
+///
+/// # Returns
+/// Asset released (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_unstake() {
+    if let Err(err) = execute_claim_unstake().and_then(|amount| try_respond(&amount)) {
+        log!("claim_unstake failed: {}", err);
+    }
+}
+
+/// Change the unbonding period for new requests (owner only)
+///
+/// # Arguments
+/// * `unbonding_period` - Seconds (max 60 days)
+#[unsafe(no_mangle)]
+pub extern "C" fn set_unbonding_period() {
+    if let Err(err) = execute_set_unbonding_period() {
+        log!("set_unbonding_period failed: {}", err);
+    }
+}
+
+/// Get an unbond request
+///
+/// # Arguments
+/// * `request_id` - Request ID
+///
+/// # Returns
+/// `UnbondRequest`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_request() {
+    let result = read_args::<RequestIdArgs>()
+        .and_then(|args| load_request(args.request_id))
+        .and_then(|request| try_respond(&request));
+    if let Err(err) = result {
+        log!("get_request failed: {}", err);
+    }
+}
+
+/// List an account's unbond requests
+///
+/// # Arguments
+/// * `account` - Staker address
+/// * `offset` - First index to return
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `RequestPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn requests_of() {
+    if let Err(err) = execute_requests_of().and_then(|page| try_respond(&page)) {
+        log!("requests_of failed: {}", err);
+    }
+}
+
+/// Get the pool totals
+///
+/// # Returns
+/// `Pool`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_pool() {
+    if let Err(err) = load_pool().and_then(|pool| try_respond(&pool)) {
+        log!("get_pool failed: {}", err);
+    }
+}
+
+/// Quote the stToken a stake would mint
+///
+/// # Arguments
+/// * `amount` - Asset to stake
+///
+/// # Returns
+/// stToken (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_stake() {
+    let result = read_args::<AmountArgs>()
+        .and_then(|args| shares_for_amount(&load_pool()?, args.amount))
+        .and_then(|shares| try_respond(&shares));
+    if let Err(err) = result {
+        log!("preview_stake failed: {}", err);
+    }
+}
+
+/// Quote the asset an unstake would queue
+///
+/// # Arguments
+/// * `shares` - stToken to burn
+///
+/// # Returns
+/// Asset (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_unstake() {
+    let result = read_args::<SharesArgs>()
+        .and_then(|args| amount_for_shares(&load_pool()?, args.shares))
+        .and_then(|amount| try_respond(&amount));
+    if let Err(err) = result {
+        log!("preview_unstake failed: {}", err);
+    }
+}
+
+/// Get the pool configuration
+///
+/// # Returns
+/// `StakingConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Transfer stToken
+///
+/// # Arguments
+/// * `to` - Recipient
+/// * `amount` - stToken to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer() {
+    if let Err(err) = execute_transfer() {
+        log!("Transfer failed: {}", err);
+    }
+}
+
+/// Approve a spender for stToken
+///
+/// # Arguments
+/// * `spender` - Address allowed to spend
+/// * `amount` - Allowance, replacing any previous one
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("Approve failed: {}", err);
+    }
+}
+
+/// Transfer stToken using an allowance
+///
+/// # Arguments
+/// * `from` - stToken owner
+/// * `to` - Recipient
+/// * `amount` - stToken to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("TransferFrom failed: {}", err);
+    }
+}
+
+/// Get the stToken held by an account
+///
+/// # Arguments
+/// * `account` - Holder address
+///
+/// # Returns
+/// Balance (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| balance_of_account(&args.account))
+        .and_then(|balance| try_respond(&balance));
+    if let Err(err) = result {
+        log!("balance_of failed: {}", err);
+    }
+}
+
+/// Get the remaining stToken allowance of a spender
+///
+/// # Arguments
+/// * `owner` - stToken owner
+/// * `spender` - Approved spender
+///
+/// # Returns
+/// Allowance (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn allowance() {
+    let result = read_args::<AllowanceArgs>()
+        .and_then(|args| Ok(allowances().get(&(args.owner, args.spender))?.unwrap_or(0)))
+        .and_then(|amount| try_respond(&amount));
+    if let Err(err) = result {
+        log!("allowance failed: {}", err);
+    }
+}
+
+/// Get the stToken supply
+///
+/// # Returns
+/// Supply (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn total_supply() {
+    if let Err(err) = load_pool().and_then(|pool| try_respond(&pool.total_shares)) {
+        log!("total_supply failed: {}", err);
+    }
+}
+
+/// Give notice that the pool will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the pool once the notice has run out (owner only)
+///
+/// Stakes, rewards and unbonding changes stop for good. Unstake requests
+/// stay open and every request can be claimed at once.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the pool is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Pause the contract (owner only)
+///
+/// Staking fails until `unpause`; unstake requests and claims stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the contract is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const STAKE_TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const NOW: u64 = 1_736_000_000;
+    const UNBONDING: u64 = 7 * 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn setup(asset: &str) {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        for method in ["transfer", "transfer_from"] {
+            mock::register_contract(STAKE_TOKEN, method, |_| Ok(Vec::new()));
+        }
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                asset: asset.to_string(),
+                name: "Staked Silica".to_string(),
+                symbol: "stSIL".to_string(),
+                decimals: 9,
+                unbonding_period: UNBONDING,
+            }),
+        );
+    }
+
+    /// Native calls attach `amount` as value
+    fn send_native(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        amount: u64,
+        value: u64,
+    ) -> Option<u64> {
+        mock::set_value(value);
+        let result = call(sender, entrypoint, encode(&AmountArgs { amount }));
+        mock::set_value(0);
+        result
+    }
+
+    fn unstake(sender: &str, shares: u64) -> Option<u64> {
+        call(sender, request_unstake, encode(&SharesArgs { shares }))
+    }
+
+    fn claim(sender: &str, request_id: u64) -> Option<u64> {
+        call(sender, claim_unstake, encode(&RequestIdArgs { request_id }))
+    }
+
+    #[test]
+    fn native_stakes_mint_shares_one_to_one() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        assert_eq!(send_native(ADDR_ALICE, stake, 1_000, 999), None);
+        assert_eq!(send_native(ADDR_ALICE, stake, 1_000, 1_000), Some(1_000));
+        assert_eq!(balance_of_account(ADDR_ALICE).unwrap(), 1_000);
+        assert_eq!(
+            load_pool().unwrap(),
+            Pool {
+                total_pooled: 1_000,
+                total_shares: 1_000,
+                unbonding: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn rewards_raise_the_exchange_rate() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        assert_eq!(send_native(ADDR_OWNER, add_rewards, 500, 500), None);
+        send_native(ADDR_ALICE, stake, 1_000, 1_000);
+        send_native(ADDR_ALICE, add_rewards, 500, 500);
+        assert_eq!(load_pool().unwrap().total_pooled, 1_000);
+        send_native(ADDR_OWNER, add_rewards, 500, 500);
+        assert_eq!(admin_log::next_sequence().unwrap(), 1);
+
+        // 1500 backs 1000 shares, so 300 buys 200.
+        assert_eq!(send_native(ADDR_BOB, stake, 300, 300), Some(200));
+        assert_eq!(
+            call::<u64>(
+                ADDR_BOB,
+                preview_unstake,
+                encode(&SharesArgs { shares: 1_000 })
+            ),
+            Some(1_500)
+        );
+    }
+
+    #[test]
+    fn unstakes_wait_out_the_unbonding_period() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        send_native(ADDR_ALICE, stake, 1_000, 1_000);
+        send_native(ADDR_OWNER, add_rewards, 1_000, 1_000);
+
+        assert_eq!(unstake(ADDR_ALICE, 1_001), None);
+        assert_eq!(unstake(ADDR_ALICE, 400), Some(0));
+        let request = load_request(0).unwrap();
+        assert_eq!((request.amount, request.ready_at), (800, NOW + UNBONDING));
+        assert_eq!(
+            load_pool().unwrap(),
+            Pool {
+                total_pooled: 1_200,
+                total_shares: 600,
+                unbonding: 800,
+            }
+        );
+        // Rewards after the request go to the remaining shares only.
+        send_native(ADDR_OWNER, add_rewards, 600, 600);
+        assert_eq!(
+            amount_for_shares(&load_pool().unwrap(), 600).unwrap(),
+            1_800
+        );
+
+        mock::set_block_timestamp(NOW + UNBONDING - 1);
+        assert_eq!(claim(ADDR_ALICE, 0), None);
+        mock::set_block_timestamp(NOW + UNBONDING);
+        assert_eq!(claim(ADDR_BOB, 0), None);
+        assert_eq!(claim(ADDR_ALICE, 0), Some(800));
+        assert_eq!(claim(ADDR_ALICE, 0), None);
+        assert_eq!(load_pool().unwrap().unbonding, 0);
+    }
+
+    #[test]
+    fn conversions_overflow_instead_of_truncating() {
+        // A pool whose share price fell far below 1 mints more shares than
+        // a u64 holds.
+        let pool = Pool {
+            total_pooled: 1,
+            total_shares: u64::MAX,
+            unbonding: 0,
+        };
+        assert!(matches!(
+            shares_for_amount(&pool, 2),
+            Err(ContractError::Overflow)
+        ));
+        let pool = Pool {
+            total_pooled: u64::MAX,
+            total_shares: 1,
+            unbonding: 0,
+        };
+        assert!(matches!(
+            amount_for_shares(&pool, 2),
+            Err(ContractError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn decommissioning_stops_stakes_and_releases_requests_early() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        send_native(ADDR_ALICE, stake, 1_000, 1_000);
+        assert_eq!(unstake(ADDR_ALICE, 400), Some(0));
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call::<u64>(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        // The notice is shorter than the unbonding period.
+        assert_eq!(claim(ADDR_ALICE, 0), None);
+        call::<()>(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(send_native(ADDR_BOB, stake, 1_000, 1_000), None);
+        assert_eq!(send_native(ADDR_OWNER, add_rewards, 100, 100), None);
+        assert_eq!(claim(ADDR_ALICE, 0), Some(400));
+        assert_eq!(unstake(ADDR_ALICE, 600), Some(1));
+        assert_eq!(claim(ADDR_ALICE, 1), Some(600));
+        assert_eq!(
+            load_pool().unwrap(),
+            Pool {
+                total_pooled: 0,
+                total_shares: 0,
+                unbonding: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn token_asset_is_pulled_and_paid_through_the_token() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(STAKE_TOKEN);
+        assert_eq!(send_native(ADDR_ALICE, stake, 1_000, 1_000), None);
+        assert_eq!(send_native(ADDR_ALICE, stake, 1_000, 0), Some(1_000));
+        let calls = mock::take_contract_calls();
+        let pulled: TransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(
+            (pulled.from.as_str(), pulled.to.as_str(), pulled.amount),
+            (ADDR_ALICE, CONTRACT, 1_000)
+        );
+
+        unstake(ADDR_ALICE, 1_000);
+        mock::set_block_timestamp(NOW + UNBONDING);
+        claim(ADDR_ALICE, 0);
+        let calls = mock::take_contract_calls();
+        let paid: TransferArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!((calls[0].method.as_str(), paid.amount), ("transfer", 1_000));
+    }
+
+    #[test]
+    fn sttoken_transfers_and_request_pages() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        send_native(ADDR_ALICE, stake, 1_000, 1_000);
+        call::<()>(
+            ADDR_ALICE,
+            transfer,
+            encode(&TransferArgs {
+                to: ADDR_BOB.to_string(),
+                amount: 300,
+            }),
+        );
+        assert_eq!(balance_of_account(ADDR_BOB).unwrap(), 300);
+        for _ in 0..3 {
+            unstake(ADDR_BOB, 100);
+        }
+        assert_eq!(unstake(ADDR_BOB, 1), None);
+
+        let page = |offset, limit| {
+            call::<RequestPage>(
+                ADDR_ALICE,
+                requests_of,
+                encode(&RequestsOfArgs {
+                    account: ADDR_BOB.to_string(),
+                    offset,
+                    limit,
+                }),
+            )
+        };
+        assert_eq!(
+            page(0, 2),
+            Some(RequestPage {
+                items: vec![0, 1],
+                total: 3,
+                next_offset: Some(2),
+            })
+        );
+        assert_eq!(page(0, 51), None);
+        assert_eq!(load_pool().unwrap().total_shares, 700);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(NATIVE_ASSET);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "liquid-staking-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
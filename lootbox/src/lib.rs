@@ -0,0 +1,1125 @@
+//! Verifiable Loot Boxes
+//!
+//! Users spend a CRC-20 key to open a box. The reward is drawn from a
+//! weighted prize table with commit-reveal randomness: the owner commits to
+//! a secret seed before a round opens, every opening adds its
+//! own client seed, and once the owner reveals the seed anyone can settle an
+//! opening. The draw mixes the seed, client seed and opening id with
+//! [`commit_reveal::randomness`], so every result can be recomputed
+//! off-chain from public data.
+//!
+//! ## Features
+//! - Weighted prize tables of CRC-20 amounts and CRC-721 mints
+//! - Each round is bound to the table in force when its seed was committed
+//! - Keys are refunded if the owner does not reveal a round in time
+//! - Per-prize award counts for every table
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use commit_reveal::Commitment;
+use commit_reveal::randomness::Mixer;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "lootbox_config";
+const NEXT_TABLE_KEY: &str = "next_table_version";
+const NEXT_ROUND_KEY: &str = "next_round_id";
+const NEXT_OPENING_KEY: &str = "next_opening_id";
+const TABLES_PREFIX: &str = "prize_tables";
+const ROUNDS_PREFIX: &str = "rounds";
+const OPENINGS_PREFIX: &str = "openings";
+const AWARDED_PREFIX: &str = "awarded";
+const NFT_NEXT_ID_PREFIX: &str = "nft_next_id";
+const ROLL_DOMAIN: &str = "silica-lootbox-v1";
+const SEED_PURPOSE: &str = "lootbox-seed";
+const MAX_PRIZES: usize = 32;
+const MIN_REVEAL_WINDOW: u64 = 60 * 60;
+const MAX_REVEAL_WINDOW: u64 = 30 * 24 * 60 * 60;
+const MAX_CALL_DATA_BYTES: usize = 16_384;
+const MAX_RETURN_BYTES: usize = 16_384;
+
+/// Contract configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LootboxConfig {
+    pub owner: String,
+    /// CRC-20 spent to open a box
+    pub key_token: String,
+    pub keys_per_open: u64,
+    /// Seconds the owner has to reveal a round's seed
+    pub reveal_window: u64,
+}
+
+/// What a prize pays out
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Reward {
+    /// CRC-20 paid from the contract's balance
+    Token { token: String, amount: u64 },
+    /// CRC-721 minted by the contract, which must be the collection's minter
+    Nft {
+        collection: String,
+        metadata_uri: String,
+    },
+}
+
+/// One entry of a prize table
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Prize {
+    pub reward: Reward,
+    /// Relative odds; the chance is `weight / total_weight`
+    pub weight: u32,
+}
+
+/// An immutable prize table
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PrizeTable {
+    pub prizes: Vec<Prize>,
+    pub total_weight: u64,
+}
+
+/// A seed commitment and the openings it covers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub seed_hash: [u8; 32],
+    pub seed: Option<[u8; 32]>,
+    pub table_version: u32,
+    pub reveal_deadline: u64,
+    pub openings: u64,
+}
+
+/// Progress of an opening
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum OpeningStatus {
+    /// Waiting for the round's seed
+    Pending,
+    /// `prize` is the index in the round's table
+    Settled { prize: u32, roll: u64 },
+    /// The seed was never revealed and the keys went back to the opener
+    Reclaimed,
+}
+
+/// A box opened by a user
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Opening {
+    pub opener: String,
+    pub round: u64,
+    pub client_seed: [u8; 32],
+    pub keys: u64,
+    pub status: OpeningStatus,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    key_token: String,
+    keys_per_open: u64,
+    reveal_window: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetTableArgs {
+    prizes: Vec<Prize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CommitSeedArgs {
+    seed_hash: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct RevealSeedArgs {
+    seed: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct OpenBoxArgs {
+    client_seed: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdArgs {
+    id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TableVersionArgs {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AwardedArgs {
+    version: u32,
+    prize: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NftMintArgs {
+    to: String,
+    token_id: u64,
+    metadata_uri: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+/// Commitment the owner submits for a round's `seed`: the
+/// [`commit_reveal`] commitment with the seed as salt, no value, and the
+/// purpose `"lootbox-seed"`.
+pub fn seed_commitment(owner: &str, seed: &[u8; 32]) -> ContractResult<Commitment> {
+    commit_reveal::commitment(SEED_PURPOSE, owner, &(), seed)
+}
+
+/// The number below `total_weight` drawn for an opening: the round's seed
+/// mixed with the opener's client seed and the opening id.
+pub fn roll(
+    seed: &[u8; 32],
+    client_seed: &[u8; 32],
+    opening_id: u64,
+    total_weight: u64,
+) -> ContractResult<u64> {
+    Mixer::new(ROLL_DOMAIN)
+        .seed(seed)
+        .input(&(client_seed, opening_id))?
+        .finish()
+        .draw(0..total_weight)
+}
+
+/// Index of the prize `roll` lands on: `roll % total_weight` walked through
+/// the cumulative weights in table order.
+pub fn pick_prize(table: &PrizeTable, roll: u64) -> usize {
+    let mut target = roll % table.total_weight;
+    for (index, prize) in table.prizes.iter().enumerate() {
+        let weight = prize.weight as u64;
+        if target < weight {
+            return index;
+        }
+        target -= weight;
+    }
+    unreachable!("total_weight is the sum of the prize weights")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn load_config() -> ContractResult<LootboxConfig> {
+    storage()
+        .get::<LootboxConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn load_owned_config() -> ContractResult<LootboxConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn tables() -> Map<u32, PrizeTable> {
+    Map::new(TABLES_PREFIX)
+}
+
+fn rounds() -> Map<u64, Round> {
+    Map::new(ROUNDS_PREFIX)
+}
+
+fn openings() -> Map<u64, Opening> {
+    Map::new(OPENINGS_PREFIX)
+}
+
+/// Times each prize of each table has been awarded
+fn awarded() -> Map<(u32, u32), u64> {
+    Map::new(AWARDED_PREFIX)
+}
+
+/// Next token id to mint per collection
+fn nft_next_ids() -> Map<String, u64> {
+    Map::new(NFT_NEXT_ID_PREFIX)
+}
+
+fn load_table(version: u32) -> ContractResult<PrizeTable> {
+    tables()
+        .get(&version)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown prize table".to_string()))
+}
+
+fn load_round(round_id: u64) -> ContractResult<Round> {
+    rounds()
+        .get(&round_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown round".to_string()))
+}
+
+fn load_opening(opening_id: u64) -> ContractResult<Opening> {
+    openings()
+        .get(&opening_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown opening".to_string()))
+}
+
+/// The latest round, if any has been committed
+fn latest_round() -> ContractResult<Option<(u64, Round)>> {
+    match storage().get::<u64>(NEXT_ROUND_KEY)? {
+        Some(next) if next > 0 => Ok(Some((next - 1, load_round(next - 1)?))),
+        _ => Ok(None),
+    }
+}
+
+fn validate_reveal_window(reveal_window: u64) -> ContractResult<()> {
+    if !(MIN_REVEAL_WINDOW..=MAX_REVEAL_WINDOW).contains(&reveal_window) {
+        return Err(ContractError::InvalidArgument(
+            "Reveal window must be between 1 hour and 30 days".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_prize(config: &LootboxConfig, prize: &Prize) -> ContractResult<()> {
+    let ctx = context();
+    if prize.weight == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Prize weight must be positive".to_string(),
+        ));
+    }
+    let target = match &prize.reward {
+        Reward::Token { token, amount } => {
+            validation::validate_positive_amount(*amount)?;
+            // Spent keys stay in the contract and must never be paid out.
+            if *token == config.key_token {
+                return Err(ContractError::InvalidArgument(
+                    "The key token cannot be a prize".to_string(),
+                ));
+            }
+            token
+        }
+        Reward::Nft {
+            collection,
+            metadata_uri,
+        } => {
+            validation::validate_non_empty(metadata_uri, "metadata_uri")?;
+            collection
+        }
+    };
+    validation::validate_address(target)?;
+    if !ctx.is_contract(target) {
+        return Err(ContractError::InvalidArgument(
+            "Prizes must be contracts".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn deliver(reward: &Reward, to: &str) -> ContractResult<()> {
+    let ctx = context();
+    match reward {
+        Reward::Token { token, amount } => {
+            let call = encode_call(&TokenTransferArgs {
+                to: to.to_string(),
+                amount: *amount,
+            })?;
+            ctx.call_contract(token, "transfer", &call).map(|_| ())
+        }
+        Reward::Nft {
+            collection,
+            metadata_uri,
+        } => {
+            let mut next_ids = nft_next_ids();
+            let token_id = next_ids.get(collection)?.unwrap_or(1);
+            next_ids.set(collection, &safe_math::add(token_id, 1)?)?;
+            let call = encode_call(&NftMintArgs {
+                to: to.to_string(),
+                token_id,
+                metadata_uri: metadata_uri.clone(),
+            })?;
+            ctx.call_contract(collection, "mint", &call).map(|_| ())
+        }
+    }
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.key_token)?;
+    if !ctx.is_contract(&args.key_token) {
+        return Err(ContractError::InvalidArgument(
+            "Key token must be a contract".to_string(),
+        ));
+    }
+    validation::validate_positive_amount(args.keys_per_open)?;
+    validate_reveal_window(args.reveal_window)?;
+
+    storage().set(
+        CONFIG_KEY,
+        &LootboxConfig {
+            owner: ctx.sender().to_string(),
+            key_token: args.key_token.clone(),
+            keys_per_open: args.keys_per_open,
+            reveal_window: args.reveal_window,
+        },
+    )?;
+
+    event!("LootboxInitialized",
+        owner: ctx.sender(),
+        key_token: args.key_token,
+        keys_per_open: args.keys_per_open,
+        reveal_window: args.reveal_window
+    );
+    Ok(())
+}
+
+fn execute_set_table() -> ContractResult<u32> {
+    let config = load_owned_config()?;
+    let args: SetTableArgs = read_args()?;
+    if args.prizes.is_empty() || args.prizes.len() > MAX_PRIZES {
+        return Err(ContractError::InvalidArgument(format!(
+            "A table holds 1-{} prizes",
+            MAX_PRIZES
+        )));
+    }
+    let mut total_weight = 0u64;
+    for prize in &args.prizes {
+        validate_prize(&config, prize)?;
+        total_weight = safe_math::add(total_weight, prize.weight as u64)?;
+    }
+
+    let version = {
+        let mut store = storage();
+        let version = store.get::<u32>(NEXT_TABLE_KEY)?.unwrap_or(0);
+        store.set(NEXT_TABLE_KEY, &(version + 1))?;
+        version
+    };
+    let prize_count = args.prizes.len();
+    tables().set(
+        &version,
+        &PrizeTable {
+            prizes: args.prizes,
+            total_weight,
+        },
+    )?;
+
+    event!("PrizeTableSet",
+        version: version,
+        prizes: prize_count,
+        total_weight: total_weight
+    );
+    admin_log::record(
+        "set_table",
+        &version.to_string(),
+        "",
+        format!("prizes={} total_weight={}", prize_count, total_weight),
+    )?;
+    Ok(version)
+}
+
+fn execute_commit_seed() -> ContractResult<u64> {
+    let config = load_owned_config()?;
+    let args: CommitSeedArgs = read_args()?;
+    let now = context().block_timestamp();
+    let table_version = storage()
+        .get::<u32>(NEXT_TABLE_KEY)?
+        .and_then(|next| next.checked_sub(1))
+        .ok_or_else(|| ContractError::InvalidArgument("No prize table set".to_string()))?;
+    if let Some((_, round)) = latest_round()?
+        && round.seed.is_none()
+        && now < round.reveal_deadline
+    {
+        return Err(ContractError::InvalidArgument(
+            "The current round has not been revealed".to_string(),
+        ));
+    }
+
+    let round_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_ROUND_KEY)?.unwrap_or(0);
+        store.set(NEXT_ROUND_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    let reveal_deadline = safe_math::add(now, config.reveal_window)?;
+    rounds().set(
+        &round_id,
+        &Round {
+            seed_hash: args.seed_hash,
+            seed: None,
+            table_version,
+            reveal_deadline,
+            openings: 0,
+        },
+    )?;
+
+    event!("SeedCommitted",
+        round: round_id,
+        seed_hash: to_hex(&args.seed_hash),
+        table_version: table_version,
+        reveal_deadline: reveal_deadline
+    );
+    admin_log::record(
+        "commit_seed",
+        &round_id.to_string(),
+        "",
+        to_hex(&args.seed_hash),
+    )?;
+    Ok(round_id)
+}
+
+fn execute_open_box() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let ctx = context();
+    let opener = ctx.sender().to_string();
+    let args: OpenBoxArgs = read_args()?;
+    let (round_id, mut round) = latest_round()?
+        .filter(|(_, round)| round.seed.is_none() && ctx.block_timestamp() < round.reveal_deadline)
+        .ok_or_else(|| ContractError::InvalidArgument("No round is open".to_string()))?;
+
+    let call = encode_call(&TokenTransferFromArgs {
+        from: opener.clone(),
+        to: ctx.contract_address().to_string(),
+        amount: config.keys_per_open,
+    })?;
+    ctx.call_contract(&config.key_token, "transfer_from", &call)?;
+
+    let opening_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_OPENING_KEY)?.unwrap_or(0);
+        store.set(NEXT_OPENING_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    openings().set(
+        &opening_id,
+        &Opening {
+            opener: opener.clone(),
+            round: round_id,
+            client_seed: args.client_seed,
+            keys: config.keys_per_open,
+            status: OpeningStatus::Pending,
+        },
+    )?;
+    round.openings = safe_math::add(round.openings, 1)?;
+    rounds().set(&round_id, &round)?;
+
+    event!("BoxOpened",
+        opening_id: opening_id,
+        opener: opener,
+        round: round_id,
+        client_seed: to_hex(&args.client_seed)
+    );
+    Ok(opening_id)
+}
+
+fn execute_reveal_seed() -> ContractResult<()> {
+    let config = load_owned_config()?;
+    let args: RevealSeedArgs = read_args()?;
+    let (round_id, mut round) = latest_round()?
+        .ok_or_else(|| ContractError::InvalidArgument("No round committed".to_string()))?;
+    if round.seed.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Round already revealed".to_string(),
+        ));
+    }
+    if context().block_timestamp() >= round.reveal_deadline {
+        return Err(ContractError::InvalidArgument(
+            "Reveal deadline has passed".to_string(),
+        ));
+    }
+    commit_reveal::verify(
+        &round.seed_hash,
+        SEED_PURPOSE,
+        &config.owner,
+        &(),
+        &args.seed,
+    )?;
+    round.seed = Some(args.seed);
+    rounds().set(&round_id, &round)?;
+
+    event!("SeedRevealed",
+        round: round_id,
+        seed: to_hex(&args.seed),
+        openings: round.openings
+    );
+    admin_log::record(
+        "reveal_seed",
+        &round_id.to_string(),
+        to_hex(&round.seed_hash),
+        to_hex(&args.seed),
+    )?;
+    Ok(())
+}
+
+fn execute_settle() -> ContractResult<u32> {
+    let _guard = ReentrancyGuard::enter()?;
+    let args: IdArgs = read_args()?;
+    let mut opening = load_opening(args.id)?;
+    if opening.status != OpeningStatus::Pending {
+        return Err(ContractError::InvalidArgument(
+            "Opening already closed".to_string(),
+        ));
+    }
+    let round = load_round(opening.round)?;
+    let seed = round
+        .seed
+        .ok_or_else(|| ContractError::InvalidArgument("Round not revealed yet".to_string()))?;
+    let table = load_table(round.table_version)?;
+
+    let roll = roll(&seed, &opening.client_seed, args.id, table.total_weight)?;
+    let prize = pick_prize(&table, roll) as u32;
+    opening.status = OpeningStatus::Settled { prize, roll };
+    openings().set(&args.id, &opening)?;
+    let key = (round.table_version, prize);
+    let mut counts = awarded();
+    let count = counts.get(&key)?.unwrap_or(0);
+    counts.set(&key, &safe_math::add(count, 1)?)?;
+
+    deliver(&table.prizes[prize as usize].reward, &opening.opener)?;
+
+    event!("PrizeAwarded",
+        opening_id: args.id,
+        opener: opening.opener,
+        table_version: round.table_version,
+        prize: prize,
+        roll: roll
+    );
+    Ok(prize)
+}
+
+fn execute_reclaim_keys() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let ctx = context();
+    let args: IdArgs = read_args()?;
+    let mut opening = load_opening(args.id)?;
+    if opening.opener != ctx.sender() {
+        return Err(ContractError::Unauthorized);
+    }
+    if opening.status != OpeningStatus::Pending {
+        return Err(ContractError::InvalidArgument(
+            "Opening already closed".to_string(),
+        ));
+    }
+    let round = load_round(opening.round)?;
+    if round.seed.is_some() || ctx.block_timestamp() < round.reveal_deadline {
+        return Err(ContractError::InvalidArgument(
+            "Keys can only be reclaimed from an unrevealed round after its deadline".to_string(),
+        ));
+    }
+    opening.status = OpeningStatus::Reclaimed;
+    openings().set(&args.id, &opening)?;
+
+    let call = encode_call(&TokenTransferArgs {
+        to: opening.opener.clone(),
+        amount: opening.keys,
+    })?;
+    ctx.call_contract(&config.key_token, "transfer", &call)?;
+
+    event!("KeysReclaimed",
+        opening_id: args.id,
+        opener: opening.opener,
+        keys: opening.keys
+    );
+    Ok(())
+}
+
+/// Initialize the contract; the caller becomes the owner
+///
+/// # Arguments
+/// * `key_token` - CRC-20 spent to open a box
+/// * `keys_per_open` - Keys spent per box
+/// * `reveal_window` - Seconds the owner has to reveal each round (1 hour to 30 days)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("initialize failed: {}", err);
+    }
+}
+
+/// Publish a new prize table (only owner); it applies from the next round
+///
+/// # Arguments
+/// * `prizes` - 1-32 `Prize` entries; CRC-20 prizes must be funded, and the
+///   contract must be allowed to mint CRC-721 prizes
+///
+/// # Returns
+/// Table version
+#[unsafe(no_mangle)]
+pub extern "C" fn set_table() {
+    let result = execute_set_table().and_then(|version| try_respond(&version));
+    if let Err(err) = result {
+        log!("set_table failed: {}", err);
+    }
+}
+
+/// Start a round by committing to a secret seed (only owner)
+///
+/// The round uses the latest prize table. The previous round must be
+/// revealed or past its reveal deadline.
+///
+/// # Arguments
+/// * `seed_hash` - `seed_commitment(owner, seed)` for a secret 32-byte seed
+///
+/// # Returns
+/// Round id
+#[unsafe(no_mangle)]
+pub extern "C" fn commit_seed() {
+    let result = execute_commit_seed().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("commit_seed failed: {}", err);
+    }
+}
+
+/// Spend keys to open a box in the current round (approve the keys first)
+///
+/// # Arguments
+/// * `client_seed` - 32 bytes of the opener's choosing, mixed into the draw
+///
+/// # Returns
+/// Opening id
+#[unsafe(no_mangle)]
+pub extern "C" fn open_box() {
+    let result = execute_open_box().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("open_box failed: {}", err);
+    }
+}
+
+/// Reveal the current round's seed (only owner), closing it to new openings
+///
+/// # Arguments
+/// * `seed` - The seed committed in `commit_seed`
+#[unsafe(no_mangle)]
+pub extern "C" fn reveal_seed() {
+    if let Err(err) = execute_reveal_seed() {
+        log!("reveal_seed failed: {}", err);
+    }
+}
+
+/// Draw and deliver the prize of an opening in a revealed round (anyone)
+///
+/// # Arguments
+/// * `id` - Opening id
+///
+/// # Returns
+/// Index of the prize in the round's table
+#[unsafe(no_mangle)]
+pub extern "C" fn settle() {
+    let result = execute_settle().and_then(|prize| try_respond(&prize));
+    if let Err(err) = result {
+        log!("settle failed: {}", err);
+    }
+}
+
+/// Get the keys back from a round the owner failed to reveal in time
+///
+/// # Arguments
+/// * `id` - Opening id
+#[unsafe(no_mangle)]
+pub extern "C" fn reclaim_keys() {
+    if let Err(err) = execute_reclaim_keys() {
+        log!("reclaim_keys failed: {}", err);
+    }
+}
+
+/// Get the contract configuration
+///
+/// # Returns
+/// `LootboxConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    let result = load_config().and_then(|config| try_respond(&config));
+    if let Err(err) = result {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get a prize table
+///
+/// # Arguments
+/// * `version` - Table version
+///
+/// # Returns
+/// `PrizeTable`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_table() {
+    let result = read_args::<TableVersionArgs>()
+        .and_then(|args| load_table(args.version))
+        .and_then(|table| try_respond(&table));
+    if let Err(err) = result {
+        log!("get_table failed: {}", err);
+    }
+}
+
+/// Get a round
+///
+/// # Arguments
+/// * `id` - Round id
+///
+/// # Returns
+/// `Round`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_round() {
+    let result = read_args::<IdArgs>()
+        .and_then(|args| load_round(args.id))
+        .and_then(|round| try_respond(&round));
+    if let Err(err) = result {
+        log!("get_round failed: {}", err);
+    }
+}
+
+/// Get an opening
+///
+/// # Arguments
+/// * `id` - Opening id
+///
+/// # Returns
+/// `Opening`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_opening() {
+    let result = read_args::<IdArgs>()
+        .and_then(|args| load_opening(args.id))
+        .and_then(|opening| try_respond(&opening));
+    if let Err(err) = result {
+        log!("get_opening failed: {}", err);
+    }
+}
+
+/// How many times a prize has been awarded
+///
+/// # Arguments
+/// * `version` - Table version
+/// * `prize` - Prize index
+///
+/// # Returns
+/// `u64` count
+#[unsafe(no_mangle)]
+pub extern "C" fn get_awarded() {
+    let result = read_args::<AwardedArgs>()
+        .and_then(|args| awarded().get(&(args.version, args.prize)))
+        .and_then(|count| try_respond(&count.unwrap_or(0)));
+    if let Err(err) = result {
+        log!("get_awarded failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000b02";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const KEY_TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const GOLD: &str = "0x0000000000000000000000000000000000000f02";
+    const COLLECTION: &str = "0x0000000000000000000000000000000000000f03";
+    const START: u64 = 1_736_000_000;
+    const WINDOW: u64 = 24 * 60 * 60;
+    const SEED: [u8; 32] = [7; 32];
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn gold(amount: u64, weight: u32) -> Prize {
+        Prize {
+            reward: Reward::Token {
+                token: GOLD.to_string(),
+                amount,
+            },
+            weight,
+        }
+    }
+
+    fn sword(weight: u32) -> Prize {
+        Prize {
+            reward: Reward::Nft {
+                collection: COLLECTION.to_string(),
+                metadata_uri: "ipfs://sword".to_string(),
+            },
+            weight,
+        }
+    }
+
+    fn setup(prizes: Vec<Prize>) {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        for token in [KEY_TOKEN, GOLD] {
+            mock::register_contract(token, "transfer_from", |_| Ok(Vec::new()));
+            mock::register_contract(token, "transfer", |_| Ok(Vec::new()));
+        }
+        mock::register_contract(COLLECTION, "mint", |_| Ok(Vec::new()));
+        call(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                key_token: KEY_TOKEN.to_string(),
+                keys_per_open: 1,
+                reveal_window: WINDOW,
+            }),
+        );
+        call(ADDR_OWNER, set_table, encode(&SetTableArgs { prizes }));
+    }
+
+    fn commit(seed: [u8; 32]) -> u64 {
+        let hash = seed_commitment(ADDR_OWNER, &seed).unwrap();
+        let data = call(
+            ADDR_OWNER,
+            commit_seed,
+            encode(&CommitSeedArgs { seed_hash: hash }),
+        );
+        postcard::from_bytes(&data).unwrap()
+    }
+
+    fn open(client_seed: u8) -> Vec<u8> {
+        call(
+            ADDR_BOB,
+            open_box,
+            encode(&OpenBoxArgs {
+                client_seed: [client_seed; 32],
+            }),
+        )
+    }
+
+    fn reveal(seed: [u8; 32]) {
+        call(ADDR_OWNER, reveal_seed, encode(&RevealSeedArgs { seed }));
+    }
+
+    #[test]
+    fn settled_prizes_match_the_published_draw() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(vec![gold(10, 3), gold(500, 1)]);
+        commit(SEED);
+        let id: u64 = postcard::from_bytes(&open(1)).unwrap();
+        assert!(call(ADDR_BOB, settle, encode(&IdArgs { id })).is_empty());
+        reveal(SEED);
+        mock::take_contract_calls();
+
+        let prize: u32 =
+            postcard::from_bytes(&call(ADDR_BOB, settle, encode(&IdArgs { id }))).unwrap();
+        let table = load_table(0).unwrap();
+        let expected_roll = roll(&SEED, &[1; 32], id, table.total_weight).unwrap();
+        assert_eq!(prize as usize, pick_prize(&table, expected_roll));
+        assert_eq!(
+            load_opening(id).unwrap().status,
+            OpeningStatus::Settled {
+                prize,
+                roll: expected_roll
+            }
+        );
+        let calls = mock::take_contract_calls();
+        let args: TokenTransferArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(args.to, ADDR_BOB);
+        assert_eq!(args.amount, [10, 500][prize as usize]);
+        assert_eq!(awarded().get(&(0, prize)).unwrap(), Some(1));
+        assert!(call(ADDR_BOB, settle, encode(&IdArgs { id })).is_empty());
+    }
+
+    #[test]
+    fn prizes_follow_the_cumulative_weights() {
+        let table = PrizeTable {
+            prizes: vec![gold(1, 3), gold(2, 1), sword(6)],
+            total_weight: 10,
+        };
+        let picks: Vec<usize> = (0..10).map(|roll| pick_prize(&table, roll)).collect();
+        assert_eq!(picks, [0, 0, 0, 1, 2, 2, 2, 2, 2, 2]);
+        assert_eq!(pick_prize(&table, 13), 1);
+    }
+
+    #[test]
+    fn rounds_keep_the_table_they_were_committed_with() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(vec![gold(10, 1)]);
+        // The key token cannot be offered as a prize.
+        let mut key_prize = gold(10, 1);
+        key_prize.reward = Reward::Token {
+            token: KEY_TOKEN.to_string(),
+            amount: 10,
+        };
+        assert!(
+            call(
+                ADDR_OWNER,
+                set_table,
+                encode(&SetTableArgs {
+                    prizes: vec![key_prize]
+                })
+            )
+            .is_empty()
+        );
+
+        let round_id = commit(SEED);
+        let id: u64 = postcard::from_bytes(&open(2)).unwrap();
+        call(
+            ADDR_OWNER,
+            set_table,
+            encode(&SetTableArgs {
+                prizes: vec![sword(1)],
+            }),
+        );
+        // A new round cannot start while this one is unrevealed.
+        assert!(
+            call(
+                ADDR_OWNER,
+                commit_seed,
+                encode(&CommitSeedArgs { seed_hash: [0; 32] })
+            )
+            .is_empty()
+        );
+        reveal([8; 32]);
+        assert_eq!(load_round(round_id).unwrap().seed, None);
+        reveal(SEED);
+        assert!(open(3).is_empty());
+        mock::take_contract_calls();
+
+        call(ADDR_BOB, settle, encode(&IdArgs { id }));
+        assert_eq!(mock::take_contract_calls()[0].method, "transfer");
+        assert_eq!(commit([9; 32]), round_id + 1);
+        assert_eq!(load_round(round_id + 1).unwrap().table_version, 1);
+        // Two tables, two commits and one reveal; refused calls log nothing.
+        assert_eq!(admin_log::next_sequence().unwrap(), 5);
+    }
+
+    #[test]
+    fn keys_are_refunded_when_the_seed_is_never_revealed() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(vec![gold(10, 1)]);
+        commit(SEED);
+        let id: u64 = postcard::from_bytes(&open(4)).unwrap();
+        call(ADDR_BOB, reclaim_keys, encode(&IdArgs { id }));
+        assert_eq!(load_opening(id).unwrap().status, OpeningStatus::Pending);
+
+        mock::set_block_timestamp(START + WINDOW);
+        reveal(SEED);
+        assert!(open(5).is_empty());
+        mock::take_contract_calls();
+        call(ADDR_OWNER, reclaim_keys, encode(&IdArgs { id }));
+        call(ADDR_BOB, reclaim_keys, encode(&IdArgs { id }));
+        assert_eq!(load_opening(id).unwrap().status, OpeningStatus::Reclaimed);
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 1);
+        let args: TokenTransferArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(
+            (calls[0].method.as_str(), args.to.as_str(), args.amount),
+            ("transfer", ADDR_BOB, 1)
+        );
+        assert!(call(ADDR_BOB, settle, encode(&IdArgs { id })).is_empty());
+    }
+
+    #[test]
+    fn nft_prizes_are_minted_with_sequential_ids() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(vec![sword(1)]);
+        commit(SEED);
+        let first: u64 = postcard::from_bytes(&open(6)).unwrap();
+        let second: u64 = postcard::from_bytes(&open(7)).unwrap();
+        reveal(SEED);
+        mock::take_contract_calls();
+
+        call(ADDR_BOB, settle, encode(&IdArgs { id: second }));
+        call(ADDR_BOB, settle, encode(&IdArgs { id: first }));
+        let minted: Vec<u64> = mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "mint")
+            .map(|c| {
+                let args: NftMintArgs = postcard::from_bytes(&c.args).unwrap();
+                assert_eq!(
+                    (args.to.as_str(), args.metadata_uri.as_str()),
+                    (ADDR_BOB, "ipfs://sword")
+                );
+                args.token_id
+            })
+            .collect();
+        assert_eq!(minted, [1, 2]);
+        assert_eq!(awarded().get(&(0, 0)).unwrap(), Some(2));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(vec![gold(10, 1)]);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "lootbox-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
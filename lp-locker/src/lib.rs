@@ -0,0 +1,1023 @@
+//! Liquidity Locker
+//!
+//! Locks LP tokens (or any CRC-20) until a fixed time and issues a CRC-721
+//! receipt for each lock. The locker is itself the receipt collection: the
+//! receipt id is the lock id, and whoever holds the receipt can extend the
+//! lock or withdraw once it expires. Receipts transfer like any CRC-721, so a
+//! locked position can change hands without unlocking the liquidity.
+//!
+//! ## Features
+//! - One receipt per lock carrying token, amount and unlock time
+//! - CRC-721 ownership, approvals and operators on receipts
+//! - Extend-only unlock times
+//! - Per-token lock listing and totals for verifying locked liquidity
+//! - Decommissioning: after the owner's notice runs out, new locks and
+//!   extensions stop; existing locks still unlock on schedule
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::ownable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const NEXT_LOCK_KEY: &str = "next_lock_id";
+const LOCKS_PREFIX: &str = "locks";
+const OWNERS_PREFIX: &str = "receipt_owners";
+const BALANCES_PREFIX: &str = "receipt_balances";
+const APPROVALS_PREFIX: &str = "receipt_approvals";
+const OPERATORS_PREFIX: &str = "receipt_operators";
+const TOKEN_LOCKS_PREFIX: &str = "token_locks";
+const TOKEN_LOCK_COUNT_PREFIX: &str = "token_lock_count";
+const TOKEN_LOCKED_PREFIX: &str = "token_locked";
+const MAX_LOCK_DURATION: u64 = 50 * 365 * 86_400;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// A locked position; its receipt has the same id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Lock {
+    pub token: String,
+    pub amount: u64,
+    pub locked_at: u64,
+    pub unlock_at: u64,
+    pub withdrawn: bool,
+}
+
+/// One page of lock ids for a token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LockPage {
+    pub items: Vec<u64>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LockArgs {
+    token: String,
+    amount: u64,
+    unlock_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExtendArgs {
+    receipt_id: u64,
+    unlock_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReceiptIdArgs {
+    receipt_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferFromArgs {
+    from: String,
+    to: String,
+    receipt_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    to: String,
+    receipt_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetApprovalForAllArgs {
+    operator: String,
+    approved: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IsApprovedForAllArgs {
+    owner: String,
+    operator: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenArgs {
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LocksOfTokenArgs {
+    token: String,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn locks() -> Map<u64, Lock> {
+    Map::new(LOCKS_PREFIX)
+}
+
+fn owners() -> Map<u64, String> {
+    Map::new(OWNERS_PREFIX)
+}
+
+fn balances() -> Map<String, u64> {
+    Map::new(BALANCES_PREFIX)
+}
+
+fn approvals() -> Map<u64, String> {
+    Map::new(APPROVALS_PREFIX)
+}
+
+fn operators() -> Map<(String, String), bool> {
+    Map::new(OPERATORS_PREFIX)
+}
+
+fn token_locks() -> Map<(String, u64), u64> {
+    Map::new(TOKEN_LOCKS_PREFIX)
+}
+
+fn token_lock_counts() -> Map<String, u64> {
+    Map::new(TOKEN_LOCK_COUNT_PREFIX)
+}
+
+/// Amount currently locked per token
+fn token_locked() -> Map<String, u64> {
+    Map::new(TOKEN_LOCKED_PREFIX)
+}
+
+fn load_lock(receipt_id: u64) -> ContractResult<Lock> {
+    locks()
+        .get(&receipt_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown lock".to_string()))
+}
+
+fn owner_of_receipt(receipt_id: u64) -> ContractResult<String> {
+    owners()
+        .get(&receipt_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown receipt".to_string()))
+}
+
+fn is_operator(owner: &str, operator: &str) -> ContractResult<bool> {
+    Ok(operators()
+        .get(&(owner.to_string(), operator.to_string()))?
+        .unwrap_or(false))
+}
+
+/// Owner of the receipt if `spender` may act on it.
+fn authorized_owner(receipt_id: u64, spender: &str) -> ContractResult<String> {
+    let owner = owner_of_receipt(receipt_id)?;
+    if owner == spender
+        || approvals().get(&receipt_id)?.as_deref() == Some(spender)
+        || is_operator(&owner, spender)?
+    {
+        return Ok(owner);
+    }
+    Err(ContractError::Unauthorized)
+}
+
+fn adjust_balance(account: &str, increase: bool) -> ContractResult<()> {
+    let mut balances = balances();
+    let balance = balances.get(&account.to_string())?.unwrap_or(0);
+    let balance = if increase {
+        safe_math::add(balance, 1)?
+    } else {
+        safe_math::sub(balance, 1)?
+    };
+    balances.set(&account.to_string(), &balance)
+}
+
+fn validate_unlock(unlock_at: u64, now: u64) -> ContractResult<()> {
+    if unlock_at <= now || unlock_at - now > MAX_LOCK_DURATION {
+        return Err(ContractError::InvalidArgument(
+            "Unlock time must be in the future and within 50 years".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn execute_lock() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let owner = ctx.sender().to_string();
+    let args: LockArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    validation::validate_positive_amount(args.amount)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    let now = ctx.block_timestamp();
+    validate_unlock(args.unlock_at, now)?;
+    let locked = token_locked().get(&args.token)?.unwrap_or(0);
+    let locked = safe_math::add(locked, args.amount)?;
+
+    let mut store = storage();
+    let receipt_id = store.get::<u64>(NEXT_LOCK_KEY)?.unwrap_or(0);
+    store.set(NEXT_LOCK_KEY, &safe_math::add(receipt_id, 1)?)?;
+    locks().set(
+        &receipt_id,
+        &Lock {
+            token: args.token.clone(),
+            amount: args.amount,
+            locked_at: now,
+            unlock_at: args.unlock_at,
+            withdrawn: false,
+        },
+    )?;
+    owners().set(&receipt_id, &owner)?;
+    adjust_balance(&owner, true)?;
+    let mut counts = token_lock_counts();
+    let count = counts.get(&args.token)?.unwrap_or(0);
+    token_locks().set(&(args.token.clone(), count), &receipt_id)?;
+    counts.set(&args.token, &safe_math::add(count, 1)?)?;
+    token_locked().set(&args.token, &locked)?;
+
+    let call = encode_call(&TokenTransferFromArgs {
+        from: owner.clone(),
+        to: ctx.contract_address().to_string(),
+        amount: args.amount,
+    })?;
+    ctx.call_contract(&args.token, "transfer_from", &call)?;
+
+    event!("Locked",
+        receipt_id: receipt_id,
+        owner: owner.clone(),
+        token: args.token,
+        amount: args.amount,
+        unlock_at: args.unlock_at
+    );
+    event!("Transfer", from: "", to: owner, token_id: receipt_id);
+    Ok(receipt_id)
+}
+
+fn execute_extend() -> ContractResult<()> {
+    decommission::when_active()?;
+    let ctx = context();
+    let args: ExtendArgs = read_args()?;
+    authorized_owner(args.receipt_id, ctx.sender())?;
+    let mut lock = load_lock(args.receipt_id)?;
+    if args.unlock_at <= lock.unlock_at {
+        return Err(ContractError::InvalidArgument(
+            "Locks can only be extended".to_string(),
+        ));
+    }
+    validate_unlock(args.unlock_at, ctx.block_timestamp())?;
+    let previous = lock.unlock_at;
+    lock.unlock_at = args.unlock_at;
+    locks().set(&args.receipt_id, &lock)?;
+
+    event!("LockExtended",
+        receipt_id: args.receipt_id,
+        previous_unlock_at: previous,
+        unlock_at: args.unlock_at
+    );
+    Ok(())
+}
+
+fn execute_withdraw() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: ReceiptIdArgs = read_args()?;
+    let owner = authorized_owner(args.receipt_id, ctx.sender())?;
+    let mut lock = load_lock(args.receipt_id)?;
+    if ctx.block_timestamp() < lock.unlock_at {
+        return Err(ContractError::InvalidArgument(format!(
+            "Locked until {}",
+            lock.unlock_at
+        )));
+    }
+    let locked = token_locked().get(&lock.token)?.unwrap_or(0);
+    let locked = safe_math::sub(locked, lock.amount)?;
+
+    lock.withdrawn = true;
+    locks().set(&args.receipt_id, &lock)?;
+    owners().remove(&args.receipt_id)?;
+    approvals().remove(&args.receipt_id)?;
+    adjust_balance(&owner, false)?;
+    token_locked().set(&lock.token, &locked)?;
+
+    let call = encode_call(&TokenTransferArgs {
+        to: owner.clone(),
+        amount: lock.amount,
+    })?;
+    ctx.call_contract(&lock.token, "transfer", &call)?;
+
+    event!("Withdrawn",
+        receipt_id: args.receipt_id,
+        owner: owner.clone(),
+        token: lock.token,
+        amount: lock.amount
+    );
+    event!("Transfer", from: owner, to: "", token_id: args.receipt_id);
+    Ok(lock.amount)
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    let ctx = context();
+    let args: TransferFromArgs = read_args()?;
+    validation::validate_address(&args.to)?;
+    let owner = authorized_owner(args.receipt_id, ctx.sender())?;
+    if owner != args.from {
+        return Err(ContractError::InvalidArgument(
+            "From is not the receipt owner".to_string(),
+        ));
+    }
+
+    approvals().remove(&args.receipt_id)?;
+    owners().set(&args.receipt_id, &args.to)?;
+    adjust_balance(&args.from, false)?;
+    adjust_balance(&args.to, true)?;
+
+    event!("Transfer",
+        from: args.from,
+        to: args.to,
+        token_id: args.receipt_id
+    );
+    Ok(())
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let ctx = context();
+    let args: ApproveArgs = read_args()?;
+    let owner = owner_of_receipt(args.receipt_id)?;
+    if ctx.sender() != owner && !is_operator(&owner, ctx.sender())? {
+        return Err(ContractError::Unauthorized);
+    }
+    if args.to.is_empty() {
+        approvals().remove(&args.receipt_id)?;
+    } else {
+        validation::validate_address(&args.to)?;
+        approvals().set(&args.receipt_id, &args.to)?;
+    }
+
+    event!("Approval",
+        owner: owner,
+        approved: args.to,
+        token_id: args.receipt_id
+    );
+    Ok(())
+}
+
+fn execute_set_approval_for_all() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: SetApprovalForAllArgs = read_args()?;
+    validation::validate_address(&args.operator)?;
+    if args.operator == owner {
+        return Err(ContractError::InvalidArgument(
+            "Cannot approve yourself".to_string(),
+        ));
+    }
+    operators().set(&(owner.clone(), args.operator.clone()), &args.approved)?;
+
+    event!("ApprovalForAll",
+        owner: owner,
+        operator: args.operator,
+        approved: args.approved
+    );
+    Ok(())
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    ownable::only_owner()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_locks_of_token() -> ContractResult<LockPage> {
+    let args: LocksOfTokenArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let total = token_lock_counts().get(&args.token)?.unwrap_or(0);
+    let end = args.offset.saturating_add(args.limit).min(total);
+    let index = token_locks();
+    let mut items = Vec::new();
+    for position in args.offset..end {
+        if let Some(id) = index.get(&(args.token.clone(), position))? {
+            items.push(id);
+        }
+    }
+    Ok(LockPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+/// Set the owner, who may decommission the locker; call right after
+/// deployment
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = ownable::initialize(context().sender()) {
+        log!("initialize failed: {}", err);
+    }
+}
+
+/// Lock tokens and mint a receipt to the caller
+///
+/// # Arguments
+/// * `token` - CRC-20 (usually an LP token) to lock; approve the locker first
+/// * `amount` - Amount to lock
+/// * `unlock_at` - Timestamp after which the receipt holder can withdraw (max 50 years out)
+///
+/// # Returns
+/// Receipt id (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn lock() {
+    if let Err(err) = execute_lock().and_then(|id| try_respond(&id)) {
+        log!("lock failed: {}", err);
+    }
+}
+
+/// Push a lock's unlock time further out (receipt holder or approved)
+///
+/// # Arguments
+/// * `receipt_id` - Receipt id
+/// * `unlock_at` - New unlock timestamp; must be later than the current one
+#[unsafe(no_mangle)]
+pub extern "C" fn extend() {
+    if let Err(err) = execute_extend() {
+        log!("extend failed: {}", err);
+    }
+}
+
+/// Withdraw an expired lock to the receipt holder and burn the receipt
+///
+/// # Arguments
+/// * `receipt_id` - Receipt id
+///
+/// # Returns
+/// Amount withdrawn (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw() {
+    if let Err(err) = execute_withdraw().and_then(|amount| try_respond(&amount)) {
+        log!("withdraw failed: {}", err);
+    }
+}
+
+/// Transfer a receipt, and with it the locked position
+///
+/// # Arguments
+/// * `from` - Current holder
+/// * `to` - New holder
+/// * `receipt_id` - Receipt id
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("transfer_from failed: {}", err);
+    }
+}
+
+/// Approve an address to manage one receipt; an empty `to` clears it
+///
+/// # Arguments
+/// * `to` - Approved address
+/// * `receipt_id` - Receipt id
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("approve failed: {}", err);
+    }
+}
+
+/// Allow or revoke an operator for all of the caller's receipts
+///
+/// # Arguments
+/// * `operator` - Operator address
+/// * `approved` - Whether the operator is allowed
+#[unsafe(no_mangle)]
+pub extern "C" fn set_approval_for_all() {
+    if let Err(err) = execute_set_approval_for_all() {
+        log!("set_approval_for_all failed: {}", err);
+    }
+}
+
+/// Get the holder of a receipt
+///
+/// # Arguments
+/// * `receipt_id` - Receipt id
+///
+/// # Returns
+/// Holder address (String)
+#[unsafe(no_mangle)]
+pub extern "C" fn owner_of() {
+    let result = read_args::<ReceiptIdArgs>()
+        .and_then(|args| owner_of_receipt(args.receipt_id))
+        .and_then(|owner| try_respond(&owner));
+    if let Err(err) = result {
+        log!("owner_of failed: {}", err);
+    }
+}
+
+/// Get the number of receipts an account holds
+///
+/// # Arguments
+/// * `account` - Account address
+///
+/// # Returns
+/// Receipt count (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| Ok(balances().get(&args.account)?.unwrap_or(0)))
+        .and_then(|balance| try_respond(&balance));
+    if let Err(err) = result {
+        log!("balance_of failed: {}", err);
+    }
+}
+
+/// Get the address approved for a receipt
+///
+/// # Arguments
+/// * `receipt_id` - Receipt id
+///
+/// # Returns
+/// `Option<String>`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_approved() {
+    let result = read_args::<ReceiptIdArgs>()
+        .and_then(|args| approvals().get(&args.receipt_id))
+        .and_then(|approved| try_respond(&approved));
+    if let Err(err) = result {
+        log!("get_approved failed: {}", err);
+    }
+}
+
+/// Check whether an operator manages all of an owner's receipts
+///
+/// # Arguments
+/// * `owner` - Receipt holder
+/// * `operator` - Operator address
+///
+/// # Returns
+/// bool
+#[unsafe(no_mangle)]
+pub extern "C" fn is_approved_for_all() {
+    let result = read_args::<IsApprovedForAllArgs>()
+        .and_then(|args| is_operator(&args.owner, &args.operator))
+        .and_then(|approved| try_respond(&approved));
+    if let Err(err) = result {
+        log!("is_approved_for_all failed: {}", err);
+    }
+}
+
+/// Give notice that the locker will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the locker once the notice has run out (owner only)
+///
+/// New locks and extensions stop for good. Existing locks still unlock
+/// on schedule and their receipts keep moving.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the locker is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Get the lock behind a receipt
+///
+/// # Arguments
+/// * `receipt_id` - Receipt id
+///
+/// # Returns
+/// `Lock`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_lock() {
+    let result = read_args::<ReceiptIdArgs>()
+        .and_then(|args| load_lock(args.receipt_id))
+        .and_then(|lock| try_respond(&lock));
+    if let Err(err) = result {
+        log!("get_lock failed: {}", err);
+    }
+}
+
+/// Get the amount of a token currently locked
+///
+/// # Arguments
+/// * `token` - CRC-20 address
+///
+/// # Returns
+/// Locked amount (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn total_locked() {
+    let result = read_args::<TokenArgs>()
+        .and_then(|args| Ok(token_locked().get(&args.token)?.unwrap_or(0)))
+        .and_then(|amount| try_respond(&amount));
+    if let Err(err) = result {
+        log!("total_locked failed: {}", err);
+    }
+}
+
+/// List every lock ever created for a token, withdrawn or not
+///
+/// # Arguments
+/// * `token` - CRC-20 address
+/// * `offset` - Index of the first lock
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `LockPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn locks_of_token() {
+    if let Err(err) = execute_locks_of_token().and_then(|page| try_respond(&page)) {
+        log!("locks_of_token failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_MARKET: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000e05";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const LP_TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const START: u64 = 1_736_000_000;
+    const DAY: u64 = 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::register_contract(LP_TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(LP_TOKEN, "transfer", |_| Ok(Vec::new()));
+    }
+
+    fn lock_as(sender: &str, amount: u64, unlock_at: u64) -> Option<u64> {
+        let response = call(
+            sender,
+            lock,
+            encode(&LockArgs {
+                token: LP_TOKEN.to_string(),
+                amount,
+                unlock_at,
+            }),
+        );
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn withdraw_as(sender: &str, receipt_id: u64) -> Option<u64> {
+        let response = call(sender, withdraw, encode(&ReceiptIdArgs { receipt_id }));
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn transfer(sender: &str, from: &str, to: &str, receipt_id: u64) {
+        call(
+            sender,
+            transfer_from,
+            encode(&TransferFromArgs {
+                from: from.to_string(),
+                to: to.to_string(),
+                receipt_id,
+            }),
+        );
+    }
+
+    fn holder(receipt_id: u64) -> Option<String> {
+        owners().get(&receipt_id).unwrap()
+    }
+
+    fn balance(account: &str) -> u64 {
+        balances().get(&account.to_string()).unwrap().unwrap_or(0)
+    }
+
+    #[test]
+    fn expired_locks_are_withdrawn_and_receipts_burned() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = lock_as(ADDR_ALICE, 1_000, START + 30 * DAY).unwrap();
+        assert_eq!(holder(id).as_deref(), Some(ADDR_ALICE));
+        assert_eq!(balance(ADDR_ALICE), 1);
+        assert_eq!(
+            token_locked().get(&LP_TOKEN.to_string()).unwrap(),
+            Some(1_000)
+        );
+        let pulled = mock::take_contract_calls();
+        let pull: TokenTransferFromArgs = postcard::from_bytes(&pulled[0].args).unwrap();
+        assert_eq!((pull.from.as_str(), pull.amount), (ADDR_ALICE, 1_000));
+
+        mock::set_block_timestamp(START + 30 * DAY - 1);
+        assert_eq!(withdraw_as(ADDR_ALICE, id), None);
+        mock::set_block_timestamp(START + 30 * DAY);
+        assert_eq!(withdraw_as(ADDR_ALICE, id), Some(1_000));
+        assert_eq!(withdraw_as(ADDR_ALICE, id), None);
+
+        let sent: Vec<TokenTransferArgs> = mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer")
+            .map(|c| postcard::from_bytes(&c.args).unwrap())
+            .collect();
+        assert_eq!(sent.len(), 1);
+        assert_eq!((sent[0].to.as_str(), sent[0].amount), (ADDR_ALICE, 1_000));
+        assert_eq!(holder(id), None);
+        assert_eq!(balance(ADDR_ALICE), 0);
+        assert!(load_lock(id).unwrap().withdrawn);
+        assert_eq!(token_locked().get(&LP_TOKEN.to_string()).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn receipts_carry_the_position_to_new_holders() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = lock_as(ADDR_ALICE, 500, START + DAY).unwrap();
+        transfer(ADDR_BOB, ADDR_ALICE, ADDR_BOB, id);
+        assert_eq!(holder(id).as_deref(), Some(ADDR_ALICE));
+        transfer(ADDR_ALICE, ADDR_ALICE, ADDR_BOB, id);
+        assert_eq!(holder(id).as_deref(), Some(ADDR_BOB));
+        assert_eq!((balance(ADDR_ALICE), balance(ADDR_BOB)), (0, 1));
+
+        mock::set_block_timestamp(START + DAY);
+        assert_eq!(withdraw_as(ADDR_ALICE, id), None);
+        assert_eq!(withdraw_as(ADDR_BOB, id), Some(500));
+        let paid: TokenTransferArgs = mock::take_contract_calls()
+            .iter()
+            .rfind(|c| c.method == "transfer")
+            .map(|c| postcard::from_bytes(&c.args).unwrap())
+            .unwrap();
+        assert_eq!(paid.to, ADDR_BOB);
+    }
+
+    #[test]
+    fn approvals_and_operators_move_receipts() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let first = lock_as(ADDR_ALICE, 100, START + DAY).unwrap();
+        let second = lock_as(ADDR_ALICE, 200, START + DAY).unwrap();
+
+        call(
+            ADDR_ALICE,
+            approve,
+            encode(&ApproveArgs {
+                to: ADDR_MARKET.to_string(),
+                receipt_id: first,
+            }),
+        );
+        transfer(ADDR_MARKET, ADDR_ALICE, ADDR_BOB, first);
+        assert_eq!(holder(first).as_deref(), Some(ADDR_BOB));
+        // The approval is cleared with the transfer.
+        assert_eq!(approvals().get(&first).unwrap(), None);
+        transfer(ADDR_MARKET, ADDR_BOB, ADDR_MARKET, first);
+        assert_eq!(holder(first).as_deref(), Some(ADDR_BOB));
+
+        transfer(ADDR_MARKET, ADDR_ALICE, ADDR_MARKET, second);
+        assert_eq!(holder(second).as_deref(), Some(ADDR_ALICE));
+        call(
+            ADDR_ALICE,
+            set_approval_for_all,
+            encode(&SetApprovalForAllArgs {
+                operator: ADDR_MARKET.to_string(),
+                approved: true,
+            }),
+        );
+        transfer(ADDR_MARKET, ADDR_ALICE, ADDR_MARKET, second);
+        assert_eq!(holder(second).as_deref(), Some(ADDR_MARKET));
+    }
+
+    #[test]
+    fn locks_can_only_be_extended() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = lock_as(ADDR_ALICE, 100, START + DAY).unwrap();
+        let extend_to = |sender: &str, unlock_at: u64| {
+            call(
+                sender,
+                extend,
+                encode(&ExtendArgs {
+                    receipt_id: id,
+                    unlock_at,
+                }),
+            );
+        };
+
+        extend_to(ADDR_BOB, START + 10 * DAY);
+        extend_to(ADDR_ALICE, START + DAY / 2);
+        extend_to(ADDR_ALICE, START + MAX_LOCK_DURATION + 1);
+        assert_eq!(load_lock(id).unwrap().unlock_at, START + DAY);
+        extend_to(ADDR_ALICE, START + 10 * DAY);
+        assert_eq!(load_lock(id).unwrap().unlock_at, START + 10 * DAY);
+
+        mock::set_block_timestamp(START + DAY);
+        assert_eq!(withdraw_as(ADDR_ALICE, id), None);
+    }
+
+    #[test]
+    fn decommissioning_stops_new_locks_but_not_withdrawals() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        call(ADDR_OWNER, initialize, Vec::new());
+        let id = lock_as(ADDR_ALICE, 100, START + DAY).unwrap();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        call(ADDR_ALICE, schedule_decommission, notice.clone());
+        assert_eq!(decommission::status().unwrap().eta, None);
+        let eta: u64 =
+            postcard::from_bytes(&call(ADDR_OWNER, schedule_decommission, notice)).unwrap();
+        mock::set_block_timestamp(eta);
+        call(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(lock_as(ADDR_ALICE, 100, eta + DAY), None);
+        call(
+            ADDR_ALICE,
+            extend,
+            encode(&ExtendArgs {
+                receipt_id: id,
+                unlock_at: eta + DAY,
+            }),
+        );
+        assert_eq!(load_lock(id).unwrap().unlock_at, START + DAY);
+
+        transfer(ADDR_ALICE, ADDR_ALICE, ADDR_BOB, id);
+        assert_eq!(withdraw_as(ADDR_BOB, id), Some(100));
+    }
+
+    #[test]
+    fn locks_are_listed_per_token() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(lock_as(ADDR_ALICE, 0, START + DAY), None);
+        assert_eq!(lock_as(ADDR_ALICE, 100, START), None);
+        let ids: Vec<u64> = (1..=3)
+            .map(|n| lock_as(ADDR_BOB, n * 100, START + DAY).unwrap())
+            .collect();
+
+        let response = call(
+            ADDR_ALICE,
+            locks_of_token,
+            encode(&LocksOfTokenArgs {
+                token: LP_TOKEN.to_string(),
+                offset: 1,
+                limit: 5,
+            }),
+        );
+        let page: LockPage = postcard::from_bytes(&response).unwrap();
+        assert_eq!(
+            page,
+            LockPage {
+                items: ids[1..].to_vec(),
+                total: 3,
+                next_offset: None
+            }
+        );
+        assert_eq!(
+            token_locked().get(&LP_TOKEN.to_string()).unwrap(),
+            Some(600)
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "lp-locker-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,783 @@
+//! Donation Matching Pool
+//!
+//! A sponsor funds a pool that matches donations to registered causes at a
+//! fixed ratio. Matches are capped per donor and by the pool balance; causes
+//! claim their donations plus matches, and the sponsor recovers whatever was
+//! not committed once the campaign ends.
+//!
+//! ## Features
+//! - Native coin or CRC-20 campaigns
+//! - Ratio matching in basis points with per-donor caps
+//! - Pull-based payouts to causes and leftover return to the sponsor
+//! - Decommissioning: after the sponsor's notice runs out, donations stop
+//!   and the sponsor can recover the uncommitted pool early
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::bounded::Name;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "pool_config";
+const CAUSES_PREFIX: &str = "causes";
+const DONOR_MATCHED_PREFIX: &str = "donor_matched";
+const NATIVE_ASSET: &str = "native";
+const BPS_DENOMINATOR: u64 = 10_000;
+const MAX_MATCH_RATIO_BPS: u64 = 10 * BPS_DENOMINATOR;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Campaign parameters and pool accounting
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PoolConfig {
+    pub sponsor: String,
+    pub token: String,
+    /// Match paid per unit donated, in basis points (10_000 = 1:1)
+    pub match_ratio_bps: u64,
+    pub per_donor_cap: u64,
+    pub ends_at: u64,
+    pub funded: u64,
+    pub committed: u64,
+    pub leftover_returned: bool,
+}
+
+/// Running totals for a registered cause
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Cause {
+    pub name: String,
+    pub donated: u64,
+    pub matched: u64,
+    pub claimed: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    token: String,
+    match_ratio_bps: u64,
+    per_donor_cap: u64,
+    ends_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegisterCauseArgs {
+    cause: String,
+    name: Name,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DonateArgs {
+    cause: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CauseArgs {
+    cause: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+/// Match owed for `amount` given the donor's and pool's remaining headroom.
+pub fn match_amount(amount: u64, ratio_bps: u64, donor_headroom: u64, pool_headroom: u64) -> u64 {
+    let raw = amount as u128 * ratio_bps as u128 / BPS_DENOMINATOR as u128;
+    let raw = u64::try_from(raw).unwrap_or(u64::MAX);
+    raw.min(donor_headroom).min(pool_headroom)
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<PoolConfig> {
+    storage()
+        .get::<PoolConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Pool not initialized".to_string()))
+}
+
+fn save_config(config: &PoolConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_sponsor_config() -> ContractResult<PoolConfig> {
+    let config = load_config()?;
+    if context().sender() != config.sponsor {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn causes() -> Map<String, Cause> {
+    Map::new(CAUSES_PREFIX)
+}
+
+fn donor_matched() -> Map<String, u64> {
+    Map::new(DONOR_MATCHED_PREFIX)
+}
+
+fn load_cause(cause: &str) -> ContractResult<Cause> {
+    causes()
+        .get(&cause.to_string())?
+        .ok_or_else(|| ContractError::InvalidArgument("Cause not registered".to_string()))
+}
+
+/// Receive `amount` from `from`: attached value for native pools, otherwise
+/// a CRC-20 `transfer_from` into this contract.
+fn collect(ctx: &Context, token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    if token == NATIVE_ASSET {
+        if ctx.value() != amount {
+            return Err(ContractError::InvalidArgument(
+                "Attached value must equal amount".to_string(),
+            ));
+        }
+        return Ok(());
+    }
+    if ctx.value() != 0 {
+        return Err(ContractError::InvalidArgument(
+            "Token pools must not attach native value".to_string(),
+        ));
+    }
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn pay_out(ctx: &Context, token: &str, recipient: &str, amount: u64) -> ContractResult<()> {
+    if token == NATIVE_ASSET {
+        ctx.transfer_tokens(recipient, amount)
+    } else {
+        let call = encode_call(&TokenTransferArgs {
+            to: recipient.to_string(),
+            amount,
+        })?;
+        ctx.call_contract(token, "transfer", &call).map(|_| ())
+    }
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Pool already initialized".to_string(),
+        ));
+    }
+    if args.token != NATIVE_ASSET {
+        validation::validate_address(&args.token)?;
+    }
+    validation::validate_range(args.match_ratio_bps, 1, MAX_MATCH_RATIO_BPS)?;
+    validation::validate_positive_amount(args.per_donor_cap)?;
+    if args.ends_at <= ctx.block_timestamp() {
+        return Err(ContractError::InvalidArgument(
+            "Campaign end must be in the future".to_string(),
+        ));
+    }
+
+    let sponsor = ctx.sender().to_string();
+    validation::validate_address(&sponsor)?;
+    save_config(&PoolConfig {
+        sponsor: sponsor.clone(),
+        token: args.token.clone(),
+        match_ratio_bps: args.match_ratio_bps,
+        per_donor_cap: args.per_donor_cap,
+        ends_at: args.ends_at,
+        funded: 0,
+        committed: 0,
+        leftover_returned: false,
+    })?;
+
+    event!("PoolInitialized",
+        sponsor: sponsor,
+        token: args.token,
+        match_ratio_bps: args.match_ratio_bps,
+        per_donor_cap: args.per_donor_cap,
+        ends_at: args.ends_at
+    );
+    Ok(())
+}
+
+fn execute_fund() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_sponsor_config()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    if config.leftover_returned {
+        return Err(ContractError::InvalidArgument(
+            "Campaign already settled".to_string(),
+        ));
+    }
+
+    collect(&ctx, &config.token, &config.sponsor, args.amount)?;
+    let previous = config.funded;
+    config.funded = safe_math::add(previous, args.amount)?;
+    save_config(&config)?;
+
+    event!("PoolFunded", amount: args.amount, funded: config.funded);
+    admin_log::record("fund", "funded", previous, config.funded)?;
+    Ok(())
+}
+
+fn execute_register_cause() -> ContractResult<()> {
+    load_sponsor_config()?;
+    decommission::when_active()?;
+    let args: RegisterCauseArgs = read_args()?;
+    validation::validate_address(&args.cause)?;
+    validation::validate_non_empty(&args.name, "name")?;
+
+    let mut registry = causes();
+    if registry.contains_key(&args.cause)? {
+        return Err(ContractError::InvalidArgument(
+            "Cause already registered".to_string(),
+        ));
+    }
+    registry.set(
+        &args.cause,
+        &Cause {
+            name: args.name.to_string(),
+            donated: 0,
+            matched: 0,
+            claimed: 0,
+        },
+    )?;
+
+    event!("CauseRegistered", cause: args.cause, name: args.name);
+    admin_log::record("register_cause", &args.cause, "", &args.name)?;
+    Ok(())
+}
+
+fn execute_donate() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    decommission::when_active()?;
+    let mut config = load_config()?;
+    let ctx = context();
+    let donor = ctx.sender().to_string();
+    let args: DonateArgs = read_args()?;
+    validation::validate_address(&donor)?;
+    validation::validate_positive_amount(args.amount)?;
+    if ctx.block_timestamp() >= config.ends_at {
+        return Err(ContractError::InvalidArgument(
+            "Campaign has ended".to_string(),
+        ));
+    }
+    let mut cause = load_cause(&args.cause)?;
+
+    collect(&ctx, &config.token, &donor, args.amount)?;
+
+    let mut matched_by_donor = donor_matched();
+    let already_matched = matched_by_donor.get(&donor)?.unwrap_or(0);
+    let matched = match_amount(
+        args.amount,
+        config.match_ratio_bps,
+        config.per_donor_cap.saturating_sub(already_matched),
+        config.funded - config.committed,
+    );
+
+    config.committed += matched;
+    save_config(&config)?;
+    matched_by_donor.set(&donor, &(already_matched + matched))?;
+    cause.donated = safe_math::add(cause.donated, args.amount)?;
+    cause.matched += matched;
+    causes().set(&args.cause, &cause)?;
+
+    event!("DonationMatched",
+        donor: donor,
+        cause: args.cause,
+        amount: args.amount,
+        matched: matched
+    );
+    try_respond(&matched)?;
+    Ok(matched)
+}
+
+fn execute_claim() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let ctx = context();
+    let claimant = ctx.sender().to_string();
+    let mut cause = load_cause(&claimant)?;
+
+    let owed = safe_math::sub(safe_math::add(cause.donated, cause.matched)?, cause.claimed)?;
+    if owed == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Nothing to claim".to_string(),
+        ));
+    }
+    cause.claimed += owed;
+    causes().set(&claimant, &cause)?;
+
+    pay_out(&ctx, &config.token, &claimant, owed)?;
+
+    event!("PayoutClaimed", cause: claimant, amount: owed);
+    Ok(owed)
+}
+
+fn execute_return_leftover() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_sponsor_config()?;
+    let ctx = context();
+    // A decommissioned campaign takes no more donations, so it is over.
+    let decommissioned = decommission::decommissioned_at()?.is_some();
+    if ctx.block_timestamp() < config.ends_at && !decommissioned {
+        return Err(ContractError::InvalidArgument(
+            "Campaign still running".to_string(),
+        ));
+    }
+    if config.leftover_returned {
+        return Err(ContractError::InvalidArgument(
+            "Leftover already returned".to_string(),
+        ));
+    }
+
+    let leftover = config.funded - config.committed;
+    config.leftover_returned = true;
+    save_config(&config)?;
+
+    if leftover > 0 {
+        pay_out(&ctx, &config.token, &config.sponsor, leftover)?;
+    }
+
+    event!("LeftoverReturned", sponsor: config.sponsor, amount: leftover);
+    admin_log::record("return_leftover", &config.sponsor, "", leftover)?;
+    Ok(leftover)
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_sponsor_config()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_sponsor_config()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_sponsor_config()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_get_config() -> ContractResult<()> {
+    try_respond(&load_config()?)
+}
+
+fn execute_get_cause() -> ContractResult<()> {
+    let args: CauseArgs = read_args()?;
+    try_respond(&load_cause(&args.cause)?)
+}
+
+/// Initialize the matching campaign; the caller becomes the sponsor
+///
+/// # Arguments
+/// * `token` - CRC-20 contract address, or `"native"`
+/// * `match_ratio_bps` - Match per unit donated (10_000 = 1:1, max 10:1)
+/// * `per_donor_cap` - Maximum total match a single donor can unlock
+/// * `ends_at` - Timestamp after which donations close
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Add funds to the matching pool (sponsor only)
+///
+/// # Arguments
+/// * `amount` - Attached native value or CRC-20 amount pulled from the sponsor
+#[unsafe(no_mangle)]
+pub extern "C" fn fund() {
+    if let Err(err) = execute_fund() {
+        log!("fund failed: {}", err);
+    }
+}
+
+/// Register a cause eligible for matched donations (sponsor only)
+///
+/// # Arguments
+/// * `cause` - Payout address of the cause
+/// * `name` - Display name, at most 64 bytes
+#[unsafe(no_mangle)]
+pub extern "C" fn register_cause() {
+    if let Err(err) = execute_register_cause() {
+        log!("register_cause failed: {}", err);
+    }
+}
+
+/// Donate to a registered cause and unlock a match from the pool
+///
+/// # Arguments
+/// * `cause` - Registered cause address
+/// * `amount` - Donation amount
+///
+/// # Returns
+/// Matched amount committed from the pool
+#[unsafe(no_mangle)]
+pub extern "C" fn donate() {
+    if let Err(err) = execute_donate() {
+        log!("donate failed: {}", err);
+    }
+}
+
+/// Pay the caller's unclaimed donations and matches (causes only)
+#[unsafe(no_mangle)]
+pub extern "C" fn claim() {
+    if let Err(err) = execute_claim() {
+        log!("claim failed: {}", err);
+    }
+}
+
+/// Return uncommitted pool funds after the campaign ends (sponsor only)
+#[unsafe(no_mangle)]
+pub extern "C" fn return_leftover() {
+    if let Err(err) = execute_return_leftover() {
+        log!("return_leftover failed: {}", err);
+    }
+}
+
+/// Give notice that the campaign will be decommissioned (sponsor only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (sponsor only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the campaign once the notice has run out (sponsor only)
+///
+/// Funding, new causes and donations stop for good. Causes can still
+/// claim, and the sponsor can take back the uncommitted pool right away.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the campaign is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Get the campaign configuration and pool totals
+///
+/// # Returns
+/// `PoolConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = execute_get_config() {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get a cause's running totals
+///
+/// # Arguments
+/// * `cause` - Cause address
+///
+/// # Returns
+/// `Cause`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_cause() {
+    if let Err(err) = execute_get_cause() {
+        log!("get_cause failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_SPONSOR: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_CAUSE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000d04";
+    const START: u64 = 1_736_000_000;
+    const END: u64 = START + 7 * 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    /// 2:1 match, 300 per donor, 1_000 in the pool.
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address("0x0000000000000000000000000000000000000f00");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::set_sender(ADDR_SPONSOR);
+        mock::set_call_data(&encode(&InitializeArgs {
+            token: NATIVE_ASSET.to_string(),
+            match_ratio_bps: 20_000,
+            per_donor_cap: 300,
+            ends_at: END,
+        }));
+        initialize();
+
+        mock::set_value(1_000);
+        mock::set_call_data(&encode(&AmountArgs { amount: 1_000 }));
+        fund();
+        mock::set_value(0);
+
+        mock::set_call_data(&encode(&RegisterCauseArgs {
+            cause: ADDR_CAUSE.to_string(),
+            name: Name::new("Open Source Fund").unwrap(),
+        }));
+        register_cause();
+    }
+
+    fn donate_as(donor: &str, cause: &str, amount: u64) -> Option<u64> {
+        mock::set_sender(donor);
+        mock::set_value(amount);
+        mock::set_call_data(&encode(&DonateArgs {
+            cause: cause.to_string(),
+            amount,
+        }));
+        donate();
+        mock::set_value(0);
+        let data = mock::take_return_data();
+        (!data.is_empty()).then(|| postcard::from_bytes(&data).expect("matched amount"))
+    }
+
+    #[test]
+    fn match_amount_applies_ratio_and_caps() {
+        assert_eq!(match_amount(100, 15_000, u64::MAX, u64::MAX), 150);
+        assert_eq!(match_amount(100, 15_000, 40, u64::MAX), 40);
+        assert_eq!(match_amount(100, 15_000, u64::MAX, 10), 10);
+        assert_eq!(
+            match_amount(u64::MAX, 100_000, u64::MAX, u64::MAX),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn donations_are_matched_up_to_donor_cap() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        assert_eq!(donate_as(ADDR_ALICE, ADDR_CAUSE, 100), Some(200));
+        assert_eq!(donate_as(ADDR_ALICE, ADDR_CAUSE, 100), Some(100));
+        assert_eq!(donate_as(ADDR_ALICE, ADDR_CAUSE, 100), Some(0));
+
+        let cause = load_cause(ADDR_CAUSE).unwrap();
+        assert_eq!(cause.donated, 300);
+        assert_eq!(cause.matched, 300);
+        assert_eq!(load_config().unwrap().committed, 300);
+    }
+
+    #[test]
+    fn pool_balance_caps_total_matching() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        for donor in 1..=3u8 {
+            let address = format!("0x00000000000000000000000000000000000010{:02x}", donor);
+            donate_as(&address, ADDR_CAUSE, 150);
+        }
+        assert_eq!(donate_as(ADDR_BOB, ADDR_CAUSE, 150), Some(100));
+        assert_eq!(donate_as(ADDR_ALICE, ADDR_CAUSE, 50), Some(0));
+        assert_eq!(load_config().unwrap().committed, 1_000);
+    }
+
+    #[test]
+    fn unregistered_causes_and_late_donations_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        assert_eq!(donate_as(ADDR_ALICE, ADDR_BOB, 100), None);
+        mock::set_block_timestamp(END);
+        assert_eq!(donate_as(ADDR_ALICE, ADDR_CAUSE, 100), None);
+        assert_eq!(load_config().unwrap().committed, 0);
+    }
+
+    #[test]
+    fn cause_claims_donations_and_matches() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        donate_as(ADDR_ALICE, ADDR_CAUSE, 100);
+
+        mock::set_sender(ADDR_CAUSE);
+        claim();
+        assert_eq!(load_cause(ADDR_CAUSE).unwrap().claimed, 300);
+
+        donate_as(ADDR_BOB, ADDR_CAUSE, 10);
+        mock::set_sender(ADDR_CAUSE);
+        claim();
+        assert_eq!(load_cause(ADDR_CAUSE).unwrap().claimed, 330);
+    }
+
+    #[test]
+    fn decommissioning_stops_donations_but_not_claims() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        donate_as(ADDR_ALICE, ADDR_CAUSE, 100);
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&notice);
+        schedule_decommission();
+        assert_eq!(decommission::status().unwrap().eta, None);
+        mock::set_sender(ADDR_SPONSOR);
+        mock::set_call_data(&notice);
+        schedule_decommission();
+        let eta: u64 = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        mock::set_block_timestamp(eta);
+        decommission();
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(donate_as(ADDR_BOB, ADDR_CAUSE, 100), None);
+        mock::set_sender(ADDR_CAUSE);
+        claim();
+        assert_eq!(load_cause(ADDR_CAUSE).unwrap().claimed, 300);
+
+        // The campaign is over, so the sponsor need not wait for `ends_at`.
+        mock::set_sender(ADDR_SPONSOR);
+        return_leftover();
+        assert!(load_config().unwrap().leftover_returned);
+    }
+
+    #[test]
+    fn sponsor_recovers_uncommitted_funds_once_after_end() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        donate_as(ADDR_ALICE, ADDR_CAUSE, 100);
+
+        mock::set_sender(ADDR_SPONSOR);
+        return_leftover();
+        assert!(!load_config().unwrap().leftover_returned);
+
+        mock::set_block_timestamp(END);
+        mock::set_sender(ADDR_ALICE);
+        return_leftover();
+        assert!(!load_config().unwrap().leftover_returned);
+
+        mock::set_sender(ADDR_SPONSOR);
+        return_leftover();
+        let events = mock::take_events();
+        let topics: Vec<&str> = events.iter().map(|event| event.topic.as_str()).collect();
+        assert!(topics.ends_with(&["LeftoverReturned", "AdminAction"]));
+        assert!(load_config().unwrap().leftover_returned);
+        // Setup's funding and cause, then the one accepted return.
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "matching-pool-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,332 @@
+//! Merkle proof verification
+//!
+//! Verifies membership proofs against a 32-byte root, for allowlist mints,
+//! airdrop claims and anything else that commits to a set off-chain.
+//!
+//! Hashing follows the sorted-pair scheme used by OpenZeppelin's
+//! `MerkleProof`, with BLAKE3 and domain separation:
+//!
+//! ```text
+//! leaf = blake3(0x00 || data)
+//! node = blake3(0x01 || min(a, b) || max(a, b))
+//! ```
+//!
+//! Sorting the pair means proofs carry no left/right flags. The prefixes
+//! keep an inner node from ever being accepted as a leaf.
+//!
+//! [`MerkleTree`] builds trees and proofs in the same layout as
+//! OpenZeppelin's `merkle-tree` library, for tests and off-chain tooling.
+//!
+//! ## Embedding
+//! ```toml
+//! merkle = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! let leaf = merkle::hash_leaf(&postcard::to_allocvec(&(ctx.sender(), amount))?);
+//! if !merkle::verify(&args.proof, &config.root, &leaf) {
+//!     return Err(ContractError::Custom("Invalid proof".into()));
+//! }
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use silica_contract_sdk::crypto::hash_blake3;
+
+/// A leaf, node or root hash
+pub type Hash = [u8; 32];
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Hash raw leaf data.
+pub fn hash_leaf(data: &[u8]) -> Hash {
+    let mut preimage = Vec::with_capacity(1 + data.len());
+    preimage.push(LEAF_PREFIX);
+    preimage.extend_from_slice(data);
+    hash_blake3(&preimage)
+}
+
+/// Hash two nodes in sorted order.
+pub fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let mut preimage = [0u8; 65];
+    preimage[0] = NODE_PREFIX;
+    preimage[1..33].copy_from_slice(lo);
+    preimage[33..].copy_from_slice(hi);
+    hash_blake3(&preimage)
+}
+
+/// Root reached by hashing `leaf` up through `proof`.
+pub fn process_proof(proof: &[Hash], leaf: &Hash) -> Hash {
+    proof
+        .iter()
+        .fold(*leaf, |node, sibling| hash_pair(&node, sibling))
+}
+
+/// Whether `proof` shows that `leaf` is in the tree with `root`.
+pub fn verify(proof: &[Hash], root: &Hash, leaf: &Hash) -> bool {
+    process_proof(proof, leaf) == *root
+}
+
+/// Root reached by a multi-proof, or `None` if the proof is malformed.
+///
+/// `leaves` must be in the order [`MerkleTree::multi_proof`] returns them.
+/// Each flag says whether the next hash combines two known nodes (`true`)
+/// or a known node with the next element of `proof` (`false`).
+pub fn process_multi_proof(proof: &[Hash], proof_flags: &[bool], leaves: &[Hash]) -> Option<Hash> {
+    let total = proof_flags.len();
+    if leaves.len() + proof.len() != total + 1 {
+        return None;
+    }
+
+    let mut hashes: Vec<Hash> = Vec::with_capacity(total);
+    let (mut leaf_pos, mut hash_pos, mut proof_pos) = (0, 0, 0);
+    let mut next_known = |hashes: &Vec<Hash>| -> Option<Hash> {
+        if leaf_pos < leaves.len() {
+            leaf_pos += 1;
+            Some(leaves[leaf_pos - 1])
+        } else {
+            hash_pos += 1;
+            hashes.get(hash_pos - 1).copied()
+        }
+    };
+
+    for &flag in proof_flags {
+        let a = next_known(&hashes)?;
+        let b = if flag {
+            next_known(&hashes)?
+        } else {
+            proof_pos += 1;
+            *proof.get(proof_pos - 1)?
+        };
+        hashes.push(hash_pair(&a, &b));
+    }
+
+    if total > 0 {
+        if proof_pos != proof.len() {
+            return None;
+        }
+        hashes.last().copied()
+    } else if let Some(leaf) = leaves.first() {
+        Some(*leaf)
+    } else {
+        proof.first().copied()
+    }
+}
+
+/// Whether a multi-proof shows that every one of `leaves` is in the tree with `root`.
+pub fn verify_multi(proof: &[Hash], proof_flags: &[bool], root: &Hash, leaves: &[Hash]) -> bool {
+    process_multi_proof(proof, proof_flags, leaves) == Some(*root)
+}
+
+/// Proof for several leaves at once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiProof {
+    /// Leaf hashes in the order the verifier consumes them
+    pub leaves: Vec<Hash>,
+    pub proof: Vec<Hash>,
+    pub proof_flags: Vec<bool>,
+}
+
+/// A complete binary tree over leaf hashes
+///
+/// Nodes are stored as an array with the root at index 0 and the leaves at
+/// the end in reverse order, matching OpenZeppelin's `merkle-tree` library.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    nodes: Vec<Hash>,
+    leaf_count: usize,
+}
+
+impl MerkleTree {
+    /// Build a tree over already-hashed leaves; `None` if there are none.
+    pub fn new(leaves: &[Hash]) -> Option<Self> {
+        if leaves.is_empty() {
+            return None;
+        }
+        let size = 2 * leaves.len() - 1;
+        let mut nodes = alloc::vec![[0u8; 32]; size];
+        for (i, leaf) in leaves.iter().enumerate() {
+            nodes[size - 1 - i] = *leaf;
+        }
+        for i in (0..size - leaves.len()).rev() {
+            nodes[i] = hash_pair(&nodes[2 * i + 1], &nodes[2 * i + 2]);
+        }
+        Some(Self {
+            nodes,
+            leaf_count: leaves.len(),
+        })
+    }
+
+    /// Build a tree over raw leaf data, hashing each entry with [`hash_leaf`].
+    pub fn from_data<D: AsRef<[u8]>>(data: &[D]) -> Option<Self> {
+        let leaves: Vec<Hash> = data.iter().map(|d| hash_leaf(d.as_ref())).collect();
+        Self::new(&leaves)
+    }
+
+    pub fn root(&self) -> Hash {
+        self.nodes[0]
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Leaf hash at `index`, in insertion order.
+    pub fn leaf(&self, index: usize) -> Option<Hash> {
+        self.node_index(index).map(|i| self.nodes[i])
+    }
+
+    /// Proof for the leaf at `index`.
+    pub fn proof(&self, index: usize) -> Option<Vec<Hash>> {
+        let mut node = self.node_index(index)?;
+        let mut proof = Vec::new();
+        while node > 0 {
+            proof.push(self.nodes[sibling(node)]);
+            node = (node - 1) / 2;
+        }
+        Some(proof)
+    }
+
+    /// Proof for several leaves; `None` on an out-of-range or repeated index.
+    pub fn multi_proof(&self, indices: &[usize]) -> Option<MultiProof> {
+        let mut stack = indices
+            .iter()
+            .map(|&index| self.node_index(index))
+            .collect::<Option<Vec<usize>>>()?;
+        stack.sort_unstable_by(|a, b| b.cmp(a));
+        if stack.windows(2).any(|pair| pair[0] == pair[1]) {
+            return None;
+        }
+        let leaves = stack.iter().map(|&i| self.nodes[i]).collect();
+
+        let mut proof = Vec::new();
+        let mut proof_flags = Vec::new();
+        let mut queue: alloc::collections::VecDeque<usize> = stack.into();
+        while let Some(&node) = queue.front() {
+            if node == 0 {
+                break;
+            }
+            queue.pop_front();
+            let sib = sibling(node);
+            if queue.front() == Some(&sib) {
+                proof_flags.push(true);
+                queue.pop_front();
+            } else {
+                proof_flags.push(false);
+                proof.push(self.nodes[sib]);
+            }
+            queue.push_back((node - 1) / 2);
+        }
+        if indices.is_empty() {
+            proof.push(self.root());
+        }
+
+        Some(MultiProof {
+            leaves,
+            proof,
+            proof_flags,
+        })
+    }
+
+    fn node_index(&self, index: usize) -> Option<usize> {
+        (index < self.leaf_count).then(|| self.nodes.len() - 1 - index)
+    }
+}
+
+fn sibling(node: usize) -> usize {
+    if node % 2 == 1 { node + 1 } else { node - 1 }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn tree(count: usize) -> MerkleTree {
+        let data: Vec<Vec<u8>> = (0..count as u32)
+            .map(|i| i.to_le_bytes().to_vec())
+            .collect();
+        MerkleTree::from_data(&data).unwrap()
+    }
+
+    #[test]
+    fn every_leaf_proves_against_the_root() {
+        for count in 1..=9 {
+            let tree = tree(count);
+            for index in 0..count {
+                let proof = tree.proof(index).unwrap();
+                assert!(verify(&proof, &tree.root(), &tree.leaf(index).unwrap()));
+            }
+            assert_eq!(tree.proof(count), None);
+        }
+    }
+
+    #[test]
+    fn tampered_proofs_and_foreign_leaves_fail() {
+        let tree = tree(6);
+        let leaf = tree.leaf(2).unwrap();
+        let mut proof = tree.proof(2).unwrap();
+        assert!(!verify(&proof, &tree.root(), &hash_leaf(b"intruder")));
+        proof[0][0] ^= 1;
+        assert!(!verify(&proof, &tree.root(), &leaf));
+
+        // Presenting two children as leaf data does not reproduce their parent.
+        let (left, right) = (tree.leaf(0).unwrap(), tree.leaf(1).unwrap());
+        let (lo, hi) = if left <= right {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        assert_ne!(hash_leaf(&[lo, hi].concat()), hash_pair(&left, &right));
+    }
+
+    #[test]
+    fn multi_proofs_cover_any_subset() {
+        for count in 1..=7usize {
+            let tree = tree(count);
+            for mask in 0u32..(1 << count) {
+                let indices: Vec<usize> = (0..count).filter(|i| mask & (1 << i) != 0).collect();
+                let multi = tree.multi_proof(&indices).unwrap();
+                assert_eq!(multi.leaves.len(), indices.len());
+                assert!(verify_multi(
+                    &multi.proof,
+                    &multi.proof_flags,
+                    &tree.root(),
+                    &multi.leaves
+                ));
+            }
+        }
+        assert_eq!(tree(4).multi_proof(&[1, 1]), None);
+        assert_eq!(tree(4).multi_proof(&[4]), None);
+    }
+
+    #[test]
+    fn malformed_multi_proofs_are_rejected() {
+        let tree = tree(5);
+        let multi = tree.multi_proof(&[0, 3]).unwrap();
+        let root = tree.root();
+
+        let mut flags = multi.proof_flags.clone();
+        flags.push(true);
+        assert_eq!(
+            process_multi_proof(&multi.proof, &flags, &multi.leaves),
+            None
+        );
+
+        let mut leaves = multi.leaves.clone();
+        leaves.swap(0, 1);
+        assert!(!verify_multi(
+            &multi.proof,
+            &multi.proof_flags,
+            &root,
+            &leaves
+        ));
+
+        assert_eq!(process_multi_proof(&[], &[], &[]), None);
+        assert_eq!(process_multi_proof(&[], &[true, true], &[root]), None);
+    }
+}
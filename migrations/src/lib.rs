@@ -0,0 +1,320 @@
+//! Versioned storage migrations
+//!
+//! A contract lists the changes to its storage layout as [`Migration`] steps,
+//! each keyed by the schema version it produces, in a [`Migrations`]
+//! registry. The stored version is the `schema_version` key from
+//! `proxy-implementation`, so proxies and beacons keep refusing downgrades.
+//! Version `0` means the contract predates versioning.
+//!
+//! * Fresh deployments call [`Migrations::initialize`] from `initialize`;
+//!   their storage already has the latest layout, so no step runs.
+//! * Deployed contracts call `migrate`, which applies every step newer than
+//!   the stored version in order and records each one as it completes.
+//! * `migrate` with `dry_run` set only validates the registry and reports
+//!   the steps that would run.
+//!
+//! ## Embedding
+//! ```toml
+//! migrations = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use migrations::{Migration, Migrations};
+//!
+//! const MIGRATIONS: Migrations = Migrations::new(&[
+//!     Migration { version: 1, name: "split_config", apply: split_config },
+//!     Migration { version: 2, name: "index_holders", apply: index_holders },
+//! ]);
+//!
+//! fn authorize() -> ContractResult<()> {
+//!     Ok(ownable::only_owner()?)
+//! }
+//!
+//! migrations::migrate_entrypoint!(MIGRATIONS, authorize);
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use proxy_implementation::{init_schema_version, migrate_schema, schema_version};
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const MAX_CALL_DATA_BYTES: usize = 64;
+
+/// One storage layout change
+#[derive(Clone, Copy)]
+pub struct Migration {
+    /// Schema version once this step has run
+    pub version: u32,
+    /// Reported in the `Migrated` event
+    pub name: &'static str,
+    /// Rewrites storage from the previous version's layout
+    pub apply: fn() -> ContractResult<()>,
+}
+
+/// Steps a `migrate` call runs, from the stored version to the latest
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MigrationPlan {
+    pub from: u32,
+    pub to: u32,
+    /// Versions of the pending steps, in the order they run
+    pub steps: Vec<u32>,
+}
+
+/// Arguments of the `migrate` entrypoint
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MigrateArgs {
+    /// Validate and report the plan without touching storage
+    pub dry_run: bool,
+}
+
+/// Every migration of one contract, ordered by version
+pub struct Migrations {
+    steps: &'static [Migration],
+}
+
+impl Migrations {
+    /// Register `steps`; versions must strictly increase from 1
+    pub const fn new(steps: &'static [Migration]) -> Self {
+        Self { steps }
+    }
+
+    /// Schema version of the current storage layout
+    pub fn latest(&self) -> u32 {
+        self.steps.last().map_or(0, |step| step.version)
+    }
+
+    /// Check that versions start above `0` and strictly increase
+    pub fn validate(&self) -> ContractResult<()> {
+        let mut previous = 0;
+        for step in self.steps {
+            if step.version <= previous {
+                return Err(ContractError::InvalidArgument(format!(
+                    "Migration {} must have a version above {}",
+                    step.name, previous
+                )));
+            }
+            previous = step.version;
+        }
+        Ok(())
+    }
+
+    /// Record the latest version for a fresh deployment
+    pub fn initialize(&self) -> ContractResult<()> {
+        self.validate()?;
+        init_schema_version(self.latest())
+    }
+
+    fn pending(&self, current: u32) -> impl Iterator<Item = &Migration> {
+        self.steps.iter().filter(move |step| step.version > current)
+    }
+
+    /// Steps that `migrate` would run now; writes nothing
+    pub fn plan(&self) -> ContractResult<MigrationPlan> {
+        self.validate()?;
+        let current = schema_version()?;
+        let latest = self.latest();
+        if current > latest {
+            return Err(ContractError::InvalidArgument(format!(
+                "Stored schema {} is newer than the latest migration {}",
+                current, latest
+            )));
+        }
+        Ok(MigrationPlan {
+            from: current,
+            to: latest,
+            steps: self.pending(current).map(|step| step.version).collect(),
+        })
+    }
+
+    /// Apply every pending step in order, recording each version as it lands
+    ///
+    /// Calling it again once storage is up to date runs nothing.
+    pub fn migrate(&self) -> ContractResult<MigrationPlan> {
+        let plan = self.plan()?;
+        let mut current = plan.from;
+        for step in self.pending(plan.from) {
+            (step.apply)()?;
+            migrate_schema(current, step.version)?;
+            event!("Migrated", from: current, to: step.version, name: step.name);
+            current = step.version;
+        }
+        Ok(plan)
+    }
+}
+
+fn read_args() -> ContractResult<MigrateArgs> {
+    let payload = context().call_data()?;
+    if payload.len() > MAX_CALL_DATA_BYTES {
+        return Err(ContractError::InvalidArgument(
+            "Call data too large".to_string(),
+        ));
+    }
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Body of the `migrate` entrypoint; answers with the [`MigrationPlan`]
+pub fn execute_migrate(
+    migrations: &Migrations,
+    authorize: fn() -> ContractResult<()>,
+) -> ContractResult<()> {
+    authorize()?;
+    let args = read_args()?;
+    let plan = if args.dry_run {
+        migrations.plan()?
+    } else {
+        migrations.migrate()?
+    };
+    let data = postcard::to_allocvec(&plan).map_err(|_| ContractError::SerializationFailed)?;
+    context().return_bytes(&data)
+}
+
+/// Run [`execute_migrate`], logging failures like every other entrypoint
+pub fn migrate_entrypoint(migrations: &Migrations, authorize: fn() -> ContractResult<()>) {
+    if let Err(err) = execute_migrate(migrations, authorize) {
+        log!("Migrate failed: {}", err);
+    }
+}
+
+/// Export the standard `migrate` entrypoint for a registry
+///
+/// `$authorize` is a `fn() -> ContractResult<()>` that rejects callers who
+/// may not migrate, usually the owner or upgrader check.
+#[macro_export]
+macro_rules! migrate_entrypoint {
+    ($migrations:expr, $authorize:path) => {
+        /// Apply pending storage migrations, or only report them
+        ///
+        /// # Arguments
+        /// * `dry_run` - Validate and report the plan without writing
+        #[unsafe(no_mangle)]
+        pub extern "C" fn migrate() {
+            $crate::migrate_entrypoint(&$migrations, $authorize);
+        }
+    };
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const OWNER: &str = "chert1owner00000000000000000000";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender(OWNER);
+        mock::set_contract_address("chert1contract0000000000000000");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    fn authorize() -> ContractResult<()> {
+        if context().sender() != OWNER {
+            return Err(ContractError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    // v1 renames `fee` to `fee_bps`; v2 derives `fee_pct` from it.
+    fn rename_fee() -> ContractResult<()> {
+        let fee: u64 = storage().get("fee")?.unwrap_or(0);
+        storage().remove("fee")?;
+        storage().set("fee_bps", &fee)
+    }
+
+    fn derive_pct() -> ContractResult<()> {
+        let bps: u64 = storage()
+            .get("fee_bps")?
+            .ok_or(ContractError::StorageReadFailed)?;
+        storage().set("fee_pct", &(bps / 100))
+    }
+
+    const MIGRATIONS: Migrations = Migrations::new(&[
+        Migration {
+            version: 1,
+            name: "rename_fee",
+            apply: rename_fee,
+        },
+        Migration {
+            version: 2,
+            name: "derive_pct",
+            apply: derive_pct,
+        },
+    ]);
+
+    migrate_entrypoint!(MIGRATIONS, authorize);
+
+    fn call_migrate(dry_run: bool) -> MigrationPlan {
+        mock::set_call_data(&postcard::to_allocvec(&MigrateArgs { dry_run }).unwrap());
+        migrate();
+        postcard::from_bytes(&mock::take_return_data()).unwrap()
+    }
+
+    #[test]
+    fn migrate_applies_pending_steps_once() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        storage().set("fee", &300u64).unwrap();
+
+        let plan = call_migrate(true);
+        assert_eq!((plan.from, plan.to, plan.steps), (0, 2, vec![1, 2]));
+        assert!(storage().has("fee"));
+        assert_eq!(schema_version().unwrap(), 0);
+
+        mock::set_sender("chert1stranger00000000000000000");
+        mock::set_call_data(&postcard::to_allocvec(&MigrateArgs::default()).unwrap());
+        migrate();
+        assert_eq!(schema_version().unwrap(), 0);
+
+        mock::set_sender(OWNER);
+        let plan = call_migrate(false);
+        assert_eq!(plan.steps, vec![1, 2]);
+        assert_eq!(schema_version().unwrap(), 2);
+        assert_eq!(storage().get::<u64>("fee_pct").unwrap(), Some(3));
+        assert_eq!(mock::take_events().len(), 2);
+
+        assert!(call_migrate(false).steps.is_empty());
+        assert!(mock::take_events().is_empty());
+    }
+
+    #[test]
+    fn registry_rejects_bad_order_and_newer_storage() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        const UNORDERED: Migrations = Migrations::new(&[
+            Migration {
+                version: 2,
+                name: "b",
+                apply: derive_pct,
+            },
+            Migration {
+                version: 2,
+                name: "c",
+                apply: derive_pct,
+            },
+        ]);
+        assert!(UNORDERED.validate().is_err());
+        assert!(UNORDERED.plan().is_err());
+
+        // A fresh deployment starts at the latest version with nothing to run.
+        MIGRATIONS.initialize().unwrap();
+        assert_eq!(MIGRATIONS.plan().unwrap().steps, Vec::<u32>::new());
+
+        const OLDER: Migrations = Migrations::new(&[Migration {
+            version: 1,
+            name: "rename_fee",
+            apply: rename_fee,
+        }]);
+        assert!(OLDER.plan().is_err());
+    }
+}
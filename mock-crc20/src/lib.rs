@@ -0,0 +1,318 @@
+//! In-memory CRC-20 token for tests of contracts that move tokens
+//!
+//! `MockCrc20::deploy` registers handlers for every CRC-20 method at an
+//! address on the SDK's mock runtime, backed by a real ledger: transfers
+//! move balances, `transfer_from` spends allowances and failures come back
+//! as errors. The contract under test is the caller of every method, as it
+//! would be on chain.
+//!
+//! Tokens can also misbehave, to test contracts against hostile or unusual
+//! implementations:
+//!
+//! * [`MockCrc20::set_fee_bps`] burns a fee from every transfer, so the
+//!   recipient gets less than the amount sent.
+//! * [`MockCrc20::revert_transfers`] makes every transfer fail.
+//! * [`MockCrc20::reenter_on_transfer`] calls back into the contract under
+//!   test after each transfer, before control returns to it.
+//!
+//! ```rust,ignore
+//! let usdc = MockCrc20::deploy(USDC);
+//! usdc.mint(ALICE, 1_000);
+//! usdc.approve(ALICE, CONTRACT, 1_000);
+//! usdc.reenter_on_transfer(Reentry::new(ALICE, withdraw, &WithdrawArgs { amount: 10 }));
+//! call(ALICE, deposit, &DepositArgs { amount: 100 });
+//! assert!(usdc.reentries()[0].is_some(), "the reentrant withdraw must fail");
+//! ```
+//!
+//! Deploy after `mock::reset()`, which clears registered handlers.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use crc_standards::crc20::{
+    AllowanceArgs, ApproveArgs, BalanceOfArgs, TransferArgs, TransferFromArgs, methods,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::testing::Reentry;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+#[derive(Default)]
+struct Ledger {
+    balances: BTreeMap<String, u64>,
+    allowances: BTreeMap<(String, String), u64>,
+    total_supply: u64,
+    fee_bps: u64,
+    revert_transfers: bool,
+    reentry: Option<Reentry>,
+    reentries: Vec<Option<String>>,
+}
+
+impl Ledger {
+    /// Move `amount` less the fee; the fee is burned
+    fn transfer(&mut self, from: &str, to: &str, amount: u64) -> ContractResult<()> {
+        if self.revert_transfers {
+            return Err(ContractError::ContractCallFailed(
+                "transfers are disabled".to_string(),
+            ));
+        }
+        let available = self.balance(from);
+        if available < amount {
+            return Err(ContractError::InsufficientBalance {
+                required: amount,
+                available,
+            });
+        }
+        let fee = amount * self.fee_bps / BPS_DENOMINATOR;
+        self.balances.insert(from.to_string(), available - amount);
+        *self.balances.entry(to.to_string()).or_default() += amount - fee;
+        self.total_supply -= fee;
+        Ok(())
+    }
+
+    fn balance(&self, account: &str) -> u64 {
+        self.balances.get(account).copied().unwrap_or(0)
+    }
+
+    fn allowance(&self, owner: &str, spender: &str) -> u64 {
+        self.allowances
+            .get(&(owner.to_string(), spender.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Handle to a mock token; clones share its ledger
+#[derive(Clone)]
+pub struct MockCrc20 {
+    address: String,
+    ledger: Arc<Mutex<Ledger>>,
+}
+
+impl MockCrc20 {
+    /// Register a token with no supply at `address`
+    pub fn deploy(address: &str) -> Self {
+        let token = MockCrc20 {
+            address: address.to_string(),
+            ledger: Arc::default(),
+        };
+        token.handle(methods::NAME, |_, ()| Ok("Mock Token".to_string()));
+        token.handle(methods::SYMBOL, |_, ()| Ok("MOCK".to_string()));
+        token.handle(methods::DECIMALS, |_, ()| Ok(6u8));
+        token.handle(methods::TOTAL_SUPPLY, |ledger, ()| Ok(ledger.total_supply));
+        token.handle(methods::BALANCE_OF, |ledger, args: BalanceOfArgs| {
+            Ok(ledger.balance(&args.account))
+        });
+        token.handle(methods::ALLOWANCE, |ledger, args: AllowanceArgs| {
+            Ok(ledger.allowance(&args.owner, &args.spender))
+        });
+        token.handle(methods::APPROVE, |ledger, args: ApproveArgs| {
+            ledger
+                .allowances
+                .insert((caller(), args.spender.into()), args.amount);
+            Ok(())
+        });
+        token.handle_transfer(methods::TRANSFER, |ledger, args: TransferArgs| {
+            ledger.transfer(&caller(), &args.to, args.amount)
+        });
+        token.handle_transfer(methods::TRANSFER_FROM, |ledger, args: TransferFromArgs| {
+            let spender = caller();
+            let allowance = ledger.allowance(&args.from, &spender);
+            if allowance < args.amount {
+                return Err(ContractError::InsufficientBalance {
+                    required: args.amount,
+                    available: allowance,
+                });
+            }
+            ledger.transfer(&args.from, &args.to, args.amount)?;
+            ledger
+                .allowances
+                .insert((args.from.into(), spender), allowance - args.amount);
+            Ok(())
+        });
+        token
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Create `amount` new tokens for `to`
+    pub fn mint(&self, to: &str, amount: u64) {
+        let mut ledger = self.ledger();
+        *ledger.balances.entry(to.to_string()).or_default() += amount;
+        ledger.total_supply += amount;
+    }
+
+    /// Let `spender` move `amount` of `owner`'s tokens, as if `owner` had
+    /// called `approve`
+    pub fn approve(&self, owner: &str, spender: &str, amount: u64) {
+        self.ledger()
+            .allowances
+            .insert((owner.to_string(), spender.to_string()), amount);
+    }
+
+    pub fn balance_of(&self, account: &str) -> u64 {
+        self.ledger().balance(account)
+    }
+
+    pub fn allowance(&self, owner: &str, spender: &str) -> u64 {
+        self.ledger().allowance(owner, spender)
+    }
+
+    pub fn total_supply(&self) -> u64 {
+        self.ledger().total_supply
+    }
+
+    /// Burn `bps` basis points of every transfer
+    pub fn set_fee_bps(&self, bps: u64) {
+        assert!(bps <= BPS_DENOMINATOR, "fee above 100%");
+        self.ledger().fee_bps = bps;
+    }
+
+    /// Make every `transfer` and `transfer_from` fail (or succeed again)
+    pub fn revert_transfers(&self, revert: bool) {
+        self.ledger().revert_transfers = revert;
+    }
+
+    /// Make `reentry` after every successful transfer
+    pub fn reenter_on_transfer(&self, reentry: Reentry) {
+        self.ledger().reentry = Some(reentry);
+    }
+
+    /// Outcome of each reentry so far: `None` if the call succeeded, or the
+    /// failure it logged
+    pub fn reentries(&self) -> Vec<Option<String>> {
+        self.ledger().reentries.clone()
+    }
+
+    fn ledger(&self) -> MutexGuard<'_, Ledger> {
+        self.ledger
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn handle<A, R, F>(&self, method: &str, handler: F)
+    where
+        A: DeserializeOwned,
+        R: Serialize,
+        F: Fn(&mut Ledger, A) -> ContractResult<R> + Send + Sync + 'static,
+    {
+        let ledger = Arc::clone(&self.ledger);
+        mock::register_contract(&self.address, method, move |call_data| {
+            let args = decode(call_data)?;
+            let mut ledger = ledger
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let value = handler(&mut ledger, args)?;
+            postcard::to_allocvec(&value).map_err(|_| ContractError::SerializationFailed)
+        });
+    }
+
+    /// Like `handle`, then run the reentry with the ledger unlocked so the
+    /// reentrant call can use this token too
+    fn handle_transfer<A, F>(&self, method: &str, handler: F)
+    where
+        A: DeserializeOwned,
+        F: Fn(&mut Ledger, A) -> ContractResult<()> + Send + Sync + 'static,
+    {
+        let ledger = Arc::clone(&self.ledger);
+        mock::register_contract(&self.address, method, move |call_data| {
+            let args = decode(call_data)?;
+            let reentry = {
+                let mut ledger = ledger
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                handler(&mut ledger, args)?;
+                ledger.reentry.clone()
+            };
+            if let Some(reentry) = reentry {
+                let outcome = reentry.run();
+                ledger
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .reentries
+                    .push(outcome);
+            }
+            Ok(Vec::new())
+        });
+    }
+}
+
+/// The contract making the cross-contract call
+fn caller() -> String {
+    context().contract_address().to_string()
+}
+
+/// Arguments of a call; argument-less methods decode `()` from no data
+fn decode<A: DeserializeOwned>(call_data: &[u8]) -> ContractResult<A> {
+    postcard::from_bytes(call_data).map_err(|_| ContractError::DeserializationFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crc_clients::{Crc20, Crc20Client};
+
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    const BOB: &str = "0x0000000000000000000000000000000000000b02";
+
+    extern "C" fn pull_one() {
+        let token = Crc20Client::new(TOKEN);
+        if let Err(err) = token.transfer_from(ALICE, BOB, 1) {
+            log!("pull_one failed: {}", err);
+        }
+    }
+
+    #[test]
+    fn ledger_fees_reverts_and_reentry() {
+        mock::reset();
+        mock::set_sender(ALICE);
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        let mock_token = MockCrc20::deploy(TOKEN);
+        let token = Crc20Client::new(TOKEN);
+        mock_token.mint(ALICE, 1_000);
+
+        assert!(token.transfer_from(ALICE, BOB, 100).is_err());
+        mock_token.approve(ALICE, CONTRACT, 300);
+        token.transfer_from(ALICE, BOB, 100).unwrap();
+        assert_eq!(token.balance_of(BOB).unwrap(), 100);
+        assert_eq!(token.allowance(ALICE, CONTRACT).unwrap(), 200);
+
+        mock_token.set_fee_bps(100);
+        token.transfer_from(ALICE, BOB, 100).unwrap();
+        assert_eq!(mock_token.balance_of(BOB), 199);
+        assert_eq!(token.total_supply().unwrap(), 999);
+
+        mock_token.revert_transfers(true);
+        assert!(token.transfer_from(ALICE, BOB, 1).is_err());
+        mock_token.revert_transfers(false);
+
+        mock_token.set_fee_bps(0);
+        mock_token.approve(ALICE, CONTRACT, 1);
+        mock_token.reenter_on_transfer(Reentry::new(BOB, pull_one, &()));
+        mock_token.mint(CONTRACT, 5);
+        token.transfer(BOB, 5).unwrap();
+        // The first reentry's own pull re-enters again, and that one finds
+        // the allowance spent. Outcomes are recorded innermost first.
+        let outcomes = mock_token.reentries();
+        assert_eq!(outcomes.len(), 2);
+        assert!(
+            outcomes[0]
+                .as_deref()
+                .unwrap()
+                .starts_with("pull_one failed")
+        );
+        assert_eq!(outcomes[1], None);
+        assert_eq!(mock_token.balance_of(BOB), 205);
+        assert_eq!(context().sender(), ALICE);
+    }
+}
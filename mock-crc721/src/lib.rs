@@ -0,0 +1,322 @@
+//! In-memory CRC-721 collection for tests of contracts that hold NFTs
+//!
+//! `MockCrc721::deploy` registers handlers for the CRC-721 methods at an
+//! address on the SDK's mock runtime, backed by real ownership records:
+//! `transfer_from` checks the caller is the owner, approved or an operator
+//! and moves the token. The contract under test is the caller of every
+//! method, as it would be on chain. `safe_transfer_from` behaves like
+//! `transfer_from`; receiver hooks are not called.
+//!
+//! Collections can also misbehave:
+//!
+//! * [`MockCrc721::revert_transfers`] makes every transfer fail.
+//! * [`MockCrc721::ignore_transfers`] reports success without moving the
+//!   token, so contracts that trust the call rather than `owner_of` can be
+//!   caught.
+//! * [`MockCrc721::reenter_on_transfer`] calls back into the contract under
+//!   test after each transfer, before control returns to it.
+//!
+//! Deploy after `mock::reset()`, which clears registered handlers.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use crc_standards::crc721::{
+    ApproveArgs, BalanceOfArgs, IsApprovedForAllArgs, SafeTransferFromArgs, SetApprovalForAllArgs,
+    TokenIdArgs, TransferFromArgs, methods,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::ffi::mock;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::testing::Reentry;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Default)]
+struct Records {
+    owners: BTreeMap<u64, String>,
+    approvals: BTreeMap<u64, String>,
+    operators: BTreeSet<(String, String)>,
+    revert_transfers: bool,
+    ignore_transfers: bool,
+    reentry: Option<Reentry>,
+    reentries: Vec<Option<String>>,
+}
+
+impl Records {
+    fn owner(&self, token_id: u64) -> ContractResult<String> {
+        self.owners
+            .get(&token_id)
+            .cloned()
+            .ok_or_else(|| ContractError::InvalidArgument("Token does not exist".to_string()))
+    }
+
+    fn transfer(
+        &mut self,
+        spender: &str,
+        from: &str,
+        to: &str,
+        token_id: u64,
+    ) -> ContractResult<()> {
+        if self.revert_transfers {
+            return Err(ContractError::ContractCallFailed(
+                "transfers are disabled".to_string(),
+            ));
+        }
+        let owner = self.owner(token_id)?;
+        let allowed = spender == owner
+            || self.approvals.get(&token_id).is_some_and(|a| a == spender)
+            || self
+                .operators
+                .contains(&(owner.clone(), spender.to_string()));
+        if owner != from || !allowed {
+            return Err(ContractError::Unauthorized);
+        }
+        if self.ignore_transfers {
+            return Ok(());
+        }
+        self.approvals.remove(&token_id);
+        self.owners.insert(token_id, to.to_string());
+        Ok(())
+    }
+}
+
+/// Handle to a mock collection; clones share its records
+#[derive(Clone)]
+pub struct MockCrc721 {
+    address: String,
+    records: Arc<Mutex<Records>>,
+}
+
+impl MockCrc721 {
+    /// Register an empty collection at `address`
+    pub fn deploy(address: &str) -> Self {
+        let collection = MockCrc721 {
+            address: address.to_string(),
+            records: Arc::default(),
+        };
+        collection.handle(methods::NAME, |_, ()| Ok("Mock Collection".to_string()));
+        collection.handle(methods::SYMBOL, |_, ()| Ok("MOCKNFT".to_string()));
+        collection.handle(methods::TOTAL_SUPPLY, |records, ()| {
+            Ok(records.owners.len() as u64)
+        });
+        collection.handle(methods::BALANCE_OF, |records, args: BalanceOfArgs| {
+            Ok(records
+                .owners
+                .values()
+                .filter(|o| **o == args.owner)
+                .count() as u64)
+        });
+        collection.handle(methods::OWNER_OF, |records, args: TokenIdArgs| {
+            records.owner(args.token_id)
+        });
+        collection.handle(methods::GET_APPROVED, |records, args: TokenIdArgs| {
+            records.owner(args.token_id)?;
+            Ok(records.approvals.get(&args.token_id).cloned())
+        });
+        collection.handle(
+            methods::IS_APPROVED_FOR_ALL,
+            |records, args: IsApprovedForAllArgs| {
+                Ok(records
+                    .operators
+                    .contains(&(args.owner.into(), args.operator.into())))
+            },
+        );
+        collection.handle(methods::APPROVE, |records, args: ApproveArgs| {
+            if records.owner(args.token_id)? != caller() {
+                return Err(ContractError::Unauthorized);
+            }
+            records.approvals.insert(args.token_id, args.to.into());
+            Ok(())
+        });
+        collection.handle(
+            methods::SET_APPROVAL_FOR_ALL,
+            |records, args: SetApprovalForAllArgs| {
+                let pair = (caller(), args.operator.into());
+                if args.approved {
+                    records.operators.insert(pair);
+                } else {
+                    records.operators.remove(&pair);
+                }
+                Ok(())
+            },
+        );
+        collection.handle_transfer(methods::TRANSFER_FROM, |records, args: TransferFromArgs| {
+            records.transfer(&caller(), &args.from, &args.to, args.token_id)
+        });
+        collection.handle_transfer(
+            methods::SAFE_TRANSFER_FROM,
+            |records, args: SafeTransferFromArgs| {
+                records.transfer(&caller(), &args.from, &args.to, args.token_id)
+            },
+        );
+        collection
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Create `token_id` owned by `to`
+    pub fn mint(&self, to: &str, token_id: u64) {
+        let previous = self.records().owners.insert(token_id, to.to_string());
+        assert!(previous.is_none(), "token {} already minted", token_id);
+    }
+
+    /// Current owner of `token_id`, if minted
+    pub fn owner_of(&self, token_id: u64) -> Option<String> {
+        self.records().owners.get(&token_id).cloned()
+    }
+
+    /// Let `operator` move all of `owner`'s tokens, as if `owner` had
+    /// called `set_approval_for_all`
+    pub fn approve_for_all(&self, owner: &str, operator: &str) {
+        self.records()
+            .operators
+            .insert((owner.to_string(), operator.to_string()));
+    }
+
+    /// Make every transfer fail (or succeed again)
+    pub fn revert_transfers(&self, revert: bool) {
+        self.records().revert_transfers = revert;
+    }
+
+    /// Report authorized transfers as successful without moving the token
+    pub fn ignore_transfers(&self, ignore: bool) {
+        self.records().ignore_transfers = ignore;
+    }
+
+    /// Make `reentry` after every successful transfer
+    pub fn reenter_on_transfer(&self, reentry: Reentry) {
+        self.records().reentry = Some(reentry);
+    }
+
+    /// Outcome of each reentry so far: `None` if the call succeeded, or the
+    /// failure it logged
+    pub fn reentries(&self) -> Vec<Option<String>> {
+        self.records().reentries.clone()
+    }
+
+    fn records(&self) -> MutexGuard<'_, Records> {
+        self.records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn handle<A, R, F>(&self, method: &str, handler: F)
+    where
+        A: DeserializeOwned,
+        R: Serialize,
+        F: Fn(&mut Records, A) -> ContractResult<R> + Send + Sync + 'static,
+    {
+        let records = Arc::clone(&self.records);
+        mock::register_contract(&self.address, method, move |call_data| {
+            let args = decode(call_data)?;
+            let mut records = records
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let value = handler(&mut records, args)?;
+            postcard::to_allocvec(&value).map_err(|_| ContractError::SerializationFailed)
+        });
+    }
+
+    /// Like `handle`, then run the reentry with the records unlocked so the
+    /// reentrant call can use this collection too
+    fn handle_transfer<A, F>(&self, method: &str, handler: F)
+    where
+        A: DeserializeOwned,
+        F: Fn(&mut Records, A) -> ContractResult<()> + Send + Sync + 'static,
+    {
+        let records = Arc::clone(&self.records);
+        mock::register_contract(&self.address, method, move |call_data| {
+            let args = decode(call_data)?;
+            let reentry = {
+                let mut records = records
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                handler(&mut records, args)?;
+                records.reentry.clone()
+            };
+            if let Some(reentry) = reentry {
+                let outcome = reentry.run();
+                records
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .reentries
+                    .push(outcome);
+            }
+            Ok(Vec::new())
+        });
+    }
+}
+
+/// The contract making the cross-contract call
+fn caller() -> String {
+    context().contract_address().to_string()
+}
+
+/// Arguments of a call; argument-less methods decode `()` from no data
+fn decode<A: DeserializeOwned>(call_data: &[u8]) -> ContractResult<A> {
+    postcard::from_bytes(call_data).map_err(|_| ContractError::DeserializationFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crc_clients::{Crc721, Crc721Client};
+
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const COLLECTION: &str = "0x0000000000000000000000000000000000000f02";
+    const ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    const BOB: &str = "0x0000000000000000000000000000000000000b02";
+
+    extern "C" fn bounce_back() {
+        let collection = Crc721Client::new(COLLECTION);
+        if let Err(err) = collection.transfer_from(BOB, ALICE, 7) {
+            log!("bounce_back failed: {}", err);
+        }
+    }
+
+    #[test]
+    fn ownership_reverts_ignored_transfers_and_reentry() {
+        mock::reset();
+        mock::set_sender(ALICE);
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        let mock_collection = MockCrc721::deploy(COLLECTION);
+        let collection = Crc721Client::new(COLLECTION);
+        mock_collection.mint(ALICE, 7);
+
+        assert!(collection.transfer_from(ALICE, BOB, 7).is_err());
+        mock_collection.approve_for_all(ALICE, CONTRACT);
+        assert!(collection.is_approved_for_all(ALICE, CONTRACT).unwrap());
+        collection.transfer_from(ALICE, BOB, 7).unwrap();
+        assert_eq!(collection.owner_of(7).unwrap(), BOB);
+        assert_eq!(collection.balance_of(BOB).unwrap(), 1);
+
+        mock_collection.revert_transfers(true);
+        mock_collection.approve_for_all(BOB, CONTRACT);
+        assert!(collection.transfer_from(BOB, ALICE, 7).is_err());
+        mock_collection.revert_transfers(false);
+
+        mock_collection.ignore_transfers(true);
+        collection.transfer_from(BOB, ALICE, 7).unwrap();
+        assert_eq!(mock_collection.owner_of(7).as_deref(), Some(BOB));
+        mock_collection.ignore_transfers(false);
+
+        // The reentry tries to move the token again once it has left BOB.
+        mock_collection.reenter_on_transfer(Reentry::new(BOB, bounce_back, &()));
+        collection.transfer_from(BOB, ALICE, 7).unwrap();
+        assert_eq!(mock_collection.owner_of(7).as_deref(), Some(ALICE));
+        let outcomes = mock_collection.reentries();
+        assert_eq!(outcomes.len(), 1);
+        assert!(
+            outcomes[0]
+                .as_deref()
+                .unwrap()
+                .starts_with("bounce_back failed")
+        );
+        assert_eq!(context().sender(), ALICE);
+    }
+}
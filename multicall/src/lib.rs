@@ -0,0 +1,328 @@
+//! Multicall
+//!
+//! Runs a batch of read-only cross-contract calls and returns every result
+//! together with the block it was read at, so frontends and indexers can
+//! load a consistent view in one round-trip. The contract keeps no state.
+//!
+//! ## Features
+//! - Up to 50 calls per batch
+//! - Per-call success flags, or fail the whole batch on the first error
+//! - Block height and timestamp in every response
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::prelude::*;
+
+const MAX_CALLS: usize = 50;
+const MAX_CALL_DATA_BYTES: usize = 65_536;
+const MAX_RETURN_BYTES: usize = 65_536;
+
+/// One call in a batch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+    pub target: String,
+    pub method: String,
+    /// Postcard-encoded arguments
+    pub args: Vec<u8>,
+}
+
+/// Outcome of one call
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CallResult {
+    pub success: bool,
+    /// Raw return bytes; empty if the call failed
+    pub data: Vec<u8>,
+}
+
+/// The block the batch was read at
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlockInfo {
+    pub height: u64,
+    pub timestamp: u64,
+}
+
+/// Results in call order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AggregateResponse {
+    pub block: BlockInfo,
+    pub results: Vec<CallResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AggregateArgs {
+    calls: Vec<Call>,
+    require_success: bool,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn respond_bytes(data: &[u8]) -> ContractResult<()> {
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(data)
+}
+
+fn encode<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn block_info() -> BlockInfo {
+    let ctx = context();
+    BlockInfo {
+        height: ctx.block_height(),
+        timestamp: ctx.block_timestamp(),
+    }
+}
+
+fn execute_aggregate() -> ContractResult<Vec<u8>> {
+    let ctx = context();
+    let args: AggregateArgs = read_args()?;
+    if args.calls.is_empty() || args.calls.len() > MAX_CALLS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Batches hold 1-{} calls",
+            MAX_CALLS
+        )));
+    }
+
+    let mut results = Vec::with_capacity(args.calls.len());
+    for call in &args.calls {
+        // Calling back into the aggregator would only nest batches.
+        if call.target == ctx.contract_address() {
+            return Err(ContractError::InvalidArgument(
+                "Multicall cannot call itself".to_string(),
+            ));
+        }
+        let result = match ctx.call_contract(&call.target, &call.method, &call.args) {
+            Ok(data) => CallResult {
+                success: true,
+                data,
+            },
+            Err(err) if args.require_success => return Err(err),
+            Err(_) => CallResult {
+                success: false,
+                data: Vec::new(),
+            },
+        };
+        results.push(result);
+    }
+
+    let response = encode(&AggregateResponse {
+        block: block_info(),
+        results,
+    })?;
+    if response.len() > MAX_RETURN_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Results exceed {} bytes; split the batch",
+            MAX_RETURN_BYTES
+        )));
+    }
+    Ok(response)
+}
+
+/// Run a batch of read-only calls
+///
+/// Calls run with this contract as the sender, so they must not depend on
+/// the caller. Calls that change state are not prevented, but their effects
+/// are attributed to this contract.
+///
+/// # Arguments
+/// * `calls` - 1-50 `Call` entries
+/// * `require_success` - Fail the whole batch if any call fails
+///
+/// # Returns
+/// `AggregateResponse` (max 64 KiB)
+#[unsafe(no_mangle)]
+pub extern "C" fn aggregate() {
+    let result = execute_aggregate().and_then(|response| respond_bytes(&response));
+    if let Err(err) = result {
+        log!("aggregate failed: {}", err);
+    }
+}
+
+/// Get the current block
+///
+/// # Returns
+/// `BlockInfo`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_block() {
+    let result = encode(&block_info()).and_then(|data| respond_bytes(&data));
+    if let Err(err) = result {
+        log!("get_block failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| encode(&crc_standards::contract_version!(schema_version)))
+        .and_then(|data| respond_bytes(&data));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_USER: &str = "0x0000000000000000000000000000000000000a01";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const ORACLE: &str = "0x0000000000000000000000000000000000000f02";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(42);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::register_contract(TOKEN, "total_supply", |_| {
+            Ok(postcard::to_allocvec(&1_000u64).unwrap())
+        });
+        mock::register_contract(TOKEN, "balance_of", |args| {
+            let account: String = postcard::from_bytes(args).unwrap();
+            let balance = if account == ADDR_USER { 250u64 } else { 0 };
+            Ok(postcard::to_allocvec(&balance).unwrap())
+        });
+        mock::register_contract(ORACLE, "latest_price", |_| {
+            Err(ContractError::InvalidArgument("Stale price".to_string()))
+        });
+    }
+
+    fn call_to(target: &str, method: &str, args: Vec<u8>) -> Call {
+        Call {
+            target: target.to_string(),
+            method: method.to_string(),
+            args,
+        }
+    }
+
+    fn run(calls: Vec<Call>, require_success: bool) -> Vec<u8> {
+        mock::set_sender(ADDR_USER);
+        mock::set_call_data(
+            &postcard::to_allocvec(&AggregateArgs {
+                calls,
+                require_success,
+            })
+            .unwrap(),
+        );
+        aggregate();
+        mock::take_return_data()
+    }
+
+    #[test]
+    fn returns_every_result_with_the_block() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let data = run(
+            vec![
+                call_to(TOKEN, "total_supply", Vec::new()),
+                call_to(ORACLE, "latest_price", Vec::new()),
+                call_to(
+                    TOKEN,
+                    "balance_of",
+                    postcard::to_allocvec(ADDR_USER).unwrap(),
+                ),
+            ],
+            false,
+        );
+        let response: AggregateResponse = postcard::from_bytes(&data).unwrap();
+        assert_eq!(
+            response.block,
+            BlockInfo {
+                height: 42,
+                timestamp: 1_736_000_000
+            }
+        );
+        let decoded: Vec<(bool, Option<u64>)> = response
+            .results
+            .iter()
+            .map(|r| (r.success, postcard::from_bytes(&r.data).ok()))
+            .collect();
+        assert_eq!(
+            decoded,
+            [(true, Some(1_000)), (false, None), (true, Some(250))]
+        );
+    }
+
+    #[test]
+    fn require_success_fails_the_whole_batch() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let calls = vec![
+            call_to(TOKEN, "total_supply", Vec::new()),
+            call_to(ORACLE, "latest_price", Vec::new()),
+        ];
+        assert!(run(calls, true).is_empty());
+        assert!(
+            mock::take_logs()
+                .iter()
+                .any(|line| line.contains("aggregate failed"))
+        );
+    }
+
+    #[test]
+    fn rejects_empty_oversized_and_recursive_batches() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert!(run(Vec::new(), false).is_empty());
+        let too_many = vec![call_to(TOKEN, "total_supply", Vec::new()); MAX_CALLS + 1];
+        assert!(run(too_many, false).is_empty());
+        assert!(run(vec![call_to(CONTRACT, "get_block", Vec::new())], false).is_empty());
+        assert!(mock::take_contract_calls().is_empty());
+
+        get_block();
+        let block: BlockInfo = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(block.height, 42);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::set_sender(ADDR_USER);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "multicall-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
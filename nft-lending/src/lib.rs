@@ -0,0 +1,1131 @@
+//! NFT-Collateralized Lending
+//!
+//! Peer-to-peer loans against CRC-721 collateral. A borrower lists an NFT
+//! with the terms they want: currency, principal, annual rate and duration.
+//! The NFT moves into escrow at listing. Any lender can fund the listing,
+//! which sends the principal straight to the borrower and starts the clock.
+//! Repaying before the deadline returns the NFT. Interest is charged only
+//! for the time the loan was actually open. If the deadline passes without
+//! repayment, the lender claims the NFT instead.
+//!
+//! ## Features
+//! - NFT escrow from listing to repayment, default or cancellation
+//! - Pro-rata interest at an annual rate, rounded up
+//! - Lender claims the collateral after the deadline
+//! - Borrowers cancel unfunded listings
+//! - An owner can pause new listings and funding; loans already open settle
+//!   as usual
+//! - Decommissioning: after the owner's notice runs out, new loans stop for
+//!   good while open ones settle
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::ownable;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const NEXT_LOAN_ID_KEY: &str = "next_loan_id";
+const LOANS_PREFIX: &str = "loans";
+const ACCOUNT_LOANS_PREFIX: &str = "account_loans";
+const ACCOUNT_LOAN_COUNT_PREFIX: &str = "account_loan_count";
+const BPS_DENOMINATOR: u64 = 10_000;
+const SECONDS_PER_YEAR: u64 = 365 * 86_400;
+const MIN_DURATION: u64 = 86_400;
+const MAX_DURATION: u64 = 365 * 86_400;
+/// 1000% APR
+const MAX_APR_BPS: u64 = 100_000;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Lifecycle of a loan
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoanStatus {
+    /// Collateral escrowed, waiting for a lender
+    Listed,
+    /// Funded; the borrower owes principal plus interest
+    Active,
+    /// Repaid, collateral returned to the borrower
+    Repaid,
+    /// Deadline missed, collateral claimed by the lender
+    Defaulted,
+    /// Withdrawn by the borrower before funding
+    Cancelled,
+}
+
+/// A listing and, once funded, the loan it became
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Loan {
+    pub borrower: String,
+    pub collection: String,
+    pub token_id: u64,
+    /// CRC-20 lent and repaid
+    pub currency: String,
+    pub principal: u64,
+    /// Annual interest rate
+    pub apr_bps: u64,
+    /// Seconds from funding to the deadline
+    pub duration: u64,
+    pub status: LoanStatus,
+    pub lender: Option<String>,
+    pub started_at: u64,
+    pub deadline: u64,
+}
+
+/// One page of loan ids for an account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LoanPage {
+    pub items: Vec<u64>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ListArgs {
+    collection: String,
+    token_id: u64,
+    currency: String,
+    principal: u64,
+    apr_bps: u64,
+    duration: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LoanIdArgs {
+    loan_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LoansOfArgs {
+    account: String,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NftTransferFromArgs {
+    from: String,
+    to: String,
+    token_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn loans() -> Map<u64, Loan> {
+    Map::new(LOANS_PREFIX)
+}
+
+fn account_loans() -> Map<(String, u64), u64> {
+    Map::new(ACCOUNT_LOANS_PREFIX)
+}
+
+fn account_loan_counts() -> Map<String, u64> {
+    Map::new(ACCOUNT_LOAN_COUNT_PREFIX)
+}
+
+fn load_loan(loan_id: u64) -> ContractResult<Loan> {
+    loans()
+        .get(&loan_id)?
+        .ok_or_else(|| ContractError::InvalidArgument(format!("Loan {} not found", loan_id)))
+}
+
+fn index_loan(account: &str, loan_id: u64) -> ContractResult<()> {
+    let mut counts = account_loan_counts();
+    let count = counts.get(&account.to_string())?.unwrap_or(0);
+    account_loans().set(&(account.to_string(), count), &loan_id)?;
+    counts.set(&account.to_string(), &safe_math::add(count, 1)?)
+}
+
+fn move_tokens(token: &str, from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer_from", &call)
+        .map(|_| ())
+}
+
+fn move_nft(collection: &str, from: &str, to: &str, token_id: u64) -> ContractResult<()> {
+    let call = encode_call(&NftTransferFromArgs {
+        from: from.to_string(),
+        to: to.to_string(),
+        token_id,
+    })?;
+    context()
+        .call_contract(collection, "transfer_from", &call)
+        .map(|_| ())
+}
+
+fn expect_status(loan: &Loan, status: LoanStatus) -> ContractResult<()> {
+    if loan.status != status {
+        return Err(ContractError::InvalidArgument(format!(
+            "Loan is {:?}, expected {:?}",
+            loan.status, status
+        )));
+    }
+    Ok(())
+}
+
+/// Interest owed on `loan` at `now`, for the elapsed part of its duration.
+///
+/// Rounded up so short loans still pay something.
+pub fn interest_due(loan: &Loan, now: u64) -> u64 {
+    let elapsed = now.saturating_sub(loan.started_at).min(loan.duration);
+    let scaled = loan.principal as u128 * loan.apr_bps as u128 * elapsed as u128;
+    let denominator = BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128;
+    // Saturates; adding the principal then fails with an overflow.
+    scaled.div_ceil(denominator).min(u64::MAX as u128) as u64
+}
+
+fn execute_list() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let borrower = ctx.sender().to_string();
+    let args: ListArgs = read_args()?;
+    for address in [&args.collection, &args.currency] {
+        validation::validate_address(address)?;
+        if !ctx.is_contract(address) {
+            return Err(ContractError::InvalidArgument(
+                "Collection and currency must be contracts".to_string(),
+            ));
+        }
+    }
+    validation::validate_positive_amount(args.principal)?;
+    validation::validate_range(args.apr_bps, 0, MAX_APR_BPS)?;
+    validation::validate_range(args.duration, MIN_DURATION, MAX_DURATION)?;
+
+    let mut store = storage();
+    let loan_id = store.get::<u64>(NEXT_LOAN_ID_KEY)?.unwrap_or(0);
+    store.set(NEXT_LOAN_ID_KEY, &safe_math::add(loan_id, 1)?)?;
+    loans().set(
+        &loan_id,
+        &Loan {
+            borrower: borrower.clone(),
+            collection: args.collection.clone(),
+            token_id: args.token_id,
+            currency: args.currency.clone(),
+            principal: args.principal,
+            apr_bps: args.apr_bps,
+            duration: args.duration,
+            status: LoanStatus::Listed,
+            lender: None,
+            started_at: 0,
+            deadline: 0,
+        },
+    )?;
+    index_loan(&borrower, loan_id)?;
+
+    move_nft(
+        &args.collection,
+        &borrower,
+        ctx.contract_address(),
+        args.token_id,
+    )?;
+
+    event!("LoanListed",
+        loan_id: loan_id,
+        borrower: borrower,
+        collection: args.collection,
+        token_id: args.token_id,
+        currency: args.currency,
+        principal: args.principal,
+        apr_bps: args.apr_bps,
+        duration: args.duration
+    );
+    Ok(loan_id)
+}
+
+fn execute_cancel() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: LoanIdArgs = read_args()?;
+    let mut loan = load_loan(args.loan_id)?;
+    if ctx.sender() != loan.borrower {
+        return Err(ContractError::Unauthorized);
+    }
+    expect_status(&loan, LoanStatus::Listed)?;
+    loan.status = LoanStatus::Cancelled;
+    loans().set(&args.loan_id, &loan)?;
+
+    move_nft(
+        &loan.collection,
+        ctx.contract_address(),
+        &loan.borrower,
+        loan.token_id,
+    )?;
+
+    event!("LoanCancelled", loan_id: args.loan_id);
+    Ok(())
+}
+
+fn execute_fund() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let lender = ctx.sender().to_string();
+    let args: LoanIdArgs = read_args()?;
+    let mut loan = load_loan(args.loan_id)?;
+    expect_status(&loan, LoanStatus::Listed)?;
+    if lender == loan.borrower {
+        return Err(ContractError::InvalidArgument(
+            "Borrower cannot fund their own loan".to_string(),
+        ));
+    }
+    let now = ctx.block_timestamp();
+    loan.status = LoanStatus::Active;
+    loan.lender = Some(lender.clone());
+    loan.started_at = now;
+    loan.deadline = safe_math::add(now, loan.duration)?;
+    loans().set(&args.loan_id, &loan)?;
+    index_loan(&lender, args.loan_id)?;
+
+    move_tokens(&loan.currency, &lender, &loan.borrower, loan.principal)?;
+
+    event!("LoanFunded",
+        loan_id: args.loan_id,
+        lender: lender,
+        principal: loan.principal,
+        deadline: loan.deadline
+    );
+    Ok(())
+}
+
+fn execute_repay() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: LoanIdArgs = read_args()?;
+    let mut loan = load_loan(args.loan_id)?;
+    if ctx.sender() != loan.borrower {
+        return Err(ContractError::Unauthorized);
+    }
+    expect_status(&loan, LoanStatus::Active)?;
+    let now = ctx.block_timestamp();
+    if now > loan.deadline {
+        return Err(ContractError::InvalidArgument(
+            "Loan is past its deadline".to_string(),
+        ));
+    }
+    let interest = interest_due(&loan, now);
+    let repayment = safe_math::add(loan.principal, interest)?;
+    let lender = loan.lender.clone().unwrap_or_default();
+    loan.status = LoanStatus::Repaid;
+    loans().set(&args.loan_id, &loan)?;
+
+    move_tokens(&loan.currency, &loan.borrower, &lender, repayment)?;
+    move_nft(
+        &loan.collection,
+        ctx.contract_address(),
+        &loan.borrower,
+        loan.token_id,
+    )?;
+
+    event!("LoanRepaid",
+        loan_id: args.loan_id,
+        principal: loan.principal,
+        interest: interest
+    );
+    Ok(repayment)
+}
+
+fn execute_claim_collateral() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: LoanIdArgs = read_args()?;
+    let mut loan = load_loan(args.loan_id)?;
+    let lender = loan.lender.clone().unwrap_or_default();
+    if ctx.sender() != lender {
+        return Err(ContractError::Unauthorized);
+    }
+    expect_status(&loan, LoanStatus::Active)?;
+    if ctx.block_timestamp() <= loan.deadline {
+        return Err(ContractError::InvalidArgument(
+            "Loan has not defaulted".to_string(),
+        ));
+    }
+    loan.status = LoanStatus::Defaulted;
+    loans().set(&args.loan_id, &loan)?;
+
+    move_nft(
+        &loan.collection,
+        ctx.contract_address(),
+        &lender,
+        loan.token_id,
+    )?;
+
+    event!("CollateralClaimed",
+        loan_id: args.loan_id,
+        lender: lender,
+        collection: loan.collection,
+        token_id: loan.token_id
+    );
+    Ok(())
+}
+
+fn execute_repayment_amount() -> ContractResult<u64> {
+    let args: LoanIdArgs = read_args()?;
+    let loan = load_loan(args.loan_id)?;
+    expect_status(&loan, LoanStatus::Active)?;
+    safe_math::add(
+        loan.principal,
+        interest_due(&loan, context().block_timestamp()),
+    )
+}
+
+fn execute_loans_of() -> ContractResult<LoanPage> {
+    let args: LoansOfArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let total = account_loan_counts().get(&args.account)?.unwrap_or(0);
+    let end = args.offset.saturating_add(args.limit).min(total);
+    let index = account_loans();
+    let mut items = Vec::new();
+    for position in args.offset..end {
+        if let Some(id) = index.get(&(args.account.clone(), position))? {
+            items.push(id);
+        }
+    }
+    Ok(LoanPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    ownable::only_owner()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    ownable::only_owner()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    ownable::only_owner()?;
+    pausable::unpause()
+}
+
+/// Set the owner, who may pause the market; call right after deployment
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = ownable::initialize(context().sender()) {
+        log!("initialize failed: {}", err);
+    }
+}
+
+/// List an NFT as collateral with the requested terms
+///
+/// The NFT is escrowed immediately; the caller must have approved this
+/// contract on the collection.
+///
+/// # Arguments
+/// * `collection` - CRC-721 contract of the collateral
+/// * `token_id` - Collateral token
+/// * `currency` - CRC-20 to borrow
+/// * `principal` - Amount to borrow
+/// * `apr_bps` - Annual interest rate (max 100000)
+/// * `duration` - Loan length in seconds (1 day to 365 days)
+///
+/// # Returns
+/// Loan id (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn list() {
+    if let Err(err) = execute_list().and_then(|loan_id| try_respond(&loan_id)) {
+        log!("list failed: {}", err);
+    }
+}
+
+/// Withdraw an unfunded listing and get the NFT back (borrower only)
+///
+/// # Arguments
+/// * `loan_id` - Listing to cancel
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel() {
+    if let Err(err) = execute_cancel() {
+        log!("cancel failed: {}", err);
+    }
+}
+
+/// Fund a listing; the principal goes straight to the borrower
+///
+/// The caller must have approved this contract for the principal on the
+/// loan currency.
+///
+/// # Arguments
+/// * `loan_id` - Listing to fund
+#[unsafe(no_mangle)]
+pub extern "C" fn fund() {
+    if let Err(err) = execute_fund() {
+        log!("fund failed: {}", err);
+    }
+}
+
+/// Repay principal plus accrued interest and get the NFT back (borrower only)
+///
+/// Allowed until the deadline. The caller must have approved this contract
+/// for the repayment on the loan currency.
+///
+/// # Arguments
+/// * `loan_id` - Loan to repay
+///
+/// # Returns
+/// Amount paid to the lender (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn repay() {
+    if let Err(err) = execute_repay().and_then(|paid| try_respond(&paid)) {
+        log!("repay failed: {}", err);
+    }
+}
+
+/// Take the collateral of a loan past its deadline (lender only)
+///
+/// # Arguments
+/// * `loan_id` - Defaulted loan
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_collateral() {
+    if let Err(err) = execute_claim_collateral() {
+        log!("claim_collateral failed: {}", err);
+    }
+}
+
+/// Get a loan
+///
+/// # Arguments
+/// * `loan_id` - Loan id
+///
+/// # Returns
+/// `Loan`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_loan() {
+    let result = read_args::<LoanIdArgs>()
+        .and_then(|args| load_loan(args.loan_id))
+        .and_then(|loan| try_respond(&loan));
+    if let Err(err) = result {
+        log!("get_loan failed: {}", err);
+    }
+}
+
+/// Get what repaying an active loan costs right now
+///
+/// # Arguments
+/// * `loan_id` - Active loan
+///
+/// # Returns
+/// Principal plus interest (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn repayment_amount() {
+    if let Err(err) = execute_repayment_amount().and_then(|amount| try_respond(&amount)) {
+        log!("repayment_amount failed: {}", err);
+    }
+}
+
+/// List the loans an account borrowed or lent, oldest first
+///
+/// # Arguments
+/// * `account` - Borrower or lender
+/// * `offset` - Index of the first item
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `LoanPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn loans_of() {
+    if let Err(err) = execute_loans_of().and_then(|page| try_respond(&page)) {
+        log!("loans_of failed: {}", err);
+    }
+}
+
+/// Give notice that the market will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the market once the notice has run out (owner only)
+///
+/// Listing and funding stop for good. Open listings can still be
+/// cancelled, and active loans repaid or their collateral claimed.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the market is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Pause the market (owner only)
+///
+/// Listing and funding fail until `unpause`; cancellation, repayment and
+/// collateral claims stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the market is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use mock_crc20::MockCrc20;
+    use mock_crc721::MockCrc721;
+    use silica_contract_sdk::ffi::mock;
+    use silica_contract_sdk::testing::Reentry;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const PUNKS: &str = "0x0000000000000000000000000000000000000f01";
+    const USDC: &str = "0x0000000000000000000000000000000000000f02";
+    const NOW: u64 = 1_736_000_000;
+    const DAY: u64 = 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        mock::register_contract(PUNKS, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(USDC, "transfer_from", |_| Ok(Vec::new()));
+    }
+
+    /// Like `setup`, with working token contracts: Alice owns punk 42 and
+    /// Bob holds 10k USDC, both approved for this contract
+    fn setup_tokens() -> (MockCrc721, MockCrc20) {
+        setup();
+        let punks = MockCrc721::deploy(PUNKS);
+        punks.mint(ADDR_ALICE, 42);
+        punks.approve_for_all(ADDR_ALICE, CONTRACT);
+        let usdc = MockCrc20::deploy(USDC);
+        usdc.mint(ADDR_BOB, 10_000_000_000);
+        usdc.approve(ADDR_BOB, CONTRACT, u64::MAX);
+        (punks, usdc)
+    }
+
+    /// Alice asks for 10k USDC at 20% APR over 30 days against punk 42
+    fn list_punk() -> u64 {
+        call(
+            ADDR_ALICE,
+            list,
+            encode(&ListArgs {
+                collection: PUNKS.to_string(),
+                token_id: 42,
+                currency: USDC.to_string(),
+                principal: 10_000_000_000,
+                apr_bps: 2_000,
+                duration: 30 * DAY,
+            }),
+        )
+        .unwrap()
+    }
+
+    fn with_id(sender: &str, entrypoint: extern "C" fn(), loan_id: u64) {
+        call::<()>(sender, entrypoint, encode(&LoanIdArgs { loan_id }));
+    }
+
+    fn nft_moves() -> Vec<(String, String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.address == PUNKS)
+            .map(|c| {
+                let args: NftTransferFromArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.from, args.to, args.token_id)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn listing_escrows_and_funding_pays_the_borrower() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let loan_id = list_punk();
+        assert_eq!(
+            nft_moves(),
+            [(ADDR_ALICE.to_string(), CONTRACT.to_string(), 42)]
+        );
+
+        with_id(ADDR_ALICE, fund, loan_id);
+        assert_eq!(load_loan(loan_id).unwrap().status, LoanStatus::Listed);
+        mock::set_block_timestamp(NOW + 100);
+        with_id(ADDR_BOB, fund, loan_id);
+        let loan = load_loan(loan_id).unwrap();
+        assert_eq!(
+            (loan.status, loan.lender.as_deref(), loan.deadline),
+            (LoanStatus::Active, Some(ADDR_BOB), NOW + 100 + 30 * DAY)
+        );
+        let calls = mock::take_contract_calls();
+        let paid: TokenTransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(
+            (paid.from.as_str(), paid.to.as_str(), paid.amount),
+            (ADDR_BOB, ADDR_ALICE, 10_000_000_000)
+        );
+        // Funded loans cannot be cancelled or funded twice.
+        with_id(ADDR_ALICE, cancel, loan_id);
+        with_id(ADDR_BOB, fund, loan_id);
+        assert!(mock::take_contract_calls().is_empty());
+    }
+
+    #[test]
+    fn interest_accrues_for_the_elapsed_time_only() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let loan_id = list_punk();
+        with_id(ADDR_BOB, fund, loan_id);
+        let loan = load_loan(loan_id).unwrap();
+        assert_eq!(interest_due(&loan, NOW), 0);
+        // 20% a year on 10k for the full 30 days, rounded up.
+        assert_eq!(interest_due(&loan, NOW + 30 * DAY), 164_383_562);
+        // Capped at the loan duration; a single second still costs something.
+        assert_eq!(interest_due(&loan, NOW + 365 * DAY), 164_383_562);
+        assert_eq!(interest_due(&loan, NOW + 1), 64);
+
+        mock::set_block_timestamp(NOW + 10 * DAY);
+        let quoted = call::<u64>(ADDR_BOB, repayment_amount, encode(&LoanIdArgs { loan_id }));
+        assert_eq!(quoted, Some(10_000_000_000 + 54_794_521));
+    }
+
+    #[test]
+    fn repayment_returns_the_nft_and_pays_the_lender() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let loan_id = list_punk();
+        with_id(ADDR_BOB, fund, loan_id);
+        mock::set_block_timestamp(NOW + 10 * DAY);
+        mock::take_contract_calls();
+
+        let repay_as = |sender: &str| call::<u64>(sender, repay, encode(&LoanIdArgs { loan_id }));
+        assert_eq!(repay_as(ADDR_BOB), None);
+        let paid = repay_as(ADDR_ALICE).unwrap();
+        assert_eq!(paid, 10_054_794_521);
+        let calls = mock::take_contract_calls();
+        let repaid: TokenTransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(
+            (repaid.from.as_str(), repaid.to.as_str(), repaid.amount),
+            (ADDR_ALICE, ADDR_BOB, paid)
+        );
+        let returned: NftTransferFromArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!(
+            (
+                returned.from.as_str(),
+                returned.to.as_str(),
+                returned.token_id
+            ),
+            (CONTRACT, ADDR_ALICE, 42)
+        );
+        assert_eq!(load_loan(loan_id).unwrap().status, LoanStatus::Repaid);
+        assert_eq!(repay_as(ADDR_ALICE), None);
+        with_id(ADDR_BOB, claim_collateral, loan_id);
+        assert!(nft_moves().is_empty());
+    }
+
+    #[test]
+    fn pausing_stops_listings_and_funding_but_not_repayment() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        call::<()>(ADDR_OWNER, initialize, Vec::new());
+        let loan_id = list_punk();
+        with_id(ADDR_BOB, fund, loan_id);
+        let listed = list_punk();
+
+        call::<()>(ADDR_ALICE, pause, Vec::new());
+        assert!(!pausable::paused().unwrap());
+        call::<()>(ADDR_OWNER, pause, Vec::new());
+        assert_eq!(call::<bool>(ADDR_BOB, paused, Vec::new()), Some(true));
+
+        mock::take_contract_calls();
+        assert!(
+            call::<u64>(
+                ADDR_ALICE,
+                list,
+                encode(&ListArgs {
+                    collection: PUNKS.to_string(),
+                    token_id: 43,
+                    currency: USDC.to_string(),
+                    principal: 1,
+                    apr_bps: 0,
+                    duration: DAY,
+                }),
+            )
+            .is_none()
+        );
+        with_id(ADDR_BOB, fund, listed);
+        assert!(mock::take_contract_calls().is_empty());
+        assert_eq!(load_loan(listed).unwrap().status, LoanStatus::Listed);
+
+        // Open loans and listings still settle.
+        assert!(call::<u64>(ADDR_ALICE, repay, encode(&LoanIdArgs { loan_id })).is_some());
+        with_id(ADDR_ALICE, cancel, listed);
+        assert_eq!(load_loan(listed).unwrap().status, LoanStatus::Cancelled);
+
+        call::<()>(ADDR_OWNER, unpause, Vec::new());
+        assert!(!pausable::paused().unwrap());
+    }
+
+    #[test]
+    fn decommissioning_stops_new_loans_but_not_repayment() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        call::<()>(ADDR_OWNER, initialize, Vec::new());
+        let loan_id = list_punk();
+        with_id(ADDR_BOB, fund, loan_id);
+        let listed = list_punk();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call::<u64>(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        call::<()>(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        with_id(ADDR_BOB, fund, listed);
+        assert_eq!(load_loan(listed).unwrap().status, LoanStatus::Listed);
+        assert!(
+            call::<u64>(
+                ADDR_ALICE,
+                list,
+                encode(&ListArgs {
+                    collection: PUNKS.to_string(),
+                    token_id: 43,
+                    currency: USDC.to_string(),
+                    principal: 1,
+                    apr_bps: 0,
+                    duration: DAY,
+                }),
+            )
+            .is_none()
+        );
+
+        // Both sides of the market can still wind down.
+        assert!(call::<u64>(ADDR_ALICE, repay, encode(&LoanIdArgs { loan_id })).is_some());
+        with_id(ADDR_ALICE, cancel, listed);
+        assert_eq!(load_loan(listed).unwrap().status, LoanStatus::Cancelled);
+    }
+
+    #[test]
+    fn lenders_claim_collateral_after_default() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let loan_id = list_punk();
+        with_id(ADDR_BOB, fund, loan_id);
+        let deadline = load_loan(loan_id).unwrap().deadline;
+        mock::take_contract_calls();
+
+        mock::set_block_timestamp(deadline);
+        with_id(ADDR_BOB, claim_collateral, loan_id);
+        assert!(nft_moves().is_empty());
+
+        mock::set_block_timestamp(deadline + 1);
+        let late = call::<u64>(ADDR_ALICE, repay, encode(&LoanIdArgs { loan_id }));
+        assert_eq!(late, None);
+        with_id(ADDR_ALICE, claim_collateral, loan_id);
+        assert!(nft_moves().is_empty());
+        with_id(ADDR_BOB, claim_collateral, loan_id);
+        assert_eq!(
+            nft_moves(),
+            [(CONTRACT.to_string(), ADDR_BOB.to_string(), 42)]
+        );
+        assert_eq!(load_loan(loan_id).unwrap().status, LoanStatus::Defaulted);
+    }
+
+    #[test]
+    fn borrowers_cancel_listings_and_accounts_index_loans() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let funded = list_punk();
+        let cancelled = list_punk();
+        with_id(ADDR_BOB, fund, funded);
+        mock::take_contract_calls();
+
+        with_id(ADDR_BOB, cancel, cancelled);
+        assert!(nft_moves().is_empty());
+        with_id(ADDR_ALICE, cancel, cancelled);
+        assert_eq!(
+            nft_moves(),
+            [(CONTRACT.to_string(), ADDR_ALICE.to_string(), 42)]
+        );
+        assert_eq!(load_loan(cancelled).unwrap().status, LoanStatus::Cancelled);
+        with_id(ADDR_BOB, fund, cancelled);
+        assert_eq!(load_loan(cancelled).unwrap().lender, None);
+
+        let page = |account: &str| {
+            call::<LoanPage>(
+                ADDR_BOB,
+                loans_of,
+                encode(&LoansOfArgs {
+                    account: account.to_string(),
+                    offset: 0,
+                    limit: 10,
+                }),
+            )
+            .unwrap()
+        };
+        assert_eq!(page(ADDR_ALICE).items, [funded, cancelled]);
+        assert_eq!(page(ADDR_BOB).items, [funded]);
+
+        let bad_terms = call::<u64>(
+            ADDR_ALICE,
+            list,
+            encode(&ListArgs {
+                collection: PUNKS.to_string(),
+                token_id: 7,
+                currency: USDC.to_string(),
+                principal: 1,
+                apr_bps: 2_000,
+                duration: DAY - 1,
+            }),
+        );
+        assert_eq!(bad_terms, None);
+    }
+
+    #[test]
+    fn loans_settle_against_real_ledgers() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let (punks, usdc) = setup_tokens();
+        let loan_id = list_punk();
+        assert_eq!(punks.owner_of(42).as_deref(), Some(CONTRACT));
+        with_id(ADDR_BOB, fund, loan_id);
+        assert_eq!(usdc.balance_of(ADDR_ALICE), 10_000_000_000);
+
+        mock::set_block_timestamp(NOW + 10 * DAY);
+        // Alice finds the interest elsewhere and approves the repayment.
+        usdc.mint(ADDR_ALICE, 54_794_521);
+        usdc.approve(ADDR_ALICE, CONTRACT, u64::MAX);
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, repay, encode(&LoanIdArgs { loan_id })),
+            Some(10_054_794_521)
+        );
+        assert_eq!(usdc.balance_of(ADDR_ALICE), 0);
+        assert_eq!(usdc.balance_of(ADDR_BOB), 10_054_794_521);
+        assert_eq!(punks.owner_of(42).as_deref(), Some(ADDR_ALICE));
+    }
+
+    #[test]
+    fn hostile_tokens_cannot_fake_escrow_or_reenter() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let (punks, usdc) = setup_tokens();
+        punks.revert_transfers(true);
+        assert_eq!(
+            call::<u64>(
+                ADDR_ALICE,
+                list,
+                encode(&ListArgs {
+                    collection: PUNKS.to_string(),
+                    token_id: 42,
+                    currency: USDC.to_string(),
+                    principal: 1,
+                    apr_bps: 0,
+                    duration: DAY,
+                }),
+            ),
+            None
+        );
+        assert_eq!(punks.owner_of(42).as_deref(), Some(ADDR_ALICE));
+
+        punks.revert_transfers(false);
+        let first = list_punk();
+        punks.mint(ADDR_ALICE, 43);
+        let second = call::<u64>(
+            ADDR_ALICE,
+            list,
+            encode(&ListArgs {
+                collection: PUNKS.to_string(),
+                token_id: 43,
+                currency: USDC.to_string(),
+                principal: 1,
+                apr_bps: 0,
+                duration: DAY,
+            }),
+        )
+        .unwrap();
+        // The currency calls back into `fund` while paying out the first loan.
+        usdc.reenter_on_transfer(Reentry::new(
+            ADDR_BOB,
+            fund,
+            &LoanIdArgs { loan_id: second },
+        ));
+        with_id(ADDR_BOB, fund, first);
+        let reentries = usdc.reentries();
+        assert_eq!(reentries.len(), 1);
+        assert_eq!(
+            reentries[0].as_deref(),
+            Some("fund failed: Reentrancy attack detected")
+        );
+        assert_eq!(load_loan(first).unwrap().status, LoanStatus::Active);
+        assert_eq!(load_loan(second).unwrap().status, LoanStatus::Listed);
+        assert_eq!(usdc.balance_of(ADDR_ALICE), 10_000_000_000);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "nft-lending-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! Reference consumer contract keeping a guarded price for one pair
+
+use crate::guard::{self, GuardConfig, GuardedPrice, PriceSource};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const STATE_KEY: &str = "consumer_state";
+const LAST_PRICE_KEY: &str = "last_price";
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Consumer configuration stored at initialization
+#[derive(Serialize, Deserialize)]
+pub struct ConsumerState {
+    pub owner: String,
+    pub guard: GuardConfig,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetFallbackArgs {
+    fallback: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetLimitsArgs {
+    max_staleness: u64,
+    max_deviation_bps: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_state() -> ContractResult<ConsumerState> {
+    storage()
+        .get::<ConsumerState>(STATE_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Consumer not initialized".to_string()))
+}
+
+fn save_state(state: &ConsumerState) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(STATE_KEY, state)
+}
+
+fn load_owned_state() -> ContractResult<ConsumerState> {
+    let state = load_state()?;
+    if context().sender() != state.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(state)
+}
+
+fn last_price() -> ContractResult<Option<GuardedPrice>> {
+    storage().get::<GuardedPrice>(LAST_PRICE_KEY)
+}
+
+fn source_label(source: PriceSource) -> &'static str {
+    match source {
+        PriceSource::Primary => "primary",
+        PriceSource::Fallback => "fallback",
+    }
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let config: GuardConfig = read_args()?;
+    guard::validate_config(&config)?;
+
+    if storage().has(STATE_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Consumer already initialized".to_string(),
+        ));
+    }
+
+    let ctx = context();
+    let owner = ctx.sender();
+    validation::validate_address(owner)?;
+
+    event!("ConsumerInitialized", owner: owner, pair: config.pair, primary: config.primary);
+    save_state(&ConsumerState {
+        owner: owner.to_string(),
+        guard: config,
+    })
+}
+
+fn execute_refresh_price() -> ContractResult<GuardedPrice> {
+    let state = load_state()?;
+    let reference = last_price()?.map(|accepted| accepted.price);
+    let price = guard::read_guarded_price(&state.guard, reference, context().block_timestamp())?;
+
+    let mut store = storage();
+    store.set(LAST_PRICE_KEY, &price)?;
+
+    event!("PriceAccepted",
+        pair: state.guard.pair,
+        price: price.price,
+        round_id: price.round_id,
+        source: source_label(price.source)
+    );
+    Ok(price)
+}
+
+fn execute_latest_price() -> ContractResult<GuardedPrice> {
+    let state = load_state()?;
+    let price = last_price()?
+        .ok_or_else(|| ContractError::InvalidArgument("No accepted price".to_string()))?;
+
+    let age = context()
+        .block_timestamp()
+        .saturating_sub(price.observed_at);
+    if age > state.guard.max_staleness {
+        return Err(ContractError::Custom("Accepted price is stale".to_string()));
+    }
+
+    try_respond(&price)?;
+    Ok(price)
+}
+
+fn execute_set_fallback() -> ContractResult<()> {
+    let mut state = load_owned_state()?;
+    let args: SetFallbackArgs = read_args()?;
+
+    let previous = core::mem::replace(&mut state.guard.fallback, args.fallback);
+    guard::validate_config(&state.guard)?;
+    save_state(&state)?;
+
+    event!("FallbackUpdated",
+        fallback: state.guard.fallback.as_deref().unwrap_or("none")
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "fallback",
+        previous.unwrap_or_default(),
+        state.guard.fallback.as_deref().unwrap_or_default(),
+    )?;
+    Ok(())
+}
+
+fn execute_set_limits() -> ContractResult<()> {
+    let mut state = load_owned_state()?;
+    let args: SetLimitsArgs = read_args()?;
+
+    let previous = (state.guard.max_staleness, state.guard.max_deviation_bps);
+    state.guard.max_staleness = args.max_staleness;
+    state.guard.max_deviation_bps = args.max_deviation_bps;
+    guard::validate_config(&state.guard)?;
+    save_state(&state)?;
+
+    event!("LimitsUpdated",
+        max_staleness: args.max_staleness,
+        max_deviation_bps: args.max_deviation_bps
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "max_staleness",
+        previous.0,
+        args.max_staleness,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "max_deviation_bps",
+        previous.1,
+        args.max_deviation_bps,
+    )?;
+    Ok(())
+}
+
+fn execute_reset_reference() -> ContractResult<()> {
+    let state = load_owned_state()?;
+    let previous = last_price()?;
+    let mut store = storage();
+    store.remove(LAST_PRICE_KEY)?;
+
+    event!("ReferenceReset", pair: state.guard.pair);
+    admin_log::record(
+        "reset_reference",
+        &state.guard.pair,
+        previous
+            .map(|price| price.price.to_string())
+            .unwrap_or_default(),
+        "",
+    )?;
+    Ok(())
+}
+
+/// Initialize the consumer
+///
+/// # Arguments
+/// * `primary` - Primary oracle contract address
+/// * `fallback` - Optional fallback oracle contract address
+/// * `pair` - Pair identifier queried on both oracles
+/// * `max_staleness` - Maximum accepted price age in seconds
+/// * `max_deviation_bps` - Maximum move per update versus the last accepted price
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Pull a new price through the guards and store it as the reference
+#[unsafe(no_mangle)]
+pub extern "C" fn refresh_price() {
+    if let Err(err) = execute_refresh_price() {
+        log!("refresh_price failed: {}", err);
+    }
+}
+
+/// Query the last accepted price, failing if it has gone stale
+///
+/// # Returns
+/// [`GuardedPrice`] with the feed that produced it
+#[unsafe(no_mangle)]
+pub extern "C" fn latest_price() {
+    if let Err(err) = execute_latest_price() {
+        log!("latest_price failed: {}", err);
+    }
+}
+
+/// Replace or clear the fallback oracle (owner only)
+///
+/// # Arguments
+/// * `fallback` - New fallback oracle address, or `None` to disable
+#[unsafe(no_mangle)]
+pub extern "C" fn set_fallback() {
+    if let Err(err) = execute_set_fallback() {
+        log!("set_fallback failed: {}", err);
+    }
+}
+
+/// Update staleness and deviation limits (owner only)
+///
+/// # Arguments
+/// * `max_staleness` - Maximum accepted price age in seconds
+/// * `max_deviation_bps` - Maximum move per update in basis points
+#[unsafe(no_mangle)]
+pub extern "C" fn set_limits() {
+    if let Err(err) = execute_set_limits() {
+        log!("set_limits failed: {}", err);
+    }
+}
+
+/// Drop the reference price so the next refresh accepts any move (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn reset_reference() {
+    if let Err(err) = execute_reset_reference() {
+        log!("reset_reference failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::guard::OracleRound;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_KEEPER: &str = "0x0000000000000000000000000000000000000b02";
+    const PRIMARY: &str = "0x00000000000000000000000000000000000000c1";
+    const FALLBACK: &str = "0x00000000000000000000000000000000000000c2";
+    const PAIR: &str = "CHERT/USD";
+    const NOW: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn serve_round(oracle: &str, round_id: u64, price: u64, observed_at: u64) {
+        let response = encode(&OracleRound {
+            pair: PAIR.to_string(),
+            round_id,
+            price,
+            decimals: 8,
+            observed_at,
+            updated_at: observed_at,
+            updated_height: 1,
+            submissions: 3,
+            age: NOW.saturating_sub(observed_at),
+            heartbeat: 600,
+            stale: NOW.saturating_sub(observed_at) > 600,
+        });
+        mock::register_contract(oracle, "latest_round", move |_| Ok(response.clone()));
+    }
+
+    fn init_default(fallback: Option<&str>) {
+        mock::reset();
+        mock::set_sender(ADDR_OWNER);
+        mock::set_contract_address("consumer_contract");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        mock::set_call_data(&encode(&GuardConfig {
+            primary: PRIMARY.to_string(),
+            fallback: fallback.map(str::to_string),
+            pair: PAIR.to_string(),
+            max_staleness: 300,
+            max_deviation_bps: 1_000,
+        }));
+        initialize();
+        mock::take_events();
+    }
+
+    fn refresh_as_keeper() {
+        mock::set_sender(ADDR_KEEPER);
+        refresh_price();
+    }
+
+    #[test]
+    fn refresh_stores_fresh_primary_price() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(Some(FALLBACK));
+        serve_round(PRIMARY, 7, 2_000, NOW - 30);
+
+        refresh_as_keeper();
+
+        let accepted = last_price().unwrap().expect("price stored");
+        assert_eq!(accepted.price, 2_000);
+        assert_eq!(accepted.round_id, 7);
+        assert_eq!(accepted.source, PriceSource::Primary);
+
+        latest_price();
+        let decoded: GuardedPrice = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(decoded, accepted);
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].address, PRIMARY);
+    }
+
+    #[test]
+    fn stale_primary_falls_back() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(Some(FALLBACK));
+        serve_round(PRIMARY, 3, 2_000, NOW - 1_000);
+        serve_round(FALLBACK, 9, 2_010, NOW - 10);
+
+        refresh_as_keeper();
+
+        let accepted = last_price().unwrap().expect("price stored");
+        assert_eq!(accepted.price, 2_010);
+        assert_eq!(accepted.source, PriceSource::Fallback);
+    }
+
+    #[test]
+    fn deviation_limit_blocks_until_reference_reset() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(None);
+        serve_round(PRIMARY, 1, 1_000, NOW);
+        refresh_as_keeper();
+
+        serve_round(PRIMARY, 2, 1_200, NOW);
+        refresh_as_keeper();
+        assert_eq!(last_price().unwrap().unwrap().round_id, 1);
+
+        mock::set_sender(ADDR_KEEPER);
+        reset_reference();
+        assert!(last_price().unwrap().is_some(), "only the owner may reset");
+
+        mock::set_sender(ADDR_OWNER);
+        reset_reference();
+        refresh_as_keeper();
+        assert_eq!(last_price().unwrap().unwrap().price, 1_200);
+    }
+
+    #[test]
+    fn latest_price_rejects_expired_reference() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(None);
+        serve_round(PRIMARY, 1, 1_000, NOW);
+        refresh_as_keeper();
+
+        mock::set_block_timestamp(NOW + 301);
+        latest_price();
+        assert!(mock::take_return_data().is_empty());
+    }
+
+    #[test]
+    fn owner_can_rotate_fallback_and_limits() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(None);
+
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&SetFallbackArgs {
+            fallback: Some(PRIMARY.to_string()),
+        }));
+        set_fallback();
+        assert!(load_state().unwrap().guard.fallback.is_none());
+
+        mock::set_call_data(&encode(&SetFallbackArgs {
+            fallback: Some(FALLBACK.to_string()),
+        }));
+        set_fallback();
+        mock::set_call_data(&encode(&SetLimitsArgs {
+            max_staleness: 120,
+            max_deviation_bps: 250,
+        }));
+        set_limits();
+
+        let state = load_state().unwrap();
+        assert_eq!(state.guard.fallback.as_deref(), Some(FALLBACK));
+        assert_eq!(state.guard.max_staleness, 120);
+        assert_eq!(state.guard.max_deviation_bps, 250);
+        // The fallback and both limits; the refused fallback is not logged.
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(Some(FALLBACK));
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "oracle-consumer-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Embeddable oracle guards
+//!
+//! Helpers for reading the oracle contract safely: every price must be fresh,
+//! must not move further than a configured bound from the caller's reference
+//! price, and an optional fallback feed is consulted when the primary fails.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+/// Denominator for basis-point deviation limits
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+const LATEST_ROUND_METHOD: &str = "latest_round";
+
+/// Mirror of the oracle contract's `RoundView` response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OracleRound {
+    pub pair: String,
+    pub round_id: u64,
+    pub price: u64,
+    pub decimals: u8,
+    pub observed_at: u64,
+    pub updated_at: u64,
+    pub updated_height: u64,
+    pub submissions: u32,
+    pub age: u64,
+    pub heartbeat: u64,
+    pub stale: bool,
+}
+
+/// Feed that produced an accepted price
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceSource {
+    Primary,
+    Fallback,
+}
+
+/// Guard parameters for a single pair
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GuardConfig {
+    pub primary: String,
+    pub fallback: Option<String>,
+    pub pair: String,
+    pub max_staleness: u64,
+    pub max_deviation_bps: u64,
+}
+
+/// Price that passed every guard
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuardedPrice {
+    pub price: u64,
+    pub decimals: u8,
+    pub round_id: u64,
+    pub observed_at: u64,
+    pub source: PriceSource,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LatestRoundArgs {
+    pair: String,
+}
+
+/// Validate guard parameters before they are stored.
+pub fn validate_config(config: &GuardConfig) -> ContractResult<()> {
+    validation::validate_address(&config.primary)?;
+    if let Some(fallback) = &config.fallback {
+        validation::validate_address(fallback)?;
+        if *fallback == config.primary {
+            return Err(ContractError::InvalidArgument(
+                "Fallback oracle must differ from primary".to_string(),
+            ));
+        }
+    }
+    validation::validate_non_empty(&config.pair, "pair")?;
+    validation::validate_positive_amount(config.max_staleness)?;
+    validation::validate_range(config.max_deviation_bps, 1, BPS_DENOMINATOR)
+}
+
+/// Query `latest_round` on an oracle contract.
+pub fn fetch_round(oracle: &str, pair: &str) -> ContractResult<OracleRound> {
+    let args = postcard::to_allocvec(&LatestRoundArgs {
+        pair: pair.to_string(),
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    let response = context().call_contract(oracle, LATEST_ROUND_METHOD, &args)?;
+    if response.is_empty() {
+        return Err(ContractError::ContractCallFailed(
+            "Oracle returned no round".to_string(),
+        ));
+    }
+
+    let round: OracleRound =
+        postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)?;
+    if round.pair != pair {
+        return Err(ContractError::ContractCallFailed(
+            "Oracle answered for a different pair".to_string(),
+        ));
+    }
+    Ok(round)
+}
+
+/// Reject rounds the oracle flags as stale or that are older than `max_staleness`.
+pub fn ensure_fresh(round: &OracleRound, max_staleness: u64, now: u64) -> ContractResult<()> {
+    if round.stale || now.saturating_sub(round.observed_at) > max_staleness {
+        return Err(ContractError::Custom("Oracle price is stale".to_string()));
+    }
+    Ok(())
+}
+
+/// Absolute deviation of `price` from `reference` in basis points, saturating at `u64::MAX`.
+pub fn deviation_bps(reference: u64, price: u64) -> u64 {
+    if reference == 0 {
+        return if price == 0 { 0 } else { u64::MAX };
+    }
+    let delta = reference.abs_diff(price) as u128;
+    let bps = delta * BPS_DENOMINATOR as u128 / reference as u128;
+    u64::try_from(bps).unwrap_or(u64::MAX)
+}
+
+/// Reject prices that move more than `max_bps` away from the reference, if any.
+pub fn ensure_within_deviation(
+    reference: Option<u64>,
+    price: u64,
+    max_bps: u64,
+) -> ContractResult<()> {
+    match reference {
+        Some(reference) if deviation_bps(reference, price) > max_bps => Err(ContractError::Custom(
+            "Oracle price deviation exceeds limit".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn read_source(
+    oracle: &str,
+    source: PriceSource,
+    config: &GuardConfig,
+    reference: Option<u64>,
+    now: u64,
+) -> ContractResult<GuardedPrice> {
+    let round = fetch_round(oracle, &config.pair)?;
+    validation::validate_positive_amount(round.price)?;
+    ensure_fresh(&round, config.max_staleness, now)?;
+    ensure_within_deviation(reference, round.price, config.max_deviation_bps)?;
+
+    Ok(GuardedPrice {
+        price: round.price,
+        decimals: round.decimals,
+        round_id: round.round_id,
+        observed_at: round.observed_at,
+        source,
+    })
+}
+
+/// Read a price that passes the staleness and deviation guards.
+///
+/// The primary oracle is tried first; if it fails any check and a fallback is
+/// configured, the fallback is held to the same guards. The fallback's error is
+/// returned when both fail.
+pub fn read_guarded_price(
+    config: &GuardConfig,
+    reference: Option<u64>,
+    now: u64,
+) -> ContractResult<GuardedPrice> {
+    let primary = read_source(
+        &config.primary,
+        PriceSource::Primary,
+        config,
+        reference,
+        now,
+    );
+    match (&primary, &config.fallback) {
+        (Err(_), Some(fallback)) => {
+            read_source(fallback, PriceSource::Fallback, config, reference, now)
+        }
+        _ => primary,
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn round(price: u64, observed_at: u64, stale: bool) -> OracleRound {
+        OracleRound {
+            pair: "CHERT/USD".to_string(),
+            round_id: 1,
+            price,
+            decimals: 8,
+            observed_at,
+            updated_at: observed_at,
+            updated_height: 1,
+            submissions: 3,
+            age: 0,
+            heartbeat: 600,
+            stale,
+        }
+    }
+
+    #[test]
+    fn deviation_is_symmetric_in_magnitude() {
+        assert_eq!(deviation_bps(1_000, 1_100), 1_000);
+        assert_eq!(deviation_bps(1_000, 900), 1_000);
+        assert_eq!(deviation_bps(1_000, 1_000), 0);
+        assert_eq!(deviation_bps(0, 1), u64::MAX);
+        assert_eq!(deviation_bps(1, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn deviation_guard_ignores_missing_reference() {
+        assert!(ensure_within_deviation(None, 5_000, 1).is_ok());
+        assert!(ensure_within_deviation(Some(1_000), 1_050, 500).is_ok());
+        assert!(ensure_within_deviation(Some(1_000), 1_051, 500).is_err());
+    }
+
+    #[test]
+    fn freshness_honors_oracle_flag_and_local_bound() {
+        assert!(ensure_fresh(&round(1, 1_000, false), 60, 1_060).is_ok());
+        assert!(ensure_fresh(&round(1, 1_000, false), 60, 1_061).is_err());
+        assert!(ensure_fresh(&round(1, 1_000, true), 60, 1_000).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+//! Oracle Consumer
+//!
+//! Reference implementation of safe oracle usage on Chert. The [`guard`]
+//! module holds the reusable checks (staleness, per-update deviation, fallback
+//! feed) that DeFi contracts embed; the `entrypoints` feature additionally
+//! exports a small contract that maintains a guarded price for one pair.
+//!
+//! ## Embedding
+//! ```toml
+//! oracle-consumer-contract = { path = "../oracle-consumer", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use oracle_consumer_contract::guard::{read_guarded_price, GuardConfig};
+//!
+//! let price = read_guarded_price(&config, last_accepted_price, ctx.block_timestamp())?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod guard;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
@@ -0,0 +1,906 @@
+//! Oracle Price Feed
+//!
+//! Aggregates signed price observations from an authorized reporter set into
+//! per-pair rounds that DeFi contracts on Chert (lending, stablecoin, AMM
+//! guards) consume through `latest_round`.
+//!
+//! ## Features
+//! - Owner-managed reporter set keyed by Ed25519 public keys
+//! - Signed submissions over (oracle, pair, round, price, timestamp)
+//! - Median aggregation once a round reaches the submission quorum
+//! - Per-pair decimals and heartbeat used to flag stale rounds
+//! - Historical round lookup by id
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "config";
+const REPORTERS_PREFIX: &str = "reporters";
+const PAIRS_PREFIX: &str = "pairs";
+const ROUNDS_PREFIX: &str = "rounds";
+const LATEST_ROUND_PREFIX: &str = "latest_round";
+const PENDING_PREFIX: &str = "pending";
+const REPORT_DOMAIN: &str = "chert-oracle-report-v1";
+const MAX_REPORTERS: u32 = 31;
+const MAX_FUTURE_DRIFT_SECS: u64 = 60;
+const MAX_PAIR_LEN: usize = 32;
+/// Role the admin log records reporter changes under
+const REPORTER_ROLE: &str = "reporter";
+const SIGNATURE_LEN: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Oracle-wide configuration stored at initialization
+#[derive(Serialize, Deserialize)]
+pub struct OracleConfig {
+    pub owner: String,
+    pub min_submissions: u32,
+    pub max_report_age: u64,
+    pub reporter_count: u32,
+}
+
+/// Registered reporter identity
+#[derive(Serialize, Deserialize)]
+pub struct Reporter {
+    pub public_key: [u8; 32],
+}
+
+/// Per-pair feed configuration
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PairConfig {
+    pub decimals: u8,
+    pub heartbeat: u64,
+}
+
+/// `decimals=<d> heartbeat=<s>`, as recorded in the admin log
+impl core::fmt::Display for PairConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "decimals={} heartbeat={}", self.decimals, self.heartbeat)
+    }
+}
+
+/// Aggregated price round as persisted in storage
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoundData {
+    pub round_id: u64,
+    pub price: u64,
+    pub observed_at: u64,
+    pub updated_at: u64,
+    pub updated_height: u64,
+    pub submissions: u32,
+}
+
+/// Round data with staleness metadata, returned by `latest_round` and `get_round`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoundView {
+    pub pair: String,
+    pub round_id: u64,
+    pub price: u64,
+    pub decimals: u8,
+    pub observed_at: u64,
+    pub updated_at: u64,
+    pub updated_height: u64,
+    pub submissions: u32,
+    pub age: u64,
+    pub heartbeat: u64,
+    pub stale: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Submission {
+    reporter: String,
+    price: u64,
+    timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    min_submissions: u32,
+    max_report_age: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AddReporterArgs {
+    reporter: String,
+    public_key: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct RemoveReporterArgs {
+    reporter: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetMinSubmissionsArgs {
+    min_submissions: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ConfigurePairArgs {
+    pair: String,
+    decimals: u8,
+    heartbeat: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SubmitPriceArgs {
+    pair: String,
+    round_id: u64,
+    price: u64,
+    timestamp: u64,
+    signature: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LatestRoundArgs {
+    pair: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetRoundArgs {
+    pair: String,
+    round_id: u64,
+}
+
+/// Build the canonical message a reporter signs for a price observation.
+///
+/// Off-chain reporters must produce byte-identical payloads, so the layout is
+/// the postcard encoding of `(domain, oracle, pair, round_id, price, timestamp)`.
+pub fn report_message(
+    oracle: &str,
+    pair: &str,
+    round_id: u64,
+    price: u64,
+    timestamp: u64,
+) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(&(REPORT_DOMAIN, oracle, pair, round_id, price, timestamp))
+        .map_err(|_| ContractError::SerializationFailed)
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<OracleConfig> {
+    storage()
+        .get::<OracleConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Oracle not initialized".to_string()))
+}
+
+fn save_config(config: &OracleConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn require_owner(config: &OracleConfig) -> ContractResult<()> {
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn validate_pair(pair: &str) -> ContractResult<()> {
+    validation::validate_non_empty(pair, "pair")?;
+    if pair.len() > MAX_PAIR_LEN {
+        return Err(ContractError::InvalidArgument(
+            "Pair identifier too long".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn reporters() -> Map<String, Reporter> {
+    Map::new(REPORTERS_PREFIX)
+}
+
+fn pairs() -> Map<String, PairConfig> {
+    Map::new(PAIRS_PREFIX)
+}
+
+fn rounds() -> Map<(String, u64), RoundData> {
+    Map::new(ROUNDS_PREFIX)
+}
+
+fn latest_round_ids() -> Map<String, u64> {
+    Map::new(LATEST_ROUND_PREFIX)
+}
+
+fn pending_submissions() -> Map<String, Vec<Submission>> {
+    Map::new(PENDING_PREFIX)
+}
+
+fn load_pair(pair: &str) -> ContractResult<PairConfig> {
+    pairs()
+        .get(&pair.to_string())?
+        .ok_or_else(|| ContractError::InvalidArgument("Pair not configured".to_string()))
+}
+
+fn latest_round_id(pair: &str) -> ContractResult<u64> {
+    Ok(latest_round_ids().get(&pair.to_string())?.unwrap_or(0))
+}
+
+/// Median of the collected prices; even-sized sets average the middle pair
+/// without overflowing.
+fn median(values: &mut [u64]) -> u64 {
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[mid]
+    } else {
+        let (a, b) = (values[mid - 1], values[mid]);
+        a / 2 + b / 2 + (a % 2 + b % 2) / 2
+    }
+}
+
+fn build_view(pair: &str, config: &PairConfig, round: RoundData, now: u64) -> RoundView {
+    let age = now.saturating_sub(round.observed_at);
+    RoundView {
+        pair: pair.to_string(),
+        round_id: round.round_id,
+        price: round.price,
+        decimals: config.decimals,
+        observed_at: round.observed_at,
+        updated_at: round.updated_at,
+        updated_height: round.updated_height,
+        submissions: round.submissions,
+        age,
+        heartbeat: config.heartbeat,
+        stale: age > config.heartbeat,
+    }
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let args: InitializeArgs = read_args()?;
+    validation::validate_range(args.min_submissions as u64, 1, MAX_REPORTERS as u64)?;
+    validation::validate_positive_amount(args.max_report_age)?;
+
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Oracle already initialized".to_string(),
+        ));
+    }
+
+    let ctx = context();
+    let owner = ctx.sender();
+    validation::validate_address(owner)?;
+
+    save_config(&OracleConfig {
+        owner: owner.to_string(),
+        min_submissions: args.min_submissions,
+        max_report_age: args.max_report_age,
+        reporter_count: 0,
+    })?;
+
+    event!("OracleInitialized", owner: owner, min_submissions: args.min_submissions);
+    Ok(())
+}
+
+fn execute_add_reporter() -> ContractResult<()> {
+    let mut config = load_config()?;
+    require_owner(&config)?;
+    let args: AddReporterArgs = read_args()?;
+    validation::validate_address(&args.reporter)?;
+
+    let mut reporters = reporters();
+    if reporters.contains_key(&args.reporter)? {
+        return Err(ContractError::InvalidArgument(
+            "Reporter already registered".to_string(),
+        ));
+    }
+    if config.reporter_count >= MAX_REPORTERS {
+        return Err(ContractError::InvalidArgument(
+            "Reporter set is full".to_string(),
+        ));
+    }
+
+    reporters.set(
+        &args.reporter,
+        &Reporter {
+            public_key: args.public_key,
+        },
+    )?;
+    config.reporter_count = safe_math::add(config.reporter_count as u64, 1)? as u32;
+    save_config(&config)?;
+    admin_log::record(admin_log::GRANT_ROLE, &args.reporter, "", REPORTER_ROLE)?;
+
+    event!("ReporterAdded", reporter: args.reporter);
+    Ok(())
+}
+
+fn execute_remove_reporter() -> ContractResult<()> {
+    let mut config = load_config()?;
+    require_owner(&config)?;
+    let args: RemoveReporterArgs = read_args()?;
+
+    let mut reporters = reporters();
+    if !reporters.contains_key(&args.reporter)? {
+        return Err(ContractError::InvalidArgument(
+            "Reporter not registered".to_string(),
+        ));
+    }
+    if config.reporter_count <= config.min_submissions {
+        return Err(ContractError::InvalidArgument(
+            "Removal would make quorum unreachable".to_string(),
+        ));
+    }
+
+    reporters.remove(&args.reporter)?;
+    config.reporter_count -= 1;
+    save_config(&config)?;
+    admin_log::record(admin_log::REVOKE_ROLE, &args.reporter, REPORTER_ROLE, "")?;
+
+    event!("ReporterRemoved", reporter: args.reporter);
+    Ok(())
+}
+
+fn execute_set_min_submissions() -> ContractResult<()> {
+    let mut config = load_config()?;
+    require_owner(&config)?;
+    let args: SetMinSubmissionsArgs = read_args()?;
+    validation::validate_range(
+        args.min_submissions as u64,
+        1,
+        config.reporter_count.max(1) as u64,
+    )?;
+
+    let previous = config.min_submissions;
+    config.min_submissions = args.min_submissions;
+    save_config(&config)?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "min_submissions",
+        previous,
+        args.min_submissions,
+    )?;
+
+    event!("MinSubmissionsUpdated", previous: previous, current: args.min_submissions);
+    Ok(())
+}
+
+fn execute_configure_pair() -> ContractResult<()> {
+    let config = load_config()?;
+    require_owner(&config)?;
+    let args: ConfigurePairArgs = read_args()?;
+    validate_pair(&args.pair)?;
+    validation::validate_positive_amount(args.heartbeat)?;
+
+    let mut pairs = pairs();
+    let previous = pairs
+        .get(&args.pair)?
+        .map(|pair| pair.to_string())
+        .unwrap_or_default();
+    let pair = PairConfig {
+        decimals: args.decimals,
+        heartbeat: args.heartbeat,
+    };
+    pairs.set(&args.pair, &pair)?;
+    admin_log::record("configure_pair", &args.pair, previous, pair)?;
+
+    event!("PairConfigured", pair: args.pair, decimals: args.decimals, heartbeat: args.heartbeat);
+    Ok(())
+}
+
+fn execute_submit_price() -> ContractResult<()> {
+    let config = load_config()?;
+    let ctx = context();
+    let reporter_addr = ctx.sender().to_string();
+    let args: SubmitPriceArgs = read_args()?;
+    validate_pair(&args.pair)?;
+    validation::validate_positive_amount(args.price)?;
+    load_pair(&args.pair)?;
+
+    let reporter = reporters()
+        .get(&reporter_addr)?
+        .ok_or(ContractError::Unauthorized)?;
+
+    let open_round = safe_math::add(latest_round_id(&args.pair)?, 1)?;
+    if args.round_id != open_round {
+        return Err(ContractError::InvalidArgument(
+            "Round is not open for submissions".to_string(),
+        ));
+    }
+
+    let now = ctx.block_timestamp();
+    if args.timestamp > safe_math::add(now, MAX_FUTURE_DRIFT_SECS)? {
+        return Err(ContractError::InvalidArgument(
+            "Observation timestamp is in the future".to_string(),
+        ));
+    }
+    let oldest_accepted = safe_math::saturating_sub(now, config.max_report_age);
+    if args.timestamp < oldest_accepted {
+        return Err(ContractError::InvalidArgument(
+            "Observation is too old".to_string(),
+        ));
+    }
+
+    let signature: [u8; SIGNATURE_LEN] = args
+        .signature
+        .as_slice()
+        .try_into()
+        .map_err(|_| ContractError::InvalidSignature)?;
+    let message = report_message(
+        ctx.contract_address(),
+        &args.pair,
+        args.round_id,
+        args.price,
+        args.timestamp,
+    )?;
+    if !crypto::verify_signature(&reporter.public_key, &message, &signature)? {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    let mut pending = pending_submissions();
+    let mut submissions = pending.get(&args.pair)?.unwrap_or_default();
+
+    // Drop observations that aged out or whose reporter was removed so a
+    // round that stalls below quorum cannot finalize with stale data.
+    let registered = reporters();
+    submissions.retain(|entry| {
+        entry.timestamp >= oldest_accepted
+            && entry.reporter != reporter_addr
+            && registered.contains_key(&entry.reporter).unwrap_or(false)
+    });
+    submissions.push(Submission {
+        reporter: reporter_addr.clone(),
+        price: args.price,
+        timestamp: args.timestamp,
+    });
+
+    event!("PriceSubmitted",
+        pair: args.pair,
+        round_id: args.round_id,
+        reporter: reporter_addr,
+        price: args.price
+    );
+
+    if (submissions.len() as u32) < config.min_submissions {
+        return pending.set(&args.pair, &submissions);
+    }
+
+    let mut prices: Vec<u64> = submissions.iter().map(|entry| entry.price).collect();
+    let mut timestamps: Vec<u64> = submissions.iter().map(|entry| entry.timestamp).collect();
+    let round = RoundData {
+        round_id: args.round_id,
+        price: median(&mut prices),
+        observed_at: median(&mut timestamps),
+        updated_at: now,
+        updated_height: ctx.block_height(),
+        submissions: submissions.len() as u32,
+    };
+
+    rounds().set(&(args.pair.clone(), args.round_id), &round)?;
+    latest_round_ids().set(&args.pair, &args.round_id)?;
+    pending.remove(&args.pair)?;
+
+    event!("RoundFinalized",
+        pair: args.pair,
+        round_id: round.round_id,
+        price: round.price,
+        observed_at: round.observed_at,
+        submissions: round.submissions
+    );
+    Ok(())
+}
+
+fn execute_latest_round() -> ContractResult<RoundView> {
+    load_config()?;
+    let args: LatestRoundArgs = read_args()?;
+    let pair_config = load_pair(&args.pair)?;
+
+    let round_id = latest_round_id(&args.pair)?;
+    let round = rounds()
+        .get(&(args.pair.clone(), round_id))?
+        .ok_or_else(|| ContractError::InvalidArgument("No round available".to_string()))?;
+
+    let view = build_view(&args.pair, &pair_config, round, context().block_timestamp());
+    try_respond(&view)?;
+    Ok(view)
+}
+
+fn execute_get_round() -> ContractResult<RoundView> {
+    load_config()?;
+    let args: GetRoundArgs = read_args()?;
+    let pair_config = load_pair(&args.pair)?;
+
+    let round = rounds()
+        .get(&(args.pair.clone(), args.round_id))?
+        .ok_or_else(|| ContractError::InvalidArgument("Round not found".to_string()))?;
+
+    let view = build_view(&args.pair, &pair_config, round, context().block_timestamp());
+    try_respond(&view)?;
+    Ok(view)
+}
+
+/// Initialize the oracle
+///
+/// # Arguments
+/// * `min_submissions` - Reporter observations required to finalize a round
+/// * `max_report_age` - Maximum age in seconds of an accepted observation
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Register a reporter (owner only)
+///
+/// # Arguments
+/// * `reporter` - Reporter account address
+/// * `public_key` - Ed25519 key used to sign observations
+#[unsafe(no_mangle)]
+pub extern "C" fn add_reporter() {
+    if let Err(err) = execute_add_reporter() {
+        log!("add_reporter failed: {}", err);
+    }
+}
+
+/// Deregister a reporter (owner only)
+///
+/// # Arguments
+/// * `reporter` - Reporter account address
+#[unsafe(no_mangle)]
+pub extern "C" fn remove_reporter() {
+    if let Err(err) = execute_remove_reporter() {
+        log!("remove_reporter failed: {}", err);
+    }
+}
+
+/// Update the submission quorum (owner only)
+///
+/// # Arguments
+/// * `min_submissions` - New quorum, bounded by the reporter count
+#[unsafe(no_mangle)]
+pub extern "C" fn set_min_submissions() {
+    if let Err(err) = execute_set_min_submissions() {
+        log!("set_min_submissions failed: {}", err);
+    }
+}
+
+/// Create or update a price pair (owner only)
+///
+/// # Arguments
+/// * `pair` - Pair identifier (e.g., "CHERT/USD")
+/// * `decimals` - Fixed-point decimals of reported prices
+/// * `heartbeat` - Seconds after which a round is flagged stale
+#[unsafe(no_mangle)]
+pub extern "C" fn configure_pair() {
+    if let Err(err) = execute_configure_pair() {
+        log!("configure_pair failed: {}", err);
+    }
+}
+
+/// Submit a signed price observation for the open round (reporters only)
+///
+/// # Arguments
+/// * `pair` - Pair identifier
+/// * `round_id` - Open round id (latest finalized round + 1)
+/// * `price` - Observed price scaled by the pair decimals
+/// * `timestamp` - Observation time (Unix seconds)
+/// * `signature` - Ed25519 signature over [`report_message`]
+#[unsafe(no_mangle)]
+pub extern "C" fn submit_price() {
+    if let Err(err) = execute_submit_price() {
+        log!("submit_price failed: {}", err);
+    }
+}
+
+/// Query the latest finalized round of a pair
+///
+/// # Arguments
+/// * `pair` - Pair identifier
+///
+/// # Returns
+/// [`RoundView`] including age and staleness flag
+#[unsafe(no_mangle)]
+pub extern "C" fn latest_round() {
+    if let Err(err) = execute_latest_round() {
+        log!("latest_round failed: {}", err);
+    }
+}
+
+/// Query a historical round of a pair
+///
+/// # Arguments
+/// * `pair` - Pair identifier
+/// * `round_id` - Round to fetch
+///
+/// # Returns
+/// [`RoundView`] including age and staleness flag
+#[unsafe(no_mangle)]
+pub extern "C" fn get_round() {
+    if let Err(err) = execute_get_round() {
+        log!("get_round failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use ed25519_dalek::{Signer, SigningKey};
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_REPORTERS: [&str; 3] = [
+        "0x0000000000000000000000000000000000000f01",
+        "0x0000000000000000000000000000000000000f02",
+        "0x0000000000000000000000000000000000000f03",
+    ];
+    const ADDR_OUTSIDER: &str = "0x0000000000000000000000000000000000000e05";
+    const ORACLE_ADDR: &str = "oracle_contract";
+    const PAIR: &str = "CHERT/USD";
+    const NOW: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn signers() -> Vec<SigningKey> {
+        crypto::generate_keypairs(ADDR_REPORTERS.len())
+            .expect("keypairs")
+            .iter()
+            .map(|(_, secret)| SigningKey::from_bytes(secret))
+            .collect()
+    }
+
+    fn setup_runtime(sender: &str) {
+        mock::reset();
+        mock::set_sender(sender);
+        mock::set_contract_address(ORACLE_ADDR);
+        mock::set_block_height(10);
+        mock::set_block_timestamp(NOW);
+    }
+
+    fn init_default(min_submissions: u32) {
+        setup_runtime(ADDR_OWNER);
+        mock::set_call_data(&encode(&InitializeArgs {
+            min_submissions,
+            max_report_age: 300,
+        }));
+        initialize();
+
+        for (addr, key) in ADDR_REPORTERS.iter().zip(signers()) {
+            mock::set_call_data(&encode(&AddReporterArgs {
+                reporter: addr.to_string(),
+                public_key: key.verifying_key().to_bytes(),
+            }));
+            add_reporter();
+        }
+
+        mock::set_call_data(&encode(&ConfigurePairArgs {
+            pair: PAIR.to_string(),
+            decimals: 8,
+            heartbeat: 600,
+        }));
+        configure_pair();
+        mock::take_events();
+    }
+
+    fn submit(index: usize, round_id: u64, price: u64, timestamp: u64) {
+        let signer = &signers()[index];
+        let message = report_message(ORACLE_ADDR, PAIR, round_id, price, timestamp).unwrap();
+        mock::set_sender(ADDR_REPORTERS[index]);
+        mock::set_call_data(&encode(&SubmitPriceArgs {
+            pair: PAIR.to_string(),
+            round_id,
+            price,
+            timestamp,
+            signature: signer.sign(&message).to_bytes().to_vec(),
+        }));
+        submit_price();
+    }
+
+    fn query_latest() -> Option<RoundView> {
+        mock::set_call_data(&encode(&LatestRoundArgs {
+            pair: PAIR.to_string(),
+        }));
+        latest_round();
+        let bytes = mock::take_return_data();
+        if bytes.is_empty() {
+            None
+        } else {
+            Some(postcard::from_bytes(&bytes).expect("decode round view"))
+        }
+    }
+
+    #[test]
+    fn quorum_finalizes_round_at_median() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(3);
+
+        submit(0, 1, 101_00000000, NOW - 10);
+        submit(1, 1, 99_00000000, NOW - 5);
+        assert!(query_latest().is_none(), "round must wait for quorum");
+
+        submit(2, 1, 250_00000000, NOW);
+        let view = query_latest().expect("finalized round");
+        assert_eq!(view.round_id, 1);
+        assert_eq!(view.price, 101_00000000);
+        assert_eq!(view.observed_at, NOW - 5);
+        assert_eq!(view.decimals, 8);
+        assert_eq!(view.submissions, 3);
+        assert!(!view.stale);
+
+        let events = mock::take_events();
+        assert!(events.iter().any(|event| event.topic == "RoundFinalized"));
+    }
+
+    #[test]
+    fn even_quorum_averages_middle_prices() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(2);
+
+        submit(0, 1, 100, NOW);
+        submit(1, 1, 103, NOW);
+        assert_eq!(query_latest().expect("round").price, 101);
+
+        submit(2, 2, 90, NOW);
+        submit(0, 2, 91, NOW);
+        assert_eq!(query_latest().expect("round").round_id, 2);
+    }
+
+    #[test]
+    fn round_is_flagged_stale_after_heartbeat() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(1);
+
+        submit(0, 1, 500, NOW);
+        mock::set_block_timestamp(NOW + 601);
+        let view = query_latest().expect("round");
+        assert_eq!(view.age, 601);
+        assert!(view.stale);
+    }
+
+    #[test]
+    fn rejects_unauthorized_or_tampered_submissions() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(1);
+
+        // Valid signature but submitted by an unregistered account.
+        let message = report_message(ORACLE_ADDR, PAIR, 1, 500, NOW).unwrap();
+        let signature = signers()[0].sign(&message).to_bytes().to_vec();
+        mock::set_sender(ADDR_OUTSIDER);
+        mock::set_call_data(&encode(&SubmitPriceArgs {
+            pair: PAIR.to_string(),
+            round_id: 1,
+            price: 500,
+            timestamp: NOW,
+            signature: signature.clone(),
+        }));
+        submit_price();
+        assert!(query_latest().is_none());
+
+        // Registered reporter, but the price does not match the signed payload.
+        mock::set_sender(ADDR_REPORTERS[0]);
+        mock::set_call_data(&encode(&SubmitPriceArgs {
+            pair: PAIR.to_string(),
+            round_id: 1,
+            price: 5_000,
+            timestamp: NOW,
+            signature,
+        }));
+        submit_price();
+        assert!(query_latest().is_none());
+
+        // Future round ids are rejected even with a valid signature.
+        submit(0, 2, 500, NOW);
+        assert!(query_latest().is_none());
+    }
+
+    #[test]
+    fn resubmission_replaces_reporter_observation() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(2);
+
+        submit(0, 1, 100, NOW - 20);
+        submit(0, 1, 120, NOW - 10);
+        assert!(
+            query_latest().is_none(),
+            "one reporter cannot reach quorum alone"
+        );
+
+        submit(1, 1, 130, NOW);
+        assert_eq!(query_latest().expect("round").price, 125);
+    }
+
+    #[test]
+    fn reporter_removal_respects_quorum() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(3);
+
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&encode(&RemoveReporterArgs {
+            reporter: ADDR_REPORTERS[0].to_string(),
+        }));
+        remove_reporter();
+        assert_eq!(load_config().unwrap().reporter_count, 3);
+
+        mock::set_call_data(&encode(&SetMinSubmissionsArgs { min_submissions: 2 }));
+        set_min_submissions();
+        mock::set_call_data(&encode(&RemoveReporterArgs {
+            reporter: ADDR_REPORTERS[0].to_string(),
+        }));
+        remove_reporter();
+        assert_eq!(load_config().unwrap().reporter_count, 2);
+        assert!(
+            !reporters()
+                .contains_key(&ADDR_REPORTERS[0].to_string())
+                .unwrap()
+        );
+
+        // Setup recorded three reporters and the pair; the refused removal
+        // recorded nothing.
+        let recorded = mock::take_events()
+            .iter()
+            .filter(|event| event.topic == admin_log::TOPIC)
+            .count();
+        assert_eq!(recorded, 2);
+        assert_eq!(admin_log::next_sequence().unwrap(), 6);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        init_default(2);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "oracle-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
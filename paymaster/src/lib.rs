@@ -0,0 +1,703 @@
+//! Fee Sponsorship Paymaster
+//!
+//! dApps deposit native coin and publish a sponsorship policy: which
+//! `(target, method)` pairs they pay for, the most a relayer may claim per
+//! operation, and a daily budget per user. Relayers submit users' signed
+//! requests through the paymaster, which forwards them via the trusted
+//! forwarder and reimburses the relayer from the sponsor's deposit.
+//!
+//! ## Features
+//! - Per-sponsor call whitelists and fee caps
+//! - Per-user daily budgets (UTC days)
+//! - Per-relayer accounting; relayers cannot sponsor their own requests
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use forwarder_client::SignedRequest;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "paymaster_config";
+const SPONSORS_PREFIX: &str = "sponsors";
+const ALLOWED_CALLS_PREFIX: &str = "allowed_calls";
+const USER_SPEND_PREFIX: &str = "user_spend";
+const RELAYERS_PREFIX: &str = "relayers";
+const SECONDS_PER_DAY: u64 = 86_400;
+const MAX_METHOD_BYTES: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 16_384;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Paymaster configuration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PaymasterConfig {
+    pub forwarder: String,
+}
+
+/// A sponsor's deposit and policy
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sponsor {
+    pub balance: u64,
+    pub max_fee_per_op: u64,
+    pub user_daily_budget: u64,
+    pub active: bool,
+    pub total_reimbursed: u64,
+}
+
+/// Reimbursements paid to a relayer across all sponsors
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RelayerStats {
+    pub operations: u64,
+    pub reimbursed: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    forwarder: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetPolicyArgs {
+    max_fee_per_op: u64,
+    user_daily_budget: u64,
+    active: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllowCallArgs {
+    target: String,
+    method: String,
+    allowed: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RelayArgs {
+    sponsor: String,
+    signed: SignedRequest,
+    fee: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UserSpendArgs {
+    sponsor: String,
+    user: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<PaymasterConfig> {
+    storage()
+        .get::<PaymasterConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Paymaster not initialized".to_string()))
+}
+
+fn sponsors() -> Map<String, Sponsor> {
+    Map::new(SPONSORS_PREFIX)
+}
+
+fn allowed_calls() -> Map<(String, String, String), bool> {
+    Map::new(ALLOWED_CALLS_PREFIX)
+}
+
+fn user_spend() -> Map<(String, String, u64), u64> {
+    Map::new(USER_SPEND_PREFIX)
+}
+
+fn relayers() -> Map<String, RelayerStats> {
+    Map::new(RELAYERS_PREFIX)
+}
+
+fn load_sponsor(sponsor: &str) -> ContractResult<Sponsor> {
+    Ok(sponsors().get(&sponsor.to_string())?.unwrap_or_default())
+}
+
+fn current_day(ctx: &Context) -> u64 {
+    ctx.block_timestamp() / SECONDS_PER_DAY
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Paymaster already initialized".to_string(),
+        ));
+    }
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.forwarder)?;
+    let mut store = storage();
+    store.set(
+        CONFIG_KEY,
+        &PaymasterConfig {
+            forwarder: args.forwarder.clone(),
+        },
+    )?;
+
+    event!("PaymasterInitialized", forwarder: args.forwarder);
+    Ok(())
+}
+
+fn execute_deposit() -> ContractResult<()> {
+    load_config()?;
+    let ctx = context();
+    let amount = ctx.value();
+    validation::validate_positive_amount(amount)?;
+    let sponsor_addr = ctx.sender().to_string();
+
+    let mut sponsor = load_sponsor(&sponsor_addr)?;
+    sponsor.balance = safe_math::add(sponsor.balance, amount)?;
+    sponsors().set(&sponsor_addr, &sponsor)?;
+
+    event!("SponsorDeposited", sponsor: sponsor_addr, amount: amount, balance: sponsor.balance);
+    Ok(())
+}
+
+fn execute_withdraw() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let sponsor_addr = ctx.sender().to_string();
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    let mut sponsor = load_sponsor(&sponsor_addr)?;
+    sponsor.balance =
+        sponsor
+            .balance
+            .checked_sub(args.amount)
+            .ok_or(ContractError::InsufficientBalance {
+                required: args.amount,
+                available: sponsor.balance,
+            })?;
+    sponsors().set(&sponsor_addr, &sponsor)?;
+    ctx.transfer_tokens(&sponsor_addr, args.amount)?;
+
+    event!("SponsorWithdrew", sponsor: sponsor_addr, amount: args.amount, balance: sponsor.balance);
+    Ok(())
+}
+
+fn execute_set_policy() -> ContractResult<()> {
+    let sponsor_addr = context().sender().to_string();
+    let args: SetPolicyArgs = read_args()?;
+    if args.active {
+        validation::validate_positive_amount(args.max_fee_per_op)?;
+        if args.user_daily_budget < args.max_fee_per_op {
+            return Err(ContractError::InvalidArgument(
+                "Daily budget must cover at least one operation".to_string(),
+            ));
+        }
+    }
+
+    let mut sponsor = load_sponsor(&sponsor_addr)?;
+    sponsor.max_fee_per_op = args.max_fee_per_op;
+    sponsor.user_daily_budget = args.user_daily_budget;
+    sponsor.active = args.active;
+    sponsors().set(&sponsor_addr, &sponsor)?;
+
+    event!("PolicyUpdated",
+        sponsor: sponsor_addr,
+        max_fee_per_op: args.max_fee_per_op,
+        user_daily_budget: args.user_daily_budget,
+        active: args.active
+    );
+    Ok(())
+}
+
+fn execute_allow_call() -> ContractResult<()> {
+    let sponsor_addr = context().sender().to_string();
+    let args: AllowCallArgs = read_args()?;
+    validation::validate_address(&args.target)?;
+    if args.method.is_empty() || args.method.len() > MAX_METHOD_BYTES {
+        return Err(ContractError::InvalidArgument(
+            "Invalid method name".to_string(),
+        ));
+    }
+
+    let key = (
+        sponsor_addr.clone(),
+        args.target.clone(),
+        args.method.clone(),
+    );
+    let mut map = allowed_calls();
+    if args.allowed {
+        map.set(&key, &true)?;
+    } else {
+        map.remove(&key)?;
+    }
+
+    event!("SponsoredCallUpdated",
+        sponsor: sponsor_addr,
+        target: args.target,
+        method: args.method,
+        allowed: args.allowed
+    );
+    Ok(())
+}
+
+fn execute_relay() -> ContractResult<Vec<u8>> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let ctx = context();
+    let relayer = ctx.sender().to_string();
+    let args: RelayArgs = read_args()?;
+    let request = &args.signed.request;
+
+    if relayer == request.from || relayer == args.sponsor {
+        return Err(ContractError::InvalidArgument(
+            "Relayer cannot be the user or the sponsor".to_string(),
+        ));
+    }
+    let key = (
+        args.sponsor.clone(),
+        request.target.clone(),
+        request.method.clone(),
+    );
+    if !allowed_calls().get(&key)?.unwrap_or(false) {
+        return Err(ContractError::Unauthorized);
+    }
+
+    let mut sponsor = load_sponsor(&args.sponsor)?;
+    if !sponsor.active {
+        return Err(ContractError::InvalidArgument(
+            "Sponsorship is not active".to_string(),
+        ));
+    }
+    if args.fee > sponsor.max_fee_per_op {
+        return Err(ContractError::InvalidArgument(
+            "Fee exceeds the sponsor's per-operation cap".to_string(),
+        ));
+    }
+    let spend_key = (
+        args.sponsor.clone(),
+        request.from.clone(),
+        current_day(&ctx),
+    );
+    let mut spend_map = user_spend();
+    let spent = safe_math::add(spend_map.get(&spend_key)?.unwrap_or(0), args.fee)?;
+    if spent > sponsor.user_daily_budget {
+        return Err(ContractError::InvalidArgument(
+            "User daily budget exhausted".to_string(),
+        ));
+    }
+    sponsor.balance =
+        sponsor
+            .balance
+            .checked_sub(args.fee)
+            .ok_or(ContractError::InsufficientBalance {
+                required: args.fee,
+                available: sponsor.balance,
+            })?;
+
+    // The forwarder verifies the signature and consumes the user's nonce;
+    // nothing is charged unless the forwarded call succeeds.
+    let response = ctx.call_contract(&config.forwarder, "execute", &encode_call(&args.signed)?)?;
+
+    sponsor.total_reimbursed = safe_math::add(sponsor.total_reimbursed, args.fee)?;
+    sponsors().set(&args.sponsor, &sponsor)?;
+    spend_map.set(&spend_key, &spent)?;
+    let mut relayer_map = relayers();
+    let mut stats = relayer_map.get(&relayer)?.unwrap_or_default();
+    stats.operations = safe_math::add(stats.operations, 1)?;
+    stats.reimbursed = safe_math::add(stats.reimbursed, args.fee)?;
+    relayer_map.set(&relayer, &stats)?;
+
+    if args.fee > 0 {
+        ctx.transfer_tokens(&relayer, args.fee)?;
+    }
+
+    event!("OperationSponsored",
+        sponsor: args.sponsor,
+        user: request.from.clone(),
+        target: request.target.clone(),
+        method: request.method.clone(),
+        relayer: relayer,
+        fee: args.fee
+    );
+    Ok(response)
+}
+
+/// Initialize the paymaster
+///
+/// # Arguments
+/// * `forwarder` - Trusted forwarder that verifies user signatures
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Add attached native value to the caller's sponsorship deposit
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    if let Err(err) = execute_deposit() {
+        log!("deposit failed: {}", err);
+    }
+}
+
+/// Withdraw from the caller's sponsorship deposit
+///
+/// # Arguments
+/// * `amount` - Amount to withdraw
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw() {
+    if let Err(err) = execute_withdraw() {
+        log!("withdraw failed: {}", err);
+    }
+}
+
+/// Set the caller's sponsorship policy
+///
+/// # Arguments
+/// * `max_fee_per_op` - Most a relayer may claim per operation
+/// * `user_daily_budget` - Most one user may consume per UTC day
+/// * `active` - Whether the sponsor pays for anything at all
+#[unsafe(no_mangle)]
+pub extern "C" fn set_policy() {
+    if let Err(err) = execute_set_policy() {
+        log!("set_policy failed: {}", err);
+    }
+}
+
+/// Add or remove a sponsored `(target, method)` pair for the caller
+///
+/// # Arguments
+/// * `target` - Contract address
+/// * `method` - Entrypoint name
+/// * `allowed` - Whether the pair is sponsored
+#[unsafe(no_mangle)]
+pub extern "C" fn allow_call() {
+    if let Err(err) = execute_allow_call() {
+        log!("allow_call failed: {}", err);
+    }
+}
+
+/// Forward a signed user request and reimburse the caller (relayer)
+///
+/// # Arguments
+/// * `sponsor` - Sponsor paying for the operation
+/// * `signed` - `SignedRequest` as accepted by the forwarder's `execute`
+/// * `fee` - Reimbursement claimed by the relayer
+///
+/// # Returns
+/// The target's return data, unchanged
+#[unsafe(no_mangle)]
+pub extern "C" fn relay() {
+    match execute_relay() {
+        Ok(response) => {
+            if let Err(err) = context().return_bytes(&response) {
+                log!("relay failed: {}", err);
+            }
+        }
+        Err(err) => log!("relay failed: {}", err),
+    }
+}
+
+/// Get a sponsor's deposit and policy
+///
+/// # Arguments
+/// * `account` - Sponsor address
+///
+/// # Returns
+/// `Sponsor`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_sponsor() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| load_sponsor(&args.account))
+        .and_then(|sponsor| try_respond(&sponsor));
+    if let Err(err) = result {
+        log!("get_sponsor failed: {}", err);
+    }
+}
+
+/// Get how much of a sponsor's budget a user consumed today
+///
+/// # Arguments
+/// * `sponsor` - Sponsor address
+/// * `user` - User address
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn user_spent_today() {
+    let result = read_args::<UserSpendArgs>().and_then(|args| {
+        let day = current_day(&context());
+        let spent = user_spend().get(&(args.sponsor, args.user, day))?;
+        try_respond(&spent.unwrap_or(0))
+    });
+    if let Err(err) = result {
+        log!("user_spent_today failed: {}", err);
+    }
+}
+
+/// Get a relayer's reimbursement totals
+///
+/// # Arguments
+/// * `account` - Relayer address
+///
+/// # Returns
+/// `RelayerStats`
+#[unsafe(no_mangle)]
+pub extern "C" fn relayer_stats() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| relayers().get(&args.account))
+        .and_then(|stats| try_respond(&stats.unwrap_or_default()));
+    if let Err(err) = result {
+        log!("relayer_stats failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use forwarder_client::ForwardRequest;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_DAPP: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_USER: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_RELAYER: &str = "0x0000000000000000000000000000000000000c03";
+    const TARGET: &str = "0x0000000000000000000000000000000000000d04";
+    const FORWARDER: &str = "0x0000000000000000000000000000000000000e05";
+    const PAYMASTER: &str = "0x0000000000000000000000000000000000000f00";
+    const DAY_START: u64 = 20_000 * SECONDS_PER_DAY;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn signed(method: &str) -> SignedRequest {
+        SignedRequest {
+            request: ForwardRequest {
+                from: ADDR_USER.to_string(),
+                target: TARGET.to_string(),
+                method: method.to_string(),
+                data: encode(&7u64),
+                nonce: 0,
+                deadline: DAY_START + SECONDS_PER_DAY,
+            },
+            signature: vec![0u8; 64],
+        }
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(PAYMASTER);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(DAY_START + 100);
+        mock::set_sender(ADDR_DAPP);
+        mock::set_call_data(&encode(&InitializeArgs {
+            forwarder: FORWARDER.to_string(),
+        }));
+        initialize();
+
+        mock::set_value(1_000);
+        deposit();
+        mock::set_value(0);
+        mock::set_call_data(&encode(&SetPolicyArgs {
+            max_fee_per_op: 10,
+            user_daily_budget: 25,
+            active: true,
+        }));
+        set_policy();
+        mock::set_call_data(&encode(&AllowCallArgs {
+            target: TARGET.to_string(),
+            method: "mint".to_string(),
+            allowed: true,
+        }));
+        allow_call();
+
+        mock::register_contract(FORWARDER, "execute", |_| Ok(encode(&42u64)));
+    }
+
+    fn relay_as(relayer: &str, method: &str, fee: u64) -> Vec<u8> {
+        mock::set_sender(relayer);
+        mock::set_call_data(&encode(&RelayArgs {
+            sponsor: ADDR_DAPP.to_string(),
+            signed: signed(method),
+            fee,
+        }));
+        relay();
+        mock::take_return_data()
+    }
+
+    fn balance() -> u64 {
+        load_sponsor(ADDR_DAPP).unwrap().balance
+    }
+
+    #[test]
+    fn relays_whitelisted_calls_and_reimburses() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        assert_eq!(relay_as(ADDR_RELAYER, "mint", 10), encode(&42u64));
+        assert_eq!(balance(), 990);
+        let stats = relayers().get(&ADDR_RELAYER.to_string()).unwrap().unwrap();
+        assert_eq!(
+            stats,
+            RelayerStats {
+                operations: 1,
+                reimbursed: 10
+            }
+        );
+
+        let calls = mock::take_contract_calls();
+        let forwarded = calls.iter().find(|c| c.address == FORWARDER).unwrap();
+        assert_eq!(forwarded.args, encode(&signed("mint")));
+        assert!(
+            mock::take_events()
+                .iter()
+                .any(|e| e.topic == "OperationSponsored")
+        );
+    }
+
+    #[test]
+    fn unlisted_calls_and_inactive_sponsors_are_not_paid() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        relay_as(ADDR_RELAYER, "burn", 5);
+        assert_eq!(balance(), 1_000);
+
+        mock::set_sender(ADDR_DAPP);
+        mock::set_call_data(&encode(&SetPolicyArgs {
+            max_fee_per_op: 10,
+            user_daily_budget: 25,
+            active: false,
+        }));
+        set_policy();
+        relay_as(ADDR_RELAYER, "mint", 5);
+        assert_eq!(balance(), 1_000);
+    }
+
+    #[test]
+    fn daily_budget_resets_each_day() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        relay_as(ADDR_RELAYER, "mint", 10);
+        relay_as(ADDR_RELAYER, "mint", 10);
+        relay_as(ADDR_RELAYER, "mint", 10);
+        assert_eq!(balance(), 980, "third operation exceeds the 25 budget");
+
+        relay_as(ADDR_RELAYER, "mint", 5);
+        assert_eq!(balance(), 975, "smaller fees still fit");
+
+        mock::set_block_timestamp(DAY_START + SECONDS_PER_DAY);
+        relay_as(ADDR_RELAYER, "mint", 10);
+        assert_eq!(balance(), 965);
+    }
+
+    #[test]
+    fn abusive_relays_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        relay_as(ADDR_RELAYER, "mint", 11);
+        relay_as(ADDR_USER, "mint", 10);
+        relay_as(ADDR_DAPP, "mint", 10);
+        assert_eq!(balance(), 1_000);
+
+        // Nothing is charged when the forwarder rejects the request.
+        mock::register_contract(FORWARDER, "execute", |_| {
+            Err(ContractError::InvalidSignature)
+        });
+        relay_as(ADDR_RELAYER, "mint", 10);
+        assert_eq!(balance(), 1_000);
+        assert!(relayers().get(&ADDR_RELAYER.to_string()).unwrap().is_none());
+    }
+
+    #[test]
+    fn sponsors_withdraw_only_their_balance() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        mock::set_sender(ADDR_DAPP);
+        mock::set_call_data(&encode(&AmountArgs { amount: 1_001 }));
+        withdraw();
+        assert_eq!(balance(), 1_000);
+
+        mock::set_call_data(&encode(&AmountArgs { amount: 400 }));
+        withdraw();
+        assert_eq!(balance(), 600);
+
+        mock::set_sender(ADDR_RELAYER);
+        mock::set_call_data(&encode(&AmountArgs { amount: 1 }));
+        withdraw();
+        assert_eq!(balance(), 600);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "paymaster-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Conventions for contracts deployed behind a proxy
+//!
+//! * Every implementation stores its storage schema version under
+//!   [`SCHEMA_VERSION_KEY`] and exports a `schema_version` entrypoint that
+//!   answers with [`respond_schema_version`]. Bump the version whenever the
+//!   storage layout changes; the proxy refuses to downgrade.
+//! * Implementations bind themselves to one proxy with [`bind_proxy`] during
+//!   `initialize` and use [`original_sender`] instead of `context().sender()`.
+//! * The proxy appends the original caller to the forwarded call data as
+//!   `[caller bytes][caller length: u8]`. Postcard ignores trailing bytes, so
+//!   implementations decode their arguments as usual. For the same reason
+//!   an argument struct cannot gain a trailing field, not even an `Option`:
+//!   calls built against the old struct would fail to decode, or have the
+//!   suffix decoded as the new field. New arguments go in a new entrypoint
+//!   with its own struct.
+//!
+//! The proxy contract re-exports this crate as `proxy_contract::implementation`.
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use silica_contract_sdk::prelude::*;
+
+/// Storage key holding the implementation's schema version (`u32`)
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+/// Storage key holding the address of the proxy allowed to forward calls
+pub const PROXY_KEY: &str = "proxy_address";
+
+/// Read the stored schema version, `0` if none was recorded.
+pub fn schema_version() -> ContractResult<u32> {
+    Ok(storage().get::<u32>(SCHEMA_VERSION_KEY)?.unwrap_or(0))
+}
+
+/// Record the initial schema version; call once from `initialize`.
+pub fn init_schema_version(version: u32) -> ContractResult<()> {
+    if storage().has(SCHEMA_VERSION_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Schema version already set".to_string(),
+        ));
+    }
+    storage().set(SCHEMA_VERSION_KEY, &version)
+}
+
+/// Move the stored schema from `from` to `to` after a migration has run.
+pub fn migrate_schema(from: u32, to: u32) -> ContractResult<()> {
+    if schema_version()? != from {
+        return Err(ContractError::InvalidArgument(
+            "Stored schema does not match migration source".to_string(),
+        ));
+    }
+    if to <= from {
+        return Err(ContractError::InvalidArgument(
+            "Schema version must increase".to_string(),
+        ));
+    }
+    storage().set(SCHEMA_VERSION_KEY, &to)
+}
+
+/// Write the stored schema version as return data.
+pub fn respond_schema_version() -> ContractResult<()> {
+    let data = postcard::to_allocvec(&schema_version()?)
+        .map_err(|_| ContractError::SerializationFailed)?;
+    context().return_bytes(&data)
+}
+
+/// Ask a deployed implementation for its schema version.
+pub fn query_schema_version(implementation: &str) -> ContractResult<u32> {
+    let ctx = context();
+    if !ctx.is_contract(implementation) {
+        return Err(ContractError::InvalidArgument(
+            "Implementation is not a contract".to_string(),
+        ));
+    }
+    let response = ctx.call_contract(implementation, "schema_version", &[])?;
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Bind this implementation to `proxy`; later calls cannot rebind it.
+pub fn bind_proxy(proxy: &str) -> ContractResult<()> {
+    validation::validate_address(proxy)?;
+    if storage().has(PROXY_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Proxy already bound".to_string(),
+        ));
+    }
+    storage().set(PROXY_KEY, &proxy.to_string())
+}
+
+/// The proxy this implementation is bound to, if any.
+pub fn bound_proxy() -> ContractResult<Option<String>> {
+    storage().get::<String>(PROXY_KEY)
+}
+
+/// Append `sender` to forwarded call data.
+pub fn append_sender(data: &[u8], sender: &str) -> ContractResult<Vec<u8>> {
+    let len = u8::try_from(sender.len())
+        .map_err(|_| ContractError::InvalidArgument("Sender too long".to_string()))?;
+    let mut out = Vec::with_capacity(data.len() + sender.len() + 1);
+    out.extend_from_slice(data);
+    out.extend_from_slice(sender.as_bytes());
+    out.push(len);
+    Ok(out)
+}
+
+/// Split forwarded call data into the original payload and the appended sender.
+pub fn split_sender(data: &[u8]) -> Option<(&[u8], &str)> {
+    let (&len, rest) = data.split_last()?;
+    let split = rest.len().checked_sub(len as usize)?;
+    let sender = core::str::from_utf8(&rest[split..]).ok()?;
+    Some((&rest[..split], sender))
+}
+
+/// The account that called the proxy, or the direct caller otherwise.
+///
+/// Only calls from the bound proxy are trusted to carry a sender suffix.
+pub fn original_sender() -> ContractResult<String> {
+    let ctx = context();
+    match bound_proxy()? {
+        Some(proxy) if proxy == ctx.sender() => {
+            let data = ctx.call_data()?;
+            let (_, sender) = split_sender(&data).ok_or(ContractError::DeserializationFailed)?;
+            validation::validate_address(sender)?;
+            Ok(sender.to_string())
+        }
+        _ => Ok(ctx.sender().to_string()),
+    }
+}
+
+/// Reject calls that did not come through the bound proxy.
+pub fn require_proxy() -> ContractResult<()> {
+    match bound_proxy()? {
+        Some(proxy) if proxy == context().sender() => Ok(()),
+        _ => Err(ContractError::Unauthorized),
+    }
+}
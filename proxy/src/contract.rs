@@ -0,0 +1,599 @@
+//! Proxy entrypoints
+
+use crate::implementation;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "proxy_config";
+const PENDING_KEY: &str = "proxy_pending_upgrade";
+const PENDING_IMPLEMENTATION_TARGET: &str = "pending_implementation";
+const MIN_UPGRADE_DELAY: u64 = 3_600;
+const MAX_UPGRADE_DELAY: u64 = 30 * 86_400;
+const MAX_METHOD_BYTES: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 16_384;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Proxy configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub admin: String,
+    pub implementation: String,
+    pub schema_version: u32,
+    pub upgrade_delay: u64,
+}
+
+/// Upgrade waiting for its timelock to expire
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingUpgrade {
+    pub implementation: String,
+    pub schema_version: u32,
+    pub eta: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    implementation: String,
+    upgrade_delay: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ForwardArgs {
+    method: String,
+    data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UpgradeArgs {
+    implementation: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChangeAdminArgs {
+    new_admin: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<ProxyConfig> {
+    storage()
+        .get::<ProxyConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Proxy not initialized".to_string()))
+}
+
+fn save_config(config: &ProxyConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_admin_config() -> ContractResult<ProxyConfig> {
+    let config = load_config()?;
+    if context().sender() != config.admin {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn load_pending() -> ContractResult<PendingUpgrade> {
+    storage()
+        .get::<PendingUpgrade>(PENDING_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("No pending upgrade".to_string()))
+}
+
+fn validate_delay(delay: u64) -> ContractResult<()> {
+    if !(MIN_UPGRADE_DELAY..=MAX_UPGRADE_DELAY).contains(&delay) {
+        return Err(ContractError::InvalidArgument(format!(
+            "Upgrade delay must be between {} and {} seconds",
+            MIN_UPGRADE_DELAY, MAX_UPGRADE_DELAY
+        )));
+    }
+    Ok(())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Proxy already initialized".to_string(),
+        ));
+    }
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.implementation)?;
+    validate_delay(args.upgrade_delay)?;
+    let schema_version = implementation::query_schema_version(&args.implementation)?;
+
+    let admin = context().sender().to_string();
+    save_config(&ProxyConfig {
+        admin: admin.clone(),
+        implementation: args.implementation.clone(),
+        schema_version,
+        upgrade_delay: args.upgrade_delay,
+    })?;
+
+    event!("ProxyInitialized",
+        admin: admin,
+        implementation: args.implementation,
+        schema_version: schema_version
+    );
+    Ok(())
+}
+
+fn execute_forward() -> ContractResult<()> {
+    let config = load_config()?;
+    let args: ForwardArgs = read_args()?;
+    if args.method.is_empty() || args.method.len() > MAX_METHOD_BYTES {
+        return Err(ContractError::InvalidArgument(
+            "Invalid method name".to_string(),
+        ));
+    }
+
+    let ctx = context();
+    let data = implementation::append_sender(&args.data, ctx.sender())?;
+    let response = ctx.call_contract(&config.implementation, &args.method, &data)?;
+    ctx.return_bytes(&response)
+}
+
+fn execute_propose_upgrade() -> ContractResult<u64> {
+    let config = load_admin_config()?;
+    let args: UpgradeArgs = read_args()?;
+    validation::validate_address(&args.implementation)?;
+    if args.implementation == config.implementation {
+        return Err(ContractError::InvalidArgument(
+            "Implementation is already current".to_string(),
+        ));
+    }
+    if storage().has(PENDING_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "An upgrade is already pending".to_string(),
+        ));
+    }
+
+    let schema_version = implementation::query_schema_version(&args.implementation)?;
+    if schema_version < config.schema_version {
+        return Err(ContractError::InvalidArgument(format!(
+            "Schema downgrade from {} to {}",
+            config.schema_version, schema_version
+        )));
+    }
+
+    let eta = context()
+        .block_timestamp()
+        .checked_add(config.upgrade_delay)
+        .ok_or(ContractError::Overflow)?;
+    storage().set(
+        PENDING_KEY,
+        &PendingUpgrade {
+            implementation: args.implementation.clone(),
+            schema_version,
+            eta,
+        },
+    )?;
+
+    event!("UpgradeProposed",
+        implementation: args.implementation,
+        schema_version: schema_version,
+        eta: eta
+    );
+    admin_log::record(
+        "propose_upgrade",
+        PENDING_IMPLEMENTATION_TARGET,
+        "",
+        &args.implementation,
+    )?;
+    Ok(eta)
+}
+
+fn execute_upgrade_to() -> ContractResult<()> {
+    let mut config = load_admin_config()?;
+    let args: UpgradeArgs = read_args()?;
+    let pending = load_pending()?;
+    if pending.implementation != args.implementation {
+        return Err(ContractError::InvalidArgument(
+            "Implementation does not match the pending upgrade".to_string(),
+        ));
+    }
+    if context().block_timestamp() < pending.eta {
+        return Err(ContractError::InvalidArgument(
+            "Upgrade timelock has not expired".to_string(),
+        ));
+    }
+
+    // The implementation could have migrated since the proposal; check again.
+    let schema_version = implementation::query_schema_version(&pending.implementation)?;
+    if schema_version < config.schema_version {
+        return Err(ContractError::InvalidArgument(format!(
+            "Schema downgrade from {} to {}",
+            config.schema_version, schema_version
+        )));
+    }
+
+    let previous = core::mem::replace(&mut config.implementation, pending.implementation);
+    config.schema_version = schema_version;
+    storage().remove(PENDING_KEY)?;
+    save_config(&config)?;
+
+    event!("Upgraded",
+        previous: previous,
+        implementation: config.implementation,
+        schema_version: schema_version
+    );
+    admin_log::record(
+        "upgrade_to",
+        "implementation",
+        previous,
+        &config.implementation,
+    )?;
+    Ok(())
+}
+
+fn execute_cancel_upgrade() -> ContractResult<()> {
+    load_admin_config()?;
+    let pending = load_pending()?;
+    storage().remove(PENDING_KEY)?;
+
+    event!("UpgradeCancelled", implementation: pending.implementation);
+    admin_log::record(
+        "cancel_upgrade",
+        PENDING_IMPLEMENTATION_TARGET,
+        &pending.implementation,
+        "",
+    )?;
+    Ok(())
+}
+
+fn execute_change_admin() -> ContractResult<()> {
+    let mut config = load_admin_config()?;
+    let args: ChangeAdminArgs = read_args()?;
+    validation::validate_address(&args.new_admin)?;
+
+    let previous = core::mem::replace(&mut config.admin, args.new_admin.clone());
+    save_config(&config)?;
+
+    event!("AdminChanged", previous: previous, admin: args.new_admin);
+    admin_log::record("change_admin", "admin", previous, &args.new_admin)?;
+    Ok(())
+}
+
+/// Initialize the proxy; the caller becomes admin
+///
+/// # Arguments
+/// * `implementation` - Initial implementation address
+/// * `upgrade_delay` - Seconds between proposing and applying an upgrade (1 hour - 30 days)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Forward a call to the current implementation
+///
+/// # Arguments
+/// * `method` - Implementation entrypoint
+/// * `data` - Postcard-encoded arguments for that entrypoint
+///
+/// # Returns
+/// The implementation's return data, unchanged
+#[unsafe(no_mangle)]
+pub extern "C" fn forward() {
+    if let Err(err) = execute_forward() {
+        log!("forward failed: {}", err);
+    }
+}
+
+/// Queue an upgrade behind the timelock (admin only)
+///
+/// # Arguments
+/// * `implementation` - New implementation address
+///
+/// # Returns
+/// Timestamp after which `upgrade_to` succeeds
+#[unsafe(no_mangle)]
+pub extern "C" fn propose_upgrade() {
+    match execute_propose_upgrade() {
+        Ok(eta) => {
+            let _ = try_respond(&eta);
+        }
+        Err(err) => log!("propose_upgrade failed: {}", err),
+    }
+}
+
+/// Apply the pending upgrade once its timelock expired (admin only)
+///
+/// # Arguments
+/// * `implementation` - Must match the pending upgrade
+#[unsafe(no_mangle)]
+pub extern "C" fn upgrade_to() {
+    if let Err(err) = execute_upgrade_to() {
+        log!("upgrade_to failed: {}", err);
+    }
+}
+
+/// Drop the pending upgrade (admin only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_upgrade() {
+    if let Err(err) = execute_cancel_upgrade() {
+        log!("cancel_upgrade failed: {}", err);
+    }
+}
+
+/// Hand the proxy to a new admin (admin only)
+///
+/// # Arguments
+/// * `new_admin` - New admin address
+#[unsafe(no_mangle)]
+pub extern "C" fn change_admin() {
+    if let Err(err) = execute_change_admin() {
+        log!("change_admin failed: {}", err);
+    }
+}
+
+/// Get the proxy configuration
+///
+/// # Returns
+/// `ProxyConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get the pending upgrade, if any
+///
+/// # Returns
+/// `Option<PendingUpgrade>`
+#[unsafe(no_mangle)]
+pub extern "C" fn pending_upgrade() {
+    let result = storage()
+        .get::<PendingUpgrade>(PENDING_KEY)
+        .and_then(|pending| try_respond(&pending));
+    if let Err(err) = result {
+        log!("pending_upgrade failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    const ADDR_ADMIN: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_USER: &str = "0x0000000000000000000000000000000000000b02";
+    const IMPL_V1: &str = "0x0000000000000000000000000000000000000c01";
+    const IMPL_V2: &str = "0x0000000000000000000000000000000000000c02";
+    const PROXY: &str = "0x0000000000000000000000000000000000000f00";
+    const DELAY: u64 = 86_400;
+    const START: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn register_schema(address: &str, version: u32) {
+        mock::register_contract(address, "schema_version", move |_| Ok(encode(&version)));
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(PROXY);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::set_sender(ADDR_ADMIN);
+        register_schema(IMPL_V1, 1);
+        mock::set_call_data(&encode(&InitializeArgs {
+            implementation: IMPL_V1.to_string(),
+            upgrade_delay: DELAY,
+        }));
+        initialize();
+    }
+
+    fn propose(implementation: &str) {
+        mock::set_call_data(&encode(&UpgradeArgs {
+            implementation: implementation.to_string(),
+        }));
+        propose_upgrade();
+    }
+
+    fn apply(implementation: &str) {
+        mock::set_call_data(&encode(&UpgradeArgs {
+            implementation: implementation.to_string(),
+        }));
+        upgrade_to();
+    }
+
+    #[test]
+    fn forward_appends_original_sender() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(load_config().unwrap().schema_version, 1);
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let captured = seen.clone();
+        mock::register_contract(IMPL_V1, "set_value", move |args| {
+            *captured.lock().unwrap() = args.to_vec();
+            Ok(encode(&42u64))
+        });
+
+        mock::set_sender(ADDR_USER);
+        mock::set_call_data(&encode(&ForwardArgs {
+            method: "set_value".to_string(),
+            data: encode(&7u64),
+        }));
+        forward();
+        assert_eq!(mock::take_return_data(), encode(&42u64));
+
+        let forwarded = seen.lock().unwrap().clone();
+        let (payload, sender) = implementation::split_sender(&forwarded).unwrap();
+        assert_eq!(sender, ADDR_USER);
+        assert_eq!(payload, encode(&7u64).as_slice());
+        // Implementations decode their arguments without stripping the suffix.
+        assert_eq!(postcard::from_bytes::<u64>(&forwarded).unwrap(), 7);
+    }
+
+    #[test]
+    fn upgrade_waits_for_timelock() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        register_schema(IMPL_V2, 2);
+
+        propose(IMPL_V2);
+        let eta: u64 = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(eta, START + DELAY);
+
+        mock::set_block_timestamp(eta - 1);
+        apply(IMPL_V2);
+        assert_eq!(load_config().unwrap().implementation, IMPL_V1);
+
+        mock::set_block_timestamp(eta);
+        apply(IMPL_V2);
+        let config = load_config().unwrap();
+        assert_eq!(config.implementation, IMPL_V2);
+        assert_eq!(config.schema_version, 2);
+        assert!(!storage().has(PENDING_KEY));
+        assert!(mock::take_events().iter().any(|e| e.topic == "Upgraded"));
+    }
+
+    #[test]
+    fn schema_downgrades_are_refused() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        register_schema(IMPL_V2, 0);
+        propose(IMPL_V2);
+        assert!(!storage().has(PENDING_KEY));
+
+        register_schema(IMPL_V2, 2);
+        propose(IMPL_V2);
+        assert!(storage().has(PENDING_KEY));
+
+        // A proposal for one address cannot be applied to another.
+        mock::set_block_timestamp(START + DELAY);
+        apply(IMPL_V1);
+        assert_eq!(load_config().unwrap().implementation, IMPL_V1);
+
+        // The schema is checked again when the upgrade is applied.
+        register_schema(IMPL_V2, 0);
+        apply(IMPL_V2);
+        assert_eq!(load_config().unwrap().implementation, IMPL_V1);
+    }
+
+    #[test]
+    fn only_admin_manages_upgrades() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        register_schema(IMPL_V2, 1);
+
+        mock::set_sender(ADDR_USER);
+        propose(IMPL_V2);
+        assert!(!storage().has(PENDING_KEY));
+
+        mock::set_sender(ADDR_ADMIN);
+        propose(IMPL_V2);
+        mock::set_sender(ADDR_USER);
+        cancel_upgrade();
+        assert!(storage().has(PENDING_KEY));
+
+        mock::set_sender(ADDR_ADMIN);
+        cancel_upgrade();
+        assert!(!storage().has(PENDING_KEY));
+        mock::set_block_timestamp(START + DELAY);
+        apply(IMPL_V2);
+        assert_eq!(load_config().unwrap().implementation, IMPL_V1);
+        // Only the admin's proposal and cancellation were recorded
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+    }
+
+    #[test]
+    fn implementation_conventions() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        mock::set_contract_address(IMPL_V1);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+
+        implementation::init_schema_version(1).unwrap();
+        assert!(implementation::init_schema_version(2).is_err());
+        assert!(implementation::migrate_schema(2, 3).is_err());
+        implementation::migrate_schema(1, 2).unwrap();
+        assert_eq!(implementation::schema_version().unwrap(), 2);
+
+        implementation::bind_proxy(PROXY).unwrap();
+        assert!(implementation::bind_proxy(ADDR_USER).is_err());
+
+        let forwarded = implementation::append_sender(&encode(&7u64), ADDR_USER).unwrap();
+        mock::set_call_data(&forwarded);
+        mock::set_sender(PROXY);
+        assert_eq!(implementation::original_sender().unwrap(), ADDR_USER);
+        assert!(implementation::require_proxy().is_ok());
+
+        // Direct callers cannot spoof a sender suffix.
+        mock::set_sender(ADDR_ADMIN);
+        assert_eq!(implementation::original_sender().unwrap(), ADDR_ADMIN);
+        assert!(matches!(
+            implementation::require_proxy(),
+            Err(ContractError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "proxy-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Upgradeable Proxy
+//!
+//! A stable address in front of a replaceable implementation contract. The
+//! proxy forwards `(method, call data)` to the current implementation with the
+//! original caller appended, and only lets the admin swap implementations
+//! after a timelock. Upgrades are refused when the new implementation reports
+//! an older storage schema than the current one.
+//!
+//! The [`implementation`] module (the `proxy-implementation` crate) holds the
+//! conventions implementation contracts follow: the `schema_version` storage
+//! key and entrypoint, binding to a single proxy, and recovering the original
+//! caller from forwarded calls.
+//!
+//! ## Embedding
+//! ```toml
+//! proxy-implementation = { path = "../proxy-implementation" }
+//! ```
+//!
+//! ```rust,ignore
+//! use proxy_implementation as implementation;
+//!
+//! let caller = implementation::original_sender()?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub use proxy_implementation as implementation;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
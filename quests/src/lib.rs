@@ -0,0 +1,1029 @@
+//! Quests and Achievements
+//!
+//! Games publish quests with a numeric completion target. Servers the game
+//! has authorized attest each player's progress; once a player reaches the
+//! target they can claim the quest exactly once, which records it as an
+//! achievement and pays the quest's CRC-20 reward, if it has one. Rewards
+//! are escrowed when the quest is created, and every completion reserves
+//! one reward, so a completed quest can always be claimed.
+//!
+//! ## Features
+//! - Any account can publish quests as a game and authorize its own servers
+//! - Batched progress attestation, capped at the quest target; large
+//!   batches can be staged in chunks
+//! - Optional CRC-20 rewards escrowed up front; unreserved rewards return on close
+//! - Per-player progress and achievement listing for UIs
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::bounded::{Name, Uri};
+use silica_contract_sdk::chunked::{
+    self, AppendPayloadArgs, BeginPayloadArgs, ChunkLimits, CommitPayloadArgs,
+};
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const NEXT_QUEST_KEY: &str = "next_quest_id";
+const QUESTS_PREFIX: &str = "quests";
+const SERVERS_PREFIX: &str = "game_servers";
+const PROGRESS_PREFIX: &str = "progress";
+const PLAYER_QUESTS_PREFIX: &str = "player_quests";
+const PLAYER_QUEST_COUNT_PREFIX: &str = "player_quest_count";
+const MAX_UPDATES: usize = 64;
+/// Updates in a batch staged with `begin_payload`
+const MAX_STAGED_UPDATES: usize = 512;
+const CHUNK_LIMITS: ChunkLimits = ChunkLimits {
+    max_payload_bytes: 32_768,
+    max_open_payloads: 4,
+};
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 8192;
+const MAX_RETURN_BYTES: usize = 8192;
+
+/// CRC-20 paid to each player who claims a quest
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenReward {
+    pub token: String,
+    pub amount: u64,
+}
+
+/// A quest published by a game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Quest {
+    /// Account that published the quest and authorizes its servers
+    pub game: String,
+    pub name: String,
+    /// Off-chain description of the completion criteria
+    pub metadata_uri: String,
+    /// Progress needed to complete the quest
+    pub target: u64,
+    pub reward: Option<TokenReward>,
+    /// Most players who can complete the quest
+    pub max_completions: u64,
+    pub completions: u64,
+    pub claims: u64,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    /// Closed quests accept no more progress
+    pub closed: bool,
+}
+
+/// A player's standing in a quest
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub progress: u64,
+    pub completed: bool,
+    pub claimed: bool,
+}
+
+/// One player's progress increment
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProgressUpdate {
+    pub player: String,
+    pub amount: u64,
+}
+
+/// A quest a player has progress in
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlayerQuest {
+    pub quest_id: u64,
+    pub target: u64,
+    pub progress: Progress,
+}
+
+/// One page of a player's quests, in the order they were started
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlayerQuestPage {
+    pub items: Vec<PlayerQuest>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateQuestArgs {
+    name: Name,
+    metadata_uri: Uri,
+    target: u64,
+    reward: Option<TokenReward>,
+    max_completions: u64,
+    starts_at: u64,
+    ends_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetServerArgs {
+    server: String,
+    authorized: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IsServerArgs {
+    game: String,
+    server: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordProgressArgs {
+    quest_id: u64,
+    updates: Vec<ProgressUpdate>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QuestIdArgs {
+    quest_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProgressArgs {
+    quest_id: u64,
+    player: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QuestsOfArgs {
+    player: String,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn quests() -> Map<u64, Quest> {
+    Map::new(QUESTS_PREFIX)
+}
+
+/// Servers each game has authorized to attest progress
+fn servers() -> Map<(String, String), bool> {
+    Map::new(SERVERS_PREFIX)
+}
+
+fn progress_map() -> Map<(u64, String), Progress> {
+    Map::new(PROGRESS_PREFIX)
+}
+
+fn player_quests() -> Map<(String, u64), u64> {
+    Map::new(PLAYER_QUESTS_PREFIX)
+}
+
+fn player_quest_counts() -> Map<String, u64> {
+    Map::new(PLAYER_QUEST_COUNT_PREFIX)
+}
+
+fn load_quest(quest_id: u64) -> ContractResult<Quest> {
+    quests()
+        .get(&quest_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown quest".to_string()))
+}
+
+fn load_progress(quest_id: u64, player: &str) -> ContractResult<Progress> {
+    Ok(progress_map()
+        .get(&(quest_id, player.to_string()))?
+        .unwrap_or_default())
+}
+
+/// Rewards held for completions that have not happened yet
+fn unreserved_budget(quest: &Quest) -> u64 {
+    quest.reward.as_ref().map_or(0, |reward| {
+        reward.amount * (quest.max_completions - quest.completions)
+    })
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn execute_create_quest() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let game = ctx.sender().to_string();
+    let args: CreateQuestArgs = read_args()?;
+    validation::validate_non_empty(&args.name, "name")?;
+    validation::validate_non_empty(&args.metadata_uri, "metadata_uri")?;
+    validation::validate_positive_amount(args.target)?;
+    validation::validate_positive_amount(args.max_completions)?;
+    if args.ends_at <= args.starts_at || args.ends_at <= ctx.block_timestamp() {
+        return Err(ContractError::InvalidArgument(
+            "Quest must end after it starts and in the future".to_string(),
+        ));
+    }
+    let budget = match &args.reward {
+        Some(reward) => {
+            validation::validate_address(&reward.token)?;
+            if !ctx.is_contract(&reward.token) {
+                return Err(ContractError::InvalidArgument(
+                    "Reward token must be a contract".to_string(),
+                ));
+            }
+            validation::validate_positive_amount(reward.amount)?;
+            safe_math::mul(reward.amount, args.max_completions)?
+        }
+        None => 0,
+    };
+
+    let quest_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_QUEST_KEY)?.unwrap_or(0);
+        store.set(NEXT_QUEST_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    let quest = Quest {
+        game: game.clone(),
+        name: args.name.into(),
+        metadata_uri: args.metadata_uri.into(),
+        target: args.target,
+        reward: args.reward,
+        max_completions: args.max_completions,
+        completions: 0,
+        claims: 0,
+        starts_at: args.starts_at,
+        ends_at: args.ends_at,
+        closed: false,
+    };
+    quests().set(&quest_id, &quest)?;
+    if let Some(reward) = &quest.reward {
+        let call = encode_call(&TokenTransferFromArgs {
+            from: game.clone(),
+            to: ctx.contract_address().to_string(),
+            amount: budget,
+        })?;
+        ctx.call_contract(&reward.token, "transfer_from", &call)?;
+    }
+
+    event!("QuestCreated",
+        quest_id: quest_id,
+        game: game,
+        name: quest.name,
+        target: quest.target,
+        max_completions: quest.max_completions,
+        budget: budget
+    );
+    Ok(quest_id)
+}
+
+fn execute_set_server() -> ContractResult<()> {
+    let game = context().sender().to_string();
+    let args: SetServerArgs = read_args()?;
+    validation::validate_address(&args.server)?;
+    let key = (game.clone(), args.server.clone());
+    if args.authorized {
+        servers().set(&key, &true)?;
+    } else {
+        servers().remove(&key)?;
+    }
+
+    event!("ServerUpdated",
+        game: game,
+        server: args.server,
+        authorized: args.authorized
+    );
+    Ok(())
+}
+
+fn execute_record_progress() -> ContractResult<()> {
+    let args: RecordProgressArgs = read_args()?;
+    apply_progress(args, MAX_UPDATES)
+}
+
+fn apply_progress(args: RecordProgressArgs, max_updates: usize) -> ContractResult<()> {
+    let ctx = context();
+    let server = ctx.sender().to_string();
+    if args.updates.is_empty() || args.updates.len() > max_updates {
+        return Err(ContractError::InvalidArgument(format!(
+            "Batches hold 1-{} updates",
+            max_updates
+        )));
+    }
+    let mut quest = load_quest(args.quest_id)?;
+    if !servers().contains_key(&(quest.game.clone(), server.clone()))? {
+        return Err(ContractError::Unauthorized);
+    }
+    let now = ctx.block_timestamp();
+    if quest.closed || now < quest.starts_at || now >= quest.ends_at {
+        return Err(ContractError::InvalidArgument(
+            "Quest is not running".to_string(),
+        ));
+    }
+
+    // Apply the whole batch in memory first so a failing update writes nothing.
+    let mut records = progress_map();
+    let mut staged: Vec<(String, Progress, bool)> = Vec::new();
+    for update in args.updates {
+        validation::validate_address(&update.player)?;
+        let position = staged
+            .iter()
+            .position(|(player, ..)| *player == update.player);
+        let (mut progress, first_seen) = match position {
+            Some(index) => (staged[index].1.clone(), false),
+            None => {
+                let existing = records.get(&(args.quest_id, update.player.clone()))?;
+                (existing.clone().unwrap_or_default(), existing.is_none())
+            }
+        };
+        if progress.completed {
+            continue;
+        }
+        progress.progress = progress
+            .progress
+            .saturating_add(update.amount)
+            .min(quest.target);
+        if progress.progress == quest.target {
+            if quest.completions == quest.max_completions {
+                return Err(ContractError::InvalidArgument(
+                    "Quest has no completions left".to_string(),
+                ));
+            }
+            quest.completions += 1;
+            progress.completed = true;
+        }
+        match position {
+            Some(index) => staged[index].1 = progress,
+            None => staged.push((update.player, progress, first_seen)),
+        }
+    }
+
+    for (player, progress, first_seen) in staged {
+        if first_seen {
+            let mut counts = player_quest_counts();
+            let count = counts.get(&player)?.unwrap_or(0);
+            player_quests().set(&(player.clone(), count), &args.quest_id)?;
+            counts.set(&player, &safe_math::add(count, 1)?)?;
+        }
+        records.set(&(args.quest_id, player.clone()), &progress)?;
+
+        event!("ProgressRecorded",
+            quest_id: args.quest_id,
+            player: player.clone(),
+            progress: progress.progress,
+            server: server.clone()
+        );
+        if progress.completed {
+            event!("QuestCompleted", quest_id: args.quest_id, player: player);
+        }
+    }
+    quests().set(&args.quest_id, &quest)
+}
+
+fn execute_commit_payload() -> ContractResult<()> {
+    let args: CommitPayloadArgs = read_args()?;
+    match args.method.as_str() {
+        "record_progress" => apply_progress(chunked::commit(args.sequence)?, MAX_STAGED_UPDATES),
+        _ => Err(chunked::unknown_method(&args.method)),
+    }
+}
+
+fn execute_close_quest() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let caller = context().sender().to_string();
+    let args: QuestIdArgs = read_args()?;
+    let mut quest = load_quest(args.quest_id)?;
+    if quest.game != caller {
+        return Err(ContractError::Unauthorized);
+    }
+    if quest.closed {
+        return Err(ContractError::InvalidArgument(
+            "Quest already closed".to_string(),
+        ));
+    }
+    // Completed players keep their reserved rewards; the rest goes back.
+    let refund = unreserved_budget(&quest);
+    quest.closed = true;
+    quest.max_completions = quest.completions;
+    quests().set(&args.quest_id, &quest)?;
+    if let Some(reward) = &quest.reward
+        && refund > 0
+    {
+        send_tokens(&reward.token, &caller, refund)?;
+    }
+
+    event!("QuestClosed", quest_id: args.quest_id, refund: refund);
+    Ok(())
+}
+
+fn execute_claim_reward() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let player = context().sender().to_string();
+    let args: QuestIdArgs = read_args()?;
+    let mut quest = load_quest(args.quest_id)?;
+    let mut progress = load_progress(args.quest_id, &player)?;
+    if !progress.completed {
+        return Err(ContractError::InvalidArgument(
+            "Quest not completed".to_string(),
+        ));
+    }
+    if progress.claimed {
+        return Err(ContractError::InvalidArgument(
+            "Reward already claimed".to_string(),
+        ));
+    }
+    progress.claimed = true;
+    progress_map().set(&(args.quest_id, player.clone()), &progress)?;
+    quest.claims += 1;
+    quests().set(&args.quest_id, &quest)?;
+    if let Some(reward) = &quest.reward {
+        send_tokens(&reward.token, &player, reward.amount)?;
+    }
+
+    event!("RewardClaimed",
+        quest_id: args.quest_id,
+        player: player,
+        amount: quest.reward.map_or(0, |reward| reward.amount)
+    );
+    Ok(())
+}
+
+fn execute_quests_of() -> ContractResult<PlayerQuestPage> {
+    let args: QuestsOfArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let total = player_quest_counts().get(&args.player)?.unwrap_or(0);
+    let end = args.offset.saturating_add(args.limit).min(total);
+    let index = player_quests();
+    let mut items = Vec::new();
+    for position in args.offset..end {
+        if let Some(quest_id) = index.get(&(args.player.clone(), position))? {
+            items.push(PlayerQuest {
+                quest_id,
+                target: load_quest(quest_id)?.target,
+                progress: load_progress(quest_id, &args.player)?,
+            });
+        }
+    }
+    Ok(PlayerQuestPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+/// Publish a quest; the caller is its game (approve the reward budget first)
+///
+/// # Arguments
+/// * `name` - Display name (max 64 bytes)
+/// * `metadata_uri` - Description of the completion criteria (max 256 bytes)
+/// * `target` - Progress needed to complete the quest
+/// * `reward` - Optional `TokenReward` per claim; `amount * max_completions` is escrowed
+/// * `max_completions` - Most players who can complete the quest
+/// * `starts_at` / `ends_at` - Window in which progress is accepted
+///
+/// # Returns
+/// Quest id
+#[unsafe(no_mangle)]
+pub extern "C" fn create_quest() {
+    let result = execute_create_quest().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("create_quest failed: {}", err);
+    }
+}
+
+/// Authorize or remove a server attesting progress for the caller's quests
+///
+/// # Arguments
+/// * `server` - Server address
+/// * `authorized` - Whether it may attest
+#[unsafe(no_mangle)]
+pub extern "C" fn set_server() {
+    if let Err(err) = execute_set_server() {
+        log!("set_server failed: {}", err);
+    }
+}
+
+/// Attest player progress (only the quest's game servers)
+///
+/// Progress is capped at the target, and updates for completed players are
+/// ignored. The batch fails if it would complete the quest for more players
+/// than `max_completions`.
+///
+/// # Arguments
+/// * `quest_id` - Quest id
+/// * `updates` - Up to 64 `ProgressUpdate` entries
+#[unsafe(no_mangle)]
+pub extern "C" fn record_progress() {
+    if let Err(err) = execute_record_progress() {
+        log!("record_progress failed: {}", err);
+    }
+}
+
+/// Open a staged payload for a batch too large for one call
+///
+/// # Arguments
+/// * `sequence` - Caller-chosen id of the payload; reusing an open one starts it over
+/// * `total_len` - Payload size, at most 32 KiB
+#[unsafe(no_mangle)]
+pub extern "C" fn begin_payload() {
+    let result = read_args::<BeginPayloadArgs>()
+        .and_then(|args| chunked::begin(&CHUNK_LIMITS, args.sequence, args.total_len));
+    if let Err(err) = result {
+        log!("begin_payload failed: {}", err);
+    }
+}
+
+/// Add the next chunk to a staged payload
+///
+/// # Arguments
+/// * `sequence` - Payload id
+/// * `chunk` - Next bytes of the payload
+#[unsafe(no_mangle)]
+pub extern "C" fn append_payload() {
+    let result = read_args::<AppendPayloadArgs>()
+        .and_then(|args| chunked::append(args.sequence, &args.chunk));
+    if let Err(err) = result {
+        log!("append_payload failed: {}", err);
+    }
+}
+
+/// Run a batch entrypoint with a complete staged payload
+///
+/// `record_progress` is the only batch entrypoint; staged batches hold up to
+/// 512 updates.
+///
+/// # Arguments
+/// * `sequence` - Payload id
+/// * `method` - `"record_progress"`
+#[unsafe(no_mangle)]
+pub extern "C" fn commit_payload() {
+    if let Err(err) = execute_commit_payload() {
+        log!("commit_payload failed: {}", err);
+    }
+}
+
+/// Stop a quest and recover rewards not reserved by completions (only its game)
+///
+/// # Arguments
+/// * `quest_id` - Quest id
+#[unsafe(no_mangle)]
+pub extern "C" fn close_quest() {
+    if let Err(err) = execute_close_quest() {
+        log!("close_quest failed: {}", err);
+    }
+}
+
+/// Claim a completed quest's reward; each player can claim once
+///
+/// # Arguments
+/// * `quest_id` - Quest id
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_reward() {
+    if let Err(err) = execute_claim_reward() {
+        log!("claim_reward failed: {}", err);
+    }
+}
+
+/// Get a quest
+///
+/// # Arguments
+/// * `quest_id` - Quest id
+///
+/// # Returns
+/// `Quest`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_quest() {
+    let result = read_args::<QuestIdArgs>()
+        .and_then(|args| load_quest(args.quest_id))
+        .and_then(|quest| try_respond(&quest));
+    if let Err(err) = result {
+        log!("get_quest failed: {}", err);
+    }
+}
+
+/// Get a player's progress in a quest
+///
+/// # Arguments
+/// * `quest_id` - Quest id
+/// * `player` - Player address
+///
+/// # Returns
+/// `Progress`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_progress() {
+    let result = read_args::<ProgressArgs>()
+        .and_then(|args| load_progress(args.quest_id, &args.player))
+        .and_then(|progress| try_respond(&progress));
+    if let Err(err) = result {
+        log!("get_progress failed: {}", err);
+    }
+}
+
+/// List the quests a player has progress in; claimed quests are achievements
+///
+/// # Arguments
+/// * `player` - Player address
+/// * `offset` - Position to start from
+/// * `limit` - Page size (max 50)
+///
+/// # Returns
+/// `PlayerQuestPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn quests_of() {
+    if let Err(err) = execute_quests_of().and_then(|page| try_respond(&page)) {
+        log!("quests_of failed: {}", err);
+    }
+}
+
+/// Whether a server may attest progress for a game
+///
+/// # Arguments
+/// * `game` - Game address
+/// * `server` - Server address
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn is_server() {
+    let result = read_args::<IsServerArgs>()
+        .and_then(|args| servers().contains_key(&(args.game, args.server)))
+        .and_then(|authorized| try_respond(&authorized));
+    if let Err(err) = result {
+        log!("is_server failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_GAME: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_SERVER: &str = "0x0000000000000000000000000000000000000a02";
+    const ADDR_OTHER_GAME: &str = "0x0000000000000000000000000000000000000b01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000c01";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c02";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const START: u64 = 1_736_000_000;
+    const DAY: u64 = 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+        call(
+            ADDR_GAME,
+            set_server,
+            encode(&SetServerArgs {
+                server: ADDR_SERVER.to_string(),
+                authorized: true,
+            }),
+        );
+    }
+
+    fn create(reward: Option<u64>, max_completions: u64) -> u64 {
+        let data = call(
+            ADDR_GAME,
+            create_quest,
+            encode(&CreateQuestArgs {
+                name: Name::new("Slay 3 dragons").unwrap(),
+                metadata_uri: Uri::new("ipfs://dragons").unwrap(),
+                target: 3,
+                reward: reward.map(|amount| TokenReward {
+                    token: TOKEN.to_string(),
+                    amount,
+                }),
+                max_completions,
+                starts_at: START,
+                ends_at: START + DAY,
+            }),
+        );
+        postcard::from_bytes(&data).unwrap()
+    }
+
+    fn record(server: &str, quest_id: u64, updates: &[(&str, u64)]) {
+        let updates = updates
+            .iter()
+            .map(|(player, amount)| ProgressUpdate {
+                player: player.to_string(),
+                amount: *amount,
+            })
+            .collect();
+        call(
+            server,
+            record_progress,
+            encode(&RecordProgressArgs { quest_id, updates }),
+        );
+    }
+
+    fn claim(player: &str, quest_id: u64) {
+        call(player, claim_reward, encode(&QuestIdArgs { quest_id }));
+    }
+
+    /// (recipient, amount) of every token transfer since the last call
+    fn payouts() -> Vec<(String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer")
+            .map(|c| {
+                let args: TokenTransferArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.to, args.amount)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_the_games_servers_attest_progress() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let quest_id = create(None, 10);
+        call(
+            ADDR_OTHER_GAME,
+            set_server,
+            encode(&SetServerArgs {
+                server: ADDR_BOB.to_string(),
+                authorized: true,
+            }),
+        );
+
+        record(ADDR_BOB, quest_id, &[(ADDR_ALICE, 1)]);
+        record(ADDR_GAME, quest_id, &[(ADDR_ALICE, 1)]);
+        assert_eq!(load_progress(quest_id, ADDR_ALICE).unwrap().progress, 0);
+
+        record(ADDR_SERVER, quest_id, &[(ADDR_ALICE, 2), (ADDR_BOB, 1)]);
+        record(ADDR_SERVER, quest_id, &[(ADDR_ALICE, 5)]);
+        assert_eq!(
+            load_progress(quest_id, ADDR_ALICE).unwrap(),
+            Progress {
+                progress: 3,
+                completed: true,
+                claimed: false
+            }
+        );
+        assert_eq!(load_progress(quest_id, ADDR_BOB).unwrap().progress, 1);
+        assert_eq!(load_quest(quest_id).unwrap().completions, 1);
+
+        call(
+            ADDR_GAME,
+            set_server,
+            encode(&SetServerArgs {
+                server: ADDR_SERVER.to_string(),
+                authorized: false,
+            }),
+        );
+        record(ADDR_SERVER, quest_id, &[(ADDR_BOB, 1)]);
+        assert_eq!(load_progress(quest_id, ADDR_BOB).unwrap().progress, 1);
+    }
+
+    #[test]
+    fn rewards_are_claimed_exactly_once() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let quest_id = create(Some(25), 4);
+        let escrow = mock::take_contract_calls();
+        let args: TokenTransferFromArgs = postcard::from_bytes(&escrow[0].args).unwrap();
+        assert_eq!((args.from.as_str(), args.amount), (ADDR_GAME, 100));
+
+        claim(ADDR_ALICE, quest_id);
+        record(ADDR_SERVER, quest_id, &[(ADDR_ALICE, 3)]);
+        claim(ADDR_ALICE, quest_id);
+        claim(ADDR_ALICE, quest_id);
+        assert_eq!(payouts(), [(ADDR_ALICE.to_string(), 25)]);
+        assert!(load_progress(quest_id, ADDR_ALICE).unwrap().claimed);
+        assert_eq!(load_quest(quest_id).unwrap().claims, 1);
+    }
+
+    #[test]
+    fn completions_are_capped() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let quest_id = create(Some(10), 2);
+        record(ADDR_SERVER, quest_id, &[(ADDR_ALICE, 3), (ADDR_BOB, 3)]);
+
+        // The whole batch fails when it would overfill the quest.
+        record(ADDR_SERVER, quest_id, &[(ADDR_CAROL, 1), (ADDR_CAROL, 2)]);
+        assert_eq!(
+            load_progress(quest_id, ADDR_CAROL).unwrap(),
+            Progress::default()
+        );
+        // Completed players are skipped.
+        record(ADDR_SERVER, quest_id, &[(ADDR_ALICE, 3), (ADDR_CAROL, 1)]);
+        assert_eq!(load_progress(quest_id, ADDR_CAROL).unwrap().progress, 1);
+        assert_eq!(load_quest(quest_id).unwrap().completions, 2);
+    }
+
+    #[test]
+    fn large_batches_are_staged_in_chunks() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let quest_id = create(None, MAX_STAGED_UPDATES as u64);
+        let updates: Vec<ProgressUpdate> = (0..MAX_STAGED_UPDATES as u64)
+            .map(|i| ProgressUpdate {
+                player: format!("0x{:040x}", 0x1000 + i),
+                amount: 1,
+            })
+            .collect();
+        let payload = encode(&RecordProgressArgs { quest_id, updates });
+        assert!(payload.len() > MAX_CALL_DATA_BYTES);
+
+        call(
+            ADDR_SERVER,
+            begin_payload,
+            encode(&BeginPayloadArgs {
+                sequence: 9,
+                total_len: payload.len() as u32,
+            }),
+        );
+        for chunk in payload.chunks(MAX_CALL_DATA_BYTES / 2) {
+            call(
+                ADDR_SERVER,
+                append_payload,
+                encode(&AppendPayloadArgs {
+                    sequence: 9,
+                    chunk: chunk.to_vec(),
+                }),
+            );
+        }
+        // Unknown methods leave the payload staged.
+        let commit = |method: &str| {
+            call(
+                ADDR_SERVER,
+                commit_payload,
+                encode(&CommitPayloadArgs {
+                    sequence: 9,
+                    method: method.to_string(),
+                }),
+            );
+        };
+        commit("claim_reward");
+        assert!(chunked::staged(ADDR_SERVER, 9).unwrap().is_some());
+        commit("record_progress");
+        assert!(chunked::staged(ADDR_SERVER, 9).unwrap().is_none());
+        let last = format!("0x{:040x}", 0x1000 + MAX_STAGED_UPDATES as u64 - 1);
+        assert_eq!(load_progress(quest_id, &last).unwrap().progress, 1);
+    }
+
+    #[test]
+    fn closing_returns_unreserved_rewards() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let quest_id = create(Some(10), 5);
+        record(ADDR_SERVER, quest_id, &[(ADDR_ALICE, 3), (ADDR_BOB, 2)]);
+        mock::take_contract_calls();
+
+        call(ADDR_SERVER, close_quest, encode(&QuestIdArgs { quest_id }));
+        call(ADDR_GAME, close_quest, encode(&QuestIdArgs { quest_id }));
+        assert_eq!(payouts(), [(ADDR_GAME.to_string(), 40)]);
+        record(ADDR_SERVER, quest_id, &[(ADDR_BOB, 1)]);
+        assert!(!load_progress(quest_id, ADDR_BOB).unwrap().completed);
+
+        claim(ADDR_ALICE, quest_id);
+        assert_eq!(payouts(), [(ADDR_ALICE.to_string(), 10)]);
+    }
+
+    #[test]
+    fn players_list_their_quests_and_achievements() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let first = create(None, 10);
+        let second = create(None, 10);
+        mock::set_block_timestamp(START - 1);
+        record(ADDR_SERVER, first, &[(ADDR_ALICE, 1)]);
+        mock::set_block_timestamp(START);
+        record(ADDR_SERVER, second, &[(ADDR_ALICE, 3)]);
+        record(ADDR_SERVER, first, &[(ADDR_ALICE, 1)]);
+        claim(ADDR_ALICE, second);
+        mock::set_block_timestamp(START + DAY);
+        record(ADDR_SERVER, first, &[(ADDR_ALICE, 1)]);
+
+        let page: PlayerQuestPage = postcard::from_bytes(&call(
+            ADDR_BOB,
+            quests_of,
+            encode(&QuestsOfArgs {
+                player: ADDR_ALICE.to_string(),
+                offset: 0,
+                limit: 1,
+            }),
+        ))
+        .unwrap();
+        assert_eq!((page.total, page.next_offset), (2, Some(1)));
+        assert_eq!(page.items[0].quest_id, second);
+        assert!(page.items[0].progress.claimed);
+
+        let page: PlayerQuestPage = postcard::from_bytes(&call(
+            ADDR_BOB,
+            quests_of,
+            encode(&QuestsOfArgs {
+                player: ADDR_ALICE.to_string(),
+                offset: 1,
+                limit: 50,
+            }),
+        ))
+        .unwrap();
+        assert_eq!(
+            page.items,
+            [PlayerQuest {
+                quest_id: first,
+                target: 3,
+                progress: Progress {
+                    progress: 1,
+                    completed: false,
+                    claimed: false
+                }
+            }]
+        );
+        assert_eq!(page.next_offset, None);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "quests-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,794 @@
+//! Referral Rewards
+//!
+//! Accounts register who referred them, once. Integrated contracts report
+//! qualifying actions by referees, such as a trade or a deposit, together
+//! with the action's value. The referrer then accrues a basis-point share
+//! of that value from a budget the owner funds up front. Each reporter has
+//! its own rate. Referrers claim what they accrued at any time, up to a
+//! lifetime cap.
+//!
+//! Sybil limits: an account cannot refer itself or its own referrer, each
+//! referrer takes a bounded number of referees, and an account that has
+//! already been reported for an action can no longer be registered as a
+//! referee.
+//!
+//! ## Features
+//! - One-time referrer links with sybil limits
+//! - Per-reporter reward rates in basis points
+//! - Funded reward budget with a lifetime cap per referrer
+//! - Pull-based reward claims
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+
+const CONFIG_KEY: &str = "referral_config";
+const REFERRERS_PREFIX: &str = "referrer_of";
+const STATS_PREFIX: &str = "referrer_stats";
+const REPORTERS_PREFIX: &str = "reporters";
+const ACTIVE_PREFIX: &str = "active_accounts";
+/// Highest reward rate a reporter can be given
+const MAX_REWARD_BPS: u64 = 5_000;
+const MAX_REFEREES_LIMIT: u64 = 10_000;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Program configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReferralConfig {
+    pub owner: String,
+    /// CRC-20 rewards are paid in
+    pub reward_token: String,
+    /// Most a single referrer can ever earn
+    pub referrer_cap: u64,
+    /// Most referees a single referrer can register
+    pub max_referees: u64,
+    /// Funded rewards not yet accrued to anyone
+    pub budget: u64,
+}
+
+/// What a referrer has brought in and earned
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReferrerStats {
+    pub referees: u64,
+    /// Lifetime rewards, capped at `referrer_cap`
+    pub earned: u64,
+    pub claimed: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    reward_token: String,
+    referrer_cap: u64,
+    max_referees: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LimitsArgs {
+    referrer_cap: u64,
+    max_referees: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReporterArgs {
+    reporter: String,
+    reward_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegisterArgs {
+    referrer: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReportArgs {
+    account: String,
+    value: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<ReferralConfig> {
+    storage()
+        .get::<ReferralConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_config(config: &ReferralConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<ReferralConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn referrers() -> Map<String, String> {
+    Map::new(REFERRERS_PREFIX)
+}
+
+fn referrer_stats() -> Map<String, ReferrerStats> {
+    Map::new(STATS_PREFIX)
+}
+
+/// Reward rate per reporting contract
+fn reporters() -> Map<String, u64> {
+    Map::new(REPORTERS_PREFIX)
+}
+
+/// Accounts that have been reported for an action
+fn active_accounts() -> Map<String, bool> {
+    Map::new(ACTIVE_PREFIX)
+}
+
+fn load_stats(referrer: &str) -> ContractResult<ReferrerStats> {
+    Ok(referrer_stats()
+        .get(&referrer.to_string())?
+        .unwrap_or_default())
+}
+
+fn validate_limits(referrer_cap: u64, max_referees: u64) -> ContractResult<()> {
+    validation::validate_positive_amount(referrer_cap)?;
+    validation::validate_range(max_referees, 1, MAX_REFEREES_LIMIT)
+}
+
+/// Reward for an action of `value` at `reward_bps`, limited by what the
+/// referrer may still earn and what is left in the budget.
+pub fn reward_for(
+    value: u64,
+    reward_bps: u64,
+    stats: &ReferrerStats,
+    config: &ReferralConfig,
+) -> ContractResult<u64> {
+    let reward = safe_math::apply_bps(value, reward_bps, Rounding::Down)?;
+    Ok(reward
+        .min(config.referrer_cap.saturating_sub(stats.earned))
+        .min(config.budget))
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let mut store = storage();
+    if store.has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.reward_token)?;
+    if !ctx.is_contract(&args.reward_token) {
+        return Err(ContractError::InvalidArgument(
+            "Reward token must be a contract".to_string(),
+        ));
+    }
+    validate_limits(args.referrer_cap, args.max_referees)?;
+    let config = ReferralConfig {
+        owner: ctx.sender().to_string(),
+        reward_token: args.reward_token,
+        referrer_cap: args.referrer_cap,
+        max_referees: args.max_referees,
+        budget: 0,
+    };
+    store.set(CONFIG_KEY, &config)?;
+
+    event!("ReferralsInitialized",
+        owner: config.owner,
+        reward_token: config.reward_token,
+        referrer_cap: config.referrer_cap,
+        max_referees: config.max_referees
+    );
+    Ok(())
+}
+
+fn execute_set_limits() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: LimitsArgs = read_args()?;
+    validate_limits(args.referrer_cap, args.max_referees)?;
+    let previous = (config.referrer_cap, config.max_referees);
+    config.referrer_cap = args.referrer_cap;
+    config.max_referees = args.max_referees;
+    save_config(&config)?;
+
+    event!("LimitsUpdated",
+        referrer_cap: args.referrer_cap,
+        max_referees: args.max_referees
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "referrer_cap",
+        previous.0,
+        args.referrer_cap,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "max_referees",
+        previous.1,
+        args.max_referees,
+    )?;
+    Ok(())
+}
+
+fn execute_set_reporter() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: ReporterArgs = read_args()?;
+    validation::validate_address(&args.reporter)?;
+    validation::validate_range(args.reward_bps, 0, MAX_REWARD_BPS)?;
+    let previous = reporters().get(&args.reporter)?.unwrap_or(0);
+    if args.reward_bps == 0 {
+        reporters().remove(&args.reporter)?;
+    } else {
+        reporters().set(&args.reporter, &args.reward_bps)?;
+    }
+
+    event!("ReporterUpdated", reporter: args.reporter, reward_bps: args.reward_bps);
+    admin_log::record("set_reporter", &args.reporter, previous, args.reward_bps)?;
+    Ok(())
+}
+
+fn execute_fund() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let mut config = load_owned_config()?;
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    let previous = config.budget;
+    config.budget = safe_math::add(previous, args.amount)?;
+    save_config(&config)?;
+
+    let call = encode_call(&TokenTransferFromArgs {
+        from: config.owner.clone(),
+        to: ctx.contract_address().to_string(),
+        amount: args.amount,
+    })?;
+    ctx.call_contract(&config.reward_token, "transfer_from", &call)?;
+
+    event!("BudgetFunded", amount: args.amount, budget: config.budget);
+    admin_log::record("fund", "budget", previous, config.budget)?;
+    Ok(())
+}
+
+fn execute_register() -> ContractResult<()> {
+    let referee = context().sender().to_string();
+    let config = load_config()?;
+    let args: RegisterArgs = read_args()?;
+    validation::validate_address(&args.referrer)?;
+    if args.referrer == referee {
+        return Err(ContractError::InvalidArgument(
+            "Accounts cannot refer themselves".to_string(),
+        ));
+    }
+    if referrers().contains_key(&referee)? {
+        return Err(ContractError::InvalidArgument(
+            "Referrer already registered".to_string(),
+        ));
+    }
+    if active_accounts().contains_key(&referee)? {
+        return Err(ContractError::InvalidArgument(
+            "Existing users cannot be referred".to_string(),
+        ));
+    }
+    if referrers().get(&args.referrer)?.as_deref() == Some(referee.as_str()) {
+        return Err(ContractError::InvalidArgument(
+            "Accounts cannot refer their own referrer".to_string(),
+        ));
+    }
+    let mut stats = load_stats(&args.referrer)?;
+    if stats.referees >= config.max_referees {
+        return Err(ContractError::InvalidArgument(
+            "Referrer has reached the referee limit".to_string(),
+        ));
+    }
+    stats.referees += 1;
+    referrer_stats().set(&args.referrer, &stats)?;
+    referrers().set(&referee, &args.referrer)?;
+
+    event!("ReferralRegistered", referrer: args.referrer, referee: referee);
+    Ok(())
+}
+
+fn execute_report_action() -> ContractResult<u64> {
+    let reporter = context().sender().to_string();
+    let mut config = load_config()?;
+    let reward_bps = reporters()
+        .get(&reporter)?
+        .ok_or(ContractError::Unauthorized)?;
+    let args: ReportArgs = read_args()?;
+    if !active_accounts().contains_key(&args.account)? {
+        active_accounts().set(&args.account, &true)?;
+    }
+    let Some(referrer) = referrers().get(&args.account)? else {
+        return Ok(0);
+    };
+    let mut stats = load_stats(&referrer)?;
+    let reward = reward_for(args.value, reward_bps, &stats, &config)?;
+    if reward == 0 {
+        return Ok(0);
+    }
+    stats.earned += reward;
+    config.budget -= reward;
+    referrer_stats().set(&referrer, &stats)?;
+    save_config(&config)?;
+
+    event!("RewardAccrued",
+        referrer: referrer,
+        referee: args.account,
+        reporter: reporter,
+        value: args.value,
+        reward: reward
+    );
+    Ok(reward)
+}
+
+fn execute_claim() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let referrer = context().sender().to_string();
+    let config = load_config()?;
+    let mut stats = load_stats(&referrer)?;
+    let amount = stats.earned - stats.claimed;
+    validation::validate_positive_amount(amount)?;
+    stats.claimed = stats.earned;
+    referrer_stats().set(&referrer, &stats)?;
+
+    let call = encode_call(&TokenTransferArgs {
+        to: referrer.clone(),
+        amount,
+    })?;
+    context().call_contract(&config.reward_token, "transfer", &call)?;
+
+    event!("RewardsClaimed", referrer: referrer, amount: amount);
+    Ok(amount)
+}
+
+/// Initialize the program; the caller becomes the owner
+///
+/// # Arguments
+/// * `reward_token` - CRC-20 rewards are paid in
+/// * `referrer_cap` - Most a single referrer can ever earn
+/// * `max_referees` - Most referees per referrer (1-10000)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Change the per-referrer limits (owner only)
+///
+/// Lowering them does not take back rewards or referees already recorded.
+///
+/// # Arguments
+/// * `referrer_cap` - Most a single referrer can ever earn
+/// * `max_referees` - Most referees per referrer (1-10000)
+#[unsafe(no_mangle)]
+pub extern "C" fn set_limits() {
+    if let Err(err) = execute_set_limits() {
+        log!("set_limits failed: {}", err);
+    }
+}
+
+/// Allow a contract to report actions, at its own reward rate (owner only)
+///
+/// # Arguments
+/// * `reporter` - Integrated contract
+/// * `reward_bps` - Share of each action's value paid to the referrer (max 5000); 0 removes the reporter
+#[unsafe(no_mangle)]
+pub extern "C" fn set_reporter() {
+    if let Err(err) = execute_set_reporter() {
+        log!("set_reporter failed: {}", err);
+    }
+}
+
+/// Add reward tokens to the budget (owner only)
+///
+/// # Arguments
+/// * `amount` - Tokens pulled from the owner
+#[unsafe(no_mangle)]
+pub extern "C" fn fund() {
+    if let Err(err) = execute_fund() {
+        log!("fund failed: {}", err);
+    }
+}
+
+/// Record who referred the caller; only possible once
+///
+/// # Arguments
+/// * `referrer` - Referring account
+#[unsafe(no_mangle)]
+pub extern "C" fn register() {
+    if let Err(err) = execute_register() {
+        log!("register failed: {}", err);
+    }
+}
+
+/// Report a qualifying action by an account (registered reporters only)
+///
+/// # Arguments
+/// * `account` - Account that performed the action
+/// * `value` - Value of the action, in reward token units
+///
+/// # Returns
+/// Reward accrued to the account's referrer, 0 if none (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn report_action() {
+    if let Err(err) = execute_report_action().and_then(|reward| try_respond(&reward)) {
+        log!("report_action failed: {}", err);
+    }
+}
+
+/// Pay out the caller's accrued rewards
+///
+/// # Returns
+/// Amount paid (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn claim() {
+    if let Err(err) = execute_claim().and_then(|amount| try_respond(&amount)) {
+        log!("claim failed: {}", err);
+    }
+}
+
+/// Get the account that referred an account
+///
+/// # Arguments
+/// * `account` - Referee address
+///
+/// # Returns
+/// `Option<String>`
+#[unsafe(no_mangle)]
+pub extern "C" fn referrer_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| referrers().get(&args.account))
+        .and_then(|referrer| try_respond(&referrer));
+    if let Err(err) = result {
+        log!("referrer_of failed: {}", err);
+    }
+}
+
+/// Get a referrer's referee count and rewards
+///
+/// # Arguments
+/// * `account` - Referrer address
+///
+/// # Returns
+/// `ReferrerStats`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_referrer_stats() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| load_stats(&args.account))
+        .and_then(|stats| try_respond(&stats));
+    if let Err(err) = result {
+        log!("get_referrer_stats failed: {}", err);
+    }
+}
+
+/// Get a reporter's reward rate
+///
+/// # Arguments
+/// * `account` - Reporter address
+///
+/// # Returns
+/// Reward rate in basis points, 0 if not a reporter (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn reporter_rate() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| Ok(reporters().get(&args.account)?.unwrap_or(0)))
+        .and_then(|bps| try_respond(&bps));
+    if let Err(err) = result {
+        log!("reporter_rate failed: {}", err);
+    }
+}
+
+/// Get the program configuration
+///
+/// # Returns
+/// `ReferralConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_DAVE: &str = "0x0000000000000000000000000000000000000e05";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const REWARD: &str = "0x0000000000000000000000000000000000000f01";
+    const DEX: &str = "0x0000000000000000000000000000000000000f02";
+    const LENDING: &str = "0x0000000000000000000000000000000000000f03";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// Cap 1000, three referees each, 5000 funded, DEX pays 1% and lending 0.5%
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        for method in ["transfer", "transfer_from"] {
+            mock::register_contract(REWARD, method, |_| Ok(Vec::new()));
+        }
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                reward_token: REWARD.to_string(),
+                referrer_cap: 1_000,
+                max_referees: 3,
+            }),
+        );
+        for (reporter, reward_bps) in [(DEX, 100), (LENDING, 50)] {
+            call::<()>(
+                ADDR_OWNER,
+                set_reporter,
+                encode(&ReporterArgs {
+                    reporter: reporter.to_string(),
+                    reward_bps,
+                }),
+            );
+        }
+        call::<()>(ADDR_OWNER, fund, encode(&AmountArgs { amount: 5_000 }));
+    }
+
+    fn refer(referee: &str, referrer: &str) {
+        call::<()>(
+            referee,
+            register,
+            encode(&RegisterArgs {
+                referrer: referrer.to_string(),
+            }),
+        );
+    }
+
+    fn report(reporter: &str, account: &str, value: u64) -> Option<u64> {
+        call(
+            reporter,
+            report_action,
+            encode(&ReportArgs {
+                account: account.to_string(),
+                value,
+            }),
+        )
+    }
+
+    fn referrer(account: &str) -> Option<String> {
+        referrers().get(&account.to_string()).unwrap()
+    }
+
+    #[test]
+    fn referees_register_their_referrer_once() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        refer(ADDR_BOB, ADDR_ALICE);
+        refer(ADDR_BOB, ADDR_CAROL);
+        assert_eq!(referrer(ADDR_BOB).as_deref(), Some(ADDR_ALICE));
+        assert_eq!(
+            call::<Option<String>>(
+                ADDR_DAVE,
+                referrer_of,
+                encode(&AccountArgs {
+                    account: ADDR_BOB.to_string(),
+                })
+            ),
+            Some(Some(ADDR_ALICE.to_string()))
+        );
+        assert_eq!(load_stats(ADDR_ALICE).unwrap().referees, 1);
+    }
+
+    #[test]
+    fn sybil_limits_reject_suspicious_links() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        refer(ADDR_ALICE, ADDR_ALICE);
+        assert_eq!(referrer(ADDR_ALICE), None);
+
+        refer(ADDR_BOB, ADDR_ALICE);
+        refer(ADDR_ALICE, ADDR_BOB);
+        assert_eq!(referrer(ADDR_ALICE), None);
+
+        // Dave already used an integrated contract.
+        report(DEX, ADDR_DAVE, 100);
+        refer(ADDR_DAVE, ADDR_ALICE);
+        assert_eq!(referrer(ADDR_DAVE), None);
+
+        refer(ADDR_CAROL, ADDR_ALICE);
+        refer(ADDR_OWNER, ADDR_ALICE);
+        refer(CONTRACT, ADDR_ALICE);
+        assert_eq!(referrer(CONTRACT), None);
+        assert_eq!(load_stats(ADDR_ALICE).unwrap().referees, 3);
+    }
+
+    #[test]
+    fn reporters_accrue_rewards_at_their_own_rate() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        refer(ADDR_BOB, ADDR_ALICE);
+        assert_eq!(report(DEX, ADDR_BOB, 10_000), Some(100));
+        assert_eq!(report(LENDING, ADDR_BOB, 10_000), Some(50));
+        assert_eq!(report(DEX, ADDR_CAROL, 10_000), Some(0));
+        assert_eq!(report(ADDR_DAVE, ADDR_BOB, 10_000), None);
+
+        call::<()>(
+            ADDR_OWNER,
+            set_reporter,
+            encode(&ReporterArgs {
+                reporter: LENDING.to_string(),
+                reward_bps: 0,
+            }),
+        );
+        assert_eq!(report(LENDING, ADDR_BOB, 10_000), None);
+        assert_eq!(load_stats(ADDR_ALICE).unwrap().earned, 150);
+        assert_eq!(load_config().unwrap().budget, 4_850);
+        // Setup's two reporters and funding, then the removal; reports are not logged.
+        assert_eq!(admin_log::next_sequence().unwrap(), 4);
+    }
+
+    #[test]
+    fn rewards_stop_at_the_referrer_cap_and_budget() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        refer(ADDR_BOB, ADDR_ALICE);
+        refer(ADDR_DAVE, ADDR_CAROL);
+        assert_eq!(report(DEX, ADDR_BOB, 90_000), Some(900));
+        assert_eq!(report(DEX, ADDR_BOB, 90_000), Some(100));
+        assert_eq!(report(DEX, ADDR_BOB, 90_000), Some(0));
+
+        call::<()>(
+            ADDR_OWNER,
+            set_limits,
+            encode(&LimitsArgs {
+                referrer_cap: 10_000,
+                max_referees: 3,
+            }),
+        );
+        // 4000 of the budget is left.
+        assert_eq!(report(DEX, ADDR_DAVE, 1_000_000), Some(4_000));
+        assert_eq!(report(DEX, ADDR_BOB, 1_000_000), Some(0));
+        assert_eq!(load_config().unwrap().budget, 0);
+    }
+
+    #[test]
+    fn referrers_claim_accrued_rewards() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        refer(ADDR_BOB, ADDR_ALICE);
+        report(DEX, ADDR_BOB, 25_000);
+        mock::take_contract_calls();
+
+        assert_eq!(call::<u64>(ADDR_ALICE, claim, Vec::new()), Some(250));
+        let calls = mock::take_contract_calls();
+        let paid: TokenTransferArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!((paid.to.as_str(), paid.amount), (ADDR_ALICE, 250));
+        assert_eq!(call::<u64>(ADDR_ALICE, claim, Vec::new()), None);
+
+        report(DEX, ADDR_BOB, 10_000);
+        assert_eq!(call::<u64>(ADDR_ALICE, claim, Vec::new()), Some(100));
+        assert_eq!(
+            load_stats(ADDR_ALICE).unwrap(),
+            ReferrerStats {
+                referees: 1,
+                earned: 350,
+                claimed: 350,
+            }
+        );
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "referrals-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
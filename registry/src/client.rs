@@ -0,0 +1,76 @@
+//! Runtime resolution helpers
+//!
+//! Thin wrappers around the registry's query entrypoints for contracts that
+//! look up their dependencies by name.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+/// 4-byte interface identifier (e.g. the first bytes of a method-set hash)
+pub type InterfaceId = [u8; 4];
+
+/// Current registration for a key, as returned by `resolve`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedEntry {
+    pub key: String,
+    pub address: String,
+    pub version: u32,
+    pub interface_id: InterfaceId,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct KeyArgs {
+    pub key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct InterfaceArgs {
+    pub interface_id: InterfaceId,
+}
+
+fn query(registry: &str, method: &str, args: &[u8]) -> ContractResult<ResolvedEntry> {
+    let response = context().call_contract(registry, method, args)?;
+    if response.is_empty() {
+        return Err(ContractError::ContractCallFailed(format!(
+            "Registry returned nothing for {}",
+            method
+        )));
+    }
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Resolve the current registration for `key`.
+pub fn resolve(registry: &str, key: &str) -> ContractResult<ResolvedEntry> {
+    let args = postcard::to_allocvec(&KeyArgs {
+        key: key.to_string(),
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    let entry = query(registry, "resolve", &args)?;
+    if entry.key != key {
+        return Err(ContractError::ContractCallFailed(
+            "Registry answered for a different key".to_string(),
+        ));
+    }
+    Ok(entry)
+}
+
+/// Resolve just the current address for `key`.
+pub fn resolve_address(registry: &str, key: &str) -> ContractResult<String> {
+    resolve(registry, key).map(|entry| entry.address)
+}
+
+/// Resolve the current registration implementing `interface_id`.
+pub fn resolve_interface(
+    registry: &str,
+    interface_id: InterfaceId,
+) -> ContractResult<ResolvedEntry> {
+    let args = postcard::to_allocvec(&InterfaceArgs { interface_id })
+        .map_err(|_| ContractError::SerializationFailed)?;
+    let entry = query(registry, "resolve_interface", &args)?;
+    if entry.interface_id != interface_id {
+        return Err(ContractError::ContractCallFailed(
+            "Registry answered for a different interface".to_string(),
+        ));
+    }
+    Ok(entry)
+}
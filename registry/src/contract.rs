@@ -0,0 +1,632 @@
+//! Registry entrypoints
+
+use crate::client::{InterfaceArgs, InterfaceId, KeyArgs, ResolvedEntry};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "registry_config";
+const ENTRIES_PREFIX: &str = "entries";
+const VERSIONS_PREFIX: &str = "versions";
+const INTERFACES_PREFIX: &str = "interfaces";
+const MAX_KEY_BYTES: usize = 32;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Registry owner
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegistryConfig {
+    pub owner: String,
+}
+
+/// Current state of a registered key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegistryEntry {
+    pub interface_id: InterfaceId,
+    pub current_version: u32,
+    pub deprecated: bool,
+}
+
+/// One historical address for a key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VersionRecord {
+    pub address: String,
+    pub registered_at: u64,
+    pub registered_height: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegisterArgs {
+    key: String,
+    interface_id: InterfaceId,
+    address: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UpgradeArgs {
+    key: String,
+    address: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VersionArgs {
+    key: String,
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferOwnershipArgs {
+    new_owner: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_config() -> ContractResult<RegistryConfig> {
+    storage()
+        .get::<RegistryConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Registry not initialized".to_string()))
+}
+
+fn save_config(config: &RegistryConfig) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<RegistryConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn entries() -> Map<String, RegistryEntry> {
+    Map::new(ENTRIES_PREFIX)
+}
+
+fn versions() -> Map<(String, u32), VersionRecord> {
+    Map::new(VERSIONS_PREFIX)
+}
+
+fn interfaces() -> Map<InterfaceId, String> {
+    Map::new(INTERFACES_PREFIX)
+}
+
+/// Keys are 1-32 bytes of lowercase ASCII letters, digits, and `-`.
+fn validate_key(key: &str) -> ContractResult<()> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_BYTES
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    if !valid {
+        return Err(ContractError::InvalidArgument(format!(
+            "Key must be 1-{} chars of [a-z0-9-]",
+            MAX_KEY_BYTES
+        )));
+    }
+    Ok(())
+}
+
+fn load_entry(key: &str) -> ContractResult<RegistryEntry> {
+    entries()
+        .get(&key.to_string())?
+        .ok_or_else(|| ContractError::InvalidArgument(format!("Unknown key: {}", key)))
+}
+
+fn record_version(key: &str, version: u32, address: &str) -> ContractResult<()> {
+    let ctx = context();
+    versions().set(
+        &(key.to_string(), version),
+        &VersionRecord {
+            address: address.to_string(),
+            registered_at: ctx.block_timestamp(),
+            registered_height: ctx.block_height(),
+        },
+    )
+}
+
+fn resolve_key(key: &str) -> ContractResult<ResolvedEntry> {
+    let entry = load_entry(key)?;
+    if entry.deprecated {
+        return Err(ContractError::InvalidArgument(format!(
+            "Key is deprecated: {}",
+            key
+        )));
+    }
+    let record = versions()
+        .get(&(key.to_string(), entry.current_version))?
+        .ok_or(ContractError::StorageReadFailed)?;
+    Ok(ResolvedEntry {
+        key: key.to_string(),
+        address: record.address,
+        version: entry.current_version,
+        interface_id: entry.interface_id,
+    })
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Registry already initialized".to_string(),
+        ));
+    }
+    let owner = context().sender().to_string();
+    validation::validate_address(&owner)?;
+    save_config(&RegistryConfig {
+        owner: owner.clone(),
+    })?;
+
+    event!("RegistryInitialized", owner: owner);
+    Ok(())
+}
+
+fn execute_register() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: RegisterArgs = read_args()?;
+    validate_key(&args.key)?;
+    validation::validate_address(&args.address)?;
+
+    let mut registry = entries();
+    if registry.contains_key(&args.key)? {
+        return Err(ContractError::InvalidArgument(
+            "Key already registered; use upgrade".to_string(),
+        ));
+    }
+    let mut interface_index = interfaces();
+    if interface_index.contains_key(&args.interface_id)? {
+        return Err(ContractError::InvalidArgument(
+            "Interface already bound to another key".to_string(),
+        ));
+    }
+
+    record_version(&args.key, 1, &args.address)?;
+    registry.set(
+        &args.key,
+        &RegistryEntry {
+            interface_id: args.interface_id,
+            current_version: 1,
+            deprecated: false,
+        },
+    )?;
+    interface_index.set(&args.interface_id, &args.key)?;
+
+    event!("ContractRegistered",
+        key: args.key,
+        interface_id: hex::encode(args.interface_id),
+        address: args.address,
+        version: 1
+    );
+    admin_log::record("register", &args.key, "", &args.address)?;
+    Ok(())
+}
+
+fn execute_upgrade() -> ContractResult<u32> {
+    load_owned_config()?;
+    let args: UpgradeArgs = read_args()?;
+    validation::validate_address(&args.address)?;
+    let mut entry = load_entry(&args.key)?;
+    if entry.deprecated {
+        return Err(ContractError::InvalidArgument(
+            "Cannot upgrade a deprecated key".to_string(),
+        ));
+    }
+
+    let previous = versions()
+        .get(&(args.key.clone(), entry.current_version))?
+        .ok_or(ContractError::StorageReadFailed)?;
+    if previous.address == args.address {
+        return Err(ContractError::InvalidArgument(
+            "Address is already current".to_string(),
+        ));
+    }
+
+    let version = entry
+        .current_version
+        .checked_add(1)
+        .ok_or(ContractError::Overflow)?;
+    record_version(&args.key, version, &args.address)?;
+    entry.current_version = version;
+    entries().set(&args.key, &entry)?;
+
+    event!("ContractUpgraded",
+        key: args.key,
+        previous: previous.address,
+        address: args.address,
+        version: version
+    );
+    admin_log::record("upgrade", &args.key, &previous.address, &args.address)?;
+    Ok(version)
+}
+
+fn execute_deprecate() -> ContractResult<()> {
+    load_owned_config()?;
+    let args: KeyArgs = read_args()?;
+    let mut entry = load_entry(&args.key)?;
+    if entry.deprecated {
+        return Err(ContractError::InvalidArgument(
+            "Key already deprecated".to_string(),
+        ));
+    }
+
+    entry.deprecated = true;
+    entries().set(&args.key, &entry)?;
+
+    event!("ContractDeprecated", key: args.key, version: entry.current_version);
+    admin_log::record("deprecate", &args.key, false, true)?;
+    Ok(())
+}
+
+fn execute_transfer_ownership() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let args: TransferOwnershipArgs = read_args()?;
+    validation::validate_address(&args.new_owner)?;
+
+    let previous = core::mem::replace(&mut config.owner, args.new_owner.clone());
+    save_config(&config)?;
+
+    event!("OwnershipTransferred", previous: previous, owner: args.new_owner);
+    admin_log::record(
+        admin_log::TRANSFER_OWNERSHIP,
+        admin_log::OWNER_TARGET,
+        &previous,
+        &args.new_owner,
+    )?;
+    Ok(())
+}
+
+fn execute_resolve() -> ContractResult<()> {
+    let args: KeyArgs = read_args()?;
+    try_respond(&resolve_key(&args.key)?)
+}
+
+fn execute_resolve_interface() -> ContractResult<()> {
+    let args: InterfaceArgs = read_args()?;
+    let key = interfaces()
+        .get(&args.interface_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown interface".to_string()))?;
+    try_respond(&resolve_key(&key)?)
+}
+
+fn execute_get_entry() -> ContractResult<()> {
+    let args: KeyArgs = read_args()?;
+    try_respond(&load_entry(&args.key)?)
+}
+
+fn execute_get_version() -> ContractResult<()> {
+    let args: VersionArgs = read_args()?;
+    let record = versions()
+        .get(&(args.key, args.version))?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown version".to_string()))?;
+    try_respond(&record)
+}
+
+/// Initialize the registry; the caller becomes its owner
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Register a new key at version 1 (owner only)
+///
+/// # Arguments
+/// * `key` - Human-readable name, e.g. `"amm-router"`
+/// * `interface_id` - 4-byte interface identifier, unique across keys
+/// * `address` - Contract address
+#[unsafe(no_mangle)]
+pub extern "C" fn register() {
+    if let Err(err) = execute_register() {
+        log!("register failed: {}", err);
+    }
+}
+
+/// Point a key at a new address, recording a new version (owner only)
+///
+/// # Arguments
+/// * `key` - Registered key
+/// * `address` - New contract address
+#[unsafe(no_mangle)]
+pub extern "C" fn upgrade() {
+    if let Err(err) = execute_upgrade() {
+        log!("upgrade failed: {}", err);
+    }
+}
+
+/// Stop a key from resolving; history stays queryable (owner only)
+///
+/// # Arguments
+/// * `key` - Registered key
+#[unsafe(no_mangle)]
+pub extern "C" fn deprecate() {
+    if let Err(err) = execute_deprecate() {
+        log!("deprecate failed: {}", err);
+    }
+}
+
+/// Hand the registry to a new owner (owner only)
+///
+/// # Arguments
+/// * `new_owner` - New owner address
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_ownership() {
+    if let Err(err) = execute_transfer_ownership() {
+        log!("transfer_ownership failed: {}", err);
+    }
+}
+
+/// Resolve a key to its current address
+///
+/// # Arguments
+/// * `key` - Registered key
+///
+/// # Returns
+/// `ResolvedEntry`; fails for unknown or deprecated keys
+#[unsafe(no_mangle)]
+pub extern "C" fn resolve() {
+    if let Err(err) = execute_resolve() {
+        log!("resolve failed: {}", err);
+    }
+}
+
+/// Resolve an interface id to the current address of its key
+///
+/// # Arguments
+/// * `interface_id` - 4-byte interface identifier
+///
+/// # Returns
+/// `ResolvedEntry`
+#[unsafe(no_mangle)]
+pub extern "C" fn resolve_interface() {
+    if let Err(err) = execute_resolve_interface() {
+        log!("resolve_interface failed: {}", err);
+    }
+}
+
+/// Get a key's interface, current version, and deprecation flag
+///
+/// # Arguments
+/// * `key` - Registered key
+///
+/// # Returns
+/// `RegistryEntry`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_entry() {
+    if let Err(err) = execute_get_entry() {
+        log!("get_entry failed: {}", err);
+    }
+}
+
+/// Get a historical version of a key
+///
+/// # Arguments
+/// * `key` - Registered key
+/// * `version` - Version number, starting at 1
+///
+/// # Returns
+/// `VersionRecord`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_version() {
+    if let Err(err) = execute_get_version() {
+        log!("get_version failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::client;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_MALLORY: &str = "0x0000000000000000000000000000000000000b02";
+    const ROUTER_V1: &str = "0x0000000000000000000000000000000000000c03";
+    const ROUTER_V2: &str = "0x0000000000000000000000000000000000000c04";
+    const REGISTRY: &str = "0x0000000000000000000000000000000000000f00";
+    const ROUTER_IFACE: InterfaceId = *b"AMMR";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(REGISTRY);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::set_sender(ADDR_OWNER);
+        initialize();
+        mock::set_call_data(&encode(&RegisterArgs {
+            key: "amm-router".to_string(),
+            interface_id: ROUTER_IFACE,
+            address: ROUTER_V1.to_string(),
+        }));
+        register();
+    }
+
+    fn resolved(key: &str) -> Option<ResolvedEntry> {
+        mock::set_call_data(&encode(&KeyArgs {
+            key: key.to_string(),
+        }));
+        resolve();
+        let data = mock::take_return_data();
+        (!data.is_empty()).then(|| postcard::from_bytes(&data).expect("resolved entry"))
+    }
+
+    fn upgrade_to(sender: &str, address: &str) {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(&UpgradeArgs {
+            key: "amm-router".to_string(),
+            address: address.to_string(),
+        }));
+        upgrade();
+    }
+
+    #[test]
+    fn register_and_resolve_by_key_and_interface() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        let entry = resolved("amm-router").expect("resolves");
+        assert_eq!(entry.address, ROUTER_V1);
+        assert_eq!(entry.version, 1);
+
+        mock::set_call_data(&encode(&InterfaceArgs {
+            interface_id: ROUTER_IFACE,
+        }));
+        resolve_interface();
+        let by_iface: ResolvedEntry = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(by_iface, entry);
+        assert!(resolved("oracle").is_none());
+    }
+
+    #[test]
+    fn upgrade_moves_pointer_and_keeps_history() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        upgrade_to(ADDR_OWNER, ROUTER_V2);
+        let entry = resolved("amm-router").unwrap();
+        assert_eq!((entry.address.as_str(), entry.version), (ROUTER_V2, 2));
+
+        mock::set_call_data(&encode(&VersionArgs {
+            key: "amm-router".to_string(),
+            version: 1,
+        }));
+        get_version();
+        let v1: VersionRecord = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(v1.address, ROUTER_V1);
+
+        upgrade_to(ADDR_OWNER, ROUTER_V2);
+        assert_eq!(resolved("amm-router").unwrap().version, 2);
+        // The registration and the one upgrade that went through
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+    }
+
+    #[test]
+    fn deprecated_keys_stop_resolving() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        mock::set_call_data(&encode(&KeyArgs {
+            key: "amm-router".to_string(),
+        }));
+        deprecate();
+        assert!(resolved("amm-router").is_none());
+
+        upgrade_to(ADDR_OWNER, ROUTER_V2);
+        assert_eq!(load_entry("amm-router").unwrap().current_version, 1);
+    }
+
+    #[test]
+    fn only_owner_mutates_and_keys_are_validated() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        upgrade_to(ADDR_MALLORY, ROUTER_V2);
+        assert_eq!(resolved("amm-router").unwrap().address, ROUTER_V1);
+
+        mock::set_sender(ADDR_OWNER);
+        for key in [
+            "",
+            "AMM-Router",
+            "oracle feed",
+            "a-key-that-is-far-too-long-to-fit",
+        ] {
+            mock::set_call_data(&encode(&RegisterArgs {
+                key: key.to_string(),
+                interface_id: *b"ORCL",
+                address: ROUTER_V2.to_string(),
+            }));
+            register();
+        }
+        mock::set_call_data(&encode(&RegisterArgs {
+            key: "oracle".to_string(),
+            interface_id: ROUTER_IFACE,
+            address: ROUTER_V2.to_string(),
+        }));
+        register();
+        assert!(resolved("oracle").is_none(), "interface ids are unique");
+    }
+
+    #[test]
+    fn client_resolves_through_cross_contract_call() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        upgrade_to(ADDR_OWNER, ROUTER_V2);
+
+        mock::register_contract(REGISTRY, "resolve", |args| {
+            mock::set_call_data(args);
+            resolve();
+            Ok(mock::take_return_data())
+        });
+        assert_eq!(
+            client::resolve_address(REGISTRY, "amm-router").unwrap(),
+            ROUTER_V2
+        );
+        assert!(client::resolve(REGISTRY, "oracle").is_err());
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "registry-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
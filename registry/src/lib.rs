@@ -0,0 +1,31 @@
+//! Contract Registry
+//!
+//! Maps human-readable keys such as `"amm-router"` or `"oracle"` and 4-byte
+//! interface ids to deployed contract addresses. Every key keeps its full
+//! version history; upgrades move the current pointer and deprecation stops
+//! the key from resolving. The [`client`] module lets other contracts resolve
+//! entries at runtime instead of hard-coding addresses.
+//!
+//! ## Embedding
+//! ```toml
+//! registry-contract = { path = "../registry", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use registry_contract::client;
+//!
+//! let router = client::resolve_address(&config.registry, "amm-router")?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod client;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
@@ -0,0 +1,814 @@
+//! Rock-Paper-Scissors
+//!
+//! A two-player wagering game built on commit-reveal. Each player stakes
+//! the same CRC-20 wager together with a hash of their move and a secret
+//! salt, so neither can see the other's move before committing. Once both
+//! have committed, each reveals within the game's reveal window and the
+//! contract pays the pot to the winner or refunds both on a draw. A player
+//! who never reveals forfeits to the one who did.
+//!
+//! ## Features
+//! - Open games or games reserved for a named opponent
+//! - Commitments bound to the player, so an opponent cannot copy them
+//! - Timeout claims and explicit forfeits
+//! - Creator can cancel a game nobody has joined
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use commit_reveal::{Commitment, Salt};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const NEXT_GAME_KEY: &str = "next_game_id";
+const GAMES_PREFIX: &str = "games";
+const COMMITMENT_PURPOSE: &str = "rock-paper-scissors";
+const MIN_REVEAL_WINDOW: u64 = 10 * 60;
+const MAX_REVEAL_WINDOW: u64 = 7 * 24 * 60 * 60;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// A move
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Move {
+    /// Whether `self` wins against `other`.
+    pub fn beats(self, other: Move) -> bool {
+        matches!(
+            (self, other),
+            (Move::Rock, Move::Scissors)
+                | (Move::Paper, Move::Rock)
+                | (Move::Scissors, Move::Paper)
+        )
+    }
+}
+
+/// Where a game is in its lifecycle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+    /// Waiting for a second player
+    Open,
+    /// Both players committed; reveals are open until `reveal_deadline`
+    Committed,
+    /// The pot went to `winner`, or back to both players if `None`
+    Finished { winner: Option<String> },
+    /// The creator withdrew before anyone joined
+    Cancelled,
+}
+
+/// One side of a game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Player {
+    pub address: String,
+    pub commitment: [u8; 32],
+    pub revealed: Option<Move>,
+}
+
+/// A game and its stakes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub token: String,
+    /// Stake per player; the pot is twice this
+    pub wager: u64,
+    pub creator: Player,
+    pub challenger: Option<Player>,
+    /// Only this address may join, if set
+    pub opponent: Option<String>,
+    pub reveal_window: u64,
+    /// Set when the challenger joins
+    pub reveal_deadline: u64,
+    pub status: GameStatus,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateGameArgs {
+    token: String,
+    wager: u64,
+    commitment: [u8; 32],
+    opponent: Option<String>,
+    reveal_window: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JoinGameArgs {
+    game_id: u64,
+    commitment: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct RevealArgs {
+    game_id: u64,
+    player_move: Move,
+    salt: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct GameIdArgs {
+    game_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+/// Commitment `player` submits for `player_move`: the [`commit_reveal`]
+/// commitment to `player_move` for the purpose `"rock-paper-scissors"`.
+pub fn move_commitment(player: &str, player_move: Move, salt: &Salt) -> ContractResult<Commitment> {
+    commit_reveal::commitment(COMMITMENT_PURPOSE, player, &player_move, salt)
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn games() -> Map<u64, Game> {
+    Map::new(GAMES_PREFIX)
+}
+
+fn load_game(game_id: u64) -> ContractResult<Game> {
+    games()
+        .get(&game_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown game".to_string()))
+}
+
+fn pull_wager(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn pay(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn require_committed(game: &Game) -> ContractResult<&Player> {
+    match (&game.status, &game.challenger) {
+        (GameStatus::Committed, Some(challenger)) => Ok(challenger),
+        _ => Err(ContractError::InvalidArgument(
+            "Game is not in play".to_string(),
+        )),
+    }
+}
+
+/// Record the result, then pay the pot to `winner` or refund both players.
+fn finish(game_id: u64, game: &mut Game, winner: Option<String>) -> ContractResult<()> {
+    let challenger = require_committed(game)?.address.clone();
+    game.status = GameStatus::Finished {
+        winner: winner.clone(),
+    };
+    games().set(&game_id, game)?;
+
+    match &winner {
+        Some(winner) => pay(&game.token, winner, safe_math::add(game.wager, game.wager)?)?,
+        None => {
+            pay(&game.token, &game.creator.address, game.wager)?;
+            pay(&game.token, &challenger, game.wager)?;
+        }
+    }
+
+    event!("GameFinished",
+        game_id: game_id,
+        winner: winner.unwrap_or_default(),
+        pot: game.wager * 2
+    );
+    Ok(())
+}
+
+fn execute_create_game() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let creator = ctx.sender().to_string();
+    let args: CreateGameArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    validation::validate_positive_amount(args.wager)?;
+    if let Some(opponent) = &args.opponent {
+        validation::validate_address(opponent)?;
+        if *opponent == creator {
+            return Err(ContractError::InvalidArgument(
+                "Cannot play against yourself".to_string(),
+            ));
+        }
+    }
+    if !(MIN_REVEAL_WINDOW..=MAX_REVEAL_WINDOW).contains(&args.reveal_window) {
+        return Err(ContractError::InvalidArgument(
+            "Reveal window must be between 10 minutes and 7 days".to_string(),
+        ));
+    }
+    // Double the wager must fit the pot.
+    safe_math::add(args.wager, args.wager)?;
+
+    pull_wager(&args.token, &creator, args.wager)?;
+    let game_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_GAME_KEY)?.unwrap_or(0);
+        store.set(NEXT_GAME_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    games().set(
+        &game_id,
+        &Game {
+            token: args.token.clone(),
+            wager: args.wager,
+            creator: Player {
+                address: creator.clone(),
+                commitment: args.commitment,
+                revealed: None,
+            },
+            challenger: None,
+            opponent: args.opponent.clone(),
+            reveal_window: args.reveal_window,
+            reveal_deadline: 0,
+            status: GameStatus::Open,
+        },
+    )?;
+
+    event!("GameCreated",
+        game_id: game_id,
+        creator: creator,
+        token: args.token,
+        wager: args.wager,
+        opponent: args.opponent.unwrap_or_default()
+    );
+    Ok(game_id)
+}
+
+fn execute_join_game() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let challenger = ctx.sender().to_string();
+    let args: JoinGameArgs = read_args()?;
+    let mut game = load_game(args.game_id)?;
+    if game.status != GameStatus::Open {
+        return Err(ContractError::InvalidArgument(
+            "Game is not open".to_string(),
+        ));
+    }
+    if challenger == game.creator.address {
+        return Err(ContractError::InvalidArgument(
+            "Cannot play against yourself".to_string(),
+        ));
+    }
+    if game
+        .opponent
+        .as_ref()
+        .is_some_and(|opponent| *opponent != challenger)
+    {
+        return Err(ContractError::Unauthorized);
+    }
+
+    pull_wager(&game.token, &challenger, game.wager)?;
+    game.challenger = Some(Player {
+        address: challenger.clone(),
+        commitment: args.commitment,
+        revealed: None,
+    });
+    game.reveal_deadline = safe_math::add(ctx.block_timestamp(), game.reveal_window)?;
+    game.status = GameStatus::Committed;
+    games().set(&args.game_id, &game)?;
+
+    event!("GameJoined",
+        game_id: args.game_id,
+        challenger: challenger,
+        reveal_deadline: game.reveal_deadline
+    );
+    Ok(())
+}
+
+fn execute_reveal() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let caller = ctx.sender().to_string();
+    let args: RevealArgs = read_args()?;
+    let mut game = load_game(args.game_id)?;
+    require_committed(&game)?;
+    if ctx.block_timestamp() >= game.reveal_deadline {
+        return Err(ContractError::InvalidArgument(
+            "Reveal window has closed".to_string(),
+        ));
+    }
+
+    let player = if caller == game.creator.address {
+        &mut game.creator
+    } else {
+        game.challenger
+            .as_mut()
+            .filter(|challenger| challenger.address == caller)
+            .ok_or(ContractError::Unauthorized)?
+    };
+    if player.revealed.is_some() {
+        return Err(ContractError::InvalidArgument(
+            "Move already revealed".to_string(),
+        ));
+    }
+    commit_reveal::verify(
+        &player.commitment,
+        COMMITMENT_PURPOSE,
+        &caller,
+        &args.player_move,
+        &args.salt,
+    )?;
+    player.revealed = Some(args.player_move);
+
+    event!("MoveRevealed",
+        game_id: args.game_id,
+        player: caller,
+        player_move: format!("{:?}", args.player_move)
+    );
+
+    let challenger = require_committed(&game)?;
+    match (game.creator.revealed, challenger.revealed) {
+        (Some(first), Some(second)) => {
+            let winner = if first.beats(second) {
+                Some(game.creator.address.clone())
+            } else if second.beats(first) {
+                Some(challenger.address.clone())
+            } else {
+                None
+            };
+            finish(args.game_id, &mut game, winner)
+        }
+        _ => games().set(&args.game_id, &game),
+    }
+}
+
+fn execute_claim_timeout() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let args: GameIdArgs = read_args()?;
+    let mut game = load_game(args.game_id)?;
+    let challenger = require_committed(&game)?;
+    if context().block_timestamp() < game.reveal_deadline {
+        return Err(ContractError::InvalidArgument(
+            "Reveal window is still open".to_string(),
+        ));
+    }
+
+    // Whoever revealed wins; if neither did, both are refunded.
+    let winner = match (game.creator.revealed, challenger.revealed) {
+        (Some(_), None) => Some(game.creator.address.clone()),
+        (None, Some(_)) => Some(challenger.address.clone()),
+        _ => None,
+    };
+    finish(args.game_id, &mut game, winner)
+}
+
+fn execute_forfeit() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let caller = context().sender().to_string();
+    let args: GameIdArgs = read_args()?;
+    let mut game = load_game(args.game_id)?;
+    let challenger = require_committed(&game)?;
+    let winner = if caller == game.creator.address {
+        challenger.address.clone()
+    } else if caller == challenger.address {
+        game.creator.address.clone()
+    } else {
+        return Err(ContractError::Unauthorized);
+    };
+
+    event!("GameForfeited", game_id: args.game_id, player: caller);
+    finish(args.game_id, &mut game, Some(winner))
+}
+
+fn execute_cancel_game() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let caller = context().sender().to_string();
+    let args: GameIdArgs = read_args()?;
+    let mut game = load_game(args.game_id)?;
+    if caller != game.creator.address {
+        return Err(ContractError::Unauthorized);
+    }
+    if game.status != GameStatus::Open {
+        return Err(ContractError::InvalidArgument(
+            "Only open games can be cancelled".to_string(),
+        ));
+    }
+
+    game.status = GameStatus::Cancelled;
+    games().set(&args.game_id, &game)?;
+    pay(&game.token, &caller, game.wager)?;
+
+    event!("GameCancelled", game_id: args.game_id);
+    Ok(())
+}
+
+/// Create a game, staking the wager with a committed move (approve first)
+///
+/// # Arguments
+/// * `token` - CRC-20 to wager
+/// * `wager` - Stake per player
+/// * `commitment` - `move_commitment(creator, move, salt)`
+/// * `opponent` - Only this address may join, if set
+/// * `reveal_window` - Seconds both players have to reveal once the game is joined (10 minutes to 7 days)
+///
+/// # Returns
+/// Game id
+#[unsafe(no_mangle)]
+pub extern "C" fn create_game() {
+    let result = execute_create_game().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("create_game failed: {}", err);
+    }
+}
+
+/// Join an open game, matching its wager with a committed move (approve first)
+///
+/// # Arguments
+/// * `game_id` - Game id
+/// * `commitment` - `move_commitment(challenger, move, salt)`
+#[unsafe(no_mangle)]
+pub extern "C" fn join_game() {
+    if let Err(err) = execute_join_game() {
+        log!("join_game failed: {}", err);
+    }
+}
+
+/// Reveal a committed move; the second reveal settles the game
+///
+/// # Arguments
+/// * `game_id` - Game id
+/// * `player_move` - Committed move
+/// * `salt` - Salt used in the commitment
+#[unsafe(no_mangle)]
+pub extern "C" fn reveal() {
+    if let Err(err) = execute_reveal() {
+        log!("reveal failed: {}", err);
+    }
+}
+
+/// Settle a game whose reveal window closed with at most one reveal
+///
+/// The player who revealed takes the pot; if neither revealed, both wagers
+/// are refunded.
+///
+/// # Arguments
+/// * `game_id` - Game id
+#[unsafe(no_mangle)]
+pub extern "C" fn claim_timeout() {
+    if let Err(err) = execute_claim_timeout() {
+        log!("claim_timeout failed: {}", err);
+    }
+}
+
+/// Concede a game in play; the opponent takes the pot
+///
+/// # Arguments
+/// * `game_id` - Game id
+#[unsafe(no_mangle)]
+pub extern "C" fn forfeit() {
+    if let Err(err) = execute_forfeit() {
+        log!("forfeit failed: {}", err);
+    }
+}
+
+/// Cancel a game nobody has joined and recover the wager (creator only)
+///
+/// # Arguments
+/// * `game_id` - Game id
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_game() {
+    if let Err(err) = execute_cancel_game() {
+        log!("cancel_game failed: {}", err);
+    }
+}
+
+/// Get a game
+///
+/// # Arguments
+/// * `game_id` - Game id
+///
+/// # Returns
+/// `Game`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_game() {
+    let result = read_args::<GameIdArgs>()
+        .and_then(|args| load_game(args.game_id))
+        .and_then(|game| try_respond(&game));
+    if let Err(err) = result {
+        log!("get_game failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const START: u64 = 1_736_000_000;
+    const WINDOW: u64 = 60 * 60;
+    const SALT_A: [u8; 32] = [1; 32];
+    const SALT_B: [u8; 32] = [2; 32];
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+    }
+
+    /// Alice opens game 0 for 100 with `alice_move` and Bob joins with `bob_move`.
+    fn start_game(alice_move: Move, bob_move: Move) -> u64 {
+        let data = call(
+            ADDR_ALICE,
+            create_game,
+            encode(&CreateGameArgs {
+                token: TOKEN.to_string(),
+                wager: 100,
+                commitment: move_commitment(ADDR_ALICE, alice_move, &SALT_A).unwrap(),
+                opponent: None,
+                reveal_window: WINDOW,
+            }),
+        );
+        let game_id = postcard::from_bytes(&data).unwrap();
+        call(
+            ADDR_BOB,
+            join_game,
+            encode(&JoinGameArgs {
+                game_id,
+                commitment: move_commitment(ADDR_BOB, bob_move, &SALT_B).unwrap(),
+            }),
+        );
+        mock::take_contract_calls();
+        game_id
+    }
+
+    fn reveal_as(player: &str, game_id: u64, player_move: Move, salt: [u8; 32]) {
+        call(
+            player,
+            reveal,
+            encode(&RevealArgs {
+                game_id,
+                player_move,
+                salt,
+            }),
+        );
+    }
+
+    /// (recipient, amount) of every payout since the last call
+    fn payouts() -> Vec<(String, u64)> {
+        mock::take_contract_calls()
+            .iter()
+            .filter(|c| c.method == "transfer")
+            .map(|c| {
+                let args: TokenTransferArgs = postcard::from_bytes(&c.args).unwrap();
+                (args.to, args.amount)
+            })
+            .collect()
+    }
+
+    fn status(game_id: u64) -> GameStatus {
+        load_game(game_id).unwrap().status
+    }
+
+    #[test]
+    fn winner_takes_the_pot() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let game_id = start_game(Move::Rock, Move::Paper);
+        assert_eq!(load_game(game_id).unwrap().reveal_deadline, START + WINDOW);
+
+        reveal_as(ADDR_ALICE, game_id, Move::Rock, SALT_A);
+        assert_eq!(status(game_id), GameStatus::Committed);
+        reveal_as(ADDR_BOB, game_id, Move::Paper, SALT_B);
+        assert_eq!(
+            status(game_id),
+            GameStatus::Finished {
+                winner: Some(ADDR_BOB.to_string())
+            }
+        );
+        assert_eq!(payouts(), [(ADDR_BOB.to_string(), 200)]);
+    }
+
+    #[test]
+    fn draws_refund_both_players() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let game_id = start_game(Move::Scissors, Move::Scissors);
+
+        reveal_as(ADDR_BOB, game_id, Move::Scissors, SALT_B);
+        reveal_as(ADDR_ALICE, game_id, Move::Scissors, SALT_A);
+        assert_eq!(status(game_id), GameStatus::Finished { winner: None });
+        assert_eq!(
+            payouts(),
+            [(ADDR_ALICE.to_string(), 100), (ADDR_BOB.to_string(), 100)]
+        );
+    }
+
+    #[test]
+    fn reveals_must_match_the_commitment() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let game_id = start_game(Move::Rock, Move::Paper);
+
+        // Changing the move, the salt or the player breaks the commitment.
+        reveal_as(ADDR_ALICE, game_id, Move::Paper, SALT_A);
+        reveal_as(ADDR_ALICE, game_id, Move::Rock, SALT_B);
+        reveal_as(ADDR_CAROL, game_id, Move::Rock, SALT_A);
+        let game = load_game(game_id).unwrap();
+        assert_eq!(game.creator.revealed, None);
+
+        mock::set_block_timestamp(START + WINDOW);
+        reveal_as(ADDR_ALICE, game_id, Move::Rock, SALT_A);
+        assert_eq!(load_game(game_id).unwrap().creator.revealed, None);
+    }
+
+    #[test]
+    fn timeouts_award_the_pot_to_the_revealer() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let revealed = start_game(Move::Rock, Move::Paper);
+        let silent = start_game(Move::Rock, Move::Paper);
+        reveal_as(ADDR_ALICE, revealed, Move::Rock, SALT_A);
+
+        call(
+            ADDR_CAROL,
+            claim_timeout,
+            encode(&GameIdArgs { game_id: revealed }),
+        );
+        assert_eq!(status(revealed), GameStatus::Committed);
+
+        mock::set_block_timestamp(START + WINDOW);
+        call(
+            ADDR_CAROL,
+            claim_timeout,
+            encode(&GameIdArgs { game_id: revealed }),
+        );
+        call(
+            ADDR_CAROL,
+            claim_timeout,
+            encode(&GameIdArgs { game_id: silent }),
+        );
+        assert_eq!(
+            status(revealed),
+            GameStatus::Finished {
+                winner: Some(ADDR_ALICE.to_string())
+            }
+        );
+        assert_eq!(status(silent), GameStatus::Finished { winner: None });
+        assert_eq!(
+            payouts(),
+            [
+                (ADDR_ALICE.to_string(), 200),
+                (ADDR_ALICE.to_string(), 100),
+                (ADDR_BOB.to_string(), 100)
+            ]
+        );
+    }
+
+    #[test]
+    fn forfeits_and_cancellations() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let game_id = start_game(Move::Rock, Move::Paper);
+        call(ADDR_CAROL, forfeit, encode(&GameIdArgs { game_id }));
+        call(ADDR_BOB, forfeit, encode(&GameIdArgs { game_id }));
+        assert_eq!(
+            status(game_id),
+            GameStatus::Finished {
+                winner: Some(ADDR_ALICE.to_string())
+            }
+        );
+        assert_eq!(payouts(), [(ADDR_ALICE.to_string(), 200)]);
+
+        // A reserved game only admits the named opponent and can be
+        // cancelled until then.
+        let data = call(
+            ADDR_ALICE,
+            create_game,
+            encode(&CreateGameArgs {
+                token: TOKEN.to_string(),
+                wager: 50,
+                commitment: move_commitment(ADDR_ALICE, Move::Rock, &SALT_A).unwrap(),
+                opponent: Some(ADDR_BOB.to_string()),
+                reveal_window: WINDOW,
+            }),
+        );
+        let game_id: u64 = postcard::from_bytes(&data).unwrap();
+        call(
+            ADDR_CAROL,
+            join_game,
+            encode(&JoinGameArgs {
+                game_id,
+                commitment: [0; 32],
+            }),
+        );
+        assert_eq!(status(game_id), GameStatus::Open);
+        call(ADDR_BOB, cancel_game, encode(&GameIdArgs { game_id }));
+        call(ADDR_ALICE, cancel_game, encode(&GameIdArgs { game_id }));
+        assert_eq!(status(game_id), GameStatus::Cancelled);
+        assert_eq!(payouts(), [(ADDR_ALICE.to_string(), 50)]);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        mock::set_sender(ADDR_ALICE);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "rock-paper-scissors-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
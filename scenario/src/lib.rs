@@ -0,0 +1,343 @@
+//! Declarative end-to-end contract tests
+//!
+//! A [`Scenario`] drives a contract's entrypoints against the SDK's mock
+//! runtime one call at a time, with expectations on each call's events,
+//! return data and errors:
+//!
+//! ```rust,ignore
+//! use scenario::Scenario;
+//!
+//! Scenario::new()
+//!     .at_contract("crc20_contract")
+//!     .as_sender(ALICE)
+//!     .call(initialize, &init_args)
+//!     .call(approve, &ApproveArgs { spender: BOB.parse()?, amount: 50 })
+//!     .expect_event(ApprovalV1 { owner: ALICE.into(), spender: BOB.into(), amount: 50 })
+//!     .as_sender(BOB)
+//!     .call(transfer_from, &TransferFromArgs { from: ALICE.parse()?, to: BOB.parse()?, amount: 80 })
+//!     .expect_error(ErrorCode::InsufficientBalance);
+//! ```
+//!
+//! * `call` runs an `extern "C"` entrypoint with postcard-encoded
+//!   arguments; pass `&()` to entrypoints that take none.
+//! * A call must succeed unless the next step is `expect_error`; an
+//!   unexpected failure is reported at the next call or when the scenario
+//!   is dropped.
+//! * `expect_*` steps look at the most recent call only.
+//! * [`Scenario::resume`] continues from state a test set up itself.
+//! * Flows shared between tests are functions from `Scenario` to
+//!   `Scenario`, applied with [`Scenario::then`].
+//!
+//! The mock runtime is global: tests must hold their crate's test lock for
+//! the lifetime of a scenario.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use crc_errors::{ErrorCode, ErrorPayload};
+use events::Event;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::ffi::mock::{self, EventRecord};
+use silica_contract_sdk::testing::{StorageDiff, StorageSnapshot};
+use std::fmt::Debug;
+
+/// Sender of calls until [`Scenario::as_sender`] picks another
+pub const DEFAULT_SENDER: &str = "0x0000000000000000000000000000000000000d01";
+
+/// Address of the contract under test until [`Scenario::at_contract`]
+pub const DEFAULT_CONTRACT: &str = "contract_under_test";
+
+/// What one entrypoint call left behind
+struct Outcome {
+    step: usize,
+    events: Vec<EventRecord>,
+    return_data: Vec<u8>,
+    storage: StorageDiff,
+    /// Failure line logged by `crc_errors::report`
+    failure: Option<String>,
+    /// Whether an `expect_error` step has accounted for the failure
+    failure_expected: bool,
+}
+
+impl Outcome {
+    fn describe(&self) -> String {
+        format!("step {}", self.step)
+    }
+
+    fn ensure_succeeded(&self) {
+        if let (Some(failure), false) = (&self.failure, self.failure_expected) {
+            panic!("{} failed unexpectedly: {}", self.describe(), failure);
+        }
+    }
+}
+
+/// A sequence of entrypoint calls against a fresh mock runtime
+pub struct Scenario {
+    steps: usize,
+    last: Option<Outcome>,
+}
+
+impl Scenario {
+    /// Reset the mock runtime: [`DEFAULT_CONTRACT`] called by
+    /// [`DEFAULT_SENDER`] at block 1
+    pub fn new() -> Self {
+        mock::reset();
+        mock::set_sender(DEFAULT_SENDER);
+        mock::set_contract_address(DEFAULT_CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        Scenario {
+            steps: 0,
+            last: None,
+        }
+    }
+
+    /// Continue from the mock runtime's current state and environment, as
+    /// left by a test's own setup helpers
+    pub fn resume() -> Self {
+        Scenario {
+            steps: 0,
+            last: None,
+        }
+    }
+
+    /// Address of the contract under test
+    pub fn at_contract(self, address: &str) -> Self {
+        mock::set_contract_address(address);
+        self
+    }
+
+    /// Sender of the following calls
+    pub fn as_sender(self, sender: &str) -> Self {
+        mock::set_sender(sender);
+        self
+    }
+
+    /// Block height of the following calls
+    pub fn at_block(self, height: u64) -> Self {
+        mock::set_block_height(height);
+        self
+    }
+
+    /// Block timestamp of the following calls
+    pub fn at_time(self, timestamp: u64) -> Self {
+        mock::set_block_timestamp(timestamp);
+        self
+    }
+
+    /// Native value attached to the following calls
+    pub fn with_value(self, amount: u64) -> Self {
+        mock::set_value(amount);
+        self
+    }
+
+    /// Call `entrypoint` with `args` as its call data
+    pub fn call<R, A: Serialize + ?Sized>(
+        mut self,
+        entrypoint: extern "C" fn() -> R,
+        args: &A,
+    ) -> Self {
+        if let Some(last) = &self.last {
+            last.ensure_succeeded();
+        }
+        mock::set_call_data(&postcard::to_allocvec(args).expect("encode call arguments"));
+        mock::take_events();
+        mock::take_logs();
+        mock::take_return_data();
+        let before = StorageSnapshot::take();
+
+        entrypoint();
+        self.steps += 1;
+        self.last = Some(Outcome {
+            step: self.steps,
+            events: mock::take_events(),
+            return_data: mock::take_return_data(),
+            storage: before.diff(&StorageSnapshot::take()),
+            failure: mock::take_logs()
+                .into_iter()
+                .find(|line| line.contains(" failed: ")),
+            failure_expected: false,
+        });
+        self
+    }
+
+    /// Apply a reusable flow
+    pub fn then(self, flow: impl FnOnce(Self) -> Self) -> Self {
+        flow(self)
+    }
+
+    /// Run assertions against contract state between calls
+    pub fn check(self, assertions: impl FnOnce()) -> Self {
+        assertions();
+        self
+    }
+
+    /// The last call failed with `code`
+    pub fn expect_error(mut self, code: ErrorCode) -> Self {
+        let last = self.last_call();
+        let describe = last.describe();
+        assert!(
+            last.failure.is_some(),
+            "{} succeeded, expected {:?}",
+            describe,
+            code
+        );
+        let payload = ErrorPayload::decode(&last.return_data)
+            .unwrap_or_else(|_| panic!("{} failed without an error payload", describe));
+        assert_eq!(
+            payload.error_code(),
+            code,
+            "{} failed with {:?}: {}",
+            describe,
+            payload.error_code(),
+            payload.message
+        );
+        self.last
+            .as_mut()
+            .expect("a call was made")
+            .failure_expected = true;
+        self
+    }
+
+    /// The last call emitted `event` (among possibly others)
+    pub fn expect_event<E: Event + DeserializeOwned + PartialEq + Debug>(self, event: E) -> Self {
+        let last = self.last_call();
+        last.ensure_succeeded();
+        let emitted: Vec<E> = last
+            .events
+            .iter()
+            .filter(|record| record.topic == E::TOPIC)
+            .filter_map(|record| events::decode::<E>(&record.data).ok().flatten())
+            .collect();
+        assert!(
+            emitted.contains(&event),
+            "{} did not emit {:?}; {} events had that schema: {:?}",
+            last.describe(),
+            event,
+            E::TOPIC,
+            emitted
+        );
+        self
+    }
+
+    /// The last call emitted exactly the events under `topics`, in order
+    pub fn expect_topics(self, topics: &[&str]) -> Self {
+        let last = self.last_call();
+        last.ensure_succeeded();
+        let emitted: Vec<&str> = last.events.iter().map(|record| &*record.topic).collect();
+        assert_eq!(emitted, topics, "{} emitted other events", last.describe());
+        self
+    }
+
+    /// The last call returned `value` as postcard return data
+    pub fn expect_return<T: DeserializeOwned + PartialEq + Debug>(self, value: T) -> Self {
+        let last = self.last_call();
+        last.ensure_succeeded();
+        let returned: T = postcard::from_bytes(&last.return_data).unwrap_or_else(|_| {
+            panic!(
+                "{} returned {:02x?}, which does not decode as {}",
+                last.describe(),
+                last.return_data,
+                std::any::type_name::<T>()
+            )
+        });
+        assert_eq!(
+            returned,
+            value,
+            "{} returned another value",
+            last.describe()
+        );
+        self
+    }
+
+    /// The last call changed no storage keys besides `keys`; see
+    /// [`StorageDiff::unexpected`] for the `prefix:*` form. The event and
+    /// admin action sequence counters may always change; check events with
+    /// `expect_events`.
+    pub fn expect_storage_changes(self, keys: &[&str]) -> Self {
+        let last = self.last_call();
+        let mut keys = keys.to_vec();
+        keys.push(silica_contract_sdk::events::SEQUENCE_KEY);
+        keys.push(silica_contract_sdk::admin_log::SEQUENCE_KEY);
+        let unexpected = last.storage.unexpected(&keys);
+        assert!(
+            unexpected.is_empty(),
+            "{} made unexpected storage changes:\n{}",
+            last.describe(),
+            unexpected.join("\n")
+        );
+        self
+    }
+
+    fn last_call(&self) -> &Outcome {
+        self.last
+            .as_ref()
+            .expect("expectations need a call to check")
+    }
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Scenario {
+    fn drop(&mut self) {
+        if let Some(last) = &self.last
+            && !std::thread::panicking()
+        {
+            last.ensure_succeeded();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use events::TransferV1;
+    use silica_contract_sdk::prelude::*;
+    use std::panic;
+
+    const BOB: &str = "0x0000000000000000000000000000000000000b02";
+
+    extern "C" fn credit() {
+        let ctx = context();
+        let amount: u64 = silica_contract_sdk::entrypoint::read_args().unwrap();
+        if amount == 0 {
+            crc_errors::report("credit", &ContractError::InvalidArgument("zero".into()));
+            return;
+        }
+        events::emit(&TransferV1 {
+            from: events::ZERO_ADDRESS.into(),
+            to: ctx.sender().into(),
+            amount,
+        });
+        ctx.return_data(&amount).unwrap();
+    }
+
+    #[test]
+    fn steps_check_the_most_recent_call() {
+        Scenario::new()
+            .as_sender(BOB)
+            .call(credit, &5u64)
+            .expect_topics(&["Transfer"])
+            .expect_event(TransferV1 {
+                from: events::ZERO_ADDRESS.into(),
+                to: BOB.into(),
+                amount: 5,
+            })
+            .expect_return(5u64)
+            .expect_storage_changes(&[])
+            .call(credit, &0u64)
+            .expect_error(ErrorCode::InvalidArgument)
+            .then(|scenario| scenario.call(credit, &1u64).expect_return(1u64));
+
+        let unexpected = panic::catch_unwind(|| {
+            Scenario::new().call(credit, &0u64).call(credit, &1u64);
+        })
+        .unwrap_err();
+        let message = unexpected.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("step 1 failed unexpectedly: credit failed"));
+    }
+}
@@ -0,0 +1,616 @@
+//! Scheduled Transaction Executor
+//!
+//! Users queue a cross-contract call to run once a target block height
+//! and/or timestamp has passed, attaching a native-coin bounty. Once a job
+//! matures, any keeper can execute it and collect the bounty. The creator
+//! can cancel a pending job and get the bounty back. The scheduler has no
+//! owner and holds no funds other than open bounties.
+//!
+//! Scheduled calls run with the scheduler as the caller, so targets either
+//! expose the method publicly or authorize this contract.
+//!
+//! ## Features
+//! - Height and timestamp conditions, alone or combined
+//! - Keeper bounties paid to whoever executes a matured job
+//! - Creator cancellation with a bounty refund
+//! - Per-creator job index with offset/limit pagination
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const JOB_COUNT_KEY: &str = "job_count";
+const JOBS_PREFIX: &str = "jobs";
+const CREATOR_INDEX_PREFIX: &str = "creator_index";
+const CREATOR_COUNTS_PREFIX: &str = "creator_counts";
+const MAX_METHOD_BYTES: usize = 64;
+const MAX_ARGS_BYTES: usize = 2048;
+const MAX_PAGE_SIZE: u64 = 20;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 65_536;
+
+/// Lifecycle of a job
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobStatus {
+    Pending,
+    Executed,
+    Cancelled,
+}
+
+/// A queued call
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Job {
+    pub id: u64,
+    pub creator: String,
+    pub target: String,
+    pub method: String,
+    /// Postcard-encoded arguments
+    pub args: Vec<u8>,
+    /// Earliest block height; 0 for no height condition
+    pub not_before_height: u64,
+    /// Earliest timestamp; 0 for no time condition
+    pub not_before_timestamp: u64,
+    /// Native coin paid to the executor
+    pub bounty: u64,
+    pub status: JobStatus,
+    pub created_at: u64,
+    pub executed_by: Option<String>,
+}
+
+/// One page of jobs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct JobPage {
+    pub items: Vec<Job>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScheduleArgs {
+    target: String,
+    method: String,
+    args: Vec<u8>,
+    not_before_height: u64,
+    not_before_timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JobIdArgs {
+    job_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreatorPageArgs {
+    creator: String,
+    offset: u64,
+    limit: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn jobs() -> Map<u64, Job> {
+    Map::new(JOBS_PREFIX)
+}
+
+fn creator_index() -> Map<(String, u64), u64> {
+    Map::new(CREATOR_INDEX_PREFIX)
+}
+
+fn creator_counts() -> Map<String, u64> {
+    Map::new(CREATOR_COUNTS_PREFIX)
+}
+
+fn load_job(job_id: u64) -> ContractResult<Job> {
+    jobs()
+        .get(&job_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown job".to_string()))
+}
+
+fn load_pending_job(job_id: u64) -> ContractResult<Job> {
+    let job = load_job(job_id)?;
+    if job.status != JobStatus::Pending {
+        return Err(ContractError::InvalidArgument(format!(
+            "Job is {:?}",
+            job.status
+        )));
+    }
+    Ok(job)
+}
+
+/// Whether both of a job's conditions hold at this block.
+pub fn is_mature(job: &Job, height: u64, timestamp: u64) -> bool {
+    height >= job.not_before_height && timestamp >= job.not_before_timestamp
+}
+
+/// Collect `[offset, offset + limit)` from an indexed sequence of `total` items.
+fn paginate<F>(total: u64, offset: u64, limit: u64, mut fetch: F) -> ContractResult<JobPage>
+where
+    F: FnMut(u64) -> ContractResult<Option<Job>>,
+{
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+
+    let end = offset.saturating_add(limit).min(total);
+    let mut items = Vec::new();
+    for index in offset..end {
+        if let Some(job) = fetch(index)? {
+            items.push(job);
+        }
+    }
+    Ok(JobPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+fn execute_schedule() -> ContractResult<u64> {
+    let ctx = context();
+    let creator = ctx.sender().to_string();
+    let args: ScheduleArgs = read_args()?;
+    validation::validate_address(&args.target)?;
+    // Scheduling calls into the scheduler would let jobs cancel or run other jobs.
+    if args.target == ctx.contract_address() || !ctx.is_contract(&args.target) {
+        return Err(ContractError::InvalidArgument(
+            "Target must be another contract".to_string(),
+        ));
+    }
+    validation::validate_non_empty(&args.method, "method")?;
+    if args.method.len() > MAX_METHOD_BYTES || args.args.len() > MAX_ARGS_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Method and args are limited to {} and {} bytes",
+            MAX_METHOD_BYTES, MAX_ARGS_BYTES
+        )));
+    }
+    if args.not_before_height <= ctx.block_height()
+        && args.not_before_timestamp <= ctx.block_timestamp()
+    {
+        return Err(ContractError::InvalidArgument(
+            "Job must be scheduled in the future".to_string(),
+        ));
+    }
+
+    let mut store = storage();
+    let id = store.get::<u64>(JOB_COUNT_KEY)?.unwrap_or(0);
+    let job = Job {
+        id,
+        creator: creator.clone(),
+        target: args.target,
+        method: args.method,
+        args: args.args,
+        not_before_height: args.not_before_height,
+        not_before_timestamp: args.not_before_timestamp,
+        bounty: ctx.value(),
+        status: JobStatus::Pending,
+        created_at: ctx.block_timestamp(),
+        executed_by: None,
+    };
+    jobs().set(&id, &job)?;
+    let position = creator_counts().get(&creator)?.unwrap_or(0);
+    creator_index().set(&(creator.clone(), position), &id)?;
+    creator_counts().set(&creator, &safe_math::add(position, 1)?)?;
+    store.set(JOB_COUNT_KEY, &safe_math::add(id, 1)?)?;
+
+    event!("JobScheduled",
+        job_id: id,
+        creator: creator,
+        target: job.target,
+        method: job.method,
+        not_before_height: job.not_before_height,
+        not_before_timestamp: job.not_before_timestamp,
+        bounty: job.bounty
+    );
+    Ok(id)
+}
+
+fn execute_execute() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let keeper = ctx.sender().to_string();
+    let args: JobIdArgs = read_args()?;
+    let mut job = load_pending_job(args.job_id)?;
+    if !is_mature(&job, ctx.block_height(), ctx.block_timestamp()) {
+        return Err(ContractError::InvalidArgument(
+            "Job has not matured".to_string(),
+        ));
+    }
+
+    // A failing call fails the execution; the job stays pending for a retry or cancel.
+    ctx.call_contract(&job.target, &job.method, &job.args)?;
+
+    job.status = JobStatus::Executed;
+    job.executed_by = Some(keeper.clone());
+    jobs().set(&args.job_id, &job)?;
+    if job.bounty > 0 {
+        ctx.transfer_tokens(&keeper, job.bounty)?;
+    }
+
+    event!("JobExecuted",
+        job_id: args.job_id,
+        keeper: keeper,
+        bounty: job.bounty
+    );
+    Ok(job.bounty)
+}
+
+fn execute_cancel() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: JobIdArgs = read_args()?;
+    let mut job = load_pending_job(args.job_id)?;
+    if ctx.sender() != job.creator {
+        return Err(ContractError::Unauthorized);
+    }
+    job.status = JobStatus::Cancelled;
+    jobs().set(&args.job_id, &job)?;
+    if job.bounty > 0 {
+        ctx.transfer_tokens(&job.creator, job.bounty)?;
+    }
+
+    event!("JobCancelled",
+        job_id: args.job_id,
+        creator: job.creator,
+        refund: job.bounty
+    );
+    Ok(())
+}
+
+fn execute_jobs_of() -> ContractResult<JobPage> {
+    let args: CreatorPageArgs = read_args()?;
+    let total = creator_counts().get(&args.creator)?.unwrap_or(0);
+    let registry = jobs();
+    let index = creator_index();
+    paginate(total, args.offset, args.limit, |position| {
+        match index.get(&(args.creator.clone(), position))? {
+            Some(id) => registry.get(&id),
+            None => Ok(None),
+        }
+    })
+}
+
+/// Queue a call; the attached value becomes the keeper bounty
+///
+/// At least one condition must still be in the future. A job matures once
+/// both hold.
+///
+/// # Arguments
+/// * `target` - Contract to call (not the scheduler)
+/// * `method` - Entrypoint name (max 64 bytes)
+/// * `args` - Postcard-encoded arguments (max 2048 bytes)
+/// * `not_before_height` - Earliest block height, or 0
+/// * `not_before_timestamp` - Earliest timestamp, or 0
+///
+/// # Returns
+/// Job ID (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule() {
+    if let Err(err) = execute_schedule().and_then(|id| try_respond(&id)) {
+        log!("schedule failed: {}", err);
+    }
+}
+
+/// Run a matured job and collect its bounty (anyone)
+///
+/// # Arguments
+/// * `job_id` - Job ID
+///
+/// # Returns
+/// Bounty paid (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn execute() {
+    if let Err(err) = execute_execute().and_then(|bounty| try_respond(&bounty)) {
+        log!("execute failed: {}", err);
+    }
+}
+
+/// Cancel a pending job and refund its bounty (creator only)
+///
+/// # Arguments
+/// * `job_id` - Job ID
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel() {
+    if let Err(err) = execute_cancel() {
+        log!("cancel failed: {}", err);
+    }
+}
+
+/// Get a job
+///
+/// # Arguments
+/// * `job_id` - Job ID
+///
+/// # Returns
+/// `Job`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_job() {
+    let result = read_args::<JobIdArgs>()
+        .and_then(|args| load_job(args.job_id))
+        .and_then(|job| try_respond(&job));
+    if let Err(err) = result {
+        log!("get_job failed: {}", err);
+    }
+}
+
+/// List the jobs an account scheduled
+///
+/// # Arguments
+/// * `creator` - Creator address
+/// * `offset` - Index of the first item
+/// * `limit` - Page size (1-20)
+///
+/// # Returns
+/// `JobPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn jobs_of() {
+    if let Err(err) = execute_jobs_of().and_then(|page| try_respond(&page)) {
+        log!("jobs_of failed: {}", err);
+    }
+}
+
+/// Get the number of jobs ever scheduled
+///
+/// # Returns
+/// Count (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn job_count() {
+    let result = storage()
+        .get::<u64>(JOB_COUNT_KEY)
+        .and_then(|count| try_respond(&count.unwrap_or(0)));
+    if let Err(err) = result {
+        log!("job_count failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_KEEPER: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TARGET: &str = "0x0000000000000000000000000000000000000f01";
+    const NOW: u64 = 1_736_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        mock::register_contract(TARGET, "poke", |_| Ok(Vec::new()));
+        mock::register_contract(TARGET, "fail", |_| {
+            Err(ContractError::Custom("target reverted".to_string()))
+        });
+    }
+
+    fn schedule_job(method: &str, height: u64, timestamp: u64, bounty: u64) -> Option<u64> {
+        mock::set_value(bounty);
+        let id = call(
+            ADDR_ALICE,
+            schedule,
+            encode(&ScheduleArgs {
+                target: TARGET.to_string(),
+                method: method.to_string(),
+                args: vec![7, 7],
+                not_before_height: height,
+                not_before_timestamp: timestamp,
+            }),
+        );
+        mock::set_value(0);
+        id
+    }
+
+    fn job_id(job_id: u64) -> Vec<u8> {
+        encode(&JobIdArgs { job_id })
+    }
+
+    #[test]
+    fn keepers_execute_matured_jobs_for_the_bounty() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(schedule_job("poke", 0, NOW + 3_600, 500), Some(0));
+        assert_eq!(load_job(0).unwrap().bounty, 500);
+        mock::take_contract_calls();
+
+        mock::set_block_timestamp(NOW + 3_599);
+        assert_eq!(call::<u64>(ADDR_KEEPER, execute, job_id(0)), None);
+        mock::set_block_timestamp(NOW + 3_600);
+        assert_eq!(call::<u64>(ADDR_KEEPER, execute, job_id(0)), Some(500));
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(
+            (
+                calls[0].address.as_str(),
+                calls[0].method.as_str(),
+                calls[0].args.clone()
+            ),
+            (TARGET, "poke", vec![7, 7])
+        );
+        let job = load_job(0).unwrap();
+        assert_eq!(job.status, JobStatus::Executed);
+        assert_eq!(job.executed_by.as_deref(), Some(ADDR_KEEPER));
+        assert_eq!(call::<u64>(ADDR_KEEPER, execute, job_id(0)), None);
+    }
+
+    #[test]
+    fn both_conditions_must_hold() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        schedule_job("poke", 10, NOW + 60, 0);
+        let job = load_job(0).unwrap();
+        assert!(!is_mature(&job, 10, NOW + 59));
+        assert!(!is_mature(&job, 9, NOW + 60));
+        assert!(is_mature(&job, 10, NOW + 60));
+
+        mock::set_block_timestamp(NOW + 60);
+        assert_eq!(call::<u64>(ADDR_KEEPER, execute, job_id(0)), None);
+        mock::set_block_height(10);
+        assert_eq!(call::<u64>(ADDR_KEEPER, execute, job_id(0)), Some(0));
+    }
+
+    #[test]
+    fn invalid_jobs_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        assert_eq!(schedule_job("poke", 1, NOW, 0), None);
+        assert_eq!(schedule_job("", 2, 0, 0), None);
+        let schedule_to = |target: &str| {
+            call::<u64>(
+                ADDR_ALICE,
+                schedule,
+                encode(&ScheduleArgs {
+                    target: target.to_string(),
+                    method: "cancel".to_string(),
+                    args: Vec::new(),
+                    not_before_height: 2,
+                    not_before_timestamp: 0,
+                }),
+            )
+        };
+        assert_eq!(schedule_to(CONTRACT), None);
+        assert_eq!(schedule_to(ADDR_BOB), None);
+        assert_eq!(call::<u64>(ADDR_ALICE, job_count, Vec::new()), Some(0));
+    }
+
+    #[test]
+    fn failed_calls_leave_the_job_pending() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        schedule_job("fail", 2, 0, 300);
+        mock::set_block_height(2);
+        assert_eq!(call::<u64>(ADDR_KEEPER, execute, job_id(0)), None);
+        assert_eq!(load_job(0).unwrap().status, JobStatus::Pending);
+
+        call::<()>(ADDR_BOB, cancel, job_id(0));
+        assert_eq!(load_job(0).unwrap().status, JobStatus::Pending);
+        call::<()>(ADDR_ALICE, cancel, job_id(0));
+        assert_eq!(load_job(0).unwrap().status, JobStatus::Cancelled);
+        call::<()>(ADDR_ALICE, cancel, job_id(0));
+        assert!(
+            mock::take_events()
+                .iter()
+                .filter(|event| event.topic == "JobCancelled")
+                .count()
+                == 1
+        );
+    }
+
+    #[test]
+    fn jobs_page_by_creator() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        for height in 2..5 {
+            schedule_job("poke", height, 0, 0);
+        }
+        let page = |offset, limit| {
+            call::<JobPage>(
+                ADDR_BOB,
+                jobs_of,
+                encode(&CreatorPageArgs {
+                    creator: ADDR_ALICE.to_string(),
+                    offset,
+                    limit,
+                }),
+            )
+        };
+        let first = page(0, 2).unwrap();
+        assert_eq!(
+            first
+                .items
+                .iter()
+                .map(|job| job.not_before_height)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!((first.total, first.next_offset), (3, Some(2)));
+        assert_eq!(page(2, 2).unwrap().next_offset, None);
+        assert_eq!(page(0, MAX_PAGE_SIZE + 1), None);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "scheduler-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
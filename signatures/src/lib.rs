@@ -0,0 +1,231 @@
+//! Structured message signing
+//!
+//! Off-chain signatures that a contract acts on (permits, vouchers,
+//! meta-transactions, bridge attestations) must not be valid anywhere else.
+//! Every message here is hashed under a [`Domain`] that names the
+//! application, its version, the chain and the verifying contract:
+//!
+//! ```text
+//! separator = blake3(postcard(("silica-domain-v1", domain)))
+//! struct    = blake3(postcard((type_name, message)))
+//! digest    = blake3(0x19 || 0x01 || separator || struct)
+//! ```
+//!
+//! Signers sign the 32-byte digest with Ed25519, or with recoverable
+//! secp256k1 (`r || s || v`). [`replay`] has the nonce, used-digest and
+//! deadline checks that make each signature single-use.
+//!
+//! ## Embedding
+//! ```toml
+//! signatures = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! let domain = Domain::current("vouchers", "1", config.chain_id)?;
+//! let digest = domain.digest("Redeem", &(args.voucher_id, &args.to, args.nonce))?;
+//! signatures::verify_ed25519(&issuer_key, &digest, &args.signature)?;
+//! signatures::replay::Nonces::new("nonces").consume(&args.to, args.nonce)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+pub mod replay;
+
+use alloc::string::{String, ToString};
+use silica_contract_sdk::prelude::*;
+
+/// A 32-byte message digest
+pub type Digest = [u8; 32];
+
+/// Tag hashed into every domain separator
+pub const DOMAIN_TAG: &str = "silica-domain-v1";
+
+const DIGEST_PREFIX: [u8; 2] = [0x19, 0x01];
+
+/// Where a signature is valid
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Domain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub verifying_contract: String,
+}
+
+impl Domain {
+    pub fn new(name: &str, version: &str, chain_id: u64, verifying_contract: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            chain_id,
+            verifying_contract: verifying_contract.to_string(),
+        }
+    }
+
+    /// Domain bound to the executing contract.
+    pub fn current(name: &str, version: &str, chain_id: u64) -> ContractResult<Self> {
+        let ctx = try_context()?;
+        Ok(Self::new(name, version, chain_id, ctx.contract_address()))
+    }
+
+    pub fn separator(&self) -> ContractResult<Digest> {
+        hash_encoded(&(DOMAIN_TAG, self))
+    }
+
+    /// Digest a signer signs for `message` in this domain.
+    pub fn digest<T: Serialize>(&self, type_name: &str, message: &T) -> ContractResult<Digest> {
+        let mut preimage = [0u8; 66];
+        preimage[..2].copy_from_slice(&DIGEST_PREFIX);
+        preimage[2..34].copy_from_slice(&self.separator()?);
+        preimage[34..].copy_from_slice(&struct_hash(type_name, message)?);
+        Ok(crypto::hash_blake3(&preimage))
+    }
+}
+
+/// Hash of a typed message, independent of any domain.
+pub fn struct_hash<T: Serialize>(type_name: &str, message: &T) -> ContractResult<Digest> {
+    hash_encoded(&(type_name, message))
+}
+
+fn hash_encoded<T: Serialize>(value: &T) -> ContractResult<Digest> {
+    let bytes = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    Ok(crypto::hash_blake3(&bytes))
+}
+
+/// Check an Ed25519 signature over `digest`.
+pub fn verify_ed25519(
+    public_key: &[u8; 32],
+    digest: &Digest,
+    signature: &[u8],
+) -> ContractResult<()> {
+    let signature: &[u8; 64] = signature
+        .try_into()
+        .map_err(|_| ContractError::InvalidSignature)?;
+    if !crypto::verify_signature(public_key, digest, signature)? {
+        return Err(ContractError::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// Recover the compressed secp256k1 key that signed `digest`.
+pub fn recover_secp256k1(digest: &Digest, signature: &[u8]) -> ContractResult<[u8; 33]> {
+    let signature: &[u8; 65] = signature
+        .try_into()
+        .map_err(|_| ContractError::InvalidSignature)?;
+    crypto::secp256k1_recover(digest, signature)?.ok_or(ContractError::InvalidSignature)
+}
+
+/// Check a recoverable secp256k1 signature over `digest` against `public_key`.
+pub fn verify_secp256k1(
+    public_key: &[u8; 33],
+    digest: &Digest,
+    signature: &[u8],
+) -> ContractResult<()> {
+    if recover_secp256k1(digest, signature)? != *public_key {
+        return Err(ContractError::InvalidSignature);
+    }
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    pub(crate) const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000a01";
+    pub(crate) const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const OTHER_CONTRACT: &str = "0x0000000000000000000000000000000000000f01";
+
+    pub(crate) fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    pub(crate) fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_sender(ADDR_ALICE);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+    }
+
+    fn signer() -> SigningKey {
+        let pairs = crypto::generate_keypairs(1).unwrap();
+        SigningKey::from_bytes(&pairs[0].1)
+    }
+
+    #[test]
+    fn digests_are_bound_to_chain_and_contract() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let domain = Domain::current("vouchers", "1", 7).unwrap();
+        assert_eq!(domain.verifying_contract, CONTRACT);
+
+        let message = (3u64, "0xbeef");
+        let digest = domain.digest("Redeem", &message).unwrap();
+        let others = [
+            Domain::new("vouchers", "1", 8, CONTRACT),
+            Domain::new("vouchers", "1", 7, OTHER_CONTRACT),
+            Domain::new("vouchers", "2", 7, CONTRACT),
+            Domain::new("tickets", "1", 7, CONTRACT),
+        ];
+        for other in &others {
+            assert_ne!(other.digest("Redeem", &message).unwrap(), digest);
+        }
+        assert_ne!(domain.digest("Cancel", &message).unwrap(), digest);
+        assert_eq!(domain.digest("Redeem", &message).unwrap(), digest);
+    }
+
+    #[test]
+    fn ed25519_signatures_verify_over_the_digest() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let key = signer();
+        let public_key = key.verifying_key().to_bytes();
+        let domain = Domain::current("forwarder", "1", 7).unwrap();
+        let digest = domain.digest("Forward", &42u64).unwrap();
+        let signature = key.sign(&digest).to_bytes();
+
+        assert!(verify_ed25519(&public_key, &digest, &signature).is_ok());
+
+        let replayed = Domain::new("forwarder", "1", 8, CONTRACT)
+            .digest("Forward", &42u64)
+            .unwrap();
+        assert!(matches!(
+            verify_ed25519(&public_key, &replayed, &signature),
+            Err(ContractError::InvalidSignature)
+        ));
+        assert!(matches!(
+            verify_ed25519(&public_key, &digest, &signature[..63]),
+            Err(ContractError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn secp256k1_signatures_recover_the_signer() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let digest = struct_hash("Attest", &1u64).unwrap();
+        let signature = [5u8; 65];
+        let public_key = [2u8; 33];
+        mock::register_secp256k1_signature(&digest, &signature, &public_key);
+
+        assert_eq!(recover_secp256k1(&digest, &signature).unwrap(), public_key);
+        assert!(verify_secp256k1(&public_key, &digest, &signature).is_ok());
+        assert!(matches!(
+            verify_secp256k1(&[3u8; 33], &digest, &signature),
+            Err(ContractError::InvalidSignature)
+        ));
+        assert!(matches!(
+            recover_secp256k1(&digest, &[6u8; 65]),
+            Err(ContractError::InvalidSignature)
+        ));
+        assert!(matches!(
+            recover_secp256k1(&digest, &signature[..64]),
+            Err(ContractError::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+//! Replay protection
+//!
+//! A valid signature stays valid forever unless the contract retires it.
+//! [`Nonces`] gives each signer an ordered sequence, [`NonceBitmap`]
+//! accepts each nonce once in any order, [`UsedDigests`] burns individual
+//! digests for messages without a nonce, and [`check_deadline`] bounds how
+//! long a signature can wait to be submitted. The nonce types live in the
+//! SDK's `nonces` module so contracts that do not verify signatures
+//! themselves can use them too.
+
+use crate::Digest;
+use alloc::string::ToString;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Deadline;
+
+pub use silica_contract_sdk::nonces::{NonceBitmap, Nonces};
+
+/// Set of digests that have already been acted on
+pub struct UsedDigests {
+    map: Map<Digest, bool>,
+}
+
+impl UsedDigests {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            map: Map::new(prefix),
+        }
+    }
+
+    pub fn is_used(&self, digest: &Digest) -> ContractResult<bool> {
+        Ok(self.map.get(digest)?.unwrap_or(false))
+    }
+
+    /// Mark `digest` used; fails if it already was.
+    pub fn consume(&mut self, digest: &Digest) -> ContractResult<()> {
+        if self.is_used(digest)? {
+            return Err(ContractError::InvalidArgument(
+                "Signature already used".to_string(),
+            ));
+        }
+        self.map.set(digest, &true)
+    }
+}
+
+/// Fail once the block timestamp is past `deadline`.
+pub fn check_deadline(deadline: u64) -> ContractResult<()> {
+    if Deadline::at(deadline).has_passed()? {
+        return Err(ContractError::InvalidArgument(
+            "Signature expired".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::tests::{setup, test_lock};
+    use silica_contract_sdk::ffi::mock;
+
+    #[test]
+    fn digests_and_deadlines_expire_signatures() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let mut used = UsedDigests::new("used");
+        let digest = [9u8; 32];
+        assert!(!used.is_used(&digest).unwrap());
+        used.consume(&digest).unwrap();
+        assert!(used.is_used(&digest).unwrap());
+        assert!(used.consume(&digest).is_err());
+
+        assert!(check_deadline(1_736_000_000).is_ok());
+        mock::set_block_timestamp(1_736_000_001);
+        assert!(matches!(
+            check_deadline(1_736_000_000),
+            Err(ContractError::InvalidArgument(msg)) if msg == "Signature expired"
+        ));
+    }
+}
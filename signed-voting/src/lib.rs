@@ -0,0 +1,1140 @@
+//! Signed Vote Verifier
+//!
+//! Gasless, Snapshot-style governance with on-chain finality. Voters sign
+//! their votes off-chain with an Ed25519 key they registered once. Anyone
+//! can relay the signatures in batches; the contract verifies each one,
+//! weights it by the voter's CRC-20 balance at the proposal's snapshot
+//! block and tallies it. After voting ends anyone can finalize the
+//! proposal. A proposal that passes runs its action, typically a call that
+//! queues the operation on the timelock.
+//!
+//! ## Features
+//! - Batches of signed votes verified and tallied in one call
+//! - Batches larger than one call's data staged in chunks
+//! - Invalid or duplicate votes in a batch are skipped, not fatal
+//! - Weights fixed at a snapshot block recorded on the proposal
+//! - Trustless execution of passed proposals
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::chunked::{
+    self, AppendPayloadArgs, BeginPayloadArgs, ChunkLimits, CommitPayloadArgs,
+};
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const CONFIG_KEY: &str = "signed_voting_config";
+const NEXT_PROPOSAL_KEY: &str = "next_proposal_id";
+const PROPOSALS_PREFIX: &str = "proposals";
+const RECEIPTS_PREFIX: &str = "vote_receipts";
+const KEYS_PREFIX: &str = "voter_keys";
+const VOTE_DOMAIN: &str = "silica-signed-vote-v1";
+const SIGNATURE_LEN: usize = 64;
+const MAX_BATCH_SIZE: usize = 64;
+/// Votes in a batch staged with `begin_payload`
+const MAX_STAGED_BATCH_SIZE: usize = 256;
+const CHUNK_LIMITS: ChunkLimits = ChunkLimits {
+    max_payload_bytes: 65_536,
+    max_open_payloads: 4,
+};
+const MAX_DESCRIPTION_LEN: usize = 256;
+const MAX_METHOD_BYTES: usize = 64;
+const MIN_VOTING_PERIOD: u64 = 60 * 60;
+const MAX_VOTING_PERIOD: u64 = 30 * 24 * 60 * 60;
+const MAX_CALL_DATA_BYTES: usize = 16_384;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Governance parameters
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VotingConfig {
+    pub owner: String,
+    /// CRC-20 whose snapshot balances weight the votes
+    pub token: String,
+    /// Seconds from proposal creation until voting ends
+    pub voting_period: u64,
+    /// Minimum total weight (for, against and abstain) for a valid result
+    pub quorum: u64,
+    /// Snapshot balance needed to create a proposal
+    pub proposal_threshold: u64,
+}
+
+/// A vote option
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Choice {
+    Against,
+    For,
+    Abstain,
+}
+
+/// Call made when a proposal passes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalAction {
+    pub target: String,
+    pub method: String,
+    pub data: Vec<u8>,
+}
+
+/// Lifecycle of a proposal
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalState {
+    Active,
+    Defeated,
+    Succeeded,
+    /// Succeeded and its action was called
+    Executed,
+}
+
+/// A proposal and its tally
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Proposal {
+    pub proposer: String,
+    pub description: String,
+    pub snapshot_block: u64,
+    pub vote_end: u64,
+    pub for_votes: u64,
+    pub against_votes: u64,
+    pub abstain_votes: u64,
+    pub voter_count: u64,
+    pub action: Option<ProposalAction>,
+    pub state: ProposalState,
+}
+
+/// A vote signed off-chain
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignedVote {
+    pub voter: String,
+    pub choice: Choice,
+    pub signature: Vec<u8>,
+}
+
+/// A counted vote
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VoteReceipt {
+    pub choice: Choice,
+    pub weight: u64,
+}
+
+/// Outcome of `submit_votes`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BatchResult {
+    pub accepted: u32,
+    pub skipped: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    token: String,
+    voting_period: u64,
+    quorum: u64,
+    proposal_threshold: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetParamsArgs {
+    voting_period: u64,
+    quorum: u64,
+    proposal_threshold: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegisterKeyArgs {
+    public_key: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProposeArgs {
+    description: String,
+    action: Option<ProposalAction>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SubmitVotesArgs {
+    proposal_id: u64,
+    votes: Vec<SignedVote>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProposalIdArgs {
+    proposal_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetReceiptArgs {
+    proposal_id: u64,
+    voter: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BalanceOfAtArgs {
+    account: String,
+    block: u64,
+}
+
+/// Bytes `voter` signs to cast `choice` on `proposal_id` at the verifier
+/// deployed at `verifier`.
+pub fn vote_message(
+    verifier: &str,
+    proposal_id: u64,
+    voter: &str,
+    choice: Choice,
+) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(&(VOTE_DOMAIN, verifier, proposal_id, voter, choice))
+        .map_err(|_| ContractError::SerializationFailed)
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<VotingConfig> {
+    storage()
+        .get::<VotingConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_config(config: &VotingConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+fn proposals() -> Map<u64, Proposal> {
+    Map::new(PROPOSALS_PREFIX)
+}
+
+fn receipts() -> Map<(u64, String), VoteReceipt> {
+    Map::new(RECEIPTS_PREFIX)
+}
+
+fn voter_keys() -> Map<String, [u8; 32]> {
+    Map::new(KEYS_PREFIX)
+}
+
+fn load_proposal(proposal_id: u64) -> ContractResult<Proposal> {
+    proposals()
+        .get(&proposal_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown proposal".to_string()))
+}
+
+fn validate_voting_period(voting_period: u64) -> ContractResult<()> {
+    if !(MIN_VOTING_PERIOD..=MAX_VOTING_PERIOD).contains(&voting_period) {
+        return Err(ContractError::InvalidArgument(
+            "Voting period must be between 1 hour and 30 days".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn snapshot_balance(token: &str, account: &str, block: u64) -> ContractResult<u64> {
+    let call = encode_call(&BalanceOfAtArgs {
+        account: account.to_string(),
+        block,
+    })?;
+    let response = context().call_contract(token, "balance_of_at", &call)?;
+    postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Weight of a valid, not yet counted vote; `None` if it must be skipped.
+fn verify_vote(
+    config: &VotingConfig,
+    proposal_id: u64,
+    proposal: &Proposal,
+    vote: &SignedVote,
+) -> ContractResult<Option<u64>> {
+    if receipts()
+        .get(&(proposal_id, vote.voter.clone()))?
+        .is_some()
+    {
+        return Ok(None);
+    }
+    let Some(public_key) = voter_keys().get(&vote.voter)? else {
+        return Ok(None);
+    };
+    let Ok(signature) = <[u8; SIGNATURE_LEN]>::try_from(vote.signature.as_slice()) else {
+        return Ok(None);
+    };
+    let message = vote_message(
+        context().contract_address(),
+        proposal_id,
+        &vote.voter,
+        vote.choice,
+    )?;
+    if !crypto::verify_signature(&public_key, &message, &signature)? {
+        return Ok(None);
+    }
+    let weight = snapshot_balance(&config.token, &vote.voter, proposal.snapshot_block)?;
+    Ok((weight > 0).then_some(weight))
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    validate_voting_period(args.voting_period)?;
+
+    save_config(&VotingConfig {
+        owner: ctx.sender().to_string(),
+        token: args.token.clone(),
+        voting_period: args.voting_period,
+        quorum: args.quorum,
+        proposal_threshold: args.proposal_threshold,
+    })?;
+
+    event!("SignedVotingInitialized", owner: ctx.sender(), token: args.token);
+    Ok(())
+}
+
+fn execute_set_params() -> ContractResult<()> {
+    let mut config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    let args: SetParamsArgs = read_args()?;
+    validate_voting_period(args.voting_period)?;
+    let changes = [
+        ("voting_period", config.voting_period, args.voting_period),
+        ("quorum", config.quorum, args.quorum),
+        (
+            "proposal_threshold",
+            config.proposal_threshold,
+            args.proposal_threshold,
+        ),
+    ];
+    config.voting_period = args.voting_period;
+    config.quorum = args.quorum;
+    config.proposal_threshold = args.proposal_threshold;
+    save_config(&config)?;
+
+    event!("ParamsUpdated",
+        voting_period: args.voting_period,
+        quorum: args.quorum,
+        proposal_threshold: args.proposal_threshold
+    );
+    for (parameter, previous, value) in changes {
+        admin_log::record(admin_log::SET_PARAMETER, parameter, previous, value)?;
+    }
+    Ok(())
+}
+
+fn execute_register_key() -> ContractResult<()> {
+    let account = context().sender().to_string();
+    let args: RegisterKeyArgs = read_args()?;
+    if args.public_key == [0u8; 32] {
+        return Err(ContractError::InvalidArgument(
+            "Public key cannot be zero".to_string(),
+        ));
+    }
+
+    voter_keys().set(&account, &args.public_key)?;
+
+    event!("VoterKeyRegistered", account: account);
+    Ok(())
+}
+
+fn execute_propose() -> ContractResult<u64> {
+    let config = load_config()?;
+    let ctx = context();
+    let proposer = ctx.sender().to_string();
+    let args: ProposeArgs = read_args()?;
+    validation::validate_non_empty(&args.description, "description")?;
+    if args.description.len() > MAX_DESCRIPTION_LEN {
+        return Err(ContractError::InvalidArgument(format!(
+            "Description cannot exceed {} bytes",
+            MAX_DESCRIPTION_LEN
+        )));
+    }
+    if let Some(action) = &args.action {
+        validation::validate_address(&action.target)?;
+        if action.method.is_empty() || action.method.len() > MAX_METHOD_BYTES {
+            return Err(ContractError::InvalidArgument(
+                "Invalid method name".to_string(),
+            ));
+        }
+    }
+    let snapshot_block = ctx.block_height().checked_sub(1).ok_or_else(|| {
+        ContractError::InvalidArgument("No finished block to snapshot".to_string())
+    })?;
+    let balance = snapshot_balance(&config.token, &proposer, snapshot_block)?;
+    if balance < config.proposal_threshold {
+        return Err(ContractError::InsufficientBalance {
+            required: config.proposal_threshold,
+            available: balance,
+        });
+    }
+
+    let vote_end = safe_math::add(ctx.block_timestamp(), config.voting_period)?;
+    let proposal_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_PROPOSAL_KEY)?.unwrap_or(0);
+        store.set(NEXT_PROPOSAL_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    proposals().set(
+        &proposal_id,
+        &Proposal {
+            proposer: proposer.clone(),
+            description: args.description,
+            snapshot_block,
+            vote_end,
+            for_votes: 0,
+            against_votes: 0,
+            abstain_votes: 0,
+            voter_count: 0,
+            action: args.action,
+            state: ProposalState::Active,
+        },
+    )?;
+
+    event!("ProposalCreated",
+        proposal_id: proposal_id,
+        proposer: proposer,
+        snapshot_block: snapshot_block,
+        vote_end: vote_end
+    );
+    Ok(proposal_id)
+}
+
+fn execute_submit_votes() -> ContractResult<BatchResult> {
+    let args: SubmitVotesArgs = read_args()?;
+    tally_votes(args, MAX_BATCH_SIZE)
+}
+
+fn tally_votes(args: SubmitVotesArgs, max_batch_size: usize) -> ContractResult<BatchResult> {
+    let config = load_config()?;
+    if args.votes.is_empty() || args.votes.len() > max_batch_size {
+        return Err(ContractError::InvalidArgument(format!(
+            "Batch must contain 1-{} votes",
+            max_batch_size
+        )));
+    }
+    let mut proposal = load_proposal(args.proposal_id)?;
+    if context().block_timestamp() >= proposal.vote_end {
+        return Err(ContractError::InvalidArgument(
+            "Voting has ended".to_string(),
+        ));
+    }
+
+    let mut result = BatchResult {
+        accepted: 0,
+        skipped: 0,
+    };
+    let mut store = receipts();
+    for vote in &args.votes {
+        let Some(weight) = verify_vote(&config, args.proposal_id, &proposal, vote)? else {
+            result.skipped += 1;
+            continue;
+        };
+        let tally = match vote.choice {
+            Choice::Against => &mut proposal.against_votes,
+            Choice::For => &mut proposal.for_votes,
+            Choice::Abstain => &mut proposal.abstain_votes,
+        };
+        *tally = safe_math::add(*tally, weight)?;
+        proposal.voter_count = safe_math::add(proposal.voter_count, 1)?;
+        store.set(
+            &(args.proposal_id, vote.voter.clone()),
+            &VoteReceipt {
+                choice: vote.choice,
+                weight,
+            },
+        )?;
+        result.accepted += 1;
+
+        event!("VoteCast",
+            proposal_id: args.proposal_id,
+            voter: vote.voter,
+            choice: format!("{:?}", vote.choice),
+            weight: weight
+        );
+    }
+    proposals().set(&args.proposal_id, &proposal)?;
+
+    event!("VotesSubmitted",
+        proposal_id: args.proposal_id,
+        relayer: context().sender(),
+        accepted: result.accepted,
+        skipped: result.skipped
+    );
+    Ok(result)
+}
+
+fn execute_commit_payload() -> ContractResult<BatchResult> {
+    let args: CommitPayloadArgs = read_args()?;
+    match args.method.as_str() {
+        "submit_votes" => tally_votes(chunked::commit(args.sequence)?, MAX_STAGED_BATCH_SIZE),
+        _ => Err(chunked::unknown_method(&args.method)),
+    }
+}
+
+fn execute_finalize() -> ContractResult<ProposalState> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let args: ProposalIdArgs = read_args()?;
+    let mut proposal = load_proposal(args.proposal_id)?;
+    if proposal.state != ProposalState::Active {
+        return Err(ContractError::InvalidArgument(
+            "Proposal already finalized".to_string(),
+        ));
+    }
+    if context().block_timestamp() < proposal.vote_end {
+        return Err(ContractError::InvalidArgument(
+            "Voting has not ended".to_string(),
+        ));
+    }
+
+    let turnout = safe_math::add(
+        safe_math::add(proposal.for_votes, proposal.against_votes)?,
+        proposal.abstain_votes,
+    )?;
+    let passed = turnout >= config.quorum && proposal.for_votes > proposal.against_votes;
+    proposal.state = match (&proposal.action, passed) {
+        (_, false) => ProposalState::Defeated,
+        (None, true) => ProposalState::Succeeded,
+        (Some(_), true) => ProposalState::Executed,
+    };
+    // Record the outcome before the action runs so it cannot re-enter.
+    proposals().set(&args.proposal_id, &proposal)?;
+    if let (ProposalState::Executed, Some(action)) = (proposal.state, &proposal.action) {
+        context().call_contract(&action.target, &action.method, &action.data)?;
+    }
+
+    event!("ProposalFinalized",
+        proposal_id: args.proposal_id,
+        state: format!("{:?}", proposal.state),
+        for_votes: proposal.for_votes,
+        against_votes: proposal.against_votes,
+        abstain_votes: proposal.abstain_votes
+    );
+    Ok(proposal.state)
+}
+
+/// Initialize the verifier; the caller becomes its owner
+///
+/// # Arguments
+/// * `token` - CRC-20 implementing `balance_of_at`
+/// * `voting_period` - Seconds each proposal accepts votes (1 hour to 30 days)
+/// * `quorum` - Minimum total weight for a valid result
+/// * `proposal_threshold` - Snapshot balance needed to propose
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Update the governance parameters (owner only); applies to new proposals
+/// and, for the quorum, to proposals not yet finalized
+///
+/// # Arguments
+/// * `voting_period` - Seconds each proposal accepts votes
+/// * `quorum` - Minimum total weight for a valid result
+/// * `proposal_threshold` - Snapshot balance needed to propose
+#[unsafe(no_mangle)]
+pub extern "C" fn set_params() {
+    if let Err(err) = execute_set_params() {
+        log!("set_params failed: {}", err);
+    }
+}
+
+/// Register the Ed25519 key that signs the caller's votes
+///
+/// Calling again rotates the key; votes signed by the old key stop
+/// verifying.
+///
+/// # Arguments
+/// * `public_key` - Ed25519 public key
+#[unsafe(no_mangle)]
+pub extern "C" fn register_key() {
+    if let Err(err) = execute_register_key() {
+        log!("register_key failed: {}", err);
+    }
+}
+
+/// Create a proposal; weights are snapshotted at the previous block
+///
+/// # Arguments
+/// * `description` - What is being decided
+/// * `action` - Optional call made if the proposal passes
+///
+/// # Returns
+/// Proposal id
+#[unsafe(no_mangle)]
+pub extern "C" fn propose() {
+    let result = execute_propose().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("propose failed: {}", err);
+    }
+}
+
+/// Verify and tally a batch of signed votes while voting is open
+///
+/// Votes with a bad signature, no registered key, no snapshot balance or
+/// an earlier counted vote are skipped.
+///
+/// # Arguments
+/// * `proposal_id` - Proposal id
+/// * `votes` - Up to 64 signed votes
+///
+/// # Returns
+/// `BatchResult`
+#[unsafe(no_mangle)]
+pub extern "C" fn submit_votes() {
+    let result = execute_submit_votes().and_then(|result| try_respond(&result));
+    if let Err(err) = result {
+        log!("submit_votes failed: {}", err);
+    }
+}
+
+/// Open a staged payload for a batch too large for one call
+///
+/// # Arguments
+/// * `sequence` - Caller-chosen id of the payload; reusing an open one starts it over
+/// * `total_len` - Payload size, at most 64 KiB
+#[unsafe(no_mangle)]
+pub extern "C" fn begin_payload() {
+    let result = read_args::<BeginPayloadArgs>()
+        .and_then(|args| chunked::begin(&CHUNK_LIMITS, args.sequence, args.total_len));
+    if let Err(err) = result {
+        log!("begin_payload failed: {}", err);
+    }
+}
+
+/// Add the next chunk to a staged payload
+///
+/// # Arguments
+/// * `sequence` - Payload id
+/// * `chunk` - Next bytes of the payload
+#[unsafe(no_mangle)]
+pub extern "C" fn append_payload() {
+    let result = read_args::<AppendPayloadArgs>()
+        .and_then(|args| chunked::append(args.sequence, &args.chunk));
+    if let Err(err) = result {
+        log!("append_payload failed: {}", err);
+    }
+}
+
+/// Run a batch entrypoint with a complete staged payload
+///
+/// `submit_votes` is the only batch entrypoint; staged batches hold up to
+/// 256 votes.
+///
+/// # Arguments
+/// * `sequence` - Payload id
+/// * `method` - `"submit_votes"`
+///
+/// # Returns
+/// `BatchResult`
+#[unsafe(no_mangle)]
+pub extern "C" fn commit_payload() {
+    let result = execute_commit_payload().and_then(|result| try_respond(&result));
+    if let Err(err) = result {
+        log!("commit_payload failed: {}", err);
+    }
+}
+
+/// Settle a proposal once voting has ended and run its action if it passed
+///
+/// # Arguments
+/// * `proposal_id` - Proposal id
+///
+/// # Returns
+/// Final `ProposalState`
+#[unsafe(no_mangle)]
+pub extern "C" fn finalize() {
+    let result = execute_finalize().and_then(|state| try_respond(&state));
+    if let Err(err) = result {
+        log!("finalize failed: {}", err);
+    }
+}
+
+/// Get a proposal
+///
+/// # Arguments
+/// * `proposal_id` - Proposal id
+///
+/// # Returns
+/// `Proposal`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_proposal() {
+    let result = read_args::<ProposalIdArgs>()
+        .and_then(|args| load_proposal(args.proposal_id))
+        .and_then(|proposal| try_respond(&proposal));
+    if let Err(err) = result {
+        log!("get_proposal failed: {}", err);
+    }
+}
+
+/// Get the counted vote of a voter
+///
+/// # Arguments
+/// * `proposal_id` - Proposal id
+/// * `voter` - Voter address
+///
+/// # Returns
+/// `Option<VoteReceipt>`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_receipt() {
+    let result = read_args::<GetReceiptArgs>()
+        .and_then(|args| receipts().get(&(args.proposal_id, args.voter)))
+        .and_then(|receipt| try_respond(&receipt));
+    if let Err(err) = result {
+        log!("get_receipt failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use ed25519_dalek::{Signer, SigningKey};
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_CAROL: &str = "0x0000000000000000000000000000000000000d04";
+    const ADDR_RELAYER: &str = "0x0000000000000000000000000000000000000e05";
+    const VERIFIER: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const TIMELOCK: &str = "0x0000000000000000000000000000000000000f02";
+    const START: u64 = 1_736_000_000;
+    const DAY: u64 = 24 * 60 * 60;
+
+    static TIMELOCK_CALLS: AtomicU64 = AtomicU64::new(0);
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Vec<u8> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        mock::take_return_data()
+    }
+
+    /// Alice, Bob and Carol in that order
+    fn keys() -> Vec<SigningKey> {
+        crypto::generate_keypairs(3)
+            .expect("keypairs")
+            .iter()
+            .map(|(_, secret)| SigningKey::from_bytes(secret))
+            .collect()
+    }
+
+    /// Snapshot balances: owner 50, Alice 400, Bob 300, Carol 100. Quorum is
+    /// 500 and proposing needs 50. Voters have registered their keys.
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(VERIFIER);
+        mock::set_block_height(10);
+        mock::set_block_timestamp(START);
+        TIMELOCK_CALLS.store(0, Ordering::SeqCst);
+        mock::register_contract(TOKEN, "balance_of_at", |args| {
+            let args: BalanceOfAtArgs = postcard::from_bytes(args).unwrap();
+            assert_eq!(args.block, 9);
+            let balance: u64 = match args.account.as_str() {
+                ADDR_OWNER => 50,
+                ADDR_ALICE => 400,
+                ADDR_BOB => 300,
+                ADDR_CAROL => 100,
+                _ => 0,
+            };
+            Ok(postcard::to_allocvec(&balance).unwrap())
+        });
+        mock::register_contract(TIMELOCK, "schedule", |_| {
+            TIMELOCK_CALLS.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        });
+        call(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                token: TOKEN.to_string(),
+                voting_period: 3 * DAY,
+                quorum: 500,
+                proposal_threshold: 50,
+            }),
+        );
+        for (voter, key) in [ADDR_ALICE, ADDR_BOB, ADDR_CAROL].iter().zip(keys()) {
+            call(
+                voter,
+                register_key,
+                encode(&RegisterKeyArgs {
+                    public_key: key.verifying_key().to_bytes(),
+                }),
+            );
+        }
+    }
+
+    fn propose_with_action() -> u64 {
+        let data = call(
+            ADDR_OWNER,
+            propose,
+            encode(&ProposeArgs {
+                description: "Raise the fee to 0.3%".to_string(),
+                action: Some(ProposalAction {
+                    target: TIMELOCK.to_string(),
+                    method: "schedule".to_string(),
+                    data: vec![1, 2, 3],
+                }),
+            }),
+        );
+        postcard::from_bytes(&data).unwrap()
+    }
+
+    fn signed(proposal_id: u64, voter: &str, key: &SigningKey, choice: Choice) -> SignedVote {
+        let message = vote_message(VERIFIER, proposal_id, voter, choice).unwrap();
+        SignedVote {
+            voter: voter.to_string(),
+            choice,
+            signature: key.sign(&message).to_bytes().to_vec(),
+        }
+    }
+
+    fn submit(proposal_id: u64, votes: Vec<SignedVote>) -> Option<BatchResult> {
+        let data = call(
+            ADDR_RELAYER,
+            submit_votes,
+            encode(&SubmitVotesArgs { proposal_id, votes }),
+        );
+        (!data.is_empty()).then(|| postcard::from_bytes(&data).unwrap())
+    }
+
+    fn finalize_now(proposal_id: u64) -> Option<ProposalState> {
+        let data = call(
+            ADDR_RELAYER,
+            finalize,
+            encode(&ProposalIdArgs { proposal_id }),
+        );
+        (!data.is_empty()).then(|| postcard::from_bytes(&data).unwrap())
+    }
+
+    #[test]
+    fn passed_proposals_execute_their_action() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = propose_with_action();
+        let keys = keys();
+
+        let result = submit(
+            id,
+            vec![
+                signed(id, ADDR_ALICE, &keys[0], Choice::For),
+                signed(id, ADDR_BOB, &keys[1], Choice::Against),
+                signed(id, ADDR_CAROL, &keys[2], Choice::Abstain),
+            ],
+        );
+        assert_eq!(
+            result,
+            Some(BatchResult {
+                accepted: 3,
+                skipped: 0
+            })
+        );
+
+        assert_eq!(finalize_now(id), None);
+        mock::set_block_timestamp(START + 3 * DAY);
+        assert_eq!(finalize_now(id), Some(ProposalState::Executed));
+        assert_eq!(TIMELOCK_CALLS.load(Ordering::SeqCst), 1);
+        let proposal = load_proposal(id).unwrap();
+        assert_eq!(
+            (
+                proposal.for_votes,
+                proposal.against_votes,
+                proposal.abstain_votes
+            ),
+            (400, 300, 100)
+        );
+
+        assert_eq!(finalize_now(id), None);
+        assert_eq!(TIMELOCK_CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn invalid_votes_in_a_batch_are_skipped() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = propose_with_action();
+        let keys = keys();
+
+        let mut tampered = signed(id, ADDR_BOB, &keys[1], Choice::Against);
+        tampered.choice = Choice::For;
+        let result = submit(
+            id,
+            vec![
+                signed(id, ADDR_ALICE, &keys[0], Choice::For),
+                // Signed by the wrong key.
+                signed(id, ADDR_CAROL, &keys[0], Choice::For),
+                tampered,
+                // Signed for another proposal.
+                signed(id + 1, ADDR_BOB, &keys[1], Choice::For),
+                // Alice again.
+                signed(id, ADDR_ALICE, &keys[0], Choice::Against),
+            ],
+        );
+        assert_eq!(
+            result,
+            Some(BatchResult {
+                accepted: 1,
+                skipped: 4
+            })
+        );
+        assert_eq!(
+            receipts()
+                .get(&(id, ADDR_ALICE.to_string()))
+                .unwrap()
+                .unwrap(),
+            VoteReceipt {
+                choice: Choice::For,
+                weight: 400
+            }
+        );
+
+        // Votes that were skipped can still be submitted correctly.
+        let result = submit(id, vec![signed(id, ADDR_BOB, &keys[1], Choice::For)]);
+        assert_eq!(result.unwrap().accepted, 1);
+        assert_eq!(load_proposal(id).unwrap().for_votes, 700);
+    }
+
+    #[test]
+    fn batches_past_the_call_data_limit_are_staged_in_chunks() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = propose_with_action();
+        let keys = keys();
+
+        // Three real votes among unregistered voters, too large for one call.
+        let mut votes: Vec<SignedVote> = (0..MAX_STAGED_BATCH_SIZE as u64 - 3)
+            .map(|i| signed(id, &format!("0x{:040x}", 0x1000 + i), &keys[0], Choice::For))
+            .collect();
+        votes.push(signed(id, ADDR_ALICE, &keys[0], Choice::For));
+        votes.push(signed(id, ADDR_BOB, &keys[1], Choice::Against));
+        votes.push(signed(id, ADDR_CAROL, &keys[2], Choice::For));
+        let payload = encode(&SubmitVotesArgs {
+            proposal_id: id,
+            votes,
+        });
+        assert!(payload.len() > MAX_CALL_DATA_BYTES);
+
+        let commit = || {
+            let data = call(
+                ADDR_RELAYER,
+                commit_payload,
+                encode(&CommitPayloadArgs {
+                    sequence: 1,
+                    method: "submit_votes".to_string(),
+                }),
+            );
+            (!data.is_empty()).then(|| postcard::from_bytes::<BatchResult>(&data).unwrap())
+        };
+        call(
+            ADDR_RELAYER,
+            begin_payload,
+            encode(&BeginPayloadArgs {
+                sequence: 1,
+                total_len: payload.len() as u32,
+            }),
+        );
+        let mut chunks = payload.chunks(MAX_CALL_DATA_BYTES / 2).peekable();
+        while let Some(chunk) = chunks.next() {
+            if chunks.peek().is_none() {
+                assert_eq!(commit(), None);
+            }
+            call(
+                ADDR_RELAYER,
+                append_payload,
+                encode(&AppendPayloadArgs {
+                    sequence: 1,
+                    chunk: chunk.to_vec(),
+                }),
+            );
+        }
+        assert_eq!(
+            commit(),
+            Some(BatchResult {
+                accepted: 3,
+                skipped: MAX_STAGED_BATCH_SIZE as u32 - 3
+            })
+        );
+        let proposal = load_proposal(id).unwrap();
+        assert_eq!((proposal.for_votes, proposal.against_votes), (500, 300));
+        // The payload was consumed.
+        assert_eq!(commit(), None);
+        assert!(chunked::staged(ADDR_RELAYER, 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn quorum_and_majority_decide_the_outcome() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let keys = keys();
+
+        // 400 for is a majority but below quorum.
+        let low_turnout = propose_with_action();
+        submit(
+            low_turnout,
+            vec![signed(low_turnout, ADDR_ALICE, &keys[0], Choice::For)],
+        );
+        // Quorum reached but against wins.
+        let rejected = propose_with_action();
+        submit(
+            rejected,
+            vec![
+                signed(rejected, ADDR_ALICE, &keys[0], Choice::Against),
+                signed(rejected, ADDR_BOB, &keys[1], Choice::For),
+            ],
+        );
+
+        mock::set_block_timestamp(START + 3 * DAY);
+        assert_eq!(finalize_now(low_turnout), Some(ProposalState::Defeated));
+        assert_eq!(finalize_now(rejected), Some(ProposalState::Defeated));
+        assert_eq!(TIMELOCK_CALLS.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn votes_close_when_voting_ends() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = propose_with_action();
+        let keys = keys();
+
+        mock::set_block_timestamp(START + 3 * DAY);
+        assert_eq!(
+            submit(id, vec![signed(id, ADDR_ALICE, &keys[0], Choice::For)]),
+            None
+        );
+        assert_eq!(load_proposal(id).unwrap().voter_count, 0);
+    }
+
+    #[test]
+    fn only_the_owner_sets_params() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let params = encode(&SetParamsArgs {
+            voting_period: 5 * DAY,
+            quorum: 600,
+            proposal_threshold: 50,
+        });
+
+        call(ADDR_ALICE, set_params, params.clone());
+        assert_eq!(load_config().unwrap().quorum, 500);
+        call(ADDR_OWNER, set_params, params);
+        let config = load_config().unwrap();
+        assert_eq!((config.voting_period, config.quorum), (5 * DAY, 600));
+        // One audit entry per parameter
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+    }
+
+    #[test]
+    fn proposing_requires_snapshot_balance() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        let data = call(
+            ADDR_RELAYER,
+            propose,
+            encode(&ProposeArgs {
+                description: "Spam".to_string(),
+                action: None,
+            }),
+        );
+        assert!(data.is_empty());
+
+        let data = call(
+            ADDR_CAROL,
+            propose,
+            encode(&ProposeArgs {
+                description: "Signal only".to_string(),
+                action: None,
+            }),
+        );
+        let id: u64 = postcard::from_bytes(&data).unwrap();
+        let keys = keys();
+        submit(
+            id,
+            vec![
+                signed(id, ADDR_ALICE, &keys[0], Choice::For),
+                signed(id, ADDR_CAROL, &keys[2], Choice::For),
+            ],
+        );
+        mock::set_block_timestamp(START + 3 * DAY);
+        assert_eq!(finalize_now(id), Some(ProposalState::Succeeded));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "signed-voting-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
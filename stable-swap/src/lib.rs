@@ -0,0 +1,1695 @@
+//! StableSwap Pair
+//!
+//! A two-coin pool for like-valued assets such as two stablecoins. The
+//! StableSwap invariant behaves like a constant-sum curve near balance and
+//! falls back to constant product as the pool skews, so trades around the
+//! peg see far less slippage than on a constant-product pair. How flat the
+//! curve is near balance is set by the amplification coefficient `A`, which
+//! the owner can only ramp gradually.
+//!
+//! Liquidity providers receive pool shares tracked by the pair itself, which
+//! behave as a CRC-20 (`transfer`, `approve`, `transfer_from`). A share of
+//! every swap fee is set aside as an admin fee for the owner to collect.
+//!
+//! ## Features
+//! - StableSwap invariant with coins of different decimals
+//! - Balanced, proportional and single-coin liquidity operations
+//! - Imbalance fee on lopsided deposits and single-coin withdrawals
+//! - Gradual amplification ramps with bounded rate of change
+//! - Admin fee collection
+//! - Decommissioning: after the owner's notice runs out, deposits and swaps
+//!   stop while LPs withdraw
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod stable_math;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+use silica_contract_sdk::time::Duration;
+use stable_math::{bps_of, mul_div};
+
+const CONFIG_KEY: &str = "stable_swap_config";
+const POOL_KEY: &str = "stable_swap_pool";
+const LP_BALANCE_PREFIX: &str = "lp_balance";
+const LP_ALLOWANCE_PREFIX: &str = "lp_allowance";
+/// Denominator for fees in basis points
+pub const BPS_DENOMINATOR: u64 = safe_math::BPS_DENOMINATOR;
+/// Scale of the virtual price
+pub const VIRTUAL_PRICE_ONE: u128 = 1_000_000_000_000_000_000;
+const MAX_DECIMALS: u8 = 18;
+const MAX_A: u64 = 1_000_000;
+const MAX_A_CHANGE: u64 = 10;
+const MIN_RAMP_TIME: u64 = 86_400;
+const MAX_FEE_BPS: u64 = 100;
+const MAX_ADMIN_FEE_BPS: u64 = 5_000;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Amplification schedule, interpolated linearly between the two points
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AmpRamp {
+    pub initial_a: u64,
+    pub future_a: u64,
+    pub initial_time: u64,
+    pub future_time: u64,
+}
+
+/// Pair configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StableSwapConfig {
+    pub owner: String,
+    pub coins: [String; 2],
+    /// Multipliers bringing both coins to the precision of the finer one
+    pub rates: [u128; 2],
+    /// Swap fee, charged on the output
+    pub fee_bps: u64,
+    /// Share of the swap fee set aside for the owner
+    pub admin_fee_bps: u64,
+    pub ramp: AmpRamp,
+}
+
+/// Pool balances and share supply
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pool {
+    /// Coins backing the LP shares
+    pub balances: [u64; 2],
+    /// Collected admin fees, held outside `balances`
+    pub admin_balances: [u64; 2],
+    pub lp_supply: u64,
+}
+
+/// Pool state at the current block
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolView {
+    pub balances: [u64; 2],
+    pub admin_balances: [u64; 2],
+    pub lp_supply: u64,
+    pub amp: u64,
+    /// Invariant value per share, scaled by 1e18
+    pub virtual_price: u128,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    coins: [String; 2],
+    decimals: [u8; 2],
+    amp: u64,
+    fee_bps: u64,
+    admin_fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AddLiquidityArgs {
+    amounts: [u64; 2],
+    min_mint_amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RemoveLiquidityArgs {
+    amount: u64,
+    min_amounts: [u64; 2],
+}
+
+#[derive(Serialize, Deserialize)]
+struct RemoveOneCoinArgs {
+    amount: u64,
+    coin: u8,
+    min_amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CalcWithdrawArgs {
+    amount: u64,
+    coin: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExchangeArgs {
+    coin_in: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GetDyArgs {
+    coin_in: u8,
+    amount_in: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RampArgs {
+    future_a: u64,
+    future_time: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetFeesArgs {
+    fee_bps: u64,
+    admin_fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllowanceArgs {
+    owner: String,
+    spender: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+/// A priced swap or single-coin withdrawal, before any state changes
+struct Quote {
+    amount_out: u64,
+    fee: u64,
+    admin_fee: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<StableSwapConfig> {
+    storage()
+        .get::<StableSwapConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_config(config: &StableSwapConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<StableSwapConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn load_pool() -> ContractResult<Pool> {
+    Ok(storage().get::<Pool>(POOL_KEY)?.unwrap_or_default())
+}
+
+fn save_pool(pool: &Pool) -> ContractResult<()> {
+    storage().set(POOL_KEY, pool)
+}
+
+fn lp_balances() -> Map<String, u64> {
+    Map::new(LP_BALANCE_PREFIX)
+}
+
+fn lp_allowances() -> Map<(String, String), u64> {
+    Map::new(LP_ALLOWANCE_PREFIX)
+}
+
+fn lp_balance_of(account: &str) -> ContractResult<u64> {
+    Ok(lp_balances().get(&account.to_string())?.unwrap_or(0))
+}
+
+fn pull_tokens(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+fn coin_index(coin: u8) -> ContractResult<usize> {
+    match coin {
+        0 | 1 => Ok(coin as usize),
+        _ => Err(ContractError::InvalidArgument(
+            "Coin index must be 0 or 1".to_string(),
+        )),
+    }
+}
+
+fn to_u64(value: u128) -> ContractResult<u64> {
+    u64::try_from(value).map_err(|_| ContractError::Overflow)
+}
+
+/// Amplification coefficient at `now`.
+pub fn amp_at(ramp: &AmpRamp, now: u64) -> u64 {
+    if now >= ramp.future_time {
+        return ramp.future_a;
+    }
+    let elapsed = (now.max(ramp.initial_time) - ramp.initial_time) as u128;
+    let duration = (ramp.future_time - ramp.initial_time) as u128;
+    let initial = ramp.initial_a as u128;
+    let future = ramp.future_a as u128;
+    // Both branches stay between the initial and future values.
+    if future >= initial {
+        (initial + (future - initial) * elapsed / duration) as u64
+    } else {
+        (initial - (initial - future) * elapsed / duration) as u64
+    }
+}
+
+fn current_amp(config: &StableSwapConfig) -> u128 {
+    amp_at(&config.ramp, context().block_timestamp()) as u128
+}
+
+/// Balances in the common precision.
+fn normalized(balances: [u64; 2], rates: [u128; 2]) -> [u128; 2] {
+    // A u64 times a rate of at most 1e18 fits in u128.
+    [
+        balances[0] as u128 * rates[0],
+        balances[1] as u128 * rates[1],
+    ]
+}
+
+fn invariant(balances: [u64; 2], config: &StableSwapConfig, amp: u128) -> ContractResult<u128> {
+    stable_math::get_d(normalized(balances, config.rates), amp)
+}
+
+fn ensure_liquidity(pool: &Pool) -> ContractResult<()> {
+    if pool.lp_supply == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Pool has no liquidity".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn quote_exchange(
+    config: &StableSwapConfig,
+    pool: &Pool,
+    coin_in: usize,
+    amount_in: u64,
+) -> ContractResult<Quote> {
+    validation::validate_positive_amount(amount_in)?;
+    ensure_liquidity(pool)?;
+    let coin_out = 1 - coin_in;
+    let amp = current_amp(config);
+    let xp = normalized(pool.balances, config.rates);
+    let d = stable_math::get_d(xp, amp)?;
+    let x = xp[coin_in] + amount_in as u128 * config.rates[coin_in];
+    let y = stable_math::get_y(amp, x, d)?;
+    // Round against the trader by one unit of the common precision.
+    let dy = xp[coin_out].saturating_sub(y).saturating_sub(1);
+    let fee = bps_of(dy, config.fee_bps)?;
+    let rate = config.rates[coin_out];
+    let amount_out = to_u64((dy - fee) / rate)?;
+    let fee = to_u64(fee / rate)?;
+    Ok(Quote {
+        amount_out,
+        fee,
+        admin_fee: safe_math::apply_bps(fee, config.admin_fee_bps, Rounding::Down)?,
+    })
+}
+
+fn quote_withdraw_one_coin(
+    config: &StableSwapConfig,
+    pool: &Pool,
+    amount: u64,
+    coin: usize,
+) -> ContractResult<Quote> {
+    validation::validate_positive_amount(amount)?;
+    ensure_liquidity(pool)?;
+    if amount >= pool.lp_supply {
+        return Err(ContractError::InvalidArgument(
+            "Use remove_liquidity to withdraw the whole pool".to_string(),
+        ));
+    }
+    let other = 1 - coin;
+    let amp = current_amp(config);
+    let xp = normalized(pool.balances, config.rates);
+    let d0 = stable_math::get_d(xp, amp)?;
+    let d1 = d0 - mul_div(amount as u128, d0, pool.lp_supply as u128)?;
+    let new_y = stable_math::get_y(amp, xp[other], d1)?;
+
+    // Charge the imbalance fee on how far each side moves from a
+    // proportional withdrawal.
+    let mut expected = [0u128; 2];
+    expected[coin] = mul_div(xp[coin], d1, d0)?.saturating_sub(new_y);
+    expected[other] = xp[other] - mul_div(xp[other], d1, d0)?;
+    let imbalance_fee_bps = config.fee_bps / 2;
+    let reduced = [
+        xp[0] - bps_of(expected[0], imbalance_fee_bps)?,
+        xp[1] - bps_of(expected[1], imbalance_fee_bps)?,
+    ];
+    let y = stable_math::get_y(amp, reduced[other], d1)?;
+    let rate = config.rates[coin];
+    let amount_out = to_u64(reduced[coin].saturating_sub(y).saturating_sub(1) / rate)?;
+    let without_fee = to_u64(xp[coin].saturating_sub(new_y) / rate)?;
+    let fee = without_fee.saturating_sub(amount_out);
+    Ok(Quote {
+        amount_out,
+        fee,
+        admin_fee: safe_math::apply_bps(fee, config.admin_fee_bps, Rounding::Down)?,
+    })
+}
+
+fn virtual_price(config: &StableSwapConfig, pool: &Pool) -> ContractResult<u128> {
+    if pool.lp_supply == 0 {
+        return Ok(0);
+    }
+    let d = invariant(pool.balances, config, current_amp(config))?;
+    mul_div(d, VIRTUAL_PRICE_ONE, pool.lp_supply as u128)
+}
+
+fn validate_fees(fee_bps: u64, admin_fee_bps: u64) -> ContractResult<()> {
+    if fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Swap fee cannot exceed {} bps",
+            MAX_FEE_BPS
+        )));
+    }
+    if admin_fee_bps > MAX_ADMIN_FEE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Admin fee cannot exceed {} bps of the swap fee",
+            MAX_ADMIN_FEE_BPS
+        )));
+    }
+    Ok(())
+}
+
+fn mint_shares(to: &str, amount: u64) -> ContractResult<()> {
+    let balance = safe_math::add(lp_balance_of(to)?, amount)?;
+    lp_balances().set(&to.to_string(), &balance)?;
+    event!("Transfer", from: "", to: to, amount: amount);
+    Ok(())
+}
+
+fn burn_shares(pool: &mut Pool, from: &str, amount: u64) -> ContractResult<()> {
+    let balance = lp_balance_of(from)?;
+    if balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: balance,
+        });
+    }
+    lp_balances().set(&from.to_string(), &(balance - amount))?;
+    pool.lp_supply -= amount;
+    event!("Transfer", from: from, to: "", amount: amount);
+    Ok(())
+}
+
+fn move_shares(from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    validation::validate_address(to)?;
+    let from_balance = lp_balance_of(from)?;
+    if from_balance < amount {
+        return Err(ContractError::InsufficientBalance {
+            required: amount,
+            available: from_balance,
+        });
+    }
+    lp_balances().set(&from.to_string(), &(from_balance - amount))?;
+    let to_balance = safe_math::add(lp_balance_of(to)?, amount)?;
+    lp_balances().set(&to.to_string(), &to_balance)?;
+    event!("Transfer", from: from, to: to, amount: amount);
+    Ok(())
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    for coin in &args.coins {
+        validation::validate_address(coin)?;
+        if !ctx.is_contract(coin) {
+            return Err(ContractError::InvalidArgument(
+                "Coins must be contracts".to_string(),
+            ));
+        }
+    }
+    if args.coins[0] == args.coins[1] {
+        return Err(ContractError::InvalidArgument(
+            "Coins must differ".to_string(),
+        ));
+    }
+    if args
+        .decimals
+        .iter()
+        .any(|decimals| *decimals > MAX_DECIMALS)
+    {
+        return Err(ContractError::InvalidArgument(format!(
+            "Coins may have at most {} decimals",
+            MAX_DECIMALS
+        )));
+    }
+    validation::validate_range(args.amp, 1, MAX_A)?;
+    validate_fees(args.fee_bps, args.admin_fee_bps)?;
+
+    let finest = args.decimals[0].max(args.decimals[1]);
+    let rates = args
+        .decimals
+        .map(|decimals| 10u128.pow((finest - decimals) as u32));
+    let now = ctx.block_timestamp();
+    save_config(&StableSwapConfig {
+        owner: ctx.sender().to_string(),
+        coins: args.coins.clone(),
+        rates,
+        fee_bps: args.fee_bps,
+        admin_fee_bps: args.admin_fee_bps,
+        ramp: AmpRamp {
+            initial_a: args.amp,
+            future_a: args.amp,
+            initial_time: now,
+            future_time: now,
+        },
+    })?;
+
+    let [coin_0, coin_1] = args.coins;
+    event!("StableSwapInitialized",
+        owner: ctx.sender(),
+        coin_0: coin_0,
+        coin_1: coin_1,
+        amp: args.amp,
+        fee_bps: args.fee_bps
+    );
+    Ok(())
+}
+
+fn execute_add_liquidity() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    let provider = context().sender().to_string();
+    let config = load_config()?;
+    let args: AddLiquidityArgs = read_args()?;
+    let mut pool = load_pool()?;
+    let first_deposit = pool.lp_supply == 0;
+    if first_deposit && args.amounts.contains(&0) {
+        return Err(ContractError::InvalidArgument(
+            "Initial deposit must include both coins".to_string(),
+        ));
+    }
+    if args.amounts == [0, 0] {
+        return Err(ContractError::InvalidArgument(
+            "Nothing to deposit".to_string(),
+        ));
+    }
+
+    let amp = current_amp(&config);
+    let old = pool.balances;
+    let d0 = if first_deposit {
+        0
+    } else {
+        invariant(old, &config, amp)?
+    };
+    let mut new = [
+        safe_math::add(old[0], args.amounts[0])?,
+        safe_math::add(old[1], args.amounts[1])?,
+    ];
+    let d1 = invariant(new, &config, amp)?;
+    if d1 <= d0 {
+        return Err(ContractError::InvalidArgument(
+            "Deposit does not grow the pool".to_string(),
+        ));
+    }
+
+    let minted = if first_deposit {
+        pool.balances = new;
+        d1
+    } else {
+        // Lopsided deposits pay the imbalance fee on the part that deviates
+        // from the current pool ratio.
+        let imbalance_fee_bps = config.fee_bps / 2;
+        for i in 0..2 {
+            let ideal = mul_div(d1, old[i] as u128, d0)?;
+            let fee = to_u64(bps_of(ideal.abs_diff(new[i] as u128), imbalance_fee_bps)?)?;
+            pool.balances[i] =
+                new[i] - safe_math::apply_bps(fee, config.admin_fee_bps, Rounding::Down)?;
+            pool.admin_balances[i] =
+                safe_math::add(pool.admin_balances[i], new[i] - pool.balances[i])?;
+            new[i] -= fee;
+        }
+        let d2 = invariant(new, &config, amp)?;
+        mul_div(pool.lp_supply as u128, d2 - d0, d0)?
+    };
+    let minted = to_u64(minted)?;
+    if minted == 0 || minted < args.min_mint_amount {
+        return Err(ContractError::InvalidArgument(format!(
+            "Minted {} below minimum {}",
+            minted, args.min_mint_amount
+        )));
+    }
+    pool.lp_supply = safe_math::add(pool.lp_supply, minted)?;
+    save_pool(&pool)?;
+    mint_shares(&provider, minted)?;
+
+    for (coin, amount) in config.coins.iter().zip(args.amounts) {
+        if amount > 0 {
+            pull_tokens(coin, &provider, amount)?;
+        }
+    }
+
+    event!("LiquidityAdded",
+        provider: provider,
+        amount_0: args.amounts[0],
+        amount_1: args.amounts[1],
+        minted: minted,
+        lp_supply: pool.lp_supply
+    );
+    Ok(minted)
+}
+
+fn execute_remove_liquidity() -> ContractResult<[u64; 2]> {
+    let _guard = ReentrancyGuard::enter()?;
+    let provider = context().sender().to_string();
+    let config = load_config()?;
+    let args: RemoveLiquidityArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    let mut pool = load_pool()?;
+    ensure_liquidity(&pool)?;
+    let amounts = pool
+        .balances
+        .map(|balance| (balance as u128 * args.amount as u128 / pool.lp_supply as u128) as u64);
+    for (amount, min_amount) in amounts.iter().zip(args.min_amounts) {
+        if *amount < min_amount {
+            return Err(ContractError::InvalidArgument(format!(
+                "Withdrawal {} below minimum {}",
+                amount, min_amount
+            )));
+        }
+    }
+    burn_shares(&mut pool, &provider, args.amount)?;
+    for (balance, amount) in pool.balances.iter_mut().zip(amounts) {
+        *balance -= amount;
+    }
+    save_pool(&pool)?;
+
+    for (coin, amount) in config.coins.iter().zip(amounts) {
+        if amount > 0 {
+            send_tokens(coin, &provider, amount)?;
+        }
+    }
+
+    event!("LiquidityRemoved",
+        provider: provider,
+        amount_0: amounts[0],
+        amount_1: amounts[1],
+        burned: args.amount,
+        lp_supply: pool.lp_supply
+    );
+    Ok(amounts)
+}
+
+fn execute_remove_liquidity_one_coin() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let provider = context().sender().to_string();
+    let config = load_config()?;
+    let args: RemoveOneCoinArgs = read_args()?;
+    let coin = coin_index(args.coin)?;
+    let mut pool = load_pool()?;
+    let quote = quote_withdraw_one_coin(&config, &pool, args.amount, coin)?;
+    if quote.amount_out == 0 || quote.amount_out < args.min_amount {
+        return Err(ContractError::InvalidArgument(format!(
+            "Withdrawal {} below minimum {}",
+            quote.amount_out, args.min_amount
+        )));
+    }
+    burn_shares(&mut pool, &provider, args.amount)?;
+    pool.balances[coin] -= quote.amount_out + quote.admin_fee;
+    pool.admin_balances[coin] = safe_math::add(pool.admin_balances[coin], quote.admin_fee)?;
+    save_pool(&pool)?;
+
+    send_tokens(&config.coins[coin], &provider, quote.amount_out)?;
+
+    event!("LiquidityRemovedOneCoin",
+        provider: provider,
+        coin: args.coin,
+        amount: quote.amount_out,
+        fee: quote.fee,
+        burned: args.amount,
+        lp_supply: pool.lp_supply
+    );
+    Ok(quote.amount_out)
+}
+
+fn execute_exchange() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    let trader = context().sender().to_string();
+    let config = load_config()?;
+    let args: ExchangeArgs = read_args()?;
+    let coin_in = coin_index(args.coin_in)?;
+    let coin_out = 1 - coin_in;
+    let mut pool = load_pool()?;
+    let quote = quote_exchange(&config, &pool, coin_in, args.amount_in)?;
+    if quote.amount_out == 0 || quote.amount_out < args.min_amount_out {
+        return Err(ContractError::InvalidArgument(format!(
+            "Output {} below minimum {}",
+            quote.amount_out, args.min_amount_out
+        )));
+    }
+    pool.balances[coin_in] = safe_math::add(pool.balances[coin_in], args.amount_in)?;
+    pool.balances[coin_out] -= quote.amount_out + quote.admin_fee;
+    pool.admin_balances[coin_out] = safe_math::add(pool.admin_balances[coin_out], quote.admin_fee)?;
+    save_pool(&pool)?;
+
+    pull_tokens(&config.coins[coin_in], &trader, args.amount_in)?;
+    send_tokens(&config.coins[coin_out], &trader, quote.amount_out)?;
+
+    event!("TokenExchange",
+        trader: trader,
+        coin_in: args.coin_in,
+        amount_in: args.amount_in,
+        amount_out: quote.amount_out,
+        fee: quote.fee
+    );
+    Ok(quote.amount_out)
+}
+
+fn execute_get_dy() -> ContractResult<u64> {
+    let args: GetDyArgs = read_args()?;
+    let coin_in = coin_index(args.coin_in)?;
+    quote_exchange(&load_config()?, &load_pool()?, coin_in, args.amount_in)
+        .map(|quote| quote.amount_out)
+}
+
+fn execute_calc_withdraw_one_coin() -> ContractResult<u64> {
+    let args: CalcWithdrawArgs = read_args()?;
+    let coin = coin_index(args.coin)?;
+    quote_withdraw_one_coin(&load_config()?, &load_pool()?, args.amount, coin)
+        .map(|quote| quote.amount_out)
+}
+
+fn execute_get_pool() -> ContractResult<PoolView> {
+    let config = load_config()?;
+    let pool = load_pool()?;
+    Ok(PoolView {
+        balances: pool.balances,
+        admin_balances: pool.admin_balances,
+        lp_supply: pool.lp_supply,
+        amp: amp_at(&config.ramp, context().block_timestamp()),
+        virtual_price: virtual_price(&config, &pool)?,
+    })
+}
+
+fn execute_ramp_a() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: RampArgs = read_args()?;
+    let now = context().block_timestamp();
+    if now < config.ramp.initial_time.saturating_add(MIN_RAMP_TIME) {
+        return Err(ContractError::InvalidArgument(
+            "Amplification was changed less than a day ago".to_string(),
+        ));
+    }
+    if args.future_time < now.saturating_add(MIN_RAMP_TIME) {
+        return Err(ContractError::InvalidArgument(
+            "Ramp must last at least a day".to_string(),
+        ));
+    }
+    validation::validate_range(args.future_a, 1, MAX_A)?;
+    let initial_a = amp_at(&config.ramp, now);
+    if args.future_a > initial_a.saturating_mul(MAX_A_CHANGE)
+        || initial_a > args.future_a.saturating_mul(MAX_A_CHANGE)
+    {
+        return Err(ContractError::InvalidArgument(format!(
+            "Amplification can change at most {}x per ramp",
+            MAX_A_CHANGE
+        )));
+    }
+    config.ramp = AmpRamp {
+        initial_a,
+        future_a: args.future_a,
+        initial_time: now,
+        future_time: args.future_time,
+    };
+    save_config(&config)?;
+
+    event!("RampA",
+        initial_a: initial_a,
+        future_a: args.future_a,
+        initial_time: now,
+        future_time: args.future_time
+    );
+    admin_log::record("ramp_a", "amplification", initial_a, args.future_a)?;
+    Ok(())
+}
+
+fn execute_stop_ramp_a() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    let now = context().block_timestamp();
+    let current = amp_at(&config.ramp, now);
+    let target = config.ramp.future_a;
+    config.ramp = AmpRamp {
+        initial_a: current,
+        future_a: current,
+        initial_time: now,
+        future_time: now,
+    };
+    save_config(&config)?;
+
+    event!("StopRampA", amp: current, time: now);
+    admin_log::record("stop_ramp_a", "amplification", target, current)?;
+    Ok(())
+}
+
+fn execute_set_fees() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: SetFeesArgs = read_args()?;
+    validate_fees(args.fee_bps, args.admin_fee_bps)?;
+    let previous = (config.fee_bps, config.admin_fee_bps);
+    config.fee_bps = args.fee_bps;
+    config.admin_fee_bps = args.admin_fee_bps;
+    save_config(&config)?;
+
+    event!("FeesUpdated",
+        fee_bps: args.fee_bps,
+        admin_fee_bps: args.admin_fee_bps
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "fee_bps",
+        previous.0,
+        args.fee_bps,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "admin_fee_bps",
+        previous.1,
+        args.admin_fee_bps,
+    )?;
+    Ok(())
+}
+
+fn execute_withdraw_admin_fees() -> ContractResult<[u64; 2]> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_owned_config()?;
+    let mut pool = load_pool()?;
+    let amounts = pool.admin_balances;
+    pool.admin_balances = [0, 0];
+    save_pool(&pool)?;
+
+    for (coin, amount) in config.coins.iter().zip(amounts) {
+        if amount > 0 {
+            send_tokens(coin, &config.owner, amount)?;
+        }
+    }
+
+    event!("AdminFeesWithdrawn",
+        owner: config.owner,
+        amount_0: amounts[0],
+        amount_1: amounts[1]
+    );
+    admin_log::record(
+        "withdraw_admin_fees",
+        &config.owner,
+        "",
+        format!("{}/{}", amounts[0], amounts[1]),
+    )?;
+    Ok(amounts)
+}
+
+fn execute_transfer() -> ContractResult<()> {
+    let args: TransferArgs = read_args()?;
+    move_shares(context().sender(), &args.to, args.amount)
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: ApproveArgs = read_args()?;
+    validation::validate_address(&args.spender)?;
+    lp_allowances().set(&(owner.clone(), args.spender.clone()), &args.amount)?;
+    event!("Approval", owner: owner, spender: args.spender, amount: args.amount);
+    Ok(())
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    let spender = context().sender().to_string();
+    let args: TransferFromArgs = read_args()?;
+    let key = (args.from.clone(), spender);
+    let allowance = lp_allowances().get(&key)?.unwrap_or(0);
+    if allowance < args.amount {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: allowance,
+        });
+    }
+    move_shares(&args.from, &args.to, args.amount)?;
+    lp_allowances().set(&key, &(allowance - args.amount))
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_owned_config()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::unpause()
+}
+
+/// Create the pair; the caller becomes its owner
+///
+/// # Arguments
+/// * `coins` - The two CRC-20s traded by the pair
+/// * `decimals` - Decimals of each coin (max 18)
+/// * `amp` - Amplification coefficient (1-1000000)
+/// * `fee_bps` - Swap fee (max 100)
+/// * `admin_fee_bps` - Share of the swap fee kept for the owner (max 5000)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Deposit coins for pool shares
+///
+/// The first deposit must include both coins. Later deposits may be
+/// lopsided but pay the imbalance fee.
+///
+/// # Arguments
+/// * `amounts` - Amount of each coin
+/// * `min_mint_amount` - Slippage bound on the shares minted
+///
+/// # Returns
+/// Shares minted (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn add_liquidity() {
+    if let Err(err) = execute_add_liquidity().and_then(|minted| try_respond(&minted)) {
+        log!("add_liquidity failed: {}", err);
+    }
+}
+
+/// Burn shares for a proportional amount of both coins, fee free
+///
+/// # Arguments
+/// * `amount` - Shares burned
+/// * `min_amounts` - Slippage bound for each coin
+///
+/// # Returns
+/// Amounts received (`[u64; 2]`)
+#[unsafe(no_mangle)]
+pub extern "C" fn remove_liquidity() {
+    if let Err(err) = execute_remove_liquidity().and_then(|amounts| try_respond(&amounts)) {
+        log!("remove_liquidity failed: {}", err);
+    }
+}
+
+/// Burn shares for a single coin, paying the imbalance fee
+///
+/// # Arguments
+/// * `amount` - Shares burned (less than the total supply)
+/// * `coin` - Index of the coin received
+/// * `min_amount` - Slippage bound
+///
+/// # Returns
+/// Amount received (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn remove_liquidity_one_coin() {
+    if let Err(err) = execute_remove_liquidity_one_coin().and_then(|out| try_respond(&out)) {
+        log!("remove_liquidity_one_coin failed: {}", err);
+    }
+}
+
+/// Swap one coin for the other
+///
+/// # Arguments
+/// * `coin_in` - Index of the coin paid
+/// * `amount_in` - Amount paid
+/// * `min_amount_out` - Slippage bound
+///
+/// # Returns
+/// Amount received (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn exchange() {
+    if let Err(err) = execute_exchange().and_then(|out| try_respond(&out)) {
+        log!("exchange failed: {}", err);
+    }
+}
+
+/// Quote a swap, net of fees
+///
+/// # Arguments
+/// * `coin_in` - Index of the coin paid
+/// * `amount_in` - Amount paid
+///
+/// # Returns
+/// Amount out (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn get_dy() {
+    if let Err(err) = execute_get_dy().and_then(|out| try_respond(&out)) {
+        log!("get_dy failed: {}", err);
+    }
+}
+
+/// Quote a single-coin withdrawal, net of fees
+///
+/// # Arguments
+/// * `amount` - Shares burned
+/// * `coin` - Index of the coin received
+///
+/// # Returns
+/// Amount out (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn calc_withdraw_one_coin() {
+    if let Err(err) = execute_calc_withdraw_one_coin().and_then(|out| try_respond(&out)) {
+        log!("calc_withdraw_one_coin failed: {}", err);
+    }
+}
+
+/// Get the invariant value of one share, which only grows with fees
+///
+/// # Returns
+/// Virtual price scaled by 1e18 (u128)
+#[unsafe(no_mangle)]
+pub extern "C" fn get_virtual_price() {
+    let result = load_config()
+        .and_then(|config| virtual_price(&config, &load_pool()?))
+        .and_then(|price| try_respond(&price));
+    if let Err(err) = result {
+        log!("get_virtual_price failed: {}", err);
+    }
+}
+
+/// Get the pair configuration
+///
+/// # Returns
+/// `StableSwapConfig`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_config() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config)) {
+        log!("get_config failed: {}", err);
+    }
+}
+
+/// Get balances, share supply, current amplification and virtual price
+///
+/// # Returns
+/// `PoolView`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_pool() {
+    if let Err(err) = execute_get_pool().and_then(|view| try_respond(&view)) {
+        log!("get_pool failed: {}", err);
+    }
+}
+
+/// Move the amplification coefficient linearly to a new value (owner only)
+///
+/// Ramps last at least a day, change `A` by at most 10x and may start at
+/// most once a day.
+///
+/// # Arguments
+/// * `future_a` - Target amplification
+/// * `future_time` - When the target is reached
+#[unsafe(no_mangle)]
+pub extern "C" fn ramp_a() {
+    if let Err(err) = execute_ramp_a() {
+        log!("ramp_a failed: {}", err);
+    }
+}
+
+/// Freeze the amplification at its current value (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn stop_ramp_a() {
+    if let Err(err) = execute_stop_ramp_a() {
+        log!("stop_ramp_a failed: {}", err);
+    }
+}
+
+/// Update the swap and admin fees (owner only)
+///
+/// # Arguments
+/// * `fee_bps` - Swap fee (max 100)
+/// * `admin_fee_bps` - Share of the swap fee kept for the owner (max 5000)
+#[unsafe(no_mangle)]
+pub extern "C" fn set_fees() {
+    if let Err(err) = execute_set_fees() {
+        log!("set_fees failed: {}", err);
+    }
+}
+
+/// Send the collected admin fees to the owner (owner only)
+///
+/// # Returns
+/// Amounts sent (`[u64; 2]`)
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw_admin_fees() {
+    if let Err(err) = execute_withdraw_admin_fees().and_then(|amounts| try_respond(&amounts)) {
+        log!("withdraw_admin_fees failed: {}", err);
+    }
+}
+
+/// Transfer pool shares
+///
+/// # Arguments
+/// * `to` - Recipient
+/// * `amount` - Shares to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer() {
+    if let Err(err) = execute_transfer() {
+        log!("Transfer failed: {}", err);
+    }
+}
+
+/// Approve a spender for pool shares
+///
+/// # Arguments
+/// * `spender` - Address allowed to spend
+/// * `amount` - Allowance, replacing any previous one
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("Approve failed: {}", err);
+    }
+}
+
+/// Transfer pool shares using an allowance
+///
+/// # Arguments
+/// * `from` - Share owner
+/// * `to` - Recipient
+/// * `amount` - Shares to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("TransferFrom failed: {}", err);
+    }
+}
+
+/// Get the pool shares held by an account
+///
+/// # Arguments
+/// * `account` - Holder address
+///
+/// # Returns
+/// Shares (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() {
+    let result = read_args::<AccountArgs>()
+        .and_then(|args| lp_balance_of(&args.account))
+        .and_then(|balance| try_respond(&balance));
+    if let Err(err) = result {
+        log!("balance_of failed: {}", err);
+    }
+}
+
+/// Get the remaining share allowance of a spender
+///
+/// # Arguments
+/// * `owner` - Share owner
+/// * `spender` - Approved spender
+///
+/// # Returns
+/// Allowance (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn allowance() {
+    let result = read_args::<AllowanceArgs>()
+        .and_then(|args| {
+            Ok(lp_allowances()
+                .get(&(args.owner, args.spender))?
+                .unwrap_or(0))
+        })
+        .and_then(|amount| try_respond(&amount));
+    if let Err(err) = result {
+        log!("allowance failed: {}", err);
+    }
+}
+
+/// Get the total supply of pool shares
+///
+/// # Returns
+/// Supply (u64)
+#[unsafe(no_mangle)]
+pub extern "C" fn total_supply() {
+    if let Err(err) = load_pool().and_then(|pool| try_respond(&pool.lp_supply)) {
+        log!("total_supply failed: {}", err);
+    }
+}
+
+/// Give notice that the pair will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the pair once the notice has run out (owner only)
+///
+/// Deposits, swaps, amplification ramps and fee changes stop for good.
+/// Every `remove_liquidity` variant keeps working, and the owner can still
+/// stop a running ramp and collect admin fees.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the pair is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Pause the contract (owner only)
+///
+/// Swaps and new liquidity fail until `unpause`; withdrawals stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the contract is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const USDC: &str = "0x0000000000000000000000000000000000000f01";
+    const USDT: &str = "0x0000000000000000000000000000000000000f02";
+    const NOW: u64 = 1_736_000_000;
+    /// 1M whole coins at 6 decimals
+    const DEPTH: u64 = 1_000_000_000_000;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call<R: DeserializeOwned>(
+        sender: &str,
+        entrypoint: extern "C" fn(),
+        data: Vec<u8>,
+    ) -> Option<R> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// A = 100, 4 bps swap fee, half of it to the owner
+    fn setup(decimals: [u8; 2]) {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(NOW);
+        for token in [USDC, USDT] {
+            for method in ["transfer", "transfer_from"] {
+                mock::register_contract(token, method, |_| Ok(Vec::new()));
+            }
+        }
+        call::<()>(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                coins: [USDC.to_string(), USDT.to_string()],
+                decimals,
+                amp: 100,
+                fee_bps: 4,
+                admin_fee_bps: 5_000,
+            }),
+        );
+    }
+
+    fn deposit(sender: &str, amounts: [u64; 2]) -> Option<u64> {
+        call(
+            sender,
+            add_liquidity,
+            encode(&AddLiquidityArgs {
+                amounts,
+                min_mint_amount: 0,
+            }),
+        )
+    }
+
+    fn swap(sender: &str, coin_in: u8, amount_in: u64) -> Option<u64> {
+        call(
+            sender,
+            exchange,
+            encode(&ExchangeArgs {
+                coin_in,
+                amount_in,
+                min_amount_out: 0,
+            }),
+        )
+    }
+
+    #[test]
+    fn swaps_near_the_peg_barely_slip() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup([6, 6]);
+        assert_eq!(deposit(ADDR_ALICE, [DEPTH, DEPTH]), Some(2 * DEPTH));
+
+        let quoted = call::<u64>(
+            ADDR_BOB,
+            get_dy,
+            encode(&GetDyArgs {
+                coin_in: 0,
+                amount_in: DEPTH / 10,
+            }),
+        )
+        .unwrap();
+        mock::take_contract_calls();
+        let out = swap(ADDR_BOB, 0, DEPTH / 10).unwrap();
+        assert_eq!(out, quoted);
+        // 10% of one side loses under 0.2% to slippage and fees; a
+        // constant-product pair would pay out ~90.9%.
+        assert!(
+            out > DEPTH / 10 * 9_980 / 10_000 && out < DEPTH / 10,
+            "{}",
+            out
+        );
+
+        let calls = mock::take_contract_calls();
+        let pulled: TransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(
+            (calls[0].address.as_str(), pulled.amount),
+            (USDC, DEPTH / 10)
+        );
+        let sent: TransferArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!(
+            (calls[1].address.as_str(), sent.to.as_str(), sent.amount),
+            (USDT, ADDR_BOB, out)
+        );
+        let pool = load_pool().unwrap();
+        assert_eq!(pool.balances[0], DEPTH + DEPTH / 10);
+        assert_eq!(pool.balances[1] + pool.admin_balances[1], DEPTH - out);
+
+        let slipped = call::<u64>(
+            ADDR_BOB,
+            exchange,
+            encode(&ExchangeArgs {
+                coin_in: 1,
+                amount_in: 1_000,
+                min_amount_out: 1_010,
+            }),
+        );
+        assert_eq!(slipped, None);
+        assert_eq!(swap(ADDR_BOB, 2, 1_000), None);
+    }
+
+    #[test]
+    fn coins_with_different_decimals_trade_at_par() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup([6, 8]);
+        assert_eq!(
+            load_config().unwrap().rates,
+            [100, 1],
+            "6-decimal coin is scaled to 8 decimals"
+        );
+        // 1M whole coins on each side; shares use the finer precision.
+        assert_eq!(
+            deposit(ADDR_ALICE, [DEPTH, DEPTH * 100]),
+            Some(2 * DEPTH * 100)
+        );
+
+        let out = swap(ADDR_BOB, 0, 1_000_000).unwrap();
+        assert!((99_950_000..100_000_000).contains(&out), "{}", out);
+        let back = swap(ADDR_BOB, 1, 100_000_000).unwrap();
+        assert!((999_500..1_000_000).contains(&back), "{}", back);
+    }
+
+    #[test]
+    fn admin_fees_accrue_and_shares_gain_value() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup([6, 6]);
+        deposit(ADDR_ALICE, [DEPTH, DEPTH]).unwrap();
+        assert_eq!(execute_get_pool().unwrap().virtual_price, VIRTUAL_PRICE_ONE);
+        for _ in 0..5 {
+            swap(ADDR_BOB, 0, DEPTH / 20).unwrap();
+            swap(ADDR_BOB, 1, DEPTH / 20).unwrap();
+        }
+        let view = execute_get_pool().unwrap();
+        assert!(view.virtual_price > VIRTUAL_PRICE_ONE);
+        // Ten swaps of 50k at 4 bps: ~200 in fees, half for the owner.
+        let collected = view.admin_balances[0] + view.admin_balances[1];
+        assert!(
+            (90_000_000..110_000_000).contains(&collected),
+            "{}",
+            collected
+        );
+
+        assert_eq!(
+            call::<[u64; 2]>(ADDR_ALICE, withdraw_admin_fees, Vec::new()),
+            None
+        );
+        mock::take_contract_calls();
+        assert_eq!(
+            call::<[u64; 2]>(ADDR_OWNER, withdraw_admin_fees, Vec::new()),
+            Some(view.admin_balances)
+        );
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 2);
+        let sent: TransferArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(sent.to, ADDR_OWNER);
+        assert_eq!(load_pool().unwrap().admin_balances, [0, 0]);
+
+        call::<()>(
+            ADDR_ALICE,
+            set_fees,
+            encode(&SetFeesArgs {
+                fee_bps: 100,
+                admin_fee_bps: 0,
+            }),
+        );
+        assert_eq!(load_config().unwrap().fee_bps, 4);
+        call::<()>(
+            ADDR_OWNER,
+            set_fees,
+            encode(&SetFeesArgs {
+                fee_bps: 101,
+                admin_fee_bps: 0,
+            }),
+        );
+        assert_eq!(load_config().unwrap().fee_bps, 4);
+        // Only the withdrawal made it into the audit log.
+        assert_eq!(admin_log::next_sequence().unwrap(), 1);
+    }
+
+    #[test]
+    fn shares_transfer_and_redeem() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup([6, 6]);
+        let shares = deposit(ADDR_ALICE, [DEPTH, DEPTH]).unwrap();
+        // A one-sided deposit pays the imbalance fee and mints less than
+        // the same value deposited evenly.
+        let lopsided = deposit(ADDR_BOB, [DEPTH / 10, 0]).unwrap();
+        assert!(lopsided < DEPTH / 10 && lopsided > DEPTH / 10 * 9_990 / 10_000);
+
+        call::<()>(
+            ADDR_ALICE,
+            approve,
+            encode(&ApproveArgs {
+                spender: ADDR_BOB.to_string(),
+                amount: shares / 2,
+            }),
+        );
+        call::<()>(
+            ADDR_BOB,
+            transfer_from,
+            encode(&TransferFromArgs {
+                from: ADDR_ALICE.to_string(),
+                to: ADDR_BOB.to_string(),
+                amount: shares / 2,
+            }),
+        );
+        assert_eq!(lp_balance_of(ADDR_ALICE).unwrap(), shares / 2);
+        assert_eq!(lp_balance_of(ADDR_BOB).unwrap(), lopsided + shares / 2);
+
+        let pool = load_pool().unwrap();
+        let amounts = call::<[u64; 2]>(
+            ADDR_ALICE,
+            remove_liquidity,
+            encode(&RemoveLiquidityArgs {
+                amount: shares / 2,
+                min_amounts: [0, 0],
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            amounts[0],
+            (pool.balances[0] as u128 * (shares / 2) as u128 / pool.lp_supply as u128) as u64
+        );
+        assert_eq!(lp_balance_of(ADDR_ALICE).unwrap(), 0);
+
+        let single = RemoveOneCoinArgs {
+            amount: lopsided,
+            coin: 1,
+            min_amount: 0,
+        };
+        let quote = |coin: u8| {
+            call::<u64>(
+                ADDR_BOB,
+                calc_withdraw_one_coin,
+                encode(&CalcWithdrawArgs {
+                    amount: lopsided,
+                    coin,
+                }),
+            )
+            .unwrap()
+        };
+        // The pool is long coin 0, so coin 1 is the dearer one to take out.
+        let quoted = quote(1);
+        assert!(quote(0) > quoted && quoted > lopsided * 9_950 / 10_000);
+        assert_eq!(
+            call::<u64>(ADDR_BOB, remove_liquidity_one_coin, encode(&single)),
+            Some(quoted)
+        );
+        assert_eq!(lp_balance_of(ADDR_BOB).unwrap(), shares / 2);
+        // Overdrawn shares are rejected before anything moves.
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, remove_liquidity_one_coin, encode(&single)),
+            None
+        );
+    }
+
+    #[test]
+    fn decommissioning_stops_trading_but_not_withdrawals() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup([6, 6]);
+        let shares = deposit(ADDR_ALICE, [DEPTH, DEPTH]).unwrap();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call::<u64>(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call::<u64>(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        call::<()>(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(deposit(ADDR_BOB, [DEPTH, DEPTH]), None);
+        assert_eq!(swap(ADDR_BOB, 0, DEPTH / 10), None);
+        call::<()>(
+            ADDR_OWNER,
+            set_fees,
+            encode(&SetFeesArgs {
+                fee_bps: 100,
+                admin_fee_bps: 0,
+            }),
+        );
+        assert_eq!(load_config().unwrap().fee_bps, 4);
+
+        let amounts = call::<[u64; 2]>(
+            ADDR_ALICE,
+            remove_liquidity,
+            encode(&RemoveLiquidityArgs {
+                amount: shares,
+                min_amounts: [0, 0],
+            }),
+        );
+        assert_eq!(amounts, Some([DEPTH, DEPTH]));
+    }
+
+    #[test]
+    fn amplification_ramps_gradually() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup([6, 6]);
+        let ramp = |future_a: u64, future_time: u64| {
+            encode(&RampArgs {
+                future_a,
+                future_time,
+            })
+        };
+        // Too soon after initialization, then too fast, too far, not owner.
+        call::<()>(ADDR_OWNER, ramp_a, ramp(200, NOW + 2 * 86_400));
+        mock::set_block_timestamp(NOW + 86_400);
+        call::<()>(ADDR_OWNER, ramp_a, ramp(200, NOW + 86_400 + 3_600));
+        call::<()>(ADDR_OWNER, ramp_a, ramp(1_001, NOW + 3 * 86_400));
+        call::<()>(ADDR_ALICE, ramp_a, ramp(200, NOW + 3 * 86_400));
+        assert_eq!(load_config().unwrap().ramp.future_a, 100);
+
+        call::<()>(ADDR_OWNER, ramp_a, ramp(200, NOW + 3 * 86_400));
+        mock::set_block_timestamp(NOW + 2 * 86_400);
+        assert_eq!(execute_get_pool().unwrap().amp, 150);
+        call::<()>(ADDR_OWNER, stop_ramp_a, Vec::new());
+        mock::set_block_timestamp(NOW + 4 * 86_400);
+        assert_eq!(execute_get_pool().unwrap().amp, 150);
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+
+        // A lower A gives a curvier pool that pays less for the same trade.
+        deposit(ADDR_ALICE, [DEPTH, DEPTH]).unwrap();
+        let get_dy_at = || {
+            call::<u64>(
+                ADDR_BOB,
+                get_dy,
+                encode(&GetDyArgs {
+                    coin_in: 0,
+                    amount_in: DEPTH / 2,
+                }),
+            )
+            .unwrap()
+        };
+        let flat = get_dy_at();
+        call::<()>(ADDR_OWNER, ramp_a, ramp(15, NOW + 5 * 86_400));
+        mock::set_block_timestamp(NOW + 5 * 86_400);
+        assert!(get_dy_at() < flat);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup([6, 6]);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "stable-swap-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! StableSwap invariant math
+//!
+//! Two-coin version of the Curve invariant
+//! `A·n^n·Σx + D = A·D·n^n + D^(n+1) / (n^n·Πx)`, solved with Newton's
+//! method on balances normalized to a common precision. Intermediate
+//! products go through `biguint`'s wide `mul_div` so large pools cannot
+//! overflow.
+
+use biguint::safe_math::{self as wide, Rounding};
+use silica_contract_sdk::prelude::*;
+
+/// Number of coins in the pool
+pub const N_COINS: u128 = 2;
+/// Newton iterations before giving up
+const MAX_ITERATIONS: usize = 255;
+
+/// `a * b / c` rounded down, through `biguint`'s 512-bit `mul_div`.
+pub fn mul_div(a: u128, b: u128, c: u128) -> ContractResult<u128> {
+    let quotient = wide::mul_div(a.into(), b.into(), c.into(), Rounding::Down)?;
+    wide::to_u128(quotient)
+}
+
+/// `bps` basis points of `value`, rounded down.
+pub fn bps_of(value: u128, bps: u64) -> ContractResult<u128> {
+    wide::to_u128(wide::apply_bps(value.into(), bps, Rounding::Down)?)
+}
+
+fn add(a: u128, b: u128) -> ContractResult<u128> {
+    a.checked_add(b).ok_or(ContractError::Overflow)
+}
+
+fn mul(a: u128, b: u128) -> ContractResult<u128> {
+    a.checked_mul(b).ok_or(ContractError::Overflow)
+}
+
+fn not_converged() -> ContractError {
+    ContractError::Custom("StableSwap invariant did not converge".to_string())
+}
+
+/// Invariant `D` for normalized balances `xp` and amplification `amp`.
+pub fn get_d(xp: [u128; 2], amp: u128) -> ContractResult<u128> {
+    let sum = add(xp[0], xp[1])?;
+    if sum == 0 {
+        return Ok(0);
+    }
+    if xp[0] == 0 || xp[1] == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Both pool balances must be positive".to_string(),
+        ));
+    }
+    let ann = mul(amp, N_COINS)?;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for x in xp {
+            d_p = mul_div(d_p, d, mul(x, N_COINS)?)?;
+        }
+        let previous = d;
+        let numerator = add(mul(ann, sum)?, mul(d_p, N_COINS)?)?;
+        let denominator = add(mul(ann - 1, d)?, mul(N_COINS + 1, d_p)?)?;
+        d = mul_div(numerator, d, denominator)?;
+        if d.abs_diff(previous) <= 1 {
+            return Ok(d);
+        }
+    }
+    Err(not_converged())
+}
+
+/// Balance of one coin that keeps the invariant at `d` when the other coin
+/// holds `x`.
+pub fn get_y(amp: u128, x: u128, d: u128) -> ContractResult<u128> {
+    if x == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Pool balance must be positive".to_string(),
+        ));
+    }
+    let ann = mul(amp, N_COINS)?;
+    // c = D^3 / (n^n · x · Ann), b = x + D / Ann
+    let c = mul_div(d, d, mul(x, N_COINS)?)?;
+    let c = mul_div(c, d, mul(ann, N_COINS)?)?;
+    let b = add(x, d / ann)?;
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let previous = y;
+        let denominator = add(mul(2, y)?, b)?
+            .checked_sub(d)
+            .ok_or_else(not_converged)?;
+        y = add(mul_div(y, y, denominator)?, c / denominator)?;
+        if y.abs_diff(previous) <= 1 {
+            return Ok(y);
+        }
+    }
+    Err(not_converged())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_handles_256_bit_products() {
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX).unwrap(), u128::MAX);
+        assert_eq!(mul_div(1 << 100, 1 << 100, 1 << 90).unwrap(), 1 << 110);
+        assert_eq!(mul_div(7, 3, 2).unwrap(), 10);
+        assert!(mul_div(u128::MAX, 2, 1).is_err());
+        assert!(mul_div(1, 1, 0).is_err());
+    }
+
+    #[test]
+    fn invariant_sits_between_constant_sum_and_product() {
+        let amp = 100;
+        // Balanced pools: D is the plain sum.
+        let d = get_d([1_000_000, 1_000_000], amp).unwrap();
+        assert!(d.abs_diff(2_000_000) <= 1);
+        // y solves back to the other balance.
+        assert!(get_y(amp, 1_000_000, d).unwrap().abs_diff(1_000_000) <= 1);
+
+        // A 10% trade barely moves the price at A = 100 ...
+        let y = get_y(amp, 1_100_000, d).unwrap();
+        let out = 1_000_000 - y;
+        assert!(out > 99_900 && out < 100_000, "{}", out);
+        // ... but costs ~9% on a constant-product curve.
+        let product_out = 1_000_000 - 1_000_000u128 * 1_000_000 / 1_100_000;
+        assert!(product_out < 91_000);
+
+        // Huge balances stay within range.
+        let big = u64::MAX as u128 * 1_000_000_000_000;
+        assert!(get_d([big, big / 3], 2_000).is_ok());
+    }
+}
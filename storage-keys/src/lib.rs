@@ -0,0 +1,514 @@
+//! Namespaced storage keys
+//!
+//! Contracts used to name their storage with short literals such as
+//! `"balances"` or `"tokens"`. Two contracts sharing a storage domain, or
+//! one contract embedding another's logic, would then read and write each
+//! other's entries. A [`Namespace`] prefixes every key with the contract id,
+//! the domain and a layout version:
+//!
+//! ```text
+//! prefix = contract "/" domain "/v" version        e.g. crc20/balances/v1
+//! ```
+//!
+//! Map entries append `:` and the key hash to the prefix, as with any
+//! [`Map`]. Contract ids and domains are lowercase letters, digits and `_`,
+//! so a namespaced key never equals an unnamespaced one: only namespaced
+//! keys contain `/`. Bump the version when a domain's value type changes;
+//! the new entries then never decode as the old type.
+//!
+//! ## Compatibility
+//! Contracts deployed before namespacing hold their data under the bare
+//! domain name. Map keys are hashes, so that data cannot be listed and
+//! copied in one migration. [`CompatMap`] and [`CompatValue`] instead read
+//! the namespaced key first and fall back to the legacy one, and every
+//! write moves the entry: the namespaced key is written and the legacy key
+//! cleared. Entries nobody touches stay where they are and keep working.
+//!
+//! A domain whose key type changes moves to a new layout version with
+//! [`Namespace::at_version`] and [`Namespace::rekeyed_map`]. Its reads fall
+//! back to every earlier layout, converting the key to the old type. When
+//! the old layout did not normalize its keys, [`Namespace::respelled_map`]
+//! tries each likely spelling.
+//!
+//! ## Embedding
+//! ```toml
+//! storage-keys = { workspace = true }
+//! ```
+//!
+//! ```rust,ignore
+//! use storage_keys::{CompatMap, Namespace};
+//!
+//! const KEYS: Namespace = Namespace::new("crc20", 1);
+//!
+//! fn balances() -> CompatMap<String, u64> {
+//!     KEYS.compat_map("balances")
+//! }
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+use alloc::rc::Rc;
+use core::marker::PhantomData;
+use silica_contract_sdk::prelude::*;
+
+/// Separates the contract id, domain and version of a prefix
+pub const SEPARATOR: char = '/';
+
+/// Whether `segment` is a valid contract id or domain: non-empty lowercase
+/// ASCII letters, digits and `_`
+pub const fn is_valid_segment(segment: &str) -> bool {
+    let bytes = segment.as_bytes();
+    if bytes.is_empty() {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if !(b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_') {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Storage keys of one contract
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Namespace {
+    contract: &'static str,
+    version: u32,
+}
+
+impl Namespace {
+    /// Namespace of `contract` at key layout `version`; an invalid id fails
+    /// to compile when the namespace is a `const`
+    pub const fn new(contract: &'static str, version: u32) -> Self {
+        assert!(
+            is_valid_segment(contract),
+            "Contract id must be lowercase letters, digits and _"
+        );
+        Self { contract, version }
+    }
+
+    pub fn contract(&self) -> &'static str {
+        self.contract
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The same contract at layout `version`, for the domains whose key or
+    /// value type changed while the others stay where they are
+    pub const fn at_version(self, version: u32) -> Self {
+        Self::new(self.contract, version)
+    }
+
+    /// Prefix of `domain`: `contract/domain/vN`
+    pub fn prefix(&self, domain: &str) -> String {
+        assert!(
+            is_valid_segment(domain),
+            "Storage domain must be lowercase letters, digits and _"
+        );
+        format!(
+            "{}{}{}{}v{}",
+            self.contract, SEPARATOR, domain, SEPARATOR, self.version
+        )
+    }
+
+    /// Map over `domain`
+    pub fn map<K, V>(&self, domain: &str) -> Map<K, V>
+    where
+        K: Serialize,
+        V: Serialize + for<'de> Deserialize<'de>,
+    {
+        Map::new(&self.prefix(domain))
+    }
+
+    /// Map over `domain` that also reads entries stored under the bare
+    /// `domain` prefix before namespacing
+    pub fn compat_map<K, V>(&self, domain: &str) -> CompatMap<K, V>
+    where
+        K: Serialize + 'static,
+        V: Serialize + for<'de> Deserialize<'de>,
+    {
+        CompatMap::new(&self.prefix(domain), domain)
+    }
+
+    /// Map over `domain` whose earlier layouts keyed entries by `L`
+    ///
+    /// Reads fall back to `domain` at every earlier version, newest first,
+    /// then to the bare `domain`, converting the key with `to_legacy`.
+    pub fn rekeyed_map<K, L, V>(&self, domain: &str, to_legacy: fn(&K) -> L) -> CompatMap<K, V>
+    where
+        K: Serialize + 'static,
+        L: Serialize + 'static,
+        V: Serialize + for<'de> Deserialize<'de>,
+    {
+        self.layouts(domain, move |key| vec![to_legacy(key)])
+    }
+
+    /// Map over `domain` whose earlier layouts keyed an entry by any of
+    /// several spellings of `L`
+    ///
+    /// Like [`Namespace::rekeyed_map`], trying every spelling `spellings`
+    /// returns in each earlier layout. Each spelling costs a storage read
+    /// when the entry is missing, so keep the list short.
+    pub fn respelled_map<K, L, V>(
+        &self,
+        domain: &str,
+        spellings: fn(&K) -> Vec<L>,
+    ) -> CompatMap<K, V>
+    where
+        K: Serialize + 'static,
+        L: Serialize + 'static,
+        V: Serialize + for<'de> Deserialize<'de>,
+    {
+        self.layouts(domain, spellings)
+    }
+
+    fn layouts<K, L, V, F>(&self, domain: &str, spellings: F) -> CompatMap<K, V>
+    where
+        K: Serialize + 'static,
+        L: Serialize + 'static,
+        V: Serialize + for<'de> Deserialize<'de>,
+        F: Fn(&K) -> Vec<L> + Copy + 'static,
+    {
+        let mut legacy: Vec<LegacyKeys<K>> = (1..self.version)
+            .rev()
+            .map(|version| rekeyed(self.at_version(version).prefix(domain), spellings))
+            .collect();
+        legacy.push(rekeyed(domain.to_string(), spellings));
+        CompatMap {
+            current: Map::new(&self.prefix(domain)),
+            legacy,
+        }
+    }
+
+    /// Single value under `name` that also reads the bare `name` key
+    /// written before namespacing
+    pub fn compat_value<V>(&self, name: &str) -> CompatValue<V>
+    where
+        V: Serialize + for<'de> Deserialize<'de>,
+    {
+        CompatValue::new(&self.prefix(name), name)
+    }
+}
+
+/// Storage keys an entry may have in an earlier layout
+type LegacyKeys<K> = Rc<dyn Fn(&K) -> ContractResult<Vec<String>>>;
+
+/// Keys under `prefix` of a map keyed by `L`
+fn rekeyed<K, L, F>(prefix: String, spellings: F) -> LegacyKeys<K>
+where
+    K: 'static,
+    L: Serialize + 'static,
+    F: Fn(&K) -> Vec<L> + 'static,
+{
+    let map: Map<L, ()> = Map::new(&prefix);
+    Rc::new(move |key| {
+        spellings(key)
+            .iter()
+            .map(|spelling| map.storage_key(spelling))
+            .collect()
+    })
+}
+
+/// A map under a namespaced prefix that falls back to earlier layouts
+#[derive(Clone)]
+pub struct CompatMap<K, V> {
+    current: Map<K, V>,
+    /// Newest first
+    legacy: Vec<LegacyKeys<K>>,
+}
+
+impl<K, V> CompatMap<K, V>
+where
+    K: Serialize + 'static,
+    V: Serialize + for<'de> Deserialize<'de>,
+{
+    /// Map under `prefix` that also reads same-keyed entries under
+    /// `legacy_prefix`
+    pub fn new(prefix: &str, legacy_prefix: &str) -> Self {
+        let legacy: Map<K, ()> = Map::new(legacy_prefix);
+        Self {
+            current: Map::new(prefix),
+            legacy: vec![Rc::new(move |key: &K| Ok(vec![legacy.storage_key(key)?]))],
+        }
+    }
+
+    /// Storage key the entry is written under
+    pub fn storage_key(&self, key: &K) -> ContractResult<String> {
+        self.current.storage_key(key)
+    }
+
+    /// Storage keys the entry may have been written under in earlier
+    /// layouts, newest first
+    pub fn legacy_storage_keys(&self, key: &K) -> ContractResult<Vec<String>> {
+        let mut keys = Vec::new();
+        for legacy in &self.legacy {
+            keys.extend(legacy(key)?);
+        }
+        Ok(keys)
+    }
+
+    pub fn get(&self, key: &K) -> ContractResult<Option<V>> {
+        if let Some(value) = self.current.get(key)? {
+            return Ok(Some(value));
+        }
+        for legacy_key in self.legacy_storage_keys(key)? {
+            if let Some(value) = storage().get(&legacy_key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Write under the namespaced key and clear any legacy entry
+    pub fn set(&mut self, key: &K, value: &V) -> ContractResult<()> {
+        self.current.set(key, value)?;
+        self.clear_legacy(key)
+    }
+
+    /// Remove both the namespaced and any legacy entry
+    pub fn remove(&mut self, key: &K) -> ContractResult<()> {
+        self.current.remove(key)?;
+        self.clear_legacy(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> ContractResult<bool> {
+        if self.current.contains_key(key)? {
+            return Ok(true);
+        }
+        let legacy_keys = self.legacy_storage_keys(key)?;
+        Ok(legacy_keys
+            .iter()
+            .any(|legacy_key| storage().has(legacy_key)))
+    }
+
+    // Only touch a legacy key when it holds something, so contracts
+    // deployed after namespacing never write to it.
+    fn clear_legacy(&mut self, key: &K) -> ContractResult<()> {
+        for legacy_key in self.legacy_storage_keys(key)? {
+            if storage().has(&legacy_key) {
+                storage().remove(&legacy_key)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single value under a namespaced key that falls back to a legacy key
+#[derive(Clone)]
+pub struct CompatValue<V> {
+    key: String,
+    legacy_key: String,
+    _phantom: PhantomData<V>,
+}
+
+impl<V> CompatValue<V>
+where
+    V: Serialize + for<'de> Deserialize<'de>,
+{
+    pub fn new(key: &str, legacy_key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            legacy_key: legacy_key.to_string(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Storage key the value is written under
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn legacy_key(&self) -> &str {
+        &self.legacy_key
+    }
+
+    pub fn get(&self) -> ContractResult<Option<V>> {
+        match storage().get(&self.key)? {
+            Some(value) => Ok(Some(value)),
+            None => storage().get(&self.legacy_key),
+        }
+    }
+
+    /// Write under the namespaced key and clear any legacy value
+    pub fn set(&mut self, value: &V) -> ContractResult<()> {
+        storage().set(&self.key, value)?;
+        self.clear_legacy()
+    }
+
+    /// Remove both the namespaced and any legacy value
+    pub fn remove(&mut self) -> ContractResult<()> {
+        storage().remove(&self.key)?;
+        self.clear_legacy()
+    }
+
+    pub fn exists(&self) -> bool {
+        storage().has(&self.key) || storage().has(&self.legacy_key)
+    }
+
+    fn clear_legacy(&mut self) -> ContractResult<()> {
+        if storage().has(&self.legacy_key) {
+            storage().remove(&self.legacy_key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use silica_contract_sdk::ffi::mock;
+    use silica_contract_sdk::testing::StorageSnapshot;
+    use std::sync::{Mutex, OnceLock};
+
+    const KEYS: Namespace = Namespace::new("token", 1);
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_sender("0x0000000000000000000000000000000000000a01");
+        mock::set_contract_address("0x0000000000000000000000000000000000000f00");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_700_000_000);
+    }
+
+    #[test]
+    fn prefixes_name_contract_domain_and_version() {
+        assert_eq!(KEYS.prefix("balances"), "token/balances/v1");
+        assert_eq!(
+            Namespace::new("token", 2).prefix("balances"),
+            "token/balances/v2"
+        );
+        assert_eq!(
+            KEYS.map::<String, u64>("balances")
+                .storage_key(&"alice".to_string())
+                .unwrap(),
+            Map::<String, u64>::new("token/balances/v1")
+                .storage_key(&"alice".to_string())
+                .unwrap()
+        );
+        for segment in ["", "Balances", "a/b", "a:b", "a b"] {
+            assert!(!is_valid_segment(segment), "{:?}", segment);
+        }
+        assert!(is_valid_segment("balance_checkpoints2"));
+        assert!(std::panic::catch_unwind(|| KEYS.prefix("a/b")).is_err());
+    }
+
+    #[test]
+    fn compat_maps_move_legacy_entries_on_write() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let mut legacy: Map<String, u64> = Map::new("balances");
+        let alice = "alice".to_string();
+        let bob = "bob".to_string();
+        legacy.set(&alice, &10).unwrap();
+        legacy.set(&bob, &20).unwrap();
+
+        let mut balances: CompatMap<String, u64> = KEYS.compat_map("balances");
+        assert_eq!(balances.get(&alice).unwrap(), Some(10));
+        assert!(balances.contains_key(&bob).unwrap());
+
+        balances.set(&alice, &15).unwrap();
+        assert_eq!(balances.get(&alice).unwrap(), Some(15));
+        assert_eq!(legacy.get(&alice).unwrap(), None);
+        balances.remove(&bob).unwrap();
+        assert_eq!(balances.get(&bob).unwrap(), None);
+        assert!(!legacy.contains_key(&bob).unwrap());
+
+        // Entries that never had a legacy copy leave the legacy prefix alone.
+        let before = StorageSnapshot::take();
+        let carol = "carol".to_string();
+        balances.set(&carol, &5).unwrap();
+        before
+            .diff(&StorageSnapshot::take())
+            .assert_only(&[&balances.storage_key(&carol).unwrap()]);
+    }
+
+    #[test]
+    fn rekeyed_maps_read_every_earlier_layout() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        fn to_legacy(id: &u32) -> String {
+            format!("id{}", id)
+        }
+        let mut bare: Map<String, u64> = Map::new("balances");
+        let mut v1: Map<String, u64> = KEYS.map("balances");
+        bare.set(&to_legacy(&1), &10).unwrap();
+        v1.set(&to_legacy(&2), &20).unwrap();
+
+        let mut balances: CompatMap<u32, u64> =
+            KEYS.at_version(2).rekeyed_map("balances", to_legacy);
+        assert_eq!(
+            balances.legacy_storage_keys(&1).unwrap(),
+            [
+                v1.storage_key(&to_legacy(&1)).unwrap(),
+                bare.storage_key(&to_legacy(&1)).unwrap()
+            ]
+        );
+        assert_eq!(balances.get(&1).unwrap(), Some(10));
+        assert_eq!(balances.get(&2).unwrap(), Some(20));
+        assert!(!balances.contains_key(&3).unwrap());
+
+        balances.set(&1, &11).unwrap();
+        balances.remove(&2).unwrap();
+        assert!(!bare.contains_key(&to_legacy(&1)).unwrap());
+        assert!(!v1.contains_key(&to_legacy(&2)).unwrap());
+        assert_eq!(balances.get(&1).unwrap(), Some(11));
+        assert_eq!(balances.get(&2).unwrap(), None);
+        assert!(
+            balances
+                .storage_key(&1)
+                .unwrap()
+                .starts_with("token/balances/v2:")
+        );
+    }
+
+    #[test]
+    fn respelled_maps_try_every_spelling() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        fn spellings(id: &u32) -> Vec<String> {
+            vec![format!("id{}", id), format!("ID{}", id)]
+        }
+        let mut bare: Map<String, u64> = Map::new("balances");
+        bare.set(&"ID1".to_string(), &10).unwrap();
+
+        let mut balances: CompatMap<u32, u64> =
+            KEYS.at_version(2).respelled_map("balances", spellings);
+        assert_eq!(balances.legacy_storage_keys(&1).unwrap().len(), 4);
+        assert_eq!(balances.get(&1).unwrap(), Some(10));
+        assert!(balances.contains_key(&1).unwrap());
+        balances.set(&1, &11).unwrap();
+        assert!(!bare.contains_key(&"ID1".to_string()).unwrap());
+        assert_eq!(balances.get(&1).unwrap(), Some(11));
+    }
+
+    #[test]
+    fn compat_values_move_legacy_values_on_write() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        storage().set("metadata", &7u32).unwrap();
+
+        let mut metadata: CompatValue<u32> = KEYS.compat_value("metadata");
+        assert_eq!(metadata.key(), "token/metadata/v1");
+        assert!(metadata.exists());
+        assert_eq!(metadata.get().unwrap(), Some(7));
+
+        metadata.set(&8).unwrap();
+        assert!(!storage().has("metadata"));
+        assert_eq!(metadata.get().unwrap(), Some(8));
+        metadata.remove().unwrap();
+        assert!(!metadata.exists());
+        assert_eq!(metadata.get().unwrap(), None);
+    }
+}
@@ -0,0 +1,227 @@
+//! Embeddable strategy base
+//!
+//! Configuration, access checks and performance fee accounting shared by
+//! every strategy. A strategy built on this module stores its vault, the
+//! underlying asset and its fee policy here, and calls [`record_harvest`]
+//! with each harvest's profit so the fee is paid and the totals are kept in
+//! one place.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+/// Highest performance fee a strategy may charge (20%)
+pub const MAX_PERFORMANCE_FEE_BPS: u16 = 2_000;
+/// Basis point denominator
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+const CONFIG_KEY: &str = "strategy_config";
+const STATS_KEY: &str = "strategy_stats";
+
+/// Strategy configuration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StrategyConfig {
+    pub vault: String,
+    pub asset: String,
+    pub owner: String,
+    pub performance_fee_bps: u16,
+    pub fee_recipient: String,
+    /// Set once the strategy has exited; it accepts no further deposits
+    pub shutdown: bool,
+}
+
+/// Arguments of the strategy `deposit` and `withdraw` entrypoints, encoded
+/// as `vault_contract::strategy::AmountArgs`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AmountArgs {
+    pub amount: u64,
+}
+
+/// Lifetime harvest totals
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HarvestStats {
+    pub harvests: u64,
+    pub total_profit: u64,
+    pub total_fees: u64,
+    pub last_harvest: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+fn validate_fee(performance_fee_bps: u16, fee_recipient: &str) -> ContractResult<()> {
+    validation::validate_address(fee_recipient)?;
+    if performance_fee_bps > MAX_PERFORMANCE_FEE_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Performance fee cannot exceed {} bps",
+            MAX_PERFORMANCE_FEE_BPS
+        )));
+    }
+    Ok(())
+}
+
+/// Store the configuration of a new strategy owned by the caller.
+pub fn initialize(
+    vault: &str,
+    asset: &str,
+    performance_fee_bps: u16,
+    fee_recipient: &str,
+) -> ContractResult<StrategyConfig> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Strategy already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    for address in [vault, asset] {
+        validation::validate_address(address)?;
+        if !ctx.is_contract(address) {
+            return Err(ContractError::InvalidArgument(
+                "Vault and asset must be contracts".to_string(),
+            ));
+        }
+    }
+    validate_fee(performance_fee_bps, fee_recipient)?;
+
+    let config = StrategyConfig {
+        vault: vault.to_string(),
+        asset: asset.to_string(),
+        owner: ctx.sender().to_string(),
+        performance_fee_bps,
+        fee_recipient: fee_recipient.to_string(),
+        shutdown: false,
+    };
+    save_config(&config)?;
+
+    event!("StrategyInitialized", vault: vault, asset: asset, owner: ctx.sender());
+    Ok(config)
+}
+
+pub fn load_config() -> ContractResult<StrategyConfig> {
+    storage()
+        .get::<StrategyConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Strategy not initialized".to_string()))
+}
+
+pub fn save_config(config: &StrategyConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+pub fn stats() -> ContractResult<HarvestStats> {
+    Ok(storage()
+        .get::<HarvestStats>(STATS_KEY)?
+        .unwrap_or_default())
+}
+
+/// Fail unless the caller is the strategy's vault.
+pub fn require_vault(config: &StrategyConfig) -> ContractResult<()> {
+    if context().sender() != config.vault {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Fail unless the caller is the strategy's owner.
+pub fn require_owner(config: &StrategyConfig) -> ContractResult<()> {
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Fail once the strategy has exited.
+pub fn require_active(config: &StrategyConfig) -> ContractResult<()> {
+    if config.shutdown {
+        return Err(ContractError::InvalidArgument(
+            "Strategy has been shut down".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Change the fee policy (owner only); applies from the next harvest.
+pub fn set_performance_fee(performance_fee_bps: u16, fee_recipient: &str) -> ContractResult<()> {
+    let mut config = load_config()?;
+    require_owner(&config)?;
+    validate_fee(performance_fee_bps, fee_recipient)?;
+    let previous_fee_bps = config.performance_fee_bps;
+    config.performance_fee_bps = performance_fee_bps;
+    let previous_recipient =
+        core::mem::replace(&mut config.fee_recipient, fee_recipient.to_string());
+    save_config(&config)?;
+
+    event!("PerformanceFeeUpdated",
+        performance_fee_bps: performance_fee_bps,
+        fee_recipient: fee_recipient
+    );
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "performance_fee_bps",
+        previous_fee_bps,
+        performance_fee_bps,
+    )?;
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "fee_recipient",
+        previous_recipient,
+        fee_recipient,
+    )?;
+    Ok(())
+}
+
+/// Fee owed on `profit` at `performance_fee_bps`, rounded down.
+pub fn performance_fee(profit: u64, performance_fee_bps: u16) -> u64 {
+    // Cannot overflow: the fee is at most 20% of a u64.
+    ((profit as u128) * (performance_fee_bps as u128) / (BPS_DENOMINATOR as u128)) as u64
+}
+
+/// Pay the performance fee on `profit` and update the harvest totals.
+/// Returns the profit left for the vault.
+pub fn record_harvest(config: &StrategyConfig, profit: u64) -> ContractResult<u64> {
+    let fee = performance_fee(profit, config.performance_fee_bps);
+    if fee > 0 {
+        send_asset(&config.asset, &config.fee_recipient, fee)?;
+    }
+
+    let mut totals = stats()?;
+    totals.harvests = safe_math::add(totals.harvests, 1)?;
+    totals.total_profit = safe_math::add(totals.total_profit, profit)?;
+    totals.total_fees = safe_math::add(totals.total_fees, fee)?;
+    totals.last_harvest = context().block_timestamp();
+    storage().set(STATS_KEY, &totals)?;
+
+    event!("Harvested", profit: profit, fee: fee, recipient: config.fee_recipient.clone());
+    Ok(profit - fee)
+}
+
+/// CRC-20 `transfer` of `amount` of `asset` from this contract.
+pub fn send_asset(asset: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = postcard::to_allocvec(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    context()
+        .call_contract(asset, "transfer", &call)
+        .map(|_| ())
+}
+
+/// CRC-20 `approve` of `spender` for `amount` of `asset`.
+pub fn approve_asset(asset: &str, spender: &str, amount: u64) -> ContractResult<()> {
+    let call = postcard::to_allocvec(&TokenApproveArgs {
+        spender: spender.to_string(),
+        amount,
+    })
+    .map_err(|_| ContractError::SerializationFailed)?;
+    context().call_contract(asset, "approve", &call).map(|_| ())
+}
@@ -0,0 +1,575 @@
+//! Sample staking strategy entrypoints
+//!
+//! Stakes everything the vault deposits in a staking pool that pays
+//! rewards in the same token. The pool is expected to export
+//! `stake(amount)` (pulling approved tokens), `unstake(amount)` (sending
+//! them back) and `claim_rewards() -> u64`.
+
+use crate::base::{self, AmountArgs, HarvestStats, StrategyConfig};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const POOL_KEY: &str = "staking_pool";
+const POSITION_KEY: &str = "staking_position";
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Funds under management: staked in the pool or held here
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub staked: u64,
+    pub idle: u64,
+}
+
+/// Summary returned by `get_strategy_info`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StrategyInfo {
+    pub config: StrategyConfig,
+    pub pool: String,
+    pub position: Position,
+    pub stats: HarvestStats,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    vault: String,
+    asset: String,
+    pool: String,
+    performance_fee_bps: u16,
+    fee_recipient: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetPerformanceFeeArgs {
+    performance_fee_bps: u16,
+    fee_recipient: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_pool() -> ContractResult<String> {
+    storage()
+        .get::<String>(POOL_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Strategy not initialized".to_string()))
+}
+
+fn load_position() -> ContractResult<Position> {
+    Ok(storage().get::<Position>(POSITION_KEY)?.unwrap_or_default())
+}
+
+fn save_position(position: &Position) -> ContractResult<()> {
+    storage().set(POSITION_KEY, position)
+}
+
+fn stake(config: &StrategyConfig, pool: &str, amount: u64) -> ContractResult<()> {
+    base::approve_asset(&config.asset, pool, amount)?;
+    context()
+        .call_contract(pool, "stake", &encode_call(&AmountArgs { amount })?)
+        .map(|_| ())
+}
+
+fn unstake(pool: &str, amount: u64) -> ContractResult<()> {
+    context()
+        .call_contract(pool, "unstake", &encode_call(&AmountArgs { amount })?)
+        .map(|_| ())
+}
+
+/// Claim pool rewards and pay the performance fee; returns the net profit.
+fn harvest_rewards(config: &StrategyConfig, pool: &str) -> ContractResult<u64> {
+    let response = context().call_contract(pool, "claim_rewards", &[])?;
+    let rewards: u64 =
+        postcard::from_bytes(&response).map_err(|_| ContractError::DeserializationFailed)?;
+    if rewards == 0 {
+        return Ok(0);
+    }
+    base::record_harvest(config, rewards)
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.pool)?;
+    if !context().is_contract(&args.pool) {
+        return Err(ContractError::InvalidArgument(
+            "Pool must be a contract".to_string(),
+        ));
+    }
+    base::initialize(
+        &args.vault,
+        &args.asset,
+        args.performance_fee_bps,
+        &args.fee_recipient,
+    )?;
+    storage().set(POOL_KEY, &args.pool)
+}
+
+fn execute_deposit() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = base::load_config()?;
+    base::require_vault(&config)?;
+    base::require_active(&config)?;
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    // The vault transferred the tokens before calling in.
+    let pool = load_pool()?;
+    stake(&config, &pool, args.amount)?;
+    let mut position = load_position()?;
+    position.staked = safe_math::add(position.staked, args.amount)?;
+    save_position(&position)?;
+
+    event!("Staked", pool: pool, amount: args.amount);
+    Ok(())
+}
+
+fn execute_withdraw() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = base::load_config()?;
+    base::require_vault(&config)?;
+    let args: AmountArgs = read_args()?;
+
+    // Serve from idle rewards first, then unstake the rest.
+    let mut position = load_position()?;
+    let from_idle = args.amount.min(position.idle);
+    let from_pool = (args.amount - from_idle).min(position.staked);
+    if from_pool > 0 {
+        unstake(&load_pool()?, from_pool)?;
+        position.staked -= from_pool;
+    }
+    position.idle -= from_idle;
+    save_position(&position)?;
+
+    let sent = from_idle + from_pool;
+    if sent > 0 {
+        base::send_asset(&config.asset, &config.vault, sent)?;
+    }
+    Ok(sent)
+}
+
+fn execute_harvest() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = base::load_config()?;
+    base::require_owner(&config)?;
+    let net = harvest_rewards(&config, &load_pool()?)?;
+    let mut position = load_position()?;
+    let previous = position.idle;
+    position.idle = safe_math::add(previous, net)?;
+    save_position(&position)?;
+    admin_log::record("harvest", "idle", previous, position.idle)?;
+    Ok(net)
+}
+
+fn execute_compound() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = base::load_config()?;
+    base::require_owner(&config)?;
+    base::require_active(&config)?;
+    let pool = load_pool()?;
+    let net = harvest_rewards(&config, &pool)?;
+
+    let mut position = load_position()?;
+    let restaked = safe_math::add(position.idle, net)?;
+    if restaked > 0 {
+        stake(&config, &pool, restaked)?;
+    }
+    position.staked = safe_math::add(position.staked, restaked)?;
+    position.idle = 0;
+    save_position(&position)?;
+
+    event!("Compounded", pool: pool, amount: restaked);
+    admin_log::record("compound", &pool, "", restaked)?;
+    Ok(restaked)
+}
+
+fn execute_exit_strategy() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = base::load_config()?;
+    base::require_vault(&config)?;
+    let pool = load_pool()?;
+
+    let mut position = load_position()?;
+    if position.staked > 0 {
+        unstake(&pool, position.staked)?;
+    }
+    let net = harvest_rewards(&config, &pool)?;
+    let returned = safe_math::add(safe_math::add(position.staked, position.idle)?, net)?;
+    position = Position::default();
+    save_position(&position)?;
+    config.shutdown = true;
+    base::save_config(&config)?;
+
+    if returned > 0 {
+        base::send_asset(&config.asset, &config.vault, returned)?;
+    }
+    event!("StrategyExited", vault: config.vault, returned: returned);
+    Ok(returned)
+}
+
+fn execute_get_strategy_info() -> ContractResult<StrategyInfo> {
+    Ok(StrategyInfo {
+        config: base::load_config()?,
+        pool: load_pool()?,
+        position: load_position()?,
+        stats: base::stats()?,
+    })
+}
+
+fn respond_amount(name: &str, result: ContractResult<u64>) {
+    if let Err(err) = result.and_then(|amount| try_respond(&amount)) {
+        log!("{} failed: {}", name, err);
+    }
+}
+
+/// Initialize the strategy; the caller becomes its owner
+///
+/// # Arguments
+/// * `vault` - Vault allowed to deposit and withdraw
+/// * `asset` - Underlying CRC-20 (also the pool's reward token)
+/// * `pool` - Staking pool
+/// * `performance_fee_bps` - Share of each harvest paid as fee (max 2000)
+/// * `fee_recipient` - Account receiving performance fees
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Stake assets the vault just transferred in (vault only)
+///
+/// # Arguments
+/// * `amount` - Amount transferred by the vault
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    if let Err(err) = execute_deposit() {
+        log!("deposit failed: {}", err);
+    }
+}
+
+/// Return up to `amount` to the vault (vault only)
+///
+/// # Arguments
+/// * `amount` - Amount requested
+///
+/// # Returns
+/// Amount sent to the vault
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw() {
+    respond_amount("withdraw", execute_withdraw());
+}
+
+/// Assets managed for the vault
+///
+/// # Returns
+/// `u64` - staked plus idle; unclaimed rewards are not counted
+#[unsafe(no_mangle)]
+pub extern "C" fn total_assets() {
+    respond_amount(
+        "total_assets",
+        load_position().and_then(|position| safe_math::add(position.staked, position.idle)),
+    );
+}
+
+/// Claim pool rewards and keep them idle after the performance fee (owner only)
+///
+/// # Returns
+/// Net profit added for the vault
+#[unsafe(no_mangle)]
+pub extern "C" fn harvest() {
+    respond_amount("harvest", execute_harvest());
+}
+
+/// Harvest, then stake every idle asset (owner only)
+///
+/// # Returns
+/// Amount newly staked
+#[unsafe(no_mangle)]
+pub extern "C" fn compound() {
+    respond_amount("compound", execute_compound());
+}
+
+/// Unstake everything, harvest, send all funds to the vault and shut down
+/// (vault only)
+///
+/// # Returns
+/// Amount sent to the vault
+#[unsafe(no_mangle)]
+pub extern "C" fn exit_strategy() {
+    respond_amount("exit_strategy", execute_exit_strategy());
+}
+
+/// Change the performance fee policy (owner only)
+///
+/// # Arguments
+/// * `performance_fee_bps` - Share of each harvest paid as fee (max 2000)
+/// * `fee_recipient` - Account receiving performance fees
+#[unsafe(no_mangle)]
+pub extern "C" fn set_performance_fee() {
+    let result = read_args::<SetPerformanceFeeArgs>()
+        .and_then(|args| base::set_performance_fee(args.performance_fee_bps, &args.fee_recipient));
+    if let Err(err) = result {
+        log!("set_performance_fee failed: {}", err);
+    }
+}
+
+/// Get the configuration, position and harvest totals
+///
+/// # Returns
+/// `StrategyInfo`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_strategy_info() {
+    if let Err(err) = execute_get_strategy_info().and_then(|info| try_respond(&info)) {
+        log!("get_strategy_info failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_TREASURY: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_MALLORY: &str = "0x0000000000000000000000000000000000000c03";
+    const STRATEGY: &str = "0x0000000000000000000000000000000000000f00";
+    const VAULT: &str = "0x0000000000000000000000000000000000000f01";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f02";
+    const POOL: &str = "0x0000000000000000000000000000000000000f03";
+
+    /// Rewards the mocked pool pays on the next claim
+    static PENDING_REWARDS: AtomicU64 = AtomicU64::new(0);
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(STRATEGY);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::set_sender(ADDR_OWNER);
+        mock::mark_contract(VAULT);
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "approve", |_| Ok(Vec::new()));
+        mock::register_contract(POOL, "stake", |_| Ok(Vec::new()));
+        mock::register_contract(POOL, "unstake", |_| Ok(Vec::new()));
+        PENDING_REWARDS.store(0, Ordering::SeqCst);
+        mock::register_contract(POOL, "claim_rewards", |_| {
+            Ok(encode(&PENDING_REWARDS.swap(0, Ordering::SeqCst)))
+        });
+
+        mock::set_call_data(&encode(&InitializeArgs {
+            vault: VAULT.to_string(),
+            asset: TOKEN.to_string(),
+            pool: POOL.to_string(),
+            performance_fee_bps: 1_000,
+            fee_recipient: ADDR_TREASURY.to_string(),
+        }));
+        initialize();
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Option<u64> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn deposit_from_vault(amount: u64) {
+        call(VAULT, deposit, encode(&AmountArgs { amount }));
+    }
+
+    fn token_transfer(to: &str, amount: u64) -> Vec<u8> {
+        #[derive(Serialize)]
+        struct TransferArgs<'a> {
+            to: &'a str,
+            amount: u64,
+        }
+        encode(&TransferArgs { to, amount })
+    }
+
+    #[test]
+    fn vault_deposits_are_staked() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        deposit_from_vault(1_000);
+        assert_eq!(
+            load_position().unwrap(),
+            Position {
+                staked: 1_000,
+                idle: 0
+            }
+        );
+        let calls = mock::take_contract_calls();
+        let methods: Vec<_> = calls.iter().map(|c| c.method.as_str()).collect();
+        assert_eq!(methods, ["approve", "stake"]);
+        assert_eq!(call(VAULT, total_assets, Vec::new()), Some(1_000));
+
+        // Only the vault may deposit.
+        call(ADDR_MALLORY, deposit, encode(&AmountArgs { amount: 10 }));
+        assert_eq!(load_position().unwrap().staked, 1_000);
+    }
+
+    #[test]
+    fn harvest_charges_performance_fee() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_from_vault(1_000);
+        mock::take_contract_calls();
+
+        PENDING_REWARDS.store(200, Ordering::SeqCst);
+        assert_eq!(call(ADDR_OWNER, harvest, Vec::new()), Some(180));
+        assert_eq!(load_position().unwrap().idle, 180);
+        let calls = mock::take_contract_calls();
+        let fee = calls.iter().find(|c| c.method == "transfer").unwrap();
+        assert_eq!(fee.args, token_transfer(ADDR_TREASURY, 20));
+
+        let stats = base::stats().unwrap();
+        assert_eq!(
+            (stats.harvests, stats.total_profit, stats.total_fees),
+            (1, 200, 20)
+        );
+
+        PENDING_REWARDS.store(50, Ordering::SeqCst);
+        assert_eq!(call(ADDR_MALLORY, harvest, Vec::new()), None);
+    }
+
+    #[test]
+    fn compound_restakes_idle_profit() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_from_vault(1_000);
+
+        PENDING_REWARDS.store(100, Ordering::SeqCst);
+        call(ADDR_OWNER, harvest, Vec::new());
+        PENDING_REWARDS.store(100, Ordering::SeqCst);
+        assert_eq!(call(ADDR_OWNER, compound, Vec::new()), Some(180));
+        assert_eq!(
+            load_position().unwrap(),
+            Position {
+                staked: 1_180,
+                idle: 0
+            }
+        );
+        // The harvest and the compound
+        assert_eq!(admin_log::next_sequence().unwrap(), 2);
+    }
+
+    #[test]
+    fn withdraw_uses_idle_before_unstaking() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_from_vault(1_000);
+        PENDING_REWARDS.store(100, Ordering::SeqCst);
+        call(ADDR_OWNER, harvest, Vec::new());
+        mock::take_contract_calls();
+
+        assert_eq!(
+            call(VAULT, withdraw, encode(&AmountArgs { amount: 300 })),
+            Some(300)
+        );
+        let calls = mock::take_contract_calls();
+        let unstaked = calls.iter().find(|c| c.method == "unstake").unwrap();
+        assert_eq!(unstaked.args, encode(&AmountArgs { amount: 210 }));
+        let sent = calls.iter().find(|c| c.method == "transfer").unwrap();
+        assert_eq!(sent.args, token_transfer(VAULT, 300));
+
+        // Requests beyond the position return what is left.
+        assert_eq!(
+            call(VAULT, withdraw, encode(&AmountArgs { amount: 5_000 })),
+            Some(790)
+        );
+    }
+
+    #[test]
+    fn exit_returns_everything_and_shuts_down() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_from_vault(1_000);
+        PENDING_REWARDS.store(50, Ordering::SeqCst);
+
+        assert_eq!(call(ADDR_OWNER, exit_strategy, Vec::new()), None);
+        assert_eq!(call(VAULT, exit_strategy, Vec::new()), Some(1_045));
+        assert_eq!(load_position().unwrap(), Position::default());
+        assert!(base::load_config().unwrap().shutdown);
+        let calls = mock::take_contract_calls();
+        assert!(
+            calls
+                .iter()
+                .any(|c| c.method == "transfer" && c.args == token_transfer(VAULT, 1_045))
+        );
+
+        deposit_from_vault(100);
+        assert_eq!(load_position().unwrap().staked, 0);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "strategy-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
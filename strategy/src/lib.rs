@@ -0,0 +1,37 @@
+//! Yield Strategies
+//!
+//! Strategies hold part of a vault's underlying and put it to work. Every
+//! strategy exports the interface in `vault_contract::strategy`
+//! (`deposit`, `withdraw`, `total_assets`, `exit_strategy`) and usually
+//! `harvest` and `compound`. The [`base`] module carries what they all
+//! share: vault and owner checks, the shutdown flag and performance fee
+//! accounting.
+//!
+//! The `entrypoints` feature exports a sample strategy that stakes the
+//! vault's assets in a staking pool and harvests the pool's rewards.
+//!
+//! ## Embedding
+//! ```toml
+//! strategy-contract = { path = "../strategy", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use strategy_contract::base;
+//!
+//! let config = base::load_config()?;
+//! base::require_vault(&config)?;
+//! let net = base::record_harvest(&config, rewards)?;
+//! ```
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod base;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
@@ -0,0 +1,1238 @@
+//! Fixed-Term Deposits
+//!
+//! Users lock a CRC-20 for one of the terms the owner offers, each with its
+//! own APR. Interest accrues linearly and is paid from a reward pool the
+//! owner funds. A deposit reserves its full-term interest when it is opened,
+//! so matured deposits are always paid in full. Withdrawing early pays the
+//! interest accrued so far minus a penalty on the principal; the penalty
+//! and the unearned interest go back to the reward pool.
+//!
+//! ## Features
+//! - Owner-defined terms (duration, APR) that can be retired
+//! - Interest reserved up front; the owner cannot withdraw reserved rewards
+//! - Configurable early-withdrawal penalty (max 50%)
+//! - Per-account deposit listing
+//! - Decommissioning: after the owner's notice runs out, new deposits stop
+//!   and every deposit can be withdrawn without penalty
+//! - Owner pause for new deposits; withdrawals stay open
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "term_deposit_config";
+const REWARD_POOL_KEY: &str = "reward_pool";
+const NEXT_TERM_KEY: &str = "next_term_id";
+const NEXT_DEPOSIT_KEY: &str = "next_deposit_id";
+const TERMS_PREFIX: &str = "terms";
+const DEPOSITS_PREFIX: &str = "deposits";
+const ACCOUNT_DEPOSITS_PREFIX: &str = "account_deposits";
+const ACCOUNT_DEPOSIT_COUNT_PREFIX: &str = "account_deposit_count";
+const SECONDS_PER_DAY: u64 = 86_400;
+const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
+const MAX_TERM_DURATION: u64 = 5 * SECONDS_PER_YEAR;
+const MAX_APR_BPS: u32 = 10_000;
+const MAX_PENALTY_BPS: u32 = 5_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+const MAX_PAGE_SIZE: u64 = 50;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Contract configuration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TermDepositConfig {
+    pub owner: String,
+    pub token: String,
+    /// Share of the principal forfeited on early withdrawal
+    pub early_penalty_bps: u32,
+}
+
+/// A term offered to depositors
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Term {
+    pub duration: u64,
+    pub apr_bps: u32,
+    pub active: bool,
+}
+
+/// Interest funding
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RewardPool {
+    /// Rewards held by the contract, reserved or not
+    pub balance: u64,
+    /// Full-term interest promised to open deposits
+    pub reserved: u64,
+    /// Principal currently locked
+    pub total_locked: u64,
+}
+
+/// A user's locked deposit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Deposit {
+    pub owner: String,
+    pub term_id: u32,
+    pub principal: u64,
+    pub apr_bps: u32,
+    pub start: u64,
+    pub maturity: u64,
+    /// Interest owed if held to maturity
+    pub max_interest: u64,
+    pub withdrawn: bool,
+}
+
+/// Breakdown of what `withdraw` would pay now
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WithdrawalQuote {
+    pub principal: u64,
+    pub interest: u64,
+    pub penalty: u64,
+    pub payout: u64,
+    pub matured: bool,
+}
+
+/// One page of an account's deposit ids
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DepositPage {
+    pub items: Vec<u64>,
+    pub total: u64,
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    token: String,
+    early_penalty_bps: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AddTermArgs {
+    duration: u64,
+    apr_bps: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetTermActiveArgs {
+    term_id: u32,
+    active: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PenaltyArgs {
+    early_penalty_bps: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AmountArgs {
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepositArgs {
+    term_id: u32,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdArgs {
+    id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TermIdArgs {
+    term_id: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepositsOfArgs {
+    account: String,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<TermDepositConfig> {
+    storage()
+        .get::<TermDepositConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Contract not initialized".to_string()))
+}
+
+fn save_config(config: &TermDepositConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+fn load_owned_config() -> ContractResult<TermDepositConfig> {
+    let config = load_config()?;
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(config)
+}
+
+fn load_reward_pool() -> ContractResult<RewardPool> {
+    Ok(storage()
+        .get::<RewardPool>(REWARD_POOL_KEY)?
+        .unwrap_or_default())
+}
+
+fn save_reward_pool(pool: &RewardPool) -> ContractResult<()> {
+    storage().set(REWARD_POOL_KEY, pool)
+}
+
+fn terms() -> Map<u32, Term> {
+    Map::new(TERMS_PREFIX)
+}
+
+fn deposits() -> Map<u64, Deposit> {
+    Map::new(DEPOSITS_PREFIX)
+}
+
+fn account_deposits() -> Map<(String, u64), u64> {
+    Map::new(ACCOUNT_DEPOSITS_PREFIX)
+}
+
+fn account_deposit_counts() -> Map<String, u64> {
+    Map::new(ACCOUNT_DEPOSIT_COUNT_PREFIX)
+}
+
+fn load_term(term_id: u32) -> ContractResult<Term> {
+    terms()
+        .get(&term_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown term".to_string()))
+}
+
+fn load_deposit(deposit_id: u64) -> ContractResult<Deposit> {
+    deposits()
+        .get(&deposit_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown deposit".to_string()))
+}
+
+fn validate_penalty(early_penalty_bps: u32) -> ContractResult<()> {
+    if early_penalty_bps > MAX_PENALTY_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Penalty cannot exceed {} bps",
+            MAX_PENALTY_BPS
+        )));
+    }
+    Ok(())
+}
+
+fn pull_tokens(token: &str, from: &str, amount: u64) -> ContractResult<()> {
+    let ctx = context();
+    let call = encode_call(&TokenTransferFromArgs {
+        from: from.to_string(),
+        to: ctx.contract_address().to_string(),
+        amount,
+    })?;
+    ctx.call_contract(token, "transfer_from", &call).map(|_| ())
+}
+
+fn send_tokens(token: &str, to: &str, amount: u64) -> ContractResult<()> {
+    let call = encode_call(&TokenTransferArgs {
+        to: to.to_string(),
+        amount,
+    })?;
+    context()
+        .call_contract(token, "transfer", &call)
+        .map(|_| ())
+}
+
+/// Interest on `principal` at `apr_bps` over `duration` seconds, rounded down.
+fn interest_for(principal: u64, apr_bps: u32, duration: u64) -> ContractResult<u64> {
+    let interest = (principal as u128) * (apr_bps as u128) * (duration as u128)
+        / (BPS_DENOMINATOR * SECONDS_PER_YEAR as u128);
+    u64::try_from(interest).map_err(|_| ContractError::Overflow)
+}
+
+/// Once decommissioned, early withdrawals pay no penalty.
+fn quote(
+    config: &TermDepositConfig,
+    deposit: &Deposit,
+    now: u64,
+    decommissioned: bool,
+) -> WithdrawalQuote {
+    if now >= deposit.maturity {
+        return WithdrawalQuote {
+            principal: deposit.principal,
+            interest: deposit.max_interest,
+            penalty: 0,
+            payout: deposit.principal + deposit.max_interest,
+            matured: true,
+        };
+    }
+    let elapsed = now.saturating_sub(deposit.start) as u128;
+    let duration = (deposit.maturity - deposit.start) as u128;
+    // Both values are bounded by `max_interest` and `principal`.
+    let interest = ((deposit.max_interest as u128) * elapsed / duration) as u64;
+    let penalty = if decommissioned {
+        0
+    } else {
+        ((deposit.principal as u128) * (config.early_penalty_bps as u128) / BPS_DENOMINATOR) as u64
+    };
+    WithdrawalQuote {
+        principal: deposit.principal,
+        interest,
+        penalty,
+        payout: deposit.principal + interest - penalty,
+        matured: false,
+    }
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Contract already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.token)?;
+    if !ctx.is_contract(&args.token) {
+        return Err(ContractError::InvalidArgument(
+            "Token must be a contract".to_string(),
+        ));
+    }
+    validate_penalty(args.early_penalty_bps)?;
+
+    save_config(&TermDepositConfig {
+        owner: ctx.sender().to_string(),
+        token: args.token.clone(),
+        early_penalty_bps: args.early_penalty_bps,
+    })?;
+
+    event!("TermDepositInitialized",
+        owner: ctx.sender(),
+        token: args.token,
+        early_penalty_bps: args.early_penalty_bps
+    );
+    Ok(())
+}
+
+fn execute_add_term() -> ContractResult<u32> {
+    load_owned_config()?;
+    decommission::when_active()?;
+    let args: AddTermArgs = read_args()?;
+    if args.duration < SECONDS_PER_DAY || args.duration > MAX_TERM_DURATION {
+        return Err(ContractError::InvalidArgument(
+            "Term must last between 1 day and 5 years".to_string(),
+        ));
+    }
+    if args.apr_bps == 0 || args.apr_bps > MAX_APR_BPS {
+        return Err(ContractError::InvalidArgument(format!(
+            "APR must be 1-{} bps",
+            MAX_APR_BPS
+        )));
+    }
+
+    let mut store = storage();
+    let term_id = store.get::<u32>(NEXT_TERM_KEY)?.unwrap_or(0);
+    store.set(
+        NEXT_TERM_KEY,
+        &term_id.checked_add(1).ok_or(ContractError::Overflow)?,
+    )?;
+    terms().set(
+        &term_id,
+        &Term {
+            duration: args.duration,
+            apr_bps: args.apr_bps,
+            active: true,
+        },
+    )?;
+
+    event!("TermAdded", term_id: term_id, duration: args.duration, apr_bps: args.apr_bps);
+    admin_log::record(
+        "add_term",
+        &term_id.to_string(),
+        "",
+        format!("duration={} apr_bps={}", args.duration, args.apr_bps),
+    )?;
+    Ok(term_id)
+}
+
+fn execute_set_term_active() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::when_active()?;
+    let args: SetTermActiveArgs = read_args()?;
+    let mut term = load_term(args.term_id)?;
+    let previous = term.active;
+    term.active = args.active;
+    terms().set(&args.term_id, &term)?;
+
+    event!("TermUpdated", term_id: args.term_id, active: args.active);
+    admin_log::record(
+        "set_term_active",
+        &args.term_id.to_string(),
+        previous,
+        args.active,
+    )?;
+    Ok(())
+}
+
+fn execute_set_early_penalty() -> ContractResult<()> {
+    let mut config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: PenaltyArgs = read_args()?;
+    validate_penalty(args.early_penalty_bps)?;
+    let previous = config.early_penalty_bps;
+    config.early_penalty_bps = args.early_penalty_bps;
+    save_config(&config)?;
+
+    event!("EarlyPenaltyUpdated", early_penalty_bps: args.early_penalty_bps);
+    admin_log::record(
+        admin_log::SET_PARAMETER,
+        "early_penalty_bps",
+        previous,
+        args.early_penalty_bps,
+    )?;
+    Ok(())
+}
+
+fn execute_fund_rewards() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_owned_config()?;
+    decommission::when_active()?;
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    pull_tokens(&config.token, &config.owner, args.amount)?;
+    let mut pool = load_reward_pool()?;
+    let previous = pool.balance;
+    pool.balance = safe_math::add(previous, args.amount)?;
+    save_reward_pool(&pool)?;
+
+    event!("RewardsFunded", amount: args.amount, balance: pool.balance);
+    admin_log::record("fund_rewards", "reward_pool", previous, pool.balance)?;
+    Ok(())
+}
+
+fn execute_withdraw_rewards() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_owned_config()?;
+    let args: AmountArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    let mut pool = load_reward_pool()?;
+    let unreserved = pool.balance - pool.reserved;
+    if args.amount > unreserved {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: unreserved,
+        });
+    }
+    let previous = pool.balance;
+    pool.balance -= args.amount;
+    save_reward_pool(&pool)?;
+    send_tokens(&config.token, &config.owner, args.amount)?;
+
+    event!("RewardsWithdrawn", amount: args.amount, balance: pool.balance);
+    admin_log::record("withdraw_rewards", "reward_pool", previous, pool.balance)?;
+    Ok(())
+}
+
+fn execute_deposit() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    decommission::when_active()?;
+    pausable::when_not_paused()?;
+    let ctx = context();
+    let depositor = ctx.sender().to_string();
+    let args: DepositArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+
+    let term = load_term(args.term_id)?;
+    if !term.active {
+        return Err(ContractError::InvalidArgument(
+            "Term is no longer offered".to_string(),
+        ));
+    }
+    let max_interest = interest_for(args.amount, term.apr_bps, term.duration)?;
+    let mut pool = load_reward_pool()?;
+    let reserved = safe_math::add(pool.reserved, max_interest)?;
+    if reserved > pool.balance {
+        return Err(ContractError::InsufficientBalance {
+            required: max_interest,
+            available: pool.balance - pool.reserved,
+        });
+    }
+    pull_tokens(&config.token, &depositor, args.amount)?;
+
+    pool.reserved = reserved;
+    pool.total_locked = safe_math::add(pool.total_locked, args.amount)?;
+    save_reward_pool(&pool)?;
+
+    let start = ctx.block_timestamp();
+    let deposit_id = {
+        let mut store = storage();
+        let id = store.get::<u64>(NEXT_DEPOSIT_KEY)?.unwrap_or(0);
+        store.set(NEXT_DEPOSIT_KEY, &safe_math::add(id, 1)?)?;
+        id
+    };
+    let maturity = safe_math::add(start, term.duration)?;
+    deposits().set(
+        &deposit_id,
+        &Deposit {
+            owner: depositor.clone(),
+            term_id: args.term_id,
+            principal: args.amount,
+            apr_bps: term.apr_bps,
+            start,
+            maturity,
+            max_interest,
+            withdrawn: false,
+        },
+    )?;
+    let mut counts = account_deposit_counts();
+    let count = counts.get(&depositor)?.unwrap_or(0);
+    account_deposits().set(&(depositor.clone(), count), &deposit_id)?;
+    counts.set(&depositor, &safe_math::add(count, 1)?)?;
+
+    event!("Deposited",
+        deposit_id: deposit_id,
+        owner: depositor,
+        term_id: args.term_id,
+        amount: args.amount,
+        maturity: maturity
+    );
+    Ok(deposit_id)
+}
+
+fn execute_withdraw() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let ctx = context();
+    let args: IdArgs = read_args()?;
+    let mut deposit = load_deposit(args.id)?;
+    if ctx.sender() != deposit.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    if deposit.withdrawn {
+        return Err(ContractError::InvalidArgument(
+            "Deposit already withdrawn".to_string(),
+        ));
+    }
+
+    let decommissioned = decommission::decommissioned_at()?.is_some();
+    let quote = quote(&config, &deposit, ctx.block_timestamp(), decommissioned);
+    let mut pool = load_reward_pool()?;
+    // Release the reservation; unearned interest and the penalty stay in
+    // the reward pool.
+    pool.reserved -= deposit.max_interest;
+    pool.balance = safe_math::add(pool.balance - quote.interest, quote.penalty)?;
+    pool.total_locked -= deposit.principal;
+    save_reward_pool(&pool)?;
+    deposit.withdrawn = true;
+    deposits().set(&args.id, &deposit)?;
+
+    send_tokens(&config.token, &deposit.owner, quote.payout)?;
+
+    event!("Withdrawn",
+        deposit_id: args.id,
+        owner: deposit.owner,
+        principal: quote.principal,
+        interest: quote.interest,
+        penalty: quote.penalty,
+        matured: quote.matured
+    );
+    Ok(quote.payout)
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    load_owned_config()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    load_owned_config()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_pause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    load_owned_config()?;
+    pausable::unpause()
+}
+
+fn execute_deposits_of() -> ContractResult<DepositPage> {
+    let args: DepositsOfArgs = read_args()?;
+    if args.limit == 0 || args.limit > MAX_PAGE_SIZE {
+        return Err(ContractError::InvalidArgument(format!(
+            "Page size must be 1-{}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let total = account_deposit_counts().get(&args.account)?.unwrap_or(0);
+    let end = args.offset.saturating_add(args.limit).min(total);
+    let index = account_deposits();
+    let mut items = Vec::new();
+    for position in args.offset..end {
+        if let Some(id) = index.get(&(args.account.clone(), position))? {
+            items.push(id);
+        }
+    }
+    Ok(DepositPage {
+        items,
+        total,
+        next_offset: (end < total).then_some(end),
+    })
+}
+
+/// Initialize the contract; the caller becomes its owner
+///
+/// # Arguments
+/// * `token` - CRC-20 deposited and paid as interest
+/// * `early_penalty_bps` - Share of the principal forfeited on early withdrawal (max 5000)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Offer a new term (owner only)
+///
+/// # Arguments
+/// * `duration` - Lock period in seconds (1 day to 5 years)
+/// * `apr_bps` - Annual interest rate in basis points
+///
+/// # Returns
+/// Term id
+#[unsafe(no_mangle)]
+pub extern "C" fn add_term() {
+    let result = execute_add_term().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("add_term failed: {}", err);
+    }
+}
+
+/// Open or close a term to new deposits (owner only)
+///
+/// # Arguments
+/// * `term_id` - Term id
+/// * `active` - Whether new deposits are accepted
+#[unsafe(no_mangle)]
+pub extern "C" fn set_term_active() {
+    if let Err(err) = execute_set_term_active() {
+        log!("set_term_active failed: {}", err);
+    }
+}
+
+/// Change the early-withdrawal penalty (owner only)
+///
+/// # Arguments
+/// * `early_penalty_bps` - Share of the principal forfeited (max 5000)
+#[unsafe(no_mangle)]
+pub extern "C" fn set_early_penalty() {
+    if let Err(err) = execute_set_early_penalty() {
+        log!("set_early_penalty failed: {}", err);
+    }
+}
+
+/// Add tokens to the reward pool (owner only; approve first)
+///
+/// # Arguments
+/// * `amount` - Tokens to add
+#[unsafe(no_mangle)]
+pub extern "C" fn fund_rewards() {
+    if let Err(err) = execute_fund_rewards() {
+        log!("fund_rewards failed: {}", err);
+    }
+}
+
+/// Withdraw unreserved rewards (owner only)
+///
+/// # Arguments
+/// * `amount` - Tokens to withdraw
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw_rewards() {
+    if let Err(err) = execute_withdraw_rewards() {
+        log!("withdraw_rewards failed: {}", err);
+    }
+}
+
+/// Lock tokens for a term (approve first)
+///
+/// # Arguments
+/// * `term_id` - Term to deposit into
+/// * `amount` - Principal
+///
+/// # Returns
+/// Deposit id
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    let result = execute_deposit().and_then(|id| try_respond(&id));
+    if let Err(err) = result {
+        log!("deposit failed: {}", err);
+    }
+}
+
+/// Withdraw a deposit; before maturity the penalty applies unless the
+/// contract is decommissioned
+///
+/// # Arguments
+/// * `id` - Deposit id
+///
+/// # Returns
+/// Tokens paid out
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw() {
+    let result = execute_withdraw().and_then(|payout| try_respond(&payout));
+    if let Err(err) = result {
+        log!("withdraw failed: {}", err);
+    }
+}
+
+/// What withdrawing a deposit would pay right now
+///
+/// # Arguments
+/// * `id` - Deposit id
+///
+/// # Returns
+/// `WithdrawalQuote`
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_withdraw() {
+    let result = read_args::<IdArgs>().and_then(|args| {
+        let deposit = load_deposit(args.id)?;
+        try_respond(&quote(
+            &load_config()?,
+            &deposit,
+            context().block_timestamp(),
+            decommission::decommissioned_at()?.is_some(),
+        ))
+    });
+    if let Err(err) = result {
+        log!("preview_withdraw failed: {}", err);
+    }
+}
+
+/// Pause the contract (owner only)
+///
+/// Deposits fail until `unpause`; withdrawals stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the contract is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Give notice that the contract will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the contract once the notice has run out (owner only)
+///
+/// New deposits, terms and funding stop for good. Deposits can be
+/// withdrawn at any time without penalty, and the owner can still withdraw
+/// unreserved rewards.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the contract is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Get a term
+///
+/// # Arguments
+/// * `term_id` - Term id
+///
+/// # Returns
+/// `Term`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_term() {
+    let result = read_args::<TermIdArgs>()
+        .and_then(|args| load_term(args.term_id))
+        .and_then(|term| try_respond(&term));
+    if let Err(err) = result {
+        log!("get_term failed: {}", err);
+    }
+}
+
+/// Get a deposit
+///
+/// # Arguments
+/// * `id` - Deposit id
+///
+/// # Returns
+/// `Deposit`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_deposit() {
+    let result = read_args::<IdArgs>()
+        .and_then(|args| load_deposit(args.id))
+        .and_then(|deposit| try_respond(&deposit));
+    if let Err(err) = result {
+        log!("get_deposit failed: {}", err);
+    }
+}
+
+/// Get the reward pool totals
+///
+/// # Returns
+/// `RewardPool`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_reward_pool() {
+    if let Err(err) = load_reward_pool().and_then(|pool| try_respond(&pool)) {
+        log!("get_reward_pool failed: {}", err);
+    }
+}
+
+/// List an account's deposit ids, oldest first
+///
+/// # Arguments
+/// * `account` - Depositor
+/// * `offset` - First index to return
+/// * `limit` - Page size (1-50)
+///
+/// # Returns
+/// `DepositPage`
+#[unsafe(no_mangle)]
+pub extern "C" fn deposits_of() {
+    if let Err(err) = execute_deposits_of().and_then(|page| try_respond(&page)) {
+        log!("deposits_of failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const CONTRACT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const START: u64 = 1_736_000_000;
+    /// 73 days at 10% APR: interest is 2% of the principal
+    const TERM_DURATION: u64 = 73 * SECONDS_PER_DAY;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Option<u64> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    /// 20% penalty, one 73-day term at 10% APR, 100 tokens of rewards
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(CONTRACT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+        call(
+            ADDR_OWNER,
+            initialize,
+            encode(&InitializeArgs {
+                token: TOKEN.to_string(),
+                early_penalty_bps: 2_000,
+            }),
+        );
+        mock::set_call_data(&encode(&AddTermArgs {
+            duration: TERM_DURATION,
+            apr_bps: 1_000,
+        }));
+        add_term();
+        call(
+            ADDR_OWNER,
+            fund_rewards,
+            encode(&AmountArgs { amount: 100 }),
+        );
+    }
+
+    fn deposit_as(sender: &str, amount: u64) -> Option<u64> {
+        call(sender, deposit, encode(&DepositArgs { term_id: 0, amount }))
+    }
+
+    fn last_payout() -> u64 {
+        let calls = mock::take_contract_calls();
+        let paid = calls.iter().rfind(|c| c.method == "transfer").unwrap();
+        postcard::from_bytes::<TokenTransferArgs>(&paid.args)
+            .unwrap()
+            .amount
+    }
+
+    #[test]
+    fn matured_deposits_pay_full_interest() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        let id = deposit_as(ADDR_ALICE, 1_000).unwrap();
+        let deposit = load_deposit(id).unwrap();
+        assert_eq!(deposit.max_interest, 20);
+        assert_eq!(deposit.maturity, START + TERM_DURATION);
+        assert_eq!(load_reward_pool().unwrap().reserved, 20);
+
+        mock::set_block_timestamp(START + TERM_DURATION);
+        assert_eq!(
+            call(ADDR_ALICE, withdraw, encode(&IdArgs { id })),
+            Some(1_020)
+        );
+        assert_eq!(last_payout(), 1_020);
+        assert_eq!(
+            load_reward_pool().unwrap(),
+            RewardPool {
+                balance: 80,
+                reserved: 0,
+                total_locked: 0
+            }
+        );
+        assert_eq!(call(ADDR_ALICE, withdraw, encode(&IdArgs { id })), None);
+    }
+
+    #[test]
+    fn early_withdrawal_forfeits_penalty_to_pool() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = deposit_as(ADDR_ALICE, 1_000).unwrap();
+
+        // Halfway: 10 interest accrued, 200 penalty.
+        mock::set_block_timestamp(START + TERM_DURATION / 2);
+        assert_eq!(call(ADDR_BOB, withdraw, encode(&IdArgs { id })), None);
+        assert_eq!(
+            call(ADDR_ALICE, withdraw, encode(&IdArgs { id })),
+            Some(810)
+        );
+        assert_eq!(last_payout(), 810);
+        assert_eq!(
+            load_reward_pool().unwrap(),
+            RewardPool {
+                balance: 290,
+                reserved: 0,
+                total_locked: 0
+            }
+        );
+    }
+
+    #[test]
+    fn pausing_stops_deposits_but_not_withdrawals() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = deposit_as(ADDR_ALICE, 1_000).unwrap();
+
+        call(ADDR_ALICE, pause, Vec::new());
+        assert!(!pausable::paused().unwrap());
+        call(ADDR_OWNER, pause, Vec::new());
+        mock::set_call_data(&[]);
+        paused();
+        assert!(postcard::from_bytes::<bool>(&mock::take_return_data()).unwrap());
+
+        assert_eq!(deposit_as(ADDR_BOB, 1_000), None);
+        mock::set_block_timestamp(START + TERM_DURATION);
+        assert_eq!(
+            call(ADDR_ALICE, withdraw, encode(&IdArgs { id })),
+            Some(1_020)
+        );
+
+        call(ADDR_OWNER, unpause, Vec::new());
+        assert!(deposit_as(ADDR_BOB, 1_000).is_some());
+    }
+
+    #[test]
+    fn deposits_require_reward_coverage() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        // 100 of rewards covers 5000 of principal for this term.
+        assert!(deposit_as(ADDR_ALICE, 4_000).is_some());
+        assert_eq!(deposit_as(ADDR_BOB, 1_050), None);
+        assert!(deposit_as(ADDR_BOB, 1_000).is_some());
+
+        // Reserved rewards cannot be withdrawn by the owner.
+        call(ADDR_OWNER, fund_rewards, encode(&AmountArgs { amount: 50 }));
+        call(
+            ADDR_OWNER,
+            withdraw_rewards,
+            encode(&AmountArgs { amount: 51 }),
+        );
+        assert_eq!(load_reward_pool().unwrap().balance, 150);
+        call(
+            ADDR_OWNER,
+            withdraw_rewards,
+            encode(&AmountArgs { amount: 50 }),
+        );
+        assert_eq!(load_reward_pool().unwrap().balance, 100);
+    }
+
+    #[test]
+    fn retired_terms_reject_new_deposits() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = deposit_as(ADDR_ALICE, 1_000).unwrap();
+
+        let retire = encode(&SetTermActiveArgs {
+            term_id: 0,
+            active: false,
+        });
+        call(ADDR_ALICE, set_term_active, retire.clone());
+        assert!(load_term(0).unwrap().active);
+        call(ADDR_OWNER, set_term_active, retire);
+        assert_eq!(deposit_as(ADDR_ALICE, 1_000), None);
+        // Setup's term and funding, then the owner's retirement
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+
+        // Existing deposits are unaffected.
+        mock::set_block_timestamp(START + TERM_DURATION);
+        assert_eq!(
+            call(ADDR_ALICE, withdraw, encode(&IdArgs { id })),
+            Some(1_020)
+        );
+    }
+
+    #[test]
+    fn decommissioning_stops_deposits_and_waives_the_penalty() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        let id = deposit_as(ADDR_ALICE, 1_000).unwrap();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        // Deposits stay open during the notice.
+        assert!(deposit_as(ADDR_BOB, 1_000).is_some());
+        call(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), None);
+
+        mock::set_block_timestamp(eta);
+        call(ADDR_ALICE, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), None);
+        call(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+        mock::set_call_data(&[]);
+        decommission_status();
+        let status: decommission::DecommissionStatus =
+            postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(status.decommissioned_at, Some(eta));
+
+        assert_eq!(deposit_as(ADDR_BOB, 1_000), None);
+        call(ADDR_OWNER, fund_rewards, encode(&AmountArgs { amount: 50 }));
+        assert_eq!(load_reward_pool().unwrap().balance, 100);
+
+        // Two days into 73: both deposits accrued 0 interest (rounded
+        // down from 0.55) and pay back their principal in full.
+        mock::take_contract_calls();
+        assert_eq!(
+            call(ADDR_ALICE, withdraw, encode(&IdArgs { id })),
+            Some(1_000)
+        );
+        assert_eq!(last_payout(), 1_000);
+        assert_eq!(
+            call(ADDR_BOB, withdraw, encode(&IdArgs { id: id + 1 })),
+            Some(1_000)
+        );
+        assert_eq!(
+            load_reward_pool().unwrap(),
+            RewardPool {
+                balance: 100,
+                reserved: 0,
+                total_locked: 0
+            }
+        );
+        // The owner recovers the rewards nobody is owed.
+        call(
+            ADDR_OWNER,
+            withdraw_rewards,
+            encode(&AmountArgs { amount: 100 }),
+        );
+        assert_eq!(load_reward_pool().unwrap().balance, 0);
+
+        let topics: Vec<String> = mock::take_events().into_iter().map(|e| e.topic).collect();
+        assert_eq!(
+            topics
+                .iter()
+                .rfind(|topic| topic.starts_with("Decommission")),
+            Some(&"Decommissioned".to_string())
+        );
+    }
+
+    #[test]
+    fn deposits_are_listed_per_account() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_as(ADDR_ALICE, 500);
+        deposit_as(ADDR_BOB, 500);
+        deposit_as(ADDR_ALICE, 500);
+
+        mock::set_call_data(&encode(&DepositsOfArgs {
+            account: ADDR_ALICE.to_string(),
+            offset: 0,
+            limit: 1,
+        }));
+        deposits_of();
+        let page: DepositPage = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(page.items, [0]);
+        assert_eq!((page.total, page.next_offset), (2, Some(1)));
+
+        mock::set_call_data(&encode(&DepositsOfArgs {
+            account: ADDR_ALICE.to_string(),
+            offset: 1,
+            limit: 50,
+        }));
+        deposits_of();
+        let page: DepositPage = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        assert_eq!(page.items, [2]);
+        assert_eq!(page.next_offset, None);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "term-deposit-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
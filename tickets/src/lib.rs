@@ -0,0 +1,1026 @@
+//! Event Ticketing
+//!
+//! Issues CRC-721 tickets for events created by organizers. Each event has a
+//! supply cap and priced tiers, a resale policy that can forbid transfers or
+//! route secondary sales through a price-capped listing, and a verifier role
+//! that checks tickets in at the door.
+//!
+//! ## Features
+//! - CRC-721 ownership, approvals, and operator approvals
+//! - Per-event supply with per-tier price and supply
+//! - Resale policies: open, non-resellable, or price-capped listings
+//! - Verifier check-in that marks tickets used
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use events::{ApprovalForAllV1, NftApprovalV1, NftTransferV1, SaleV1};
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::bounded::Name;
+use silica_contract_sdk::event;
+use silica_contract_sdk::prelude::*;
+
+const STATE_KEY: &str = "tickets_state";
+const EVENTS_PREFIX: &str = "events";
+const TICKETS_PREFIX: &str = "tickets";
+const BALANCES_PREFIX: &str = "balances";
+const TOKEN_APPROVALS_PREFIX: &str = "token_approvals";
+const OPERATOR_APPROVALS_PREFIX: &str = "operator_approvals";
+const LISTINGS_PREFIX: &str = "listings";
+const ZERO_ADDRESS: &str = "0x0";
+const MAX_TIERS: usize = 8;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Global counters
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TicketsState {
+    pub next_event_id: u64,
+    pub next_token_id: u64,
+    pub total_supply: u64,
+}
+
+/// Secondary-market rules for an event's tickets
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResalePolicy {
+    /// Standard CRC-721 transfers
+    Open,
+    /// Tickets stay with the original buyer
+    NonResellable,
+    /// Transfers only through listings priced at or below `max_price`
+    PriceCap { max_price: u64 },
+}
+
+/// Priced ticket class within an event
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TicketTier {
+    pub name: String,
+    pub price: u64,
+    pub supply: u64,
+    pub sold: u64,
+}
+
+/// Event configuration and sales progress
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EventInfo {
+    pub name: String,
+    pub organizer: String,
+    pub verifier: String,
+    pub max_supply: u64,
+    pub sold: u64,
+    pub resale: ResalePolicy,
+    pub tiers: Vec<TicketTier>,
+}
+
+/// Ticket token data
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Ticket {
+    pub event_id: u64,
+    pub tier: u32,
+    pub owner: String,
+    pub used: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TierArgs {
+    name: Name,
+    price: u64,
+    supply: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateEventArgs {
+    name: Name,
+    verifier: String,
+    max_supply: u64,
+    resale: ResalePolicy,
+    tiers: Vec<TierArgs>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetVerifierArgs {
+    event_id: u64,
+    verifier: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BuyTicketArgs {
+    event_id: u64,
+    tier: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenIdArgs {
+    token_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EventIdArgs {
+    event_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferFromArgs {
+    from: String,
+    to: String,
+    token_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    to: String,
+    token_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetApprovalForAllArgs {
+    operator: String,
+    approved: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ListTicketArgs {
+    token_id: u64,
+    price: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BalanceOfArgs {
+    owner: String,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn load_state() -> ContractResult<TicketsState> {
+    Ok(storage()
+        .get::<TicketsState>(STATE_KEY)?
+        .unwrap_or_default())
+}
+
+fn save_state(state: &TicketsState) -> ContractResult<()> {
+    let mut store = storage();
+    store.set(STATE_KEY, state)
+}
+
+fn events() -> Map<u64, EventInfo> {
+    Map::new(EVENTS_PREFIX)
+}
+
+fn tickets() -> Map<u64, Ticket> {
+    Map::new(TICKETS_PREFIX)
+}
+
+fn balances() -> Map<String, u64> {
+    Map::new(BALANCES_PREFIX)
+}
+
+fn token_approvals() -> Map<u64, String> {
+    Map::new(TOKEN_APPROVALS_PREFIX)
+}
+
+fn operator_approvals() -> Map<(String, String), bool> {
+    Map::new(OPERATOR_APPROVALS_PREFIX)
+}
+
+fn listings() -> Map<u64, u64> {
+    Map::new(LISTINGS_PREFIX)
+}
+
+fn load_event(event_id: u64) -> ContractResult<EventInfo> {
+    events()
+        .get(&event_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown event".to_string()))
+}
+
+fn load_ticket(token_id: u64) -> ContractResult<Ticket> {
+    tickets()
+        .get(&token_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown ticket".to_string()))
+}
+
+fn is_approved_or_owner(ticket: &Ticket, token_id: u64, caller: &str) -> ContractResult<bool> {
+    if ticket.owner == caller {
+        return Ok(true);
+    }
+    if token_approvals().get(&token_id)?.as_deref() == Some(caller) {
+        return Ok(true);
+    }
+    Ok(operator_approvals()
+        .get(&(ticket.owner.clone(), caller.to_string()))?
+        .unwrap_or(false))
+}
+
+fn adjust_balance(owner: &str, increase: bool) -> ContractResult<()> {
+    let mut balances = balances();
+    let key = owner.to_string();
+    let current = balances.get(&key)?.unwrap_or(0);
+    let updated = if increase {
+        safe_math::add(current, 1)?
+    } else {
+        safe_math::sub(current, 1)?
+    };
+    balances.set(&key, &updated)
+}
+
+/// Move a ticket between accounts, clearing its approval and any listing.
+fn move_ticket(token_id: u64, mut ticket: Ticket, to: &str) -> ContractResult<()> {
+    validation::validate_address(to)?;
+    if ticket.used {
+        return Err(ContractError::InvalidArgument(
+            "Ticket already used".to_string(),
+        ));
+    }
+
+    let from = core::mem::replace(&mut ticket.owner, to.to_string());
+    adjust_balance(&from, false)?;
+    adjust_balance(to, true)?;
+    tickets().set(&token_id, &ticket)?;
+    token_approvals().remove(&token_id)?;
+    listings().remove(&token_id)?;
+
+    events::emit(&NftTransferV1 {
+        from: from.to_string(),
+        to: to.to_string(),
+        token_id,
+    });
+    Ok(())
+}
+
+fn execute_create_event() -> ContractResult<u64> {
+    let organizer = context().sender().to_string();
+    let args: CreateEventArgs = read_args()?;
+    validation::validate_address(&organizer)?;
+    validation::validate_address(&args.verifier)?;
+    validation::validate_non_empty(&args.name, "name")?;
+    validation::validate_positive_amount(args.max_supply)?;
+    if args.tiers.is_empty() || args.tiers.len() > MAX_TIERS {
+        return Err(ContractError::InvalidArgument(format!(
+            "Events need 1-{} tiers",
+            MAX_TIERS
+        )));
+    }
+    if let ResalePolicy::PriceCap { max_price } = args.resale {
+        validation::validate_positive_amount(max_price)?;
+    }
+
+    let mut tiers = Vec::with_capacity(args.tiers.len());
+    for tier in args.tiers {
+        validation::validate_non_empty(&tier.name, "tier name")?;
+        validation::validate_positive_amount(tier.supply)?;
+        tiers.push(TicketTier {
+            name: tier.name.into(),
+            price: tier.price,
+            supply: tier.supply,
+            sold: 0,
+        });
+    }
+
+    let mut state = load_state()?;
+    let event_id = state.next_event_id;
+    state.next_event_id = safe_math::add(event_id, 1)?;
+    save_state(&state)?;
+
+    events().set(
+        &event_id,
+        &EventInfo {
+            name: args.name.to_string(),
+            organizer: organizer.clone(),
+            verifier: args.verifier.clone(),
+            max_supply: args.max_supply,
+            sold: 0,
+            resale: args.resale,
+            tiers,
+        },
+    )?;
+
+    event!("EventCreated",
+        event_id: event_id,
+        name: args.name,
+        organizer: organizer,
+        verifier: args.verifier,
+        max_supply: args.max_supply
+    );
+    try_respond(&event_id)?;
+    Ok(event_id)
+}
+
+fn execute_set_verifier() -> ContractResult<()> {
+    let args: SetVerifierArgs = read_args()?;
+    let mut info = load_event(args.event_id)?;
+    if context().sender() != info.organizer {
+        return Err(ContractError::Unauthorized);
+    }
+    validation::validate_address(&args.verifier)?;
+
+    info.verifier = args.verifier.clone();
+    events().set(&args.event_id, &info)?;
+
+    event!("VerifierUpdated", event_id: args.event_id, verifier: args.verifier);
+    Ok(())
+}
+
+fn execute_buy_ticket() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let buyer = ctx.sender().to_string();
+    let args: BuyTicketArgs = read_args()?;
+    validation::validate_address(&buyer)?;
+
+    let mut info = load_event(args.event_id)?;
+    if info.sold >= info.max_supply {
+        return Err(ContractError::InvalidArgument("Event sold out".to_string()));
+    }
+    let tier = info
+        .tiers
+        .get_mut(args.tier as usize)
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown tier".to_string()))?;
+    if tier.sold >= tier.supply {
+        return Err(ContractError::InvalidArgument("Tier sold out".to_string()));
+    }
+    if ctx.value() != tier.price {
+        return Err(ContractError::InvalidArgument(
+            "Attached value must equal tier price".to_string(),
+        ));
+    }
+    let price = tier.price;
+    tier.sold += 1;
+    info.sold += 1;
+    events().set(&args.event_id, &info)?;
+
+    let mut state = load_state()?;
+    let token_id = state.next_token_id;
+    state.next_token_id = safe_math::add(token_id, 1)?;
+    state.total_supply = safe_math::add(state.total_supply, 1)?;
+    save_state(&state)?;
+
+    tickets().set(
+        &token_id,
+        &Ticket {
+            event_id: args.event_id,
+            tier: args.tier,
+            owner: buyer.clone(),
+            used: false,
+        },
+    )?;
+    adjust_balance(&buyer, true)?;
+
+    if price > 0 {
+        ctx.transfer_tokens(&info.organizer, price)?;
+    }
+
+    events::emit(&NftTransferV1 {
+        from: ZERO_ADDRESS.to_string(),
+        to: buyer.clone(),
+        token_id,
+    });
+    event!("TicketPurchased",
+        event_id: args.event_id,
+        tier: args.tier,
+        token_id: token_id,
+        price: price
+    );
+    try_respond(&token_id)?;
+    Ok(token_id)
+}
+
+fn execute_check_in() -> ContractResult<()> {
+    let args: TokenIdArgs = read_args()?;
+    let mut ticket = load_ticket(args.token_id)?;
+    let info = load_event(ticket.event_id)?;
+    if context().sender() != info.verifier {
+        return Err(ContractError::Unauthorized);
+    }
+    if ticket.used {
+        return Err(ContractError::InvalidArgument(
+            "Ticket already used".to_string(),
+        ));
+    }
+
+    ticket.used = true;
+    tickets().set(&args.token_id, &ticket)?;
+    listings().remove(&args.token_id)?;
+
+    event!("CheckedIn",
+        event_id: ticket.event_id,
+        token_id: args.token_id,
+        holder: ticket.owner
+    );
+    Ok(())
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let caller = context().sender().to_string();
+    let args: TransferFromArgs = read_args()?;
+    let ticket = load_ticket(args.token_id)?;
+
+    if ticket.owner != args.from {
+        return Err(ContractError::InvalidArgument(
+            "From address is not the ticket owner".to_string(),
+        ));
+    }
+    if !is_approved_or_owner(&ticket, args.token_id, &caller)? {
+        return Err(ContractError::Unauthorized);
+    }
+    match load_event(ticket.event_id)?.resale {
+        ResalePolicy::Open => {}
+        ResalePolicy::NonResellable => {
+            return Err(ContractError::InvalidArgument(
+                "Tickets for this event are non-resellable".to_string(),
+            ));
+        }
+        ResalePolicy::PriceCap { .. } => {
+            return Err(ContractError::InvalidArgument(
+                "Tickets for this event move only through capped listings".to_string(),
+            ));
+        }
+    }
+
+    move_ticket(args.token_id, ticket, &args.to)
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let caller = context().sender().to_string();
+    let args: ApproveArgs = read_args()?;
+    let ticket = load_ticket(args.token_id)?;
+    let is_operator = operator_approvals()
+        .get(&(ticket.owner.clone(), caller.clone()))?
+        .unwrap_or(false);
+    if ticket.owner != caller && !is_operator {
+        return Err(ContractError::Unauthorized);
+    }
+    validation::validate_address(&args.to)?;
+
+    token_approvals().set(&args.token_id, &args.to)?;
+    events::emit(&NftApprovalV1 {
+        owner: ticket.owner,
+        approved: args.to,
+        token_id: args.token_id,
+    });
+    Ok(())
+}
+
+fn execute_set_approval_for_all() -> ContractResult<()> {
+    let owner = context().sender().to_string();
+    let args: SetApprovalForAllArgs = read_args()?;
+    validation::validate_address(&args.operator)?;
+    if args.operator == owner {
+        return Err(ContractError::InvalidArgument(
+            "Cannot set yourself as operator".to_string(),
+        ));
+    }
+
+    operator_approvals().set(&(owner.clone(), args.operator.clone()), &args.approved)?;
+    events::emit(&ApprovalForAllV1 {
+        owner: owner.to_string(),
+        operator: args.operator,
+        approved: args.approved,
+    });
+    Ok(())
+}
+
+fn execute_list_ticket() -> ContractResult<()> {
+    let caller = context().sender().to_string();
+    let args: ListTicketArgs = read_args()?;
+    let ticket = load_ticket(args.token_id)?;
+    if ticket.owner != caller {
+        return Err(ContractError::Unauthorized);
+    }
+    if ticket.used {
+        return Err(ContractError::InvalidArgument(
+            "Ticket already used".to_string(),
+        ));
+    }
+    match load_event(ticket.event_id)?.resale {
+        ResalePolicy::PriceCap { max_price } if args.price <= max_price => {}
+        ResalePolicy::PriceCap { .. } => {
+            return Err(ContractError::InvalidArgument(
+                "Listing price exceeds resale cap".to_string(),
+            ));
+        }
+        _ => {
+            return Err(ContractError::InvalidArgument(
+                "Event does not use capped listings".to_string(),
+            ));
+        }
+    }
+
+    listings().set(&args.token_id, &args.price)?;
+    event!("TicketListed", token_id: args.token_id, seller: caller, price: args.price);
+    Ok(())
+}
+
+fn execute_cancel_listing() -> ContractResult<()> {
+    let args: TokenIdArgs = read_args()?;
+    let ticket = load_ticket(args.token_id)?;
+    if context().sender() != ticket.owner {
+        return Err(ContractError::Unauthorized);
+    }
+
+    listings().remove(&args.token_id)?;
+    event!("ListingCancelled", token_id: args.token_id);
+    Ok(())
+}
+
+fn execute_buy_listed() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let buyer = ctx.sender().to_string();
+    let args: TokenIdArgs = read_args()?;
+    let price = listings()
+        .get(&args.token_id)?
+        .ok_or_else(|| ContractError::InvalidArgument("Ticket not listed".to_string()))?;
+    if ctx.value() != price {
+        return Err(ContractError::InvalidArgument(
+            "Attached value must equal listing price".to_string(),
+        ));
+    }
+
+    let ticket = load_ticket(args.token_id)?;
+    let seller = ticket.owner.clone();
+    if seller == buyer {
+        return Err(ContractError::InvalidArgument(
+            "Seller cannot buy own listing".to_string(),
+        ));
+    }
+    move_ticket(args.token_id, ticket, &buyer)?;
+
+    if price > 0 {
+        ctx.transfer_tokens(&seller, price)?;
+    }
+
+    events::emit(&SaleV1 {
+        seller,
+        buyer,
+        token_id: args.token_id,
+        price,
+    });
+    Ok(())
+}
+
+fn execute_owner_of() -> ContractResult<()> {
+    let args: TokenIdArgs = read_args()?;
+    try_respond(&load_ticket(args.token_id)?.owner)
+}
+
+fn execute_balance_of() -> ContractResult<u64> {
+    let args: BalanceOfArgs = read_args()?;
+    let balance = balances().get(&args.owner)?.unwrap_or(0);
+    try_respond(&balance)?;
+    Ok(balance)
+}
+
+fn execute_total_supply() -> ContractResult<u64> {
+    let supply = load_state()?.total_supply;
+    try_respond(&supply)?;
+    Ok(supply)
+}
+
+fn execute_get_ticket() -> ContractResult<()> {
+    let args: TokenIdArgs = read_args()?;
+    try_respond(&load_ticket(args.token_id)?)
+}
+
+fn execute_get_event() -> ContractResult<()> {
+    let args: EventIdArgs = read_args()?;
+    try_respond(&load_event(args.event_id)?)
+}
+
+/// Create an event; the caller becomes its organizer
+///
+/// # Arguments
+/// * `name` - Event name, at most 64 bytes
+/// * `verifier` - Address allowed to check tickets in
+/// * `max_supply` - Maximum tickets across all tiers
+/// * `resale` - `Open`, `NonResellable`, or `PriceCap { max_price }`
+/// * `tiers` - Up to 8 `{ name, price, supply }` tiers; tier names are at most 64 bytes
+///
+/// # Returns
+/// New event id
+#[unsafe(no_mangle)]
+pub extern "C" fn create_event() {
+    if let Err(err) = execute_create_event() {
+        log!("create_event failed: {}", err);
+    }
+}
+
+/// Replace an event's verifier (organizer only)
+///
+/// # Arguments
+/// * `event_id` - Event to update
+/// * `verifier` - New verifier address
+#[unsafe(no_mangle)]
+pub extern "C" fn set_verifier() {
+    if let Err(err) = execute_set_verifier() {
+        log!("set_verifier failed: {}", err);
+    }
+}
+
+/// Buy a ticket with the tier price attached; proceeds go to the organizer
+///
+/// # Arguments
+/// * `event_id` - Event to buy for
+/// * `tier` - Tier index
+///
+/// # Returns
+/// Minted ticket token id
+#[unsafe(no_mangle)]
+pub extern "C" fn buy_ticket() {
+    if let Err(err) = execute_buy_ticket() {
+        log!("buy_ticket failed: {}", err);
+    }
+}
+
+/// Mark a ticket used (event verifier only)
+///
+/// # Arguments
+/// * `token_id` - Ticket to check in
+#[unsafe(no_mangle)]
+pub extern "C" fn check_in() {
+    if let Err(err) = execute_check_in() {
+        log!("check_in failed: {}", err);
+    }
+}
+
+/// Transfer a ticket (events with the `Open` resale policy only)
+///
+/// # Arguments
+/// * `from` - Current owner
+/// * `to` - Recipient
+/// * `token_id` - Ticket to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("transfer_from failed: {}", err);
+    }
+}
+
+/// Approve an address to transfer a ticket
+///
+/// # Arguments
+/// * `to` - Address to approve
+/// * `token_id` - Ticket to approve
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("approve failed: {}", err);
+    }
+}
+
+/// Approve or revoke an operator for all of the caller's tickets
+///
+/// # Arguments
+/// * `operator` - Operator address
+/// * `approved` - True to approve, false to revoke
+#[unsafe(no_mangle)]
+pub extern "C" fn set_approval_for_all() {
+    if let Err(err) = execute_set_approval_for_all() {
+        log!("set_approval_for_all failed: {}", err);
+    }
+}
+
+/// List a ticket for resale at or below the event's price cap
+///
+/// # Arguments
+/// * `token_id` - Ticket to list
+/// * `price` - Asking price in native coin
+#[unsafe(no_mangle)]
+pub extern "C" fn list_ticket() {
+    if let Err(err) = execute_list_ticket() {
+        log!("list_ticket failed: {}", err);
+    }
+}
+
+/// Withdraw a resale listing (ticket owner only)
+///
+/// # Arguments
+/// * `token_id` - Listed ticket
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_listing() {
+    if let Err(err) = execute_cancel_listing() {
+        log!("cancel_listing failed: {}", err);
+    }
+}
+
+/// Buy a listed ticket with the listing price attached
+///
+/// # Arguments
+/// * `token_id` - Listed ticket
+#[unsafe(no_mangle)]
+pub extern "C" fn buy_listed() {
+    if let Err(err) = execute_buy_listed() {
+        log!("buy_listed failed: {}", err);
+    }
+}
+
+/// Get the owner of a ticket
+///
+/// # Arguments
+/// * `token_id` - Ticket to query
+#[unsafe(no_mangle)]
+pub extern "C" fn owner_of() {
+    if let Err(err) = execute_owner_of() {
+        log!("owner_of failed: {}", err);
+    }
+}
+
+/// Get the number of tickets held by an address
+///
+/// # Arguments
+/// * `owner` - Address to query
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() -> u64 {
+    match execute_balance_of() {
+        Ok(value) => value,
+        Err(err) => {
+            log!("balance_of failed: {}", err);
+            0
+        }
+    }
+}
+
+/// Get the number of tickets issued across all events
+#[unsafe(no_mangle)]
+pub extern "C" fn total_supply() -> u64 {
+    match execute_total_supply() {
+        Ok(value) => value,
+        Err(err) => {
+            log!("total_supply failed: {}", err);
+            0
+        }
+    }
+}
+
+/// Get ticket data
+///
+/// # Arguments
+/// * `token_id` - Ticket to query
+///
+/// # Returns
+/// `Ticket`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_ticket() {
+    if let Err(err) = execute_get_ticket() {
+        log!("get_ticket failed: {}", err);
+    }
+}
+
+/// Get event configuration and sales
+///
+/// # Arguments
+/// * `event_id` - Event to query
+///
+/// # Returns
+/// `EventInfo`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_event() {
+    if let Err(err) = execute_get_event() {
+        log!("get_event failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_ORGANIZER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_VERIFIER: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000d04";
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup(resale: ResalePolicy, max_supply: u64) -> u64 {
+        mock::reset();
+        mock::set_contract_address("0x0000000000000000000000000000000000000f00");
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::set_sender(ADDR_ORGANIZER);
+        mock::set_call_data(&encode(&CreateEventArgs {
+            name: Name::new("Chert Summit").unwrap(),
+            verifier: ADDR_VERIFIER.to_string(),
+            max_supply,
+            resale,
+            tiers: vec![
+                TierArgs {
+                    name: Name::new("General").unwrap(),
+                    price: 100,
+                    supply: 10,
+                },
+                TierArgs {
+                    name: Name::new("VIP").unwrap(),
+                    price: 500,
+                    supply: 1,
+                },
+            ],
+        }));
+        create_event();
+        postcard::from_bytes(&mock::take_return_data()).expect("event id")
+    }
+
+    fn buy_as(buyer: &str, event_id: u64, tier: u32, value: u64) -> Option<u64> {
+        mock::set_sender(buyer);
+        mock::set_value(value);
+        mock::set_call_data(&encode(&BuyTicketArgs { event_id, tier }));
+        buy_ticket();
+        mock::set_value(0);
+        let data = mock::take_return_data();
+        (!data.is_empty()).then(|| postcard::from_bytes(&data).expect("token id"))
+    }
+
+    fn transfer_as(sender: &str, from: &str, to: &str, token_id: u64) {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(&TransferFromArgs {
+            from: from.to_string(),
+            to: to.to_string(),
+            token_id,
+        }));
+        transfer_from();
+    }
+
+    fn owner(token_id: u64) -> String {
+        load_ticket(token_id).expect("ticket").owner
+    }
+
+    #[test]
+    fn buying_mints_ticket_and_tracks_tier_supply() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let event_id = setup(ResalePolicy::Open, 5);
+
+        assert_eq!(buy_as(ADDR_ALICE, event_id, 0, 99), None);
+        let token_id = buy_as(ADDR_ALICE, event_id, 1, 500).expect("vip ticket");
+        assert_eq!(buy_as(ADDR_BOB, event_id, 1, 500), None, "VIP sold out");
+
+        assert_eq!(owner(token_id), ADDR_ALICE);
+        assert_eq!(balances().get(&ADDR_ALICE.to_string()).unwrap(), Some(1));
+        let info = load_event(event_id).unwrap();
+        assert_eq!(info.sold, 1);
+        assert_eq!(info.tiers[1].sold, 1);
+        assert_eq!(load_state().unwrap().total_supply, 1);
+    }
+
+    #[test]
+    fn event_supply_caps_all_tiers() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let event_id = setup(ResalePolicy::Open, 2);
+
+        assert!(buy_as(ADDR_ALICE, event_id, 0, 100).is_some());
+        assert!(buy_as(ADDR_ALICE, event_id, 1, 500).is_some());
+        assert_eq!(buy_as(ADDR_BOB, event_id, 0, 100), None);
+        assert_eq!(load_event(event_id).unwrap().sold, 2);
+    }
+
+    #[test]
+    fn open_tickets_transfer_via_approval() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let event_id = setup(ResalePolicy::Open, 5);
+        let token_id = buy_as(ADDR_ALICE, event_id, 0, 100).unwrap();
+
+        transfer_as(ADDR_BOB, ADDR_ALICE, ADDR_BOB, token_id);
+        assert_eq!(owner(token_id), ADDR_ALICE);
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&encode(&ApproveArgs {
+            to: ADDR_BOB.to_string(),
+            token_id,
+        }));
+        approve();
+        transfer_as(ADDR_BOB, ADDR_ALICE, ADDR_BOB, token_id);
+        assert_eq!(owner(token_id), ADDR_BOB);
+        assert!(token_approvals().get(&token_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn non_resellable_tickets_stay_with_buyer() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let event_id = setup(ResalePolicy::NonResellable, 5);
+        let token_id = buy_as(ADDR_ALICE, event_id, 0, 100).unwrap();
+
+        transfer_as(ADDR_ALICE, ADDR_ALICE, ADDR_BOB, token_id);
+        mock::set_call_data(&encode(&ListTicketArgs { token_id, price: 1 }));
+        list_ticket();
+
+        assert_eq!(owner(token_id), ADDR_ALICE);
+        assert!(listings().get(&token_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn price_capped_resale_goes_through_listings() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let event_id = setup(ResalePolicy::PriceCap { max_price: 150 }, 5);
+        let token_id = buy_as(ADDR_ALICE, event_id, 0, 100).unwrap();
+
+        transfer_as(ADDR_ALICE, ADDR_ALICE, ADDR_BOB, token_id);
+        assert_eq!(owner(token_id), ADDR_ALICE);
+
+        mock::set_sender(ADDR_ALICE);
+        mock::set_call_data(&encode(&ListTicketArgs {
+            token_id,
+            price: 151,
+        }));
+        list_ticket();
+        assert!(listings().get(&token_id).unwrap().is_none());
+
+        mock::set_call_data(&encode(&ListTicketArgs {
+            token_id,
+            price: 150,
+        }));
+        list_ticket();
+
+        mock::set_sender(ADDR_BOB);
+        mock::set_value(150);
+        mock::set_call_data(&encode(&TokenIdArgs { token_id }));
+        buy_listed();
+        mock::set_value(0);
+
+        assert_eq!(owner(token_id), ADDR_BOB);
+        assert!(listings().get(&token_id).unwrap().is_none());
+        assert_eq!(balances().get(&ADDR_ALICE.to_string()).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn verifier_checks_in_and_used_tickets_are_frozen() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        let event_id = setup(ResalePolicy::Open, 5);
+        let token_id = buy_as(ADDR_ALICE, event_id, 0, 100).unwrap();
+        let call = encode(&TokenIdArgs { token_id });
+
+        mock::set_sender(ADDR_ORGANIZER);
+        mock::set_call_data(&call);
+        check_in();
+        assert!(!load_ticket(token_id).unwrap().used);
+
+        mock::set_sender(ADDR_VERIFIER);
+        check_in();
+        assert!(load_ticket(token_id).unwrap().used);
+
+        transfer_as(ADDR_ALICE, ADDR_ALICE, ADDR_BOB, token_id);
+        assert_eq!(owner(token_id), ADDR_ALICE);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup(ResalePolicy::Open, 5);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "tickets-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
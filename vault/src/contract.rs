@@ -0,0 +1,1499 @@
+//! Vault entrypoints
+
+use crate::strategy;
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::admin_log;
+use silica_contract_sdk::bounded::{Name, Symbol};
+use silica_contract_sdk::circuit_breaker::{self, Trip};
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::pausable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::security::safe_math::Rounding;
+use silica_contract_sdk::time::Duration;
+
+const CONFIG_KEY: &str = "vault_config";
+const STATE_KEY: &str = "vault_state";
+const SHARES_PREFIX: &str = "shares";
+const SHARE_ALLOWANCES_PREFIX: &str = "share_allowances";
+const ZERO_ADDRESS: &str = "0x0";
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Vault configuration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VaultConfig {
+    pub owner: String,
+    pub asset: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub strategy: Option<String>,
+}
+
+/// Vault accounting; assets in the strategy are queried live
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VaultState {
+    pub idle: u64,
+    pub total_supply: u64,
+}
+
+/// Summary returned by `get_vault_info`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultInfo {
+    pub asset: String,
+    pub owner: String,
+    pub strategy: Option<String>,
+    pub idle: u64,
+    pub total_assets: u64,
+    pub total_supply: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InitializeArgs {
+    asset: String,
+    name: Name,
+    symbol: Symbol,
+    decimals: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepositArgs {
+    assets: u64,
+    receiver: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MintArgs {
+    shares: u64,
+    receiver: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WithdrawArgs {
+    assets: u64,
+    receiver: String,
+    owner: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RedeemArgs {
+    shares: u64,
+    receiver: String,
+    owner: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AssetsArgs {
+    assets: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SharesArgs {
+    shares: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountArgs {
+    account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutflowLimitArgs {
+    max_outflow: u64,
+    window: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SetStrategyArgs {
+    strategy: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ApproveArgs {
+    spender: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn load_config() -> ContractResult<VaultConfig> {
+    storage()
+        .get::<VaultConfig>(CONFIG_KEY)?
+        .ok_or_else(|| ContractError::InvalidArgument("Vault not initialized".to_string()))
+}
+
+fn save_config(config: &VaultConfig) -> ContractResult<()> {
+    storage().set(CONFIG_KEY, config)
+}
+
+fn load_state() -> ContractResult<VaultState> {
+    Ok(storage().get::<VaultState>(STATE_KEY)?.unwrap_or_default())
+}
+
+fn save_state(state: &VaultState) -> ContractResult<()> {
+    storage().set(STATE_KEY, state)
+}
+
+fn shares() -> Map<String, u64> {
+    Map::new(SHARES_PREFIX)
+}
+
+fn share_allowances() -> Map<(String, String), u64> {
+    Map::new(SHARE_ALLOWANCES_PREFIX)
+}
+
+fn share_balance(account: &str) -> ContractResult<u64> {
+    Ok(shares().get(&account.to_string())?.unwrap_or(0))
+}
+
+fn set_share_balance(account: &str, amount: u64) -> ContractResult<()> {
+    shares().set(&account.to_string(), &amount)
+}
+
+fn require_owner(config: &VaultConfig) -> ContractResult<()> {
+    if context().sender() != config.owner {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Idle assets plus whatever the strategy reports.
+fn vault_assets(config: &VaultConfig, state: &VaultState) -> ContractResult<u64> {
+    match &config.strategy {
+        Some(strategy) => safe_math::add(state.idle, strategy::total_assets(strategy)?),
+        None => Ok(state.idle),
+    }
+}
+
+// One virtual share and one virtual asset keep the first depositor from
+// inflating the share price by donating assets to an empty vault.
+fn to_shares(
+    assets: u64,
+    total_assets: u64,
+    supply: u64,
+    rounding: Rounding,
+) -> ContractResult<u64> {
+    safe_math::mul_div(
+        assets,
+        safe_math::add(supply, 1)?,
+        safe_math::add(total_assets, 1)?,
+        rounding,
+    )
+}
+
+fn to_assets(
+    shares: u64,
+    total_assets: u64,
+    supply: u64,
+    rounding: Rounding,
+) -> ContractResult<u64> {
+    safe_math::mul_div(
+        shares,
+        safe_math::add(total_assets, 1)?,
+        safe_math::add(supply, 1)?,
+        rounding,
+    )
+}
+
+fn move_shares(from: &str, to: &str, amount: u64) -> ContractResult<()> {
+    validation::validate_address(to)?;
+    let available = share_balance(from)?;
+    let remaining = available
+        .checked_sub(amount)
+        .ok_or(ContractError::InsufficientBalance {
+            required: amount,
+            available,
+        })?;
+    set_share_balance(from, remaining)?;
+    set_share_balance(to, safe_math::add(share_balance(to)?, amount)?)?;
+
+    event!("Transfer", from: from, to: to, amount: amount);
+    Ok(())
+}
+
+/// Allowance `spender` would have left after spending `amount` of `owner`'s
+fn allowance_after(owner: &str, spender: &str, amount: u64) -> ContractResult<u64> {
+    let allowed = share_allowances()
+        .get(&(owner.to_string(), spender.to_string()))?
+        .unwrap_or(0);
+    allowed
+        .checked_sub(amount)
+        .ok_or(ContractError::InsufficientBalance {
+            required: amount,
+            available: allowed,
+        })
+}
+
+fn spend_allowance(owner: &str, spender: &str, amount: u64) -> ContractResult<()> {
+    let left = allowance_after(owner, spender, amount)?;
+    share_allowances().set(&(owner.to_string(), spender.to_string()), &left)
+}
+
+fn execute_initialize() -> ContractResult<()> {
+    if storage().has(CONFIG_KEY) {
+        return Err(ContractError::InvalidArgument(
+            "Vault already initialized".to_string(),
+        ));
+    }
+    let ctx = context();
+    let args: InitializeArgs = read_args()?;
+    validation::validate_address(&args.asset)?;
+    if !ctx.is_contract(&args.asset) {
+        return Err(ContractError::InvalidArgument(
+            "Asset must be a token contract".to_string(),
+        ));
+    }
+    validation::validate_non_empty(&args.name, "name")?;
+    validation::validate_non_empty(&args.symbol, "symbol")?;
+
+    save_config(&VaultConfig {
+        owner: ctx.sender().to_string(),
+        asset: args.asset.clone(),
+        name: args.name.into(),
+        symbol: args.symbol.into(),
+        decimals: args.decimals,
+        strategy: None,
+    })?;
+    save_state(&VaultState::default())?;
+
+    event!("VaultInitialized", owner: ctx.sender(), asset: args.asset);
+    Ok(())
+}
+
+/// Pull `assets` from the sender and mint `shares` to `receiver`.
+fn settle_deposit(
+    config: &VaultConfig,
+    mut state: VaultState,
+    receiver: &str,
+    assets: u64,
+    shares: u64,
+) -> ContractResult<()> {
+    validation::validate_address(receiver)?;
+    if assets == 0 || shares == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Deposit too small to mint shares".to_string(),
+        ));
+    }
+    let ctx = context();
+    let sender = ctx.sender().to_string();
+    let call = encode_call(&TransferFromArgs {
+        from: sender.clone(),
+        to: ctx.contract_address().to_string(),
+        amount: assets,
+    })?;
+    ctx.call_contract(&config.asset, "transfer_from", &call)?;
+
+    state.idle = safe_math::add(state.idle, assets)?;
+    state.total_supply = safe_math::add(state.total_supply, shares)?;
+    save_state(&state)?;
+    set_share_balance(receiver, safe_math::add(share_balance(receiver)?, shares)?)?;
+
+    event!("Transfer", from: ZERO_ADDRESS, to: receiver, amount: shares);
+    event!("Deposit", sender: sender, owner: receiver, assets: assets, shares: shares);
+    Ok(())
+}
+
+/// Burn `shares` from `owner` and send `assets` to `receiver`, recalling
+/// any shortfall from the strategy.
+fn settle_withdraw(
+    config: &VaultConfig,
+    mut state: VaultState,
+    receiver: &str,
+    owner: &str,
+    assets: u64,
+    shares: u64,
+) -> ContractResult<()> {
+    validation::validate_address(receiver)?;
+    if assets == 0 || shares == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Nothing to withdraw".to_string(),
+        ));
+    }
+    let ctx = context();
+    let sender = ctx.sender().to_string();
+    if sender != owner {
+        allowance_after(owner, &sender, shares)?;
+    }
+    let available = share_balance(owner)?;
+    let remaining = available
+        .checked_sub(shares)
+        .ok_or(ContractError::InsufficientBalance {
+            required: shares,
+            available,
+        })?;
+    // Last check before any write: a trip must not leave shares half-burned.
+    circuit_breaker::record_outflow(&config.asset, assets)?;
+    if sender != owner {
+        spend_allowance(owner, &sender, shares)?;
+    }
+    set_share_balance(owner, remaining)?;
+    state.total_supply = safe_math::sub(state.total_supply, shares)?;
+
+    if state.idle < assets
+        && let Some(strategy) = &config.strategy
+    {
+        let recalled = strategy::withdraw(strategy, assets - state.idle)?;
+        state.idle = safe_math::add(state.idle, recalled)?;
+        event!("StrategyRecalled", strategy: strategy, amount: recalled);
+    }
+    state.idle = state
+        .idle
+        .checked_sub(assets)
+        .ok_or(ContractError::InsufficientBalance {
+            required: assets,
+            available: state.idle,
+        })?;
+    save_state(&state)?;
+
+    let call = encode_call(&TransferArgs {
+        to: receiver.to_string(),
+        amount: assets,
+    })?;
+    ctx.call_contract(&config.asset, "transfer", &call)?;
+
+    event!("Transfer", from: owner, to: ZERO_ADDRESS, amount: shares);
+    event!("Withdraw",
+        sender: sender,
+        receiver: receiver,
+        owner: owner,
+        assets: assets,
+        shares: shares
+    );
+    Ok(())
+}
+
+fn execute_deposit() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    circuit_breaker::when_not_tripped()?;
+    let config = load_config()?;
+    let state = load_state()?;
+    let args: DepositArgs = read_args()?;
+    let total = vault_assets(&config, &state)?;
+    let minted = to_shares(args.assets, total, state.total_supply, Rounding::Down)?;
+    settle_deposit(&config, state, &args.receiver, args.assets, minted)?;
+    Ok(minted)
+}
+
+fn execute_mint() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    pausable::when_not_paused()?;
+    decommission::when_active()?;
+    circuit_breaker::when_not_tripped()?;
+    let config = load_config()?;
+    let state = load_state()?;
+    let args: MintArgs = read_args()?;
+    let total = vault_assets(&config, &state)?;
+    let assets = to_assets(args.shares, total, state.total_supply, Rounding::Up)?;
+    settle_deposit(&config, state, &args.receiver, assets, args.shares)?;
+    Ok(assets)
+}
+
+fn execute_withdraw() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let state = load_state()?;
+    let args: WithdrawArgs = read_args()?;
+    let total = vault_assets(&config, &state)?;
+    let burned = to_shares(args.assets, total, state.total_supply, Rounding::Up)?;
+    settle_withdraw(
+        &config,
+        state,
+        &args.receiver,
+        &args.owner,
+        args.assets,
+        burned,
+    )?;
+    Ok(burned)
+}
+
+fn execute_redeem() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    let state = load_state()?;
+    let args: RedeemArgs = read_args()?;
+    let total = vault_assets(&config, &state)?;
+    let assets = to_assets(args.shares, total, state.total_supply, Rounding::Down)?;
+    settle_withdraw(
+        &config,
+        state,
+        &args.receiver,
+        &args.owner,
+        assets,
+        args.shares,
+    )?;
+    Ok(assets)
+}
+
+/// Convert with the vault's current exchange rate.
+fn quote(convert: impl FnOnce(u64, u64) -> ContractResult<u64>) -> ContractResult<u64> {
+    let config = load_config()?;
+    let state = load_state()?;
+    let total = vault_assets(&config, &state)?;
+    convert(total, state.total_supply)
+}
+
+fn execute_set_strategy() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let mut config = load_config()?;
+    require_owner(&config)?;
+    let args: SetStrategyArgs = read_args()?;
+    // Detaching only brings assets home, so it stays open for good.
+    if args.strategy.is_some() {
+        decommission::when_active()?;
+    }
+    if let Some(strategy) = &args.strategy {
+        validation::validate_address(strategy)?;
+        if !context().is_contract(strategy) {
+            return Err(ContractError::InvalidArgument(
+                "Strategy must be a contract".to_string(),
+            ));
+        }
+    }
+
+    let mut state = load_state()?;
+    let mut recovered = 0;
+    if let Some(previous) = &config.strategy {
+        recovered = strategy::exit(previous)?;
+        state.idle = safe_math::add(state.idle, recovered)?;
+        save_state(&state)?;
+    }
+    let previous = core::mem::replace(&mut config.strategy, args.strategy);
+    save_config(&config)?;
+
+    let previous = previous.unwrap_or_default();
+    let current = config.strategy.clone().unwrap_or_default();
+    event!("StrategyChanged",
+        previous: previous,
+        strategy: current,
+        recovered: recovered
+    );
+    admin_log::record(admin_log::SET_PARAMETER, "strategy", previous, current)?;
+    Ok(())
+}
+
+fn execute_deploy_to_strategy() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    require_owner(&config)?;
+    decommission::when_active()?;
+    let strategy_address = config
+        .strategy
+        .clone()
+        .ok_or_else(|| ContractError::InvalidArgument("No strategy set".to_string()))?;
+    let args: AssetsArgs = read_args()?;
+    validation::validate_positive_amount(args.assets)?;
+
+    let mut state = load_state()?;
+    state.idle = state
+        .idle
+        .checked_sub(args.assets)
+        .ok_or(ContractError::InsufficientBalance {
+            required: args.assets,
+            available: state.idle,
+        })?;
+    save_state(&state)?;
+
+    let call = encode_call(&TransferArgs {
+        to: strategy_address.clone(),
+        amount: args.assets,
+    })?;
+    context().call_contract(&config.asset, "transfer", &call)?;
+    strategy::deposit(&strategy_address, args.assets)?;
+
+    event!("StrategyDeployed", strategy: strategy_address, amount: args.assets);
+    admin_log::record("deploy_to_strategy", &strategy_address, "", args.assets)?;
+    Ok(())
+}
+
+fn execute_recall_from_strategy() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let config = load_config()?;
+    require_owner(&config)?;
+    let strategy_address = config
+        .strategy
+        .clone()
+        .ok_or_else(|| ContractError::InvalidArgument("No strategy set".to_string()))?;
+    let args: AssetsArgs = read_args()?;
+    validation::validate_positive_amount(args.assets)?;
+
+    let recalled = strategy::withdraw(&strategy_address, args.assets)?;
+    let mut state = load_state()?;
+    state.idle = safe_math::add(state.idle, recalled)?;
+    save_state(&state)?;
+
+    event!("StrategyRecalled", strategy: strategy_address, amount: recalled);
+    admin_log::record("recall_from_strategy", &strategy_address, "", recalled)?;
+    Ok(recalled)
+}
+
+fn execute_transfer() -> ContractResult<()> {
+    let args: TransferArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    move_shares(context().sender(), &args.to, args.amount)
+}
+
+fn execute_approve() -> ContractResult<()> {
+    let args: ApproveArgs = read_args()?;
+    validation::validate_address(&args.spender)?;
+    let ctx = context();
+    share_allowances().set(
+        &(ctx.sender().to_string(), args.spender.clone()),
+        &args.amount,
+    )?;
+
+    event!("Approval", owner: ctx.sender(), spender: args.spender, amount: args.amount);
+    Ok(())
+}
+
+fn execute_transfer_from() -> ContractResult<()> {
+    let args: TransferFromArgs = read_args()?;
+    validation::validate_positive_amount(args.amount)?;
+    spend_allowance(&args.from, context().sender(), args.amount)?;
+    move_shares(&args.from, &args.to, args.amount)
+}
+
+fn execute_get_vault_info() -> ContractResult<VaultInfo> {
+    let config = load_config()?;
+    let state = load_state()?;
+    Ok(VaultInfo {
+        total_assets: vault_assets(&config, &state)?,
+        asset: config.asset,
+        owner: config.owner,
+        strategy: config.strategy,
+        idle: state.idle,
+        total_supply: state.total_supply,
+    })
+}
+
+fn respond_amount(name: &str, result: ContractResult<u64>) {
+    if let Err(err) = result.and_then(|amount| try_respond(&amount)) {
+        log!("{} failed: {}", name, err);
+    }
+}
+
+fn execute_pause() -> ContractResult<()> {
+    require_owner(&load_config()?)?;
+    pausable::pause()
+}
+
+fn execute_unpause() -> ContractResult<()> {
+    require_owner(&load_config()?)?;
+    pausable::unpause()
+}
+
+fn execute_set_outflow_limit() -> ContractResult<()> {
+    let config = load_config()?;
+    require_owner(&config)?;
+    decommission::when_active()?;
+    let args: OutflowLimitArgs = read_args()?;
+    if args.max_outflow == 0 {
+        return circuit_breaker::remove_limit(&config.asset);
+    }
+    circuit_breaker::set_limit(
+        &config.asset,
+        args.max_outflow,
+        Duration::from_secs(args.window),
+    )
+}
+
+fn execute_reset_circuit_breaker() -> ContractResult<()> {
+    require_owner(&load_config()?)?;
+    circuit_breaker::reset()
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    require_owner(&load_config()?)?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    require_owner(&load_config()?)?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    require_owner(&load_config()?)?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_circuit_breaker_status() -> ContractResult<Option<Trip>> {
+    let trip = circuit_breaker::tripped()?;
+    try_respond(&trip)?;
+    Ok(trip)
+}
+
+/// Initialize the vault; the caller becomes its owner
+///
+/// # Arguments
+/// * `asset` - Underlying CRC-20 token
+/// * `name` - Share token name, at most 64 bytes
+/// * `symbol` - Share token symbol, at most 16 bytes
+/// * `decimals` - Share token decimals (usually the asset's)
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = execute_initialize() {
+        log!("Initialize failed: {}", err);
+    }
+}
+
+/// Deposit assets and mint shares (caller must approve the vault first)
+///
+/// # Arguments
+/// * `assets` - Amount of the underlying to deposit
+/// * `receiver` - Account receiving the shares
+///
+/// # Returns
+/// Shares minted (rounded down)
+#[unsafe(no_mangle)]
+pub extern "C" fn deposit() {
+    respond_amount("deposit", execute_deposit());
+}
+
+/// Mint an exact number of shares by depositing assets
+///
+/// # Arguments
+/// * `shares` - Shares to mint
+/// * `receiver` - Account receiving the shares
+///
+/// # Returns
+/// Assets pulled from the caller (rounded up)
+#[unsafe(no_mangle)]
+pub extern "C" fn mint() {
+    respond_amount("mint", execute_mint());
+}
+
+/// Withdraw an exact amount of assets by burning shares
+///
+/// # Arguments
+/// * `assets` - Amount of the underlying to withdraw
+/// * `receiver` - Account receiving the assets
+/// * `owner` - Share owner; callers other than the owner spend its share allowance
+///
+/// # Returns
+/// Shares burned (rounded up)
+#[unsafe(no_mangle)]
+pub extern "C" fn withdraw() {
+    respond_amount("withdraw", execute_withdraw());
+}
+
+/// Burn an exact number of shares for assets
+///
+/// # Arguments
+/// * `shares` - Shares to burn
+/// * `receiver` - Account receiving the assets
+/// * `owner` - Share owner; callers other than the owner spend its share allowance
+///
+/// # Returns
+/// Assets sent (rounded down)
+#[unsafe(no_mangle)]
+pub extern "C" fn redeem() {
+    respond_amount("redeem", execute_redeem());
+}
+
+/// Shares worth `assets` at the current rate (rounded down)
+///
+/// # Arguments
+/// * `assets` - Amount of the underlying
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn convert_to_shares() {
+    let result = read_args::<AssetsArgs>().and_then(|args| {
+        quote(|total, supply| to_shares(args.assets, total, supply, Rounding::Down))
+    });
+    respond_amount("convert_to_shares", result);
+}
+
+/// Assets worth `shares` at the current rate (rounded down)
+///
+/// # Arguments
+/// * `shares` - Number of shares
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn convert_to_assets() {
+    let result = read_args::<SharesArgs>().and_then(|args| {
+        quote(|total, supply| to_assets(args.shares, total, supply, Rounding::Down))
+    });
+    respond_amount("convert_to_assets", result);
+}
+
+/// Shares `deposit(assets)` would mint
+///
+/// # Arguments
+/// * `assets` - Amount of the underlying
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_deposit() {
+    let result = read_args::<AssetsArgs>().and_then(|args| {
+        quote(|total, supply| to_shares(args.assets, total, supply, Rounding::Down))
+    });
+    respond_amount("preview_deposit", result);
+}
+
+/// Assets `mint(shares)` would pull
+///
+/// # Arguments
+/// * `shares` - Number of shares
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_mint() {
+    let result = read_args::<SharesArgs>().and_then(|args| {
+        quote(|total, supply| to_assets(args.shares, total, supply, Rounding::Up))
+    });
+    respond_amount("preview_mint", result);
+}
+
+/// Shares `withdraw(assets)` would burn
+///
+/// # Arguments
+/// * `assets` - Amount of the underlying
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_withdraw() {
+    let result = read_args::<AssetsArgs>().and_then(|args| {
+        quote(|total, supply| to_shares(args.assets, total, supply, Rounding::Up))
+    });
+    respond_amount("preview_withdraw", result);
+}
+
+/// Assets `redeem(shares)` would send
+///
+/// # Arguments
+/// * `shares` - Number of shares
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn preview_redeem() {
+    let result = read_args::<SharesArgs>().and_then(|args| {
+        quote(|total, supply| to_assets(args.shares, total, supply, Rounding::Down))
+    });
+    respond_amount("preview_redeem", result);
+}
+
+/// Most assets an account can withdraw
+///
+/// # Arguments
+/// * `account` - Share owner
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn max_withdraw() {
+    let result = read_args::<AccountArgs>().and_then(|args| {
+        let balance = share_balance(&args.account)?;
+        quote(|total, supply| to_assets(balance, total, supply, Rounding::Down))
+    });
+    respond_amount("max_withdraw", result);
+}
+
+/// Most shares an account can redeem
+///
+/// # Arguments
+/// * `account` - Share owner
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn max_redeem() {
+    let result = read_args::<AccountArgs>().and_then(|args| share_balance(&args.account));
+    respond_amount("max_redeem", result);
+}
+
+/// Idle assets plus assets reported by the strategy
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn total_assets() {
+    let result = load_config().and_then(|config| vault_assets(&config, &load_state()?));
+    respond_amount("total_assets", result);
+}
+
+/// Attach, replace or detach the strategy (owner only)
+///
+/// The previous strategy, if any, is exited and its funds return to the
+/// vault as idle assets.
+///
+/// # Arguments
+/// * `strategy` - New strategy contract, or `None` to detach
+#[unsafe(no_mangle)]
+pub extern "C" fn set_strategy() {
+    if let Err(err) = execute_set_strategy() {
+        log!("set_strategy failed: {}", err);
+    }
+}
+
+/// Move idle assets into the strategy (owner only)
+///
+/// # Arguments
+/// * `assets` - Amount to deploy
+#[unsafe(no_mangle)]
+pub extern "C" fn deploy_to_strategy() {
+    if let Err(err) = execute_deploy_to_strategy() {
+        log!("deploy_to_strategy failed: {}", err);
+    }
+}
+
+/// Pull assets back from the strategy (owner only)
+///
+/// # Arguments
+/// * `assets` - Amount to recall
+///
+/// # Returns
+/// Amount the strategy returned
+#[unsafe(no_mangle)]
+pub extern "C" fn recall_from_strategy() {
+    respond_amount("recall_from_strategy", execute_recall_from_strategy());
+}
+
+/// Transfer shares
+///
+/// # Arguments
+/// * `to` - Recipient address
+/// * `amount` - Shares to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer() {
+    if let Err(err) = execute_transfer() {
+        log!("Transfer failed: {}", err);
+    }
+}
+
+/// Approve a spender to transfer or redeem the caller's shares
+///
+/// # Arguments
+/// * `spender` - Address allowed to spend
+/// * `amount` - Maximum shares they can spend
+#[unsafe(no_mangle)]
+pub extern "C" fn approve() {
+    if let Err(err) = execute_approve() {
+        log!("Approve failed: {}", err);
+    }
+}
+
+/// Transfer shares on behalf of another account (requires prior approval)
+///
+/// # Arguments
+/// * `from` - Account to transfer from
+/// * `to` - Recipient address
+/// * `amount` - Shares to transfer
+#[unsafe(no_mangle)]
+pub extern "C" fn transfer_from() {
+    if let Err(err) = execute_transfer_from() {
+        log!("TransferFrom failed: {}", err);
+    }
+}
+
+/// Share balance of an account
+///
+/// # Arguments
+/// * `account` - Address to query
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn balance_of() {
+    let result = read_args::<AccountArgs>().and_then(|args| share_balance(&args.account));
+    respond_amount("balance_of", result);
+}
+
+/// Total shares outstanding
+///
+/// # Returns
+/// `u64`
+#[unsafe(no_mangle)]
+pub extern "C" fn total_supply() {
+    respond_amount("total_supply", load_state().map(|state| state.total_supply));
+}
+
+/// Share token name
+///
+/// # Returns
+/// `String`
+#[unsafe(no_mangle)]
+pub extern "C" fn name() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config.name)) {
+        log!("name failed: {}", err);
+    }
+}
+
+/// Share token symbol
+///
+/// # Returns
+/// `String`
+#[unsafe(no_mangle)]
+pub extern "C" fn symbol() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config.symbol)) {
+        log!("symbol failed: {}", err);
+    }
+}
+
+/// Share token decimals
+///
+/// # Returns
+/// `u8`
+#[unsafe(no_mangle)]
+pub extern "C" fn decimals() {
+    if let Err(err) = load_config().and_then(|config| try_respond(&config.decimals)) {
+        log!("decimals failed: {}", err);
+    }
+}
+
+/// Get the vault's asset, owner, strategy and totals
+///
+/// # Returns
+/// `VaultInfo`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_vault_info() {
+    if let Err(err) = execute_get_vault_info().and_then(|info| try_respond(&info)) {
+        log!("get_vault_info failed: {}", err);
+    }
+}
+
+/// Pause the contract (owner only)
+///
+/// Deposits and mints fail until `unpause`; withdrawals and redemptions stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn pause() {
+    if let Err(err) = execute_pause() {
+        log!("pause failed: {}", err);
+    }
+}
+
+/// Lift a pause (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn unpause() {
+    if let Err(err) = execute_unpause() {
+        log!("unpause failed: {}", err);
+    }
+}
+
+/// Check whether the contract is paused
+///
+/// # Returns
+/// `bool`
+#[unsafe(no_mangle)]
+pub extern "C" fn paused() {
+    if let Err(err) = pausable::paused().and_then(|paused| try_respond(&paused)) {
+        log!("paused failed: {}", err);
+    }
+}
+
+/// Limit the assets withdrawals and redemptions may pay out per window (owner only)
+///
+/// Exceeding the limit trips the circuit breaker, which halts deposits,
+/// mints, withdrawals and redemptions until `reset_circuit_breaker`.
+///
+/// # Arguments
+/// * `max_outflow` - Assets allowed per window; 0 removes the limit
+/// * `window` - Window length in seconds
+#[unsafe(no_mangle)]
+pub extern "C" fn set_outflow_limit() {
+    if let Err(err) = execute_set_outflow_limit() {
+        log!("set_outflow_limit failed: {}", err);
+    }
+}
+
+/// Clear a tripped circuit breaker (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn reset_circuit_breaker() {
+    if let Err(err) = execute_reset_circuit_breaker() {
+        log!("reset_circuit_breaker failed: {}", err);
+    }
+}
+
+/// Give notice that the vault will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the vault once the notice has run out (owner only)
+///
+/// Deposits, mints, new strategies, deployments and outflow limits stop
+/// for good. Withdrawals, redemptions and strategy recalls stay open.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the vault is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Query the circuit breaker
+///
+/// # Returns
+/// `Option<Trip>` - The outflow that tripped it, if tripped
+#[unsafe(no_mangle)]
+pub extern "C" fn circuit_breaker_status() {
+    if let Err(err) = execute_circuit_breaker_status() {
+        log!("circuit_breaker_status failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::strategy::AmountArgs;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_ALICE: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_BOB: &str = "0x0000000000000000000000000000000000000c03";
+    const VAULT: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000f01";
+    const STRATEGY: &str = "0x0000000000000000000000000000000000000f02";
+
+    /// Underlying held by the mocked strategy
+    static STRATEGY_ASSETS: AtomicU64 = AtomicU64::new(0);
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup() {
+        mock::reset();
+        mock::set_contract_address(VAULT);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(1_736_000_000);
+        mock::set_sender(ADDR_OWNER);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+
+        STRATEGY_ASSETS.store(0, Ordering::SeqCst);
+        mock::register_contract(STRATEGY, "deposit", |args| {
+            let args: AmountArgs = postcard::from_bytes(args).unwrap();
+            STRATEGY_ASSETS.fetch_add(args.amount, Ordering::SeqCst);
+            Ok(Vec::new())
+        });
+        mock::register_contract(STRATEGY, "withdraw", |args| {
+            let args: AmountArgs = postcard::from_bytes(args).unwrap();
+            let held = STRATEGY_ASSETS.load(Ordering::SeqCst);
+            let sent = args.amount.min(held);
+            STRATEGY_ASSETS.store(held - sent, Ordering::SeqCst);
+            Ok(encode(&sent))
+        });
+        mock::register_contract(STRATEGY, "total_assets", |_| {
+            Ok(encode(&STRATEGY_ASSETS.load(Ordering::SeqCst)))
+        });
+        mock::register_contract(STRATEGY, "exit_strategy", |_| {
+            Ok(encode(&STRATEGY_ASSETS.swap(0, Ordering::SeqCst)))
+        });
+
+        mock::set_call_data(&encode(&InitializeArgs {
+            asset: TOKEN.to_string(),
+            name: Name::new("Vault Chert").unwrap(),
+            symbol: Symbol::new("vCHT").unwrap(),
+            decimals: 8,
+        }));
+        initialize();
+    }
+
+    fn call(sender: &str, entrypoint: extern "C" fn(), data: Vec<u8>) -> Option<u64> {
+        mock::set_sender(sender);
+        mock::set_call_data(&data);
+        entrypoint();
+        let response = mock::take_return_data();
+        (!response.is_empty()).then(|| postcard::from_bytes(&response).unwrap())
+    }
+
+    fn deposit_as(sender: &str, assets: u64) -> Option<u64> {
+        call(
+            sender,
+            deposit,
+            encode(&DepositArgs {
+                assets,
+                receiver: sender.to_string(),
+            }),
+        )
+    }
+
+    fn attach_strategy_with(assets: u64) {
+        call(
+            ADDR_OWNER,
+            set_strategy,
+            encode(&SetStrategyArgs {
+                strategy: Some(STRATEGY.to_string()),
+            }),
+        );
+        call(
+            ADDR_OWNER,
+            deploy_to_strategy,
+            encode(&AssetsArgs { assets }),
+        );
+    }
+
+    #[test]
+    fn deposit_and_redeem_round_trip() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+
+        assert_eq!(deposit_as(ADDR_ALICE, 1_000), Some(1_000));
+        assert_eq!(share_balance(ADDR_ALICE).unwrap(), 1_000);
+        let pulled = mock::take_contract_calls();
+        assert_eq!(
+            pulled[0].args,
+            encode(&TransferFromArgs {
+                from: ADDR_ALICE.to_string(),
+                to: VAULT.to_string(),
+                amount: 1_000,
+            })
+        );
+
+        let redeemed = call(
+            ADDR_ALICE,
+            redeem,
+            encode(&RedeemArgs {
+                shares: 400,
+                receiver: ADDR_BOB.to_string(),
+                owner: ADDR_ALICE.to_string(),
+            }),
+        );
+        assert_eq!(redeemed, Some(400));
+        assert_eq!(
+            load_state().unwrap(),
+            VaultState {
+                idle: 600,
+                total_supply: 600
+            }
+        );
+        let paid = mock::take_contract_calls();
+        assert_eq!(paid[0].method, "transfer");
+        assert!(mock::take_events().iter().any(|e| e.topic == "Withdraw"));
+    }
+
+    #[test]
+    fn strategy_yield_raises_share_price() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_as(ADDR_ALICE, 1_000);
+        attach_strategy_with(1_000);
+
+        // The strategy earns 50%.
+        STRATEGY_ASSETS.store(1_500, Ordering::SeqCst);
+        let worth = call(
+            ADDR_BOB,
+            convert_to_assets,
+            encode(&SharesArgs { shares: 1_000 }),
+        );
+        assert_eq!(worth, Some(1_499));
+
+        // Deposits round shares down, mints round assets up.
+        assert_eq!(deposit_as(ADDR_BOB, 300), Some(200));
+        let cost = call(ADDR_BOB, preview_mint, encode(&SharesArgs { shares: 100 }));
+        assert_eq!(cost, Some(150));
+    }
+
+    #[test]
+    fn withdraw_recalls_shortfall_from_strategy() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_as(ADDR_ALICE, 1_000);
+        attach_strategy_with(800);
+        mock::take_contract_calls();
+
+        let burned = call(
+            ADDR_ALICE,
+            withdraw,
+            encode(&WithdrawArgs {
+                assets: 500,
+                receiver: ADDR_ALICE.to_string(),
+                owner: ADDR_ALICE.to_string(),
+            }),
+        );
+        assert_eq!(burned, Some(500));
+        let calls = mock::take_contract_calls();
+        let recall = calls.iter().find(|c| c.method == "withdraw").unwrap();
+        assert_eq!(recall.args, encode(&AmountArgs { amount: 300 }));
+        assert_eq!(STRATEGY_ASSETS.load(Ordering::SeqCst), 500);
+        assert_eq!(load_state().unwrap().idle, 0);
+    }
+
+    #[test]
+    fn third_parties_spend_share_allowance() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_as(ADDR_ALICE, 1_000);
+
+        let redeem_for_alice = || {
+            call(
+                ADDR_BOB,
+                redeem,
+                encode(&RedeemArgs {
+                    shares: 300,
+                    receiver: ADDR_BOB.to_string(),
+                    owner: ADDR_ALICE.to_string(),
+                }),
+            )
+        };
+        assert_eq!(redeem_for_alice(), None);
+        assert_eq!(share_balance(ADDR_ALICE).unwrap(), 1_000);
+
+        call(
+            ADDR_ALICE,
+            approve,
+            encode(&ApproveArgs {
+                spender: ADDR_BOB.to_string(),
+                amount: 500,
+            }),
+        );
+        assert_eq!(redeem_for_alice(), Some(300));
+        assert_eq!(share_balance(ADDR_ALICE).unwrap(), 700);
+        let key = (ADDR_ALICE.to_string(), ADDR_BOB.to_string());
+        assert_eq!(share_allowances().get(&key).unwrap(), Some(200));
+    }
+
+    #[test]
+    fn only_owner_manages_strategy() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_as(ADDR_ALICE, 1_000);
+
+        call(
+            ADDR_ALICE,
+            set_strategy,
+            encode(&SetStrategyArgs {
+                strategy: Some(STRATEGY.to_string()),
+            }),
+        );
+        assert_eq!(load_config().unwrap().strategy, None);
+
+        attach_strategy_with(600);
+        call(
+            ADDR_ALICE,
+            deploy_to_strategy,
+            encode(&AssetsArgs { assets: 400 }),
+        );
+        assert_eq!(load_state().unwrap().idle, 400);
+
+        // Detaching exits the strategy and returns everything as idle assets.
+        STRATEGY_ASSETS.store(650, Ordering::SeqCst);
+        call(
+            ADDR_OWNER,
+            set_strategy,
+            encode(&SetStrategyArgs { strategy: None }),
+        );
+        assert_eq!(load_config().unwrap().strategy, None);
+        assert_eq!(load_state().unwrap().idle, 1_050);
+        assert_eq!(STRATEGY_ASSETS.load(Ordering::SeqCst), 0);
+        // Attach, deploy and detach are logged; Alice's attempts are not.
+        assert_eq!(admin_log::next_sequence().unwrap(), 3);
+    }
+
+    #[test]
+    fn decommissioning_stops_deposits_but_not_withdrawals() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_as(ADDR_ALICE, 1_000);
+        attach_strategy_with(600);
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        assert_eq!(
+            call(ADDR_ALICE, schedule_decommission, notice.clone()),
+            None
+        );
+        let eta = call(ADDR_OWNER, schedule_decommission, notice).unwrap();
+        mock::set_block_timestamp(eta);
+        call(ADDR_OWNER, decommission, Vec::new());
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        assert_eq!(deposit_as(ADDR_BOB, 100), None);
+        let minted = encode(&MintArgs {
+            shares: 100,
+            receiver: ADDR_BOB.to_string(),
+        });
+        assert_eq!(call(ADDR_BOB, mint, minted), None);
+        call(
+            ADDR_OWNER,
+            deploy_to_strategy,
+            encode(&AssetsArgs { assets: 100 }),
+        );
+        assert_eq!(load_state().unwrap().idle, 400);
+
+        // Holders still get out, and the owner can still bring assets home.
+        let redeemed = call(
+            ADDR_ALICE,
+            redeem,
+            encode(&RedeemArgs {
+                shares: 500,
+                receiver: ADDR_ALICE.to_string(),
+                owner: ADDR_ALICE.to_string(),
+            }),
+        );
+        assert_eq!(redeemed, Some(500));
+        call(
+            ADDR_OWNER,
+            set_strategy,
+            encode(&SetStrategyArgs { strategy: None }),
+        );
+        assert_eq!(load_config().unwrap().strategy, None);
+        assert_eq!(load_state().unwrap().idle, 500);
+    }
+
+    #[test]
+    fn outflow_limit_trips_circuit_breaker() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        deposit_as(ADDR_ALICE, 1_000);
+        call(
+            ADDR_OWNER,
+            set_outflow_limit,
+            encode(&OutflowLimitArgs {
+                max_outflow: 500,
+                window: 86_400,
+            }),
+        );
+        let withdraw_as_alice = |assets| {
+            call(
+                ADDR_ALICE,
+                withdraw,
+                encode(&WithdrawArgs {
+                    assets,
+                    receiver: ADDR_ALICE.to_string(),
+                    owner: ADDR_ALICE.to_string(),
+                }),
+            )
+        };
+
+        assert_eq!(withdraw_as_alice(400), Some(400));
+        assert_eq!(withdraw_as_alice(200), None);
+        assert_eq!(share_balance(ADDR_ALICE).unwrap(), 600);
+        assert_eq!(load_state().unwrap().idle, 600);
+        assert!(circuit_breaker::tripped().unwrap().is_some());
+        assert_eq!(deposit_as(ADDR_BOB, 100), None);
+        assert_eq!(withdraw_as_alice(1), None);
+
+        call(ADDR_ALICE, reset_circuit_breaker, Vec::new());
+        assert!(circuit_breaker::tripped().unwrap().is_some());
+        call(ADDR_OWNER, reset_circuit_breaker, Vec::new());
+        assert_eq!(withdraw_as_alice(200), Some(200));
+        assert_eq!(share_balance(ADDR_ALICE).unwrap(), 400);
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup();
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "vault-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}
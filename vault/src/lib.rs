@@ -0,0 +1,38 @@
+//! Tokenized Yield Vault
+//!
+//! An ERC-4626 style vault over a single CRC-20. Depositors receive vault
+//! shares (themselves a CRC-20) that track a proportional claim on the
+//! vault's assets. The owner may attach a strategy contract and deploy idle
+//! assets to it; yield the strategy reports raises the value of every share.
+//!
+//! ## Features
+//! - `deposit` / `mint` / `withdraw` / `redeem` with ERC-4626 rounding
+//! - `convert_to_shares` / `convert_to_assets` and `preview_*` queries
+//! - Shares are transferable and approvable like any CRC-20
+//! - Withdrawals pull any shortfall from the strategy automatically
+//! - Decommissioning: after the owner's notice runs out, deposits stop
+//!   while withdrawals continue
+//!
+//! ## Writing a strategy
+//! ```toml
+//! vault-contract = { path = "../vault", default-features = false }
+//! ```
+//!
+//! ```rust,ignore
+//! use vault_contract::strategy::AmountArgs;
+//! ```
+//!
+//! See [`strategy`] for the entrypoints a strategy must export.
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+pub mod strategy;
+
+#[cfg(feature = "entrypoints")]
+mod contract;
+
+#[cfg(feature = "entrypoints")]
+pub use contract::*;
@@ -0,0 +1,61 @@
+//! Strategy interface
+//!
+//! A strategy is a contract that holds part of a vault's underlying asset
+//! and puts it to work. The vault is its only depositor and talks to it
+//! through four entrypoints:
+//!
+//! | Method          | Arguments     | Returns | Effect                                       |
+//! |-----------------|---------------|---------|----------------------------------------------|
+//! | `deposit`       | `AmountArgs`  | -       | Vault already transferred `amount` to it     |
+//! | `withdraw`      | `AmountArgs`  | `u64`   | Send up to `amount` back to the vault        |
+//! | `total_assets`  | -             | `u64`   | Underlying held or owed to the vault         |
+//! | `exit_strategy` | -             | `u64`   | Send everything back to the vault            |
+//!
+//! `withdraw` and `exit_strategy` return the amount actually transferred;
+//! the vault credits exactly that much. Strategies should reject calls
+//! whose sender is not their vault.
+
+use serde::{Deserialize, Serialize};
+use silica_contract_sdk::prelude::*;
+
+/// Arguments of the strategy `deposit` and `withdraw` entrypoints
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AmountArgs {
+    pub amount: u64,
+}
+
+fn call_amount(strategy: &str, method: &str, amount: u64) -> ContractResult<Vec<u8>> {
+    let args = postcard::to_allocvec(&AmountArgs { amount })
+        .map_err(|_| ContractError::SerializationFailed)?;
+    context().call_contract(strategy, method, &args)
+}
+
+fn decode_amount(response: &[u8]) -> ContractResult<u64> {
+    postcard::from_bytes(response).map_err(|_| ContractError::DeserializationFailed)
+}
+
+/// Tell `strategy` that `amount` of the underlying was transferred to it.
+pub fn deposit(strategy: &str, amount: u64) -> ContractResult<()> {
+    call_amount(strategy, "deposit", amount).map(|_| ())
+}
+
+/// Ask `strategy` to return up to `amount`; returns what it sent back.
+pub fn withdraw(strategy: &str, amount: u64) -> ContractResult<u64> {
+    let returned = decode_amount(&call_amount(strategy, "withdraw", amount)?)?;
+    if returned > amount {
+        return Err(ContractError::InvalidArgument(
+            "Strategy returned more than requested".to_string(),
+        ));
+    }
+    Ok(returned)
+}
+
+/// Underlying currently managed by `strategy` on behalf of the vault.
+pub fn total_assets(strategy: &str) -> ContractResult<u64> {
+    decode_amount(&context().call_contract(strategy, "total_assets", &[])?)
+}
+
+/// Unwind `strategy` completely; returns what it sent back.
+pub fn exit(strategy: &str) -> ContractResult<u64> {
+    decode_amount(&context().call_contract(strategy, "exit_strategy", &[])?)
+}
@@ -0,0 +1,659 @@
+//! Gift Card Vouchers
+//!
+//! A purchaser locks native coin or CRC-20 tokens against the BLAKE3 hash of a
+//! secret claim code. Whoever presents the code can redeem the voucher, in one
+//! go or in several partial redemptions; unredeemed value returns to the
+//! purchaser once the voucher expires.
+//!
+//! ## Features
+//! - Hash-locked claim codes (only the hash is stored on-chain)
+//! - Partial redemptions bound to the first redeemer
+//! - Expiry refunds of the remaining balance to the purchaser
+//! - Decommissioning: after the owner's notice runs out, purchases stop
+//!   while existing vouchers are redeemed or refunded
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate alloc;
+
+use serde::de::DeserializeOwned;
+use silica_contract_sdk::decommission;
+use silica_contract_sdk::event;
+use silica_contract_sdk::ownable;
+use silica_contract_sdk::prelude::*;
+use silica_contract_sdk::time::Duration;
+
+const VOUCHERS_PREFIX: &str = "vouchers";
+const NATIVE_ASSET: &str = "native";
+const MIN_CODE_BYTES: usize = 16;
+const MAX_CODE_BYTES: usize = 64;
+const MAX_CALL_DATA_BYTES: usize = 4096;
+const MAX_RETURN_BYTES: usize = 4096;
+
+/// Locked voucher keyed by its claim-code hash
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Voucher {
+    pub purchaser: String,
+    pub token: String,
+    pub face_value: u64,
+    pub remaining: u64,
+    pub expires_at: u64,
+    /// Set by the first redemption; later redemptions must come from this account
+    pub holder: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PurchaseArgs {
+    token: String,
+    code_hash: [u8; 32],
+    amount: u64,
+    expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RedeemArgs {
+    code: Vec<u8>,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CodeHashArgs {
+    code_hash: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoticeArgs {
+    notice: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferArgs {
+    to: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenTransferFromArgs {
+    from: String,
+    to: String,
+    amount: u64,
+}
+
+/// Hash a claim code the way `purchase` expects it.
+pub fn code_hash(code: &[u8]) -> [u8; 32] {
+    crypto::hash_blake3(code)
+}
+
+fn read_args<T>() -> ContractResult<T>
+where
+    T: DeserializeOwned,
+{
+    let payload = context().call_data()?;
+    assert!(
+        payload.len() <= MAX_CALL_DATA_BYTES,
+        "Call data exceeds static bound"
+    );
+    if payload.is_empty() {
+        return Err(ContractError::CallDataUnavailable);
+    }
+
+    postcard::from_bytes(&payload).map_err(|_| ContractError::DeserializationFailed)
+}
+
+fn try_respond<T: Serialize>(value: &T) -> ContractResult<()> {
+    let data = postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)?;
+    assert!(
+        data.len() <= MAX_RETURN_BYTES,
+        "Return payload exceeds static bound"
+    );
+    context().return_bytes(&data)
+}
+
+fn encode_call<T: Serialize>(value: &T) -> ContractResult<Vec<u8>> {
+    postcard::to_allocvec(value).map_err(|_| ContractError::SerializationFailed)
+}
+
+fn vouchers() -> Map<[u8; 32], Voucher> {
+    Map::new(VOUCHERS_PREFIX)
+}
+
+fn load_voucher(code_hash: &[u8; 32]) -> ContractResult<Voucher> {
+    vouchers()
+        .get(code_hash)?
+        .ok_or_else(|| ContractError::InvalidArgument("Unknown voucher".to_string()))
+}
+
+fn pay_out(ctx: &Context, token: &str, recipient: &str, amount: u64) -> ContractResult<()> {
+    if token == NATIVE_ASSET {
+        ctx.transfer_tokens(recipient, amount)
+    } else {
+        let call = encode_call(&TokenTransferArgs {
+            to: recipient.to_string(),
+            amount,
+        })?;
+        ctx.call_contract(token, "transfer", &call).map(|_| ())
+    }
+}
+
+fn execute_purchase() -> ContractResult<()> {
+    let _guard = ReentrancyGuard::enter()?;
+    decommission::when_active()?;
+    let ctx = context();
+    let purchaser = ctx.sender().to_string();
+    let args: PurchaseArgs = read_args()?;
+    validation::validate_address(&purchaser)?;
+    validation::validate_positive_amount(args.amount)?;
+
+    if args.expires_at <= ctx.block_timestamp() {
+        return Err(ContractError::InvalidArgument(
+            "Expiry must be in the future".to_string(),
+        ));
+    }
+    let mut registry = vouchers();
+    if registry.contains_key(&args.code_hash)? {
+        return Err(ContractError::InvalidArgument(
+            "Claim code already in use".to_string(),
+        ));
+    }
+
+    if args.token == NATIVE_ASSET {
+        if ctx.value() != args.amount {
+            return Err(ContractError::InvalidArgument(
+                "Attached value must equal voucher amount".to_string(),
+            ));
+        }
+    } else {
+        validation::validate_address(&args.token)?;
+        if ctx.value() != 0 {
+            return Err(ContractError::InvalidArgument(
+                "Token vouchers must not attach native value".to_string(),
+            ));
+        }
+        let call = encode_call(&TokenTransferFromArgs {
+            from: purchaser.clone(),
+            to: ctx.contract_address().to_string(),
+            amount: args.amount,
+        })?;
+        ctx.call_contract(&args.token, "transfer_from", &call)?;
+    }
+
+    registry.set(
+        &args.code_hash,
+        &Voucher {
+            purchaser: purchaser.clone(),
+            token: args.token.clone(),
+            face_value: args.amount,
+            remaining: args.amount,
+            expires_at: args.expires_at,
+            holder: None,
+        },
+    )?;
+
+    event!("VoucherPurchased",
+        code_hash: hex::encode(args.code_hash),
+        purchaser: purchaser,
+        token: args.token,
+        amount: args.amount,
+        expires_at: args.expires_at
+    );
+    Ok(())
+}
+
+fn execute_redeem() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let redeemer = ctx.sender().to_string();
+    let args: RedeemArgs = read_args()?;
+    validation::validate_address(&redeemer)?;
+    validation::validate_positive_amount(args.amount)?;
+    if args.code.len() < MIN_CODE_BYTES || args.code.len() > MAX_CODE_BYTES {
+        return Err(ContractError::InvalidArgument(format!(
+            "Claim code must be {}-{} bytes",
+            MIN_CODE_BYTES, MAX_CODE_BYTES
+        )));
+    }
+
+    let hash = code_hash(&args.code);
+    let mut voucher = load_voucher(&hash)?;
+    if ctx.block_timestamp() >= voucher.expires_at {
+        return Err(ContractError::InvalidArgument(
+            "Voucher expired".to_string(),
+        ));
+    }
+    // The code is public once the first redemption lands, so the remainder
+    // stays with whoever redeemed first.
+    if voucher
+        .holder
+        .as_ref()
+        .is_some_and(|holder| *holder != redeemer)
+    {
+        return Err(ContractError::Unauthorized);
+    }
+    if args.amount > voucher.remaining {
+        return Err(ContractError::InsufficientBalance {
+            required: args.amount,
+            available: voucher.remaining,
+        });
+    }
+
+    voucher.remaining -= args.amount;
+    voucher.holder = Some(redeemer.clone());
+    vouchers().set(&hash, &voucher)?;
+
+    pay_out(&ctx, &voucher.token, &redeemer, args.amount)?;
+
+    event!("VoucherRedeemed",
+        code_hash: hex::encode(hash),
+        redeemer: redeemer,
+        amount: args.amount,
+        remaining: voucher.remaining
+    );
+    Ok(voucher.remaining)
+}
+
+fn execute_refund() -> ContractResult<u64> {
+    let _guard = ReentrancyGuard::enter()?;
+    let ctx = context();
+    let args: CodeHashArgs = read_args()?;
+    let mut voucher = load_voucher(&args.code_hash)?;
+
+    if ctx.sender() != voucher.purchaser {
+        return Err(ContractError::Unauthorized);
+    }
+    if ctx.block_timestamp() < voucher.expires_at {
+        return Err(ContractError::InvalidArgument(
+            "Voucher has not expired".to_string(),
+        ));
+    }
+    let refund = voucher.remaining;
+    if refund == 0 {
+        return Err(ContractError::InvalidArgument(
+            "Nothing left to refund".to_string(),
+        ));
+    }
+
+    voucher.remaining = 0;
+    vouchers().set(&args.code_hash, &voucher)?;
+
+    pay_out(&ctx, &voucher.token, &voucher.purchaser, refund)?;
+
+    event!("VoucherRefunded",
+        code_hash: hex::encode(args.code_hash),
+        purchaser: voucher.purchaser,
+        amount: refund
+    );
+    Ok(refund)
+}
+
+fn execute_schedule_decommission() -> ContractResult<u64> {
+    ownable::only_owner()?;
+    let args: NoticeArgs = read_args()?;
+    decommission::schedule(args.notice)
+}
+
+fn execute_cancel_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::cancel()
+}
+
+fn execute_decommission() -> ContractResult<()> {
+    ownable::only_owner()?;
+    decommission::decommission()?;
+    Ok(())
+}
+
+fn execute_get_voucher() -> ContractResult<()> {
+    let args: CodeHashArgs = read_args()?;
+    try_respond(&load_voucher(&args.code_hash)?)
+}
+
+/// Set the owner, who may decommission the contract; call right after
+/// deployment
+#[unsafe(no_mangle)]
+pub extern "C" fn initialize() {
+    if let Err(err) = ownable::initialize(context().sender()) {
+        log!("initialize failed: {}", err);
+    }
+}
+
+/// Lock funds behind a claim-code hash
+///
+/// # Arguments
+/// * `token` - CRC-20 contract address, or `"native"` with the amount attached
+/// * `code_hash` - `blake3(code)` for a secret 16-64 byte claim code
+/// * `amount` - Voucher face value
+/// * `expires_at` - Timestamp after which only the purchaser can reclaim funds
+#[unsafe(no_mangle)]
+pub extern "C" fn purchase() {
+    if let Err(err) = execute_purchase() {
+        log!("purchase failed: {}", err);
+    }
+}
+
+/// Redeem part or all of a voucher to the caller
+///
+/// # Arguments
+/// * `code` - Claim code preimage
+/// * `amount` - Amount to redeem, at most the remaining balance
+///
+/// # Returns
+/// Remaining balance after the redemption
+#[unsafe(no_mangle)]
+pub extern "C" fn redeem() {
+    if let Err(err) = execute_redeem() {
+        log!("redeem failed: {}", err);
+    }
+}
+
+/// Return the unredeemed balance of an expired voucher (purchaser only)
+///
+/// # Arguments
+/// * `code_hash` - Voucher claim-code hash
+#[unsafe(no_mangle)]
+pub extern "C" fn refund() {
+    if let Err(err) = execute_refund() {
+        log!("refund failed: {}", err);
+    }
+}
+
+/// Give notice that the voucher contract will be decommissioned (owner only)
+///
+/// # Arguments
+/// * `notice` - Seconds until it can take effect (2-90 days)
+///
+/// # Returns
+/// Earliest decommission time
+#[unsafe(no_mangle)]
+pub extern "C" fn schedule_decommission() {
+    let result = execute_schedule_decommission().and_then(|eta| try_respond(&eta));
+    if let Err(err) = result {
+        log!("schedule_decommission failed: {}", err);
+    }
+}
+
+/// Withdraw the notice of a scheduled decommission (owner only)
+#[unsafe(no_mangle)]
+pub extern "C" fn cancel_decommission() {
+    if let Err(err) = execute_cancel_decommission() {
+        log!("cancel_decommission failed: {}", err);
+    }
+}
+
+/// Retire the voucher contract once the notice has run out (owner only)
+///
+/// New vouchers stop for good. Existing vouchers can still be redeemed
+/// until they expire and refunded after.
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission() {
+    if let Err(err) = execute_decommission() {
+        log!("decommission failed: {}", err);
+    }
+}
+
+/// Where the voucher contract is in its end of life
+///
+/// # Returns
+/// `DecommissionStatus`
+#[unsafe(no_mangle)]
+pub extern "C" fn decommission_status() {
+    if let Err(err) = decommission::status().and_then(|status| try_respond(&status)) {
+        log!("decommission_status failed: {}", err);
+    }
+}
+
+/// Get a voucher by claim-code hash
+///
+/// # Arguments
+/// * `code_hash` - Voucher claim-code hash
+///
+/// # Returns
+/// `Voucher`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_voucher() {
+    if let Err(err) = execute_get_voucher() {
+        log!("get_voucher failed: {}", err);
+    }
+}
+
+/// Crate version, build commit and storage schema version of this deployment
+///
+/// # Returns
+/// `ContractVersion`
+#[unsafe(no_mangle)]
+pub extern "C" fn contract_version() {
+    let result = proxy_implementation::schema_version()
+        .and_then(|schema_version| try_respond(&crc_standards::contract_version!(schema_version)));
+    if let Err(err) = result {
+        log!("contract_version failed: {}", err);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crc_standards::version::ContractVersion;
+    use silica_contract_sdk::ffi::mock;
+    use std::sync::{Mutex, OnceLock};
+
+    const ADDR_BUYER: &str = "0x0000000000000000000000000000000000000a01";
+    const ADDR_FRIEND: &str = "0x0000000000000000000000000000000000000b02";
+    const ADDR_THIEF: &str = "0x0000000000000000000000000000000000000c03";
+    const ADDR_OWNER: &str = "0x0000000000000000000000000000000000000d04";
+    const VOUCHERS: &str = "0x0000000000000000000000000000000000000f00";
+    const TOKEN: &str = "0x0000000000000000000000000000000000000e05";
+    const CODE: &[u8] = b"happy-birthday-2026-xyz";
+    const START: u64 = 1_736_000_000;
+    const EXPIRY: u64 = START + 30 * 86_400;
+
+    fn test_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("encode call arguments")
+    }
+
+    fn setup_native_voucher(amount: u64) {
+        mock::reset();
+        mock::set_contract_address(VOUCHERS);
+        mock::set_block_height(1);
+        mock::set_block_timestamp(START);
+        mock::set_sender(ADDR_BUYER);
+        mock::set_value(amount);
+        mock::set_call_data(&encode(&PurchaseArgs {
+            token: NATIVE_ASSET.to_string(),
+            code_hash: code_hash(CODE),
+            amount,
+            expires_at: EXPIRY,
+        }));
+        purchase();
+        mock::set_value(0);
+    }
+
+    fn redeem_as(sender: &str, code: &[u8], amount: u64) {
+        mock::set_sender(sender);
+        mock::set_call_data(&encode(&RedeemArgs {
+            code: code.to_vec(),
+            amount,
+        }));
+        redeem();
+    }
+
+    fn stored() -> Voucher {
+        load_voucher(&code_hash(CODE)).expect("voucher stored")
+    }
+
+    #[test]
+    fn purchase_stores_only_the_hash() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup_native_voucher(500);
+
+        let voucher = stored();
+        assert_eq!(voucher.purchaser, ADDR_BUYER);
+        assert_eq!(voucher.remaining, 500);
+        assert!(voucher.holder.is_none());
+
+        // Reusing a claim code is rejected.
+        mock::set_value(100);
+        mock::set_call_data(&encode(&PurchaseArgs {
+            token: NATIVE_ASSET.to_string(),
+            code_hash: code_hash(CODE),
+            amount: 100,
+            expires_at: EXPIRY,
+        }));
+        purchase();
+        assert_eq!(stored().face_value, 500);
+    }
+
+    #[test]
+    fn partial_redemptions_bind_to_first_redeemer() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup_native_voucher(500);
+
+        redeem_as(ADDR_FRIEND, CODE, 200);
+        assert_eq!(stored().remaining, 300);
+        assert_eq!(stored().holder.as_deref(), Some(ADDR_FRIEND));
+
+        redeem_as(ADDR_THIEF, CODE, 300);
+        assert_eq!(stored().remaining, 300);
+
+        redeem_as(ADDR_FRIEND, CODE, 300);
+        assert_eq!(stored().remaining, 0);
+    }
+
+    #[test]
+    fn wrong_code_and_overdraw_are_rejected() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup_native_voucher(500);
+
+        redeem_as(ADDR_FRIEND, b"not-the-right-code-at-all", 100);
+        redeem_as(ADDR_FRIEND, CODE, 501);
+        let voucher = stored();
+        assert_eq!(voucher.remaining, 500);
+        assert!(voucher.holder.is_none());
+    }
+
+    #[test]
+    fn expired_voucher_refunds_remainder_to_purchaser() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup_native_voucher(500);
+        redeem_as(ADDR_FRIEND, CODE, 150);
+
+        let refund_call = encode(&CodeHashArgs {
+            code_hash: code_hash(CODE),
+        });
+        mock::set_sender(ADDR_BUYER);
+        mock::set_call_data(&refund_call);
+        refund();
+        assert_eq!(stored().remaining, 350, "refund before expiry");
+
+        mock::set_block_timestamp(EXPIRY);
+        redeem_as(ADDR_FRIEND, CODE, 100);
+        assert_eq!(stored().remaining, 350, "redeem after expiry");
+
+        mock::set_sender(ADDR_FRIEND);
+        mock::set_call_data(&refund_call);
+        refund();
+        assert_eq!(stored().remaining, 350, "refund by non-purchaser");
+
+        mock::set_sender(ADDR_BUYER);
+        refund();
+        assert_eq!(stored().remaining, 0);
+    }
+
+    #[test]
+    fn decommissioning_stops_purchases_but_not_redemptions() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup_native_voucher(500);
+        mock::set_sender(ADDR_OWNER);
+        initialize();
+        let notice = encode(&NoticeArgs {
+            notice: decommission::MIN_NOTICE,
+        });
+
+        mock::set_sender(ADDR_BUYER);
+        mock::set_call_data(&notice);
+        schedule_decommission();
+        assert_eq!(decommission::status().unwrap().eta, None);
+        mock::set_sender(ADDR_OWNER);
+        mock::set_call_data(&notice);
+        schedule_decommission();
+        let eta: u64 = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        mock::set_block_timestamp(eta);
+        decommission();
+        assert_eq!(decommission::decommissioned_at().unwrap(), Some(eta));
+
+        let other = b"another-voucher-code-0001";
+        mock::set_sender(ADDR_BUYER);
+        mock::set_value(100);
+        mock::set_call_data(&encode(&PurchaseArgs {
+            token: NATIVE_ASSET.to_string(),
+            code_hash: code_hash(other),
+            amount: 100,
+            expires_at: EXPIRY,
+        }));
+        purchase();
+        mock::set_value(0);
+        assert!(vouchers().get(&code_hash(other)).unwrap().is_none());
+
+        redeem_as(ADDR_FRIEND, CODE, 200);
+        assert_eq!(stored().remaining, 300);
+        mock::set_block_timestamp(EXPIRY);
+        mock::set_sender(ADDR_BUYER);
+        mock::set_call_data(&encode(&CodeHashArgs {
+            code_hash: code_hash(CODE),
+        }));
+        refund();
+        assert_eq!(stored().remaining, 0);
+    }
+
+    #[test]
+    fn token_voucher_pulls_and_pays_out_via_crc20() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        mock::reset();
+        mock::set_contract_address(VOUCHERS);
+        mock::set_block_timestamp(START);
+        mock::register_contract(TOKEN, "transfer_from", |_| Ok(Vec::new()));
+        mock::register_contract(TOKEN, "transfer", |_| Ok(Vec::new()));
+
+        mock::set_sender(ADDR_BUYER);
+        mock::set_call_data(&encode(&PurchaseArgs {
+            token: TOKEN.to_string(),
+            code_hash: code_hash(CODE),
+            amount: 1_000,
+            expires_at: EXPIRY,
+        }));
+        purchase();
+        redeem_as(ADDR_FRIEND, CODE, 400);
+
+        let calls = mock::take_contract_calls();
+        assert_eq!(calls.len(), 2);
+        let pulled: TokenTransferFromArgs = postcard::from_bytes(&calls[0].args).unwrap();
+        assert_eq!(
+            (pulled.from.as_str(), pulled.to.as_str()),
+            (ADDR_BUYER, VOUCHERS)
+        );
+        let paid: TokenTransferArgs = postcard::from_bytes(&calls[1].args).unwrap();
+        assert_eq!((paid.to.as_str(), paid.amount), (ADDR_FRIEND, 400));
+    }
+
+    #[test]
+    fn contract_version_reports_the_build() {
+        let _guard = test_lock().lock().expect("test mutex poisoned");
+        setup_native_voucher(500);
+        contract_version();
+        let version: ContractVersion = postcard::from_bytes(&mock::take_return_data()).unwrap();
+        // No storage schema version has been recorded.
+        assert_eq!(
+            version,
+            ContractVersion::new(
+                "vouchers-contract",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("SILICA_GIT_HASH"),
+                0
+            )
+        );
+    }
+}